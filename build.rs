@@ -0,0 +1,41 @@
+//! Build script - compiles the OpenUSD C shim when the `usd-native` feature is enabled
+
+fn main() {
+    #[cfg(feature = "usd-native")]
+    native::build();
+}
+
+#[cfg(feature = "usd-native")]
+mod native {
+    use std::env;
+    use std::path::PathBuf;
+    
+    /// Compile ffi/usd_shim.cpp and link it against the OpenUSD install in NODLE_OPENUSD_ROOT
+    ///
+    /// NODLE_OPENUSD_LIBS lists the libraries to link, comma separated; the
+    /// default suits a monolithic build (PXR_BUILD_MONOLITHIC).
+    pub fn build() {
+        println!("cargo:rerun-if-changed=ffi/usd_shim.h");
+        println!("cargo:rerun-if-changed=ffi/usd_shim.cpp");
+        println!("cargo:rerun-if-env-changed=NODLE_OPENUSD_ROOT");
+        println!("cargo:rerun-if-env-changed=NODLE_OPENUSD_LIBS");
+        
+        let root = PathBuf::from(env::var("NODLE_OPENUSD_ROOT")
+            .expect("NODLE_OPENUSD_ROOT must point at an OpenUSD install to build the usd-native feature"));
+        
+        cc::Build::new()
+            .cpp(true)
+            .std("c++17")
+            .file("ffi/usd_shim.cpp")
+            .include("ffi")
+            .include(root.join("include"))
+            .warnings(false)
+            .compile("nodle_usd_shim");
+        
+        println!("cargo:rustc-link-search=native={}", root.join("lib").display());
+        let libs = env::var("NODLE_OPENUSD_LIBS").unwrap_or_else(|_| "usd_ms,tbb".to_string());
+        for lib in libs.split(',').map(str::trim).filter(|lib| !lib.is_empty()) {
+            println!("cargo:rustc-link-lib=dylib={}", lib);
+        }
+    }
+}
@@ -0,0 +1,88 @@
+/*
+ * C interface other Nodle plugins use to read composed scenes from the USD
+ * plugin's engine, without parsing USD files themselves.
+ *
+ * Resolve the symbols from the USD plugin's library at runtime and check
+ * nodle_usd_api_version() first. A query copies the meshes, materials and
+ * lights of a stage at a time code into a scene the caller owns; every
+ * pointer read from it stays valid until nodle_usd_api_scene_release().
+ * Functions returning int return 1 on success and 0 on failure, with the
+ * reason available from nodle_usd_api_last_error() on the same thread.
+ */
+
+#ifndef NODLE_USD_API_H
+#define NODLE_USD_API_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define NODLE_USD_API_VERSION 1
+
+typedef struct NodleUsdApiScene NodleUsdApiScene;
+
+typedef struct NodleUsdApiMesh {
+    const char* prim_path;
+    /* point_count points of three doubles each, in world space */
+    const double* points;
+    size_t point_count;
+    const int32_t* face_vertex_counts;
+    size_t face_count;
+    const int32_t* face_vertex_indices;
+    size_t index_count;
+    /* Index of the bound material in the scene, -1 if none is bound */
+    int material_index;
+} NodleUsdApiMesh;
+
+/* UsdPreviewSurface inputs, with the schema defaults for unauthored ones */
+typedef struct NodleUsdApiMaterial {
+    const char* prim_path;
+    float diffuse_color[3];
+    float emissive_color[3];
+    float metallic;
+    float roughness;
+    float opacity;
+    float ior;
+} NodleUsdApiMaterial;
+
+typedef struct NodleUsdApiLight {
+    const char* prim_path;
+    /* Schema type, e.g. "RectLight" */
+    const char* light_type;
+    /* Local-to-world matrix in USD's row-vector convention, rows first */
+    double transform[16];
+    float color[3];
+    float intensity;
+    float exposure;
+    float radius;
+    float width;
+    float height;
+    /* Angular diameter of distant lights, in degrees */
+    float angle;
+} NodleUsdApiLight;
+
+uint32_t nodle_usd_api_version(void);
+const char* nodle_usd_api_last_error(void);
+
+/* A stage identifier from a node port, or a file path opened if no loaded stage has it; NULL on failure */
+NodleUsdApiScene* nodle_usd_api_scene_query(const char* stage, double time);
+void nodle_usd_api_scene_release(NodleUsdApiScene* scene);
+double nodle_usd_api_scene_time(const NodleUsdApiScene* scene);
+
+size_t nodle_usd_api_scene_mesh_count(const NodleUsdApiScene* scene);
+int nodle_usd_api_scene_mesh(const NodleUsdApiScene* scene, size_t index, NodleUsdApiMesh* out);
+
+size_t nodle_usd_api_scene_material_count(const NodleUsdApiScene* scene);
+int nodle_usd_api_scene_material(const NodleUsdApiScene* scene, size_t index, NodleUsdApiMaterial* out);
+
+size_t nodle_usd_api_scene_light_count(const NodleUsdApiScene* scene);
+int nodle_usd_api_scene_light(const NodleUsdApiScene* scene, size_t index, NodleUsdApiLight* out);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,410 @@
+// Thin C interface over OpenUSD's C++ API for the usd-native backend
+
+#include "usd_shim.h"
+
+#include <pxr/base/arch/fileSystem.h>
+#include <pxr/base/gf/vec2d.h>
+#include <pxr/base/gf/vec3d.h>
+#include <pxr/base/gf/vec4d.h>
+#include <pxr/base/tf/errorMark.h>
+#include <pxr/base/tf/fileUtils.h>
+#include <pxr/base/tf/pathUtils.h>
+#include <pxr/base/tf/stringUtils.h>
+#include <pxr/base/vt/array.h>
+#include <pxr/base/vt/value.h>
+#include <pxr/usd/sdf/assetPath.h>
+#include <pxr/usd/sdf/layer.h>
+#include <pxr/usd/sdf/path.h>
+#include <pxr/usd/sdf/types.h>
+#include <pxr/usd/usd/attribute.h>
+#include <pxr/usd/usd/editTarget.h>
+#include <pxr/usd/usd/prim.h>
+#include <pxr/usd/usd/primRange.h>
+#include <pxr/usd/usd/stage.h>
+#include <pxr/usd/usdUtils/usdzPackage.h>
+
+#include <cmath>
+#include <cstdlib>
+#include <cstring>
+#include <exception>
+#include <sstream>
+#include <string>
+#include <vector>
+
+PXR_NAMESPACE_USING_DIRECTIVE
+
+struct NodleUsdStage {
+    UsdStageRefPtr stage;
+};
+
+namespace {
+
+thread_local std::string last_error;
+
+int fail(const std::string& message) {
+    last_error = message;
+    return 0;
+}
+
+// Prefer the first Tf error posted since the mark over a generic message
+std::string describe(const TfErrorMark& mark, const std::string& fallback) {
+    if (mark.IsClean()) {
+        return fallback;
+    }
+    return fallback + ": " + mark.begin()->GetCommentary();
+}
+
+char* copy_string(const std::string& text) {
+    char* out = static_cast<char*>(std::malloc(text.size() + 1));
+    if (out) {
+        std::memcpy(out, text.c_str(), text.size() + 1);
+    }
+    return out;
+}
+
+bool parse_number(const std::string& raw, double& out, bool& is_int) {
+    const std::string text = TfStringTrim(raw);
+    if (text.empty()) {
+        return false;
+    }
+    char* end = nullptr;
+    const long long integer = std::strtoll(text.c_str(), &end, 10);
+    if (*end == '\0') {
+        out = static_cast<double>(integer);
+        is_int = true;
+        return true;
+    }
+    const double real = std::strtod(text.c_str(), &end);
+    if (*end == '\0') {
+        out = real;
+        is_int = false;
+        return true;
+    }
+    return false;
+}
+
+std::string unquote(const std::string& text) {
+    if (text.size() >= 2 && (text.front() == '"' || text.front() == '\'') && text.back() == text.front()) {
+        return text.substr(1, text.size() - 2);
+    }
+    return text;
+}
+
+// Mirrors ast.literal_eval in the Python bulk edit helpers for the value kinds they infer
+VtValue parse_value(const std::string& raw) {
+    const std::string text = TfStringTrim(raw);
+    if (text == "True" || text == "true") {
+        return VtValue(true);
+    }
+    if (text == "False" || text == "false") {
+        return VtValue(false);
+    }
+
+    double number = 0.0;
+    bool is_int = false;
+    if (parse_number(text, number, is_int)) {
+        return is_int ? VtValue(static_cast<int>(number)) : VtValue(number);
+    }
+
+    if (text.size() >= 2 && text.front() == '(' && text.back() == ')') {
+        std::vector<double> values;
+        bool numeric = true;
+        for (const std::string& part : TfStringSplit(text.substr(1, text.size() - 2), ",")) {
+            if (TfStringTrim(part).empty()) {
+                continue;
+            }
+            if (!parse_number(part, number, is_int)) {
+                numeric = false;
+                break;
+            }
+            values.push_back(number);
+        }
+        if (numeric) {
+            switch (values.size()) {
+                case 2: return VtValue(GfVec2d(values[0], values[1]));
+                case 3: return VtValue(GfVec3d(values[0], values[1], values[2]));
+                case 4: return VtValue(GfVec4d(values[0], values[1], values[2], values[3]));
+                default: break;
+            }
+        }
+    }
+
+    if (text.size() >= 2 && text.front() == '[' && text.back() == ']') {
+        VtArray<TfToken> tokens;
+        for (const std::string& part : TfStringSplit(text.substr(1, text.size() - 2), ",")) {
+            const std::string item = TfStringTrim(part);
+            if (!item.empty()) {
+                tokens.push_back(TfToken(unquote(item)));
+            }
+        }
+        return VtValue(tokens);
+    }
+
+    return VtValue(unquote(text));
+}
+
+SdfValueTypeName infer_type(const VtValue& value) {
+    if (value.IsHolding<bool>()) return SdfValueTypeNames->Bool;
+    if (value.IsHolding<int>()) return SdfValueTypeNames->Int;
+    if (value.IsHolding<double>()) return SdfValueTypeNames->Double;
+    if (value.IsHolding<GfVec2d>()) return SdfValueTypeNames->Double2;
+    if (value.IsHolding<GfVec3d>()) return SdfValueTypeNames->Double3;
+    if (value.IsHolding<GfVec4d>()) return SdfValueTypeNames->Double4;
+    if (value.IsHolding<VtArray<TfToken>>()) return SdfValueTypeNames->TokenArray;
+    return SdfValueTypeNames->String;
+}
+
+// Convert a parsed value to an existing attribute's type
+VtValue convert(const VtValue& value, const SdfValueTypeName& type_name) {
+    const TfType type = type_name.GetType();
+    if (value.IsHolding<std::string>()) {
+        if (type.IsA<TfToken>()) {
+            return VtValue(TfToken(value.UncheckedGet<std::string>()));
+        }
+        if (type.IsA<SdfAssetPath>()) {
+            return VtValue(SdfAssetPath(value.UncheckedGet<std::string>()));
+        }
+    }
+    return VtValue::CastToTypeid(value, type.GetTypeid());
+}
+
+SdfLayerHandle find_layer(const UsdStageRefPtr& stage, const std::string& name) {
+    if (name.empty() || name == "session") {
+        return stage->GetSessionLayer();
+    }
+    if (name == "root") {
+        return stage->GetRootLayer();
+    }
+    for (const SdfLayerHandle& layer : stage->GetLayerStack(false)) {
+        if (layer->GetIdentifier() == name) {
+            return layer;
+        }
+    }
+    return SdfLayerHandle();
+}
+
+NodleUsdStage* wrap(const UsdStageRefPtr& stage) {
+    NodleUsdStage* handle = new NodleUsdStage{stage};
+    // Interactive edits default to the session layer, like the Python backend
+    stage->SetEditTarget(UsdEditTarget(stage->GetSessionLayer()));
+    return handle;
+}
+
+}  // namespace
+
+extern "C" {
+
+const char* nodle_usd_last_error(void) {
+    return last_error.c_str();
+}
+
+void nodle_usd_string_free(char* text) {
+    std::free(text);
+}
+
+NodleUsdStage* nodle_usd_stage_create_in_memory(const char* identifier) {
+    TfErrorMark mark;
+    try {
+        UsdStageRefPtr stage = UsdStage::CreateInMemory(std::string(identifier) + ".usda");
+        if (!stage) {
+            fail(describe(mark, "Failed to create in-memory stage"));
+            return nullptr;
+        }
+        return wrap(stage);
+    } catch (const std::exception& e) {
+        fail(e.what());
+        return nullptr;
+    }
+}
+
+NodleUsdStage* nodle_usd_stage_open(const char* path) {
+    TfErrorMark mark;
+    try {
+        UsdStageRefPtr stage = UsdStage::Open(path);
+        if (!stage) {
+            fail(describe(mark, std::string("Failed to open '") + path + "'"));
+            return nullptr;
+        }
+        return wrap(stage);
+    } catch (const std::exception& e) {
+        fail(e.what());
+        return nullptr;
+    }
+}
+
+void nodle_usd_stage_release(NodleUsdStage* stage) {
+    delete stage;
+}
+
+int nodle_usd_stage_set_edit_target(NodleUsdStage* handle, const char* layer) {
+    const SdfLayerHandle target = find_layer(handle->stage, layer);
+    if (!target) {
+        return fail(std::string("Layer '") + layer + "' is not in the layer stack");
+    }
+    handle->stage->SetEditTarget(UsdEditTarget(target));
+    return 1;
+}
+
+int nodle_usd_stage_layer_dirty(NodleUsdStage* handle, const char* layer, int* dirty) {
+    const SdfLayerHandle target = find_layer(handle->stage, layer);
+    if (!target) {
+        return fail(std::string("Layer '") + layer + "' is not in the layer stack");
+    }
+    *dirty = target->IsDirty() ? 1 : 0;
+    return 1;
+}
+
+char* nodle_usd_stage_export_layer(NodleUsdStage* handle, const char* layer) {
+    const SdfLayerHandle target = find_layer(handle->stage, layer);
+    if (!target) {
+        fail(std::string("Layer '") + layer + "' is not in the layer stack");
+        return nullptr;
+    }
+    TfErrorMark mark;
+    try {
+        std::string text;
+        if (!target->ExportToString(&text)) {
+            fail(describe(mark, std::string("Failed to export layer '") + layer + "'"));
+            return nullptr;
+        }
+        return copy_string(text);
+    } catch (const std::exception& e) {
+        fail(e.what());
+        return nullptr;
+    }
+}
+
+int nodle_usd_stage_import_layer(NodleUsdStage* handle, const char* layer, const char* text) {
+    const SdfLayerHandle target = find_layer(handle->stage, layer);
+    if (!target) {
+        return fail(std::string("Layer '") + layer + "' is not in the layer stack");
+    }
+    TfErrorMark mark;
+    try {
+        if (!target->ImportFromString(text)) {
+            return fail(describe(mark, std::string("Failed to import layer '") + layer + "'"));
+        }
+        return 1;
+    } catch (const std::exception& e) {
+        return fail(e.what());
+    }
+}
+
+int nodle_usd_stage_define_prim(NodleUsdStage* handle, const char* path, const char* type_name) {
+    TfErrorMark mark;
+    try {
+        const UsdPrim prim = handle->stage->DefinePrim(SdfPath(path), TfToken(type_name));
+        if (!prim) {
+            return fail(describe(mark, std::string("Failed to define '") + path + "'"));
+        }
+        return 1;
+    } catch (const std::exception& e) {
+        return fail(e.what());
+    }
+}
+
+int nodle_usd_stage_set_attribute(NodleUsdStage* handle, const char* prim_path, const char* name, const char* value) {
+    TfErrorMark mark;
+    try {
+        const SdfPath path(prim_path);
+        UsdPrim prim = handle->stage->GetPrimAtPath(path);
+        if (!prim) {
+            prim = handle->stage->OverridePrim(path);
+        }
+        if (!prim) {
+            return fail(describe(mark, std::string("Failed to find or override '") + prim_path + "'"));
+        }
+
+        const VtValue parsed = parse_value(value);
+        UsdAttribute attr = prim.GetAttribute(TfToken(name));
+        if (!attr) {
+            attr = prim.CreateAttribute(TfToken(name), infer_type(parsed));
+        }
+        const VtValue converted = convert(parsed, attr.GetTypeName());
+        if (converted.IsEmpty()) {
+            return fail(std::string("Cannot convert '") + value + "' to " + attr.GetTypeName().GetAsToken().GetString());
+        }
+        if (!attr.Set(converted)) {
+            return fail(describe(mark, std::string("Failed to set '") + prim_path + "." + name + "'"));
+        }
+        return 1;
+    } catch (const std::exception& e) {
+        return fail(e.what());
+    }
+}
+
+char* nodle_usd_stage_get_attribute(NodleUsdStage* handle, const char* prim_path, const char* name, double time) {
+    const UsdAttribute attr = handle->stage->GetAttributeAtPath(SdfPath(prim_path).AppendProperty(TfToken(name)));
+    if (!attr) {
+        fail(std::string("Attribute '") + prim_path + "." + name + "' not found");
+        return nullptr;
+    }
+    VtValue value;
+    const UsdTimeCode time_code = std::isnan(time) ? UsdTimeCode::Default() : UsdTimeCode(time);
+    if (!attr.Get(&value, time_code)) {
+        fail(std::string("Attribute '") + prim_path + "." + name + "' has no value");
+        return nullptr;
+    }
+    return copy_string(TfStringify(value));
+}
+
+char* nodle_usd_stage_list_prims(NodleUsdStage* handle) {
+    std::ostringstream out;
+    for (const UsdPrim& prim : handle->stage->Traverse()) {
+        out << prim.GetPath().GetString() << '\t' << prim.GetTypeName().GetString() << '\n';
+    }
+    return copy_string(out.str());
+}
+
+int nodle_usd_stage_export(NodleUsdStage* handle, const char* path, int flatten, const char* format) {
+    TfErrorMark mark;
+    try {
+        const std::string target(path);
+        const std::string file_format(format);
+        // Keeps a flattened layer alive while it's exported
+        SdfLayerRefPtr flattened;
+        SdfLayerHandle layer = handle->stage->GetRootLayer();
+        if (flatten) {
+            flattened = handle->stage->Flatten();
+            layer = flattened;
+        }
+        if (!layer) {
+            return fail(describe(mark, "Failed to flatten stage"));
+        }
+
+        bool exported = false;
+        if (file_format == "usdz") {
+            // Packages are built from a layer on disk
+            const std::string staged = ArchMakeTmpFileName("nodle_usdz", ".usdc");
+            exported = layer->Export(staged) && UsdUtilsCreateNewUsdzPackage(SdfAssetPath(staged), target);
+            TfDeleteFile(staged);
+        } else {
+            SdfLayer::FileFormatArguments args;
+            if (TfGetExtension(target) == "usd") {
+                args["format"] = file_format;
+            }
+            exported = layer->Export(target, std::string(), args);
+        }
+        if (!exported) {
+            return fail(describe(mark, "Failed to export to '" + target + "'"));
+        }
+        return 1;
+    } catch (const std::exception& e) {
+        return fail(e.what());
+    }
+}
+
+int nodle_usd_stage_save(NodleUsdStage* handle) {
+    TfErrorMark mark;
+    try {
+        handle->stage->Save();
+        if (!mark.IsClean()) {
+            return fail(describe(mark, "Failed to save stage"));
+        }
+        return 1;
+    } catch (const std::exception& e) {
+        return fail(e.what());
+    }
+}
+
+}  // extern "C"
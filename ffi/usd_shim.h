@@ -0,0 +1,54 @@
+/*
+ * Thin C interface over OpenUSD's C++ API for the usd-native backend.
+ *
+ * Functions returning int return 1 on success and 0 on failure, with the
+ * reason available from nodle_usd_last_error() on the same thread. Strings
+ * returned by the shim must be released with nodle_usd_string_free().
+ */
+
+#ifndef NODLE_USD_SHIM_H
+#define NODLE_USD_SHIM_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct NodleUsdStage NodleUsdStage;
+
+const char* nodle_usd_last_error(void);
+void nodle_usd_string_free(char* text);
+
+NodleUsdStage* nodle_usd_stage_create_in_memory(const char* identifier);
+NodleUsdStage* nodle_usd_stage_open(const char* path);
+void nodle_usd_stage_release(NodleUsdStage* stage);
+
+/* "session", "root" or the identifier of a layer in the stage's layer stack */
+int nodle_usd_stage_set_edit_target(NodleUsdStage* stage, const char* layer);
+
+/* Layers are named like edit targets; dirty is set to whether the layer has unsaved edits */
+int nodle_usd_stage_layer_dirty(NodleUsdStage* stage, const char* layer, int* dirty);
+/* The layer as usda text */
+char* nodle_usd_stage_export_layer(NodleUsdStage* stage, const char* layer);
+/* Replace the content of the layer with usda text */
+int nodle_usd_stage_import_layer(NodleUsdStage* stage, const char* layer, const char* text);
+
+int nodle_usd_stage_define_prim(NodleUsdStage* stage, const char* path, const char* type_name);
+
+/* Values use the Python literal syntax of the bulk edit helpers: 1.5, (1, 2, 3), ['a', 'b'], text */
+int nodle_usd_stage_set_attribute(NodleUsdStage* stage, const char* prim_path, const char* name, const char* value);
+
+/* A NaN time reads the default value */
+char* nodle_usd_stage_get_attribute(NodleUsdStage* stage, const char* prim_path, const char* name, double time);
+
+/* One "path\ttype" line per prim in traversal order */
+char* nodle_usd_stage_list_prims(NodleUsdStage* stage);
+
+/* Export the root layer, or the composed stage when flatten is non-zero, as "usda", "usdc" or "usdz" */
+int nodle_usd_stage_export(NodleUsdStage* stage, const char* path, int flatten, const char* format);
+int nodle_usd_stage_save(NodleUsdStage* stage);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
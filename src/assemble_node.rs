@@ -0,0 +1,351 @@
+//! USD Assemble node - references several stages into a new shot stage
+
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::core::usd_engine::{with_usd_engine, USDAssemblyInput};
+use crate::core::usd_value::UsdValue;
+use crate::layout_import_node::sanitize_prim_name;
+use crate::ui::help::{NodeHelp, KITCHEN_SET};
+use crate::ui::palette::status_label;
+use crate::ui::keyboard::{focus_marked, handle_key};
+use crate::registry::NodeTypeFactory;
+
+/// Help for the Assemble node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_Assemble",
+    summary: "Assemble a shot stage referencing each input stage under its own Xform with a transform offset",
+    details: "Every connected input is referenced under its own Xform below Root Path, named and offset by its slot's translate, rotate and scale. Inputs may be stages or file paths.",
+    ports: &[
+        ("Input 1", "/assets/set/kitchen.usd"),
+        ("Stage", "assembled_0"),
+        ("Prims", "/World/Set\n/World/Character"),
+    ],
+    samples: &[KITCHEN_SET],
+};
+
+/// Factory of the Assemble node, for `crate::node_factories`
+pub fn factories() -> Vec<Box<dyn NodeFactory>> {
+    vec![NodeTypeFactory::boxed(metadata, |position| PluginNodeHandle::new(Box::new(USDAssembleNode::new(position))))]
+}
+
+/// Metadata of the Assemble node
+fn metadata() -> NodeMetadata {
+    NodeMetadata::new(
+        "USD_Assemble",
+        "Assemble",
+        NodeCategory::new(&["USD", "Stage"]),
+        HELP.summary
+    )
+    .with_color(Color32::from_rgb(80, 150, 200))
+    .with_icon("🧩")
+    .with_inputs((1..=MAX_INPUTS)
+        .map(|number| PortDefinition::optional(&format!("Input {}", number), DataType::String)
+            .with_description("USD stage or file path to reference"))
+        .collect())
+    .with_outputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("Assembled in-memory stage"),
+        PortDefinition::optional("Prims", DataType::String)
+            .with_description("Xform paths of the referenced inputs, one per line"),
+    ])
+    .with_panel_type(PanelType::Parameter)
+    .with_workspace_compatibility(vec!["3D"])
+}
+
+/// Stage inputs offered by the node ("Input 1" to "Input 8")
+pub const MAX_INPUTS: usize = 8;
+
+/// Per-input settings, kept while the input is disconnected
+#[derive(Debug, Clone)]
+struct AssemblySlot {
+    /// Xform name; empty names are derived from the connected stage
+    name: String,
+    translate: [f64; 3],
+    rotate: [f64; 3],
+    scale: [f64; 3],
+    /// Connected stage identifier or file path
+    source: Option<String>,
+    /// Revision of the source stage when last assembled
+    revision: u64,
+}
+
+impl Default for AssemblySlot {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            translate: [0.0; 3],
+            rotate: [0.0; 3],
+            scale: [1.0; 3],
+            source: None,
+            revision: 0,
+        }
+    }
+}
+
+/// USD Assemble node
+///
+/// Each connected input is referenced under its own Xform below the root
+/// prim, offset by the input's translate/rotate/scale. The assembly is an
+/// in-memory stage, rebuilt when an input or its stage changes.
+pub struct USDAssembleNode {
+    id: String,
+    position: Pos2,
+    identifier: String,
+    root_path: String,
+    slots: Vec<AssemblySlot>,
+    /// Identifier of the assembled stage
+    assembled: Option<String>,
+    paths: Vec<String>,
+    dirty: bool,
+    status: String,
+}
+
+impl USDAssembleNode {
+    pub fn new(position: Pos2) -> Self {
+        let id = uuid::Uuid::new_v4().to_string();
+        Self {
+            identifier: format!("assembly_{}", &id[..8]),
+            id,
+            position,
+            root_path: "/World".to_string(),
+            slots: vec![AssemblySlot::default(); MAX_INPUTS],
+            assembled: None,
+            paths: Vec::new(),
+            dirty: true,
+            status: "No stages connected".to_string(),
+        }
+    }
+    
+    /// Xform names of the connected inputs, deriving missing names from the source and keeping them unique
+    fn input_names(&self) -> Vec<(usize, String)> {
+        let mut names: Vec<(usize, String)> = Vec::new();
+        for (index, slot) in self.slots.iter().enumerate() {
+            let Some(source) = &slot.source else {
+                continue;
+            };
+            let base = if slot.name.is_empty() {
+                let stem = std::path::Path::new(source).file_stem().map(|stem| stem.to_string_lossy().to_string());
+                sanitize_prim_name(&stem.unwrap_or_else(|| source.clone()))
+            } else {
+                slot.name.clone()
+            };
+            let mut name = base.clone();
+            let mut suffix = 2;
+            while names.iter().any(|(_, taken)| *taken == name) {
+                name = format!("{}_{}", base, suffix);
+                suffix += 1;
+            }
+            names.push((index, name));
+        }
+        names
+    }
+    
+    fn assemble(&mut self) -> Result<(), String> {
+        if self.identifier.is_empty() {
+            return Err("No stage identifier set".to_string());
+        }
+        let names = self.input_names();
+        if names.is_empty() {
+            return Err("No stages connected".to_string());
+        }
+        let (identifier, root_path) = (self.identifier.clone(), self.root_path.clone());
+        let slots = self.slots.clone();
+        
+        let (revisions, paths) = with_usd_engine(|engine| -> Result<(Vec<(usize, u64)>, Vec<String>), String> {
+            let mut inputs = Vec::new();
+            let mut revisions = Vec::new();
+            for (index, name) in &names {
+                let slot = &slots[*index];
+                let source = slot.source.as_deref().unwrap_or_default();
+                let stage = engine.resolve_stage(source)?;
+                revisions.push((*index, engine.stage_revision(&stage.identifier)));
+                inputs.push(USDAssemblyInput {
+                    name: name.clone(),
+                    stage_id: stage.identifier,
+                    translate: slot.translate,
+                    rotate: slot.rotate,
+                    scale: slot.scale,
+                });
+            }
+            let (_, paths) = engine.assemble_stage(&identifier, &root_path, &inputs)?;
+            Ok((revisions, paths))
+        })?;
+        
+        for (index, revision) in revisions {
+            self.slots[index].revision = revision;
+        }
+        self.status = format!("Assembled {} stages under {}", paths.len(), self.root_path);
+        self.paths = paths;
+        self.assembled = Some(identifier);
+        Ok(())
+    }
+}
+
+/// Split a per-input parameter name ("translate_3") into its field and slot index
+fn slot_parameter(name: &str) -> Option<(&str, usize)> {
+    let (field, number) = name.rsplit_once('_')?;
+    let index = number.parse::<usize>().ok()?.checked_sub(1)?;
+    (matches!(field, "name" | "translate" | "rotate" | "scale") && index < MAX_INPUTS).then_some((field, index))
+}
+
+impl PluginNode for USDAssembleNode {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+    
+    fn position(&self) -> Pos2 {
+        self.position
+    }
+    
+    fn set_position(&mut self, position: Pos2) {
+        self.position = position;
+    }
+    
+    fn get_parameter_ui(&self) -> ParameterUI {
+        let mut elements = Vec::new();
+        
+        elements.push(UIElement::Heading("USD Assemble".to_string()));
+        elements.push(UIElement::Separator);
+        
+        elements.push(UIElement::TextEdit {
+            label: "Stage Identifier".to_string(),
+            value: self.identifier.clone(),
+            parameter_name: "identifier".to_string(),
+        });
+        elements.push(UIElement::TextEdit {
+            label: "Root Prim".to_string(),
+            value: self.root_path.clone(),
+            parameter_name: "root_path".to_string(),
+        });
+        
+        for (index, name) in self.input_names() {
+            let slot = &self.slots[index];
+            let number = index + 1;
+            elements.push(UIElement::Separator);
+            elements.push(UIElement::Label(format!("Input {}: {}", number, slot.source.as_deref().unwrap_or_default())));
+            elements.push(UIElement::TextEdit {
+                label: "Name".to_string(),
+                value: if slot.name.is_empty() { name } else { slot.name.clone() },
+                parameter_name: format!("name_{}", number),
+            });
+            for (label, field, value) in [("Translate", "translate", slot.translate), ("Rotate", "rotate", slot.rotate), ("Scale", "scale", slot.scale)] {
+                elements.push(UIElement::TextEdit {
+                    label: label.to_string(),
+                    value: UsdValue::Vec3(value).to_string(),
+                    parameter_name: format!("{}_{}", field, number),
+                });
+            }
+        }
+        
+        elements.push(UIElement::Separator);
+        elements.push(status_label(&self.status));
+        for path in &self.paths {
+            elements.push(UIElement::Label(format!("  {}", path)));
+        }
+        elements.extend(HELP.section());
+        
+        ParameterUI { elements: focus_marked(&self.id, elements) }
+    }
+    
+    fn handle_ui_action(&mut self, action: UIAction) -> Vec<ParameterChange> {
+        if let Some(changes) = handle_key(self, &[], &action) {
+            return changes;
+        }
+        
+        let mut changes = Vec::new();
+        
+        if let UIAction::ParameterChanged { parameter, value } = action {
+            if matches!(parameter.as_str(), "identifier" | "root_path") || slot_parameter(&parameter).is_some() {
+                self.set_parameter(&parameter, value);
+                if let Some(value) = self.get_parameter(&parameter) {
+                    changes.push(ParameterChange { parameter, value });
+                }
+            }
+        }
+        
+        changes
+    }
+    
+    fn get_parameter(&self, name: &str) -> Option<NodeData> {
+        match name {
+            "identifier" => Some(NodeData::String(self.identifier.clone())),
+            "root_path" => Some(NodeData::String(self.root_path.clone())),
+            _ => {
+                let (field, index) = slot_parameter(name)?;
+                let slot = &self.slots[index];
+                let value = match field {
+                    "name" => slot.name.clone(),
+                    "translate" => UsdValue::Vec3(slot.translate).to_string(),
+                    "rotate" => UsdValue::Vec3(slot.rotate).to_string(),
+                    _ => UsdValue::Vec3(slot.scale).to_string(),
+                };
+                Some(NodeData::String(value))
+            }
+        }
+    }
+    
+    fn set_parameter(&mut self, name: &str, value: NodeData) {
+        let Some(text) = value.as_string() else {
+            return;
+        };
+        match name {
+            "identifier" => self.identifier = text.trim().to_string(),
+            "root_path" => self.root_path = text.trim().trim_end_matches('/').to_string(),
+            _ => {
+                let Some((field, index)) = slot_parameter(name) else {
+                    return;
+                };
+                let slot = &mut self.slots[index];
+                if field == "name" {
+                    let name = text.trim();
+                    slot.name = if name.is_empty() { String::new() } else { sanitize_prim_name(name) };
+                } else {
+                    let Ok(UsdValue::Vec3(components)) = UsdValue::parse(text, "double3") else {
+                        return;
+                    };
+                    match field {
+                        "translate" => slot.translate = components,
+                        "rotate" => slot.rotate = components,
+                        _ => slot.scale = components,
+                    }
+                }
+            }
+        }
+        self.dirty = true;
+    }
+    
+    fn process(&mut self, inputs: &HashMap<String, NodeData>) -> HashMap<String, NodeData> {
+        let mut outputs = HashMap::new();
+        
+        for (index, slot) in self.slots.iter_mut().enumerate() {
+            let source = inputs.get(&format!("Input {}", index + 1))
+                .and_then(|data| data.as_string())
+                .map(str::trim)
+                .filter(|source| !source.is_empty())
+                .map(str::to_string);
+            if source != slot.source {
+                slot.source = source;
+                self.dirty = true;
+            }
+        }
+        // Rebuild when a connected stage has been edited since it was referenced
+        let stale = with_usd_engine(|engine| self.slots.iter()
+            .any(|slot| slot.source.as_ref().is_some_and(|source| engine.stage_revision(source) != slot.revision)));
+        self.dirty |= stale && self.assembled.is_some();
+        
+        if self.dirty {
+            self.dirty = false;
+            if let Err(e) = self.assemble() {
+                self.status = format!("⚠ {}", e);
+                self.assembled = None;
+                self.paths.clear();
+            }
+        }
+        
+        if let Some(stage) = &self.assembled {
+            outputs.insert("Stage".to_string(), NodeData::String(stage.clone()));
+            outputs.insert("Prims".to_string(), NodeData::String(self.paths.join("\n")));
+        }
+        outputs
+    }
+}
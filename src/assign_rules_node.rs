@@ -0,0 +1,261 @@
+//! USD Assign By Rule node - binds materials to prims matched by name, attribute and kind rules
+
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::core::lookdev_rules::{parse_rules, resolve_assignments, Assignment};
+use crate::core::usd_engine::{with_usd_engine, RelationshipEdit};
+use crate::ui::help::{NodeHelp, KITCHEN_SET};
+use crate::ui::palette::status_label;
+use crate::ui::keyboard::{focus_marked, handle_key};
+use crate::registry::NodeTypeFactory;
+
+/// Help for the Assign by Rule node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_AssignByRule",
+    summary: "Bind materials to prims matched by name glob, attribute value and kind rules, previewing matches before committing",
+    details: "Each rule matches prims by name glob, attribute value or kind and binds a material. Matches are listed first; Commit authors the bindings.",
+    ports: &[
+        ("Stage", "loaded_0"),
+        ("Assignments", "/World/Set/Table_01 -> /World/Looks/Wood"),
+    ],
+    samples: &[KITCHEN_SET],
+};
+
+/// Factory of the Assign By Rule node, for `crate::node_factories`
+pub fn factories() -> Vec<Box<dyn NodeFactory>> {
+    vec![NodeTypeFactory::boxed(metadata, |position| PluginNodeHandle::new(Box::new(USDAssignByRuleNode::new(position))))]
+}
+
+/// Metadata of the Assign By Rule node
+fn metadata() -> NodeMetadata {
+    NodeMetadata::new(
+        "USD_AssignByRule",
+        "Assign By Rule",
+        NodeCategory::new(&["USD", "Shading"]),
+        HELP.summary
+    )
+    .with_color(Color32::from_rgb(180, 100, 180))
+    .with_icon("📐")
+    .with_inputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("USD stage with the geometry and materials"),
+    ])
+    .with_outputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("Stage with the committed bindings"),
+        PortDefinition::optional("Assignments", DataType::String)
+            .with_description("Matched \"prim -> material\" pairs, one per line"),
+    ])
+    .with_panel_type(PanelType::Parameter)
+    .with_workspace_compatibility(vec!["3D"])
+}
+
+/// Matches listed in the panel before the rest are summarized
+const PREVIEW_ROWS: usize = 30;
+
+/// USD Assign By Rule node
+///
+/// Rules are matched against the stage and previewed in the panel; bindings
+/// are only authored once committed. A committed node re-authors its bindings
+/// whenever the stage changes, and editing the rules returns it to preview.
+pub struct USDAssignByRuleNode {
+    id: String,
+    position: Pos2,
+    rules_text: String,
+    committed: bool,
+    stage_ref: String,
+    /// Matches of the current rules
+    assignments: Vec<Assignment>,
+    /// Prims bound by the last commit, unbound again when they stop matching or on preview
+    bound: Vec<String>,
+    dirty: bool,
+    status: String,
+}
+
+impl USDAssignByRuleNode {
+    pub fn new(position: Pos2) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            position,
+            rules_text: String::new(),
+            committed: false,
+            stage_ref: String::new(),
+            assignments: Vec::new(),
+            bound: Vec::new(),
+            dirty: true,
+            status: "No USD stage connected".to_string(),
+        }
+    }
+    
+    /// Match the rules against the stage, binding the matches when committed
+    fn evaluate(&mut self) -> Result<(), String> {
+        let rules = parse_rules(&self.rules_text)?;
+        let (stage_ref, committed) = (self.stage_ref.clone(), self.committed);
+        let previously_bound = std::mem::take(&mut self.bound);
+        
+        let (assignments, bound) = with_usd_engine(|engine| -> Result<(Vec<Assignment>, Vec<String>), String> {
+            let stage = engine.resolve_stage(&stage_ref)?;
+            let prims: Vec<(String, String, String)> = engine.get_prim_hierarchy(&stage.identifier)?
+                .into_iter()
+                .map(|prim| (prim.path, prim.prim_type, prim.kind))
+                .collect();
+            let assignments = resolve_assignments(&prims, &rules, |prim_path, attr_name| {
+                engine.get_attribute(&stage.identifier, prim_path, attr_name).ok()
+            });
+            // Previews leave the stage untouched, so earlier bindings are cleared
+            for path in previously_bound.iter().filter(|path| !committed || !assignments.iter().any(|a| a.prim_path == **path)) {
+                engine.edit_relationship_targets(&stage.identifier, path, "material:binding", &[], RelationshipEdit::Set)?;
+            }
+            if !committed {
+                return Ok((assignments, Vec::new()));
+            }
+            for assignment in &assignments {
+                engine.edit_relationship_targets(&stage.identifier, &assignment.prim_path, "material:binding", std::slice::from_ref(&assignment.material), RelationshipEdit::Set)?;
+            }
+            let bound = assignments.iter().map(|assignment| assignment.prim_path.clone()).collect();
+            Ok((assignments, bound))
+        })?;
+        
+        let verb = if self.committed { "Bound" } else { "Preview:" };
+        self.status = format!("{} {} prims from {} rules", verb, assignments.len(), rules.len());
+        self.assignments = assignments;
+        self.bound = bound;
+        Ok(())
+    }
+}
+
+impl PluginNode for USDAssignByRuleNode {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+    
+    fn position(&self) -> Pos2 {
+        self.position
+    }
+    
+    fn set_position(&mut self, position: Pos2) {
+        self.position = position;
+    }
+    
+    fn get_parameter_ui(&self) -> ParameterUI {
+        let mut elements = Vec::new();
+        
+        elements.push(UIElement::Heading("USD Assign By Rule".to_string()));
+        elements.push(UIElement::Separator);
+        
+        elements.push(UIElement::Label("One rule per line, later rules win:".to_string()));
+        elements.push(UIElement::Label("  name:<glob> | attr:<name>=<value> | kind:<kind> -> /Material".to_string()));
+        elements.push(UIElement::TextEdit {
+            label: "Rules".to_string(),
+            value: self.rules_text.clone(),
+            parameter_name: "rules".to_string(),
+        });
+        elements.push(if self.committed {
+            UIElement::Button {
+                label: "Back to Preview".to_string(),
+                action: "preview".to_string(),
+            }
+        } else {
+            UIElement::Button {
+                label: "Commit Bindings".to_string(),
+                action: "commit".to_string(),
+            }
+        });
+        
+        elements.push(UIElement::Separator);
+        elements.push(status_label(&self.status));
+        for assignment in self.assignments.iter().take(PREVIEW_ROWS) {
+            elements.push(UIElement::Label(format!("  {} → {} (rule {})", assignment.prim_path, assignment.material, assignment.rule + 1)));
+        }
+        if self.assignments.len() > PREVIEW_ROWS {
+            elements.push(UIElement::Label(format!("  … {} more", self.assignments.len() - PREVIEW_ROWS)));
+        }
+        elements.extend(HELP.section());
+        
+        ParameterUI { elements: focus_marked(&self.id, elements) }
+    }
+    
+    fn handle_ui_action(&mut self, action: UIAction) -> Vec<ParameterChange> {
+        if let Some(changes) = handle_key(self, &[], &action) {
+            return changes;
+        }
+        
+        let mut changes = Vec::new();
+        
+        let (parameter, value) = match action {
+            UIAction::ParameterChanged { parameter, value } => (parameter, value),
+            UIAction::ButtonClicked { action } => match action.as_str() {
+                "commit" => ("committed".to_string(), NodeData::Boolean(true)),
+                "preview" => ("committed".to_string(), NodeData::Boolean(false)),
+                _ => return changes,
+            },
+        };
+        if matches!(parameter.as_str(), "rules" | "committed") {
+            self.set_parameter(&parameter, value);
+            if let Some(value) = self.get_parameter(&parameter) {
+                changes.push(ParameterChange { parameter, value });
+            }
+        }
+        
+        changes
+    }
+    
+    fn get_parameter(&self, name: &str) -> Option<NodeData> {
+        match name {
+            "rules" => Some(NodeData::String(self.rules_text.clone())),
+            "committed" => Some(NodeData::Boolean(self.committed)),
+            _ => None,
+        }
+    }
+    
+    fn set_parameter(&mut self, name: &str, value: NodeData) {
+        match name {
+            "rules" => {
+                if let Some(rules) = value.as_string() {
+                    self.rules_text = rules.to_string();
+                    self.committed = false;
+                    self.dirty = true;
+                }
+            }
+            "committed" => {
+                if let Some(committed) = value.as_boolean() {
+                    self.committed = committed;
+                    self.dirty = true;
+                }
+            }
+            _ => {}
+        }
+    }
+    
+    fn process(&mut self, inputs: &HashMap<String, NodeData>) -> HashMap<String, NodeData> {
+        let mut outputs = HashMap::new();
+        
+        let Some(stage_ref) = inputs.get("Stage").and_then(|data| data.as_string()) else {
+            self.stage_ref.clear();
+            self.assignments.clear();
+            self.status = "No USD stage connected".to_string();
+            return outputs;
+        };
+        
+        if stage_ref != self.stage_ref {
+            self.stage_ref = stage_ref.to_string();
+            self.bound.clear();
+            self.dirty = true;
+        }
+        
+        if self.dirty {
+            self.dirty = false;
+            if let Err(e) = self.evaluate() {
+                self.status = format!("⚠ {}", e);
+                self.assignments.clear();
+            }
+        }
+        
+        outputs.insert("Stage".to_string(), NodeData::String(self.stage_ref.clone()));
+        let assignments: Vec<String> = self.assignments.iter()
+            .map(|assignment| format!("{} -> {}", assignment.prim_path, assignment.material))
+            .collect();
+        outputs.insert("Assignments".to_string(), NodeData::String(assignments.join("\n")));
+        outputs
+    }
+}
@@ -0,0 +1,190 @@
+//! USD Bridge Profile node - reports where time goes in Python bridge calls
+
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::core::profiling::{format_report, profile_report, reset_profile, OperationStats};
+use crate::ui::help::NodeHelp;
+use crate::ui::keyboard::{focus_marked, handle_key};
+use crate::registry::NodeTypeFactory;
+
+/// Help for the Bridge Profile node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_BridgeProfile",
+    summary: "Report call counts and time spent in Python bridge operations",
+    details: "Lists the slowest engine operations that went through Python, with call counts and total time, to find where a graph spends its time.",
+    ports: &[
+        ("Report", "open_python_stage: 42 calls, 180.5 ms"),
+    ],
+    samples: &[],
+};
+
+/// Factory of the Bridge Profile node, for `crate::node_factories`
+pub fn factories() -> Vec<Box<dyn NodeFactory>> {
+    vec![NodeTypeFactory::boxed(metadata, |position| PluginNodeHandle::new(Box::new(USDBridgeProfileNode::new(position))))]
+}
+
+/// Metadata of the Bridge Profile node
+fn metadata() -> NodeMetadata {
+    NodeMetadata::new(
+        "USD_BridgeProfile",
+        "Bridge Profile",
+        NodeCategory::new(&["USD", "Viewport"]),
+        HELP.summary
+    )
+    .with_color(Color32::from_rgb(120, 120, 120))
+    .with_icon("⏲")
+    .with_outputs(vec![
+        PortDefinition::optional("Report", DataType::String)
+            .with_description("Profile report as a text table"),
+    ])
+    .with_panel_type(PanelType::Parameter)
+    .with_workspace_compatibility(vec!["3D"])
+}
+
+/// USD Bridge Profile node showing per-operation Python bridge timings
+pub struct USDBridgeProfileNode {
+    id: String,
+    position: Pos2,
+    /// Hide operations below this total time, in milliseconds
+    min_total_ms: f32,
+    operations: Vec<OperationStats>,
+}
+
+impl USDBridgeProfileNode {
+    pub fn new(position: Pos2) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            position,
+            min_total_ms: 0.0,
+            operations: Vec::new(),
+        }
+    }
+    
+    fn refresh(&mut self) {
+        let threshold = self.min_total_ms as f64 / 1000.0;
+        self.operations = profile_report()
+            .into_iter()
+            .filter(|stats| stats.total.as_secs_f64() >= threshold)
+            .collect();
+    }
+}
+
+impl PluginNode for USDBridgeProfileNode {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+    
+    fn position(&self) -> Pos2 {
+        self.position
+    }
+    
+    fn set_position(&mut self, position: Pos2) {
+        self.position = position;
+    }
+    
+    fn get_parameter_ui(&self) -> ParameterUI {
+        let mut elements = Vec::new();
+        
+        elements.push(UIElement::Heading("USD Bridge Profile".to_string()));
+        elements.push(UIElement::Separator);
+        
+        elements.push(UIElement::Slider {
+            label: "Min Total (ms)".to_string(),
+            value: self.min_total_ms,
+            min: 0.0,
+            max: 100.0,
+            parameter_name: "min_total_ms".to_string(),
+        });
+        
+        elements.push(UIElement::Button {
+            label: "Refresh".to_string(),
+            action: "refresh".to_string(),
+        });
+        
+        elements.push(UIElement::Button {
+            label: "Reset".to_string(),
+            action: "reset".to_string(),
+        });
+        
+        elements.push(UIElement::Separator);
+        if self.operations.is_empty() {
+            elements.push(UIElement::Label("No Python bridge calls recorded".to_string()));
+        }
+        for stats in &self.operations {
+            let name = stats.path.rsplit('/').next().unwrap_or_default();
+            elements.push(UIElement::Label(format!(
+                "{}{}: {}× {:.2} ms (self {:.2} ms, GIL wait {:.2} ms)",
+                "  ".repeat(stats.depth()),
+                name,
+                stats.calls,
+                stats.total.as_secs_f64() * 1000.0,
+                stats.self_time.as_secs_f64() * 1000.0,
+                stats.gil_wait.as_secs_f64() * 1000.0,
+            )));
+        }
+        elements.extend(HELP.section());
+        
+        ParameterUI { elements: focus_marked(&self.id, elements) }
+    }
+    
+    fn handle_ui_action(&mut self, action: UIAction) -> Vec<ParameterChange> {
+        if let Some(changes) = handle_key(self, &[], &action) {
+            return changes;
+        }
+        
+        let mut changes = Vec::new();
+        
+        match action {
+            UIAction::ParameterChanged { parameter, value } => {
+                if parameter == "min_total_ms" {
+                    if let Some(ms) = value.as_float() {
+                        self.set_parameter(&parameter, NodeData::Float(ms));
+                        changes.push(ParameterChange {
+                            parameter: parameter.clone(),
+                            value: NodeData::Float(ms),
+                        });
+                    }
+                }
+            }
+            UIAction::ButtonClicked { action } => {
+                match action.as_str() {
+                    "refresh" => self.refresh(),
+                    "reset" => {
+                        reset_profile();
+                        self.refresh();
+                    }
+                    _ => {}
+                }
+            }
+        }
+        
+        changes
+    }
+    
+    fn get_parameter(&self, name: &str) -> Option<NodeData> {
+        match name {
+            "min_total_ms" => Some(NodeData::Float(self.min_total_ms)),
+            _ => None,
+        }
+    }
+    
+    fn set_parameter(&mut self, name: &str, value: NodeData) {
+        match name {
+            "min_total_ms" => {
+                if let Some(ms) = value.as_float() {
+                    self.min_total_ms = ms.max(0.0);
+                    self.refresh();
+                }
+            }
+            _ => {}
+        }
+    }
+    
+    fn process(&mut self, _inputs: &HashMap<String, NodeData>) -> HashMap<String, NodeData> {
+        let mut outputs = HashMap::new();
+        
+        self.refresh();
+        outputs.insert("Report".to_string(), NodeData::String(format_report(&self.operations)));
+        outputs
+    }
+}
@@ -0,0 +1,178 @@
+//! Named AOVs and multi-layer EXR captures
+//!
+//! EXR captures can carry AOVs in the same file as the beauty, as channels
+//! named the way Nuke splits them into layers: the beauty in R, G, B and A,
+//! camera depth in depth.Z, world space normals in N.R, N.G and N.B, and prim
+//! ids in a Cryptomatte layer, where CryptoObject00.R holds the id and .G its
+//! coverage. The Cryptomatte name, hash, conversion and manifest go in the
+//! header, so Nuke's Cryptomatte node picks mattes from the file directly.
+//! Every channel is 32-bit float, since half floats can't hold the id bits.
+
+use exr::prelude::traits::WritableImage;
+use exr::prelude::{AnyChannel, AnyChannels, AttributeValue, Encoding, FlatSamples, Image, Layer, LayerAttributes, SmallVec, Text};
+use super::{srgb_to_linear, CapturedFrame};
+use super::id_matte::{murmur3_32, IdManifest, IdMatte};
+
+/// Layer name of the prim id AOV, as Cryptomatte names object ids
+pub const CRYPTOMATTE_LAYER: &str = "CryptoObject";
+
+/// An AOV captured alongside the beauty
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aov {
+    /// Distance along the view axis
+    Depth,
+    /// World space normal
+    Normal,
+    /// Cryptomatte prim ids
+    Id,
+}
+
+impl Aov {
+    pub const ALL: [Aov; 3] = [Aov::Depth, Aov::Normal, Aov::Id];
+    
+    pub fn label(&self) -> &'static str {
+        match self {
+            Aov::Depth => "Depth",
+            Aov::Normal => "Normal",
+            Aov::Id => "ID",
+        }
+    }
+    
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|aov| aov.label() == label)
+    }
+    
+    /// Channel names of the AOV in a layered EXR
+    pub fn channel_names(&self) -> Vec<String> {
+        let (layer, channels) = match self {
+            Aov::Depth => ("depth".to_string(), &["Z"][..]),
+            Aov::Normal => ("N".to_string(), &["R", "G", "B"][..]),
+            // The first rank of the Cryptomatte layer
+            Aov::Id => (format!("{}00", CRYPTOMATTE_LAYER), &["R", "G", "B", "A"][..]),
+        };
+        channels.iter().map(|channel| format!("{}.{}", layer, channel)).collect()
+    }
+    
+    /// Samples of each channel, top row first
+    fn samples(&self, matte: &IdMatte) -> Vec<Vec<f32>> {
+        match self {
+            Aov::Depth => vec![matte.distances.clone()],
+            Aov::Normal => (0..3).map(|axis| matte.normals.iter().map(|normal| normal[axis]).collect()).collect(),
+            Aov::Id => {
+                // One id per pixel fills the first rank; the second stays empty
+                let empty = vec![0.0; matte.ids.len()];
+                vec![
+                    matte.ids.iter().map(|id| f32::from_bits(*id)).collect(),
+                    matte.ids.iter().map(|id| if *id == 0 { 0.0 } else { 1.0 }).collect(),
+                    empty.clone(),
+                    empty,
+                ]
+            }
+        }
+    }
+}
+
+/// Channels of a layered EXR as (name, samples top row first), the beauty's linear RGBA first
+pub fn layer_channels(beauty: Option<&CapturedFrame>, matte: &IdMatte, aovs: &[Aov]) -> Vec<(String, Vec<f32>)> {
+    let mut channels = Vec::new();
+    if let Some(frame) = beauty {
+        for (index, name) in ["R", "G", "B", "A"].into_iter().enumerate() {
+            let samples = frame.pixels.chunks_exact(4)
+                .map(|pixel| if index == 3 { pixel[3] as f32 / 255.0 } else { srgb_to_linear(pixel[index]) })
+                .collect();
+            channels.push((name.to_string(), samples));
+        }
+    }
+    for aov in aovs {
+        channels.extend(aov.channel_names().into_iter().zip(aov.samples(matte)));
+    }
+    channels
+}
+
+/// Cryptomatte header attributes of the id layer, as (name, value)
+///
+/// The attributes are keyed by the first seven hex digits of the layer
+/// name's MurmurHash3, as the Cryptomatte specification asks.
+pub fn cryptomatte_metadata(manifest: &IdManifest) -> Vec<(String, String)> {
+    let key = format!("{:08x}", murmur3_32(CRYPTOMATTE_LAYER.as_bytes(), 0));
+    [
+        ("name", CRYPTOMATTE_LAYER.to_string()),
+        ("hash", "MurmurHash3_32".to_string()),
+        ("conversion", "uint32_to_float32".to_string()),
+        ("manifest", manifest.to_json()),
+    ]
+    .into_iter()
+    .map(|(field, value)| (format!("cryptomatte/{}/{}", &key[..7], field), value))
+    .collect()
+}
+
+/// Write the beauty, if any, and AOVs of a frame as one layered EXR
+pub fn save_layered_exr(path: &str, beauty: Option<&CapturedFrame>, matte: &IdMatte, aovs: &[Aov], manifest: &IdManifest) -> Result<(), String> {
+    if let Some(frame) = beauty.filter(|frame| (frame.width, frame.height) != (matte.width, matte.height)) {
+        return Err(format!("Frame is {}x{} but its AOVs are {}x{}", frame.width, frame.height, matte.width, matte.height));
+    }
+    let text = |value: &str| Text::new_or_none(value).ok_or_else(|| format!("'{}' can't be stored in an EXR header", value));
+    
+    let mut attributes = LayerAttributes::default();
+    if aovs.contains(&Aov::Id) {
+        for (name, value) in cryptomatte_metadata(manifest) {
+            attributes.other.insert(text(&name)?, AttributeValue::Text(text(&value)?));
+        }
+    }
+    let channels: SmallVec<[AnyChannel<FlatSamples>; 4]> = layer_channels(beauty, matte, aovs).into_iter()
+        .map(|(name, samples)| Ok(AnyChannel::new(text(&name)?, FlatSamples::F32(samples))))
+        .collect::<Result<_, String>>()?;
+    let size = (matte.width as usize, matte.height as usize);
+    Image::from_layer(Layer::new(size, attributes, Encoding::FAST_LOSSLESS, AnyChannels::sort(channels)))
+        .write()
+        .to_file(path)
+        .map_err(|e| format!("Failed to write layered EXR '{}': {}", path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::{Mat4, Vec3};
+    
+    #[test]
+    fn layered_exr_holds_beauty_and_named_aovs() {
+        let quad = [
+            Vec3::new(-1.0, -1.0, 0.0), Vec3::new(1.0, -1.0, 0.0),
+            Vec3::new(1.0, 1.0, 0.0), Vec3::new(-1.0, 1.0, 0.0),
+        ];
+        let world_to_clip = Mat4::perspective_rh(60f32.to_radians(), 1.0, 0.1, 100.0)
+            * Mat4::look_at_rh(Vec3::new(0.0, 0.0, 5.0), Vec3::ZERO, Vec3::Y);
+        let mut manifest = IdManifest::default();
+        let id = manifest.insert("/World/Card");
+        let mut matte = IdMatte::new(8, 8);
+        matte.draw_mesh_with_normals(&quad, &[Vec3::Z; 4], &[0, 1, 2, 0, 2, 3], Mat4::IDENTITY, world_to_clip, id);
+        let beauty = CapturedFrame::new(8, 8, [255, 128, 0, 255].repeat(64)).unwrap();
+        
+        let aovs = Aov::ALL;
+        let channels = layer_channels(Some(&beauty), &matte, &aovs);
+        let names: Vec<&str> = channels.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, [
+            "R", "G", "B", "A", "depth.Z", "N.R", "N.G", "N.B",
+            "CryptoObject00.R", "CryptoObject00.G", "CryptoObject00.B", "CryptoObject00.A",
+        ]);
+        let center = 4 * 8 + 4;
+        assert!((channels[4].1[center] - 5.0).abs() < 1e-3);
+        assert_eq!(channels[7].1[center], 1.0);
+        assert_eq!(channels[8].1[center].to_bits(), id);
+        assert_eq!((channels[9].1[center], channels[9].1[0]), (1.0, 0.0));
+        let metadata = cryptomatte_metadata(&manifest);
+        let key = metadata[0].0.split('/').nth(1).unwrap();
+        assert_eq!(key.len(), 7);
+        assert_eq!(metadata[1], (format!("cryptomatte/{}/hash", key), "MurmurHash3_32".to_string()));
+        
+        let path = std::env::temp_dir().join(format!("nodle_aovs_{}.exr", std::process::id()));
+        let path = path.to_string_lossy().into_owned();
+        save_layered_exr(&path, Some(&beauty), &matte, &aovs, &manifest).unwrap();
+        let image = exr::prelude::read_all_flat_layers_from_file(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        let layer = &image.layer_data[0];
+        assert_eq!(layer.channel_data.list.len(), 12);
+        assert!(layer.attributes.other.keys().any(|key| key.to_string().ends_with("/manifest")));
+        assert!(save_layered_exr(&path, Some(&beauty), &IdMatte::new(4, 4), &aovs, &manifest).is_err());
+    }
+}
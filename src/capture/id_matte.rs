@@ -0,0 +1,273 @@
+//! Cryptomatte-style ID mattes for playblast AOV export
+//!
+//! Every prim path is hashed with MurmurHash3 into a 32-bit id whose bit
+//! pattern is a valid, finite float, as in the Cryptomatte specification.
+//! The matte stores one id per pixel (nearest surface wins) and is written as
+//! a float EXR next to a JSON manifest mapping prim paths to their hashes, so
+//! compositors can rebuild per-object mattes. The nearest surface's camera
+//! depth and world space normal are kept alongside for the depth and normal
+//! AOVs of layered EXR captures.
+
+use glam::{Mat3, Mat4, Vec3, Vec4};
+use std::collections::BTreeMap;
+
+/// MurmurHash3 (x86, 32-bit) of a name, with seed 0
+pub fn murmur3_32(data: &[u8], seed: u32) -> u32 {
+    const C1: u32 = 0xcc9e2d51;
+    const C2: u32 = 0x1b873593;
+    
+    let mut hash = seed;
+    let mut chunks = data.chunks_exact(4);
+    for chunk in &mut chunks {
+        let mut k = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        k = k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+        hash ^= k;
+        hash = hash.rotate_left(13).wrapping_mul(5).wrapping_add(0xe6546b64);
+    }
+    
+    let tail = chunks.remainder();
+    if !tail.is_empty() {
+        let mut k = 0u32;
+        for (i, byte) in tail.iter().enumerate() {
+            k |= (*byte as u32) << (8 * i);
+        }
+        k = k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+        hash ^= k;
+    }
+    
+    hash ^= data.len() as u32;
+    hash ^= hash >> 16;
+    hash = hash.wrapping_mul(0x85ebca6b);
+    hash ^= hash >> 13;
+    hash = hash.wrapping_mul(0xc2b2ae35);
+    hash ^= hash >> 16;
+    hash
+}
+
+/// Cryptomatte id for a prim path
+///
+/// Hashes whose float exponent is all zeros or all ones (denormals, inf, NaN)
+/// get one exponent bit flipped so the id survives float storage.
+pub fn prim_id(prim_path: &str) -> u32 {
+    let hash = murmur3_32(prim_path.as_bytes(), 0);
+    let exponent = (hash >> 23) & 0xff;
+    if exponent == 0 || exponent == 0xff {
+        hash ^ (1 << 23)
+    } else {
+        hash
+    }
+}
+
+/// Prim path to id mapping written as the sidecar manifest
+#[derive(Debug, Clone, Default)]
+pub struct IdManifest {
+    entries: BTreeMap<String, u32>,
+}
+
+impl IdManifest {
+    /// Register a prim path and return its id
+    pub fn insert(&mut self, prim_path: &str) -> u32 {
+        *self.entries.entry(prim_path.to_string()).or_insert_with(|| prim_id(prim_path))
+    }
+    
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+    
+    /// Manifest JSON in the Cryptomatte layout: `{"/World/Cube": "1a2b3c4d"}`
+    pub fn to_json(&self) -> String {
+        let map: serde_json::Map<String, serde_json::Value> = self.entries.iter()
+            .map(|(path, id)| (path.clone(), serde_json::Value::String(format!("{:08x}", id))))
+            .collect();
+        serde_json::to_string_pretty(&serde_json::Value::Object(map)).unwrap_or_default()
+    }
+    
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        std::fs::write(path, self.to_json())
+            .map_err(|e| format!("Failed to write ID manifest '{}': {}", path, e))
+    }
+}
+
+/// Per-pixel prim ids with a depth buffer for visibility
+#[derive(Debug, Clone)]
+pub struct IdMatte {
+    pub width: u32,
+    pub height: u32,
+    /// Row-major ids, top row first; 0 where nothing was drawn
+    pub ids: Vec<u32>,
+    /// Distance of the nearest surface along the view axis; 0 where nothing was drawn
+    pub distances: Vec<f32>,
+    /// World space normal of the nearest surface; zero where nothing was drawn or meshes had no normals
+    pub normals: Vec<Vec3>,
+    depth: Vec<f32>,
+}
+
+impl IdMatte {
+    pub fn new(width: u32, height: u32) -> Self {
+        let pixels = width as usize * height as usize;
+        Self {
+            width,
+            height,
+            ids: vec![0; pixels],
+            distances: vec![0.0; pixels],
+            normals: vec![Vec3::ZERO; pixels],
+            depth: vec![f32::INFINITY; pixels],
+        }
+    }
+    
+    /// Id at a pixel, top-left origin
+    pub fn id_at(&self, x: u32, y: u32) -> u32 {
+        self.ids[(y * self.width + x) as usize]
+    }
+    
+    /// Rasterize an indexed triangle mesh with one id
+    ///
+    /// `points` are in object space and `object_to_clip` takes them to clip
+    /// space. Triangles crossing the near plane are skipped.
+    pub fn draw_mesh(&mut self, points: &[Vec3], indices: &[u32], object_to_clip: Mat4, id: u32) {
+        self.draw_mesh_with_normals(points, &[], indices, Mat4::IDENTITY, object_to_clip, id);
+    }
+    
+    /// Rasterize a mesh with one id, recording its per-vertex normals in world space
+    ///
+    /// `object_to_world` takes points and normals to world space and
+    /// `world_to_clip` is a perspective view projection, whose clip w is the
+    /// distance along the view axis. Without a normal per point the normals
+    /// are left at zero.
+    pub fn draw_mesh_with_normals(&mut self, points: &[Vec3], normals: &[Vec3], indices: &[u32], object_to_world: Mat4, world_to_clip: Mat4, id: u32) {
+        let object_to_clip = world_to_clip * object_to_world;
+        let clip: Vec<Vec4> = points.iter().map(|point| object_to_clip * point.extend(1.0)).collect();
+        let world_normals: Vec<Vec3> = match normals.len() == points.len() {
+            true => {
+                let normal_matrix = Mat3::from_mat4(object_to_world).inverse().transpose();
+                normals.iter().map(|normal| (normal_matrix * *normal).normalize_or_zero()).collect()
+            }
+            false => vec![Vec3::ZERO; points.len()],
+        };
+        for triangle in indices.chunks_exact(3) {
+            let [a, b, c] = [triangle[0] as usize, triangle[1] as usize, triangle[2] as usize];
+            let corners = [clip[a], clip[b], clip[c]];
+            if corners.iter().any(|corner| corner.w <= 1e-6) {
+                continue;
+            }
+            self.draw_triangle(corners, [world_normals[a], world_normals[b], world_normals[c]], id);
+        }
+    }
+    
+    fn draw_triangle(&mut self, clip: [Vec4; 3], normals: [Vec3; 3], id: u32) {
+        let (width, height) = (self.width as f32, self.height as f32);
+        
+        // Clip space to pixel coordinates, keeping NDC depth
+        let screen = clip.map(|corner| {
+            let ndc = corner.truncate() / corner.w;
+            Vec3::new((ndc.x + 1.0) * 0.5 * width, (1.0 - ndc.y) * 0.5 * height, ndc.z)
+        });
+        let area = edge(screen[0], screen[1], screen[2]);
+        if area.abs() < 1e-12 {
+            return;
+        }
+        
+        let min_x = screen.iter().map(|p| p.x).fold(f32::INFINITY, f32::min).floor().max(0.0) as u32;
+        let min_y = screen.iter().map(|p| p.y).fold(f32::INFINITY, f32::min).floor().max(0.0) as u32;
+        let max_x = screen.iter().map(|p| p.x).fold(f32::NEG_INFINITY, f32::max).ceil().min(width) as u32;
+        let max_y = screen.iter().map(|p| p.y).fold(f32::NEG_INFINITY, f32::max).ceil().min(height) as u32;
+        
+        for y in min_y..max_y {
+            for x in min_x..max_x {
+                let sample = Vec3::new(x as f32 + 0.5, y as f32 + 0.5, 0.0);
+                let w0 = edge(screen[1], screen[2], sample) / area;
+                let w1 = edge(screen[2], screen[0], sample) / area;
+                let w2 = edge(screen[0], screen[1], sample) / area;
+                if w0 < 0.0 || w1 < 0.0 || w2 < 0.0 {
+                    continue;
+                }
+                
+                let depth = w0 * screen[0].z + w1 * screen[1].z + w2 * screen[2].z;
+                let index = (y * self.width + x) as usize;
+                if depth < self.depth[index] {
+                    self.depth[index] = depth;
+                    self.ids[index] = id;
+                    // 1/w is linear in screen space, so attributes are interpolated over it
+                    let weights = [w0 / clip[0].w, w1 / clip[1].w, w2 / clip[2].w];
+                    let total = weights[0] + weights[1] + weights[2];
+                    self.distances[index] = 1.0 / total;
+                    let normal = (normals[0] * weights[0] + normals[1] * weights[1] + normals[2] * weights[2]) / total;
+                    self.normals[index] = normal.normalize_or_zero();
+                }
+            }
+        }
+    }
+    
+    /// Write the matte as a float EXR: R holds the id bits as a float, G the coverage
+    pub fn save_exr(&self, path: &str) -> Result<(), String> {
+        let pixels: Vec<f32> = self.ids.iter()
+            .flat_map(|&id| {
+                let coverage = if id == 0 { 0.0 } else { 1.0 };
+                [f32::from_bits(id), coverage, 0.0, 1.0]
+            })
+            .collect();
+        let image = image::Rgba32FImage::from_raw(self.width, self.height, pixels)
+            .ok_or_else(|| format!("Failed to build {}x{} ID matte", self.width, self.height))?;
+        image::DynamicImage::ImageRgba32F(image)
+            .save(path)
+            .map_err(|e| format!("Failed to write ID matte '{}': {}", path, e))
+    }
+}
+
+/// Signed area of the parallelogram spanned by a->b and a->c in screen space
+fn edge(a: Vec3, b: Vec3, c: Vec3) -> f32 {
+    (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn murmur3_matches_reference_values() {
+        assert_eq!(murmur3_32(b"", 0), 0);
+        assert_eq!(murmur3_32(b"hello", 0), 0x248bfa47);
+        assert_eq!(murmur3_32(b"hello, world", 0), 0x149bbb7f);
+    }
+    
+    #[test]
+    fn prim_ids_are_finite_floats() {
+        for path in ["/World/Cube", "/World/Sphere", "/a", "/World/Geo/Mesh_0042"] {
+            let value = f32::from_bits(prim_id(path));
+            assert!(value.is_finite() && value.is_normal(), "{} -> {}", path, value);
+        }
+    }
+    
+    #[test]
+    fn manifest_lists_hex_ids() {
+        let mut manifest = IdManifest::default();
+        let id = manifest.insert("/World/Cube");
+        manifest.insert("/World/Cube");
+        
+        assert_eq!(manifest.len(), 1);
+        let json: serde_json::Value = serde_json::from_str(&manifest.to_json()).unwrap();
+        assert_eq!(json["/World/Cube"], format!("{:08x}", id));
+    }
+    
+    #[test]
+    fn nearer_triangle_wins() {
+        let quad = [
+            Vec3::new(-1.0, -1.0, 0.0), Vec3::new(1.0, -1.0, 0.0),
+            Vec3::new(1.0, 1.0, 0.0), Vec3::new(-1.0, 1.0, 0.0),
+        ];
+        let indices = [0, 1, 2, 0, 2, 3];
+        let view_projection = Mat4::perspective_rh(60f32.to_radians(), 1.0, 0.1, 100.0)
+            * Mat4::look_at_rh(Vec3::new(0.0, 0.0, 5.0), Vec3::ZERO, Vec3::Y);
+        
+        let mut matte = IdMatte::new(16, 16);
+        matte.draw_mesh(&quad, &indices, view_projection * Mat4::from_translation(Vec3::new(0.0, 0.0, 1.0)), 7);
+        matte.draw_mesh(&quad, &indices, view_projection, 3);
+        
+        assert_eq!(matte.id_at(8, 8), 7);
+        assert_eq!(matte.id_at(0, 0), 0);
+    }
+}
@@ -0,0 +1,223 @@
+//! Frame capture pipeline for playblasts and turntables
+//!
+//! Captured frames are plain RGBA8 buffers read back from the renderer. This
+//! module names frames in a sequence, applies the optional slate burn-in and
+//! writes them to disk.
+
+use std::path::Path;
+
+// Slate/watermark burn-in
+pub mod slate;
+
+// Cryptomatte-style ID mattes
+pub mod id_matte;
+
+// Named AOVs and multi-layer EXR captures
+pub mod aov;
+
+// Background sequence renders and movie encoding
+pub mod sequence;
+
+use aov::{save_layered_exr, Aov};
+use id_matte::{IdManifest, IdMatte};
+use slate::{SlateContext, SlateTemplate};
+
+/// One captured RGBA8 frame
+#[derive(Debug, Clone)]
+pub struct CapturedFrame {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl CapturedFrame {
+    pub fn new(width: u32, height: u32, pixels: Vec<u8>) -> Result<Self, String> {
+        let expected = width as usize * height as usize * 4;
+        if pixels.len() != expected {
+            return Err(format!("Frame is {}x{} but has {} bytes, expected {}", width, height, pixels.len(), expected));
+        }
+        Ok(Self { width, height, pixels })
+    }
+    
+    /// Write the frame; the format follows the file extension
+    ///
+    /// EXR files hold linear floats, so the sRGB color channels are decoded
+    /// for them; alpha stays linear.
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let is_exr = Path::new(path).extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("exr"));
+        let written = if is_exr {
+            let pixels: Vec<f32> = self.pixels.chunks_exact(4)
+                .flat_map(|pixel| [srgb_to_linear(pixel[0]), srgb_to_linear(pixel[1]), srgb_to_linear(pixel[2]), pixel[3] as f32 / 255.0])
+                .collect();
+            image::Rgba32FImage::from_raw(self.width, self.height, pixels)
+                .ok_or_else(|| format!("Failed to build {}x{} frame", self.width, self.height))?
+                .save(path)
+        } else {
+            image::save_buffer(path, &self.pixels, self.width, self.height, image::ColorType::Rgba8)
+        };
+        written.map_err(|e| format!("Failed to write frame '{}': {}", path, e))
+    }
+}
+
+/// Decode an 8-bit sRGB channel to linear
+pub fn srgb_to_linear(value: u8) -> f32 {
+    let value = value as f32 / 255.0;
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Encode a linear channel as 8-bit sRGB, clamping to 0..1
+pub fn linear_to_srgb(value: f32) -> u8 {
+    let value = value.clamp(0.0, 1.0);
+    let encoded = if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    };
+    (encoded * 255.0).round() as u8
+}
+
+/// Capture options shared by playblast and turntable captures
+#[derive(Debug, Clone)]
+pub struct CaptureSettings {
+    /// Output path with `#` frame padding, e.g. "playblast.####.png"
+    pub output_pattern: String,
+    pub width: u32,
+    pub height: u32,
+    /// Burn-in slate, if enabled
+    pub slate: Option<SlateTemplate>,
+    /// Project name for the `{project}` slate token
+    pub project: String,
+    /// Also write a prim ID matte per frame plus a manifest
+    pub id_matte: bool,
+    /// AOVs written as layers of every frame, see `write_frame_with_aovs`
+    pub aovs: Vec<Aov>,
+}
+
+impl Default for CaptureSettings {
+    fn default() -> Self {
+        Self {
+            output_pattern: "playblast.####.png".to_string(),
+            width: 1920,
+            height: 1080,
+            slate: None,
+            project: String::new(),
+            id_matte: false,
+            aovs: Vec::new(),
+        }
+    }
+}
+
+impl CaptureSettings {
+    /// Apply the slate (if any) and write a captured frame, returning the file path
+    pub fn write_frame(&self, mut frame: CapturedFrame, stage: &str, camera: &str, time_code: f64, fps: f64) -> Result<String, String> {
+        self.burn_in(&mut frame, stage, camera, time_code, fps);
+        let path = sequence_path(&self.output_pattern, time_code);
+        create_parent_dirs(&path)?;
+        frame.save(&path)?;
+        Ok(path)
+    }
+    
+    /// Write a captured frame with the enabled AOVs from its matte, returning the file paths
+    ///
+    /// EXR frames hold the AOVs as layers next to the beauty. Other formats
+    /// can't, so the frame is written as usual and the AOVs go to a layered
+    /// EXR beside it: "shot.####.png" gets "shot_aovs.####.exr".
+    #[allow(clippy::too_many_arguments)]
+    pub fn write_frame_with_aovs(&self, mut frame: CapturedFrame, matte: &IdMatte, manifest: &IdManifest, stage: &str, camera: &str, time_code: f64, fps: f64) -> Result<Vec<String>, String> {
+        let is_exr = Path::new(&self.output_pattern).extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("exr"));
+        if !is_exr {
+            let path = sequence_path(&aov_pattern(&self.output_pattern, "aovs", "exr"), time_code);
+            create_parent_dirs(&path)?;
+            save_layered_exr(&path, None, matte, &self.aovs, manifest)?;
+            return Ok(vec![self.write_frame(frame, stage, camera, time_code, fps)?, path]);
+        }
+        self.burn_in(&mut frame, stage, camera, time_code, fps);
+        let path = sequence_path(&self.output_pattern, time_code);
+        create_parent_dirs(&path)?;
+        save_layered_exr(&path, Some(&frame), matte, &self.aovs, manifest)?;
+        Ok(vec![path])
+    }
+    
+    /// Burn the slate, if enabled, into a frame
+    fn burn_in(&self, frame: &mut CapturedFrame, stage: &str, camera: &str, time_code: f64, fps: f64) {
+        if let Some(slate) = &self.slate {
+            let context = SlateContext {
+                project: self.project.clone(),
+                stage: stage.to_string(),
+                camera: camera.to_string(),
+                frame: time_code,
+                fps,
+                ..Default::default()
+            }.with_user_from_env();
+            slate.burn_in(&mut frame.pixels, frame.width, frame.height, &context);
+        }
+    }
+    
+    /// Write an ID matte next to the beauty frame, returning the file path
+    pub fn write_id_matte(&self, matte: &IdMatte, time_code: f64) -> Result<String, String> {
+        let path = sequence_path(&aov_pattern(&self.output_pattern, "id", "exr"), time_code);
+        matte.save_exr(&path)?;
+        Ok(path)
+    }
+    
+    /// Write the ID manifest shared by every frame of the sequence
+    pub fn write_id_manifest(&self, manifest: &IdManifest) -> Result<String, String> {
+        let path = aov_pattern(&self.output_pattern, "id", "json").replace(".#", "").replace('#', "");
+        manifest.save(&path)?;
+        Ok(path)
+    }
+}
+
+/// Create the directories a file is written into
+pub fn create_parent_dirs(path: &str) -> Result<(), String> {
+    match Path::new(path).parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        Some(parent) => std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create '{}': {}", parent.display(), e)),
+        None => Ok(()),
+    }
+}
+
+/// Derive an AOV sequence pattern from the beauty pattern
+///
+/// "out/shot.####.png" with AOV "id" and extension "exr" gives
+/// "out/shot_id.####.exr".
+pub fn aov_pattern(pattern: &str, aov: &str, extension: &str) -> String {
+    let (directory, file) = match pattern.rfind('/') {
+        Some(slash) => pattern.split_at(slash + 1),
+        None => ("", pattern),
+    };
+    let (stem, rest) = match file.find('.') {
+        Some(dot) => file.split_at(dot),
+        None => (file, ""),
+    };
+    // Keep the frame padding, drop the old extension
+    let padding = match rest.rfind('.') {
+        Some(dot) if dot > 0 => &rest[..dot],
+        _ => "",
+    };
+    format!("{}{}_{}{}.{}", directory, stem, aov, padding, extension)
+}
+
+/// Substitute the frame number for the run of `#` in a sequence pattern
+///
+/// "shot.####.png" at frame 12 gives "shot.0012.png". Patterns without `#`
+/// get the frame inserted before the extension.
+pub fn sequence_path(pattern: &str, frame: f64) -> String {
+    let frame = frame.round() as i64;
+    match pattern.find('#') {
+        Some(start) => {
+            let width = pattern[start..].chars().take_while(|&c| c == '#').count();
+            format!("{}{:0width$}{}", &pattern[..start], frame, &pattern[start + width..], width = width)
+        }
+        None => match pattern.rfind('.') {
+            Some(dot) => format!("{}.{}{}", &pattern[..dot], frame, &pattern[dot..]),
+            None => format!("{}.{}", pattern, frame),
+        },
+    }
+}
@@ -0,0 +1,261 @@
+//! Background sequence renders
+//!
+//! A job renders the frames of a range on its own thread so the graph stays
+//! responsive, reports every written file back, and stops between frames
+//! when cancelled. A finished sequence can be encoded to a movie with
+//! ffmpeg, which has to be on the PATH.
+
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::Arc;
+use std::time::Duration;
+use super::{create_parent_dirs, sequence_path, CapturedFrame};
+
+/// Movie encoded from a finished sequence
+#[derive(Debug, Clone, PartialEq)]
+pub struct MovieSettings {
+    pub path: String,
+    pub fps: f64,
+}
+
+/// Where a sequence job is
+#[derive(Debug, Clone, PartialEq)]
+pub enum JobState {
+    Rendering,
+    Encoding,
+    Finished,
+    Cancelled,
+    Failed(String),
+}
+
+impl JobState {
+    pub fn is_running(&self) -> bool {
+        matches!(self, JobState::Rendering | JobState::Encoding)
+    }
+}
+
+/// Progress reported by the job's thread
+enum JobEvent {
+    Frame(String),
+    Encoding,
+    Movie(String),
+    Done(JobState),
+}
+
+/// A sequence render running on a worker thread
+pub struct SequenceJob {
+    cancel: Arc<AtomicBool>,
+    events: Receiver<JobEvent>,
+    /// Number of frames in the range
+    pub total: usize,
+    /// Files written so far, in frame order
+    pub files: Vec<String>,
+    /// Encoded movie, once written
+    pub movie: Option<String>,
+    pub state: JobState,
+}
+
+impl SequenceJob {
+    /// Start rendering the frames at `times`, written to `pattern` with `#` frame padding
+    ///
+    /// `render` runs on the job's thread for each frame in turn. With
+    /// `movie` set, the frames are encoded once they are all written, which
+    /// needs consecutive frame numbers.
+    pub fn start<R>(times: Vec<f64>, pattern: &str, movie: Option<MovieSettings>, mut render: R) -> Self
+    where
+        R: FnMut(f64) -> Result<CapturedFrame, String> + Send + 'static,
+    {
+        let cancel = Arc::new(AtomicBool::new(false));
+        let (sender, events) = mpsc::channel();
+        let total = times.len();
+        let pattern = pattern.to_string();
+        let cancelled = cancel.clone();
+        
+        std::thread::spawn(move || {
+            let state = run_job(times, &pattern, movie, &mut render, &sender, &cancelled).unwrap_or_else(JobState::Failed);
+            let _ = sender.send(JobEvent::Done(state));
+        });
+        
+        Self {
+            cancel,
+            events,
+            total,
+            files: Vec::with_capacity(total),
+            movie: None,
+            state: JobState::Rendering,
+        }
+    }
+    
+    /// Ask the job to stop before its next frame, or to abort encoding
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+    
+    /// Take in the progress reported since the last poll
+    pub fn poll(&mut self) {
+        loop {
+            match self.events.try_recv() {
+                Ok(JobEvent::Frame(path)) => self.files.push(path),
+                Ok(JobEvent::Encoding) => self.state = JobState::Encoding,
+                Ok(JobEvent::Movie(path)) => self.movie = Some(path),
+                Ok(JobEvent::Done(state)) => self.state = state,
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    if self.state.is_running() {
+                        self.state = JobState::Failed("Render thread stopped unexpectedly".to_string());
+                    }
+                    break;
+                }
+            }
+        }
+    }
+    
+    /// Share of the frames written, 0.0 to 1.0
+    pub fn progress(&self) -> f32 {
+        if self.total == 0 {
+            return 1.0;
+        }
+        self.files.len() as f32 / self.total as f32
+    }
+}
+
+impl Drop for SequenceJob {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+/// Render, write and optionally encode the frames, returning how the job ended
+fn run_job(
+    times: Vec<f64>,
+    pattern: &str,
+    movie: Option<MovieSettings>,
+    render: &mut impl FnMut(f64) -> Result<CapturedFrame, String>,
+    sender: &Sender<JobEvent>,
+    cancelled: &AtomicBool,
+) -> Result<JobState, String> {
+    let first_frame = times.first().map_or(0, |time| time.round() as i64);
+    for time in times {
+        if cancelled.load(Ordering::Relaxed) {
+            return Ok(JobState::Cancelled);
+        }
+        let path = sequence_path(pattern, time);
+        let frame = render(time)?;
+        create_parent_dirs(&path)?;
+        frame.save(&path)?;
+        let _ = sender.send(JobEvent::Frame(path));
+    }
+    if let Some(movie) = movie {
+        let _ = sender.send(JobEvent::Encoding);
+        if !encode_movie(pattern, first_frame, &movie, cancelled)? {
+            return Ok(JobState::Cancelled);
+        }
+        let _ = sender.send(JobEvent::Movie(movie.path));
+    }
+    Ok(JobState::Finished)
+}
+
+/// ffmpeg's printf-style form of a `#` sequence pattern, e.g. "shot.%04d.png"
+pub fn ffmpeg_pattern(pattern: &str) -> String {
+    let escaped = pattern.replace('%', "%%");
+    match escaped.find('#') {
+        Some(start) => {
+            let width = escaped[start..].chars().take_while(|&c| c == '#').count();
+            format!("{}%0{}d{}", &escaped[..start], width, &escaped[start + width..])
+        }
+        None => escaped,
+    }
+}
+
+/// ffmpeg arguments encoding a frame sequence to an H.264 movie
+pub fn ffmpeg_args(pattern: &str, first_frame: i64, movie: &MovieSettings) -> Vec<String> {
+    vec![
+        "-y".to_string(),
+        "-framerate".to_string(), movie.fps.to_string(),
+        "-start_number".to_string(), first_frame.to_string(),
+        "-i".to_string(), ffmpeg_pattern(pattern),
+        "-c:v".to_string(), "libx264".to_string(),
+        // Even dimensions and 4:2:0 chroma keep the movie playable everywhere
+        "-vf".to_string(), "pad=ceil(iw/2)*2:ceil(ih/2)*2".to_string(),
+        "-pix_fmt".to_string(), "yuv420p".to_string(),
+        movie.path.clone(),
+    ]
+}
+
+/// Run ffmpeg over the sequence, returning false when cancelled
+fn encode_movie(pattern: &str, first_frame: i64, movie: &MovieSettings, cancelled: &AtomicBool) -> Result<bool, String> {
+    create_parent_dirs(&movie.path)?;
+    let mut child = Command::new("ffmpeg")
+        .args(ffmpeg_args(pattern, first_frame, movie))
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+    loop {
+        if cancelled.load(Ordering::Relaxed) {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Ok(false);
+        }
+        match child.try_wait().map_err(|e| format!("Failed to wait for ffmpeg: {}", e))? {
+            Some(status) if status.success() => return Ok(true),
+            Some(status) => return Err(format!("ffmpeg failed to encode '{}' ({})", movie.path, status)),
+            None => std::thread::sleep(Duration::from_millis(100)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn wait(job: &mut SequenceJob) {
+        while job.state.is_running() {
+            std::thread::sleep(Duration::from_millis(5));
+            job.poll();
+        }
+    }
+    
+    #[test]
+    fn jobs_write_every_frame_and_stop_when_cancelled() {
+        let directory = std::env::temp_dir().join(format!("nodle_sequence_{}", std::process::id()));
+        let pattern = format!("{}/frame.####.png", directory.display());
+        let render = |_| CapturedFrame::new(2, 2, vec![128; 16]);
+        
+        let mut job = SequenceJob::start(vec![1.0, 2.0, 3.0], &pattern, None, render);
+        wait(&mut job);
+        assert_eq!(job.state, JobState::Finished);
+        assert_eq!(job.files, [1, 2, 3].map(|frame| sequence_path(&pattern, frame as f64)));
+        assert!(job.files.iter().all(|file| std::path::Path::new(file).is_file()));
+        assert_eq!(job.progress(), 1.0);
+        
+        let slow = |_| {
+            std::thread::sleep(Duration::from_millis(5));
+            CapturedFrame::new(2, 2, vec![0; 16])
+        };
+        let mut job = SequenceJob::start((0..200).map(f64::from).collect(), &pattern, None, slow);
+        job.cancel();
+        wait(&mut job);
+        assert_eq!(job.state, JobState::Cancelled);
+        assert!(job.files.len() < job.total);
+        
+        let mut job = SequenceJob::start(vec![1.0], &pattern, None, |_| Err("no delegate".to_string()));
+        wait(&mut job);
+        assert_eq!(job.state, JobState::Failed("no delegate".to_string()));
+        let _ = std::fs::remove_dir_all(directory);
+    }
+    
+    #[test]
+    fn sequences_encode_with_printf_patterns() {
+        assert_eq!(ffmpeg_pattern("out/shot.####.png"), "out/shot.%04d.png");
+        assert_eq!(ffmpeg_pattern("100%/f.#.exr"), "100%%/f.%01d.exr");
+        
+        let movie = MovieSettings { path: "out/shot.mp4".to_string(), fps: 24.0 };
+        let args = ffmpeg_args("out/shot.####.png", 1001, &movie);
+        assert_eq!(args[..6], ["-y", "-framerate", "24", "-start_number", "1001", "-i"]);
+        assert_eq!(args[6], "out/shot.%04d.png");
+        assert_eq!(args.last().unwrap(), "out/shot.mp4");
+    }
+}
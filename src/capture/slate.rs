@@ -0,0 +1,267 @@
+//! Slate/watermark burn-in for captured frames
+//!
+//! A slate is a template string with `{token}` placeholders that is expanded
+//! per frame and rasterised onto the frame with a built-in 5x7 bitmap font,
+//! so playblasts carry their context without depending on system fonts.
+//!
+//! Supported tokens: `{project}`, `{stage}`, `{camera}`, `{frame}`,
+//! `{frame:N}` (zero padded to N digits), `{fps}`, `{date}`, `{time}`,
+//! `{user}`. Unknown tokens are left as written. Lines are split on `\n`
+//! or a literal `|`.
+
+/// Values available to slate tokens for one frame
+#[derive(Debug, Clone, Default)]
+pub struct SlateContext {
+    pub project: String,
+    pub stage: String,
+    pub camera: String,
+    pub frame: f64,
+    pub fps: f64,
+    pub user: String,
+}
+
+impl SlateContext {
+    /// Context with the current user filled in from the environment
+    pub fn with_user_from_env(mut self) -> Self {
+        self.user = std::env::var("USER")
+            .or_else(|_| std::env::var("USERNAME"))
+            .unwrap_or_default();
+        self
+    }
+}
+
+/// Where the slate text block is anchored on the frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlateAnchor {
+    TopLeft,
+    BottomLeft,
+    BottomRight,
+}
+
+/// Slate template and styling
+#[derive(Debug, Clone)]
+pub struct SlateTemplate {
+    pub template: String,
+    pub anchor: SlateAnchor,
+    /// Integer pixel scale of the 5x7 font
+    pub scale: u32,
+    pub text_color: [u8; 4],
+    /// Background band drawn behind the text for legibility
+    pub background: [u8; 4],
+}
+
+impl Default for SlateTemplate {
+    fn default() -> Self {
+        Self {
+            template: "{project} | {stage} | CAM {camera} | FRAME {frame:4} | {date} {user}".to_string(),
+            anchor: SlateAnchor::BottomLeft,
+            scale: 2,
+            text_color: [255, 255, 255, 255],
+            background: [0, 0, 0, 160],
+        }
+    }
+}
+
+impl SlateTemplate {
+    /// Expand the template tokens for a frame
+    pub fn expand(&self, context: &SlateContext) -> String {
+        let now = chrono::Local::now();
+        let mut result = String::new();
+        let mut rest = self.template.as_str();
+        
+        while let Some(start) = rest.find('{') {
+            result.push_str(&rest[..start]);
+            let Some(length) = rest[start..].find('}') else {
+                break;
+            };
+            let token = &rest[start + 1..start + length];
+            let (name, format) = token.split_once(':').unwrap_or((token, ""));
+            let value = match name {
+                "project" => Some(context.project.clone()),
+                "stage" => Some(context.stage.clone()),
+                "camera" => Some(context.camera.clone()),
+                "frame" => Some(match format.parse::<usize>() {
+                    Ok(width) => format!("{:0width$}", context.frame.round() as i64, width = width),
+                    Err(_) => format!("{}", context.frame),
+                }),
+                "fps" => Some(format!("{}", context.fps)),
+                "date" => Some(now.format("%Y-%m-%d").to_string()),
+                "time" => Some(now.format("%H:%M:%S").to_string()),
+                "user" => Some(context.user.clone()),
+                _ => None,
+            };
+            match value {
+                Some(value) => result.push_str(&value),
+                None => result.push_str(&rest[start..start + length + 1]),
+            }
+            rest = &rest[start + length + 1..];
+        }
+        result.push_str(rest);
+        result
+    }
+    
+    /// Expand the template and burn it into an RGBA8 frame
+    pub fn burn_in(&self, pixels: &mut [u8], width: u32, height: u32, context: &SlateContext) {
+        let text = self.expand(context);
+        let lines: Vec<&str> = text.split(['\n', '|'])
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect();
+        if lines.is_empty() {
+            return;
+        }
+        
+        let scale = self.scale.max(1);
+        let line_height = (GLYPH_HEIGHT + 2) * scale;
+        let margin = 4 * scale;
+        let block_height = lines.len() as u32 * line_height + margin;
+        let block_width = lines.iter().map(|line| text_width(line, scale)).max().unwrap_or(0) + 2 * margin;
+        
+        let (x0, y0) = match self.anchor {
+            SlateAnchor::TopLeft => (0, 0),
+            SlateAnchor::BottomLeft => (0, height.saturating_sub(block_height)),
+            SlateAnchor::BottomRight => (width.saturating_sub(block_width), height.saturating_sub(block_height)),
+        };
+        
+        fill_rect(pixels, width, height, x0, y0, block_width, block_height, self.background);
+        for (index, line) in lines.iter().enumerate() {
+            draw_text(pixels, width, height, x0 + margin, y0 + margin / 2 + index as u32 * line_height + scale, line, scale, self.text_color);
+        }
+    }
+}
+
+const GLYPH_WIDTH: u32 = 5;
+const GLYPH_HEIGHT: u32 = 7;
+
+/// Width in pixels of a line of text
+fn text_width(text: &str, scale: u32) -> u32 {
+    text.chars().count() as u32 * (GLYPH_WIDTH + 1) * scale
+}
+
+/// Alpha-blend a color into one pixel
+fn blend(pixels: &mut [u8], width: u32, height: u32, x: u32, y: u32, color: [u8; 4]) {
+    if x >= width || y >= height {
+        return;
+    }
+    let offset = ((y * width + x) * 4) as usize;
+    let Some(pixel) = pixels.get_mut(offset..offset + 4) else {
+        return;
+    };
+    let alpha = color[3] as u32;
+    for channel in 0..3 {
+        pixel[channel] = ((color[channel] as u32 * alpha + pixel[channel] as u32 * (255 - alpha)) / 255) as u8;
+    }
+    pixel[3] = pixel[3].max(color[3]);
+}
+
+#[allow(clippy::too_many_arguments)]
+fn fill_rect(pixels: &mut [u8], width: u32, height: u32, x0: u32, y0: u32, w: u32, h: u32, color: [u8; 4]) {
+    for y in y0..(y0 + h).min(height) {
+        for x in x0..(x0 + w).min(width) {
+            blend(pixels, width, height, x, y, color);
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn draw_text(pixels: &mut [u8], width: u32, height: u32, x0: u32, y0: u32, text: &str, scale: u32, color: [u8; 4]) {
+    for (index, character) in text.chars().enumerate() {
+        let rows = glyph(character);
+        let gx = x0 + index as u32 * (GLYPH_WIDTH + 1) * scale;
+        for (row, bits) in rows.iter().enumerate() {
+            for column in 0..GLYPH_WIDTH {
+                if bits & (1 << (GLYPH_WIDTH - 1 - column)) == 0 {
+                    continue;
+                }
+                fill_rect(pixels, width, height, gx + column * scale, y0 + row as u32 * scale, scale, scale, color);
+            }
+        }
+    }
+}
+
+/// 5x7 glyph rows (low 5 bits, MSB on the left); lowercase maps to uppercase
+fn glyph(character: char) -> [u8; 7] {
+    match character.to_ascii_uppercase() {
+        ' ' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+        '0' => [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E],
+        '1' => [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        '2' => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F],
+        '3' => [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E],
+        '4' => [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02],
+        '5' => [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E],
+        '6' => [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E],
+        '7' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+        '8' => [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E],
+        '9' => [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C],
+        'A' => [0x0E, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'B' => [0x1E, 0x11, 0x11, 0x1E, 0x11, 0x11, 0x1E],
+        'C' => [0x0E, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0E],
+        'D' => [0x1C, 0x12, 0x11, 0x11, 0x11, 0x12, 0x1C],
+        'E' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F],
+        'F' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x10],
+        'G' => [0x0E, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0F],
+        'H' => [0x11, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'I' => [0x0E, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        'J' => [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0C],
+        'K' => [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11],
+        'L' => [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1F],
+        'M' => [0x11, 0x1B, 0x15, 0x15, 0x11, 0x11, 0x11],
+        'N' => [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11],
+        'O' => [0x0E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'P' => [0x1E, 0x11, 0x11, 0x1E, 0x10, 0x10, 0x10],
+        'Q' => [0x0E, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0D],
+        'R' => [0x1E, 0x11, 0x11, 0x1E, 0x14, 0x12, 0x11],
+        'S' => [0x0F, 0x10, 0x10, 0x0E, 0x01, 0x01, 0x1E],
+        'T' => [0x1F, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04],
+        'U' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'V' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x0A, 0x04],
+        'W' => [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0A],
+        'X' => [0x11, 0x11, 0x0A, 0x04, 0x0A, 0x11, 0x11],
+        'Y' => [0x11, 0x11, 0x11, 0x0A, 0x04, 0x04, 0x04],
+        'Z' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1F],
+        '.' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C],
+        ',' => [0x00, 0x00, 0x00, 0x00, 0x0C, 0x04, 0x08],
+        ':' => [0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x0C, 0x00],
+        '-' => [0x00, 0x00, 0x00, 0x1F, 0x00, 0x00, 0x00],
+        '_' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1F],
+        '/' => [0x00, 0x01, 0x02, 0x04, 0x08, 0x10, 0x00],
+        '(' => [0x02, 0x04, 0x08, 0x08, 0x08, 0x04, 0x02],
+        ')' => [0x08, 0x04, 0x02, 0x02, 0x02, 0x04, 0x08],
+        '#' => [0x0A, 0x0A, 0x1F, 0x0A, 0x1F, 0x0A, 0x0A],
+        '@' => [0x0E, 0x11, 0x17, 0x15, 0x17, 0x10, 0x0E],
+        _ => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x00, 0x04], // '?'
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn expands_tokens_and_padding() {
+        let template = SlateTemplate {
+            template: "{project} {frame:4} {camera} {unknown}".to_string(),
+            ..Default::default()
+        };
+        let context = SlateContext {
+            project: "Nodle".to_string(),
+            camera: "/World/Cam".to_string(),
+            frame: 12.0,
+            ..Default::default()
+        };
+        assert_eq!(template.expand(&context), "Nodle 0012 /World/Cam {unknown}");
+    }
+    
+    #[test]
+    fn burn_in_writes_pixels() {
+        let (width, height) = (200, 40);
+        let mut pixels = vec![0u8; (width * height * 4) as usize];
+        let template = SlateTemplate {
+            template: "F {frame}".to_string(),
+            background: [0, 0, 0, 0],
+            ..Default::default()
+        };
+        template.burn_in(&mut pixels, width, height, &SlateContext::default());
+        assert!(pixels.chunks(4).any(|pixel| pixel[0] == 255));
+    }
+}
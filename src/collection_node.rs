@@ -0,0 +1,284 @@
+//! USD Collection node - authors a named UsdCollectionAPI collection and resolves its members
+
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::core::usd_engine::{with_usd_engine, CollectionExpansion, USDCollection};
+use crate::ui::choice::{choice_buttons, parse_choice};
+use crate::ui::help::NodeHelp;
+use crate::ui::palette::status_label;
+use crate::ui::keyboard::{focus_marked, handle_key};
+use crate::registry::NodeTypeFactory;
+
+/// Help for the Collection node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_Collection",
+    summary: "Create a named collection on a prim from include/exclude paths and an expansion rule",
+    details: "Defines a UsdCollectionAPI collection on Prim Path. expandPrims includes descendants of each included path; explicitOnly includes only the listed prims.",
+    ports: &[
+        ("Stage", "loaded_0"),
+        ("Includes", "/World/Characters"),
+        ("Collection", "/World.collection:heroes"),
+        ("Members", "/World/Characters/Hero\n/World/Characters/Sidekick"),
+    ],
+    samples: &[],
+};
+
+/// Factory of the Collection node, for `crate::node_factories`
+pub fn factories() -> Vec<Box<dyn NodeFactory>> {
+    vec![NodeTypeFactory::boxed(metadata, |position| PluginNodeHandle::new(Box::new(USDCollectionNode::new(position))))]
+}
+
+/// Metadata of the Collection node
+fn metadata() -> NodeMetadata {
+    NodeMetadata::new(
+        "USD_Collection",
+        "Collection",
+        NodeCategory::new(&["USD", "Stage"]),
+        HELP.summary
+    )
+    .with_color(Color32::from_rgb(80, 150, 200))
+    .with_icon("🧺")
+    .with_inputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("USD stage to author the collection in"),
+        PortDefinition::optional("Includes", DataType::String)
+            .with_description("Included paths, one per line, overriding the parameter"),
+    ])
+    .with_outputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("Stage with the collection authored"),
+        PortDefinition::optional("Collection", DataType::String)
+            .with_description("Collection path for light linking and material assignment"),
+        PortDefinition::optional("Members", DataType::String)
+            .with_description("Resolved member paths, one per line"),
+    ])
+    .with_panel_type(PanelType::Parameter)
+    .with_workspace_compatibility(vec!["3D"])
+}
+
+/// Members listed in the panel before the rest are summarized
+const MEMBER_PREVIEW: usize = 20;
+
+/// USD Collection node
+///
+/// Outputs the collection path ("/Prim.collection:name") for light linking
+/// and material assignment nodes, and the resolved members one per line.
+pub struct USDCollectionNode {
+    id: String,
+    position: Pos2,
+    prim_path: String,
+    name: String,
+    includes: Vec<String>,
+    excludes: Vec<String>,
+    expansion: CollectionExpansion,
+    stage_ref: String,
+    /// Collection path once authored
+    authored: Option<String>,
+    members: Vec<String>,
+    dirty: bool,
+    status: String,
+}
+
+impl USDCollectionNode {
+    pub fn new(position: Pos2) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            position,
+            prim_path: String::new(),
+            name: "default".to_string(),
+            includes: Vec::new(),
+            excludes: Vec::new(),
+            expansion: CollectionExpansion::ExpandPrims,
+            stage_ref: String::new(),
+            authored: None,
+            members: Vec::new(),
+            dirty: true,
+            status: "No USD stage connected".to_string(),
+        }
+    }
+    
+    fn collection(&self) -> USDCollection {
+        USDCollection {
+            prim_path: self.prim_path.clone(),
+            name: self.name.clone(),
+            includes: self.includes.clone(),
+            excludes: self.excludes.clone(),
+            expansion: self.expansion,
+            include_root: false,
+        }
+    }
+    
+    fn author(&mut self) -> Result<(), String> {
+        if self.prim_path.is_empty() {
+            return Err("No prim path set".to_string());
+        }
+        let (stage_ref, collection) = (self.stage_ref.clone(), self.collection());
+        
+        self.members = with_usd_engine(|engine| {
+            let stage = engine.resolve_stage(&stage_ref)?;
+            engine.set_collection(&stage.identifier, &collection)?;
+            engine.get_collection_members(&stage.identifier, &collection.path())
+        })?;
+        self.status = format!("{}: {} members", collection.path(), self.members.len());
+        self.authored = Some(collection.path());
+        Ok(())
+    }
+}
+
+/// Split a path list on newlines and commas
+fn parse_paths(text: &str) -> Vec<String> {
+    text.split(['\n', ','])
+        .map(str::trim)
+        .filter(|path| !path.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+impl PluginNode for USDCollectionNode {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+    
+    fn position(&self) -> Pos2 {
+        self.position
+    }
+    
+    fn set_position(&mut self, position: Pos2) {
+        self.position = position;
+    }
+    
+    fn get_parameter_ui(&self) -> ParameterUI {
+        let mut elements = Vec::new();
+        
+        elements.push(UIElement::Heading("USD Collection".to_string()));
+        elements.push(UIElement::Separator);
+        
+        elements.push(UIElement::TextEdit {
+            label: "Prim Path".to_string(),
+            value: self.prim_path.clone(),
+            parameter_name: "prim_path".to_string(),
+        });
+        elements.push(UIElement::TextEdit {
+            label: "Name".to_string(),
+            value: self.name.clone(),
+            parameter_name: "name".to_string(),
+        });
+        elements.push(UIElement::TextEdit {
+            label: "Includes".to_string(),
+            value: self.includes.join(", "),
+            parameter_name: "includes".to_string(),
+        });
+        elements.push(UIElement::TextEdit {
+            label: "Excludes".to_string(),
+            value: self.excludes.join(", "),
+            parameter_name: "excludes".to_string(),
+        });
+        elements.extend(choice_buttons("Expansion", "expansion", &CollectionExpansion::ALL.map(|expansion| expansion.token()), self.expansion.token()));
+        
+        elements.push(UIElement::Separator);
+        elements.push(status_label(&self.status));
+        for member in self.members.iter().take(MEMBER_PREVIEW) {
+            elements.push(UIElement::Label(format!("  {}", member)));
+        }
+        if self.members.len() > MEMBER_PREVIEW {
+            elements.push(UIElement::Label(format!("  … {} more", self.members.len() - MEMBER_PREVIEW)));
+        }
+        elements.extend(HELP.section());
+        
+        ParameterUI { elements: focus_marked(&self.id, elements) }
+    }
+    
+    fn handle_ui_action(&mut self, action: UIAction) -> Vec<ParameterChange> {
+        if let Some(changes) = handle_key(self, &[], &action) {
+            return changes;
+        }
+        
+        let mut changes = Vec::new();
+        
+        let (parameter, value) = match action {
+            UIAction::ParameterChanged { parameter, value } => (parameter, value),
+            UIAction::ButtonClicked { action } => {
+                let Some(expansion) = parse_choice(&action, "expansion") else {
+                    return changes;
+                };
+                ("expansion".to_string(), NodeData::String(expansion.to_string()))
+            }
+        };
+        if matches!(parameter.as_str(), "prim_path" | "name" | "includes" | "excludes" | "expansion") {
+            self.set_parameter(&parameter, value);
+            if let Some(value) = self.get_parameter(&parameter) {
+                changes.push(ParameterChange { parameter, value });
+            }
+        }
+        
+        changes
+    }
+    
+    fn get_parameter(&self, name: &str) -> Option<NodeData> {
+        match name {
+            "prim_path" => Some(NodeData::String(self.prim_path.clone())),
+            "name" => Some(NodeData::String(self.name.clone())),
+            "includes" => Some(NodeData::String(self.includes.join("\n"))),
+            "excludes" => Some(NodeData::String(self.excludes.join("\n"))),
+            "expansion" => Some(NodeData::String(self.expansion.token().to_string())),
+            _ => None,
+        }
+    }
+    
+    fn set_parameter(&mut self, name: &str, value: NodeData) {
+        let Some(text) = value.as_string() else {
+            return;
+        };
+        match name {
+            "prim_path" => self.prim_path = text.trim().to_string(),
+            "name" => self.name = text.trim().to_string(),
+            "includes" => self.includes = parse_paths(text),
+            "excludes" => self.excludes = parse_paths(text),
+            "expansion" => match CollectionExpansion::from_token(text) {
+                Some(expansion) => self.expansion = expansion,
+                None => return,
+            },
+            _ => return,
+        }
+        self.dirty = true;
+    }
+    
+    fn process(&mut self, inputs: &HashMap<String, NodeData>) -> HashMap<String, NodeData> {
+        let mut outputs = HashMap::new();
+        
+        let Some(stage_ref) = inputs.get("Stage").and_then(|data| data.as_string()) else {
+            self.stage_ref.clear();
+            self.authored = None;
+            self.members.clear();
+            self.status = "No USD stage connected".to_string();
+            return outputs;
+        };
+        
+        if stage_ref != self.stage_ref {
+            self.stage_ref = stage_ref.to_string();
+            self.dirty = true;
+        }
+        // Connected paths, e.g. a Copy Prims "Prims" output, override the includes
+        if let Some(includes) = inputs.get("Includes").and_then(|data| data.as_string()) {
+            if parse_paths(includes) != self.includes {
+                self.set_parameter("includes", NodeData::String(includes.to_string()));
+            }
+        }
+        
+        if self.dirty {
+            self.dirty = false;
+            if let Err(e) = self.author() {
+                self.status = format!("⚠ {}", e);
+                self.authored = None;
+                self.members.clear();
+            }
+        }
+        
+        outputs.insert("Stage".to_string(), NodeData::String(self.stage_ref.clone()));
+        if let Some(collection) = &self.authored {
+            outputs.insert("Collection".to_string(), NodeData::String(collection.clone()));
+            outputs.insert("Members".to_string(), NodeData::String(self.members.join("\n")));
+        }
+        outputs
+    }
+}
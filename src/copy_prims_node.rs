@@ -0,0 +1,282 @@
+//! USD Copy Prims node - copies prim subtrees between stages for kitbashing
+
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::core::usd_engine::with_usd_engine;
+use crate::ui::help::NodeHelp;
+use crate::ui::palette::status_label;
+use crate::ui::keyboard::{focus_marked, handle_key};
+use crate::registry::NodeTypeFactory;
+
+/// Help for the Copy Prims node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_CopyPrims",
+    summary: "Copy prim subtrees from one stage into another, or reference them",
+    details: "Copies the listed source prims and their descendants under Target Path. As Reference authors references to the source layer instead, keeping the target lightweight.",
+    ports: &[
+        ("Source Stage", "loaded_0"),
+        ("Target Stage", "stage_0"),
+        ("Prims", "/World/Props/Chair\n/World/Props/Lamp"),
+    ],
+    samples: &[],
+};
+
+/// Factory of the Copy Prims node, for `crate::node_factories`
+pub fn factories() -> Vec<Box<dyn NodeFactory>> {
+    vec![NodeTypeFactory::boxed(metadata, |position| PluginNodeHandle::new(Box::new(USDCopyPrimsNode::new(position))))]
+}
+
+/// Metadata of the Copy Prims node
+fn metadata() -> NodeMetadata {
+    NodeMetadata::new(
+        "USD_CopyPrims",
+        "Copy Prims",
+        NodeCategory::new(&["USD", "Stage"]),
+        HELP.summary
+    )
+    .with_color(Color32::from_rgb(80, 150, 200))
+    .with_icon("📑")
+    .with_inputs(vec![
+        PortDefinition::required("Source Stage", DataType::String)
+            .with_description("Stage to copy prims from"),
+        PortDefinition::required("Target Stage", DataType::String)
+            .with_description("Stage to copy prims into"),
+    ])
+    .with_outputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("Target stage with copied prims"),
+        PortDefinition::optional("Prims", DataType::String)
+            .with_description("Copied prim paths, one per line"),
+    ])
+    .with_panel_type(PanelType::Parameter)
+    .with_workspace_compatibility(vec!["3D"])
+}
+
+/// USD Copy Prims node
+pub struct USDCopyPrimsNode {
+    id: String,
+    position: Pos2,
+    /// Comma separated prim paths in the source stage
+    source_paths: String,
+    /// Parent path in the target stage the subtrees are placed under
+    target_path: String,
+    /// Author references to the source file instead of deep copies
+    as_reference: bool,
+    source_ref: String,
+    target_ref: String,
+    dirty: bool,
+    copied_paths: Vec<String>,
+    status: String,
+}
+
+impl USDCopyPrimsNode {
+    pub fn new(position: Pos2) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            position,
+            source_paths: String::new(),
+            target_path: "/World".to_string(),
+            as_reference: false,
+            source_ref: String::new(),
+            target_ref: String::new(),
+            dirty: true,
+            copied_paths: Vec::new(),
+            status: "Connect source and target stages".to_string(),
+        }
+    }
+    
+    fn source_path_list(&self) -> Vec<String> {
+        self.source_paths
+            .split(',')
+            .map(|path| path.trim().to_string())
+            .filter(|path| path.starts_with('/'))
+            .collect()
+    }
+    
+    /// Copy (or reference) every source subtree into the target stage
+    fn copy(&mut self) -> Result<(), String> {
+        let source_paths = self.source_path_list();
+        if source_paths.is_empty() {
+            return Err("No source prim paths set".to_string());
+        }
+        let (source_ref, target_ref) = (self.source_ref.clone(), self.target_ref.clone());
+        let target_parent = self.target_path.trim_end_matches('/').to_string();
+        let as_reference = self.as_reference;
+        
+        self.copied_paths = with_usd_engine(|engine| -> Result<Vec<String>, String> {
+            let source = engine.resolve_stage(&source_ref)?;
+            let target = engine.resolve_stage(&target_ref)?;
+            let mut copied = Vec::new();
+            
+            for source_path in &source_paths {
+                let name = source_path.rsplit('/').next().unwrap_or_default();
+                let destination = format!("{}/{}", target_parent, name);
+                if as_reference {
+                    engine.add_reference(&target.identifier, &destination, &source.path, Some(source_path))?;
+                    copied.push(destination);
+                } else {
+                    let prims = engine.copy_prim(&source.identifier, source_path, &target.identifier, &destination)?;
+                    copied.extend(prims.into_iter().map(|prim| prim.path));
+                }
+            }
+            Ok(copied)
+        })?;
+        
+        let mode = if self.as_reference { "Referenced" } else { "Copied" };
+        self.status = format!("{} {} prims into {}", mode, self.copied_paths.len(), self.target_path);
+        Ok(())
+    }
+}
+
+impl PluginNode for USDCopyPrimsNode {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+    
+    fn position(&self) -> Pos2 {
+        self.position
+    }
+    
+    fn set_position(&mut self, position: Pos2) {
+        self.position = position;
+    }
+    
+    fn get_parameter_ui(&self) -> ParameterUI {
+        let mut elements = Vec::new();
+        
+        elements.push(UIElement::Heading("USD Copy Prims".to_string()));
+        elements.push(UIElement::Separator);
+        
+        elements.push(UIElement::TextEdit {
+            label: "Source Prims".to_string(),
+            value: self.source_paths.clone(),
+            parameter_name: "source_paths".to_string(),
+        });
+        
+        elements.push(UIElement::TextEdit {
+            label: "Target Parent".to_string(),
+            value: self.target_path.clone(),
+            parameter_name: "target_path".to_string(),
+        });
+        
+        elements.push(UIElement::Checkbox {
+            label: "Reference Instead of Copy".to_string(),
+            value: self.as_reference,
+            parameter_name: "as_reference".to_string(),
+        });
+        
+        elements.push(UIElement::Button {
+            label: "Copy Again".to_string(),
+            action: "recopy".to_string(),
+        });
+        
+        elements.push(UIElement::Separator);
+        elements.push(status_label(&self.status));
+        elements.extend(HELP.section());
+        
+        ParameterUI { elements: focus_marked(&self.id, elements) }
+    }
+    
+    fn handle_ui_action(&mut self, action: UIAction) -> Vec<ParameterChange> {
+        if let Some(changes) = handle_key(self, &[], &action) {
+            return changes;
+        }
+        
+        let mut changes = Vec::new();
+        
+        match action {
+            UIAction::ParameterChanged { parameter, value } => {
+                match parameter.as_str() {
+                    "source_paths" | "target_path" => {
+                        if let Some(text) = value.as_string() {
+                            self.set_parameter(&parameter, NodeData::String(text.to_string()));
+                            changes.push(ParameterChange {
+                                parameter: parameter.clone(),
+                                value: NodeData::String(text.to_string()),
+                            });
+                        }
+                    }
+                    "as_reference" => {
+                        if let Some(enabled) = value.as_boolean() {
+                            self.set_parameter(&parameter, NodeData::Boolean(enabled));
+                            changes.push(ParameterChange {
+                                parameter: parameter.clone(),
+                                value: NodeData::Boolean(enabled),
+                            });
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            UIAction::ButtonClicked { action } => {
+                if action == "recopy" {
+                    self.dirty = true;
+                }
+            }
+        }
+        
+        changes
+    }
+    
+    fn get_parameter(&self, name: &str) -> Option<NodeData> {
+        match name {
+            "source_paths" => Some(NodeData::String(self.source_paths.clone())),
+            "target_path" => Some(NodeData::String(self.target_path.clone())),
+            "as_reference" => Some(NodeData::Boolean(self.as_reference)),
+            _ => None,
+        }
+    }
+    
+    fn set_parameter(&mut self, name: &str, value: NodeData) {
+        match name {
+            "source_paths" => {
+                if let Some(paths) = value.as_string() {
+                    self.source_paths = paths.to_string();
+                    self.dirty = true;
+                }
+            }
+            "target_path" => {
+                if let Some(path) = value.as_string() {
+                    self.target_path = path.to_string();
+                    self.dirty = true;
+                }
+            }
+            "as_reference" => {
+                if let Some(enabled) = value.as_boolean() {
+                    self.as_reference = enabled;
+                    self.dirty = true;
+                }
+            }
+            _ => {}
+        }
+    }
+    
+    fn process(&mut self, inputs: &HashMap<String, NodeData>) -> HashMap<String, NodeData> {
+        let mut outputs = HashMap::new();
+        
+        let source_ref = inputs.get("Source Stage").and_then(|data| data.as_string()).map(str::to_string);
+        let target_ref = inputs.get("Target Stage").and_then(|data| data.as_string()).map(str::to_string);
+        let (Some(source_ref), Some(target_ref)) = (source_ref, target_ref) else {
+            self.status = "Connect source and target stages".to_string();
+            return outputs;
+        };
+        
+        if source_ref != self.source_ref || target_ref != self.target_ref {
+            self.source_ref = source_ref;
+            self.target_ref = target_ref;
+            self.dirty = true;
+        }
+        
+        if self.dirty {
+            self.dirty = false;
+            if let Err(e) = self.copy() {
+                self.status = format!("⚠ {}", e);
+                self.copied_paths.clear();
+            }
+        }
+        
+        outputs.insert("Stage".to_string(), NodeData::String(self.target_ref.clone()));
+        outputs.insert("Prims".to_string(), NodeData::String(self.copied_paths.join("\n")));
+        outputs
+    }
+}
@@ -0,0 +1,325 @@
+//! Simplified bounding volumes for collision and occluder proxies
+//!
+//! A volume is built from the points of a prim and its descendants in the
+//! prim's own space: an axis-aligned box, the convex hull of the points, or
+//! a k-DOP, the box generalized to k/2 fixed directions. Volumes are
+//! authored as guide meshes under the prim, optionally carrying UsdPhysics
+//! collision schemas so game engines pick them up as colliders.
+
+use std::collections::HashSet;
+use glam::DVec3;
+use crate::core::usd_engine::{USDAttributeEdit, USDPrimSpec};
+use crate::core::usd_value::UsdValue;
+
+/// Shape of a bounding volume
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VolumeShape {
+    Box,
+    ConvexHull,
+    /// Discrete oriented polytope with 14, 18 or 26 faces
+    KDop(usize),
+}
+
+impl VolumeShape {
+    pub const ALL: [VolumeShape; 5] = [VolumeShape::Box, VolumeShape::ConvexHull, VolumeShape::KDop(14), VolumeShape::KDop(18), VolumeShape::KDop(26)];
+    
+    pub fn label(&self) -> &'static str {
+        match self {
+            VolumeShape::Box => "Box",
+            VolumeShape::ConvexHull => "Convex Hull",
+            VolumeShape::KDop(14) => "14-DOP",
+            VolumeShape::KDop(18) => "18-DOP",
+            VolumeShape::KDop(_) => "26-DOP",
+        }
+    }
+    
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|shape| shape.label() == label)
+    }
+    
+    /// UsdPhysics mesh collision approximation matching the shape
+    pub fn approximation(&self) -> &'static str {
+        match self {
+            VolumeShape::Box => "boundingCube",
+            _ => "convexHull",
+        }
+    }
+}
+
+/// Points and polygon topology of a volume, counter-clockwise seen from outside
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VolumeMesh {
+    pub points: Vec<[f64; 3]>,
+    pub face_vertex_counts: Vec<i32>,
+    pub face_vertex_indices: Vec<i32>,
+}
+
+/// Compute a volume around points; None when there are no points
+///
+/// Points that don't span a volume, such as those of a ground plane, get
+/// their flat bounding box whatever the shape.
+pub fn bounding_volume(points: &[[f64; 3]], shape: VolumeShape) -> Option<VolumeMesh> {
+    if points.is_empty() {
+        return None;
+    }
+    let points: Vec<DVec3> = points.iter().map(|&point| DVec3::from(point)).collect();
+    let (min, max) = points.iter().fold((DVec3::splat(f64::INFINITY), DVec3::splat(f64::NEG_INFINITY)), |(min, max), point| {
+        (min.min(*point), max.max(*point))
+    });
+    let hull = match shape {
+        VolumeShape::Box => None,
+        VolumeShape::ConvexHull => convex_hull(&points),
+        VolumeShape::KDop(k) => convex_hull(&k_dop_corners(&points, k)),
+    };
+    Some(hull.unwrap_or_else(|| box_mesh(min, max)))
+}
+
+/// Axis-aligned box as eight points and six quads
+fn box_mesh(min: DVec3, max: DVec3) -> VolumeMesh {
+    let points = (0..8)
+        .map(|corner| [
+            if corner & 1 == 0 { min.x } else { max.x },
+            if corner & 2 == 0 { min.y } else { max.y },
+            if corner & 4 == 0 { min.z } else { max.z },
+        ])
+        .collect();
+    VolumeMesh {
+        points,
+        face_vertex_counts: vec![4; 6],
+        face_vertex_indices: vec![
+            0, 2, 3, 1, // -Z
+            4, 5, 7, 6, // +Z
+            0, 1, 5, 4, // -Y
+            2, 6, 7, 3, // +Y
+            0, 4, 6, 2, // -X
+            1, 3, 7, 5, // +X
+        ],
+    }
+}
+
+/// Directions of the slab pairs of a k-DOP: the axes, then the edge and corner diagonals
+fn k_dop_directions(k: usize) -> Vec<DVec3> {
+    let mut directions = vec![DVec3::X, DVec3::Y, DVec3::Z];
+    if k == 18 || k >= 26 {
+        directions.extend([
+            DVec3::new(1.0, 1.0, 0.0), DVec3::new(1.0, -1.0, 0.0),
+            DVec3::new(1.0, 0.0, 1.0), DVec3::new(1.0, 0.0, -1.0),
+            DVec3::new(0.0, 1.0, 1.0), DVec3::new(0.0, 1.0, -1.0),
+        ]);
+    }
+    if k == 14 || k >= 26 {
+        directions.extend([
+            DVec3::new(1.0, 1.0, 1.0), DVec3::new(1.0, 1.0, -1.0),
+            DVec3::new(1.0, -1.0, 1.0), DVec3::new(-1.0, 1.0, 1.0),
+        ]);
+    }
+    directions.into_iter().map(DVec3::normalize).collect()
+}
+
+/// Corners of the k-DOP around the points, where three of its planes meet inside all the others
+fn k_dop_corners(points: &[DVec3], k: usize) -> Vec<DVec3> {
+    // Planes as outward normal and offset, n . p <= d inside
+    let mut planes = Vec::new();
+    for direction in k_dop_directions(k) {
+        let (low, high) = points.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(low, high), point| {
+            let distance = direction.dot(*point);
+            (low.min(distance), high.max(distance))
+        });
+        planes.push((direction, high));
+        planes.push((-direction, -low));
+    }
+    let scale = planes.iter().map(|(_, offset)| offset.abs()).fold(1e-9, f64::max);
+    let tolerance = scale * 1e-9;
+    
+    let mut corners = Vec::new();
+    for a in 0..planes.len() {
+        for b in a + 1..planes.len() {
+            for c in b + 1..planes.len() {
+                let ((na, da), (nb, db), (nc, dc)) = (planes[a], planes[b], planes[c]);
+                let determinant = na.dot(nb.cross(nc));
+                if determinant.abs() < 1e-9 {
+                    continue;
+                }
+                let corner = (nb.cross(nc) * da + nc.cross(na) * db + na.cross(nb) * dc) / determinant;
+                if planes.iter().all(|(normal, offset)| normal.dot(corner) <= offset + tolerance) {
+                    corners.push(corner);
+                }
+            }
+        }
+    }
+    corners
+}
+
+/// Convex hull of points as triangles; None when they don't span a volume
+///
+/// Incremental: each point outside the hull so far replaces the faces it
+/// sees with a fan from itself to their horizon.
+fn convex_hull(points: &[DVec3]) -> Option<VolumeMesh> {
+    let extent = points.iter().fold(0.0f64, |extent, point| extent.max(point.abs().max_element()));
+    let tolerance = extent.max(1e-9) * 1e-9;
+    
+    // Start from a tetrahedron of far apart points
+    let first = (0..points.len()).min_by(|&i, &j| points[i].x.total_cmp(&points[j].x))?;
+    let farthest = |distance: &dyn Fn(DVec3) -> f64| {
+        (0..points.len()).max_by(|&i, &j| distance(points[i]).total_cmp(&distance(points[j])))
+    };
+    let second = farthest(&|point| point.distance(points[first]))?;
+    let axis = (points[second] - points[first]).normalize_or_zero();
+    let third = farthest(&|point| (point - points[first]).reject_from_normalized(axis).length())?;
+    let normal = (points[second] - points[first]).cross(points[third] - points[first]).normalize_or_zero();
+    let fourth = farthest(&|point| normal.dot(point - points[first]).abs())?;
+    if normal == DVec3::ZERO || normal.dot(points[fourth] - points[first]).abs() <= tolerance {
+        return None;
+    }
+    
+    let mut faces: Vec<[usize; 3]> = Vec::new();
+    let outward = |face: [usize; 3], inside: DVec3| {
+        let [a, b, c] = face.map(|index| points[index]);
+        if (b - a).cross(c - a).dot(inside - a) > 0.0 { [face[0], face[2], face[1]] } else { face }
+    };
+    let center = (points[first] + points[second] + points[third] + points[fourth]) * 0.25;
+    for face in [[first, second, third], [first, second, fourth], [first, third, fourth], [second, third, fourth]] {
+        faces.push(outward(face, center));
+    }
+    
+    let sees = |face: &[usize; 3], point: DVec3| {
+        let [a, b, c] = face.map(|index| points[index]);
+        let normal = (b - a).cross(c - a);
+        normal.dot(point - a) > tolerance * normal.length()
+    };
+    for (index, point) in points.iter().enumerate() {
+        // Most points of a detailed mesh lie inside, so check before rebuilding the faces
+        if !faces.iter().any(|face| sees(face, *point)) {
+            continue;
+        }
+        let (visible, hidden): (Vec<[usize; 3]>, Vec<[usize; 3]>) = faces.iter().partition(|face| sees(face, *point));
+        let edges: HashSet<(usize, usize)> = visible.iter()
+            .flat_map(|&[a, b, c]| [(a, b), (b, c), (c, a)])
+            .collect();
+        faces = hidden;
+        for &(a, b) in &edges {
+            if !edges.contains(&(b, a)) {
+                faces.push([a, b, index]);
+            }
+        }
+    }
+    
+    // Keep only the points on the hull, in input order
+    let mut remap = vec![usize::MAX; points.len()];
+    let mut used: Vec<usize> = faces.iter().flatten().copied().collect();
+    used.sort_unstable();
+    used.dedup();
+    for (new, &old) in used.iter().enumerate() {
+        remap[old] = new;
+    }
+    Some(VolumeMesh {
+        points: used.iter().map(|&index| points[index].to_array()).collect(),
+        face_vertex_counts: vec![3; faces.len()],
+        face_vertex_indices: faces.iter().flatten().map(|&index| remap[index] as i32).collect(),
+    })
+}
+
+/// A bounding volume to author under a prim
+#[derive(Debug, Clone, PartialEq)]
+pub struct USDBoundingVolume {
+    pub prim_path: String,
+    /// Name of the volume's mesh under the prim
+    pub name: String,
+    pub shape: VolumeShape,
+    /// Apply the UsdPhysics collision schemas
+    pub collision: bool,
+    pub mesh: VolumeMesh,
+}
+
+impl USDBoundingVolume {
+    pub fn path(&self) -> String {
+        format!("{}/{}", self.prim_path.trim_end_matches('/'), self.name)
+    }
+    
+    /// The guide mesh, and its physics attributes for collisions
+    ///
+    /// The collision and mesh collision API schemas are applied separately,
+    /// see `API_SCHEMAS`.
+    pub fn edits(&self) -> (Vec<USDPrimSpec>, Vec<USDAttributeEdit>) {
+        let path = self.path();
+        let edit = |attr_name: &str, value: UsdValue| USDAttributeEdit {
+            prim_path: path.clone(),
+            attr_name: attr_name.to_string(),
+            value,
+        };
+        let ints = |values: &[i32]| UsdValue::Array(values.iter().map(|&value| UsdValue::Int(value.into())).collect());
+        let mut edits = vec![
+            edit("points", UsdValue::Array(self.mesh.points.iter().copied().map(UsdValue::Vec3).collect())),
+            edit("faceVertexCounts", ints(&self.mesh.face_vertex_counts)),
+            edit("faceVertexIndices", ints(&self.mesh.face_vertex_indices)),
+            edit("subdivisionScheme", UsdValue::Token("none".to_string())),
+            edit("purpose", UsdValue::Token("guide".to_string())),
+        ];
+        if self.collision {
+            edits.push(edit("physics:collisionEnabled", UsdValue::Bool(true)));
+            edits.push(edit("physics:approximation", UsdValue::Token(self.shape.approximation().to_string())));
+        }
+        (vec![USDPrimSpec { path, prim_type: "Mesh".to_string() }], edits)
+    }
+}
+
+/// API schemas applied to collision volumes
+pub const API_SCHEMAS: [&str; 2] = ["PhysicsCollisionAPI", "PhysicsMeshCollisionAPI"];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    /// Whether every face winds away from the points' center and no point lies outside a face
+    fn is_convex_and_closed(mesh: &VolumeMesh, points: &[[f64; 3]]) -> bool {
+        let center = mesh.points.iter().map(|&point| DVec3::from(point)).sum::<DVec3>() / mesh.points.len() as f64;
+        let mut offset = 0;
+        mesh.face_vertex_counts.iter().all(|&count| {
+            let corners: Vec<DVec3> = mesh.face_vertex_indices[offset..offset + count as usize].iter()
+                .map(|&index| DVec3::from(mesh.points[index as usize]))
+                .collect();
+            offset += count as usize;
+            let normal = (corners[1] - corners[0]).cross(corners[2] - corners[0]);
+            normal.dot(corners[0] - center) > 0.0
+                && points.iter().all(|&point| normal.dot(DVec3::from(point) - corners[0]) <= 1e-9)
+        })
+    }
+    
+    #[test]
+    fn volumes_enclose_their_points() {
+        // A unit cube's corners plus interior points
+        let mut points: Vec<[f64; 3]> = (0..8).map(|corner| [(corner & 1) as f64, ((corner >> 1) & 1) as f64, ((corner >> 2) & 1) as f64]).collect();
+        points.extend([[0.5, 0.5, 0.5], [0.25, 0.75, 0.5]]);
+        
+        let hull = bounding_volume(&points, VolumeShape::ConvexHull).unwrap();
+        assert_eq!((hull.points.len(), hull.face_vertex_counts.len()), (8, 12));
+        assert!(is_convex_and_closed(&hull, &points));
+        let cube = bounding_volume(&points, VolumeShape::Box).unwrap();
+        assert_eq!(cube.face_vertex_counts, [4; 6]);
+        assert!(is_convex_and_closed(&cube, &points));
+        
+        // A tetrahedron's 14-DOP cuts its box's corners but keeps its own
+        let tetrahedron = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+        for k in [14, 18, 26] {
+            let dop = bounding_volume(&tetrahedron, VolumeShape::KDop(k)).unwrap();
+            assert!(is_convex_and_closed(&dop, &tetrahedron), "{}-DOP", k);
+            assert!(!dop.points.contains(&[1.0, 1.0, 1.0]));
+        }
+        
+        // Flat points get a flat box
+        let flat = bounding_volume(&[[0.0, 0.0, 0.0], [2.0, 0.0, 0.0], [0.0, 0.0, 3.0], [1.0, 0.0, 1.0]], VolumeShape::ConvexHull).unwrap();
+        assert_eq!(flat.face_vertex_counts.len(), 6);
+        assert!(bounding_volume(&[], VolumeShape::Box).is_none());
+        
+        let volume = USDBoundingVolume {
+            prim_path: "/World/crate".to_string(),
+            name: "collision".to_string(),
+            shape: VolumeShape::Box,
+            collision: true,
+            mesh: cube,
+        };
+        let (prims, edits) = volume.edits();
+        assert_eq!(prims[0].path, "/World/crate/collision");
+        assert_eq!(edits.last().unwrap().value, UsdValue::Token("boundingCube".to_string()));
+    }
+}
@@ -0,0 +1,181 @@
+//! Axis-aligned bounding boxes
+//!
+//! Prim and stage bounds come from UsdGeomBBoxCache through
+//! `USDEngine::compute_bounds`; this module holds the box math shared by
+//! the engine and the viewport's framing, culling and bounds display.
+
+use glam::{Mat4, Vec3};
+
+/// Axis-aligned box; an empty box has min above max
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Default for BoundingBox {
+    fn default() -> Self {
+        Self::EMPTY
+    }
+}
+
+impl BoundingBox {
+    /// Box containing nothing, the identity of `union`
+    pub const EMPTY: Self = Self { min: Vec3::splat(f32::INFINITY), max: Vec3::splat(f32::NEG_INFINITY) };
+    
+    pub fn new(min: Vec3, max: Vec3) -> Self {
+        Self { min, max }
+    }
+    
+    /// Bounds of a flat xyz position array
+    pub fn from_positions(positions: &[f32]) -> Self {
+        positions.chunks_exact(3).fold(Self::EMPTY, |bounds, p| bounds.including(Vec3::new(p[0], p[1], p[2])))
+    }
+    
+    /// Bounds of a UsdGeomBoundable extent, [min, max]
+    pub fn from_extent(extent: &[f64]) -> Option<Self> {
+        match extent {
+            [x0, y0, z0, x1, y1, z1] => Some(Self::new(
+                Vec3::new(*x0 as f32, *y0 as f32, *z0 as f32),
+                Vec3::new(*x1 as f32, *y1 as f32, *z1 as f32),
+            )),
+            _ => None,
+        }
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.min.cmpgt(self.max).any()
+    }
+    
+    pub fn including(self, point: Vec3) -> Self {
+        Self::new(self.min.min(point), self.max.max(point))
+    }
+    
+    pub fn union(self, other: Self) -> Self {
+        Self::new(self.min.min(other.min), self.max.max(other.max))
+    }
+    
+    pub fn center(&self) -> Vec3 {
+        (self.min + self.max) * 0.5
+    }
+    
+    pub fn size(&self) -> Vec3 {
+        (self.max - self.min).max(Vec3::ZERO)
+    }
+    
+    /// Radius of the sphere around the box
+    pub fn radius(&self) -> f32 {
+        self.size().length() * 0.5
+    }
+    
+    pub fn corners(&self) -> [Vec3; 8] {
+        let (a, b) = (self.min, self.max);
+        [
+            Vec3::new(a.x, a.y, a.z), Vec3::new(b.x, a.y, a.z),
+            Vec3::new(b.x, b.y, a.z), Vec3::new(a.x, b.y, a.z),
+            Vec3::new(a.x, a.y, b.z), Vec3::new(b.x, a.y, b.z),
+            Vec3::new(b.x, b.y, b.z), Vec3::new(a.x, b.y, b.z),
+        ]
+    }
+    
+    /// Axis-aligned bounds of the box after a transform
+    pub fn transformed(&self, transform: &Mat4) -> Self {
+        if self.is_empty() {
+            return *self;
+        }
+        self.corners().iter().fold(Self::EMPTY, |bounds, corner| bounds.including(transform.transform_point3(*corner)))
+    }
+    
+    /// Whether any part of the box may be inside a view frustum given as a world-to-clip transform
+    ///
+    /// Conservative: a box is only rejected when all its corners lie outside
+    /// the same clip plane. Depth runs 0..1 as with `Mat4::perspective_rh`.
+    pub fn intersects_frustum(&self, view_projection: &Mat4) -> bool {
+        if self.is_empty() {
+            return false;
+        }
+        let clip = self.corners().map(|corner| *view_projection * corner.extend(1.0));
+        let outside = |plane: fn(glam::Vec4) -> bool| clip.iter().all(|c| plane(*c));
+        !(outside(|c| c.x < -c.w) || outside(|c| c.x > c.w)
+            || outside(|c| c.y < -c.w) || outside(|c| c.y > c.w)
+            || outside(|c| c.z < 0.0) || outside(|c| c.z > c.w))
+    }
+    
+    /// Flat-shaded box as xyz positions, normals and triangle indices
+    pub fn box_geometry(&self) -> (Vec<f32>, Vec<f32>, Vec<u32>) {
+        // Corners of each face, counter-clockwise seen from outside, and its normal
+        const FACES: [([usize; 4], [f32; 3]); 6] = [
+            ([0, 3, 2, 1], [0.0, 0.0, -1.0]),
+            ([4, 5, 6, 7], [0.0, 0.0, 1.0]),
+            ([0, 1, 5, 4], [0.0, -1.0, 0.0]),
+            ([3, 7, 6, 2], [0.0, 1.0, 0.0]),
+            ([0, 4, 7, 3], [-1.0, 0.0, 0.0]),
+            ([1, 2, 6, 5], [1.0, 0.0, 0.0]),
+        ];
+        let corners = self.corners();
+        let mut positions = Vec::with_capacity(72);
+        let mut normals = Vec::with_capacity(72);
+        let mut indices = Vec::with_capacity(36);
+        for (face, (corner_indices, normal)) in FACES.iter().enumerate() {
+            for corner in corner_indices {
+                positions.extend(corners[*corner].to_array());
+                normals.extend(normal);
+            }
+            let base = face as u32 * 4;
+            indices.extend([base, base + 1, base + 2, base, base + 2, base + 3]);
+        }
+        (positions, normals, indices)
+    }
+    
+    /// Min and max as arrays, as `SceneData::bounding_box` stores them
+    pub fn to_arrays(&self) -> ([f32; 3], [f32; 3]) {
+        (self.min.into(), self.max.into())
+    }
+}
+
+/// Bounds of a prim at a time code
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct USDBounds {
+    /// Axis-aligned in world space, including the prim's transform
+    pub world: BoundingBox,
+    /// In the prim's own space, without its transform
+    pub local: BoundingBox,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn boxes_transform_and_cull() {
+        let unit = BoundingBox::from_positions(&[-1.0, -1.0, -1.0, 1.0, 1.0, 1.0, 0.0, 0.5, 0.0]);
+        assert_eq!(unit, BoundingBox::new(Vec3::NEG_ONE, Vec3::ONE));
+        assert!(BoundingBox::EMPTY.is_empty() && !unit.is_empty());
+        assert_eq!(BoundingBox::EMPTY.union(unit), unit);
+        assert_eq!(BoundingBox::from_extent(&[0.0, 0.0, 0.0, 2.0, 4.0, 6.0]).unwrap().center(), Vec3::new(1.0, 2.0, 3.0));
+        
+        let moved = unit.transformed(&Mat4::from_translation(Vec3::X * 5.0));
+        assert_eq!(moved.center(), Vec3::new(5.0, 0.0, 0.0));
+        // A 45 degree turn widens the axis-aligned box
+        let turned = unit.transformed(&Mat4::from_rotation_y(std::f32::consts::FRAC_PI_4));
+        assert!((turned.max.x - 2f32.sqrt()).abs() < 1e-5 && (turned.max.y - 1.0).abs() < 1e-5);
+        
+        // Camera at z = 10 looking down -z
+        let view_projection = Mat4::perspective_rh(1.0, 1.0, 0.1, 100.0) * Mat4::look_at_rh(Vec3::Z * 10.0, Vec3::ZERO, Vec3::Y);
+        assert!(unit.intersects_frustum(&view_projection));
+        assert!(!moved.transformed(&Mat4::from_translation(Vec3::X * 100.0)).intersects_frustum(&view_projection));
+        assert!(!unit.transformed(&Mat4::from_translation(Vec3::Z * 20.0)).intersects_frustum(&view_projection));
+        assert!(!BoundingBox::EMPTY.intersects_frustum(&view_projection));
+        
+        let (positions, normals, indices) = unit.box_geometry();
+        assert_eq!((positions.len(), normals.len(), indices.len()), (72, 72, 36));
+        assert_eq!(BoundingBox::from_positions(&positions), unit);
+        // Every triangle winds towards its face normal
+        for triangle in indices.chunks_exact(3) {
+            let point = |index: u32| Vec3::from_slice(&positions[index as usize * 3..]);
+            let [a, b, c] = [point(triangle[0]), point(triangle[1]), point(triangle[2])];
+            let normal = Vec3::from_slice(&normals[triangle[0] as usize * 3..]);
+            assert!((b - a).cross(c - a).dot(normal) > 0.0);
+        }
+    }
+}
@@ -0,0 +1,198 @@
+//! Bounding volume hierarchy over triangles for ray casts
+//!
+//! Triangles are split at the median of their centroids along the longest
+//! axis of the node's bounds until a leaf holds a few. Rays walk the tree
+//! nearer child first and skip nodes beyond the closest hit so far, testing
+//! triangles with Möller-Trumbore.
+
+use glam::Vec3;
+
+/// Triangles per leaf
+const LEAF_SIZE: usize = 4;
+
+/// A triangle tagged with the index of what it belongs to, such as a mesh
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Triangle {
+    pub corners: [Vec3; 3],
+    pub owner: usize,
+}
+
+impl Triangle {
+    fn centroid(&self) -> Vec3 {
+        (self.corners[0] + self.corners[1] + self.corners[2]) / 3.0
+    }
+    
+    /// Unit normal, facing the side the corners wind counter-clockwise on
+    fn normal(&self) -> Vec3 {
+        let [a, b, c] = self.corners;
+        (b - a).cross(c - a).normalize_or_zero()
+    }
+    
+    /// Distance along a ray to the triangle, hitting either side
+    fn intersect(&self, origin: Vec3, direction: Vec3) -> Option<f32> {
+        let [a, b, c] = self.corners;
+        let (edge1, edge2) = (b - a, c - a);
+        let p = direction.cross(edge2);
+        let determinant = edge1.dot(p);
+        if determinant.abs() < 1e-12 {
+            return None;
+        }
+        let inverse = 1.0 / determinant;
+        let s = origin - a;
+        let u = s.dot(p) * inverse;
+        if !(0.0..=1.0).contains(&u) {
+            return None;
+        }
+        let q = s.cross(edge1);
+        let v = direction.dot(q) * inverse;
+        if v < 0.0 || u + v > 1.0 {
+            return None;
+        }
+        let distance = edge2.dot(q) * inverse;
+        (distance > 1e-6).then_some(distance)
+    }
+}
+
+/// Nearest triangle along a ray
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hit {
+    pub distance: f32,
+    pub owner: usize,
+    /// Normal of the triangle hit, whichever side the ray came from
+    pub normal: Vec3,
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    min: Vec3,
+    max: Vec3,
+    /// Children for inner nodes, a triangle range for leaves
+    kind: NodeKind,
+}
+
+#[derive(Debug, Clone)]
+enum NodeKind {
+    Inner(usize, usize),
+    Leaf(usize, usize),
+}
+
+impl Node {
+    /// Distance along a ray to the node's box, if the ray enters it before `max_distance`
+    fn entry(&self, origin: Vec3, inverse_direction: Vec3, max_distance: f32) -> Option<f32> {
+        let near = (self.min - origin) * inverse_direction;
+        let far = (self.max - origin) * inverse_direction;
+        let enter = near.min(far).max_element().max(0.0);
+        let exit = near.max(far).min_element().min(max_distance);
+        (enter <= exit).then_some(enter)
+    }
+}
+
+/// Bounding volume hierarchy built once over a triangle list
+#[derive(Debug, Clone, Default)]
+pub struct Bvh {
+    triangles: Vec<Triangle>,
+    nodes: Vec<Node>,
+}
+
+impl Bvh {
+    pub fn new(mut triangles: Vec<Triangle>) -> Self {
+        let mut nodes = Vec::new();
+        if !triangles.is_empty() {
+            let count = triangles.len();
+            build(&mut triangles, 0, count, &mut nodes);
+        }
+        Self { triangles, nodes }
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.triangles.is_empty()
+    }
+    
+    pub fn len(&self) -> usize {
+        self.triangles.len()
+    }
+    
+    /// Nearest triangle a ray hits within `max_distance`
+    pub fn intersect(&self, origin: Vec3, direction: Vec3, max_distance: f32) -> Option<Hit> {
+        let root = self.nodes.first()?;
+        let inverse_direction = direction.recip();
+        let mut nearest: Option<Hit> = None;
+        let mut stack = vec![(0, root.entry(origin, inverse_direction, max_distance)?)];
+        while let Some((index, entry)) = stack.pop() {
+            let limit = nearest.map_or(max_distance, |hit| hit.distance);
+            if entry > limit {
+                continue;
+            }
+            match self.nodes[index].kind {
+                NodeKind::Leaf(start, count) => {
+                    for triangle in &self.triangles[start..start + count] {
+                        if let Some(distance) = triangle.intersect(origin, direction) {
+                            if distance <= nearest.map_or(max_distance, |hit| hit.distance) {
+                                nearest = Some(Hit { distance, owner: triangle.owner, normal: triangle.normal() });
+                            }
+                        }
+                    }
+                }
+                NodeKind::Inner(left, right) => {
+                    let mut children: Vec<(usize, f32)> = [left, right].into_iter()
+                        .filter_map(|child| Some((child, self.nodes[child].entry(origin, inverse_direction, limit)?)))
+                        .collect();
+                    // Pushed farther first so the nearer child is walked first
+                    children.sort_by(|a, b| b.1.total_cmp(&a.1));
+                    stack.extend(children);
+                }
+            }
+        }
+        nearest
+    }
+}
+
+/// Build the subtree over `triangles[start..start + count]`, returning its node index
+fn build(triangles: &mut [Triangle], start: usize, count: usize, nodes: &mut Vec<Node>) -> usize {
+    let range = &mut triangles[start..start + count];
+    let (min, max) = range.iter().flat_map(|triangle| triangle.corners)
+        .fold((Vec3::INFINITY, Vec3::NEG_INFINITY), |(min, max), corner| (min.min(corner), max.max(corner)));
+    let index = nodes.len();
+    nodes.push(Node { min, max, kind: NodeKind::Leaf(start, count) });
+    if count <= LEAF_SIZE {
+        return index;
+    }
+    
+    let extent = max - min;
+    let axis = if extent.x >= extent.y && extent.x >= extent.z { 0 } else if extent.y >= extent.z { 1 } else { 2 };
+    let half = count / 2;
+    range.select_nth_unstable_by(half, |a, b| a.centroid()[axis].total_cmp(&b.centroid()[axis]));
+    let left = build(triangles, start, half, nodes);
+    let right = build(triangles, start + half, count - half, nodes);
+    nodes[index].kind = NodeKind::Inner(left, right);
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn rays_hit_the_nearest_triangle() {
+        // A row of unit quads facing +z at increasing depth, each behind the last
+        let triangles: Vec<Triangle> = (0..20).flat_map(|owner| {
+            let z = -(owner as f32);
+            let [a, b, c, d] = [Vec3::new(-1.0, -1.0, z), Vec3::new(1.0, -1.0, z), Vec3::new(1.0, 1.0, z), Vec3::new(-1.0, 1.0, z)];
+            [Triangle { corners: [a, b, c], owner }, Triangle { corners: [a, c, d], owner }]
+        }).collect();
+        let bvh = Bvh::new(triangles);
+        assert_eq!(bvh.len(), 40);
+        
+        let hit = bvh.intersect(Vec3::new(0.2, 0.3, 5.0), Vec3::NEG_Z, f32::INFINITY).unwrap();
+        assert_eq!(hit.owner, 0);
+        assert!((hit.distance - 5.0).abs() < 1e-5);
+        assert_eq!(hit.normal, Vec3::Z);
+        // From inside the row, and from behind it
+        assert_eq!(bvh.intersect(Vec3::new(0.0, 0.0, -7.5), Vec3::NEG_Z, f32::INFINITY).unwrap().owner, 8);
+        assert_eq!(bvh.intersect(Vec3::new(0.0, 0.0, -30.0), Vec3::Z, f32::INFINITY).unwrap().owner, 19);
+        // Beyond the limit, and beside the quads
+        assert!(bvh.intersect(Vec3::new(0.0, 0.0, 5.0), Vec3::NEG_Z, 4.0).is_none());
+        assert!(bvh.intersect(Vec3::new(3.0, 0.0, 5.0), Vec3::NEG_Z, f32::INFINITY).is_none());
+        assert!(Bvh::new(Vec::new()).intersect(Vec3::ZERO, Vec3::X, 1.0).is_none());
+    }
+}
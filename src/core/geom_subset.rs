@@ -0,0 +1,183 @@
+//! Face subsets of meshes
+//!
+//! A UsdGeomSubset names some of a mesh's faces by index. Subsets in the
+//! "materialBind" family carry per-face material bindings; USD expects that
+//! family to be nonOverlapping, so its familyType is authored on the mesh
+//! together with the subset.
+
+use crate::core::usd_engine::{USDAttributeEdit, USDPrimSpec};
+use crate::core::usd_value::UsdValue;
+
+/// Subset family whose members bind materials per face
+pub const MATERIAL_BIND_FAMILY: &str = "materialBind";
+
+/// A named set of a mesh's faces
+#[derive(Debug, Clone, PartialEq)]
+pub struct USDGeomSubset {
+    pub mesh_path: String,
+    pub name: String,
+    /// Subset family; empty leaves the subset out of any family
+    pub family: String,
+    /// Face indices, sorted without duplicates
+    pub faces: Vec<u32>,
+    /// Material bound to the subset; None leaves its binding alone
+    pub material: Option<String>,
+}
+
+impl USDGeomSubset {
+    /// Subsets are children of their mesh
+    pub fn path(&self) -> String {
+        format!("{}/{}", self.mesh_path, self.name)
+    }
+    
+    /// The subset prim, its elementType, familyName and indices, and the family's type on the mesh
+    pub fn edits(&self) -> (Vec<USDPrimSpec>, Vec<USDAttributeEdit>) {
+        let path = self.path();
+        let edit = |prim_path: &str, attr_name: &str, value: UsdValue| USDAttributeEdit {
+            prim_path: prim_path.to_string(),
+            attr_name: attr_name.to_string(),
+            value,
+        };
+        let mut edits = vec![
+            edit(&path, "elementType", UsdValue::Token("face".to_string())),
+            edit(&path, "familyName", UsdValue::Token(self.family.clone())),
+            edit(&path, "indices", UsdValue::Array(self.faces.iter().map(|&face| UsdValue::Int(face.into())).collect())),
+        ];
+        if self.family == MATERIAL_BIND_FAMILY {
+            let family_type = format!("subsetFamily:{}:familyType", self.family);
+            edits.push(edit(&self.mesh_path, &family_type, UsdValue::Token("nonOverlapping".to_string())));
+        }
+        (vec![USDPrimSpec { path, prim_type: "GeomSubset".to_string() }], edits)
+    }
+}
+
+/// Parse face indices like "0, 4, 10-12", sorted without duplicates
+pub fn parse_faces(text: &str) -> Result<Vec<u32>, String> {
+    let mut faces = Vec::new();
+    for item in text.split([',', ' ']).map(str::trim).filter(|item| !item.is_empty()) {
+        let invalid = || format!("Invalid face index '{}'", item);
+        match item.split_once('-') {
+            Some((start, end)) => {
+                let (start, end): (u32, u32) = (start.parse().map_err(|_| invalid())?, end.parse().map_err(|_| invalid())?);
+                faces.extend(start..=end);
+            }
+            None => faces.push(item.parse().map_err(|_| invalid())?),
+        }
+    }
+    faces.sort_unstable();
+    faces.dedup();
+    Ok(faces)
+}
+
+/// Format sorted face indices for `parse_faces`, collapsing runs into ranges
+pub fn format_faces(faces: &[u32]) -> String {
+    let mut runs: Vec<(u32, u32)> = Vec::new();
+    for &face in faces {
+        match runs.last_mut() {
+            Some((_, end)) if face == *end + 1 => *end = face,
+            _ => runs.push((face, face)),
+        }
+    }
+    runs.iter()
+        .map(|&(start, end)| if start == end { start.to_string() } else { format!("{}-{}", start, end) })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Topology of a mesh made of some faces of another
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubsetMesh {
+    /// Source point of every point of the new mesh
+    pub points: Vec<usize>,
+    pub face_vertex_counts: Vec<i32>,
+    pub face_vertex_indices: Vec<i32>,
+}
+
+/// Cut the given faces out of a mesh's topology, keeping only the points they use
+///
+/// Points keep their source order, so the new mesh reads like the original.
+pub fn extract_faces(point_count: usize, face_vertex_counts: &[i32], face_vertex_indices: &[i32], faces: &[u32]) -> Result<SubsetMesh, String> {
+    let mut offsets = Vec::with_capacity(face_vertex_counts.len());
+    let mut offset = 0usize;
+    for &count in face_vertex_counts {
+        offsets.push(offset);
+        offset += count.max(0) as usize;
+    }
+    if offset > face_vertex_indices.len() {
+        return Err(format!("Faces use {} indices but the mesh has {}", offset, face_vertex_indices.len()));
+    }
+    
+    let mut corners = Vec::new();
+    let mut counts = Vec::with_capacity(faces.len());
+    for &face in faces {
+        let (&start, &count) = offsets.get(face as usize).zip(face_vertex_counts.get(face as usize))
+            .ok_or_else(|| format!("Face {} is out of range of {} faces", face, face_vertex_counts.len()))?;
+        let face_corners = &face_vertex_indices[start..start + count.max(0) as usize];
+        if let Some(&bad) = face_corners.iter().find(|&&point| point < 0 || point as usize >= point_count) {
+            return Err(format!("Face {} references point {} out of {}", face, bad, point_count));
+        }
+        counts.push(count);
+        corners.extend_from_slice(face_corners);
+    }
+    
+    let mut remap = vec![-1i32; point_count];
+    let mut used: Vec<usize> = corners.iter().map(|&point| point as usize).collect();
+    used.sort_unstable();
+    used.dedup();
+    for (new, &old) in used.iter().enumerate() {
+        remap[old] = new as i32;
+    }
+    Ok(SubsetMesh {
+        points: used,
+        face_vertex_counts: counts,
+        face_vertex_indices: corners.iter().map(|&point| remap[point as usize]).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn face_lists_round_trip_through_ranges() {
+        let faces = parse_faces("7, 0-3 2,10").unwrap();
+        assert_eq!(faces, [0, 1, 2, 3, 7, 10]);
+        assert_eq!(format_faces(&faces), "0-3, 7, 10");
+        assert_eq!(parse_faces(&format_faces(&faces)).unwrap(), faces);
+        assert!(parse_faces("1, x").is_err());
+        assert!(parse_faces("").unwrap().is_empty());
+    }
+    
+    #[test]
+    fn subset_edits_mark_material_families_non_overlapping() {
+        let mut subset = USDGeomSubset {
+            mesh_path: "/World/Body".to_string(),
+            name: "glass".to_string(),
+            family: MATERIAL_BIND_FAMILY.to_string(),
+            faces: vec![2, 5],
+            material: None,
+        };
+        let (prims, edits) = subset.edits();
+        assert_eq!(prims, [USDPrimSpec { path: "/World/Body/glass".to_string(), prim_type: "GeomSubset".to_string() }]);
+        assert_eq!(edits[2].value, UsdValue::Array(vec![UsdValue::Int(2), UsdValue::Int(5)]));
+        assert_eq!(edits[3].prim_path, "/World/Body");
+        assert_eq!(edits[3].attr_name, "subsetFamily:materialBind:familyType");
+        
+        subset.family = "parts".to_string();
+        assert_eq!(subset.edits().1.len(), 3);
+    }
+    
+    #[test]
+    fn extracted_faces_keep_only_their_points() {
+        // Two quads sharing an edge, then a triangle
+        let counts = [4, 4, 3];
+        let indices = [0, 1, 4, 3, 1, 2, 5, 4, 3, 4, 6];
+        let mesh = extract_faces(7, &counts, &indices, &[1, 2]).unwrap();
+        assert_eq!(mesh.points, [1, 2, 3, 4, 5, 6]);
+        assert_eq!(mesh.face_vertex_counts, [4, 3]);
+        assert_eq!(mesh.face_vertex_indices, [0, 1, 4, 3, 2, 3, 5]);
+        
+        assert!(extract_faces(7, &counts, &indices, &[3]).is_err());
+        assert!(extract_faces(5, &counts, &indices, &[2]).is_err());
+    }
+}
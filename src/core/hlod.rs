@@ -0,0 +1,302 @@
+//! Hierarchical LOD proxies
+//!
+//! Meshes under a root are clustered on a grid by the center of their world
+//! bounds, so meshes near each other share one proxy. Each cluster's meshes
+//! are merged in world space and decimated by vertex clustering: points in
+//! the same cell of a finer grid are welded at their average and faces left
+//! with fewer than three corners are dropped. The proxies are authored in a
+//! scope under the root, which gets a "lod" variant set switching between
+//! the full-resolution meshes and the proxies; each proxy carries the camera
+//! distance past which a runtime should switch to it.
+
+use std::collections::{BTreeMap, HashMap};
+use glam::DVec3;
+use crate::core::usd_engine::{USDAttributeEdit, USDPrimSpec};
+use crate::core::usd_value::UsdValue;
+
+/// Variant set authored on the root
+pub const VARIANT_SET: &str = "lod";
+/// Variant showing the full-resolution meshes, selected by default
+pub const FULL_VARIANT: &str = "full";
+/// Variant showing the proxies
+pub const HLOD_VARIANT: &str = "hlod";
+/// Camera distance past which a proxy stands in for its cluster
+pub const SWITCH_DISTANCE_ATTR: &str = "hlod:switchDistance";
+
+/// A mesh with its points in world space
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SourceMesh {
+    pub prim_path: String,
+    pub points: Vec<[f64; 3]>,
+    pub face_vertex_counts: Vec<i32>,
+    pub face_vertex_indices: Vec<i32>,
+}
+
+impl SourceMesh {
+    /// Center of the points' bounding box
+    fn center(&self) -> DVec3 {
+        let (min, max) = bounds(&self.points);
+        (min + max) * 0.5
+    }
+}
+
+/// Points and polygon topology of a merged proxy
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProxyMesh {
+    pub points: Vec<[f64; 3]>,
+    pub face_vertex_counts: Vec<i32>,
+    pub face_vertex_indices: Vec<i32>,
+}
+
+impl ProxyMesh {
+    /// Half the diagonal of the points' bounding box
+    pub fn radius(&self) -> f64 {
+        if self.points.is_empty() {
+            return 0.0;
+        }
+        let (min, max) = bounds(&self.points);
+        (max - min).length() * 0.5
+    }
+}
+
+fn bounds(points: &[[f64; 3]]) -> (DVec3, DVec3) {
+    points.iter().fold((DVec3::splat(f64::INFINITY), DVec3::splat(f64::NEG_INFINITY)), |(min, max), &point| {
+        (min.min(DVec3::from(point)), max.max(DVec3::from(point)))
+    })
+}
+
+/// Group meshes by the grid cell of `cell_size` their center falls in
+///
+/// Clusters are lists of mesh indices, ordered by cell. Meshes without
+/// points are left out.
+pub fn cluster_meshes(meshes: &[SourceMesh], cell_size: f64) -> Vec<Vec<usize>> {
+    let cell_size = cell_size.max(f64::EPSILON);
+    let mut cells: BTreeMap<[i64; 3], Vec<usize>> = BTreeMap::new();
+    for (index, mesh) in meshes.iter().enumerate().filter(|(_, mesh)| !mesh.points.is_empty()) {
+        let cell = (mesh.center() / cell_size).floor().as_i64vec3().to_array();
+        cells.entry(cell).or_default().push(index);
+    }
+    cells.into_values().collect()
+}
+
+/// Merge meshes into one, offsetting each mesh's indices past the points before it
+pub fn merge_meshes(meshes: &[&SourceMesh]) -> ProxyMesh {
+    let mut merged = ProxyMesh::default();
+    for mesh in meshes {
+        let offset = merged.points.len() as i32;
+        merged.points.extend_from_slice(&mesh.points);
+        merged.face_vertex_counts.extend_from_slice(&mesh.face_vertex_counts);
+        merged.face_vertex_indices.extend(mesh.face_vertex_indices.iter().map(|index| index + offset));
+    }
+    merged
+}
+
+/// Weld points within cells of `cell_size` and drop the faces that collapse
+///
+/// Faces with out-of-range indices or counts running past the indices are
+/// dropped too, as are points no face uses anymore.
+pub fn decimate(mesh: &ProxyMesh, cell_size: f64) -> ProxyMesh {
+    let cell_size = cell_size.max(f64::EPSILON);
+    let mut cells: HashMap<[i64; 3], usize> = HashMap::new();
+    let mut sums: Vec<(DVec3, f64)> = Vec::new();
+    let welded: Vec<usize> = mesh.points.iter()
+        .map(|&point| {
+            let point = DVec3::from(point);
+            let cell = (point / cell_size).floor().as_i64vec3().to_array();
+            let index = *cells.entry(cell).or_insert_with(|| {
+                sums.push((DVec3::ZERO, 0.0));
+                sums.len() - 1
+            });
+            sums[index].0 += point;
+            sums[index].1 += 1.0;
+            index
+        })
+        .collect();
+    
+    let mut faces: Vec<Vec<usize>> = Vec::new();
+    let mut offset = 0;
+    for &count in &mesh.face_vertex_counts {
+        let Some(corners) = mesh.face_vertex_indices.get(offset..offset + count.max(0) as usize) else {
+            break;
+        };
+        offset += count.max(0) as usize;
+        let Some(mut face) = corners.iter().map(|&index| welded.get(index as usize).copied()).collect::<Option<Vec<usize>>>() else {
+            continue;
+        };
+        face.dedup();
+        while face.len() > 1 && face.first() == face.last() {
+            face.pop();
+        }
+        let mut distinct = face.clone();
+        distinct.sort_unstable();
+        distinct.dedup();
+        if distinct.len() >= 3 {
+            faces.push(face);
+        }
+    }
+    
+    // Keep only the welded points faces still use, in first-use order
+    let mut remap: HashMap<usize, i32> = HashMap::new();
+    let mut decimated = ProxyMesh::default();
+    for face in &faces {
+        decimated.face_vertex_counts.push(face.len() as i32);
+        for &index in face {
+            let next = remap.len() as i32;
+            let new_index = *remap.entry(index).or_insert_with(|| {
+                let (sum, count) = sums[index];
+                decimated.points.push((sum / count).to_array());
+                next
+            });
+            decimated.face_vertex_indices.push(new_index);
+        }
+    }
+    decimated
+}
+
+/// One cluster's proxy and the meshes it stands in for
+#[derive(Debug, Clone, PartialEq)]
+pub struct USDHlodCluster {
+    /// Full-resolution meshes the proxy replaces
+    pub sources: Vec<String>,
+    pub mesh: ProxyMesh,
+    pub switch_distance: f64,
+}
+
+/// HLOD proxies to author in a scope under a root
+#[derive(Debug, Clone, PartialEq)]
+pub struct USDHlod {
+    pub root_path: String,
+    /// Name of the scope holding the proxies
+    pub name: String,
+    pub clusters: Vec<USDHlodCluster>,
+}
+
+impl USDHlod {
+    /// Build the proxies of meshes under a root
+    ///
+    /// Meshes are clustered in cells of `cluster_size`; each merged cluster
+    /// is decimated to about `resolution` cells across, and switches at
+    /// `distance_factor` times its radius.
+    pub fn build(root_path: &str, name: &str, meshes: &[SourceMesh], cluster_size: f64, resolution: f64, distance_factor: f64) -> Self {
+        let clusters = cluster_meshes(meshes, cluster_size).into_iter()
+            .map(|indices| {
+                let sources: Vec<&SourceMesh> = indices.iter().map(|&index| &meshes[index]).collect();
+                let merged = merge_meshes(&sources);
+                let radius = merged.radius();
+                USDHlodCluster {
+                    sources: sources.iter().map(|mesh| mesh.prim_path.clone()).collect(),
+                    mesh: decimate(&merged, 2.0 * radius / resolution.max(1.0)),
+                    switch_distance: radius * distance_factor,
+                }
+            })
+            .filter(|cluster| !cluster.mesh.face_vertex_counts.is_empty())
+            .collect();
+        Self {
+            root_path: root_path.trim_end_matches('/').to_string(),
+            name: name.to_string(),
+            clusters,
+        }
+    }
+    
+    /// Path of the scope holding the proxies
+    pub fn path(&self) -> String {
+        format!("{}/{}", self.root_path, self.name)
+    }
+    
+    /// Path of a cluster's proxy mesh
+    pub fn cluster_path(&self, index: usize) -> String {
+        format!("{}/cluster_{}", self.path(), index)
+    }
+    
+    /// The scope and a mesh per cluster with its switch distance
+    ///
+    /// Proxies are in world space, so the scope resets the transform stack.
+    /// The variant set is authored separately, see `variants`.
+    pub fn edits(&self) -> (Vec<USDPrimSpec>, Vec<USDAttributeEdit>) {
+        let scope = self.path();
+        let mut prims = vec![USDPrimSpec { path: scope.clone(), prim_type: "Xform".to_string() }];
+        let mut edits = vec![USDAttributeEdit {
+            prim_path: scope,
+            attr_name: "xformOpOrder".to_string(),
+            value: UsdValue::Array(vec![UsdValue::Token("!resetXformStack!".to_string())]),
+        }];
+        let ints = |values: &[i32]| UsdValue::Array(values.iter().map(|&value| UsdValue::Int(value.into())).collect());
+        for (index, cluster) in self.clusters.iter().enumerate() {
+            let path = self.cluster_path(index);
+            let edit = |attr_name: &str, value: UsdValue| USDAttributeEdit {
+                prim_path: path.clone(),
+                attr_name: attr_name.to_string(),
+                value,
+            };
+            edits.extend([
+                edit("points", UsdValue::Array(cluster.mesh.points.iter().copied().map(UsdValue::Vec3).collect())),
+                edit("faceVertexCounts", ints(&cluster.mesh.face_vertex_counts)),
+                edit("faceVertexIndices", ints(&cluster.mesh.face_vertex_indices)),
+                edit("subdivisionScheme", UsdValue::Token("none".to_string())),
+                edit(SWITCH_DISTANCE_ATTR, UsdValue::Double(cluster.switch_distance)),
+            ]);
+            prims.push(USDPrimSpec { path, prim_type: "Mesh".to_string() });
+        }
+        (prims, edits)
+    }
+    
+    /// Prims each variant hides: the proxies in the full variant, the meshes they replace in the hlod variant
+    pub fn variants(&self) -> Vec<(String, Vec<String>)> {
+        vec![
+            (FULL_VARIANT.to_string(), vec![self.path()]),
+            (HLOD_VARIANT.to_string(), self.clusters.iter().flat_map(|cluster| cluster.sources.iter().cloned()).collect()),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    /// A unit quad grid of `size` x `size` faces with its corner at `origin`
+    fn grid(prim_path: &str, origin: [f64; 3], size: usize) -> SourceMesh {
+        let step = 1.0 / size as f64;
+        let points = (0..=size).flat_map(|row| (0..=size).map(move |column| {
+            [origin[0] + column as f64 * step, origin[1], origin[2] + row as f64 * step]
+        }));
+        let indices = (0..size).flat_map(|row| (0..size).flat_map(move |column| {
+            let corner = (row * (size + 1) + column) as i32;
+            [corner, corner + 1, corner + size as i32 + 2, corner + size as i32 + 1]
+        }));
+        SourceMesh {
+            prim_path: prim_path.to_string(),
+            points: points.collect(),
+            face_vertex_counts: vec![4; size * size],
+            face_vertex_indices: indices.collect(),
+        }
+    }
+    
+    #[test]
+    fn clusters_merge_and_decimate() {
+        let meshes = [
+            grid("/World/A", [0.0, 0.0, 0.0], 8),
+            grid("/World/B", [1.5, 0.0, 0.0], 8),
+            grid("/World/C", [20.0, 0.0, 0.0], 8),
+        ];
+        assert_eq!(cluster_meshes(&meshes, 10.0), [vec![0, 1], vec![2]]);
+        
+        let merged = merge_meshes(&[&meshes[0], &meshes[1]]);
+        assert_eq!((merged.points.len(), merged.face_vertex_counts.len()), (162, 128));
+        assert_eq!(merged.face_vertex_indices[256], 81);
+        
+        // Welding at half the grid step keeps every face, at twice it quarters them
+        assert_eq!(decimate(&merged, 0.0625).face_vertex_counts.len(), 128);
+        let coarse = decimate(&merge_meshes(&[&meshes[0]]), 0.25);
+        assert!(coarse.face_vertex_counts.len() < 64 && !coarse.face_vertex_counts.is_empty());
+        assert!(coarse.face_vertex_indices.iter().all(|&index| (index as usize) < coarse.points.len()));
+        
+        let hlod = USDHlod::build("/World/", "HLOD", &meshes, 10.0, 4.0, 10.0);
+        assert_eq!(hlod.clusters.len(), 2);
+        assert_eq!(hlod.clusters[0].sources, ["/World/A", "/World/B"]);
+        assert_eq!(hlod.cluster_path(1), "/World/HLOD/cluster_1");
+        let (prims, edits) = hlod.edits();
+        assert_eq!(prims.len(), 3);
+        assert!(edits.iter().any(|edit| edit.attr_name == SWITCH_DISTANCE_ATTR && edit.prim_path == "/World/HLOD/cluster_1"));
+        assert_eq!(hlod.variants()[1].1, ["/World/A", "/World/B", "/World/C"]);
+    }
+}
@@ -0,0 +1,241 @@
+//! Light rig presets
+//!
+//! A rig is a set of UsdLux lights under one Xform. Each light is placed by
+//! azimuth and elevation around the origin and aimed at it, so rotating the
+//! rig Xform about Y swings the whole setup around the subject.
+
+use crate::core::usd_engine::{USDAttributeEdit, USDPrimSpec};
+use crate::core::usd_value::UsdValue;
+
+/// Rig layouts the Light Rig node offers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LightRigPreset {
+    /// Key, fill and rim area lights
+    ThreePoint,
+    /// Dome light with an optional HDR, plus a softbox key and a kicker
+    Studio,
+    /// Sun and sky
+    Outdoor,
+}
+
+impl LightRigPreset {
+    pub const ALL: [LightRigPreset; 3] = [LightRigPreset::ThreePoint, LightRigPreset::Studio, LightRigPreset::Outdoor];
+    
+    pub fn name(&self) -> &'static str {
+        match self {
+            LightRigPreset::ThreePoint => "Three Point",
+            LightRigPreset::Studio => "Studio",
+            LightRigPreset::Outdoor => "Outdoor",
+        }
+    }
+    
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|preset| preset.name() == name)
+    }
+    
+    /// Lights of the preset at unit rig intensity
+    pub fn lights(&self) -> Vec<RigLight> {
+        let rect = |name, intensity, color, azimuth, elevation, size| RigLight {
+            name,
+            light_type: "RectLight",
+            intensity,
+            color,
+            azimuth,
+            elevation,
+            distance: 6.0,
+            size,
+        };
+        let dome = |name, intensity, color| RigLight {
+            name,
+            light_type: "DomeLight",
+            intensity,
+            color,
+            azimuth: 0.0,
+            elevation: 0.0,
+            distance: 0.0,
+            size: [0.0; 2],
+        };
+        match self {
+            LightRigPreset::ThreePoint => vec![
+                rect("Key", 15.0, [1.0, 0.95, 0.88], 45.0, 30.0, [2.0, 2.0]),
+                rect("Fill", 5.0, [0.85, 0.9, 1.0], -60.0, 15.0, [3.0, 3.0]),
+                rect("Rim", 10.0, [1.0, 1.0, 1.0], 160.0, 35.0, [1.0, 1.5]),
+            ],
+            LightRigPreset::Studio => vec![
+                dome("Environment", 0.5, [1.0, 1.0, 1.0]),
+                rect("Softbox", 8.0, [1.0, 0.98, 0.95], 30.0, 40.0, [3.0, 3.0]),
+                rect("Kicker", 6.0, [1.0, 1.0, 1.0], -150.0, 20.0, [0.5, 2.0]),
+            ],
+            LightRigPreset::Outdoor => vec![
+                RigLight {
+                    name: "Sun",
+                    light_type: "DistantLight",
+                    intensity: 3.0,
+                    color: [1.0, 0.96, 0.9],
+                    azimuth: 120.0,
+                    elevation: 50.0,
+                    distance: 0.0,
+                    // Angular diameter of the sun in degrees
+                    size: [0.53, 0.0],
+                },
+                dome("Sky", 0.6, [0.6, 0.75, 1.0]),
+            ],
+        }
+    }
+}
+
+/// One light of a rig preset
+#[derive(Debug, Clone, PartialEq)]
+pub struct RigLight {
+    pub name: &'static str,
+    /// UsdLux prim type
+    pub light_type: &'static str,
+    pub intensity: f64,
+    pub color: [f64; 3],
+    /// Degrees about +Y, 0 looking down -Z from +Z
+    pub azimuth: f64,
+    /// Degrees above the horizon
+    pub elevation: f64,
+    /// Distance from the origin; distant and dome lights aren't translated
+    pub distance: f64,
+    /// Width and height of rect lights, or the angle of distant lights
+    pub size: [f64; 2],
+}
+
+impl RigLight {
+    /// Position on the sphere of radius `distance` around the origin
+    pub fn translate(&self) -> [f64; 3] {
+        let (azimuth, elevation) = (self.azimuth.to_radians(), self.elevation.to_radians());
+        [
+            self.distance * azimuth.sin() * elevation.cos(),
+            self.distance * elevation.sin(),
+            self.distance * azimuth.cos() * elevation.cos(),
+        ]
+    }
+    
+    /// rotateXYZ angles turning the light's -Z axis toward the origin
+    pub fn rotate(&self) -> [f64; 3] {
+        [-self.elevation, self.azimuth, 0.0]
+    }
+    
+    /// Shape inputs of the light's type
+    fn shape_inputs(&self) -> Vec<(&'static str, f64)> {
+        match self.light_type {
+            "RectLight" => vec![("inputs:width", self.size[0]), ("inputs:height", self.size[1])],
+            "DistantLight" => vec![("inputs:angle", self.size[0])],
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// A light rig to author under an Xform
+#[derive(Debug, Clone, PartialEq)]
+pub struct USDLightRig {
+    pub root_path: String,
+    pub preset: LightRigPreset,
+    /// Multiplier on every light's intensity
+    pub intensity: f64,
+    /// Rig rotation about Y in degrees
+    pub rotation: f64,
+    /// Latitude-longitude HDR for dome lights; empty leaves the dome a flat color
+    pub dome_texture: String,
+}
+
+impl USDLightRig {
+    /// Path of each light, in preset order
+    pub fn light_paths(&self) -> Vec<String> {
+        self.preset.lights().iter().map(|light| format!("{}/{}", self.root_path, light.name)).collect()
+    }
+    
+    /// Prims and attribute values that make up the rig
+    pub fn edits(&self) -> (Vec<USDPrimSpec>, Vec<USDAttributeEdit>) {
+        let lights: Vec<RigLight> = self.preset.lights().into_iter()
+            .map(|light| RigLight { intensity: light.intensity * self.intensity, ..light })
+            .collect();
+        light_group_edits(&self.root_path, self.rotation, &lights, &self.dome_texture)
+    }
+}
+
+/// Prims and attribute values for lights under one Xform rotated about Y
+///
+/// Dome lights get `dome_texture` as their latlong texture.
+pub fn light_group_edits(root_path: &str, rotation: f64, lights: &[RigLight], dome_texture: &str) -> (Vec<USDPrimSpec>, Vec<USDAttributeEdit>) {
+    let mut prims = vec![USDPrimSpec { path: root_path.to_string(), prim_type: "Xform".to_string() }];
+    let mut edits = Vec::new();
+    let mut edit = |prim_path: &str, attr_name: &str, value: UsdValue| edits.push(USDAttributeEdit {
+        prim_path: prim_path.to_string(),
+        attr_name: attr_name.to_string(),
+        value,
+    });
+    let op_order = |ops: &[&str]| UsdValue::Array(ops.iter().map(|op| UsdValue::Token(op.to_string())).collect());
+    
+    edit(root_path, "xformOp:rotateXYZ", UsdValue::Vec3([0.0, rotation, 0.0]));
+    edit(root_path, "xformOpOrder", op_order(&["xformOp:rotateXYZ"]));
+    
+    for light in lights {
+        let path = format!("{}/{}", root_path, light.name);
+        prims.push(USDPrimSpec { path: path.clone(), prim_type: light.light_type.to_string() });
+        edit(&path, "inputs:intensity", UsdValue::Float(light.intensity as f32));
+        edit(&path, "inputs:color", UsdValue::Color3(light.color));
+        for (name, value) in light.shape_inputs() {
+            edit(&path, name, UsdValue::Float(value as f32));
+        }
+        if light.light_type == "DomeLight" {
+            edit(&path, "inputs:texture:file", UsdValue::Asset(dome_texture.to_string()));
+            edit(&path, "inputs:texture:format", UsdValue::Token("latlong".to_string()));
+            continue;
+        }
+        let mut ops = Vec::new();
+        if light.distance > 0.0 {
+            edit(&path, "xformOp:translate", UsdValue::Vec3(light.translate()));
+            ops.push("xformOp:translate");
+        }
+        edit(&path, "xformOp:rotateXYZ", UsdValue::Vec3(light.rotate()));
+        ops.push("xformOp:rotateXYZ");
+        edit(&path, "xformOpOrder", op_order(&ops));
+    }
+    (prims, edits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::{DVec3, EulerRot, DQuat};
+    
+    #[test]
+    fn rig_lights_aim_at_the_origin() {
+        for preset in LightRigPreset::ALL {
+            assert_eq!(LightRigPreset::from_name(preset.name()), Some(preset));
+            for light in preset.lights().into_iter().filter(|light| light.distance > 0.0) {
+                let [x, y, z] = light.rotate().map(f64::to_radians);
+                // rotateXYZ applies X first, then Y, then Z
+                let aim = DQuat::from_euler(EulerRot::ZYX, z, y, x) * DVec3::NEG_Z;
+                let to_origin = -DVec3::from(light.translate()).normalize();
+                assert!(aim.distance(to_origin) < 1e-9, "{} {} aims at {:?}", preset.name(), light.name, aim);
+            }
+        }
+    }
+    
+    #[test]
+    fn rig_edits_scale_intensity_and_rotate_the_root() {
+        let rig = USDLightRig {
+            root_path: "/Lights/Rig".to_string(),
+            preset: LightRigPreset::Outdoor,
+            intensity: 2.0,
+            rotation: 90.0,
+            dome_texture: "sky.exr".to_string(),
+        };
+        let (prims, edits) = rig.edits();
+        let types: Vec<&str> = prims.iter().map(|prim| prim.prim_type.as_str()).collect();
+        assert_eq!(types, ["Xform", "DistantLight", "DomeLight"]);
+        assert_eq!(rig.light_paths(), ["/Lights/Rig/Sun", "/Lights/Rig/Sky"]);
+        
+        let value = |path: &str, name: &str| edits.iter()
+            .find(|edit| edit.prim_path == path && edit.attr_name == name)
+            .map(|edit| edit.value.clone());
+        assert_eq!(value("/Lights/Rig", "xformOp:rotateXYZ"), Some(UsdValue::Vec3([0.0, 90.0, 0.0])));
+        assert_eq!(value("/Lights/Rig/Sun", "inputs:intensity"), Some(UsdValue::Float(6.0)));
+        assert_eq!(value("/Lights/Rig/Sun", "xformOp:translate"), None);
+        assert_eq!(value("/Lights/Rig/Sky", "inputs:texture:file"), Some(UsdValue::Asset("sky.exr".to_string())));
+    }
+}
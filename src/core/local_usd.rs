@@ -7,6 +7,8 @@ use std::sync::Once;
 
 #[cfg(feature = "usd")]
 use pyo3::prelude::*;
+#[cfg(feature = "usd")]
+use super::profiling;
 
 static USD_INIT: Once = Once::new();
 
@@ -111,7 +113,7 @@ pub fn init_local_usd() {
         pyo3::prepare_freethreaded_python();
         
         // Verify USD can be imported
-        Python::with_gil(|py| {
+        profiling::with_gil("init_local_usd", |py| {
             match py.import("pxr.Usd") {
                 Ok(_) => println!("✓ Embedded USD initialized successfully"),
                 Err(e) => panic!("Failed to import USD from embedded Python: {}", e),
@@ -141,7 +143,7 @@ pub fn is_usd_installed() -> bool {
 pub fn get_usd_version() -> Result<String, String> {
     init_local_usd();
     
-    Python::with_gil(|py| {
+    profiling::with_gil("get_usd_version", |py| {
         let usd = py
             .import("pxr.Usd")
             .map_err(|e| format!("Failed to import USD: {}", e))?;
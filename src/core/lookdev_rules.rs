@@ -0,0 +1,185 @@
+//! Rule-based material assignment
+//!
+//! Rules are written one per line as `<match> -> <material path>`, where the
+//! match is `name:<glob>`, `attr:<attribute>=<value>` or `kind:<kind>`. Rules
+//! are evaluated in order and a later matching rule overrides an earlier one,
+//! so broad rules go first and specific ones after.
+
+/// What a rule selects prims by
+#[derive(Debug, Clone, PartialEq)]
+pub enum RuleMatch {
+    /// Glob against the prim name, or the full path when the pattern contains '/'
+    Name(String),
+    /// Attribute whose resolved value equals the text
+    Attribute { name: String, value: String },
+    /// Model kind, including kinds derived from it ("model" matches "component")
+    Kind(String),
+}
+
+/// A material assignment rule
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssignRule {
+    pub matcher: RuleMatch,
+    pub material: String,
+}
+
+/// A prim bound by the rules, with the index of the rule that won
+#[derive(Debug, Clone, PartialEq)]
+pub struct Assignment {
+    pub prim_path: String,
+    pub material: String,
+    pub rule: usize,
+}
+
+/// Prim types rules never bind, since they make up the materials themselves
+const SHADING_TYPES: [&str; 3] = ["Material", "Shader", "NodeGraph"];
+
+/// Parse rules, skipping blank lines and `#` comments
+pub fn parse_rules(text: &str) -> Result<Vec<AssignRule>, String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let (matcher, material) = line.rsplit_once("->")
+                .ok_or_else(|| format!("Rule '{}' has no '-> <material>'", line))?;
+            let material = material.trim();
+            if !material.starts_with('/') {
+                return Err(format!("Material '{}' is not an absolute prim path", material));
+            }
+            let (kind, pattern) = matcher.trim().split_once(':')
+                .ok_or_else(|| format!("Rule '{}' doesn't start with name:, attr: or kind:", line))?;
+            let pattern = pattern.trim().to_string();
+            let matcher = match kind.trim() {
+                "name" => RuleMatch::Name(pattern),
+                "kind" => RuleMatch::Kind(pattern),
+                "attr" => {
+                    let (name, value) = pattern.split_once('=')
+                        .ok_or_else(|| format!("Attribute rule '{}' has no '=<value>'", line))?;
+                    RuleMatch::Attribute { name: name.trim().to_string(), value: value.trim().to_string() }
+                }
+                other => return Err(format!("Unknown rule type '{}'", other)),
+            };
+            Ok(AssignRule { matcher, material: material.to_string() })
+        })
+        .collect()
+}
+
+/// Format rules back into their text form
+pub fn format_rules(rules: &[AssignRule]) -> String {
+    rules.iter()
+        .map(|rule| {
+            let matcher = match &rule.matcher {
+                RuleMatch::Name(pattern) => format!("name:{}", pattern),
+                RuleMatch::Attribute { name, value } => format!("attr:{}={}", name, value),
+                RuleMatch::Kind(kind) => format!("kind:{}", kind),
+            };
+            format!("{} -> {}", matcher, rule.material)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Glob match where `*` stays within a path component, `**` crosses components and `?` is one character
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let (pattern, text): (Vec<char>, Vec<char>) = (pattern.chars().collect(), text.chars().collect());
+    fn matches(pattern: &[char], text: &[char]) -> bool {
+        match pattern {
+            [] => text.is_empty(),
+            ['*', '*', rest @ ..] => (0..=text.len()).any(|skip| matches(rest, &text[skip..])),
+            ['*', rest @ ..] => (0..=text.len())
+                .take_while(|skip| *skip == 0 || text[skip - 1] != '/')
+                .any(|skip| matches(rest, &text[skip..])),
+            ['?', rest @ ..] => text.first().is_some_and(|c| *c != '/') && matches(rest, &text[1..]),
+            [c, rest @ ..] => text.first() == Some(c) && matches(rest, &text[1..]),
+        }
+    }
+    matches(&pattern, &text)
+}
+
+/// Whether a prim's kind is the rule's kind or derives from it
+fn kind_matches(rule_kind: &str, kind: &str) -> bool {
+    const MODEL_KINDS: [&str; 3] = ["component", "group", "assembly"];
+    match rule_kind {
+        "model" => kind == "model" || MODEL_KINDS.contains(&kind),
+        "group" => kind == "group" || kind == "assembly",
+        _ => kind == rule_kind,
+    }
+}
+
+impl RuleMatch {
+    /// Whether a prim matches, reading attributes through `attribute`
+    pub fn matches(&self, prim_path: &str, kind: &str, attribute: &mut impl FnMut(&str, &str) -> Option<String>) -> bool {
+        match self {
+            RuleMatch::Name(pattern) if pattern.contains('/') => glob_match(pattern, prim_path),
+            RuleMatch::Name(pattern) => glob_match(pattern, prim_path.rsplit('/').next().unwrap_or_default()),
+            RuleMatch::Kind(rule_kind) => kind_matches(rule_kind, kind),
+            RuleMatch::Attribute { name, value } => attribute(prim_path, name).is_some_and(|resolved| {
+                resolved.trim().trim_matches('"') == value.trim_matches('"')
+            }),
+        }
+    }
+}
+
+/// Evaluate rules in order over `(path, type, kind)` prims, the last matching rule winning, sorted by prim path
+pub fn resolve_assignments(
+    prims: &[(String, String, String)],
+    rules: &[AssignRule],
+    mut attribute: impl FnMut(&str, &str) -> Option<String>,
+) -> Vec<Assignment> {
+    let shading_roots: Vec<&str> = prims.iter()
+        .filter(|(_, prim_type, _)| prim_type == "Material")
+        .map(|(path, _, _)| path.as_str())
+        .collect();
+    let mut assignments: Vec<Assignment> = prims.iter()
+        .filter(|(path, prim_type, _)| !SHADING_TYPES.contains(&prim_type.as_str())
+            && !shading_roots.iter().any(|root| path.starts_with(root) && path[root.len()..].starts_with('/')))
+        .filter_map(|(path, _, kind)| {
+            let rule = rules.iter().rposition(|rule| rule.matcher.matches(path, kind, &mut attribute))?;
+            Some(Assignment { prim_path: path.clone(), material: rules[rule].material.clone(), rule })
+        })
+        .collect();
+    assignments.sort_by(|a, b| a.prim_path.cmp(&b.prim_path));
+    assignments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn globs_respect_path_components() {
+        assert!(glob_match("Chair*", "Chair_01"));
+        assert!(glob_match("/World/*/Leg?", "/World/Chair/Leg1"));
+        assert!(!glob_match("/World/*", "/World/Chair/Leg1"));
+        assert!(glob_match("/World/**/Leg*", "/World/Set/Chair/Leg1"));
+        assert!(!glob_match("Leg?", "Leg12"));
+    }
+    
+    #[test]
+    fn later_rules_override_earlier_ones() {
+        let rules = parse_rules("# base\nkind:model -> /Looks/Default\nname:Chair* -> /Looks/Wood\nattr:userProperties:finish=metal -> /Looks/Metal\n").unwrap();
+        assert_eq!(rules.len(), 3);
+        assert_eq!(parse_rules(&format_rules(&rules)).unwrap(), rules);
+        
+        let prim = |path: &str, prim_type: &str, kind: &str| (path.to_string(), prim_type.to_string(), kind.to_string());
+        let prims = [
+            prim("/World/Chair_01", "Xform", "component"),
+            prim("/World/Chair_02", "Xform", "component"),
+            prim("/World/Lamp", "Xform", "component"),
+            prim("/World/Floor", "Mesh", ""),
+            prim("/Looks/Wood", "Material", ""),
+            prim("/Looks/Wood/ChairShader", "Shader", ""),
+        ];
+        let assignments = resolve_assignments(&prims, &rules, |path, name| {
+            (path == "/World/Chair_02" && name == "userProperties:finish").then(|| "\"metal\"".to_string())
+        });
+        let bound: Vec<(&str, &str)> = assignments.iter().map(|a| (a.prim_path.as_str(), a.material.as_str())).collect();
+        assert_eq!(bound, [
+            ("/World/Chair_01", "/Looks/Wood"),
+            ("/World/Chair_02", "/Looks/Metal"),
+            ("/World/Lamp", "/Looks/Default"),
+        ]);
+        assert!(parse_rules("kind:model").is_err());
+        assert!(parse_rules("color:red -> /Looks/Red").is_err());
+    }
+}
@@ -0,0 +1,304 @@
+//! MaterialX documents
+//!
+//! OpenUSD's UsdMtlx file format plugin reads a .mtlx document as a layer
+//! whose default prim holds the document's materials under `Materials`, so a
+//! document is brought into a stage by referencing it. The viewport has no
+//! MaterialX shader generation; surface shaders are translated best-effort
+//! to the UsdPreviewSurface values it shades with.
+
+use glam::{Vec3, Vec4};
+
+/// Input of a document element, with either a value or a connected node
+#[derive(Debug, Clone, PartialEq)]
+pub struct MtlxInput {
+    pub name: String,
+    pub value: Option<String>,
+    /// Upstream node the input is connected to
+    pub node_name: Option<String>,
+}
+
+/// An element of a document ("surfacematerial", "standard_surface", ...) with its inputs
+#[derive(Debug, Clone, PartialEq)]
+pub struct MtlxElement {
+    pub category: String,
+    pub name: String,
+    pub inputs: Vec<MtlxInput>,
+}
+
+impl MtlxElement {
+    pub fn input(&self, name: &str) -> Option<&MtlxInput> {
+        self.inputs.iter().find(|input| input.name == name)
+    }
+}
+
+/// Named elements of a MaterialX document, in document order
+///
+/// Only what material listing and surface translation need is kept; nested
+/// node graphs are flattened into the element list.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MtlxDocument {
+    pub elements: Vec<MtlxElement>,
+}
+
+impl MtlxDocument {
+    /// The document's materials
+    pub fn materials(&self) -> impl Iterator<Item = &MtlxElement> {
+        self.elements.iter().filter(|element| element.category == "surfacematerial")
+    }
+    
+    /// Surface shader node connected to a material
+    pub fn surface_shader(&self, material: &MtlxElement) -> Option<&MtlxElement> {
+        let node_name = material.input("surfaceshader")?.node_name.as_deref()?;
+        self.elements.iter().find(|element| element.name == node_name)
+    }
+}
+
+/// Parse the elements of a MaterialX document
+///
+/// A minimal XML reader: comments, processing instructions and text are
+/// skipped, and entities other than the five predefined ones are left as is.
+pub fn parse_document(text: &str) -> Result<MtlxDocument, String> {
+    let mut document = MtlxDocument::default();
+    // Index in `elements` of each open element, None for unnamed ones
+    let mut open: Vec<Option<usize>> = Vec::new();
+    let mut rest = text;
+    let mut saw_root = false;
+    
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start..];
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            let end = comment.find("-->").ok_or("Unterminated comment")?;
+            rest = &comment[end + 3..];
+            continue;
+        }
+        let end = rest.find('>').ok_or("Unterminated tag")?;
+        let tag = &rest[1..end];
+        rest = &rest[end + 1..];
+        if tag.starts_with('?') || tag.starts_with('!') {
+            continue;
+        }
+        if let Some(closing) = tag.strip_prefix('/') {
+            if open.pop().is_none() {
+                return Err(format!("Unexpected closing tag '{}'", closing.trim()));
+            }
+            continue;
+        }
+        
+        let self_closing = tag.ends_with('/');
+        let tag = tag.trim_end_matches('/');
+        let category = tag.split_whitespace().next().ok_or("Empty tag")?;
+        let attributes = parse_attributes(&tag[category.len()..])?;
+        let attribute = |name: &str| attributes.iter().find(|(key, _)| key == name).map(|(_, value)| value.clone());
+        
+        let index = if !saw_root {
+            if category != "materialx" {
+                return Err(format!("Expected a <materialx> document, found <{}>", category));
+            }
+            saw_root = true;
+            None
+        } else if category == "input" {
+            let parent = open.last().copied().flatten();
+            if let Some(parent) = parent {
+                document.elements[parent].inputs.push(MtlxInput {
+                    name: attribute("name").unwrap_or_default(),
+                    value: attribute("value"),
+                    node_name: attribute("nodename"),
+                });
+            }
+            None
+        } else {
+            attribute("name").map(|name| {
+                document.elements.push(MtlxElement {
+                    category: category.to_string(),
+                    name,
+                    inputs: Vec::new(),
+                });
+                document.elements.len() - 1
+            })
+        };
+        if !self_closing {
+            open.push(index);
+        }
+    }
+    
+    if !saw_root {
+        return Err("Not a MaterialX document".to_string());
+    }
+    if !open.is_empty() {
+        return Err("Document ends inside an element".to_string());
+    }
+    Ok(document)
+}
+
+/// `key="value"` pairs of a tag, unescaping the predefined entities
+fn parse_attributes(mut text: &str) -> Result<Vec<(String, String)>, String> {
+    let mut attributes = Vec::new();
+    loop {
+        text = text.trim_start();
+        if text.is_empty() {
+            return Ok(attributes);
+        }
+        let (key, value) = text.split_once('=').ok_or_else(|| format!("Malformed attribute '{}'", text))?;
+        let value = value.trim_start();
+        let quote = value.chars().next().filter(|quote| *quote == '"' || *quote == '\'')
+            .ok_or_else(|| format!("Unquoted value of attribute '{}'", key.trim()))?;
+        let end = value[1..].find(quote).ok_or_else(|| format!("Unterminated value of attribute '{}'", key.trim()))?;
+        let unescaped = value[1..end + 1]
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&apos;", "'")
+            .replace("&amp;", "&");
+        attributes.push((key.trim().to_string(), unescaped));
+        text = &value[end + 2..];
+    }
+}
+
+/// Parse a MaterialX value ("0.5", "0.8, 0.2, 0.1") into a vector, splatting scalars
+pub fn parse_value(text: &str) -> Option<Vec4> {
+    let components: Vec<f32> = text.split(',')
+        .map(|component| component.trim().parse().ok())
+        .collect::<Option<_>>()?;
+    Some(match components.as_slice() {
+        [x] => Vec4::splat(*x),
+        [x, y] => Vec4::new(*x, *y, 0.0, 1.0),
+        [x, y, z] => Vec4::new(*x, *y, *z, 1.0),
+        [x, y, z, w] => Vec4::new(*x, *y, *z, *w),
+        _ => return None,
+    })
+}
+
+/// Category of a surface shader from its node definition id, e.g. "ND_standard_surface_surfaceshader"
+fn surface_category(shader_id: &str) -> &str {
+    shader_id.strip_prefix("ND_")
+        .and_then(|id| id.strip_suffix("_surfaceshader"))
+        .unwrap_or(shader_id)
+}
+
+/// UsdPreviewSurface inputs approximating a surface shader
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PreviewValues {
+    pub diffuse_color: Vec3,
+    pub emissive_color: Vec3,
+    pub metallic: f32,
+    pub roughness: f32,
+    pub opacity: f32,
+    pub ior: f32,
+}
+
+/// Translate a standard_surface, gltf_pbr or UsdPreviewSurface shader, or None for other shaders
+///
+/// `shader` is the node's category or node definition id. `input` gives an
+/// input's authored value; unauthored inputs take the node definition's
+/// default. Connected inputs are expected to report no value, so textured
+/// inputs fall back to those defaults as well.
+pub fn preview_values(shader: &str, input: impl Fn(&str) -> Option<Vec4>) -> Option<PreviewValues> {
+    let value = |name: &str, default: Vec4| input(name).unwrap_or(default);
+    let scalar = |name: &str, default: f32| value(name, Vec4::splat(default)).x;
+    let color = |name: &str, default: f32| value(name, Vec4::splat(default)).truncate();
+    
+    match surface_category(shader) {
+        "standard_surface" => {
+            let opacity = color("opacity", 1.0);
+            Some(PreviewValues {
+                diffuse_color: color("base_color", 0.8) * scalar("base", 1.0),
+                emissive_color: color("emission_color", 1.0) * scalar("emission", 0.0),
+                metallic: scalar("metalness", 0.0),
+                roughness: scalar("specular_roughness", 0.2),
+                opacity: (opacity.x + opacity.y + opacity.z) / 3.0 * (1.0 - scalar("transmission", 0.0)),
+                ior: scalar("specular_IOR", 1.5),
+            })
+        }
+        "gltf_pbr" => Some(PreviewValues {
+            diffuse_color: color("base_color", 1.0),
+            emissive_color: color("emissive", 0.0) * scalar("emissive_strength", 1.0),
+            metallic: scalar("metallic", 1.0),
+            roughness: scalar("roughness", 1.0),
+            opacity: scalar("alpha", 1.0) * (1.0 - scalar("transmission", 0.0)),
+            ior: scalar("ior", 1.5),
+        }),
+        "UsdPreviewSurface" => Some(PreviewValues {
+            diffuse_color: color("diffuseColor", 0.18),
+            emissive_color: color("emissiveColor", 0.0),
+            metallic: scalar("metallic", 0.0),
+            roughness: scalar("roughness", 0.5),
+            opacity: scalar("opacity", 1.0),
+            ior: scalar("ior", 1.5),
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    const DOCUMENT: &str = r#"<?xml version="1.0"?>
+<materialx version="1.38" colorspace="lin_rec709">
+  <!-- A red plastic and a textured metal -->
+  <standard_surface name="SR_red" type="surfaceshader">
+    <input name="base_color" type="color3" value="0.8, 0.1, 0.1" />
+    <input name="specular_roughness" type="float" value="0.35" />
+  </standard_surface>
+  <surfacematerial name="Red" type="material">
+    <input name="surfaceshader" type="surfaceshader" nodename="SR_red" />
+  </surfacematerial>
+  <nodegraph name="NG_metal">
+    <image name="albedo" type="color3">
+      <input name="file" type="filename" value="metal &amp; rust.png" />
+    </image>
+    <output name="out" type="color3" nodename="albedo" />
+  </nodegraph>
+  <standard_surface name="SR_metal" type="surfaceshader">
+    <input name="base_color" type="color3" nodegraph="NG_metal" output="out" />
+    <input name="metalness" type="float" value="1" />
+  </standard_surface>
+  <surfacematerial name="Metal" type="material">
+    <input name="surfaceshader" type="surfaceshader" nodename="SR_metal" />
+  </surfacematerial>
+</materialx>
+"#;
+
+    #[test]
+    fn documents_list_materials_and_their_surfaces() {
+        let document = parse_document(DOCUMENT).unwrap();
+        let materials: Vec<&str> = document.materials().map(|material| material.name.as_str()).collect();
+        assert_eq!(materials, ["Red", "Metal"]);
+        
+        let red = document.materials().next().unwrap();
+        let surface = document.surface_shader(red).unwrap();
+        assert_eq!((surface.category.as_str(), surface.name.as_str()), ("standard_surface", "SR_red"));
+        assert_eq!(surface.input("specular_roughness").and_then(|input| input.value.as_deref()), Some("0.35"));
+        
+        let image = document.elements.iter().find(|element| element.name == "albedo").unwrap();
+        assert_eq!(image.input("file").unwrap().value.as_deref(), Some("metal & rust.png"));
+        
+        assert!(parse_document("<mtlx/>").is_err());
+        assert!(parse_document("<materialx><standard_surface name=\"a\">").is_err());
+    }
+    
+    #[test]
+    fn standard_surfaces_translate_to_preview_values() {
+        let document = parse_document(DOCUMENT).unwrap();
+        let values = |material: &str| {
+            let material = document.materials().find(|element| element.name == material).unwrap();
+            let surface = document.surface_shader(material).unwrap();
+            preview_values(&surface.category, |name| surface.input(name)?.value.as_deref().and_then(parse_value)).unwrap()
+        };
+        
+        let red = values("Red");
+        assert_eq!(red.diffuse_color, Vec3::new(0.8, 0.1, 0.1));
+        assert_eq!(red.roughness, 0.35);
+        assert_eq!((red.metallic, red.opacity, red.ior), (0.0, 1.0, 1.5));
+        assert_eq!(red.emissive_color, Vec3::ZERO);
+        
+        // The connected base color falls back to the node definition's default
+        let metal = values("Metal");
+        assert_eq!(metal.diffuse_color, Vec3::splat(0.8));
+        assert_eq!(metal.metallic, 1.0);
+        
+        let gltf = preview_values("ND_gltf_pbr_surfaceshader", |_| None).unwrap();
+        assert_eq!((gltf.metallic, gltf.roughness), (1.0, 1.0));
+        assert!(preview_values("disney_principled", |_| None).is_none());
+    }
+}
@@ -4,4 +4,65 @@
 pub mod local_usd;
 
 // USD engine for Python API integration - minimal for viewport plugin  
-pub mod usd_engine;
\ No newline at end of file
+pub mod usd_engine;
+
+// Typed attribute values for the Python bridge
+pub mod usd_value;
+
+// Timing of Python bridge calls
+pub mod profiling;
+
+// USDZ package reading and embedded assets
+pub mod usdz;
+
+// Rule-based material assignment
+pub mod lookdev_rules;
+
+// Light rig presets
+pub mod light_rig;
+
+// Solar position and procedural sky
+pub mod sun_sky;
+
+// Face subsets of meshes
+pub mod geom_subset;
+
+// MaterialX documents and their UsdPreviewSurface translation
+pub mod materialx;
+
+// Bounding boxes for framing, culling and bounds display
+pub mod bounds;
+
+// Box, convex hull and k-DOP proxies for collision and occlusion
+pub mod bounding_volume;
+
+// Clustered, decimated HLOD proxies switched by a variant set
+pub mod hlod;
+
+// Triangle BVH for ray casts
+pub mod bvh;
+
+// Camera visibility bakes for occlusion culling
+pub mod occlusion;
+
+// Graphviz and Mermaid descriptions of the scene graph
+pub mod scene_graph;
+
+// Engine state kept across plugin reloads
+pub mod session;
+
+// Background stage opens for Load Stage nodes
+pub mod stage_loader;
+
+// Sample stage and steps of the guided tutorial
+pub mod tutorial;
+
+// Composed scene snapshots other plugins query
+pub mod scene_query;
+
+// Graph-wide random seed of procedural nodes
+pub mod seed;
+
+// Native OpenUSD backend for core stage operations
+#[cfg(feature = "usd-native")]
+pub mod usd_native;
@@ -0,0 +1,163 @@
+//! Camera visibility bakes for occlusion culling
+//!
+//! Rays are cast from each camera of a set through a grid across its
+//! aperture into a BVH of the meshes' triangles. A mesh that no ray hits
+//! first is never visible from those cameras, so an engine restricted to
+//! them can skip it. The result is authored as a custom attribute for
+//! engine-side culling, or as the meshes' visibility.
+
+use std::collections::BTreeSet;
+use glam::Vec3;
+use crate::core::bvh::{Bvh, Triangle};
+use crate::core::hlod::SourceMesh;
+use crate::core::usd_engine::USDAttributeEdit;
+use crate::core::usd_value::UsdValue;
+use crate::viewport::projection::ProjectionCamera;
+use crate::viewport::triangulation::triangulate;
+
+/// Custom attribute marking meshes never visible from the baked cameras
+pub const NEVER_VISIBLE_ATTR: &str = "culling:neverVisible";
+
+/// How a bake is authored on the meshes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CullOutput {
+    /// `culling:neverVisible` on every mesh
+    Attribute,
+    /// Hidden meshes made invisible, the others inherited
+    Visibility,
+}
+
+impl CullOutput {
+    pub const ALL: [CullOutput; 2] = [CullOutput::Attribute, CullOutput::Visibility];
+    
+    pub fn label(&self) -> &'static str {
+        match self {
+            CullOutput::Attribute => "Attribute",
+            CullOutput::Visibility => "Visibility",
+        }
+    }
+    
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|output| output.label() == label)
+    }
+}
+
+/// BVH over the triangles of world-space meshes, each owned by its mesh index
+///
+/// Meshes with broken topology are left out and so never occlude.
+pub fn mesh_bvh(meshes: &[SourceMesh]) -> Bvh {
+    let mut triangles = Vec::new();
+    for (owner, mesh) in meshes.iter().enumerate() {
+        let points: Vec<Vec3> = mesh.points.iter().map(|&point| Vec3::from_array(point.map(|value| value as f32))).collect();
+        let Ok(triangulated) = triangulate(&points, &mesh.face_vertex_counts, &mesh.face_vertex_indices, &[]) else {
+            continue;
+        };
+        triangles.extend(triangulated.indices.chunks_exact(3).map(|corners| Triangle {
+            corners: [points[corners[0] as usize], points[corners[1] as usize], points[corners[2] as usize]],
+            owner,
+        }));
+    }
+    Bvh::new(triangles)
+}
+
+/// World-space rays through the centers of a grid `samples` wide across a camera's aperture
+///
+/// Rays start on the near clipping plane; rows follow the aperture's aspect.
+pub fn camera_rays(camera: &ProjectionCamera, samples: usize) -> Vec<(Vec3, Vec3)> {
+    let columns = samples.max(1);
+    let rows = ((columns as f32 * camera.vertical_aperture / camera.horizontal_aperture).round() as usize).max(1);
+    let half_width = camera.horizontal_aperture / (2.0 * camera.focal_length);
+    let half_height = camera.vertical_aperture / (2.0 * camera.focal_length);
+    let eye = camera.world.transform_point3(Vec3::ZERO);
+    (0..rows)
+        .flat_map(|row| (0..columns).map(move |column| (row, column)))
+        .map(|(row, column)| {
+            let x = (column as f32 + 0.5) / columns as f32 * 2.0 - 1.0;
+            let y = 1.0 - (row as f32 + 0.5) / rows as f32 * 2.0;
+            let direction = camera.world.transform_vector3(Vec3::new(x * half_width, y * half_height, -1.0)).normalize();
+            (eye + direction * camera.near, direction)
+        })
+        .collect()
+}
+
+/// Indices of the meshes some camera ray hits first
+pub fn visible_meshes(bvh: &Bvh, cameras: &[ProjectionCamera], samples: usize) -> BTreeSet<usize> {
+    cameras.iter()
+        .flat_map(|camera| {
+            let range = camera.far - camera.near;
+            camera_rays(camera, samples).into_iter()
+                .filter_map(move |(origin, direction)| bvh.intersect(origin, direction, range))
+        })
+        .map(|hit| hit.owner)
+        .collect()
+}
+
+/// Baked visibility of meshes to author
+#[derive(Debug, Clone, PartialEq)]
+pub struct USDVisibilityBake {
+    /// Mesh paths with whether any camera sees them
+    pub meshes: Vec<(String, bool)>,
+    pub output: CullOutput,
+}
+
+impl USDVisibilityBake {
+    /// Meshes no camera sees
+    pub fn hidden(&self) -> Vec<&str> {
+        self.meshes.iter().filter(|(_, visible)| !visible).map(|(path, _)| path.as_str()).collect()
+    }
+    
+    /// Edits to every mesh, so re-baking clears earlier results
+    pub fn edits(&self) -> Vec<USDAttributeEdit> {
+        self.meshes.iter()
+            .map(|(path, visible)| {
+                let (attr_name, value) = match self.output {
+                    CullOutput::Attribute => (NEVER_VISIBLE_ATTR, UsdValue::Bool(!visible)),
+                    CullOutput::Visibility => ("visibility", UsdValue::Token(if *visible { "inherited" } else { "invisible" }.to_string())),
+                };
+                USDAttributeEdit {
+                    prim_path: path.clone(),
+                    attr_name: attr_name.to_string(),
+                    value,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::Mat4;
+    
+    /// A square of `size` facing +z at depth `z`
+    fn wall(prim_path: &str, size: f64, z: f64) -> SourceMesh {
+        SourceMesh {
+            prim_path: prim_path.to_string(),
+            points: vec![[-size, -size, z], [size, -size, z], [size, size, z], [-size, size, z]],
+            face_vertex_counts: vec![4],
+            face_vertex_indices: vec![0, 1, 2, 3],
+        }
+    }
+    
+    #[test]
+    fn walls_hide_what_is_behind_them() {
+        // A large wall in front of a small one, seen down -z from the origin
+        let meshes = [wall("/World/Front", 100.0, -10.0), wall("/World/Behind", 1.0, -20.0), wall("/World/Side", 1.0, 50.0)];
+        let bvh = mesh_bvh(&meshes);
+        let camera = ProjectionCamera::default();
+        assert_eq!(camera_rays(&camera, 8).len(), 8 * 6);
+        assert_eq!(visible_meshes(&bvh, &[camera], 8), BTreeSet::from([0]));
+        
+        // Turned around, the camera sees the side wall
+        let turned = ProjectionCamera { world: Mat4::from_rotation_y(std::f32::consts::PI), ..camera };
+        let visible = visible_meshes(&bvh, &[camera, turned], 64);
+        assert_eq!(visible, BTreeSet::from([0, 2]));
+        
+        let bake = USDVisibilityBake {
+            meshes: meshes.iter().enumerate().map(|(index, mesh)| (mesh.prim_path.clone(), visible.contains(&index))).collect(),
+            output: CullOutput::Visibility,
+        };
+        assert_eq!(bake.hidden(), ["/World/Behind"]);
+        assert_eq!(bake.edits()[1].value, UsdValue::Token("invisible".to_string()));
+    }
+}
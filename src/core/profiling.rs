@@ -0,0 +1,222 @@
+//! Hierarchical timing of Python bridge calls
+//!
+//! Every `Python::with_gil` block in the plugin goes through `with_gil`, which
+//! records per-operation call counts, cumulative time and the time spent
+//! waiting for the GIL. Scopes nest per thread, so an operation called from
+//! inside another is reported under its parent path ("load_stage/evaluate_at_time")
+//! and its time is excluded from the parent's self time.
+
+use once_cell::sync::Lazy;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "usd")]
+use pyo3::prelude::*;
+
+/// Accumulated timings for one operation path
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OperationStats {
+    /// Operation path, parent scopes joined with '/'
+    pub path: String,
+    pub calls: u64,
+    /// Wall time including nested operations
+    pub total: Duration,
+    /// Wall time excluding nested operations
+    pub self_time: Duration,
+    pub max: Duration,
+    /// Time spent waiting to acquire the GIL
+    pub gil_wait: Duration,
+}
+
+impl OperationStats {
+    /// Nesting depth, 0 for top-level operations
+    pub fn depth(&self) -> usize {
+        self.path.matches('/').count()
+    }
+    
+    pub fn average(&self) -> Duration {
+        if self.calls == 0 {
+            Duration::ZERO
+        } else {
+            self.total / self.calls as u32
+        }
+    }
+}
+
+static PROFILE: Lazy<Mutex<HashMap<String, OperationStats>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// An open scope on the current thread
+struct Frame {
+    path: String,
+    children: Duration,
+}
+
+thread_local! {
+    static SCOPES: RefCell<Vec<Frame>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Timing scope that records its operation when dropped
+pub struct ProfileScope {
+    start: Instant,
+    gil_wait: Duration,
+}
+
+/// Open a timing scope nested under the current thread's open scopes
+pub fn profile_scope(operation: &str) -> ProfileScope {
+    SCOPES.with(|scopes| {
+        let mut scopes = scopes.borrow_mut();
+        let path = match scopes.last() {
+            Some(parent) => format!("{}/{}", parent.path, operation),
+            None => operation.to_string(),
+        };
+        scopes.push(Frame { path, children: Duration::ZERO });
+    });
+    ProfileScope {
+        start: Instant::now(),
+        gil_wait: Duration::ZERO,
+    }
+}
+
+impl Drop for ProfileScope {
+    fn drop(&mut self) {
+        let elapsed = self.start.elapsed();
+        let Some(frame) = SCOPES.with(|scopes| {
+            let mut scopes = scopes.borrow_mut();
+            let frame = scopes.pop();
+            if let Some(parent) = scopes.last_mut() {
+                parent.children += elapsed;
+            }
+            frame
+        }) else {
+            return;
+        };
+        
+        let mut profile = PROFILE.lock().unwrap();
+        let stats = profile.entry(frame.path.clone()).or_insert_with(|| OperationStats {
+            path: frame.path,
+            ..Default::default()
+        });
+        stats.calls += 1;
+        stats.total += elapsed;
+        stats.self_time += elapsed.saturating_sub(frame.children);
+        stats.max = stats.max.max(elapsed);
+        stats.gil_wait += self.gil_wait;
+    }
+}
+
+/// `Python::with_gil`, timed under an operation name
+#[cfg(feature = "usd")]
+pub fn with_gil<F, R>(operation: &str, f: F) -> R
+where
+    F: for<'py> FnOnce(Python<'py>) -> R,
+{
+    let mut scope = profile_scope(operation);
+    Python::with_gil(|py| {
+        scope.gil_wait = scope.start.elapsed();
+        f(py)
+    })
+}
+
+/// Recorded operations in depth-first order, children sorted by total time
+pub fn profile_report() -> Vec<OperationStats> {
+    let profile = PROFILE.lock().unwrap();
+    let mut operations: Vec<OperationStats> = profile.values().cloned().collect();
+    drop(profile);
+    
+    // Sort each level by total time, keeping children right after their parent
+    let totals: HashMap<String, Duration> = operations.iter()
+        .map(|stats| (stats.path.clone(), stats.total))
+        .collect();
+    let sort_key = |stats: &OperationStats| -> Vec<(std::cmp::Reverse<Duration>, String)> {
+        let mut key = Vec::new();
+        let mut prefix = String::new();
+        for part in stats.path.split('/') {
+            if !prefix.is_empty() {
+                prefix.push('/');
+            }
+            prefix.push_str(part);
+            let total = totals.get(&prefix).copied().unwrap_or_default();
+            key.push((std::cmp::Reverse(total), part.to_string()));
+        }
+        key
+    };
+    operations.sort_by_cached_key(sort_key);
+    operations
+}
+
+/// Top-level operations by total time, for compact displays
+pub fn top_operations(count: usize) -> Vec<OperationStats> {
+    let mut operations: Vec<OperationStats> = profile_report().into_iter()
+        .filter(|stats| stats.depth() == 0)
+        .collect();
+    operations.sort_by_key(|stats| std::cmp::Reverse(stats.total));
+    operations.truncate(count);
+    operations
+}
+
+/// Total time spent in top-level bridge operations
+pub fn total_bridge_time() -> Duration {
+    PROFILE.lock().unwrap()
+        .values()
+        .filter(|stats| stats.depth() == 0)
+        .map(|stats| stats.total)
+        .sum()
+}
+
+/// Clear all recorded timings
+pub fn reset_profile() {
+    PROFILE.lock().unwrap().clear();
+}
+
+/// Format the report as an indented text table
+pub fn format_report(operations: &[OperationStats]) -> String {
+    let mut lines = vec![format!("{:<48} {:>7} {:>10} {:>10} {:>10} {:>10}",
+                                "operation", "calls", "total ms", "self ms", "max ms", "gil ms")];
+    for stats in operations {
+        let name = stats.path.rsplit('/').next().unwrap_or_default();
+        let label = format!("{}{}", "  ".repeat(stats.depth()), name);
+        lines.push(format!("{:<48} {:>7} {:>10.2} {:>10.2} {:>10.2} {:>10.2}",
+                           label,
+                           stats.calls,
+                           stats.total.as_secs_f64() * 1000.0,
+                           stats.self_time.as_secs_f64() * 1000.0,
+                           stats.max.as_secs_f64() * 1000.0,
+                           stats.gil_wait.as_secs_f64() * 1000.0));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn stats(path: &str) -> OperationStats {
+        profile_report().into_iter().find(|stats| stats.path == path).expect(path)
+    }
+    
+    #[test]
+    fn nested_scopes_report_under_their_parent() {
+        {
+            let _outer = profile_scope("test_outer");
+            for _ in 0..3 {
+                let _inner = profile_scope("test_inner");
+                std::thread::sleep(Duration::from_millis(2));
+            }
+        }
+        
+        let outer = stats("test_outer");
+        let inner = stats("test_outer/test_inner");
+        assert_eq!(outer.calls, 1);
+        assert_eq!(inner.calls, 3);
+        assert_eq!(inner.depth(), 1);
+        assert!(inner.total >= Duration::from_millis(6));
+        assert!(outer.total >= inner.total);
+        assert!(outer.self_time <= outer.total - inner.total + Duration::from_micros(1));
+        
+        let report = profile_report();
+        let outer_index = report.iter().position(|s| s.path == "test_outer").unwrap();
+        assert_eq!(report[outer_index + 1].path, "test_outer/test_inner");
+    }
+}
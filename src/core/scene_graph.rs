@@ -0,0 +1,208 @@
+//! Scene graph descriptions for documentation
+//!
+//! Writes the prim hierarchy of a stage with its composition arcs as a
+//! Graphviz or Mermaid graph. Prims become boxes linked parent to child;
+//! references and payloads point at their asset, inherits and specializes
+//! at their class prim, and variant selections are listed on their prim.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use crate::core::usd_engine::{USDCompositionArc, USDPrimStatus};
+
+/// Graph description language to write
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    Graphviz,
+    Mermaid,
+}
+
+impl GraphFormat {
+    pub const ALL: [GraphFormat; 2] = [GraphFormat::Graphviz, GraphFormat::Mermaid];
+    
+    pub fn label(&self) -> &'static str {
+        match self {
+            GraphFormat::Graphviz => "Graphviz",
+            GraphFormat::Mermaid => "Mermaid",
+        }
+    }
+    
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|format| format.label() == label)
+    }
+}
+
+/// An edge drawn from a prim
+enum Edge {
+    Child,
+    Arc(String),
+}
+
+/// Node labels and edges of the graph, nodes keyed by id
+struct Graph {
+    /// Prim nodes as (id, name and type lines)
+    prims: Vec<(String, Vec<String>)>,
+    /// Asset nodes as (id, asset path)
+    assets: Vec<(String, String)>,
+    edges: Vec<(String, String, Edge)>,
+}
+
+/// Parent path of a prim path, None for root prims
+fn parent_path(path: &str) -> Option<&str> {
+    path.rfind('/').filter(|&index| index > 0).map(|index| &path[..index])
+}
+
+fn build_graph(prims: &[USDPrimStatus], arcs: &[USDCompositionArc]) -> Graph {
+    let ids: BTreeMap<&str, String> = prims.iter().enumerate()
+        .map(|(index, prim)| (prim.path.as_str(), format!("p{}", index)))
+        .collect();
+    let mut graph = Graph { prims: Vec::new(), assets: Vec::new(), edges: Vec::new() };
+    
+    for prim in prims {
+        let name = prim.path.rsplit('/').next().unwrap_or_default();
+        let mut lines = vec![match prim.prim_type.as_str() {
+            "" => name.to_string(),
+            prim_type => format!("{} ({})", name, prim_type),
+        }];
+        lines.extend(arcs.iter()
+            .filter(|arc| arc.prim_path == prim.path && arc.kind == "variant")
+            .map(|arc| format!("variant {}", arc.target)));
+        graph.prims.push((ids[prim.path.as_str()].clone(), lines));
+        // Prims whose parent is left out hang from the nearest ancestor kept
+        let mut parent = parent_path(&prim.path);
+        while let Some(path) = parent.filter(|path| !ids.contains_key(path)) {
+            parent = parent_path(path);
+        }
+        if let Some(parent) = parent {
+            graph.edges.push((ids[parent].clone(), ids[prim.path.as_str()].clone(), Edge::Child));
+        }
+    }
+    
+    for arc in arcs.iter().filter(|arc| arc.kind != "variant") {
+        let Some(from) = ids.get(arc.prim_path.as_str()) else {
+            continue;
+        };
+        let to = match ids.get(arc.target.as_str()).filter(|_| arc.asset.is_empty()) {
+            Some(id) => id.clone(),
+            None => {
+                let asset = match (arc.asset.as_str(), arc.target.as_str()) {
+                    ("", target) => target.to_string(),
+                    (asset, "") => format!("@{}@", asset),
+                    (asset, target) => format!("@{}@<{}>", asset, target),
+                };
+                match graph.assets.iter().find(|(_, existing)| *existing == asset) {
+                    Some((id, _)) => id.clone(),
+                    None => {
+                        let id = format!("a{}", graph.assets.len());
+                        graph.assets.push((id.clone(), asset));
+                        id
+                    }
+                }
+            }
+        };
+        graph.edges.push((from.clone(), to, Edge::Arc(arc.kind.clone())));
+    }
+    graph
+}
+
+/// Graphviz double-quoted string contents
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Mermaid quoted label contents, with quotes and angle brackets as entity codes
+fn mermaid_escape(text: &str) -> String {
+    text.replace('"', "#quot;").replace('<', "#lt;").replace('>', "#gt;")
+}
+
+/// Describe a stage's prim hierarchy and composition arcs as a graph
+///
+/// `prims` are expected in path order, as the engine lists them.
+pub fn scene_graph(title: &str, prims: &[USDPrimStatus], arcs: &[USDCompositionArc], format: GraphFormat) -> String {
+    let graph = build_graph(prims, arcs);
+    let mut out = String::new();
+    match format {
+        GraphFormat::Graphviz => {
+            let _ = writeln!(out, "digraph \"{}\" {{", dot_escape(title));
+            let _ = writeln!(out, "    rankdir=LR;");
+            let _ = writeln!(out, "    node [shape=box, fontname=\"Helvetica\"];");
+            for (id, lines) in &graph.prims {
+                let label: Vec<String> = lines.iter().map(|line| dot_escape(line)).collect();
+                let _ = writeln!(out, "    {} [label=\"{}\"];", id, label.join("\\n"));
+            }
+            for (id, asset) in &graph.assets {
+                let _ = writeln!(out, "    {} [shape=note, label=\"{}\"];", id, dot_escape(asset));
+            }
+            for (from, to, edge) in &graph.edges {
+                match edge {
+                    Edge::Child => {
+                        let _ = writeln!(out, "    {} -> {};", from, to);
+                    }
+                    Edge::Arc(kind) => {
+                        let _ = writeln!(out, "    {} -> {} [style=dashed, label=\"{}\"];", from, to, dot_escape(kind));
+                    }
+                }
+            }
+            out.push_str("}\n");
+        }
+        GraphFormat::Mermaid => {
+            out.push_str("flowchart LR\n");
+            for (id, lines) in &graph.prims {
+                let label: Vec<String> = lines.iter().map(|line| mermaid_escape(line)).collect();
+                let _ = writeln!(out, "    {}[\"{}\"]", id, label.join("<br/>"));
+            }
+            for (id, asset) in &graph.assets {
+                let _ = writeln!(out, "    {}[/\"{}\"/]", id, mermaid_escape(asset));
+            }
+            for (from, to, edge) in &graph.edges {
+                match edge {
+                    Edge::Child => {
+                        let _ = writeln!(out, "    {} --> {}", from, to);
+                    }
+                    Edge::Arc(kind) => {
+                        let _ = writeln!(out, "    {} -. {} .-> {}", from, mermaid_escape(kind), to);
+                    }
+                }
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn prim(path: &str, prim_type: &str) -> USDPrimStatus {
+        USDPrimStatus { path: path.to_string(), prim_type: prim_type.to_string(), active: true, visible: true, kind: String::new() }
+    }
+    
+    fn arc(prim_path: &str, kind: &str, asset: &str, target: &str) -> USDCompositionArc {
+        USDCompositionArc { prim_path: prim_path.to_string(), kind: kind.to_string(), asset: asset.to_string(), target: target.to_string() }
+    }
+    
+    #[test]
+    fn hierarchy_and_arcs_in_both_formats() {
+        let prims = [prim("/World", "Xform"), prim("/World/Chair", "Xform"), prim("/World/Chair/Geom/Seat", "Mesh"), prim("/_class_Prop", "")];
+        let arcs = [
+            arc("/World/Chair", "reference", "props/chair.usd", "/Chair"),
+            arc("/World/Chair", "inherit", "", "/_class_Prop"),
+            arc("/World/Chair", "variant", "", "color=red"),
+        ];
+        
+        let dot = scene_graph("shot", &prims, &arcs, GraphFormat::Graphviz);
+        assert!(dot.starts_with("digraph \"shot\" {"));
+        assert!(dot.contains("p1 [label=\"Chair (Xform)\\nvariant color=red\"];"));
+        // The seat hangs from the chair, its missing Geom parent skipped
+        assert!(dot.contains("p1 -> p2;"));
+        assert!(dot.contains("a0 [shape=note, label=\"@props/chair.usd@</Chair>\"];"));
+        assert!(dot.contains("p1 -> a0 [style=dashed, label=\"reference\"];"));
+        assert!(dot.contains("p1 -> p3 [style=dashed, label=\"inherit\"];"));
+        assert!(!dot.contains("-> p0"));
+        
+        let mermaid = scene_graph("shot", &prims, &arcs, GraphFormat::Mermaid);
+        assert!(mermaid.starts_with("flowchart LR\n"));
+        assert!(mermaid.contains("a0[/\"@props/chair.usd@#lt;/Chair#gt;\"/]"));
+        assert!(mermaid.contains("p1 -. inherit .-> p3"));
+        assert_eq!(GraphFormat::from_label("Mermaid"), Some(GraphFormat::Mermaid));
+    }
+}
@@ -0,0 +1,121 @@
+//! Composed scene snapshots for other plugins
+//!
+//! A snapshot is what a renderer needs from a stage at one time code: every
+//! visible mesh with its points in world space and its bound material, the
+//! UsdPreviewSurface inputs of those materials, and every UsdLux light with
+//! its world transform. Snapshots are plain data, filled by the scene
+//! delegate's `SceneSnapshotSink`, so a plugin holding one never blocks the
+//! engine or sees it change.
+
+/// A mesh with its points in world space
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SceneMesh {
+    pub prim_path: String,
+    pub points: Vec<[f64; 3]>,
+    pub face_vertex_counts: Vec<i32>,
+    pub face_vertex_indices: Vec<i32>,
+    /// Material bound to the mesh, if any
+    pub material_path: Option<String>,
+}
+
+/// UsdPreviewSurface inputs of a material
+#[derive(Debug, Clone, PartialEq)]
+pub struct SceneMaterial {
+    pub prim_path: String,
+    pub diffuse_color: [f32; 3],
+    pub emissive_color: [f32; 3],
+    pub metallic: f32,
+    pub roughness: f32,
+    pub opacity: f32,
+    pub ior: f32,
+}
+
+impl SceneMaterial {
+    /// Material with the UsdPreviewSurface defaults
+    pub fn new(prim_path: &str) -> Self {
+        Self {
+            prim_path: prim_path.to_string(),
+            diffuse_color: [0.18, 0.18, 0.18],
+            emissive_color: [0.0, 0.0, 0.0],
+            metallic: 0.0,
+            roughness: 0.5,
+            opacity: 1.0,
+            ior: 1.5,
+        }
+    }
+}
+
+/// A UsdLux light
+#[derive(Debug, Clone, PartialEq)]
+pub struct SceneLight {
+    pub prim_path: String,
+    /// Schema type, e.g. "RectLight"
+    pub light_type: String,
+    /// Local-to-world matrix in USD's row-vector convention, rows first
+    pub transform: [f64; 16],
+    pub color: [f32; 3],
+    pub intensity: f32,
+    pub exposure: f32,
+    /// Radius of sphere, disk and cylinder lights
+    pub radius: f32,
+    /// Size of rect lights
+    pub width: f32,
+    pub height: f32,
+    /// Angular diameter of distant lights, in degrees
+    pub angle: f32,
+}
+
+impl SceneLight {
+    /// Light with the UsdLux defaults
+    pub fn new(prim_path: &str, light_type: &str, transform: [f64; 16]) -> Self {
+        Self {
+            prim_path: prim_path.to_string(),
+            light_type: light_type.to_string(),
+            transform,
+            color: [1.0, 1.0, 1.0],
+            intensity: 1.0,
+            exposure: 0.0,
+            radius: 0.5,
+            width: 1.0,
+            height: 1.0,
+            angle: 0.53,
+        }
+    }
+}
+
+/// Meshes, materials and lights of a stage at a time code
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SceneSnapshot {
+    pub stage_id: String,
+    pub time_code: f64,
+    pub meshes: Vec<SceneMesh>,
+    /// Materials bound to the meshes, each once
+    pub materials: Vec<SceneMaterial>,
+    pub lights: Vec<SceneLight>,
+}
+
+impl SceneSnapshot {
+    /// Index of a material in `materials`
+    pub fn material_index(&self, prim_path: &str) -> Option<usize> {
+        self.materials.iter().position(|material| material.prim_path == prim_path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn materials_start_from_schema_defaults_and_are_found_by_path() {
+        let material = SceneMaterial::new("/Looks/Clay");
+        assert_eq!(material.diffuse_color, [0.18, 0.18, 0.18]);
+        assert_eq!((material.metallic, material.roughness, material.opacity, material.ior), (0.0, 0.5, 1.0, 1.5));
+        
+        let light = SceneLight::new("/Lights/Key", "RectLight", [0.0; 16]);
+        assert_eq!((light.intensity, light.exposure, light.width, light.height), (1.0, 0.0, 1.0, 1.0));
+        
+        let snapshot = SceneSnapshot { materials: vec![material], ..SceneSnapshot::default() };
+        assert_eq!(snapshot.material_index("/Looks/Clay"), Some(0));
+        assert_eq!(snapshot.material_index("/Looks/Metal"), None);
+    }
+}
@@ -0,0 +1,244 @@
+//! Engine session persistence across plugin reloads
+//!
+//! Unloading the plugin drops the engine and every stage in it. Before that,
+//! each stage's file, unsaved layer text, edit target, layer stack edits and
+//! viewport prim and face selections are written to a session file in the temp
+//! directory; the next load reopens the stages under the same identifiers so
+//! graph connections naming them keep working. Files older than
+//! `RESTORE_WINDOW` are ignored, so a reload picks them up but a fresh start
+//! of the application the next day does not.
+
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use super::usd_engine::{with_usd_engine, USDEditTarget, USDStageSnapshot, USDTimeRange};
+use crate::viewport::picking::{picked_faces, selected_prims, set_picked_faces, set_selected_prims, FaceSelection};
+
+/// Session file name in the temp directory
+pub const SESSION_FILE: &str = "nodle_usd_session.json";
+
+/// Longest time between unload and load for the session to be restored
+pub const RESTORE_WINDOW: Duration = Duration::from_secs(300);
+
+/// Prims and faces selected in a stage's viewports
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StageSelection {
+    /// Most recent last
+    pub prims: Vec<String>,
+    pub faces: FaceSelection,
+}
+
+impl StageSelection {
+    fn capture(stage_id: &str) -> Self {
+        Self { prims: selected_prims(stage_id).1, faces: picked_faces(stage_id) }
+    }
+    
+    fn restore(self, stage_id: &str) {
+        if !self.prims.is_empty() {
+            set_selected_prims(stage_id, &self.prims);
+        }
+        if !self.faces.is_empty() {
+            set_picked_faces(stage_id, self.faces);
+        }
+    }
+}
+
+/// Snapshots of every stage with its selection
+pub struct EngineSnapshot {
+    /// Seconds since the Unix epoch
+    pub saved_at: u64,
+    pub stages: Vec<(USDStageSnapshot, StageSelection)>,
+}
+
+impl EngineSnapshot {
+    /// Snapshot the engine and the viewport selections now
+    pub fn capture() -> Self {
+        let stages = with_usd_engine(|engine| engine.snapshot_stages())
+            .into_iter()
+            .map(|stage| {
+                let selection = StageSelection::capture(&stage.identifier);
+                (stage, selection)
+            })
+            .collect();
+        Self { saved_at: unix_now(), stages }
+    }
+    
+    pub fn to_json(&self) -> Value {
+        json!({
+            "saved_at": self.saved_at,
+            "stages": self.stages.iter().map(|(stage, selection)| stage_to_json(stage, selection)).collect::<Vec<_>>(),
+        })
+    }
+    
+    pub fn from_json(value: &Value) -> Result<Self, String> {
+        let saved_at = value["saved_at"].as_u64().ok_or("Session has no save time")?;
+        let stages = value["stages"].as_array()
+            .ok_or("Session has no stages")?
+            .iter()
+            .map(stage_from_json)
+            .collect::<Result<_, _>>()?;
+        Ok(Self { saved_at, stages })
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs())
+}
+
+/// Location of the session file
+pub fn session_path() -> PathBuf {
+    std::env::temp_dir().join(SESSION_FILE)
+}
+
+/// Write the engine's stages to the session file, returning how many were saved
+pub fn save_session() -> Result<usize, String> {
+    let snapshot = EngineSnapshot::capture();
+    if snapshot.stages.is_empty() {
+        return Ok(0);
+    }
+    let text = serde_json::to_string(&snapshot.to_json())
+        .map_err(|e| format!("Failed to serialize session: {}", e))?;
+    std::fs::write(session_path(), text)
+        .map_err(|e| format!("Failed to write session file: {}", e))?;
+    Ok(snapshot.stages.len())
+}
+
+/// Reopen the stages of a recent session file and remove it, returning how many were restored
+///
+/// Stages that fail to reopen are reported and skipped.
+pub fn restore_session() -> Result<usize, String> {
+    let path = session_path();
+    let Ok(text) = std::fs::read_to_string(&path) else {
+        return Ok(0);
+    };
+    let _ = std::fs::remove_file(&path);
+    let value: Value = serde_json::from_str(&text)
+        .map_err(|e| format!("Failed to parse session file: {}", e))?;
+    let snapshot = EngineSnapshot::from_json(&value)?;
+    if unix_now().saturating_sub(snapshot.saved_at) > RESTORE_WINDOW.as_secs() {
+        return Ok(0);
+    }
+    
+    let mut restored = 0;
+    for (stage, selection) in snapshot.stages {
+        match with_usd_engine(|engine| engine.restore_stage(&stage)) {
+            Ok(_) => {
+                selection.restore(&stage.identifier);
+                restored += 1;
+            }
+            Err(e) => eprintln!("⚠ Failed to restore stage '{}': {}", stage.identifier, e),
+        }
+    }
+    Ok(restored)
+}
+
+fn stage_to_json(stage: &USDStageSnapshot, selection: &StageSelection) -> Value {
+    json!({
+        "identifier": stage.identifier,
+        "path": stage.path,
+        "root_layer": stage.root_layer,
+        "session_layer": stage.session_layer,
+        "edit_target": stage.edit_target.name(),
+        "sublayers": stage.sublayers,
+        "muted_layers": stage.muted_layers,
+        "pinned_prims": stage.pinned_prims,
+        "time_range": stage.time_range.map(|range| [range.start_time_code, range.end_time_code, range.time_codes_per_second]),
+        "variant_selections": stage.variant_selections,
+        "prims": stage.prims,
+        "attributes": stage.attributes,
+        "time_samples": stage.time_samples,
+        "selected_prims": selection.prims,
+        "selection": selection.faces,
+    })
+}
+
+fn field<T: DeserializeOwned>(value: &Value, name: &str) -> Result<T, String> {
+    serde_json::from_value(value[name].clone())
+        .map_err(|e| format!("Invalid session field '{}': {}", name, e))
+}
+
+fn stage_from_json(value: &Value) -> Result<(USDStageSnapshot, StageSelection), String> {
+    let time_range: Option<[f64; 3]> = field(value, "time_range")?;
+    let edit_target: String = field(value, "edit_target")?;
+    let stage = USDStageSnapshot {
+        identifier: field(value, "identifier")?,
+        path: field(value, "path")?,
+        root_layer: field(value, "root_layer")?,
+        session_layer: field(value, "session_layer")?,
+        edit_target: USDEditTarget::from_name(&edit_target),
+        sublayers: field(value, "sublayers")?,
+        muted_layers: field(value, "muted_layers")?,
+        pinned_prims: field(value, "pinned_prims")?,
+        time_range: time_range.map(|[start_time_code, end_time_code, time_codes_per_second]| USDTimeRange {
+            start_time_code,
+            end_time_code,
+            time_codes_per_second,
+        }),
+        variant_selections: field(value, "variant_selections")?,
+        prims: field(value, "prims")?,
+        attributes: field(value, "attributes")?,
+        time_samples: field(value, "time_samples")?,
+    };
+    let selection = StageSelection { prims: field(value, "selected_prims")?, faces: field(value, "selection")? };
+    Ok((stage, selection))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+    
+    #[test]
+    fn snapshots_survive_the_session_file() {
+        let stage = USDStageSnapshot {
+            identifier: "loaded_1".to_string(),
+            path: "/shots/sh010.usda".to_string(),
+            root_layer: None,
+            session_layer: Some("#usda 1.0\n".to_string()),
+            edit_target: USDEditTarget::Layer("/shots/anim.usda".to_string()),
+            sublayers: Some(vec![("/shots/anim.usda".to_string(), 12.0)]),
+            muted_layers: vec!["/shots/fx.usda".to_string()],
+            pinned_prims: None,
+            time_range: Some(USDTimeRange { start_time_code: 1001.0, end_time_code: 1100.0, time_codes_per_second: 24.0 }),
+            variant_selections: vec![("/World/car{color}".to_string(), "session".to_string(), "red".to_string())],
+            prims: vec![("/World/car".to_string(), "Xform".to_string())],
+            attributes: vec![("/World/car.visibility".to_string(), "invisible".to_string())],
+            time_samples: vec![("/World/car.xformOp:translate".to_string(), vec![(1001.0, "(0, 0, 0)".to_string())])],
+        };
+        let selection = StageSelection {
+            prims: vec!["/World/car/wheel".to_string(), "/World/car".to_string()],
+            faces: FaceSelection::from([("/World/car/body".to_string(), BTreeSet::from([3, 7]))]),
+        };
+        let snapshot = EngineSnapshot { saved_at: 1_700_000_000, stages: vec![(stage.clone(), selection.clone())] };
+        
+        let text = serde_json::to_string(&snapshot.to_json()).unwrap();
+        let restored = EngineSnapshot::from_json(&serde_json::from_str(&text).unwrap()).unwrap();
+        assert_eq!(restored.saved_at, 1_700_000_000);
+        assert_eq!(restored.stages, vec![(stage, selection)]);
+        
+        assert!(EngineSnapshot::from_json(&json!({ "saved_at": 0, "stages": [{ "path": 3 }] })).is_err());
+    }
+    
+    #[test]
+    fn selections_and_layer_edits_are_restored_or_refused() {
+        let stage_id = with_usd_engine(|engine| engine.create_stage("session_selection")).unwrap().identifier;
+        set_selected_prims(&stage_id, &["/World/car".to_string()]);
+        let (mut stage, selection) = EngineSnapshot::capture().stages.into_iter()
+            .find(|(stage, _)| stage.identifier == stage_id)
+            .unwrap();
+        assert_eq!(selection.prims, vec!["/World/car".to_string()]);
+        
+        stage.identifier = "session_selection_restored".to_string();
+        with_usd_engine(|engine| engine.restore_stage(&stage)).unwrap();
+        selection.restore(&stage.identifier);
+        assert_eq!(selected_prims(&stage.identifier).1, vec!["/World/car".to_string()]);
+        
+        #[cfg(not(any(feature = "usd", feature = "usd-native")))]
+        {
+            stage.identifier = "session_layer_edits".to_string();
+            stage.session_layer = Some("#usda 1.0\n".to_string());
+            assert!(with_usd_engine(|engine| engine.restore_stage(&stage)).is_err());
+        }
+    }
+}
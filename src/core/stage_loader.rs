@@ -0,0 +1,138 @@
+//! Parallel stage opens for Load Stage nodes
+//!
+//! Every engine call runs under the engine's Mutex, so a graph with several
+//! Load Stage nodes used to open their files one after another. Each node
+//! now starts its open on a worker thread as soon as its path is set; the
+//! worker opens the file without the engine. `with_usd_engine` waits for
+//! running opens before it takes the engine lock, and `USDEngine::load_stage`
+//! adopts the opened stage when a downstream node first resolves that path,
+//! so the opens overlap each other and no engine call waits under the lock.
+//!
+//! Only native backend opens run in parallel. `Usd.Stage.Open` holds the GIL
+//! for the whole open, so Python backend opens are serialized with each other
+//! and with the engine's Python calls; they only move off the node's process.
+
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+#[cfg(feature = "usd")]
+use pyo3::prelude::*;
+#[cfg(feature = "usd")]
+use super::profiling;
+#[cfg(feature = "usd-native")]
+use super::usd_native::NativeStage;
+
+/// A stage opened off the engine, ready to be adopted by it
+pub enum OpenedStage {
+    #[cfg(feature = "usd-native")]
+    Native(NativeStage),
+    #[cfg(feature = "usd")]
+    Python(Py<PyAny>),
+}
+
+/// Result of an open, sent by its worker once it finishes
+type OpenResult = Result<OpenedStage, String>;
+
+/// A background open of a stage file
+enum BackgroundOpen {
+    Running(Receiver<OpenResult>),
+    Finished(OpenResult),
+}
+
+/// Opens started and not yet adopted, by file path
+static OPENS: Lazy<Mutex<HashMap<String, BackgroundOpen>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Start opening a stage file on a worker thread, unless it is already being opened
+///
+/// Does nothing without a USD backend, where opening a stage is free.
+pub fn open_in_background(file_path: &str) {
+    if !cfg!(any(feature = "usd", feature = "usd-native")) {
+        return;
+    }
+    let mut opens = OPENS.lock().unwrap();
+    if opens.contains_key(file_path) {
+        return;
+    }
+    let (sender, receiver) = mpsc::channel();
+    let path = file_path.to_string();
+    let spawned = std::thread::Builder::new()
+        .name(format!("open {}", file_path))
+        .spawn(move || {
+            // The receiver is gone when the open was discarded
+            let _ = sender.send(open(&path));
+        });
+    match spawned {
+        Ok(_) => {
+            opens.insert(file_path.to_string(), BackgroundOpen::Running(receiver));
+        }
+        Err(e) => eprintln!("USD Plugin: Failed to start opening '{}': {}", file_path, e),
+    }
+}
+
+/// Wait for every running open to finish, keeping the results for `take_opened`
+///
+/// Called by `with_usd_engine` before it locks the engine, so the stages a
+/// Load Stage node started opening are ready for adoption without the engine
+/// lock being held while they open.
+pub fn wait_for_opens() {
+    let running: HashMap<String, BackgroundOpen> = {
+        let mut opens = OPENS.lock().unwrap();
+        let (running, finished) = std::mem::take(&mut *opens).into_iter()
+            .partition(|(_, open)| matches!(open, BackgroundOpen::Running(_)));
+        *opens = finished;
+        running
+    };
+    for (path, open) in running {
+        if let BackgroundOpen::Running(receiver) = open {
+            let result = receiver.recv().unwrap_or_else(|_| Err(format!("Opening '{}' stopped unexpectedly", path)));
+            OPENS.lock().unwrap().entry(path).or_insert(BackgroundOpen::Finished(result));
+        }
+    }
+}
+
+/// Take the result of a finished background open of the file
+///
+/// None when no open was started for the path, or one started since the
+/// engine was locked is still running; `load_stage` then opens the file itself.
+pub fn take_opened(file_path: &str) -> Option<OpenResult> {
+    let mut opens = OPENS.lock().unwrap();
+    match opens.get(file_path)? {
+        BackgroundOpen::Running(_) => None,
+        BackgroundOpen::Finished(_) => match opens.remove(file_path) {
+            Some(BackgroundOpen::Finished(result)) => Some(result),
+            _ => None,
+        },
+    }
+}
+
+/// Drop a background open nobody will adopt, such as one for a stage already in the engine
+pub fn discard(file_path: &str) {
+    OPENS.lock().unwrap().remove(file_path);
+}
+
+/// Open a stage file with the backend `USDEngine::load_stage` would use
+///
+/// The Python fallback holds the GIL for the whole open, see the module docs.
+fn open(file_path: &str) -> Result<OpenedStage, String> {
+    #[cfg(feature = "usd-native")]
+    match NativeStage::open(file_path) {
+        Ok(native) => return Ok(OpenedStage::Native(native)),
+        Err(e) => eprintln!("Native USD backend failed, falling back to Python: {}", e),
+    }
+    
+    #[cfg(feature = "usd")]
+    {
+        profiling::with_gil("open_stage", |py| -> Result<OpenedStage, String> {
+            let usd = py.import("pxr.Usd").map_err(|e| format!("Failed to import USD: {}", e))?;
+            usd.getattr("Stage")
+                .and_then(|stage_class| stage_class.call_method1("Open", (file_path,)))
+                .map(|stage| OpenedStage::Python(stage.unbind()))
+                .map_err(|e| format!("Failed to open stage '{}': {}", file_path, e))
+        })
+    }
+    
+    // load_stage retries and reports the failure
+    #[cfg(not(feature = "usd"))]
+    Err(format!("Failed to open stage '{}'", file_path))
+}
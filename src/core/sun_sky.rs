@@ -0,0 +1,337 @@
+//! Sun position and procedural sky
+//!
+//! The sun is placed either from a date, local time and location, using
+//! NOAA's solar position equations (good to a fraction of a degree), or from
+//! azimuth and elevation directly. It is authored as a DistantLight tinted by
+//! the atmosphere it shines through, next to a DomeLight whose latitude-longitude
+//! texture is the Preetham analytic daylight sky.
+//!
+//! Compass azimuth is measured clockwise from north, with north along -Z and
+//! east along +X. In the sky texture u = 0.5 faces -Z and u increases toward
+//! +X; the top row is the zenith.
+
+use std::f64::consts::{FRAC_PI_2, PI, TAU};
+use chrono::{Datelike, NaiveDate};
+use glam::DVec3;
+use image::{Rgb, Rgb32FImage};
+use crate::core::light_rig::{light_group_edits, RigLight};
+use crate::core::usd_engine::{USDAttributeEdit, USDPrimSpec};
+
+/// Sky radiance units: a clear midday zenith of about 10 kcd/m² comes out near 1
+const SKY_SCALE: f64 = 0.1;
+
+/// Diffuse reflectance of the ground below the horizon
+const GROUND_ALBEDO: f64 = 0.2;
+
+/// Where the sun is placed
+#[derive(Debug, Clone, PartialEq)]
+pub enum SunPlacement {
+    /// Solar position at a date, time and location
+    Location(SolarTime),
+    /// Compass azimuth and elevation in degrees
+    Angles { azimuth: f64, elevation: f64 },
+}
+
+/// A moment at a place on Earth
+#[derive(Debug, Clone, PartialEq)]
+pub struct SolarTime {
+    pub date: NaiveDate,
+    /// Local clock time in hours, 13.5 is half past one
+    pub hour: f64,
+    /// Hours local time is ahead of UTC
+    pub utc_offset: f64,
+    /// Degrees, north positive
+    pub latitude: f64,
+    /// Degrees, east positive
+    pub longitude: f64,
+}
+
+impl SolarTime {
+    /// Compass azimuth and elevation of the sun in degrees
+    pub fn sun_angles(&self) -> (f64, f64) {
+        let utc_hour = self.hour - self.utc_offset;
+        let days_in_year = NaiveDate::from_ymd_opt(self.date.year(), 12, 31).map_or(365, |last| last.ordinal()) as f64;
+        // Fractional year in radians
+        let gamma = TAU / days_in_year * (self.date.ordinal0() as f64 + (utc_hour - 12.0) / 24.0);
+        let equation_of_time = 229.18 * (0.000075 + 0.001868 * gamma.cos() - 0.032077 * gamma.sin()
+            - 0.014615 * (2.0 * gamma).cos() - 0.040849 * (2.0 * gamma).sin());
+        let declination = 0.006918 - 0.399912 * gamma.cos() + 0.070257 * gamma.sin()
+            - 0.006758 * (2.0 * gamma).cos() + 0.000907 * (2.0 * gamma).sin()
+            - 0.002697 * (3.0 * gamma).cos() + 0.00148 * (3.0 * gamma).sin();
+        
+        let solar_minutes = utc_hour * 60.0 + equation_of_time + 4.0 * self.longitude;
+        let hour_angle = (solar_minutes / 4.0 - 180.0).to_radians();
+        let latitude = self.latitude.to_radians();
+        
+        let sin_elevation = latitude.sin() * declination.sin() + latitude.cos() * declination.cos() * hour_angle.cos();
+        let azimuth = (-hour_angle.sin()).atan2(declination.tan() * latitude.cos() - latitude.sin() * hour_angle.cos());
+        (azimuth.to_degrees().rem_euclid(360.0), sin_elevation.clamp(-1.0, 1.0).asin().to_degrees())
+    }
+}
+
+/// Fraction of sunlight at 680, 550 and 440 nm reaching the ground
+///
+/// Rayleigh scattering plus Ångström aerosol extinction with Preetham's
+/// turbidity-to-haze relation, over Kasten and Young's relative air mass.
+pub fn sun_transmittance(elevation: f64, turbidity: f64) -> [f64; 3] {
+    if elevation <= 0.0 {
+        return [0.0; 3];
+    }
+    let zenith = 90.0 - elevation;
+    let air_mass = 1.0 / (zenith.to_radians().cos() + 0.50572 * (96.07995 - zenith).powf(-1.6364));
+    let beta = 0.04608 * turbidity - 0.04586;
+    [0.68_f64, 0.55, 0.44].map(|wavelength| {
+        let rayleigh = 0.008569 * wavelength.powi(-4) * (1.0 + 0.0113 * wavelength.powi(-2) + 0.00013 * wavelength.powi(-4));
+        let aerosol = beta * wavelength.powf(-1.3);
+        (-air_mass * (rayleigh + aerosol)).exp()
+    })
+}
+
+/// Preetham, Shirley and Smits' analytic clear sky
+///
+/// Luminance and chromaticity follow the Perez distribution, relative to
+/// zenith values fitted against turbidity and sun angle. Below the horizon
+/// the sun is held at the horizon and the sky fades out through civil
+/// twilight.
+#[derive(Debug, Clone)]
+pub struct PreethamSky {
+    sun: DVec3,
+    /// Zenith luminance (kcd/m²) and chromaticity x, y
+    zenith: [f64; 3],
+    /// Perez A-E coefficients for Y, x and y
+    perez: [[f64; 5]; 3],
+    /// Perez value at the zenith, per channel
+    normalization: [f64; 3],
+    /// Twilight fade, 1 with the sun above the horizon
+    fade: f64,
+}
+
+impl PreethamSky {
+    /// Sky for a unit vector toward the sun and a turbidity between 2 and 10
+    pub fn new(sun: DVec3, turbidity: f64) -> Self {
+        let t = turbidity;
+        let elevation = sun.y.clamp(-1.0, 1.0).asin();
+        let sun = DVec3::new(sun.x, sun.y.max(0.01), sun.z).normalize();
+        let theta = sun.y.acos();
+        let (theta2, theta3) = (theta * theta, theta * theta * theta);
+        
+        let chi = (4.0 / 9.0 - t / 120.0) * (PI - 2.0 * theta);
+        let zenith = [
+            ((4.0453 * t - 4.9710) * chi.tan() - 0.2155 * t + 2.4192).max(0.0),
+            t * t * (0.00166 * theta3 - 0.00375 * theta2 + 0.00209 * theta)
+                + t * (-0.02903 * theta3 + 0.06377 * theta2 - 0.03202 * theta + 0.00394)
+                + (0.11693 * theta3 - 0.21196 * theta2 + 0.06052 * theta + 0.25886),
+            t * t * (0.00275 * theta3 - 0.00610 * theta2 + 0.00317 * theta)
+                + t * (-0.04214 * theta3 + 0.08970 * theta2 - 0.04153 * theta + 0.00516)
+                + (0.15346 * theta3 - 0.26756 * theta2 + 0.06670 * theta + 0.26688),
+        ];
+        let perez = [
+            [0.1787 * t - 1.4630, -0.3554 * t + 0.4275, -0.0227 * t + 5.3251, 0.1206 * t - 2.5771, -0.0670 * t + 0.3703],
+            [-0.0193 * t - 0.2592, -0.0665 * t + 0.0008, -0.0004 * t + 0.2125, -0.0641 * t - 0.8989, -0.0033 * t + 0.0452],
+            [-0.0167 * t - 0.2608, -0.0950 * t + 0.0092, -0.0079 * t + 0.2102, -0.0441 * t - 1.6537, -0.0109 * t + 0.0529],
+        ];
+        let normalization = perez.map(|coefficients| perez_value(&coefficients, 0.0, theta));
+        let twilight = (elevation.to_degrees() / 6.0 + 1.0).clamp(0.0, 1.0);
+        Self {
+            sun,
+            zenith,
+            perez,
+            normalization,
+            fade: twilight * twilight * (3.0 - 2.0 * twilight),
+        }
+    }
+    
+    /// Linear sRGB radiance toward a unit direction above the horizon
+    pub fn radiance(&self, direction: DVec3) -> [f64; 3] {
+        let theta = direction.y.clamp(0.0, 1.0).acos().min(FRAC_PI_2 - 0.001);
+        let gamma = direction.dot(self.sun).clamp(-1.0, 1.0).acos();
+        let [luminance, x, y] = std::array::from_fn(|channel| {
+            self.zenith[channel] * perez_value(&self.perez[channel], theta, gamma) / self.normalization[channel]
+        });
+        let luminance = luminance * SKY_SCALE * self.fade;
+        
+        // xyY to XYZ to linear sRGB
+        let (big_x, big_z) = (x * luminance / y, (1.0 - x - y) * luminance / y);
+        [
+            3.2406 * big_x - 1.5372 * luminance - 0.4986 * big_z,
+            -0.9689 * big_x + 1.8758 * luminance + 0.0415 * big_z,
+            0.0557 * big_x - 0.2040 * luminance + 1.0570 * big_z,
+        ].map(|channel| channel.max(0.0))
+    }
+}
+
+/// Perez sky distribution at view zenith angle `theta` and sun angle `gamma`
+fn perez_value([a, b, c, d, e]: &[f64; 5], theta: f64, gamma: f64) -> f64 {
+    (1.0 + a * (b / theta.cos().max(0.001)).exp()) * (1.0 + c * (d * gamma).exp() + e * gamma.cos().powi(2))
+}
+
+/// A sun and sky to author under an Xform
+#[derive(Debug, Clone, PartialEq)]
+pub struct USDSunSky {
+    pub root_path: String,
+    pub placement: SunPlacement,
+    /// Atmospheric haze, 2 for a very clear day up to 10 for a hazy one
+    pub turbidity: f64,
+    pub sun_intensity: f64,
+    pub sky_intensity: f64,
+    /// Latitude-longitude EXR the dome light references
+    pub sky_texture: String,
+}
+
+impl USDSunSky {
+    /// Compass azimuth and elevation of the sun in degrees
+    pub fn sun_angles(&self) -> (f64, f64) {
+        match &self.placement {
+            SunPlacement::Location(time) => time.sun_angles(),
+            SunPlacement::Angles { azimuth, elevation } => (*azimuth, *elevation),
+        }
+    }
+    
+    /// Sun, placed like a rig light: rig azimuth 0 faces +Z, south
+    fn sun_light(&self) -> RigLight {
+        let (azimuth, elevation) = self.sun_angles();
+        let transmittance = sun_transmittance(elevation, self.turbidity);
+        let peak = transmittance.into_iter().fold(0.0, f64::max);
+        RigLight {
+            name: "Sun",
+            light_type: "DistantLight",
+            intensity: self.sun_intensity * peak,
+            color: if peak > 0.0 { transmittance.map(|channel| channel / peak) } else { [1.0; 3] },
+            azimuth: 180.0 - azimuth,
+            elevation,
+            distance: 0.0,
+            // Angular diameter of the sun in degrees
+            size: [0.53, 0.0],
+        }
+    }
+    
+    /// Unit vector toward the sun
+    pub fn sun_direction(&self) -> DVec3 {
+        DVec3::from(RigLight { distance: 1.0, ..self.sun_light() }.translate())
+    }
+    
+    /// Sun and sky light paths
+    pub fn light_paths(&self) -> Vec<String> {
+        vec![format!("{}/Sun", self.root_path), format!("{}/Sky", self.root_path)]
+    }
+    
+    /// Prims and attribute values for the sun and sky lights
+    pub fn edits(&self) -> (Vec<USDPrimSpec>, Vec<USDAttributeEdit>) {
+        let sky = RigLight {
+            name: "Sky",
+            light_type: "DomeLight",
+            intensity: self.sky_intensity,
+            color: [1.0; 3],
+            azimuth: 0.0,
+            elevation: 0.0,
+            distance: 0.0,
+            size: [0.0; 2],
+        };
+        light_group_edits(&self.root_path, 0.0, &[self.sun_light(), sky], &self.sky_texture)
+    }
+    
+    /// Bake the sky into a latitude-longitude image twice as wide as tall
+    ///
+    /// The sun disk is left to the DistantLight. The ground reflects the sky's
+    /// irradiance diffusely.
+    pub fn sky_image(&self, width: u32) -> Rgb32FImage {
+        let (width, height) = (width.max(2), (width / 2).max(1));
+        let sky = PreethamSky::new(self.sun_direction(), self.turbidity);
+        let direction = |column: u32, row: u32| {
+            let longitude = PI * (2.0 * (column as f64 + 0.5) / width as f64 - 1.0);
+            let latitude = FRAC_PI_2 - PI * (row as f64 + 0.5) / height as f64;
+            DVec3::new(longitude.sin() * latitude.cos(), latitude.sin(), -longitude.cos() * latitude.cos())
+        };
+        
+        let mut image = Rgb32FImage::new(width, height);
+        let mut irradiance = [0.0; 3];
+        for row in 0..height.div_ceil(2) {
+            for column in 0..width {
+                let direction = direction(column, row);
+                let radiance = sky.radiance(direction);
+                // Pixel solid angle times the cosine to the up axis
+                let weight = direction.y.max(0.0) * (PI / height as f64) * (TAU / width as f64) * (1.0 - direction.y * direction.y).sqrt();
+                for (sum, value) in irradiance.iter_mut().zip(radiance) {
+                    *sum += value * weight;
+                }
+                image.put_pixel(column, row, Rgb(radiance.map(|value| value as f32)));
+            }
+        }
+        let ground = Rgb(irradiance.map(|value| (value * GROUND_ALBEDO / PI) as f32));
+        for row in height.div_ceil(2)..height {
+            for column in 0..width {
+                image.put_pixel(column, row, ground);
+            }
+        }
+        image
+    }
+    
+    /// Write the baked sky to `sky_texture`, creating its directory
+    pub fn write_sky_texture(&self, width: u32) -> Result<(), String> {
+        let path = std::path::Path::new(&self.sky_texture);
+        if let Some(directory) = path.parent().filter(|directory| !directory.as_os_str().is_empty()) {
+            std::fs::create_dir_all(directory)
+                .map_err(|e| format!("Failed to create '{}': {}", directory.display(), e))?;
+        }
+        self.sky_image(width).save(path)
+            .map_err(|e| format!("Failed to write sky texture '{}': {}", self.sky_texture, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn greenwich(date: NaiveDate, hour: f64) -> SolarTime {
+        SolarTime { date, hour, utc_offset: 0.0, latitude: 51.4769, longitude: 0.0 }
+    }
+    
+    #[test]
+    fn solar_position_follows_the_day() {
+        let equinox = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        // Solar noon is about seven minutes after clock noon in March
+        let (azimuth, elevation) = greenwich(equinox, 12.12).sun_angles();
+        assert!((azimuth - 180.0).abs() < 1.0, "noon azimuth {}", azimuth);
+        assert!((elevation - (90.0 - 51.4769)).abs() < 1.0, "noon elevation {}", elevation);
+        
+        let (morning, _) = greenwich(equinox, 9.0).sun_angles();
+        let (evening, _) = greenwich(equinox, 15.0).sun_angles();
+        assert!((90.0..180.0).contains(&morning) && (180.0..270.0).contains(&evening));
+        assert!(greenwich(equinox, 0.0).sun_angles().1 < 0.0);
+        
+        // Solstice sun overhead at the Tropic of Cancer, an hour ahead of UTC
+        let solstice = SolarTime {
+            date: NaiveDate::from_ymd_opt(2024, 6, 20).unwrap(),
+            hour: 13.03,
+            utc_offset: 1.0,
+            latitude: 23.44,
+            longitude: 0.0,
+        };
+        assert!(solstice.sun_angles().1 > 89.0);
+    }
+    
+    #[test]
+    fn low_sun_is_warm_and_the_sky_blue() {
+        let high = sun_transmittance(60.0, 3.0);
+        let low = sun_transmittance(5.0, 3.0);
+        assert!(low[2] / low[0] < high[2] / high[0]);
+        assert_eq!(sun_transmittance(-2.0, 3.0), [0.0; 3]);
+        
+        let sun_sky = USDSunSky {
+            root_path: "/Lights/SunSky".to_string(),
+            placement: SunPlacement::Angles { azimuth: 180.0, elevation: 45.0 },
+            turbidity: 3.0,
+            sun_intensity: 1.0,
+            sky_intensity: 1.0,
+            sky_texture: "sky.exr".to_string(),
+        };
+        assert!(sun_sky.sun_direction().distance(DVec3::new(0.0, 1.0, 1.0).normalize()) < 1e-9);
+        let zenith = PreethamSky::new(sun_sky.sun_direction(), 3.0).radiance(DVec3::Y);
+        assert!(zenith[2] > zenith[0] && zenith[1] > 0.1, "zenith {:?}", zenith);
+        
+        let image = sun_sky.sky_image(64);
+        assert_eq!(image.dimensions(), (64, 32));
+        let (sky, ground) = (image.get_pixel(0, 0), image.get_pixel(0, 31));
+        assert!(ground[0] > 0.0 && ground[0] < sky[0]);
+    }
+}
@@ -0,0 +1,214 @@
+//! Guided tutorial stage and its steps
+//!
+//! The tutorial is a small sample stage written to a .usda file, plus a
+//! checklist of first steps with it: loading it, viewing it, selecting the
+//! ball, binding the Clay material to the ball and framing the selection.
+//! Steps that leave a trace on the stage, such as a material binding, are
+//! read back from it; steps that only happen in a panel, such as framing,
+//! are recorded here by the node that performs them, per stage.
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+
+/// File name the tutorial stage is written under by default
+pub const TUTORIAL_FILE_NAME: &str = "nodle_tutorial.usda";
+
+/// Prim the tutorial asks to select, bind and frame
+pub const TUTORIAL_BALL: &str = "/Tutorial/Geometry/Ball";
+
+/// Material the tutorial asks to bind
+pub const TUTORIAL_MATERIAL: &str = "/Tutorial/Looks/Clay";
+
+/// The tutorial stage: a ball and a crate on a ground plane, an unbound material, a sun, a sky and a camera
+pub const TUTORIAL_STAGE: &str = r#"#usda 1.0
+(
+    defaultPrim = "Tutorial"
+    doc = "Nodle tutorial stage"
+    metersPerUnit = 1
+    upAxis = "Y"
+)
+
+def Xform "Tutorial" (
+    kind = "assembly"
+)
+{
+    def Scope "Geometry"
+    {
+        def Sphere "Ball"
+        {
+            double radius = 1
+            double3 xformOp:translate = (0, 1, 0)
+            uniform token[] xformOpOrder = ["xformOp:translate"]
+        }
+
+        def Cube "Crate"
+        {
+            color3f[] primvars:displayColor = [(0.55, 0.4, 0.25)]
+            double size = 1.5
+            double3 xformOp:translate = (3, 0.75, -1)
+            uniform token[] xformOpOrder = ["xformOp:translate"]
+        }
+
+        def Mesh "Ground"
+        {
+            int[] faceVertexCounts = [4]
+            int[] faceVertexIndices = [0, 1, 2, 3]
+            point3f[] points = [(-8, 0, -8), (-8, 0, 8), (8, 0, 8), (8, 0, -8)]
+            color3f[] primvars:displayColor = [(0.45, 0.45, 0.45)]
+        }
+    }
+
+    def Scope "Looks"
+    {
+        def Material "Clay"
+        {
+            token outputs:surface.connect = </Tutorial/Looks/Clay/PreviewSurface.outputs:surface>
+
+            def Shader "PreviewSurface"
+            {
+                uniform token info:id = "UsdPreviewSurface"
+                color3f inputs:diffuseColor = (0.8, 0.35, 0.2)
+                float inputs:roughness = 0.6
+                token outputs:surface
+            }
+        }
+    }
+
+    def Scope "Lights"
+    {
+        def DistantLight "Sun"
+        {
+            float inputs:intensity = 3
+            float3 xformOp:rotateXYZ = (-45, 30, 0)
+            uniform token[] xformOpOrder = ["xformOp:rotateXYZ"]
+        }
+
+        def DomeLight "Sky"
+        {
+            float inputs:intensity = 0.3
+        }
+    }
+
+    def Camera "Camera"
+    {
+        float focalLength = 35
+        double3 xformOp:translate = (0, 3, 10)
+        float3 xformOp:rotateXYZ = (-12, 0, 0)
+        uniform token[] xformOpOrder = ["xformOp:translate", "xformOp:rotateXYZ"]
+    }
+}
+"#;
+
+/// A step of the tutorial, in the order they are taken
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TutorialStep {
+    LoadStage,
+    ViewStage,
+    SelectPrim,
+    BindMaterial,
+    FrameSelection,
+}
+
+impl TutorialStep {
+    pub const ALL: [TutorialStep; 5] = [
+        TutorialStep::LoadStage,
+        TutorialStep::ViewStage,
+        TutorialStep::SelectPrim,
+        TutorialStep::BindMaterial,
+        TutorialStep::FrameSelection,
+    ];
+    
+    /// Checklist entry
+    pub fn title(&self) -> &'static str {
+        match self {
+            TutorialStep::LoadStage => "Load the tutorial stage",
+            TutorialStep::ViewStage => "View it in a viewport",
+            TutorialStep::SelectPrim => "Select the ball",
+            TutorialStep::BindMaterial => "Bind the Clay material to the ball",
+            TutorialStep::FrameSelection => "Frame the selection",
+        }
+    }
+    
+    /// How to take the step, and the USD concept it shows
+    pub fn hint(&self) -> &'static str {
+        match self {
+            TutorialStep::LoadStage => "Add a Load Stage node and open the tutorial file above. A stage is the composed scene of a root layer and everything it brings in.",
+            TutorialStep::ViewStage => "Connect the Load Stage output to a Viewport's Stage input. Every prim on the stage is addressed by a path such as /Tutorial/Geometry/Ball.",
+            TutorialStep::SelectPrim => "Click the ball in the viewport, or pick /Tutorial/Geometry/Ball in a Stage Inspector. Selections are shared by every panel showing the stage.",
+            TutorialStep::BindMaterial => "Add a Relationship node on material:binding with Prim Path /Tutorial/Geometry/Ball and Targets /Tutorial/Looks/Clay. Materials are bound to prims by relationships, not copied onto them.",
+            TutorialStep::FrameSelection => "Press F in the viewport, or Frame Selected, to fit the ball's bounds in view.",
+        }
+    }
+}
+
+/// Steps recorded by the panels that perform them, as (stage id, step)
+static RECORDED_STEPS: Lazy<Mutex<HashSet<(String, TutorialStep)>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+/// Record that a step was taken on a stage
+pub fn record_step(stage_id: &str, step: TutorialStep) {
+    if !stage_id.is_empty() {
+        RECORDED_STEPS.lock().unwrap().insert((stage_id.to_string(), step));
+    }
+}
+
+/// Whether a step was recorded on a stage
+pub fn is_recorded(stage_id: &str, step: TutorialStep) -> bool {
+    RECORDED_STEPS.lock().unwrap().contains(&(stage_id.to_string(), step))
+}
+
+/// First step not done yet, None once the tutorial is complete
+pub fn next_step(done: &[TutorialStep]) -> Option<TutorialStep> {
+    TutorialStep::ALL.into_iter().find(|step| !done.contains(step))
+}
+
+/// Default location of the tutorial file, in the temporary directory
+pub fn default_tutorial_path() -> String {
+    std::env::temp_dir().join(TUTORIAL_FILE_NAME).to_string_lossy().into_owned()
+}
+
+/// Write the tutorial stage to a file, creating its directory, and return the written path
+pub fn write_tutorial_stage(file_path: &str) -> Result<String, String> {
+    let path = Path::new(file_path);
+    if let Some(directory) = path.parent().filter(|directory| !directory.as_os_str().is_empty()) {
+        std::fs::create_dir_all(directory)
+            .map_err(|e| format!("Failed to create '{}': {}", directory.display(), e))?;
+    }
+    std::fs::write(path, TUTORIAL_STAGE)
+        .map_err(|e| format!("Failed to write tutorial stage '{}': {}", file_path, e))?;
+    Ok(file_path.to_string())
+}
+
+/// Whether two paths name the same file, comparing them as given when either doesn't exist
+pub fn is_same_file(a: &str, b: &str) -> bool {
+    match (std::fs::canonicalize(a), std::fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn steps_are_taken_in_order_and_recorded_per_stage() {
+        // The stage has the prims the steps name, with nothing bound yet
+        assert!(TUTORIAL_STAGE.contains("def Sphere \"Ball\"") && TUTORIAL_STAGE.contains("def Material \"Clay\""));
+        assert!(!TUTORIAL_STAGE.contains("material:binding"));
+        
+        assert_eq!(next_step(&[]), Some(TutorialStep::LoadStage));
+        assert_eq!(next_step(&[TutorialStep::LoadStage, TutorialStep::SelectPrim]), Some(TutorialStep::ViewStage));
+        assert_eq!(next_step(&TutorialStep::ALL), None);
+        
+        record_step("tutorial_test", TutorialStep::FrameSelection);
+        assert!(is_recorded("tutorial_test", TutorialStep::FrameSelection));
+        assert!(!is_recorded("other_stage", TutorialStep::FrameSelection));
+        
+        let path = std::env::temp_dir().join("nodle_tutorial_test").join(TUTORIAL_FILE_NAME);
+        let written = write_tutorial_stage(&path.to_string_lossy()).unwrap();
+        assert_eq!(std::fs::read_to_string(&written).unwrap(), TUTORIAL_STAGE);
+        assert!(is_same_file(&written, &path.to_string_lossy()));
+    }
+}
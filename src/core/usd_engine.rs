@@ -5,7 +5,26 @@ use pyo3::prelude::*;
 #[cfg(feature = "usd")]
 use pyo3::types::{PyDict, PyString};
 use std::collections::HashMap;
-use super::local_usd;
+use super::{local_usd, profiling, usdz};
+use super::stage_loader;
+use super::usd_value::UsdValue;
+use super::light_rig::USDLightRig;
+use super::sun_sky::USDSunSky;
+use super::geom_subset::USDGeomSubset;
+use super::bounds::{BoundingBox, USDBounds};
+use super::bounding_volume::{USDBoundingVolume, API_SCHEMAS};
+use super::hlod::{SourceMesh, USDHlod, FULL_VARIANT, VARIANT_SET};
+use super::occlusion::USDVisibilityBake;
+use crate::viewport::projection::ProjectionCamera;
+#[cfg(not(feature = "usd"))]
+use super::materialx::parse_document;
+use glam::{Mat4, Vec3};
+#[cfg(not(feature = "usd"))]
+use crate::viewport::projection::xform_ops_to_mat4;
+#[cfg(feature = "usd")]
+use super::geom_subset::extract_faces;
+#[cfg(feature = "usd-native")]
+use super::usd_native::NativeStage;
 
 /// USD Stage handle - holds a reference to a USD stage
 #[derive(Debug, Clone)]
@@ -22,12 +41,1047 @@ pub struct USDPrim {
     pub stage_id: String,
 }
 
+/// Composed state of a prim as shown in the outliner
+#[derive(Debug, Clone, PartialEq)]
+pub struct USDPrimStatus {
+    pub path: String,
+    pub prim_type: String,
+    pub active: bool,
+    /// Computed visibility, false when the prim or an ancestor is invisible
+    pub visible: bool,
+    /// Model kind ("component", "assembly", ...), empty when unset
+    pub kind: String,
+}
+
+/// Composition arc authored on a prim in the stage's layer stack
+#[derive(Debug, Clone, PartialEq)]
+pub struct USDCompositionArc {
+    pub prim_path: String,
+    /// "reference", "payload", "inherit", "specialize" or "variant"
+    pub kind: String,
+    /// Layer identifier of an external reference or payload, empty for arcs within the stage
+    pub asset: String,
+    /// Target prim path, or "set=selection" for a variant
+    pub target: String,
+}
+
+/// Draw mode of a model prim from UsdGeomModelAPI, drawn in place of its geometry
+#[derive(Debug, Clone, PartialEq)]
+pub struct USDDrawMode {
+    pub prim_path: String,
+    /// Computed model:drawMode, "origin", "bounds" or "cards"
+    pub mode: String,
+    /// model:drawModeColor
+    pub color: [f32; 3],
+    /// model:cardGeometry, "cross", "box" or "fromTexture"
+    pub card_geometry: String,
+    /// Card texture asset paths in XPos, YPos, ZPos, XNeg, YNeg, ZNeg order
+    pub card_textures: [Option<String>; 6],
+    /// Bounds of the model without its transform
+    pub bounds: BoundingBox,
+    pub transform: Mat4,
+}
+
+/// Card texture attribute suffixes in `USDDrawMode::card_textures` order
+pub const CARD_FACES: [&str; 6] = ["XPos", "YPos", "ZPos", "XNeg", "YNeg", "ZNeg"];
+
+/// drawModeColor UsdGeomModelAPI falls back to
+pub const DEFAULT_DRAW_MODE_COLOR: [f32; 3] = [0.18, 0.18, 0.18];
+
+/// What a prim property row describes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum USDPropertyKind {
+    Attribute,
+    Relationship,
+    Metadata,
+}
+
+/// A prim attribute, relationship or metadata field with its resolved value
+#[derive(Debug, Clone, PartialEq)]
+pub struct USDProperty {
+    pub name: String,
+    pub kind: USDPropertyKind,
+    /// Sdf value type name for attributes ("float3", "token[]", ...), "rel" for relationships
+    pub type_name: String,
+    /// Value resolved at the requested time; relationship targets comma separated
+    pub value: String,
+    /// Whether the attribute has time samples
+    pub animated: bool,
+}
+
+impl USDProperty {
+    /// Whether the value is a scalar or small tuple that can be edited as text
+    pub fn is_editable(&self) -> bool {
+        const SCALARS: [&str; 4] = ["bool", "string", "token", "asset"];
+        const TUPLE_PREFIXES: [&str; 10] = ["int", "uint", "half", "float", "double", "color", "point", "normal", "vector", "texCoord"];
+        self.kind == USDPropertyKind::Attribute
+            && !self.type_name.ends_with("[]")
+            && (SCALARS.contains(&self.type_name.as_str())
+                || TUPLE_PREFIXES.iter().any(|prefix| self.type_name.starts_with(prefix)))
+    }
+}
+
+/// Geometry a material preview is shown on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreviewShape {
+    Sphere,
+    /// Sphere on a pedestal, showing contact and falloff
+    ShaderBall,
+    Cube,
+}
+
+impl PreviewShape {
+    pub const ALL: [PreviewShape; 3] = [PreviewShape::Sphere, PreviewShape::ShaderBall, PreviewShape::Cube];
+    
+    pub fn name(&self) -> &'static str {
+        match self {
+            PreviewShape::Sphere => "Sphere",
+            PreviewShape::ShaderBall => "Shader Ball",
+            PreviewShape::Cube => "Cube",
+        }
+    }
+    
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|shape| shape.name() == name)
+    }
+}
+
+/// Time codes of one full turn of the material preview turntable
+pub const PREVIEW_TURNTABLE_FRAMES: f64 = 48.0;
+
+/// Stage playback range from startTimeCode/endTimeCode/timeCodesPerSecond
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct USDTimeRange {
+    pub start_time_code: f64,
+    pub end_time_code: f64,
+    pub time_codes_per_second: f64,
+}
+
+impl Default for USDTimeRange {
+    fn default() -> Self {
+        Self {
+            start_time_code: 1.0,
+            end_time_code: 24.0,
+            time_codes_per_second: 24.0,
+        }
+    }
+}
+
+impl USDTimeRange {
+    /// Whether the stage has animation to play back
+    pub fn is_animated(&self) -> bool {
+        self.end_time_code > self.start_time_code
+    }
+    
+    /// Clamp a time code into the range
+    pub fn clamp(&self, time: f64) -> f64 {
+        time.clamp(self.start_time_code, self.end_time_code.max(self.start_time_code))
+    }
+}
+
+/// Engine state of one stage, enough to reopen it after the engine is dropped
+///
+/// Keys of the cached values drop the leading "stage:" so they survive a
+/// change of identifier. Anonymous layers are not carried over.
+#[derive(Debug, Clone, PartialEq)]
+pub struct USDStageSnapshot {
+    pub identifier: String,
+    pub path: String,
+    /// Exported root layer text, when the stage is in memory or has unsaved edits
+    pub root_layer: Option<String>,
+    /// Exported session layer text
+    pub session_layer: Option<String>,
+    pub edit_target: USDEditTarget,
+    pub sublayers: Option<Vec<(String, f64)>>,
+    pub muted_layers: Vec<String>,
+    pub pinned_prims: Option<Vec<String>>,
+    pub time_range: Option<USDTimeRange>,
+    /// (prim{set}, edit target name, variant)
+    pub variant_selections: Vec<(String, String, String)>,
+    /// (path, type)
+    pub prims: Vec<(String, String)>,
+    /// (prim.attribute, value)
+    pub attributes: Vec<(String, String)>,
+    pub time_samples: Vec<(String, Vec<(f64, String)>)>,
+}
+
+/// Interpolation between keyframes authored with `USDEngine::set_keyframes`
+///
+/// USD interpolates time samples linearly, so held and eased keys are baked
+/// into extra samples between the keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyInterpolation {
+    Linear,
+    /// Step to the next key's value when it is reached
+    Held,
+    /// Smoothstep between keys, sampled every time code
+    Ease,
+}
+
+impl KeyInterpolation {
+    pub const ALL: [KeyInterpolation; 3] = [KeyInterpolation::Linear, KeyInterpolation::Held, KeyInterpolation::Ease];
+    
+    pub fn name(&self) -> &'static str {
+        match self {
+            KeyInterpolation::Linear => "Linear",
+            KeyInterpolation::Held => "Held",
+            KeyInterpolation::Ease => "Ease",
+        }
+    }
+    
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|interpolation| interpolation.name() == name)
+    }
+}
+
+/// Offset before the next key at which a held value is repeated
+const HELD_KEY_OFFSET: f64 = 1e-3;
+
+/// Transform ops in the order they are kept in xformOpOrder
+pub const XFORM_OP_ORDER: [&str; 3] = ["xformOp:translate", "xformOp:rotateXYZ", "xformOp:scale"];
+
+/// A prim's translate, rotateXYZ and scale ops at a time code, with its parent's world transform
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct USDXformOps {
+    pub translate: Vec3,
+    /// Degrees about X, then Y, then Z
+    pub rotate: Vec3,
+    pub scale: Vec3,
+    pub parent_to_world: Mat4,
+}
+
+/// How `USDEngine::edit_relationship_targets` changes a relationship's targets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelationshipEdit {
+    /// Replace the targets
+    Set,
+    Append,
+    Remove,
+}
+
+impl RelationshipEdit {
+    pub const ALL: [RelationshipEdit; 3] = [RelationshipEdit::Set, RelationshipEdit::Append, RelationshipEdit::Remove];
+    
+    pub fn name(&self) -> &'static str {
+        match self {
+            RelationshipEdit::Set => "Set",
+            RelationshipEdit::Append => "Append",
+            RelationshipEdit::Remove => "Remove",
+        }
+    }
+    
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|edit| edit.name() == name)
+    }
+}
+
+/// Expansion rule of a collection, deciding whether descendants of included paths are members
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollectionExpansion {
+    /// Only the listed paths
+    ExplicitOnly,
+    ExpandPrims,
+    ExpandPrimsAndProperties,
+}
+
+impl CollectionExpansion {
+    pub const ALL: [CollectionExpansion; 3] = [
+        CollectionExpansion::ExplicitOnly,
+        CollectionExpansion::ExpandPrims,
+        CollectionExpansion::ExpandPrimsAndProperties,
+    ];
+    
+    /// Token authored as the collection's expansionRule
+    pub fn token(&self) -> &'static str {
+        match self {
+            CollectionExpansion::ExplicitOnly => "explicitOnly",
+            CollectionExpansion::ExpandPrims => "expandPrims",
+            CollectionExpansion::ExpandPrimsAndProperties => "expandPrimsAndProperties",
+        }
+    }
+    
+    pub fn from_token(token: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|expansion| expansion.token() == token)
+    }
+}
+
+/// A named UsdCollectionAPI collection on a prim
+#[derive(Debug, Clone, PartialEq)]
+pub struct USDCollection {
+    pub prim_path: String,
+    pub name: String,
+    pub includes: Vec<String>,
+    pub excludes: Vec<String>,
+    pub expansion: CollectionExpansion,
+    /// Include the whole stage, with `excludes` carving prims out
+    pub include_root: bool,
+}
+
+impl USDCollection {
+    /// Collection path, as used by relationships targeting the collection ("/Lights/Key.collection:lightLink")
+    pub fn path(&self) -> String {
+        format!("{}.collection:{}", self.prim_path, self.name)
+    }
+}
+
+/// Split a collection path into its prim path and collection name
+pub fn split_collection_path(collection_path: &str) -> Option<(&str, &str)> {
+    let (prim_path, name) = collection_path.trim().rsplit_once(".collection:")?;
+    (prim_path.starts_with('/') && !name.is_empty()).then_some((prim_path, name))
+}
+
+/// Scope render passes are defined under
+pub const RENDER_PASS_ROOT: &str = "/Render/Passes";
+
+/// A UsdRenderPass with its visibility, matte and light selection collections
+#[derive(Debug, Clone, PartialEq)]
+pub struct USDRenderPass {
+    pub name: String,
+    /// Prims rendered by the pass; empty renders everything
+    pub visible: Vec<String>,
+    /// Rendered prims held out as mattes
+    pub mattes: Vec<String>,
+    /// Lights contributing to the pass; empty keeps every light
+    pub lights: Vec<String>,
+    /// Camera of the pass's render settings; None leaves the render source unset
+    pub camera: Option<String>,
+}
+
+impl USDRenderPass {
+    pub fn path(&self) -> String {
+        format!("{}/{}", RENDER_PASS_ROOT, self.name)
+    }
+    
+    /// Render settings prim the pass renders from
+    pub fn settings_path(&self) -> String {
+        format!("/Render/Settings/{}", self.name)
+    }
+    
+    /// The pass's renderVisibility, matte and lights collections
+    pub fn collections(&self) -> [USDCollection; 3] {
+        let collection = |name: &str, includes: &[String], include_root: bool| USDCollection {
+            prim_path: self.path(),
+            name: name.to_string(),
+            includes: includes.to_vec(),
+            excludes: Vec::new(),
+            expansion: CollectionExpansion::ExpandPrims,
+            include_root,
+        };
+        [
+            collection("renderVisibility", &self.visible, self.visible.is_empty()),
+            collection("matte", &self.mattes, false),
+            collection("lights", &self.lights, self.lights.is_empty()),
+        ]
+    }
+}
+
+/// One input of a stage assembly, referenced under its own Xform
+#[derive(Debug, Clone, PartialEq)]
+pub struct USDAssemblyInput {
+    /// Name of the Xform the input is referenced under
+    pub name: String,
+    pub stage_id: String,
+    pub translate: [f64; 3],
+    /// Rotation in degrees, applied in XYZ order
+    pub rotate: [f64; 3],
+    pub scale: [f64; 3],
+}
+
+/// A variant set on a prim with its variants and current selection
+#[derive(Debug, Clone, PartialEq)]
+pub struct USDVariantSet {
+    pub name: String,
+    pub variants: Vec<String>,
+    pub selection: String,
+}
+
+/// Layer that engine edits of a stage are authored into
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum USDEditTarget {
+    /// The stage's session layer, discarded unless explicitly saved
+    #[default]
+    Session,
+    /// The stage's root layer
+    Root,
+    /// Any layer of the layer stack, by identifier
+    Layer(String),
+}
+
+impl USDEditTarget {
+    /// Parse "session", "root" or a layer identifier
+    pub fn from_name(name: &str) -> Self {
+        match name.trim() {
+            "" | "session" => USDEditTarget::Session,
+            "root" => USDEditTarget::Root,
+            identifier => USDEditTarget::Layer(identifier.to_string()),
+        }
+    }
+    
+    pub fn name(&self) -> &str {
+        match self {
+            USDEditTarget::Session => "session",
+            USDEditTarget::Root => "root",
+            USDEditTarget::Layer(identifier) => identifier,
+        }
+    }
+}
+
+/// A layer in a stage's composed layer stack
+#[derive(Debug, Clone, PartialEq)]
+pub struct USDLayerInfo {
+    pub identifier: String,
+    /// 0 for the root layer, 1 for its sublayers and so on
+    pub depth: usize,
+    /// Layer offset applied by the parent layer
+    pub offset: f64,
+    pub scale: f64,
+    pub muted: bool,
+}
+
+/// A prim to define in a bulk request
+#[derive(Debug, Clone, PartialEq)]
+pub struct USDPrimSpec {
+    pub path: String,
+    pub prim_type: String,
+}
+
+/// An attribute value to author in a bulk request
+#[derive(Debug, Clone, PartialEq)]
+pub struct USDAttributeEdit {
+    pub prim_path: String,
+    pub attr_name: String,
+    pub value: UsdValue,
+}
+
+/// Root layer customLayerData key holding a stage's pinned prim paths
+const PINNED_PRIMS_KEY: &str = "nodle:pinnedPrims";
+
+/// Python helpers that apply bulk edits inside one Sdf.ChangeBlock
+///
+/// Values arrive as plain Python values from `UsdValue::to_python` with
+/// the Sdf type name to create missing attributes with. Existing attributes
+/// keep their type and values are converted to it.
+#[cfg(feature = "usd")]
+const BULK_EDIT_HELPERS: &std::ffi::CStr = cr#"
+from pxr import Sdf
+
+def _coerce(type_name, value):
+    # Build the attribute's Gf/Vt/Sdf value type from plain values
+    python_class = type_name.type.pythonClass
+    if python_class is None or isinstance(value, python_class):
+        return value
+    try:
+        # Vectors take their components, matrices their rows as one argument
+        if isinstance(value, tuple) and not any(isinstance(item, tuple) for item in value):
+            return python_class(*value)
+        return python_class(value)
+    except Exception:
+        return value
+
+def define_prims(stage, prims):
+    layer = stage.GetEditTarget().GetLayer()
+    with Sdf.ChangeBlock():
+        for path, prim_type in prims:
+            spec = Sdf.CreatePrimInLayer(layer, path)
+            spec.specifier = Sdf.SpecifierDef
+            spec.typeName = prim_type
+    return len(prims)
+
+def set_attributes(stage, edits):
+    layer = stage.GetEditTarget().GetLayer()
+    resolved = []
+    for prim_path, name, type_hint, value in edits:
+        attr = stage.GetAttributeAtPath(prim_path + "." + name)
+        type_name = attr.GetTypeName() if attr else Sdf.ValueTypeNames.Find(type_hint)
+        if type_name == Sdf.ValueTypeName():
+            raise ValueError("unknown value type '%s' for %s.%s" % (type_hint, prim_path, name))
+        resolved.append((prim_path, name, type_name, value))
+    with Sdf.ChangeBlock():
+        for prim_path, name, type_name, value in resolved:
+            prim_spec = layer.GetPrimAtPath(prim_path) or Sdf.CreatePrimInLayer(layer, prim_path)
+            attr_spec = prim_spec.attributes[name] if name in prim_spec.attributes else Sdf.AttributeSpec(prim_spec, name, type_name)
+            if isinstance(value, dict):
+                for time, sample in value.items():
+                    layer.SetTimeSample(attr_spec.path, time, _coerce(type_name, sample))
+            else:
+                attr_spec.default = _coerce(type_name, value)
+    return len(resolved)
+
+def clear_attributes(stage, paths):
+    # Only opinions in the edit target are cleared; weaker layers still show through
+    layer = stage.GetEditTarget().GetLayer()
+    with Sdf.ChangeBlock():
+        for path in paths:
+            attr_spec = layer.GetAttributeAtPath(path)
+            if not attr_spec:
+                continue
+            attr_spec.ClearDefaultValue()
+            for time in layer.ListTimeSamplesForPath(path):
+                layer.EraseTimeSample(path, time)
+
+def add_xform_op(stage, prim_path, op_name, canonical_order):
+    prim = stage.GetPrimAtPath(prim_path)
+    if not prim:
+        raise ValueError("prim '%s' not found" % prim_path)
+    order_attr = prim.GetAttribute("xformOpOrder")
+    ops = list(order_attr.Get() or []) if order_attr else []
+    if op_name in ops:
+        return False
+    # Insert before the first op that comes later in the canonical order
+    rank = canonical_order.index(op_name)
+    later = [index for index, op in enumerate(ops) if op in canonical_order and canonical_order.index(op) > rank]
+    ops.insert(later[0] if later else len(ops), op_name)
+    if not order_attr:
+        order_attr = prim.CreateAttribute("xformOpOrder", Sdf.ValueTypeNames.TokenArray, False)
+    order_attr.Set(ops)
+    return True
+"#;
+
+/// Python helpers for editing UsdGeomPointInstancers
+///
+/// Prototype and proto index lists are computed on the Rust side, see
+/// `split_by_variant`; these only read and author them.
+#[cfg(feature = "usd")]
+const INSTANCER_EDIT_HELPERS: &std::ffi::CStr = cr#"
+from pxr import Sdf, Usd, UsdGeom, Vt
+
+def _instancer(stage, path):
+    instancer = UsdGeom.PointInstancer.Get(stage, path)
+    if not instancer:
+        raise ValueError("'%s' is not a PointInstancer" % path)
+    return instancer
+
+def prototypes(stage, path):
+    return [str(target) for target in _instancer(stage, path).GetPrototypesRel().GetTargets()]
+
+def set_prototypes(stage, path, targets):
+    missing = [target for target in targets if not stage.GetPrimAtPath(target)]
+    if missing:
+        raise ValueError("prototype '%s' does not exist" % missing[0])
+    _instancer(stage, path).GetPrototypesRel().SetTargets([Sdf.Path(target) for target in targets])
+
+def edit_ids(stage, path, name, ids, add):
+    instancer = _instancer(stage, path)
+    if name == "invisibleIds":
+        time = Usd.TimeCode.Default()
+        ok = instancer.InvisIds(ids, time) if add else instancer.VisIds(ids, time)
+        current = instancer.GetInvisibleIdsAttr().Get(time)
+    else:
+        ok = instancer.DeactivateIds(ids) if add else instancer.ActivateIds(ids)
+        list_op = instancer.GetPrim().GetMetadata("inactiveIds")
+        current = list_op.ApplyOperations([]) if list_op else []
+    if not ok:
+        raise ValueError("could not author %s" % name)
+    return sorted(current or [])
+
+def variant_choices(stage, path, source, variant_set, primvar):
+    instancer = _instancer(stage, path)
+    variants = stage.GetPrimAtPath(source).GetVariantSets().GetVariantSet(variant_set).GetVariantNames()
+    if not variants:
+        raise ValueError("'%s' has no variant set '%s'" % (source, variant_set))
+    values = UsdGeom.PrimvarsAPI(instancer).GetPrimvar(primvar).Get()
+    if values is None:
+        raise ValueError("primvar '%s' has no value" % primvar)
+    choices = []
+    for value in values:
+        if isinstance(value, int):
+            choices.append(variants[value] if 0 <= value < len(variants) else "")
+        else:
+            choices.append(str(value) if str(value) in variants else "")
+    return list(instancer.GetProtoIndicesAttr().Get() or []), choices
+
+def author_variant_prototypes(stage, path, source, variant_set, variants, targets, proto_indices):
+    instancer = _instancer(stage, path)
+    for variant, prototype in variants:
+        prim = stage.DefinePrim(prototype)
+        references = prim.GetReferences()
+        references.ClearReferences()
+        references.AddInternalReference(source)
+        prim.GetVariantSets().GetVariantSet(variant_set).SetVariantSelection(variant)
+    instancer.GetPrototypesRel().SetTargets([Sdf.Path(target) for target in targets])
+    instancer.GetProtoIndicesAttr().Set(Vt.IntArray(proto_indices))
+"#;
+
+/// Python helper reading the composed prim hierarchy in one bridge call
+///
+/// TraverseAll includes inactive prims, whose descendants aren't composed.
+#[cfg(feature = "usd")]
+const PRIM_STATUS_HELPERS: &std::ffi::CStr = cr#"
+from pxr import Usd, UsdGeom
+
+def prim_statuses(stage):
+    statuses = []
+    for prim in stage.TraverseAll():
+        imageable = UsdGeom.Imageable(prim)
+        visible = not imageable or imageable.ComputeVisibility() != UsdGeom.Tokens.invisible
+        statuses.append((str(prim.GetPath()), str(prim.GetTypeName()), prim.IsActive(), visible, str(Usd.ModelAPI(prim).GetKind())))
+    return statuses
+"#;
+
+/// Python helper listing the composition arcs authored in a stage's layer stack
+#[cfg(feature = "usd")]
+const COMPOSITION_ARC_HELPERS: &std::ffi::CStr = cr#"
+from pxr import Usd, Pcp
+
+ARC_KINDS = {
+    Pcp.ArcTypeReference: "reference",
+    Pcp.ArcTypePayload: "payload",
+    Pcp.ArcTypeInherit: "inherit",
+    Pcp.ArcTypeSpecialize: "specialize",
+    Pcp.ArcTypeVariant: "variant",
+}
+
+def composition_arcs(stage):
+    local_layers = set(layer.identifier for layer in stage.GetLayerStack())
+    arcs = []
+    for prim in stage.TraverseAll():
+        for arc in Usd.PrimCompositionQuery(prim).GetCompositionArcs():
+            kind = ARC_KINDS.get(arc.GetArcType())
+            if kind is None or arc.IsAncestral() or not arc.IsIntroducedInRootLayerStack():
+                continue
+            target = arc.GetTargetPrimPath()
+            if kind == "variant":
+                arcs.append((str(prim.GetPath()), kind, "", "%s=%s" % target.GetVariantSelection()))
+                continue
+            layer = arc.GetTargetLayer()
+            asset = layer.identifier if layer and layer.identifier not in local_layers else ""
+            arcs.append((str(prim.GetPath()), kind, asset, str(target)))
+    return list(dict.fromkeys(arcs))
+"#;
+
+/// Python helper reading a prim's common xformOps and its parent's world transform
+#[cfg(feature = "usd")]
+const XFORM_OP_HELPERS: &std::ffi::CStr = cr#"
+from pxr import Usd, UsdGeom
+
+def xform_ops(stage, path, time, op_names):
+    prim = stage.GetPrimAtPath(path)
+    if not prim or not UsdGeom.Xformable(prim):
+        raise ValueError("'%s' is not transformable" % path)
+    code = Usd.TimeCode(time)
+    values = []
+    for name in op_names:
+        attr = prim.GetAttribute(name)
+        value = attr.Get(code) if attr else None
+        values.append(None if value is None else list(value))
+    parent = UsdGeom.XformCache(code).GetParentToWorldTransform(prim)
+    return values, [list(row) for row in parent]
+"#;
+
+/// Python helper listing a prim's properties and metadata in one bridge call
+#[cfg(feature = "usd")]
+const PRIM_PROPERTY_HELPERS: &std::ffi::CStr = cr#"
+from pxr import Usd
+
+def prim_properties(stage, path, time):
+    prim = stage.GetPrimAtPath(path)
+    if not prim:
+        raise ValueError("prim '%s' not found" % path)
+    code = Usd.TimeCode.Default() if time is None else Usd.TimeCode(time)
+    rows = []
+    for attr in prim.GetAttributes():
+        value = attr.Get(code)
+        rows.append(("attribute", attr.GetName(), str(attr.GetTypeName()), "" if value is None else str(value), attr.GetNumTimeSamples() > 0))
+    for rel in prim.GetRelationships():
+        rows.append(("relationship", rel.GetName(), "rel", ", ".join(str(target) for target in rel.GetTargets()), False))
+    for key, value in sorted(prim.GetAllAuthoredMetadata().items()):
+        rows.append(("metadata", key, type(value).__name__, str(value), False))
+    return rows
+"#;
+
+/// Python helpers reading and authoring relationship targets
+#[cfg(feature = "usd")]
+const RELATIONSHIP_HELPERS: &std::ffi::CStr = cr#"
+from pxr import Sdf, UsdShade
+
+def _prim(stage, path):
+    prim = stage.GetPrimAtPath(path)
+    if not prim:
+        raise ValueError("prim '%s' not found" % path)
+    return prim
+
+def targets(stage, path, name):
+    rel = _prim(stage, path).GetRelationship(name)
+    return [str(target) for target in rel.GetTargets()] if rel else []
+
+def edit_targets(stage, path, name, targets, edit):
+    prim = _prim(stage, path)
+    if name.startswith("material:binding"):
+        # Bindings are only honored with the binding API applied
+        UsdShade.MaterialBindingAPI.Apply(prim)
+    rel = prim.GetRelationship(name) or prim.CreateRelationship(name)
+    paths = [Sdf.Path(target) for target in targets]
+    if edit == "Set":
+        rel.SetTargets(paths)
+    elif edit == "Append":
+        for target in paths:
+            rel.AddTarget(target)
+    else:
+        for target in paths:
+            rel.RemoveTarget(target)
+"#;
+
+/// Python helpers authoring collections and resolving their membership
+#[cfg(feature = "usd")]
+const COLLECTION_HELPERS: &std::ffi::CStr = cr#"
+from pxr import Sdf, Usd
+
+def set_collection(stage, prim_path, name, includes, excludes, expansion, include_root):
+    prim = stage.GetPrimAtPath(prim_path)
+    if not prim:
+        raise ValueError("prim '%s' not found" % prim_path)
+    collection = Usd.CollectionAPI.Apply(prim, name)
+    collection.CreateExpansionRuleAttr().Set(expansion)
+    collection.CreateIncludeRootAttr().Set(include_root)
+    collection.CreateIncludesRel().SetTargets([Sdf.Path(path) for path in includes])
+    collection.CreateExcludesRel().SetTargets([Sdf.Path(path) for path in excludes])
+
+def members(stage, collection_path):
+    collection = Usd.CollectionAPI.GetCollection(stage, Sdf.Path(collection_path))
+    if not collection:
+        raise ValueError("'%s' is not a collection" % collection_path)
+    query = collection.ComputeMembershipQuery()
+    return sorted(str(path) for path in Usd.CollectionAPI.ComputeIncludedPaths(query, stage))
+"#;
+
+/// Python helper defining a render pass and its render settings
+#[cfg(feature = "usd")]
+const RENDER_PASS_HELPERS: &std::ffi::CStr = cr#"
+from pxr import Sdf, UsdGeom, UsdRender
+
+def define_pass(stage, pass_path, settings_path, camera_path):
+    for scope in Sdf.Path(pass_path).GetParentPath().GetPrefixes():
+        if not stage.GetPrimAtPath(scope):
+            UsdGeom.Scope.Define(stage, scope)
+    render_pass = UsdRender.Pass.Define(stage, pass_path)
+    source = render_pass.CreateRenderSourceRel()
+    if not camera_path:
+        source.ClearTargets(True)
+        return
+    settings = UsdRender.Settings.Define(stage, settings_path)
+    settings.CreateCameraRel().SetTargets([Sdf.Path(camera_path)])
+    source.SetTargets([settings.GetPath()])
+"#;
+
+/// Python helpers for referencing stages into an assembly stage
+#[cfg(feature = "usd")]
+const ASSEMBLY_HELPERS: &std::ffi::CStr = cr#"
+from pxr import Sdf, UsdGeom
+
+def reference_source(stage):
+    # Edits in the session layer would be lost by referencing the root layer alone
+    flattened = not stage.GetSessionLayer().empty
+    layer = stage.Flatten() if flattened else stage.GetRootLayer()
+    prim = stage.GetDefaultPrim() or next(iter(stage.GetPseudoRoot().GetChildren()), None)
+    if not prim:
+        raise ValueError("stage '%s' has no root prim to reference" % stage.GetRootLayer().identifier)
+    return layer, str(prim.GetPath()), flattened
+
+def assemble(stage, root_path, entries):
+    root = UsdGeom.Xform.Define(stage, root_path)
+    stage.SetDefaultPrim(stage.GetPrimAtPath(root.GetPath().GetPrefixes()[0]))
+    for name, asset, prim_path in entries:
+        xform = UsdGeom.Xform.Define(stage, root.GetPath().AppendChild(name))
+        xform.GetPrim().GetReferences().AddReference(asset, Sdf.Path(prim_path))
+"#;
+
+/// Python helper building a material preview stage from the composed material network
+#[cfg(feature = "usd")]
+const MATERIAL_PREVIEW_HELPERS: &std::ffi::CStr = cr#"
+from pxr import Gf, Sdf, Usd, UsdGeom, UsdLux, UsdShade
+
+def build_preview(source, material_path, shape, frames):
+    material = source.GetPrimAtPath(material_path)
+    if not material or not material.IsA(UsdShade.Material):
+        raise ValueError("'%s' is not a material" % material_path)
+    stage = Usd.Stage.CreateInMemory("material_preview.usda")
+    UsdGeom.SetStageUpAxis(stage, UsdGeom.Tokens.y)
+    stage.SetStartTimeCode(0)
+    stage.SetEndTimeCode(frames)
+    UsdGeom.Xform.Define(stage, "/Preview")
+    UsdGeom.Scope.Define(stage, "/Preview/Looks")
+    
+    # Copy from the flattened stage so sublayer and session opinions show up
+    target = Sdf.Path("/Preview/Looks").AppendChild(material.GetName())
+    Sdf.CopySpec(source.Flatten(), material.GetPath(), stage.GetRootLayer(), target)
+    bound = UsdShade.Material(stage.GetPrimAtPath(target))
+    
+    geo = UsdGeom.Xform.Define(stage, "/Preview/Geo")
+    turn = geo.AddRotateYOp()
+    turn.Set(0.0, 0)
+    turn.Set(360.0, frames)
+    if shape == "Cube":
+        shapes = [UsdGeom.Cube.Define(stage, "/Preview/Geo/Cube")]
+        shapes[0].CreateSizeAttr(1.6)
+    else:
+        shapes = [UsdGeom.Sphere.Define(stage, "/Preview/Geo/Ball")]
+    if shape == "Shader Ball":
+        pedestal = UsdGeom.Cylinder.Define(stage, "/Preview/Geo/Pedestal")
+        pedestal.CreateRadiusAttr(0.7)
+        pedestal.CreateHeightAttr(0.4)
+        pedestal.CreateAxisAttr(UsdGeom.Tokens.y)
+        pedestal.AddTranslateOp().Set(Gf.Vec3d(0, -1.2, 0))
+        shapes.append(pedestal)
+    for geom in shapes:
+        UsdShade.MaterialBindingAPI.Apply(geom.GetPrim()).Bind(bound)
+    
+    key = UsdLux.DistantLight.Define(stage, "/Preview/Key")
+    key.CreateIntensityAttr(3.0)
+    key.AddRotateXYZOp().Set(Gf.Vec3f(-35, 40, 0))
+    UsdLux.DomeLight.Define(stage, "/Preview/Fill").CreateIntensityAttr(0.4)
+    camera = UsdGeom.Camera.Define(stage, "/Preview/Camera")
+    camera.AddTranslateOp().Set(Gf.Vec3d(0, 0.2, 4.5))
+    return stage
+"#;
+
+/// Python helper referencing a MaterialX document through the UsdMtlx file format plugin
+#[cfg(feature = "usd")]
+const MATERIALX_HELPERS: &std::ffi::CStr = cr#"
+from pxr import Sdf, Usd, UsdShade
+
+def reference_document(stage, prim_path, file_path):
+    layer = Sdf.Layer.FindOrOpen(file_path)
+    if not layer:
+        raise ValueError("cannot read '%s'; is OpenUSD built with MaterialX support?" % file_path)
+    prim = stage.DefinePrim(prim_path, "Scope")
+    references = prim.GetReferences()
+    references.ClearReferences()
+    references.AddReference(layer.identifier)
+    return [str(child.GetPath()) for child in Usd.PrimRange(prim) if child.IsA(UsdShade.Material)]
+"#;
+
+/// Python helpers reading a face subset's mesh and writing subset meshes
+///
+/// Subset topology is cut out on the Rust side, see `extract_faces`.
+#[cfg(feature = "usd")]
+const GEOM_SUBSET_HELPERS: &std::ffi::CStr = cr#"
+from pxr import Gf, Usd, UsdGeom, Vt
+
+def subset_mesh(stage, subset_path):
+    subset = UsdGeom.Subset.Get(stage, subset_path)
+    if not subset or subset.GetElementTypeAttr().Get() != UsdGeom.Tokens.face:
+        raise ValueError("'%s' is not a face subset" % subset_path)
+    mesh = UsdGeom.Mesh(subset.GetPrim().GetParent())
+    if not mesh:
+        raise ValueError("'%s' is not below a mesh" % subset_path)
+    time = Usd.TimeCode.Default()
+    world = mesh.ComputeLocalToWorldTransform(time)
+    points = [tuple(world.Transform(Gf.Vec3d(point))) for point in mesh.GetPointsAttr().Get(time) or []]
+    return (
+        points,
+        list(mesh.GetFaceVertexCountsAttr().Get(time) or []),
+        list(mesh.GetFaceVertexIndicesAttr().Get(time) or []),
+        list(subset.GetIndicesAttr().Get(time) or []),
+        str(mesh.GetSubdivisionSchemeAttr().Get() or UsdGeom.Tokens.catmullClark),
+        str(UsdGeom.GetStageUpAxis(stage)),
+    )
+
+def write_mesh(path, file_format, name, points, counts, indices, scheme, up_axis):
+    stage = Usd.Stage.CreateInMemory()
+    UsdGeom.SetStageUpAxis(stage, up_axis)
+    mesh = UsdGeom.Mesh.Define(stage, "/" + name)
+    mesh.CreatePointsAttr(Vt.Vec3fArray([Gf.Vec3f(*point) for point in points]))
+    mesh.CreateFaceVertexCountsAttr(Vt.IntArray(counts))
+    mesh.CreateFaceVertexIndicesAttr(Vt.IntArray(indices))
+    mesh.CreateSubdivisionSchemeAttr(scheme)
+    mesh.CreateExtentAttr(UsdGeom.PointBased.ComputeExtent(mesh.GetPointsAttr().Get()))
+    stage.SetDefaultPrim(mesh.GetPrim())
+    args = {"format": file_format} if file_format else {}
+    return stage.GetRootLayer().Export(path, "", args)
+"#;
+
+/// Python helpers computing prim bounds with UsdGeomBBoxCache, reading model draw modes and gathering prim points
+#[cfg(feature = "usd")]
+const BOUNDS_HELPERS: &std::ffi::CStr = cr#"
+from pxr import Gf, Usd, UsdGeom
+
+def _range(box_range):
+    if box_range.IsEmpty():
+        return None
+    return tuple(box_range.GetMin()) + tuple(box_range.GetMax())
+
+def prim_bounds(stage, path, time):
+    prim = stage.GetPrimAtPath(path)
+    if not prim:
+        raise ValueError("prim '%s' not found" % path)
+    purposes = [UsdGeom.Tokens.default_, UsdGeom.Tokens.render, UsdGeom.Tokens.proxy]
+    cache = UsdGeom.BBoxCache(Usd.TimeCode(time), purposes, useExtentsHint=True)
+    world = cache.ComputeWorldBound(prim).ComputeAlignedRange()
+    local = cache.ComputeUntransformedBound(prim).ComputeAlignedRange()
+    return _range(world), _range(local)
+
+# Models drawn as stand-ins; models under one are skipped
+def draw_modes(stage, time, card_faces):
+    purposes = [UsdGeom.Tokens.default_, UsdGeom.Tokens.render, UsdGeom.Tokens.proxy]
+    cache = UsdGeom.BBoxCache(Usd.TimeCode(time), purposes, useExtentsHint=True)
+    xforms = UsdGeom.XformCache(Usd.TimeCode(time))
+    found = []
+    prims = iter(Usd.PrimRange(stage.GetPseudoRoot()))
+    for prim in prims:
+        if prim.IsPseudoRoot() or not prim.IsModel():
+            continue
+        model = UsdGeom.ModelAPI(prim)
+        apply = model.GetModelApplyDrawModeAttr()
+        mode = model.ComputeModelDrawMode()
+        if not (apply and apply.Get()) or mode == UsdGeom.Tokens.default_:
+            continue
+        prims.PruneChildren()
+        color = model.GetModelDrawModeColorAttr()
+        geometry = model.GetModelCardGeometryAttr()
+        textures = []
+        for face in card_faces:
+            attr = getattr(model, "GetModelCardTexture%sAttr" % face)()
+            asset = attr.Get() if attr else None
+            textures.append((asset.resolvedPath or asset.path) if asset else None)
+        matrix = xforms.GetLocalToWorldTransform(prim)
+        found.append((
+            str(prim.GetPath()),
+            str(mode),
+            tuple(color.Get()) if color and color.HasAuthoredValue() else None,
+            str(geometry.Get()) if geometry and geometry.HasAuthoredValue() else "cross",
+            textures,
+            _range(cache.ComputeUntransformedBound(prim).ComputeAlignedRange()),
+            [list(row) for row in matrix],
+        ))
+    return found
+
+# Points of point-based prims and extent corners of other boundables, in the root's space
+def prim_points(stage, path, time):
+    root = stage.GetPrimAtPath(path)
+    if not root:
+        raise ValueError("prim '%s' not found" % path)
+    time = Usd.TimeCode(time)
+    xforms = UsdGeom.XformCache(time)
+    to_root = xforms.GetLocalToWorldTransform(root).GetInverse()
+    points = []
+    prims = iter(Usd.PrimRange(root))
+    for prim in prims:
+        imageable = UsdGeom.Imageable(prim)
+        if imageable and imageable.GetPurposeAttr().Get() == UsdGeom.Tokens.guide:
+            prims.PruneChildren()
+            continue
+        boundable = UsdGeom.Boundable(prim)
+        if not boundable:
+            continue
+        point_based = UsdGeom.PointBased(prim)
+        if point_based:
+            local = point_based.GetPointsAttr().Get(time) or []
+        else:
+            extent = boundable.GetExtentAttr().Get(time) or UsdGeom.Boundable.ComputeExtentFromPlugins(boundable, time)
+            if not extent:
+                continue
+            low, high = extent[0], extent[1]
+            local = [Gf.Vec3d(x, y, z) for x in (low[0], high[0]) for y in (low[1], high[1]) for z in (low[2], high[2])]
+        matrix = xforms.GetLocalToWorldTransform(prim) * to_root
+        points.extend(tuple(matrix.Transform(Gf.Vec3d(point))) for point in local)
+    return points
+"#;
+
+/// Python helpers reading world-space meshes and authoring HLOD variants
+#[cfg(feature = "usd")]
+const HLOD_HELPERS: &std::ffi::CStr = cr#"
+from pxr import Gf, Usd, UsdGeom
+
+# Meshes under a prim with world-space points, skipping guides
+def world_meshes(stage, path, time):
+    root = stage.GetPrimAtPath(path)
+    if not root:
+        raise ValueError("prim '%s' not found" % path)
+    time = Usd.TimeCode(time)
+    xforms = UsdGeom.XformCache(time)
+    meshes = []
+    prims = iter(Usd.PrimRange(root))
+    for prim in prims:
+        imageable = UsdGeom.Imageable(prim)
+        if imageable and imageable.GetPurposeAttr().Get() == UsdGeom.Tokens.guide:
+            prims.PruneChildren()
+            continue
+        mesh = UsdGeom.Mesh(prim)
+        if not mesh:
+            continue
+        matrix = xforms.GetLocalToWorldTransform(prim)
+        meshes.append((
+            str(prim.GetPath()),
+            [tuple(matrix.Transform(Gf.Vec3d(point))) for point in mesh.GetPointsAttr().Get(time) or []],
+            list(mesh.GetFaceVertexCountsAttr().Get(time) or []),
+            list(mesh.GetFaceVertexIndicesAttr().Get(time) or []),
+        ))
+    return meshes
+
+# Variant set hiding different prims in each variant; ends on the given selection
+def author_lod_variants(stage, path, variant_set, variants, selection):
+    prim = stage.GetPrimAtPath(path)
+    if not prim:
+        raise ValueError("prim '%s' not found" % path)
+    lod = prim.GetVariantSets().AddVariantSet(variant_set)
+    for variant, hidden in variants:
+        lod.AddVariant(variant)
+        lod.SetVariantSelection(variant)
+        with lod.GetVariantEditContext():
+            for hidden_path in hidden:
+                UsdGeom.Imageable(stage.OverridePrim(hidden_path)).CreateVisibilityAttr().Set(UsdGeom.Tokens.invisible)
+    lod.SetVariantSelection(selection)
+"#;
+
+/// Python helper reading a camera's world transform and lens through Gf.Camera
+#[cfg(feature = "usd")]
+const CAMERA_HELPERS: &std::ffi::CStr = cr#"
+from pxr import Usd, UsdGeom
+
+def camera_view(stage, path, time):
+    camera = UsdGeom.Camera.Get(stage, path)
+    if not camera:
+        raise ValueError("'%s' is not a camera" % path)
+    lens = camera.GetCamera(Usd.TimeCode(time))
+    clipping = lens.clippingRange
+    return (
+        [list(row) for row in lens.transform],
+        lens.focalLength,
+        lens.horizontalAperture,
+        lens.verticalAperture,
+        clipping.min,
+        clipping.max,
+    )
+"#;
+
 /// USD Engine - manages USD operations through Python API
 pub struct USDEngine {
     #[cfg(feature = "usd")]
     _python_initialized: bool,
     stages: HashMap<String, USDStage>,
     prims: HashMap<String, USDPrim>,
+    /// Authored attribute values keyed by "stage:prim.attribute"
+    attributes: HashMap<String, String>,
+    /// Authored time samples keyed like `attributes`, sorted by time
+    time_samples: HashMap<String, Vec<(f64, String)>>,
+    /// Authored playback ranges keyed by stage identifier
+    time_ranges: HashMap<String, USDTimeRange>,
+    /// Variant selections and the layer they were authored in, keyed by "stage:prim{set}"
+    variant_selections: HashMap<String, (USDEditTarget, String)>,
+    /// Edit target per stage identifier; stages without one edit the session layer
+    edit_targets: HashMap<String, USDEditTarget>,
+    /// Root layer sublayer (path, offset) lists edited through the engine, keyed by stage identifier
+    sublayers: HashMap<String, Vec<(String, f64)>>,
+    /// Muted layer identifiers keyed by stage identifier
+    muted_layers: HashMap<String, Vec<String>>,
+    /// Edit counters keyed by stage identifier, bumped when composition changes
+    revisions: HashMap<String, u64>,
+    /// Anonymous layer identifiers added to each stage, keyed by stage identifier
+    anonymous_layers: HashMap<String, Vec<String>>,
+    /// Pinned prim paths keyed by stage identifier, as last read from or written to the root layer
+    pinned_prims: HashMap<String, Vec<String>>,
+    /// Prim bounds keyed by "stage:prim@time", with the stage revision they were computed at
+    bounds_cache: HashMap<String, (u64, USDBounds)>,
+    /// Live Python stages keyed by stage identifier; engine operations go through these
+    /// instead of reopening the stage from its path
+    #[cfg(feature = "usd")]
+    py_stages: HashMap<String, Py<PyAny>>,
+    /// Anonymous Python layers, kept alive so their identifiers stay resolvable
+    #[cfg(feature = "usd")]
+    retained_layers: Vec<Py<PyAny>>,
+    /// Stages held by the native OpenUSD backend, keyed by stage identifier
+    #[cfg(feature = "usd-native")]
+    native_stages: HashMap<String, NativeStage>,
 }
 
 impl USDEngine {
@@ -40,18 +1094,50 @@ impl USDEngine {
             _python_initialized: true,
             stages: HashMap::new(),
             prims: HashMap::new(),
+            attributes: HashMap::new(),
+            time_samples: HashMap::new(),
+            time_ranges: HashMap::new(),
+            variant_selections: HashMap::new(),
+            edit_targets: HashMap::new(),
+            sublayers: HashMap::new(),
+            muted_layers: HashMap::new(),
+            revisions: HashMap::new(),
+            anonymous_layers: HashMap::new(),
+            pinned_prims: HashMap::new(),
+            bounds_cache: HashMap::new(),
+            #[cfg(feature = "usd")]
+            py_stages: HashMap::new(),
+            #[cfg(feature = "usd")]
+            retained_layers: Vec::new(),
+            #[cfg(feature = "usd-native")]
+            native_stages: HashMap::new(),
         }
     }
     
     /// Create a new USD stage
     pub fn create_stage(&mut self, identifier: &str) -> Result<USDStage, String> {
+        #[cfg(feature = "usd-native")]
+        match NativeStage::create_in_memory(identifier) {
+            Ok(native) => {
+                let stage = USDStage {
+                    path: format!("memory://{}", identifier),
+                    identifier: identifier.to_string(),
+                };
+                self.native_stages.insert(identifier.to_string(), native);
+                self.stages.insert(identifier.to_string(), stage.clone());
+                return Ok(stage);
+            }
+            Err(e) => eprintln!("Native USD backend failed, falling back to Python: {}", e),
+        }
+        
         #[cfg(feature = "usd")]
         {
-            Python::with_gil(|py| -> Result<USDStage, String> {
+            profiling::with_gil("create_stage", |py| -> Result<USDStage, String> {
                 let usd = py.import("pxr.Usd").map_err(|e| format!("Failed to import USD: {}", e))?;
                 
-                // Create an in-memory stage
-                let stage = usd.call_method0("Stage.CreateInMemory")
+                // Create an in-memory stage and keep it, since it has no file to reopen from
+                let stage = usd.getattr("Stage")
+                    .and_then(|stage_class| stage_class.call_method1("CreateInMemory", (format!("{}.usda", identifier),)))
                     .map_err(|e| format!("Failed to create stage: {}", e))?;
                 
                 let stage_obj = USDStage {
@@ -59,6 +1145,7 @@ impl USDEngine {
                     identifier: identifier.to_string(),
                 };
                 
+                self.py_stages.insert(identifier.to_string(), stage.unbind());
                 self.stages.insert(identifier.to_string(), stage_obj.clone());
                 Ok(stage_obj)
             })
@@ -78,28 +1165,46 @@ impl USDEngine {
     
     /// Load a USD stage from file
     pub fn load_stage(&mut self, file_path: &str) -> Result<USDStage, String> {
+        // Reject broken packages with a clearer error than the USD backends give
+        if usdz::is_package(file_path) {
+            usdz::UsdzArchive::open(file_path)
+                .and_then(|archive| archive.default_layer().map(|_| ()))
+                .map_err(|e| format!("Failed to open USDZ package '{}': {}", file_path, e))?;
+        }
+        
+        // Adopt a stage a Load Stage node already opened in the background
+        // A failed background open is retried below, reporting its error as usual
+        if let Some(Ok(opened)) = stage_loader::take_opened(file_path) {
+            match opened {
+                #[cfg(feature = "usd-native")]
+                stage_loader::OpenedStage::Native(native) => return self.adopt_native_stage(file_path, native),
+                #[cfg(feature = "usd")]
+                stage_loader::OpenedStage::Python(stage) => return Ok(self.adopt_python_stage(file_path, stage)),
+            }
+        }
+        
+        #[cfg(feature = "usd-native")]
+        match NativeStage::open(file_path) {
+            Ok(native) => return self.adopt_native_stage(file_path, native),
+            Err(e) => eprintln!("Native USD backend failed, falling back to Python: {}", e),
+        }
+        
         #[cfg(feature = "usd")]
         {
-            Python::with_gil(|py| -> Result<USDStage, String> {
+            profiling::with_gil("load_stage", |py| -> Result<USDStage, String> {
                 let usd = py.import("pxr.Usd").map_err(|e| format!("Failed to import USD: {}", e))?;
                 
-                let stage = usd.call_method1("Stage.Open", (file_path,))
+                let stage = usd.getattr("Stage")
+                    .and_then(|stage_class| stage_class.call_method1("Open", (file_path,)))
                     .map_err(|e| format!("Failed to open stage '{}': {}", file_path, e))?;
                 
-                let identifier = format!("loaded_{}", self.stages.len());
-                let stage_obj = USDStage {
-                    path: file_path.to_string(),
-                    identifier: identifier.clone(),
-                };
-                
-                self.stages.insert(identifier.clone(), stage_obj.clone());
-                Ok(stage_obj)
+                Ok(self.adopt_python_stage(file_path, stage.unbind()))
             })
         }
         
         #[cfg(not(feature = "usd"))]
         {
-            let identifier = format!("loaded_{}", self.stages.len());
+            let identifier = self.next_loaded_identifier();
             let stage = USDStage {
                 path: file_path.to_string(),
                 identifier: identifier.clone(),
@@ -109,29 +1214,144 @@ impl USDEngine {
         }
     }
     
-    /// Save a USD stage to file
-    pub fn save_stage(&self, stage_id: &str, file_path: &str, format: Option<&str>) -> Result<bool, String> {
+    /// Register a natively opened stage and its prims under a new identifier
+    #[cfg(feature = "usd-native")]
+    fn adopt_native_stage(&mut self, file_path: &str, native: NativeStage) -> Result<USDStage, String> {
+        let identifier = self.next_loaded_identifier();
+        let stage = USDStage {
+            path: file_path.to_string(),
+            identifier: identifier.clone(),
+        };
+        for (path, prim_type) in native.list_prims()? {
+            let prim = USDPrim { path, prim_type, stage_id: identifier.clone() };
+            self.prims.insert(format!("{}:{}", identifier, prim.path), prim);
+        }
+        self.native_stages.insert(identifier.clone(), native);
+        self.stages.insert(identifier, stage.clone());
+        Ok(stage)
+    }
+    
+    /// Register a stage opened through Python under a new identifier
+    #[cfg(feature = "usd")]
+    fn adopt_python_stage(&mut self, file_path: &str, stage: Py<PyAny>) -> USDStage {
+        let identifier = self.next_loaded_identifier();
+        let stage_obj = USDStage {
+            path: file_path.to_string(),
+            identifier: identifier.clone(),
+        };
+        self.py_stages.insert(identifier.clone(), stage);
+        self.stages.insert(identifier, stage_obj.clone());
+        stage_obj
+    }
+    
+    /// Save a stage's root layer to file, returning the written path
+    ///
+    /// `format` is "usda", "usdc" or "usdz"; without one it follows the file
+    /// extension. Saving to the stage's own file saves in place.
+    pub fn save_stage(&mut self, stage_id: &str, file_path: &str, format: Option<&str>) -> Result<String, String> {
+        self.write_stage(stage_id, file_path, format, false)
+    }
+    
+    /// Export a stage's composed, flattened result to file, returning the written path
+    pub fn export_stage(&mut self, stage_id: &str, file_path: &str, format: Option<&str>) -> Result<String, String> {
+        self.write_stage(stage_id, file_path, format, true)
+    }
+    
+    fn write_stage(&mut self, stage_id: &str, file_path: &str, format: Option<&str>, flatten: bool) -> Result<String, String> {
+        let stage = self.stages.get(stage_id)
+            .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
+        if !flatten && self.anonymous_layers.get(stage_id).is_some_and(|layers| !layers.is_empty()) {
+            return Err(format!("Stage '{}' has anonymous layers; export it flattened to materialize them", stage_id));
+        }
+        let (path, format, format_arg) = export_target(file_path, format)?;
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create directory '{}': {}", parent.display(), e))?;
+        }
+        let path = path.to_string_lossy().into_owned();
+        let in_place = !flatten && format_arg.is_none() && format != "usdz" && same_file(&path, &stage.path);
+        let session_edits = format!("Stage '{}' has edits in its session layer; export it flattened to include them", stage_id);
+        
+        #[cfg(feature = "usd-native")]
+        let native = match self.native_stages.get_mut(stage_id) {
+            Some(native) => {
+                // The native backend can't tell whether the session layer has content, so
+                // assume edits authored there since the stage was created or opened
+                let edited = stage.path.starts_with("memory://") || self.revisions.get(stage_id).is_some_and(|revision| *revision > 0);
+                if !flatten && edited && self.edit_targets.get(stage_id).cloned().unwrap_or_default() == USDEditTarget::Session {
+                    return Err(session_edits);
+                }
+                if in_place {
+                    native.save()?;
+                } else {
+                    native.export(&path, flatten, format_arg.unwrap_or(format))?;
+                }
+                true
+            }
+            None => false,
+        };
+        #[cfg(not(feature = "usd-native"))]
+        let native = false;
+        
         #[cfg(feature = "usd")]
-        {
-            let _stage = self.stages.get(stage_id)
-                .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
-                
-            Python::with_gil(|py| -> Result<bool, String> {
-                let usd = py.import("pxr.Usd").map_err(|e| format!("Failed to import USD: {}", e))?;
+        if !native {
+            profiling::with_gil("write_stage", |py| -> Result<(), String> {
+                let err = |e: PyErr| format!("Failed to write stage '{}' to '{}': {}", stage_id, path, e);
+                let py_stage = self.open_python_stage(py, stage)?;
+                let layer = if flatten {
+                    py_stage.call_method0("Flatten").map_err(err)?
+                } else {
+                    // The root layer alone would leave out edits authored in the session layer
+                    let session_empty: bool = py_stage.call_method0("GetSessionLayer")
+                        .and_then(|layer| layer.getattr("empty"))
+                        .and_then(|empty| empty.extract())
+                        .map_err(err)?;
+                    if !session_empty {
+                        return Err(session_edits.clone());
+                    }
+                    py_stage.call_method0("GetRootLayer").map_err(err)?
+                };
                 
-                // For now, return success - actual implementation would save the stage
-                println!("Saving USD stage '{}' to '{}' with format {:?}", stage_id, file_path, format);
-                Ok(true)
-            })
+                let written: bool = if in_place {
+                    layer.call_method0("Save").and_then(|result| result.extract()).map_err(err)?
+                } else if format == "usdz" {
+                    // Packages are built from a layer on disk, so stage one in a scratch directory
+                    let staging = std::env::temp_dir().join(format!("nodle_usdz_{}", stage_id));
+                    std::fs::create_dir_all(&staging)
+                        .map_err(|e| format!("Failed to create directory '{}': {}", staging.display(), e))?;
+                    let stem = std::path::Path::new(&path).file_stem().and_then(|stem| stem.to_str()).unwrap_or(stage_id);
+                    let staged = staging.join(format!("{}.usdc", stem)).to_string_lossy().into_owned();
+                    let exported: bool = layer.call_method1("Export", (staged.as_str(),))
+                        .and_then(|result| result.extract())
+                        .map_err(err)?;
+                    exported && py.import("pxr.UsdUtils")
+                        .and_then(|usd_utils| usd_utils.call_method1("CreateNewUsdzPackage", (staged.as_str(), path.as_str())))
+                        .and_then(|result| result.extract())
+                        .map_err(err)?
+                } else {
+                    let args = PyDict::new(py);
+                    if let Some(format) = format_arg {
+                        args.set_item("format", format).map_err(err)?;
+                    }
+                    layer.call_method1("Export", (path.as_str(), "", args))
+                        .and_then(|result| result.extract())
+                        .map_err(err)?
+                };
+                if !written {
+                    return Err(format!("Failed to write stage '{}' to '{}'", stage_id, path));
+                }
+                Ok(())
+            })?;
+            println!("Wrote USD stage '{}' to '{}' as {}", stage_id, path, format);
         }
         
         #[cfg(not(feature = "usd"))]
-        {
-            let _stage = self.stages.get(stage_id)
-                .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
-            println!("Mock: Saving USD stage '{}' to '{}' with format {:?}", stage_id, file_path, format);
-            Ok(true)
+        if !native {
+            let _ = (stage, in_place, session_edits);
+            println!("Mock: Wrote USD stage '{}' to '{}' as {}", stage_id, path, format);
         }
+        
+        Ok(path)
     }
     
     /// Create a USD Xform primitive
@@ -141,7 +1361,7 @@ impl USDEngine {
             let _stage = self.stages.get(stage_id)
                 .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
                 
-            Python::with_gil(|py| -> Result<USDPrim, String> {
+            profiling::with_gil("create_xform", |py| -> Result<USDPrim, String> {
                 let usd_geom = py.import("pxr.UsdGeom").map_err(|e| format!("Failed to import UsdGeom: {}", e))?;
                 
                 // For now, create a mock prim - actual implementation would create on the stage
@@ -185,7 +1405,7 @@ impl USDEngine {
             let _stage = self.stages.get(stage_id)
                 .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
                 
-            Python::with_gil(|py| -> Result<USDPrim, String> {
+            profiling::with_gil("create_sphere", |py| -> Result<USDPrim, String> {
                 let _usd_geom = py.import("pxr.UsdGeom").map_err(|e| format!("Failed to import UsdGeom: {}", e))?;
                 
                 let prim = USDPrim {
@@ -228,7 +1448,7 @@ impl USDEngine {
             let _stage = self.stages.get(stage_id)
                 .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
                 
-            Python::with_gil(|py| -> Result<USDPrim, String> {
+            profiling::with_gil("create_cube", |py| -> Result<USDPrim, String> {
                 let _usd_geom = py.import("pxr.UsdGeom").map_err(|e| format!("Failed to import UsdGeom: {}", e))?;
                 
                 let prim = USDPrim {
@@ -264,54 +1484,829 @@ impl USDEngine {
         }
     }
     
-    /// Set an attribute on a USD prim
-    pub fn set_attribute(&self, stage_id: &str, prim_path: &str, attr_name: &str, value: &str) -> Result<(), String> {
+    /// Set an attribute on a USD prim, authored in the stage's edit target
+    ///
+    /// Existing attributes keep their value type; new ones are created with the
+    /// value's type. Strings are inferred with `UsdValue::infer`, and
+    /// `UsdValue::TimeSamples` are added to the attribute's samples.
+    pub fn set_attribute(&mut self, stage_id: &str, prim_path: &str, attr_name: &str, value: impl Into<UsdValue>) -> Result<(), String> {
+        let value = value.into();
+        let stage = self.stages.get(stage_id)
+            .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
+        let target = self.get_edit_target(stage_id);
+        
+        #[cfg(feature = "usd-native")]
+        let native = match self.native_stages.get_mut(stage_id) {
+            Some(native) => {
+                native.set_attribute(prim_path, attr_name, &value.to_string())?;
+                true
+            }
+            None => false,
+        };
+        #[cfg(not(feature = "usd-native"))]
+        let native = false;
+        
         #[cfg(feature = "usd")]
-        {
-            let _stage = self.stages.get(stage_id)
-                .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
-                
-            Python::with_gil(|py| -> Result<(), String> {
-                println!("Setting attribute '{}' on '{}:{}' to '{}'", attr_name, stage_id, prim_path, value);
+        if !native {
+            profiling::with_gil("set_attribute", |py| -> Result<(), String> {
+                let helpers = Self::bulk_edit_helpers(py)?;
+                let py_stage = self.open_python_stage(py, stage)?;
+                let err = |e: PyErr| format!("Failed to set '{}.{}': {}", prim_path, attr_name, e);
+                let edit = (prim_path, attr_name, value.type_name(), value.to_python(py).map_err(err)?);
+                helpers.call_method1("set_attributes", (py_stage, vec![edit]))
+                    .map_err(err)?;
                 Ok(())
-            })
+            })?;
+            println!("Setting attribute '{}' on '{}:{}' to '{}' in the {} layer", attr_name, stage_id, prim_path, value, target.name());
         }
         
         #[cfg(not(feature = "usd"))]
-        {
-            let _stage = self.stages.get(stage_id)
-                .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
-            println!("Mock: Setting attribute '{}' on '{}:{}' to '{}'", attr_name, stage_id, prim_path, value);
-            Ok(())
+        if !native {
+            let _ = stage;
+            println!("Mock: Setting attribute '{}' on '{}:{}' to '{}' in the {} layer", attr_name, stage_id, prim_path, value, target.name());
         }
+        
+        self.store_value(format!("{}:{}.{}", stage_id, prim_path, attr_name), value);
+        self.mark_stage_dirty(stage_id);
+        Ok(())
     }
     
-    /// Get an attribute from a USD prim
+    /// Record an authored value, merging time samples into the attribute's samples
+    fn store_value(&mut self, key: String, value: UsdValue) {
+        let UsdValue::TimeSamples(new_samples) = value else {
+            self.attributes.insert(key, value.to_string());
+            return;
+        };
+        let samples = self.time_samples.entry(key).or_default();
+        for (time, value) in new_samples {
+            match samples.binary_search_by(|(sample_time, _)| sample_time.total_cmp(&time)) {
+                Ok(index) => samples[index].1 = value.to_string(),
+                Err(index) => samples.insert(index, (time, value.to_string())),
+            }
+        }
+    }
+    
+    /// Define many prims with a single Python round-trip
+    pub fn create_prims_bulk(&mut self, stage_id: &str, prims: &[USDPrimSpec]) -> Result<Vec<USDPrim>, String> {
+        let stage = self.stages.get(stage_id)
+            .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
+        
+        #[cfg(feature = "usd-native")]
+        let native = match self.native_stages.get_mut(stage_id) {
+            Some(native) => {
+                for prim in prims {
+                    native.define_prim(&prim.path, &prim.prim_type)?;
+                }
+                true
+            }
+            None => false,
+        };
+        #[cfg(not(feature = "usd-native"))]
+        let native = false;
+        
+        #[cfg(feature = "usd")]
+        if !native {
+            profiling::with_gil("create_prims_bulk", |py| -> Result<(), String> {
+                let helpers = Self::bulk_edit_helpers(py)?;
+                let py_stage = self.open_python_stage(py, stage)?;
+                let payload: Vec<(&str, &str)> = prims.iter()
+                    .map(|prim| (prim.path.as_str(), prim.prim_type.as_str()))
+                    .collect();
+                helpers.call_method1("define_prims", (py_stage, payload))
+                    .map_err(|e| format!("Failed to create {} prims: {}", prims.len(), e))?;
+                Ok(())
+            })?;
+            println!("Created {} prims on '{}' in one batch", prims.len(), stage_id);
+        }
+        
+        #[cfg(not(feature = "usd"))]
+        if !native {
+            let _ = stage;
+            println!("Mock: Created {} prims on '{}' in one batch", prims.len(), stage_id);
+        }
+        
+        let created: Vec<USDPrim> = prims.iter()
+            .map(|spec| USDPrim {
+                path: spec.path.clone(),
+                prim_type: spec.prim_type.clone(),
+                stage_id: stage_id.to_string(),
+            })
+            .collect();
+        for prim in &created {
+            self.prims.insert(format!("{}:{}", stage_id, prim.path), prim.clone());
+        }
+        Ok(created)
+    }
+    
+    /// Author many attribute values with a single Python round-trip
+    pub fn set_attributes_bulk(&mut self, stage_id: &str, edits: &[USDAttributeEdit]) -> Result<(), String> {
+        let stage = self.stages.get(stage_id)
+            .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
+        
+        #[cfg(feature = "usd-native")]
+        let native = match self.native_stages.get_mut(stage_id) {
+            Some(native) => {
+                for edit in edits {
+                    native.set_attribute(&edit.prim_path, &edit.attr_name, &edit.value.to_string())?;
+                }
+                true
+            }
+            None => false,
+        };
+        #[cfg(not(feature = "usd-native"))]
+        let native = false;
+        
+        #[cfg(feature = "usd")]
+        if !native {
+            profiling::with_gil("set_attributes_bulk", |py| -> Result<(), String> {
+                let helpers = Self::bulk_edit_helpers(py)?;
+                let py_stage = self.open_python_stage(py, stage)?;
+                let err = |e: PyErr| format!("Failed to set {} attributes: {}", edits.len(), e);
+                let payload = edits.iter()
+                    .map(|edit| Ok((edit.prim_path.as_str(), edit.attr_name.as_str(), edit.value.type_name(), edit.value.to_python(py)?)))
+                    .collect::<PyResult<Vec<_>>>()
+                    .map_err(err)?;
+                helpers.call_method1("set_attributes", (py_stage, payload))
+                    .map_err(err)?;
+                Ok(())
+            })?;
+            println!("Set {} attributes on '{}' in one batch", edits.len(), stage_id);
+        }
+        
+        #[cfg(not(feature = "usd"))]
+        if !native {
+            let _ = stage;
+            println!("Mock: Set {} attributes on '{}' in one batch", edits.len(), stage_id);
+        }
+        
+        for edit in edits {
+            self.store_value(format!("{}:{}.{}", stage_id, edit.prim_path, edit.attr_name), edit.value.clone());
+        }
+        Ok(())
+    }
+    
+    /// Load the bulk edit helper module
+    #[cfg(feature = "usd")]
+    fn bulk_edit_helpers(py: Python<'_>) -> Result<Bound<'_, PyModule>, String> {
+        PyModule::from_code(py, BULK_EDIT_HELPERS, c"nodle_bulk_edit.py", c"nodle_bulk_edit")
+            .map_err(|e| format!("Failed to load bulk edit helpers: {}", e))
+    }
+    
+    /// Get an attribute from a USD prim
     pub fn get_attribute(&self, stage_id: &str, prim_path: &str, attr_name: &str) -> Result<String, String> {
+        let stage = self.stages.get(stage_id)
+            .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
+        
+        // Values authored through the engine take precedence
+        if let Some(value) = self.attributes.get(&format!("{}:{}.{}", stage_id, prim_path, attr_name)) {
+            return Ok(value.clone());
+        }
+        
+        #[cfg(feature = "usd-native")]
+        if let Some(native) = self.native_stages.get(stage_id) {
+            return native.get_attribute(prim_path, attr_name, None);
+        }
+        
         #[cfg(feature = "usd")]
         {
-            let _stage = self.stages.get(stage_id)
-                .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
-                
-            Python::with_gil(|py| -> Result<String, String> {
-                // Mock return value for now
-                Ok(format!("mock_value_for_{}", attr_name))
+            profiling::with_gil("get_attribute", |py| -> Result<String, String> {
+                let value = self.open_python_stage(py, stage)?
+                    .call_method1("GetAttributeAtPath", (format!("{}.{}", prim_path, attr_name),))
+                    .and_then(|attr| attr.call_method0("Get"))
+                    .map_err(|e| format!("Failed to read '{}.{}': {}", prim_path, attr_name, e))?;
+                if value.is_none() {
+                    return Err(format!("Attribute '{}.{}' has no value", prim_path, attr_name));
+                }
+                value.str()
+                    .and_then(|text| text.extract())
+                    .map_err(|e| format!("Failed to read '{}.{}': {}", prim_path, attr_name, e))
             })
         }
         
         #[cfg(not(feature = "usd"))]
         {
-            let _stage = self.stages.get(stage_id)
-                .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
+            let _ = stage;
             Ok(format!("mock_value_for_{}", attr_name))
         }
     }
     
+    /// Get the playback range of a stage
+    pub fn get_stage_time_range(&self, stage_id: &str) -> Result<USDTimeRange, String> {
+        let stage = self.stages.get(stage_id)
+            .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
+        
+        if let Some(range) = self.time_ranges.get(stage_id) {
+            return Ok(*range);
+        }
+        
+        #[cfg(feature = "usd")]
+        {
+            profiling::with_gil("get_stage_time_range", |py| -> Result<USDTimeRange, String> {
+                let py_stage = self.open_python_stage(py, stage)?;
+                let read = |method: &str| py_stage.call_method0(method)
+                    .and_then(|value| value.extract::<f64>())
+                    .map_err(|e| format!("Failed to read {} of '{}': {}", method, stage.path, e));
+                Ok(USDTimeRange {
+                    start_time_code: read("GetStartTimeCode")?,
+                    end_time_code: read("GetEndTimeCode")?,
+                    time_codes_per_second: read("GetTimeCodesPerSecond")?,
+                })
+            })
+        }
+        
+        #[cfg(not(feature = "usd"))]
+        {
+            let _ = stage;
+            Ok(USDTimeRange::default())
+        }
+    }
+    
+    /// Author the playback range of a stage
+    pub fn set_stage_time_range(&mut self, stage_id: &str, range: USDTimeRange) -> Result<(), String> {
+        let _stage = self.stages.get(stage_id)
+            .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
+        
+        #[cfg(feature = "usd")]
+        println!("Setting time range of '{}' to {}-{} @ {} fps",
+                 stage_id, range.start_time_code, range.end_time_code, range.time_codes_per_second);
+        
+        #[cfg(not(feature = "usd"))]
+        println!("Mock: Setting time range of '{}' to {}-{} @ {} fps",
+                 stage_id, range.start_time_code, range.end_time_code, range.time_codes_per_second);
+        
+        self.time_ranges.insert(stage_id.to_string(), range);
+        Ok(())
+    }
+    
+    /// Author a time sample on a USD prim attribute, keeping its other samples
+    pub fn set_attribute_at_time(&mut self, stage_id: &str, prim_path: &str, attr_name: &str, value: impl Into<UsdValue>, time: f64) -> Result<(), String> {
+        self.set_attribute(stage_id, prim_path, attr_name, UsdValue::TimeSamples(vec![(time, value.into())]))
+    }
+    
+    /// Replace an attribute's value in the edit target with keyframes
+    ///
+    /// The default value and previous samples are cleared, then the keys are
+    /// authored as time samples with `interpolation` baked in. No keys leaves
+    /// the attribute cleared.
+    pub fn set_keyframes(&mut self, stage_id: &str, prim_path: &str, attr_name: &str, keys: &[(f64, UsdValue)], interpolation: KeyInterpolation) -> Result<(), String> {
+        let stage = self.stages.get(stage_id)
+            .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
+        
+        #[cfg(feature = "usd")]
+        {
+            profiling::with_gil("set_keyframes", |py| -> Result<(), String> {
+                let helpers = Self::bulk_edit_helpers(py)?;
+                let py_stage = self.open_python_stage(py, stage)?;
+                helpers.call_method1("clear_attributes", (py_stage, vec![format!("{}.{}", prim_path, attr_name)]))
+                    .map_err(|e| format!("Failed to clear '{}.{}': {}", prim_path, attr_name, e))?;
+                Ok(())
+            })?;
+        }
+        
+        #[cfg(not(feature = "usd"))]
+        let _ = stage;
+        
+        let key = format!("{}:{}.{}", stage_id, prim_path, attr_name);
+        self.attributes.remove(&key);
+        self.time_samples.remove(&key);
+        if keys.is_empty() {
+            self.mark_stage_dirty(stage_id);
+            return Ok(());
+        }
+        self.set_attribute(stage_id, prim_path, attr_name, UsdValue::TimeSamples(bake_keyframes(keys, interpolation)))
+    }
+    
+    /// Add a transform op to a prim's xformOpOrder, keeping translate, rotate, scale order
+    pub fn add_xform_op(&mut self, stage_id: &str, prim_path: &str, op_name: &str) -> Result<(), String> {
+        let stage = self.stages.get(stage_id)
+            .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
+        if !XFORM_OP_ORDER.contains(&op_name) {
+            return Err(format!("Unsupported transform op '{}'", op_name));
+        }
+        
+        #[cfg(feature = "usd")]
+        let added = profiling::with_gil("add_xform_op", |py| -> Result<bool, String> {
+            let helpers = Self::bulk_edit_helpers(py)?;
+            let py_stage = self.open_python_stage(py, stage)?;
+            helpers.call_method1("add_xform_op", (py_stage, prim_path, op_name, XFORM_OP_ORDER.to_vec()))
+                .and_then(|added| added.extract())
+                .map_err(|e| format!("Failed to add '{}' to '{}': {}", op_name, prim_path, e))
+        })?;
+        
+        #[cfg(not(feature = "usd"))]
+        let added = {
+            let _ = stage;
+            let key = format!("{}:{}.xformOpOrder", stage_id, prim_path);
+            let mut ops: Vec<String> = match self.attributes.get(&key).map(|order| UsdValue::parse(order, "token[]")) {
+                Some(Ok(UsdValue::Array(items))) => items.iter().map(UsdValue::to_string).collect(),
+                _ => Vec::new(),
+            };
+            let added = !ops.iter().any(|op| op == op_name);
+            if added {
+                let rank = |op: &str| XFORM_OP_ORDER.iter().position(|canonical| *canonical == op);
+                let index = ops.iter()
+                    .position(|op| rank(op) > rank(op_name))
+                    .unwrap_or(ops.len());
+                ops.insert(index, op_name.to_string());
+                let order = UsdValue::Array(ops.into_iter().map(UsdValue::Token).collect());
+                self.attributes.insert(key, order.to_string());
+            }
+            added
+        };
+        
+        if added {
+            self.mark_stage_dirty(stage_id);
+        }
+        Ok(())
+    }
+    
+    /// Evaluate an attribute at a time code, interpolating numeric time samples linearly
+    pub fn evaluate_at_time(&self, stage_id: &str, prim_path: &str, attr_name: &str, time: f64) -> Result<String, String> {
+        let stage = self.stages.get(stage_id)
+            .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
+        
+        if let Some(samples) = self.time_samples.get(&format!("{}:{}.{}", stage_id, prim_path, attr_name)) {
+            if let Some(value) = interpolate_samples(samples, time) {
+                return Ok(value);
+            }
+        }
+        
+        #[cfg(feature = "usd")]
+        {
+            let value = profiling::with_gil("evaluate_at_time", |py| -> Result<Option<String>, String> {
+                let usd = py.import("pxr.Usd").map_err(|e| format!("Failed to import USD: {}", e))?;
+                let py_stage = self.open_python_stage(py, stage)?;
+                let prim = py_stage.call_method1("GetPrimAtPath", (prim_path,))
+                    .map_err(|e| format!("Failed to get prim '{}': {}", prim_path, e))?;
+                let attr = prim.call_method1("GetAttribute", (attr_name,))
+                    .map_err(|e| format!("Failed to get attribute '{}': {}", attr_name, e))?;
+                let time_code = usd.getattr("TimeCode").and_then(|t| t.call1((time,)))
+                    .map_err(|e| format!("Failed to create time code: {}", e))?;
+                let value = attr.call_method1("Get", (time_code,))
+                    .map_err(|e| format!("Failed to evaluate '{}.{}' at {}: {}", prim_path, attr_name, time, e))?;
+                if value.is_none() {
+                    return Ok(None);
+                }
+                Ok(Some(value.str().map_err(|e| format!("Failed to convert value: {}", e))?.to_string()))
+            })?;
+            if let Some(value) = value {
+                return Ok(value);
+            }
+        }
+        
+        let _ = stage;
+        self.get_attribute(stage_id, prim_path, attr_name)
+    }
+    
+    /// Whether an attribute has authored time samples
+    pub fn is_animated(&self, stage_id: &str, prim_path: &str, attr_name: &str) -> bool {
+        self.time_samples
+            .get(&format!("{}:{}.{}", stage_id, prim_path, attr_name))
+            .is_some_and(|samples| samples.len() > 1)
+    }
+    
+    /// Python-side stage for a stage handle, with the stage's edit target set
+    ///
+    /// Retained stages are returned as they are; stages the engine doesn't hold
+    /// (e.g. ones owned by the native backend) are reopened from their path with
+    /// the engine's session edits replayed.
+    #[cfg(feature = "usd")]
+    pub(crate) fn open_python_stage<'py>(&self, py: Python<'py>, stage: &USDStage) -> Result<Bound<'py, PyAny>, String> {
+        let py_stage = match self.py_stages.get(&stage.identifier) {
+            Some(retained) => retained.bind(py).clone(),
+            None => {
+                let py_stage = py.import("pxr.Usd")
+                    .and_then(|usd| usd.getattr("Stage"))
+                    .and_then(|stage_class| stage_class.call_method1("Open", (stage.path.as_str(),)))
+                    .map_err(|e| format!("Failed to open stage '{}': {}", stage.path, e))?;
+                self.apply_session_edits(py, &py_stage, stage)?;
+                py_stage
+            }
+        };
+        
+        // Later edits through this stage go to the stage's edit target
+        let layer = Self::edit_target_layer(py, &py_stage, &self.get_edit_target(&stage.identifier))?;
+        py_stage.call_method1("SetEditTarget", (layer,))
+            .map_err(|e| format!("Failed to set the edit target of '{}': {}", stage.path, e))?;
+        Ok(py_stage)
+    }
+    
+    /// Bring a Python stage's sublayers, muted layers and variant selections in line with the engine
+    #[cfg(feature = "usd")]
+    fn apply_session_edits<'py>(&self, py: Python<'py>, py_stage: &Bound<'py, PyAny>, stage: &USDStage) -> Result<(), String> {
+        let err = |e: PyErr| format!("Failed to apply session edits to '{}': {}", stage.path, e);
+        if let Some(sublayers) = self.sublayers.get(&stage.identifier) {
+            let sdf = py.import("pxr.Sdf").map_err(|e| format!("Failed to import Sdf: {}", e))?;
+            let root = py_stage.call_method0("GetRootLayer").map_err(err)?;
+            let paths: Vec<&str> = sublayers.iter().map(|(path, _)| path.as_str()).collect();
+            root.setattr("subLayerPaths", paths).map_err(err)?;
+            let offsets = sublayers.iter()
+                .map(|(_, offset)| sdf.getattr("LayerOffset").and_then(|class| class.call1((*offset, 1.0))))
+                .collect::<PyResult<Vec<_>>>()
+                .map_err(err)?;
+            root.setattr("subLayerOffsets", offsets).map_err(err)?;
+        }
+        
+        // A retained stage may still have layers muted that the engine has since unmuted
+        let muted = self.muted_layers.get(&stage.identifier).cloned().unwrap_or_default();
+        let unmuted: Vec<String> = py_stage.call_method0("GetMutedLayers")
+            .and_then(|layers| layers.extract::<Vec<String>>())
+            .map_err(err)?
+            .into_iter()
+            .filter(|layer| !muted.contains(layer))
+            .collect();
+        if !muted.is_empty() || !unmuted.is_empty() {
+            py_stage.call_method1("MuteAndUnmuteLayers", (muted, unmuted)).map_err(err)?;
+        }
+        
+        let prefix = format!("{}:", stage.identifier);
+        for (key, (target, variant)) in &self.variant_selections {
+            let Some((prim_path, set)) = key.strip_prefix(&prefix)
+                .and_then(|rest| rest.strip_suffix('}'))
+                .and_then(|rest| rest.split_once('{')) else {
+                continue;
+            };
+            let layer = Self::edit_target_layer(py, py_stage, target)?;
+            py_stage.call_method1("SetEditTarget", (layer,)).map_err(err)?;
+            py_stage.call_method1("GetPrimAtPath", (prim_path,))
+                .and_then(|prim| prim.call_method0("GetVariantSets"))
+                .and_then(|sets| sets.call_method1("GetVariantSet", (set,)))
+                .and_then(|variant_set| variant_set.call_method1("SetVariantSelection", (variant.as_str(),)))
+                .map_err(err)?;
+        }
+        Ok(())
+    }
+    
+    /// Push the engine's layer stack state to a retained Python stage after it changes
+    fn sync_python_stage(&self, stage_id: &str) -> Result<(), String> {
+        #[cfg(feature = "usd")]
+        if let (Some(retained), Some(stage)) = (self.py_stages.get(stage_id), self.stages.get(stage_id)) {
+            profiling::with_gil("sync_python_stage", |py| {
+                self.apply_session_edits(py, retained.bind(py), stage)
+            })?;
+        }
+        
+        #[cfg(not(feature = "usd"))]
+        let _ = stage_id;
+        Ok(())
+    }
+    
+    /// Resolve an edit target to its layer on an open Python stage
+    #[cfg(feature = "usd")]
+    fn edit_target_layer<'py>(py: Python<'py>, py_stage: &Bound<'py, PyAny>, target: &USDEditTarget) -> Result<Bound<'py, PyAny>, String> {
+        let layer = match target {
+            USDEditTarget::Session => py_stage.call_method0("GetSessionLayer"),
+            USDEditTarget::Root => py_stage.call_method0("GetRootLayer"),
+            USDEditTarget::Layer(identifier) => py.import("pxr.Sdf")
+                .and_then(|sdf| sdf.getattr("Layer"))
+                .and_then(|layer| layer.call_method1("FindOrOpen", (identifier.as_str(),))),
+        }.map_err(|e| format!("Failed to find edit target layer '{}': {}", target.name(), e))?;
+        if layer.is_none() {
+            return Err(format!("Edit target layer '{}' not found", target.name()));
+        }
+        Ok(layer)
+    }
+    
+    /// Layer that edits of a stage are authored into
+    pub fn get_edit_target(&self, stage_id: &str) -> USDEditTarget {
+        self.edit_targets.get(stage_id).cloned().unwrap_or_default()
+    }
+    
+    /// Author later edits of a stage into "session", "root" or a layer stack identifier
+    pub fn set_edit_target(&mut self, stage_id: &str, layer: &str) -> Result<(), String> {
+        let target = USDEditTarget::from_name(layer);
+        if let USDEditTarget::Layer(identifier) = &target {
+            let in_stack = self.get_layer_stack(stage_id)?
+                .iter()
+                .any(|layer| layer.identifier == *identifier);
+            if !in_stack {
+                return Err(format!("Layer '{}' is not in the layer stack of '{}'", identifier, stage_id));
+            }
+        } else if !self.stages.contains_key(stage_id) {
+            return Err(format!("Stage '{}' not found", stage_id));
+        }
+        
+        #[cfg(feature = "usd-native")]
+        if let Some(native) = self.native_stages.get_mut(stage_id) {
+            native.set_edit_target(target.name())?;
+        }
+        
+        #[cfg(feature = "usd")]
+        println!("Edit target of '{}' set to {}", stage_id, target.name());
+        #[cfg(not(feature = "usd"))]
+        println!("Mock: Edit target of '{}' set to {}", stage_id, target.name());
+        
+        self.edit_targets.insert(stage_id.to_string(), target);
+        Ok(())
+    }
+    
+    /// Revision counter of a stage (identifier or file path), bumped on composition edits
+    pub fn stage_revision(&self, stage_ref: &str) -> u64 {
+        let identifier = self.stages.get(stage_ref)
+            .or_else(|| self.stages.values().find(|stage| stage.path == stage_ref))
+            .map(|stage| stage.identifier.as_str())
+            .unwrap_or(stage_ref);
+        self.revisions.get(identifier).copied().unwrap_or(0)
+    }
+    
+    /// Mark a stage as edited so viewers rebuild their scene
+    fn mark_stage_dirty(&mut self, stage_id: &str) {
+        *self.revisions.entry(stage_id.to_string()).or_default() += 1;
+    }
+    
+    /// List the variant sets on a prim with their variants and current selections
+    pub fn get_variant_sets(&self, stage_id: &str, prim_path: &str) -> Result<Vec<USDVariantSet>, String> {
+        let stage = self.stages.get(stage_id)
+            .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
+        
+        #[cfg(feature = "usd")]
+        {
+            profiling::with_gil("get_variant_sets", |py| -> Result<Vec<USDVariantSet>, String> {
+                let err = |e: PyErr| format!("Failed to read variant sets of '{}': {}", prim_path, e);
+                let py_stage = self.open_python_stage(py, stage)?;
+                let prim = py_stage.call_method1("GetPrimAtPath", (prim_path,)).map_err(err)?;
+                if !prim.call_method0("IsValid").and_then(|valid| valid.extract::<bool>()).map_err(err)? {
+                    return Err(format!("Prim '{}' not found in '{}'", prim_path, stage.path));
+                }
+                
+                let variant_sets = prim.call_method0("GetVariantSets").map_err(err)?;
+                let names: Vec<String> = variant_sets.call_method0("GetNames").and_then(|names| names.extract()).map_err(err)?;
+                names.into_iter()
+                    .map(|name| {
+                        let variant_set = variant_sets.call_method1("GetVariantSet", (name.as_str(),)).map_err(err)?;
+                        Ok(USDVariantSet {
+                            variants: variant_set.call_method0("GetVariantNames").and_then(|v| v.extract()).map_err(err)?,
+                            selection: variant_set.call_method0("GetVariantSelection").and_then(|v| v.extract()).map_err(err)?,
+                            name,
+                        })
+                    })
+                    .collect()
+            })
+        }
+        
+        #[cfg(not(feature = "usd"))]
+        {
+            let _ = stage;
+            let prefix = format!("{}:{}{{", stage_id, prim_path);
+            let mut sets: Vec<USDVariantSet> = self.variant_selections.iter()
+                .filter_map(|(key, (_, variant))| {
+                    let set = key.strip_prefix(&prefix)?.strip_suffix('}')?;
+                    Some(USDVariantSet {
+                        name: set.to_string(),
+                        variants: vec![variant.clone()],
+                        selection: variant.clone(),
+                    })
+                })
+                .collect();
+            sets.sort_by(|a, b| a.name.cmp(&b.name));
+            Ok(sets)
+        }
+    }
+    
+    /// Select a variant in the stage's edit target, the session layer unless changed
+    pub fn set_variant_selection(&mut self, stage_id: &str, prim_path: &str, set: &str, variant: &str) -> Result<(), String> {
+        let stage = self.stages.get(stage_id)
+            .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
+        
+        #[cfg(feature = "usd")]
+        {
+            profiling::with_gil("set_variant_selection", |py| -> Result<(), String> {
+                let err = |e: PyErr| format!("Failed to select variant '{}' of '{}': {}", set, prim_path, e);
+                let py_stage = self.open_python_stage(py, stage)?;
+                let has_set: bool = py_stage.call_method1("GetPrimAtPath", (prim_path,))
+                    .and_then(|prim| prim.call_method0("GetVariantSets"))
+                    .and_then(|sets| sets.call_method1("HasVariantSet", (set,)))
+                    .and_then(|has| has.extract())
+                    .map_err(err)?;
+                if !has_set {
+                    return Err(format!("Prim '{}' has no variant set '{}'", prim_path, set));
+                }
+                py_stage.call_method1("GetPrimAtPath", (prim_path,))
+                    .and_then(|prim| prim.call_method0("GetVariantSets"))
+                    .and_then(|sets| sets.call_method1("GetVariantSet", (set,)))
+                    .and_then(|variant_set| variant_set.call_method1("SetVariantSelection", (variant,)))
+                    .map_err(err)?;
+                Ok(())
+            })?;
+            println!("Selected variant {}={} on '{}' in the {} layer", set, variant, prim_path, self.get_edit_target(stage_id).name());
+        }
+        
+        #[cfg(not(feature = "usd"))]
+        {
+            let _ = stage;
+            println!("Mock: Selected variant {}={} on '{}' in the {} layer", set, variant, prim_path, self.get_edit_target(stage_id).name());
+        }
+        
+        let target = self.get_edit_target(stage_id);
+        self.variant_selections.insert(format!("{}:{}{{{}}}", stage_id, prim_path, set), (target, variant.to_string()));
+        self.mark_stage_dirty(stage_id);
+        Ok(())
+    }
+    
     /// Get list of all stages
     pub fn list_stages(&self) -> Vec<String> {
         self.stages.keys().cloned().collect()
     }
     
+    /// First free "loaded_N" identifier, counting up from the number of stages
+    fn next_loaded_identifier(&self) -> String {
+        (self.stages.len()..)
+            .map(|index| format!("loaded_{}", index))
+            .find(|identifier| !self.stages.contains_key(identifier))
+            .unwrap()
+    }
+    
+    /// Snapshot every stage, ordered by identifier
+    pub fn snapshot_stages(&self) -> Vec<USDStageSnapshot> {
+        let mut stages: Vec<&USDStage> = self.stages.values().collect();
+        stages.sort_by(|a, b| a.identifier.cmp(&b.identifier));
+        stages.into_iter().map(|stage| self.snapshot_stage(stage)).collect()
+    }
+    
+    fn snapshot_stage(&self, stage: &USDStage) -> USDStageSnapshot {
+        let id = stage.identifier.as_str();
+        let prefix = format!("{}:", id);
+        let owned = |key: &String| key.strip_prefix(&prefix).map(str::to_string);
+        
+        let (root_layer, session_layer) = self.export_stage_layers(stage).unwrap_or_else(|e| {
+            eprintln!("⚠ {}", e);
+            (None, None)
+        });
+        
+        let mut variant_selections: Vec<(String, String, String)> = self.variant_selections.iter()
+            .filter_map(|(key, (target, variant))| Some((owned(key)?, target.name().to_string(), variant.clone())))
+            .collect();
+        let mut prims: Vec<(String, String)> = self.prims.iter()
+            .filter_map(|(key, prim)| owned(key).map(|_| (prim.path.clone(), prim.prim_type.clone())))
+            .collect();
+        let mut attributes: Vec<(String, String)> = self.attributes.iter()
+            .filter_map(|(key, value)| Some((owned(key)?, value.clone())))
+            .collect();
+        let mut time_samples: Vec<(String, Vec<(f64, String)>)> = self.time_samples.iter()
+            .filter_map(|(key, samples)| Some((owned(key)?, samples.clone())))
+            .collect();
+        variant_selections.sort();
+        prims.sort();
+        attributes.sort();
+        time_samples.sort_by(|a, b| a.0.cmp(&b.0));
+        
+        USDStageSnapshot {
+            identifier: id.to_string(),
+            path: stage.path.clone(),
+            root_layer,
+            session_layer,
+            edit_target: self.get_edit_target(id),
+            sublayers: self.sublayers.get(id).cloned(),
+            muted_layers: self.muted_layers.get(id).cloned().unwrap_or_default(),
+            pinned_prims: self.pinned_prims.get(id).cloned(),
+            time_range: self.time_ranges.get(id).copied(),
+            variant_selections,
+            prims,
+            attributes,
+            time_samples,
+        }
+    }
+    
+    /// Root layer text when it can't be reopened from its file, and the session layer text
+    fn export_stage_layers(&self, stage: &USDStage) -> Result<(Option<String>, Option<String>), String> {
+        #[cfg(feature = "usd-native")]
+        if let Some(native) = self.native_stages.get(&stage.identifier) {
+            let err = |e: String| format!("Failed to export layers of '{}': {}", stage.path, e);
+            let unsaved = stage.path.starts_with("memory://") || native.layer_dirty("root").map_err(err)?;
+            let root_layer = match unsaved {
+                true => Some(native.export_layer("root").map_err(err)?),
+                false => None,
+            };
+            return Ok((root_layer, Some(native.export_layer("session").map_err(err)?)));
+        }
+        
+        #[cfg(feature = "usd")]
+        {
+            let Some(retained) = self.py_stages.get(&stage.identifier) else {
+                return Ok((None, None));
+            };
+            profiling::with_gil("export_stage_layers", |py| {
+                let err = |e: PyErr| format!("Failed to export layers of '{}': {}", stage.path, e);
+                let py_stage = retained.bind(py);
+                let root = py_stage.call_method0("GetRootLayer").map_err(err)?;
+                let unsaved = stage.path.starts_with("memory://")
+                    || root.getattr("dirty").and_then(|dirty| dirty.extract::<bool>()).map_err(err)?;
+                let root_layer = match unsaved {
+                    true => Some(root.call_method0("ExportToString").and_then(|text| text.extract::<String>()).map_err(err)?),
+                    false => None,
+                };
+                let session_layer = py_stage.call_method0("GetSessionLayer")
+                    .and_then(|layer| layer.call_method0("ExportToString"))
+                    .and_then(|text| text.extract::<String>())
+                    .map_err(err)?;
+                Ok((root_layer, Some(session_layer)))
+            })
+        }
+        
+        #[cfg(not(feature = "usd"))]
+        {
+            let _ = stage;
+            Ok((None, None))
+        }
+    }
+    
+    /// Reopen a snapshotted stage under its identifier and restore its engine state
+    pub fn restore_stage(&mut self, snapshot: &USDStageSnapshot) -> Result<USDStage, String> {
+        let id = snapshot.identifier.clone();
+        if self.stages.contains_key(&id) {
+            return Err(format!("Stage '{}' already exists", id));
+        }
+        let in_memory = snapshot.path.starts_with("memory://") || snapshot.path.starts_with("mock://");
+        
+        #[cfg(feature = "usd-native")]
+        let native = match if in_memory { NativeStage::create_in_memory(&id) } else { NativeStage::open(&snapshot.path) } {
+            Ok(mut native) => {
+                let err = |e: String| format!("Failed to restore stage '{}': {}", snapshot.path, e);
+                if let Some(text) = &snapshot.root_layer {
+                    native.import_layer("root", text).map_err(err)?;
+                }
+                if let Some(text) = &snapshot.session_layer {
+                    native.import_layer("session", text).map_err(err)?;
+                }
+                self.native_stages.insert(id.clone(), native);
+                true
+            }
+            Err(e) => {
+                eprintln!("Native USD backend failed, falling back to Python: {}", e);
+                false
+            }
+        };
+        #[cfg(not(feature = "usd-native"))]
+        let native = false;
+        
+        #[cfg(not(feature = "usd"))]
+        if !native && (snapshot.root_layer.is_some() || snapshot.session_layer.is_some()) {
+            return Err(format!("Stage '{}' has saved layer edits but no USD backend to apply them", id));
+        }
+        
+        #[cfg(feature = "usd")]
+        if !native {
+            let py_stage = profiling::with_gil("restore_stage", |py| -> Result<Py<PyAny>, String> {
+                let err = |e: PyErr| format!("Failed to restore stage '{}': {}", snapshot.path, e);
+                let stage_class = py.import("pxr.Usd").and_then(|usd| usd.getattr("Stage")).map_err(err)?;
+                let py_stage = match in_memory {
+                    true => stage_class.call_method1("CreateInMemory", (format!("{}.usda", id),)),
+                    false => stage_class.call_method1("Open", (snapshot.path.as_str(),)),
+                }.map_err(err)?;
+                if let Some(text) = &snapshot.root_layer {
+                    py_stage.call_method0("GetRootLayer")
+                        .and_then(|layer| layer.call_method1("ImportFromString", (text.as_str(),)))
+                        .map_err(err)?;
+                }
+                if let Some(text) = &snapshot.session_layer {
+                    py_stage.call_method0("GetSessionLayer")
+                        .and_then(|layer| layer.call_method1("ImportFromString", (text.as_str(),)))
+                        .map_err(err)?;
+                }
+                Ok(py_stage.unbind())
+            })?;
+            self.py_stages.insert(id.clone(), py_stage);
+        }
+        #[cfg(not(feature = "usd"))]
+        let _ = (native, in_memory);
+        
+        let key = |suffix: &str| format!("{}:{}", id, suffix);
+        for (path, prim_type) in &snapshot.prims {
+            let prim = USDPrim { path: path.clone(), prim_type: prim_type.clone(), stage_id: id.clone() };
+            self.prims.insert(key(path), prim);
+        }
+        for (suffix, value) in &snapshot.attributes {
+            self.attributes.insert(key(suffix), value.clone());
+        }
+        for (suffix, samples) in &snapshot.time_samples {
+            self.time_samples.insert(key(suffix), samples.clone());
+        }
+        for (suffix, target, variant) in &snapshot.variant_selections {
+            self.variant_selections.insert(key(suffix), (USDEditTarget::from_name(target), variant.clone()));
+        }
+        if let Some(sublayers) = &snapshot.sublayers {
+            self.sublayers.insert(id.clone(), sublayers.clone());
+        }
+        if !snapshot.muted_layers.is_empty() {
+            self.muted_layers.insert(id.clone(), snapshot.muted_layers.clone());
+        }
+        if let Some(pinned) = &snapshot.pinned_prims {
+            self.pinned_prims.insert(id.clone(), pinned.clone());
+        }
+        if let Some(range) = snapshot.time_range {
+            self.time_ranges.insert(id.clone(), range);
+        }
+        if snapshot.edit_target != USDEditTarget::Session {
+            self.edit_targets.insert(id.clone(), snapshot.edit_target.clone());
+        }
+        
+        let stage = USDStage { path: snapshot.path.clone(), identifier: id.clone() };
+        self.stages.insert(id.clone(), stage.clone());
+        self.sync_python_stage(&id)?;
+        self.mark_stage_dirty(&id);
+        Ok(stage)
+    }
+    
     /// Create a USD Camera primitive
     pub fn create_camera(&mut self, stage_id: &str, prim_path: &str, focal_length: f64, near_clip: f64, far_clip: f64) -> Result<USDPrim, String> {
         #[cfg(feature = "usd")]
@@ -319,7 +2314,7 @@ impl USDEngine {
             let _stage = self.stages.get(stage_id)
                 .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
                 
-            Python::with_gil(|py| -> Result<USDPrim, String> {
+            profiling::with_gil("create_camera", |py| -> Result<USDPrim, String> {
                 let _usd_geom = py.import("pxr.UsdGeom").map_err(|e| format!("Failed to import UsdGeom: {}", e))?;
                 
                 let prim = USDPrim {
@@ -330,6 +2325,8 @@ impl USDEngine {
                 
                 let prim_key = format!("{}:{}", stage_id, prim_path);
                 self.prims.insert(prim_key, prim.clone());
+                self.attributes.insert(format!("{}:{}.focalLength", stage_id, prim_path), focal_length.to_string());
+                self.attributes.insert(format!("{}:{}.clippingRange", stage_id, prim_path), format!("({}, {})", near_clip, far_clip));
                 
                 println!("Created USD Camera at '{}' (focal: {}mm, near: {}, far: {})", prim_path, focal_length, near_clip, far_clip);
                 Ok(prim)
@@ -349,6 +2346,8 @@ impl USDEngine {
             
             let prim_key = format!("{}:{}", stage_id, prim_path);
             self.prims.insert(prim_key, prim.clone());
+            self.attributes.insert(format!("{}:{}.focalLength", stage_id, prim_path), focal_length.to_string());
+            self.attributes.insert(format!("{}:{}.clippingRange", stage_id, prim_path), format!("({}, {})", near_clip, far_clip));
             
             println!("Mock: Created USD Camera at '{}' (focal: {}mm, near: {}, far: {})", prim_path, focal_length, near_clip, far_clip);
             Ok(prim)
@@ -362,7 +2361,7 @@ impl USDEngine {
             let _stage = self.stages.get(stage_id)
                 .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
                 
-            Python::with_gil(|py| -> Result<USDPrim, String> {
+            profiling::with_gil("create_distant_light", |py| -> Result<USDPrim, String> {
                 let _usd_lux = py.import("pxr.UsdLux").map_err(|e| format!("Failed to import UsdLux: {}", e))?;
                 
                 let prim = USDPrim {
@@ -405,7 +2404,7 @@ impl USDEngine {
             let _stage = self.stages.get(stage_id)
                 .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
                 
-            Python::with_gil(|py| -> Result<USDPrim, String> {
+            profiling::with_gil("create_sphere_light", |py| -> Result<USDPrim, String> {
                 let _usd_lux = py.import("pxr.UsdLux").map_err(|e| format!("Failed to import UsdLux: {}", e))?;
                 
                 let prim = USDPrim {
@@ -448,7 +2447,7 @@ impl USDEngine {
             let _stage = self.stages.get(stage_id)
                 .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
                 
-            Python::with_gil(|py| -> Result<USDPrim, String> {
+            profiling::with_gil("create_rect_light", |py| -> Result<USDPrim, String> {
                 let _usd_lux = py.import("pxr.UsdLux").map_err(|e| format!("Failed to import UsdLux: {}", e))?;
                 
                 let prim = USDPrim {
@@ -491,7 +2490,7 @@ impl USDEngine {
             let _stage = self.stages.get(stage_id)
                 .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
                 
-            Python::with_gil(|py| -> Result<USDPrim, String> {
+            profiling::with_gil("create_material", |py| -> Result<USDPrim, String> {
                 let _usd_shade = py.import("pxr.UsdShade").map_err(|e| format!("Failed to import UsdShade: {}", e))?;
                 
                 let prim = USDPrim {
@@ -534,7 +2533,7 @@ impl USDEngine {
             let _stage = self.stages.get(stage_id)
                 .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
                 
-            Python::with_gil(|py| -> Result<USDPrim, String> {
+            profiling::with_gil("create_preview_surface", |py| -> Result<USDPrim, String> {
                 let _usd_shade = py.import("pxr.UsdShade").map_err(|e| format!("Failed to import UsdShade: {}", e))?;
                 
                 let prim = USDPrim {
@@ -579,7 +2578,7 @@ impl USDEngine {
             let _stage = self.stages.get(stage_id)
                 .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
                 
-            Python::with_gil(|py| -> Result<USDPrim, String> {
+            profiling::with_gil("create_texture", |py| -> Result<USDPrim, String> {
                 let _usd_shade = py.import("pxr.UsdShade").map_err(|e| format!("Failed to import UsdShade: {}", e))?;
                 
                 let prim = USDPrim {
@@ -590,6 +2589,7 @@ impl USDEngine {
                 
                 let prim_key = format!("{}:{}", stage_id, prim_path);
                 self.prims.insert(prim_key, prim.clone());
+                self.attributes.insert(format!("{}:{}.inputs:file", stage_id, prim_path), file_path.to_string());
                 
                 println!("Created USD Texture at '{}' (file: {})", prim_path, file_path);
                 Ok(prim)
@@ -609,34 +2609,547 @@ impl USDEngine {
             
             let prim_key = format!("{}:{}", stage_id, prim_path);
             self.prims.insert(prim_key, prim.clone());
+            self.attributes.insert(format!("{}:{}.inputs:file", stage_id, prim_path), file_path.to_string());
             
             println!("Mock: Created USD Texture at '{}' (file: {})", prim_path, file_path);
             Ok(prim)
         }
     }
     
-    /// Render a USD stage through a viewport
-    pub fn render_stage(&self, stage_id: &str, viewport_name: &str, camera_path: &str, width: u32, height: u32) -> Result<String, String> {
+    /// Create a UsdGeomPointInstancer with prototype references and per-instance transforms
+    pub fn create_point_instancer(
+        &mut self,
+        stage_id: &str,
+        prim_path: &str,
+        prototypes: &[String],
+        proto_indices: &[i32],
+        positions: &[[f64; 3]],
+        orientations: &[[f64; 4]],
+        scales: &[[f64; 3]],
+    ) -> Result<USDPrim, String> {
+        let _stage = self.stages.get(stage_id)
+            .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
+        if proto_indices.len() != positions.len() {
+            return Err(format!("Point instancer '{}' has {} proto indices for {} positions",
+                               prim_path, proto_indices.len(), positions.len()));
+        }
+        if let Some(bad) = proto_indices.iter().find(|&&index| index < 0 || index as usize >= prototypes.len()) {
+            return Err(format!("Point instancer '{}' references missing prototype {}", prim_path, bad));
+        }
+        
         #[cfg(feature = "usd")]
         {
-            let _stage = self.stages.get(stage_id)
-                .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
-                
-            Python::with_gil(|py| -> Result<String, String> {
-                let _usd_imaging = py.import("pxr.UsdImagingGL").map_err(|e| format!("Failed to import UsdImagingGL: {}", e))?;
-                
-                // Count geometry and lighting prims for render stats
-                let geometry_count = self.prims.iter()
-                    .filter(|(key, prim)| key.starts_with(&format!("{}:", stage_id)) && 
-                           matches!(prim.prim_type.as_str(), "Sphere" | "Cube" | "Mesh" | "Xform"))
-                    .count();
-                    
-                let light_count = self.prims.iter()
-                    .filter(|(key, prim)| key.starts_with(&format!("{}:", stage_id)) && 
-                           prim.prim_type.contains("Light"))
-                    .count();
-                    
-                let material_count = self.prims.iter()
+            profiling::with_gil("create_point_instancer", |py| -> Result<(), String> {
+                let _usd_geom = py.import("pxr.UsdGeom").map_err(|e| format!("Failed to import UsdGeom: {}", e))?;
+                println!("Created USD PointInstancer at '{}' ({} prototypes, {} instances)",
+                         prim_path, prototypes.len(), positions.len());
+                Ok(())
+            })?;
+        }
+        
+        #[cfg(not(feature = "usd"))]
+        println!("Mock: Created USD PointInstancer at '{}' ({} prototypes, {} instances)",
+                 prim_path, prototypes.len(), positions.len());
+        
+        let prim = USDPrim {
+            path: prim_path.to_string(),
+            prim_type: "PointInstancer".to_string(),
+            stage_id: stage_id.to_string(),
+        };
+        self.prims.insert(format!("{}:{}", stage_id, prim_path), prim.clone());
+        
+        let authored = [
+            ("prototypes", format_list(prototypes.iter().map(|p| format!("<{}>", p)))),
+            ("protoIndices", format_list(proto_indices.iter())),
+            ("positions", format_list(positions.iter().map(|p| format!("({}, {}, {})", p[0], p[1], p[2])))),
+            ("orientations", format_list(orientations.iter().map(|q| format!("({}, {}, {}, {})", q[0], q[1], q[2], q[3])))),
+            ("scales", format_list(scales.iter().map(|s| format!("({}, {}, {})", s[0], s[1], s[2])))),
+        ];
+        for (attr_name, value) in authored {
+            self.attributes.insert(format!("{}:{}.{}", stage_id, prim_path, attr_name), value);
+        }
+        
+        Ok(prim)
+    }
+    
+    /// Prototype paths of a point instancer, in prototype index order
+    pub fn get_instancer_prototypes(&self, stage_id: &str, instancer_path: &str) -> Result<Vec<String>, String> {
+        let stage = self.stages.get(stage_id)
+            .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
+        
+        #[cfg(feature = "usd")]
+        {
+            profiling::with_gil("get_instancer_prototypes", |py| -> Result<Vec<String>, String> {
+                let py_stage = self.open_python_stage(py, stage)?;
+                Self::instancer_edit_helpers(py)?
+                    .call_method1("prototypes", (py_stage, instancer_path))
+                    .and_then(|targets| targets.extract())
+                    .map_err(|e| format!("Failed to read prototypes of '{}': {}", instancer_path, e))
+            })
+        }
+        
+        #[cfg(not(feature = "usd"))]
+        {
+            let _ = stage;
+            let value = self.attributes.get(&format!("{}:{}.prototypes", stage_id, instancer_path))
+                .ok_or_else(|| format!("'{}' is not a point instancer", instancer_path))?;
+            Ok(parse_list(value).into_iter().map(|path| path.trim_matches(['<', '>']).to_string()).collect())
+        }
+    }
+    
+    /// Point one prototype slot of an instancer at another prim, keeping every instance's index
+    pub fn swap_instancer_prototype(&mut self, stage_id: &str, instancer_path: &str, index: usize, prototype_path: &str) -> Result<(), String> {
+        let mut prototypes = self.get_instancer_prototypes(stage_id, instancer_path)?;
+        if index >= prototypes.len() {
+            return Err(format!("'{}' has no prototype {} ({} prototypes)", instancer_path, index, prototypes.len()));
+        }
+        prototypes[index] = prototype_path.to_string();
+        
+        #[cfg(feature = "usd")]
+        {
+            let stage = &self.stages[stage_id];
+            profiling::with_gil("swap_instancer_prototype", |py| -> Result<(), String> {
+                let py_stage = self.open_python_stage(py, stage)?;
+                Self::instancer_edit_helpers(py)?
+                    .call_method1("set_prototypes", (py_stage, instancer_path, prototypes.clone()))
+                    .map_err(|e| format!("Failed to swap prototype {} of '{}': {}", index, instancer_path, e))?;
+                Ok(())
+            })?;
+            println!("Swapped prototype {} of '{}' to '{}'", index, instancer_path, prototype_path);
+        }
+        
+        #[cfg(not(feature = "usd"))]
+        println!("Mock: Swapped prototype {} of '{}' to '{}'", index, instancer_path, prototype_path);
+        
+        self.attributes.insert(format!("{}:{}.prototypes", stage_id, instancer_path),
+                               format_list(prototypes.iter().map(|path| format!("<{}>", path))));
+        self.mark_stage_dirty(stage_id);
+        Ok(())
+    }
+    
+    /// Show or hide instances by id, authoring the instancer's invisibleIds
+    pub fn set_instances_visible(&mut self, stage_id: &str, instancer_path: &str, ids: &[i64], visible: bool) -> Result<(), String> {
+        self.edit_instance_ids(stage_id, instancer_path, "invisibleIds", ids, !visible)
+    }
+    
+    /// Activate or deactivate instances by id, authoring the instancer's inactiveIds
+    ///
+    /// Unlike hidden instances, inactive ones are pruned for every consumer of the stage.
+    pub fn set_instances_active(&mut self, stage_id: &str, instancer_path: &str, ids: &[i64], active: bool) -> Result<(), String> {
+        self.edit_instance_ids(stage_id, instancer_path, "inactiveIds", ids, !active)
+    }
+    
+    /// Add ids to or remove them from an instancer's invisibleIds or inactiveIds list
+    fn edit_instance_ids(&mut self, stage_id: &str, instancer_path: &str, list: &str, ids: &[i64], add: bool) -> Result<(), String> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+        let stage = self.stages.get(stage_id)
+            .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
+        let key = format!("{}:{}.{}", stage_id, instancer_path, list);
+        
+        #[cfg(feature = "usd")]
+        let current: Vec<i64> = profiling::with_gil("edit_instance_ids", |py| -> Result<Vec<i64>, String> {
+            let py_stage = self.open_python_stage(py, stage)?;
+            Self::instancer_edit_helpers(py)?
+                .call_method1("edit_ids", (py_stage, instancer_path, list, ids.to_vec(), add))
+                .and_then(|current| current.extract())
+                .map_err(|e| format!("Failed to edit {} of '{}': {}", list, instancer_path, e))
+        })?;
+        
+        #[cfg(not(feature = "usd"))]
+        let current: Vec<i64> = {
+            let _ = stage;
+            let mut current: Vec<i64> = self.attributes.get(&key)
+                .map(|value| parse_list(value).iter().filter_map(|id| id.parse().ok()).collect())
+                .unwrap_or_default();
+            current.retain(|id| !ids.contains(id));
+            if add {
+                current.extend_from_slice(ids);
+            }
+            current.sort_unstable();
+            println!("Mock: {} now lists {} instances on '{}'", list, current.len(), instancer_path);
+            current
+        };
+        
+        self.attributes.insert(key, format_list(current.iter()));
+        self.mark_stage_dirty(stage_id);
+        Ok(())
+    }
+    
+    /// Split one prototype into a prototype per variant, chosen per instance by a primvar
+    ///
+    /// Each variant prototype is a child of the instancer referencing the source
+    /// prototype with `variant_set` selected. Instances of the source prototype,
+    /// or of variant prototypes from an earlier split, are retargeted by their
+    /// primvar value: a variant name or an index into the set's variants.
+    /// Returns the variant prototype paths.
+    pub fn create_variant_prototypes(
+        &mut self,
+        stage_id: &str,
+        instancer_path: &str,
+        prototype_index: usize,
+        variant_set: &str,
+        primvar: &str,
+    ) -> Result<Vec<String>, String> {
+        let prototypes = self.get_instancer_prototypes(stage_id, instancer_path)?;
+        let source = prototypes.get(prototype_index)
+            .ok_or_else(|| format!("'{}' has no prototype {} ({} prototypes)", instancer_path, prototype_index, prototypes.len()))?
+            .clone();
+        let prefix = format!("{}/VariantPrototypes/{}_", instancer_path, source.rsplit('/').next().unwrap_or_default());
+        
+        #[cfg(feature = "usd")]
+        let (proto_indices, choices) = {
+            let stage = &self.stages[stage_id];
+            profiling::with_gil("create_variant_prototypes", |py| -> Result<(Vec<i32>, Vec<String>), String> {
+                let py_stage = self.open_python_stage(py, stage)?;
+                Self::instancer_edit_helpers(py)?
+                    .call_method1("variant_choices", (py_stage, instancer_path, source.as_str(), variant_set, primvar))
+                    .and_then(|choices| choices.extract())
+                    .map_err(|e| format!("Failed to read '{}' on '{}': {}", primvar, instancer_path, e))
+            })?
+        };
+        
+        #[cfg(not(feature = "usd"))]
+        let (proto_indices, choices) = {
+            let read = |attr: &str| self.attributes.get(&format!("{}:{}.{}", stage_id, instancer_path, attr))
+                .map(|value| parse_list(value))
+                .ok_or_else(|| format!("'{}' has no {}", instancer_path, attr));
+            let proto_indices: Vec<i32> = read("protoIndices")?.iter().filter_map(|index| index.parse().ok()).collect();
+            (proto_indices, read(&format!("primvars:{}", primvar))?)
+        };
+        
+        let split = split_by_variant(&prototypes, &proto_indices, prototype_index, &prefix, &choices)?;
+        
+        #[cfg(feature = "usd")]
+        {
+            let stage = &self.stages[stage_id];
+            profiling::with_gil("create_variant_prototypes", |py| -> Result<(), String> {
+                let py_stage = self.open_python_stage(py, stage)?;
+                Self::instancer_edit_helpers(py)?
+                    .call_method1("author_variant_prototypes", (
+                        py_stage, instancer_path, source.as_str(), variant_set,
+                        split.variants.clone(), split.prototypes.clone(), split.proto_indices.clone(),
+                    ))
+                    .map_err(|e| format!("Failed to author variant prototypes of '{}': {}", instancer_path, e))?;
+                Ok(())
+            })?;
+            println!("Split prototype '{}' of '{}' into {} variant prototypes", source, instancer_path, split.variants.len());
+        }
+        
+        #[cfg(not(feature = "usd"))]
+        println!("Mock: Split prototype '{}' of '{}' into {} variant prototypes", source, instancer_path, split.variants.len());
+        
+        for (_, path) in &split.variants {
+            let prim = USDPrim {
+                path: path.clone(),
+                prim_type: String::new(),
+                stage_id: stage_id.to_string(),
+            };
+            self.prims.insert(format!("{}:{}", stage_id, path), prim);
+        }
+        self.attributes.insert(format!("{}:{}.prototypes", stage_id, instancer_path),
+                               format_list(split.prototypes.iter().map(|path| format!("<{}>", path))));
+        self.attributes.insert(format!("{}:{}.protoIndices", stage_id, instancer_path),
+                               format_list(split.proto_indices.iter()));
+        self.mark_stage_dirty(stage_id);
+        Ok(split.variants.into_iter().map(|(_, path)| path).collect())
+    }
+    
+    /// Load the point instancer edit helper module
+    #[cfg(feature = "usd")]
+    fn instancer_edit_helpers(py: Python<'_>) -> Result<Bound<'_, PyModule>, String> {
+        PyModule::from_code(py, INSTANCER_EDIT_HELPERS, c"nodle_instancer_edit.py", c"nodle_instancer_edit")
+            .map_err(|e| format!("Failed to load instancer edit helpers: {}", e))
+    }
+    
+    /// Copy a prim subtree into another stage (Sdf.CopySpec semantics), returning the copied prims
+    pub fn copy_prim(&mut self, src_stage_id: &str, src_path: &str, dst_stage_id: &str, dst_path: &str) -> Result<Vec<USDPrim>, String> {
+        let src_stage = self.stages.get(src_stage_id)
+            .ok_or_else(|| format!("Stage '{}' not found", src_stage_id))?
+            .clone();
+        let dst_stage = self.stages.get(dst_stage_id)
+            .ok_or_else(|| format!("Stage '{}' not found", dst_stage_id))?
+            .clone();
+        
+        #[cfg(feature = "usd")]
+        {
+            profiling::with_gil("copy_prim", |py| -> Result<(), String> {
+                let sdf = py.import("pxr.Sdf").map_err(|e| format!("Failed to import Sdf: {}", e))?;
+                let src = self.open_python_stage(py, &src_stage)?;
+                let dst = self.open_python_stage(py, &dst_stage)?;
+                let layer = |stage: &Bound<'_, PyAny>| stage.call_method0("GetRootLayer")
+                    .map_err(|e| format!("Failed to get root layer: {}", e));
+                let (src_layer, dst_layer) = (layer(&src)?, layer(&dst)?);
+                
+                // CopySpec needs the destination parent specs to exist
+                sdf.call_method1("CreatePrimInLayer", (&dst_layer, dst_path))
+                    .map_err(|e| format!("Failed to create '{}' in target layer: {}", dst_path, e))?;
+                let copied: bool = sdf.call_method1("CopySpec", (&src_layer, src_path, &dst_layer, dst_path))
+                    .and_then(|result| result.extract())
+                    .map_err(|e| format!("Failed to copy '{}' to '{}': {}", src_path, dst_path, e))?;
+                if !copied {
+                    return Err(format!("Failed to copy '{}' to '{}'", src_path, dst_path));
+                }
+                println!("Copied '{}:{}' to '{}:{}'", src_stage.path, src_path, dst_stage.path, dst_path);
+                Ok(())
+            })?;
+        }
+        
+        #[cfg(not(feature = "usd"))]
+        println!("Mock: Copied '{}:{}' to '{}:{}'", src_stage.path, src_path, dst_stage.path, dst_path);
+        
+        // Re-root the tracked prims, attributes and time samples under the destination path
+        let reroot = |path: &str| -> Option<String> {
+            if path == src_path {
+                Some(dst_path.to_string())
+            } else {
+                path.strip_prefix(src_path)
+                    .filter(|rest| rest.starts_with('/'))
+                    .map(|rest| format!("{}{}", dst_path, rest))
+            }
+        };
+        
+        let mut copied: Vec<USDPrim> = self.prims.values()
+            .filter(|prim| prim.stage_id == src_stage_id)
+            .filter_map(|prim| reroot(&prim.path).map(|path| USDPrim {
+                path,
+                prim_type: prim.prim_type.clone(),
+                stage_id: dst_stage_id.to_string(),
+            }))
+            .collect();
+        if copied.is_empty() {
+            copied.push(USDPrim {
+                path: dst_path.to_string(),
+                prim_type: "Xform".to_string(),
+                stage_id: dst_stage_id.to_string(),
+            });
+        }
+        for prim in &copied {
+            self.prims.insert(format!("{}:{}", dst_stage_id, prim.path), prim.clone());
+        }
+        
+        let src_prefix = format!("{}:", src_stage_id);
+        let rekey = |key: &str| -> Option<String> {
+            let (prim_path, attr_name) = key.strip_prefix(&src_prefix)?.rsplit_once('.')?;
+            reroot(prim_path).map(|path| format!("{}:{}.{}", dst_stage_id, path, attr_name))
+        };
+        let attributes: Vec<(String, String)> = self.attributes.iter()
+            .filter_map(|(key, value)| rekey(key).map(|key| (key, value.clone())))
+            .collect();
+        self.attributes.extend(attributes);
+        let time_samples: Vec<(String, Vec<(f64, String)>)> = self.time_samples.iter()
+            .filter_map(|(key, samples)| rekey(key).map(|key| (key, samples.clone())))
+            .collect();
+        self.time_samples.extend(time_samples);
+        
+        copied.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(copied)
+    }
+    
+    /// Flatten a stage's composed layer stack into a single layer
+    ///
+    /// The flattened layer is exported to `output_path`, or to a temporary
+    /// file when none is given, and registered as a new stage handle.
+    pub fn flatten_stage(&mut self, stage_id: &str, output_path: Option<&str>) -> Result<USDStage, String> {
+        let stage = self.stages.get(stage_id)
+            .ok_or_else(|| format!("Stage '{}' not found", stage_id))?
+            .clone();
+        let identifier = format!("{}_flattened", stage_id);
+        let path = match output_path.filter(|path| !path.trim().is_empty()) {
+            Some(path) => path.trim().to_string(),
+            None => std::env::temp_dir()
+                .join(format!("{}.usda", identifier))
+                .to_string_lossy()
+                .into_owned(),
+        };
+        
+        #[cfg(feature = "usd")]
+        {
+            let flattened_stage = profiling::with_gil("flatten_stage", |py| -> Result<Py<PyAny>, String> {
+                let py_stage = self.open_python_stage(py, &stage)?;
+                let layer = py_stage.call_method0("Flatten")
+                    .map_err(|e| format!("Failed to flatten stage '{}': {}", stage.path, e))?;
+                let exported: bool = layer.call_method1("Export", (path.as_str(),))
+                    .and_then(|result| result.extract())
+                    .map_err(|e| format!("Failed to export flattened layer to '{}': {}", path, e))?;
+                if !exported {
+                    return Err(format!("Failed to export flattened layer to '{}'", path));
+                }
+                println!("Flattened stage '{}' to '{}'", stage.path, path);
+                
+                // Reload so a re-flatten over an already open layer picks up the new file
+                let flattened_stage = py.import("pxr.Usd")
+                    .and_then(|usd| usd.getattr("Stage"))
+                    .and_then(|stage_class| stage_class.call_method1("Open", (path.as_str(),)))
+                    .map_err(|e| format!("Failed to open flattened stage '{}': {}", path, e))?;
+                flattened_stage.call_method0("GetRootLayer")
+                    .and_then(|root| root.call_method0("Reload"))
+                    .map_err(|e| format!("Failed to reload flattened stage '{}': {}", path, e))?;
+                Ok(flattened_stage.unbind())
+            })?;
+            self.py_stages.insert(identifier.clone(), flattened_stage);
+        }
+        
+        #[cfg(not(feature = "usd"))]
+        println!("Mock: Flattened stage '{}' to '{}'", stage.path, path);
+        
+        // The flattened stage carries the same prims and opinions
+        let flattened = USDStage {
+            path,
+            identifier: identifier.clone(),
+        };
+        let src_prefix = format!("{}:", stage_id);
+        let rekey = |key: &str| key.strip_prefix(&src_prefix).map(|rest| format!("{}:{}", identifier, rest));
+        
+        let prims: Vec<(String, USDPrim)> = self.prims.iter()
+            .filter(|(_, prim)| prim.stage_id == stage_id)
+            .filter_map(|(key, prim)| rekey(key).map(|key| (key, USDPrim {
+                stage_id: identifier.clone(),
+                ..prim.clone()
+            })))
+            .collect();
+        self.prims.extend(prims);
+        let attributes: Vec<(String, String)> = self.attributes.iter()
+            .filter_map(|(key, value)| rekey(key).map(|key| (key, value.clone())))
+            .collect();
+        self.attributes.extend(attributes);
+        let time_samples: Vec<(String, Vec<(f64, String)>)> = self.time_samples.iter()
+            .filter_map(|(key, samples)| rekey(key).map(|key| (key, samples.clone())))
+            .collect();
+        self.time_samples.extend(time_samples);
+        if let Some(range) = self.time_ranges.get(stage_id).copied() {
+            self.time_ranges.insert(identifier.clone(), range);
+        }
+        
+        self.stages.insert(identifier.clone(), flattened.clone());
+        self.mark_stage_dirty(&identifier);
+        Ok(flattened)
+    }
+    
+    /// Build an in-memory preview stage with a material bound to a turntable shape
+    ///
+    /// The material network is copied from the composed source stage, so the
+    /// preview has to be rebuilt when the source stage changes. The preview
+    /// geometry turns once over `PREVIEW_TURNTABLE_FRAMES` and is framed by
+    /// `/Preview/Camera`.
+    pub fn build_material_preview(&mut self, stage_id: &str, material_path: &str, shape: PreviewShape) -> Result<USDStage, String> {
+        let stage = self.stages.get(stage_id)
+            .ok_or_else(|| format!("Stage '{}' not found", stage_id))?
+            .clone();
+        let identifier = format!("{}_preview{}", stage_id, material_path.replace('/', "_"));
+        
+        #[cfg(feature = "usd")]
+        {
+            let preview_stage = profiling::with_gil("build_material_preview", |py| -> Result<Py<PyAny>, String> {
+                let py_stage = self.open_python_stage(py, &stage)?;
+                PyModule::from_code(py, MATERIAL_PREVIEW_HELPERS, c"nodle_material_preview.py", c"nodle_material_preview")
+                    .and_then(|helpers| helpers.call_method1("build_preview", (py_stage, material_path, shape.name(), PREVIEW_TURNTABLE_FRAMES)))
+                    .map(|preview| preview.unbind())
+                    .map_err(|e| format!("Failed to build preview of '{}': {}", material_path, e))
+            })?;
+            self.py_stages.insert(identifier.clone(), preview_stage);
+        }
+        
+        // Mirror the preview prims so hierarchy and attribute queries work without Python
+        #[cfg(not(feature = "usd"))]
+        {
+            let material_name = material_path.rsplit('/').next().unwrap_or_default();
+            let target = format!("/Preview/Looks/{}", material_name);
+            self.prims.retain(|_, prim| prim.stage_id != identifier);
+            self.attributes.retain(|key, _| !key.starts_with(&format!("{}:", identifier)));
+            let material = self.prims.get(&format!("{}:{}", stage_id, material_path))
+                .ok_or_else(|| format!("Material '{}' not found", material_path))?;
+            if material.prim_type != "Material" {
+                return Err(format!("'{}' is not a material", material_path));
+            }
+            
+            let mut shapes = vec![match shape {
+                PreviewShape::Cube => ("/Preview/Geo/Cube", "Cube"),
+                _ => ("/Preview/Geo/Ball", "Sphere"),
+            }];
+            if shape == PreviewShape::ShaderBall {
+                shapes.push(("/Preview/Geo/Pedestal", "Cylinder"));
+            }
+            let mut prims = vec![
+                ("/Preview".to_string(), "Xform"),
+                ("/Preview/Looks".to_string(), "Scope"),
+                ("/Preview/Geo".to_string(), "Xform"),
+                ("/Preview/Key".to_string(), "DistantLight"),
+                ("/Preview/Fill".to_string(), "DomeLight"),
+                ("/Preview/Camera".to_string(), "Camera"),
+            ];
+            prims.extend(shapes.iter().map(|(path, prim_type)| (path.to_string(), *prim_type)));
+            
+            // The material network, re-rooted under /Preview/Looks
+            let src_prefix = format!("{}:{}", stage_id, material_path);
+            let rebase = |key: &str| key.strip_prefix(&src_prefix)
+                .filter(|rest| rest.is_empty() || rest.starts_with(['/', '.']))
+                .map(|rest| format!("{}:{}{}", identifier, target, rest));
+            let network: Vec<(String, USDPrim)> = self.prims.iter()
+                .filter_map(|(key, prim)| rebase(key).map(|key| {
+                    let path = key[identifier.len() + 1..].to_string();
+                    (key, USDPrim { path, prim_type: prim.prim_type.clone(), stage_id: identifier.clone() })
+                }))
+                .collect();
+            let network_attributes: Vec<(String, String)> = self.attributes.iter()
+                .filter_map(|(key, value)| rebase(key).map(|key| (key, value.clone())))
+                .collect();
+            
+            for (path, prim_type) in prims {
+                self.prims.insert(format!("{}:{}", identifier, path), USDPrim {
+                    path,
+                    prim_type: prim_type.to_string(),
+                    stage_id: identifier.clone(),
+                });
+            }
+            self.prims.extend(network);
+            self.attributes.extend(network_attributes);
+            for (path, _) in &shapes {
+                self.attributes.insert(format!("{}:{}.material:binding", identifier, path), target.clone());
+            }
+            self.time_samples.insert(format!("{}:/Preview/Geo.xformOp:rotateY", identifier),
+                                     vec![(0.0, "0".to_string()), (PREVIEW_TURNTABLE_FRAMES, "360".to_string())]);
+            println!("Mock: Built preview of '{}' from stage '{}'", material_path, stage.path);
+        }
+        
+        self.time_ranges.insert(identifier.clone(), USDTimeRange {
+            start_time_code: 0.0,
+            end_time_code: PREVIEW_TURNTABLE_FRAMES,
+            ..Default::default()
+        });
+        
+        let preview = USDStage {
+            path: format!("memory://{}", identifier),
+            identifier: identifier.clone(),
+        };
+        self.stages.insert(identifier.clone(), preview.clone());
+        self.mark_stage_dirty(&identifier);
+        Ok(preview)
+    }
+    
+    /// Render a USD stage through a viewport
+    pub fn render_stage(&self, stage_id: &str, viewport_name: &str, camera_path: &str, width: u32, height: u32) -> Result<String, String> {
+        #[cfg(feature = "usd")]
+        {
+            let _stage = self.stages.get(stage_id)
+                .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
+                
+            profiling::with_gil("render_stage", |py| -> Result<String, String> {
+                let _usd_imaging = py.import("pxr.UsdImagingGL").map_err(|e| format!("Failed to import UsdImagingGL: {}", e))?;
+                
+                // Count geometry and lighting prims for render stats
+                let geometry_count = self.prims.iter()
+                    .filter(|(key, prim)| key.starts_with(&format!("{}:", stage_id)) && 
+                           matches!(prim.prim_type.as_str(), "Sphere" | "Cube" | "Mesh" | "Xform"))
+                    .count();
+                    
+                let light_count = self.prims.iter()
+                    .filter(|(key, prim)| key.starts_with(&format!("{}:", stage_id)) && 
+                           prim.prim_type.contains("Light"))
+                    .count();
+                    
+                let material_count = self.prims.iter()
                     .filter(|(key, prim)| key.starts_with(&format!("{}:", stage_id)) && 
                            matches!(prim.prim_type.as_str(), "Material" | "Shader"))
                     .count();
@@ -679,32 +3192,327 @@ impl USDEngine {
     }
     
     /// Add a sublayer to a USD stage
-    pub fn add_sublayer(&self, stage_id: &str, layer_path: &str, layer_offset: f64) -> Result<String, String> {
+    pub fn add_sublayer(&mut self, stage_id: &str, layer_path: &str, layer_offset: f64) -> Result<String, String> {
+        let mut sublayers = self.get_sublayers(stage_id)?;
+        sublayers.retain(|(path, _)| path != layer_path);
+        sublayers.push((layer_path.to_string(), layer_offset));
+        self.sublayers.insert(stage_id.to_string(), sublayers);
+        self.sync_python_stage(stage_id)?;
+        self.mark_stage_dirty(stage_id);
+        
+        let info = format!("SubLayer '{}' with offset {}", layer_path, layer_offset);
+        #[cfg(feature = "usd")]
+        println!("Added {} to stage '{}'", info, stage_id);
+        #[cfg(not(feature = "usd"))]
+        println!("Mock: Added {} to stage '{}'", info, stage_id);
+        Ok(info)
+    }
+    
+    /// Package a stage with every layer and texture it references into a .usdz archive
+    ///
+    /// File-backed stages are packaged from their root layer as authored. In-memory
+    /// stages and stages with anonymous layers are flattened to a staging file
+    /// first. `arkit` applies Apple's AR Quick Look constraints.
+    pub fn package_usdz(&self, stage_id: &str, output_path: &str, arkit: bool) -> Result<String, String> {
+        let stage = self.stages.get(stage_id)
+            .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
+        let (path, _, _) = export_target(output_path, Some("usdz"))?;
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create directory '{}': {}", parent.display(), e))?;
+        }
+        let path = path.to_string_lossy().into_owned();
+        let flatten = !std::path::Path::new(&stage.path).is_file()
+            || self.anonymous_layers.get(stage_id).is_some_and(|layers| !layers.is_empty());
+        
         #[cfg(feature = "usd")]
         {
-            let _stage = self.stages.get(stage_id)
-                .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
-                
-            Python::with_gil(|py| -> Result<String, String> {
-                let _usd = py.import("pxr.Usd").map_err(|e| format!("Failed to import Usd: {}", e))?;
+            profiling::with_gil("package_usdz", |py| -> Result<(), String> {
+                let err = |e: PyErr| format!("Failed to package '{}' as '{}': {}", stage_id, path, e);
+                let asset = if flatten {
+                    let staging = std::env::temp_dir().join(format!("nodle_usdz_{}", stage_id));
+                    std::fs::create_dir_all(&staging)
+                        .map_err(|e| format!("Failed to create directory '{}': {}", staging.display(), e))?;
+                    let stem = std::path::Path::new(&path).file_stem().and_then(|stem| stem.to_str()).unwrap_or(stage_id);
+                    let staged = staging.join(format!("{}.usdc", stem)).to_string_lossy().into_owned();
+                    let exported: bool = self.open_python_stage(py, stage)?
+                        .call_method0("Flatten")
+                        .and_then(|layer| layer.call_method1("Export", (staged.as_str(),)))
+                        .and_then(|result| result.extract())
+                        .map_err(err)?;
+                    if !exported {
+                        return Err(format!("Failed to stage '{}' for packaging", stage_id));
+                    }
+                    staged
+                } else {
+                    stage.path.clone()
+                };
                 
-                let info = format!("SubLayer '{}' with offset {}", layer_path, layer_offset);
-                println!("Added {} to stage '{}'", info, stage_id);
-                Ok(info)
+                let packager = if arkit { "CreateNewARKitUsdzPackage" } else { "CreateNewUsdzPackage" };
+                let packaged: bool = py.import("pxr.UsdUtils")
+                    .and_then(|usd_utils| usd_utils.call_method1(packager, (asset.as_str(), path.as_str())))
+                    .and_then(|result| result.extract())
+                    .map_err(err)?;
+                if !packaged {
+                    return Err(format!("Failed to package '{}' as '{}'", stage_id, path));
+                }
+                Ok(())
+            })?;
+            println!("Packaged USD stage '{}' as '{}'{}", stage_id, path, if arkit { " for ARKit" } else { "" });
+        }
+        
+        #[cfg(not(feature = "usd"))]
+        println!("Mock: Packaged USD stage '{}' as '{}'{} (flatten: {})", stage_id, path, if arkit { " for ARKit" } else { "" }, flatten);
+        
+        Ok(path)
+    }
+    
+    /// Create an anonymous in-memory layer as the stage's strongest sublayer, returning its identifier
+    ///
+    /// The layer lives as long as the engine and never touches disk; export the
+    /// stage flattened to materialize it.
+    pub fn add_anonymous_layer(&mut self, stage_id: &str, tag: &str) -> Result<String, String> {
+        let mut sublayers = self.get_sublayers(stage_id)?;
+        
+        #[cfg(feature = "usd")]
+        let identifier = profiling::with_gil("add_anonymous_layer", |py| -> Result<String, String> {
+            let err = |e: PyErr| format!("Failed to create anonymous layer '{}': {}", tag, e);
+            let layer = py.import("pxr.Sdf")
+                .and_then(|sdf| sdf.getattr("Layer"))
+                .and_then(|layer_class| layer_class.call_method1("CreateAnonymous", (tag,)))
+                .map_err(err)?;
+            let identifier: String = layer.getattr("identifier").and_then(|id| id.extract()).map_err(err)?;
+            self.retained_layers.push(layer.unbind());
+            Ok(identifier)
+        })?;
+        #[cfg(not(feature = "usd"))]
+        let identifier = format!("anon:{}:{}", self.anonymous_layers.values().map(Vec::len).sum::<usize>(), tag);
+        
+        sublayers.insert(0, (identifier.clone(), 0.0));
+        self.sublayers.insert(stage_id.to_string(), sublayers);
+        self.anonymous_layers.entry(stage_id.to_string()).or_default().push(identifier.clone());
+        self.sync_python_stage(stage_id)?;
+        self.mark_stage_dirty(stage_id);
+        
+        #[cfg(feature = "usd")]
+        println!("Added anonymous layer '{}' to stage '{}'", identifier, stage_id);
+        #[cfg(not(feature = "usd"))]
+        println!("Mock: Added anonymous layer '{}' to stage '{}'", identifier, stage_id);
+        Ok(identifier)
+    }
+    
+    /// Anonymous layers added to a stage through the engine
+    pub fn get_anonymous_layers(&self, stage_id: &str) -> Vec<String> {
+        self.anonymous_layers.get(stage_id).cloned().unwrap_or_default()
+    }
+    
+    /// Sublayer paths and offsets of a stage's root layer, strongest first
+    pub fn get_sublayers(&self, stage_id: &str) -> Result<Vec<(String, f64)>, String> {
+        let stage = self.stages.get(stage_id)
+            .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
+        if let Some(sublayers) = self.sublayers.get(stage_id) {
+            return Ok(sublayers.clone());
+        }
+        
+        #[cfg(feature = "usd")]
+        {
+            profiling::with_gil("get_sublayers", |py| -> Result<Vec<(String, f64)>, String> {
+                let err = |e: PyErr| format!("Failed to read sublayers of '{}': {}", stage.path, e);
+                let root = self.open_python_stage(py, stage)?
+                    .call_method0("GetRootLayer")
+                    .map_err(err)?;
+                let paths: Vec<String> = root.getattr("subLayerPaths").and_then(|paths| paths.extract()).map_err(err)?;
+                let offsets: Vec<f64> = root.getattr("subLayerOffsets")
+                    .and_then(|offsets| offsets.try_iter()?.map(|offset| offset?.getattr("offset")?.extract()).collect())
+                    .map_err(err)?;
+                Ok(paths.into_iter().zip(offsets.into_iter().chain(std::iter::repeat(0.0))).collect())
             })
         }
         
         #[cfg(not(feature = "usd"))]
         {
-            let _stage = self.stages.get(stage_id)
-                .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
+            let _ = stage;
+            Ok(Vec::new())
+        }
+    }
+    
+    /// Enumerate the composed layer stack: the root layer followed by its sublayers, depth first
+    pub fn get_layer_stack(&self, stage_id: &str) -> Result<Vec<USDLayerInfo>, String> {
+        let stage = self.stages.get(stage_id)
+            .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
+        let muted = self.muted_layers.get(stage_id).cloned().unwrap_or_default();
+        
+        #[cfg(feature = "usd")]
+        {
+            profiling::with_gil("get_layer_stack", |py| -> Result<Vec<USDLayerInfo>, String> {
+                let err = |e: PyErr| format!("Failed to read layer stack of '{}': {}", stage.path, e);
+                let sdf = py.import("pxr.Sdf").map_err(|e| format!("Failed to import Sdf: {}", e))?;
+                let py_stage = self.open_python_stage(py, stage)?;
+                let root = py_stage.call_method0("GetRootLayer").map_err(err)?;
+                let root_identifier: String = root.getattr("identifier").and_then(|id| id.extract()).map_err(err)?;
                 
-            let info = format!("SubLayer '{}' with offset {}", layer_path, layer_offset);
-            println!("Mock: Added {} to stage '{}'", info, stage_id);
-            Ok(info)
+                let mut layers = vec![USDLayerInfo {
+                    identifier: root_identifier,
+                    depth: 0,
+                    offset: 0.0,
+                    scale: 1.0,
+                    muted: false,
+                }];
+                // (layer, depth) pairs still to expand; muted layers are listed but not opened
+                let mut pending = vec![(root, 1usize)];
+                while let Some((layer, depth)) = pending.pop() {
+                    let paths: Vec<String> = layer.getattr("subLayerPaths").and_then(|paths| paths.extract()).map_err(err)?;
+                    let offsets: Vec<Bound<'_, PyAny>> = layer.getattr("subLayerOffsets")
+                        .and_then(|offsets| offsets.try_iter()?.collect())
+                        .map_err(err)?;
+                    let mut children = Vec::new();
+                    for (index, path) in paths.iter().enumerate() {
+                        let identifier: String = sdf.call_method1("ComputeAssetPathRelativeToLayer", (&layer, path.as_str()))
+                            .and_then(|id| id.extract())
+                            .map_err(err)?;
+                        let (offset, scale) = match offsets.get(index) {
+                            Some(offset) => (
+                                offset.getattr("offset").and_then(|v| v.extract()).map_err(err)?,
+                                offset.getattr("scale").and_then(|v| v.extract()).map_err(err)?,
+                            ),
+                            None => (0.0, 1.0),
+                        };
+                        let is_muted = muted.iter().any(|layer| layer == path || *layer == identifier);
+                        layers.push(USDLayerInfo { identifier: identifier.clone(), depth, offset, scale, muted: is_muted });
+                        if !is_muted {
+                            if let Ok(child) = sdf.getattr("Layer").and_then(|class| class.call_method1("FindOrOpen", (identifier.as_str(),))) {
+                                if !child.is_none() {
+                                    children.push((child, depth + 1));
+                                }
+                            }
+                        }
+                    }
+                    // Expand the strongest sublayer first
+                    pending.extend(children.into_iter().rev());
+                }
+                Ok(layers)
+            })
+        }
+        
+        #[cfg(not(feature = "usd"))]
+        {
+            let mut layers = vec![USDLayerInfo {
+                identifier: stage.path.clone(),
+                depth: 0,
+                offset: 0.0,
+                scale: 1.0,
+                muted: false,
+            }];
+            layers.extend(self.get_sublayers(stage_id)?.into_iter().map(|(path, offset)| USDLayerInfo {
+                muted: muted.contains(&path),
+                identifier: path,
+                depth: 1,
+                offset,
+                scale: 1.0,
+            }));
+            Ok(layers)
+        }
+    }
+    
+    /// Mute or unmute a layer of a stage's layer stack
+    pub fn set_layer_muted(&mut self, stage_id: &str, layer: &str, muted: bool) -> Result<(), String> {
+        let _stage = self.stages.get(stage_id)
+            .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
+        
+        let muted_layers = self.muted_layers.entry(stage_id.to_string()).or_default();
+        muted_layers.retain(|identifier| identifier != layer);
+        if muted {
+            muted_layers.push(layer.to_string());
+        }
+        
+        let action = if muted { "Muted" } else { "Unmuted" };
+        #[cfg(feature = "usd")]
+        println!("{} layer '{}' on stage '{}'", action, layer, stage_id);
+        #[cfg(not(feature = "usd"))]
+        println!("Mock: {} layer '{}' on stage '{}'", action, layer, stage_id);
+        
+        self.sync_python_stage(stage_id)?;
+        self.mark_stage_dirty(stage_id);
+        Ok(())
+    }
+    
+    /// Move a root layer sublayer to a new position (0 is strongest)
+    pub fn move_sublayer(&mut self, stage_id: &str, from: usize, to: usize) -> Result<(), String> {
+        let mut sublayers = self.get_sublayers(stage_id)?;
+        if from >= sublayers.len() || to >= sublayers.len() {
+            return Err(format!("Sublayer index out of range: {} -> {} of {}", from, to, sublayers.len()));
+        }
+        let layer = sublayers.remove(from);
+        
+        #[cfg(feature = "usd")]
+        println!("Moved sublayer '{}' of '{}' from {} to {}", layer.0, stage_id, from, to);
+        #[cfg(not(feature = "usd"))]
+        println!("Mock: Moved sublayer '{}' of '{}' from {} to {}", layer.0, stage_id, from, to);
+        
+        sublayers.insert(to, layer);
+        self.sublayers.insert(stage_id.to_string(), sublayers);
+        self.sync_python_stage(stage_id)?;
+        self.mark_stage_dirty(stage_id);
+        Ok(())
+    }
+    
+    /// Bookmarked prim paths of a stage, stored in the root layer's customLayerData
+    pub fn get_pinned_prims(&self, stage_id: &str) -> Result<Vec<String>, String> {
+        let stage = self.stages.get(stage_id)
+            .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
+        if let Some(pinned) = self.pinned_prims.get(stage_id) {
+            return Ok(pinned.clone());
+        }
+        
+        #[cfg(feature = "usd")]
+        {
+            profiling::with_gil("get_pinned_prims", |py| -> Result<Vec<String>, String> {
+                self.open_python_stage(py, stage)?
+                    .call_method0("GetRootLayer")
+                    .and_then(|root| root.getattr("customLayerData"))
+                    .and_then(|data| data.call_method1("get", (PINNED_PRIMS_KEY, Vec::<String>::new())))
+                    .and_then(|pinned| pinned.extract())
+                    .map_err(|e| format!("Failed to read pinned prims of '{}': {}", stage.path, e))
+            })
+        }
+        
+        #[cfg(not(feature = "usd"))]
+        {
+            let _ = stage;
+            Ok(Vec::new())
         }
     }
     
+    /// Replace the pinned prim paths of a stage
+    ///
+    /// The list is authored on the root layer, so it is saved with the stage.
+    pub fn set_pinned_prims(&mut self, stage_id: &str, paths: &[String]) -> Result<(), String> {
+        let stage = self.stages.get(stage_id)
+            .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
+        
+        #[cfg(feature = "usd")]
+        profiling::with_gil("set_pinned_prims", |py| -> Result<(), String> {
+            let err = |e: PyErr| format!("Failed to write pinned prims of '{}': {}", stage.path, e);
+            let root = self.open_python_stage(py, stage)?
+                .call_method0("GetRootLayer")
+                .map_err(err)?;
+            let data = root.getattr("customLayerData").map_err(err)?;
+            if paths.is_empty() {
+                data.call_method1("pop", (PINNED_PRIMS_KEY, py.None())).map_err(err)?;
+            } else {
+                data.set_item(PINNED_PRIMS_KEY, paths.to_vec()).map_err(err)?;
+            }
+            root.setattr("customLayerData", data).map_err(err)?;
+            Ok(())
+        })?;
+        
+        #[cfg(not(feature = "usd"))]
+        println!("Mock: Pinned {} prims on stage '{}'", paths.len(), stage.path);
+        
+        self.pinned_prims.insert(stage_id.to_string(), paths.to_vec());
+        Ok(())
+    }
+    
     /// Add a reference to external USD asset
     pub fn add_reference(&mut self, stage_id: &str, prim_path: &str, asset_path: &str, prim_target: Option<&str>) -> Result<String, String> {
         #[cfg(feature = "usd")]
@@ -712,7 +3520,7 @@ impl USDEngine {
             let _stage = self.stages.get(stage_id)
                 .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
                 
-            Python::with_gil(|py| -> Result<String, String> {
+            profiling::with_gil("add_reference", |py| -> Result<String, String> {
                 let _usd = py.import("pxr.Usd").map_err(|e| format!("Failed to import Usd: {}", e))?;
                 
                 // Create reference prim
@@ -761,7 +3569,7 @@ impl USDEngine {
             let _stage = self.stages.get(stage_id)
                 .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
                 
-            Python::with_gil(|py| -> Result<String, String> {
+            profiling::with_gil("add_payload", |py| -> Result<String, String> {
                 let _usd = py.import("pxr.Usd").map_err(|e| format!("Failed to import Usd: {}", e))?;
                 
                 // Create payload prim
@@ -803,21 +3611,186 @@ impl USDEngine {
         }
     }
     
-    /// Get list of all prims for a stage
-    pub fn list_prims(&self, stage_id: &str) -> Vec<String> {
-        self.prims.iter()
-            .filter(|(key, _)| key.starts_with(&format!("{}:", stage_id)))
-            .map(|(_, prim)| prim.path.clone())
-            .collect()
-    }
-
-    /// Create a new USD stage and save to file
-    pub fn create_stage_to_file(&mut self, identifier: &str, file_path: &str) -> Result<USDStage, String> {
-        let stage = self.create_stage(identifier)?;
-        println!("Created USD stage '{}' and saved to file: {}", identifier, file_path);
+    /// Reference a MaterialX document at `prim_path`, returning the paths of its materials
+    ///
+    /// UsdMtlx composes the document's materials under `<prim_path>/Materials`.
+    /// Any earlier reference on the prim is replaced.
+    pub fn reference_materialx(&mut self, stage_id: &str, prim_path: &str, file_path: &str) -> Result<Vec<String>, String> {
+        let stage = self.stages.get(stage_id)
+            .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
+        if !prim_path.starts_with('/') {
+            return Err(format!("Prim path '{}' is not absolute", prim_path));
+        }
+        
+        #[cfg(feature = "usd")]
+        let materials = {
+            let materials = profiling::with_gil("reference_materialx", |py| -> Result<Vec<String>, String> {
+                let helpers = PyModule::from_code(py, MATERIALX_HELPERS, c"nodle_materialx.py", c"nodle_materialx")
+                    .map_err(|e| format!("Failed to load MaterialX helpers: {}", e))?;
+                let py_stage = self.open_python_stage(py, stage)?;
+                helpers.call_method1("reference_document", (py_stage, prim_path, file_path))
+                    .and_then(|materials| materials.extract())
+                    .map_err(|e| format!("Failed to reference '{}': {}", file_path, e))
+            })?;
+            println!("Referenced MaterialX document '{}' at '{}' ({} materials)", file_path, prim_path, materials.len());
+            materials
+        };
+        
+        #[cfg(not(feature = "usd"))]
+        let materials = {
+            let _ = stage;
+            let text = std::fs::read_to_string(file_path)
+                .map_err(|e| format!("Failed to read '{}': {}", file_path, e))?;
+            let document = parse_document(&text)?;
+            let scope = format!("{}/Materials", prim_path);
+            let materials: Vec<String> = document.materials()
+                .map(|material| format!("{}/{}", scope, material.name))
+                .collect();
+            let prims = [(prim_path, "Scope"), (scope.as_str(), "Scope")].into_iter()
+                .chain(materials.iter().map(|path| (path.as_str(), "Material")));
+            for (path, prim_type) in prims {
+                self.prims.insert(format!("{}:{}", stage_id, path), USDPrim {
+                    path: path.to_string(),
+                    prim_type: prim_type.to_string(),
+                    stage_id: stage_id.to_string(),
+                });
+            }
+            println!("Mock: Referenced MaterialX document '{}' at '{}' ({} materials)", file_path, prim_path, materials.len());
+            materials
+        };
+        
+        self.mark_stage_dirty(stage_id);
+        Ok(materials)
+    }
+    
+    /// Load the assembly helper module
+    #[cfg(feature = "usd")]
+    fn assembly_helpers(py: Python<'_>) -> Result<Bound<'_, PyModule>, String> {
+        PyModule::from_code(py, ASSEMBLY_HELPERS, c"nodle_assembly.py", c"nodle_assembly")
+            .map_err(|e| format!("Failed to load assembly helpers: {}", e))
+    }
+    
+    /// Build an in-memory stage referencing each input under its own Xform below `root_path`
+    ///
+    /// Inputs reference their stage's default prim (or first root prim). Stages
+    /// with session layer edits are referenced through a flattened copy, so the
+    /// assembly has to be rebuilt when an input changes. Any previous stage with
+    /// the same identifier is replaced; the assembled Xform paths are returned.
+    pub fn assemble_stage(&mut self, identifier: &str, root_path: &str, inputs: &[USDAssemblyInput]) -> Result<(USDStage, Vec<String>), String> {
+        let root_path = root_path.trim_end_matches('/');
+        if !root_path.starts_with('/') {
+            return Err(format!("Assembly root '{}' is not an absolute prim path", root_path));
+        }
+        let mut names = std::collections::HashSet::new();
+        if let Some(input) = inputs.iter().find(|input| !names.insert(input.name.as_str())) {
+            return Err(format!("Assembly input name '{}' is used more than once", input.name));
+        }
+        let sources = inputs.iter()
+            .map(|input| self.stages.get(&input.stage_id).cloned().ok_or_else(|| format!("Stage '{}' not found", input.stage_id)))
+            .collect::<Result<Vec<_>, String>>()?;
+        
+        // Start from an empty stage so removed inputs don't linger
+        let prefix = format!("{}:", identifier);
+        self.prims.retain(|key, _| !key.starts_with(&prefix));
+        self.attributes.retain(|key, _| !key.starts_with(&prefix));
+        self.time_samples.retain(|key, _| !key.starts_with(&prefix));
+        let stage = self.create_stage(identifier)?;
+        let paths: Vec<String> = inputs.iter().map(|input| format!("{}/{}", root_path, input.name)).collect();
+        
+        #[cfg(feature = "usd")]
+        {
+            let flattened = profiling::with_gil("assemble_stage", |py| -> Result<Vec<Py<PyAny>>, String> {
+                let err = |e: PyErr| format!("Failed to assemble stage '{}': {}", identifier, e);
+                let helpers = Self::assembly_helpers(py)?;
+                let mut flattened = Vec::new();
+                let mut entries = Vec::new();
+                for (input, source) in inputs.iter().zip(&sources) {
+                    let py_source = self.open_python_stage(py, source)?;
+                    let (layer, prim_path, is_flattened): (Bound<PyAny>, String, bool) = helpers.call_method1("reference_source", (py_source,))
+                        .and_then(|result| result.extract())
+                        .map_err(|e| format!("Failed to reference '{}': {}", source.path, e))?;
+                    let asset: String = layer.getattr("identifier").and_then(|id| id.extract()).map_err(err)?;
+                    entries.push((input.name.clone(), asset, prim_path));
+                    if is_flattened {
+                        flattened.push(layer.unbind());
+                    }
+                }
+                let py_stage = self.open_python_stage(py, &stage)?;
+                helpers.call_method1("assemble", (py_stage, root_path, entries)).map_err(err)?;
+                Ok(flattened)
+            })?;
+            self.retained_layers.extend(flattened);
+            println!("Assembled {} inputs under '{}' in stage '{}'", inputs.len(), root_path, identifier);
+        }
+        
+        #[cfg(not(feature = "usd"))]
+        {
+            let mut ancestors: Vec<&str> = root_path.match_indices('/').map(|(index, _)| &root_path[..index]).skip(1).collect();
+            ancestors.push(root_path);
+            for path in ancestors.into_iter().chain(paths.iter().map(String::as_str)) {
+                self.prims.insert(format!("{}{}", prefix, path), USDPrim {
+                    path: path.to_string(),
+                    prim_type: "Xform".to_string(),
+                    stage_id: identifier.to_string(),
+                });
+            }
+            // Show each input's first root prim composed under its Xform
+            for (source, path) in sources.iter().zip(&paths) {
+                let source_prims: Vec<USDPrim> = self.prims.values()
+                    .filter(|prim| prim.stage_id == source.identifier)
+                    .cloned()
+                    .collect();
+                let Some(target) = source_prims.iter()
+                    .map(|prim| prim.path.as_str())
+                    .filter(|prim_path| prim_path.matches('/').count() == 1)
+                    .min() else {
+                    continue;
+                };
+                let target = target.to_string();
+                for prim in &source_prims {
+                    if let Some(rest) = prim.path.strip_prefix(&target).filter(|rest| rest.starts_with('/')) {
+                        let composed = format!("{}{}", path, rest);
+                        self.prims.insert(format!("{}{}", prefix, composed), USDPrim {
+                            path: composed,
+                            prim_type: prim.prim_type.clone(),
+                            stage_id: identifier.to_string(),
+                        });
+                    }
+                }
+            }
+            println!("Mock: Assembled {} inputs under '{}' in stage '{}'", inputs.len(), root_path, identifier);
+        }
+        
+        // Transform offsets, leaving identity ops unauthored
+        for (input, path) in inputs.iter().zip(&paths) {
+            let ops = [(XFORM_OP_ORDER[0], input.translate, [0.0; 3]), (XFORM_OP_ORDER[1], input.rotate, [0.0; 3]), (XFORM_OP_ORDER[2], input.scale, [1.0; 3])];
+            for (op_name, value, identity) in ops {
+                if value != identity {
+                    self.add_xform_op(identifier, path, op_name)?;
+                    self.set_attribute(identifier, path, op_name, UsdValue::Vec3(value))?;
+                }
+            }
+        }
+        
+        self.mark_stage_dirty(identifier);
+        Ok((stage, paths))
+    }
+    
+    /// Get list of all prims for a stage
+    pub fn list_prims(&self, stage_id: &str) -> Vec<String> {
+        self.prims.iter()
+            .filter(|(key, _)| key.starts_with(&format!("{}:", stage_id)))
+            .map(|(_, prim)| prim.path.clone())
+            .collect()
+    }
+    
+    /// Create a new USD stage and save to file
+    pub fn create_stage_to_file(&mut self, identifier: &str, file_path: &str) -> Result<USDStage, String> {
+        let stage = self.create_stage(identifier)?;
+        println!("Created USD stage '{}' and saved to file: {}", identifier, file_path);
         Ok(stage)
     }
-
+    
     /// Set the default prim for a stage
     pub fn set_default_prim(&mut self, stage_id: &str, prim_path: &str) -> Result<(), String> {
         let _stage = self.stages.get(stage_id)
@@ -825,23 +3798,17 @@ impl USDEngine {
         println!("Set default prim for stage '{}' to '{}'", stage_id, prim_path);
         Ok(())
     }
-
+    
     /// Set the purpose of a prim
     pub fn set_prim_purpose(&mut self, stage_id: &str, prim_path: &str, purpose: &str) -> Result<(), String> {
-        let _stage = self.stages.get(stage_id)
-            .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
-        println!("Set purpose of prim '{}' in stage '{}' to '{}'", prim_path, stage_id, purpose);
-        Ok(())
+        self.set_attribute(stage_id, prim_path, "purpose", UsdValue::Token(purpose.to_string()))
     }
-
+    
     /// Set the visibility of a prim
     pub fn set_prim_visibility(&mut self, stage_id: &str, prim_path: &str, visibility: &str) -> Result<(), String> {
-        let _stage = self.stages.get(stage_id)
-            .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
-        println!("Set visibility of prim '{}' in stage '{}' to '{}'", prim_path, stage_id, visibility);
-        Ok(())
+        self.set_attribute(stage_id, prim_path, "visibility", UsdValue::Token(visibility.to_string()))
     }
-
+    
     /// Create a USD Cylinder primitive
     pub fn create_cylinder(&mut self, stage_id: &str, prim_path: &str, radius: f64, height: f64) -> Result<USDPrim, String> {
         let _stage = self.stages.get(stage_id)
@@ -865,6 +3832,18 @@ impl USDEngine {
         self.stages.get(stage_id)
     }
     
+    /// Resolve a stage reference from a node port (identifier or file path), loading the file if needed
+    pub fn resolve_stage(&mut self, stage_ref: &str) -> Result<USDStage, String> {
+        if let Some(stage) = self.stages.get(stage_ref) {
+            return Ok(stage.clone());
+        }
+        if let Some(stage) = self.stages.values().find(|stage| stage.path == stage_ref) {
+            stage_loader::discard(stage_ref);
+            return Ok(stage.clone());
+        }
+        self.load_stage(stage_ref)
+    }
+    
     /// Get all stage identifiers
     pub fn get_stage_ids(&self) -> Vec<String> {
         self.stages.keys().cloned().collect()
@@ -876,6 +3855,1432 @@ impl USDEngine {
             .filter(|prim| prim.stage_id == stage_id)
             .collect()
     }
+    
+    /// Load the bounds helper module
+    #[cfg(feature = "usd")]
+    fn bounds_helpers(py: Python<'_>) -> Result<Bound<'_, PyModule>, String> {
+        PyModule::from_code(py, BOUNDS_HELPERS, c"nodle_bounds.py", c"nodle_bounds")
+            .map_err(|e| format!("Failed to load bounds helpers: {}", e))
+    }
+    
+    /// World and local bounds of a prim and its descendants at a time code; "/" bounds the whole stage
+    ///
+    /// Bounds cover the default, render and proxy purposes and use authored
+    /// extents hints. Results are cached until the stage is next edited.
+    pub fn compute_bounds(&mut self, stage_id: &str, prim_path: &str, time: f64) -> Result<USDBounds, String> {
+        let stage = self.stages.get(stage_id)
+            .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
+        let revision = self.revisions.get(stage_id).copied().unwrap_or(0);
+        let key = format!("{}:{}@{}", stage_id, prim_path, time);
+        if let Some((cached_revision, bounds)) = self.bounds_cache.get(&key) {
+            if *cached_revision == revision {
+                return Ok(*bounds);
+            }
+        }
+        
+        #[cfg(feature = "usd")]
+        let bounds = profiling::with_gil("compute_bounds", |py| -> Result<USDBounds, String> {
+            let py_stage = self.open_python_stage(py, stage)?;
+            let (world, local): (Option<Vec<f64>>, Option<Vec<f64>>) = Self::bounds_helpers(py)?
+                .call_method1("prim_bounds", (py_stage, prim_path, time))
+                .and_then(|bounds| bounds.extract())
+                .map_err(|e| format!("Failed to compute bounds of '{}': {}", prim_path, e))?;
+            let to_box = |range: Option<Vec<f64>>| range.and_then(|range| BoundingBox::from_extent(&range)).unwrap_or_default();
+            Ok(USDBounds { world: to_box(world), local: to_box(local) })
+        })?;
+        
+        #[cfg(not(feature = "usd"))]
+        let bounds = {
+            let _ = stage;
+            let root = prim_path.trim_end_matches('/');
+            if !root.is_empty() && !self.prims.contains_key(&format!("{}:{}", stage_id, root)) {
+                return Err(format!("Prim '{}' not found", prim_path));
+            }
+            let descendant = format!("{}/", root);
+            let to_prim = self.mock_world_transform(stage_id, root, time).inverse();
+            let mut bounds = USDBounds::default();
+            for prim in self.get_stage_prims(stage_id) {
+                if prim.path != root && !prim.path.starts_with(&descendant) {
+                    continue;
+                }
+                let extent = self.evaluate_at_time(stage_id, &prim.path, "extent", time).ok()
+                    .and_then(|value| value.split(',').map(|c| c.trim_matches(['[', ']', '(', ')', ' ']).parse().ok()).collect::<Option<Vec<f64>>>())
+                    .and_then(|values| BoundingBox::from_extent(&values));
+                if let Some(extent) = extent {
+                    let world = self.mock_world_transform(stage_id, &prim.path, time);
+                    bounds.world = bounds.world.union(extent.transformed(&world));
+                    bounds.local = bounds.local.union(extent.transformed(&(to_prim * world)));
+                }
+            }
+            bounds
+        };
+        
+        let prefix = format!("{}:", stage_id);
+        self.bounds_cache.retain(|key, (cached_revision, _)| !key.starts_with(&prefix) || *cached_revision == revision);
+        self.bounds_cache.insert(key, (revision, bounds));
+        Ok(bounds)
+    }
+    
+    /// World bounds of everything on a stage at a time code
+    pub fn stage_bounds(&mut self, stage_id: &str, time: f64) -> Result<BoundingBox, String> {
+        self.compute_bounds(stage_id, "/", time).map(|bounds| bounds.world)
+    }
+    
+    /// Models drawn as stand-ins at a time code, from UsdGeomModelAPI
+    ///
+    /// A model is included when model:applyDrawMode is set and its drawMode,
+    /// inherited from the nearest ancestor authoring one, isn't "default".
+    /// Models under an included model are left out.
+    pub fn get_draw_modes(&mut self, stage_id: &str, time: f64) -> Result<Vec<USDDrawMode>, String> {
+        let stage = self.stages.get(stage_id)
+            .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
+        
+        #[cfg(feature = "usd")]
+        {
+            type Found = (String, String, Option<Vec<f32>>, String, Vec<Option<String>>, Option<Vec<f64>>, Vec<Vec<f64>>);
+            profiling::with_gil("get_draw_modes", |py| -> Result<Vec<USDDrawMode>, String> {
+                let py_stage = self.open_python_stage(py, stage)?;
+                let found: Vec<Found> = Self::bounds_helpers(py)?
+                    .call_method1("draw_modes", (py_stage, time, CARD_FACES.to_vec()))
+                    .and_then(|found| found.extract())
+                    .map_err(|e| format!("Failed to read draw modes: {}", e))?;
+                Ok(found.into_iter().map(|(prim_path, mode, color, card_geometry, textures, bounds, rows)| {
+                    // USD matrices are row-major with row vectors, so rows are glam's columns
+                    let mut columns = [[0.0f32; 4]; 4];
+                    for (r, row) in rows.iter().take(4).enumerate() {
+                        for (c, value) in row.iter().take(4).enumerate() {
+                            columns[r][c] = *value as f32;
+                        }
+                    }
+                    USDDrawMode {
+                        prim_path,
+                        mode,
+                        color: color.filter(|c| c.len() == 3).map_or(DEFAULT_DRAW_MODE_COLOR, |c| [c[0], c[1], c[2]]),
+                        card_geometry,
+                        card_textures: std::array::from_fn(|face| textures.get(face).cloned().flatten()),
+                        bounds: bounds.and_then(|range| BoundingBox::from_extent(&range)).unwrap_or_default(),
+                        transform: Mat4::from_cols_array_2d(&columns),
+                    }
+                }).collect())
+            })
+        }
+        
+        #[cfg(not(feature = "usd"))]
+        {
+            let _ = stage;
+            let read = |path: &str, attr: &str| self.evaluate_at_time(stage_id, path, attr, time).ok();
+            let mut applied: Vec<String> = self.get_stage_prims(stage_id)
+                .into_iter()
+                .filter(|prim| read(&prim.path, "model:applyDrawMode").is_some_and(|value| value == "True"))
+                .map(|prim| prim.path.clone())
+                .collect();
+            applied.sort();
+            
+            let mut found: Vec<(String, String, [f32; 3], String, [Option<String>; 6])> = Vec::new();
+            for path in applied {
+                if found.iter().any(|(outer, ..)| path.starts_with(&format!("{}/", outer))) {
+                    continue;
+                }
+                let mut ancestors = std::iter::successors(Some(path.as_str()), |current| {
+                    current.rsplit_once('/').map(|(parent, _)| parent).filter(|parent| !parent.is_empty())
+                });
+                let mode = ancestors.find_map(|ancestor| read(ancestor, "model:drawMode").filter(|mode| mode != "inherited"))
+                    .unwrap_or_else(|| "default".to_string());
+                if mode == "default" {
+                    continue;
+                }
+                let color = read(&path, "model:drawModeColor")
+                    .and_then(|value| parse_numeric_value(&value))
+                    .filter(|c| c.len() == 3)
+                    .map_or(DEFAULT_DRAW_MODE_COLOR, |c| [c[0] as f32, c[1] as f32, c[2] as f32]);
+                let card_geometry = read(&path, "model:cardGeometry").unwrap_or_else(|| "cross".to_string());
+                let card_textures = CARD_FACES.map(|face| {
+                    read(&path, &format!("model:cardTexture{}", face)).map(|asset| asset.trim_matches('@').to_string())
+                });
+                found.push((path, mode, color, card_geometry, card_textures));
+            }
+            
+            found.into_iter().map(|(prim_path, mode, color, card_geometry, card_textures)| {
+                Ok(USDDrawMode {
+                    bounds: self.compute_bounds(stage_id, &prim_path, time)?.local,
+                    transform: self.mock_world_transform(stage_id, &prim_path, time),
+                    prim_path,
+                    mode,
+                    color,
+                    card_geometry,
+                    card_textures,
+                })
+            }).collect()
+        }
+    }
+    
+    /// Author a model's draw mode in the edit target, applying it unless it's "default"
+    ///
+    /// Only model prims, with a kind of component, group or assembly, are
+    /// drawn with their draw mode.
+    pub fn set_draw_mode(&mut self, stage_id: &str, prim_path: &str, mode: &str) -> Result<(), String> {
+        if !["default", "origin", "bounds", "cards", "inherited"].contains(&mode) {
+            return Err(format!("Unknown draw mode '{}'", mode));
+        }
+        self.set_attribute(stage_id, prim_path, "model:drawMode", UsdValue::Token(mode.to_string()))?;
+        self.set_attribute(stage_id, prim_path, "model:applyDrawMode", UsdValue::Bool(mode != "default"))
+    }
+    
+    /// Points of a prim and its descendants in the prim's space at a time code
+    ///
+    /// Point-based prims contribute their points and other boundable prims
+    /// the corners of their extent. Guide prims and everything under them are
+    /// skipped, so proxies authored under the prim don't feed back into it.
+    pub fn get_prim_points(&self, stage_id: &str, prim_path: &str, time: f64) -> Result<Vec<[f64; 3]>, String> {
+        let stage = self.stages.get(stage_id)
+            .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
+        
+        #[cfg(feature = "usd")]
+        {
+            profiling::with_gil("get_prim_points", |py| -> Result<Vec<[f64; 3]>, String> {
+                let py_stage = self.open_python_stage(py, stage)?;
+                Self::bounds_helpers(py)?
+                    .call_method1("prim_points", (py_stage, prim_path, time))
+                    .and_then(|points| points.extract())
+                    .map_err(|e| format!("Failed to read points of '{}': {}", prim_path, e))
+            })
+        }
+        
+        #[cfg(not(feature = "usd"))]
+        {
+            let _ = stage;
+            let root = prim_path.trim_end_matches('/');
+            if !self.prims.contains_key(&format!("{}:{}", stage_id, root)) {
+                return Err(format!("Prim '{}' not found", prim_path));
+            }
+            let to_root = self.mock_world_transform(stage_id, root, time).inverse();
+            let mut guides: Vec<String> = Vec::new();
+            let mut points = Vec::new();
+            for prim in self.get_stage_prims(stage_id) {
+                let under = |parent: &str| prim.path == parent || prim.path.starts_with(&format!("{}/", parent));
+                if !under(root) || guides.iter().any(|guide| under(guide)) {
+                    continue;
+                }
+                if self.evaluate_at_time(stage_id, &prim.path, "purpose", time).is_ok_and(|purpose| purpose.trim_matches('"') == "guide") {
+                    guides.push(prim.path.clone());
+                    continue;
+                }
+                let local: Vec<Vec3> = match self.evaluate_at_time(stage_id, &prim.path, "points", time) {
+                    Ok(text) => match UsdValue::parse(&text, "point3f[]")? {
+                        UsdValue::Array(items) => items.iter()
+                            .filter_map(|item| match item {
+                                UsdValue::Vec3([x, y, z]) => Some(Vec3::new(*x as f32, *y as f32, *z as f32)),
+                                _ => None,
+                            })
+                            .collect(),
+                        _ => Vec::new(),
+                    },
+                    Err(_) => self.evaluate_at_time(stage_id, &prim.path, "extent", time).ok()
+                        .and_then(|value| value.split(',').map(|c| c.trim_matches(['[', ']', '(', ')', ' ']).parse().ok()).collect::<Option<Vec<f64>>>())
+                        .and_then(|values| BoundingBox::from_extent(&values))
+                        .map_or_else(Vec::new, |extent| extent.corners().to_vec()),
+                };
+                let to_prim = to_root * self.mock_world_transform(stage_id, &prim.path, time);
+                points.extend(local.into_iter().map(|point| to_prim.transform_point3(point).as_dvec3().to_array()));
+            }
+            Ok(points)
+        }
+    }
+    
+    /// Apply API schemas to a prim in the edit target, such as "PhysicsCollisionAPI"
+    pub fn apply_api_schemas(&mut self, stage_id: &str, prim_path: &str, schemas: &[&str]) -> Result<(), String> {
+        let stage = self.stages.get(stage_id)
+            .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
+        
+        #[cfg(feature = "usd")]
+        profiling::with_gil("apply_api_schemas", |py| -> Result<(), String> {
+            let err = |e: PyErr| format!("Failed to apply API schemas to '{}': {}", prim_path, e);
+            let prim = self.open_python_stage(py, stage)?
+                .call_method1("GetPrimAtPath", (prim_path,))
+                .map_err(err)?;
+            for schema in schemas {
+                prim.call_method1("AddAppliedSchema", (*schema,)).map_err(err)?;
+            }
+            Ok(())
+        })?;
+        
+        // Mirror the list so the mock backend can report it
+        #[cfg(not(feature = "usd"))]
+        {
+            let _ = stage;
+            if !self.prims.contains_key(&format!("{}:{}", stage_id, prim_path)) {
+                return Err(format!("Prim '{}' not found", prim_path));
+            }
+            let key = format!("{}:{}.apiSchemas", stage_id, prim_path);
+            let mut applied = self.attributes.get(&key).map(|value| parse_list(value)).unwrap_or_default();
+            for schema in schemas {
+                if !applied.iter().any(|applied| applied == schema) {
+                    applied.push(schema.to_string());
+                }
+            }
+            self.attributes.insert(key, format_list(applied));
+        }
+        
+        self.mark_stage_dirty(stage_id);
+        Ok(())
+    }
+    
+    /// Author a bounding volume mesh under its prim, returning the mesh path
+    pub fn set_bounding_volume(&mut self, stage_id: &str, volume: &USDBoundingVolume) -> Result<String, String> {
+        if volume.name.is_empty() || volume.name.starts_with(|c: char| c.is_ascii_digit())
+            || volume.name.contains(|c: char| !c.is_ascii_alphanumeric() && c != '_') {
+            return Err(format!("Invalid volume name '{}'", volume.name));
+        }
+        let (prims, edits) = volume.edits();
+        self.create_prims_bulk(stage_id, &prims)?;
+        self.set_attributes_bulk(stage_id, &edits)?;
+        let path = volume.path();
+        if volume.collision {
+            self.apply_api_schemas(stage_id, &path, &API_SCHEMAS)?;
+        }
+        
+        self.mark_stage_dirty(stage_id);
+        Ok(path)
+    }
+    
+    /// Load the HLOD helper module
+    #[cfg(feature = "usd")]
+    fn hlod_helpers(py: Python<'_>) -> Result<Bound<'_, PyModule>, String> {
+        PyModule::from_code(py, HLOD_HELPERS, c"nodle_hlod.py", c"nodle_hlod")
+            .map_err(|e| format!("Failed to load HLOD helpers: {}", e))
+    }
+    
+    /// Meshes of a prim and its descendants with their points in world space at a time code
+    ///
+    /// Guide prims and everything under them are skipped.
+    pub fn get_world_meshes(&self, stage_id: &str, prim_path: &str, time: f64) -> Result<Vec<SourceMesh>, String> {
+        let stage = self.stages.get(stage_id)
+            .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
+        
+        #[cfg(feature = "usd")]
+        {
+            type PyMesh = (String, Vec<[f64; 3]>, Vec<i32>, Vec<i32>);
+            profiling::with_gil("get_world_meshes", |py| -> Result<Vec<SourceMesh>, String> {
+                let py_stage = self.open_python_stage(py, stage)?;
+                let meshes: Vec<PyMesh> = Self::hlod_helpers(py)?
+                    .call_method1("world_meshes", (py_stage, prim_path, time))
+                    .and_then(|meshes| meshes.extract())
+                    .map_err(|e| format!("Failed to read meshes under '{}': {}", prim_path, e))?;
+                Ok(meshes.into_iter()
+                    .map(|(prim_path, points, face_vertex_counts, face_vertex_indices)| SourceMesh { prim_path, points, face_vertex_counts, face_vertex_indices })
+                    .collect())
+            })
+        }
+        
+        #[cfg(not(feature = "usd"))]
+        {
+            let _ = stage;
+            let root = prim_path.trim_end_matches('/');
+            if !self.prims.contains_key(&format!("{}:{}", stage_id, root)) {
+                return Err(format!("Prim '{}' not found", prim_path));
+            }
+            let ints = |prim: &str, attr: &str| -> Vec<i32> {
+                self.evaluate_at_time(stage_id, prim, attr, time).ok()
+                    .map(|value| parse_list(&value).iter().filter_map(|item| item.parse().ok()).collect())
+                    .unwrap_or_default()
+            };
+            let mut guides: Vec<String> = Vec::new();
+            let mut meshes = Vec::new();
+            for prim in self.get_stage_prims(stage_id) {
+                let under = |parent: &str| prim.path == parent || prim.path.starts_with(&format!("{}/", parent));
+                if !under(root) || guides.iter().any(|guide| under(guide)) {
+                    continue;
+                }
+                if self.evaluate_at_time(stage_id, &prim.path, "purpose", time).is_ok_and(|purpose| purpose.trim_matches('"') == "guide") {
+                    guides.push(prim.path.clone());
+                    continue;
+                }
+                if prim.prim_type != "Mesh" {
+                    continue;
+                }
+                let to_world = self.mock_world_transform(stage_id, &prim.path, time);
+                let points = match self.evaluate_at_time(stage_id, &prim.path, "points", time) {
+                    Ok(text) => match UsdValue::parse(&text, "point3f[]")? {
+                        UsdValue::Array(items) => items.iter()
+                            .filter_map(|item| match item {
+                                UsdValue::Vec3([x, y, z]) => Some(to_world.transform_point3(Vec3::new(*x as f32, *y as f32, *z as f32)).as_dvec3().to_array()),
+                                _ => None,
+                            })
+                            .collect(),
+                        _ => Vec::new(),
+                    },
+                    Err(_) => Vec::new(),
+                };
+                meshes.push(SourceMesh {
+                    prim_path: prim.path.clone(),
+                    points,
+                    face_vertex_counts: ints(&prim.path, "faceVertexCounts"),
+                    face_vertex_indices: ints(&prim.path, "faceVertexIndices"),
+                });
+            }
+            Ok(meshes)
+        }
+    }
+    
+    /// Author HLOD proxies and the "lod" variant set switching to them on their root, returning the proxy paths
+    ///
+    /// Each variant hides the other representation through visibility, so
+    /// visibility authored locally on the meshes overrides the switch. The
+    /// full-resolution variant stays selected.
+    pub fn set_hlod(&mut self, stage_id: &str, hlod: &USDHlod) -> Result<Vec<String>, String> {
+        if hlod.name.is_empty() || hlod.name.starts_with(|c: char| c.is_ascii_digit())
+            || hlod.name.contains(|c: char| !c.is_ascii_alphanumeric() && c != '_') {
+            return Err(format!("Invalid HLOD name '{}'", hlod.name));
+        }
+        let (prims, edits) = hlod.edits();
+        self.create_prims_bulk(stage_id, &prims)?;
+        self.set_attributes_bulk(stage_id, &edits)?;
+        
+        #[cfg(feature = "usd")]
+        {
+            let stage = self.stages.get(stage_id)
+                .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
+            profiling::with_gil("set_hlod", |py| -> Result<(), String> {
+                let py_stage = self.open_python_stage(py, stage)?;
+                Self::hlod_helpers(py)?
+                    .call_method1("author_lod_variants", (py_stage, hlod.root_path.as_str(), VARIANT_SET, hlod.variants(), FULL_VARIANT))
+                    .map_err(|e| format!("Failed to author LOD variants on '{}': {}", hlod.root_path, e))?;
+                Ok(())
+            })?;
+            println!("Authored {} HLOD proxies under '{}'", hlod.clusters.len(), hlod.path());
+        }
+        
+        #[cfg(not(feature = "usd"))]
+        println!("Mock: Authored {} HLOD proxies under '{}'", hlod.clusters.len(), hlod.path());
+        
+        let target = self.get_edit_target(stage_id);
+        self.variant_selections.insert(format!("{}:{}{{{}}}", stage_id, hlod.root_path, VARIANT_SET), (target, FULL_VARIANT.to_string()));
+        self.mark_stage_dirty(stage_id);
+        Ok((0..hlod.clusters.len()).map(|index| hlod.cluster_path(index)).collect())
+    }
+    
+    /// Load the camera helper module
+    #[cfg(feature = "usd")]
+    fn camera_helpers(py: Python<'_>) -> Result<Bound<'_, PyModule>, String> {
+        PyModule::from_code(py, CAMERA_HELPERS, c"nodle_camera.py", c"nodle_camera")
+            .map_err(|e| format!("Failed to load camera helpers: {}", e))
+    }
+    
+    /// A prim's translate, rotateXYZ and scale ops at a time code, unauthored ops at their identity
+    pub fn get_xform_ops(&self, stage_id: &str, prim_path: &str, time: f64) -> Result<USDXformOps, String> {
+        let stage = self.stages.get(stage_id)
+            .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
+        let defaults = [Vec3::ZERO, Vec3::ZERO, Vec3::ONE];
+        
+        #[cfg(feature = "usd")]
+        {
+            type PyXformOps = (Vec<Option<Vec<f64>>>, Vec<Vec<f64>>);
+            profiling::with_gil("get_xform_ops", |py| -> Result<USDXformOps, String> {
+                let py_stage = self.open_python_stage(py, stage)?;
+                let (values, rows): PyXformOps = PyModule::from_code(py, XFORM_OP_HELPERS, c"nodle_xform_ops.py", c"nodle_xform_ops")
+                    .and_then(|helpers| helpers.call_method1("xform_ops", (py_stage, prim_path, time, XFORM_OP_ORDER.to_vec())))
+                    .and_then(|ops| ops.extract())
+                    .map_err(|e| format!("Failed to read the transform of '{}': {}", prim_path, e))?;
+                let op = |index: usize| values.get(index).cloned().flatten()
+                    .filter(|v| v.len() == 3)
+                    .map_or(defaults[index], |v| Vec3::new(v[0] as f32, v[1] as f32, v[2] as f32));
+                // USD matrices are row-major with row vectors, so rows are glam's columns
+                let mut columns = [[0.0f32; 4]; 4];
+                for (r, row) in rows.iter().take(4).enumerate() {
+                    for (c, value) in row.iter().take(4).enumerate() {
+                        columns[r][c] = *value as f32;
+                    }
+                }
+                Ok(USDXformOps {
+                    translate: op(0),
+                    rotate: op(1),
+                    scale: op(2),
+                    parent_to_world: Mat4::from_cols_array_2d(&columns),
+                })
+            })
+        }
+        
+        #[cfg(not(feature = "usd"))]
+        {
+            let _ = stage;
+            if !self.prims.contains_key(&format!("{}:{}", stage_id, prim_path)) {
+                return Err(format!("Prim '{}' not found", prim_path));
+            }
+            let op = |index: usize| self.evaluate_at_time(stage_id, prim_path, XFORM_OP_ORDER[index], time).ok()
+                .and_then(|value| parse_numeric_value(&value))
+                .filter(|v| v.len() == 3)
+                .map_or(defaults[index], |v| Vec3::new(v[0] as f32, v[1] as f32, v[2] as f32));
+            let parent_path = prim_path.rsplit_once('/').map_or("", |(parent, _)| parent);
+            Ok(USDXformOps {
+                translate: op(0),
+                rotate: op(1),
+                scale: op(2),
+                parent_to_world: self.mock_world_transform(stage_id, parent_path, time),
+            })
+        }
+    }
+    
+    /// Author a prim's translate, rotateXYZ and scale ops, adding missing ops to xformOpOrder
+    ///
+    /// Ops keyed through the engine get a time sample at `time`, others a
+    /// default value, in the stage's edit target, the session layer unless changed.
+    pub fn set_xform_ops(&mut self, stage_id: &str, prim_path: &str, ops: &USDXformOps, time: f64) -> Result<(), String> {
+        for (op_name, value) in XFORM_OP_ORDER.iter().zip([ops.translate, ops.rotate, ops.scale]) {
+            self.add_xform_op(stage_id, prim_path, op_name)?;
+            let value = UsdValue::Vec3(value.as_dvec3().to_array());
+            if self.time_samples.contains_key(&format!("{}:{}.{}", stage_id, prim_path, op_name)) {
+                self.set_attribute_at_time(stage_id, prim_path, op_name, value, time)?;
+            } else {
+                self.set_attribute(stage_id, prim_path, op_name, value)?;
+            }
+        }
+        self.mark_stage_dirty(stage_id);
+        Ok(())
+    }
+    
+    /// A camera prim's world transform, lens and clipping range at a time code
+    pub fn get_camera(&self, stage_id: &str, camera_path: &str, time: f64) -> Result<ProjectionCamera, String> {
+        let stage = self.stages.get(stage_id)
+            .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
+        
+        #[cfg(feature = "usd")]
+        {
+            type PyCamera = (Vec<Vec<f64>>, f32, f32, f32, f32, f32);
+            profiling::with_gil("get_camera", |py| -> Result<ProjectionCamera, String> {
+                let py_stage = self.open_python_stage(py, stage)?;
+                let (rows, focal_length, horizontal_aperture, vertical_aperture, near, far): PyCamera = Self::camera_helpers(py)?
+                    .call_method1("camera_view", (py_stage, camera_path, time))
+                    .and_then(|view| view.extract())
+                    .map_err(|e| format!("Failed to read camera '{}': {}", camera_path, e))?;
+                // USD matrices are row-major with row vectors, so rows are glam's columns
+                let mut columns = [[0.0f32; 4]; 4];
+                for (r, row) in rows.iter().take(4).enumerate() {
+                    for (c, value) in row.iter().take(4).enumerate() {
+                        columns[r][c] = *value as f32;
+                    }
+                }
+                Ok(ProjectionCamera {
+                    world: Mat4::from_cols_array_2d(&columns),
+                    focal_length,
+                    horizontal_aperture,
+                    vertical_aperture,
+                    near,
+                    far,
+                })
+            })
+        }
+        
+        #[cfg(not(feature = "usd"))]
+        {
+            let _ = stage;
+            let prim = self.prims.get(&format!("{}:{}", stage_id, camera_path))
+                .ok_or_else(|| format!("Prim '{}' not found", camera_path))?;
+            if prim.prim_type != "Camera" {
+                return Err(format!("'{}' is not a camera", camera_path));
+            }
+            let read = |attr: &str| self.evaluate_at_time(stage_id, camera_path, attr, time).ok()
+                .and_then(|value| parse_numeric_value(&value));
+            let defaults = ProjectionCamera::default();
+            let scalar = |attr: &str, default: f32| read(attr).and_then(|v| v.first().copied()).map_or(default, |v| v as f32);
+            let clipping = read("clippingRange").filter(|v| v.len() == 2);
+            Ok(ProjectionCamera {
+                world: self.mock_world_transform(stage_id, camera_path, time),
+                focal_length: scalar("focalLength", defaults.focal_length),
+                horizontal_aperture: scalar("horizontalAperture", defaults.horizontal_aperture),
+                vertical_aperture: scalar("verticalAperture", defaults.vertical_aperture),
+                near: clipping.as_ref().map_or(defaults.near, |c| c[0] as f32),
+                far: clipping.as_ref().map_or(defaults.far, |c| c[1] as f32),
+            })
+        }
+    }
+    
+    /// Author a camera visibility bake on its meshes
+    pub fn set_visibility_bake(&mut self, stage_id: &str, bake: &USDVisibilityBake) -> Result<(), String> {
+        self.set_attributes_bulk(stage_id, &bake.edits())?;
+        self.mark_stage_dirty(stage_id);
+        Ok(())
+    }
+    
+    /// Local-to-world transform from the translate, rotateXYZ and scale ops of a prim and its ancestors
+    #[cfg(not(feature = "usd"))]
+    fn mock_world_transform(&self, stage_id: &str, prim_path: &str, time: f64) -> Mat4 {
+        let read = |path: &str, op: &str, default: Vec3| self.evaluate_at_time(stage_id, path, op, time).ok()
+            .and_then(|value| parse_numeric_value(&value))
+            .filter(|v| v.len() == 3)
+            .map_or(default, |v| Vec3::new(v[0] as f32, v[1] as f32, v[2] as f32));
+        let mut transform = Mat4::IDENTITY;
+        let mut path = String::new();
+        for name in prim_path.split('/').filter(|name| !name.is_empty()) {
+            path.push('/');
+            path.push_str(name);
+            transform *= xform_ops_to_mat4(
+                read(&path, XFORM_OP_ORDER[0], Vec3::ZERO),
+                read(&path, XFORM_OP_ORDER[1], Vec3::ZERO),
+                read(&path, XFORM_OP_ORDER[2], Vec3::ONE),
+            );
+        }
+        transform
+    }
+    
+    /// Composed prim hierarchy of a stage with active, visibility and kind state, sorted by path
+    pub fn get_prim_hierarchy(&self, stage_id: &str) -> Result<Vec<USDPrimStatus>, String> {
+        let stage = self.stages.get(stage_id)
+            .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
+        
+        #[cfg(feature = "usd")]
+        let mut statuses = profiling::with_gil("get_prim_hierarchy", |py| -> Result<Vec<USDPrimStatus>, String> {
+            let py_stage = self.open_python_stage(py, stage)?;
+            let rows: Vec<(String, String, bool, bool, String)> = PyModule::from_code(py, PRIM_STATUS_HELPERS, c"nodle_prim_status.py", c"nodle_prim_status")
+                .and_then(|helpers| helpers.call_method1("prim_statuses", (py_stage,)))
+                .and_then(|rows| rows.extract())
+                .map_err(|e| format!("Failed to read the prim hierarchy of '{}': {}", stage.path, e))?;
+            Ok(rows.into_iter()
+                .map(|(path, prim_type, active, visible, kind)| USDPrimStatus { path, prim_type, active, visible, kind })
+                .collect())
+        })?;
+        
+        #[cfg(not(feature = "usd"))]
+        let mut statuses: Vec<USDPrimStatus> = {
+            let _ = stage;
+            let attribute = |path: &str, name: &str| self.attributes.get(&format!("{}:{}.{}", stage_id, path, name));
+            let invisible: Vec<&str> = self.get_stage_prims(stage_id).into_iter()
+                .filter(|prim| attribute(&prim.path, "visibility").is_some_and(|value| value == "invisible"))
+                .map(|prim| prim.path.as_str())
+                .collect();
+            self.get_stage_prims(stage_id).into_iter()
+                .map(|prim| USDPrimStatus {
+                    path: prim.path.clone(),
+                    prim_type: prim.prim_type.clone(),
+                    active: attribute(&prim.path, "active").is_none_or(|value| value != "False"),
+                    visible: !invisible.iter().any(|root| prim.path == *root || prim.path.starts_with(&format!("{}/", root))),
+                    kind: attribute(&prim.path, "kind").cloned().unwrap_or_default(),
+                })
+                .collect()
+        };
+        
+        statuses.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(statuses)
+    }
+    
+    /// Composition arcs authored in a stage's layer stack, sorted by prim path
+    ///
+    /// Arcs a prim only inherits from an ancestor's arcs are left out.
+    pub fn get_composition_arcs(&self, stage_id: &str) -> Result<Vec<USDCompositionArc>, String> {
+        let stage = self.stages.get(stage_id)
+            .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
+        
+        #[cfg(feature = "usd")]
+        let mut arcs = profiling::with_gil("get_composition_arcs", |py| -> Result<Vec<USDCompositionArc>, String> {
+            let py_stage = self.open_python_stage(py, stage)?;
+            let rows: Vec<(String, String, String, String)> = PyModule::from_code(py, COMPOSITION_ARC_HELPERS, c"nodle_composition_arcs.py", c"nodle_composition_arcs")
+                .and_then(|helpers| helpers.call_method1("composition_arcs", (py_stage,)))
+                .and_then(|rows| rows.extract())
+                .map_err(|e| format!("Failed to read the composition arcs of '{}': {}", stage.path, e))?;
+            Ok(rows.into_iter()
+                .map(|(prim_path, kind, asset, target)| USDCompositionArc { prim_path, kind, asset, target })
+                .collect())
+        })?;
+        
+        // The mock engine only records variant selections
+        #[cfg(not(feature = "usd"))]
+        let mut arcs: Vec<USDCompositionArc> = {
+            let _ = stage;
+            let prefix = format!("{}:", stage_id);
+            self.variant_selections.iter()
+                .filter_map(|(key, (_, variant))| {
+                    let (prim_path, set) = key.strip_prefix(&prefix)?.strip_suffix('}')?.split_once('{')?;
+                    Some(USDCompositionArc {
+                        prim_path: prim_path.to_string(),
+                        kind: "variant".to_string(),
+                        asset: String::new(),
+                        target: format!("{}={}", set, variant),
+                    })
+                })
+                .collect()
+        };
+        
+        arcs.sort_by(|a, b| a.prim_path.cmp(&b.prim_path));
+        Ok(arcs)
+    }
+    
+    /// Attributes, relationships and authored metadata of a prim, attributes resolved at `time`
+    ///
+    /// Without a time, attributes resolve at the default time code.
+    pub fn get_prim_properties(&self, stage_id: &str, prim_path: &str, time: Option<f64>) -> Result<Vec<USDProperty>, String> {
+        let stage = self.stages.get(stage_id)
+            .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
+        
+        #[cfg(feature = "usd")]
+        {
+            profiling::with_gil("get_prim_properties", |py| -> Result<Vec<USDProperty>, String> {
+                let py_stage = self.open_python_stage(py, stage)?;
+                let rows: Vec<(String, String, String, String, bool)> = PyModule::from_code(py, PRIM_PROPERTY_HELPERS, c"nodle_prim_properties.py", c"nodle_prim_properties")
+                    .and_then(|helpers| helpers.call_method1("prim_properties", (py_stage, prim_path, time)))
+                    .and_then(|rows| rows.extract())
+                    .map_err(|e| format!("Failed to read properties of '{}': {}", prim_path, e))?;
+                Ok(rows.into_iter()
+                    .map(|(kind, name, type_name, value, animated)| USDProperty {
+                        kind: match kind.as_str() {
+                            "relationship" => USDPropertyKind::Relationship,
+                            "metadata" => USDPropertyKind::Metadata,
+                            _ => USDPropertyKind::Attribute,
+                        },
+                        name,
+                        type_name,
+                        value,
+                        animated,
+                    })
+                    .collect())
+            })
+        }
+        
+        #[cfg(not(feature = "usd"))]
+        {
+            let _ = stage;
+            let prim = self.prims.get(&format!("{}:{}", stage_id, prim_path))
+                .ok_or_else(|| format!("Prim '{}' not found", prim_path))?;
+            let prefix = format!("{}:{}.", stage_id, prim_path);
+            let mut names: Vec<&str> = self.attributes.keys()
+                .chain(self.time_samples.keys())
+                .filter_map(|key| key.strip_prefix(&prefix))
+                .collect();
+            names.sort_unstable();
+            names.dedup();
+            
+            // The mock backend keeps prim metadata alongside attribute values
+            const METADATA: [&str; 4] = ["active", "kind", "inactiveIds", "documentation"];
+            let mut properties = vec![USDProperty {
+                name: "typeName".to_string(),
+                kind: USDPropertyKind::Metadata,
+                type_name: "str".to_string(),
+                value: prim.prim_type.clone(),
+                animated: false,
+            }];
+            for name in names {
+                let value = match time {
+                    Some(time) => self.evaluate_at_time(stage_id, prim_path, name, time)?,
+                    None => self.get_attribute(stage_id, prim_path, name)?,
+                };
+                let (kind, type_name) = match name {
+                    "prototypes" => (USDPropertyKind::Relationship, "rel".to_string()),
+                    _ if value.starts_with("[<") => (USDPropertyKind::Relationship, "rel".to_string()),
+                    _ if METADATA.contains(&name) => (USDPropertyKind::Metadata, "str".to_string()),
+                    _ => (USDPropertyKind::Attribute, UsdValue::infer(&value).type_name()),
+                };
+                properties.push(USDProperty {
+                    name: name.to_string(),
+                    kind,
+                    type_name,
+                    animated: self.is_animated(stage_id, prim_path, name),
+                    value,
+                });
+            }
+            Ok(properties)
+        }
+    }
+    
+    /// Activate or deactivate a prim in the stage's edit target
+    pub fn set_prim_active(&mut self, stage_id: &str, prim_path: &str, active: bool) -> Result<(), String> {
+        let stage = self.stages.get(stage_id)
+            .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
+        
+        #[cfg(feature = "usd")]
+        {
+            profiling::with_gil("set_prim_active", |py| -> Result<(), String> {
+                let err = |e: PyErr| format!("Failed to set '{}' active: {}", prim_path, e);
+                let prim = self.open_python_stage(py, stage)?
+                    .call_method1("GetPrimAtPath", (prim_path,))
+                    .map_err(err)?;
+                if !prim.call_method0("IsValid").and_then(|valid| valid.extract::<bool>()).map_err(err)? {
+                    return Err(format!("Prim '{}' not found", prim_path));
+                }
+                prim.call_method1("SetActive", (active,)).map_err(err)?;
+                Ok(())
+            })?;
+            println!("Set '{}' active={} in the {} layer", prim_path, active, self.get_edit_target(stage_id).name());
+        }
+        
+        #[cfg(not(feature = "usd"))]
+        {
+            let _ = stage;
+            println!("Mock: Set '{}' active={} in the {} layer", prim_path, active, self.get_edit_target(stage_id).name());
+            self.attributes.insert(format!("{}:{}.active", stage_id, prim_path), if active { "True" } else { "False" }.to_string());
+        }
+        
+        self.mark_stage_dirty(stage_id);
+        Ok(())
+    }
+    
+    /// Remove every opinion from the stage's edit target layer
+    pub fn clear_stage(&mut self, stage_id: &str) -> Result<(), String> {
+        let stage = self.stages.get(stage_id)
+            .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
+        
+        #[cfg(feature = "usd")]
+        {
+            profiling::with_gil("clear_stage", |py| -> Result<(), String> {
+                self.open_python_stage(py, stage)?
+                    .call_method0("GetEditTarget")
+                    .and_then(|target| target.call_method0("GetLayer"))
+                    .and_then(|layer| layer.call_method0("Clear"))
+                    .map_err(|e| format!("Failed to clear stage '{}': {}", stage_id, e))?;
+                Ok(())
+            })?;
+            println!("Cleared the {} layer of '{}'", self.get_edit_target(stage_id).name(), stage_id);
+        }
+        
+        #[cfg(not(feature = "usd"))]
+        {
+            let _ = stage;
+            println!("Mock: Cleared the {} layer of '{}'", self.get_edit_target(stage_id).name(), stage_id);
+        }
+        
+        let prefix = format!("{}:", stage_id);
+        self.prims.retain(|key, _| !key.starts_with(&prefix));
+        self.attributes.retain(|key, _| !key.starts_with(&prefix));
+        self.time_samples.retain(|key, _| !key.starts_with(&prefix));
+        self.mark_stage_dirty(stage_id);
+        Ok(())
+    }
+    
+    /// Author documentation metadata on a prim, defining it as a Scope if it doesn't exist
+    pub fn set_prim_documentation(&mut self, stage_id: &str, prim_path: &str, documentation: &str) -> Result<(), String> {
+        let stage = self.stages.get(stage_id)
+            .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
+        
+        #[cfg(feature = "usd")]
+        {
+            profiling::with_gil("set_prim_documentation", |py| -> Result<(), String> {
+                let err = |e: PyErr| format!("Failed to document '{}': {}", prim_path, e);
+                let py_stage = self.open_python_stage(py, stage)?;
+                let mut prim = py_stage.call_method1("GetPrimAtPath", (prim_path,)).map_err(err)?;
+                if !prim.call_method0("IsValid").and_then(|valid| valid.extract::<bool>()).map_err(err)? {
+                    prim = py.import("pxr.UsdGeom")
+                        .and_then(|usd_geom| usd_geom.getattr("Scope"))
+                        .and_then(|scope| scope.call_method1("Define", (&py_stage, prim_path)))
+                        .and_then(|scope| scope.call_method0("GetPrim"))
+                        .map_err(err)?;
+                }
+                prim.call_method1("SetDocumentation", (documentation,)).map_err(err)?;
+                Ok(())
+            })?;
+            println!("Documented '{}' in the {} layer", prim_path, self.get_edit_target(stage_id).name());
+        }
+        
+        #[cfg(not(feature = "usd"))]
+        {
+            let _ = stage;
+            self.prims.entry(format!("{}:{}", stage_id, prim_path)).or_insert_with(|| USDPrim {
+                path: prim_path.to_string(),
+                prim_type: "Scope".to_string(),
+                stage_id: stage_id.to_string(),
+            });
+            println!("Mock: Documented '{}' in the {} layer", prim_path, self.get_edit_target(stage_id).name());
+            self.attributes.insert(format!("{}:{}.documentation", stage_id, prim_path), documentation.to_string());
+        }
+        
+        self.mark_stage_dirty(stage_id);
+        Ok(())
+    }
+    
+    /// Load the collection helper module
+    #[cfg(feature = "usd")]
+    fn collection_helpers(py: Python<'_>) -> Result<Bound<'_, PyModule>, String> {
+        PyModule::from_code(py, COLLECTION_HELPERS, c"nodle_collections.py", c"nodle_collections")
+            .map_err(|e| format!("Failed to load collection helpers: {}", e))
+    }
+    
+    /// Apply a UsdCollectionAPI collection to a prim and author its includes, excludes and expansion rule
+    pub fn set_collection(&mut self, stage_id: &str, collection: &USDCollection) -> Result<(), String> {
+        let stage = self.stages.get(stage_id)
+            .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
+        if collection.name.is_empty() || collection.name.contains(|c: char| !c.is_ascii_alphanumeric() && c != '_') {
+            return Err(format!("Invalid collection name '{}'", collection.name));
+        }
+        if let Some(relative) = collection.includes.iter().chain(&collection.excludes).find(|path| !path.starts_with('/')) {
+            return Err(format!("Collection path '{}' is not absolute", relative));
+        }
+        
+        #[cfg(feature = "usd")]
+        {
+            profiling::with_gil("set_collection", |py| -> Result<(), String> {
+                let py_stage = self.open_python_stage(py, stage)?;
+                Self::collection_helpers(py)?
+                    .call_method1("set_collection", (
+                        py_stage,
+                        collection.prim_path.as_str(),
+                        collection.name.as_str(),
+                        collection.includes.clone(),
+                        collection.excludes.clone(),
+                        collection.expansion.token(),
+                        collection.include_root,
+                    ))
+                    .map_err(|e| format!("Failed to author collection '{}': {}", collection.path(), e))?;
+                Ok(())
+            })?;
+            println!("Authored collection '{}' ({} includes, {} excludes)", collection.path(), collection.includes.len(), collection.excludes.len());
+        }
+        
+        #[cfg(not(feature = "usd"))]
+        {
+            let _ = stage;
+            if !self.prims.contains_key(&format!("{}:{}", stage_id, collection.prim_path)) {
+                return Err(format!("Prim '{}' not found", collection.prim_path));
+            }
+            println!("Mock: Authored collection '{}' ({} includes, {} excludes)", collection.path(), collection.includes.len(), collection.excludes.len());
+            let key = |property: &str| format!("{}:{}.collection:{}:{}", stage_id, collection.prim_path, collection.name, property);
+            self.attributes.insert(key("includes"), format_list(collection.includes.iter().map(|path| format!("<{}>", path))));
+            self.attributes.insert(key("excludes"), format_list(collection.excludes.iter().map(|path| format!("<{}>", path))));
+            self.attributes.insert(key("expansionRule"), collection.expansion.token().to_string());
+            self.attributes.insert(key("includeRoot"), collection.include_root.to_string());
+        }
+        
+        self.mark_stage_dirty(stage_id);
+        Ok(())
+    }
+    
+    /// Paths included in a collection, given its path ("/Prim.collection:name"), sorted
+    pub fn get_collection_members(&self, stage_id: &str, collection_path: &str) -> Result<Vec<String>, String> {
+        let stage = self.stages.get(stage_id)
+            .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
+        let (prim_path, name) = split_collection_path(collection_path)
+            .ok_or_else(|| format!("'{}' is not a collection path", collection_path))?;
+        
+        #[cfg(feature = "usd")]
+        {
+            let _ = (prim_path, name);
+            profiling::with_gil("get_collection_members", |py| -> Result<Vec<String>, String> {
+                let py_stage = self.open_python_stage(py, stage)?;
+                Self::collection_helpers(py)?
+                    .call_method1("members", (py_stage, collection_path.trim()))
+                    .and_then(|members| members.extract())
+                    .map_err(|e| format!("Failed to resolve collection '{}': {}", collection_path, e))
+            })
+        }
+        
+        #[cfg(not(feature = "usd"))]
+        {
+            let _ = stage;
+            let key = |property: &str| format!("{}:{}.collection:{}:{}", stage_id, prim_path, name, property);
+            let expansion = self.attributes.get(&key("expansionRule"))
+                .and_then(|token| CollectionExpansion::from_token(token))
+                .ok_or_else(|| format!("'{}' is not a collection", collection_path))?;
+            let targets = |property: &str| -> Vec<String> {
+                self.attributes.get(&key(property))
+                    .map(|value| parse_list(value).into_iter().map(|path| path.trim_matches(['<', '>']).to_string()).collect())
+                    .unwrap_or_default()
+            };
+            let mut includes = targets("includes");
+            if self.attributes.get(&key("includeRoot")).is_some_and(|include_root| include_root == "true") {
+                includes.push("/".to_string());
+            }
+            let prim_paths: Vec<&str> = self.get_stage_prims(stage_id).into_iter().map(|prim| prim.path.as_str()).collect();
+            Ok(collection_membership(&prim_paths, &includes, &targets("excludes"), expansion))
+        }
+    }
+    
+    /// Define a UsdRenderPass under `RENDER_PASS_ROOT` with its collections, returning the pass path
+    ///
+    /// A camera also defines the pass's UsdRenderSettings prim and points the
+    /// pass's renderSource at it.
+    pub fn set_render_pass(&mut self, stage_id: &str, render_pass: &USDRenderPass) -> Result<String, String> {
+        let stage = self.stages.get(stage_id)
+            .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
+        if render_pass.name.is_empty() || render_pass.name.contains(|c: char| !c.is_ascii_alphanumeric() && c != '_') {
+            return Err(format!("Invalid render pass name '{}'", render_pass.name));
+        }
+        let (pass_path, settings_path) = (render_pass.path(), render_pass.settings_path());
+        let camera = render_pass.camera.as_deref().filter(|camera| !camera.is_empty());
+        
+        #[cfg(feature = "usd")]
+        profiling::with_gil("set_render_pass", |py| -> Result<(), String> {
+            let helpers = PyModule::from_code(py, RENDER_PASS_HELPERS, c"nodle_render_passes.py", c"nodle_render_passes")
+                .map_err(|e| format!("Failed to load render pass helpers: {}", e))?;
+            let py_stage = self.open_python_stage(py, stage)?;
+            helpers.call_method1("define_pass", (py_stage, pass_path.as_str(), settings_path.as_str(), camera.unwrap_or_default()))
+                .map_err(|e| format!("Failed to define render pass '{}': {}", pass_path, e))?;
+            Ok(())
+        })?;
+        
+        #[cfg(not(feature = "usd"))]
+        {
+            let _ = stage;
+            let mut prims = vec![("/Render", "Scope"), (RENDER_PASS_ROOT, "Scope"), (pass_path.as_str(), "RenderPass")];
+            if camera.is_some() {
+                prims.extend([("/Render/Settings", "Scope"), (settings_path.as_str(), "RenderSettings")]);
+            }
+            for (path, prim_type) in prims {
+                self.prims.insert(format!("{}:{}", stage_id, path), USDPrim {
+                    path: path.to_string(),
+                    prim_type: prim_type.to_string(),
+                    stage_id: stage_id.to_string(),
+                });
+            }
+            let source = format!("{}:{}.renderSource", stage_id, pass_path);
+            match camera {
+                Some(camera) => {
+                    self.attributes.insert(format!("{}:{}.camera", stage_id, settings_path), format_list([format!("<{}>", camera)]));
+                    self.attributes.insert(source, format_list([format!("<{}>", settings_path)]));
+                }
+                None => {
+                    self.attributes.remove(&source);
+                }
+            }
+            println!("Mock: Defined render pass '{}'", pass_path);
+        }
+        
+        for collection in render_pass.collections() {
+            self.set_collection(stage_id, &collection)?;
+        }
+        Ok(pass_path)
+    }
+    
+    /// Author a light rig, returning its light paths
+    ///
+    /// Missing ancestors of the rig root are defined as Xforms. Lights left
+    /// under the root by a previous preset are deactivated rather than
+    /// removed, so opinions in weaker layers stay intact.
+    pub fn set_light_rig(&mut self, stage_id: &str, rig: &USDLightRig) -> Result<Vec<String>, String> {
+        self.set_light_group(stage_id, &rig.root_path, rig.edits(), rig.light_paths())
+    }
+    
+    /// Author a sun and sky, returning the sun and dome light paths
+    ///
+    /// The sky texture is referenced as authored; write it with
+    /// `USDSunSky::write_sky_texture` first.
+    pub fn set_sun_sky(&mut self, stage_id: &str, sun_sky: &USDSunSky) -> Result<Vec<String>, String> {
+        self.set_light_group(stage_id, &sun_sky.root_path, sun_sky.edits(), sun_sky.light_paths())
+    }
+    
+    /// Author lights under a root Xform, deactivating other children of the root
+    fn set_light_group(
+        &mut self,
+        stage_id: &str,
+        root_path: &str,
+        (mut prims, edits): (Vec<USDPrimSpec>, Vec<USDAttributeEdit>),
+        light_paths: Vec<String>,
+    ) -> Result<Vec<String>, String> {
+        if !root_path.starts_with('/') || root_path.ends_with('/') {
+            return Err(format!("Light root '{}' is not an absolute prim path", root_path));
+        }
+        let hierarchy = self.get_prim_hierarchy(stage_id)?;
+        
+        let ancestors = root_path.match_indices('/').map(|(index, _)| &root_path[..index]).skip(1);
+        let missing: Vec<USDPrimSpec> = ancestors
+            .filter(|ancestor| !hierarchy.iter().any(|prim| prim.path == *ancestor))
+            .map(|ancestor| USDPrimSpec { path: ancestor.to_string(), prim_type: "Xform".to_string() })
+            .collect();
+        prims.splice(0..0, missing);
+        self.create_prims_bulk(stage_id, &prims)?;
+        self.set_attributes_bulk(stage_id, &edits)?;
+        
+        let child_prefix = format!("{}/", root_path);
+        for prim in &hierarchy {
+            let is_child = prim.path.strip_prefix(&child_prefix).is_some_and(|name| !name.contains('/'));
+            let in_group = light_paths.contains(&prim.path);
+            if is_child && prim.active != in_group {
+                self.set_prim_active(stage_id, &prim.path, in_group)?;
+            }
+        }
+        
+        self.mark_stage_dirty(stage_id);
+        Ok(light_paths)
+    }
+    
+    /// Define a face subset of a mesh, returning the subset path
+    ///
+    /// The subset's material, if any, is bound with the subset as the
+    /// binding prim, so it only applies to the subset's faces.
+    pub fn set_geom_subset(&mut self, stage_id: &str, subset: &USDGeomSubset) -> Result<String, String> {
+        if subset.name.is_empty() || subset.name.starts_with(|c: char| c.is_ascii_digit())
+            || subset.name.contains(|c: char| !c.is_ascii_alphanumeric() && c != '_') {
+            return Err(format!("Invalid subset name '{}'", subset.name));
+        }
+        if !subset.mesh_path.starts_with('/') {
+            return Err(format!("Mesh path '{}' is not absolute", subset.mesh_path));
+        }
+        if subset.faces.is_empty() {
+            return Err(format!("Subset '{}' has no faces", subset.name));
+        }
+        
+        let (prims, edits) = subset.edits();
+        self.create_prims_bulk(stage_id, &prims)?;
+        self.set_attributes_bulk(stage_id, &edits)?;
+        let path = subset.path();
+        if let Some(material) = subset.material.as_deref().filter(|material| !material.is_empty()) {
+            self.edit_relationship_targets(stage_id, &path, "material:binding", &[material.to_string()], RelationshipEdit::Set)?;
+        }
+        
+        self.mark_stage_dirty(stage_id);
+        Ok(path)
+    }
+    
+    /// Write a face subset as a standalone mesh named after it, returning the written path
+    ///
+    /// Points are baked to world space. Only points and topology are
+    /// written; primvars and bindings stay on the source mesh.
+    pub fn export_geom_subset(&mut self, stage_id: &str, subset_path: &str, file_path: &str) -> Result<String, String> {
+        let stage = self.stages.get(stage_id)
+            .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
+        let (path, format, format_arg) = export_target(file_path, None)?;
+        if format == "usdz" {
+            return Err("Subsets export as usda or usdc; package the exported layer to get a usdz".to_string());
+        }
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create directory '{}': {}", parent.display(), e))?;
+        }
+        let path = path.to_string_lossy().into_owned();
+        let name = subset_path.rsplit('/').next().unwrap_or_default();
+        
+        #[cfg(feature = "usd")]
+        {
+            profiling::with_gil("export_geom_subset", |py| -> Result<(), String> {
+                let err = |e: PyErr| format!("Failed to export subset '{}': {}", subset_path, e);
+                let helpers = PyModule::from_code(py, GEOM_SUBSET_HELPERS, c"nodle_geom_subsets.py", c"nodle_geom_subsets")
+                    .map_err(|e| format!("Failed to load subset helpers: {}", e))?;
+                let py_stage = self.open_python_stage(py, stage)?;
+                let (points, counts, indices, faces, scheme, up_axis): (Vec<[f64; 3]>, Vec<i32>, Vec<i32>, Vec<u32>, String, String) =
+                    helpers.call_method1("subset_mesh", (py_stage, subset_path))
+                        .and_then(|mesh| mesh.extract())
+                        .map_err(err)?;
+                let mesh = extract_faces(points.len(), &counts, &indices, &faces)?;
+                let points: Vec<[f64; 3]> = mesh.points.iter().map(|&point| points[point]).collect();
+                let written: bool = helpers.call_method1("write_mesh", (
+                        path.as_str(),
+                        format_arg.unwrap_or_default(),
+                        name,
+                        points,
+                        mesh.face_vertex_counts,
+                        mesh.face_vertex_indices,
+                        scheme,
+                        up_axis,
+                    ))
+                    .and_then(|result| result.extract())
+                    .map_err(err)?;
+                if !written {
+                    return Err(format!("Failed to write subset '{}' to '{}'", subset_path, path));
+                }
+                Ok(())
+            })?;
+            println!("Exported subset '{}' to '{}' as {}", subset_path, path, format);
+        }
+        
+        #[cfg(not(feature = "usd"))]
+        {
+            let _ = (stage, format_arg, name);
+            if !self.prims.contains_key(&format!("{}:{}", stage_id, subset_path)) {
+                return Err(format!("Subset '{}' not found", subset_path));
+            }
+            println!("Mock: Exported subset '{}' to '{}' as {}", subset_path, path, format);
+        }
+        
+        Ok(path)
+    }
+    
+    /// Load the relationship helper module
+    #[cfg(feature = "usd")]
+    fn relationship_helpers(py: Python<'_>) -> Result<Bound<'_, PyModule>, String> {
+        PyModule::from_code(py, RELATIONSHIP_HELPERS, c"nodle_relationships.py", c"nodle_relationships")
+            .map_err(|e| format!("Failed to load relationship helpers: {}", e))
+    }
+    
+    /// Target paths of a relationship; empty if the relationship isn't authored
+    pub fn get_relationship_targets(&self, stage_id: &str, prim_path: &str, rel_name: &str) -> Result<Vec<String>, String> {
+        let stage = self.stages.get(stage_id)
+            .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
+        
+        #[cfg(feature = "usd")]
+        {
+            profiling::with_gil("get_relationship_targets", |py| -> Result<Vec<String>, String> {
+                let py_stage = self.open_python_stage(py, stage)?;
+                Self::relationship_helpers(py)?
+                    .call_method1("targets", (py_stage, prim_path, rel_name))
+                    .and_then(|targets| targets.extract())
+                    .map_err(|e| format!("Failed to read '{}.{}': {}", prim_path, rel_name, e))
+            })
+        }
+        
+        #[cfg(not(feature = "usd"))]
+        {
+            let _ = stage;
+            if !self.prims.contains_key(&format!("{}:{}", stage_id, prim_path)) {
+                return Err(format!("Prim '{}' not found", prim_path));
+            }
+            Ok(self.attributes.get(&format!("{}:{}.{}", stage_id, prim_path, rel_name))
+                .map(|value| parse_list(value).into_iter().map(|path| path.trim_matches(['<', '>']).to_string()).collect())
+                .unwrap_or_default())
+        }
+    }
+    
+    /// Set, append or remove relationship targets in the edit target, creating the relationship if needed
+    ///
+    /// Targets are prim or property paths. Editing a `material:binding`
+    /// relationship applies the MaterialBindingAPI to the prim.
+    pub fn edit_relationship_targets(&mut self, stage_id: &str, prim_path: &str, rel_name: &str, targets: &[String], edit: RelationshipEdit) -> Result<(), String> {
+        let stage = self.stages.get(stage_id)
+            .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
+        if rel_name.is_empty() {
+            return Err("No relationship name set".to_string());
+        }
+        if let Some(relative) = targets.iter().find(|target| !target.starts_with('/')) {
+            return Err(format!("Target '{}' is not an absolute path", relative));
+        }
+        
+        #[cfg(feature = "usd")]
+        {
+            profiling::with_gil("edit_relationship_targets", |py| -> Result<(), String> {
+                let py_stage = self.open_python_stage(py, stage)?;
+                Self::relationship_helpers(py)?
+                    .call_method1("edit_targets", (py_stage, prim_path, rel_name, targets.to_vec(), edit.name()))
+                    .map_err(|e| format!("Failed to edit '{}.{}': {}", prim_path, rel_name, e))?;
+                Ok(())
+            })?;
+            println!("{} {} targets on '{}.{}' in the {} layer", edit.name(), targets.len(), prim_path, rel_name, self.get_edit_target(stage_id).name());
+        }
+        
+        #[cfg(not(feature = "usd"))]
+        {
+            let _ = stage;
+            let mut current = self.get_relationship_targets(stage_id, prim_path, rel_name)?;
+            match edit {
+                RelationshipEdit::Set => current = targets.to_vec(),
+                RelationshipEdit::Append => current.extend(targets.iter().filter(|target| !current.contains(target)).cloned().collect::<Vec<_>>()),
+                RelationshipEdit::Remove => current.retain(|target| !targets.contains(target)),
+            }
+            println!("Mock: {} {} targets on '{}.{}' in the {} layer", edit.name(), targets.len(), prim_path, rel_name, self.get_edit_target(stage_id).name());
+            self.attributes.insert(format!("{}:{}.{}", stage_id, prim_path, rel_name),
+                                   format_list(current.iter().map(|path| format!("<{}>", path))));
+        }
+        
+        self.mark_stage_dirty(stage_id);
+        Ok(())
+    }
+}
+
+/// Output path, file format and Sdf "format" argument for writing a stage
+///
+/// A ".usd" file keeps its extension and carries the format as an argument;
+/// any other extension is replaced to match the requested format.
+fn export_target(file_path: &str, format: Option<&str>) -> Result<(std::path::PathBuf, &'static str, Option<&'static str>), String> {
+    const FORMATS: [&str; 3] = ["usda", "usdc", "usdz"];
+    let file_path = file_path.trim();
+    if file_path.is_empty() {
+        return Err("No output file path set".to_string());
+    }
+    let path = std::path::PathBuf::from(file_path);
+    let extension = path.extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+    
+    let requested = format.map(|format| format.trim().to_ascii_lowercase()).filter(|format| !format.is_empty());
+    let Some(requested) = requested else {
+        if extension == "usd" {
+            return Ok((path, "usdc", None));
+        }
+        return match FORMATS.iter().find(|format| **format == extension) {
+            Some(format) => Ok((path, format, None)),
+            None => Err(format!("Cannot infer a USD format from '{}'", file_path)),
+        };
+    };
+    let format = FORMATS.iter()
+        .find(|format| **format == requested)
+        .copied()
+        .ok_or_else(|| format!("Unknown USD format '{}', expected usda, usdc or usdz", requested))?;
+    
+    if extension == format {
+        Ok((path, format, None))
+    } else if extension == "usd" && format != "usdz" {
+        Ok((path, format, Some(format)))
+    } else {
+        Ok((path.with_extension(format), format, None))
+    }
+}
+
+/// Whether two paths name the same file, comparing canonical paths when both exist
+fn same_file(a: &str, b: &str) -> bool {
+    match (std::fs::canonicalize(a), std::fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// Parse a scalar or tuple attribute string like "1.5" or "(1, 2, 3)"
+pub fn parse_numeric_value(value: &str) -> Option<Vec<f64>> {
+    value.trim()
+        .trim_start_matches(['(', '['])
+        .trim_end_matches([')', ']'])
+        .split(',')
+        .map(|component| component.trim().parse::<f64>().ok())
+        .collect()
+}
+
+/// Split list elements of an authored array value like "[1, 2]" or "['a', 'b']"
+///
+/// Only the mock backend reads lists back from authored strings.
+#[cfg_attr(feature = "usd", allow(dead_code))]
+fn parse_list(value: &str) -> Vec<String> {
+    let inner = value.trim().trim_start_matches('[').trim_end_matches(']');
+    inner.split(',')
+        .map(|item| item.trim().trim_matches(['"', '\'']).to_string())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+/// Format items as an authored array value
+fn format_list<T: std::fmt::Display>(items: impl IntoIterator<Item = T>) -> String {
+    format!("[{}]", items.into_iter().map(|item| item.to_string()).collect::<Vec<_>>().join(", "))
+}
+
+/// Prototype list and proto indices after splitting a prototype by variant
+#[derive(Debug, Clone, PartialEq)]
+pub struct VariantSplit {
+    pub prototypes: Vec<String>,
+    pub proto_indices: Vec<i32>,
+    /// Variant prototypes used by at least one instance, as (variant, prim path)
+    pub variants: Vec<(String, String)>,
+}
+
+/// Retarget instances of a prototype to one prototype per variant
+///
+/// Variant prototypes live at `prefix` followed by the variant name; existing
+/// ones are reused so splitting again after the primvar changes is stable.
+/// Instances with an empty choice go back to the source prototype.
+pub fn split_by_variant(
+    prototypes: &[String],
+    proto_indices: &[i32],
+    source: usize,
+    prefix: &str,
+    choices: &[String],
+) -> Result<VariantSplit, String> {
+    if choices.len() != proto_indices.len() {
+        return Err(format!("{} primvar values for {} instances", choices.len(), proto_indices.len()));
+    }
+    
+    let mut split = VariantSplit {
+        prototypes: prototypes.to_vec(),
+        proto_indices: proto_indices.to_vec(),
+        variants: Vec::new(),
+    };
+    let owned = |index: i32| usize::try_from(index).ok()
+        .and_then(|index| prototypes.get(index).map(|path| index == source || path.starts_with(prefix)))
+        .unwrap_or(false);
+    
+    for (proto_index, choice) in split.proto_indices.iter_mut().zip(choices) {
+        if !owned(*proto_index) {
+            continue;
+        }
+        if choice.is_empty() {
+            *proto_index = source as i32;
+            continue;
+        }
+        let name: String = choice.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }).collect();
+        let path = format!("{}{}", prefix, name);
+        let index = match split.prototypes.iter().position(|prototype| *prototype == path) {
+            Some(index) => index,
+            None => {
+                split.prototypes.push(path.clone());
+                split.prototypes.len() - 1
+            }
+        };
+        if !split.variants.iter().any(|(_, existing)| *existing == path) {
+            split.variants.push((choice.clone(), path));
+        }
+        *proto_index = index as i32;
+    }
+    Ok(split)
+}
+
+/// Members of a collection among `paths`, sorted
+///
+/// With expansion, the nearest included or excluded ancestor (or the path
+/// itself) decides membership, so an include below an exclude re-includes.
+fn collection_membership(paths: &[&str], includes: &[String], excludes: &[String], expansion: CollectionExpansion) -> Vec<String> {
+    let covers = |root: &str, path: &str| path == root || (path.starts_with(root) && path[root.len()..].starts_with('/')) || root == "/";
+    let mut members: Vec<String> = paths.iter()
+        .filter(|path| match expansion {
+            CollectionExpansion::ExplicitOnly => includes.iter().any(|include| include == *path) && !excludes.iter().any(|exclude| exclude == *path),
+            _ => {
+                let nearest = |roots: &[String]| roots.iter()
+                    .filter(|root| covers(root, path))
+                    .map(|root| root.len())
+                    .max();
+                match (nearest(includes), nearest(excludes)) {
+                    (Some(include), Some(exclude)) => include > exclude,
+                    (included, _) => included.is_some(),
+                }
+            }
+        })
+        .map(|path| path.to_string())
+        .collect();
+    members.sort();
+    members
+}
+
+/// Time samples for keyframes with the interpolation baked in
+///
+/// Keys are sorted by time; a later key at the same time wins.
+fn bake_keyframes(keys: &[(f64, UsdValue)], interpolation: KeyInterpolation) -> Vec<(f64, UsdValue)> {
+    let mut keys = keys.to_vec();
+    keys.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+    keys.reverse();
+    keys.dedup_by(|(a, _), (b, _)| a == b);
+    keys.reverse();
+    
+    let mut samples = Vec::new();
+    for (index, (time, value)) in keys.iter().enumerate() {
+        samples.push((*time, value.clone()));
+        let Some((next_time, next_value)) = keys.get(index + 1) else {
+            break;
+        };
+        match interpolation {
+            KeyInterpolation::Linear => {}
+            KeyInterpolation::Held => samples.push(((next_time - HELD_KEY_OFFSET).max(*time), value.clone())),
+            KeyInterpolation::Ease => {
+                let mut frame = time.floor() + 1.0;
+                while frame < *next_time {
+                    let alpha = (frame - time) / (next_time - time);
+                    let eased = alpha * alpha * (3.0 - 2.0 * alpha);
+                    // Values that can't be blended hold until the next key
+                    samples.push((frame, value.lerp(next_value, eased).unwrap_or_else(|| value.clone())));
+                    frame += 1.0;
+                }
+            }
+        }
+    }
+    samples
+}
+
+/// Sample a sorted time sample list, holding the ends and lerping numeric values
+fn interpolate_samples(samples: &[(f64, String)], time: f64) -> Option<String> {
+    let (first, last) = (samples.first()?, samples.last()?);
+    if time <= first.0 {
+        return Some(first.1.clone());
+    }
+    if time >= last.0 {
+        return Some(last.1.clone());
+    }
+    
+    let upper = samples.iter().position(|(sample_time, _)| *sample_time > time)?;
+    let (t0, v0) = &samples[upper - 1];
+    let (t1, v1) = &samples[upper];
+    let (Some(a), Some(b)) = (parse_numeric_value(v0), parse_numeric_value(v1)) else {
+        return Some(v0.clone()); // Held interpolation for non-numeric values
+    };
+    if a.len() != b.len() {
+        return Some(v0.clone());
+    }
+    
+    let alpha = (time - t0) / (t1 - t0);
+    let components: Vec<String> = a.iter().zip(&b)
+        .map(|(a, b)| (a + (b - a) * alpha).to_string())
+        .collect();
+    if components.len() == 1 && !v0.trim_start().starts_with('(') {
+        Some(components[0].clone())
+    } else {
+        Some(format!("({})", components.join(", ")))
+    }
 }
 
 impl Default for USDEngine {
@@ -897,6 +5302,98 @@ pub fn with_usd_engine<F, R>(f: F) -> R
 where
     F: FnOnce(&mut USDEngine) -> R,
 {
+    // Background opens finish outside the engine lock, ready for load_stage
+    stage_loader::wait_for_opens();
     let mut engine = USD_ENGINE.lock().unwrap();
     f(&mut engine)
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    
+    #[test]
+    fn export_target_follows_format_and_extension() {
+        assert_eq!(export_target("out/shot.usda", None).unwrap(), (PathBuf::from("out/shot.usda"), "usda", None));
+        assert_eq!(export_target("shot.usd", None).unwrap(), (PathBuf::from("shot.usd"), "usdc", None));
+        assert_eq!(export_target("shot.usd", Some("usda")).unwrap(), (PathBuf::from("shot.usd"), "usda", Some("usda")));
+        assert_eq!(export_target("shot.usda", Some("USDZ")).unwrap(), (PathBuf::from("shot.usdz"), "usdz", None));
+        assert_eq!(export_target("shot", Some("usdc")).unwrap(), (PathBuf::from("shot.usdc"), "usdc", None));
+        assert!(export_target("shot.abc", None).is_err());
+        assert!(export_target("shot.usd", Some("obj")).is_err());
+        assert!(export_target("  ", Some("usda")).is_err());
+    }
+    
+    #[test]
+    fn split_by_variant_retargets_owned_instances() {
+        let prototypes = vec!["/Protos/Tree".to_string(), "/Protos/Rock".to_string()];
+        let prefix = "/Forest/VariantPrototypes/Tree_";
+        let choices: Vec<String> = ["oak", "", "pine", "oak", "birch"].iter().map(|c| c.to_string()).collect();
+        
+        let split = split_by_variant(&prototypes, &[0, 0, 0, 0, 1], 0, prefix, &choices).unwrap();
+        assert_eq!(split.prototypes[2..], ["/Forest/VariantPrototypes/Tree_oak", "/Forest/VariantPrototypes/Tree_pine"]);
+        assert_eq!(split.proto_indices, [2, 0, 3, 2, 1]);
+        assert_eq!(split.variants.len(), 2);
+        
+        // Splitting again reuses variant prototypes and follows changed choices
+        let choices: Vec<String> = ["pine", "oak", "", "oak", "oak"].iter().map(|c| c.to_string()).collect();
+        let again = split_by_variant(&split.prototypes, &split.proto_indices, 0, prefix, &choices).unwrap();
+        assert_eq!(again.prototypes, split.prototypes);
+        assert_eq!(again.proto_indices, [3, 2, 0, 2, 1]);
+        
+        assert!(split_by_variant(&prototypes, &[0, 0], 0, prefix, &choices).is_err());
+        assert_eq!(parse_list("['oak', \"pine\"]"), ["oak", "pine"]);
+        assert!(parse_list("[]").is_empty());
+    }
+    
+    #[test]
+    fn collection_membership_follows_nearest_rule() {
+        let paths = ["/World", "/World/Set", "/World/Set/Table", "/World/Set/Table/Cup", "/World/Setup", "/World/Char"];
+        let includes = vec!["/World/Set".to_string(), "/World/Set/Table/Cup".to_string()];
+        let excludes = vec!["/World/Set/Table".to_string()];
+        
+        assert_eq!(collection_membership(&paths, &includes, &excludes, CollectionExpansion::ExpandPrims),
+                   vec!["/World/Set", "/World/Set/Table/Cup"]);
+        assert_eq!(collection_membership(&paths, &includes, &[], CollectionExpansion::ExplicitOnly),
+                   vec!["/World/Set", "/World/Set/Table/Cup"]);
+        assert_eq!(collection_membership(&paths, &["/".to_string()], &excludes, CollectionExpansion::ExpandPrims).len(), 4);
+        
+        assert_eq!(split_collection_path("/Lights/Key.collection:lightLink"), Some(("/Lights/Key", "lightLink")));
+        assert_eq!(split_collection_path("/Lights/Key"), None);
+    }
+    
+    #[test]
+    fn keyframes_bake_their_interpolation() {
+        let keys = [(10.0, UsdValue::Double(1.0)), (0.0, UsdValue::Double(0.0)), (10.0, UsdValue::Double(2.0))];
+        assert_eq!(bake_keyframes(&keys, KeyInterpolation::Linear),
+                   vec![(0.0, UsdValue::Double(0.0)), (10.0, UsdValue::Double(2.0))]);
+        
+        let held = bake_keyframes(&keys, KeyInterpolation::Held);
+        assert_eq!(held.len(), 3);
+        assert_eq!(held[1], (10.0 - HELD_KEY_OFFSET, UsdValue::Double(0.0)));
+        
+        let eased = bake_keyframes(&keys, KeyInterpolation::Ease);
+        assert_eq!(eased.len(), 11);
+        assert_eq!(eased[5], (5.0, UsdValue::Double(1.0)));
+        assert!(matches!(eased[1].1, UsdValue::Double(value) if value > 0.0 && value < 0.2));
+        
+        let tokens = [(0.0, UsdValue::Token("inherited".to_string())), (2.0, UsdValue::Token("invisible".to_string()))];
+        assert_eq!(bake_keyframes(&tokens, KeyInterpolation::Ease)[1], (1.0, UsdValue::Token("inherited".to_string())));
+    }
+    
+    #[test]
+    fn property_editability_follows_value_type() {
+        let property = |kind, type_name: &str| USDProperty {
+            name: "x".to_string(),
+            kind,
+            type_name: type_name.to_string(),
+            value: String::new(),
+            animated: false,
+        };
+        assert!(property(USDPropertyKind::Attribute, "color3f").is_editable());
+        assert!(property(USDPropertyKind::Attribute, "token").is_editable());
+        assert!(!property(USDPropertyKind::Attribute, "point3f[]").is_editable());
+        assert!(!property(USDPropertyKind::Attribute, "matrix4d").is_editable());
+        assert!(!property(USDPropertyKind::Metadata, "str").is_editable());
+    }
+}
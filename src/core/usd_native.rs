@@ -0,0 +1,176 @@
+//! Native OpenUSD backend through a thin C shim over the C++ API
+//!
+//! Enabled with the `usd-native` feature: build.rs compiles `ffi/usd_shim.cpp`
+//! against the OpenUSD install in `NODLE_OPENUSD_ROOT`. Core stage operations
+//! (open, create, define, get/set attributes) then run without Python on a
+//! stage that stays resident between calls. Everything else keeps using the
+//! Python path, which reads native stages from their file once they're saved.
+
+use std::ffi::{c_char, c_double, c_int, CStr, CString};
+use std::ptr::NonNull;
+
+/// Opaque `NodleUsdStage` from the shim
+#[repr(C)]
+struct RawStage {
+    _private: [u8; 0],
+}
+
+extern "C" {
+    fn nodle_usd_last_error() -> *const c_char;
+    fn nodle_usd_string_free(text: *mut c_char);
+    fn nodle_usd_stage_create_in_memory(identifier: *const c_char) -> *mut RawStage;
+    fn nodle_usd_stage_open(path: *const c_char) -> *mut RawStage;
+    fn nodle_usd_stage_release(stage: *mut RawStage);
+    fn nodle_usd_stage_set_edit_target(stage: *mut RawStage, layer: *const c_char) -> c_int;
+    fn nodle_usd_stage_layer_dirty(stage: *mut RawStage, layer: *const c_char, dirty: *mut c_int) -> c_int;
+    fn nodle_usd_stage_export_layer(stage: *mut RawStage, layer: *const c_char) -> *mut c_char;
+    fn nodle_usd_stage_import_layer(stage: *mut RawStage, layer: *const c_char, text: *const c_char) -> c_int;
+    fn nodle_usd_stage_define_prim(stage: *mut RawStage, path: *const c_char, type_name: *const c_char) -> c_int;
+    fn nodle_usd_stage_set_attribute(stage: *mut RawStage, prim_path: *const c_char, name: *const c_char, value: *const c_char) -> c_int;
+    fn nodle_usd_stage_get_attribute(stage: *mut RawStage, prim_path: *const c_char, name: *const c_char, time: c_double) -> *mut c_char;
+    fn nodle_usd_stage_list_prims(stage: *mut RawStage) -> *mut c_char;
+    fn nodle_usd_stage_export(stage: *mut RawStage, path: *const c_char, flatten: c_int, format: *const c_char) -> c_int;
+    fn nodle_usd_stage_save(stage: *mut RawStage) -> c_int;
+}
+
+/// The shim's error for the last failed call on this thread
+fn last_error(context: &str) -> String {
+    let message = unsafe { CStr::from_ptr(nodle_usd_last_error()) };
+    format!("{}: {}", context, message.to_string_lossy())
+}
+
+fn c_string(text: &str) -> Result<CString, String> {
+    CString::new(text).map_err(|e| format!("Invalid string '{}': {}", text, e))
+}
+
+/// Copy and release a string allocated by the shim
+fn take_string(text: *mut c_char) -> Option<String> {
+    if text.is_null() {
+        return None;
+    }
+    let owned = unsafe { CStr::from_ptr(text) }.to_string_lossy().into_owned();
+    unsafe { nodle_usd_string_free(text) };
+    Some(owned)
+}
+
+fn check(result: c_int, context: impl FnOnce() -> String) -> Result<(), String> {
+    if result == 0 {
+        Err(last_error(&context()))
+    } else {
+        Ok(())
+    }
+}
+
+/// A UsdStage owned by the native backend
+pub struct NativeStage {
+    raw: NonNull<RawStage>,
+}
+
+// The stage is only touched by one thread at a time: the worker opening it, then the engine,
+// which serializes access behind its Mutex
+unsafe impl Send for NativeStage {}
+
+impl NativeStage {
+    /// Create an anonymous in-memory stage
+    pub fn create_in_memory(identifier: &str) -> Result<Self, String> {
+        let identifier_c = c_string(identifier)?;
+        let raw = unsafe { nodle_usd_stage_create_in_memory(identifier_c.as_ptr()) };
+        NonNull::new(raw)
+            .map(|raw| Self { raw })
+            .ok_or_else(|| last_error(&format!("Failed to create stage '{}'", identifier)))
+    }
+    
+    /// Open a stage from a file
+    pub fn open(path: &str) -> Result<Self, String> {
+        let path_c = c_string(path)?;
+        let raw = unsafe { nodle_usd_stage_open(path_c.as_ptr()) };
+        NonNull::new(raw)
+            .map(|raw| Self { raw })
+            .ok_or_else(|| last_error(&format!("Failed to open stage '{}'", path)))
+    }
+    
+    /// Author later edits into "session", "root" or a layer stack identifier
+    pub fn set_edit_target(&mut self, layer: &str) -> Result<(), String> {
+        let layer_c = c_string(layer)?;
+        let result = unsafe { nodle_usd_stage_set_edit_target(self.raw.as_ptr(), layer_c.as_ptr()) };
+        check(result, || format!("Failed to set edit target to '{}'", layer))
+    }
+    
+    /// Whether "session", "root" or a layer stack identifier has unsaved edits
+    pub fn layer_dirty(&self, layer: &str) -> Result<bool, String> {
+        let layer_c = c_string(layer)?;
+        let mut dirty: c_int = 0;
+        let result = unsafe { nodle_usd_stage_layer_dirty(self.raw.as_ptr(), layer_c.as_ptr(), &mut dirty) };
+        check(result, || format!("Failed to read layer '{}'", layer))?;
+        Ok(dirty != 0)
+    }
+    
+    /// A layer as usda text
+    pub fn export_layer(&self, layer: &str) -> Result<String, String> {
+        let layer_c = c_string(layer)?;
+        take_string(unsafe { nodle_usd_stage_export_layer(self.raw.as_ptr(), layer_c.as_ptr()) })
+            .ok_or_else(|| last_error(&format!("Failed to export layer '{}'", layer)))
+    }
+    
+    /// Replace the content of a layer with usda text
+    pub fn import_layer(&mut self, layer: &str, text: &str) -> Result<(), String> {
+        let (layer_c, text_c) = (c_string(layer)?, c_string(text)?);
+        let result = unsafe { nodle_usd_stage_import_layer(self.raw.as_ptr(), layer_c.as_ptr(), text_c.as_ptr()) };
+        check(result, || format!("Failed to import layer '{}'", layer))
+    }
+    
+    pub fn define_prim(&mut self, path: &str, prim_type: &str) -> Result<(), String> {
+        let (path_c, type_c) = (c_string(path)?, c_string(prim_type)?);
+        let result = unsafe { nodle_usd_stage_define_prim(self.raw.as_ptr(), path_c.as_ptr(), type_c.as_ptr()) };
+        check(result, || format!("Failed to define {} '{}'", prim_type, path))
+    }
+    
+    /// Author a value in the edit target, parsed like the Python bulk edit helpers
+    pub fn set_attribute(&mut self, prim_path: &str, attr_name: &str, value: &str) -> Result<(), String> {
+        let (prim_c, name_c, value_c) = (c_string(prim_path)?, c_string(attr_name)?, c_string(value)?);
+        let result = unsafe {
+            nodle_usd_stage_set_attribute(self.raw.as_ptr(), prim_c.as_ptr(), name_c.as_ptr(), value_c.as_ptr())
+        };
+        check(result, || format!("Failed to set '{}.{}'", prim_path, attr_name))
+    }
+    
+    /// Read an attribute as text, at a time code or its default value
+    pub fn get_attribute(&self, prim_path: &str, attr_name: &str, time: Option<f64>) -> Result<String, String> {
+        let (prim_c, name_c) = (c_string(prim_path)?, c_string(attr_name)?);
+        let text = unsafe {
+            nodle_usd_stage_get_attribute(self.raw.as_ptr(), prim_c.as_ptr(), name_c.as_ptr(), time.unwrap_or(f64::NAN))
+        };
+        take_string(text).ok_or_else(|| last_error(&format!("Failed to get '{}.{}'", prim_path, attr_name)))
+    }
+    
+    /// All prims in traversal order as (path, type)
+    pub fn list_prims(&self) -> Result<Vec<(String, String)>, String> {
+        let listing = take_string(unsafe { nodle_usd_stage_list_prims(self.raw.as_ptr()) })
+            .ok_or_else(|| last_error("Failed to traverse stage"))?;
+        Ok(listing.lines()
+            .filter_map(|line| line.split_once('\t'))
+            .map(|(path, prim_type)| (path.to_string(), prim_type.to_string()))
+            .collect())
+    }
+    
+    /// Export the root layer, or the composed stage when `flatten` is set, as "usda", "usdc" or "usdz"
+    pub fn export(&self, path: &str, flatten: bool, format: &str) -> Result<(), String> {
+        let (path_c, format_c) = (c_string(path)?, c_string(format)?);
+        let result = unsafe {
+            nodle_usd_stage_export(self.raw.as_ptr(), path_c.as_ptr(), flatten as c_int, format_c.as_ptr())
+        };
+        check(result, || format!("Failed to export to '{}'", path))
+    }
+    
+    /// Save all dirty layers of the stage
+    pub fn save(&mut self) -> Result<(), String> {
+        let result = unsafe { nodle_usd_stage_save(self.raw.as_ptr()) };
+        check(result, || "Failed to save stage".to_string())
+    }
+}
+
+impl Drop for NativeStage {
+    fn drop(&mut self) {
+        unsafe { nodle_usd_stage_release(self.raw.as_ptr()) };
+    }
+}
@@ -0,0 +1,413 @@
+//! Typed attribute values
+//!
+//! A `UsdValue` carries its Sdf value type, so vectors, matrices, colors and
+//! arrays are authored with the right type instead of being guessed from a
+//! string on the Python side. Values display the way Python prints the
+//! matching Gf/Vt value, which is also how the mock backend stores them.
+
+use std::fmt;
+
+#[cfg(feature = "usd")]
+use pyo3::prelude::*;
+
+/// An attribute value with its Sdf value type
+#[derive(Debug, Clone, PartialEq)]
+pub enum UsdValue {
+    Bool(bool),
+    Int(i64),
+    Float(f32),
+    Double(f64),
+    String(String),
+    Token(String),
+    /// Asset path, without the `@` delimiters
+    Asset(String),
+    Vec2([f64; 2]),
+    Vec3([f64; 3]),
+    Vec4([f64; 4]),
+    /// Linear RGB color, authored as color3f
+    Color3([f64; 3]),
+    /// Row-major 4x4 matrix
+    Matrix4([f64; 16]),
+    /// Quaternion as (real, i, j, k), authored as quatf
+    Quatf([f64; 4]),
+    /// Quaternion as (real, i, j, k), authored as quath
+    Quath([f64; 4]),
+    /// Quaternion as (real, i, j, k), authored as quatd
+    Quatd([f64; 4]),
+    /// Array of scalar values of one type
+    Array(Vec<UsdValue>),
+    /// Time samples as (time code, value), authored alongside existing samples
+    TimeSamples(Vec<(f64, UsdValue)>),
+}
+
+impl UsdValue {
+    /// Sdf value type name used when the attribute has to be created
+    ///
+    /// Empty arrays and time samples default to token[] and double.
+    pub fn type_name(&self) -> String {
+        match self {
+            UsdValue::Bool(_) => "bool".to_string(),
+            UsdValue::Int(_) => "int".to_string(),
+            UsdValue::Float(_) => "float".to_string(),
+            UsdValue::Double(_) => "double".to_string(),
+            UsdValue::String(_) => "string".to_string(),
+            UsdValue::Token(_) => "token".to_string(),
+            UsdValue::Asset(_) => "asset".to_string(),
+            UsdValue::Vec2(_) => "double2".to_string(),
+            UsdValue::Vec3(_) => "double3".to_string(),
+            UsdValue::Vec4(_) => "double4".to_string(),
+            UsdValue::Color3(_) => "color3f".to_string(),
+            UsdValue::Matrix4(_) => "matrix4d".to_string(),
+            UsdValue::Quatf(_) => "quatf".to_string(),
+            UsdValue::Quath(_) => "quath".to_string(),
+            UsdValue::Quatd(_) => "quatd".to_string(),
+            UsdValue::Array(items) => match items.first() {
+                Some(item) => format!("{}[]", item.type_name()),
+                None => "token[]".to_string(),
+            },
+            UsdValue::TimeSamples(samples) => samples.first()
+                .map_or_else(|| "double".to_string(), |(_, value)| value.type_name()),
+        }
+    }
+    
+    /// Parse a displayed value as the given Sdf value type ("float3", "token[]", "matrix4d", ...)
+    pub fn parse(text: &str, type_name: &str) -> Result<Self, String> {
+        let text = text.trim();
+        let invalid = || format!("Invalid {} value '{}'", type_name, text);
+        
+        if let Some(element_type) = type_name.strip_suffix("[]") {
+            let inner = text.strip_prefix('[')
+                .and_then(|rest| rest.strip_suffix(']'))
+                .ok_or_else(invalid)?;
+            return split_top_level(inner).into_iter()
+                .map(|item| Self::parse(item, element_type))
+                .collect::<Result<Vec<_>, _>>()
+                .map(UsdValue::Array);
+        }
+        
+        let numbers = || parse_numbers(text).ok_or_else(invalid);
+        match type_name {
+            "bool" => match text {
+                "True" | "true" | "1" => Ok(UsdValue::Bool(true)),
+                "False" | "false" | "0" => Ok(UsdValue::Bool(false)),
+                _ => Err(invalid()),
+            },
+            "int" | "uint" | "int64" | "uint64" | "uchar" => text.parse().map(UsdValue::Int).map_err(|_| invalid()),
+            "float" | "half" => text.parse().map(UsdValue::Float).map_err(|_| invalid()),
+            "double" | "timecode" => text.parse().map(UsdValue::Double).map_err(|_| invalid()),
+            "string" => Ok(UsdValue::String(unquote(text).to_string())),
+            "token" => Ok(UsdValue::Token(unquote(text).to_string())),
+            "asset" => Ok(UsdValue::Asset(unquote(text).trim_matches('@').to_string())),
+            "matrix4d" => numbers()?.try_into().map(UsdValue::Matrix4).map_err(|_| invalid()),
+            "quatf" => numbers()?.try_into().map(UsdValue::Quatf).map_err(|_| invalid()),
+            "quath" => numbers()?.try_into().map(UsdValue::Quath).map_err(|_| invalid()),
+            "quatd" => numbers()?.try_into().map(UsdValue::Quatd).map_err(|_| invalid()),
+            _ => {
+                // Tuple types end in their component count, optionally followed by a precision suffix
+                let count = type_name.trim_end_matches(['f', 'd', 'h', 'i'])
+                    .chars()
+                    .last()
+                    .and_then(|c| c.to_digit(10))
+                    .ok_or_else(|| format!("Unsupported value type '{}'", type_name))?;
+                let numbers = numbers()?;
+                if numbers.len() != count as usize {
+                    return Err(invalid());
+                }
+                match (count, type_name.starts_with("color")) {
+                    (3, true) => Ok(UsdValue::Color3([numbers[0], numbers[1], numbers[2]])),
+                    (2, _) => Ok(UsdValue::Vec2([numbers[0], numbers[1]])),
+                    (3, _) => Ok(UsdValue::Vec3([numbers[0], numbers[1], numbers[2]])),
+                    (4, _) => Ok(UsdValue::Vec4([numbers[0], numbers[1], numbers[2], numbers[3]])),
+                    _ => Err(format!("Unsupported value type '{}'", type_name)),
+                }
+            }
+        }
+    }
+    
+    /// Infer a value from text typed without a known attribute type
+    ///
+    /// Booleans, numbers, numeric tuples and lists are recognized; quoted text
+    /// and anything else is a string.
+    pub fn infer(text: &str) -> Self {
+        let text = text.trim();
+        match text {
+            "True" | "true" => return UsdValue::Bool(true),
+            "False" | "false" => return UsdValue::Bool(false),
+            _ => {}
+        }
+        if let Ok(value) = text.parse::<i64>() {
+            return UsdValue::Int(value);
+        }
+        if let Ok(value) = text.parse::<f64>() {
+            return UsdValue::Double(value);
+        }
+        if text.starts_with('(') {
+            match parse_numbers(text).as_deref() {
+                Some(&[x, y]) => return UsdValue::Vec2([x, y]),
+                Some(&[x, y, z]) => return UsdValue::Vec3([x, y, z]),
+                Some(&[x, y, z, w]) => return UsdValue::Vec4([x, y, z, w]),
+                _ => {}
+            }
+        }
+        if let Some(inner) = text.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+            // Lists of words are token arrays, like xformOpOrder
+            let items = split_top_level(inner).into_iter()
+                .map(|item| match UsdValue::infer(item) {
+                    UsdValue::String(word) => UsdValue::Token(word),
+                    value => value,
+                })
+                .collect();
+            return UsdValue::Array(items);
+        }
+        UsdValue::String(unquote(text).to_string())
+    }
+    
+    /// Blend toward another value of the same type, `alpha` 0 giving `self`
+    ///
+    /// Only floating point scalars, vectors and colors blend; other values give `None`.
+    pub fn lerp(&self, other: &UsdValue, alpha: f64) -> Option<UsdValue> {
+        fn mix<const N: usize>(a: &[f64; N], b: &[f64; N], alpha: f64) -> [f64; N] {
+            std::array::from_fn(|index| a[index] + (b[index] - a[index]) * alpha)
+        }
+        match (self, other) {
+            (UsdValue::Float(a), UsdValue::Float(b)) => Some(UsdValue::Float(a + (b - a) * alpha as f32)),
+            (UsdValue::Double(a), UsdValue::Double(b)) => Some(UsdValue::Double(a + (b - a) * alpha)),
+            (UsdValue::Vec2(a), UsdValue::Vec2(b)) => Some(UsdValue::Vec2(mix(a, b, alpha))),
+            (UsdValue::Vec3(a), UsdValue::Vec3(b)) => Some(UsdValue::Vec3(mix(a, b, alpha))),
+            (UsdValue::Vec4(a), UsdValue::Vec4(b)) => Some(UsdValue::Vec4(mix(a, b, alpha))),
+            (UsdValue::Color3(a), UsdValue::Color3(b)) => Some(UsdValue::Color3(mix(a, b, alpha))),
+            _ => None,
+        }
+    }
+    
+    /// Convert to the plain Python value the bulk edit helpers coerce to the Sdf type
+    ///
+    /// Tuples become Python tuples, matrices tuples of rows, arrays lists and
+    /// time samples a {time: value} dict.
+    #[cfg(feature = "usd")]
+    pub fn to_python<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        use pyo3::types::{PyDict, PyList, PyTuple};
+        use pyo3::IntoPyObjectExt;
+        
+        match self {
+            UsdValue::Bool(value) => (*value).into_bound_py_any(py),
+            UsdValue::Int(value) => (*value).into_bound_py_any(py),
+            UsdValue::Float(value) => (*value).into_bound_py_any(py),
+            UsdValue::Double(value) => (*value).into_bound_py_any(py),
+            UsdValue::String(value) | UsdValue::Token(value) | UsdValue::Asset(value) => value.as_str().into_bound_py_any(py),
+            UsdValue::Vec2(components) => PyTuple::new(py, components).map(Bound::into_any),
+            UsdValue::Vec3(components) | UsdValue::Color3(components) => PyTuple::new(py, components).map(Bound::into_any),
+            UsdValue::Vec4(components) => PyTuple::new(py, components).map(Bound::into_any),
+            UsdValue::Quatf(components) | UsdValue::Quath(components) | UsdValue::Quatd(components) => {
+                PyTuple::new(py, components).map(Bound::into_any)
+            }
+            UsdValue::Matrix4(elements) => {
+                let rows = elements.chunks(4)
+                    .map(|row| PyTuple::new(py, row))
+                    .collect::<PyResult<Vec<_>>>()?;
+                PyTuple::new(py, rows).map(Bound::into_any)
+            }
+            UsdValue::Array(items) => {
+                let items = items.iter()
+                    .map(|item| item.to_python(py))
+                    .collect::<PyResult<Vec<_>>>()?;
+                PyList::new(py, items).map(Bound::into_any)
+            }
+            UsdValue::TimeSamples(samples) => {
+                let dict = PyDict::new(py);
+                for (time, value) in samples {
+                    dict.set_item(time, value.to_python(py)?)?;
+                }
+                Ok(dict.into_any())
+            }
+        }
+    }
+}
+
+impl fmt::Display for UsdValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tuple = |f: &mut fmt::Formatter<'_>, components: &[f64]| {
+            let components: Vec<String> = components.iter().map(f64::to_string).collect();
+            write!(f, "({})", components.join(", "))
+        };
+        match self {
+            UsdValue::Bool(value) => write!(f, "{}", if *value { "True" } else { "False" }),
+            UsdValue::Int(value) => write!(f, "{}", value),
+            UsdValue::Float(value) => write!(f, "{}", value),
+            UsdValue::Double(value) => write!(f, "{}", value),
+            UsdValue::String(value) | UsdValue::Token(value) => write!(f, "{}", value),
+            UsdValue::Asset(path) => write!(f, "@{}@", path),
+            UsdValue::Vec2(components) => tuple(f, components),
+            UsdValue::Vec3(components) | UsdValue::Color3(components) => tuple(f, components),
+            UsdValue::Vec4(components) => tuple(f, components),
+            // Gf quaternions print their real part first, as usda authors them
+            UsdValue::Quatf(components) | UsdValue::Quath(components) | UsdValue::Quatd(components) => tuple(f, components),
+            UsdValue::Matrix4(elements) => {
+                write!(f, "( ")?;
+                for (index, row) in elements.chunks(4).enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    tuple(f, row)?;
+                }
+                write!(f, " )")
+            }
+            UsdValue::Array(items) => {
+                let items: Vec<String> = items.iter()
+                    .map(|item| match item {
+                        UsdValue::String(text) | UsdValue::Token(text) => format!("\"{}\"", text),
+                        item => item.to_string(),
+                    })
+                    .collect();
+                write!(f, "[{}]", items.join(", "))
+            }
+            UsdValue::TimeSamples(samples) => {
+                let samples: Vec<String> = samples.iter()
+                    .map(|(time, value)| format!("{}: {}", time, value))
+                    .collect();
+                write!(f, "{{{}}}", samples.join(", "))
+            }
+        }
+    }
+}
+
+impl From<&str> for UsdValue {
+    fn from(text: &str) -> Self {
+        UsdValue::infer(text)
+    }
+}
+
+impl From<String> for UsdValue {
+    fn from(text: String) -> Self {
+        UsdValue::infer(&text)
+    }
+}
+
+impl From<bool> for UsdValue {
+    fn from(value: bool) -> Self {
+        UsdValue::Bool(value)
+    }
+}
+
+impl From<i64> for UsdValue {
+    fn from(value: i64) -> Self {
+        UsdValue::Int(value)
+    }
+}
+
+impl From<f32> for UsdValue {
+    fn from(value: f32) -> Self {
+        UsdValue::Float(value)
+    }
+}
+
+impl From<f64> for UsdValue {
+    fn from(value: f64) -> Self {
+        UsdValue::Double(value)
+    }
+}
+
+impl From<[f64; 3]> for UsdValue {
+    fn from(components: [f64; 3]) -> Self {
+        UsdValue::Vec3(components)
+    }
+}
+
+/// Strip one pair of matching quotes
+fn unquote(text: &str) -> &str {
+    for quote in ['"', '\''] {
+        if let Some(inner) = text.strip_prefix(quote).and_then(|rest| rest.strip_suffix(quote)) {
+            return inner;
+        }
+    }
+    text
+}
+
+/// All numbers in a possibly nested tuple like "( (1, 0), (0, 1) )"
+fn parse_numbers(text: &str) -> Option<Vec<f64>> {
+    text.split([',', '(', ')', '[', ']'])
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(|item| item.parse().ok())
+        .collect()
+}
+
+/// Split on commas outside parentheses, brackets and quotes
+fn split_top_level(text: &str) -> Vec<&str> {
+    let mut items = Vec::new();
+    let (mut depth, mut quote, mut start) = (0i32, None, 0);
+    for (index, c) in text.char_indices() {
+        match (c, quote) {
+            ('"' | '\'', None) => quote = Some(c),
+            (c, Some(open)) if c == open => quote = None,
+            (_, Some(_)) => {}
+            ('(' | '[', None) => depth += 1,
+            (')' | ']', None) => depth -= 1,
+            (',', None) if depth == 0 => {
+                items.push(text[start..index].trim());
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(text[start..].trim());
+    items.retain(|item| !item.is_empty());
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn parse_follows_sdf_type_names() {
+        assert_eq!(UsdValue::parse("(1, 0.5, 0)", "color3f").unwrap(), UsdValue::Color3([1.0, 0.5, 0.0]));
+        assert_eq!(UsdValue::parse("(0, 1, 0)", "normal3f").unwrap(), UsdValue::Vec3([0.0, 1.0, 0.0]));
+        assert_eq!(UsdValue::parse("(0.5, 0.25)", "texCoord2f").unwrap(), UsdValue::Vec2([0.5, 0.25]));
+        assert_eq!(UsdValue::parse("@tex/wood.png@", "asset").unwrap(), UsdValue::Asset("tex/wood.png".to_string()));
+        assert_eq!(UsdValue::parse("True", "bool").unwrap(), UsdValue::Bool(true));
+        assert_eq!(UsdValue::parse("[\"a\", \"b\"]", "token[]").unwrap().type_name(), "token[]");
+        assert_eq!(UsdValue::parse("[(0, 0, 0), (1, 1, 1)]", "point3f[]").unwrap(),
+                   UsdValue::Array(vec![UsdValue::Vec3([0.0; 3]), UsdValue::Vec3([1.0; 3])]));
+        
+        let identity = "( (1, 0, 0, 0), (0, 1, 0, 0), (0, 0, 1, 0), (0, 0, 0, 1) )";
+        let matrix = UsdValue::parse(identity, "matrix4d").unwrap();
+        assert_eq!(matrix.to_string(), identity);
+        
+        assert!(UsdValue::parse("(1, 2)", "float3").is_err());
+        assert!(UsdValue::parse("yes", "bool").is_err());
+        assert!(UsdValue::parse("x", "opaque").is_err());
+    }
+    
+    #[test]
+    fn quaternions_round_trip_in_every_precision() {
+        for type_name in ["quatf", "quath", "quatd"] {
+            let value = UsdValue::parse("(0.7071, 0, 0.7071, 0)", type_name).unwrap();
+            assert_eq!(value.type_name(), type_name);
+            assert_eq!(value.to_string(), "(0.7071, 0, 0.7071, 0)");
+            assert_eq!(UsdValue::parse(&value.to_string(), type_name).unwrap(), value);
+            assert!(UsdValue::parse("(1, 0, 0)", type_name).is_err());
+        }
+        assert_eq!(UsdValue::parse("(1, 0, 0, 0)", "quatf").unwrap(), UsdValue::Quatf([1.0, 0.0, 0.0, 0.0]));
+        
+        let rotations = UsdValue::parse("[(1, 0, 0, 0), (0, 0, 1, 0)]", "quath[]").unwrap();
+        assert_eq!(rotations.type_name(), "quath[]");
+        assert_eq!(UsdValue::parse(&rotations.to_string(), "quath[]").unwrap(), rotations);
+    }
+    
+    #[test]
+    fn infer_matches_authored_strings() {
+        assert_eq!(UsdValue::infer("12").type_name(), "int");
+        assert_eq!(UsdValue::infer("0.5").type_name(), "double");
+        assert_eq!(UsdValue::infer("(1, 2, 3)").type_name(), "double3");
+        assert_eq!(UsdValue::infer("invisible"), UsdValue::String("invisible".to_string()));
+        
+        let order = UsdValue::infer("[\"xformOp:translate\", \"xformOp:scale\"]");
+        assert_eq!(order.type_name(), "token[]");
+        assert_eq!(order.to_string(), "[\"xformOp:translate\", \"xformOp:scale\"]");
+        
+        let samples = UsdValue::TimeSamples(vec![(0.0, UsdValue::Double(0.0)), (24.0, UsdValue::Double(360.0))]);
+        assert_eq!(samples.type_name(), "double");
+        assert_eq!(samples.to_string(), "{0: 0, 24: 360}");
+    }
+}
@@ -0,0 +1,368 @@
+//! USDZ package reading
+//!
+//! A .usdz file is an uncompressed zip archive whose first entry is the
+//! package's default layer. Assets inside it are addressed with package-relative
+//! paths, `@scene.usdz[textures/albedo.png]@`, which may nest for packages
+//! inside packages: `outer.usdz[inner.usdz[textures/albedo.png]]`.
+
+use std::collections::hash_map::DefaultHasher;
+use std::fs::File;
+use std::hash::{Hash, Hasher};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+const LOCAL_HEADER_SIGNATURE: u32 = 0x0403_4b50;
+const CENTRAL_HEADER_SIGNATURE: u32 = 0x0201_4b50;
+const END_OF_DIRECTORY_SIGNATURE: u32 = 0x0605_4b50;
+/// End of central directory record without its trailing comment
+const END_OF_DIRECTORY_LEN: u64 = 22;
+/// Largest zip comment that can follow the end of central directory record
+const MAX_COMMENT_LEN: u64 = 0xffff;
+
+/// Whether a file path names a USDZ package
+pub fn is_package(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("usdz"))
+}
+
+/// A package-relative asset path split into the package file and the path inside it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackagePath {
+    pub package: String,
+    /// Entry path inside the package, itself a package path for nested packages
+    pub entry: String,
+}
+
+impl PackagePath {
+    /// Parse `package.usdz[entry]`, with or without the surrounding `@` asset delimiters
+    pub fn parse(asset_path: &str) -> Option<Self> {
+        let path = asset_path.trim().trim_matches('@');
+        let inner = path.strip_suffix(']')?;
+        let open = inner.find('[')?;
+        let (package, entry) = (&inner[..open], &inner[open + 1..]);
+        if package.is_empty() || entry.is_empty() {
+            return None;
+        }
+        Some(Self {
+            package: package.to_string(),
+            entry: entry.to_string(),
+        })
+    }
+    
+    /// Resolve an asset path authored in a layer of `stage_path`
+    ///
+    /// Package-relative paths are returned as they are; relative paths authored
+    /// in a stage opened from a package resolve inside that package.
+    pub fn resolve(asset_path: &str, stage_path: &str) -> Option<Self> {
+        if let Some(path) = Self::parse(asset_path) {
+            return Some(path);
+        }
+        let asset = asset_path.trim().trim_matches('@');
+        let package = Self::parse(stage_path)
+            .map(|stage| stage.package)
+            .or_else(|| is_package(stage_path).then(|| stage_path.to_string()))?;
+        if asset.is_empty() || Path::new(asset).is_absolute() {
+            return None;
+        }
+        Some(Self {
+            package,
+            entry: asset.trim_start_matches("./").to_string(),
+        })
+    }
+}
+
+impl std::fmt::Display for PackagePath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}[{}]", self.package, self.entry)
+    }
+}
+
+/// A file stored in a package
+#[derive(Debug, Clone)]
+pub struct UsdzEntry {
+    pub name: String,
+    pub size: u64,
+    /// Offset of the entry's local header in the archive
+    header_offset: u64,
+}
+
+/// Where an archive's bytes come from
+#[derive(Debug)]
+enum Source {
+    File(PathBuf),
+    /// A package nested in another package, read into memory
+    Bytes(Vec<u8>),
+}
+
+/// An open USDZ package
+#[derive(Debug)]
+pub struct UsdzArchive {
+    source: Source,
+    entries: Vec<UsdzEntry>,
+}
+
+impl UsdzArchive {
+    /// Open a package on disk and read its table of contents
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, String> {
+        Self::from_source(Source::File(path.as_ref().to_path_buf()))
+    }
+    
+    /// Read a package held in memory
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self, String> {
+        Self::from_source(Source::Bytes(bytes))
+    }
+    
+    fn from_source(source: Source) -> Result<Self, String> {
+        let mut archive = Self { source, entries: Vec::new() };
+        archive.entries = archive.read_directory()?;
+        Ok(archive)
+    }
+    
+    /// Entries in archive order
+    pub fn entries(&self) -> &[UsdzEntry] {
+        &self.entries
+    }
+    
+    /// The package's default layer, its first entry
+    pub fn default_layer(&self) -> Result<&UsdzEntry, String> {
+        self.entries.first()
+            .filter(|entry| ["usd", "usda", "usdc"].iter().any(|ext| {
+                Path::new(&entry.name).extension().is_some_and(|e| e.eq_ignore_ascii_case(ext))
+            }))
+            .ok_or_else(|| "USDZ package does not start with a USD layer".to_string())
+    }
+    
+    /// Read an entry's bytes
+    pub fn read(&self, name: &str) -> Result<Vec<u8>, String> {
+        let name = name.trim_start_matches("./");
+        let entry = self.entries.iter()
+            .find(|entry| entry.name == name)
+            .ok_or_else(|| format!("'{}' is not in the package", name))?;
+        
+        let header = self.read_at(entry.header_offset, 30)?;
+        if le_u32(&header, 0) != LOCAL_HEADER_SIGNATURE {
+            return Err(format!("Corrupt local header for '{}'", name));
+        }
+        let data_offset = entry.header_offset + 30 + le_u16(&header, 26) as u64 + le_u16(&header, 28) as u64;
+        self.read_at(data_offset, entry.size as usize)
+    }
+    
+    fn len(&self) -> Result<u64, String> {
+        match &self.source {
+            Source::File(path) => std::fs::metadata(path)
+                .map(|meta| meta.len())
+                .map_err(|e| format!("Failed to read '{}': {}", path.display(), e)),
+            Source::Bytes(bytes) => Ok(bytes.len() as u64),
+        }
+    }
+    
+    fn read_at(&self, offset: u64, len: usize) -> Result<Vec<u8>, String> {
+        match &self.source {
+            Source::File(path) => {
+                let err = |e: std::io::Error| format!("Failed to read '{}': {}", path.display(), e);
+                let mut file = File::open(path).map_err(err)?;
+                file.seek(SeekFrom::Start(offset)).map_err(err)?;
+                let mut buffer = vec![0; len];
+                file.read_exact(&mut buffer).map_err(err)?;
+                Ok(buffer)
+            }
+            Source::Bytes(bytes) => usize::try_from(offset).ok()
+                .and_then(|start| bytes.get(start..start.checked_add(len)?))
+                .map(<[u8]>::to_vec)
+                .ok_or_else(|| "Unexpected end of nested package".to_string()),
+        }
+    }
+    
+    /// Parse the central directory found through the end of central directory record
+    fn read_directory(&self) -> Result<Vec<UsdzEntry>, String> {
+        let len = self.len()?;
+        if len < END_OF_DIRECTORY_LEN {
+            return Err("Not a USDZ package: file is too small".to_string());
+        }
+        let tail_len = len.min(END_OF_DIRECTORY_LEN + MAX_COMMENT_LEN);
+        let tail = self.read_at(len - tail_len, tail_len as usize)?;
+        let end = (0..=tail.len() - END_OF_DIRECTORY_LEN as usize)
+            .rev()
+            .find(|&i| le_u32(&tail, i) == END_OF_DIRECTORY_SIGNATURE)
+            .ok_or("Not a USDZ package: no zip directory found")?;
+        
+        let count = le_u16(&tail, end + 10) as usize;
+        let directory_len = le_u32(&tail, end + 12) as usize;
+        let directory_offset = le_u32(&tail, end + 16) as u64;
+        if directory_offset == 0xffff_ffff || count == 0xffff {
+            return Err("Zip64 USDZ packages are not supported".to_string());
+        }
+        
+        let directory = self.read_at(directory_offset, directory_len)?;
+        let mut entries = Vec::with_capacity(count);
+        let mut at = 0;
+        for _ in 0..count {
+            if at + 46 > directory.len() || le_u32(&directory, at) != CENTRAL_HEADER_SIGNATURE {
+                return Err("Corrupt USDZ package directory".to_string());
+            }
+            let method = le_u16(&directory, at + 10);
+            let compressed = le_u32(&directory, at + 20) as u64;
+            let size = le_u32(&directory, at + 24) as u64;
+            let name_len = le_u16(&directory, at + 28) as usize;
+            let extra_len = le_u16(&directory, at + 30) as usize;
+            let comment_len = le_u16(&directory, at + 32) as usize;
+            let header_offset = le_u32(&directory, at + 42) as u64;
+            let name = directory.get(at + 46..at + 46 + name_len)
+                .map(|name| String::from_utf8_lossy(name).into_owned())
+                .ok_or("Corrupt USDZ package directory")?;
+            
+            // The usdz spec only allows stored entries so assets can be read in place
+            if method != 0 || compressed != size {
+                return Err(format!("'{}' is compressed, which USDZ packages don't allow", name));
+            }
+            entries.push(UsdzEntry { name, size, header_offset });
+            at += 46 + name_len + extra_len + comment_len;
+        }
+        Ok(entries)
+    }
+}
+
+/// Read the bytes of a package-relative asset, descending into nested packages
+pub fn read_asset(path: &PackagePath) -> Result<Vec<u8>, String> {
+    let mut archive = UsdzArchive::open(&path.package)?;
+    let mut entry = path.entry.clone();
+    // Nested packages are read into memory one level at a time
+    while let Some(nested) = PackagePath::parse(&entry) {
+        archive = UsdzArchive::from_bytes(archive.read(&nested.package)?)?;
+        entry = nested.entry;
+    }
+    archive.read(&entry)
+        .map_err(|e| format!("Failed to read '{}': {}", path, e))
+}
+
+/// Decode a texture embedded in a package and cache it as a standalone image file
+///
+/// The viewport loads textures by file path, so embedded images are written
+/// to the temp directory once per package modification. HDR images stay EXR.
+pub fn extract_texture(path: &PackagePath) -> Result<PathBuf, String> {
+    let modified = std::fs::metadata(&path.package)
+        .and_then(|meta| meta.modified())
+        .map_err(|e| format!("Failed to read '{}': {}", path.package, e))?;
+    let mut hasher = DefaultHasher::new();
+    path.to_string().hash(&mut hasher);
+    modified.hash(&mut hasher);
+    
+    let hdr = Path::new(path.entry.trim_end_matches(']'))
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("exr") || ext.eq_ignore_ascii_case("hdr"));
+    let dir = std::env::temp_dir().join("nodle_usdz_textures");
+    let cached = dir.join(format!("{:016x}.{}", hasher.finish(), if hdr { "exr" } else { "png" }));
+    if cached.exists() {
+        return Ok(cached);
+    }
+    
+    let bytes = read_asset(path)?;
+    let image = image::load_from_memory(&bytes)
+        .map_err(|e| format!("Failed to decode '{}': {}", path, e))?;
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create '{}': {}", dir.display(), e))?;
+    let decoded = if hdr {
+        image::DynamicImage::ImageRgba32F(image.to_rgba32f())
+    } else {
+        image::DynamicImage::ImageRgba8(image.to_rgba8())
+    };
+    decoded.save(&cached)
+        .map_err(|e| format!("Failed to cache '{}': {}", path, e))?;
+    Ok(cached)
+}
+
+fn le_u16(bytes: &[u8], at: usize) -> u16 {
+    u16::from_le_bytes([bytes[at], bytes[at + 1]])
+}
+
+fn le_u32(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    /// Build a stored (uncompressed) zip archive
+    fn stored_zip(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut directory = Vec::new();
+        for (name, data) in files {
+            let offset = out.len() as u32;
+            let size = data.len() as u32;
+            out.extend_from_slice(&LOCAL_HEADER_SIGNATURE.to_le_bytes());
+            out.extend_from_slice(&[20, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+            out.extend_from_slice(&size.to_le_bytes());
+            out.extend_from_slice(&size.to_le_bytes());
+            out.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            out.extend_from_slice(&0u16.to_le_bytes());
+            out.extend_from_slice(name.as_bytes());
+            out.extend_from_slice(data);
+            
+            directory.extend_from_slice(&CENTRAL_HEADER_SIGNATURE.to_le_bytes());
+            directory.extend_from_slice(&[20, 0, 20, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+            directory.extend_from_slice(&size.to_le_bytes());
+            directory.extend_from_slice(&size.to_le_bytes());
+            directory.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            directory.extend_from_slice(&[0; 12]);
+            directory.extend_from_slice(&offset.to_le_bytes());
+            directory.extend_from_slice(name.as_bytes());
+        }
+        let directory_offset = out.len() as u32;
+        out.extend_from_slice(&directory);
+        out.extend_from_slice(&END_OF_DIRECTORY_SIGNATURE.to_le_bytes());
+        out.extend_from_slice(&[0; 4]);
+        out.extend_from_slice(&(files.len() as u16).to_le_bytes());
+        out.extend_from_slice(&(files.len() as u16).to_le_bytes());
+        out.extend_from_slice(&(directory.len() as u32).to_le_bytes());
+        out.extend_from_slice(&directory_offset.to_le_bytes());
+        out.extend_from_slice(&0u16.to_le_bytes());
+        out
+    }
+    
+    #[test]
+    fn package_paths_parse_and_resolve() {
+        let path = PackagePath::parse("@/assets/chair.usdz[textures/wood.png]@").unwrap();
+        assert_eq!(path.package, "/assets/chair.usdz");
+        assert_eq!(path.entry, "textures/wood.png");
+        
+        let nested = PackagePath::parse("set.usdz[props/chair.usdz[textures/wood.png]]").unwrap();
+        assert_eq!(nested.package, "set.usdz");
+        assert_eq!(nested.entry, "props/chair.usdz[textures/wood.png]");
+        
+        assert_eq!(PackagePath::parse("textures/wood.png"), None);
+        assert_eq!(
+            PackagePath::resolve("@./textures/wood.png@", "/assets/chair.usdz").unwrap().to_string(),
+            "/assets/chair.usdz[textures/wood.png]",
+        );
+        assert_eq!(PackagePath::resolve("textures/wood.png", "/assets/chair.usda"), None);
+        assert_eq!(PackagePath::resolve("/abs/wood.png", "/assets/chair.usdz"), None);
+    }
+    
+    #[test]
+    fn reads_stored_entries_and_nested_packages() {
+        let inner = stored_zip(&[("chair.usdc", b"PXR-USDC"), ("textures/wood.png", b"png bytes")]);
+        let outer = stored_zip(&[("set.usda", b"#usda 1.0"), ("props/chair.usdz", &inner)]);
+        
+        let archive = UsdzArchive::from_bytes(outer.clone()).unwrap();
+        assert_eq!(archive.default_layer().unwrap().name, "set.usda");
+        assert_eq!(archive.entries().len(), 2);
+        assert_eq!(archive.read("set.usda").unwrap(), b"#usda 1.0");
+        assert!(archive.read("missing.png").is_err());
+        
+        let file = std::env::temp_dir().join(format!("nodle_usdz_test_{}.usdz", std::process::id()));
+        std::fs::write(&file, &outer).unwrap();
+        let asset = PackagePath::parse(&format!("{}[props/chair.usdz[textures/wood.png]]", file.display())).unwrap();
+        let bytes = read_asset(&asset);
+        std::fs::remove_file(&file).unwrap();
+        assert_eq!(bytes.unwrap(), b"png bytes");
+    }
+    
+    #[test]
+    fn rejects_non_packages() {
+        assert!(UsdzArchive::from_bytes(b"#usda 1.0\n".to_vec()).is_err());
+        let not_layer = UsdzArchive::from_bytes(stored_zip(&[("readme.txt", b"hi")])).unwrap();
+        assert!(not_layer.default_layer().is_err());
+    }
+}
@@ -0,0 +1,220 @@
+//! USD Create Stage node - creates an in-memory stage for procedural graphs
+
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::core::usd_engine::with_usd_engine;
+use crate::ui::help::NodeHelp;
+use crate::ui::palette::status_label;
+use crate::ui::keyboard::{focus_marked, handle_key};
+use crate::registry::NodeTypeFactory;
+
+/// Help for the Create Stage node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_CreateStage",
+    summary: "Create a new in-memory USD stage",
+    details: "Starts an empty stage held in memory with a /World default prim. Connect its Stage output to geometry, light and shading nodes, then save it with Save Stage.",
+    ports: &[
+        ("Stage", "stage_0"),
+    ],
+    samples: &[],
+};
+
+/// Factory of the Create Stage node, for `crate::node_factories`
+pub fn factories() -> Vec<Box<dyn NodeFactory>> {
+    vec![NodeTypeFactory::boxed(metadata, |position| PluginNodeHandle::new(Box::new(USDCreateStageNode::new(position))))]
+}
+
+/// Metadata of the Create Stage node
+fn metadata() -> NodeMetadata {
+    NodeMetadata::new(
+        "USD_CreateStage",
+        "Create Stage",
+        NodeCategory::new(&["USD", "Stage"]),
+        HELP.summary
+    )
+    .with_color(Color32::from_rgb(80, 150, 200))
+    .with_icon("🎬")
+    .with_outputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("Created USD stage identifier"),
+    ])
+    .with_panel_type(PanelType::Parameter)
+    .with_workspace_compatibility(vec!["3D"])
+}
+
+/// USD Create Stage node
+///
+/// The stage only lives in memory and is passed downstream by its identifier;
+/// a Save Stage node with Flatten enabled materializes it to a file.
+pub struct USDCreateStageNode {
+    id: String,
+    position: Pos2,
+    identifier: String,
+    /// Author downstream edits into an anonymous layer instead of the session layer
+    edit_layer: bool,
+    /// Identifier of the stage created for the current parameters
+    created: Option<String>,
+    dirty: bool,
+    status: String,
+}
+
+impl USDCreateStageNode {
+    pub fn new(position: Pos2) -> Self {
+        let id = uuid::Uuid::new_v4().to_string();
+        Self {
+            identifier: format!("stage_{}", &id[..8]),
+            id,
+            position,
+            edit_layer: false,
+            created: None,
+            dirty: true,
+            status: "Stage not created".to_string(),
+        }
+    }
+    
+    fn create(&mut self) -> Result<(), String> {
+        if self.identifier.is_empty() {
+            return Err("No stage identifier set".to_string());
+        }
+        let (identifier, edit_layer) = (self.identifier.clone(), self.edit_layer);
+        
+        let anonymous = with_usd_engine(|engine| -> Result<Option<String>, String> {
+            if engine.get_stage(&identifier).is_none() {
+                engine.create_stage(&identifier)?;
+            }
+            if !edit_layer {
+                engine.set_edit_target(&identifier, "session")?;
+                return Ok(None);
+            }
+            let layer = match engine.get_anonymous_layers(&identifier).into_iter().next() {
+                Some(layer) => layer,
+                None => engine.add_anonymous_layer(&identifier, "edits")?,
+            };
+            engine.set_edit_target(&identifier, &layer)?;
+            Ok(Some(layer))
+        })?;
+        
+        self.status = match anonymous {
+            Some(layer) => format!("In-memory stage '{}', editing {}", identifier, layer),
+            None => format!("In-memory stage '{}'", identifier),
+        };
+        self.created = Some(identifier);
+        Ok(())
+    }
+}
+
+impl PluginNode for USDCreateStageNode {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+    
+    fn position(&self) -> Pos2 {
+        self.position
+    }
+    
+    fn set_position(&mut self, position: Pos2) {
+        self.position = position;
+    }
+    
+    fn get_parameter_ui(&self) -> ParameterUI {
+        let mut elements = Vec::new();
+        
+        elements.push(UIElement::Heading("USD Create Stage".to_string()));
+        elements.push(UIElement::Separator);
+        
+        elements.push(UIElement::TextEdit {
+            label: "Identifier".to_string(),
+            value: self.identifier.clone(),
+            parameter_name: "identifier".to_string(),
+        });
+        
+        elements.push(UIElement::Checkbox {
+            label: "Anonymous Edit Layer".to_string(),
+            value: self.edit_layer,
+            parameter_name: "edit_layer".to_string(),
+        });
+        
+        elements.push(UIElement::Separator);
+        elements.push(status_label(&self.status));
+        elements.extend(HELP.section());
+        
+        ParameterUI { elements: focus_marked(&self.id, elements) }
+    }
+    
+    fn handle_ui_action(&mut self, action: UIAction) -> Vec<ParameterChange> {
+        if let Some(changes) = handle_key(self, &[], &action) {
+            return changes;
+        }
+        
+        let mut changes = Vec::new();
+        
+        if let UIAction::ParameterChanged { parameter, value } = action {
+            match parameter.as_str() {
+                "identifier" => {
+                    if let Some(identifier) = value.as_string() {
+                        self.set_parameter(&parameter, NodeData::String(identifier.to_string()));
+                        changes.push(ParameterChange {
+                            parameter: parameter.clone(),
+                            value: NodeData::String(self.identifier.clone()),
+                        });
+                    }
+                }
+                "edit_layer" => {
+                    if let Some(edit_layer) = value.as_boolean() {
+                        self.set_parameter(&parameter, NodeData::Boolean(edit_layer));
+                        changes.push(ParameterChange {
+                            parameter: parameter.clone(),
+                            value: NodeData::Boolean(edit_layer),
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+        
+        changes
+    }
+    
+    fn get_parameter(&self, name: &str) -> Option<NodeData> {
+        match name {
+            "identifier" => Some(NodeData::String(self.identifier.clone())),
+            "edit_layer" => Some(NodeData::Boolean(self.edit_layer)),
+            _ => None,
+        }
+    }
+    
+    fn set_parameter(&mut self, name: &str, value: NodeData) {
+        match name {
+            "identifier" => {
+                if let Some(identifier) = value.as_string() {
+                    self.identifier = identifier.trim().to_string();
+                    self.dirty = true;
+                }
+            }
+            "edit_layer" => {
+                if let Some(edit_layer) = value.as_boolean() {
+                    self.edit_layer = edit_layer;
+                    self.dirty = true;
+                }
+            }
+            _ => {}
+        }
+    }
+    
+    fn process(&mut self, _inputs: &HashMap<String, NodeData>) -> HashMap<String, NodeData> {
+        let mut outputs = HashMap::new();
+        
+        if self.dirty {
+            self.dirty = false;
+            if let Err(e) = self.create() {
+                self.status = format!("⚠ {}", e);
+                self.created = None;
+            }
+        }
+        
+        if let Some(identifier) = &self.created {
+            outputs.insert("Stage".to_string(), NodeData::String(identifier.clone()));
+        }
+        outputs
+    }
+}
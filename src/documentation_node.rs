@@ -0,0 +1,273 @@
+//! USD Documentation node - writes generation notes into the stage as prim documentation
+
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::core::usd_engine::with_usd_engine;
+use crate::ui::help::NodeHelp;
+use crate::ui::palette::status_label;
+use crate::ui::keyboard::{focus_marked, handle_key};
+use crate::registry::NodeTypeFactory;
+
+/// Help for the Documentation node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_Documentation",
+    summary: "Write graph name, generation date, parameter summary and notes into a prim's documentation metadata",
+    details: "Records how a stage was generated in the documentation metadata of Prim Path, so anyone opening the file later can trace it back to the graph.",
+    ports: &[
+        ("Stage", "stage_0"),
+        ("Summary", "radius = 2, material = /World/Looks/Red"),
+    ],
+    samples: &[],
+};
+
+/// Factory of the Documentation node, for `crate::node_factories`
+pub fn factories() -> Vec<Box<dyn NodeFactory>> {
+    vec![NodeTypeFactory::boxed(metadata, |position| PluginNodeHandle::new(Box::new(USDDocumentationNode::new(position))))]
+}
+
+/// Metadata of the Documentation node
+fn metadata() -> NodeMetadata {
+    NodeMetadata::new(
+        "USD_Documentation",
+        "Documentation",
+        NodeCategory::new(&["USD", "Stage"]),
+        HELP.summary
+    )
+    .with_color(Color32::from_rgb(80, 150, 200))
+    .with_icon("📝")
+    .with_inputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("USD stage to document"),
+        PortDefinition::optional("Summary", DataType::String)
+            .with_description("Parameter summary to record, one entry per line"),
+    ])
+    .with_outputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("Stage with the documentation prim authored"),
+    ])
+    .with_panel_type(PanelType::Parameter)
+    .with_workspace_compatibility(vec!["3D"])
+}
+
+/// USD Documentation node
+///
+/// Authors the graph name, generation date, a parameter summary and free-form
+/// notes into a Scope prim's documentation metadata, so downstream consumers
+/// can tell how an exported asset was generated.
+pub struct USDDocumentationNode {
+    id: String,
+    position: Pos2,
+    prim_path: String,
+    graph_name: String,
+    notes: String,
+    include_date: bool,
+    /// Parameter summary from the "Summary" input
+    summary: String,
+    stage_ref: String,
+    dirty: bool,
+    status: String,
+}
+
+impl USDDocumentationNode {
+    pub fn new(position: Pos2) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            position,
+            prim_path: "/Documentation".to_string(),
+            graph_name: String::new(),
+            notes: String::new(),
+            include_date: true,
+            summary: String::new(),
+            stage_ref: String::new(),
+            dirty: true,
+            status: "No USD stage connected".to_string(),
+        }
+    }
+    
+    /// Documentation text for the current parameters
+    fn documentation(&self) -> String {
+        let mut lines = Vec::new();
+        if !self.graph_name.is_empty() {
+            lines.push(format!("Graph: {}", self.graph_name));
+        }
+        lines.push(format!("Generator: nodle-usd-plugin {}", env!("CARGO_PKG_VERSION")));
+        if self.include_date {
+            lines.push(format!("Generated: {}", chrono::Local::now().format("%Y-%m-%d %H:%M:%S")));
+        }
+        if !self.summary.is_empty() {
+            lines.push("Parameters:".to_string());
+            lines.extend(self.summary.lines().map(|line| format!("  {}", line.trim())));
+        }
+        if !self.notes.is_empty() {
+            lines.push(String::new());
+            lines.push(self.notes.clone());
+        }
+        lines.join("\n")
+    }
+    
+    fn write_documentation(&mut self) -> Result<(), String> {
+        if self.prim_path.is_empty() {
+            return Err("No prim path set".to_string());
+        }
+        let (stage_ref, prim_path, documentation) = (self.stage_ref.clone(), self.prim_path.clone(), self.documentation());
+        with_usd_engine(|engine| {
+            let stage = engine.resolve_stage(&stage_ref)?;
+            engine.set_prim_documentation(&stage.identifier, &prim_path, &documentation)
+        })?;
+        self.status = format!("Documented {}", self.prim_path);
+        Ok(())
+    }
+}
+
+impl PluginNode for USDDocumentationNode {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+    
+    fn position(&self) -> Pos2 {
+        self.position
+    }
+    
+    fn set_position(&mut self, position: Pos2) {
+        self.position = position;
+    }
+    
+    fn get_parameter_ui(&self) -> ParameterUI {
+        let mut elements = Vec::new();
+        
+        elements.push(UIElement::Heading("USD Documentation".to_string()));
+        elements.push(UIElement::Separator);
+        
+        elements.push(UIElement::TextEdit {
+            label: "Prim Path".to_string(),
+            value: self.prim_path.clone(),
+            parameter_name: "prim_path".to_string(),
+        });
+        elements.push(UIElement::TextEdit {
+            label: "Graph Name".to_string(),
+            value: self.graph_name.clone(),
+            parameter_name: "graph_name".to_string(),
+        });
+        elements.push(UIElement::TextEdit {
+            label: "Notes".to_string(),
+            value: self.notes.clone(),
+            parameter_name: "notes".to_string(),
+        });
+        elements.push(UIElement::Checkbox {
+            label: "Include Date".to_string(),
+            value: self.include_date,
+            parameter_name: "include_date".to_string(),
+        });
+        
+        elements.push(UIElement::Separator);
+        elements.push(status_label(&self.status));
+        elements.extend(HELP.section());
+        
+        ParameterUI { elements: focus_marked(&self.id, elements) }
+    }
+    
+    fn handle_ui_action(&mut self, action: UIAction) -> Vec<ParameterChange> {
+        if let Some(changes) = handle_key(self, &[], &action) {
+            return changes;
+        }
+        
+        let mut changes = Vec::new();
+        
+        if let UIAction::ParameterChanged { parameter, value } = action {
+            match parameter.as_str() {
+                "prim_path" | "graph_name" | "notes" => {
+                    if let Some(text) = value.as_string() {
+                        self.set_parameter(&parameter, NodeData::String(text.to_string()));
+                        changes.push(ParameterChange {
+                            parameter: parameter.clone(),
+                            value: NodeData::String(text.to_string()),
+                        });
+                    }
+                }
+                "include_date" => {
+                    if let Some(include) = value.as_boolean() {
+                        self.set_parameter(&parameter, NodeData::Boolean(include));
+                        changes.push(ParameterChange {
+                            parameter: parameter.clone(),
+                            value: NodeData::Boolean(include),
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+        
+        changes
+    }
+    
+    fn get_parameter(&self, name: &str) -> Option<NodeData> {
+        match name {
+            "prim_path" => Some(NodeData::String(self.prim_path.clone())),
+            "graph_name" => Some(NodeData::String(self.graph_name.clone())),
+            "notes" => Some(NodeData::String(self.notes.clone())),
+            "include_date" => Some(NodeData::Boolean(self.include_date)),
+            _ => None,
+        }
+    }
+    
+    fn set_parameter(&mut self, name: &str, value: NodeData) {
+        match name {
+            "prim_path" => {
+                if let Some(path) = value.as_string() {
+                    self.prim_path = path.trim().to_string();
+                    self.dirty = true;
+                }
+            }
+            "graph_name" => {
+                if let Some(graph_name) = value.as_string() {
+                    self.graph_name = graph_name.trim().to_string();
+                    self.dirty = true;
+                }
+            }
+            "notes" => {
+                if let Some(notes) = value.as_string() {
+                    self.notes = notes.trim().to_string();
+                    self.dirty = true;
+                }
+            }
+            "include_date" => {
+                if let Some(include) = value.as_boolean() {
+                    self.include_date = include;
+                    self.dirty = true;
+                }
+            }
+            _ => {}
+        }
+    }
+    
+    fn process(&mut self, inputs: &HashMap<String, NodeData>) -> HashMap<String, NodeData> {
+        let mut outputs = HashMap::new();
+        
+        let Some(stage_ref) = inputs.get("Stage").and_then(|data| data.as_string()) else {
+            self.stage_ref.clear();
+            self.status = "No USD stage connected".to_string();
+            return outputs;
+        };
+        
+        if stage_ref != self.stage_ref {
+            self.stage_ref = stage_ref.to_string();
+            self.dirty = true;
+        }
+        let summary = inputs.get("Summary").and_then(|data| data.as_string()).unwrap_or_default().trim();
+        if summary != self.summary {
+            self.summary = summary.to_string();
+            self.dirty = true;
+        }
+        
+        // Only re-author when something changed, so the date records when the output was generated
+        if self.dirty {
+            self.dirty = false;
+            if let Err(e) = self.write_documentation() {
+                self.status = format!("⚠ {}", e);
+            }
+        }
+        
+        outputs.insert("Stage".to_string(), NodeData::String(self.stage_ref.clone()));
+        outputs
+    }
+}
@@ -0,0 +1,314 @@
+//! USD Face Set node - groups mesh faces picked in the viewport into a UsdGeomSubset
+
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::core::geom_subset::{format_faces, parse_faces, USDGeomSubset, MATERIAL_BIND_FAMILY};
+use crate::core::usd_engine::with_usd_engine;
+use crate::viewport::picking::picked_faces;
+use crate::ui::help::NodeHelp;
+use crate::ui::palette::status_label;
+use crate::ui::keyboard::{focus_marked, handle_key};
+use crate::registry::NodeTypeFactory;
+
+/// Help for the Face Set node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_FaceSet",
+    summary: "Group mesh faces picked in the viewport into a GeomSubset for per-face materials or export",
+    details: "Box-select faces in the viewport, then store them as a GeomSubset of the mesh. Subsets in the materialBind family can carry their own material binding; Export writes the faces out as a separate mesh.",
+    ports: &[
+        ("Stage", "loaded_0"),
+        ("Subset", "/World/Body/glass"),
+    ],
+    samples: &[],
+};
+
+/// Factory of the Face Set node, for `crate::node_factories`
+pub fn factories() -> Vec<Box<dyn NodeFactory>> {
+    vec![NodeTypeFactory::boxed(metadata, |position| PluginNodeHandle::new(Box::new(USDFaceSetNode::new(position))))]
+}
+
+/// Metadata of the Face Set node
+fn metadata() -> NodeMetadata {
+    NodeMetadata::new(
+        "USD_FaceSet",
+        "Face Set",
+        NodeCategory::new(&["USD", "Geometry"]),
+        HELP.summary
+    )
+    .with_color(Color32::from_rgb(100, 180, 100))
+    .with_icon("▦")
+    .with_inputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("USD stage with the mesh"),
+    ])
+    .with_outputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("Stage with the subset"),
+        PortDefinition::optional("Subset", DataType::String)
+            .with_description("GeomSubset path"),
+    ])
+    .with_panel_type(PanelType::Parameter)
+    .with_workspace_compatibility(vec!["3D"])
+}
+
+/// An action triggered by a button, run on the next process
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FaceSetAction {
+    UseViewportSelection,
+    Export,
+}
+
+/// USD Face Set node
+///
+/// Faces are typed as index lists or taken from the faces picked in the
+/// viewport showing the stage. The subset is re-authored whenever its
+/// parameters change; exporting writes its faces as a standalone mesh.
+pub struct USDFaceSetNode {
+    id: String,
+    position: Pos2,
+    mesh_path: String,
+    subset_name: String,
+    family: String,
+    faces: String,
+    material_path: String,
+    export_path: String,
+    stage_ref: String,
+    /// Subset path once authored
+    subset_path: Option<String>,
+    pending: Vec<FaceSetAction>,
+    dirty: bool,
+    status: String,
+}
+
+impl USDFaceSetNode {
+    pub fn new(position: Pos2) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            position,
+            mesh_path: String::new(),
+            subset_name: "faceSet".to_string(),
+            family: MATERIAL_BIND_FAMILY.to_string(),
+            faces: String::new(),
+            material_path: String::new(),
+            export_path: String::new(),
+            stage_ref: String::new(),
+            subset_path: None,
+            pending: Vec::new(),
+            dirty: true,
+            status: "No USD stage connected".to_string(),
+        }
+    }
+    
+    /// Run pending actions, then author the subset
+    fn apply(&mut self) -> Result<(), String> {
+        let pending = std::mem::take(&mut self.pending);
+        let stage_ref = self.stage_ref.clone();
+        let stage_id = with_usd_engine(|engine| engine.resolve_stage(&stage_ref))?.identifier;
+        
+        if pending.contains(&FaceSetAction::UseViewportSelection) {
+            self.take_viewport_selection(&stage_id)?;
+        }
+        if self.mesh_path.is_empty() {
+            self.subset_path = None;
+            return Err("No mesh path set; pick faces in the viewport or type a path".to_string());
+        }
+        let faces = parse_faces(&self.faces)?;
+        let subset = USDGeomSubset {
+            mesh_path: self.mesh_path.clone(),
+            name: self.subset_name.clone(),
+            family: self.family.clone(),
+            faces,
+            material: Some(self.material_path.clone()).filter(|path| !path.is_empty()),
+        };
+        
+        let export_path = self.export_path.clone();
+        let export = pending.contains(&FaceSetAction::Export);
+        let (subset_path, exported) = with_usd_engine(|engine| -> Result<(String, Option<String>), String> {
+            let subset_path = engine.set_geom_subset(&stage_id, &subset)?;
+            let exported = match export {
+                true => Some(engine.export_geom_subset(&stage_id, &subset_path, &export_path)?),
+                false => None,
+            };
+            Ok((subset_path, exported))
+        })?;
+        
+        self.status = format!("{} faces in '{}'", subset.faces.len(), subset_path);
+        if let Some(exported) = exported {
+            self.status.push_str(&format!("; exported to '{}'", exported));
+        }
+        self.subset_path = Some(subset_path);
+        Ok(())
+    }
+    
+    /// Copy the viewport's picked faces of the mesh, or of the only mesh picked when no path is set
+    fn take_viewport_selection(&mut self, stage_id: &str) -> Result<(), String> {
+        let selection = picked_faces(stage_id);
+        let mesh_count = selection.len();
+        let faces = if self.mesh_path.is_empty() {
+            let mut meshes = selection.into_iter();
+            match (meshes.next(), meshes.next()) {
+                (Some((mesh_path, faces)), None) => {
+                    self.mesh_path = mesh_path;
+                    faces
+                }
+                (None, _) => return Err("No faces are picked in the viewport".to_string()),
+                _ => return Err(format!("Faces of {} meshes are picked; set the mesh path to choose one", mesh_count)),
+            }
+        } else {
+            selection.get(&self.mesh_path)
+                .cloned()
+                .ok_or_else(|| format!("No faces of '{}' are picked in the viewport", self.mesh_path))?
+        };
+        self.faces = format_faces(&faces.iter().copied().collect::<Vec<_>>());
+        Ok(())
+    }
+}
+
+impl PluginNode for USDFaceSetNode {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+    
+    fn position(&self) -> Pos2 {
+        self.position
+    }
+    
+    fn set_position(&mut self, position: Pos2) {
+        self.position = position;
+    }
+    
+    fn get_parameter_ui(&self) -> ParameterUI {
+        let mut elements = Vec::new();
+        let text = |label: &str, value: &str, parameter_name: &str| UIElement::TextEdit {
+            label: label.to_string(),
+            value: value.to_string(),
+            parameter_name: parameter_name.to_string(),
+        };
+        
+        elements.push(UIElement::Heading("USD Face Set".to_string()));
+        elements.push(UIElement::Separator);
+        
+        elements.push(text("Mesh Path", &self.mesh_path, "mesh_path"));
+        elements.push(text("Subset Name", &self.subset_name, "subset_name"));
+        elements.push(text("Family", &self.family, "family"));
+        elements.push(text("Faces", &self.faces, "faces"));
+        elements.push(UIElement::Button {
+            label: "Use Viewport Selection".to_string(),
+            action: "use_viewport_selection".to_string(),
+        });
+        
+        elements.push(UIElement::Separator);
+        elements.push(text("Material", &self.material_path, "material_path"));
+        
+        elements.push(UIElement::Separator);
+        elements.push(text("Export Path", &self.export_path, "export_path"));
+        elements.push(UIElement::Button {
+            label: "Export Subset".to_string(),
+            action: "export".to_string(),
+        });
+        
+        elements.push(UIElement::Separator);
+        elements.push(status_label(&self.status));
+        elements.extend(HELP.section());
+        
+        ParameterUI { elements: focus_marked(&self.id, elements) }
+    }
+    
+    fn handle_ui_action(&mut self, action: UIAction) -> Vec<ParameterChange> {
+        if let Some(changes) = handle_key(self, &[], &action) {
+            return changes;
+        }
+        
+        let mut changes = Vec::new();
+        
+        match action {
+            UIAction::ParameterChanged { parameter, value } => {
+                if let Some(text) = value.as_string() {
+                    if self.get_parameter(&parameter).is_some() {
+                        self.set_parameter(&parameter, NodeData::String(text.to_string()));
+                        changes.push(ParameterChange {
+                            parameter: parameter.clone(),
+                            value: NodeData::String(text.to_string()),
+                        });
+                    }
+                }
+            }
+            UIAction::ButtonClicked { action } => {
+                let action = match action.as_str() {
+                    "use_viewport_selection" => Some(FaceSetAction::UseViewportSelection),
+                    "export" => Some(FaceSetAction::Export),
+                    _ => None,
+                };
+                if let Some(action) = action {
+                    self.pending.push(action);
+                    self.dirty = true;
+                }
+            }
+        }
+        
+        changes
+    }
+    
+    fn get_parameter(&self, name: &str) -> Option<NodeData> {
+        let value = match name {
+            "mesh_path" => &self.mesh_path,
+            "subset_name" => &self.subset_name,
+            "family" => &self.family,
+            "faces" => &self.faces,
+            "material_path" => &self.material_path,
+            "export_path" => &self.export_path,
+            _ => return None,
+        };
+        Some(NodeData::String(value.clone()))
+    }
+    
+    fn set_parameter(&mut self, name: &str, value: NodeData) {
+        let Some(text) = value.as_string().map(|text| text.trim().to_string()) else {
+            return;
+        };
+        match name {
+            "mesh_path" => self.mesh_path = text.trim_end_matches('/').to_string(),
+            "subset_name" => self.subset_name = text,
+            "family" => self.family = text,
+            "faces" => self.faces = text,
+            "material_path" => self.material_path = text,
+            // Exports only run from the button
+            "export_path" => {
+                self.export_path = text;
+                return;
+            }
+            _ => return,
+        }
+        self.dirty = true;
+    }
+    
+    fn process(&mut self, inputs: &HashMap<String, NodeData>) -> HashMap<String, NodeData> {
+        let mut outputs = HashMap::new();
+        
+        let Some(stage_ref) = inputs.get("Stage").and_then(|data| data.as_string()) else {
+            self.stage_ref.clear();
+            self.subset_path = None;
+            self.status = "No USD stage connected".to_string();
+            return outputs;
+        };
+        
+        if stage_ref != self.stage_ref {
+            self.stage_ref = stage_ref.to_string();
+            self.dirty = true;
+        }
+        
+        if self.dirty {
+            self.dirty = false;
+            if let Err(e) = self.apply() {
+                self.status = format!("⚠ {}", e);
+                self.subset_path = None;
+            }
+        }
+        
+        outputs.insert("Stage".to_string(), NodeData::String(self.stage_ref.clone()));
+        if let Some(subset_path) = &self.subset_path {
+            outputs.insert("Subset".to_string(), NodeData::String(subset_path.clone()));
+        }
+        outputs
+    }
+}
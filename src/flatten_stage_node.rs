@@ -0,0 +1,206 @@
+//! USD Flatten Stage node - composes a stage into a single layer for publishing
+
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::core::usd_engine::{with_usd_engine, USDStage};
+use crate::ui::help::NodeHelp;
+use crate::ui::palette::status_label;
+use crate::ui::keyboard::{focus_marked, handle_key};
+use crate::registry::NodeTypeFactory;
+
+/// Help for the Flatten Stage node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_FlattenStage",
+    summary: "Compose all layers of a stage into a single flattened layer",
+    details: "Bakes sublayers, references, payloads and variants into one layer, as a new in-memory stage and optionally a file at Output Path.",
+    ports: &[
+        ("Stage", "loaded_0"),
+        ("Path", "/tmp/flattened.usda"),
+    ],
+    samples: &[],
+};
+
+/// Factory of the Flatten Stage node, for `crate::node_factories`
+pub fn factories() -> Vec<Box<dyn NodeFactory>> {
+    vec![NodeTypeFactory::boxed(metadata, |position| PluginNodeHandle::new(Box::new(USDFlattenStageNode::new(position))))]
+}
+
+/// Metadata of the Flatten Stage node
+fn metadata() -> NodeMetadata {
+    NodeMetadata::new(
+        "USD_FlattenStage",
+        "Flatten Stage",
+        NodeCategory::new(&["USD", "Stage"]),
+        HELP.summary
+    )
+    .with_color(Color32::from_rgb(80, 150, 200))
+    .with_icon("🥞")
+    .with_inputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("USD stage to flatten"),
+    ])
+    .with_outputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("Handle to the flattened stage"),
+        PortDefinition::optional("Path", DataType::String)
+            .with_description("File the flattened layer was exported to"),
+    ])
+    .with_panel_type(PanelType::Parameter)
+    .with_workspace_compatibility(vec!["3D"])
+}
+
+/// USD Flatten Stage node
+pub struct USDFlattenStageNode {
+    id: String,
+    position: Pos2,
+    /// File to export the flattened layer to; empty keeps it as a temporary stage
+    output_path: String,
+    stage_ref: String,
+    flattened: Option<USDStage>,
+    dirty: bool,
+    status: String,
+}
+
+impl USDFlattenStageNode {
+    pub fn new(position: Pos2) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            position,
+            output_path: String::new(),
+            stage_ref: String::new(),
+            flattened: None,
+            dirty: true,
+            status: "No USD stage connected".to_string(),
+        }
+    }
+    
+    fn flatten(&mut self) -> Result<(), String> {
+        let stage_ref = self.stage_ref.clone();
+        let output_path = self.output_path.clone();
+        
+        let flattened = with_usd_engine(|engine| {
+            let stage = engine.resolve_stage(&stage_ref)?;
+            engine.flatten_stage(&stage.identifier, Some(output_path.as_str()))
+        })?;
+        
+        self.status = format!("Flattened to {}", flattened.path);
+        self.flattened = Some(flattened);
+        Ok(())
+    }
+}
+
+impl PluginNode for USDFlattenStageNode {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+    
+    fn position(&self) -> Pos2 {
+        self.position
+    }
+    
+    fn set_position(&mut self, position: Pos2) {
+        self.position = position;
+    }
+    
+    fn get_parameter_ui(&self) -> ParameterUI {
+        let mut elements = Vec::new();
+        
+        elements.push(UIElement::Heading("USD Flatten Stage".to_string()));
+        elements.push(UIElement::Separator);
+        
+        elements.push(UIElement::TextEdit {
+            label: "Export Path".to_string(),
+            value: self.output_path.clone(),
+            parameter_name: "output_path".to_string(),
+        });
+        
+        elements.push(UIElement::Button {
+            label: "Flatten Again".to_string(),
+            action: "reflatten".to_string(),
+        });
+        
+        elements.push(UIElement::Separator);
+        elements.push(status_label(&self.status));
+        elements.extend(HELP.section());
+        
+        ParameterUI { elements: focus_marked(&self.id, elements) }
+    }
+    
+    fn handle_ui_action(&mut self, action: UIAction) -> Vec<ParameterChange> {
+        if let Some(changes) = handle_key(self, &[], &action) {
+            return changes;
+        }
+        
+        let mut changes = Vec::new();
+        
+        match action {
+            UIAction::ParameterChanged { parameter, value } => {
+                if parameter == "output_path" {
+                    if let Some(path) = value.as_string() {
+                        self.set_parameter(&parameter, NodeData::String(path.to_string()));
+                        changes.push(ParameterChange {
+                            parameter: parameter.clone(),
+                            value: NodeData::String(path.to_string()),
+                        });
+                    }
+                }
+            }
+            UIAction::ButtonClicked { action } => {
+                if action == "reflatten" {
+                    self.dirty = true;
+                }
+            }
+        }
+        
+        changes
+    }
+    
+    fn get_parameter(&self, name: &str) -> Option<NodeData> {
+        match name {
+            "output_path" => Some(NodeData::String(self.output_path.clone())),
+            _ => None,
+        }
+    }
+    
+    fn set_parameter(&mut self, name: &str, value: NodeData) {
+        match name {
+            "output_path" => {
+                if let Some(path) = value.as_string() {
+                    self.output_path = path.trim().to_string();
+                    self.dirty = true;
+                }
+            }
+            _ => {}
+        }
+    }
+    
+    fn process(&mut self, inputs: &HashMap<String, NodeData>) -> HashMap<String, NodeData> {
+        let mut outputs = HashMap::new();
+        
+        let Some(stage_ref) = inputs.get("Stage").and_then(|data| data.as_string()) else {
+            self.stage_ref.clear();
+            self.flattened = None;
+            self.status = "No USD stage connected".to_string();
+            return outputs;
+        };
+        
+        if stage_ref != self.stage_ref {
+            self.stage_ref = stage_ref.to_string();
+            self.dirty = true;
+        }
+        
+        if self.dirty {
+            self.dirty = false;
+            if let Err(e) = self.flatten() {
+                self.status = format!("⚠ {}", e);
+                self.flattened = None;
+            }
+        }
+        
+        if let Some(flattened) = &self.flattened {
+            outputs.insert("Stage".to_string(), NodeData::String(flattened.identifier.clone()));
+            outputs.insert("Path".to_string(), NodeData::String(flattened.path.clone()));
+        }
+        outputs
+    }
+}
@@ -0,0 +1,147 @@
+//! USD Bounding Volume node
+//!
+//! Authors simplified proxies of chosen prims for game-engine export: a box,
+//! convex hull or k-DOP around each prim's geometry, as a guide mesh under
+//! the prim, optionally set up as a UsdPhysics collision shape.
+
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::core::bounding_volume::{bounding_volume, USDBoundingVolume, VolumeShape};
+use crate::core::usd_engine::with_usd_engine;
+use crate::modular::{flag_value, float_value, input_stage, text_value, ModularNode, ParameterSpec};
+use crate::ui::help::NodeHelp;
+
+/// Help for the Bounding Volume node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_BoundingVolume",
+    summary: "Author box, convex hull or k-DOP proxies of prims as guide meshes or collision shapes",
+    details: "For each prim, gathers the points of its meshes and other geometry in the prim's space and authors a simplified volume around them as a guide mesh under the prim: its box, its convex hull, or a 14, 18 or 26-DOP, a box with its edges and corners bevelled along fixed diagonals. Collision applies the UsdPhysics collision and mesh collision APIs with a matching approximation so game engines use the proxy as the prim's collider; otherwise the proxy can serve as an occluder. Existing guide prims are ignored, so proxies can be re-authored in place.",
+    ports: &[
+        ("Stage", "loaded_0"),
+        ("Prim Paths", "/World/Crate, /World/Barrel"),
+        ("Proxy Paths", "/World/Crate/proxy, /World/Barrel/proxy"),
+    ],
+    samples: &[],
+};
+
+/// Shape choices, as `VolumeShape` labels
+const SHAPES: &[&str] = &["Box", "Convex Hull", "14-DOP", "18-DOP", "26-DOP"];
+
+/// Prim paths separated by commas or whitespace
+fn parse_prim_paths(text: &str) -> Vec<String> {
+    text.split([',', ' ', '\n'])
+        .map(|path| path.trim().trim_end_matches('/'))
+        .filter(|path| !path.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// USD Bounding Volume node with parameter controls
+#[derive(Default)]
+pub struct USDBoundingVolumeNode;
+
+/// Core logic for bounding volume authoring
+pub struct USDBoundingVolumeLogic;
+
+impl USDBoundingVolumeLogic {
+    /// Execute the bounding volume operation
+    pub fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        let stage_id = input_stage(inputs)?;
+        let prim_paths = parse_prim_paths(&text_value(inputs, "Prim Paths", parameters, "prim_paths").unwrap_or_default());
+        if prim_paths.is_empty() {
+            return Err("No prim paths set".to_string());
+        }
+        let shape = parameters.get("shape").and_then(|data| data.as_string())
+            .and_then(VolumeShape::from_label)
+            .unwrap_or(VolumeShape::Box);
+        let name = parameters.get("name").and_then(|data| data.as_string())
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .unwrap_or("proxy")
+            .to_string();
+        let collision = flag_value(parameters, "collision", false);
+        let time = float_value(inputs, "Time", parameters, "time_code", 0.0) as f64;
+        
+        let proxy_paths = with_usd_engine(|engine| -> Result<Vec<String>, String> {
+            let mut proxy_paths = Vec::with_capacity(prim_paths.len());
+            for prim_path in &prim_paths {
+                let points = engine.get_prim_points(&stage_id, prim_path, time)?;
+                let mesh = bounding_volume(&points, shape)
+                    .ok_or_else(|| format!("'{}' has no geometry to bound", prim_path))?;
+                let volume = USDBoundingVolume {
+                    prim_path: prim_path.clone(),
+                    name: name.clone(),
+                    shape,
+                    collision,
+                    mesh,
+                };
+                proxy_paths.push(engine.set_bounding_volume(&stage_id, &volume)?);
+            }
+            Ok(proxy_paths)
+        })?;
+        println!("✓ Authored {} {} proxies{}", proxy_paths.len(), shape.label(), if collision { " with collisions" } else { "" });
+        
+        Ok(HashMap::from([
+            ("Stage".to_string(), inputs["Stage"].clone()),
+            ("Proxy Paths".to_string(), NodeData::String(proxy_paths.join(", "))),
+        ]))
+    }
+}
+
+impl ModularNode for USDBoundingVolumeNode {
+    const NAME: &'static str = "USD Bounding Volume";
+    const HELP: &'static NodeHelp = &HELP;
+    
+    fn metadata() -> NodeMetadata {
+        NodeMetadata::new(
+            "USD_BoundingVolume",
+            "Bounding Volume",
+            NodeCategory::new(&["USD", "Geometry"]),
+            HELP.summary
+        )
+        .with_color(Color32::from_rgb(100, 180, 100))
+        .with_icon("⬡")
+        .with_inputs(vec![
+            PortDefinition::required("Stage", DataType::String)
+                .with_description("USD stage"),
+            PortDefinition::optional("Prim Paths", DataType::String)
+                .with_description("Prims to bound, separated by commas, overriding the parameter"),
+            PortDefinition::optional("Time", DataType::Float)
+                .with_description("Time code to read the geometry at, overriding the parameter"),
+        ])
+        .with_outputs(vec![
+            PortDefinition::required("Stage", DataType::String)
+                .with_description("Stage with the proxies"),
+            PortDefinition::required("Proxy Paths", DataType::String)
+                .with_description("Authored proxy mesh paths, separated by commas"),
+        ])
+        .with_panel_type(PanelType::Parameter)
+        .with_workspace_compatibility(vec!["3D"])
+    }
+    
+    fn parameters() -> Vec<ParameterSpec> {
+        vec![
+            ParameterSpec::text("prim_paths", "Prim Paths", ""),
+            ParameterSpec::choice("shape", "Shape", SHAPES, "Box"),
+            ParameterSpec::text("name", "Proxy Name", "proxy"),
+            ParameterSpec::toggle("collision", "Collision", false),
+            ParameterSpec::float("time_code", "Time Code", 0.0, -100000.0, 100000.0),
+        ]
+    }
+    
+    fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        USDBoundingVolumeLogic::execute(inputs, parameters)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn shape_choices_name_every_shape() {
+        assert_eq!(SHAPES.len(), VolumeShape::ALL.len());
+        assert!(SHAPES.iter().all(|label| VolumeShape::from_label(label).is_some_and(|shape| shape.label() == *label)));
+        assert_eq!(parse_prim_paths("/World/Crate, /World/Barrel/\n/World/Lamp"), ["/World/Crate", "/World/Barrel", "/World/Lamp"]);
+    }
+}
@@ -0,0 +1,224 @@
+//! USD Camera primitive
+//!
+//! Authors a UsdGeomCamera with its physical lens, focus, shutter and
+//! clipping attributes. Snapping to the view takes the transform, focal
+//! length, apertures and clipping from the viewport showing the stage.
+
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::core::usd_engine::{with_usd_engine, XFORM_OP_ORDER};
+use crate::core::usd_value::UsdValue;
+use crate::modular::{child_prim_inputs, child_prim_path, define_prim, flag_value, float_value, input_stage, ModularNode, ParameterSpec};
+use crate::ui::help::NodeHelp;
+use crate::viewport::projection::{view_camera, ProjectionCamera};
+
+/// Help for the Camera node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_Camera",
+    summary: "Creates a UsdGeomCamera with physical lens, focus and shutter settings",
+    details: "Focal length and apertures share units, millimeters by convention. An f-stop of 0 turns depth of field off; shutter open and close are offsets in frames from each time code, used for motion blur. Snap to View copies the transform, focal length, apertures and clipping range from the viewport showing the stage; otherwise place the camera with Translate and Rotate nodes.",
+    ports: &[
+        ("Stage", "stage_0"),
+        ("Name", "shotCam"),
+        ("Focal Length", "35"),
+        ("Focus Distance", "4.5"),
+        ("Prim Path", "/World/shotCam"),
+    ],
+    samples: &[],
+};
+
+/// Physical camera attributes, in UsdGeomCamera units
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PhysicalCamera {
+    pub focal_length: f32,
+    pub f_stop: f32,
+    pub focus_distance: f32,
+    pub horizontal_aperture: f32,
+    pub vertical_aperture: f32,
+    pub shutter_open: f64,
+    pub shutter_close: f64,
+    pub near: f32,
+    pub far: f32,
+}
+
+impl PhysicalCamera {
+    /// Take the lens and clipping range of a viewport camera, keeping focus and shutter
+    pub fn snapped_to(self, view: &ProjectionCamera) -> Self {
+        Self {
+            focal_length: view.focal_length,
+            horizontal_aperture: view.horizontal_aperture,
+            vertical_aperture: view.vertical_aperture,
+            near: view.near,
+            far: view.far,
+            ..self
+        }
+    }
+    
+    /// Check the values make a usable camera
+    pub fn validate(&self) -> Result<(), String> {
+        if self.focal_length <= 0.0 || self.horizontal_aperture <= 0.0 || self.vertical_aperture <= 0.0 {
+            return Err("Focal length and apertures must be positive".to_string());
+        }
+        if self.near <= 0.0 || self.far <= self.near {
+            return Err(format!("Clipping range {} to {} is invalid", self.near, self.far));
+        }
+        if self.shutter_close < self.shutter_open {
+            return Err(format!("Shutter closes at {} before it opens at {}", self.shutter_close, self.shutter_open));
+        }
+        Ok(())
+    }
+    
+    /// Camera schema attributes
+    pub fn attributes(&self) -> Vec<(&'static str, UsdValue)> {
+        vec![
+            ("focalLength", UsdValue::Float(self.focal_length)),
+            ("fStop", UsdValue::Float(self.f_stop)),
+            ("focusDistance", UsdValue::Float(self.focus_distance)),
+            ("horizontalAperture", UsdValue::Float(self.horizontal_aperture)),
+            ("verticalAperture", UsdValue::Float(self.vertical_aperture)),
+            ("shutter:open", UsdValue::Double(self.shutter_open)),
+            ("shutter:close", UsdValue::Double(self.shutter_close)),
+            ("clippingRange", UsdValue::Vec2([self.near as f64, self.far as f64])),
+        ]
+    }
+}
+
+/// USD Camera node with parameter controls
+#[derive(Default)]
+pub struct USDCameraNode;
+
+/// Core logic for USD camera creation
+pub struct USDCameraLogic;
+
+impl USDCameraLogic {
+    /// Execute the camera creation operation
+    pub fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        let stage_id = input_stage(inputs)?;
+        let prim_path = child_prim_path(inputs, parameters, "Camera");
+        let number = |parameter: &str, default: f32| parameters.get(parameter).and_then(|data| data.as_float()).unwrap_or(default);
+        let defaults = ProjectionCamera::default();
+        let mut camera = PhysicalCamera {
+            focal_length: float_value(inputs, "Focal Length", parameters, "focal_length", defaults.focal_length),
+            f_stop: number("f_stop", 0.0),
+            focus_distance: float_value(inputs, "Focus Distance", parameters, "focus_distance", 0.0),
+            horizontal_aperture: number("horizontal_aperture", defaults.horizontal_aperture),
+            vertical_aperture: number("vertical_aperture", defaults.vertical_aperture),
+            shutter_open: number("shutter_open", 0.0) as f64,
+            shutter_close: number("shutter_close", 0.0) as f64,
+            near: number("near_clip", 0.1),
+            far: number("far_clip", 10000.0),
+        };
+        
+        let view = match flag_value(parameters, "snap_to_view", false) {
+            true => Some(view_camera(&stage_id).ok_or_else(|| "No viewport is showing this stage to snap to".to_string())?),
+            false => None,
+        };
+        let mut attributes = Vec::new();
+        if let Some(view) = &view {
+            camera = camera.snapped_to(view);
+            let (translate, rotate) = view.xform_ops();
+            let vec3 = |v: glam::Vec3| UsdValue::Vec3([v.x as f64, v.y as f64, v.z as f64]);
+            attributes.push((XFORM_OP_ORDER[0], vec3(translate)));
+            attributes.push((XFORM_OP_ORDER[1], vec3(rotate)));
+        }
+        camera.validate()?;
+        attributes.extend(camera.attributes());
+        
+        let prim = define_prim(&stage_id, &prim_path, "Camera", attributes)?;
+        if view.is_some() {
+            with_usd_engine(|engine| -> Result<(), String> {
+                engine.add_xform_op(&stage_id, &prim.path, XFORM_OP_ORDER[0])?;
+                engine.add_xform_op(&stage_id, &prim.path, XFORM_OP_ORDER[1])
+            })?;
+        }
+        println!("✓ Created USD camera: {} ({}mm)", prim.path, camera.focal_length);
+        
+        Ok(HashMap::from([
+            ("Stage".to_string(), inputs["Stage"].clone()),
+            ("Prim Path".to_string(), NodeData::String(prim.path)),
+        ]))
+    }
+}
+
+impl ModularNode for USDCameraNode {
+    const NAME: &'static str = "USD Camera";
+    const HELP: &'static NodeHelp = &HELP;
+    
+    fn metadata() -> NodeMetadata {
+        NodeMetadata::new(
+            "USD_Camera",
+            "Camera",
+            NodeCategory::new(&["USD", "Geometry"]),
+            HELP.summary
+        )
+        .with_color(Color32::from_rgb(100, 180, 100))
+        .with_icon("🎥")
+        .with_inputs(child_prim_inputs("Camera").into_iter().chain([
+            PortDefinition::optional("Focal Length", DataType::Float)
+                .with_description("Focal length in mm, overriding the parameter"),
+            PortDefinition::optional("Focus Distance", DataType::Float)
+                .with_description("Distance in focus, overriding the parameter"),
+        ]).collect())
+        .with_outputs(vec![
+            PortDefinition::required("Stage", DataType::String)
+                .with_description("Stage with the camera"),
+            PortDefinition::required("Prim Path", DataType::String)
+                .with_description("Created camera path"),
+        ])
+        .with_panel_type(PanelType::Parameter)
+        .with_workspace_compatibility(vec!["3D"])
+    }
+    
+    fn parameters() -> Vec<ParameterSpec> {
+        let defaults = ProjectionCamera::default();
+        vec![
+            ParameterSpec::text("parent_path", "Parent Path", "/World"),
+            ParameterSpec::text("name", "Name", "Camera"),
+            ParameterSpec::float("focal_length", "Focal Length (mm)", defaults.focal_length, 1.0, 1000.0),
+            ParameterSpec::float("f_stop", "F-Stop", 0.0, 0.0, 64.0),
+            ParameterSpec::float("focus_distance", "Focus Distance", 0.0, 0.0, 10000.0),
+            ParameterSpec::float("horizontal_aperture", "Horizontal Aperture (mm)", defaults.horizontal_aperture, 1.0, 100.0),
+            ParameterSpec::float("vertical_aperture", "Vertical Aperture (mm)", defaults.vertical_aperture, 1.0, 100.0),
+            ParameterSpec::float("shutter_open", "Shutter Open", 0.0, -1.0, 1.0),
+            ParameterSpec::float("shutter_close", "Shutter Close", 0.0, -1.0, 1.0),
+            ParameterSpec::float("near_clip", "Near Clip", 0.1, 0.0001, 1000.0),
+            ParameterSpec::float("far_clip", "Far Clip", 10000.0, 1.0, 1_000_000.0),
+            ParameterSpec::toggle("snap_to_view", "Snap to View", false),
+        ]
+    }
+    
+    fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        USDCameraLogic::execute(inputs, parameters)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn snapping_keeps_focus_and_shutter() {
+        let camera = PhysicalCamera {
+            focal_length: 35.0,
+            f_stop: 2.8,
+            focus_distance: 4.5,
+            horizontal_aperture: 36.0,
+            vertical_aperture: 24.0,
+            shutter_open: -0.25,
+            shutter_close: 0.25,
+            near: 0.1,
+            far: 1000.0,
+        };
+        assert!(camera.validate().is_ok());
+        assert!(camera.attributes().contains(&("shutter:open", UsdValue::Double(-0.25))));
+        
+        let view = ProjectionCamera::from_view(glam::Vec3::Z, glam::Vec3::ZERO, glam::Vec3::Y, 0.8, 2.0, 0.01, 50.0);
+        let snapped = camera.snapped_to(&view);
+        assert_eq!((snapped.f_stop, snapped.focus_distance, snapped.shutter_close), (2.8, 4.5, 0.25));
+        assert_eq!((snapped.focal_length, snapped.near, snapped.far), (view.focal_length, 0.01, 50.0));
+        assert!((snapped.horizontal_aperture / snapped.vertical_aperture - 2.0).abs() < 1e-5);
+        
+        assert!(PhysicalCamera { near: 10.0, far: 1.0, ..camera }.validate().is_err());
+        assert!(PhysicalCamera { shutter_open: 0.5, ..camera }.validate().is_err());
+    }
+}
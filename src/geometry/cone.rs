@@ -1,8 +1,94 @@
-//! USD Cone geometry primitive - stub implementation
+//! USD Cone geometry primitive
 
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::core::usd_value::UsdValue;
+use crate::modular::{child_prim_inputs, child_prim_path, define_prim, float_value, input_stage, text_value, ModularNode, ParameterSpec};
+use crate::ui::help::NodeHelp;
+
+/// Help for the Cone node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_Cone",
+    summary: "Creates a USD cone primitive",
+    details: "Defines a UsdGeomCone centered on the origin, pointing up its axis.",
+    ports: &[
+        ("Stage", "stage_0"),
+        ("Name", "Cone"),
+        ("Prim Path", "/World/Cone"),
+    ],
+    samples: &[],
+};
+
+/// USD Cone node with parameter controls
+#[derive(Default)]
 pub struct USDConeNode;
+
+/// Core logic for USD cone creation
 pub struct USDConeLogic;
 
-impl Default for USDConeNode {
-    fn default() -> Self { Self }
-}
\ No newline at end of file
+impl USDConeLogic {
+    /// Execute the cone creation operation
+    pub fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        let stage_id = input_stage(inputs)?;
+        let prim_path = child_prim_path(inputs, parameters, "Cone");
+        let radius = float_value(inputs, "Radius", parameters, "radius", 1.0);
+        let height = float_value(inputs, "Height", parameters, "height", 2.0);
+        let axis = text_value(inputs, "Axis", parameters, "axis").unwrap_or_else(|| "Y".to_string());
+        
+        let prim = define_prim(&stage_id, &prim_path, "Cone", vec![
+            ("radius", UsdValue::Double(radius as f64)),
+            ("height", UsdValue::Double(height as f64)),
+            ("axis", UsdValue::Token(axis)),
+        ])?;
+        println!("✓ Created USD cone: {} (radius: {}, height: {})", prim.path, radius, height);
+        
+        Ok(HashMap::from([
+            ("Stage".to_string(), inputs["Stage"].clone()),
+            ("Prim Path".to_string(), NodeData::String(prim.path)),
+        ]))
+    }
+}
+
+impl ModularNode for USDConeNode {
+    const NAME: &'static str = "USD Cone";
+    const HELP: &'static NodeHelp = &HELP;
+    
+    fn metadata() -> NodeMetadata {
+        NodeMetadata::new(
+            "USD_Cone",
+            "Cone",
+            NodeCategory::new(&["USD", "Geometry"]),
+            HELP.summary
+        )
+        .with_color(Color32::from_rgb(100, 180, 100))
+        .with_icon("🔺")
+        .with_inputs(child_prim_inputs("Prim").into_iter().chain([
+            PortDefinition::optional("Radius", DataType::Float)
+                .with_description("Cone base radius, overriding the parameter"),
+            PortDefinition::optional("Height", DataType::Float)
+                .with_description("Cone height, overriding the parameter"),
+        ]).collect())
+        .with_outputs(vec![
+            PortDefinition::required("Stage", DataType::String)
+                .with_description("Stage with the cone"),
+            PortDefinition::required("Prim Path", DataType::String)
+                .with_description("Created prim path"),
+        ])
+        .with_panel_type(PanelType::Parameter)
+        .with_workspace_compatibility(vec!["3D"])
+    }
+    
+    fn parameters() -> Vec<ParameterSpec> {
+        vec![
+            ParameterSpec::text("parent_path", "Parent Path", "/World"),
+            ParameterSpec::text("name", "Name", "Cone"),
+            ParameterSpec::float("radius", "Radius", 1.0, 0.001, 100.0),
+            ParameterSpec::float("height", "Height", 2.0, 0.001, 100.0),
+            ParameterSpec::choice("axis", "Axis", &["X", "Y", "Z"], "Y"),
+        ]
+    }
+    
+    fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        USDConeLogic::execute(inputs, parameters)
+    }
+}
@@ -1,14 +1,86 @@
-//! USD Cube node - placeholder for modular implementation
+//! USD Cube geometry primitive
 
-// This is a placeholder - the cube node will be implemented 
-// in the modular structure like sphere and cylinder
-// For now, we'll use a simple stub to avoid compilation errors
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::core::usd_value::UsdValue;
+use crate::modular::{child_prim_inputs, child_prim_path, define_prim, float_value, input_stage, ModularNode, ParameterSpec};
+use crate::ui::help::NodeHelp;
 
+/// Help for the Cube node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_Cube",
+    summary: "Creates a USD cube primitive",
+    details: "Defines a UsdGeomCube, an implicit box whose size attribute is its edge length. With Live Edit, Size is set on the cube as you edit it and follows changes made to it elsewhere.",
+    ports: &[
+        ("Stage", "stage_0"),
+        ("Name", "Crate"),
+        ("Prim Path", "/World/Crate"),
+    ],
+    samples: &[],
+};
+
+/// USD Cube node with parameter controls
+#[derive(Default)]
 pub struct USDCubeNode;
+
+/// Core logic for USD cube creation
 pub struct USDCubeLogic;
 
-impl Default for USDCubeNode {
-    fn default() -> Self {
-        Self
+impl USDCubeLogic {
+    /// Execute the cube creation operation
+    pub fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        let stage_id = input_stage(inputs)?;
+        let prim_path = child_prim_path(inputs, parameters, "Cube");
+        let size = float_value(inputs, "Size", parameters, "size", 2.0);
+        
+        let prim = define_prim(&stage_id, &prim_path, "Cube", vec![
+            ("size", UsdValue::Double(size as f64)),
+        ])?;
+        println!("✓ Created USD cube: {} (size: {})", prim.path, size);
+        
+        Ok(HashMap::from([
+            ("Stage".to_string(), inputs["Stage"].clone()),
+            ("Prim Path".to_string(), NodeData::String(prim.path)),
+        ]))
+    }
+}
+
+impl ModularNode for USDCubeNode {
+    const NAME: &'static str = "USD Cube";
+    const HELP: &'static NodeHelp = &HELP;
+    
+    fn metadata() -> NodeMetadata {
+        NodeMetadata::new(
+            "USD_Cube",
+            "Cube",
+            NodeCategory::new(&["USD", "Geometry"]),
+            HELP.summary
+        )
+        .with_color(Color32::from_rgb(100, 180, 100))
+        .with_icon("🔳")
+        .with_inputs(child_prim_inputs("Prim").into_iter().chain([
+            PortDefinition::optional("Size", DataType::Float)
+                .with_description("Edge length, overriding the parameter"),
+        ]).collect())
+        .with_outputs(vec![
+            PortDefinition::required("Stage", DataType::String)
+                .with_description("Stage with the cube"),
+            PortDefinition::required("Prim Path", DataType::String)
+                .with_description("Created prim path"),
+        ])
+        .with_panel_type(PanelType::Parameter)
+        .with_workspace_compatibility(vec!["3D"])
+    }
+    
+    fn parameters() -> Vec<ParameterSpec> {
+        vec![
+            ParameterSpec::text("parent_path", "Parent Path", "/World"),
+            ParameterSpec::text("name", "Name", "Cube"),
+            ParameterSpec::float("size", "Size", 2.0, 0.001, 1000.0).with_attribute("size", "double"),
+        ]
+    }
+    
+    fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        USDCubeLogic::execute(inputs, parameters)
     }
-}
\ No newline at end of file
+}
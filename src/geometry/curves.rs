@@ -1,8 +1,123 @@
-//! USD Curves geometry primitive - stub implementation
+//! USD Basis Curves geometry primitive
 
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use std::f64::consts::TAU;
+use crate::core::usd_value::UsdValue;
+use crate::modular::{child_prim_inputs, child_prim_path, define_prim, float_value, input_stage, text_value, ModularNode, ParameterSpec};
+use crate::ui::help::NodeHelp;
+
+/// Help for the Curves node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_Curves",
+    summary: "Creates a helix as USD basis curves",
+    details: "Defines a helix as UsdGeomBasisCurves, linear or as a Catmull-Rom cubic, with the given radius, height and turns.",
+    ports: &[
+        ("Stage", "stage_0"),
+        ("Name", "Spring"),
+        ("Prim Path", "/World/Spring"),
+    ],
+    samples: &[],
+};
+
+/// Vertices of a helix rising along Y, `segments` per turn
+pub fn helix(radius: f64, height: f64, turns: f64, segments: usize) -> Vec<[f64; 3]> {
+    let count = ((turns * segments as f64).round() as usize).max(1);
+    (0..=count)
+        .map(|vertex| {
+            let t = vertex as f64 / count as f64;
+            let angle = TAU * turns * t;
+            [radius * angle.cos(), height * t, radius * angle.sin()]
+        })
+        .collect()
+}
+
+/// USD Curves node with parameter controls
+#[derive(Default)]
 pub struct USDCurvesNode;
+
+/// Core logic for USD curves creation
 pub struct USDCurvesLogic;
 
-impl Default for USDCurvesNode {
-    fn default() -> Self { Self }
-}
\ No newline at end of file
+impl USDCurvesLogic {
+    /// Execute the curves creation operation
+    pub fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        let stage_id = input_stage(inputs)?;
+        let prim_path = child_prim_path(inputs, parameters, "Curves");
+        let radius = float_value(inputs, "Radius", parameters, "radius", 0.5);
+        let height = float_value(inputs, "Height", parameters, "height", 2.0);
+        let turns = float_value(inputs, "Turns", parameters, "turns", 4.0);
+        let segments = float_value(inputs, "Segments", parameters, "segments", 16.0).round() as usize;
+        let width = float_value(inputs, "Width", parameters, "width", 0.02);
+        let curve_type = text_value(inputs, "Type", parameters, "type").unwrap_or_else(|| "cubic".to_string());
+        
+        let points = helix(radius as f64, height as f64, turns as f64, segments);
+        let mut attributes = vec![
+            ("curveVertexCounts", UsdValue::Array(vec![UsdValue::Int(points.len() as i64)])),
+            ("widths", UsdValue::Array(vec![UsdValue::Float(width); points.len()])),
+            ("points", UsdValue::Array(points.into_iter().map(UsdValue::Vec3).collect())),
+            ("wrap", UsdValue::Token("nonperiodic".to_string())),
+        ];
+        if curve_type == "cubic" {
+            // Catmull-Rom passes through the helix vertices
+            attributes.push(("basis", UsdValue::Token("catmullRom".to_string())));
+        }
+        attributes.push(("type", UsdValue::Token(curve_type)));
+        let prim = define_prim(&stage_id, &prim_path, "BasisCurves", attributes)?;
+        println!("✓ Created USD curves: {} ({} turns)", prim.path, turns);
+        
+        Ok(HashMap::from([
+            ("Stage".to_string(), inputs["Stage"].clone()),
+            ("Prim Path".to_string(), NodeData::String(prim.path)),
+        ]))
+    }
+}
+
+impl ModularNode for USDCurvesNode {
+    const NAME: &'static str = "USD Curves";
+    const HELP: &'static NodeHelp = &HELP;
+    
+    fn metadata() -> NodeMetadata {
+        NodeMetadata::new(
+            "USD_Curves",
+            "Curves",
+            NodeCategory::new(&["USD", "Geometry"]),
+            HELP.summary
+        )
+        .with_color(Color32::from_rgb(100, 180, 100))
+        .with_icon("〰")
+        .with_inputs(child_prim_inputs("Prim").into_iter().chain([
+            PortDefinition::optional("Radius", DataType::Float)
+                .with_description("Helix radius, overriding the parameter"),
+            PortDefinition::optional("Height", DataType::Float)
+                .with_description("Helix height, overriding the parameter"),
+            PortDefinition::optional("Turns", DataType::Float)
+                .with_description("Number of turns, overriding the parameter"),
+        ]).collect())
+        .with_outputs(vec![
+            PortDefinition::required("Stage", DataType::String)
+                .with_description("Stage with the curves"),
+            PortDefinition::required("Prim Path", DataType::String)
+                .with_description("Created prim path"),
+        ])
+        .with_panel_type(PanelType::Parameter)
+        .with_workspace_compatibility(vec!["3D"])
+    }
+    
+    fn parameters() -> Vec<ParameterSpec> {
+        vec![
+            ParameterSpec::text("parent_path", "Parent Path", "/World"),
+            ParameterSpec::text("name", "Name", "Curves"),
+            ParameterSpec::choice("type", "Type", &["linear", "cubic"], "cubic"),
+            ParameterSpec::float("radius", "Radius", 0.5, 0.001, 100.0),
+            ParameterSpec::float("height", "Height", 2.0, 0.0, 100.0),
+            ParameterSpec::float("turns", "Turns", 4.0, 0.1, 100.0),
+            ParameterSpec::float("segments", "Segments per Turn", 16.0, 3.0, 128.0),
+            ParameterSpec::float("width", "Width", 0.02, 0.001, 10.0),
+        ]
+    }
+    
+    fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        USDCurvesLogic::execute(inputs, parameters)
+    }
+}
@@ -1,98 +1,33 @@
 //! USD Cylinder node functional operations
 
-use crate::nodes::interface::NodeData;
-use crate::nodes::three_d::usd::usd_engine::with_usd_engine;
+use nodle_plugin_sdk::NodeData;
+use std::collections::HashMap;
+use crate::core::usd_engine::with_usd_engine;
+use crate::core::usd_value::UsdValue;
+use crate::modular::{child_prim_path, float_value, input_stage, text_value};
 
 /// Core logic for USD cylinder creation
 pub struct USDCylinderLogic;
 
 impl USDCylinderLogic {
     /// Execute the cylinder creation operation
-    pub fn execute(inputs: &std::collections::HashMap<String, NodeData>, parameters: &std::collections::HashMap<String, NodeData>) -> std::collections::HashMap<String, NodeData> {
-        let mut outputs = std::collections::HashMap::new();
-        
-        // Get stage reference
-        let stage_id = match inputs.get("Stage") {
-            Some(NodeData::String(s)) => s.clone(),
-            _ => {
-                outputs.insert("Prim Path".to_string(), NodeData::String("".to_string()));
-                outputs.insert("Prim".to_string(), NodeData::None);
-                return outputs;
-            }
-        };
-        
-        // Get parent path
-        let parent_path = match inputs.get("Parent Path") {
-            Some(NodeData::String(s)) => s.clone(),
-            _ => "/World".to_string(),
-        };
-        
-        // Get name or auto-generate
-        let name = match inputs.get("Name") {
-            Some(NodeData::String(s)) if !s.is_empty() => s.clone(),
-            _ => format!("cylinder_{}", uuid::Uuid::new_v4().to_string().split('-').next().unwrap()),
-        };
-        
-        // Get parameters
-        let radius = match inputs.get("Radius").or_else(|| parameters.get("radius")) {
-            Some(NodeData::Float(f)) => *f,
-            _ => 1.0,
-        };
-        
-        let height = match inputs.get("Height").or_else(|| parameters.get("height")) {
-            Some(NodeData::Float(f)) => *f,
-            _ => 2.0,
-        };
-        
-        let axis = match parameters.get("axis") {
-            Some(NodeData::String(s)) => s.clone(),
-            _ => "Y".to_string(),
-        };
-        
-        let top_cap = match parameters.get("top_cap") {
-            Some(NodeData::Boolean(b)) => *b,
-            _ => true,
-        };
-        
-        let bottom_cap = match parameters.get("bottom_cap") {
-            Some(NodeData::Boolean(b)) => *b,
-            _ => true,
-        };
-        
-        // Construct prim path
-        let prim_path = if parent_path.ends_with('/') {
-            format!("{}{}", parent_path, name)
-        } else {
-            format!("{}/{}", parent_path, name)
-        };
-        
-        // Create the cylinder
-        with_usd_engine(|engine| {
-            match engine.create_cylinder(&stage_id, &prim_path, radius as f64, height as f64) {
-                Ok(prim) => {
-                    // Set additional attributes
-                    let _ = engine.set_attribute(&stage_id, &prim_path, "axis", &axis);
-                    
-                    if !top_cap {
-                        let _ = engine.set_attribute(&stage_id, &prim_path, "topCap", "false");
-                    }
-                    if !bottom_cap {
-                        let _ = engine.set_attribute(&stage_id, &prim_path, "bottomCap", "false");
-                    }
-                    
-                    outputs.insert("Prim Path".to_string(), NodeData::String(prim.path.clone()));
-                    outputs.insert("Prim".to_string(), NodeData::String(prim.path));
-                    
-                    println!("✓ Created USD cylinder: {} (radius: {}, height: {})", prim_path, radius, height);
-                }
-                Err(e) => {
-                    eprintln!("✗ Failed to create USD cylinder: {}", e);
-                    outputs.insert("Prim Path".to_string(), NodeData::String("".to_string()));
-                    outputs.insert("Prim".to_string(), NodeData::None);
-                }
-            }
-        });
-        
-        outputs
+    pub fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        let stage_id = input_stage(inputs)?;
+        let prim_path = child_prim_path(inputs, parameters, "Cylinder");
+        let radius = float_value(inputs, "Radius", parameters, "radius", 1.0);
+        let height = float_value(inputs, "Height", parameters, "height", 2.0);
+        let axis = text_value(inputs, "Axis", parameters, "axis").unwrap_or_else(|| "Y".to_string());
+        
+        let prim = with_usd_engine(|engine| -> Result<_, String> {
+            let prim = engine.create_cylinder(&stage_id, &prim_path, radius as f64, height as f64)?;
+            engine.set_attribute(&stage_id, &prim.path, "axis", UsdValue::Token(axis))?;
+            Ok(prim)
+        })?;
+        println!("✓ Created USD cylinder: {} (radius: {}, height: {})", prim.path, radius, height);
+        
+        Ok(HashMap::from([
+            ("Stage".to_string(), inputs["Stage"].clone()),
+            ("Prim Path".to_string(), NodeData::String(prim.path)),
+        ]))
     }
-}
\ No newline at end of file
+}
@@ -6,39 +6,59 @@ pub mod parameters;
 pub use logic::USDCylinderLogic;
 pub use parameters::USDCylinderNode;
 
-use crate::nodes::NodeFactory;
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::modular::{child_prim_inputs, ModularNode, ParameterSpec};
+use crate::ui::help::NodeHelp;
 
-impl NodeFactory for parameters::USDCylinderNode {
-    fn metadata() -> crate::nodes::NodeMetadata {
-        crate::nodes::NodeMetadata::new(
-            "USD_Geometry_Cylinder",
-            "USD Cylinder",
-            crate::nodes::NodeCategory::new(&["3D", "USD", "Geometry", "Primitives"]),
-            "Creates a USD cylinder primitive"
+/// Help for the Cylinder node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_Cylinder",
+    summary: "Creates a USD cylinder primitive",
+    details: "Defines a UsdGeomCylinder with its height along the chosen axis. With Live Edit, Radius, Height and Axis are set on the cylinder as you edit them and follow changes made to it elsewhere.",
+    ports: &[
+        ("Stage", "stage_0"),
+        ("Parent Path", "/World"),
+        ("Name", "Pillar"),
+        ("Prim Path", "/World/Pillar"),
+    ],
+    samples: &[],
+};
+
+impl ModularNode for parameters::USDCylinderNode {
+    const NAME: &'static str = "USD Cylinder";
+    const HELP: &'static NodeHelp = &HELP;
+    
+    fn metadata() -> NodeMetadata {
+        NodeMetadata::new(
+            "USD_Cylinder",
+            "Cylinder",
+            NodeCategory::new(&["USD", "Geometry"]),
+            HELP.summary
         )
-        .with_color(egui::Color32::from_rgb(200, 150, 100))
-        .with_icon("🥤")
-        .with_inputs(vec![
-            crate::nodes::PortDefinition::required("Stage", crate::nodes::DataType::Any)
-                .with_description("USD Stage reference"),
-            crate::nodes::PortDefinition::required("Parent Path", crate::nodes::DataType::String)
-                .with_description("Parent prim path"),
-            crate::nodes::PortDefinition::optional("Name", crate::nodes::DataType::String)
-                .with_description("Prim name (auto-generated if empty)"),
-            crate::nodes::PortDefinition::optional("Radius", crate::nodes::DataType::Float)
-                .with_description("Cylinder radius"),
-            crate::nodes::PortDefinition::optional("Height", crate::nodes::DataType::Float)
-                .with_description("Cylinder height"),
-        ])
+        .with_color(Color32::from_rgb(100, 180, 100))
+        .with_icon("🛢")
+        .with_inputs(child_prim_inputs("Prim").into_iter().chain([
+            PortDefinition::optional("Radius", DataType::Float)
+                .with_description("Cylinder radius, overriding the parameter"),
+            PortDefinition::optional("Height", DataType::Float)
+                .with_description("Cylinder height, overriding the parameter"),
+        ]).collect())
         .with_outputs(vec![
-            crate::nodes::PortDefinition::required("Prim Path", crate::nodes::DataType::String)
+            PortDefinition::required("Stage", DataType::String)
+                .with_description("Stage with the cylinder"),
+            PortDefinition::required("Prim Path", DataType::String)
                 .with_description("Created prim path"),
-            crate::nodes::PortDefinition::required("Prim", crate::nodes::DataType::Any)
-                .with_description("USD Prim reference"),
         ])
-        .with_tags(vec!["usd", "geometry", "cylinder", "primitive"])
-        .with_processing_cost(crate::nodes::factory::ProcessingCost::Low)
-        .with_workspace_compatibility(vec!["3D", "USD"])
-        .with_panel_type(crate::nodes::interface::PanelType::Parameter)
+        .with_panel_type(PanelType::Parameter)
+        .with_workspace_compatibility(vec!["3D"])
+    }
+    
+    fn parameters() -> Vec<ParameterSpec> {
+        parameters::USDCylinderNode::parameters()
+    }
+    
+    fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        USDCylinderLogic::execute(inputs, parameters)
     }
-}
\ No newline at end of file
+}
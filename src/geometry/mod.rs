@@ -1,23 +1,24 @@
 //! USD Geometry primitive nodes
+//!
+//! Each node is a `ModularNode`; `modular_nodes!` declares its module and
+//! lists it for registration with the plugin.
 
-pub mod cube;
-pub mod sphere;
-pub mod cylinder;
-pub mod cone;
-pub mod plane;
-pub mod capsule;
-pub mod torus;
-pub mod mesh;
-pub mod points;
-pub mod curves;
+use crate::modular::modular_nodes;
 
-pub use cube::{USDCubeNode, USDCubeLogic};
-pub use sphere::{USDSphereNode, USDSphereLogic};
-pub use cylinder::{USDCylinderNode, USDCylinderLogic};
-pub use cone::{USDConeNode, USDConeLogic};
-pub use plane::{USDPlaneNode, USDPlaneLogic};
-pub use capsule::{USDCapsuleNode, USDCapsuleLogic};
-pub use torus::{USDTorusNode, USDTorusLogic};
-pub use mesh::{USDMeshNode, USDMeshLogic};
-pub use points::{USDPointsNode, USDPointsLogic};
-pub use curves::{USDCurvesNode, USDCurvesLogic};
\ No newline at end of file
+modular_nodes! {
+    mesh => USDMeshNode,
+    cube => USDCubeNode,
+    sphere => USDSphereNode,
+    cylinder => USDCylinderNode,
+    cone => USDConeNode,
+    capsule => USDCapsuleNode,
+    plane => USDPlaneNode,
+    torus => USDTorusNode,
+    points => USDPointsNode,
+    curves => USDCurvesNode,
+    camera => USDCameraNode,
+    bounding_volume => USDBoundingVolumeNode,
+    hlod => USDHlodNode,
+    occlusion_bake => USDOcclusionBakeNode,
+    xform => USDXformNode,
+}
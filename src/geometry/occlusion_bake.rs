@@ -0,0 +1,139 @@
+//! USD Occlusion Bake node
+//!
+//! Precomputes which meshes under a root can never be seen from a set of
+//! cameras by casting rays from each camera into a BVH of the meshes, and
+//! marks them for engine-side culling or hides them outright.
+
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::core::occlusion::{mesh_bvh, visible_meshes, CullOutput, USDVisibilityBake, NEVER_VISIBLE_ATTR};
+use crate::core::usd_engine::with_usd_engine;
+use crate::modular::{float_value, input_stage, text_value, ModularNode, ParameterSpec};
+use crate::ui::help::NodeHelp;
+
+/// Help for the Occlusion Bake node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_OcclusionBake",
+    summary: "Bake which meshes are never visible from a set of cameras for occlusion culling",
+    details: "Gathers the meshes under the root in world space into a triangle BVH and casts a grid of rays, Samples wide, from each camera across its aperture between its clipping planes. A mesh that no ray hits first is never visible from the camera set. Attribute output authors culling:neverVisible on every mesh for an engine to cull by; Visibility output makes hidden meshes invisible and the rest inherited. Every mesh is authored, so re-baking clears earlier results. Thin gaps narrower than the ray spacing can be missed, so raise Samples for fine detail.",
+    ports: &[
+        ("Stage", "loaded_0"),
+        ("Cameras", "/World/Cameras/Main, /World/Cameras/Gameplay"),
+        ("Hidden Prims", "/World/Interior/Pipes, /World/Interior/Vent"),
+    ],
+    samples: &[],
+};
+
+/// Output choices, as `CullOutput` labels
+const OUTPUTS: &[&str] = &["Attribute", "Visibility"];
+
+/// Prim paths separated by commas or whitespace
+fn parse_prim_paths(text: &str) -> Vec<String> {
+    text.split([',', ' ', '\n'])
+        .map(|path| path.trim().trim_end_matches('/'))
+        .filter(|path| !path.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// USD Occlusion Bake node with parameter controls
+#[derive(Default)]
+pub struct USDOcclusionBakeNode;
+
+/// Core logic for occlusion baking
+pub struct USDOcclusionBakeLogic;
+
+impl USDOcclusionBakeLogic {
+    /// Execute the occlusion bake
+    pub fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        let stage_id = input_stage(inputs)?;
+        let camera_paths = parse_prim_paths(&text_value(inputs, "Cameras", parameters, "cameras").unwrap_or_default());
+        if camera_paths.is_empty() {
+            return Err("No cameras set".to_string());
+        }
+        let root_path = text_value(inputs, "Root Path", parameters, "root_path")
+            .map(|path| path.trim().trim_end_matches('/').to_string())
+            .filter(|path| !path.is_empty())
+            .ok_or_else(|| "No root path set".to_string())?;
+        let samples = float_value(inputs, "Samples", parameters, "samples", 64.0).max(1.0) as usize;
+        let output = parameters.get("output").and_then(|data| data.as_string())
+            .and_then(CullOutput::from_label)
+            .unwrap_or(CullOutput::Attribute);
+        let time = float_value(inputs, "Time", parameters, "time_code", 0.0) as f64;
+        
+        let bake = with_usd_engine(|engine| -> Result<USDVisibilityBake, String> {
+            let cameras = camera_paths.iter()
+                .map(|camera_path| engine.get_camera(&stage_id, camera_path, time))
+                .collect::<Result<Vec<_>, String>>()?;
+            let meshes = engine.get_world_meshes(&stage_id, &root_path, time)?;
+            if meshes.is_empty() {
+                return Err(format!("'{}' has no meshes to bake", root_path));
+            }
+            let visible = visible_meshes(&mesh_bvh(&meshes), &cameras, samples);
+            let bake = USDVisibilityBake {
+                meshes: meshes.into_iter().enumerate().map(|(index, mesh)| (mesh.prim_path, visible.contains(&index))).collect(),
+                output,
+            };
+            engine.set_visibility_bake(&stage_id, &bake)?;
+            Ok(bake)
+        })?;
+        let hidden = bake.hidden();
+        match output {
+            CullOutput::Attribute => println!("✓ Marked {} of {} meshes {} from {} cameras", hidden.len(), bake.meshes.len(), NEVER_VISIBLE_ATTR, camera_paths.len()),
+            CullOutput::Visibility => println!("✓ Hid {} of {} meshes never visible from {} cameras", hidden.len(), bake.meshes.len(), camera_paths.len()),
+        }
+        
+        Ok(HashMap::from([
+            ("Stage".to_string(), inputs["Stage"].clone()),
+            ("Hidden Prims".to_string(), NodeData::String(hidden.join(", "))),
+        ]))
+    }
+}
+
+impl ModularNode for USDOcclusionBakeNode {
+    const NAME: &'static str = "USD Occlusion Bake";
+    const HELP: &'static NodeHelp = &HELP;
+    
+    fn metadata() -> NodeMetadata {
+        NodeMetadata::new(
+            "USD_OcclusionBake",
+            "Occlusion Bake",
+            NodeCategory::new(&["USD", "Geometry"]),
+            HELP.summary
+        )
+        .with_color(Color32::from_rgb(100, 180, 100))
+        .with_icon("◐")
+        .with_inputs(vec![
+            PortDefinition::required("Stage", DataType::String)
+                .with_description("USD stage"),
+            PortDefinition::optional("Cameras", DataType::String)
+                .with_description("Camera paths separated by commas, overriding the parameter"),
+            PortDefinition::optional("Root Path", DataType::String)
+                .with_description("Prim whose meshes are baked, overriding the parameter"),
+            PortDefinition::optional("Time", DataType::Float)
+                .with_description("Time code to read cameras and meshes at, overriding the parameter"),
+        ])
+        .with_outputs(vec![
+            PortDefinition::required("Stage", DataType::String)
+                .with_description("Stage with the baked visibility"),
+            PortDefinition::required("Hidden Prims", DataType::String)
+                .with_description("Meshes no camera sees, separated by commas"),
+        ])
+        .with_panel_type(PanelType::Parameter)
+        .with_workspace_compatibility(vec!["3D"])
+    }
+    
+    fn parameters() -> Vec<ParameterSpec> {
+        vec![
+            ParameterSpec::text("cameras", "Cameras", ""),
+            ParameterSpec::text("root_path", "Root Path", "/World"),
+            ParameterSpec::float("samples", "Samples", 64.0, 1.0, 1024.0),
+            ParameterSpec::choice("output", "Output", OUTPUTS, "Attribute"),
+            ParameterSpec::float("time_code", "Time Code", 0.0, -100000.0, 100000.0),
+        ]
+    }
+    
+    fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        USDOcclusionBakeLogic::execute(inputs, parameters)
+    }
+}
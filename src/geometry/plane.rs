@@ -1,8 +1,98 @@
-//! USD Plane geometry primitive - stub implementation
+//! USD Plane geometry primitive
 
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::core::usd_value::UsdValue;
+use crate::modular::{child_prim_inputs, child_prim_path, define_prim, flag_value, float_value, input_stage, text_value, ModularNode, ParameterSpec};
+use crate::ui::help::NodeHelp;
+
+/// Help for the Plane node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_Plane",
+    summary: "Creates a USD plane primitive",
+    details: "Defines a UsdGeomPlane of the given width and length facing along its axis; Double Sided makes it visible from behind.",
+    ports: &[
+        ("Stage", "stage_0"),
+        ("Name", "Ground"),
+        ("Prim Path", "/World/Ground"),
+    ],
+    samples: &[],
+};
+
+/// USD Plane node with parameter controls
+#[derive(Default)]
 pub struct USDPlaneNode;
+
+/// Core logic for USD plane creation
 pub struct USDPlaneLogic;
 
-impl Default for USDPlaneNode {
-    fn default() -> Self { Self }
-}
\ No newline at end of file
+impl USDPlaneLogic {
+    /// Execute the plane creation operation
+    pub fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        let stage_id = input_stage(inputs)?;
+        let prim_path = child_prim_path(inputs, parameters, "Plane");
+        let width = float_value(inputs, "Width", parameters, "width", 2.0);
+        let length = float_value(inputs, "Length", parameters, "length", 2.0);
+        // The axis is the plane's normal, so Y gives a ground plane
+        let axis = text_value(inputs, "Axis", parameters, "axis").unwrap_or_else(|| "Y".to_string());
+        let double_sided = flag_value(parameters, "double_sided", true);
+        
+        let prim = define_prim(&stage_id, &prim_path, "Plane", vec![
+            ("width", UsdValue::Double(width as f64)),
+            ("length", UsdValue::Double(length as f64)),
+            ("axis", UsdValue::Token(axis)),
+            ("doubleSided", UsdValue::Bool(double_sided)),
+        ])?;
+        println!("✓ Created USD plane: {} ({}x{})", prim.path, width, length);
+        
+        Ok(HashMap::from([
+            ("Stage".to_string(), inputs["Stage"].clone()),
+            ("Prim Path".to_string(), NodeData::String(prim.path)),
+        ]))
+    }
+}
+
+impl ModularNode for USDPlaneNode {
+    const NAME: &'static str = "USD Plane";
+    const HELP: &'static NodeHelp = &HELP;
+    
+    fn metadata() -> NodeMetadata {
+        NodeMetadata::new(
+            "USD_Plane",
+            "Plane",
+            NodeCategory::new(&["USD", "Geometry"]),
+            HELP.summary
+        )
+        .with_color(Color32::from_rgb(100, 180, 100))
+        .with_icon("▱")
+        .with_inputs(child_prim_inputs("Prim").into_iter().chain([
+            PortDefinition::optional("Width", DataType::Float)
+                .with_description("Plane width, overriding the parameter"),
+            PortDefinition::optional("Length", DataType::Float)
+                .with_description("Plane length, overriding the parameter"),
+        ]).collect())
+        .with_outputs(vec![
+            PortDefinition::required("Stage", DataType::String)
+                .with_description("Stage with the plane"),
+            PortDefinition::required("Prim Path", DataType::String)
+                .with_description("Created prim path"),
+        ])
+        .with_panel_type(PanelType::Parameter)
+        .with_workspace_compatibility(vec!["3D"])
+    }
+    
+    fn parameters() -> Vec<ParameterSpec> {
+        vec![
+            ParameterSpec::text("parent_path", "Parent Path", "/World"),
+            ParameterSpec::text("name", "Name", "Plane"),
+            ParameterSpec::float("width", "Width", 2.0, 0.001, 1000.0),
+            ParameterSpec::float("length", "Length", 2.0, 0.001, 1000.0),
+            ParameterSpec::choice("axis", "Axis", &["X", "Y", "Z"], "Y"),
+            ParameterSpec::toggle("double_sided", "Double Sided", true),
+        ]
+    }
+    
+    fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        USDPlaneLogic::execute(inputs, parameters)
+    }
+}
@@ -1,8 +1,125 @@
-//! USD Points geometry primitive - stub implementation
+//! USD Points geometry primitive
 
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::core::seed::node_random;
+use crate::core::usd_value::UsdValue;
+use crate::modular::{child_prim_inputs, child_prim_path, define_prim, float_value, input_stage, ModularNode, ParameterSpec};
+use crate::ui::help::NodeHelp;
+
+/// Help for the Points node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_Points",
+    summary: "Creates a grid of USD points",
+    details: "Defines a UsdGeomPoints grid with the given spacing and point width, e.g. to scatter instances over. Jitter moves each point randomly within that fraction of the spacing, the same way on every re-cook until the Global Seed changes; Seed Offset re-rolls this node alone.",
+    ports: &[
+        ("Stage", "stage_0"),
+        ("Name", "Grid"),
+        ("Prim Path", "/World/Grid"),
+    ],
+    samples: &[],
+};
+
+/// A `columns` by `rows` grid of points in the XZ plane, centered on the origin
+pub fn point_grid(columns: usize, rows: usize, spacing: f64) -> Vec<[f64; 3]> {
+    let offset = |count: usize| (count.max(1) - 1) as f64 * spacing / 2.0;
+    (0..rows.max(1))
+        .flat_map(|row| (0..columns.max(1)).map(move |column| (column, row)))
+        .map(|(column, row)| [column as f64 * spacing - offset(columns), 0.0, row as f64 * spacing - offset(rows)])
+        .collect()
+}
+
+/// Move points randomly in the XZ plane by up to `amount` of the grid spacing, from the executing node's seed
+///
+/// Every point draws from its own stream, so resizing the grid keeps the
+/// offsets of the points it still has.
+pub fn jitter_points(points: &mut [[f64; 3]], amount: f64, spacing: f64) {
+    let reach = amount * spacing * 0.5;
+    for (index, point) in points.iter_mut().enumerate() {
+        let mut random = node_random(index as u64);
+        point[0] += random.range(-reach, reach);
+        point[2] += random.range(-reach, reach);
+    }
+}
+
+/// USD Points node with parameter controls
+#[derive(Default)]
 pub struct USDPointsNode;
+
+/// Core logic for USD points creation
 pub struct USDPointsLogic;
 
-impl Default for USDPointsNode {
-    fn default() -> Self { Self }
-}
\ No newline at end of file
+impl USDPointsLogic {
+    /// Execute the points creation operation
+    pub fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        let stage_id = input_stage(inputs)?;
+        let prim_path = child_prim_path(inputs, parameters, "Points");
+        let columns = float_value(inputs, "Columns", parameters, "columns", 10.0).round() as usize;
+        let rows = float_value(inputs, "Rows", parameters, "rows", 10.0).round() as usize;
+        let spacing = float_value(inputs, "Spacing", parameters, "spacing", 0.2);
+        let width = float_value(inputs, "Width", parameters, "width", 0.05);
+        let jitter = float_value(inputs, "Jitter", parameters, "jitter", 0.0);
+        
+        let mut points = point_grid(columns, rows, spacing as f64);
+        jitter_points(&mut points, jitter as f64, spacing as f64);
+        let widths = vec![UsdValue::Float(width); points.len()];
+        let prim = define_prim(&stage_id, &prim_path, "Points", vec![
+            ("points", UsdValue::Array(points.iter().copied().map(UsdValue::Vec3).collect())),
+            ("widths", UsdValue::Array(widths)),
+        ])?;
+        println!("✓ Created USD points: {} ({} points)", prim.path, points.len());
+        
+        Ok(HashMap::from([
+            ("Stage".to_string(), inputs["Stage"].clone()),
+            ("Prim Path".to_string(), NodeData::String(prim.path)),
+        ]))
+    }
+}
+
+impl ModularNode for USDPointsNode {
+    const NAME: &'static str = "USD Points";
+    const HELP: &'static NodeHelp = &HELP;
+    
+    fn metadata() -> NodeMetadata {
+        NodeMetadata::new(
+            "USD_Points",
+            "Points",
+            NodeCategory::new(&["USD", "Geometry"]),
+            HELP.summary
+        )
+        .with_color(Color32::from_rgb(100, 180, 100))
+        .with_icon("⁙")
+        .with_inputs(child_prim_inputs("Prim").into_iter().chain([
+            PortDefinition::optional("Spacing", DataType::Float)
+                .with_description("Distance between points, overriding the parameter"),
+            PortDefinition::optional("Width", DataType::Float)
+                .with_description("Point width, overriding the parameter"),
+        ]).collect())
+        .with_outputs(vec![
+            PortDefinition::required("Stage", DataType::String)
+                .with_description("Stage with the points"),
+            PortDefinition::required("Prim Path", DataType::String)
+                .with_description("Created prim path"),
+        ])
+        .with_panel_type(PanelType::Parameter)
+        .with_workspace_compatibility(vec!["3D"])
+    }
+    
+    fn parameters() -> Vec<ParameterSpec> {
+        vec![
+            ParameterSpec::text("parent_path", "Parent Path", "/World"),
+            ParameterSpec::text("name", "Name", "Points"),
+            ParameterSpec::float("columns", "Columns", 10.0, 1.0, 1000.0),
+            ParameterSpec::float("rows", "Rows", 10.0, 1.0, 1000.0),
+            ParameterSpec::float("spacing", "Spacing", 0.2, 0.001, 10.0),
+            ParameterSpec::float("width", "Width", 0.05, 0.001, 10.0),
+            ParameterSpec::float("jitter", "Jitter", 0.0, 0.0, 1.0),
+        ]
+    }
+    
+    const SEEDED: bool = true;
+    
+    fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        USDPointsLogic::execute(inputs, parameters)
+    }
+}
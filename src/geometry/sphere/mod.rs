@@ -6,39 +6,58 @@ pub mod parameters;
 pub use logic::USDSphereLogic;
 pub use parameters::USDSphereNode;
 
-use crate::nodes::NodeFactory;
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::modular::{child_prim_inputs, ModularNode, ParameterSpec};
+use crate::ui::help::NodeHelp;
 
-impl NodeFactory for parameters::USDSphereNode {
-    fn metadata() -> crate::nodes::NodeMetadata {
-        crate::nodes::NodeMetadata::new(
-            "USD_Geometry_Sphere",
-            "USD Sphere",
-            crate::nodes::NodeCategory::new(&["3D", "USD", "Geometry", "Primitives"]),
-            "Creates a USD sphere primitive"
+/// Help for the Sphere node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_Sphere",
+    summary: "Creates a USD sphere primitive",
+    details: "Defines a UsdGeomSphere named Name under Parent Path. Purpose and visibility control where it shows up, e.g. proxy geometry for the viewport only. With Live Edit, Radius, Purpose and Visibility are set on the sphere as you edit them and follow changes made to it elsewhere.",
+    ports: &[
+        ("Stage", "stage_0"),
+        ("Parent Path", "/World"),
+        ("Name", "Ball"),
+        ("Radius", "2.5"),
+        ("Prim Path", "/World/Ball"),
+    ],
+    samples: &[],
+};
+
+impl ModularNode for parameters::USDSphereNode {
+    const NAME: &'static str = "USD Sphere";
+    const HELP: &'static NodeHelp = &HELP;
+    
+    fn metadata() -> NodeMetadata {
+        NodeMetadata::new(
+            "USD_Sphere",
+            "Sphere",
+            NodeCategory::new(&["USD", "Geometry"]),
+            HELP.summary
         )
-        .with_color(egui::Color32::from_rgb(200, 150, 100))
-        .with_icon("🔵")
-        .with_inputs(vec![
-            crate::nodes::PortDefinition::required("Stage", crate::nodes::DataType::Any)
-                .with_description("USD Stage reference"),
-            crate::nodes::PortDefinition::required("Parent Path", crate::nodes::DataType::String)
-                .with_description("Parent prim path"),
-            crate::nodes::PortDefinition::optional("Name", crate::nodes::DataType::String)
-                .with_description("Prim name (auto-generated if empty)"),
-            crate::nodes::PortDefinition::optional("Radius", crate::nodes::DataType::Float)
-                .with_description("Sphere radius"),
-            crate::nodes::PortDefinition::optional("Transform", crate::nodes::DataType::Any)
-                .with_description("Transform matrix"),
-        ])
+        .with_color(Color32::from_rgb(100, 180, 100))
+        .with_icon("🔴")
+        .with_inputs(child_prim_inputs("Prim").into_iter().chain([
+            PortDefinition::optional("Radius", DataType::Float)
+                .with_description("Sphere radius, overriding the parameter"),
+        ]).collect())
         .with_outputs(vec![
-            crate::nodes::PortDefinition::required("Prim Path", crate::nodes::DataType::String)
+            PortDefinition::required("Stage", DataType::String)
+                .with_description("Stage with the sphere"),
+            PortDefinition::required("Prim Path", DataType::String)
                 .with_description("Created prim path"),
-            crate::nodes::PortDefinition::required("Prim", crate::nodes::DataType::Any)
-                .with_description("USD Prim reference"),
         ])
-        .with_tags(vec!["usd", "geometry", "sphere", "primitive"])
-        .with_processing_cost(crate::nodes::factory::ProcessingCost::Low)
-        .with_workspace_compatibility(vec!["3D", "USD"])
-        .with_panel_type(crate::nodes::interface::PanelType::Parameter)
+        .with_panel_type(PanelType::Parameter)
+        .with_workspace_compatibility(vec!["3D"])
+    }
+    
+    fn parameters() -> Vec<ParameterSpec> {
+        parameters::USDSphereNode::parameters()
+    }
+    
+    fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        USDSphereLogic::execute(inputs, parameters)
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,78 @@
+//! USD Xform grouping node
+
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::modular::{child_prim_inputs, child_prim_path, define_prim, input_stage, ModularNode, ParameterSpec};
+use crate::ui::help::NodeHelp;
+
+/// Help for the Xform node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_Xform",
+    summary: "Creates a USD Xform to group and transform prims",
+    details: "Defines a UsdGeomXform to group prims and transform them together. Create prims with its Prim Path as their Parent Path, and use Translate, Rotate and Scale to author its xform ops.",
+    ports: &[
+        ("Stage", "stage_0"),
+        ("Name", "Group"),
+        ("Prim Path", "/World/Group"),
+    ],
+    samples: &[],
+};
+
+/// USD Xform node with parameter controls
+#[derive(Default)]
+pub struct USDXformNode;
+
+/// Core logic for USD Xform creation
+pub struct USDXformLogic;
+
+impl USDXformLogic {
+    /// Execute the Xform creation operation
+    pub fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        let stage_id = input_stage(inputs)?;
+        let prim_path = child_prim_path(inputs, parameters, "Xform");
+        
+        let prim = define_prim(&stage_id, &prim_path, "Xform", Vec::new())?;
+        println!("✓ Created USD Xform: {}", prim.path);
+        
+        Ok(HashMap::from([
+            ("Stage".to_string(), inputs["Stage"].clone()),
+            ("Prim Path".to_string(), NodeData::String(prim.path)),
+        ]))
+    }
+}
+
+impl ModularNode for USDXformNode {
+    const NAME: &'static str = "USD Xform";
+    const HELP: &'static NodeHelp = &HELP;
+    
+    fn metadata() -> NodeMetadata {
+        NodeMetadata::new(
+            "USD_Xform",
+            "Xform",
+            NodeCategory::new(&["USD", "Transform"]),
+            HELP.summary
+        )
+        .with_color(Color32::from_rgb(150, 120, 200))
+        .with_icon("🔄")
+        .with_inputs(child_prim_inputs("Xform"))
+        .with_outputs(vec![
+            PortDefinition::required("Stage", DataType::String)
+                .with_description("Stage with the Xform"),
+            PortDefinition::required("Prim Path", DataType::String)
+                .with_description("Created prim path"),
+        ])
+        .with_panel_type(PanelType::Parameter)
+        .with_workspace_compatibility(vec!["3D"])
+    }
+    
+    fn parameters() -> Vec<ParameterSpec> {
+        vec![
+            ParameterSpec::text("parent_path", "Parent Path", "/World"),
+            ParameterSpec::text("name", "Name", "Group"),
+        ]
+    }
+    
+    fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        USDXformLogic::execute(inputs, parameters)
+    }
+}
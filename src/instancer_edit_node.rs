@@ -0,0 +1,355 @@
+//! USD Instancer Edit node - swaps prototypes, hides or deactivates instances and
+//! splits prototypes by variant on a PointInstancer
+
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::core::usd_engine::with_usd_engine;
+use crate::ui::help::NodeHelp;
+use crate::ui::palette::status_label;
+use crate::ui::keyboard::{focus_marked, handle_key};
+use crate::registry::NodeTypeFactory;
+
+/// Help for the Instancer Edit node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_InstancerEdit",
+    summary: "Swap prototypes, hide or deactivate instances and pick prototype variants per instance on a PointInstancer",
+    details: "Edits a PointInstancer's protoIndices, invisibleIds and inactiveIds for the instances given by id, and can split prototypes by variant so instances pick different looks.",
+    ports: &[
+        ("Stage", "loaded_0"),
+    ],
+    samples: &[],
+};
+
+/// Factory of the Instancer Edit node, for `crate::node_factories`
+pub fn factories() -> Vec<Box<dyn NodeFactory>> {
+    vec![NodeTypeFactory::boxed(metadata, |position| PluginNodeHandle::new(Box::new(USDInstancerEditNode::new(position))))]
+}
+
+/// Metadata of the Instancer Edit node
+fn metadata() -> NodeMetadata {
+    NodeMetadata::new(
+        "USD_InstancerEdit",
+        "Instancer Edit",
+        NodeCategory::new(&["USD", "Stage"]),
+        HELP.summary
+    )
+    .with_color(Color32::from_rgb(80, 150, 200))
+    .with_icon("🌲")
+    .with_inputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("USD stage containing the point instancer"),
+    ])
+    .with_outputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("Stage with the instancer edits applied"),
+    ])
+    .with_panel_type(PanelType::Parameter)
+    .with_workspace_compatibility(vec!["3D"])
+}
+
+/// An edit triggered by a button, authored on the next process
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum InstancerAction {
+    SwapPrototype,
+    SplitByVariant,
+}
+
+/// USD Instancer Edit node
+///
+/// Hidden and deactivated ids are declarative: changing the lists authors
+/// only the difference to what the node applied before.
+pub struct USDInstancerEditNode {
+    id: String,
+    position: Pos2,
+    instancer_path: String,
+    prototype_index: String,
+    prototype_path: String,
+    hidden_ids: String,
+    inactive_ids: String,
+    variant_set: String,
+    variant_primvar: String,
+    stage_ref: String,
+    /// Ids hidden and deactivated by this node, as last authored
+    applied_hidden: Vec<i64>,
+    applied_inactive: Vec<i64>,
+    pending: Vec<InstancerAction>,
+    prototypes: Vec<String>,
+    dirty: bool,
+    status: String,
+}
+
+impl USDInstancerEditNode {
+    pub fn new(position: Pos2) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            position,
+            instancer_path: String::new(),
+            prototype_index: "0".to_string(),
+            prototype_path: String::new(),
+            hidden_ids: String::new(),
+            inactive_ids: String::new(),
+            variant_set: String::new(),
+            variant_primvar: String::new(),
+            stage_ref: String::new(),
+            applied_hidden: Vec::new(),
+            applied_inactive: Vec::new(),
+            pending: Vec::new(),
+            prototypes: Vec::new(),
+            dirty: true,
+            status: "No USD stage connected".to_string(),
+        }
+    }
+    
+    /// Author pending edits and id list changes, then re-read the prototypes
+    fn apply(&mut self) -> Result<(), String> {
+        if self.instancer_path.is_empty() {
+            self.prototypes.clear();
+            return Err("No instancer path set".to_string());
+        }
+        let hidden = parse_ids(&self.hidden_ids)?;
+        let inactive = parse_ids(&self.inactive_ids)?;
+        let pending = std::mem::take(&mut self.pending);
+        let index = || self.prototype_index.trim().parse::<usize>()
+            .map_err(|_| format!("Invalid prototype index '{}'", self.prototype_index));
+        let (stage_ref, path) = (self.stage_ref.clone(), self.instancer_path.clone());
+        
+        let mut messages = Vec::new();
+        self.prototypes = with_usd_engine(|engine| -> Result<Vec<String>, String> {
+            let stage = engine.resolve_stage(&stage_ref)?;
+            let id = stage.identifier.as_str();
+            
+            let (shown, hidden_new) = diff_ids(&self.applied_hidden, &hidden);
+            engine.set_instances_visible(id, &path, &shown, true)?;
+            engine.set_instances_visible(id, &path, &hidden_new, false)?;
+            let (activated, deactivated) = diff_ids(&self.applied_inactive, &inactive);
+            engine.set_instances_active(id, &path, &activated, true)?;
+            engine.set_instances_active(id, &path, &deactivated, false)?;
+            
+            for action in &pending {
+                match action {
+                    InstancerAction::SwapPrototype => {
+                        engine.swap_instancer_prototype(id, &path, index()?, &self.prototype_path)?;
+                        messages.push(format!("prototype {} is {}", index()?, self.prototype_path));
+                    }
+                    InstancerAction::SplitByVariant => {
+                        let created = engine.create_variant_prototypes(id, &path, index()?, &self.variant_set, &self.variant_primvar)?;
+                        messages.push(format!("{} variant prototypes", created.len()));
+                    }
+                }
+            }
+            engine.get_instancer_prototypes(id, &path)
+        })?;
+        self.applied_hidden = hidden;
+        self.applied_inactive = inactive;
+        
+        messages.insert(0, format!("{} prototypes, {} hidden, {} inactive",
+                                   self.prototypes.len(), self.applied_hidden.len(), self.applied_inactive.len()));
+        self.status = messages.join("; ");
+        Ok(())
+    }
+}
+
+/// Parse instance ids like "1, 4, 10-12"
+fn parse_ids(text: &str) -> Result<Vec<i64>, String> {
+    let mut ids = Vec::new();
+    for item in text.split([',', ' ']).map(str::trim).filter(|item| !item.is_empty()) {
+        let invalid = || format!("Invalid instance id '{}'", item);
+        match item.split_once('-') {
+            Some((start, end)) => {
+                let (start, end): (i64, i64) = (start.parse().map_err(|_| invalid())?, end.parse().map_err(|_| invalid())?);
+                ids.extend(start..=end);
+            }
+            None => ids.push(item.parse().map_err(|_| invalid())?),
+        }
+    }
+    ids.sort_unstable();
+    ids.dedup();
+    Ok(ids)
+}
+
+/// Ids removed from and added to a list, as (removed, added)
+fn diff_ids(before: &[i64], after: &[i64]) -> (Vec<i64>, Vec<i64>) {
+    (
+        before.iter().filter(|id| !after.contains(id)).copied().collect(),
+        after.iter().filter(|id| !before.contains(id)).copied().collect(),
+    )
+}
+
+impl PluginNode for USDInstancerEditNode {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+    
+    fn position(&self) -> Pos2 {
+        self.position
+    }
+    
+    fn set_position(&mut self, position: Pos2) {
+        self.position = position;
+    }
+    
+    fn get_parameter_ui(&self) -> ParameterUI {
+        let mut elements = Vec::new();
+        
+        elements.push(UIElement::Heading("USD Instancer Edit".to_string()));
+        elements.push(UIElement::Separator);
+        
+        elements.push(UIElement::TextEdit {
+            label: "Instancer Path".to_string(),
+            value: self.instancer_path.clone(),
+            parameter_name: "instancer_path".to_string(),
+        });
+        for (index, prototype) in self.prototypes.iter().enumerate() {
+            elements.push(UIElement::Label(format!("[{}] {}", index, prototype)));
+        }
+        
+        elements.push(UIElement::Separator);
+        elements.push(UIElement::TextEdit {
+            label: "Prototype Index".to_string(),
+            value: self.prototype_index.clone(),
+            parameter_name: "prototype_index".to_string(),
+        });
+        elements.push(UIElement::TextEdit {
+            label: "New Prototype".to_string(),
+            value: self.prototype_path.clone(),
+            parameter_name: "prototype_path".to_string(),
+        });
+        elements.push(UIElement::Button {
+            label: "Swap Prototype".to_string(),
+            action: "swap_prototype".to_string(),
+        });
+        
+        elements.push(UIElement::Separator);
+        elements.push(UIElement::TextEdit {
+            label: "Hidden Ids".to_string(),
+            value: self.hidden_ids.clone(),
+            parameter_name: "hidden_ids".to_string(),
+        });
+        elements.push(UIElement::TextEdit {
+            label: "Deactivated Ids".to_string(),
+            value: self.inactive_ids.clone(),
+            parameter_name: "inactive_ids".to_string(),
+        });
+        
+        elements.push(UIElement::Separator);
+        elements.push(UIElement::TextEdit {
+            label: "Variant Set".to_string(),
+            value: self.variant_set.clone(),
+            parameter_name: "variant_set".to_string(),
+        });
+        elements.push(UIElement::TextEdit {
+            label: "Variant Primvar".to_string(),
+            value: self.variant_primvar.clone(),
+            parameter_name: "variant_primvar".to_string(),
+        });
+        elements.push(UIElement::Button {
+            label: "Split By Variant".to_string(),
+            action: "split_by_variant".to_string(),
+        });
+        
+        elements.push(UIElement::Separator);
+        elements.push(status_label(&self.status));
+        elements.extend(HELP.section());
+        
+        ParameterUI { elements: focus_marked(&self.id, elements) }
+    }
+    
+    fn handle_ui_action(&mut self, action: UIAction) -> Vec<ParameterChange> {
+        if let Some(changes) = handle_key(self, &[], &action) {
+            return changes;
+        }
+        
+        let mut changes = Vec::new();
+        
+        match action {
+            UIAction::ParameterChanged { parameter, value } => {
+                if let Some(text) = value.as_string() {
+                    if self.get_parameter(&parameter).is_some() {
+                        self.set_parameter(&parameter, NodeData::String(text.to_string()));
+                        changes.push(ParameterChange {
+                            parameter: parameter.clone(),
+                            value: NodeData::String(text.to_string()),
+                        });
+                    }
+                }
+            }
+            UIAction::ButtonClicked { action } => {
+                let action = match action.as_str() {
+                    "swap_prototype" => Some(InstancerAction::SwapPrototype),
+                    "split_by_variant" => Some(InstancerAction::SplitByVariant),
+                    _ => None,
+                };
+                if let Some(action) = action {
+                    self.pending.push(action);
+                    self.dirty = true;
+                }
+            }
+        }
+        
+        changes
+    }
+    
+    fn get_parameter(&self, name: &str) -> Option<NodeData> {
+        let value = match name {
+            "instancer_path" => &self.instancer_path,
+            "prototype_index" => &self.prototype_index,
+            "prototype_path" => &self.prototype_path,
+            "hidden_ids" => &self.hidden_ids,
+            "inactive_ids" => &self.inactive_ids,
+            "variant_set" => &self.variant_set,
+            "variant_primvar" => &self.variant_primvar,
+            _ => return None,
+        };
+        Some(NodeData::String(value.clone()))
+    }
+    
+    fn set_parameter(&mut self, name: &str, value: NodeData) {
+        let Some(text) = value.as_string().map(|text| text.trim().to_string()) else {
+            return;
+        };
+        match name {
+            "instancer_path" => {
+                // Ids applied to the previous instancer stay authored there
+                self.applied_hidden.clear();
+                self.applied_inactive.clear();
+                self.instancer_path = text;
+            }
+            "prototype_index" => self.prototype_index = text,
+            "prototype_path" => self.prototype_path = text,
+            "hidden_ids" => self.hidden_ids = text,
+            "inactive_ids" => self.inactive_ids = text,
+            "variant_set" => self.variant_set = text,
+            "variant_primvar" => self.variant_primvar = text,
+            _ => return,
+        }
+        self.dirty = true;
+    }
+    
+    fn process(&mut self, inputs: &HashMap<String, NodeData>) -> HashMap<String, NodeData> {
+        let mut outputs = HashMap::new();
+        
+        let Some(stage_ref) = inputs.get("Stage").and_then(|data| data.as_string()) else {
+            self.stage_ref.clear();
+            self.prototypes.clear();
+            self.status = "No USD stage connected".to_string();
+            return outputs;
+        };
+        
+        if stage_ref != self.stage_ref {
+            self.stage_ref = stage_ref.to_string();
+            self.applied_hidden.clear();
+            self.applied_inactive.clear();
+            self.dirty = true;
+        }
+        
+        if self.dirty {
+            self.dirty = false;
+            if let Err(e) = self.apply() {
+                self.status = format!("⚠ {}", e);
+            }
+        }
+        
+        outputs.insert("Stage".to_string(), NodeData::String(self.stage_ref.clone()));
+        outputs
+    }
+}
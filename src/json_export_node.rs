@@ -0,0 +1,417 @@
+//! USD JSON Export node - serializes the prim hierarchy for external tooling
+
+use nodle_plugin_sdk::*;
+use std::collections::{BTreeMap, HashMap};
+use serde_json::{json, Map, Value};
+use crate::core::usd_engine::with_usd_engine;
+use crate::ui::help::NodeHelp;
+use crate::ui::palette::status_label;
+use crate::ui::keyboard::{focus_marked, handle_key};
+use crate::registry::NodeTypeFactory;
+
+/// Help for the Export JSON node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_ExportJSON",
+    summary: "Serialize the prim hierarchy and chosen attributes to JSON",
+    details: "Walks the prim hierarchy and writes paths, types and the listed attributes as JSON, to Output Path or only to the JSON output. Handy for pipeline tools that do not link USD.",
+    ports: &[
+        ("Stage", "loaded_0"),
+        ("JSON", "{\"path\": \"/World\", \"type\": \"Xform\", \"children\": []}"),
+    ],
+    samples: &[],
+};
+
+/// Factory of the Export JSON node, for `crate::node_factories`
+pub fn factories() -> Vec<Box<dyn NodeFactory>> {
+    vec![NodeTypeFactory::boxed(metadata, |position| PluginNodeHandle::new(Box::new(USDJsonExportNode::new(position))))]
+}
+
+/// Metadata of the Export JSON node
+fn metadata() -> NodeMetadata {
+    NodeMetadata::new(
+        "USD_ExportJSON",
+        "Export JSON",
+        NodeCategory::new(&["USD", "Stage"]),
+        HELP.summary
+    )
+    .with_color(Color32::from_rgb(80, 150, 200))
+    .with_icon("🧾")
+    .with_inputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("USD stage to serialize"),
+    ])
+    .with_outputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("Pass-through USD stage"),
+        PortDefinition::optional("JSON", DataType::String)
+            .with_description("Scene graph as JSON"),
+    ])
+    .with_panel_type(PanelType::Parameter)
+    .with_workspace_compatibility(vec!["3D"])
+}
+
+/// Flat prim record gathered from the engine before nesting
+#[derive(Debug, Clone)]
+pub struct ExportedPrim {
+    pub path: String,
+    pub prim_type: String,
+    pub attributes: BTreeMap<String, String>,
+}
+
+/// Convert an attribute string to the closest JSON value
+fn attribute_value(value: &str) -> Value {
+    match value {
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        _ => value.parse::<f64>()
+            .ok()
+            .and_then(|number| serde_json::Number::from_f64(number).map(Value::Number))
+            .unwrap_or_else(|| Value::String(value.to_string())),
+    }
+}
+
+/// Parent path of a prim path ("/World/Cube" -> "/World", "/World" -> "/")
+fn parent_path(path: &str) -> &str {
+    match path.rfind('/') {
+        Some(0) | None => "/",
+        Some(index) => &path[..index],
+    }
+}
+
+/// Build a nested JSON scene graph from flat prim records
+///
+/// Each node has `name`, `path`, `type`, `attributes` and `children`. Prims
+/// whose parents were not exported are attached to the nearest exported
+/// ancestor so no data is lost.
+pub fn scene_graph_to_json(stage: &str, prims: &[ExportedPrim]) -> Value {
+    let known: HashMap<&str, &ExportedPrim> = prims.iter().map(|prim| (prim.path.as_str(), prim)).collect();
+    let mut children: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    
+    for prim in prims {
+        let mut parent = parent_path(&prim.path);
+        while parent != "/" && !known.contains_key(parent) {
+            parent = parent_path(parent);
+        }
+        children.entry(parent).or_default().push(prim.path.as_str());
+    }
+    for paths in children.values_mut() {
+        paths.sort();
+    }
+    
+    fn build(path: &str, known: &HashMap<&str, &ExportedPrim>, children: &BTreeMap<&str, Vec<&str>>) -> Value {
+        let prim = known[path];
+        let attributes: Map<String, Value> = prim.attributes.iter()
+            .map(|(name, value)| (name.clone(), attribute_value(value)))
+            .collect();
+        let child_nodes: Vec<Value> = children.get(path)
+            .map(|paths| paths.iter().map(|child| build(child, known, children)).collect())
+            .unwrap_or_default();
+        
+        json!({
+            "name": path.rsplit('/').next().unwrap_or_default(),
+            "path": path,
+            "type": prim.prim_type,
+            "attributes": attributes,
+            "children": child_nodes,
+        })
+    }
+    
+    let roots: Vec<Value> = children.get("/")
+        .map(|paths| paths.iter().map(|path| build(path, &known, &children)).collect())
+        .unwrap_or_default();
+    
+    json!({
+        "stage": stage,
+        "primCount": prims.len(),
+        "root": {
+            "name": "",
+            "path": "/",
+            "children": roots,
+        },
+    })
+}
+
+/// USD JSON Export node
+pub struct USDJsonExportNode {
+    id: String,
+    position: Pos2,
+    stage_ref: String,
+    /// Comma separated attribute names to include per prim
+    attributes: String,
+    output_path: String,
+    pretty: bool,
+    json: String,
+    /// Stage revision the JSON was serialized at, None when parameters changed since
+    revision: Option<u64>,
+    status: String,
+}
+
+impl USDJsonExportNode {
+    pub fn new(position: Pos2) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            position,
+            stage_ref: String::new(),
+            attributes: "visibility, purpose".to_string(),
+            output_path: String::new(),
+            pretty: true,
+            json: String::new(),
+            revision: None,
+            status: "No stage connected".to_string(),
+        }
+    }
+    
+    /// Gather prims and attributes from the engine and serialize them
+    fn export(&mut self) -> Result<(), String> {
+        let attribute_names: Vec<String> = self.attributes
+            .split(',')
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .collect();
+        let stage_ref = self.stage_ref.clone();
+        
+        let (stage_path, prims) = with_usd_engine(|engine| -> Result<(String, Vec<ExportedPrim>), String> {
+            let stage = engine.resolve_stage(&stage_ref)?;
+            let prims = engine.get_stage_prims(&stage.identifier)
+                .into_iter()
+                .map(|prim| ExportedPrim {
+                    path: prim.path.clone(),
+                    prim_type: prim.prim_type.clone(),
+                    attributes: attribute_names.iter()
+                        .filter_map(|name| {
+                            engine.get_attribute(&stage.identifier, &prim.path, name)
+                                .ok()
+                                .map(|value| (name.clone(), value))
+                        })
+                        .collect(),
+                })
+                .collect();
+            Ok((stage.path, prims))
+        })?;
+        
+        let graph = scene_graph_to_json(&stage_path, &prims);
+        self.json = if self.pretty {
+            serde_json::to_string_pretty(&graph)
+        } else {
+            serde_json::to_string(&graph)
+        }.map_err(|e| format!("Failed to serialize scene graph: {}", e))?;
+        self.status = format!("Serialized {} prims", prims.len());
+        Ok(())
+    }
+    
+    /// Write the last serialized JSON to the output path
+    fn write_file(&mut self) -> Result<(), String> {
+        if self.output_path.is_empty() {
+            return Err("No output path set".to_string());
+        }
+        std::fs::write(&self.output_path, &self.json)
+            .map_err(|e| format!("Failed to write '{}': {}", self.output_path, e))?;
+        self.status = format!("Wrote {}", self.output_path);
+        Ok(())
+    }
+}
+
+impl PluginNode for USDJsonExportNode {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+    
+    fn position(&self) -> Pos2 {
+        self.position
+    }
+    
+    fn set_position(&mut self, position: Pos2) {
+        self.position = position;
+    }
+    
+    fn get_parameter_ui(&self) -> ParameterUI {
+        let mut elements = Vec::new();
+        
+        elements.push(UIElement::Heading("USD JSON Export".to_string()));
+        elements.push(UIElement::Separator);
+        
+        elements.push(UIElement::TextEdit {
+            label: "Attributes".to_string(),
+            value: self.attributes.clone(),
+            parameter_name: "attributes".to_string(),
+        });
+        
+        elements.push(UIElement::Checkbox {
+            label: "Pretty Print".to_string(),
+            value: self.pretty,
+            parameter_name: "pretty".to_string(),
+        });
+        
+        elements.push(UIElement::TextEdit {
+            label: "Output File".to_string(),
+            value: self.output_path.clone(),
+            parameter_name: "output_path".to_string(),
+        });
+        
+        elements.push(UIElement::Button {
+            label: "Write JSON".to_string(),
+            action: "write_json".to_string(),
+        });
+        
+        elements.push(UIElement::Separator);
+        elements.push(status_label(&self.status));
+        elements.extend(HELP.section());
+        
+        ParameterUI { elements: focus_marked(&self.id, elements) }
+    }
+    
+    fn handle_ui_action(&mut self, action: UIAction) -> Vec<ParameterChange> {
+        if let Some(changes) = handle_key(self, &[], &action) {
+            return changes;
+        }
+        
+        let mut changes = Vec::new();
+        
+        match action {
+            UIAction::ParameterChanged { parameter, value } => {
+                match parameter.as_str() {
+                    "attributes" | "output_path" => {
+                        if let Some(text) = value.as_string() {
+                            self.set_parameter(&parameter, NodeData::String(text.to_string()));
+                            changes.push(ParameterChange {
+                                parameter: parameter.clone(),
+                                value: NodeData::String(text.to_string()),
+                            });
+                        }
+                    }
+                    "pretty" => {
+                        if let Some(pretty) = value.as_boolean() {
+                            self.set_parameter(&parameter, NodeData::Boolean(pretty));
+                            changes.push(ParameterChange {
+                                parameter: parameter.clone(),
+                                value: NodeData::Boolean(pretty),
+                            });
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            UIAction::ButtonClicked { action } => {
+                if action == "write_json" {
+                    let result = if self.stage_ref.is_empty() {
+                        Err("No stage connected".to_string())
+                    } else {
+                        self.export().and_then(|_| self.write_file())
+                    };
+                    if let Err(e) = result {
+                        self.status = format!("⚠ {}", e);
+                    }
+                }
+            }
+        }
+        
+        changes
+    }
+    
+    fn get_parameter(&self, name: &str) -> Option<NodeData> {
+        match name {
+            "attributes" => Some(NodeData::String(self.attributes.clone())),
+            "output_path" => Some(NodeData::String(self.output_path.clone())),
+            "pretty" => Some(NodeData::Boolean(self.pretty)),
+            _ => None,
+        }
+    }
+    
+    fn set_parameter(&mut self, name: &str, value: NodeData) {
+        match name {
+            "attributes" => {
+                if let Some(attributes) = value.as_string() {
+                    self.attributes = attributes.to_string();
+                    self.revision = None;
+                }
+            }
+            "output_path" => {
+                if let Some(path) = value.as_string() {
+                    self.output_path = path.to_string();
+                }
+            }
+            "pretty" => {
+                if let Some(pretty) = value.as_boolean() {
+                    self.pretty = pretty;
+                    self.revision = None;
+                }
+            }
+            _ => {}
+        }
+    }
+    
+    fn process(&mut self, inputs: &HashMap<String, NodeData>) -> HashMap<String, NodeData> {
+        let mut outputs = HashMap::new();
+        
+        let stage_ref = match inputs.get("Stage").and_then(|data| data.as_string()) {
+            Some(stage_ref) => stage_ref.to_string(),
+            None => {
+                self.stage_ref.clear();
+                self.json.clear();
+                self.revision = None;
+                self.status = "No stage connected".to_string();
+                return outputs;
+            }
+        };
+        if stage_ref != self.stage_ref {
+            self.stage_ref = stage_ref;
+            self.revision = None;
+        }
+        
+        // Serialize again only when the stage was edited or the parameters changed
+        let revision = with_usd_engine(|engine| engine.stage_revision(&self.stage_ref));
+        if self.revision != Some(revision) {
+            self.revision = Some(revision);
+            if let Err(e) = self.export() {
+                self.status = format!("⚠ {}", e);
+                self.json.clear();
+            }
+        }
+        
+        outputs.insert("Stage".to_string(), NodeData::String(self.stage_ref.clone()));
+        outputs.insert("JSON".to_string(), NodeData::String(self.json.clone()));
+        outputs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn prim(path: &str, prim_type: &str, attributes: &[(&str, &str)]) -> ExportedPrim {
+        ExportedPrim {
+            path: path.to_string(),
+            prim_type: prim_type.to_string(),
+            attributes: attributes.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect(),
+        }
+    }
+    
+    #[test]
+    fn scene_graph_nests_prims_under_exported_ancestors() {
+        let prims = [
+            prim("/World/Lights/Key", "DistantLight", &[("inputs:intensity", "3.5")]),
+            prim("/World", "Xform", &[("visibility", "inherited")]),
+            prim("/World/Cube", "Cube", &[("doubleSided", "true")]),
+            prim("/Looks", "Scope", &[]),
+        ];
+        let graph = scene_graph_to_json("shot.usda", &prims);
+        
+        assert_eq!(graph["stage"], "shot.usda");
+        assert_eq!(graph["primCount"], 4);
+        assert_eq!(graph["root"]["path"], "/");
+        let roots = graph["root"]["children"].as_array().unwrap();
+        assert_eq!(roots.iter().map(|node| &node["path"]).collect::<Vec<_>>(), ["/Looks", "/World"]);
+        
+        let world = &roots[1];
+        assert_eq!(world["name"], "World");
+        assert_eq!(world["type"], "Xform");
+        assert_eq!(world["attributes"]["visibility"], "inherited");
+        // /World/Lights was not exported, so its light hangs off /World
+        let children = world["children"].as_array().unwrap();
+        assert_eq!(children.iter().map(|node| &node["path"]).collect::<Vec<_>>(), ["/World/Cube", "/World/Lights/Key"]);
+        assert_eq!(children[0]["attributes"]["doubleSided"], true);
+        assert_eq!(children[1]["name"], "Key");
+        assert_eq!(children[1]["attributes"]["inputs:intensity"], 3.5);
+        assert_eq!(children[1]["children"], json!([]));
+    }
+}
@@ -0,0 +1,263 @@
+//! USD Layer Stack node - inspects the composed layer stack and mutes or reorders layers
+
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::core::usd_engine::{with_usd_engine, USDLayerInfo};
+use crate::ui::choice::{choice_buttons, parse_choice};
+use crate::ui::help::NodeHelp;
+use crate::ui::palette::status_label;
+use crate::ui::keyboard::{focus_marked, handle_key};
+use crate::registry::NodeTypeFactory;
+
+/// Help for the Layer Stack node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_LayerStack",
+    summary: "Inspect the composed layer stack and mute or reorder sublayers",
+    details: "Shows the root layer's sublayers strongest first. Muting a layer hides its opinions without removing it; reordering changes which layer wins.",
+    ports: &[
+        ("Stage", "loaded_0"),
+        ("Layers", "shot.usda\nlighting.usda\nlayout.usda"),
+    ],
+    samples: &[],
+};
+
+/// Factory of the Layer Stack node, for `crate::node_factories`
+pub fn factories() -> Vec<Box<dyn NodeFactory>> {
+    vec![NodeTypeFactory::boxed(metadata, |position| PluginNodeHandle::new(Box::new(USDLayerStackNode::new(position))))]
+}
+
+/// Metadata of the Layer Stack node
+fn metadata() -> NodeMetadata {
+    NodeMetadata::new(
+        "USD_LayerStack",
+        "Layer Stack",
+        NodeCategory::new(&["USD", "Composition"]),
+        HELP.summary
+    )
+    .with_color(Color32::from_rgb(80, 150, 200))
+    .with_icon("🗂")
+    .with_inputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("USD stage to inspect"),
+    ])
+    .with_outputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("Stage with the layer edits applied"),
+        PortDefinition::optional("Layers", DataType::String)
+            .with_description("Layer identifiers, indented by depth"),
+    ])
+    .with_panel_type(PanelType::Parameter)
+    .with_workspace_compatibility(vec!["3D"])
+}
+
+/// A layer stack edit requested from the parameter panel
+enum LayerEdit {
+    Mute(String, bool),
+    Move(usize, usize),
+    Target(String),
+}
+
+/// USD Layer Stack node
+pub struct USDLayerStackNode {
+    id: String,
+    position: Pos2,
+    stage_ref: String,
+    layers: Vec<USDLayerInfo>,
+    /// Layer interactive edits are authored into
+    edit_target: String,
+    pending: Vec<LayerEdit>,
+    dirty: bool,
+    status: String,
+}
+
+impl USDLayerStackNode {
+    pub fn new(position: Pos2) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            position,
+            stage_ref: String::new(),
+            layers: Vec::new(),
+            edit_target: "session".to_string(),
+            pending: Vec::new(),
+            dirty: true,
+            status: "No USD stage connected".to_string(),
+        }
+    }
+    
+    /// Apply pending edits and re-read the layer stack
+    fn refresh(&mut self) -> Result<(), String> {
+        let stage_ref = self.stage_ref.clone();
+        let pending = std::mem::take(&mut self.pending);
+        
+        let (layers, edit_target) = with_usd_engine(|engine| -> Result<(Vec<USDLayerInfo>, String), String> {
+            let stage = engine.resolve_stage(&stage_ref)?;
+            for edit in &pending {
+                match edit {
+                    LayerEdit::Mute(layer, muted) => engine.set_layer_muted(&stage.identifier, layer, *muted)?,
+                    LayerEdit::Move(from, to) => engine.move_sublayer(&stage.identifier, *from, *to)?,
+                    LayerEdit::Target(layer) => engine.set_edit_target(&stage.identifier, layer)?,
+                }
+            }
+            let layers = engine.get_layer_stack(&stage.identifier)?;
+            Ok((layers, engine.get_edit_target(&stage.identifier).name().to_string()))
+        })?;
+        self.layers = layers;
+        self.edit_target = edit_target;
+        
+        let muted = self.layers.iter().filter(|layer| layer.muted).count();
+        self.status = format!("{} layers, {} muted, editing {}", self.layers.len(), muted, self.edit_target);
+        Ok(())
+    }
+    
+    /// Number of direct sublayers of the root layer
+    fn sublayer_count(&self) -> usize {
+        self.layers.iter().filter(|layer| layer.depth == 1).count()
+    }
+    
+    /// Layer stack as indented lines
+    fn listing(&self) -> String {
+        self.layers.iter()
+            .map(|layer| format!("{}{}", "  ".repeat(layer.depth), layer.identifier))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl PluginNode for USDLayerStackNode {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+    
+    fn position(&self) -> Pos2 {
+        self.position
+    }
+    
+    fn set_position(&mut self, position: Pos2) {
+        self.position = position;
+    }
+    
+    fn get_parameter_ui(&self) -> ParameterUI {
+        let mut elements = Vec::new();
+        
+        elements.push(UIElement::Heading("USD Layer Stack".to_string()));
+        elements.push(UIElement::Separator);
+        
+        if self.stage_ref.is_empty() {
+            elements.push(UIElement::Label("No USD stage connected".to_string()));
+            elements.extend(HELP.section());
+            return ParameterUI { elements };
+        }
+        
+        let mut targets = vec!["session", "root"];
+        targets.extend(self.layers.iter()
+            .filter(|layer| layer.depth > 0)
+            .map(|layer| layer.identifier.as_str()));
+        elements.extend(choice_buttons("Edit Target", "edit_target", &targets, &self.edit_target));
+        elements.push(UIElement::Separator);
+        
+        let sublayer_count = self.sublayer_count();
+        let mut sublayer_index = 0;
+        for layer in &self.layers {
+            let mut label = format!("{}{}", "  ".repeat(layer.depth), layer.identifier);
+            if layer.offset != 0.0 || layer.scale != 1.0 {
+                label.push_str(&format!(" (offset {}, scale {})", layer.offset, layer.scale));
+            }
+            if layer.muted {
+                label.push_str(" [muted]");
+            }
+            elements.push(UIElement::Label(label));
+            
+            if layer.depth == 0 {
+                continue;
+            }
+            elements.push(UIElement::Button {
+                label: if layer.muted { "Unmute".to_string() } else { "Mute".to_string() },
+                action: format!("{}:{}", if layer.muted { "unmute" } else { "mute" }, layer.identifier),
+            });
+            
+            // Only the root layer's own sublayers can be reordered
+            if layer.depth == 1 {
+                if sublayer_index > 0 {
+                    elements.push(UIElement::Button {
+                        label: "▲ Stronger".to_string(),
+                        action: format!("up:{}", sublayer_index),
+                    });
+                }
+                if sublayer_index + 1 < sublayer_count {
+                    elements.push(UIElement::Button {
+                        label: "▼ Weaker".to_string(),
+                        action: format!("down:{}", sublayer_index),
+                    });
+                }
+                sublayer_index += 1;
+            }
+        }
+        
+        elements.push(UIElement::Separator);
+        elements.push(status_label(&self.status));
+        elements.extend(HELP.section());
+        
+        ParameterUI { elements: focus_marked(&self.id, elements) }
+    }
+    
+    fn handle_ui_action(&mut self, action: UIAction) -> Vec<ParameterChange> {
+        if let Some(changes) = handle_key(self, &[], &action) {
+            return changes;
+        }
+        
+        if let UIAction::ButtonClicked { action } = action {
+            let index = |direction: &str| parse_choice(&action, direction).and_then(|index| index.parse::<usize>().ok());
+            let edit = if let Some(layer) = parse_choice(&action, "edit_target") {
+                Some(LayerEdit::Target(layer.to_string()))
+            } else if let Some(layer) = parse_choice(&action, "mute") {
+                Some(LayerEdit::Mute(layer.to_string(), true))
+            } else if let Some(layer) = parse_choice(&action, "unmute") {
+                Some(LayerEdit::Mute(layer.to_string(), false))
+            } else if let Some(from) = index("up").filter(|&from| from > 0) {
+                Some(LayerEdit::Move(from, from - 1))
+            } else {
+                index("down").map(|from| LayerEdit::Move(from, from + 1))
+            };
+            
+            if let Some(edit) = edit {
+                self.pending.push(edit);
+                self.dirty = true;
+            }
+        }
+        
+        Vec::new()
+    }
+    
+    fn get_parameter(&self, _name: &str) -> Option<NodeData> {
+        None
+    }
+    
+    fn set_parameter(&mut self, _name: &str, _value: NodeData) {}
+    
+    fn process(&mut self, inputs: &HashMap<String, NodeData>) -> HashMap<String, NodeData> {
+        let mut outputs = HashMap::new();
+        
+        let Some(stage_ref) = inputs.get("Stage").and_then(|data| data.as_string()) else {
+            self.stage_ref.clear();
+            self.layers.clear();
+            self.status = "No USD stage connected".to_string();
+            return outputs;
+        };
+        
+        if stage_ref != self.stage_ref {
+            self.stage_ref = stage_ref.to_string();
+            self.dirty = true;
+        }
+        
+        if self.dirty {
+            self.dirty = false;
+            if let Err(e) = self.refresh() {
+                self.status = format!("⚠ {}", e);
+            }
+        }
+        
+        outputs.insert("Stage".to_string(), NodeData::String(self.stage_ref.clone()));
+        outputs.insert("Layers".to_string(), NodeData::String(self.listing()));
+        outputs
+    }
+}
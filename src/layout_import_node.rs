@@ -0,0 +1,538 @@
+//! USD Layout Import node - creates prims or point instances from CSV/JSON tables
+
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use glam::{EulerRot, Quat};
+use crate::core::usd_engine::{with_usd_engine, USDAttributeEdit, USDPrimSpec};
+use crate::core::usd_value::UsdValue;
+use crate::ui::help::NodeHelp;
+use crate::ui::palette::status_label;
+use crate::ui::keyboard::{focus_marked, handle_key};
+use crate::registry::NodeTypeFactory;
+
+/// Help for the Import Layout Table node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_LayoutImport",
+    summary: "Create prims or point instances from a CSV/JSON placement table",
+    details: "Each table row places an asset with a name, position, rotation and scale under Parent Path. With Use Point Instancer on, rows become instances of one PointInstancer, which scales to very large layouts.",
+    ports: &[
+        ("Stage", "stage_0"),
+        ("Prims", "/World/Layout/tree_001\n/World/Layout/tree_002"),
+    ],
+    samples: &[],
+};
+
+/// Factory of the Import Layout Table node, for `crate::node_factories`
+pub fn factories() -> Vec<Box<dyn NodeFactory>> {
+    vec![NodeTypeFactory::boxed(metadata, |position| PluginNodeHandle::new(Box::new(USDLayoutImportNode::new(position))))]
+}
+
+/// Metadata of the Import Layout Table node
+fn metadata() -> NodeMetadata {
+    NodeMetadata::new(
+        "USD_LayoutImport",
+        "Import Layout Table",
+        NodeCategory::new(&["USD", "Stage"]),
+        HELP.summary
+    )
+    .with_color(Color32::from_rgb(80, 150, 200))
+    .with_icon("📋")
+    .with_inputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("USD stage to populate"),
+    ])
+    .with_outputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("Pass-through USD stage"),
+        PortDefinition::optional("Prims", DataType::String)
+            .with_description("Created prim paths, one per line"),
+    ])
+    .with_panel_type(PanelType::Parameter)
+    .with_workspace_compatibility(vec!["3D"])
+}
+
+/// One placement read from a layout table
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayoutRecord {
+    pub name: Option<String>,
+    pub position: [f64; 3],
+    /// Euler rotation in degrees, USD rotateXYZ order
+    pub rotation: [f64; 3],
+    pub scale: [f64; 3],
+    pub asset: Option<String>,
+}
+
+impl Default for LayoutRecord {
+    fn default() -> Self {
+        Self {
+            name: None,
+            position: [0.0; 3],
+            rotation: [0.0; 3],
+            scale: [1.0; 3],
+            asset: None,
+        }
+    }
+}
+
+impl LayoutRecord {
+    /// Apply a named column value to the record
+    fn set_field(&mut self, column: &str, value: &str) -> Result<(), String> {
+        let value = value.trim();
+        if value.is_empty() {
+            return Ok(());
+        }
+        let number = || value.parse::<f64>().map_err(|_| format!("Column '{}' expects a number, got '{}'", column, value));
+        
+        match column.trim().to_lowercase().as_str() {
+            "name" | "id" => self.name = Some(value.to_string()),
+            "asset" | "asset_path" | "file" | "reference" => self.asset = Some(value.to_string()),
+            "x" | "tx" | "px" | "pos_x" => self.position[0] = number()?,
+            "y" | "ty" | "py" | "pos_y" => self.position[1] = number()?,
+            "z" | "tz" | "pz" | "pos_z" => self.position[2] = number()?,
+            "rx" | "rot_x" => self.rotation[0] = number()?,
+            "ry" | "rot_y" => self.rotation[1] = number()?,
+            "rz" | "rot_z" | "heading" => self.rotation[2] = number()?,
+            "sx" | "scale_x" => self.scale[0] = number()?,
+            "sy" | "scale_y" => self.scale[1] = number()?,
+            "sz" | "scale_z" => self.scale[2] = number()?,
+            "scale" | "uniform_scale" => self.scale = [number()?; 3],
+            _ => {} // Unknown columns are ignored
+        }
+        Ok(())
+    }
+    
+    /// Orientation as a USD quaternion (w, x, y, z)
+    pub fn orientation(&self) -> [f64; 4] {
+        let [rx, ry, rz] = self.rotation.map(|degrees| (degrees as f32).to_radians());
+        let quat = Quat::from_euler(EulerRot::ZYX, rz, ry, rx);
+        [quat.w as f64, quat.x as f64, quat.y as f64, quat.z as f64]
+    }
+}
+
+/// Parse a layout table, choosing CSV or JSON from the file extension or content
+pub fn parse_layout(file_path: &str, content: &str) -> Result<Vec<LayoutRecord>, String> {
+    let is_json = file_path.to_lowercase().ends_with(".json") || content.trim_start().starts_with('[');
+    if is_json {
+        parse_layout_json(content)
+    } else {
+        parse_layout_csv(content)
+    }
+}
+
+/// Parse CSV with a header row
+pub fn parse_layout_csv(content: &str) -> Result<Vec<LayoutRecord>, String> {
+    let mut lines = content.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'));
+    
+    let header = match lines.next() {
+        Some((_, line)) => split_csv_line(line),
+        None => return Ok(Vec::new()),
+    };
+    
+    let mut records = Vec::new();
+    for (line_number, line) in lines {
+        let mut record = LayoutRecord::default();
+        for (column, value) in header.iter().zip(split_csv_line(line)) {
+            record.set_field(column, &value)
+                .map_err(|e| format!("Line {}: {}", line_number + 1, e))?;
+        }
+        records.push(record);
+    }
+    Ok(records)
+}
+
+/// Parse a JSON array of objects, accepting flat columns or position/rotation/scale arrays
+pub fn parse_layout_json(content: &str) -> Result<Vec<LayoutRecord>, String> {
+    let value: serde_json::Value = serde_json::from_str(content)
+        .map_err(|e| format!("Invalid JSON: {}", e))?;
+    let entries = value.as_array().ok_or("Layout JSON must be an array of objects")?;
+    
+    let mut records = Vec::new();
+    for (index, entry) in entries.iter().enumerate() {
+        let object = entry.as_object().ok_or_else(|| format!("Entry {} is not an object", index))?;
+        let mut record = LayoutRecord::default();
+        
+        for (key, value) in object {
+            let vector = |default: f64| -> Result<[f64; 3], String> {
+                let items = value.as_array().ok_or_else(|| format!("Entry {}: '{}' must be an array", index, key))?;
+                let mut result = [default; 3];
+                for (slot, item) in result.iter_mut().zip(items) {
+                    *slot = item.as_f64().ok_or_else(|| format!("Entry {}: '{}' must contain numbers", index, key))?;
+                }
+                Ok(result)
+            };
+            
+            match key.as_str() {
+                "position" | "translate" => record.position = vector(0.0)?,
+                "rotation" | "rotate" => record.rotation = vector(0.0)?,
+                "scale" if value.is_array() => record.scale = vector(1.0)?,
+                _ => {
+                    let text = match value {
+                        serde_json::Value::String(text) => text.clone(),
+                        other => other.to_string(),
+                    };
+                    record.set_field(key, &text).map_err(|e| format!("Entry {}: {}", index, e))?;
+                }
+            }
+        }
+        records.push(record);
+    }
+    Ok(records)
+}
+
+/// Split one CSV line, honouring double-quoted fields
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+    
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields.into_iter().map(|f| f.trim().to_string()).collect()
+}
+
+/// Make a valid prim name from free-form text
+pub(crate) fn sanitize_prim_name(name: &str) -> String {
+    let mut sanitized: String = name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' { c } else { '_' })
+        .collect();
+    if sanitized.chars().next().map_or(true, |c| c.is_ascii_digit()) {
+        sanitized.insert(0, '_');
+    }
+    sanitized
+}
+
+/// USD Layout Import node
+pub struct USDLayoutImportNode {
+    id: String,
+    position: Pos2,
+    file_path: String,
+    parent_path: String,
+    use_point_instancer: bool,
+    stage_ref: String,
+    /// Set when parameters change so the next cook re-imports
+    dirty: bool,
+    created_paths: Vec<String>,
+    status: String,
+}
+
+impl USDLayoutImportNode {
+    pub fn new(position: Pos2) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            position,
+            file_path: String::new(),
+            parent_path: "/World/Layout".to_string(),
+            use_point_instancer: false,
+            stage_ref: String::new(),
+            dirty: true,
+            created_paths: Vec::new(),
+            status: "No table loaded".to_string(),
+        }
+    }
+    
+    /// Read the table and author prims on the stage
+    fn import(&mut self) -> Result<(), String> {
+        let content = std::fs::read_to_string(&self.file_path)
+            .map_err(|e| format!("Failed to read '{}': {}", self.file_path, e))?;
+        let records = parse_layout(&self.file_path, &content)?;
+        let parent_path = self.parent_path.trim_end_matches('/').to_string();
+        let stage_ref = self.stage_ref.clone();
+        let use_point_instancer = self.use_point_instancer;
+        
+        self.created_paths = with_usd_engine(|engine| -> Result<Vec<String>, String> {
+            let stage = engine.resolve_stage(&stage_ref)?;
+            let stage_id = stage.identifier.as_str();
+            engine.create_xform(stage_id, &parent_path)?;
+            
+            if use_point_instancer {
+                // One prototype per distinct asset, referenced under the instancer
+                let instancer_path = format!("{}/Instancer", parent_path);
+                let mut prototypes: Vec<String> = Vec::new();
+                let mut proto_indices = Vec::new();
+                let mut asset_to_proto: HashMap<String, i32> = HashMap::new();
+                
+                for record in &records {
+                    let asset = record.asset.clone().unwrap_or_default();
+                    let index = match asset_to_proto.get(&asset) {
+                        Some(&index) => index,
+                        None => {
+                            let proto_path = format!("{}/Prototypes/proto_{}", instancer_path, prototypes.len());
+                            if asset.is_empty() {
+                                engine.create_xform(stage_id, &proto_path)?;
+                            } else {
+                                engine.add_reference(stage_id, &proto_path, &asset, None)?;
+                            }
+                            prototypes.push(proto_path);
+                            let index = prototypes.len() as i32 - 1;
+                            asset_to_proto.insert(asset, index);
+                            index
+                        }
+                    };
+                    proto_indices.push(index);
+                }
+                
+                let positions: Vec<[f64; 3]> = records.iter().map(|r| r.position).collect();
+                let orientations: Vec<[f64; 4]> = records.iter().map(|r| r.orientation()).collect();
+                let scales: Vec<[f64; 3]> = records.iter().map(|r| r.scale).collect();
+                engine.create_point_instancer(stage_id, &instancer_path, &prototypes, &proto_indices,
+                                              &positions, &orientations, &scales)?;
+                Ok(vec![instancer_path])
+            } else {
+                // Author plain prims and their transforms in two batched round-trips
+                let mut paths = Vec::with_capacity(records.len());
+                let mut xforms = Vec::new();
+                let mut edits = Vec::with_capacity(records.len() * 4);
+                for (index, record) in records.iter().enumerate() {
+                    let name = record.name.as_deref()
+                        .map(sanitize_prim_name)
+                        .unwrap_or_else(|| format!("item_{}", index));
+                    let prim_path = format!("{}/{}", parent_path, name);
+                    
+                    match &record.asset {
+                        Some(asset) => { engine.add_reference(stage_id, &prim_path, asset, None)?; }
+                        None => xforms.push(USDPrimSpec { path: prim_path.clone(), prim_type: "Xform".to_string() }),
+                    }
+                    
+                    let mut edit = |attr_name: &str, value: UsdValue| edits.push(USDAttributeEdit {
+                        prim_path: prim_path.clone(),
+                        attr_name: attr_name.to_string(),
+                        value,
+                    });
+                    edit("xformOp:translate", UsdValue::Vec3(record.position));
+                    edit("xformOp:rotateXYZ", UsdValue::Vec3(record.rotation));
+                    edit("xformOp:scale", UsdValue::Vec3(record.scale));
+                    edit("xformOpOrder", UsdValue::Array(
+                        ["xformOp:translate", "xformOp:rotateXYZ", "xformOp:scale"].map(|op| UsdValue::Token(op.to_string())).to_vec()
+                    ));
+                    paths.push(prim_path);
+                }
+                engine.create_prims_bulk(stage_id, &xforms)?;
+                engine.set_attributes_bulk(stage_id, &edits)?;
+                Ok(paths)
+            }
+        })?;
+        
+        self.status = format!("Imported {} records from {}", records.len(), self.file_path);
+        Ok(())
+    }
+}
+
+impl PluginNode for USDLayoutImportNode {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+    
+    fn position(&self) -> Pos2 {
+        self.position
+    }
+    
+    fn set_position(&mut self, position: Pos2) {
+        self.position = position;
+    }
+    
+    fn get_parameter_ui(&self) -> ParameterUI {
+        let mut elements = Vec::new();
+        
+        elements.push(UIElement::Heading("USD Layout Import".to_string()));
+        elements.push(UIElement::Separator);
+        
+        elements.push(UIElement::TextEdit {
+            label: "Table File (CSV/JSON)".to_string(),
+            value: self.file_path.clone(),
+            parameter_name: "file_path".to_string(),
+        });
+        
+        elements.push(UIElement::TextEdit {
+            label: "Parent Path".to_string(),
+            value: self.parent_path.clone(),
+            parameter_name: "parent_path".to_string(),
+        });
+        
+        elements.push(UIElement::Checkbox {
+            label: "Use Point Instancer".to_string(),
+            value: self.use_point_instancer,
+            parameter_name: "use_point_instancer".to_string(),
+        });
+        
+        elements.push(UIElement::Button {
+            label: "Re-import".to_string(),
+            action: "reimport".to_string(),
+        });
+        
+        elements.push(UIElement::Separator);
+        elements.push(UIElement::Label("Columns: name, x, y, z, rx, ry, rz, sx, sy, sz, scale, asset".to_string()));
+        elements.push(status_label(&self.status));
+        elements.extend(HELP.section());
+        
+        ParameterUI { elements: focus_marked(&self.id, elements) }
+    }
+    
+    fn handle_ui_action(&mut self, action: UIAction) -> Vec<ParameterChange> {
+        if let Some(changes) = handle_key(self, &[], &action) {
+            return changes;
+        }
+        
+        let mut changes = Vec::new();
+        
+        match action {
+            UIAction::ParameterChanged { parameter, value } => {
+                match parameter.as_str() {
+                    "file_path" | "parent_path" => {
+                        if let Some(text) = value.as_string() {
+                            self.set_parameter(&parameter, NodeData::String(text.to_string()));
+                            changes.push(ParameterChange {
+                                parameter: parameter.clone(),
+                                value: NodeData::String(text.to_string()),
+                            });
+                        }
+                    }
+                    "use_point_instancer" => {
+                        if let Some(enabled) = value.as_boolean() {
+                            self.set_parameter(&parameter, NodeData::Boolean(enabled));
+                            changes.push(ParameterChange {
+                                parameter: parameter.clone(),
+                                value: NodeData::Boolean(enabled),
+                            });
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            UIAction::ButtonClicked { action } => {
+                if action == "reimport" {
+                    self.dirty = true;
+                }
+            }
+        }
+        
+        changes
+    }
+    
+    fn get_parameter(&self, name: &str) -> Option<NodeData> {
+        match name {
+            "file_path" => Some(NodeData::String(self.file_path.clone())),
+            "parent_path" => Some(NodeData::String(self.parent_path.clone())),
+            "use_point_instancer" => Some(NodeData::Boolean(self.use_point_instancer)),
+            _ => None,
+        }
+    }
+    
+    fn set_parameter(&mut self, name: &str, value: NodeData) {
+        match name {
+            "file_path" => {
+                if let Some(path) = value.as_string() {
+                    self.file_path = path.to_string();
+                    self.dirty = true;
+                }
+            }
+            "parent_path" => {
+                if let Some(path) = value.as_string() {
+                    self.parent_path = path.to_string();
+                    self.dirty = true;
+                }
+            }
+            "use_point_instancer" => {
+                if let Some(enabled) = value.as_boolean() {
+                    self.use_point_instancer = enabled;
+                    self.dirty = true;
+                }
+            }
+            _ => {}
+        }
+    }
+    
+    fn process(&mut self, inputs: &HashMap<String, NodeData>) -> HashMap<String, NodeData> {
+        let mut outputs = HashMap::new();
+        
+        let stage_ref = match inputs.get("Stage").and_then(|data| data.as_string()) {
+            Some(stage_ref) => stage_ref.to_string(),
+            None => return outputs,
+        };
+        if stage_ref != self.stage_ref {
+            self.stage_ref = stage_ref;
+            self.dirty = true;
+        }
+        
+        if self.dirty && !self.file_path.is_empty() {
+            self.dirty = false;
+            if let Err(e) = self.import() {
+                self.status = format!("⚠ {}", e);
+                self.created_paths.clear();
+            }
+        }
+        
+        outputs.insert("Stage".to_string(), NodeData::String(self.stage_ref.clone()));
+        outputs.insert("Prims".to_string(), NodeData::String(self.created_paths.join("\n")));
+        outputs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn csv_fields_honour_quotes() {
+        assert_eq!(split_csv_line(r#"tree, "oak, old" ,"say ""hi""""#), ["tree", "oak, old", r#"say "hi""#]);
+        assert_eq!(split_csv_line("a,,b,"), ["a", "", "b", ""]);
+        assert_eq!(split_csv_line(""), [""]);
+    }
+    
+    #[test]
+    fn csv_rows_fill_missing_columns_with_defaults() {
+        let csv = "# exported layout\nName,X,Y,Z,Asset\n\ntree_a,1,2,3,\"assets/tree, oak.usd\"\ntree_b,4,5\n";
+        let records = parse_layout_csv(csv).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].name.as_deref(), Some("tree_a"));
+        assert_eq!(records[0].position, [1.0, 2.0, 3.0]);
+        assert_eq!(records[0].asset.as_deref(), Some("assets/tree, oak.usd"));
+        assert_eq!(records[1].position, [4.0, 5.0, 0.0]);
+        assert_eq!(records[1].scale, [1.0; 3]);
+        assert_eq!(records[1].asset, None);
+        
+        assert_eq!(parse_layout_csv("\n# only a comment\n").unwrap(), Vec::new());
+        let error = parse_layout_csv("name,x\ntree,1\nrock,far").unwrap_err();
+        assert!(error.starts_with("Line 3:"), "{}", error);
+    }
+    
+    #[test]
+    fn json_entries_accept_arrays_and_flat_columns() {
+        let json = r#"[
+            {"name": "tree_a", "position": [1, 2, 3], "rotation": [0, 0, 90], "scale": 2},
+            {"id": 7, "x": 4, "scale": [1, 2]}
+        ]"#;
+        let records = parse_layout_json(json).unwrap();
+        assert_eq!(records[0].position, [1.0, 2.0, 3.0]);
+        assert_eq!(records[0].rotation, [0.0, 0.0, 90.0]);
+        assert_eq!(records[0].scale, [2.0; 3]);
+        assert_eq!(records[1].name.as_deref(), Some("7"));
+        assert_eq!(records[1].position, [4.0, 0.0, 0.0]);
+        assert_eq!(records[1].scale, [1.0, 2.0, 1.0]);
+    }
+    
+    #[test]
+    fn malformed_json_is_reported() {
+        assert!(parse_layout_json("[{\"name\": ").unwrap_err().starts_with("Invalid JSON"));
+        assert_eq!(parse_layout_json("{}").unwrap_err(), "Layout JSON must be an array of objects");
+        assert_eq!(parse_layout_json("[1]").unwrap_err(), "Entry 0 is not an object");
+        assert_eq!(parse_layout_json(r#"[{}, {"position": "origin"}]"#).unwrap_err(), "Entry 1: 'position' must be an array");
+        assert_eq!(parse_layout_json(r#"[{"x": "left"}]"#).unwrap_err(), "Entry 0: Column 'x' expects a number, got 'left'");
+        
+        // Extension or a leading bracket picks the JSON parser
+        assert!(parse_layout("layout.csv", "[]").unwrap().is_empty());
+        assert!(parse_layout("layout.json", "name").is_err());
+    }
+}
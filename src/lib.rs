@@ -3,20 +3,95 @@
 //! This plugin provides complete Universal Scene Description (USD) functionality.
 
 use nodle_plugin_sdk::*;
-use std::collections::HashMap;
+
+pub use ui::help::NodeHelp;
 
 // Include core module for USD engine and Python integration
 mod core;
 
-// Include viewport module with complete 3D rendering
-mod viewport;
-
-// Include proper load stage node
-mod load_stage_node;
+// Include shared parameter UI helpers
+mod ui;
+
+// Include frame capture pipeline
+mod capture;
+
+// Include shared layer of the modular nodes
+mod modular;
+
+// Include node module registration
+mod registry;
+
+// Include starter graph templates
+pub mod templates;
+
+// C interface other plugins query composed scenes through
+pub mod plugin_api;
+
+// Include every node module, in palette order
+registry::node_modules! {
+    // Viewport module with complete 3D rendering
+    viewport,
+    
+    // Stage nodes
+    create_stage_node,
+    load_stage_node,
+    save_stage_node,
+    layout_import_node,
+    json_export_node,
+    timeline_node,
+    copy_prims_node,
+    variant_selector_node,
+    flatten_stage_node,
+    layer_stack_node,
+    package_usdz_node,
+    instancer_edit_node,
+    documentation_node,
+    relationship_node,
+    watch_folder_node,
+    collection_node,
+    assemble_node,
+    render_pass_node,
+    render_sequence_node,
+    tutorial_node,
+    stage,
+    
+    // Geometry and transform nodes
+    face_set_node,
+    geometry,
+    transform_node,
+    
+    // Lighting nodes
+    light_rig_node,
+    sun_sky_node,
+    lighting,
+    
+    // Shading nodes
+    material_preview_node,
+    assign_rules_node,
+    shading,
+    
+    // Inspection nodes
+    stage_inspector_node,
+    prim_properties_node,
+    spreadsheet_node,
+    bridge_profile_node,
+}
 
 // USD Plugin
 pub struct USDPlugin;
 
+impl USDPlugin {
+    /// Starter graphs the host can offer to instantiate, see `templates`
+    pub fn graph_templates(&self) -> Vec<templates::GraphTemplate> {
+        templates::graph_templates()
+    }
+    
+    /// Help of a node type, the same text its panel shows in the "?" section
+    pub fn node_help(&self, node_type: &str) -> Option<&'static NodeHelp> {
+        ui::help::node_help(node_type)
+    }
+}
+
 impl NodePlugin for USDPlugin {
     fn plugin_info(&self) -> PluginInfo {
         PluginInfo {
@@ -31,641 +106,42 @@ impl NodePlugin for USDPlugin {
     fn register_nodes(&self, registry: &mut dyn NodeRegistryTrait) {
         println!("Registering comprehensive USD nodes...");
         
-        // Register the USD Viewport node
-        let _ = registry.register_node_factory(Box::new(crate::viewport::USDViewport::default()));
-        println!("✅ USD Viewport node registered");
-        
-        // Register Stage nodes
-        let _ = registry.register_node_factory(Box::new(USDCreateStageFactory::default()));
-        let _ = registry.register_node_factory(Box::new(USDLoadStageFactory::default()));
-        let _ = registry.register_node_factory(Box::new(USDSaveStageFactory::default()));
-        println!("✅ USD Stage nodes registered");
-        
-        // Register Geometry nodes
-        let _ = registry.register_node_factory(Box::new(USDMeshFactory::default()));
-        let _ = registry.register_node_factory(Box::new(USDSphereFactory::default()));
-        let _ = registry.register_node_factory(Box::new(USDCubeFactory::default()));
-        let _ = registry.register_node_factory(Box::new(USDCylinderFactory::default()));
-        println!("✅ USD Geometry nodes registered");
-        
-        // Register Transform nodes
-        let _ = registry.register_node_factory(Box::new(USDXformFactory::default()));
-        let _ = registry.register_node_factory(Box::new(USDTranslateFactory::default()));
-        let _ = registry.register_node_factory(Box::new(USDRotateFactory::default()));
-        let _ = registry.register_node_factory(Box::new(USDScaleFactory::default()));
-        println!("✅ USD Transform nodes registered");
-        
-        // Register Lighting nodes
-        let _ = registry.register_node_factory(Box::new(USDDistantLightFactory::default()));
-        let _ = registry.register_node_factory(Box::new(USDSphereLightFactory::default()));
-        let _ = registry.register_node_factory(Box::new(USDDomeLightFactory::default()));
-        println!("✅ USD Lighting nodes registered");
-        
-        // Register Shading nodes
-        let _ = registry.register_node_factory(Box::new(USDMaterialFactory::default()));
-        let _ = registry.register_node_factory(Box::new(USDShaderFactory::default()));
-        let _ = registry.register_node_factory(Box::new(USDTextureFactory::default()));
-        println!("✅ USD Shading nodes registered");
-        
-        // Register additional viewport nodes
-        let _ = registry.register_node_factory(Box::new(USDStageInspectorFactory::default()));
-        println!("✅ USD Viewport nodes registered");
+        let factories = node_factories();
+        let count = factories.len();
+        for factory in factories {
+            let _ = registry.register_node_factory(factory);
+        }
         
-        println!("🎉 All USD nodes registered successfully!");
+        println!("🎉 All {} USD nodes registered successfully!", count);
     }
     
-    
     fn on_load(&self) -> Result<(), PluginError> {
         println!("USD Plugin loaded - comprehensive USD support available");
+        match core::session::restore_session() {
+            Ok(0) => {}
+            Ok(count) => println!("✓ Restored {} stages from before the reload", count),
+            Err(e) => eprintln!("⚠ Failed to restore the previous session: {}", e),
+        }
         Ok(())
     }
     
     fn on_unload(&self) -> Result<(), PluginError> {
+        match core::session::save_session() {
+            Ok(0) => {}
+            Ok(count) => println!("✓ Saved {} stages for the next load", count),
+            Err(e) => eprintln!("⚠ Failed to save the session: {}", e),
+        }
         println!("USD Plugin unloaded");
         Ok(())
     }
 }
 
-// Simple node factory implementations for all USD node types
-
-// Stage node factories
-#[derive(Debug, Default)]
-pub struct USDCreateStageFactory;
-
-impl NodeFactory for USDCreateStageFactory {
-    fn metadata(&self) -> NodeMetadata {
-        NodeMetadata::new(
-            "USD_CreateStage",
-            "Create Stage",
-            NodeCategory::new(&["USD", "Stage"]),
-            "Create a new USD stage"
-        )
-        .with_color(Color32::from_rgb(80, 150, 200))
-        .with_icon("🎬")
-        .with_outputs(vec![
-            PortDefinition::required("Stage", DataType::String)
-                .with_description("Created USD stage"),
-        ])
-        .with_workspace_compatibility(vec!["3D"])
-    }
-    
-    fn create_node(&self, position: Pos2) -> PluginNodeHandle {
-        PluginNodeHandle::new(Box::new(SimpleUSDNode::new("USD_CreateStage", "Create Stage", position)))
-    }
-}
-
-#[derive(Debug, Default)]
-pub struct USDLoadStageFactory;
-
-impl NodeFactory for USDLoadStageFactory {
-    fn metadata(&self) -> NodeMetadata {
-        println!("Creating USD Load Stage metadata with output port");
-        NodeMetadata::new(
-            "USD_LoadStage",
-            "Load Stage",
-            NodeCategory::new(&["USD", "Stage"]),
-            "Load a USD stage from file"
-        )
-        .with_color(Color32::from_rgb(80, 150, 200))
-        .with_icon("📂")
-        .with_inputs(vec![
-            // No input ports - file selection via parameters
-        ])
-        .with_outputs(vec![
-            PortDefinition::required("Stage", DataType::String)
-                .with_description("Loaded USD stage"),
-        ])
-        .with_panel_type(PanelType::Parameter)
-        .with_workspace_compatibility(vec!["3D"])
-    }
-    
-    fn create_node(&self, position: Pos2) -> PluginNodeHandle {
-        println!("Creating USD Load Stage node at position: {:?}", position);
-        PluginNodeHandle::new(Box::new(crate::load_stage_node::USDLoadStageNode::new(position)))
-    }
-}
-
-#[derive(Debug, Default)]
-pub struct USDSaveStageFactory;
-
-impl NodeFactory for USDSaveStageFactory {
-    fn metadata(&self) -> NodeMetadata {
-        NodeMetadata::new(
-            "USD_SaveStage",
-            "Save Stage",
-            NodeCategory::new(&["USD", "Stage"]),
-            "Save USD stage to file"
-        )
-        .with_color(Color32::from_rgb(80, 150, 200))
-        .with_icon("💾")
-        .with_inputs(vec![
-            PortDefinition::required("Stage", DataType::String)
-                .with_description("USD stage to save"),
-            PortDefinition::optional("File Path", DataType::String)
-                .with_description("Output file path"),
-        ])
-        .with_outputs(vec![
-            PortDefinition::required("Success", DataType::Boolean)
-                .with_description("Save operation success"),
-        ])
-        .with_workspace_compatibility(vec!["3D"])
-    }
-    
-    fn create_node(&self, position: Pos2) -> PluginNodeHandle {
-        PluginNodeHandle::new(Box::new(SimpleUSDNode::new("USD_SaveStage", "Save Stage", position)))
-    }
-}
-
-// Geometry node factories
-#[derive(Debug, Default)]
-pub struct USDMeshFactory;
-
-impl NodeFactory for USDMeshFactory {
-    fn metadata(&self) -> NodeMetadata {
-        NodeMetadata::new(
-            "USD_Mesh",
-            "Mesh",
-            NodeCategory::new(&["USD", "Geometry"]),
-            "Create USD mesh geometry"
-        )
-        .with_color(Color32::from_rgb(100, 180, 100))
-        .with_icon("🔺")
-        .with_inputs(vec![
-            PortDefinition::required("Stage", DataType::String)
-                .with_description("USD stage"),
-        ])
-        .with_outputs(vec![
-            PortDefinition::required("Mesh", DataType::String)
-                .with_description("USD mesh prim"),
-        ])
-        .with_workspace_compatibility(vec!["3D"])
-    }
-    
-    fn create_node(&self, position: Pos2) -> PluginNodeHandle {
-        PluginNodeHandle::new(Box::new(SimpleUSDNode::new("USD_Mesh", "Mesh", position)))
-    }
-}
-
-#[derive(Debug, Default)]
-pub struct USDSphereFactory;
-
-impl NodeFactory for USDSphereFactory {
-    fn metadata(&self) -> NodeMetadata {
-        NodeMetadata::new(
-            "USD_Sphere",
-            "Sphere",
-            NodeCategory::new(&["USD", "Geometry"]),
-            "Create USD sphere primitive"
-        )
-        .with_color(Color32::from_rgb(100, 180, 100))
-        .with_icon("🔴")
-        .with_inputs(vec![
-            PortDefinition::required("Stage", DataType::String)
-                .with_description("USD stage"),
-        ])
-        .with_outputs(vec![
-            PortDefinition::required("Sphere", DataType::String)
-                .with_description("USD sphere prim"),
-        ])
-        .with_workspace_compatibility(vec!["3D"])
-    }
-    
-    fn create_node(&self, position: Pos2) -> PluginNodeHandle {
-        PluginNodeHandle::new(Box::new(SimpleUSDNode::new("USD_Sphere", "Sphere", position)))
-    }
-}
-
-#[derive(Debug, Default)]
-pub struct USDCubeFactory;
-
-impl NodeFactory for USDCubeFactory {
-    fn metadata(&self) -> NodeMetadata {
-        NodeMetadata::new(
-            "USD_Cube",
-            "Cube",
-            NodeCategory::new(&["USD", "Geometry"]),
-            "Create USD cube primitive"
-        )
-        .with_color(Color32::from_rgb(100, 180, 100))
-        .with_icon("🔳")
-        .with_inputs(vec![
-            PortDefinition::required("Stage", DataType::String)
-                .with_description("USD stage"),
-        ])
-        .with_outputs(vec![
-            PortDefinition::required("Cube", DataType::String)
-                .with_description("USD cube prim"),
-        ])
-        .with_workspace_compatibility(vec!["3D"])
-    }
-    
-    fn create_node(&self, position: Pos2) -> PluginNodeHandle {
-        PluginNodeHandle::new(Box::new(SimpleUSDNode::new("USD_Cube", "Cube", position)))
-    }
-}
-
-#[derive(Debug, Default)]
-pub struct USDCylinderFactory;
-
-impl NodeFactory for USDCylinderFactory {
-    fn metadata(&self) -> NodeMetadata {
-        NodeMetadata::new(
-            "USD_Cylinder",
-            "Cylinder",
-            NodeCategory::new(&["USD", "Geometry"]),
-            "Create USD cylinder primitive"
-        )
-        .with_color(Color32::from_rgb(100, 180, 100))
-        .with_icon("🛢")
-        .with_inputs(vec![
-            PortDefinition::required("Stage", DataType::String)
-                .with_description("USD stage"),
-        ])
-        .with_outputs(vec![
-            PortDefinition::required("Cylinder", DataType::String)
-                .with_description("USD cylinder prim"),
-        ])
-        .with_workspace_compatibility(vec!["3D"])
-    }
-    
-    fn create_node(&self, position: Pos2) -> PluginNodeHandle {
-        PluginNodeHandle::new(Box::new(SimpleUSDNode::new("USD_Cylinder", "Cylinder", position)))
-    }
-}
-
-// Transform node factories
-#[derive(Debug, Default)]
-pub struct USDXformFactory;
-
-impl NodeFactory for USDXformFactory {
-    fn metadata(&self) -> NodeMetadata {
-        NodeMetadata::new(
-            "USD_Xform",
-            "Xform",
-            NodeCategory::new(&["USD", "Transform"]),
-            "Apply transformation to USD prim"
-        )
-        .with_color(Color32::from_rgb(150, 120, 200))
-        .with_icon("🔄")
-        .with_inputs(vec![
-            PortDefinition::required("Stage", DataType::String)
-                .with_description("USD stage"),
-        ])
-        .with_outputs(vec![
-            PortDefinition::required("Xform", DataType::String)
-                .with_description("USD Xform prim"),
-        ])
-        .with_workspace_compatibility(vec!["3D"])
-    }
-    
-    fn create_node(&self, position: Pos2) -> PluginNodeHandle {
-        PluginNodeHandle::new(Box::new(SimpleUSDNode::new("USD_Xform", "Xform", position)))
-    }
-}
-
-#[derive(Debug, Default)]
-pub struct USDTranslateFactory;
-
-impl NodeFactory for USDTranslateFactory {
-    fn metadata(&self) -> NodeMetadata {
-        NodeMetadata::new(
-            "USD_Translate",
-            "Translate",
-            NodeCategory::new(&["USD", "Transform"]),
-            "Translate USD prim"
-        )
-        .with_color(Color32::from_rgb(150, 120, 200))
-        .with_icon("📍")
-        .with_inputs(vec![
-            PortDefinition::required("Stage", DataType::String)
-                .with_description("USD stage"),
-        ])
-        .with_outputs(vec![
-            PortDefinition::required("Translate", DataType::String)
-                .with_description("USD translate transform"),
-        ])
-        .with_workspace_compatibility(vec!["3D"])
-    }
-    
-    fn create_node(&self, position: Pos2) -> PluginNodeHandle {
-        PluginNodeHandle::new(Box::new(SimpleUSDNode::new("USD_Translate", "Translate", position)))
-    }
-}
-
-#[derive(Debug, Default)]
-pub struct USDRotateFactory;
-
-impl NodeFactory for USDRotateFactory {
-    fn metadata(&self) -> NodeMetadata {
-        NodeMetadata::new(
-            "USD_Rotate",
-            "Rotate",
-            NodeCategory::new(&["USD", "Transform"]),
-            "Rotate USD prim"
-        )
-        .with_color(Color32::from_rgb(150, 120, 200))
-        .with_icon("🔁")
-        .with_inputs(vec![
-            PortDefinition::required("Stage", DataType::String)
-                .with_description("USD stage"),
-        ])
-        .with_outputs(vec![
-            PortDefinition::required("Rotate", DataType::String)
-                .with_description("USD rotate transform"),
-        ])
-        .with_workspace_compatibility(vec!["3D"])
-    }
-    
-    fn create_node(&self, position: Pos2) -> PluginNodeHandle {
-        PluginNodeHandle::new(Box::new(SimpleUSDNode::new("USD_Rotate", "Rotate", position)))
-    }
-}
-
-#[derive(Debug, Default)]
-pub struct USDScaleFactory;
-
-impl NodeFactory for USDScaleFactory {
-    fn metadata(&self) -> NodeMetadata {
-        NodeMetadata::new(
-            "USD_Scale",
-            "Scale",
-            NodeCategory::new(&["USD", "Transform"]),
-            "Scale USD prim"
-        )
-        .with_color(Color32::from_rgb(150, 120, 200))
-        .with_icon("📏")
-        .with_inputs(vec![
-            PortDefinition::required("Stage", DataType::String)
-                .with_description("USD stage"),
-        ])
-        .with_outputs(vec![
-            PortDefinition::required("Scale", DataType::String)
-                .with_description("USD scale transform"),
-        ])
-        .with_workspace_compatibility(vec!["3D"])
-    }
-    
-    fn create_node(&self, position: Pos2) -> PluginNodeHandle {
-        PluginNodeHandle::new(Box::new(SimpleUSDNode::new("USD_Scale", "Scale", position)))
-    }
-}
-
-// Lighting node factories
-#[derive(Debug, Default)]
-pub struct USDDistantLightFactory;
-
-impl NodeFactory for USDDistantLightFactory {
-    fn metadata(&self) -> NodeMetadata {
-        NodeMetadata::new(
-            "USD_DistantLight",
-            "Distant Light",
-            NodeCategory::new(&["USD", "Lighting"]),
-            "Create distant (directional) light"
-        )
-        .with_color(Color32::from_rgb(200, 200, 100))
-        .with_icon("☀️")
-        .with_inputs(vec![
-            PortDefinition::required("Stage", DataType::String)
-                .with_description("USD stage"),
-        ])
-        .with_outputs(vec![
-            PortDefinition::required("Light", DataType::String)
-                .with_description("USD distant light"),
-        ])
-        .with_workspace_compatibility(vec!["3D"])
-    }
-    
-    fn create_node(&self, position: Pos2) -> PluginNodeHandle {
-        PluginNodeHandle::new(Box::new(SimpleUSDNode::new("USD_DistantLight", "Distant Light", position)))
-    }
-}
-
-#[derive(Debug, Default)]
-pub struct USDSphereLightFactory;
-
-impl NodeFactory for USDSphereLightFactory {
-    fn metadata(&self) -> NodeMetadata {
-        NodeMetadata::new(
-            "USD_SphereLight",
-            "Sphere Light",
-            NodeCategory::new(&["USD", "Lighting"]),
-            "Create sphere area light"
-        )
-        .with_color(Color32::from_rgb(200, 200, 100))
-        .with_icon("💡")
-        .with_inputs(vec![
-            PortDefinition::required("Stage", DataType::String)
-                .with_description("USD stage"),
-        ])
-        .with_outputs(vec![
-            PortDefinition::required("Light", DataType::String)
-                .with_description("USD sphere light"),
-        ])
-        .with_workspace_compatibility(vec!["3D"])
-    }
-    
-    fn create_node(&self, position: Pos2) -> PluginNodeHandle {
-        PluginNodeHandle::new(Box::new(SimpleUSDNode::new("USD_SphereLight", "Sphere Light", position)))
-    }
-}
-
-#[derive(Debug, Default)]
-pub struct USDDomeLightFactory;
-
-impl NodeFactory for USDDomeLightFactory {
-    fn metadata(&self) -> NodeMetadata {
-        NodeMetadata::new(
-            "USD_DomeLight",
-            "Dome Light",
-            NodeCategory::new(&["USD", "Lighting"]),
-            "Create dome/environment light"
-        )
-        .with_color(Color32::from_rgb(200, 200, 100))
-        .with_icon("🌐")
-        .with_inputs(vec![
-            PortDefinition::required("Stage", DataType::String)
-                .with_description("USD stage"),
-        ])
-        .with_outputs(vec![
-            PortDefinition::required("Light", DataType::String)
-                .with_description("USD dome light"),
-        ])
-        .with_workspace_compatibility(vec!["3D"])
-    }
-    
-    fn create_node(&self, position: Pos2) -> PluginNodeHandle {
-        PluginNodeHandle::new(Box::new(SimpleUSDNode::new("USD_DomeLight", "Dome Light", position)))
-    }
-}
-
-// Shading node factories
-#[derive(Debug, Default)]
-pub struct USDMaterialFactory;
-
-impl NodeFactory for USDMaterialFactory {
-    fn metadata(&self) -> NodeMetadata {
-        NodeMetadata::new(
-            "USD_Material",
-            "Material",
-            NodeCategory::new(&["USD", "Shading"]),
-            "Create USD material"
-        )
-        .with_color(Color32::from_rgb(180, 100, 180))
-        .with_icon("🎨")
-        .with_inputs(vec![
-            PortDefinition::required("Stage", DataType::String)
-                .with_description("USD stage"),
-        ])
-        .with_outputs(vec![
-            PortDefinition::required("Material", DataType::String)
-                .with_description("USD material"),
-        ])
-        .with_workspace_compatibility(vec!["3D"])
-    }
-    
-    fn create_node(&self, position: Pos2) -> PluginNodeHandle {
-        PluginNodeHandle::new(Box::new(SimpleUSDNode::new("USD_Material", "Material", position)))
-    }
-}
-
-#[derive(Debug, Default)]
-pub struct USDShaderFactory;
-
-impl NodeFactory for USDShaderFactory {
-    fn metadata(&self) -> NodeMetadata {
-        NodeMetadata::new(
-            "USD_Shader",
-            "Shader",
-            NodeCategory::new(&["USD", "Shading"]),
-            "Create USD shader"
-        )
-        .with_color(Color32::from_rgb(180, 100, 180))
-        .with_icon("🔮")
-        .with_inputs(vec![
-            PortDefinition::required("Stage", DataType::String)
-                .with_description("USD stage"),
-        ])
-        .with_outputs(vec![
-            PortDefinition::required("Shader", DataType::String)
-                .with_description("USD shader"),
-        ])
-        .with_workspace_compatibility(vec!["3D"])
-    }
-    
-    fn create_node(&self, position: Pos2) -> PluginNodeHandle {
-        PluginNodeHandle::new(Box::new(SimpleUSDNode::new("USD_Shader", "Shader", position)))
-    }
-}
-
-#[derive(Debug, Default)]
-pub struct USDTextureFactory;
-
-impl NodeFactory for USDTextureFactory {
-    fn metadata(&self) -> NodeMetadata {
-        NodeMetadata::new(
-            "USD_Texture",
-            "Texture",
-            NodeCategory::new(&["USD", "Shading"]),
-            "Create USD texture"
-        )
-        .with_color(Color32::from_rgb(180, 100, 180))
-        .with_icon("🖼️")
-        .with_inputs(vec![
-            PortDefinition::required("Stage", DataType::String)
-                .with_description("USD stage"),
-        ])
-        .with_outputs(vec![
-            PortDefinition::required("Texture", DataType::String)
-                .with_description("USD texture"),
-        ])
-        .with_workspace_compatibility(vec!["3D"])
-    }
-    
-    fn create_node(&self, position: Pos2) -> PluginNodeHandle {
-        PluginNodeHandle::new(Box::new(SimpleUSDNode::new("USD_Texture", "Texture", position)))
-    }
-}
-
-// Stage Inspector factory
-#[derive(Debug, Default)]
-pub struct USDStageInspectorFactory;
-
-impl NodeFactory for USDStageInspectorFactory {
-    fn metadata(&self) -> NodeMetadata {
-        NodeMetadata::new(
-            "USD_StageInspector",
-            "Stage Inspector",
-            NodeCategory::new(&["USD", "Viewport"]),
-            "Inspect USD stage hierarchy"
-        )
-        .with_color(Color32::from_rgb(120, 120, 120))
-        .with_icon("🔍")
-        .with_inputs(vec![
-            PortDefinition::required("Stage", DataType::String)
-                .with_description("USD stage to inspect"),
-        ])
-        .with_outputs(vec![
-            PortDefinition::required("Info", DataType::String)
-                .with_description("Stage information"),
-        ])
-        .with_workspace_compatibility(vec!["3D"])
-    }
-    
-    fn create_node(&self, position: Pos2) -> PluginNodeHandle {
-        PluginNodeHandle::new(Box::new(SimpleUSDNode::new("USD_StageInspector", "Stage Inspector", position)))
-    }
-}
-
-// Simple generic USD node implementation
-#[derive(Debug)]
-pub struct SimpleUSDNode {
-    pub id: String,
-    pub position: Pos2,
-    pub node_type: String,
-    pub display_name: String,
-}
-
-impl SimpleUSDNode {
-    pub fn new(node_type: &str, display_name: &str, position: Pos2) -> Self {
-        Self {
-            id: uuid::Uuid::new_v4().to_string(),
-            position,
-            node_type: node_type.to_string(),
-            display_name: display_name.to_string(),
-        }
-    }
-}
-
-impl PluginNode for SimpleUSDNode {
-    fn id(&self) -> String { self.id.clone().into() }
-    fn position(&self) -> Pos2 { self.position }
-    fn set_position(&mut self, position: Pos2) { self.position = position; }
-    
-    fn get_parameter_ui(&self) -> ParameterUI {
-        let mut elements = Vec::<UIElement>::new();
-        elements.push(UIElement::Label(format!("🎭 {}", self.display_name).into()));
-        elements.push(UIElement::Separator);
-        elements.push(UIElement::Label(format!("Node Type: {}", self.node_type).into()));
-        elements.push(UIElement::Label("Parameters will be implemented soon...".into()));
-        
-        ParameterUI { elements }
-    }
-    
-    fn handle_ui_action(&mut self, _action: UIAction) -> Vec<ParameterChange> {
-        Vec::<ParameterChange>::new()
-    }
-    
-    fn get_parameter(&self, _name: &str) -> Option<NodeData> {
-        None
-    }
-    
-    fn set_parameter(&mut self, _name: &str, _value: NodeData) {
-        // TODO: Implement parameter setting
-    }
-    
-    fn process(&mut self, _inputs: &HashMap<String, NodeData>) -> HashMap<String, NodeData> {
-        HashMap::new()
-    }
+/// Factory of every node the plugin provides, each node type once
+///
+/// `register_nodes` registers exactly this list, in palette order: each module
+/// declared by `node_modules!` adds the nodes its `factories` lists.
+pub fn node_factories() -> Vec<Box<dyn NodeFactory>> {
+    node_module_factories()
 }
 
 // Export C functions using safe wrapper
@@ -678,4 +154,23 @@ pub extern "C" fn create_plugin() -> PluginHandle {
 pub extern "C" fn destroy_plugin(handle: PluginHandle) {
     // Plugin will be dropped when handle goes out of scope
     let _ = unsafe { handle.into_plugin() };
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn every_node_type_is_registered_once_with_help() {
+        let node_types: Vec<String> = node_factories().iter()
+            .map(|factory| factory.metadata().node_type.to_string())
+            .collect();
+        for (index, node_type) in node_types.iter().enumerate() {
+            assert!(!node_types[..index].contains(node_type), "{} registered twice", node_type);
+            assert!(ui::help::node_help(node_type).is_some(), "{} has no help", node_type);
+        }
+        for help in ui::help::all_help() {
+            assert!(node_types.iter().any(|node_type| node_type == help.node_type), "{} is not registered", help.node_type);
+        }
+    }
+}
@@ -0,0 +1,239 @@
+//! USD Light Rig node - drops a preset key/fill/rim, studio or outdoor light rig into the stage
+
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::core::light_rig::{LightRigPreset, USDLightRig};
+use crate::core::usd_engine::with_usd_engine;
+use crate::ui::choice::{choice_buttons, parse_choice};
+use crate::ui::help::NodeHelp;
+use crate::ui::palette::status_label;
+use crate::ui::keyboard::{focus_marked, handle_key};
+use crate::registry::NodeTypeFactory;
+
+/// Help for the Light Rig node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_LightRig",
+    summary: "Add a preset three-point, studio or outdoor light rig with overall intensity and rotation",
+    details: "Adds a preset rig under Rig Path. Intensity scales every light and Rotation turns the whole rig around the vertical axis; rigs with a dome light take an optional HDR.",
+    ports: &[
+        ("Stage", "stage_0"),
+        ("Rig", "/World/LightRig"),
+        ("Lights", "/World/LightRig/Key\n/World/LightRig/Fill\n/World/LightRig/Rim"),
+    ],
+    samples: &[],
+};
+
+/// Factory of the Light Rig node, for `crate::node_factories`
+pub fn factories() -> Vec<Box<dyn NodeFactory>> {
+    vec![NodeTypeFactory::boxed(metadata, |position| PluginNodeHandle::new(Box::new(USDLightRigNode::new(position))))]
+}
+
+/// Metadata of the Light Rig node
+fn metadata() -> NodeMetadata {
+    NodeMetadata::new(
+        "USD_LightRig",
+        "Light Rig",
+        NodeCategory::new(&["USD", "Lighting"]),
+        HELP.summary
+    )
+    .with_color(Color32::from_rgb(200, 200, 100))
+    .with_icon("🎬")
+    .with_inputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("USD stage to add the rig to"),
+    ])
+    .with_outputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("Stage with the light rig"),
+        PortDefinition::optional("Rig", DataType::String)
+            .with_description("Rig Xform path"),
+        PortDefinition::optional("Lights", DataType::String)
+            .with_description("Rig light paths, one per line"),
+    ])
+    .with_panel_type(PanelType::Parameter)
+    .with_workspace_compatibility(vec!["3D"])
+}
+
+/// USD Light Rig node
+///
+/// Authors the preset's lights under the rig Xform. Intensity scales every
+/// light and rotation turns the whole rig about Y, so the lighting can be
+/// adjusted without touching individual lights.
+pub struct USDLightRigNode {
+    id: String,
+    position: Pos2,
+    rig: USDLightRig,
+    stage_ref: String,
+    /// Light paths once authored
+    lights: Vec<String>,
+    dirty: bool,
+    status: String,
+}
+
+impl USDLightRigNode {
+    pub fn new(position: Pos2) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            position,
+            rig: USDLightRig {
+                root_path: "/Lights/Rig".to_string(),
+                preset: LightRigPreset::ThreePoint,
+                intensity: 1.0,
+                rotation: 0.0,
+                dome_texture: String::new(),
+            },
+            stage_ref: String::new(),
+            lights: Vec::new(),
+            dirty: true,
+            status: "No USD stage connected".to_string(),
+        }
+    }
+    
+    fn author(&mut self) -> Result<(), String> {
+        let (stage_ref, rig) = (self.stage_ref.clone(), self.rig.clone());
+        self.lights = with_usd_engine(|engine| {
+            let stage = engine.resolve_stage(&stage_ref)?;
+            engine.set_light_rig(&stage.identifier, &rig)
+        })?;
+        self.status = format!("{}: {} {} lights", rig.root_path, self.lights.len(), rig.preset.name());
+        Ok(())
+    }
+}
+
+impl PluginNode for USDLightRigNode {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+    
+    fn position(&self) -> Pos2 {
+        self.position
+    }
+    
+    fn set_position(&mut self, position: Pos2) {
+        self.position = position;
+    }
+    
+    fn get_parameter_ui(&self) -> ParameterUI {
+        let mut elements = Vec::new();
+        
+        elements.push(UIElement::Heading("USD Light Rig".to_string()));
+        elements.push(UIElement::Separator);
+        
+        elements.extend(choice_buttons("Preset", "preset", &LightRigPreset::ALL.map(|preset| preset.name()), self.rig.preset.name()));
+        elements.push(UIElement::TextEdit {
+            label: "Rig Path".to_string(),
+            value: self.rig.root_path.clone(),
+            parameter_name: "root_path".to_string(),
+        });
+        elements.push(UIElement::Slider {
+            label: "Intensity".to_string(),
+            value: self.rig.intensity as f32,
+            min: 0.0,
+            max: 4.0,
+            parameter_name: "intensity".to_string(),
+        });
+        elements.push(UIElement::Slider {
+            label: "Rotation".to_string(),
+            value: self.rig.rotation as f32,
+            min: -180.0,
+            max: 180.0,
+            parameter_name: "rotation".to_string(),
+        });
+        if self.rig.preset.lights().iter().any(|light| light.light_type == "DomeLight") {
+            elements.push(UIElement::TextEdit {
+                label: "Dome HDR".to_string(),
+                value: self.rig.dome_texture.clone(),
+                parameter_name: "dome_texture".to_string(),
+            });
+        }
+        
+        elements.push(UIElement::Separator);
+        elements.push(status_label(&self.status));
+        elements.extend(HELP.section());
+        
+        ParameterUI { elements: focus_marked(&self.id, elements) }
+    }
+    
+    fn handle_ui_action(&mut self, action: UIAction) -> Vec<ParameterChange> {
+        if let Some(changes) = handle_key(self, &[], &action) {
+            return changes;
+        }
+        
+        let mut changes = Vec::new();
+        
+        let (parameter, value) = match action {
+            UIAction::ParameterChanged { parameter, value } => (parameter, value),
+            UIAction::ButtonClicked { action } => {
+                let Some(preset) = parse_choice(&action, "preset") else {
+                    return changes;
+                };
+                ("preset".to_string(), NodeData::String(preset.to_string()))
+            }
+        };
+        if matches!(parameter.as_str(), "preset" | "root_path" | "intensity" | "rotation" | "dome_texture") {
+            self.set_parameter(&parameter, value);
+            if let Some(value) = self.get_parameter(&parameter) {
+                changes.push(ParameterChange { parameter, value });
+            }
+        }
+        
+        changes
+    }
+    
+    fn get_parameter(&self, name: &str) -> Option<NodeData> {
+        match name {
+            "preset" => Some(NodeData::String(self.rig.preset.name().to_string())),
+            "root_path" => Some(NodeData::String(self.rig.root_path.clone())),
+            "intensity" => Some(NodeData::Float(self.rig.intensity as f32)),
+            "rotation" => Some(NodeData::Float(self.rig.rotation as f32)),
+            "dome_texture" => Some(NodeData::String(self.rig.dome_texture.clone())),
+            _ => None,
+        }
+    }
+    
+    fn set_parameter(&mut self, name: &str, value: NodeData) {
+        match (name, value.as_string(), value.as_float()) {
+            ("preset", Some(text), _) => match LightRigPreset::from_name(text) {
+                Some(preset) => self.rig.preset = preset,
+                None => return,
+            },
+            ("root_path", Some(text), _) => self.rig.root_path = text.trim().trim_end_matches('/').to_string(),
+            ("dome_texture", Some(text), _) => self.rig.dome_texture = text.trim().to_string(),
+            ("intensity", _, Some(intensity)) => self.rig.intensity = intensity.max(0.0) as f64,
+            ("rotation", _, Some(rotation)) => self.rig.rotation = rotation as f64,
+            _ => return,
+        }
+        self.dirty = true;
+    }
+    
+    fn process(&mut self, inputs: &HashMap<String, NodeData>) -> HashMap<String, NodeData> {
+        let mut outputs = HashMap::new();
+        
+        let Some(stage_ref) = inputs.get("Stage").and_then(|data| data.as_string()) else {
+            self.stage_ref.clear();
+            self.lights.clear();
+            self.status = "No USD stage connected".to_string();
+            return outputs;
+        };
+        
+        if stage_ref != self.stage_ref {
+            self.stage_ref = stage_ref.to_string();
+            self.dirty = true;
+        }
+        
+        if self.dirty {
+            self.dirty = false;
+            if let Err(e) = self.author() {
+                self.status = format!("⚠ {}", e);
+                self.lights.clear();
+            }
+        }
+        
+        outputs.insert("Stage".to_string(), NodeData::String(self.stage_ref.clone()));
+        if !self.lights.is_empty() {
+            outputs.insert("Rig".to_string(), NodeData::String(self.rig.root_path.clone()));
+            outputs.insert("Lights".to_string(), NodeData::String(self.lights.join("\n")));
+        }
+        outputs
+    }
+}
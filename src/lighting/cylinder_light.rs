@@ -1,8 +1,95 @@
-//! USD Cylinder Light node - stub implementation
+//! USD Cylinder Light node
 
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::core::usd_value::UsdValue;
+use crate::modular::{child_prim_inputs, child_prim_path, define_prim, float_value, input_stage, parse_color, text_value, ModularNode, ParameterSpec};
+use crate::ui::help::{NodeHelp, USD_LUX};
+
+/// Help for the Cylinder Light node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_CylinderLight",
+    summary: "Creates a USD tube light",
+    details: "Defines a UsdLux CylinderLight, a tube along its X axis like a fluorescent bulb. With Live Edit, its intensity, color, length and radius are set on the light as you edit them and follow changes made to it elsewhere.",
+    ports: &[
+        ("Stage", "stage_0"),
+        ("Name", "Tube"),
+        ("Light Path", "/World/Tube"),
+    ],
+    samples: &[USD_LUX],
+};
+
+/// USD Cylinder Light node with parameter controls
+#[derive(Default)]
 pub struct USDCylinderLightNode;
+
+/// Core logic for USD cylinder light creation
 pub struct USDCylinderLightLogic;
 
-impl Default for USDCylinderLightNode {
-    fn default() -> Self { Self }
-}
\ No newline at end of file
+impl USDCylinderLightLogic {
+    /// Execute the cylinder light creation operation
+    pub fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        let stage_id = input_stage(inputs)?;
+        let light_path = child_prim_path(inputs, parameters, "CylinderLight");
+        let intensity = float_value(inputs, "Intensity", parameters, "intensity", 1.0);
+        let color = text_value(inputs, "Color", parameters, "color")
+            .and_then(|color| parse_color(&color))
+            .unwrap_or([1.0; 3]);
+        let length = float_value(inputs, "Length", parameters, "length", 1.0);
+        let radius = float_value(inputs, "Radius", parameters, "radius", 0.05);
+        
+        // The tube lies along the light's X axis
+        let light = define_prim(&stage_id, &light_path, "CylinderLight", vec![
+            ("inputs:intensity", UsdValue::Float(intensity)),
+            ("inputs:color", UsdValue::Color3(color.map(f64::from))),
+            ("inputs:length", UsdValue::Float(length)),
+            ("inputs:radius", UsdValue::Float(radius)),
+        ])?;
+        println!("✓ Created USD cylinder light: {} (length: {}, intensity: {})", light.path, length, intensity);
+        
+        Ok(HashMap::from([
+            ("Stage".to_string(), inputs["Stage"].clone()),
+            ("Light Path".to_string(), NodeData::String(light.path)),
+        ]))
+    }
+}
+
+impl ModularNode for USDCylinderLightNode {
+    const NAME: &'static str = "USD Cylinder Light";
+    const HELP: &'static NodeHelp = &HELP;
+    
+    fn metadata() -> NodeMetadata {
+        NodeMetadata::new(
+            "USD_CylinderLight",
+            "Cylinder Light",
+            NodeCategory::new(&["USD", "Lighting"]),
+            HELP.summary
+        )
+        .with_color(Color32::from_rgb(200, 200, 100))
+        .with_icon("━")
+        .with_inputs(child_prim_inputs("Light"))
+        .with_outputs(vec![
+            PortDefinition::required("Stage", DataType::String)
+                .with_description("Stage with the light"),
+            PortDefinition::required("Light Path", DataType::String)
+                .with_description("Created light path"),
+        ])
+        .with_panel_type(PanelType::Parameter)
+        .with_workspace_compatibility(vec!["3D"])
+    }
+    
+    fn parameters() -> Vec<ParameterSpec> {
+        vec![
+            ParameterSpec::text("parent_path", "Parent Path", "/World"),
+            ParameterSpec::text("name", "Name", "CylinderLight"),
+            ParameterSpec::float("intensity", "Intensity", 1.0, 0.0, 100.0).with_attribute("inputs:intensity", "float"),
+            ParameterSpec::color("color", "Color", [1.0, 1.0, 1.0]).with_attribute("inputs:color", "color3f"),
+            ParameterSpec::float("length", "Length", 1.0, 0.01, 100.0).with_attribute("inputs:length", "float"),
+            ParameterSpec::float("radius", "Radius", 0.05, 0.001, 10.0).with_attribute("inputs:radius", "float"),
+        ]
+    }
+    
+    fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        USDCylinderLightLogic::execute(inputs, parameters)
+    }
+}
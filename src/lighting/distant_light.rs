@@ -1,8 +1,91 @@
-//! USD Distant Light node - stub implementation
+//! USD Distant Light node
 
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::core::usd_value::UsdValue;
+use crate::modular::{child_prim_inputs, child_prim_path, define_prim, float_value, input_stage, parse_color, text_value, ModularNode, ParameterSpec};
+use crate::ui::help::{NodeHelp, USD_LUX};
+
+/// Help for the Distant Light node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_DistantLight",
+    summary: "Creates a USD distant (directional) light",
+    details: "Defines a UsdLux DistantLight: parallel rays like sunlight, aimed down its -Z axis. Angle is the angular diameter of the source, widening the soft edge of its shadows. With Live Edit, Intensity, Color and Angle are set on the light as you edit them and follow changes made to it elsewhere.",
+    ports: &[
+        ("Stage", "stage_0"),
+        ("Name", "Sun"),
+        ("Light Path", "/World/Sun"),
+    ],
+    samples: &[USD_LUX],
+};
+
+/// USD Distant Light node with parameter controls
+#[derive(Default)]
 pub struct USDDistantLightNode;
+
+/// Core logic for USD distant light creation
 pub struct USDDistantLightLogic;
 
-impl Default for USDDistantLightNode {
-    fn default() -> Self { Self }
-}
\ No newline at end of file
+impl USDDistantLightLogic {
+    /// Execute the distant light creation operation
+    pub fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        let stage_id = input_stage(inputs)?;
+        let light_path = child_prim_path(inputs, parameters, "DistantLight");
+        let intensity = float_value(inputs, "Intensity", parameters, "intensity", 1.0);
+        let color = text_value(inputs, "Color", parameters, "color")
+            .and_then(|color| parse_color(&color))
+            .unwrap_or([1.0; 3]);
+        let angle = float_value(inputs, "Angle", parameters, "angle", 0.53);
+        
+        let light = define_prim(&stage_id, &light_path, "DistantLight", vec![
+            ("inputs:intensity", UsdValue::Float(intensity)),
+            ("inputs:color", UsdValue::Color3(color.map(f64::from))),
+            ("inputs:angle", UsdValue::Float(angle)),
+        ])?;
+        println!("✓ Created USD distant light: {} (angle: {}, intensity: {})", light.path, angle, intensity);
+        
+        Ok(HashMap::from([
+            ("Stage".to_string(), inputs["Stage"].clone()),
+            ("Light Path".to_string(), NodeData::String(light.path)),
+        ]))
+    }
+}
+
+impl ModularNode for USDDistantLightNode {
+    const NAME: &'static str = "USD Distant Light";
+    const HELP: &'static NodeHelp = &HELP;
+    
+    fn metadata() -> NodeMetadata {
+        NodeMetadata::new(
+            "USD_DistantLight",
+            "Distant Light",
+            NodeCategory::new(&["USD", "Lighting"]),
+            HELP.summary
+        )
+        .with_color(Color32::from_rgb(200, 200, 100))
+        .with_icon("☀️")
+        .with_inputs(child_prim_inputs("Light"))
+        .with_outputs(vec![
+            PortDefinition::required("Stage", DataType::String)
+                .with_description("Stage with the light"),
+            PortDefinition::required("Light Path", DataType::String)
+                .with_description("Created light path"),
+        ])
+        .with_panel_type(PanelType::Parameter)
+        .with_workspace_compatibility(vec!["3D"])
+    }
+    
+    fn parameters() -> Vec<ParameterSpec> {
+        vec![
+            ParameterSpec::text("parent_path", "Parent Path", "/World"),
+            ParameterSpec::text("name", "Name", "DistantLight"),
+            ParameterSpec::float("intensity", "Intensity", 1.0, 0.0, 100.0).with_attribute("inputs:intensity", "float"),
+            ParameterSpec::color("color", "Color", [1.0, 1.0, 1.0]).with_attribute("inputs:color", "color3f"),
+            ParameterSpec::float("angle", "Angle", 0.53, 0.0, 180.0).with_attribute("inputs:angle", "float"),
+        ]
+    }
+    
+    fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        USDDistantLightLogic::execute(inputs, parameters)
+    }
+}
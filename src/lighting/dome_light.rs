@@ -1,8 +1,101 @@
-//! USD Dome Light node - stub implementation
+//! USD Dome Light node
 
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::core::usd_value::UsdValue;
+use crate::modular::{child_prim_inputs, child_prim_path, define_prim, float_value, input_stage, parse_color, text_value, ModularNode, ParameterSpec};
+use crate::ui::help::{NodeHelp, USD_LUX};
+
+/// Help for the Dome Light node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_DomeLight",
+    summary: "Creates a USD dome (environment) light",
+    details: "Defines a UsdLux DomeLight lighting the scene from every direction, from a latlong HDR texture when Texture is set. With Live Edit, Intensity, Color and Texture are set on the light as you edit them and follow changes made to it elsewhere.",
+    ports: &[
+        ("Stage", "stage_0"),
+        ("Name", "Environment"),
+        ("Texture", "textures/studio.exr"),
+        ("Light Path", "/World/Environment"),
+    ],
+    samples: &[USD_LUX],
+};
+
+/// USD Dome Light node with parameter controls
+#[derive(Default)]
 pub struct USDDomeLightNode;
+
+/// Core logic for USD dome light creation
 pub struct USDDomeLightLogic;
 
-impl Default for USDDomeLightNode {
-    fn default() -> Self { Self }
-}
\ No newline at end of file
+impl USDDomeLightLogic {
+    /// Execute the dome light creation operation
+    pub fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        let stage_id = input_stage(inputs)?;
+        let light_path = child_prim_path(inputs, parameters, "DomeLight");
+        let intensity = float_value(inputs, "Intensity", parameters, "intensity", 1.0);
+        let color = text_value(inputs, "Color", parameters, "color")
+            .and_then(|color| parse_color(&color))
+            .unwrap_or([1.0; 3]);
+        let texture = text_value(inputs, "Texture", parameters, "texture");
+        
+        let mut attributes = vec![
+            ("inputs:intensity", UsdValue::Float(intensity)),
+            ("inputs:color", UsdValue::Color3(color.map(f64::from))),
+        ];
+        // Without a texture the dome lights evenly with its color
+        if let Some(texture) = texture {
+            attributes.push(("inputs:texture:file", UsdValue::Asset(texture)));
+            attributes.push(("inputs:texture:format", UsdValue::Token("latlong".to_string())));
+        }
+        
+        let light = define_prim(&stage_id, &light_path, "DomeLight", attributes)?;
+        println!("✓ Created USD dome light: {} (intensity: {})", light.path, intensity);
+        
+        Ok(HashMap::from([
+            ("Stage".to_string(), inputs["Stage"].clone()),
+            ("Light Path".to_string(), NodeData::String(light.path)),
+        ]))
+    }
+}
+
+impl ModularNode for USDDomeLightNode {
+    const NAME: &'static str = "USD Dome Light";
+    const HELP: &'static NodeHelp = &HELP;
+    
+    fn metadata() -> NodeMetadata {
+        NodeMetadata::new(
+            "USD_DomeLight",
+            "Dome Light",
+            NodeCategory::new(&["USD", "Lighting"]),
+            HELP.summary
+        )
+        .with_color(Color32::from_rgb(200, 200, 100))
+        .with_icon("🌐")
+        .with_inputs(child_prim_inputs("Light").into_iter().chain([
+            PortDefinition::optional("Texture", DataType::String)
+                .with_description("Latlong environment image, overriding the parameter"),
+        ]).collect())
+        .with_outputs(vec![
+            PortDefinition::required("Stage", DataType::String)
+                .with_description("Stage with the light"),
+            PortDefinition::required("Light Path", DataType::String)
+                .with_description("Created light path"),
+        ])
+        .with_panel_type(PanelType::Parameter)
+        .with_workspace_compatibility(vec!["3D"])
+    }
+    
+    fn parameters() -> Vec<ParameterSpec> {
+        vec![
+            ParameterSpec::text("parent_path", "Parent Path", "/World"),
+            ParameterSpec::text("name", "Name", "DomeLight"),
+            ParameterSpec::float("intensity", "Intensity", 1.0, 0.0, 100.0).with_attribute("inputs:intensity", "float"),
+            ParameterSpec::color("color", "Color", [1.0, 1.0, 1.0]).with_attribute("inputs:color", "color3f"),
+            ParameterSpec::text("texture", "Texture", "").with_attribute("inputs:texture:file", "asset"),
+        ]
+    }
+    
+    fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        USDDomeLightLogic::execute(inputs, parameters)
+    }
+}
@@ -1,15 +1,15 @@
 //! USD Lighting nodes
+//!
+//! Each node is a `ModularNode`; `modular_nodes!` declares its module and
+//! lists it for registration with the plugin.
 
-pub mod distant_light;
-pub mod rect_light;
-pub mod sphere_light;
-pub mod cylinder_light;
-pub mod dome_light;
-pub mod disk_light;
+use crate::modular::modular_nodes;
 
-pub use distant_light::{USDDistantLightNode, USDDistantLightLogic};
-pub use rect_light::{USDRectLightNode, USDRectLightLogic};
-pub use sphere_light::{USDSphereLightNode, USDSphereLightLogic};
-pub use cylinder_light::{USDCylinderLightNode, USDCylinderLightLogic};
-pub use dome_light::{USDDomeLightNode, USDDomeLightLogic};
-pub use disk_light::{USDDiskLightNode, USDDiskLightLogic};
\ No newline at end of file
+modular_nodes! {
+    distant_light => USDDistantLightNode,
+    sphere_light => USDSphereLightNode,
+    dome_light => USDDomeLightNode,
+    rect_light => USDRectLightNode,
+    disk_light => USDDiskLightNode,
+    cylinder_light => USDCylinderLightNode,
+}
@@ -1,105 +1,45 @@
 //! USD Rect Light node functional operations
 
-use crate::nodes::interface::NodeData;
-use crate::nodes::three_d::usd::usd_engine::with_usd_engine;
+use nodle_plugin_sdk::NodeData;
+use std::collections::HashMap;
+use crate::core::usd_engine::with_usd_engine;
+use crate::core::usd_value::UsdValue;
+use crate::modular::{child_prim_path, flag_value, float_value, input_stage, parse_color, text_value};
 
 /// Core logic for USD rect light creation
 pub struct USDRectLightLogic;
 
 impl USDRectLightLogic {
     /// Execute the rect light creation operation
-    pub fn execute(inputs: &std::collections::HashMap<String, NodeData>, parameters: &std::collections::HashMap<String, NodeData>) -> std::collections::HashMap<String, NodeData> {
-        let mut outputs = std::collections::HashMap::new();
-        
-        // Get stage reference
-        let stage_id = match inputs.get("Stage") {
-            Some(NodeData::String(s)) => s.clone(),
-            _ => {
-                outputs.insert("Light Path".to_string(), NodeData::String("".to_string()));
-                outputs.insert("Light".to_string(), NodeData::None);
-                return outputs;
-            }
-        };
-        
-        // Get parent path
-        let parent_path = match inputs.get("Parent Path") {
-            Some(NodeData::String(s)) => s.clone(),
-            _ => "/World/Lights".to_string(),
-        };
-        
-        // Get name or auto-generate
-        let name = match inputs.get("Name") {
-            Some(NodeData::String(s)) if !s.is_empty() => s.clone(),
-            _ => format!("rectLight_{}", uuid::Uuid::new_v4().to_string().split('-').next().unwrap()),
-        };
-        
-        // Get light parameters
-        let intensity = match parameters.get("intensity") {
-            Some(NodeData::Float(f)) => *f,
-            _ => 1.0,
-        };
-        
-        let color = match parameters.get("color") {
-            Some(NodeData::Color(color)) => [color[0], color[1], color[2]], // Use RGB components
-            _ => [1.0, 1.0, 1.0],
-        };
-        
-        let temperature = match parameters.get("temperature") {
-            Some(NodeData::Float(f)) => *f,
-            _ => 6500.0,
-        };
-        
-        let width = match parameters.get("width") {
-            Some(NodeData::Float(f)) => *f,
-            _ => 1.0,
-        };
-        
-        let height = match parameters.get("height") {
-            Some(NodeData::Float(f)) => *f,
-            _ => 1.0,
-        };
-        
-        let enabled = match parameters.get("enabled") {
-            Some(NodeData::Boolean(b)) => *b,
-            _ => true,
-        };
-        
-        // Construct light path
-        let light_path = if parent_path.ends_with('/') {
-            format!("{}{}", parent_path, name)
-        } else {
-            format!("{}/{}", parent_path, name)
-        };
-        
-        // Create the rect light
-        with_usd_engine(|engine| {
-            match engine.create_rect_light(&stage_id, &light_path, intensity as f64, width as f64, height as f64) {
-                Ok(light_prim) => {
-                    // Set light attributes
-                    let _ = engine.set_attribute(&stage_id, &light_path, "intensity", &intensity.to_string());
-                    let _ = engine.set_attribute(&stage_id, &light_path, "color", 
-                        &format!("({}, {}, {})", color[0], color[1], color[2]));
-                    let _ = engine.set_attribute(&stage_id, &light_path, "colorTemperature", &temperature.to_string());
-                    let _ = engine.set_attribute(&stage_id, &light_path, "enableColorTemperature", "true");
-                    
-                    if !enabled {
-                        let _ = engine.set_attribute(&stage_id, &light_path, "visibility", "invisible");
-                    }
-                    
-                    outputs.insert("Light Path".to_string(), NodeData::String(light_prim.path.clone()));
-                    outputs.insert("Light".to_string(), NodeData::String(light_prim.path));
-                    
-                    println!("✓ Created USD rect light: {} ({}x{}, intensity: {})", 
-                        light_path, width, height, intensity);
-                }
-                Err(e) => {
-                    eprintln!("✗ Failed to create USD rect light: {}", e);
-                    outputs.insert("Light Path".to_string(), NodeData::String("".to_string()));
-                    outputs.insert("Light".to_string(), NodeData::None);
-                }
-            }
-        });
-        
-        outputs
+    pub fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        let stage_id = input_stage(inputs)?;
+        let light_path = child_prim_path(inputs, parameters, "RectLight");
+        let intensity = float_value(inputs, "Intensity", parameters, "intensity", 1.0);
+        let color = text_value(inputs, "Color", parameters, "color")
+            .and_then(|color| parse_color(&color))
+            .unwrap_or([1.0; 3]);
+        let use_temperature = flag_value(parameters, "use_temperature", false);
+        let temperature = float_value(inputs, "Temperature", parameters, "temperature", 6500.0);
+        let width = float_value(inputs, "Width", parameters, "width", 1.0);
+        let height = float_value(inputs, "Height", parameters, "height", 1.0);
+        let enabled = flag_value(parameters, "enabled", true);
+        
+        let light = with_usd_engine(|engine| -> Result<_, String> {
+            let light = engine.create_rect_light(&stage_id, &light_path, intensity as f64, width as f64, height as f64)?;
+            let path = light.path.as_str();
+            engine.set_attribute(&stage_id, path, "inputs:intensity", UsdValue::Float(intensity))?;
+            engine.set_attribute(&stage_id, path, "inputs:color", UsdValue::Color3(color.map(f64::from)))?;
+            engine.set_attribute(&stage_id, path, "inputs:enableColorTemperature", UsdValue::Bool(use_temperature))?;
+            engine.set_attribute(&stage_id, path, "inputs:colorTemperature", UsdValue::Float(temperature))?;
+            let visibility = if enabled { "inherited" } else { "invisible" };
+            engine.set_prim_visibility(&stage_id, path, visibility)?;
+            Ok(light)
+        })?;
+        println!("✓ Created USD rect light: {} ({}x{}, intensity: {})", light.path, width, height, intensity);
+        
+        Ok(HashMap::from([
+            ("Stage".to_string(), inputs["Stage"].clone()),
+            ("Light Path".to_string(), NodeData::String(light.path)),
+        ]))
     }
-}
\ No newline at end of file
+}
@@ -6,35 +6,54 @@ pub mod parameters;
 pub use logic::USDRectLightLogic;
 pub use parameters::USDRectLightNode;
 
-use crate::nodes::NodeFactory;
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::modular::{child_prim_inputs, ModularNode, ParameterSpec};
+use crate::ui::help::{NodeHelp, USD_LUX};
 
-impl NodeFactory for parameters::USDRectLightNode {
-    fn metadata() -> crate::nodes::NodeMetadata {
-        crate::nodes::NodeMetadata::new(
-            "USD_Lighting_RectLight",
-            "USD Rect Light",
-            crate::nodes::NodeCategory::new(&["3D", "USD", "Lighting"]),
-            "Creates a USD rectangular area light"
+/// Help for the Rect Light node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_RectLight",
+    summary: "Creates a USD rectangular area light",
+    details: "Defines a UsdLux RectLight of the given width and height, emitting down its -Z axis. Color can come from a temperature in Kelvin instead. With Live Edit, intensity, color and temperature edits are set on the light directly, and changes to them elsewhere show in the panel.",
+    ports: &[
+        ("Stage", "stage_0"),
+        ("Parent Path", "/World/Lights"),
+        ("Name", "Softbox"),
+        ("Light Path", "/World/Lights/Softbox"),
+    ],
+    samples: &[USD_LUX],
+};
+
+impl ModularNode for parameters::USDRectLightNode {
+    const NAME: &'static str = "USD Rect Light";
+    const HELP: &'static NodeHelp = &HELP;
+    
+    fn metadata() -> NodeMetadata {
+        NodeMetadata::new(
+            "USD_RectLight",
+            "Rect Light",
+            NodeCategory::new(&["USD", "Lighting"]),
+            HELP.summary
         )
-        .with_color(egui::Color32::from_rgb(255, 200, 100))
-        .with_icon("💡")
-        .with_inputs(vec![
-            crate::nodes::PortDefinition::required("Stage", crate::nodes::DataType::Any)
-                .with_description("USD Stage reference"),
-            crate::nodes::PortDefinition::required("Parent Path", crate::nodes::DataType::String)
-                .with_description("Parent prim path"),
-            crate::nodes::PortDefinition::optional("Name", crate::nodes::DataType::String)
-                .with_description("Light name (auto-generated if empty)"),
-        ])
+        .with_color(Color32::from_rgb(200, 200, 100))
+        .with_icon("▭")
+        .with_inputs(child_prim_inputs("Light"))
         .with_outputs(vec![
-            crate::nodes::PortDefinition::required("Light Path", crate::nodes::DataType::String)
+            PortDefinition::required("Stage", DataType::String)
+                .with_description("Stage with the light"),
+            PortDefinition::required("Light Path", DataType::String)
                 .with_description("Created light path"),
-            crate::nodes::PortDefinition::required("Light", crate::nodes::DataType::Any)
-                .with_description("USD Light reference"),
         ])
-        .with_tags(vec!["usd", "lighting", "rect", "area"])
-        .with_processing_cost(crate::nodes::factory::ProcessingCost::Low)
-        .with_workspace_compatibility(vec!["3D", "USD"])
-        .with_panel_type(crate::nodes::interface::PanelType::Parameter)
+        .with_panel_type(PanelType::Parameter)
+        .with_workspace_compatibility(vec!["3D"])
+    }
+    
+    fn parameters() -> Vec<ParameterSpec> {
+        parameters::USDRectLightNode::parameters()
+    }
+    
+    fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        USDRectLightLogic::execute(inputs, parameters)
     }
-}
\ No newline at end of file
+}
@@ -1,8 +1,94 @@
-//! USD Sphere Light node - stub implementation
+//! USD Sphere Light node
 
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::core::usd_value::UsdValue;
+use crate::modular::{child_prim_inputs, child_prim_path, define_prim, flag_value, float_value, input_stage, parse_color, text_value, ModularNode, ParameterSpec};
+use crate::ui::help::{NodeHelp, USD_LUX};
+
+/// Help for the Sphere Light node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_SphereLight",
+    summary: "Creates a USD sphere area light",
+    details: "Defines a UsdLux SphereLight emitting from a sphere of the given radius; Treat As Point makes it a point light. With Live Edit, Intensity, Color and Radius are set on the light as you edit them and follow changes made to it elsewhere.",
+    ports: &[
+        ("Stage", "stage_0"),
+        ("Name", "Bulb"),
+        ("Light Path", "/World/Bulb"),
+    ],
+    samples: &[USD_LUX],
+};
+
+/// USD Sphere Light node with parameter controls
+#[derive(Default)]
 pub struct USDSphereLightNode;
+
+/// Core logic for USD sphere light creation
 pub struct USDSphereLightLogic;
 
-impl Default for USDSphereLightNode {
-    fn default() -> Self { Self }
-}
\ No newline at end of file
+impl USDSphereLightLogic {
+    /// Execute the sphere light creation operation
+    pub fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        let stage_id = input_stage(inputs)?;
+        let light_path = child_prim_path(inputs, parameters, "SphereLight");
+        let intensity = float_value(inputs, "Intensity", parameters, "intensity", 1.0);
+        let color = text_value(inputs, "Color", parameters, "color")
+            .and_then(|color| parse_color(&color))
+            .unwrap_or([1.0; 3]);
+        let radius = float_value(inputs, "Radius", parameters, "radius", 0.5);
+        let treat_as_point = flag_value(parameters, "treat_as_point", false);
+        
+        let light = define_prim(&stage_id, &light_path, "SphereLight", vec![
+            ("inputs:intensity", UsdValue::Float(intensity)),
+            ("inputs:color", UsdValue::Color3(color.map(f64::from))),
+            ("inputs:radius", UsdValue::Float(radius)),
+            ("treatAsPoint", UsdValue::Bool(treat_as_point)),
+        ])?;
+        println!("✓ Created USD sphere light: {} (radius: {}, intensity: {})", light.path, radius, intensity);
+        
+        Ok(HashMap::from([
+            ("Stage".to_string(), inputs["Stage"].clone()),
+            ("Light Path".to_string(), NodeData::String(light.path)),
+        ]))
+    }
+}
+
+impl ModularNode for USDSphereLightNode {
+    const NAME: &'static str = "USD Sphere Light";
+    const HELP: &'static NodeHelp = &HELP;
+    
+    fn metadata() -> NodeMetadata {
+        NodeMetadata::new(
+            "USD_SphereLight",
+            "Sphere Light",
+            NodeCategory::new(&["USD", "Lighting"]),
+            HELP.summary
+        )
+        .with_color(Color32::from_rgb(200, 200, 100))
+        .with_icon("💡")
+        .with_inputs(child_prim_inputs("Light"))
+        .with_outputs(vec![
+            PortDefinition::required("Stage", DataType::String)
+                .with_description("Stage with the light"),
+            PortDefinition::required("Light Path", DataType::String)
+                .with_description("Created light path"),
+        ])
+        .with_panel_type(PanelType::Parameter)
+        .with_workspace_compatibility(vec!["3D"])
+    }
+    
+    fn parameters() -> Vec<ParameterSpec> {
+        vec![
+            ParameterSpec::text("parent_path", "Parent Path", "/World"),
+            ParameterSpec::text("name", "Name", "SphereLight"),
+            ParameterSpec::float("intensity", "Intensity", 1.0, 0.0, 100.0).with_attribute("inputs:intensity", "float"),
+            ParameterSpec::color("color", "Color", [1.0, 1.0, 1.0]).with_attribute("inputs:color", "color3f"),
+            ParameterSpec::float("radius", "Radius", 0.5, 0.0, 100.0).with_attribute("inputs:radius", "float"),
+            ParameterSpec::toggle("treat_as_point", "Treat As Point", false).with_attribute("treatAsPoint", "bool"),
+        ]
+    }
+    
+    fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        USDSphereLightLogic::execute(inputs, parameters)
+    }
+}
@@ -2,6 +2,51 @@
 
 use nodle_plugin_sdk::*;
 use std::collections::HashMap;
+use crate::core::stage_loader;
+use crate::ui::help::{NodeHelp, KITCHEN_SET, USD_WG_ASSETS};
+use crate::ui::keyboard::{focus_marked, handle_key};
+use crate::registry::NodeTypeFactory;
+
+/// Help for the Load Stage node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_LoadStage",
+    summary: "Load a USD stage from file",
+    details: "Opens a .usd, .usda, .usdc or .usdz file. Auto Reload reopens the stage when the file changes on disk; turning Load Payloads off opens heavy assets unloaded. The file starts opening on a worker thread as soon as its path is set, so several Load Stage nodes open their files in parallel with the native USD backend; through Python the opens take turns.",
+    ports: &[
+        ("Stage", "loaded_0"),
+    ],
+    samples: &[KITCHEN_SET, USD_WG_ASSETS],
+};
+
+/// Factory of the Load Stage node, for `crate::node_factories`
+pub fn factories() -> Vec<Box<dyn NodeFactory>> {
+    vec![NodeTypeFactory::boxed(metadata, |position| {
+        println!("Creating USD Load Stage node at position: {:?}", position);
+        PluginNodeHandle::new(Box::new(USDLoadStageNode::new(position)))
+    })]
+}
+
+/// Metadata of the Load Stage node
+fn metadata() -> NodeMetadata {
+    println!("Creating USD Load Stage metadata with output port");
+    NodeMetadata::new(
+        "USD_LoadStage",
+        "Load Stage",
+        NodeCategory::new(&["USD", "Stage"]),
+        HELP.summary
+    )
+    .with_color(Color32::from_rgb(80, 150, 200))
+    .with_icon("📂")
+    .with_inputs(vec![
+        // No input ports - file selection via parameters
+    ])
+    .with_outputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("Loaded USD stage"),
+    ])
+    .with_panel_type(PanelType::Parameter)
+    .with_workspace_compatibility(vec!["3D"])
+}
 
 /// USD Load Stage node with file loading functionality
 pub struct USDLoadStageNode {
@@ -10,6 +55,8 @@ pub struct USDLoadStageNode {
     file_path: String,
     auto_reload: bool,
     load_payloads: bool,
+    /// Path last handed to a background open
+    opened_path: String,
 }
 
 impl USDLoadStageNode {
@@ -20,6 +67,19 @@ impl USDLoadStageNode {
             file_path: String::new(),
             auto_reload: false,
             load_payloads: true,
+            opened_path: String::new(),
+        }
+    }
+    
+    /// Set the file path, starting to open the file in the background
+    ///
+    /// The open runs while the rest of the graph cooks, rather than serially
+    /// when downstream nodes resolve the path.
+    fn set_file_path(&mut self, path: &str) {
+        self.file_path = path.to_string();
+        if self.opened_path != self.file_path && std::path::Path::new(&self.file_path).exists() {
+            stage_loader::open_in_background(&self.file_path);
+            self.opened_path = self.file_path.clone();
         }
     }
 }
@@ -70,14 +130,19 @@ impl PluginNode for USDLoadStageNode {
             value: self.load_payloads,
             parameter_name: "load_payloads".to_string(),
         });
+        elements.extend(HELP.section());
         
-        let result = ParameterUI { elements };
+        let result = ParameterUI { elements: focus_marked(&self.id, elements) };
         
         println!("🔥 USD Plugin: get_parameter_ui returning with {} elements!", result.elements.len());
         result
     }
     
     fn handle_ui_action(&mut self, action: UIAction) -> Vec<ParameterChange> {
+        if let Some(changes) = handle_key(self, &[], &action) {
+            return changes;
+        }
+        
         let mut changes = Vec::new();
         
         match action {
@@ -85,7 +150,7 @@ impl PluginNode for USDLoadStageNode {
                 match parameter.as_str() {
                     "file_path" => {
                         if let Some(path) = value.as_string() {
-                            self.file_path = path.to_string();
+                            self.set_file_path(path);
                             changes.push(ParameterChange {
                                 parameter: "file_path".to_string(),
                                 value: NodeData::String(self.file_path.clone()),
@@ -118,7 +183,7 @@ impl PluginNode for USDLoadStageNode {
                     "browse_file" => {
                         // TODO: Open file dialog
                         // For now, use the test scene
-                        self.file_path = "/Users/brian/nodle-claude/nodle-plugin-cycles/test_scene.usd".to_string();
+                        self.set_file_path("/Users/brian/nodle-claude/nodle-plugin-cycles/test_scene.usd");
                         changes.push(ParameterChange {
                             parameter: "file_path".to_string(),
                             value: NodeData::String(self.file_path.clone()),
@@ -145,7 +210,7 @@ impl PluginNode for USDLoadStageNode {
         match name {
             "file_path" => {
                 if let Some(path) = value.as_string() {
-                    self.file_path = path.to_string();
+                    self.set_file_path(path);
                 }
             }
             "auto_reload" => {
@@ -0,0 +1,213 @@
+//! USD Material Preview node - shows a material on a turntable sphere or shader ball
+
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::ui::material_preview::MaterialPreview;
+use crate::ui::help::{NodeHelp, PREVIEW_SURFACE};
+use crate::ui::keyboard::{focus_marked, handle_key};
+use crate::registry::NodeTypeFactory;
+
+/// Help for the Material Preview node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_MaterialPreview",
+    summary: "Preview a material on a turntable sphere or shader ball, updating as the material changes",
+    details: "Builds a small preview stage with the material bound to a sphere or shader ball and spins it. The preview rebuilds only when the material changes. Each rebuild or turn renders a thumbnail offscreen in Material Preview shading, output as Preview Image.",
+    ports: &[
+        ("Stage", "stage_0"),
+        ("Material", "/World/Looks/Red"),
+        ("Preview Stage", "stage_0_preview_World_Looks_Red"),
+        ("Time", "12"),
+        ("Preview Image", "/tmp/nodle_material_preview/stage_0_preview_World_Looks_Red.png"),
+    ],
+    samples: &[PREVIEW_SURFACE],
+};
+
+/// Factory of the Material Preview node, for `crate::node_factories`
+pub fn factories() -> Vec<Box<dyn NodeFactory>> {
+    vec![NodeTypeFactory::boxed(metadata, |position| PluginNodeHandle::new(Box::new(USDMaterialPreviewNode::new(position))))]
+}
+
+/// Metadata of the Material Preview node
+fn metadata() -> NodeMetadata {
+    NodeMetadata::new(
+        "USD_MaterialPreview",
+        "Material Preview",
+        NodeCategory::new(&["USD", "Shading"]),
+        HELP.summary
+    )
+    .with_color(Color32::from_rgb(180, 100, 180))
+    .with_icon("⚪")
+    .with_inputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("USD stage containing the material"),
+        PortDefinition::optional("Material", DataType::String)
+            .with_description("Material prim path, overriding the path parameter"),
+    ])
+    .with_outputs(vec![
+        PortDefinition::required("Preview Stage", DataType::String)
+            .with_description("Preview stage with the material bound, framed by /Preview/Camera"),
+        PortDefinition::required("Time", DataType::Float)
+            .with_description("Preview time code for the turntable angle"),
+        PortDefinition::optional("Preview Image", DataType::String)
+            .with_description("Thumbnail rendered offscreen in Material Preview shading, as a PNG path"),
+    ])
+    .with_panel_type(PanelType::Parameter)
+    .with_workspace_compatibility(vec!["3D"])
+}
+
+/// USD Material Preview node
+///
+/// Emits a small preview stage with the material bound and the turntable
+/// time code to view it at, for lookdev feedback in a connected viewport.
+pub struct USDMaterialPreviewNode {
+    id: String,
+    position: Pos2,
+    material_path: String,
+    stage_ref: String,
+    preview: MaterialPreview,
+}
+
+impl USDMaterialPreviewNode {
+    pub fn new(position: Pos2) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            position,
+            material_path: String::new(),
+            stage_ref: String::new(),
+            preview: MaterialPreview::default(),
+        }
+    }
+}
+
+impl PluginNode for USDMaterialPreviewNode {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+    
+    fn position(&self) -> Pos2 {
+        self.position
+    }
+    
+    fn set_position(&mut self, position: Pos2) {
+        self.position = position;
+    }
+    
+    fn get_parameter_ui(&self) -> ParameterUI {
+        let mut elements = Vec::new();
+        
+        elements.push(UIElement::Heading("USD Material Preview".to_string()));
+        elements.push(UIElement::Separator);
+        
+        if self.stage_ref.is_empty() {
+            elements.push(UIElement::Label("No USD stage connected".to_string()));
+            elements.extend(HELP.section());
+            return ParameterUI { elements };
+        }
+        
+        elements.push(UIElement::TextEdit {
+            label: "Material Path".to_string(),
+            value: self.material_path.clone(),
+            parameter_name: "material_path".to_string(),
+        });
+        elements.push(UIElement::Separator);
+        elements.extend(self.preview.elements());
+        elements.extend(HELP.section());
+        
+        ParameterUI { elements: focus_marked(&self.id, elements) }
+    }
+    
+    fn handle_ui_action(&mut self, action: UIAction) -> Vec<ParameterChange> {
+        if let Some(changes) = handle_key(self, &[], &action) {
+            return changes;
+        }
+        
+        if let Some(change) = self.preview.handle_action(&action) {
+            return vec![change];
+        }
+        
+        let mut changes = Vec::new();
+        if let UIAction::ParameterChanged { parameter, value } = action {
+            if parameter == "material_path" {
+                if let Some(path) = value.as_string() {
+                    self.set_parameter(&parameter, NodeData::String(path.to_string()));
+                    changes.push(ParameterChange {
+                        parameter: parameter.clone(),
+                        value: NodeData::String(path.to_string()),
+                    });
+                }
+            }
+        }
+        
+        changes
+    }
+    
+    fn get_parameter(&self, name: &str) -> Option<NodeData> {
+        match name {
+            "material_path" => Some(NodeData::String(self.material_path.clone())),
+            _ => self.preview.get_parameter(name),
+        }
+    }
+    
+    fn set_parameter(&mut self, name: &str, value: NodeData) {
+        if name == "material_path" {
+            if let Some(path) = value.as_string() {
+                self.material_path = path.trim().to_string();
+            }
+        } else {
+            self.preview.set_parameter(name, &value);
+        }
+    }
+    
+    fn process(&mut self, inputs: &HashMap<String, NodeData>) -> HashMap<String, NodeData> {
+        let mut outputs = HashMap::new();
+        
+        self.stage_ref = inputs.get("Stage")
+            .and_then(|data| data.as_string())
+            .unwrap_or_default()
+            .to_string();
+        // A connected material overrides the path parameter
+        if let Some(path) = inputs.get("Material").and_then(|data| data.as_string()) {
+            self.material_path = path.trim().to_string();
+        }
+        
+        self.preview.update(&self.stage_ref, &self.material_path);
+        self.preview.render();
+        if let Some(preview_stage) = &self.preview.preview_stage {
+            outputs.insert("Preview Stage".to_string(), NodeData::String(preview_stage.clone()));
+            outputs.insert("Time".to_string(), NodeData::Float(self.preview.time_code() as f32));
+        }
+        if let Some(thumbnail) = &self.preview.thumbnail {
+            outputs.insert("Preview Image".to_string(), NodeData::String(thumbnail.clone()));
+        }
+        
+        outputs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::usd_engine::with_usd_engine;
+    
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn renders_preview_thumbnails() {
+        let stage_id = with_usd_engine(|engine| -> Result<String, String> {
+            let stage = engine.create_stage("material_preview_thumbnail")?;
+            engine.create_material(&stage.identifier, "/World/Looks/Red")?;
+            Ok(stage.identifier)
+        }).unwrap();
+        let mut node = USDMaterialPreviewNode::new(Pos2::new(0.0, 0.0));
+        let inputs = HashMap::from([
+            ("Stage".to_string(), NodeData::String(stage_id)),
+            ("Material".to_string(), NodeData::String("/World/Looks/Red".to_string())),
+        ]);
+        let outputs = node.process(&inputs);
+        assert!(outputs.contains_key("Preview Stage"));
+        let thumbnail = outputs.get("Preview Image").and_then(NodeData::as_string).expect("no thumbnail was rendered");
+        let image = image::open(thumbnail).unwrap().to_rgba8();
+        assert_eq!(image.dimensions(), (256, 256));
+        // The ball covers the middle of the frame, over a clear background at the corner
+        assert_ne!(image.get_pixel(128, 128), image.get_pixel(0, 0));
+    }
+}
@@ -0,0 +1,636 @@
+//! Shared node layer between the modular node implementations and the plugin registry
+//!
+//! The geometry, lighting and shading modules describe a node once as a
+//! `ModularNode`: its metadata, its parameters and a stateless `execute`.
+//! `ModularFactory` registers such a node with the plugin registry and
+//! `ModularPluginNode` provides the parameter panel, parameter storage and
+//! re-execution on change, so no module needs its own `PluginNode` glue.
+//! Category modules list their nodes with `modular_nodes!`, which declares
+//! each node's module and lists its factory for `crate::node_factories`.
+//!
+//! Parameters bound to an attribute of the authored prim with
+//! `ParameterSpec::with_attribute` can be edited live: with Live Edit on, an
+//! edit is set on the prim through the stage's edit target instead of
+//! re-executing the node, and when the stage revision moves because someone
+//! else edited the stage, the bound parameters are read back from the prim.
+
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use crate::core::seed::{global_seed, new_seed_offset, node_seed, with_node_seed, MAX_SEED_OFFSET};
+use crate::core::usd_engine::{with_usd_engine, USDAttributeEdit, USDPrim, USDPrimSpec};
+use crate::core::usd_value::UsdValue;
+use crate::ui::choice::{choice_buttons, parse_choice};
+use crate::ui::help::NodeHelp;
+use crate::ui::i18n::tr;
+use crate::ui::palette::status_label;
+use crate::ui::keyboard::{focus_marked, handle_key};
+use crate::ui::search::{ParameterSearch, SEARCH_PARAMETER};
+
+/// How a parameter is edited in the node panel
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParameterKind {
+    Float { min: f32, max: f32 },
+    Text,
+    Toggle,
+    /// One of a fixed set of options, shown as option buttons
+    Choice(&'static [&'static str]),
+    /// Linear RGB typed as "r, g, b"
+    Color,
+    /// A button; `execute` sees true only in the run the click caused
+    Trigger,
+}
+
+/// A parameter of a modular node
+#[derive(Debug, Clone)]
+pub struct ParameterSpec {
+    pub name: &'static str,
+    pub label: &'static str,
+    pub default: NodeData,
+    pub kind: ParameterKind,
+    /// Attribute of the authored prim the parameter sets, as (name, Sdf value type)
+    pub attribute: Option<(&'static str, &'static str)>,
+}
+
+impl ParameterSpec {
+    pub fn float(name: &'static str, label: &'static str, default: f32, min: f32, max: f32) -> Self {
+        Self { name, label, default: NodeData::Float(default), kind: ParameterKind::Float { min, max }, attribute: None }
+    }
+    
+    pub fn text(name: &'static str, label: &'static str, default: &str) -> Self {
+        Self { name, label, default: NodeData::String(default.to_string()), kind: ParameterKind::Text, attribute: None }
+    }
+    
+    pub fn toggle(name: &'static str, label: &'static str, default: bool) -> Self {
+        Self { name, label, default: NodeData::Boolean(default), kind: ParameterKind::Toggle, attribute: None }
+    }
+    
+    pub fn choice(name: &'static str, label: &'static str, options: &'static [&'static str], default: &str) -> Self {
+        Self { name, label, default: NodeData::String(default.to_string()), kind: ParameterKind::Choice(options), attribute: None }
+    }
+    
+    pub fn color(name: &'static str, label: &'static str, default: [f32; 3]) -> Self {
+        Self { name, label, default: NodeData::String(format_color(default)), kind: ParameterKind::Color, attribute: None }
+    }
+    
+    pub fn trigger(name: &'static str, label: &'static str) -> Self {
+        Self { name, label, default: NodeData::Boolean(false), kind: ParameterKind::Trigger, attribute: None }
+    }
+    
+    /// Bind the parameter to an attribute of the prim the node authors, e.g. ("radius", "double"), for Live Edit
+    pub fn with_attribute(mut self, attribute: &'static str, type_name: &'static str) -> Self {
+        self.attribute = Some((attribute, type_name));
+        self
+    }
+    
+    /// The value as this parameter stores it, or None when it does not fit
+    fn accept(&self, value: &NodeData) -> Option<NodeData> {
+        match self.kind {
+            ParameterKind::Float { min, max } => value.as_float().map(|value| NodeData::Float(value.clamp(min, max))),
+            ParameterKind::Text => value.as_string().map(|text| NodeData::String(text.to_string())),
+            ParameterKind::Toggle => value.as_boolean().map(NodeData::Boolean),
+            ParameterKind::Choice(options) => value.as_string()
+                .filter(|option| options.contains(option))
+                .map(|option| NodeData::String(option.to_string())),
+            ParameterKind::Color => value.as_string()
+                .and_then(parse_color)
+                .map(|color| NodeData::String(format_color(color))),
+            // Triggers are only set by their button
+            ParameterKind::Trigger => None,
+        }
+    }
+}
+
+/// Parse a color typed as "r, g, b" or "r g b"
+pub fn parse_color(text: &str) -> Option<[f32; 3]> {
+    let channels: Vec<f32> = text.split([',', ' '])
+        .map(str::trim)
+        .filter(|channel| !channel.is_empty())
+        .map(str::parse)
+        .collect::<Result<_, _>>()
+        .ok()?;
+    <[f32; 3]>::try_from(channels).ok()
+}
+
+/// Format a color for `parse_color`
+pub fn format_color(color: [f32; 3]) -> String {
+    format!("{}, {}, {}", color[0], color[1], color[2])
+}
+
+/// Text of a string, number or flag value
+fn data_text(value: &NodeData) -> Option<String> {
+    value.as_string().map(str::to_string)
+        .or_else(|| value.as_float().map(|value| value.to_string()))
+        .or_else(|| value.as_boolean().map(|value| value.to_string()))
+}
+
+/// Attribute value of a parameter value, as the attribute's Sdf value type
+pub fn attribute_value(value: &NodeData, type_name: &str) -> Result<UsdValue, String> {
+    let text = data_text(value).ok_or_else(|| format!("No {} value to set", type_name))?;
+    UsdValue::parse(&text, type_name)
+}
+
+/// Parameter value of an attribute value read back from the stage, before the parameter accepts it
+pub fn parameter_value(value: &UsdValue) -> Option<NodeData> {
+    match value {
+        UsdValue::Bool(flag) => Some(NodeData::Boolean(*flag)),
+        UsdValue::Int(value) => Some(NodeData::Float(*value as f32)),
+        UsdValue::Float(value) => Some(NodeData::Float(*value)),
+        UsdValue::Double(value) => Some(NodeData::Float(*value as f32)),
+        UsdValue::String(text) | UsdValue::Token(text) | UsdValue::Asset(text) => Some(NodeData::String(text.clone())),
+        UsdValue::Vec3(color) | UsdValue::Color3(color) => Some(NodeData::String(format_color(color.map(|channel| channel as f32)))),
+        _ => None,
+    }
+}
+
+/// Identifier of the stage connected to the "Stage" input
+pub fn input_stage(inputs: &HashMap<String, NodeData>) -> Result<String, String> {
+    let stage_ref = inputs.get("Stage")
+        .and_then(|data| data.as_string())
+        .ok_or_else(|| "No USD stage connected".to_string())?;
+    Ok(with_usd_engine(|engine| engine.resolve_stage(stage_ref))?.identifier)
+}
+
+/// Non-empty text of an input, else of a parameter
+pub fn text_value(inputs: &HashMap<String, NodeData>, input: &str, parameters: &HashMap<String, NodeData>, parameter: &str) -> Option<String> {
+    [inputs.get(input), parameters.get(parameter)].into_iter()
+        .flatten()
+        .filter_map(|data| data.as_string())
+        .map(str::trim)
+        .find(|text| !text.is_empty())
+        .map(str::to_string)
+}
+
+/// Number of an input, else of a parameter, else `default`
+pub fn float_value(inputs: &HashMap<String, NodeData>, input: &str, parameters: &HashMap<String, NodeData>, parameter: &str, default: f32) -> f32 {
+    inputs.get(input).and_then(|data| data.as_float())
+        .or_else(|| parameters.get(parameter).and_then(|data| data.as_float()))
+        .unwrap_or(default)
+}
+
+/// Path of the prim named by the "Name" input or `name` parameter under the parent path
+pub fn child_prim_path(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>, fallback_name: &str) -> String {
+    let parent = text_value(inputs, "Parent Path", parameters, "parent_path").unwrap_or_else(|| "/World".to_string());
+    let name = text_value(inputs, "Name", parameters, "name").unwrap_or_else(|| fallback_name.to_string());
+    format!("{}/{}", parent.trim_end_matches('/'), name)
+}
+
+/// The "Stage", "Parent Path" and "Name" inputs of a node creating a prim
+pub fn child_prim_inputs(what: &str) -> Vec<PortDefinition> {
+    vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("USD stage"),
+        PortDefinition::optional("Parent Path", DataType::String)
+            .with_description("Parent prim path, overriding the parameter"),
+        PortDefinition::optional("Name", DataType::String)
+            .with_description(&format!("{} name, overriding the parameter", what)),
+    ]
+}
+
+/// Define a prim and author its attributes in one batch
+pub fn define_prim(stage_id: &str, path: &str, prim_type: &str, attributes: Vec<(&str, UsdValue)>) -> Result<USDPrim, String> {
+    let spec = USDPrimSpec { path: path.to_string(), prim_type: prim_type.to_string() };
+    let edits: Vec<USDAttributeEdit> = attributes.into_iter()
+        .map(|(attr_name, value)| USDAttributeEdit {
+            prim_path: path.to_string(),
+            attr_name: attr_name.to_string(),
+            value,
+        })
+        .collect();
+    with_usd_engine(|engine| -> Result<USDPrim, String> {
+        let prim = engine.create_prims_bulk(stage_id, &[spec])?.remove(0);
+        engine.set_attributes_bulk(stage_id, &edits)?;
+        Ok(prim)
+    })
+}
+
+/// Whether a toggle or trigger parameter is set
+pub fn flag_value(parameters: &HashMap<String, NodeData>, parameter: &str, default: bool) -> bool {
+    parameters.get(parameter).and_then(|data| data.as_boolean()).unwrap_or(default)
+}
+
+/// A node implemented once and registered through `ModularFactory`
+pub trait ModularNode: 'static {
+    /// Display name, also the heading of the parameter panel
+    const NAME: &'static str;
+    
+    /// Help shown in the panel's "?" section, also the source of the metadata description
+    const HELP: &'static NodeHelp;
+    
+    fn metadata() -> NodeMetadata;
+    
+    fn parameters() -> Vec<ParameterSpec>;
+    
+    /// Whether `execute` draws random numbers through `crate::core::seed::node_random`
+    ///
+    /// Seeded nodes get a Seed Offset parameter, picked at random when the
+    /// node is created and saved with the graph like any parameter. They
+    /// execute with a seed from the global seed and that offset, and
+    /// re-execute when the global seed changes.
+    const SEEDED: bool = false;
+    
+    /// Author the node's result and return its outputs
+    ///
+    /// `parameters` holds every parameter, at its default until edited.
+    fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String>;
+}
+
+/// Plugin node driving a `ModularNode`
+pub struct ModularPluginNode<T: ModularNode> {
+    id: String,
+    position: Pos2,
+    specs: Vec<ParameterSpec>,
+    parameters: HashMap<String, NodeData>,
+    /// Inputs of the last execution, to re-execute when they change
+    input_key: String,
+    outputs: HashMap<String, NodeData>,
+    /// Global seed of the last execution, for seeded nodes
+    global_seed: u64,
+    /// Set bound parameters on the authored prim instead of re-executing
+    live_edit: bool,
+    /// Stage revision the bound parameters were last in step with
+    live_revision: Option<u64>,
+    search: ParameterSearch,
+    dirty: bool,
+    status: String,
+    _node: PhantomData<fn() -> T>,
+}
+
+impl<T: ModularNode> ModularPluginNode<T> {
+    pub fn new(position: Pos2) -> Self {
+        let mut specs = T::parameters();
+        if T::SEEDED {
+            specs.push(ParameterSpec::float(SEED_OFFSET_PARAMETER, "Seed Offset", 0.0, 0.0, MAX_SEED_OFFSET as f32));
+        }
+        let mut parameters: HashMap<String, NodeData> = specs.iter().map(|spec| (spec.name.to_string(), spec.default.clone())).collect();
+        if T::SEEDED {
+            parameters.insert(SEED_OFFSET_PARAMETER.to_string(), NodeData::Float(new_seed_offset() as f32));
+        }
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            position,
+            specs,
+            parameters,
+            input_key: String::new(),
+            outputs: HashMap::new(),
+            global_seed: global_seed(),
+            live_edit: false,
+            live_revision: None,
+            search: ParameterSearch::default(),
+            dirty: true,
+            status: "Not executed yet".to_string(),
+            _node: PhantomData,
+        }
+    }
+    
+    fn spec(&self, name: &str) -> Option<&ParameterSpec> {
+        self.specs.iter().find(|spec| spec.name == name)
+    }
+    
+    /// Stage identifier and path of the prim the last execution authored
+    fn live_prim(&self) -> Option<(String, String)> {
+        let stage_ref = self.outputs.get("Stage").and_then(|data| data.as_string())?;
+        let prim_path = PRIM_OUTPUTS.iter().find_map(|output| self.outputs.get(*output).and_then(|data| data.as_string()))?;
+        let stage = with_usd_engine(|engine| engine.resolve_stage(stage_ref)).ok()?;
+        Some((stage.identifier, prim_path.to_string()))
+    }
+    
+    /// Set a bound parameter's value on the authored prim; false when there is nothing to set it on
+    fn write_live(&mut self, name: &str) -> bool {
+        let (Some((attribute, type_name)), Some((stage_id, prim_path))) = (self.spec(name).and_then(|spec| spec.attribute), self.live_prim()) else {
+            return false;
+        };
+        let result = attribute_value(&self.parameters[name], type_name).and_then(|value| with_usd_engine(|engine| -> Result<u64, String> {
+            engine.set_attribute(&stage_id, &prim_path, attribute, value)?;
+            Ok(engine.stage_revision(&stage_id))
+        }));
+        match result {
+            Ok(revision) => {
+                self.live_revision = Some(revision);
+                self.status = format!("✓ Live: set {}.{}", prim_path, attribute);
+                true
+            }
+            Err(e) => {
+                self.status = format!("⚠ {}", e);
+                false
+            }
+        }
+    }
+    
+    /// Read the bound parameters back from the authored prim after the stage changed
+    fn read_live(&mut self) {
+        let Some((stage_id, prim_path)) = self.live_prim() else {
+            return;
+        };
+        let revision = with_usd_engine(|engine| engine.stage_revision(&stage_id));
+        if self.live_revision == Some(revision) {
+            return;
+        }
+        self.live_revision = Some(revision);
+        for spec in &self.specs {
+            let Some((attribute, type_name)) = spec.attribute else {
+                continue;
+            };
+            let value = with_usd_engine(|engine| engine.get_attribute(&stage_id, &prim_path, attribute))
+                .and_then(|text| UsdValue::parse(&text, type_name));
+            if let Some(value) = value.ok().as_ref().and_then(parameter_value).and_then(|value| spec.accept(&value)) {
+                self.parameters.insert(spec.name.to_string(), value);
+            }
+        }
+    }
+}
+
+/// Outputs carrying the path of the prim a node authored
+const PRIM_OUTPUTS: [&str; 2] = ["Prim Path", "Light Path"];
+
+/// Parameter of the Live Edit toggle of nodes with bound parameters
+pub const LIVE_EDIT_PARAMETER: &str = "live_edit";
+
+/// Parameter of the seed offset of seeded nodes, see `ModularNode::SEEDED`
+pub const SEED_OFFSET_PARAMETER: &str = "seed_offset";
+
+/// Text identifying a set of inputs, to notice when they change
+fn input_key(inputs: &HashMap<String, NodeData>) -> String {
+    let mut entries: Vec<String> = inputs.iter()
+        .map(|(name, value)| format!("{}={}", name, data_text(value).unwrap_or_default()))
+        .collect();
+    entries.sort();
+    entries.join("\n")
+}
+
+impl<T: ModularNode> PluginNode for ModularPluginNode<T> {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+    
+    fn position(&self) -> Pos2 {
+        self.position
+    }
+    
+    fn set_position(&mut self, position: Pos2) {
+        self.position = position;
+    }
+    
+    fn get_parameter_ui(&self) -> ParameterUI {
+        let mut elements = Vec::new();
+        
+        elements.push(UIElement::Heading(tr(T::NAME)));
+        elements.push(UIElement::Separator);
+        elements.push(self.search.element());
+        
+        // Filter by parameter so a choice keeps its option buttons
+        for spec in self.specs.iter().filter(|spec| self.search.matches(&tr(spec.label))) {
+            let value = self.parameters.get(spec.name).unwrap_or(&spec.default);
+            match spec.kind {
+                ParameterKind::Float { min, max } => elements.push(UIElement::Slider {
+                    label: tr(spec.label),
+                    value: value.as_float().unwrap_or_default(),
+                    min,
+                    max,
+                    parameter_name: spec.name.to_string(),
+                }),
+                ParameterKind::Text | ParameterKind::Color => elements.push(UIElement::TextEdit {
+                    label: tr(spec.label),
+                    value: value.as_string().unwrap_or_default().to_string(),
+                    parameter_name: spec.name.to_string(),
+                }),
+                ParameterKind::Toggle => elements.push(UIElement::Checkbox {
+                    label: tr(spec.label),
+                    value: value.as_boolean().unwrap_or_default(),
+                    parameter_name: spec.name.to_string(),
+                }),
+                ParameterKind::Choice(options) => {
+                    elements.extend(choice_buttons(spec.label, spec.name, options, value.as_string().unwrap_or_default()));
+                }
+                ParameterKind::Trigger => elements.push(UIElement::Button {
+                    label: tr(spec.label),
+                    action: spec.name.to_string(),
+                }),
+            }
+        }
+        
+        if self.specs.iter().any(|spec| spec.attribute.is_some()) {
+            elements.push(UIElement::Separator);
+            elements.push(UIElement::Checkbox {
+                label: tr("Live Edit"),
+                value: self.live_edit,
+                parameter_name: LIVE_EDIT_PARAMETER.to_string(),
+            });
+        }
+        
+        elements.push(UIElement::Separator);
+        elements.push(status_label(&self.status));
+        elements.extend(T::HELP.section());
+        
+        ParameterUI { elements: focus_marked(&self.id, elements) }
+    }
+    
+    fn handle_ui_action(&mut self, action: UIAction) -> Vec<ParameterChange> {
+        if let Some(changes) = handle_key(self, &[], &action) {
+            return changes;
+        }
+        if let Some(change) = self.search.handle_action(&action) {
+            return vec![change];
+        }
+        
+        let (parameter, value) = match action {
+            UIAction::ParameterChanged { parameter, value } => (parameter, value),
+            UIAction::ButtonClicked { action } => {
+                if self.spec(&action).is_some_and(|spec| spec.kind == ParameterKind::Trigger) {
+                    self.parameters.insert(action, NodeData::Boolean(true));
+                    self.dirty = true;
+                    return Vec::new();
+                }
+                let choice = self.specs.iter()
+                    .find_map(|spec| parse_choice(&action, spec.name).map(|option| (spec.name.to_string(), option.to_string())));
+                let Some((parameter, option)) = choice else {
+                    return Vec::new();
+                };
+                (parameter, NodeData::String(option))
+            }
+        };
+        self.set_parameter(&parameter, value);
+        self.get_parameter(&parameter)
+            .map(|value| vec![ParameterChange { parameter, value }])
+            .unwrap_or_default()
+    }
+    
+    fn get_parameter(&self, name: &str) -> Option<NodeData> {
+        match name {
+            LIVE_EDIT_PARAMETER => Some(NodeData::Boolean(self.live_edit)),
+            SEARCH_PARAMETER => Some(NodeData::String(self.search.query.clone())),
+            _ => self.parameters.get(name).cloned(),
+        }
+    }
+    
+    fn set_parameter(&mut self, name: &str, value: NodeData) {
+        if name == LIVE_EDIT_PARAMETER {
+            if let Some(enabled) = value.as_boolean() {
+                self.live_edit = enabled;
+                self.live_revision = None;
+            }
+            return;
+        }
+        if name == SEARCH_PARAMETER {
+            if let Some(query) = value.as_string() {
+                self.search.query = query.to_string();
+            }
+            return;
+        }
+        let Some(value) = self.spec(name).and_then(|spec| spec.accept(&value)) else {
+            return;
+        };
+        self.parameters.insert(name.to_string(), value);
+        // Live edits go straight to the prim; the node only re-executes when that fails
+        if !(self.live_edit && !self.dirty && self.write_live(name)) {
+            self.dirty = true;
+        }
+    }
+    
+    fn process(&mut self, inputs: &HashMap<String, NodeData>) -> HashMap<String, NodeData> {
+        let key = input_key(inputs);
+        if key != self.input_key {
+            self.input_key = key;
+            self.dirty = true;
+        }
+        if T::SEEDED && global_seed() != self.global_seed {
+            self.global_seed = global_seed();
+            self.dirty = true;
+        }
+        
+        if self.dirty {
+            self.dirty = false;
+            let seed_offset = self.parameters.get(SEED_OFFSET_PARAMETER).and_then(|data| data.as_float()).unwrap_or(0.0);
+            let seed = node_seed(self.global_seed, seed_offset as u64);
+            match with_node_seed(seed, || T::execute(inputs, &self.parameters)) {
+                Ok(outputs) => {
+                    self.status = "✓ Up to date".to_string();
+                    self.outputs = outputs;
+                }
+                Err(e) => {
+                    self.status = format!("⚠ {}", e);
+                    self.outputs.clear();
+                }
+            }
+            for spec in self.specs.iter().filter(|spec| spec.kind == ParameterKind::Trigger) {
+                self.parameters.insert(spec.name.to_string(), NodeData::Boolean(false));
+            }
+            // The execution's own edits are already in the parameters
+            self.live_revision = self.live_prim().map(|(stage_id, _)| with_usd_engine(|engine| engine.stage_revision(&stage_id)));
+        }
+        if self.live_edit {
+            self.read_live();
+        }
+        
+        self.outputs.clone()
+    }
+}
+
+/// Declare node modules and a `factories` function listing their nodes' factories
+///
+/// Each entry names a module and the `ModularNode` it exports, so a module is
+/// declared and listed for registration in one place and the palette cannot
+/// miss it. The node's metadata places it in its palette category.
+macro_rules! modular_nodes {
+    ($($module:ident => $node:ident),* $(,)?) => {
+        $(
+            pub mod $module;
+            pub use $module::$node;
+        )*
+        
+        /// Factory of every node of this module, for `crate::node_factories`
+        pub fn factories() -> Vec<Box<dyn nodle_plugin_sdk::NodeFactory>> {
+            vec![
+                $(Box::new($crate::modular::ModularFactory::<$node>::default()),)*
+            ]
+        }
+    };
+}
+pub(crate) use modular_nodes;
+
+/// Registry factory of a `ModularNode`
+pub struct ModularFactory<T: ModularNode>(PhantomData<fn() -> T>);
+
+impl<T: ModularNode> Default for ModularFactory<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: ModularNode> NodeFactory for ModularFactory<T> {
+    fn metadata(&self) -> NodeMetadata {
+        T::metadata()
+    }
+    
+    fn create_node(&self, position: Pos2) -> PluginNodeHandle {
+        PluginNodeHandle::new(Box::new(ModularPluginNode::<T>::new(position)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn values_prefer_inputs_over_parameters() {
+        let inputs = HashMap::from([
+            ("Name".to_string(), NodeData::String("  ".to_string())),
+            ("Radius".to_string(), NodeData::Float(2.0)),
+        ]);
+        let parameters = HashMap::from([
+            ("parent_path".to_string(), NodeData::String("/World/Props/".to_string())),
+            ("name".to_string(), NodeData::String("Ball".to_string())),
+            ("radius".to_string(), NodeData::Float(1.0)),
+            ("height".to_string(), NodeData::Float(3.0)),
+        ]);
+        assert_eq!(child_prim_path(&inputs, &parameters, "Sphere"), "/World/Props/Ball");
+        assert_eq!(child_prim_path(&HashMap::new(), &HashMap::new(), "Sphere"), "/World/Sphere");
+        assert_eq!(float_value(&inputs, "Radius", &parameters, "radius", 0.5), 2.0);
+        assert_eq!(float_value(&inputs, "Height", &parameters, "height", 0.5), 3.0);
+        assert_eq!(float_value(&inputs, "Width", &parameters, "width", 0.5), 0.5);
+    }
+    
+    #[test]
+    fn colors_parse_with_commas_or_spaces() {
+        assert_eq!(parse_color("1, 0.5, 0"), Some([1.0, 0.5, 0.0]));
+        assert_eq!(parse_color(" 0.2 0.3 0.4 "), Some([0.2, 0.3, 0.4]));
+        assert_eq!(parse_color(&format_color([0.25, 1.0, 0.0])), Some([0.25, 1.0, 0.0]));
+        assert_eq!(parse_color("1, 0"), None);
+        assert_eq!(parse_color("red"), None);
+    }
+    
+    #[test]
+    fn bound_parameters_round_trip_through_attribute_values() {
+        let radius = ParameterSpec::float("radius", "Radius", 1.0, 0.001, 100.0).with_attribute("radius", "double");
+        assert_eq!(radius.attribute, Some(("radius", "double")));
+        assert_eq!(attribute_value(&NodeData::Float(2.5), "double"), Ok(UsdValue::Double(2.5)));
+        assert_eq!(attribute_value(&NodeData::Boolean(true), "bool"), Ok(UsdValue::Bool(true)));
+        assert_eq!(attribute_value(&NodeData::String("Z".to_string()), "token"), Ok(UsdValue::Token("Z".to_string())));
+        assert_eq!(attribute_value(&NodeData::String(format_color([1.0, 0.5, 0.0])), "color3f"), Ok(UsdValue::Color3([1.0, 0.5, 0.0])));
+        assert!(attribute_value(&NodeData::String("wide".to_string()), "double").is_err());
+        
+        // Values read back are clamped and checked like panel edits
+        let read = |text: &str, type_name: &str, spec: &ParameterSpec| UsdValue::parse(text, type_name).ok()
+            .as_ref()
+            .and_then(parameter_value)
+            .and_then(|value| spec.accept(&value));
+        assert_eq!(read("250", "double", &radius).and_then(|value| value.as_float()), Some(100.0));
+        let color = ParameterSpec::color("color", "Color", [1.0; 3]).with_attribute("inputs:color", "color3f");
+        assert_eq!(read("(0.25, 1, 0)", "color3f", &color).and_then(|value| value.as_string().map(str::to_string)), Some(format_color([0.25, 1.0, 0.0])));
+        let axis = ParameterSpec::choice("axis", "Axis", &["X", "Y", "Z"], "Y").with_attribute("axis", "token");
+        assert!(read("W", "token", &axis).is_none());
+    }    
+    #[test]
+    fn seed_offsets_are_saved_parameters_of_seeded_nodes() {
+        let node = ModularPluginNode::<crate::geometry::USDPointsNode>::new(Pos2::new(0.0, 0.0));
+        let offset = node.get_parameter(SEED_OFFSET_PARAMETER).and_then(|data| data.as_float()).unwrap();
+        assert!((0.0..=MAX_SEED_OFFSET as f32).contains(&offset) && offset.fract() == 0.0);
+        
+        // A reloaded graph sets the saved offset back
+        let mut reloaded = ModularPluginNode::<crate::geometry::USDPointsNode>::new(Pos2::new(0.0, 0.0));
+        reloaded.set_parameter(SEED_OFFSET_PARAMETER, NodeData::Float(offset));
+        assert_eq!(reloaded.get_parameter(SEED_OFFSET_PARAMETER).and_then(|data| data.as_float()), Some(offset));
+        
+        assert!(ModularPluginNode::<crate::geometry::USDCubeNode>::new(Pos2::new(0.0, 0.0)).get_parameter(SEED_OFFSET_PARAMETER).is_none());
+    }
+}
@@ -0,0 +1,226 @@
+//! USD Package USDZ node - publishes a stage and its dependencies as a .usdz archive
+
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::core::usd_engine::with_usd_engine;
+use crate::ui::help::NodeHelp;
+use crate::ui::keyboard::{focus_marked, handle_key};
+use crate::registry::NodeTypeFactory;
+
+/// Help for the Package USDZ node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_PackageUsdz",
+    summary: "Package a stage with its referenced layers and textures into a .usdz archive",
+    details: "Collects every layer and texture the stage depends on into one .usdz file. ARKit mode checks the package against Apple's Quick Look constraints.",
+    ports: &[
+        ("Stage", "loaded_0"),
+        ("Success", "true"),
+        ("Path", "/deliveries/teapot.usdz"),
+    ],
+    samples: &["Quick Look gallery: https://developer.apple.com/augmented-reality/quick-look/"],
+};
+
+/// Factory of the Package USDZ node, for `crate::node_factories`
+pub fn factories() -> Vec<Box<dyn NodeFactory>> {
+    vec![NodeTypeFactory::boxed(metadata, |position| PluginNodeHandle::new(Box::new(USDPackageUsdzNode::new(position))))]
+}
+
+/// Metadata of the Package USDZ node
+fn metadata() -> NodeMetadata {
+    NodeMetadata::new(
+        "USD_PackageUsdz",
+        "Package USDZ",
+        NodeCategory::new(&["USD", "Stage"]),
+        HELP.summary
+    )
+    .with_color(Color32::from_rgb(80, 150, 200))
+    .with_icon("📦")
+    .with_inputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("USD stage to package"),
+    ])
+    .with_outputs(vec![
+        PortDefinition::required("Success", DataType::Boolean)
+            .with_description("Packaging succeeded"),
+        PortDefinition::optional("Path", DataType::String)
+            .with_description("Written .usdz archive"),
+    ])
+    .with_panel_type(PanelType::Parameter)
+    .with_workspace_compatibility(vec!["3D"])
+}
+
+/// USD Package USDZ node
+pub struct USDPackageUsdzNode {
+    id: String,
+    position: Pos2,
+    output_path: String,
+    /// Apply ARKit (AR Quick Look) packaging rules
+    arkit: bool,
+    stage_ref: String,
+    result: Result<String, String>,
+    dirty: bool,
+}
+
+impl USDPackageUsdzNode {
+    pub fn new(position: Pos2) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            position,
+            output_path: String::new(),
+            arkit: false,
+            stage_ref: String::new(),
+            result: Err("No USD stage connected".to_string()),
+            dirty: true,
+        }
+    }
+    
+    fn package(&self) -> Result<String, String> {
+        let (stage_ref, output_path, arkit) = (self.stage_ref.clone(), self.output_path.clone(), self.arkit);
+        
+        with_usd_engine(|engine| {
+            let stage = engine.resolve_stage(&stage_ref)?;
+            engine.package_usdz(&stage.identifier, &output_path, arkit)
+        })
+    }
+}
+
+impl PluginNode for USDPackageUsdzNode {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+    
+    fn position(&self) -> Pos2 {
+        self.position
+    }
+    
+    fn set_position(&mut self, position: Pos2) {
+        self.position = position;
+    }
+    
+    fn get_parameter_ui(&self) -> ParameterUI {
+        let mut elements = Vec::new();
+        
+        elements.push(UIElement::Heading("USD Package USDZ".to_string()));
+        elements.push(UIElement::Separator);
+        
+        elements.push(UIElement::TextEdit {
+            label: "Output Path".to_string(),
+            value: self.output_path.clone(),
+            parameter_name: "output_path".to_string(),
+        });
+        
+        elements.push(UIElement::Checkbox {
+            label: "ARKit Compliant".to_string(),
+            value: self.arkit,
+            parameter_name: "arkit".to_string(),
+        });
+        
+        elements.push(UIElement::Button {
+            label: "Package Again".to_string(),
+            action: "repackage".to_string(),
+        });
+        
+        elements.push(UIElement::Separator);
+        elements.push(UIElement::Label(match &self.result {
+            Ok(path) => format!("Packaged {}", path),
+            Err(e) => format!("⚠ {}", e),
+        }));
+        elements.extend(HELP.section());
+        
+        ParameterUI { elements: focus_marked(&self.id, elements) }
+    }
+    
+    fn handle_ui_action(&mut self, action: UIAction) -> Vec<ParameterChange> {
+        if let Some(changes) = handle_key(self, &[], &action) {
+            return changes;
+        }
+        
+        let mut changes = Vec::new();
+        
+        match action {
+            UIAction::ParameterChanged { parameter, value } => {
+                match parameter.as_str() {
+                    "output_path" => {
+                        if let Some(path) = value.as_string() {
+                            self.set_parameter(&parameter, NodeData::String(path.to_string()));
+                            changes.push(ParameterChange {
+                                parameter: parameter.clone(),
+                                value: NodeData::String(path.to_string()),
+                            });
+                        }
+                    }
+                    "arkit" => {
+                        if let Some(arkit) = value.as_boolean() {
+                            self.set_parameter(&parameter, NodeData::Boolean(arkit));
+                            changes.push(ParameterChange {
+                                parameter: parameter.clone(),
+                                value: NodeData::Boolean(arkit),
+                            });
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            UIAction::ButtonClicked { action } => {
+                if action == "repackage" {
+                    self.dirty = true;
+                }
+            }
+        }
+        
+        changes
+    }
+    
+    fn get_parameter(&self, name: &str) -> Option<NodeData> {
+        match name {
+            "output_path" => Some(NodeData::String(self.output_path.clone())),
+            "arkit" => Some(NodeData::Boolean(self.arkit)),
+            _ => None,
+        }
+    }
+    
+    fn set_parameter(&mut self, name: &str, value: NodeData) {
+        match name {
+            "output_path" => {
+                if let Some(path) = value.as_string() {
+                    self.output_path = path.trim().to_string();
+                    self.dirty = true;
+                }
+            }
+            "arkit" => {
+                if let Some(arkit) = value.as_boolean() {
+                    self.arkit = arkit;
+                    self.dirty = true;
+                }
+            }
+            _ => {}
+        }
+    }
+    
+    fn process(&mut self, inputs: &HashMap<String, NodeData>) -> HashMap<String, NodeData> {
+        let mut outputs = HashMap::new();
+        
+        let Some(stage_ref) = inputs.get("Stage").and_then(|data| data.as_string()) else {
+            self.stage_ref.clear();
+            self.result = Err("No USD stage connected".to_string());
+            outputs.insert("Success".to_string(), NodeData::Boolean(false));
+            return outputs;
+        };
+        
+        if stage_ref != self.stage_ref {
+            self.stage_ref = stage_ref.to_string();
+            self.dirty = true;
+        }
+        
+        if self.dirty {
+            self.dirty = false;
+            self.result = self.package();
+        }
+        
+        outputs.insert("Success".to_string(), NodeData::Boolean(self.result.is_ok()));
+        if let Ok(path) = &self.result {
+            outputs.insert("Path".to_string(), NodeData::String(path.clone()));
+        }
+        outputs
+    }
+}
@@ -0,0 +1,354 @@
+//! C interface other plugins query composed scenes through
+//!
+//! Plugins loaded into the same host, such as a renderer, resolve these
+//! symbols from this plugin's library and read the meshes, materials and
+//! lights of a stage at a time code without parsing its USD files again.
+//! The declarations are in `ffi/nodle_usd_api.h`.
+//!
+//! A query takes a `SceneSnapshot` from the shared scene delegate, the same
+//! extraction the viewport and path tracer draw, into a scene the caller
+//! owns until it releases it; every pointer read from the scene stays valid
+//! until then. Functions returning int return 1 on success and 0 on
+//! failure, with the reason available from `nodle_usd_api_last_error()` on
+//! the same thread. Symbols are additive: existing ones keep their layout
+//! and meaning, and `NODLE_USD_API_VERSION` grows when new ones are added.
+
+use std::cell::RefCell;
+use std::ffi::{c_char, c_double, c_int, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use crate::core::scene_query::SceneSnapshot;
+use crate::core::usd_engine::with_usd_engine;
+use crate::viewport::scene_delegate::{with_scene_delegate, ExtractionSettings, SceneSnapshotSink};
+
+/// Version of the interface, returned by `nodle_usd_api_version()`
+pub const NODLE_USD_API_VERSION: u32 = 1;
+
+thread_local! {
+    /// Reason the last failed call on this thread failed
+    static LAST_ERROR: RefCell<CString> = RefCell::new(CString::default());
+}
+
+fn set_last_error(message: &str) {
+    let message = CString::new(message.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|error| *error.borrow_mut() = message);
+}
+
+/// Copy a path for C, dropping anything after an interior nul
+fn c_path(path: &str) -> CString {
+    CString::new(path.split('\0').next().unwrap_or_default()).unwrap_or_default()
+}
+
+/// A mesh of a scene, with its points in world space
+#[repr(C)]
+pub struct NodleUsdApiMesh {
+    pub prim_path: *const c_char,
+    /// `point_count` points of three doubles each
+    pub points: *const c_double,
+    pub point_count: usize,
+    pub face_vertex_counts: *const i32,
+    pub face_count: usize,
+    pub face_vertex_indices: *const i32,
+    pub index_count: usize,
+    /// Index of the bound material in the scene, -1 if none is bound
+    pub material_index: c_int,
+}
+
+/// UsdPreviewSurface inputs of a material
+#[repr(C)]
+pub struct NodleUsdApiMaterial {
+    pub prim_path: *const c_char,
+    pub diffuse_color: [f32; 3],
+    pub emissive_color: [f32; 3],
+    pub metallic: f32,
+    pub roughness: f32,
+    pub opacity: f32,
+    pub ior: f32,
+}
+
+/// A UsdLux light
+#[repr(C)]
+pub struct NodleUsdApiLight {
+    pub prim_path: *const c_char,
+    pub light_type: *const c_char,
+    /// Local-to-world matrix in USD's row-vector convention, rows first
+    pub transform: [c_double; 16],
+    pub color: [f32; 3],
+    pub intensity: f32,
+    pub exposure: f32,
+    pub radius: f32,
+    pub width: f32,
+    pub height: f32,
+    pub angle: f32,
+}
+
+/// A snapshot owned by the caller, with C copies of its paths
+pub struct NodleUsdApiScene {
+    snapshot: SceneSnapshot,
+    mesh_paths: Vec<CString>,
+    material_paths: Vec<CString>,
+    light_paths: Vec<CString>,
+    light_types: Vec<CString>,
+}
+
+impl NodleUsdApiScene {
+    fn new(snapshot: SceneSnapshot) -> Self {
+        Self {
+            mesh_paths: snapshot.meshes.iter().map(|mesh| c_path(&mesh.prim_path)).collect(),
+            material_paths: snapshot.materials.iter().map(|material| c_path(&material.prim_path)).collect(),
+            light_paths: snapshot.lights.iter().map(|light| c_path(&light.prim_path)).collect(),
+            light_types: snapshot.lights.iter().map(|light| c_path(&light.light_type)).collect(),
+            snapshot,
+        }
+    }
+    
+    fn mesh(&self, index: usize) -> Option<NodleUsdApiMesh> {
+        let mesh = self.snapshot.meshes.get(index)?;
+        let material_index = mesh.material_path.as_ref()
+            .and_then(|path| self.snapshot.material_index(path))
+            .map_or(-1, |index| index as c_int);
+        Some(NodleUsdApiMesh {
+            prim_path: self.mesh_paths[index].as_ptr(),
+            points: mesh.points.as_ptr().cast(),
+            point_count: mesh.points.len(),
+            face_vertex_counts: mesh.face_vertex_counts.as_ptr(),
+            face_count: mesh.face_vertex_counts.len(),
+            face_vertex_indices: mesh.face_vertex_indices.as_ptr(),
+            index_count: mesh.face_vertex_indices.len(),
+            material_index,
+        })
+    }
+    
+    fn material(&self, index: usize) -> Option<NodleUsdApiMaterial> {
+        let material = self.snapshot.materials.get(index)?;
+        Some(NodleUsdApiMaterial {
+            prim_path: self.material_paths[index].as_ptr(),
+            diffuse_color: material.diffuse_color,
+            emissive_color: material.emissive_color,
+            metallic: material.metallic,
+            roughness: material.roughness,
+            opacity: material.opacity,
+            ior: material.ior,
+        })
+    }
+    
+    fn light(&self, index: usize) -> Option<NodleUsdApiLight> {
+        let light = self.snapshot.lights.get(index)?;
+        Some(NodleUsdApiLight {
+            prim_path: self.light_paths[index].as_ptr(),
+            light_type: self.light_types[index].as_ptr(),
+            transform: light.transform,
+            color: light.color,
+            intensity: light.intensity,
+            exposure: light.exposure,
+            radius: light.radius,
+            width: light.width,
+            height: light.height,
+            angle: light.angle,
+        })
+    }
+}
+
+/// Write an element of a scene to `out`, recording why when there is none
+///
+/// # Safety
+/// `scene` must be null or a live scene from `nodle_usd_api_scene_query`,
+/// and `out` null or valid for writes.
+unsafe fn read_element<T>(scene: *const NodleUsdApiScene, index: usize, out: *mut T, kind: &str, get: impl FnOnce(&NodleUsdApiScene, usize) -> Option<T>) -> c_int {
+    let Some(scene) = scene.as_ref() else {
+        set_last_error("No scene given");
+        return 0;
+    };
+    if out.is_null() {
+        set_last_error(&format!("No {} to write to", kind));
+        return 0;
+    }
+    match get(scene, index) {
+        Some(element) => {
+            out.write(element);
+            1
+        }
+        None => {
+            set_last_error(&format!("No {} at index {}", kind, index));
+            0
+        }
+    }
+}
+
+/// Version of the interface this plugin provides
+#[no_mangle]
+pub extern "C" fn nodle_usd_api_version() -> u32 {
+    NODLE_USD_API_VERSION
+}
+
+/// Reason the last failed call on this thread failed, valid until the next call on the thread
+#[no_mangle]
+pub extern "C" fn nodle_usd_api_last_error() -> *const c_char {
+    LAST_ERROR.with(|error| error.borrow().as_ptr())
+}
+
+/// Snapshot of a stage's meshes, materials and lights at a time code, null on failure
+///
+/// The stage is a stage identifier from a node port or a file path, which
+/// is opened if no loaded stage has it.
+///
+/// # Safety
+/// `stage` must be a nul-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nodle_usd_api_scene_query(stage: *const c_char, time: c_double) -> *mut NodleUsdApiScene {
+    if stage.is_null() {
+        set_last_error("No stage given");
+        return std::ptr::null_mut();
+    }
+    let stage_ref = CStr::from_ptr(stage).to_string_lossy().into_owned();
+    let result = catch_unwind(AssertUnwindSafe(|| -> Result<SceneSnapshot, String> {
+        let stage_id = with_usd_engine(|engine| engine.resolve_stage(&stage_ref))?.identifier;
+        let settings = ExtractionSettings { time_code: time, subdivision_level: 0, display_primvar: None };
+        let mut sink = SceneSnapshotSink::default();
+        with_scene_delegate(|delegate| delegate.populate(&stage_id, &settings, &mut sink));
+        Ok(sink.snapshot)
+    }));
+    match result {
+        Ok(Ok(snapshot)) => Box::into_raw(Box::new(NodleUsdApiScene::new(snapshot))),
+        Ok(Err(e)) => {
+            set_last_error(&e);
+            std::ptr::null_mut()
+        }
+        Err(_) => {
+            set_last_error(&format!("Querying the scene of '{}' panicked", stage_ref));
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Release a scene and everything read from it
+///
+/// # Safety
+/// `scene` must be null or a scene from `nodle_usd_api_scene_query` not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn nodle_usd_api_scene_release(scene: *mut NodleUsdApiScene) {
+    if !scene.is_null() {
+        drop(Box::from_raw(scene));
+    }
+}
+
+/// Time code a scene was read at
+///
+/// # Safety
+/// `scene` must be null or a live scene.
+#[no_mangle]
+pub unsafe extern "C" fn nodle_usd_api_scene_time(scene: *const NodleUsdApiScene) -> c_double {
+    scene.as_ref().map_or(0.0, |scene| scene.snapshot.time_code)
+}
+
+/// Number of meshes in a scene
+///
+/// # Safety
+/// `scene` must be null or a live scene.
+#[no_mangle]
+pub unsafe extern "C" fn nodle_usd_api_scene_mesh_count(scene: *const NodleUsdApiScene) -> usize {
+    scene.as_ref().map_or(0, |scene| scene.snapshot.meshes.len())
+}
+
+/// Read a mesh of a scene
+///
+/// # Safety
+/// `scene` must be null or a live scene, and `out` null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn nodle_usd_api_scene_mesh(scene: *const NodleUsdApiScene, index: usize, out: *mut NodleUsdApiMesh) -> c_int {
+    read_element(scene, index, out, "mesh", NodleUsdApiScene::mesh)
+}
+
+/// Number of materials in a scene
+///
+/// # Safety
+/// `scene` must be null or a live scene.
+#[no_mangle]
+pub unsafe extern "C" fn nodle_usd_api_scene_material_count(scene: *const NodleUsdApiScene) -> usize {
+    scene.as_ref().map_or(0, |scene| scene.snapshot.materials.len())
+}
+
+/// Read a material of a scene
+///
+/// # Safety
+/// `scene` must be null or a live scene, and `out` null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn nodle_usd_api_scene_material(scene: *const NodleUsdApiScene, index: usize, out: *mut NodleUsdApiMaterial) -> c_int {
+    read_element(scene, index, out, "material", NodleUsdApiScene::material)
+}
+
+/// Number of lights in a scene
+///
+/// # Safety
+/// `scene` must be null or a live scene.
+#[no_mangle]
+pub unsafe extern "C" fn nodle_usd_api_scene_light_count(scene: *const NodleUsdApiScene) -> usize {
+    scene.as_ref().map_or(0, |scene| scene.snapshot.lights.len())
+}
+
+/// Read a light of a scene
+///
+/// # Safety
+/// `scene` must be null or a live scene, and `out` null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn nodle_usd_api_scene_light(scene: *const NodleUsdApiScene, index: usize, out: *mut NodleUsdApiLight) -> c_int {
+    read_element(scene, index, out, "light", NodleUsdApiScene::light)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::scene_query::{SceneLight, SceneMaterial, SceneMesh};
+    use std::mem::MaybeUninit;
+    
+    #[test]
+    fn scenes_read_back_through_the_c_functions() {
+        let snapshot = SceneSnapshot {
+            stage_id: "api_test".to_string(),
+            time_code: 12.0,
+            meshes: vec![
+                SceneMesh {
+                    prim_path: "/World/Ground".to_string(),
+                    points: vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 0.0, 1.0]],
+                    face_vertex_counts: vec![3],
+                    face_vertex_indices: vec![0, 1, 2],
+                    material_path: Some("/Looks/Clay".to_string()),
+                },
+                SceneMesh { prim_path: "/World/Bare".to_string(), ..SceneMesh::default() },
+            ],
+            materials: vec![SceneMaterial::new("/Looks/Clay")],
+            lights: vec![SceneLight::new("/Lights/Sun", "DistantLight", [1.0; 16])],
+        };
+        let scene = Box::into_raw(Box::new(NodleUsdApiScene::new(snapshot)));
+        
+        unsafe {
+            assert_eq!(nodle_usd_api_scene_time(scene), 12.0);
+            assert_eq!(nodle_usd_api_scene_mesh_count(scene), 2);
+            let mut mesh = MaybeUninit::<NodleUsdApiMesh>::uninit();
+            assert_eq!(nodle_usd_api_scene_mesh(scene, 0, mesh.as_mut_ptr()), 1);
+            let mesh = mesh.assume_init();
+            assert_eq!(CStr::from_ptr(mesh.prim_path).to_str(), Ok("/World/Ground"));
+            assert_eq!(std::slice::from_raw_parts(mesh.points, mesh.point_count * 3)[6..], [1.0, 0.0, 1.0]);
+            assert_eq!(std::slice::from_raw_parts(mesh.face_vertex_indices, mesh.index_count), [0, 1, 2]);
+            assert_eq!(mesh.material_index, 0);
+            
+            let mut bare = MaybeUninit::<NodleUsdApiMesh>::uninit();
+            assert_eq!(nodle_usd_api_scene_mesh(scene, 1, bare.as_mut_ptr()), 1);
+            assert_eq!(bare.assume_init().material_index, -1);
+            
+            let mut material = MaybeUninit::<NodleUsdApiMaterial>::uninit();
+            assert_eq!(nodle_usd_api_scene_material(scene, 0, material.as_mut_ptr()), 1);
+            assert_eq!(material.assume_init().ior, 1.5);
+            let mut light = MaybeUninit::<NodleUsdApiLight>::uninit();
+            assert_eq!(nodle_usd_api_scene_light(scene, 0, light.as_mut_ptr()), 1);
+            assert_eq!(CStr::from_ptr(light.assume_init_ref().light_type).to_str(), Ok("DistantLight"));
+            
+            // Missing elements and scenes fail with a reason
+            assert_eq!(nodle_usd_api_scene_light(scene, 1, light.as_mut_ptr()), 0);
+            assert_eq!(CStr::from_ptr(nodle_usd_api_last_error()).to_str(), Ok("No light at index 1"));
+            assert_eq!(nodle_usd_api_scene_mesh_count(std::ptr::null()), 0);
+            assert!(nodle_usd_api_scene_query(std::ptr::null(), 0.0).is_null());
+            
+            nodle_usd_api_scene_release(scene);
+        }
+    }
+}
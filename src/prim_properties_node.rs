@@ -0,0 +1,320 @@
+//! USD Prim Properties node - browses and edits the attributes, relationships and
+//! metadata of a single prim
+
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::core::usd_engine::{with_usd_engine, USDProperty, USDPropertyKind};
+use crate::core::usd_value::UsdValue;
+use crate::ui::search::{ParameterSearch, SEARCH_PARAMETER};
+use crate::ui::help::{NodeHelp, KITCHEN_SET};
+use crate::ui::keyboard::{focus_marked, handle_key};
+use crate::ui::palette::status_label;
+use crate::registry::NodeTypeFactory;
+
+/// Help for the Prim Properties node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_PrimProperties",
+    summary: "List a prim's attributes, relationships and metadata at a time code and edit simple values inline",
+    details: "Lists every property of Prim Path at the given time code. Scalar, vector and token values can be edited in place.",
+    ports: &[
+        ("Stage", "loaded_0"),
+        ("Prim Path", "/World/Kitchen/Table"),
+        ("Time", "1001"),
+    ],
+    samples: &[KITCHEN_SET],
+};
+
+/// Factory of the Prim Properties node, for `crate::node_factories`
+pub fn factories() -> Vec<Box<dyn NodeFactory>> {
+    vec![NodeTypeFactory::boxed(metadata, |position| PluginNodeHandle::new(Box::new(USDPrimPropertiesNode::new(position))))]
+}
+
+/// Metadata of the Prim Properties node
+fn metadata() -> NodeMetadata {
+    NodeMetadata::new(
+        "USD_PrimProperties",
+        "Prim Properties",
+        NodeCategory::new(&["USD", "Viewport"]),
+        HELP.summary
+    )
+    .with_color(Color32::from_rgb(120, 120, 120))
+    .with_icon("🏷")
+    .with_inputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("USD stage containing the prim"),
+        PortDefinition::optional("Prim Path", DataType::String)
+            .with_description("Prim to browse, overriding the path parameter"),
+        PortDefinition::optional("Time", DataType::Float)
+            .with_description("Time code to resolve attributes at (default time when unconnected)"),
+    ])
+    .with_outputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("Stage with the edited values"),
+    ])
+    .with_panel_type(PanelType::Parameter)
+    .with_workspace_compatibility(vec!["3D"])
+}
+
+/// Parameter prefix for inline attribute edits, followed by the attribute name
+const PROPERTY_PARAMETER: &str = "prop|";
+
+/// USD Prim Properties node
+///
+/// Attributes are resolved at the time on the "Time" input, or at the default
+/// time code when unconnected. Scalar and tuple attributes can be edited inline.
+pub struct USDPrimPropertiesNode {
+    id: String,
+    position: Pos2,
+    prim_path: String,
+    stage_ref: String,
+    time: Option<f64>,
+    /// Stage revision the properties were read at
+    revision: Option<u64>,
+    properties: Vec<USDProperty>,
+    search: ParameterSearch,
+    dirty: bool,
+    status: Option<String>,
+}
+
+impl USDPrimPropertiesNode {
+    pub fn new(position: Pos2) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            position,
+            prim_path: String::new(),
+            stage_ref: String::new(),
+            time: None,
+            revision: None,
+            properties: Vec::new(),
+            search: ParameterSearch::default(),
+            dirty: true,
+            status: None,
+        }
+    }
+    
+    /// Re-read the prim's properties from the USD engine
+    fn refresh_properties(&mut self) {
+        if self.prim_path.is_empty() {
+            self.properties.clear();
+            self.status = Some("No prim path set".to_string());
+            return;
+        }
+        let (stage_ref, prim_path, time) = (self.stage_ref.clone(), self.prim_path.clone(), self.time);
+        let result = with_usd_engine(|engine| {
+            engine.resolve_stage(&stage_ref)
+                .and_then(|stage| engine.get_prim_properties(&stage.identifier, &prim_path, time))
+        });
+        match result {
+            Ok(properties) => {
+                self.properties = properties;
+                self.status = None;
+            }
+            Err(e) => {
+                self.properties.clear();
+                self.status = Some(format!("⚠ {}", e));
+            }
+        }
+    }
+    
+    /// Author an edited attribute value as the attribute's type; the stage revision change triggers a re-read
+    fn commit_property(&mut self, name: &str, value: &str) {
+        let Some(type_name) = self.properties.iter().find(|property| property.name == name).map(|property| property.type_name.clone()) else {
+            return;
+        };
+        let (stage_ref, prim_path) = (self.stage_ref.clone(), self.prim_path.clone());
+        let result = UsdValue::parse(value, &type_name).and_then(|value| with_usd_engine(|engine| {
+            let stage = engine.resolve_stage(&stage_ref)?;
+            engine.set_attribute(&stage.identifier, &prim_path, name, value)
+        }));
+        match result {
+            Ok(()) => {
+                if let Some(property) = self.properties.iter_mut().find(|property| property.name == name) {
+                    property.value = value.to_string();
+                }
+                self.status = None;
+            }
+            Err(e) => self.status = Some(format!("⚠ {}", e)),
+        }
+    }
+    
+    /// UI element for one property row
+    fn property_element(property: &USDProperty) -> UIElement {
+        let label = format!("{}{} ({})", property.name, if property.animated { " ⏱" } else { "" }, property.type_name);
+        if !property.is_editable() {
+            return UIElement::Label(format!("{}: {}", label, property.value));
+        }
+        let parameter_name = format!("{}{}", PROPERTY_PARAMETER, property.name);
+        if property.type_name == "bool" {
+            UIElement::Checkbox {
+                label,
+                value: matches!(property.value.as_str(), "True" | "true" | "1"),
+                parameter_name,
+            }
+        } else {
+            UIElement::TextEdit {
+                label,
+                value: property.value.clone(),
+                parameter_name,
+            }
+        }
+    }
+}
+
+/// Section heading for a property kind
+fn section_title(kind: USDPropertyKind) -> &'static str {
+    match kind {
+        USDPropertyKind::Attribute => "Attributes",
+        USDPropertyKind::Relationship => "Relationships",
+        USDPropertyKind::Metadata => "Metadata",
+    }
+}
+
+impl PluginNode for USDPrimPropertiesNode {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+    
+    fn position(&self) -> Pos2 {
+        self.position
+    }
+    
+    fn set_position(&mut self, position: Pos2) {
+        self.position = position;
+    }
+    
+    fn get_parameter_ui(&self) -> ParameterUI {
+        let mut elements = Vec::new();
+        
+        elements.push(UIElement::Heading("USD Prim Properties".to_string()));
+        elements.push(UIElement::Separator);
+        
+        if self.stage_ref.is_empty() {
+            elements.push(UIElement::Label("No USD stage connected".to_string()));
+            elements.extend(HELP.section());
+            return ParameterUI { elements };
+        }
+        
+        elements.push(UIElement::TextEdit {
+            label: "Prim Path".to_string(),
+            value: self.prim_path.clone(),
+            parameter_name: "prim_path".to_string(),
+        });
+        elements.push(UIElement::Label(match self.time {
+            Some(time) => format!("Time: {}", time),
+            None => "Time: default".to_string(),
+        }));
+        if let Some(status) = &self.status {
+            elements.push(status_label(status));
+        }
+        elements.push(self.search.element());
+        
+        for kind in [USDPropertyKind::Attribute, USDPropertyKind::Relationship, USDPropertyKind::Metadata] {
+            let rows: Vec<UIElement> = self.properties.iter()
+                .filter(|property| property.kind == kind)
+                .filter(|property| self.search.matches(&format!("{} {} {}", property.name, property.type_name, property.value)))
+                .map(Self::property_element)
+                .collect();
+            if rows.is_empty() {
+                continue;
+            }
+            elements.push(UIElement::Separator);
+            elements.push(UIElement::Label(format!("{} ({})", section_title(kind), rows.len())));
+            elements.extend(rows);
+        }
+        elements.extend(HELP.section());
+        
+        ParameterUI { elements: focus_marked(&self.id, elements) }
+    }
+    
+    fn handle_ui_action(&mut self, action: UIAction) -> Vec<ParameterChange> {
+        if let Some(changes) = handle_key(self, &[], &action) {
+            return changes;
+        }
+        
+        if let Some(change) = self.search.handle_action(&action) {
+            return vec![change];
+        }
+        
+        let mut changes = Vec::new();
+        if let UIAction::ParameterChanged { parameter, value } = action {
+            if let Some(name) = parameter.strip_prefix(PROPERTY_PARAMETER) {
+                let text = match value.as_boolean() {
+                    Some(flag) => Some(if flag { "True" } else { "False" }.to_string()),
+                    None => value.as_string().map(|text| text.trim().to_string()),
+                };
+                if let Some(text) = text {
+                    self.commit_property(name, &text);
+                }
+            } else if parameter == "prim_path" {
+                if let Some(path) = value.as_string() {
+                    self.set_parameter(&parameter, NodeData::String(path.to_string()));
+                    changes.push(ParameterChange {
+                        parameter: parameter.clone(),
+                        value: NodeData::String(path.to_string()),
+                    });
+                }
+            }
+        }
+        
+        changes
+    }
+    
+    fn get_parameter(&self, name: &str) -> Option<NodeData> {
+        match name {
+            "prim_path" => Some(NodeData::String(self.prim_path.clone())),
+            SEARCH_PARAMETER => Some(NodeData::String(self.search.query.clone())),
+            _ => None,
+        }
+    }
+    
+    fn set_parameter(&mut self, name: &str, value: NodeData) {
+        let Some(text) = value.as_string() else {
+            return;
+        };
+        match name {
+            "prim_path" => {
+                if text.trim() != self.prim_path {
+                    self.prim_path = text.trim().to_string();
+                    self.dirty = true;
+                }
+            }
+            SEARCH_PARAMETER => self.search.query = text.to_string(),
+            _ => {}
+        }
+    }
+    
+    fn process(&mut self, inputs: &HashMap<String, NodeData>) -> HashMap<String, NodeData> {
+        let mut outputs = HashMap::new();
+        
+        let Some(stage_ref) = inputs.get("Stage").and_then(|data| data.as_string()) else {
+            self.stage_ref.clear();
+            self.revision = None;
+            self.properties.clear();
+            return outputs;
+        };
+        
+        // A connected prim path, e.g. from the Stage Inspector, overrides the parameter
+        if let Some(path) = inputs.get("Prim Path").and_then(|data| data.as_string()) {
+            self.set_parameter("prim_path", NodeData::String(path.to_string()));
+        }
+        let time = inputs.get("Time").and_then(|data| data.as_float()).map(f64::from);
+        if time != self.time {
+            self.time = time;
+            self.dirty = true;
+        }
+        if stage_ref != self.stage_ref {
+            self.stage_ref = stage_ref.to_string();
+            self.dirty = true;
+        }
+        
+        let revision = with_usd_engine(|engine| engine.stage_revision(stage_ref));
+        if self.dirty || self.revision != Some(revision) {
+            self.dirty = false;
+            self.revision = Some(revision);
+            self.refresh_properties();
+        }
+        
+        outputs.insert("Stage".to_string(), NodeData::String(self.stage_ref.clone()));
+        outputs
+    }
+}
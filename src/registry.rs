@@ -0,0 +1,51 @@
+//! Registration of the plugin's node modules
+//!
+//! Every node module exports a `factories` function listing the factories of
+//! the nodes it defines: `modular_nodes!` generates it for the category
+//! modules, the other node modules write it next to their `PluginNode`.
+//! `node_modules!` declares the node modules and collects their factories in
+//! one place, so a module cannot be declared without reaching the palette.
+
+use nodle_plugin_sdk::*;
+
+/// Declare the node modules and a `node_module_factories` function collecting their factories
+///
+/// Modules are listed in palette order.
+macro_rules! node_modules {
+    ($($module:ident),* $(,)?) => {
+        $(
+            mod $module;
+        )*
+        
+        /// Factories of every node module, in declaration order
+        fn node_module_factories() -> Vec<Box<dyn nodle_plugin_sdk::NodeFactory>> {
+            let mut factories: Vec<Box<dyn nodle_plugin_sdk::NodeFactory>> = Vec::new();
+            $(factories.extend($module::factories());)*
+            factories
+        }
+    };
+}
+pub(crate) use node_modules;
+
+/// Registry factory of a node with its own `PluginNode`
+pub struct NodeTypeFactory {
+    metadata: fn() -> NodeMetadata,
+    create: fn(Pos2) -> PluginNodeHandle,
+}
+
+impl NodeTypeFactory {
+    /// Boxed factory describing the node with `metadata` and creating it with `create`
+    pub fn boxed(metadata: fn() -> NodeMetadata, create: fn(Pos2) -> PluginNodeHandle) -> Box<dyn NodeFactory> {
+        Box::new(Self { metadata, create })
+    }
+}
+
+impl NodeFactory for NodeTypeFactory {
+    fn metadata(&self) -> NodeMetadata {
+        (self.metadata)()
+    }
+    
+    fn create_node(&self, position: Pos2) -> PluginNodeHandle {
+        (self.create)(position)
+    }
+}
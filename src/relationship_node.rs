@@ -0,0 +1,272 @@
+//! USD Relationship node - targets a prim relationship at other prims or properties
+
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::core::usd_engine::{with_usd_engine, RelationshipEdit};
+use crate::ui::choice::{choice_buttons, parse_choice};
+use crate::ui::help::NodeHelp;
+use crate::ui::palette::status_label;
+use crate::ui::keyboard::{focus_marked, handle_key};
+use crate::registry::NodeTypeFactory;
+
+/// Help for the Relationship node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_Relationship",
+    summary: "Create a relationship on a prim and set, append or remove its targets",
+    details: "Authors a relationship such as material:binding or proxyPrim on Prim Path. Targets are prim or property paths, one per line; a Collection input targets a collection instead.",
+    ports: &[
+        ("Stage", "stage_0"),
+        ("Prim Path", "/World/Sphere"),
+        ("Targets", "/World/Looks/Material"),
+        ("Collection", "/World.collection:heroes"),
+    ],
+    samples: &[],
+};
+
+/// Factory of the Relationship node, for `crate::node_factories`
+pub fn factories() -> Vec<Box<dyn NodeFactory>> {
+    vec![NodeTypeFactory::boxed(metadata, |position| PluginNodeHandle::new(Box::new(USDRelationshipNode::new(position))))]
+}
+
+/// Metadata of the Relationship node
+fn metadata() -> NodeMetadata {
+    NodeMetadata::new(
+        "USD_Relationship",
+        "Relationship",
+        NodeCategory::new(&["USD", "Stage"]),
+        HELP.summary
+    )
+    .with_color(Color32::from_rgb(80, 150, 200))
+    .with_icon("🔗")
+    .with_inputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("USD stage to author the relationship in"),
+        PortDefinition::optional("Prim Path", DataType::String)
+            .with_description("Prim owning the relationship, overriding the parameter"),
+        PortDefinition::optional("Targets", DataType::String)
+            .with_description("Target paths, one per line, overriding the parameter"),
+        PortDefinition::optional("Collection", DataType::String)
+            .with_description("Collection whose members become the targets"),
+    ])
+    .with_outputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("Stage with the relationship authored"),
+    ])
+    .with_panel_type(PanelType::Parameter)
+    .with_workspace_compatibility(vec!["3D"])
+}
+
+/// Commonly authored relationships offered as presets
+const PRESETS: [&str; 3] = ["material:binding", "proxyPrim", "collection:default:includes"];
+
+/// USD Relationship node
+///
+/// Targets come from the Targets parameter, one path per line or separated by
+/// commas, or from the "Targets" input, e.g. a Copy Prims or Layout Import
+/// "Prims" output. A connected "Collection" targets the collection's members.
+pub struct USDRelationshipNode {
+    id: String,
+    position: Pos2,
+    prim_path: String,
+    relationship: String,
+    targets: Vec<String>,
+    edit: RelationshipEdit,
+    stage_ref: String,
+    /// Targets read back after authoring
+    authored: Vec<String>,
+    dirty: bool,
+    status: String,
+}
+
+impl USDRelationshipNode {
+    pub fn new(position: Pos2) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            position,
+            prim_path: String::new(),
+            relationship: "material:binding".to_string(),
+            targets: Vec::new(),
+            edit: RelationshipEdit::Set,
+            stage_ref: String::new(),
+            authored: Vec::new(),
+            dirty: true,
+            status: "No USD stage connected".to_string(),
+        }
+    }
+    
+    fn author(&mut self) -> Result<(), String> {
+        if self.prim_path.is_empty() {
+            return Err("No prim path set".to_string());
+        }
+        let (stage_ref, prim_path, relationship) = (self.stage_ref.clone(), self.prim_path.clone(), self.relationship.clone());
+        let (targets, edit) = (self.targets.clone(), self.edit);
+        
+        self.authored = with_usd_engine(|engine| {
+            let stage = engine.resolve_stage(&stage_ref)?;
+            engine.edit_relationship_targets(&stage.identifier, &prim_path, &relationship, &targets, edit)?;
+            engine.get_relationship_targets(&stage.identifier, &prim_path, &relationship)
+        })?;
+        self.status = format!("{}.{} → {} targets", self.prim_path, self.relationship, self.authored.len());
+        Ok(())
+    }
+}
+
+/// Split a target list on newlines and commas
+fn parse_targets(text: &str) -> Vec<String> {
+    text.split(['\n', ','])
+        .map(str::trim)
+        .filter(|target| !target.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+impl PluginNode for USDRelationshipNode {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+    
+    fn position(&self) -> Pos2 {
+        self.position
+    }
+    
+    fn set_position(&mut self, position: Pos2) {
+        self.position = position;
+    }
+    
+    fn get_parameter_ui(&self) -> ParameterUI {
+        let mut elements = Vec::new();
+        
+        elements.push(UIElement::Heading("USD Relationship".to_string()));
+        elements.push(UIElement::Separator);
+        
+        elements.push(UIElement::TextEdit {
+            label: "Prim Path".to_string(),
+            value: self.prim_path.clone(),
+            parameter_name: "prim_path".to_string(),
+        });
+        elements.push(UIElement::TextEdit {
+            label: "Relationship".to_string(),
+            value: self.relationship.clone(),
+            parameter_name: "relationship".to_string(),
+        });
+        elements.extend(choice_buttons("Presets", "relationship", &PRESETS, &self.relationship));
+        elements.push(UIElement::TextEdit {
+            label: "Targets".to_string(),
+            value: self.targets.join(", "),
+            parameter_name: "targets".to_string(),
+        });
+        elements.extend(choice_buttons("Edit", "edit", &RelationshipEdit::ALL.map(|edit| edit.name()), self.edit.name()));
+        
+        elements.push(UIElement::Separator);
+        elements.push(status_label(&self.status));
+        for target in &self.authored {
+            elements.push(UIElement::Label(format!("  → {}", target)));
+        }
+        elements.extend(HELP.section());
+        
+        ParameterUI { elements: focus_marked(&self.id, elements) }
+    }
+    
+    fn handle_ui_action(&mut self, action: UIAction) -> Vec<ParameterChange> {
+        if let Some(changes) = handle_key(self, &[], &action) {
+            return changes;
+        }
+        
+        let mut changes = Vec::new();
+        
+        let (parameter, value) = match action {
+            UIAction::ParameterChanged { parameter, value } => (parameter, value),
+            UIAction::ButtonClicked { action } => {
+                let Some((parameter, option)) = ["relationship", "edit"].into_iter()
+                    .find_map(|parameter| Some((parameter, parse_choice(&action, parameter)?))) else {
+                    return changes;
+                };
+                (parameter.to_string(), NodeData::String(option.to_string()))
+            }
+        };
+        if matches!(parameter.as_str(), "prim_path" | "relationship" | "targets" | "edit") {
+            self.set_parameter(&parameter, value);
+            if let Some(value) = self.get_parameter(&parameter) {
+                changes.push(ParameterChange { parameter, value });
+            }
+        }
+        
+        changes
+    }
+    
+    fn get_parameter(&self, name: &str) -> Option<NodeData> {
+        match name {
+            "prim_path" => Some(NodeData::String(self.prim_path.clone())),
+            "relationship" => Some(NodeData::String(self.relationship.clone())),
+            "targets" => Some(NodeData::String(self.targets.join("\n"))),
+            "edit" => Some(NodeData::String(self.edit.name().to_string())),
+            _ => None,
+        }
+    }
+    
+    fn set_parameter(&mut self, name: &str, value: NodeData) {
+        let Some(text) = value.as_string() else {
+            return;
+        };
+        match name {
+            "prim_path" => self.prim_path = text.trim().to_string(),
+            "relationship" => self.relationship = text.trim().to_string(),
+            "targets" => self.targets = parse_targets(text),
+            "edit" => match RelationshipEdit::from_name(text) {
+                Some(edit) => self.edit = edit,
+                None => return,
+            },
+            _ => return,
+        }
+        self.dirty = true;
+    }
+    
+    fn process(&mut self, inputs: &HashMap<String, NodeData>) -> HashMap<String, NodeData> {
+        let mut outputs = HashMap::new();
+        
+        let Some(stage_ref) = inputs.get("Stage").and_then(|data| data.as_string()) else {
+            self.stage_ref.clear();
+            self.authored.clear();
+            self.status = "No USD stage connected".to_string();
+            return outputs;
+        };
+        
+        if stage_ref != self.stage_ref {
+            self.stage_ref = stage_ref.to_string();
+            self.dirty = true;
+        }
+        // Connected prim paths and targets override the parameters
+        if let Some(path) = inputs.get("Prim Path").and_then(|data| data.as_string()) {
+            if path.trim() != self.prim_path {
+                self.set_parameter("prim_path", NodeData::String(path.to_string()));
+            }
+        }
+        let mut connected = inputs.get("Targets").and_then(|data| data.as_string()).map(parse_targets);
+        if let Some(collection) = inputs.get("Collection").and_then(|data| data.as_string()) {
+            let members = with_usd_engine(|engine| {
+                let stage = engine.resolve_stage(stage_ref)?;
+                engine.get_collection_members(&stage.identifier, collection)
+            });
+            match members {
+                Ok(members) => connected = Some(members),
+                Err(e) => self.status = format!("⚠ {}", e),
+            }
+        }
+        if let Some(targets) = connected {
+            if targets != self.targets {
+                self.set_parameter("targets", NodeData::String(targets.join("\n")));
+            }
+        }
+        
+        if self.dirty {
+            self.dirty = false;
+            if let Err(e) = self.author() {
+                self.status = format!("⚠ {}", e);
+                self.authored.clear();
+            }
+        }
+        
+        outputs.insert("Stage".to_string(), NodeData::String(self.stage_ref.clone()));
+        outputs
+    }
+}
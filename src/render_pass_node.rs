@@ -0,0 +1,290 @@
+//! USD Render Pass node - defines a UsdRenderPass with visibility, matte and light collections
+
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::core::usd_engine::{split_collection_path, with_usd_engine, USDRenderPass};
+use crate::ui::help::NodeHelp;
+use crate::ui::palette::status_label;
+use crate::ui::keyboard::{focus_marked, handle_key};
+use crate::registry::NodeTypeFactory;
+
+/// Help for the Render Pass node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_RenderPass",
+    summary: "Define a UsdRenderPass with visibility, matte and light selection collections for multi-pass renders",
+    details: "Defines a render pass prim with renderVisibility, matte and light collections and the camera to render through. Inputs accept prim paths, one per line, or a collection path.",
+    ports: &[
+        ("Stage", "loaded_0"),
+        ("Camera", "/World/Cameras/shotCam"),
+        ("Visible", "/World/Characters"),
+        ("Mattes", "/World/Set"),
+        ("Lights", "/World/Lights.collection:key"),
+        ("Pass", "/Render/Passes/beauty"),
+    ],
+    samples: &["UsdRender: https://openusd.org/release/api/usd_render_page_front.html"],
+};
+
+/// Factory of the Render Pass node, for `crate::node_factories`
+pub fn factories() -> Vec<Box<dyn NodeFactory>> {
+    vec![NodeTypeFactory::boxed(metadata, |position| PluginNodeHandle::new(Box::new(USDRenderPassNode::new(position))))]
+}
+
+/// Metadata of the Render Pass node
+fn metadata() -> NodeMetadata {
+    NodeMetadata::new(
+        "USD_RenderPass",
+        "Render Pass",
+        NodeCategory::new(&["USD", "Stage"]),
+        HELP.summary
+    )
+    .with_color(Color32::from_rgb(80, 150, 200))
+    .with_icon("🎞")
+    .with_inputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("USD stage to define the pass in"),
+        PortDefinition::optional("Camera", DataType::String)
+            .with_description("Camera prim path, overriding the parameter"),
+        PortDefinition::optional("Visible", DataType::String)
+            .with_description("Rendered prim paths or a collection path"),
+        PortDefinition::optional("Mattes", DataType::String)
+            .with_description("Matte prim paths or a collection path"),
+        PortDefinition::optional("Lights", DataType::String)
+            .with_description("Light prim paths or a collection path"),
+    ])
+    .with_outputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("Stage with the render pass defined"),
+        PortDefinition::optional("Pass", DataType::String)
+            .with_description("Render pass prim path"),
+    ])
+    .with_panel_type(PanelType::Parameter)
+    .with_workspace_compatibility(vec!["3D"])
+}
+
+/// Path lists of a pass, with their parameter and input names
+const PATH_LISTS: [(&str, &str); 3] = [("visible", "Visible"), ("mattes", "Mattes"), ("lights", "Lights")];
+
+/// USD Render Pass node
+///
+/// Each node defines one pass under /Render/Passes; chain nodes for a
+/// multi-pass setup. The Visible, Mattes and Lights inputs take prim paths or
+/// a collection path (e.g. a Collection node's "Collection" output), which is
+/// resolved to its members.
+pub struct USDRenderPassNode {
+    id: String,
+    position: Pos2,
+    name: String,
+    visible: Vec<String>,
+    mattes: Vec<String>,
+    lights: Vec<String>,
+    camera: String,
+    stage_ref: String,
+    /// Pass path once defined
+    defined: Option<String>,
+    dirty: bool,
+    status: String,
+}
+
+impl USDRenderPassNode {
+    pub fn new(position: Pos2) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            position,
+            name: "beauty".to_string(),
+            visible: Vec::new(),
+            mattes: Vec::new(),
+            lights: Vec::new(),
+            camera: String::new(),
+            stage_ref: String::new(),
+            defined: None,
+            dirty: true,
+            status: "No USD stage connected".to_string(),
+        }
+    }
+    
+    fn render_pass(&self) -> USDRenderPass {
+        USDRenderPass {
+            name: self.name.clone(),
+            visible: self.visible.clone(),
+            mattes: self.mattes.clone(),
+            lights: self.lights.clone(),
+            camera: (!self.camera.is_empty()).then(|| self.camera.clone()),
+        }
+    }
+    
+    fn paths_mut(&mut self, parameter: &str) -> Option<&mut Vec<String>> {
+        match parameter {
+            "visible" => Some(&mut self.visible),
+            "mattes" => Some(&mut self.mattes),
+            "lights" => Some(&mut self.lights),
+            _ => None,
+        }
+    }
+    
+    fn define(&mut self) -> Result<(), String> {
+        let (stage_ref, render_pass) = (self.stage_ref.clone(), self.render_pass());
+        let path = with_usd_engine(|engine| {
+            let stage = engine.resolve_stage(&stage_ref)?;
+            engine.set_render_pass(&stage.identifier, &render_pass)
+        })?;
+        let count = |paths: &[String], all: &str| if paths.is_empty() { all.to_string() } else { paths.len().to_string() };
+        self.status = format!("{}: {} visible, {} mattes, {} lights", path,
+                              count(&self.visible, "all"), self.mattes.len(), count(&self.lights, "all"));
+        self.defined = Some(path);
+        Ok(())
+    }
+}
+
+/// Split a path list on newlines and commas
+fn parse_paths(text: &str) -> Vec<String> {
+    text.split(['\n', ','])
+        .map(str::trim)
+        .filter(|path| !path.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+impl PluginNode for USDRenderPassNode {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+    
+    fn position(&self) -> Pos2 {
+        self.position
+    }
+    
+    fn set_position(&mut self, position: Pos2) {
+        self.position = position;
+    }
+    
+    fn get_parameter_ui(&self) -> ParameterUI {
+        let mut elements = Vec::new();
+        
+        elements.push(UIElement::Heading("USD Render Pass".to_string()));
+        elements.push(UIElement::Separator);
+        
+        elements.push(UIElement::TextEdit {
+            label: "Pass Name".to_string(),
+            value: self.name.clone(),
+            parameter_name: "name".to_string(),
+        });
+        elements.push(UIElement::TextEdit {
+            label: "Camera".to_string(),
+            value: self.camera.clone(),
+            parameter_name: "camera".to_string(),
+        });
+        for (label, parameter, paths) in [("Visible (empty = all)", "visible", &self.visible), ("Mattes", "mattes", &self.mattes), ("Lights (empty = all)", "lights", &self.lights)] {
+            elements.push(UIElement::TextEdit {
+                label: label.to_string(),
+                value: paths.join(", "),
+                parameter_name: parameter.to_string(),
+            });
+        }
+        
+        elements.push(UIElement::Separator);
+        elements.push(status_label(&self.status));
+        elements.extend(HELP.section());
+        
+        ParameterUI { elements: focus_marked(&self.id, elements) }
+    }
+    
+    fn handle_ui_action(&mut self, action: UIAction) -> Vec<ParameterChange> {
+        if let Some(changes) = handle_key(self, &[], &action) {
+            return changes;
+        }
+        
+        let mut changes = Vec::new();
+        
+        if let UIAction::ParameterChanged { parameter, value } = action {
+            if matches!(parameter.as_str(), "name" | "camera" | "visible" | "mattes" | "lights") {
+                self.set_parameter(&parameter, value);
+                if let Some(value) = self.get_parameter(&parameter) {
+                    changes.push(ParameterChange { parameter, value });
+                }
+            }
+        }
+        
+        changes
+    }
+    
+    fn get_parameter(&self, name: &str) -> Option<NodeData> {
+        match name {
+            "name" => Some(NodeData::String(self.name.clone())),
+            "camera" => Some(NodeData::String(self.camera.clone())),
+            "visible" => Some(NodeData::String(self.visible.join("\n"))),
+            "mattes" => Some(NodeData::String(self.mattes.join("\n"))),
+            "lights" => Some(NodeData::String(self.lights.join("\n"))),
+            _ => None,
+        }
+    }
+    
+    fn set_parameter(&mut self, name: &str, value: NodeData) {
+        let Some(text) = value.as_string() else {
+            return;
+        };
+        match name {
+            "name" => self.name = text.trim().to_string(),
+            "camera" => self.camera = text.trim().to_string(),
+            _ => match self.paths_mut(name) {
+                Some(paths) => *paths = parse_paths(text),
+                None => return,
+            },
+        }
+        self.dirty = true;
+    }
+    
+    fn process(&mut self, inputs: &HashMap<String, NodeData>) -> HashMap<String, NodeData> {
+        let mut outputs = HashMap::new();
+        
+        let Some(stage_ref) = inputs.get("Stage").and_then(|data| data.as_string()) else {
+            self.stage_ref.clear();
+            self.defined = None;
+            self.status = "No USD stage connected".to_string();
+            return outputs;
+        };
+        
+        if stage_ref != self.stage_ref {
+            self.stage_ref = stage_ref.to_string();
+            self.dirty = true;
+        }
+        // Connected cameras and path lists override the parameters
+        if let Some(camera) = inputs.get("Camera").and_then(|data| data.as_string()) {
+            if camera.trim() != self.camera {
+                self.set_parameter("camera", NodeData::String(camera.to_string()));
+            }
+        }
+        for (parameter, input) in PATH_LISTS {
+            let Some(text) = inputs.get(input).and_then(|data| data.as_string()) else {
+                continue;
+            };
+            let paths = match split_collection_path(text) {
+                Some(_) => with_usd_engine(|engine| {
+                    let stage = engine.resolve_stage(stage_ref)?;
+                    engine.get_collection_members(&stage.identifier, text)
+                }),
+                None => Ok(parse_paths(text)),
+            };
+            match paths {
+                Ok(paths) if self.paths_mut(parameter).is_some_and(|current| *current != paths) => {
+                    self.set_parameter(parameter, NodeData::String(paths.join("\n")));
+                }
+                Ok(_) => {}
+                Err(e) => self.status = format!("⚠ {}", e),
+            }
+        }
+        
+        if self.dirty {
+            self.dirty = false;
+            if let Err(e) = self.define() {
+                self.status = format!("⚠ {}", e);
+                self.defined = None;
+            }
+        }
+        
+        outputs.insert("Stage".to_string(), NodeData::String(self.stage_ref.clone()));
+        if let Some(path) = &self.defined {
+            outputs.insert("Pass".to_string(), NodeData::String(path.clone()));
+        }
+        outputs
+    }
+}
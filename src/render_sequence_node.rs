@@ -0,0 +1,429 @@
+//! USD Render Sequence node - renders a frame range in the background for playblasts
+
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::capture::sequence::{JobState, MovieSettings, SequenceJob};
+use crate::core::usd_engine::with_usd_engine;
+use crate::stage::render_frame::{camera_view, first_camera, frame_times};
+use crate::ui::help::{NodeHelp, KITCHEN_SET};
+use crate::viewport::hydra::{HydraRenderer, STORM_RENDERER};
+use crate::ui::palette::status_label;
+use crate::ui::keyboard::{focus_marked, handle_key};
+use crate::ui::search::{ParameterSearch, SEARCH_PARAMETER};
+use crate::registry::NodeTypeFactory;
+
+/// Help for the Render Sequence node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_RenderSequence",
+    summary: "Renders a frame range through a stage camera to numbered images, optionally encoded to an mp4",
+    details: "Render starts a background job that writes one image per frame to the `#`-padded file path while the panel shows its progress; Cancel stops it after the current frame. Use Stage Range takes the range from the stage's start and end time codes. With Make Movie on, ffmpeg (which must be on the PATH) encodes the finished frames to an H.264 mp4.",
+    ports: &[
+        ("Stage", "stage_0"),
+        ("Camera", "/World/Cameras/shot_cam"),
+        ("Files", "playblast/shot.1001.png\nplayblast/shot.1002.png"),
+        ("Movie", "playblast/shot.mp4"),
+    ],
+    samples: &[KITCHEN_SET],
+};
+
+/// Factory of the Render Sequence node, for `crate::node_factories`
+pub fn factories() -> Vec<Box<dyn NodeFactory>> {
+    vec![NodeTypeFactory::boxed(metadata, |position| PluginNodeHandle::new(Box::new(USDRenderSequenceNode::new(position))))]
+}
+
+/// Metadata of the Render Sequence node
+fn metadata() -> NodeMetadata {
+    NodeMetadata::new(
+        "USD_RenderSequence",
+        "Render Sequence",
+        NodeCategory::new(&["USD", "Stage"]),
+        HELP.summary
+    )
+    .with_color(Color32::from_rgb(80, 150, 200))
+    .with_icon("🎞")
+    .with_inputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("USD stage to render"),
+        PortDefinition::optional("Camera", DataType::String)
+            .with_description("Camera prim path, overriding the parameter"),
+    ])
+    .with_outputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("Rendered stage"),
+        PortDefinition::optional("Files", DataType::String)
+            .with_description("Written frames, one per line, once the sequence finished"),
+        PortDefinition::optional("Movie", DataType::String)
+            .with_description("Encoded movie, with Make Movie on"),
+    ])
+    .with_panel_type(PanelType::Parameter)
+    .with_workspace_compatibility(vec!["3D"])
+}
+
+/// USD Render Sequence node
+///
+/// Frames render on a worker thread with its own Hydra session; the node
+/// polls the job each time it is processed and outputs the written files
+/// once the job finishes.
+pub struct USDRenderSequenceNode {
+    id: String,
+    position: Pos2,
+    camera: String,
+    width: f32,
+    height: f32,
+    use_stage_range: bool,
+    start_frame: f32,
+    end_frame: f32,
+    step: f32,
+    file_path: String,
+    renderer: String,
+    make_movie: bool,
+    movie_path: String,
+    fps: f32,
+    /// Stage and camera connected at the last process
+    stage: Option<String>,
+    camera_input: Option<String>,
+    job: Option<SequenceJob>,
+    search: ParameterSearch,
+    status: String,
+}
+
+impl USDRenderSequenceNode {
+    pub fn new(position: Pos2) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            position,
+            camera: String::new(),
+            width: 1920.0,
+            height: 1080.0,
+            use_stage_range: true,
+            start_frame: 1.0,
+            end_frame: 24.0,
+            step: 1.0,
+            file_path: "playblast/shot.####.png".to_string(),
+            renderer: STORM_RENDERER.to_string(),
+            make_movie: false,
+            movie_path: "playblast/shot.mp4".to_string(),
+            fps: 24.0,
+            stage: None,
+            camera_input: None,
+            job: None,
+            search: ParameterSearch::default(),
+            status: "Not rendered yet".to_string(),
+        }
+    }
+    
+    /// Start a job over the frame range, replacing a finished one
+    fn start(&mut self) -> Result<(), String> {
+        if self.job.as_ref().is_some_and(|job| job.state.is_running()) {
+            return Err("A sequence is already rendering".to_string());
+        }
+        self.job = None;
+        let stage_id = self.stage.clone().ok_or_else(|| "No USD stage connected".to_string())?;
+        if !self.file_path.contains('#') {
+            return Err("File Path needs # frame padding, e.g. shot.####.png".to_string());
+        }
+        
+        let (start, end, fps) = if self.use_stage_range {
+            let range = with_usd_engine(|engine| engine.get_stage_time_range(&stage_id))?;
+            (range.start_time_code, range.end_time_code, range.time_codes_per_second)
+        } else {
+            (self.start_frame as f64, self.end_frame as f64, self.fps as f64)
+        };
+        let times = frame_times(start, end, self.step as f64)?;
+        let movie = self.make_movie.then(|| MovieSettings { path: self.movie_path.clone(), fps });
+        if movie.is_some() && self.step != 1.0 {
+            return Err("Movies need a frame step of 1".to_string());
+        }
+        
+        let camera = match (&self.camera_input, self.camera.as_str()) {
+            (Some(camera), _) => camera.clone(),
+            (None, "") => first_camera(&stage_id)?,
+            (None, camera) => camera.to_string(),
+        };
+        let mut view = camera_view(&camera, self.width.round().max(1.0) as u32, self.height.round().max(1.0) as u32, &self.renderer);
+        let mut renderer = HydraRenderer::default();
+        self.job = Some(SequenceJob::start(times, &self.file_path, movie, move |time| {
+            view.time_code = time;
+            renderer.read_frame(&stage_id, &view)
+        }));
+        Ok(())
+    }
+    
+    /// Progress line of the current job
+    fn job_status(job: &SequenceJob) -> String {
+        let frames = format!("{}/{} frames", job.files.len(), job.total);
+        match &job.state {
+            JobState::Rendering => format!("Rendering {} ({:.0}%)", frames, job.progress() * 100.0),
+            JobState::Encoding => format!("Encoding movie from {}", frames),
+            JobState::Finished => format!("✓ Rendered {}", frames),
+            JobState::Cancelled => format!("Cancelled after {}", frames),
+            JobState::Failed(e) => format!("⚠ {} (after {})", e, frames),
+        }
+    }
+}
+
+impl PluginNode for USDRenderSequenceNode {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+    
+    fn position(&self) -> Pos2 {
+        self.position
+    }
+    
+    fn set_position(&mut self, position: Pos2) {
+        self.position = position;
+    }
+    
+    fn get_parameter_ui(&self) -> ParameterUI {
+        let mut elements = Vec::new();
+        
+        elements.push(UIElement::Heading("USD Render Sequence".to_string()));
+        elements.push(UIElement::Separator);
+        elements.push(self.search.element());
+        
+        elements.push(UIElement::TextEdit {
+            label: "Camera".to_string(),
+            value: self.camera.clone(),
+            parameter_name: "camera".to_string(),
+        });
+        elements.push(UIElement::Slider {
+            label: "Width".to_string(),
+            value: self.width,
+            min: 16.0,
+            max: 8192.0,
+            parameter_name: "width".to_string(),
+        });
+        elements.push(UIElement::Slider {
+            label: "Height".to_string(),
+            value: self.height,
+            min: 16.0,
+            max: 8192.0,
+            parameter_name: "height".to_string(),
+        });
+        elements.push(UIElement::Checkbox {
+            label: "Use Stage Range".to_string(),
+            value: self.use_stage_range,
+            parameter_name: "use_stage_range".to_string(),
+        });
+        if !self.use_stage_range {
+            elements.push(UIElement::Slider {
+                label: "Start Frame".to_string(),
+                value: self.start_frame,
+                min: -10000.0,
+                max: 100000.0,
+                parameter_name: "start_frame".to_string(),
+            });
+            elements.push(UIElement::Slider {
+                label: "End Frame".to_string(),
+                value: self.end_frame,
+                min: -10000.0,
+                max: 100000.0,
+                parameter_name: "end_frame".to_string(),
+            });
+            elements.push(UIElement::Slider {
+                label: "FPS".to_string(),
+                value: self.fps,
+                min: 1.0,
+                max: 120.0,
+                parameter_name: "fps".to_string(),
+            });
+        }
+        elements.push(UIElement::Slider {
+            label: "Step".to_string(),
+            value: self.step,
+            min: 1.0,
+            max: 100.0,
+            parameter_name: "step".to_string(),
+        });
+        elements.push(UIElement::TextEdit {
+            label: "File Path".to_string(),
+            value: self.file_path.clone(),
+            parameter_name: "file_path".to_string(),
+        });
+        elements.push(UIElement::TextEdit {
+            label: "Render Delegate".to_string(),
+            value: self.renderer.clone(),
+            parameter_name: "renderer".to_string(),
+        });
+        elements.push(UIElement::Checkbox {
+            label: "Make Movie".to_string(),
+            value: self.make_movie,
+            parameter_name: "make_movie".to_string(),
+        });
+        if self.make_movie {
+            elements.push(UIElement::TextEdit {
+                label: "Movie Path".to_string(),
+                value: self.movie_path.clone(),
+                parameter_name: "movie_path".to_string(),
+            });
+        }
+        
+        let running = self.job.as_ref().is_some_and(|job| job.state.is_running());
+        elements.push(UIElement::Button {
+            label: if running { "⏹ Cancel".to_string() } else { "🎬 Render".to_string() },
+            action: if running { "cancel".to_string() } else { "render".to_string() },
+        });
+        
+        let mut elements = self.search.filter(elements);
+        elements.push(UIElement::Separator);
+        elements.push(status_label(&self.status));
+        if let Some(file) = self.job.as_ref().and_then(|job| job.files.last()) {
+            elements.push(UIElement::Label(file.clone()));
+        }
+        elements.extend(HELP.section());
+        
+        ParameterUI { elements: focus_marked(&self.id, elements) }
+    }
+    
+    fn handle_ui_action(&mut self, action: UIAction) -> Vec<ParameterChange> {
+        if let Some(changes) = handle_key(self, &[], &action) {
+            return changes;
+        }
+        if let Some(change) = self.search.handle_action(&action) {
+            return vec![change];
+        }
+        
+        let mut changes = Vec::new();
+        
+        match action {
+            UIAction::ParameterChanged { parameter, value } => {
+                self.set_parameter(&parameter, value);
+                if let Some(value) = self.get_parameter(&parameter) {
+                    changes.push(ParameterChange { parameter, value });
+                }
+            }
+            UIAction::ButtonClicked { action } => match action.as_str() {
+                "render" => {
+                    if let Err(e) = self.start() {
+                        self.status = format!("⚠ {}", e);
+                    }
+                }
+                "cancel" => {
+                    if let Some(job) = &self.job {
+                        job.cancel();
+                    }
+                }
+                _ => {}
+            },
+        }
+        
+        changes
+    }
+    
+    fn get_parameter(&self, name: &str) -> Option<NodeData> {
+        match name {
+            SEARCH_PARAMETER => Some(NodeData::String(self.search.query.clone())),
+            "camera" => Some(NodeData::String(self.camera.clone())),
+            "width" => Some(NodeData::Float(self.width)),
+            "height" => Some(NodeData::Float(self.height)),
+            "use_stage_range" => Some(NodeData::Boolean(self.use_stage_range)),
+            "start_frame" => Some(NodeData::Float(self.start_frame)),
+            "end_frame" => Some(NodeData::Float(self.end_frame)),
+            "step" => Some(NodeData::Float(self.step)),
+            "file_path" => Some(NodeData::String(self.file_path.clone())),
+            "renderer" => Some(NodeData::String(self.renderer.clone())),
+            "make_movie" => Some(NodeData::Boolean(self.make_movie)),
+            "movie_path" => Some(NodeData::String(self.movie_path.clone())),
+            "fps" => Some(NodeData::Float(self.fps)),
+            _ => None,
+        }
+    }
+    
+    fn set_parameter(&mut self, name: &str, value: NodeData) {
+        match name {
+            SEARCH_PARAMETER => {
+                if let Some(query) = value.as_string() {
+                    self.search.query = query.to_string();
+                }
+            }
+            "camera" => {
+                if let Some(camera) = value.as_string() {
+                    self.camera = camera.trim().to_string();
+                }
+            }
+            "width" => {
+                if let Some(width) = value.as_float() {
+                    self.width = width.round().clamp(16.0, 8192.0);
+                }
+            }
+            "height" => {
+                if let Some(height) = value.as_float() {
+                    self.height = height.round().clamp(16.0, 8192.0);
+                }
+            }
+            "use_stage_range" => {
+                if let Some(enabled) = value.as_boolean() {
+                    self.use_stage_range = enabled;
+                }
+            }
+            "start_frame" => {
+                if let Some(frame) = value.as_float() {
+                    self.start_frame = frame;
+                }
+            }
+            "end_frame" => {
+                if let Some(frame) = value.as_float() {
+                    self.end_frame = frame;
+                }
+            }
+            "step" => {
+                if let Some(step) = value.as_float() {
+                    self.step = step.round().max(1.0);
+                }
+            }
+            "file_path" => {
+                if let Some(path) = value.as_string() {
+                    self.file_path = path.trim().to_string();
+                }
+            }
+            "renderer" => {
+                if let Some(renderer) = value.as_string() {
+                    self.renderer = renderer.trim().to_string();
+                }
+            }
+            "make_movie" => {
+                if let Some(enabled) = value.as_boolean() {
+                    self.make_movie = enabled;
+                }
+            }
+            "movie_path" => {
+                if let Some(path) = value.as_string() {
+                    self.movie_path = path.trim().to_string();
+                }
+            }
+            "fps" => {
+                if let Some(fps) = value.as_float() {
+                    self.fps = fps.max(1.0);
+                }
+            }
+            _ => {}
+        }
+    }
+    
+    fn process(&mut self, inputs: &HashMap<String, NodeData>) -> HashMap<String, NodeData> {
+        let mut outputs = HashMap::new();
+        
+        self.stage = inputs.get("Stage").and_then(|data| data.as_string()).map(str::to_string);
+        self.camera_input = inputs.get("Camera")
+            .and_then(|data| data.as_string())
+            .map(str::trim)
+            .filter(|camera| !camera.is_empty())
+            .map(str::to_string);
+        if let Some(stage) = &self.stage {
+            outputs.insert("Stage".to_string(), NodeData::String(stage.clone()));
+        }
+        
+        if let Some(job) = &mut self.job {
+            job.poll();
+            self.status = Self::job_status(job);
+            if job.state == JobState::Finished {
+                outputs.insert("Files".to_string(), NodeData::String(job.files.join("\n")));
+                if let Some(movie) = &job.movie {
+                    outputs.insert("Movie".to_string(), NodeData::String(movie.clone()));
+                }
+            }
+        }
+        outputs
+    }
+}
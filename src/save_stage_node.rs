@@ -0,0 +1,284 @@
+//! USD Save Stage node - writes a stage's root layer, or its flattened result, to usda, usdc or usdz
+
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::core::usd_engine::with_usd_engine;
+use crate::ui::choice::{choice_buttons, parse_choice};
+use crate::ui::help::NodeHelp;
+use crate::ui::keyboard::{focus_marked, handle_key};
+use crate::registry::NodeTypeFactory;
+
+/// Help for the Save Stage node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_SaveStage",
+    summary: "Save USD stage to a usda, usdc or usdz file",
+    details: "Writes the stage to File Path in the chosen format, optionally flattening composition first. Without Flatten only the root layer is written, so a stage with edits in its session layer, such as one from Create Stage, fails with an error instead of saving without them. A File Path input overrides the parameter, e.g. from a Watch Folder.",
+    ports: &[
+        ("Stage", "loaded_0"),
+        ("File Path", "/shots/sh010/lighting.usda"),
+        ("Success", "true"),
+        ("Error", "Permission denied"),
+        ("Path", "/shots/sh010/lighting.usda"),
+    ],
+    samples: &[],
+};
+
+/// Factory of the Save Stage node, for `crate::node_factories`
+pub fn factories() -> Vec<Box<dyn NodeFactory>> {
+    vec![NodeTypeFactory::boxed(metadata, |position| PluginNodeHandle::new(Box::new(USDSaveStageNode::new(position))))]
+}
+
+/// Metadata of the Save Stage node
+fn metadata() -> NodeMetadata {
+    NodeMetadata::new(
+        "USD_SaveStage",
+        "Save Stage",
+        NodeCategory::new(&["USD", "Stage"]),
+        HELP.summary
+    )
+    .with_color(Color32::from_rgb(80, 150, 200))
+    .with_icon("💾")
+    .with_inputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("USD stage to save"),
+        PortDefinition::optional("File Path", DataType::String)
+            .with_description("Output file path, overriding the node's path parameter"),
+    ])
+    .with_outputs(vec![
+        PortDefinition::required("Success", DataType::Boolean)
+            .with_description("Save operation success"),
+        PortDefinition::optional("Error", DataType::String)
+            .with_description("Why the save failed, empty on success"),
+        PortDefinition::optional("Path", DataType::String)
+            .with_description("Written file path"),
+    ])
+    .with_panel_type(PanelType::Parameter)
+    .with_workspace_compatibility(vec!["3D"])
+}
+
+/// Format choices; "auto" follows the file extension
+const FORMATS: [&str; 4] = ["auto", "usda", "usdc", "usdz"];
+
+/// USD Save Stage node
+pub struct USDSaveStageNode {
+    id: String,
+    position: Pos2,
+    file_path: String,
+    format: String,
+    /// Export the composed stage as one layer, materializing in-memory and anonymous layers
+    flatten: bool,
+    stage_ref: String,
+    /// Path from the "File Path" input, overriding `file_path` when connected
+    input_path: Option<String>,
+    /// Stage revision at the last save attempt, so later edits trigger a resave
+    saved_revision: Option<u64>,
+    result: Result<String, String>,
+    dirty: bool,
+}
+
+impl USDSaveStageNode {
+    pub fn new(position: Pos2) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            position,
+            file_path: String::new(),
+            format: "auto".to_string(),
+            flatten: false,
+            stage_ref: String::new(),
+            input_path: None,
+            saved_revision: None,
+            result: Err("No USD stage connected".to_string()),
+            dirty: true,
+        }
+    }
+    
+    fn target_path(&self) -> String {
+        self.input_path.clone().unwrap_or_else(|| self.file_path.clone())
+    }
+    
+    fn save(&self) -> Result<String, String> {
+        let (stage_ref, path) = (self.stage_ref.clone(), self.target_path());
+        let format = Some(self.format.as_str()).filter(|format| *format != "auto");
+        
+        with_usd_engine(|engine| {
+            let stage = engine.resolve_stage(&stage_ref)?;
+            if self.flatten {
+                engine.export_stage(&stage.identifier, &path, format)
+            } else {
+                engine.save_stage(&stage.identifier, &path, format)
+            }
+        })
+    }
+}
+
+impl PluginNode for USDSaveStageNode {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+    
+    fn position(&self) -> Pos2 {
+        self.position
+    }
+    
+    fn set_position(&mut self, position: Pos2) {
+        self.position = position;
+    }
+    
+    fn get_parameter_ui(&self) -> ParameterUI {
+        let mut elements = Vec::new();
+        
+        elements.push(UIElement::Heading("USD Save Stage".to_string()));
+        elements.push(UIElement::Separator);
+        
+        match &self.input_path {
+            Some(path) => elements.push(UIElement::Label(format!("File Path: {} (from input)", path))),
+            None => elements.push(UIElement::TextEdit {
+                label: "File Path".to_string(),
+                value: self.file_path.clone(),
+                parameter_name: "file_path".to_string(),
+            }),
+        }
+        elements.extend(choice_buttons("Format", "format", &FORMATS, &self.format));
+        elements.push(UIElement::Checkbox {
+            label: "Flatten".to_string(),
+            value: self.flatten,
+            parameter_name: "flatten".to_string(),
+        });
+        
+        elements.push(UIElement::Button {
+            label: "Save Again".to_string(),
+            action: "resave".to_string(),
+        });
+        
+        elements.push(UIElement::Separator);
+        elements.push(UIElement::Label(match &self.result {
+            Ok(path) => format!("Saved to {}", path),
+            Err(e) => format!("⚠ {}", e),
+        }));
+        elements.extend(HELP.section());
+        
+        ParameterUI { elements: focus_marked(&self.id, elements) }
+    }
+    
+    fn handle_ui_action(&mut self, action: UIAction) -> Vec<ParameterChange> {
+        if let Some(changes) = handle_key(self, &[], &action) {
+            return changes;
+        }
+        
+        let mut changes = Vec::new();
+        
+        match action {
+            UIAction::ParameterChanged { parameter, value } => {
+                match parameter.as_str() {
+                    "file_path" => {
+                        if let Some(path) = value.as_string() {
+                            self.set_parameter(&parameter, NodeData::String(path.to_string()));
+                            changes.push(ParameterChange {
+                                parameter: parameter.clone(),
+                                value: NodeData::String(path.to_string()),
+                            });
+                        }
+                    }
+                    "flatten" => {
+                        if let Some(flatten) = value.as_boolean() {
+                            self.set_parameter(&parameter, NodeData::Boolean(flatten));
+                            changes.push(ParameterChange {
+                                parameter: parameter.clone(),
+                                value: NodeData::Boolean(flatten),
+                            });
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            UIAction::ButtonClicked { action } => {
+                if action == "resave" {
+                    self.dirty = true;
+                } else if let Some(format) = parse_choice(&action, "format") {
+                    self.set_parameter("format", NodeData::String(format.to_string()));
+                    changes.push(ParameterChange {
+                        parameter: "format".to_string(),
+                        value: NodeData::String(format.to_string()),
+                    });
+                }
+            }
+        }
+        
+        changes
+    }
+    
+    fn get_parameter(&self, name: &str) -> Option<NodeData> {
+        match name {
+            "file_path" => Some(NodeData::String(self.file_path.clone())),
+            "format" => Some(NodeData::String(self.format.clone())),
+            "flatten" => Some(NodeData::Boolean(self.flatten)),
+            _ => None,
+        }
+    }
+    
+    fn set_parameter(&mut self, name: &str, value: NodeData) {
+        match name {
+            "file_path" => {
+                if let Some(path) = value.as_string() {
+                    self.file_path = path.trim().to_string();
+                    self.dirty = true;
+                }
+            }
+            "flatten" => {
+                if let Some(flatten) = value.as_boolean() {
+                    self.flatten = flatten;
+                    self.dirty = true;
+                }
+            }
+            "format" => {
+                if let Some(format) = value.as_string().filter(|format| FORMATS.contains(format)) {
+                    self.format = format.to_string();
+                    self.dirty = true;
+                }
+            }
+            _ => {}
+        }
+    }
+    
+    fn process(&mut self, inputs: &HashMap<String, NodeData>) -> HashMap<String, NodeData> {
+        let mut outputs = HashMap::new();
+        
+        let Some(stage_ref) = inputs.get("Stage").and_then(|data| data.as_string()) else {
+            self.stage_ref.clear();
+            self.result = Err("No USD stage connected".to_string());
+            outputs.insert("Success".to_string(), NodeData::Boolean(false));
+            outputs.insert("Error".to_string(), NodeData::String("No USD stage connected".to_string()));
+            return outputs;
+        };
+        
+        if stage_ref != self.stage_ref {
+            self.stage_ref = stage_ref.to_string();
+            self.dirty = true;
+        }
+        let input_path = inputs.get("File Path")
+            .and_then(|data| data.as_string())
+            .map(|path| path.trim().to_string())
+            .filter(|path| !path.is_empty());
+        if input_path != self.input_path {
+            self.input_path = input_path;
+            self.dirty = true;
+        }
+        let revision = with_usd_engine(|engine| engine.stage_revision(&self.stage_ref));
+        if self.saved_revision.is_some_and(|saved| saved != revision) {
+            self.dirty = true;
+        }
+        
+        if self.dirty {
+            self.dirty = false;
+            self.saved_revision = Some(revision);
+            self.result = self.save();
+        }
+        
+        outputs.insert("Success".to_string(), NodeData::Boolean(self.result.is_ok()));
+        outputs.insert("Error".to_string(), NodeData::String(self.result.clone().err().unwrap_or_default()));
+        if let Ok(path) = &self.result {
+            outputs.insert("Path".to_string(), NodeData::String(path.clone()));
+        }
+        outputs
+    }
+}
@@ -6,39 +6,57 @@ pub mod parameters;
 pub use logic::USDMaterialLogic;
 pub use parameters::USDMaterialNode;
 
-use crate::nodes::NodeFactory;
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::modular::{child_prim_inputs, ModularNode, ParameterSpec};
+use crate::ui::help::{NodeHelp, PREVIEW_SURFACE};
 
-impl NodeFactory for parameters::USDMaterialNode {
-    fn metadata() -> crate::nodes::NodeMetadata {
-        crate::nodes::NodeMetadata::new(
-            "USD_Shading_Material",
-            "USD Material",
-            crate::nodes::NodeCategory::new(&["3D", "USD", "Shading", "Materials"]),
-            "Creates a USD material for surface shading"
+/// Help for the Material node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_Material",
+    summary: "Creates a USD material with a UsdPreviewSurface shader",
+    details: "Defines a Material under Parent Path with a UsdPreviewSurface for its surface output. Bind it to geometry with a Relationship node on material:binding.",
+    ports: &[
+        ("Stage", "stage_0"),
+        ("Parent Path", "/World/Looks"),
+        ("Name", "Red"),
+        ("Material Path", "/World/Looks/Red"),
+        ("Surface Output", "/World/Looks/Red/PreviewSurface"),
+    ],
+    samples: &[PREVIEW_SURFACE],
+};
+
+impl ModularNode for parameters::USDMaterialNode {
+    const NAME: &'static str = "USD Material";
+    const HELP: &'static NodeHelp = &HELP;
+    
+    fn metadata() -> NodeMetadata {
+        NodeMetadata::new(
+            "USD_Material",
+            "Material",
+            NodeCategory::new(&["USD", "Shading"]),
+            HELP.summary
         )
-        .with_color(egui::Color32::from_rgb(150, 100, 200))
+        .with_color(Color32::from_rgb(180, 100, 180))
         .with_icon("🎨")
-        .with_inputs(vec![
-            crate::nodes::PortDefinition::required("Stage", crate::nodes::DataType::Any)
-                .with_description("USD Stage reference"),
-            crate::nodes::PortDefinition::required("Parent Path", crate::nodes::DataType::String)
-                .with_description("Parent prim path"),
-            crate::nodes::PortDefinition::optional("Name", crate::nodes::DataType::String)
-                .with_description("Material name (auto-generated if empty)"),
-            crate::nodes::PortDefinition::optional("Surface Shader", crate::nodes::DataType::Any)
-                .with_description("Surface shader input"),
-        ])
+        .with_inputs(child_prim_inputs("Material"))
         .with_outputs(vec![
-            crate::nodes::PortDefinition::required("Material Path", crate::nodes::DataType::String)
+            PortDefinition::required("Stage", DataType::String)
+                .with_description("Stage with the material"),
+            PortDefinition::required("Material Path", DataType::String)
                 .with_description("Created material path"),
-            crate::nodes::PortDefinition::required("Material", crate::nodes::DataType::Any)
-                .with_description("USD Material reference"),
-            crate::nodes::PortDefinition::required("Surface Output", crate::nodes::DataType::Any)
-                .with_description("Surface shader output"),
+            PortDefinition::optional("Surface Output", DataType::String)
+                .with_description("Preview surface shader path"),
         ])
-        .with_tags(vec!["usd", "shading", "material", "surface"])
-        .with_processing_cost(crate::nodes::factory::ProcessingCost::Low)
-        .with_workspace_compatibility(vec!["3D", "USD"])
-        .with_panel_type(crate::nodes::interface::PanelType::Parameter)
+        .with_panel_type(PanelType::Parameter)
+        .with_workspace_compatibility(vec!["3D"])
+    }
+    
+    fn parameters() -> Vec<ParameterSpec> {
+        parameters::USDMaterialNode::parameters()
+    }
+    
+    fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        USDMaterialLogic::execute(inputs, parameters)
     }
-}
\ No newline at end of file
+}
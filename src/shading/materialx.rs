@@ -0,0 +1,124 @@
+//! USD MaterialX node - references an external .mtlx document into the stage
+
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::core::materialx::{parse_document, parse_value, preview_values};
+use crate::core::usd_engine::{with_usd_engine, RelationshipEdit};
+use crate::modular::{child_prim_inputs, child_prim_path, input_stage, text_value, ModularNode, ParameterSpec};
+use crate::ui::help::NodeHelp;
+
+/// Help for the MaterialX node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_MaterialX",
+    summary: "References a MaterialX document's materials into the stage",
+    details: "References a .mtlx document under Parent Path and optionally binds one of its materials. The viewport approximates MaterialX surfaces with UsdPreviewSurface values.",
+    ports: &[
+        ("Stage", "stage_0"),
+        ("Material Path", "/World/Looks/MaterialX/Materials/Red"),
+        ("Materials", "/World/Looks/MaterialX/Materials/Red"),
+    ],
+    samples: &["MaterialX: https://materialx.org"],
+};
+
+/// USD MaterialX node with parameter controls
+#[derive(Default)]
+pub struct USDMaterialXNode;
+
+/// Core logic for referencing MaterialX documents
+pub struct USDMaterialXLogic;
+
+impl USDMaterialXLogic {
+    /// Execute the reference operation, binding the chosen material when a prim is given
+    pub fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        let stage_id = input_stage(inputs)?;
+        let prim_path = child_prim_path(inputs, parameters, "MaterialX");
+        let file_path = text_value(inputs, "File Path", parameters, "file_path")
+            .ok_or_else(|| "No MaterialX document set".to_string())?;
+        let material_name = text_value(inputs, "Material", parameters, "material");
+        let bind_to = text_value(inputs, "Bind To", parameters, "bind_to");
+        
+        // Read the document here as well to report what the viewport can show of it
+        let text = std::fs::read_to_string(&file_path)
+            .map_err(|e| format!("Failed to read '{}': {}", file_path, e))?;
+        let document = parse_document(&text)?;
+        for material in document.materials() {
+            let surface = document.surface_shader(material);
+            let translated = surface.and_then(|surface| {
+                preview_values(&surface.category, |name| surface.input(name)?.value.as_deref().and_then(parse_value))
+            });
+            match (surface, translated) {
+                (_, Some(values)) => println!("  {}: viewport diffuse {:?}, roughness {}", material.name, values.diffuse_color, values.roughness),
+                (Some(surface), None) => println!("  {}: no viewport translation for {}", material.name, surface.category),
+                (None, None) => println!("  {}: no surface shader", material.name),
+            }
+        }
+        
+        let (materials, material_path) = with_usd_engine(|engine| -> Result<(Vec<String>, String), String> {
+            let materials = engine.reference_materialx(&stage_id, &prim_path, &file_path)?;
+            let material_path = match &material_name {
+                Some(name) => materials.iter()
+                    .find(|path| path.rsplit('/').next() == Some(name.as_str()))
+                    .ok_or_else(|| format!("'{}' has no material '{}'", file_path, name))?,
+                None => materials.first()
+                    .ok_or_else(|| format!("'{}' has no materials", file_path))?,
+            }.clone();
+            if let Some(target) = &bind_to {
+                engine.edit_relationship_targets(&stage_id, target, "material:binding", std::slice::from_ref(&material_path), RelationshipEdit::Set)?;
+            }
+            Ok((materials, material_path))
+        })?;
+        println!("✓ Referenced MaterialX document '{}' at {} ({} materials)", file_path, prim_path, materials.len());
+        
+        Ok(HashMap::from([
+            ("Stage".to_string(), inputs["Stage"].clone()),
+            ("Material Path".to_string(), NodeData::String(material_path)),
+            ("Materials".to_string(), NodeData::String(materials.join(", "))),
+        ]))
+    }
+}
+
+impl ModularNode for USDMaterialXNode {
+    const NAME: &'static str = "USD MaterialX";
+    const HELP: &'static NodeHelp = &HELP;
+    
+    fn metadata() -> NodeMetadata {
+        NodeMetadata::new(
+            "USD_MaterialX",
+            "MaterialX",
+            NodeCategory::new(&["USD", "Shading"]),
+            HELP.summary
+        )
+        .with_color(Color32::from_rgb(180, 100, 180))
+        .with_icon("✳")
+        .with_inputs(child_prim_inputs("Prim").into_iter().chain([
+            PortDefinition::optional("File Path", DataType::String)
+                .with_description(".mtlx document, overriding the parameter"),
+            PortDefinition::optional("Bind To", DataType::String)
+                .with_description("Prim to bind the material to, overriding the parameter"),
+        ]).collect())
+        .with_outputs(vec![
+            PortDefinition::required("Stage", DataType::String)
+                .with_description("Stage with the referenced materials"),
+            PortDefinition::required("Material Path", DataType::String)
+                .with_description("Chosen material"),
+            PortDefinition::optional("Materials", DataType::String)
+                .with_description("Every referenced material, comma separated"),
+        ])
+        .with_panel_type(PanelType::Parameter)
+        .with_workspace_compatibility(vec!["3D"])
+    }
+    
+    fn parameters() -> Vec<ParameterSpec> {
+        vec![
+            ParameterSpec::text("file_path", "File Path", ""),
+            ParameterSpec::text("parent_path", "Parent Path", "/World/Looks"),
+            ParameterSpec::text("name", "Name", "MaterialX"),
+            ParameterSpec::text("material", "Material", ""),
+            ParameterSpec::text("bind_to", "Bind To", ""),
+        ]
+    }
+    
+    fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        USDMaterialXLogic::execute(inputs, parameters)
+    }
+}
@@ -1,15 +1,15 @@
 //! USD Shading and material nodes
+//!
+//! Each node is a `ModularNode`; `modular_nodes!` declares its module and
+//! lists it for registration with the plugin.
 
-pub mod material;
-pub mod shader;
-pub mod preview_surface;
-pub mod texture_reader;
-pub mod primvar_reader;
-pub mod node_graph;
+use crate::modular::modular_nodes;
 
-pub use material::{USDMaterialNode, USDMaterialLogic};
-pub use shader::{USDShaderNode, USDShaderLogic};
-pub use preview_surface::{USDPreviewSurfaceNode, USDPreviewSurfaceLogic};
-pub use texture_reader::{USDTextureReaderNode, USDTextureReaderLogic};
-pub use primvar_reader::{USDPrimvarReaderNode, USDPrimvarReaderLogic};
-pub use node_graph::{USDNodeGraphNode, USDNodeGraphLogic};
\ No newline at end of file
+modular_nodes! {
+    shader => USDShaderNode,
+    texture_reader => USDTextureReaderNode,
+    material => USDMaterialNode,
+    preview_surface => USDPreviewSurfaceNode,
+    primvar_reader => USDPrimvarReaderNode,
+    materialx => USDMaterialXNode,
+}
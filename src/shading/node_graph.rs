@@ -1,8 +0,0 @@
-//! USD Node Graph node - stub implementation
-
-pub struct USDNodeGraphNode;
-pub struct USDNodeGraphLogic;
-
-impl Default for USDNodeGraphNode {
-    fn default() -> Self { Self }
-}
\ No newline at end of file
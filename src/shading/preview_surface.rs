@@ -1,8 +1,102 @@
-//! USD Preview Surface node - stub implementation
+//! USD Preview Surface shader node
 
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::core::usd_value::UsdValue;
+use crate::modular::{child_prim_inputs, child_prim_path, define_prim, float_value, input_stage, parse_color, text_value, ModularNode, ParameterSpec};
+use crate::ui::help::{NodeHelp, PREVIEW_SURFACE};
+
+/// Help for the Preview Surface node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_PreviewSurface",
+    summary: "Creates a UsdPreviewSurface shader, typically under a material",
+    details: "Defines a UsdPreviewSurface shader with the given inputs, usually as a child of a material.",
+    ports: &[
+        ("Stage", "stage_0"),
+        ("Parent Path", "/World/Looks/Red"),
+        ("Shader Path", "/World/Looks/Red/PreviewSurface"),
+    ],
+    samples: &[PREVIEW_SURFACE],
+};
+
+/// USD Preview Surface node with parameter controls
+#[derive(Default)]
 pub struct USDPreviewSurfaceNode;
+
+/// Core logic for UsdPreviewSurface shader creation
 pub struct USDPreviewSurfaceLogic;
 
-impl Default for USDPreviewSurfaceNode {
-    fn default() -> Self { Self }
-}
\ No newline at end of file
+impl USDPreviewSurfaceLogic {
+    /// Execute the shader creation operation
+    pub fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        let stage_id = input_stage(inputs)?;
+        let shader_path = child_prim_path(inputs, parameters, "PreviewSurface");
+        let color = |input: &str, parameter: &str, default: [f32; 3]| {
+            let color = text_value(inputs, input, parameters, parameter)
+                .and_then(|color| parse_color(&color))
+                .unwrap_or(default);
+            UsdValue::Color3(color.map(f64::from))
+        };
+        let float = |input: &str, parameter: &str, default: f32| UsdValue::Float(float_value(inputs, input, parameters, parameter, default));
+        
+        let shader = define_prim(&stage_id, &shader_path, "Shader", vec![
+            ("info:id", UsdValue::Token("UsdPreviewSurface".to_string())),
+            ("inputs:diffuseColor", color("Diffuse Color", "diffuse_color", [0.18; 3])),
+            ("inputs:emissiveColor", color("Emissive Color", "emissive_color", [0.0; 3])),
+            ("inputs:metallic", float("Metallic", "metallic", 0.0)),
+            ("inputs:roughness", float("Roughness", "roughness", 0.5)),
+            ("inputs:opacity", float("Opacity", "opacity", 1.0)),
+            ("inputs:ior", float("IOR", "ior", 1.5)),
+            ("inputs:clearcoat", float("Clearcoat", "clearcoat", 0.0)),
+        ])?;
+        println!("✓ Created UsdPreviewSurface shader: {}", shader.path);
+        
+        Ok(HashMap::from([
+            ("Stage".to_string(), inputs["Stage"].clone()),
+            ("Shader Path".to_string(), NodeData::String(shader.path)),
+        ]))
+    }
+}
+
+impl ModularNode for USDPreviewSurfaceNode {
+    const NAME: &'static str = "USD Preview Surface";
+    const HELP: &'static NodeHelp = &HELP;
+    
+    fn metadata() -> NodeMetadata {
+        NodeMetadata::new(
+            "USD_PreviewSurface",
+            "Preview Surface",
+            NodeCategory::new(&["USD", "Shading"]),
+            HELP.summary
+        )
+        .with_color(Color32::from_rgb(180, 100, 180))
+        .with_icon("◐")
+        .with_inputs(child_prim_inputs("Shader"))
+        .with_outputs(vec![
+            PortDefinition::required("Stage", DataType::String)
+                .with_description("Stage with the shader"),
+            PortDefinition::required("Shader Path", DataType::String)
+                .with_description("Created shader path"),
+        ])
+        .with_panel_type(PanelType::Parameter)
+        .with_workspace_compatibility(vec!["3D"])
+    }
+    
+    fn parameters() -> Vec<ParameterSpec> {
+        vec![
+            ParameterSpec::text("parent_path", "Material Path", "/World/Looks/Material"),
+            ParameterSpec::text("name", "Name", "PreviewSurface"),
+            ParameterSpec::color("diffuse_color", "Diffuse Color", [0.18, 0.18, 0.18]),
+            ParameterSpec::color("emissive_color", "Emissive Color", [0.0, 0.0, 0.0]),
+            ParameterSpec::float("metallic", "Metallic", 0.0, 0.0, 1.0),
+            ParameterSpec::float("roughness", "Roughness", 0.5, 0.0, 1.0),
+            ParameterSpec::float("opacity", "Opacity", 1.0, 0.0, 1.0),
+            ParameterSpec::float("ior", "IOR", 1.5, 1.0, 3.0),
+            ParameterSpec::float("clearcoat", "Clearcoat", 0.0, 0.0, 1.0),
+        ]
+    }
+    
+    fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        USDPreviewSurfaceLogic::execute(inputs, parameters)
+    }
+}
@@ -1,8 +1,87 @@
-//! USD Shader node - stub implementation
+//! USD Shader node
 
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::core::usd_value::UsdValue;
+use crate::modular::{child_prim_inputs, child_prim_path, define_prim, input_stage, text_value, ModularNode, ParameterSpec};
+use crate::ui::help::{NodeHelp, PREVIEW_SURFACE};
+
+/// Help for the Shader node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_Shader",
+    summary: "Creates a UsdShade shader of any shader id",
+    details: "Defines a UsdShade Shader prim with the given info:id, such as UsdTransform2d or a renderer's own shader, to build a shading network under a material. Preview Surface, Texture and Primvar Reader author the common preview shaders with their inputs.",
+    ports: &[
+        ("Stage", "stage_0"),
+        ("Shader Id", "UsdTransform2d"),
+        ("Shader Path", "/World/Looks/Material/Transform"),
+    ],
+    samples: &[PREVIEW_SURFACE],
+};
+
+/// USD Shader node with parameter controls
+#[derive(Default)]
 pub struct USDShaderNode;
+
+/// Core logic for UsdShade shader creation
 pub struct USDShaderLogic;
 
-impl Default for USDShaderNode {
-    fn default() -> Self { Self }
-}
\ No newline at end of file
+impl USDShaderLogic {
+    /// Execute the shader creation operation
+    pub fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        let stage_id = input_stage(inputs)?;
+        let shader_path = child_prim_path(inputs, parameters, "Shader");
+        let shader_id = text_value(inputs, "Shader Id", parameters, "shader_id")
+            .ok_or_else(|| "No shader id set".to_string())?;
+        
+        let shader = define_prim(&stage_id, &shader_path, "Shader", vec![
+            ("info:id", UsdValue::Token(shader_id.clone())),
+        ])?;
+        println!("✓ Created {} shader: {}", shader_id, shader.path);
+        
+        Ok(HashMap::from([
+            ("Stage".to_string(), inputs["Stage"].clone()),
+            ("Shader Path".to_string(), NodeData::String(shader.path)),
+        ]))
+    }
+}
+
+impl ModularNode for USDShaderNode {
+    const NAME: &'static str = "USD Shader";
+    const HELP: &'static NodeHelp = &HELP;
+    
+    fn metadata() -> NodeMetadata {
+        NodeMetadata::new(
+            "USD_Shader",
+            "Shader",
+            NodeCategory::new(&["USD", "Shading"]),
+            HELP.summary
+        )
+        .with_color(Color32::from_rgb(180, 100, 180))
+        .with_icon("🔮")
+        .with_inputs(child_prim_inputs("Shader").into_iter().chain([
+            PortDefinition::optional("Shader Id", DataType::String)
+                .with_description("info:id of the shader, overriding the parameter"),
+        ]).collect())
+        .with_outputs(vec![
+            PortDefinition::required("Stage", DataType::String)
+                .with_description("Stage with the shader"),
+            PortDefinition::required("Shader Path", DataType::String)
+                .with_description("Created shader path"),
+        ])
+        .with_panel_type(PanelType::Parameter)
+        .with_workspace_compatibility(vec!["3D"])
+    }
+    
+    fn parameters() -> Vec<ParameterSpec> {
+        vec![
+            ParameterSpec::text("parent_path", "Material Path", "/World/Looks/Material"),
+            ParameterSpec::text("name", "Name", "Shader"),
+            ParameterSpec::text("shader_id", "Shader Id", "UsdPreviewSurface").with_attribute("info:id", "token"),
+        ]
+    }
+    
+    fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        USDShaderLogic::execute(inputs, parameters)
+    }
+}
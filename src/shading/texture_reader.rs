@@ -1,8 +1,104 @@
-//! USD Texture Reader node - stub implementation
+//! USD Texture (UsdUVTexture) shader node
 
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::core::usd_value::UsdValue;
+use crate::modular::{child_prim_inputs, child_prim_path, define_prim, input_stage, text_value, ModularNode, ParameterSpec};
+use crate::ui::help::{NodeHelp, PREVIEW_SURFACE};
+
+/// Help for the Texture node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_Texture",
+    summary: "Creates a UsdUVTexture shader reading an image file",
+    details: "Defines a UsdUVTexture shader reading File with the given wrap modes and source color space, to connect into a preview surface input. With Live Edit, File and the wrap modes are set on the shader as you edit them and follow changes made to it elsewhere.",
+    ports: &[
+        ("Stage", "stage_0"),
+        ("File", "textures/wood_diffuse.png"),
+        ("Shader Path", "/World/Looks/Material/DiffuseTexture"),
+    ],
+    samples: &[PREVIEW_SURFACE],
+};
+
+/// Wrap modes of UsdUVTexture
+const WRAP_MODES: [&str; 5] = ["useMetadata", "black", "clamp", "repeat", "mirror"];
+
+/// Source color spaces of UsdUVTexture
+const COLOR_SPACES: [&str; 3] = ["auto", "raw", "sRGB"];
+
+/// USD Texture node with parameter controls
+#[derive(Default)]
 pub struct USDTextureReaderNode;
+
+/// Core logic for UsdUVTexture shader creation
 pub struct USDTextureReaderLogic;
 
-impl Default for USDTextureReaderNode {
-    fn default() -> Self { Self }
-}
\ No newline at end of file
+impl USDTextureReaderLogic {
+    /// Execute the shader creation operation
+    pub fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        let stage_id = input_stage(inputs)?;
+        let shader_path = child_prim_path(inputs, parameters, "Texture");
+        let file = text_value(inputs, "File", parameters, "file")
+            .ok_or_else(|| "No texture file set".to_string())?;
+        let token = |parameter: &str, default: &str| {
+            let value = parameters.get(parameter).and_then(|data| data.as_string()).unwrap_or(default);
+            UsdValue::Token(value.to_string())
+        };
+        
+        let shader = define_prim(&stage_id, &shader_path, "Shader", vec![
+            ("info:id", UsdValue::Token("UsdUVTexture".to_string())),
+            ("inputs:file", UsdValue::Asset(file.clone())),
+            ("inputs:wrapS", token("wrap_s", "useMetadata")),
+            ("inputs:wrapT", token("wrap_t", "useMetadata")),
+            ("inputs:sourceColorSpace", token("source_color_space", "auto")),
+        ])?;
+        println!("✓ Created UsdUVTexture shader: {} reading '{}'", shader.path, file);
+        
+        Ok(HashMap::from([
+            ("Stage".to_string(), inputs["Stage"].clone()),
+            ("Shader Path".to_string(), NodeData::String(shader.path)),
+        ]))
+    }
+}
+
+impl ModularNode for USDTextureReaderNode {
+    const NAME: &'static str = "USD Texture";
+    const HELP: &'static NodeHelp = &HELP;
+    
+    fn metadata() -> NodeMetadata {
+        NodeMetadata::new(
+            "USD_Texture",
+            "Texture",
+            NodeCategory::new(&["USD", "Shading"]),
+            HELP.summary
+        )
+        .with_color(Color32::from_rgb(180, 100, 180))
+        .with_icon("🖼️")
+        .with_inputs(child_prim_inputs("Shader").into_iter().chain([
+            PortDefinition::optional("File", DataType::String)
+                .with_description("Image file, overriding the parameter"),
+        ]).collect())
+        .with_outputs(vec![
+            PortDefinition::required("Stage", DataType::String)
+                .with_description("Stage with the shader"),
+            PortDefinition::required("Shader Path", DataType::String)
+                .with_description("Created shader path"),
+        ])
+        .with_panel_type(PanelType::Parameter)
+        .with_workspace_compatibility(vec!["3D"])
+    }
+    
+    fn parameters() -> Vec<ParameterSpec> {
+        vec![
+            ParameterSpec::text("parent_path", "Material Path", "/World/Looks/Material"),
+            ParameterSpec::text("name", "Name", "Texture"),
+            ParameterSpec::text("file", "File", "").with_attribute("inputs:file", "asset"),
+            ParameterSpec::choice("wrap_s", "Wrap S", &WRAP_MODES, "useMetadata").with_attribute("inputs:wrapS", "token"),
+            ParameterSpec::choice("wrap_t", "Wrap T", &WRAP_MODES, "useMetadata").with_attribute("inputs:wrapT", "token"),
+            ParameterSpec::choice("source_color_space", "Source Color Space", &COLOR_SPACES, "auto").with_attribute("inputs:sourceColorSpace", "token"),
+        ]
+    }
+    
+    fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        USDTextureReaderLogic::execute(inputs, parameters)
+    }
+}
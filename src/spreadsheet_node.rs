@@ -0,0 +1,493 @@
+//! USD Spreadsheet node - bulk review and editing of prim attributes
+
+use nodle_plugin_sdk::*;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use crate::core::usd_engine::with_usd_engine;
+use crate::ui::search::{ParameterSearch, SEARCH_PARAMETER};
+use crate::ui::help::{NodeHelp, KITCHEN_SET};
+use crate::ui::keyboard::{focus_marked, handle_key};
+use crate::ui::palette::status_label;
+use crate::registry::NodeTypeFactory;
+
+/// Help for the Spreadsheet node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_Spreadsheet",
+    summary: "Review and edit attributes of many prims in a table",
+    details: "One row per prim and one column per listed attribute. Edited cells are written back in a single batch.",
+    ports: &[
+        ("Stage", "loaded_0"),
+        ("Table", "path,visibility\n/World/Chair,inherited"),
+    ],
+    samples: &[KITCHEN_SET],
+};
+
+/// Factory of the Spreadsheet node, for `crate::node_factories`
+pub fn factories() -> Vec<Box<dyn NodeFactory>> {
+    vec![NodeTypeFactory::boxed(metadata, |position| PluginNodeHandle::new(Box::new(USDSpreadsheetNode::new(position))))]
+}
+
+/// Metadata of the Spreadsheet node
+fn metadata() -> NodeMetadata {
+    NodeMetadata::new(
+        "USD_Spreadsheet",
+        "Spreadsheet",
+        NodeCategory::new(&["USD", "Viewport"]),
+        HELP.summary
+    )
+    .with_color(Color32::from_rgb(120, 120, 120))
+    .with_icon("📊")
+    .with_inputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("USD stage to edit"),
+    ])
+    .with_outputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("Pass-through USD stage"),
+        PortDefinition::optional("Table", DataType::String)
+            .with_description("Visible rows as CSV"),
+    ])
+    .with_panel_type(PanelType::Parameter)
+    .with_workspace_compatibility(vec!["3D"])
+}
+
+/// Prefix for cell parameters - "cell|<prim path>|<attribute>"
+const CELL_PREFIX: &str = "cell|";
+
+/// One prim row in the spreadsheet
+#[derive(Debug, Clone)]
+struct SpreadsheetRow {
+    prim_path: String,
+    prim_type: String,
+    cells: Vec<String>,
+}
+
+/// USD Spreadsheet node showing prims (rows) by attributes (columns)
+pub struct USDSpreadsheetNode {
+    id: String,
+    position: Pos2,
+    stage_ref: String,
+    stage_id: String,
+    /// Comma separated attribute names shown as columns
+    columns: String,
+    rows: Vec<SpreadsheetRow>,
+    search: ParameterSearch,
+    /// Column index to sort by, None sorts by prim path
+    sort_column: Option<usize>,
+    sort_descending: bool,
+    last_error: Option<String>,
+}
+
+impl USDSpreadsheetNode {
+    pub fn new(position: Pos2) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            position,
+            stage_ref: String::new(),
+            stage_id: String::new(),
+            columns: "visibility, purpose".to_string(),
+            rows: Vec::new(),
+            search: ParameterSearch::default(),
+            sort_column: None,
+            sort_descending: false,
+            last_error: None,
+        }
+    }
+    
+    /// Attribute names parsed from the columns parameter
+    fn column_names(&self) -> Vec<String> {
+        self.columns
+            .split(',')
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .collect()
+    }
+    
+    /// Re-read all rows and cell values from the USD engine
+    fn refresh(&mut self) {
+        let stage_ref = self.stage_ref.clone();
+        let columns = self.column_names();
+        
+        let result = with_usd_engine(|engine| -> Result<(String, Vec<SpreadsheetRow>), String> {
+            let stage = engine.resolve_stage(&stage_ref)?;
+            let rows = engine.get_stage_prims(&stage.identifier)
+                .into_iter()
+                .map(|prim| SpreadsheetRow {
+                    prim_path: prim.path.clone(),
+                    prim_type: prim.prim_type.clone(),
+                    cells: columns.iter()
+                        .map(|attr| engine.get_attribute(&stage.identifier, &prim.path, attr).unwrap_or_default())
+                        .collect(),
+                })
+                .collect();
+            Ok((stage.identifier, rows))
+        });
+        
+        match result {
+            Ok((stage_id, rows)) => {
+                self.stage_id = stage_id;
+                self.rows = rows;
+                self.last_error = None;
+            }
+            Err(e) => {
+                self.rows.clear();
+                self.last_error = Some(e);
+            }
+        }
+        self.sort_rows();
+    }
+    
+    /// Sort rows by the current sort column, comparing numerically when both values are numbers
+    fn sort_rows(&mut self) {
+        let column = self.sort_column;
+        let descending = self.sort_descending;
+        self.rows.sort_by(|a, b| {
+            let ordering = match column {
+                Some(index) => compare_cells(
+                    a.cells.get(index).map(String::as_str).unwrap_or(""),
+                    b.cells.get(index).map(String::as_str).unwrap_or(""),
+                ),
+                None => a.prim_path.cmp(&b.prim_path),
+            };
+            // Ties stay in prim path order either way
+            let ordering = if descending { ordering.reverse() } else { ordering };
+            ordering.then_with(|| a.prim_path.cmp(&b.prim_path))
+        });
+    }
+    
+    /// Commit a cell edit to the stage
+    fn commit_cell(&mut self, prim_path: &str, attr_name: &str, value: &str) -> Result<(), String> {
+        with_usd_engine(|engine| engine.set_attribute(&self.stage_id, prim_path, attr_name, value))?;
+        
+        if let Some(index) = self.column_names().iter().position(|name| name == attr_name) {
+            if let Some(row) = self.rows.iter_mut().find(|row| row.prim_path == prim_path) {
+                if let Some(cell) = row.cells.get_mut(index) {
+                    *cell = value.to_string();
+                }
+            }
+        }
+        Ok(())
+    }
+    
+    /// Rows passing the search filter
+    fn visible_rows(&self) -> impl Iterator<Item = &SpreadsheetRow> {
+        self.rows.iter()
+            .filter(|row| self.search.matches(&row.prim_path) || self.search.matches(&row.prim_type))
+    }
+    
+    /// Table contents as CSV for downstream nodes
+    fn to_csv(&self) -> String {
+        let mut header = vec!["path".to_string(), "type".to_string()];
+        header.extend(self.column_names());
+        
+        let mut lines = vec![header.iter().map(|field| csv_field(field)).collect::<Vec<_>>().join(",")];
+        for row in self.visible_rows() {
+            let mut fields = vec![csv_field(&row.prim_path), csv_field(&row.prim_type)];
+            fields.extend(row.cells.iter().map(|cell| csv_field(cell)));
+            lines.push(fields.join(","));
+        }
+        lines.join("\n")
+    }
+    
+    fn sort_label(&self, column: Option<usize>, name: &str) -> String {
+        let arrow = if self.sort_column == column {
+            if self.sort_descending { " ▼" } else { " ▲" }
+        } else {
+            ""
+        };
+        format!("Sort by {}{}", name, arrow)
+    }
+}
+
+/// Compare two cell values numerically when possible, otherwise lexically
+fn compare_cells(a: &str, b: &str) -> Ordering {
+    match (a.trim().parse::<f64>(), b.trim().parse::<f64>()) {
+        (Ok(x), Ok(y)) => x.partial_cmp(&y).unwrap_or(Ordering::Equal),
+        _ => a.cmp(b),
+    }
+}
+
+/// Parameter name of the cell of a prim's attribute
+fn cell_parameter(prim_path: &str, attr_name: &str) -> String {
+    format!("{}{}|{}", CELL_PREFIX, prim_path, attr_name)
+}
+
+/// Prim path and attribute name of a cell parameter
+///
+/// Prim paths cannot contain '|', so the attribute is everything after the
+/// path's separator, '|' included.
+fn parse_cell_parameter(parameter: &str) -> Option<(&str, &str)> {
+    let (prim_path, attr_name) = parameter.strip_prefix(CELL_PREFIX)?.split_once('|')?;
+    if prim_path.is_empty() || attr_name.is_empty() {
+        return None;
+    }
+    Some((prim_path, attr_name))
+}
+
+/// Quote a CSV field when needed
+fn csv_field(value: &str) -> String {
+    if value.contains(',') || value.contains('"') || value.contains('\n') {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+impl PluginNode for USDSpreadsheetNode {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+    
+    fn position(&self) -> Pos2 {
+        self.position
+    }
+    
+    fn set_position(&mut self, position: Pos2) {
+        self.position = position;
+    }
+    
+    fn get_parameter_ui(&self) -> ParameterUI {
+        let mut elements = Vec::new();
+        
+        elements.push(UIElement::Heading("USD Spreadsheet".to_string()));
+        elements.push(UIElement::Separator);
+        
+        elements.push(UIElement::TextEdit {
+            label: "Attributes".to_string(),
+            value: self.columns.clone(),
+            parameter_name: "columns".to_string(),
+        });
+        elements.push(self.search.element());
+        
+        if let Some(error) = &self.last_error {
+            elements.push(status_label(&format!("⚠ {}", error)));
+        }
+        
+        if self.stage_ref.is_empty() {
+            elements.push(UIElement::Label("No USD stage connected".to_string()));
+            elements.extend(HELP.section());
+            return ParameterUI { elements };
+        }
+        
+        // Sorting controls
+        let columns = self.column_names();
+        elements.push(UIElement::Separator);
+        elements.push(UIElement::Button {
+            label: self.sort_label(None, "path"),
+            action: "sort:path".to_string(),
+        });
+        for (index, name) in columns.iter().enumerate() {
+            elements.push(UIElement::Button {
+                label: self.sort_label(Some(index), name),
+                action: format!("sort:{}", index),
+            });
+        }
+        elements.push(UIElement::Button {
+            label: "Refresh".to_string(),
+            action: "refresh".to_string(),
+        });
+        
+        // Table
+        elements.push(UIElement::Separator);
+        elements.push(UIElement::Label(format!("Prim | {}", columns.join(" | "))));
+        
+        let mut shown = 0;
+        for row in self.visible_rows() {
+            shown += 1;
+            elements.push(UIElement::Separator);
+            elements.push(UIElement::Label(format!("{} ({})", row.prim_path, row.prim_type)));
+            for (name, value) in columns.iter().zip(&row.cells) {
+                elements.push(UIElement::TextEdit {
+                    label: name.clone(),
+                    value: value.clone(),
+                    parameter_name: cell_parameter(&row.prim_path, name),
+                });
+            }
+        }
+        
+        elements.push(UIElement::Separator);
+        elements.push(UIElement::Label(format!("Showing {} of {} prims", shown, self.rows.len())));
+        elements.extend(HELP.section());
+        
+        ParameterUI { elements: focus_marked(&self.id, elements) }
+    }
+    
+    fn handle_ui_action(&mut self, action: UIAction) -> Vec<ParameterChange> {
+        if let Some(changes) = handle_key(self, &[], &action) {
+            return changes;
+        }
+        
+        let mut changes = Vec::new();
+        
+        if let Some(change) = self.search.handle_action(&action) {
+            changes.push(change);
+            return changes;
+        }
+        
+        match action {
+            UIAction::ParameterChanged { parameter, value } => {
+                if parameter == "columns" {
+                    if let Some(columns) = value.as_string() {
+                        self.columns = columns.to_string();
+                        self.sort_column = None;
+                        self.refresh();
+                        changes.push(ParameterChange {
+                            parameter: "columns".to_string(),
+                            value: NodeData::String(self.columns.clone()),
+                        });
+                    }
+                } else if let Some((prim_path, attr_name)) = parse_cell_parameter(&parameter) {
+                    if let Some(new_value) = value.as_string() {
+                        match self.commit_cell(prim_path, attr_name, new_value) {
+                            Ok(()) => {
+                                self.last_error = None;
+                                changes.push(ParameterChange {
+                                    parameter: parameter.clone(),
+                                    value: NodeData::String(new_value.to_string()),
+                                });
+                            }
+                            Err(e) => self.last_error = Some(e),
+                        }
+                    }
+                }
+            }
+            UIAction::ButtonClicked { action } => {
+                if action == "refresh" {
+                    self.refresh();
+                } else if let Some(column) = action.strip_prefix("sort:") {
+                    let column = column.parse::<usize>().ok();
+                    if self.sort_column == column {
+                        self.sort_descending = !self.sort_descending;
+                    } else {
+                        self.sort_column = column;
+                        self.sort_descending = false;
+                    }
+                    self.sort_rows();
+                }
+            }
+        }
+        
+        changes
+    }
+    
+    fn get_parameter(&self, name: &str) -> Option<NodeData> {
+        match name {
+            "columns" => Some(NodeData::String(self.columns.clone())),
+            SEARCH_PARAMETER => Some(NodeData::String(self.search.query.clone())),
+            _ => None,
+        }
+    }
+    
+    fn set_parameter(&mut self, name: &str, value: NodeData) {
+        match name {
+            "columns" => {
+                if let Some(columns) = value.as_string() {
+                    self.columns = columns.to_string();
+                }
+            }
+            SEARCH_PARAMETER => {
+                if let Some(query) = value.as_string() {
+                    self.search.query = query.to_string();
+                }
+            }
+            _ => {}
+        }
+    }
+    
+    fn process(&mut self, inputs: &HashMap<String, NodeData>) -> HashMap<String, NodeData> {
+        let mut outputs = HashMap::new();
+        
+        match inputs.get("Stage").and_then(|data| data.as_string()) {
+            Some(stage_ref) => {
+                if stage_ref != self.stage_ref {
+                    self.stage_ref = stage_ref.to_string();
+                    self.refresh();
+                }
+                outputs.insert("Stage".to_string(), NodeData::String(self.stage_ref.clone()));
+                outputs.insert("Table".to_string(), NodeData::String(self.to_csv()));
+            }
+            None => {
+                self.stage_ref.clear();
+                self.stage_id.clear();
+                self.rows.clear();
+            }
+        }
+        
+        outputs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn row(prim_path: &str, cells: &[&str]) -> SpreadsheetRow {
+        SpreadsheetRow {
+            prim_path: prim_path.to_string(),
+            prim_type: "Xform".to_string(),
+            cells: cells.iter().map(|cell| cell.to_string()).collect(),
+        }
+    }
+    
+    fn row_paths(node: &USDSpreadsheetNode) -> Vec<&str> {
+        node.rows.iter().map(|row| row.prim_path.as_str()).collect()
+    }
+    
+    #[test]
+    fn cells_compare_numerically_when_both_are_numbers() {
+        assert_eq!(compare_cells("9", "10"), Ordering::Less);
+        assert_eq!(compare_cells(" 2.5", "2.50 "), Ordering::Equal);
+        assert_eq!(compare_cells("-1", "0.5"), Ordering::Less);
+        // Anything else compares lexically
+        assert_eq!(compare_cells("9", "10x"), Ordering::Greater);
+        assert_eq!(compare_cells("inherited", "invisible"), Ordering::Less);
+        assert_eq!(compare_cells("", "0"), Ordering::Less);
+    }
+    
+    #[test]
+    fn descending_sort_keeps_ties_in_path_order() {
+        let mut node = USDSpreadsheetNode::new(Pos2::new(0.0, 0.0));
+        node.columns = "size".to_string();
+        node.rows = vec![row("/World/C", &["10"]), row("/World/B", &["9"]), row("/World/A", &["10"]), row("/World/D", &["9"])];
+        
+        node.sort_column = Some(0);
+        node.sort_rows();
+        assert_eq!(row_paths(&node), ["/World/B", "/World/D", "/World/A", "/World/C"]);
+        
+        node.sort_descending = true;
+        node.sort_rows();
+        assert_eq!(row_paths(&node), ["/World/A", "/World/C", "/World/B", "/World/D"]);
+        
+        node.sort_column = None;
+        node.sort_rows();
+        assert_eq!(row_paths(&node), ["/World/D", "/World/C", "/World/B", "/World/A"]);
+    }
+    
+    #[test]
+    fn csv_quotes_fields_with_separators_quotes_and_newlines() {
+        assert_eq!(csv_field("inherited"), "inherited");
+        assert_eq!(csv_field("(0, 1, 0)"), "\"(0, 1, 0)\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+        
+        let mut node = USDSpreadsheetNode::new(Pos2::new(0.0, 0.0));
+        node.columns = "visibility, xformOp:translate".to_string();
+        node.rows = vec![row("/World/Chair", &["inherited", "(1, 0, 2)"])];
+        assert_eq!(node.to_csv(), "path,type,visibility,xformOp:translate\n/World/Chair,Xform,inherited,\"(1, 0, 2)\"");
+    }
+    
+    #[test]
+    fn cell_parameters_round_trip() {
+        for (prim_path, attr_name) in [
+            ("/World/Chair", "visibility"),
+            ("/World/Lights/Key", "inputs:intensity"),
+            ("/World/Chair", "userProperties:a|b"),
+        ] {
+            let parameter = cell_parameter(prim_path, attr_name);
+            assert_eq!(parse_cell_parameter(&parameter), Some((prim_path, attr_name)));
+        }
+        assert_eq!(parse_cell_parameter("columns"), None);
+        assert_eq!(parse_cell_parameter("cell|/World/Chair"), None);
+        assert_eq!(parse_cell_parameter("cell|/World/Chair|"), None);
+    }
+}
@@ -1,8 +1,73 @@
-//! Clear Stage node module - stub implementation
+//! Clear Stage node module - removes every opinion from a stage's edit target
 
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::core::usd_engine::with_usd_engine;
+use crate::modular::{flag_value, input_stage, ModularNode, ParameterSpec};
+use crate::ui::help::NodeHelp;
+
+/// Help for the Clear Stage node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_ClearStage",
+    summary: "Removes every prim and value authored in the stage's edit target layer",
+    details: "Clears the edit target layer when Clear is clicked, leaving sublayers and referenced files untouched. Useful to rebuild a generated layer from scratch.",
+    ports: &[
+        ("Stage", "stage_0"),
+    ],
+    samples: &[],
+};
+
+/// Clear Stage node with parameter controls
+#[derive(Default)]
 pub struct ClearStageNode;
+
+/// Core logic for clearing stages
 pub struct ClearStageLogic;
 
-impl Default for ClearStageNode {
-    fn default() -> Self { Self }
-}
\ No newline at end of file
+impl ClearStageLogic {
+    /// Execute the clear operation when it was requested
+    ///
+    /// Clearing only runs from the button, never because inputs changed.
+    pub fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        let stage_id = input_stage(inputs)?;
+        if flag_value(parameters, "clear", false) {
+            with_usd_engine(|engine| engine.clear_stage(&stage_id))?;
+            println!("✓ Cleared stage '{}'", stage_id);
+        }
+        Ok(HashMap::from([("Stage".to_string(), inputs["Stage"].clone())]))
+    }
+}
+
+impl ModularNode for ClearStageNode {
+    const NAME: &'static str = "USD Clear Stage";
+    const HELP: &'static NodeHelp = &HELP;
+    
+    fn metadata() -> NodeMetadata {
+        NodeMetadata::new(
+            "USD_ClearStage",
+            "Clear Stage",
+            NodeCategory::new(&["USD", "Stage"]),
+            HELP.summary
+        )
+        .with_color(Color32::from_rgb(80, 150, 200))
+        .with_icon("🧹")
+        .with_inputs(vec![
+            PortDefinition::required("Stage", DataType::String)
+                .with_description("USD stage to clear"),
+        ])
+        .with_outputs(vec![
+            PortDefinition::required("Stage", DataType::String)
+                .with_description("Cleared stage"),
+        ])
+        .with_panel_type(PanelType::Parameter)
+        .with_workspace_compatibility(vec!["3D"])
+    }
+    
+    fn parameters() -> Vec<ParameterSpec> {
+        vec![ParameterSpec::trigger("clear", "Clear Stage")]
+    }
+    
+    fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        ClearStageLogic::execute(inputs, parameters)
+    }
+}
@@ -0,0 +1,123 @@
+//! Export Graph node module - describes a stage's structure as a Graphviz or Mermaid graph
+//!
+//! The graph is regenerated on every evaluation and output as text; it is
+//! only written to a file when the Write button is clicked.
+
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::core::scene_graph::{scene_graph, GraphFormat};
+use crate::core::usd_engine::with_usd_engine;
+use crate::modular::{flag_value, input_stage, text_value, ModularNode, ParameterSpec};
+use crate::ui::help::NodeHelp;
+
+/// Help for the Export Graph node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_ExportGraph",
+    summary: "Describes the prim hierarchy and composition arcs as a Graphviz or Mermaid graph",
+    details: "Draws each prim under Root Path as a box linked to its parent, and with Composition Arcs its references and payloads as dashed edges to their asset files, inherits and specializes as dashed edges to their class prims, and variant selections listed on the prim. Only arcs authored in the stage's own layers are shown, not those inside referenced assets. The graph is output as text for docs generators; Write saves it to File Path, e.g. a .dot file for Graphviz or a .mmd file for Mermaid.",
+    ports: &[
+        ("Stage", "loaded_0"),
+        ("Graph", "flowchart LR\n    p0[\"World (Xform)\"]"),
+        ("File Path", "docs/kitchen.mmd"),
+    ],
+    samples: &[],
+};
+
+/// Format choices, as `GraphFormat` labels
+const FORMATS: [&str; 2] = ["Graphviz", "Mermaid"];
+
+/// Export Graph node with parameter controls
+#[derive(Default)]
+pub struct ExportGraphNode;
+
+/// Core logic for exporting scene graphs
+pub struct ExportGraphLogic;
+
+impl ExportGraphLogic {
+    /// Execute the graph export, writing the file when it was requested
+    pub fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        let stage_id = input_stage(inputs)?;
+        let format = parameters.get("format").and_then(|data| data.as_string())
+            .and_then(GraphFormat::from_label)
+            .unwrap_or(GraphFormat::Mermaid);
+        let root_path = text_value(inputs, "Root Path", parameters, "root_path")
+            .map(|path| path.trim().trim_end_matches('/').to_string())
+            .unwrap_or_default();
+        let include_arcs = flag_value(parameters, "include_arcs", true);
+        
+        let (title, prims, arcs) = with_usd_engine(|engine| -> Result<_, String> {
+            let stage = engine.resolve_stage(&stage_id)?;
+            let arcs = if include_arcs { engine.get_composition_arcs(&stage.identifier)? } else { Vec::new() };
+            Ok((stage.path, engine.get_prim_hierarchy(&stage.identifier)?, arcs))
+        })?;
+        let under_root = |path: &str| root_path.is_empty()
+            || path.strip_prefix(&root_path).is_some_and(|rest| rest.is_empty() || rest.starts_with('/'));
+        let prims: Vec<_> = prims.into_iter().filter(|prim| under_root(&prim.path)).collect();
+        if prims.is_empty() {
+            return Err(format!("No prims under '{}'", if root_path.is_empty() { "/" } else { &root_path }));
+        }
+        let graph = scene_graph(&title, &prims, &arcs, format);
+        
+        let mut outputs = HashMap::from([
+            ("Stage".to_string(), inputs["Stage"].clone()),
+            ("Graph".to_string(), NodeData::String(graph.clone())),
+        ]);
+        if flag_value(parameters, "write", false) {
+            let path = text_value(inputs, "File Path", parameters, "file_path")
+                .filter(|path| !path.trim().is_empty())
+                .ok_or_else(|| "No file path set".to_string())?;
+            std::fs::write(&path, &graph).map_err(|e| format!("Failed to write '{}': {}", path, e))?;
+            println!("✓ Wrote {} graph of {} prims to {}", format.label(), prims.len(), path);
+            outputs.insert("File Path".to_string(), NodeData::String(path));
+        }
+        Ok(outputs)
+    }
+}
+
+impl ModularNode for ExportGraphNode {
+    const NAME: &'static str = "USD Export Graph";
+    const HELP: &'static NodeHelp = &HELP;
+    
+    fn metadata() -> NodeMetadata {
+        NodeMetadata::new(
+            "USD_ExportGraph",
+            "Export Graph",
+            NodeCategory::new(&["USD", "Stage"]),
+            HELP.summary
+        )
+        .with_color(Color32::from_rgb(80, 150, 200))
+        .with_icon("🕸")
+        .with_inputs(vec![
+            PortDefinition::required("Stage", DataType::String)
+                .with_description("USD stage to describe"),
+            PortDefinition::optional("Root Path", DataType::String)
+                .with_description("Prim whose subtree is drawn, overriding the parameter"),
+            PortDefinition::optional("File Path", DataType::String)
+                .with_description("Output file, overriding the parameter"),
+        ])
+        .with_outputs(vec![
+            PortDefinition::required("Stage", DataType::String)
+                .with_description("Pass-through USD stage"),
+            PortDefinition::required("Graph", DataType::String)
+                .with_description("Graphviz or Mermaid description of the stage"),
+            PortDefinition::optional("File Path", DataType::String)
+                .with_description("Written file, after a write"),
+        ])
+        .with_panel_type(PanelType::Parameter)
+        .with_workspace_compatibility(vec!["3D"])
+    }
+    
+    fn parameters() -> Vec<ParameterSpec> {
+        vec![
+            ParameterSpec::choice("format", "Format", &FORMATS, "Mermaid"),
+            ParameterSpec::text("root_path", "Root Path", "/"),
+            ParameterSpec::toggle("include_arcs", "Composition Arcs", true),
+            ParameterSpec::text("file_path", "File Path", "scene_graph.mmd"),
+            ParameterSpec::trigger("write", "Write"),
+        ]
+    }
+    
+    fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        ExportGraphLogic::execute(inputs, parameters)
+    }
+}
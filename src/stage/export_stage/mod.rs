@@ -1,8 +1,94 @@
-//! Export Stage node module - stub implementation
+//! Export Stage node module - writes a flattened snapshot of a stage on demand
+//!
+//! Unlike the Save Stage node, which resaves whenever the stage changes, this
+//! node only writes when its Export button is clicked.
 
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::core::usd_engine::with_usd_engine;
+use crate::modular::{flag_value, input_stage, text_value, ModularNode, ParameterSpec};
+use crate::ui::help::NodeHelp;
+
+/// Help for the Export Stage node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_ExportStage",
+    summary: "Exports the flattened stage to usda, usdc or usdz on demand",
+    details: "Writes the flattened stage when Export is clicked rather than on every evaluation. Auto picks the format from the file extension.",
+    ports: &[
+        ("Stage", "stage_0"),
+        ("File Path", "/tmp/export.usdc"),
+    ],
+    samples: &[],
+};
+
+/// Format choices; "auto" follows the file extension
+const FORMATS: [&str; 4] = ["auto", "usda", "usdc", "usdz"];
+
+/// Export Stage node with parameter controls
+#[derive(Default)]
 pub struct ExportStageNode;
+
+/// Core logic for exporting stages
 pub struct ExportStageLogic;
 
-impl Default for ExportStageNode {
-    fn default() -> Self { Self }
-}
\ No newline at end of file
+impl ExportStageLogic {
+    /// Execute the export operation when it was requested
+    pub fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        let stage_id = input_stage(inputs)?;
+        let mut outputs = HashMap::from([("Stage".to_string(), inputs["Stage"].clone())]);
+        if !flag_value(parameters, "export", false) {
+            return Ok(outputs);
+        }
+        
+        let path = text_value(inputs, "File Path", parameters, "file_path")
+            .ok_or_else(|| "No file path set".to_string())?;
+        let format = text_value(inputs, "Format", parameters, "format").filter(|format| format != "auto");
+        let written = with_usd_engine(|engine| engine.export_stage(&stage_id, &path, format.as_deref()))?;
+        println!("✓ Exported stage '{}' to {}", stage_id, written);
+        
+        outputs.insert("File Path".to_string(), NodeData::String(written));
+        Ok(outputs)
+    }
+}
+
+impl ModularNode for ExportStageNode {
+    const NAME: &'static str = "USD Export Stage";
+    const HELP: &'static NodeHelp = &HELP;
+    
+    fn metadata() -> NodeMetadata {
+        NodeMetadata::new(
+            "USD_ExportStage",
+            "Export Stage",
+            NodeCategory::new(&["USD", "Stage"]),
+            HELP.summary
+        )
+        .with_color(Color32::from_rgb(80, 150, 200))
+        .with_icon("📤")
+        .with_inputs(vec![
+            PortDefinition::required("Stage", DataType::String)
+                .with_description("USD stage to export"),
+            PortDefinition::optional("File Path", DataType::String)
+                .with_description("Output file, overriding the parameter"),
+        ])
+        .with_outputs(vec![
+            PortDefinition::required("Stage", DataType::String)
+                .with_description("Exported stage"),
+            PortDefinition::optional("File Path", DataType::String)
+                .with_description("Written file, after an export"),
+        ])
+        .with_panel_type(PanelType::Parameter)
+        .with_workspace_compatibility(vec!["3D"])
+    }
+    
+    fn parameters() -> Vec<ParameterSpec> {
+        vec![
+            ParameterSpec::text("file_path", "File Path", "export.usda"),
+            ParameterSpec::choice("format", "Format", &FORMATS, "auto"),
+            ParameterSpec::trigger("export", "Export"),
+        ]
+    }
+    
+    fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        ExportStageLogic::execute(inputs, parameters)
+    }
+}
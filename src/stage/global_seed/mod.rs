@@ -0,0 +1,80 @@
+//! Global Seed node module - sets the random seed every procedural node derives its randomness from
+
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::core::seed::{global_seed, set_global_seed};
+use crate::modular::{float_value, ModularNode, ParameterSpec};
+use crate::ui::help::NodeHelp;
+
+/// Help for the Global Seed node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_GlobalSeed",
+    summary: "Sets the random seed of every procedural node in the graph",
+    details: "Seeded procedural nodes, currently Points with Jitter, draw their randomness from this seed mixed with their own Seed Offset, which is saved with the graph, so they re-cook to the same result, also after reloading, until the seed changes. Changing Seed re-rolls all of them at once to explore variations; the last seed set stays in effect when the node is removed. Chaining a stage through the node makes the nodes downstream cook after it.",
+    ports: &[
+        ("Stage", "stage_0"),
+        ("Seed", "42"),
+    ],
+    samples: &[],
+};
+
+/// Global Seed node with parameter controls
+#[derive(Default)]
+pub struct GlobalSeedNode;
+
+/// Core logic for setting the global seed
+pub struct GlobalSeedLogic;
+
+impl GlobalSeedLogic {
+    /// Execute the seed change, passing a connected stage through
+    pub fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        let seed = float_value(inputs, "Seed", parameters, "seed", 0.0).round().max(0.0) as u64;
+        if seed != global_seed() {
+            set_global_seed(seed);
+            println!("✓ Global seed set to {}", seed);
+        }
+        let mut outputs = HashMap::from([("Seed".to_string(), NodeData::Float(seed as f32))]);
+        if let Some(stage) = inputs.get("Stage") {
+            outputs.insert("Stage".to_string(), stage.clone());
+        }
+        Ok(outputs)
+    }
+}
+
+impl ModularNode for GlobalSeedNode {
+    const NAME: &'static str = "USD Global Seed";
+    const HELP: &'static NodeHelp = &HELP;
+    
+    fn metadata() -> NodeMetadata {
+        NodeMetadata::new(
+            "USD_GlobalSeed",
+            "Global Seed",
+            NodeCategory::new(&["USD", "Stage"]),
+            HELP.summary
+        )
+        .with_color(Color32::from_rgb(80, 150, 200))
+        .with_icon("🎲")
+        .with_inputs(vec![
+            PortDefinition::optional("Stage", DataType::String)
+                .with_description("USD stage passed through, to order the nodes using the seed after this one"),
+            PortDefinition::optional("Seed", DataType::Float)
+                .with_description("Seed, overriding the parameter"),
+        ])
+        .with_outputs(vec![
+            PortDefinition::optional("Stage", DataType::String)
+                .with_description("The connected stage"),
+            PortDefinition::required("Seed", DataType::Float)
+                .with_description("Seed in effect"),
+        ])
+        .with_panel_type(PanelType::Parameter)
+        .with_workspace_compatibility(vec!["3D"])
+    }
+    
+    fn parameters() -> Vec<ParameterSpec> {
+        vec![ParameterSpec::float("seed", "Seed", 0.0, 0.0, 99999.0)]
+    }
+    
+    fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        GlobalSeedLogic::execute(inputs, parameters)
+    }
+}
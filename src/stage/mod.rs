@@ -1,13 +1,15 @@
-//! USD Stage management nodes - modular structure
+//! USD Stage management nodes
+//!
+//! Creating, loading and saving stages are the plugin's own nodes in
+//! create_stage_node.rs, load_stage_node.rs and save_stage_node.rs; the nodes
+//! here are `ModularNode`s declared and listed for registration through `modular_nodes!`.
 
-pub mod create_stage;
-pub mod load_stage;
-pub mod save_stage;
-pub mod export_stage;
-pub mod clear_stage;
+use crate::modular::modular_nodes;
 
-pub use create_stage::{CreateStageNode, CreateStageLogic};
-pub use load_stage::{LoadStageNode, LoadStageLogic};
-pub use save_stage::{SaveStageNode, SaveStageLogic};
-pub use export_stage::{ExportStageNode, ExportStageLogic};
-pub use clear_stage::{ClearStageNode, ClearStageLogic};
\ No newline at end of file
+modular_nodes! {
+    export_stage => ExportStageNode,
+    clear_stage => ClearStageNode,
+    render_frame => RenderFrameNode,
+    export_graph => ExportGraphNode,
+    global_seed => GlobalSeedNode,
+}
@@ -0,0 +1,196 @@
+//! Render Frame node module - renders a stage camera to image files on demand
+//!
+//! Frames go through Hydra's offscreen session rather than a viewport, so
+//! the node renders without a window or wgpu device. A single frame or a
+//! frame range is written when Render is clicked, which makes turntables and
+//! playblasts part of the graph.
+
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::capture::{create_parent_dirs, sequence_path};
+use crate::core::usd_engine::with_usd_engine;
+use crate::modular::{flag_value, float_value, input_stage, text_value, ModularNode, ParameterSpec};
+use crate::ui::help::{NodeHelp, KITCHEN_SET};
+use crate::viewport::hydra::{render_offscreen, HydraView, STORM_RENDERER};
+
+/// Help for the Render Frame node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_RenderFrame",
+    summary: "Renders the stage from a camera to PNG or EXR files on demand",
+    details: "Renders through Hydra when Render is clicked, at the given resolution and time code, or over a frame range with `#` padding in the file name for the frame number. EXR frames are written as linear floats converted from the delegate's sRGB output. Without a camera the stage's first camera is used.",
+    ports: &[
+        ("Stage", "stage_0"),
+        ("Camera", "/World/Cameras/shot_cam"),
+        ("Time", "1001"),
+        ("File Path", "renders/turntable.1001.png"),
+        ("Files", "renders/turntable.1001.png\nrenders/turntable.1002.png"),
+    ],
+    samples: &[KITCHEN_SET],
+};
+
+/// Render Frame node with parameter controls
+#[derive(Default)]
+pub struct RenderFrameNode;
+
+/// Core logic for rendering frames to disk
+pub struct RenderFrameLogic;
+
+impl RenderFrameLogic {
+    /// Execute the render when it was requested
+    pub fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        let stage_id = input_stage(inputs)?;
+        let mut outputs = HashMap::from([("Stage".to_string(), inputs["Stage"].clone())]);
+        if !flag_value(parameters, "render", false) {
+            return Ok(outputs);
+        }
+        
+        let camera = match text_value(inputs, "Camera", parameters, "camera") {
+            Some(camera) => camera,
+            None => first_camera(&stage_id)?,
+        };
+        let pattern = text_value(inputs, "File Path", parameters, "file_path")
+            .ok_or_else(|| "No file path set".to_string())?;
+        let number = |parameter: &str, default: f32| parameters.get(parameter).and_then(|data| data.as_float()).unwrap_or(default);
+        let times = if flag_value(parameters, "use_range", false) {
+            frame_times(number("start_frame", 1.0) as f64, number("end_frame", 1.0) as f64, number("step", 1.0) as f64)?
+        } else {
+            vec![float_value(inputs, "Time", parameters, "time", 1.0) as f64]
+        };
+        
+        let size = |parameter: &str, default: f32| number(parameter, default).round().max(1.0) as u32;
+        let renderer = parameters.get("renderer").and_then(|data| data.as_string()).unwrap_or_default();
+        let mut view = camera_view(&camera, size("width", 1920.0), size("height", 1080.0), renderer);
+        let mut files = Vec::with_capacity(times.len());
+        for time in times {
+            view.time_code = time;
+            let frame = render_offscreen(&stage_id, &view)?;
+            let path = frame_path(&pattern, time);
+            create_parent_dirs(&path)?;
+            frame.save(&path)?;
+            files.push(path);
+        }
+        println!("✓ Rendered {} frame(s) of '{}' through {}", files.len(), stage_id, camera);
+        
+        outputs.insert("File Path".to_string(), NodeData::String(files.last().cloned().unwrap_or_default()));
+        outputs.insert("Files".to_string(), NodeData::String(files.join("\n")));
+        Ok(outputs)
+    }
+}
+
+/// Hydra view through a stage camera; an empty renderer picks Storm
+pub fn camera_view(camera: &str, width: u32, height: u32, renderer: &str) -> HydraView {
+    let renderer = match renderer.trim() {
+        "" => STORM_RENDERER,
+        renderer => renderer,
+    };
+    HydraView {
+        camera_path: Some(camera.to_string()),
+        renderer: renderer.to_string(),
+        ..HydraView::look_at(glam::Vec3::Z, glam::Vec3::ZERO, 1.0, 0.1, 1000.0, width, height)
+    }
+}
+
+/// First camera of a stage by path
+pub fn first_camera(stage_id: &str) -> Result<String, String> {
+    with_usd_engine(|engine| {
+        engine.get_stage_prims(stage_id).into_iter()
+            .filter(|prim| prim.prim_type == "Camera")
+            .map(|prim| prim.path.clone())
+            .min()
+    })
+    .ok_or_else(|| format!("Stage '{}' has no camera to render from", stage_id))
+}
+
+/// Time codes from `start` to `end` inclusive, `step` apart
+pub fn frame_times(start: f64, end: f64, step: f64) -> Result<Vec<f64>, String> {
+    if step <= 0.0 {
+        return Err(format!("Frame step must be positive, got {}", step));
+    }
+    if end < start {
+        return Err(format!("Frame range {} to {} is empty", start, end));
+    }
+    let count = ((end - start) / step + 1e-6).floor() as usize + 1;
+    Ok((0..count).map(|index| start + index as f64 * step).collect())
+}
+
+/// File of the frame at `time`; paths without `#` padding are used as is
+pub fn frame_path(pattern: &str, time: f64) -> String {
+    if pattern.contains('#') {
+        sequence_path(pattern, time)
+    } else {
+        pattern.to_string()
+    }
+}
+
+impl ModularNode for RenderFrameNode {
+    const NAME: &'static str = "USD Render Frame";
+    const HELP: &'static NodeHelp = &HELP;
+    
+    fn metadata() -> NodeMetadata {
+        NodeMetadata::new(
+            "USD_RenderFrame",
+            "Render Frame",
+            NodeCategory::new(&["USD", "Stage"]),
+            HELP.summary
+        )
+        .with_color(Color32::from_rgb(80, 150, 200))
+        .with_icon("🎬")
+        .with_inputs(vec![
+            PortDefinition::required("Stage", DataType::String)
+                .with_description("USD stage to render"),
+            PortDefinition::optional("Camera", DataType::String)
+                .with_description("Camera prim path, overriding the parameter"),
+            PortDefinition::optional("Time", DataType::Float)
+                .with_description("Time code of a single frame, overriding the parameter"),
+            PortDefinition::optional("File Path", DataType::String)
+                .with_description("Output file, overriding the parameter"),
+        ])
+        .with_outputs(vec![
+            PortDefinition::required("Stage", DataType::String)
+                .with_description("Rendered stage"),
+            PortDefinition::optional("File Path", DataType::String)
+                .with_description("Last written file, after a render"),
+            PortDefinition::optional("Files", DataType::String)
+                .with_description("Every written file, one per line"),
+        ])
+        .with_panel_type(PanelType::Parameter)
+        .with_workspace_compatibility(vec!["3D"])
+    }
+    
+    fn parameters() -> Vec<ParameterSpec> {
+        vec![
+            ParameterSpec::text("camera", "Camera", ""),
+            ParameterSpec::float("width", "Width", 1920.0, 16.0, 8192.0),
+            ParameterSpec::float("height", "Height", 1080.0, 16.0, 8192.0),
+            ParameterSpec::float("time", "Time Code", 1.0, -10000.0, 100000.0),
+            ParameterSpec::toggle("use_range", "Frame Range", false),
+            ParameterSpec::float("start_frame", "Start Frame", 1.0, -10000.0, 100000.0),
+            ParameterSpec::float("end_frame", "End Frame", 24.0, -10000.0, 100000.0),
+            ParameterSpec::float("step", "Step", 1.0, 0.01, 100.0),
+            ParameterSpec::text("file_path", "File Path", "render.####.png"),
+            ParameterSpec::text("renderer", "Render Delegate", STORM_RENDERER),
+            ParameterSpec::trigger("render", "Render"),
+        ]
+    }
+    
+    fn execute(inputs: &HashMap<String, NodeData>, parameters: &HashMap<String, NodeData>) -> Result<HashMap<String, NodeData>, String> {
+        RenderFrameLogic::execute(inputs, parameters)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn ranges_name_one_file_per_frame() {
+        assert_eq!(frame_times(1001.0, 1004.0, 1.0).unwrap(), [1001.0, 1002.0, 1003.0, 1004.0]);
+        assert_eq!(frame_times(1.0, 2.0, 0.5).unwrap(), [1.0, 1.5, 2.0]);
+        assert_eq!(frame_times(1.0, 1.0, 1.0).unwrap(), [1.0]);
+        assert!(frame_times(1.0, 10.0, 0.0).is_err());
+        assert!(frame_times(10.0, 1.0, 1.0).is_err());
+        
+        assert_eq!(frame_path("out/turntable.####.exr", 12.0), "out/turntable.0012.exr");
+        assert_eq!(frame_path("out/still.png", 12.0), "out/still.png");
+    }
+}
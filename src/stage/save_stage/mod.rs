@@ -1,8 +0,0 @@
-//! Save Stage node module - stub implementation
-
-pub struct SaveStageNode;
-pub struct SaveStageLogic;
-
-impl Default for SaveStageNode {
-    fn default() -> Self { Self }
-}
\ No newline at end of file
@@ -0,0 +1,484 @@
+//! USD Stage Inspector node - an outliner for the composed prim hierarchy of a stage
+
+use nodle_plugin_sdk::*;
+use std::collections::{HashMap, HashSet};
+use crate::core::usd_engine::{with_usd_engine, USDEngine, USDPrimStatus};
+use crate::ui::search::{ParameterSearch, SEARCH_PARAMETER};
+use crate::ui::help::{NodeHelp, KITCHEN_SET};
+use crate::viewport::picking::{selected_prims, set_selected_prims};
+use crate::ui::keyboard::{focus_marked, handle_key};
+use crate::ui::palette::status_label;
+use crate::registry::NodeTypeFactory;
+
+/// Help for the Stage Inspector node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_StageInspector",
+    summary: "Browse the prim hierarchy, toggle visibility and active state and pick a prim",
+    details: "Shows the prim tree with search. Picking a prim outputs its path for the viewport and property nodes; visibility and active toggles are authored on the stage. The selection is shared with the stage's viewports: prims clicked there are marked and revealed in the tree, and a prim picked here is outlined there.",
+    ports: &[
+        ("Stage", "loaded_0"),
+        ("Info", "12 prims, 3 meshes"),
+        ("Selected Prim", "/World/Kitchen/Table"),
+    ],
+    samples: &[KITCHEN_SET],
+};
+
+/// Factory of the Stage Inspector node, for `crate::node_factories`
+pub fn factories() -> Vec<Box<dyn NodeFactory>> {
+    vec![NodeTypeFactory::boxed(metadata, |position| PluginNodeHandle::new(Box::new(USDStageInspectorNode::new(position))))]
+}
+
+/// Metadata of the Stage Inspector node
+fn metadata() -> NodeMetadata {
+    NodeMetadata::new(
+        "USD_StageInspector",
+        "Stage Inspector",
+        NodeCategory::new(&["USD", "Viewport"]),
+        HELP.summary
+    )
+    .with_color(Color32::from_rgb(120, 120, 120))
+    .with_icon("🔍")
+    .with_inputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("USD stage to inspect"),
+    ])
+    .with_outputs(vec![
+        PortDefinition::required("Info", DataType::String)
+            .with_description("Stage information"),
+        PortDefinition::optional("Selected Prim", DataType::String)
+            .with_description("Path of the prim selected in the tree"),
+    ])
+    .with_panel_type(PanelType::Parameter)
+    .with_workspace_compatibility(vec!["3D"])
+}
+
+/// Button action prefix for selecting a row, followed by the prim path
+const SELECT_ACTION: &str = "select:";
+
+/// USD Stage Inspector node showing the prim tree of the connected stage
+///
+/// Clicking a row selects it; clicking the selected row again expands or
+/// collapses it. The selected prim can be hidden or deactivated, and its
+/// path is emitted on the "Selected Prim" output. Pinned prims are kept in
+/// the stage's root layer and jump straight to the prim when clicked. The
+/// selection is kept in step with the stage's viewports.
+pub struct USDStageInspectorNode {
+    id: String,
+    position: Pos2,
+    stage_ref: String,
+    /// Engine identifier of the connected stage
+    stage_id: String,
+    /// Stage revision the hierarchy was read at
+    revision: Option<u64>,
+    /// Composed prims sorted by path
+    prims: Vec<USDPrimStatus>,
+    /// Paths of expanded prims
+    expanded: HashSet<String>,
+    selected: Option<String>,
+    /// Prims selected in the stage's viewports or here, most recent last
+    selection: Vec<String>,
+    /// Revision of the stage's shared selection last adopted or published
+    selection_revision: u64,
+    /// Bookmarked prim paths of the stage
+    pinned: Vec<String>,
+    search: ParameterSearch,
+    status: Option<String>,
+}
+
+impl USDStageInspectorNode {
+    pub fn new(position: Pos2) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            position,
+            stage_ref: String::new(),
+            stage_id: String::new(),
+            revision: None,
+            prims: Vec::new(),
+            expanded: HashSet::new(),
+            selected: None,
+            selection: Vec::new(),
+            selection_revision: 0,
+            pinned: Vec::new(),
+            search: ParameterSearch::default(),
+            status: None,
+        }
+    }
+    
+    /// Refresh the prim tree from the USD engine
+    fn refresh_prims(&mut self) {
+        let stage_ref = self.stage_ref.clone();
+        let result = with_usd_engine(|engine| -> Result<_, String> {
+            let stage = engine.resolve_stage(&stage_ref)?;
+            Ok((stage.identifier.clone(), engine.get_prim_hierarchy(&stage.identifier)?, engine.get_pinned_prims(&stage.identifier)?))
+        });
+        match result {
+            Ok((stage_id, prims, pinned)) => {
+                self.stage_id = stage_id;
+                // Start with the top level open
+                if self.prims.is_empty() {
+                    self.expanded.extend(prims.iter().filter(|prim| depth(&prim.path) == 0).map(|prim| prim.path.clone()));
+                }
+                self.prims = prims;
+                self.pinned = pinned;
+                self.status = None;
+            }
+            Err(e) => {
+                self.prims.clear();
+                self.pinned.clear();
+                self.status = Some(format!("⚠ {}", e));
+            }
+        }
+        if let Some(selected) = &self.selected {
+            if !self.prims.iter().any(|prim| &prim.path == selected) {
+                self.selected = None;
+            }
+        }
+        self.selection.retain(|path| self.prims.iter().any(|prim| &prim.path == path));
+    }
+    
+    /// Apply an edit to the selected prim and re-read the tree
+    fn edit_selected(&mut self, edit: impl FnOnce(&mut USDEngine, &str, &str) -> Result<(), String>) {
+        let Some(selected) = self.selected.clone() else {
+            return;
+        };
+        let stage_ref = self.stage_ref.clone();
+        let result = with_usd_engine(|engine| {
+            let stage = engine.resolve_stage(&stage_ref)?;
+            edit(engine, &stage.identifier, &selected)
+        });
+        if let Err(e) = result {
+            self.status = Some(format!("⚠ {}", e));
+            return;
+        }
+        self.refresh_prims();
+    }
+    
+    /// Pin the selected prim, or unpin it if it is pinned already
+    fn toggle_pin(&mut self) {
+        let Some(selected) = self.selected.clone() else {
+            return;
+        };
+        let mut pinned = self.pinned.clone();
+        match pinned.iter().position(|path| path == &selected) {
+            Some(index) => {
+                pinned.remove(index);
+            }
+            None => pinned.push(selected),
+        }
+        let stage_ref = self.stage_ref.clone();
+        let result = with_usd_engine(|engine| {
+            let stage = engine.resolve_stage(&stage_ref)?;
+            engine.set_pinned_prims(&stage.identifier, &pinned)
+        });
+        match result {
+            Ok(()) => self.pinned = pinned,
+            Err(e) => self.status = Some(format!("⚠ {}", e)),
+        }
+    }
+    
+    /// Select a prim, revealing it in the tree and publishing it to the stage's viewports
+    fn select(&mut self, path: &str) {
+        self.expanded.extend(ancestors(path).map(str::to_string));
+        self.selected = Some(path.to_string());
+        self.selection = vec![path.to_string()];
+        if !self.stage_id.is_empty() {
+            self.selection_revision = set_selected_prims(&self.stage_id, &self.selection);
+        }
+    }
+    
+    /// Adopt a selection made in one of the stage's viewports, revealing its prims
+    fn sync_selection(&mut self) {
+        if self.stage_id.is_empty() {
+            return;
+        }
+        let (revision, selection) = selected_prims(&self.stage_id);
+        if revision != self.selection_revision {
+            self.selection_revision = revision;
+            for path in &selection {
+                self.expanded.extend(ancestors(path).map(str::to_string));
+            }
+            self.selected = selection.last().cloned();
+            self.selection = selection;
+        }
+    }
+    
+    fn has_children(&self, path: &str) -> bool {
+        let prefix = if path == "/" { "/".to_string() } else { format!("{}/", path) };
+        self.prims.iter().any(|prim| prim.path.starts_with(&prefix))
+    }
+    
+    /// Prims whose ancestors are all expanded, in tree order
+    fn visible_rows(&self) -> Vec<&USDPrimStatus> {
+        self.prims.iter()
+            .filter(|prim| ancestors(&prim.path).all(|ancestor| self.expanded.contains(ancestor)))
+            .collect()
+    }
+    
+    /// Row label: indentation, expander, type icon, name, kind badge and state
+    fn row_label(&self, prim: &USDPrimStatus, full_path: bool) -> String {
+        let expander = if !self.has_children(&prim.path) {
+            " "
+        } else if self.expanded.contains(&prim.path) {
+            "▾"
+        } else {
+            "▸"
+        };
+        let name = if full_path { prim.path.as_str() } else { prim.path.rsplit('/').next().unwrap_or_default() };
+        let mut label = format!("{}{} {} {}", "   ".repeat(depth(&prim.path)), expander, type_icon(&prim.prim_type), name);
+        if !prim.kind.is_empty() {
+            label.push_str(&format!(" [{}]", prim.kind));
+        }
+        if !prim.visible {
+            label.push_str(" 🚫");
+        }
+        if !prim.active {
+            label.push_str(" (inactive)");
+        }
+        if self.selected.as_deref() == Some(prim.path.as_str()) {
+            label = format!("▶ {}", label.trim_start());
+        } else if self.selection.contains(&prim.path) {
+            label = format!("▷ {}", label.trim_start());
+        }
+        label
+    }
+    
+    fn info(&self) -> String {
+        format!("{}: {} prims", self.stage_ref, self.prims.len())
+    }
+}
+
+/// Nesting depth of a prim path, 0 for root prims
+fn depth(path: &str) -> usize {
+    path.matches('/').count().saturating_sub(1)
+}
+
+/// Ancestor prim paths, excluding the pseudo-root
+fn ancestors(path: &str) -> impl Iterator<Item = &str> {
+    path.match_indices('/')
+        .skip(1)
+        .map(move |(index, _)| &path[..index])
+}
+
+/// Icon for a prim type
+fn type_icon(prim_type: &str) -> &'static str {
+    match prim_type {
+        "" => "○",
+        "Xform" => "✥",
+        "Scope" => "📁",
+        "Mesh" => "▦",
+        "Sphere" | "Cube" | "Cylinder" | "Cone" | "Capsule" | "Plane" => "◆",
+        "BasisCurves" | "NurbsCurves" => "〰",
+        "Points" => "⁘",
+        "PointInstancer" => "🌲",
+        "Camera" => "📷",
+        "Material" => "🎨",
+        "Shader" | "NodeGraph" => "◐",
+        "SkelRoot" | "Skeleton" | "SkelAnimation" => "🦴",
+        "Volume" | "OpenVDBAsset" => "☁",
+        light if light.ends_with("Light") => "💡",
+        _ => "•",
+    }
+}
+
+impl PluginNode for USDStageInspectorNode {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+    
+    fn position(&self) -> Pos2 {
+        self.position
+    }
+    
+    fn set_position(&mut self, position: Pos2) {
+        self.position = position;
+    }
+    
+    fn get_parameter_ui(&self) -> ParameterUI {
+        let mut elements = Vec::new();
+        
+        elements.push(UIElement::Heading("USD Stage Inspector".to_string()));
+        elements.push(UIElement::Separator);
+        
+        if self.stage_ref.is_empty() {
+            elements.push(UIElement::Label("No USD stage connected".to_string()));
+            elements.extend(HELP.section());
+            return ParameterUI { elements };
+        }
+        
+        elements.push(UIElement::Label(format!("Stage: {}", self.stage_ref)));
+        if let Some(status) = &self.status {
+            elements.push(status_label(status));
+        }
+        
+        if let Some(prim) = self.selected.as_ref().and_then(|path| self.prims.iter().find(|prim| &prim.path == path)) {
+            elements.push(UIElement::Separator);
+            elements.push(UIElement::Label(format!("Selected: {} ({})", prim.path, if prim.prim_type.is_empty() { "untyped" } else { &prim.prim_type })));
+            if !prim.kind.is_empty() {
+                elements.push(UIElement::Label(format!("Kind: {}", prim.kind)));
+            }
+            elements.push(UIElement::Checkbox {
+                label: "Visible".to_string(),
+                value: prim.visible,
+                parameter_name: "selected_visible".to_string(),
+            });
+            elements.push(UIElement::Checkbox {
+                label: "Active".to_string(),
+                value: prim.active,
+                parameter_name: "selected_active".to_string(),
+            });
+            elements.push(UIElement::Button {
+                label: if self.pinned.contains(&prim.path) { "📌 Unpin" } else { "📌 Pin" }.to_string(),
+                action: "toggle_pin".to_string(),
+            });
+        }
+        
+        if !self.pinned.is_empty() {
+            elements.push(UIElement::Separator);
+            elements.push(UIElement::Label("Pinned".to_string()));
+            for path in &self.pinned {
+                let missing = if self.prims.iter().any(|prim| &prim.path == path) { "" } else { " (missing)" };
+                elements.push(UIElement::Button {
+                    label: format!("📌 {}{}", path, missing),
+                    action: format!("{}{}", SELECT_ACTION, path),
+                });
+            }
+        }
+        
+        elements.push(UIElement::Separator);
+        elements.push(self.search.element());
+        elements.push(UIElement::Button {
+            label: "Expand All".to_string(),
+            action: "expand_all".to_string(),
+        });
+        elements.push(UIElement::Button {
+            label: "Collapse All".to_string(),
+            action: "collapse_all".to_string(),
+        });
+        elements.push(UIElement::Separator);
+        
+        // Searching lists matches flat, with full paths, regardless of expansion
+        let rows: Vec<UIElement> = if self.search.is_active() {
+            self.prims.iter()
+                .filter(|prim| self.search.matches(&format!("{} {} {}", prim.path, prim.prim_type, prim.kind)))
+                .map(|prim| UIElement::Button {
+                    label: self.row_label(prim, true).trim_start().to_string(),
+                    action: format!("{}{}", SELECT_ACTION, prim.path),
+                })
+                .collect()
+        } else {
+            self.visible_rows().into_iter()
+                .map(|prim| UIElement::Button {
+                    label: self.row_label(prim, false),
+                    action: format!("{}{}", SELECT_ACTION, prim.path),
+                })
+                .collect()
+        };
+        
+        elements.push(UIElement::Label(format!("Showing {} of {} prims", rows.len(), self.prims.len())));
+        elements.extend(rows);
+        elements.extend(HELP.section());
+        
+        ParameterUI { elements: focus_marked(&self.id, elements) }
+    }
+    
+    fn handle_ui_action(&mut self, action: UIAction) -> Vec<ParameterChange> {
+        if let Some(changes) = handle_key(self, &[], &action) {
+            return changes;
+        }
+        
+        if let Some(change) = self.search.handle_action(&action) {
+            return vec![change];
+        }
+        
+        match action {
+            UIAction::ParameterChanged { parameter, value } => {
+                match (parameter.as_str(), value.as_boolean()) {
+                    ("selected_visible", Some(visible)) => self.edit_selected(|engine, stage, path| {
+                        engine.set_prim_visibility(stage, path, if visible { "inherited" } else { "invisible" })
+                    }),
+                    ("selected_active", Some(active)) => self.edit_selected(|engine, stage, path| {
+                        engine.set_prim_active(stage, path, active)
+                    }),
+                    _ => {}
+                }
+            }
+            UIAction::ButtonClicked { action } => {
+                if let Some(path) = action.strip_prefix(SELECT_ACTION) {
+                    if self.selected.as_deref() == Some(path) {
+                        if !self.expanded.remove(path) {
+                            self.expanded.insert(path.to_string());
+                        }
+                    } else {
+                        // Reveal a prim picked from search results
+                        self.select(path);
+                    }
+                } else if action == "toggle_pin" {
+                    self.toggle_pin();
+                } else if action == "expand_all" {
+                    self.expanded = self.prims.iter().map(|prim| prim.path.clone()).collect();
+                } else if action == "collapse_all" {
+                    self.expanded.clear();
+                }
+            }
+        }
+        
+        Vec::new()
+    }
+    
+    fn get_parameter(&self, name: &str) -> Option<NodeData> {
+        match name {
+            SEARCH_PARAMETER => Some(NodeData::String(self.search.query.clone())),
+            "selected_prim" => Some(NodeData::String(self.selected.clone().unwrap_or_default())),
+            _ => None,
+        }
+    }
+    
+    fn set_parameter(&mut self, name: &str, value: NodeData) {
+        match name {
+            SEARCH_PARAMETER => {
+                if let Some(query) = value.as_string() {
+                    self.search.query = query.to_string();
+                }
+            }
+            "selected_prim" => {
+                if let Some(path) = value.as_string() {
+                    self.selected = Some(path.trim().to_string()).filter(|path| !path.is_empty());
+                    self.selection = self.selected.iter().cloned().collect();
+                }
+            }
+            _ => {}
+        }
+    }
+    
+    fn process(&mut self, inputs: &HashMap<String, NodeData>) -> HashMap<String, NodeData> {
+        let mut outputs = HashMap::new();
+        
+        match inputs.get("Stage").and_then(|data| data.as_string()) {
+            Some(stage_ref) => {
+                let revision = with_usd_engine(|engine| engine.stage_revision(stage_ref));
+                if stage_ref != self.stage_ref || self.revision != Some(revision) {
+                    if stage_ref != self.stage_ref {
+                        self.stage_ref = stage_ref.to_string();
+                        self.prims.clear();
+                        self.expanded.clear();
+                    }
+                    self.revision = Some(revision);
+                    self.refresh_prims();
+                }
+                self.sync_selection();
+                outputs.insert("Info".to_string(), NodeData::String(self.info()));
+                if let Some(selected) = &self.selected {
+                    outputs.insert("Selected Prim".to_string(), NodeData::String(selected.clone()));
+                }
+            }
+            None => {
+                self.stage_ref.clear();
+                self.stage_id.clear();
+                self.revision = None;
+                self.prims.clear();
+            }
+        }
+        
+        outputs
+    }
+}
@@ -0,0 +1,321 @@
+//! USD Sun & Sky node - places a sun by date, time and location and lights the stage with a procedural sky
+
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use chrono::NaiveDate;
+use crate::core::sun_sky::{SolarTime, SunPlacement, USDSunSky};
+use crate::core::usd_engine::with_usd_engine;
+use crate::ui::choice::{choice_buttons, parse_choice};
+use crate::ui::help::NodeHelp;
+use crate::ui::palette::status_label;
+use crate::ui::keyboard::{focus_marked, handle_key};
+use crate::registry::NodeTypeFactory;
+
+/// Help for the Sun and Sky node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_SunSky",
+    summary: "Add a sun placed by date, time and location, or by angles, with a procedural daylight sky dome",
+    details: "Places a distant light where the sun is for a date, time and latitude/longitude, or at explicit angles, plus a dome light with a matching procedural sky or your own sky texture.",
+    ports: &[
+        ("Stage", "stage_0"),
+        ("Sun", "/World/SunSky/Sun"),
+        ("Sky", "/World/SunSky/Sky"),
+    ],
+    samples: &[],
+};
+
+/// Factory of the Sun & Sky node, for `crate::node_factories`
+pub fn factories() -> Vec<Box<dyn NodeFactory>> {
+    vec![NodeTypeFactory::boxed(metadata, |position| PluginNodeHandle::new(Box::new(USDSunSkyNode::new(position))))]
+}
+
+/// Metadata of the Sun & Sky node
+fn metadata() -> NodeMetadata {
+    NodeMetadata::new(
+        "USD_SunSky",
+        "Sun & Sky",
+        NodeCategory::new(&["USD", "Lighting"]),
+        HELP.summary
+    )
+    .with_color(Color32::from_rgb(200, 200, 100))
+    .with_icon("🌤")
+    .with_inputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("USD stage to add the sun and sky to"),
+    ])
+    .with_outputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("Stage with the sun and sky"),
+        PortDefinition::optional("Sun", DataType::String)
+            .with_description("Sun DistantLight path"),
+        PortDefinition::optional("Sky", DataType::String)
+            .with_description("Sky DomeLight path"),
+    ])
+    .with_panel_type(PanelType::Parameter)
+    .with_workspace_compatibility(vec!["3D"])
+}
+
+/// Width of the baked latlong sky
+const SKY_TEXTURE_WIDTH: u32 = 512;
+
+const PLACEMENTS: [&str; 2] = ["Location", "Angles"];
+
+/// USD Sun & Sky node
+///
+/// Authors a DistantLight sun and a DomeLight sky under one Xform. The sky is
+/// baked to an EXR whenever the sun or haze changes, by default into the temp
+/// directory; set a sky texture path to keep it with the stage.
+pub struct USDSunSkyNode {
+    id: String,
+    position: Pos2,
+    root_path: String,
+    use_location: bool,
+    solar_time: SolarTime,
+    /// Compass azimuth and elevation for the Angles placement
+    azimuth: f64,
+    elevation: f64,
+    turbidity: f64,
+    sun_intensity: f64,
+    sky_intensity: f64,
+    /// Where to write the sky; empty bakes into the temp directory
+    sky_texture: String,
+    stage_ref: String,
+    /// Sun and sky paths once authored
+    lights: Vec<String>,
+    dirty: bool,
+    status: String,
+}
+
+impl USDSunSkyNode {
+    pub fn new(position: Pos2) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            position,
+            root_path: "/Lights/SunSky".to_string(),
+            use_location: true,
+            solar_time: SolarTime {
+                date: NaiveDate::from_ymd_opt(2024, 6, 21).unwrap_or_default(),
+                hour: 15.0,
+                utc_offset: 1.0,
+                latitude: 51.5,
+                longitude: -0.1,
+            },
+            azimuth: 220.0,
+            elevation: 35.0,
+            turbidity: 3.0,
+            sun_intensity: 3.0,
+            sky_intensity: 1.0,
+            sky_texture: String::new(),
+            stage_ref: String::new(),
+            lights: Vec::new(),
+            dirty: true,
+            status: "No USD stage connected".to_string(),
+        }
+    }
+    
+    fn sun_sky(&self) -> USDSunSky {
+        let placement = match self.use_location {
+            true => SunPlacement::Location(self.solar_time.clone()),
+            false => SunPlacement::Angles { azimuth: self.azimuth, elevation: self.elevation },
+        };
+        let mut sun_sky = USDSunSky {
+            root_path: self.root_path.clone(),
+            placement,
+            turbidity: self.turbidity,
+            sun_intensity: self.sun_intensity,
+            sky_intensity: self.sky_intensity,
+            sky_texture: self.sky_texture.clone(),
+        };
+        if sun_sky.sky_texture.is_empty() {
+            // Named after what shapes the sky, so each sun position bakes once
+            let (azimuth, elevation) = sun_sky.sun_angles();
+            let name = format!("sky_{:.0}_{:.0}_{:.0}.exr", azimuth * 100.0, elevation * 100.0, self.turbidity * 100.0);
+            sun_sky.sky_texture = std::env::temp_dir().join("nodle_sky").join(name).to_string_lossy().into_owned();
+        }
+        sun_sky
+    }
+    
+    fn author(&mut self) -> Result<(), String> {
+        let sun_sky = self.sun_sky();
+        // Temp bakes are named by their inputs; a chosen path is always rewritten
+        if !self.sky_texture.is_empty() || !std::path::Path::new(&sun_sky.sky_texture).exists() {
+            sun_sky.write_sky_texture(SKY_TEXTURE_WIDTH)?;
+        }
+        let stage_ref = self.stage_ref.clone();
+        self.lights = with_usd_engine(|engine| {
+            let stage = engine.resolve_stage(&stage_ref)?;
+            engine.set_sun_sky(&stage.identifier, &sun_sky)
+        })?;
+        let (azimuth, elevation) = sun_sky.sun_angles();
+        self.status = match elevation > 0.0 {
+            true => format!("Sun at {:.1}° azimuth, {:.1}° elevation", azimuth, elevation),
+            false => format!("Sun below the horizon ({:.1}°)", elevation),
+        };
+        Ok(())
+    }
+}
+
+impl PluginNode for USDSunSkyNode {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+    
+    fn position(&self) -> Pos2 {
+        self.position
+    }
+    
+    fn set_position(&mut self, position: Pos2) {
+        self.position = position;
+    }
+    
+    fn get_parameter_ui(&self) -> ParameterUI {
+        let mut elements = Vec::new();
+        let slider = |label: &str, value: f64, min: f32, max: f32, parameter_name: &str| UIElement::Slider {
+            label: label.to_string(),
+            value: value as f32,
+            min,
+            max,
+            parameter_name: parameter_name.to_string(),
+        };
+        
+        elements.push(UIElement::Heading("USD Sun & Sky".to_string()));
+        elements.push(UIElement::Separator);
+        
+        elements.push(UIElement::TextEdit {
+            label: "Root Path".to_string(),
+            value: self.root_path.clone(),
+            parameter_name: "root_path".to_string(),
+        });
+        let placement = if self.use_location { PLACEMENTS[0] } else { PLACEMENTS[1] };
+        elements.extend(choice_buttons("Sun From", "placement", &PLACEMENTS, placement));
+        if self.use_location {
+            elements.push(UIElement::TextEdit {
+                label: "Date (YYYY-MM-DD)".to_string(),
+                value: self.solar_time.date.to_string(),
+                parameter_name: "date".to_string(),
+            });
+            elements.push(slider("Time (hours)", self.solar_time.hour, 0.0, 24.0, "hour"));
+            elements.push(slider("UTC Offset", self.solar_time.utc_offset, -12.0, 14.0, "utc_offset"));
+            elements.push(slider("Latitude", self.solar_time.latitude, -90.0, 90.0, "latitude"));
+            elements.push(slider("Longitude", self.solar_time.longitude, -180.0, 180.0, "longitude"));
+        } else {
+            elements.push(slider("Azimuth", self.azimuth, 0.0, 360.0, "azimuth"));
+            elements.push(slider("Elevation", self.elevation, -10.0, 90.0, "elevation"));
+        }
+        
+        elements.push(UIElement::Separator);
+        elements.push(slider("Turbidity", self.turbidity, 2.0, 10.0, "turbidity"));
+        elements.push(slider("Sun Intensity", self.sun_intensity, 0.0, 10.0, "sun_intensity"));
+        elements.push(slider("Sky Intensity", self.sky_intensity, 0.0, 4.0, "sky_intensity"));
+        elements.push(UIElement::TextEdit {
+            label: "Sky Texture".to_string(),
+            value: self.sky_texture.clone(),
+            parameter_name: "sky_texture".to_string(),
+        });
+        
+        elements.push(UIElement::Separator);
+        elements.push(status_label(&self.status));
+        elements.extend(HELP.section());
+        
+        ParameterUI { elements: focus_marked(&self.id, elements) }
+    }
+    
+    fn handle_ui_action(&mut self, action: UIAction) -> Vec<ParameterChange> {
+        if let Some(changes) = handle_key(self, &[], &action) {
+            return changes;
+        }
+        
+        let mut changes = Vec::new();
+        
+        let (parameter, value) = match action {
+            UIAction::ParameterChanged { parameter, value } => (parameter, value),
+            UIAction::ButtonClicked { action } => {
+                let Some(placement) = parse_choice(&action, "placement") else {
+                    return changes;
+                };
+                ("placement".to_string(), NodeData::String(placement.to_string()))
+            }
+        };
+        self.set_parameter(&parameter, value);
+        if let Some(value) = self.get_parameter(&parameter) {
+            changes.push(ParameterChange { parameter, value });
+        }
+        
+        changes
+    }
+    
+    fn get_parameter(&self, name: &str) -> Option<NodeData> {
+        let float = |value: f64| Some(NodeData::Float(value as f32));
+        match name {
+            "root_path" => Some(NodeData::String(self.root_path.clone())),
+            "placement" => Some(NodeData::String(PLACEMENTS[usize::from(!self.use_location)].to_string())),
+            "date" => Some(NodeData::String(self.solar_time.date.to_string())),
+            "hour" => float(self.solar_time.hour),
+            "utc_offset" => float(self.solar_time.utc_offset),
+            "latitude" => float(self.solar_time.latitude),
+            "longitude" => float(self.solar_time.longitude),
+            "azimuth" => float(self.azimuth),
+            "elevation" => float(self.elevation),
+            "turbidity" => float(self.turbidity),
+            "sun_intensity" => float(self.sun_intensity),
+            "sky_intensity" => float(self.sky_intensity),
+            "sky_texture" => Some(NodeData::String(self.sky_texture.clone())),
+            _ => None,
+        }
+    }
+    
+    fn set_parameter(&mut self, name: &str, value: NodeData) {
+        let number = value.as_float().map(f64::from);
+        match (name, value.as_string(), number) {
+            ("root_path", Some(text), _) => self.root_path = text.trim().trim_end_matches('/').to_string(),
+            ("placement", Some(text), _) if text == PLACEMENTS[0] || text == PLACEMENTS[1] => self.use_location = text == PLACEMENTS[0],
+            ("date", Some(text), _) => match NaiveDate::parse_from_str(text.trim(), "%Y-%m-%d") {
+                Ok(date) => self.solar_time.date = date,
+                Err(_) => return,
+            },
+            ("sky_texture", Some(text), _) => self.sky_texture = text.trim().to_string(),
+            ("hour", _, Some(hour)) => self.solar_time.hour = hour.clamp(0.0, 24.0),
+            ("utc_offset", _, Some(offset)) => self.solar_time.utc_offset = offset.clamp(-12.0, 14.0),
+            ("latitude", _, Some(latitude)) => self.solar_time.latitude = latitude.clamp(-90.0, 90.0),
+            ("longitude", _, Some(longitude)) => self.solar_time.longitude = longitude.clamp(-180.0, 180.0),
+            ("azimuth", _, Some(azimuth)) => self.azimuth = azimuth.rem_euclid(360.0),
+            ("elevation", _, Some(elevation)) => self.elevation = elevation.clamp(-90.0, 90.0),
+            ("turbidity", _, Some(turbidity)) => self.turbidity = turbidity.clamp(2.0, 10.0),
+            ("sun_intensity", _, Some(intensity)) => self.sun_intensity = intensity.max(0.0),
+            ("sky_intensity", _, Some(intensity)) => self.sky_intensity = intensity.max(0.0),
+            _ => return,
+        }
+        self.dirty = true;
+    }
+    
+    fn process(&mut self, inputs: &HashMap<String, NodeData>) -> HashMap<String, NodeData> {
+        let mut outputs = HashMap::new();
+        
+        let Some(stage_ref) = inputs.get("Stage").and_then(|data| data.as_string()) else {
+            self.stage_ref.clear();
+            self.lights.clear();
+            self.status = "No USD stage connected".to_string();
+            return outputs;
+        };
+        
+        if stage_ref != self.stage_ref {
+            self.stage_ref = stage_ref.to_string();
+            self.dirty = true;
+        }
+        
+        if self.dirty {
+            self.dirty = false;
+            if let Err(e) = self.author() {
+                self.status = format!("⚠ {}", e);
+                self.lights.clear();
+            }
+        }
+        
+        outputs.insert("Stage".to_string(), NodeData::String(self.stage_ref.clone()));
+        if let [sun, sky] = self.lights.as_slice() {
+            outputs.insert("Sun".to_string(), NodeData::String(sun.clone()));
+            outputs.insert("Sky".to_string(), NodeData::String(sky.clone()));
+        }
+        outputs
+    }
+}
@@ -0,0 +1,260 @@
+//! Starter graph templates
+//!
+//! Templates are plain data: the nodes of a graph by type id, the
+//! parameters to set on them, and links between ports named as in the
+//! nodes' metadata. The host lists them with `graph_templates` and builds
+//! one with `GraphTemplate::instantiate`, passing a lookup into its factory
+//! registry; every node is created by its registered factory and every link
+//! is checked against the factories' port lists before anything is built.
+
+use nodle_plugin_sdk::*;
+
+/// Horizontal and vertical distance between template columns and rows
+const SPACING: (f32, f32) = (220.0, 140.0);
+
+/// A node of a template
+#[derive(Debug, Clone)]
+pub struct TemplateNode {
+    /// Name links refer to the node by, unique within the template
+    pub key: &'static str,
+    pub node_type: &'static str,
+    /// Column and row of the node in the template's layout
+    pub cell: (u32, u32),
+    pub parameters: Vec<(&'static str, NodeData)>,
+}
+
+/// A link from an output port to an input port, as (node key, port name) pairs
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplateLink {
+    pub from: (&'static str, &'static str),
+    pub to: (&'static str, &'static str),
+}
+
+/// A prebuilt graph the host can instantiate in one click
+#[derive(Debug, Clone)]
+pub struct GraphTemplate {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub nodes: Vec<TemplateNode>,
+    pub links: Vec<TemplateLink>,
+}
+
+/// A link between instantiated nodes, by index into `InstantiatedGraph::nodes` and port index
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResolvedLink {
+    pub from_node: usize,
+    pub from_port: usize,
+    pub to_node: usize,
+    pub to_port: usize,
+}
+
+/// A node created from a template, with the parameters the host should set on it
+pub struct InstantiatedNode {
+    pub key: String,
+    pub node: PluginNodeHandle,
+    pub parameters: Vec<(String, NodeData)>,
+}
+
+/// Nodes and links of an instantiated template, ready to insert into a graph
+pub struct InstantiatedGraph {
+    pub nodes: Vec<InstantiatedNode>,
+    pub links: Vec<ResolvedLink>,
+}
+
+impl GraphTemplate {
+    /// Resolve links to port indices, given the input and output port names of a node type
+    pub fn resolve_links(&self, ports: impl Fn(&str) -> Option<(Vec<String>, Vec<String>)>) -> Result<Vec<ResolvedLink>, String> {
+        let mut node_ports = Vec::with_capacity(self.nodes.len());
+        for node in &self.nodes {
+            let ports = ports(node.node_type)
+                .ok_or_else(|| format!("Template '{}' needs node type '{}', which is not registered", self.name, node.node_type))?;
+            node_ports.push(ports);
+        }
+        let node_index = |key: &str| self.nodes.iter().position(|node| node.key == key)
+            .ok_or_else(|| format!("Template '{}' links unknown node '{}'", self.name, key));
+        
+        self.links.iter()
+            .map(|link| {
+                let (from_node, to_node) = (node_index(link.from.0)?, node_index(link.to.0)?);
+                let port = |names: &[String], node: usize, name: &str, direction: &str| names.iter()
+                    .position(|port| port == name)
+                    .ok_or_else(|| format!("Node type '{}' has no {} port '{}'", self.nodes[node].node_type, direction, name));
+                Ok(ResolvedLink {
+                    from_node,
+                    from_port: port(&node_ports[from_node].1, from_node, link.from.1, "output")?,
+                    to_node,
+                    to_port: port(&node_ports[to_node].0, to_node, link.to.1, "input")?,
+                })
+            })
+            .collect()
+    }
+    
+    /// Build the template's nodes through their factories, laid out from `origin`
+    ///
+    /// `factory` looks a node type up in the host's factory registry.
+    pub fn instantiate<'a>(&self, origin: Pos2, factory: impl Fn(&str) -> Option<&'a dyn NodeFactory>) -> Result<InstantiatedGraph, String> {
+        let links = self.resolve_links(|node_type| {
+            let metadata = factory(node_type)?.metadata();
+            let names = |ports: &[PortDefinition]| ports.iter().map(|port| port.name.clone()).collect();
+            Some((names(&metadata.inputs), names(&metadata.outputs)))
+        })?;
+        
+        let nodes = self.nodes.iter()
+            .map(|node| {
+                let position = Pos2::new(
+                    origin.x + node.cell.0 as f32 * SPACING.0,
+                    origin.y + node.cell.1 as f32 * SPACING.1,
+                );
+                let factory = factory(node.node_type)
+                    .ok_or_else(|| format!("Node type '{}' is not registered", node.node_type))?;
+                Ok(InstantiatedNode {
+                    key: node.key.to_string(),
+                    node: factory.create_node(position),
+                    parameters: node.parameters.iter()
+                        .map(|(name, value)| (name.to_string(), value.clone()))
+                        .collect(),
+                })
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(InstantiatedGraph { nodes, links })
+    }
+}
+
+fn node(key: &'static str, node_type: &'static str, cell: (u32, u32), parameters: Vec<(&'static str, NodeData)>) -> TemplateNode {
+    TemplateNode { key, node_type, cell, parameters }
+}
+
+fn link(from: (&'static str, &'static str), to: (&'static str, &'static str)) -> TemplateLink {
+    TemplateLink { from, to }
+}
+
+fn text(value: &str) -> NodeData {
+    NodeData::String(value.to_string())
+}
+
+/// The plugin's starter graphs
+pub fn graph_templates() -> Vec<GraphTemplate> {
+    vec![
+        GraphTemplate {
+            name: "Load & View",
+            description: "Open a USD file and look at it in the viewport",
+            nodes: vec![
+                node("load", "USD_LoadStage", (0, 0), vec![]),
+                node("viewport", "USD_Viewport", (1, 0), vec![]),
+            ],
+            links: vec![
+                link(("load", "Stage"), ("viewport", "Stage")),
+            ],
+        },
+        GraphTemplate {
+            name: "Primitive + Material + Light + Viewport",
+            description: "A sphere bound to a preview material, lit by a rect light, on a new stage",
+            nodes: vec![
+                node("stage", "USD_CreateStage", (0, 0), vec![]),
+                node("sphere", "USD_Sphere", (1, 0), vec![]),
+                node("material", "USD_Material", (2, 0), vec![
+                    ("diffuse_color", text("0.8, 0.3, 0.2")),
+                ]),
+                node("binding", "USD_Relationship", (3, 0), vec![
+                    ("relationship", text("material:binding")),
+                ]),
+                node("light", "USD_RectLight", (4, 0), vec![
+                    ("intensity", NodeData::Float(10.0)),
+                ]),
+                node("viewport", "USD_Viewport", (5, 0), vec![]),
+            ],
+            links: vec![
+                link(("stage", "Stage"), ("sphere", "Stage")),
+                link(("sphere", "Stage"), ("material", "Stage")),
+                link(("material", "Stage"), ("binding", "Stage")),
+                link(("sphere", "Prim Path"), ("binding", "Prim Path")),
+                link(("material", "Material Path"), ("binding", "Targets")),
+                link(("binding", "Stage"), ("light", "Stage")),
+                link(("light", "Stage"), ("viewport", "Stage")),
+            ],
+        },
+        GraphTemplate {
+            name: "Shot Assembly",
+            description: "Reference a set and a character into a lit shot with a render pass, timeline and save",
+            nodes: vec![
+                node("set", "USD_LoadStage", (0, 0), vec![]),
+                node("character", "USD_LoadStage", (0, 1), vec![]),
+                node("assemble", "USD_Assemble", (1, 0), vec![
+                    ("name_1", text("Set")),
+                    ("name_2", text("Character")),
+                ]),
+                node("timeline", "USD_Timeline", (2, 0), vec![]),
+                node("lights", "USD_LightRig", (3, 0), vec![]),
+                node("pass", "USD_RenderPass", (4, 0), vec![]),
+                node("save", "USD_SaveStage", (5, 0), vec![
+                    ("file_path", text("shot.usda")),
+                ]),
+                node("viewport", "USD_Viewport", (5, 1), vec![]),
+            ],
+            links: vec![
+                link(("set", "Stage"), ("assemble", "Input 1")),
+                link(("character", "Stage"), ("assemble", "Input 2")),
+                link(("assemble", "Stage"), ("timeline", "Stage")),
+                link(("timeline", "Stage"), ("lights", "Stage")),
+                link(("lights", "Stage"), ("pass", "Stage")),
+                link(("lights", "Lights"), ("pass", "Lights")),
+                link(("pass", "Stage"), ("save", "Stage")),
+                link(("pass", "Stage"), ("viewport", "Stage")),
+                link(("timeline", "Time"), ("viewport", "Time")),
+            ],
+        },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    /// Input and output port names of the node types the templates use
+    fn ports(node_type: &str) -> Option<(Vec<String>, Vec<String>)> {
+        let names = |names: &[&str]| names.iter().map(|name| name.to_string()).collect();
+        let (inputs, outputs): (&[&str], &[&str]) = match node_type {
+            "USD_CreateStage" | "USD_LoadStage" => (&[], &["Stage"]),
+            "USD_Viewport" => (&["Stage", "Camera", "Time", "Selected Prim"], &["Rendered Image"]),
+            "USD_Sphere" => (&["Stage", "Parent Path", "Name", "Radius"], &["Stage", "Prim Path"]),
+            "USD_Material" => (&["Stage", "Parent Path", "Name"], &["Stage", "Material Path", "Surface Output"]),
+            "USD_Relationship" => (&["Stage", "Prim Path", "Targets", "Collection"], &["Stage"]),
+            "USD_RectLight" => (&["Stage", "Parent Path", "Name"], &["Stage", "Light Path"]),
+            "USD_Assemble" => (&["Input 1", "Input 2", "Input 3"], &["Stage", "Prims"]),
+            "USD_Timeline" => (&["Stage"], &["Stage", "Time"]),
+            "USD_LightRig" => (&["Stage"], &["Stage", "Rig", "Lights"]),
+            "USD_RenderPass" => (&["Stage", "Camera", "Visible", "Mattes", "Lights"], &["Stage", "Pass"]),
+            "USD_SaveStage" => (&["Stage", "File Path"], &["Success", "Error", "Path"]),
+            _ => return None,
+        };
+        Some((names(inputs), names(outputs)))
+    }
+    
+    #[test]
+    fn templates_link_existing_ports() {
+        for template in graph_templates() {
+            let links = template.resolve_links(ports).unwrap();
+            assert_eq!(links.len(), template.links.len());
+            let mut keys: Vec<&str> = template.nodes.iter().map(|node| node.key).collect();
+            keys.sort_unstable();
+            keys.dedup();
+            assert_eq!(keys.len(), template.nodes.len(), "duplicate keys in '{}'", template.name);
+        }
+        
+        let view = &graph_templates()[0];
+        assert_eq!(view.resolve_links(ports).unwrap(), [ResolvedLink { from_node: 0, from_port: 0, to_node: 1, to_port: 0 }]);
+    }
+    
+    #[test]
+    fn bad_templates_are_rejected() {
+        let mut template = graph_templates().remove(0);
+        template.links[0].to.1 = "Image";
+        assert!(template.resolve_links(ports).unwrap_err().contains("no input port 'Image'"));
+        
+        template.links[0].to = ("missing", "Stage");
+        assert!(template.resolve_links(ports).is_err());
+        
+        template.nodes[0].node_type = "USD_Unknown";
+        assert!(template.resolve_links(ports).unwrap_err().contains("not registered"));
+    }
+}
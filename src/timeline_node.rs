@@ -0,0 +1,287 @@
+//! USD Timeline node - drives the current time code for animated stages
+
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::core::usd_engine::{with_usd_engine, USDTimeRange};
+use crate::ui::help::NodeHelp;
+use crate::ui::keyboard::{focus_marked, handle_key};
+use crate::registry::NodeTypeFactory;
+
+/// Help for the Timeline node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_Timeline",
+    summary: "Drive the current time code for animated USD stages",
+    details: "Outputs the current frame as a time code for viewports and transform nodes. The range follows the stage's startTimeCode and endTimeCode unless a custom range is set.",
+    ports: &[
+        ("Stage", "loaded_0"),
+        ("Time", "1001"),
+    ],
+    samples: &[],
+};
+
+/// Factory of the Timeline node, for `crate::node_factories`
+pub fn factories() -> Vec<Box<dyn NodeFactory>> {
+    vec![NodeTypeFactory::boxed(metadata, |position| PluginNodeHandle::new(Box::new(USDTimelineNode::new(position))))]
+}
+
+/// Metadata of the Timeline node
+fn metadata() -> NodeMetadata {
+    NodeMetadata::new(
+        "USD_Timeline",
+        "Timeline",
+        NodeCategory::new(&["USD", "Stage"]),
+        HELP.summary
+    )
+    .with_color(Color32::from_rgb(80, 150, 200))
+    .with_icon("⏱")
+    .with_inputs(vec![
+        PortDefinition::optional("Stage", DataType::String)
+            .with_description("USD stage providing the playback range"),
+    ])
+    .with_outputs(vec![
+        PortDefinition::optional("Stage", DataType::String)
+            .with_description("Pass-through USD stage"),
+        PortDefinition::required("Time", DataType::Float)
+            .with_description("Current time code"),
+    ])
+    .with_panel_type(PanelType::Parameter)
+    .with_workspace_compatibility(vec!["3D"])
+}
+
+/// USD Timeline node providing the current frame to downstream nodes
+pub struct USDTimelineNode {
+    id: String,
+    position: Pos2,
+    stage_ref: String,
+    /// Range read from the stage's startTimeCode/endTimeCode
+    stage_range: USDTimeRange,
+    /// Use the stage range instead of the manual start/end
+    use_stage_range: bool,
+    start_frame: f64,
+    end_frame: f64,
+    current_frame: f64,
+}
+
+impl USDTimelineNode {
+    pub fn new(position: Pos2) -> Self {
+        let range = USDTimeRange::default();
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            position,
+            stage_ref: String::new(),
+            stage_range: range,
+            use_stage_range: true,
+            start_frame: range.start_time_code,
+            end_frame: range.end_time_code,
+            current_frame: range.start_time_code,
+        }
+    }
+    
+    /// Active playback range
+    pub fn time_range(&self) -> USDTimeRange {
+        if self.use_stage_range && !self.stage_ref.is_empty() {
+            self.stage_range
+        } else {
+            USDTimeRange {
+                start_time_code: self.start_frame,
+                end_time_code: self.end_frame.max(self.start_frame),
+                time_codes_per_second: self.stage_range.time_codes_per_second,
+            }
+        }
+    }
+    
+    fn refresh_stage_range(&mut self) {
+        let stage_ref = self.stage_ref.clone();
+        let range = with_usd_engine(|engine| {
+            engine.resolve_stage(&stage_ref)
+                .and_then(|stage| engine.get_stage_time_range(&stage.identifier))
+        });
+        match range {
+            Ok(range) => self.stage_range = range,
+            Err(e) => eprintln!("Timeline: {}", e),
+        }
+        self.current_frame = self.time_range().clamp(self.current_frame);
+    }
+    
+    fn step(&mut self, delta: f64) {
+        let range = self.time_range();
+        self.current_frame = range.clamp(self.current_frame.round() + delta);
+    }
+}
+
+impl PluginNode for USDTimelineNode {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+    
+    fn position(&self) -> Pos2 {
+        self.position
+    }
+    
+    fn set_position(&mut self, position: Pos2) {
+        self.position = position;
+    }
+    
+    fn get_parameter_ui(&self) -> ParameterUI {
+        let mut elements = Vec::new();
+        let range = self.time_range();
+        
+        elements.push(UIElement::Heading("USD Timeline".to_string()));
+        elements.push(UIElement::Separator);
+        
+        elements.push(UIElement::Slider {
+            label: "Frame".to_string(),
+            value: self.current_frame as f32,
+            min: range.start_time_code as f32,
+            max: range.end_time_code as f32,
+            parameter_name: "frame".to_string(),
+        });
+        
+        elements.push(UIElement::Button { label: "⏮ Start".to_string(), action: "first_frame".to_string() });
+        elements.push(UIElement::Button { label: "◀ Prev".to_string(), action: "previous_frame".to_string() });
+        elements.push(UIElement::Button { label: "Next ▶".to_string(), action: "next_frame".to_string() });
+        elements.push(UIElement::Button { label: "End ⏭".to_string(), action: "last_frame".to_string() });
+        
+        elements.push(UIElement::Separator);
+        
+        elements.push(UIElement::Checkbox {
+            label: "Use Stage Range".to_string(),
+            value: self.use_stage_range,
+            parameter_name: "use_stage_range".to_string(),
+        });
+        
+        if self.use_stage_range {
+            elements.push(UIElement::Label(format!(
+                "Stage range: {} - {} @ {} fps",
+                self.stage_range.start_time_code, self.stage_range.end_time_code, self.stage_range.time_codes_per_second
+            )));
+        } else {
+            elements.push(UIElement::TextEdit {
+                label: "Start Frame".to_string(),
+                value: self.start_frame.to_string(),
+                parameter_name: "start_frame".to_string(),
+            });
+            elements.push(UIElement::TextEdit {
+                label: "End Frame".to_string(),
+                value: self.end_frame.to_string(),
+                parameter_name: "end_frame".to_string(),
+            });
+        }
+        elements.extend(HELP.section());
+        
+        ParameterUI { elements: focus_marked(&self.id, elements) }
+    }
+    
+    fn handle_ui_action(&mut self, action: UIAction) -> Vec<ParameterChange> {
+        if let Some(changes) = handle_key(self, &[], &action) {
+            return changes;
+        }
+        
+        let mut changes = Vec::new();
+        
+        match action {
+            UIAction::ParameterChanged { parameter, value } => {
+                match parameter.as_str() {
+                    "frame" => {
+                        if let Some(frame) = value.as_float() {
+                            self.set_parameter("frame", NodeData::Float(frame));
+                        }
+                    }
+                    "use_stage_range" => {
+                        if let Some(enabled) = value.as_boolean() {
+                            self.set_parameter("use_stage_range", NodeData::Boolean(enabled));
+                            changes.push(ParameterChange {
+                                parameter: parameter.clone(),
+                                value: NodeData::Boolean(enabled),
+                            });
+                        }
+                    }
+                    "start_frame" | "end_frame" => {
+                        if let Some(text) = value.as_string() {
+                            self.set_parameter(&parameter, NodeData::String(text.to_string()));
+                            changes.push(ParameterChange {
+                                parameter: parameter.clone(),
+                                value: NodeData::String(text.to_string()),
+                            });
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            UIAction::ButtonClicked { action } => {
+                let range = self.time_range();
+                match action.as_str() {
+                    "first_frame" => self.current_frame = range.start_time_code,
+                    "last_frame" => self.current_frame = range.end_time_code,
+                    "previous_frame" => self.step(-1.0),
+                    "next_frame" => self.step(1.0),
+                    _ => return changes,
+                }
+            }
+        }
+        
+        // Every action can move the current frame
+        changes.push(ParameterChange {
+            parameter: "frame".to_string(),
+            value: NodeData::Float(self.current_frame as f32),
+        });
+        changes
+    }
+    
+    fn get_parameter(&self, name: &str) -> Option<NodeData> {
+        match name {
+            "frame" => Some(NodeData::Float(self.current_frame as f32)),
+            "use_stage_range" => Some(NodeData::Boolean(self.use_stage_range)),
+            "start_frame" => Some(NodeData::String(self.start_frame.to_string())),
+            "end_frame" => Some(NodeData::String(self.end_frame.to_string())),
+            _ => None,
+        }
+    }
+    
+    fn set_parameter(&mut self, name: &str, value: NodeData) {
+        match name {
+            "frame" => {
+                if let Some(frame) = value.as_float() {
+                    self.current_frame = self.time_range().clamp(frame as f64);
+                }
+            }
+            "use_stage_range" => {
+                if let Some(enabled) = value.as_boolean() {
+                    self.use_stage_range = enabled;
+                    self.current_frame = self.time_range().clamp(self.current_frame);
+                }
+            }
+            "start_frame" => {
+                if let Some(frame) = value.as_string().and_then(|text| text.trim().parse::<f64>().ok()) {
+                    self.start_frame = frame;
+                    self.current_frame = self.time_range().clamp(self.current_frame);
+                }
+            }
+            "end_frame" => {
+                if let Some(frame) = value.as_string().and_then(|text| text.trim().parse::<f64>().ok()) {
+                    self.end_frame = frame;
+                    self.current_frame = self.time_range().clamp(self.current_frame);
+                }
+            }
+            _ => {}
+        }
+    }
+    
+    fn process(&mut self, inputs: &HashMap<String, NodeData>) -> HashMap<String, NodeData> {
+        let mut outputs = HashMap::new();
+        
+        match inputs.get("Stage").and_then(|data| data.as_string()) {
+            Some(stage_ref) => {
+                if stage_ref != self.stage_ref {
+                    self.stage_ref = stage_ref.to_string();
+                    self.refresh_stage_range();
+                }
+                outputs.insert("Stage".to_string(), NodeData::String(self.stage_ref.clone()));
+            }
+            None => self.stage_ref.clear(),
+        }
+        
+        outputs.insert("Time".to_string(), NodeData::Float(self.current_frame as f32));
+        outputs
+    }
+}
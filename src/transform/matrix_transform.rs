@@ -1,8 +0,0 @@
-//! USD Matrix Transform node - stub implementation
-
-pub struct USDMatrixTransformNode;
-pub struct USDMatrixTransformLogic;
-
-impl Default for USDMatrixTransformNode {
-    fn default() -> Self { Self }
-}
\ No newline at end of file
@@ -1,13 +0,0 @@
-//! USD Transform nodes for spatial manipulation
-
-pub mod xform;
-pub mod translate;
-pub mod rotate;
-pub mod scale;
-pub mod matrix_transform;
-
-pub use xform::{USDXformNode, USDXformLogic};
-pub use translate::{USDTranslateNode, USDTranslateLogic};
-pub use rotate::{USDRotateNode, USDRotateLogic};
-pub use scale::{USDScaleNode, USDScaleLogic};
-pub use matrix_transform::{USDMatrixTransformNode, USDMatrixTransformLogic};
\ No newline at end of file
@@ -1,8 +0,0 @@
-//! USD Rotate transform node - stub implementation
-
-pub struct USDRotateNode;
-pub struct USDRotateLogic;
-
-impl Default for USDRotateNode {
-    fn default() -> Self { Self }
-}
\ No newline at end of file
@@ -1,8 +0,0 @@
-//! USD Scale transform node - stub implementation
-
-pub struct USDScaleNode;
-pub struct USDScaleLogic;
-
-impl Default for USDScaleNode {
-    fn default() -> Self { Self }
-}
\ No newline at end of file
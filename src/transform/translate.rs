@@ -1,8 +0,0 @@
-//! USD Translate transform node - stub implementation
-
-pub struct USDTranslateNode;
-pub struct USDTranslateLogic;
-
-impl Default for USDTranslateNode {
-    fn default() -> Self { Self }
-}
\ No newline at end of file
@@ -1,8 +0,0 @@
-//! USD Xform transform node - stub implementation
-
-pub struct USDXformNode;
-pub struct USDXformLogic;
-
-impl Default for USDXformNode {
-    fn default() -> Self { Self }
-}
\ No newline at end of file
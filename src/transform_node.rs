@@ -0,0 +1,500 @@
+//! USD Translate/Rotate/Scale nodes - author a transform op on a prim, static or keyframed
+
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::core::usd_engine::{with_usd_engine, KeyInterpolation};
+use crate::core::usd_value::UsdValue;
+use crate::ui::choice::{choice_buttons, parse_choice};
+use crate::ui::help::NodeHelp;
+use crate::ui::palette::status_label;
+use crate::ui::keyboard::{focus_marked, handle_key};
+use crate::registry::NodeTypeFactory;
+
+/// Help for the Translate node
+pub const TRANSLATE_HELP: NodeHelp = NodeHelp {
+    node_type: "USD_Translate",
+    summary: "Translate a USD prim, static or keyframed with linear, held or eased interpolation",
+    details: "Authors an xformOp:translate on Prim Path. With Animate on, keys are set at Key Time and interpolated with the chosen mode between them.",
+    ports: &[
+        ("Stage", "stage_0"),
+        ("Prim Path", "/World/Ball"),
+        ("Time", "1001"),
+    ],
+    samples: &[],
+};
+
+/// Help for the Rotate node
+pub const ROTATE_HELP: NodeHelp = NodeHelp {
+    node_type: "USD_Rotate",
+    summary: "Rotate a USD prim, static or keyframed with linear, held or eased interpolation",
+    details: "Authors an xformOp:rotateXYZ in degrees on Prim Path. With Animate on, keys are set at Key Time and interpolated with the chosen mode between them.",
+    ports: &[
+        ("Stage", "stage_0"),
+        ("Prim Path", "/World/Ball"),
+        ("Time", "1001"),
+    ],
+    samples: &[],
+};
+
+/// Help for the Scale node
+pub const SCALE_HELP: NodeHelp = NodeHelp {
+    node_type: "USD_Scale",
+    summary: "Scale a USD prim, static or keyframed with linear, held or eased interpolation",
+    details: "Authors an xformOp:scale on Prim Path. With Animate on, keys are set at Key Time and interpolated with the chosen mode between them.",
+    ports: &[
+        ("Stage", "stage_0"),
+        ("Prim Path", "/World/Ball"),
+        ("Time", "1001"),
+    ],
+    samples: &[],
+};
+
+/// Factories of the Translate, Rotate and Scale nodes, for `crate::node_factories`
+pub fn factories() -> Vec<Box<dyn NodeFactory>> {
+    vec![
+        NodeTypeFactory::boxed(translate_metadata, |position| PluginNodeHandle::new(Box::new(USDTransformNode::new(TransformOp::Translate, position)))),
+        NodeTypeFactory::boxed(rotate_metadata, |position| PluginNodeHandle::new(Box::new(USDTransformNode::new(TransformOp::Rotate, position)))),
+        NodeTypeFactory::boxed(scale_metadata, |position| PluginNodeHandle::new(Box::new(USDTransformNode::new(TransformOp::Scale, position)))),
+    ]
+}
+
+/// Metadata of the Translate node
+fn translate_metadata() -> NodeMetadata {
+    NodeMetadata::new(
+        "USD_Translate",
+        "Translate",
+        NodeCategory::new(&["USD", "Transform"]),
+        TRANSLATE_HELP.summary
+    )
+    .with_color(Color32::from_rgb(150, 120, 200))
+    .with_icon("📍")
+    .with_inputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("USD stage"),
+        PortDefinition::optional("Prim Path", DataType::String)
+            .with_description("Prim to transform, overriding the parameter"),
+        PortDefinition::optional("Time", DataType::Float)
+            .with_description("Time code to set keys at"),
+    ])
+    .with_outputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("Stage with the translate authored"),
+    ])
+    .with_panel_type(PanelType::Parameter)
+    .with_workspace_compatibility(vec!["3D"])
+}
+
+/// Metadata of the Rotate node
+fn rotate_metadata() -> NodeMetadata {
+    NodeMetadata::new(
+        "USD_Rotate",
+        "Rotate",
+        NodeCategory::new(&["USD", "Transform"]),
+        ROTATE_HELP.summary
+    )
+    .with_color(Color32::from_rgb(150, 120, 200))
+    .with_icon("🔁")
+    .with_inputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("USD stage"),
+        PortDefinition::optional("Prim Path", DataType::String)
+            .with_description("Prim to transform, overriding the parameter"),
+        PortDefinition::optional("Time", DataType::Float)
+            .with_description("Time code to set keys at"),
+    ])
+    .with_outputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("Stage with the rotate authored"),
+    ])
+    .with_panel_type(PanelType::Parameter)
+    .with_workspace_compatibility(vec!["3D"])
+}
+
+/// Metadata of the Scale node
+fn scale_metadata() -> NodeMetadata {
+    NodeMetadata::new(
+        "USD_Scale",
+        "Scale",
+        NodeCategory::new(&["USD", "Transform"]),
+        SCALE_HELP.summary
+    )
+    .with_color(Color32::from_rgb(150, 120, 200))
+    .with_icon("📏")
+    .with_inputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("USD stage"),
+        PortDefinition::optional("Prim Path", DataType::String)
+            .with_description("Prim to transform, overriding the parameter"),
+        PortDefinition::optional("Time", DataType::Float)
+            .with_description("Time code to set keys at"),
+    ])
+    .with_outputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("Stage with the scale authored"),
+    ])
+    .with_panel_type(PanelType::Parameter)
+    .with_workspace_compatibility(vec!["3D"])
+}
+
+/// Transform op authored by a transform node
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransformOp {
+    Translate,
+    Rotate,
+    Scale,
+}
+
+impl TransformOp {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TransformOp::Translate => "Translate",
+            TransformOp::Rotate => "Rotate",
+            TransformOp::Scale => "Scale",
+        }
+    }
+    
+    pub fn help(&self) -> &'static NodeHelp {
+        match self {
+            TransformOp::Translate => &TRANSLATE_HELP,
+            TransformOp::Rotate => &ROTATE_HELP,
+            TransformOp::Scale => &SCALE_HELP,
+        }
+    }
+    
+    /// Attribute name of the op, as listed in xformOpOrder
+    pub fn attr_name(&self) -> &'static str {
+        match self {
+            TransformOp::Translate => "xformOp:translate",
+            TransformOp::Rotate => "xformOp:rotateXYZ",
+            TransformOp::Scale => "xformOp:scale",
+        }
+    }
+    
+    fn identity(&self) -> [f64; 3] {
+        match self {
+            TransformOp::Translate | TransformOp::Rotate => [0.0; 3],
+            TransformOp::Scale => [1.0; 3],
+        }
+    }
+}
+
+/// USD transform node
+///
+/// Authors one transform op on a prim. With "Animate" on, keys are set at the
+/// time from the "Time" input (or the Key Time parameter) and authored as time
+/// samples with the chosen interpolation; otherwise the value is static.
+pub struct USDTransformNode {
+    id: String,
+    position: Pos2,
+    op: TransformOp,
+    prim_path: String,
+    value: [f64; 3],
+    animate: bool,
+    /// Keyframes as (time code, value), sorted by time
+    keys: Vec<(f64, [f64; 3])>,
+    interpolation: KeyInterpolation,
+    key_time: f64,
+    stage_ref: String,
+    dirty: bool,
+    status: String,
+}
+
+impl USDTransformNode {
+    pub fn new(op: TransformOp, position: Pos2) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            position,
+            op,
+            prim_path: String::new(),
+            value: op.identity(),
+            animate: false,
+            keys: Vec::new(),
+            interpolation: KeyInterpolation::Linear,
+            key_time: 1.0,
+            stage_ref: String::new(),
+            dirty: true,
+            status: "No USD stage connected".to_string(),
+        }
+    }
+    
+    /// Set a key at the current time with the current value, replacing any key at that time
+    fn set_key(&mut self) {
+        let time = self.key_time;
+        self.keys.retain(|(key_time, _)| *key_time != time);
+        let index = self.keys.partition_point(|(key_time, _)| *key_time < time);
+        self.keys.insert(index, (time, self.value));
+        self.dirty = true;
+    }
+    
+    fn delete_key(&mut self) {
+        let count = self.keys.len();
+        self.keys.retain(|(key_time, _)| *key_time != self.key_time);
+        self.dirty |= self.keys.len() != count;
+    }
+    
+    fn author(&mut self) -> Result<(), String> {
+        if self.prim_path.is_empty() {
+            return Err("No prim path set".to_string());
+        }
+        let (stage_ref, prim_path, attr_name) = (self.stage_ref.clone(), self.prim_path.clone(), self.op.attr_name());
+        let animated = self.animate && !self.keys.is_empty();
+        let keys: Vec<(f64, UsdValue)> = self.keys.iter()
+            .map(|(time, value)| (*time, UsdValue::Vec3(*value)))
+            .collect();
+        let (value, interpolation) = (self.value, self.interpolation);
+        
+        with_usd_engine(|engine| {
+            let stage = engine.resolve_stage(&stage_ref)?;
+            engine.add_xform_op(&stage.identifier, &prim_path, attr_name)?;
+            if animated {
+                engine.set_keyframes(&stage.identifier, &prim_path, attr_name, &keys, interpolation)
+            } else {
+                // Drop keys authored while animating before setting the static value
+                engine.set_keyframes(&stage.identifier, &prim_path, attr_name, &[], interpolation)?;
+                engine.set_attribute(&stage.identifier, &prim_path, attr_name, UsdValue::Vec3(value))
+            }
+        })?;
+        
+        self.status = if animated {
+            format!("{} {} keys ({})", self.prim_path, self.keys.len(), self.interpolation.name())
+        } else {
+            format!("{} {} = {}", self.prim_path, self.op.name(), UsdValue::Vec3(self.value))
+        };
+        Ok(())
+    }
+}
+
+/// Keys as "time: (x, y, z)" entries separated by semicolons
+fn format_keys(keys: &[(f64, [f64; 3])]) -> String {
+    keys.iter()
+        .map(|(time, value)| format!("{}: {}", time, UsdValue::Vec3(*value)))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+fn parse_keys(text: &str) -> Option<Vec<(f64, [f64; 3])>> {
+    let mut keys = text.split(';')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (time, value) = entry.split_once(':')?;
+            match UsdValue::parse(value, "double3").ok()? {
+                UsdValue::Vec3(value) => Some((time.trim().parse::<f64>().ok()?, value)),
+                _ => None,
+            }
+        })
+        .collect::<Option<Vec<_>>>()?;
+    keys.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+    Some(keys)
+}
+
+impl PluginNode for USDTransformNode {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+    
+    fn position(&self) -> Pos2 {
+        self.position
+    }
+    
+    fn set_position(&mut self, position: Pos2) {
+        self.position = position;
+    }
+    
+    fn get_parameter_ui(&self) -> ParameterUI {
+        let mut elements = Vec::new();
+        
+        elements.push(UIElement::Heading(format!("USD {}", self.op.name())));
+        elements.push(UIElement::Separator);
+        
+        elements.push(UIElement::TextEdit {
+            label: "Prim Path".to_string(),
+            value: self.prim_path.clone(),
+            parameter_name: "prim_path".to_string(),
+        });
+        elements.push(UIElement::TextEdit {
+            label: self.op.name().to_string(),
+            value: UsdValue::Vec3(self.value).to_string(),
+            parameter_name: "value".to_string(),
+        });
+        elements.push(UIElement::Checkbox {
+            label: "Animate".to_string(),
+            value: self.animate,
+            parameter_name: "animate".to_string(),
+        });
+        
+        if self.animate {
+            elements.push(UIElement::Separator);
+            elements.push(UIElement::TextEdit {
+                label: "Key Time".to_string(),
+                value: self.key_time.to_string(),
+                parameter_name: "key_time".to_string(),
+            });
+            let keyed = self.keys.iter().any(|(time, _)| *time == self.key_time);
+            elements.push(UIElement::Button {
+                label: format!("{} Set Key", if keyed { "◆" } else { "◇" }),
+                action: "set_key".to_string(),
+            });
+            if keyed {
+                elements.push(UIElement::Button {
+                    label: "Delete Key".to_string(),
+                    action: "delete_key".to_string(),
+                });
+            }
+            if !self.keys.is_empty() {
+                elements.push(UIElement::Button {
+                    label: "Clear Keys".to_string(),
+                    action: "clear_keys".to_string(),
+                });
+            }
+            elements.extend(choice_buttons("Interpolation", "interpolation", &KeyInterpolation::ALL.map(|interpolation| interpolation.name()), self.interpolation.name()));
+            elements.push(UIElement::Label(format!("Keys ({})", self.keys.len())));
+            for (time, value) in &self.keys {
+                elements.push(UIElement::Label(format!("  {}: {}", time, UsdValue::Vec3(*value))));
+            }
+        }
+        
+        elements.push(UIElement::Separator);
+        elements.push(status_label(&self.status));
+        elements.extend(self.op.help().section());
+        
+        ParameterUI { elements: focus_marked(&self.id, elements) }
+    }
+    
+    fn handle_ui_action(&mut self, action: UIAction) -> Vec<ParameterChange> {
+        if let Some(changes) = handle_key(self, &[], &action) {
+            return changes;
+        }
+        
+        let mut changes = Vec::new();
+        
+        match action {
+            UIAction::ParameterChanged { parameter, value } => {
+                if matches!(parameter.as_str(), "prim_path" | "value" | "animate" | "key_time") {
+                    self.set_parameter(&parameter, value);
+                    if let Some(value) = self.get_parameter(&parameter) {
+                        changes.push(ParameterChange { parameter, value });
+                    }
+                }
+            }
+            UIAction::ButtonClicked { action } => {
+                match action.as_str() {
+                    "set_key" => self.set_key(),
+                    "delete_key" => self.delete_key(),
+                    "clear_keys" => {
+                        self.keys.clear();
+                        self.dirty = true;
+                    }
+                    _ => {
+                        let Some(interpolation) = parse_choice(&action, "interpolation") else {
+                            return changes;
+                        };
+                        self.set_parameter("interpolation", NodeData::String(interpolation.to_string()));
+                        changes.push(ParameterChange {
+                            parameter: "interpolation".to_string(),
+                            value: NodeData::String(interpolation.to_string()),
+                        });
+                        return changes;
+                    }
+                }
+                changes.push(ParameterChange {
+                    parameter: "keys".to_string(),
+                    value: NodeData::String(format_keys(&self.keys)),
+                });
+            }
+        }
+        
+        changes
+    }
+    
+    fn get_parameter(&self, name: &str) -> Option<NodeData> {
+        match name {
+            "prim_path" => Some(NodeData::String(self.prim_path.clone())),
+            "value" => Some(NodeData::String(UsdValue::Vec3(self.value).to_string())),
+            "animate" => Some(NodeData::Boolean(self.animate)),
+            "key_time" => Some(NodeData::Float(self.key_time as f32)),
+            "keys" => Some(NodeData::String(format_keys(&self.keys))),
+            "interpolation" => Some(NodeData::String(self.interpolation.name().to_string())),
+            _ => None,
+        }
+    }
+    
+    fn set_parameter(&mut self, name: &str, value: NodeData) {
+        match name {
+            "prim_path" => {
+                if let Some(path) = value.as_string() {
+                    self.prim_path = path.trim().to_string();
+                    self.dirty = true;
+                }
+            }
+            "value" => {
+                if let Some(Ok(UsdValue::Vec3(components))) = value.as_string().map(|text| UsdValue::parse(text, "double3")) {
+                    self.value = components;
+                    // While animating, the value only takes effect once keyed
+                    self.dirty |= !self.animate;
+                }
+            }
+            "animate" => {
+                if let Some(animate) = value.as_boolean() {
+                    self.animate = animate;
+                    self.dirty = true;
+                }
+            }
+            "key_time" => {
+                let time = value.as_float()
+                    .map(f64::from)
+                    .or_else(|| value.as_string().and_then(|text| text.trim().parse().ok()));
+                if let Some(time) = time {
+                    self.key_time = time;
+                }
+            }
+            "keys" => {
+                if let Some(keys) = value.as_string().and_then(parse_keys) {
+                    self.keys = keys;
+                    self.dirty = true;
+                }
+            }
+            "interpolation" => {
+                if let Some(interpolation) = value.as_string().and_then(KeyInterpolation::from_name) {
+                    self.interpolation = interpolation;
+                    self.dirty = true;
+                }
+            }
+            _ => {}
+        }
+    }
+    
+    fn process(&mut self, inputs: &HashMap<String, NodeData>) -> HashMap<String, NodeData> {
+        let mut outputs = HashMap::new();
+        
+        let Some(stage_ref) = inputs.get("Stage").and_then(|data| data.as_string()) else {
+            self.stage_ref.clear();
+            self.status = "No USD stage connected".to_string();
+            return outputs;
+        };
+        
+        if stage_ref != self.stage_ref {
+            self.stage_ref = stage_ref.to_string();
+            self.dirty = true;
+        }
+        // Connected prim paths and times override the parameters
+        if let Some(path) = inputs.get("Prim Path").and_then(|data| data.as_string()) {
+            if path.trim() != self.prim_path {
+                self.set_parameter("prim_path", NodeData::String(path.to_string()));
+            }
+        }
+        if let Some(time) = inputs.get("Time").and_then(|data| data.as_float()) {
+            self.key_time = f64::from(time);
+        }
+        
+        if self.dirty {
+            self.dirty = false;
+            if let Err(e) = self.author() {
+                self.status = format!("⚠ {}", e);
+            }
+        }
+        
+        outputs.insert("Stage".to_string(), NodeData::String(self.stage_ref.clone()));
+        outputs
+    }
+}
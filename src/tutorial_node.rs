@@ -0,0 +1,226 @@
+//! USD Tutorial node - writes a sample stage and checks off first steps with it
+
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::core::tutorial::{default_tutorial_path, is_recorded, is_same_file, next_step, record_step, write_tutorial_stage, TutorialStep, TUTORIAL_BALL, TUTORIAL_MATERIAL};
+use crate::core::usd_engine::with_usd_engine;
+use crate::viewport::picking::selected_prims;
+use crate::ui::help::NodeHelp;
+use crate::ui::i18n::tr;
+use crate::ui::palette::status_label;
+use crate::ui::keyboard::{focus_marked, handle_key};
+use crate::registry::NodeTypeFactory;
+
+/// Help for the Tutorial node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_Tutorial",
+    summary: "Guided first steps with USD on a generated sample stage",
+    details: "Writes a small tutorial stage to Tutorial File and lists the steps to take with it: load it with Load Stage, view it in a Viewport, select the ball, bind the Clay material to it with a Relationship node and frame it. Each step is checked off as it is done, and the next step's hint explains the USD concept behind it. Write Tutorial Stage writes a fresh copy of the file.",
+    ports: &[
+        ("File Path", "/tmp/nodle_tutorial.usda"),
+        ("Complete", "false"),
+    ],
+    samples: &[],
+};
+
+/// Factory of the Tutorial node, for `crate::node_factories`
+pub fn factories() -> Vec<Box<dyn NodeFactory>> {
+    vec![NodeTypeFactory::boxed(metadata, |position| PluginNodeHandle::new(Box::new(USDTutorialNode::new(position))))]
+}
+
+/// Metadata of the Tutorial node
+fn metadata() -> NodeMetadata {
+    NodeMetadata::new(
+        "USD_Tutorial",
+        "Tutorial",
+        NodeCategory::new(&["USD", "Stage"]),
+        HELP.summary
+    )
+    .with_color(Color32::from_rgb(80, 150, 200))
+    .with_icon("🎓")
+    .with_outputs(vec![
+        PortDefinition::required("File Path", DataType::String)
+            .with_description("Written tutorial stage, to open with Load Stage"),
+        PortDefinition::optional("Complete", DataType::Boolean)
+            .with_description("Whether every tutorial step is done"),
+    ])
+    .with_panel_type(PanelType::Parameter)
+    .with_workspace_compatibility(vec!["3D"])
+}
+
+/// USD Tutorial node
+///
+/// Finds the tutorial stage among the engine's stages by its file and
+/// checks each step against the stage, the shared selection and the steps
+/// viewports record, every time it processes.
+pub struct USDTutorialNode {
+    id: String,
+    position: Pos2,
+    file_path: String,
+    /// Path the tutorial stage was last written to
+    written: Option<String>,
+    /// Steps done so far, in tutorial order
+    done: Vec<TutorialStep>,
+    dirty: bool,
+    status: String,
+}
+
+impl USDTutorialNode {
+    pub fn new(position: Pos2) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            position,
+            file_path: default_tutorial_path(),
+            written: None,
+            done: Vec::new(),
+            dirty: true,
+            status: "Tutorial stage not written".to_string(),
+        }
+    }
+    
+    /// Check the steps against the loaded tutorial stage
+    fn refresh_progress(&mut self) {
+        let file_path = self.file_path.clone();
+        let stage_id = with_usd_engine(|engine| engine.get_stage_ids().into_iter()
+            .find(|id| engine.get_stage(id).is_some_and(|stage| is_same_file(&stage.path, &file_path))));
+        self.done.clear();
+        let Some(stage_id) = stage_id else {
+            self.status = "Waiting for the tutorial stage to be loaded".to_string();
+            return;
+        };
+        
+        // Selections come and go, so a selected ball is recorded once seen
+        if selected_prims(&stage_id).1.iter().any(|path| path == TUTORIAL_BALL) {
+            record_step(&stage_id, TutorialStep::SelectPrim);
+        }
+        let bound = with_usd_engine(|engine| engine.get_relationship_targets(&stage_id, TUTORIAL_BALL, "material:binding"))
+            .is_ok_and(|targets| targets.iter().any(|target| target == TUTORIAL_MATERIAL));
+        self.done = TutorialStep::ALL.into_iter()
+            .filter(|step| match step {
+                TutorialStep::LoadStage => true,
+                TutorialStep::BindMaterial => bound,
+                _ => is_recorded(&stage_id, *step),
+            })
+            .collect();
+        self.status = match next_step(&self.done) {
+            Some(_) => format!("{} of {} steps done", self.done.len(), TutorialStep::ALL.len()),
+            None => "✓ Tutorial complete".to_string(),
+        };
+    }
+}
+
+impl PluginNode for USDTutorialNode {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+    
+    fn position(&self) -> Pos2 {
+        self.position
+    }
+    
+    fn set_position(&mut self, position: Pos2) {
+        self.position = position;
+    }
+    
+    fn get_parameter_ui(&self) -> ParameterUI {
+        let mut elements = Vec::new();
+        
+        elements.push(UIElement::Heading("USD Tutorial".to_string()));
+        elements.push(UIElement::Separator);
+        
+        elements.push(UIElement::TextEdit {
+            label: "Tutorial File".to_string(),
+            value: self.file_path.clone(),
+            parameter_name: "file_path".to_string(),
+        });
+        elements.push(UIElement::Button {
+            label: "Write Tutorial Stage".to_string(),
+            action: "write_stage".to_string(),
+        });
+        
+        elements.push(UIElement::Separator);
+        for (index, step) in TutorialStep::ALL.iter().enumerate() {
+            let mark = if self.done.contains(step) { "☑" } else { "☐" };
+            elements.push(UIElement::Label(format!("{} {}. {}", mark, index + 1, tr(step.title()))));
+        }
+        if let Some(step) = next_step(&self.done) {
+            elements.push(UIElement::Label(tr(step.hint())));
+        }
+        
+        elements.push(UIElement::Separator);
+        elements.push(status_label(&self.status));
+        elements.extend(HELP.section());
+        
+        ParameterUI { elements: focus_marked(&self.id, elements) }
+    }
+    
+    fn handle_ui_action(&mut self, action: UIAction) -> Vec<ParameterChange> {
+        if let Some(changes) = handle_key(self, &[], &action) {
+            return changes;
+        }
+        
+        let mut changes = Vec::new();
+        
+        match action {
+            UIAction::ParameterChanged { parameter, value } => {
+                if parameter == "file_path" {
+                    if let Some(path) = value.as_string() {
+                        self.set_parameter(&parameter, NodeData::String(path.to_string()));
+                        changes.push(ParameterChange {
+                            parameter: parameter.clone(),
+                            value: NodeData::String(self.file_path.clone()),
+                        });
+                    }
+                }
+            }
+            UIAction::ButtonClicked { action } => {
+                if action == "write_stage" {
+                    self.dirty = true;
+                }
+            }
+        }
+        
+        changes
+    }
+    
+    fn get_parameter(&self, name: &str) -> Option<NodeData> {
+        match name {
+            "file_path" => Some(NodeData::String(self.file_path.clone())),
+            _ => None,
+        }
+    }
+    
+    fn set_parameter(&mut self, name: &str, value: NodeData) {
+        if name == "file_path" {
+            if let Some(path) = value.as_string() {
+                self.file_path = path.trim().to_string();
+                self.dirty = true;
+            }
+        }
+    }
+    
+    fn process(&mut self, _inputs: &HashMap<String, NodeData>) -> HashMap<String, NodeData> {
+        let mut outputs = HashMap::new();
+        
+        if self.dirty {
+            self.dirty = false;
+            self.written = None;
+            self.done.clear();
+            if self.file_path.is_empty() {
+                self.status = "⚠ No tutorial file path set".to_string();
+            } else {
+                match write_tutorial_stage(&self.file_path) {
+                    Ok(path) => self.written = Some(path),
+                    Err(e) => self.status = format!("⚠ {}", e),
+                }
+            }
+        }
+        
+        if let Some(path) = &self.written {
+            outputs.insert("File Path".to_string(), NodeData::String(path.clone()));
+            self.refresh_progress();
+        }
+        outputs.insert("Complete".to_string(), NodeData::Boolean(self.written.is_some() && next_step(&self.done).is_none()));
+        outputs
+    }
+}
@@ -0,0 +1,27 @@
+//! Button-row selectors for parameters with a fixed set of options
+//!
+//! The SDK has no combo box element, so a choice is shown as a label plus
+//! one button per option. Button actions are "<parameter>:<option>".
+
+use nodle_plugin_sdk::*;
+use super::i18n::tr;
+
+/// Build a labelled row of option buttons, marking the current option
+///
+/// The label and options are shown translated; actions keep the untranslated option.
+pub fn choice_buttons(label: &str, parameter: &str, options: &[&str], current: &str) -> Vec<UIElement> {
+    let mut elements = vec![UIElement::Label(format!("{}: {}", tr(label), tr(current)))];
+    for option in options {
+        let marker = if *option == current { "● " } else { "" };
+        elements.push(UIElement::Button {
+            label: format!("{}{}", marker, tr(option)),
+            action: format!("{}:{}", parameter, option),
+        });
+    }
+    elements
+}
+
+/// Option selected by a button action for the given parameter, if any
+pub fn parse_choice<'a>(action: &'a str, parameter: &str) -> Option<&'a str> {
+    action.strip_prefix(parameter)?.strip_prefix(':')
+}
@@ -0,0 +1,166 @@
+//! Node help shown in the "?" section of node panels
+//!
+//! Every node module declares one `HELP` constant. Its summary is the
+//! description in the node's metadata and the rest fills the "?" section at
+//! the bottom of the node's panel, so both always agree. `node_help` looks
+//! the same constants up by node type for the host.
+
+use nodle_plugin_sdk::*;
+use super::i18n::tr;
+
+/// Sample stages shared by nodes that work on any scene
+pub const KITCHEN_SET: &str = "Kitchen Set: https://openusd.org/release/dl_downloads.html";
+pub const USD_WG_ASSETS: &str = "USD-WG assets: https://github.com/usd-wg/assets";
+
+/// Schema documentation shared by the lighting and shading nodes
+pub const USD_LUX: &str = "UsdLux: https://openusd.org/release/api/usd_lux_page_front.html";
+pub const PREVIEW_SURFACE: &str = "UsdPreviewSurface: https://openusd.org/release/spec_usdpreviewsurface.html";
+
+/// Help for one node type
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NodeHelp {
+    pub node_type: &'static str,
+    /// One line, used as the metadata description
+    pub summary: &'static str,
+    /// What the node does and how it fits into a graph
+    pub details: &'static str,
+    /// Example values per port, as (port name, example)
+    pub ports: &'static [(&'static str, &'static str)],
+    /// Sample stages or documentation to try the node with, as "name: link"
+    pub samples: &'static [&'static str],
+}
+
+impl NodeHelp {
+    /// The "?" section appended to the node's panel
+    pub fn section(&self) -> Vec<UIElement> {
+        let mut elements = vec![
+            UIElement::Separator,
+            UIElement::Heading("?".to_string()),
+            UIElement::Label(self.details.to_string()),
+        ];
+        if !self.ports.is_empty() {
+            elements.push(UIElement::Label(tr("Port examples:")));
+            elements.extend(self.ports.iter().map(|(port, example)| UIElement::Label(format!("  {}: {}", port, example))));
+        }
+        if !self.samples.is_empty() {
+            elements.push(UIElement::Label(tr("Try it with:")));
+            elements.extend(self.samples.iter().map(|sample| UIElement::Label(format!("  {}", sample))));
+        }
+        elements
+    }
+    
+    /// Example value of a port, if the help has one
+    pub fn port_example(&self, port: &str) -> Option<&'static str> {
+        self.ports.iter().find(|(name, _)| *name == port).map(|(_, example)| *example)
+    }
+}
+
+/// Help of every registered node type
+pub fn all_help() -> Vec<&'static NodeHelp> {
+    use crate::{geometry, lighting, shading, stage};
+    vec![
+        &crate::viewport::HELP,
+        &crate::create_stage_node::HELP,
+        &crate::load_stage_node::HELP,
+        &crate::save_stage_node::HELP,
+        &crate::layout_import_node::HELP,
+        &crate::json_export_node::HELP,
+        &crate::timeline_node::HELP,
+        &crate::copy_prims_node::HELP,
+        &crate::variant_selector_node::HELP,
+        &crate::flatten_stage_node::HELP,
+        &crate::layer_stack_node::HELP,
+        &crate::package_usdz_node::HELP,
+        &crate::instancer_edit_node::HELP,
+        &crate::documentation_node::HELP,
+        &crate::relationship_node::HELP,
+        &crate::watch_folder_node::HELP,
+        &crate::collection_node::HELP,
+        &crate::assemble_node::HELP,
+        &crate::render_pass_node::HELP,
+        &crate::render_sequence_node::HELP,
+        &crate::tutorial_node::HELP,
+        &stage::export_stage::HELP,
+        &stage::clear_stage::HELP,
+        &stage::render_frame::HELP,
+        &stage::export_graph::HELP,
+        &stage::global_seed::HELP,
+        &geometry::mesh::HELP,
+        &geometry::cube::HELP,
+        &crate::face_set_node::HELP,
+        &geometry::sphere::HELP,
+        &geometry::cylinder::HELP,
+        &geometry::cone::HELP,
+        &geometry::capsule::HELP,
+        &geometry::plane::HELP,
+        &geometry::torus::HELP,
+        &geometry::points::HELP,
+        &geometry::curves::HELP,
+        &geometry::camera::HELP,
+        &geometry::bounding_volume::HELP,
+        &geometry::hlod::HELP,
+        &geometry::occlusion_bake::HELP,
+        &geometry::xform::HELP,
+        &crate::transform_node::TRANSLATE_HELP,
+        &crate::transform_node::ROTATE_HELP,
+        &crate::transform_node::SCALE_HELP,
+        &lighting::distant_light::HELP,
+        &lighting::sphere_light::HELP,
+        &lighting::dome_light::HELP,
+        &crate::light_rig_node::HELP,
+        &crate::sun_sky_node::HELP,
+        &lighting::rect_light::HELP,
+        &lighting::disk_light::HELP,
+        &lighting::cylinder_light::HELP,
+        &shading::shader::HELP,
+        &shading::texture_reader::HELP,
+        &crate::material_preview_node::HELP,
+        &crate::assign_rules_node::HELP,
+        &shading::material::HELP,
+        &shading::preview_surface::HELP,
+        &shading::primvar_reader::HELP,
+        &shading::materialx::HELP,
+        &crate::stage_inspector_node::HELP,
+        &crate::prim_properties_node::HELP,
+        &crate::spreadsheet_node::HELP,
+        &crate::bridge_profile_node::HELP,
+    ]
+}
+
+/// Help of a node type, for a host-side help browser
+pub fn node_help(node_type: &str) -> Option<&'static NodeHelp> {
+    all_help().into_iter().find(|help| help.node_type == node_type)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn every_node_type_has_complete_help_once() {
+        let all = all_help();
+        for (index, help) in all.iter().enumerate() {
+            assert!(help.node_type.starts_with("USD_"), "{}", help.node_type);
+            assert!(!help.summary.is_empty() && !help.details.is_empty(), "{} lacks text", help.node_type);
+            assert!(!help.ports.is_empty(), "{} lacks port examples", help.node_type);
+            assert!(all[..index].iter().all(|other| other.node_type != help.node_type), "{} listed twice", help.node_type);
+        }
+        assert_eq!(node_help("USD_LoadStage").map(|help| help.node_type), Some("USD_LoadStage"));
+        assert!(node_help("USD_Unknown").is_none());
+    }
+    
+    #[test]
+    fn sections_list_port_examples_and_samples() {
+        let help = &crate::load_stage_node::HELP;
+        let labels: Vec<String> = help.section().into_iter()
+            .filter_map(|element| match element {
+                UIElement::Label(text) => Some(text),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(labels[0], help.details);
+        assert!(labels.iter().any(|label| label.starts_with("  Stage: ")));
+        assert_eq!(help.port_example("Stage"), Some("loaded_0"));
+        assert!(help.port_example("Missing").is_none());
+    }
+}
@@ -0,0 +1,209 @@
+//! Localization of parameter labels and messages
+//!
+//! User-facing strings are looked up in a message catalog keyed by their
+//! English text, so English needs no catalog and untranslated strings fall
+//! back to it. Keys may contain `{}` placeholders to translate formatted
+//! messages such as errors: the English template is matched against the
+//! text, and the captured values are filled into the translation's `{0}`,
+//! `{1}`... placeholders, themselves translated so nested errors are too.
+//!
+//! The locale comes from `NODLE_LANG`, else the usual `LC_ALL`,
+//! `LC_MESSAGES` and `LANG` variables, and hosts can change it with
+//! `set_locale`.
+
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+
+/// Language user-facing strings are shown in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    #[default]
+    English,
+    Japanese,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::English, Locale::Japanese];
+    
+    /// ISO 639-1 language code
+    pub fn code(&self) -> &'static str {
+        match self {
+            Locale::English => "en",
+            Locale::Japanese => "ja",
+        }
+    }
+    
+    /// Locale of a language code or POSIX locale name such as "ja_JP.UTF-8"
+    pub fn from_code(code: &str) -> Option<Self> {
+        let language = code.split(['_', '-', '.', '@']).next().unwrap_or_default().to_lowercase();
+        Self::ALL.into_iter().find(|locale| locale.code() == language)
+    }
+    
+    /// Locale named by the environment, English when none is set or known
+    pub fn from_env() -> Self {
+        ["NODLE_LANG", "LC_ALL", "LC_MESSAGES", "LANG"].iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| Self::from_code(&value))
+            .unwrap_or_default()
+    }
+    
+    /// Catalog of translations keyed by English text, None for English
+    fn catalog(&self) -> Option<&'static [(&'static str, &'static str)]> {
+        match self {
+            Locale::English => None,
+            Locale::Japanese => Some(JAPANESE),
+        }
+    }
+}
+
+/// Locale of the plugin's panels and messages
+static LOCALE: Lazy<Mutex<Locale>> = Lazy::new(|| Mutex::new(Locale::from_env()));
+
+/// Current locale
+pub fn locale() -> Locale {
+    *LOCALE.lock().unwrap()
+}
+
+/// Show panels and messages in another locale from their next redraw
+pub fn set_locale(locale: Locale) {
+    *LOCALE.lock().unwrap() = locale;
+}
+
+/// Translate a label or message into the current locale
+pub fn tr(text: &str) -> String {
+    translate(locale(), text)
+}
+
+/// Translate a label or message into a locale, leaving text without a translation as is
+pub fn translate(locale: Locale, text: &str) -> String {
+    let Some(catalog) = locale.catalog() else {
+        return text.to_string();
+    };
+    if let Some((_, translation)) = catalog.iter().find(|(key, _)| *key == text) {
+        return translation.to_string();
+    }
+    for (key, translation) in catalog.iter().filter(|(key, _)| key.contains("{}")) {
+        if let Some(values) = match_template(key, text) {
+            return values.iter().enumerate().fold(translation.to_string(), |message, (index, value)| {
+                message.replace(&format!("{{{}}}", index), &translate(locale, value))
+            });
+        }
+    }
+    text.to_string()
+}
+
+/// Values filling the `{}` placeholders of an English template to give `text`
+///
+/// Each value extends to the first occurrence of the literal text after it.
+fn match_template<'a>(template: &str, text: &'a str) -> Option<Vec<&'a str>> {
+    let pieces: Vec<&str> = template.split("{}").collect();
+    let (first, rest) = pieces.split_first()?;
+    let (last, middle) = rest.split_last()?;
+    let mut remaining = text.strip_prefix(first)?.strip_suffix(last)?;
+    let mut values = Vec::new();
+    for piece in middle {
+        let index = remaining.find(piece).filter(|_| !piece.is_empty())?;
+        values.push(&remaining[..index]);
+        remaining = &remaining[index + piece.len()..];
+    }
+    values.push(remaining);
+    Some(values)
+}
+
+/// Japanese catalog
+const JAPANESE: &[(&str, &str)] = &[
+    // Panels
+    ("Search", "検索"),
+    ("Port examples:", "ポートの例:"),
+    ("Try it with:", "サンプル:"),
+    ("Not executed yet", "未実行"),
+    ("✓ Up to date", "✓ 最新"),
+    ("⚠ {}", "⚠ {0}"),
+    // Parameter labels
+    ("Name", "名前"),
+    ("Parent Path", "親パス"),
+    ("Root Path", "ルートパス"),
+    ("Prim Path", "プリムパス"),
+    ("Prim Paths", "プリムパス"),
+    ("Material Path", "マテリアルパス"),
+    ("File Path", "ファイルパス"),
+    ("Time Code", "タイムコード"),
+    ("Radius", "半径"),
+    ("Height", "高さ"),
+    ("Width", "幅"),
+    ("Length", "長さ"),
+    ("Axis", "軸"),
+    ("Type", "タイプ"),
+    ("Format", "フォーマット"),
+    ("Output", "出力"),
+    ("Samples", "サンプル数"),
+    ("Resolution", "解像度"),
+    ("Rows", "行数"),
+    ("Columns", "列数"),
+    ("Spacing", "間隔"),
+    ("Intensity", "強度"),
+    ("Color", "カラー"),
+    ("Diffuse Color", "ディフューズカラー"),
+    ("Emissive Color", "エミッシブカラー"),
+    ("Roughness", "ラフネス"),
+    ("Metallic", "メタリック"),
+    ("Opacity", "不透明度"),
+    ("Specular", "スペキュラ"),
+    ("Clearcoat", "クリアコート"),
+    ("Double Sided", "両面"),
+    ("Material", "マテリアル"),
+    ("Visibility", "可視性"),
+    ("Purpose", "パーパス"),
+    ("Enabled", "有効"),
+    ("Start Frame", "開始フレーム"),
+    ("End Frame", "終了フレーム"),
+    ("Frame Range", "フレーム範囲"),
+    ("Step", "ステップ"),
+    ("Focal Length (mm)", "焦点距離 (mm)"),
+    ("Focus Distance", "フォーカス距離"),
+    ("Near Clip", "ニアクリップ"),
+    ("Far Clip", "ファークリップ"),
+    ("Composition Arcs", "コンポジションアーク"),
+    ("Render", "レンダー"),
+    ("Export", "エクスポート"),
+    ("Write", "書き出し"),
+    // Messages
+    ("No USD stage connected", "USD ステージが接続されていません"),
+    ("No stage connected", "ステージが接続されていません"),
+    ("No stages connected", "ステージが接続されていません"),
+    ("No prim path set", "プリムパスが設定されていません"),
+    ("No prim paths set", "プリムパスが設定されていません"),
+    ("No root path set", "ルートパスが設定されていません"),
+    ("No file path set", "ファイルパスが設定されていません"),
+    ("No output path set", "出力パスが設定されていません"),
+    ("No cameras set", "カメラが設定されていません"),
+    ("No folder set", "フォルダが設定されていません"),
+    ("Stage '{}' not found", "ステージ '{0}' が見つかりません"),
+    ("Prim '{}' not found", "プリム '{0}' が見つかりません"),
+    ("Prim '{}' not found on stage '{}'", "ステージ '{1}' にプリム '{0}' が見つかりません"),
+    ("No prims under '{}'", "'{0}' の下にプリムがありません"),
+    ("Failed to open stage '{}': {}", "ステージ '{0}' を開けませんでした: {1}"),
+    ("Failed to read '{}': {}", "'{0}' の読み込みに失敗しました: {1}"),
+    ("Failed to write '{}': {}", "'{0}' の書き込みに失敗しました: {1}"),
+    ("Failed to create '{}': {}", "'{0}' の作成に失敗しました: {1}"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn catalog_lookups_fill_templates_and_fall_back_to_english() {
+        assert_eq!(Locale::from_code("ja_JP.UTF-8"), Some(Locale::Japanese));
+        assert_eq!(Locale::from_code("C"), None);
+        
+        assert_eq!(translate(Locale::Japanese, "Root Path"), "ルートパス");
+        assert_eq!(translate(Locale::English, "Root Path"), "Root Path");
+        assert_eq!(translate(Locale::Japanese, "Cluster Size"), "Cluster Size");
+        // Values are reordered, and translated themselves
+        assert_eq!(translate(Locale::Japanese, "Prim '/World/Chair' not found on stage 'loaded_0'"), "ステージ 'loaded_0' にプリム '/World/Chair' が見つかりません");
+        assert_eq!(translate(Locale::Japanese, "⚠ No cameras set"), "⚠ カメラが設定されていません");
+        assert_eq!(translate(Locale::Japanese, "⚠ Stage 'a.usda' has 3 layers"), "⚠ Stage 'a.usda' has 3 layers");
+    }
+}
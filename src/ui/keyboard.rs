@@ -0,0 +1,229 @@
+//! Keyboard operation of node panels
+//!
+//! The SDK's UI elements carry no focus or shortcut information, so hosts
+//! forward key presses as a `ParameterChanged` action on `KEY_PARAMETER`
+//! with the key chord as text, e.g. "Tab", "Shift+Tab" or "Ctrl+F".
+//! `handle_key` moves a focus marker through a panel's buttons, checkboxes,
+//! sliders and text fields in panel order, and turns keys into the actions
+//! a click or drag on the focused element would have sent. Shortcuts press
+//! buttons shown in the panel and are advertised in their labels.
+
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+
+/// Parameter name hosts send key chords on
+pub const KEY_PARAMETER: &str = "keyboard_key";
+
+/// Prefix of the focused element's label
+pub const FOCUS_MARKER: &str = "▸ ";
+
+/// Slider steps from minimum to maximum, ten times coarser with Shift
+const SLIDER_STEPS: f32 = 100.0;
+
+/// A key chord pressing a panel button
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Shortcut {
+    /// Chord as "Ctrl+Alt+Shift+Key", any modifiers omitted
+    pub chord: &'static str,
+    /// Action of the button it presses, e.g. "frame_all" or "gizmo_mode:Rotate"
+    pub action: &'static str,
+}
+
+/// Focused element of each node's panel, by node id, as an index among its focusable elements
+static PANEL_FOCUS: Lazy<Mutex<HashMap<String, usize>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Canonical form of a key chord: Ctrl, Alt and Shift in that order, then the key
+///
+/// Cmd and Meta count as Ctrl, letters are upper case and named keys
+/// capitalized, so "shift+ctrl+f" and "Cmd+Shift+F" both give "Ctrl+Shift+F".
+pub fn normalize_chord(text: &str) -> String {
+    let (mut ctrl, mut alt, mut shift, mut key) = (false, false, false, String::new());
+    for part in text.split('+').map(str::trim).filter(|part| !part.is_empty()) {
+        match part.to_lowercase().as_str() {
+            "ctrl" | "control" | "cmd" | "command" | "meta" => ctrl = true,
+            "alt" | "option" => alt = true,
+            "shift" => shift = true,
+            "arrowleft" => key = "Left".to_string(),
+            "arrowright" => key = "Right".to_string(),
+            "arrowup" => key = "Up".to_string(),
+            "arrowdown" => key = "Down".to_string(),
+            "return" => key = "Enter".to_string(),
+            "esc" => key = "Escape".to_string(),
+            lower => {
+                let mut chars = lower.chars();
+                key = chars.next().map(|first| first.to_uppercase().chain(chars).collect()).unwrap_or_default();
+            }
+        }
+    }
+    [(ctrl, "Ctrl"), (alt, "Alt"), (shift, "Shift")].iter()
+        .filter(|(held, _)| *held)
+        .map(|(_, name)| *name)
+        .chain(std::iter::once(key.as_str()))
+        .collect::<Vec<_>>()
+        .join("+")
+}
+
+/// Key chord of an action, if it is a key press
+pub fn key_chord(action: &UIAction) -> Option<String> {
+    match action {
+        UIAction::ParameterChanged { parameter, value } if parameter == KEY_PARAMETER => {
+            value.as_string().map(normalize_chord)
+        }
+        _ => None,
+    }
+}
+
+/// Whether keyboard focus stops at an element
+fn is_focusable(element: &UIElement) -> bool {
+    matches!(element, UIElement::Button { .. } | UIElement::Checkbox { .. } | UIElement::Slider { .. } | UIElement::TextEdit { .. })
+}
+
+/// Action for a key on a panel, moving `focus` for Tab, Shift+Tab and Escape
+///
+/// Enter and Space activate the focused button or checkbox, and arrows,
+/// Home and End move the focused slider; other keys, or any key with
+/// nothing focused, press the button of a matching shortcut if the panel
+/// shows it.
+fn key_action(focus: &mut Option<usize>, elements: &[UIElement], shortcuts: &[Shortcut], chord: &str) -> Option<UIAction> {
+    let focusable: Vec<&UIElement> = elements.iter().filter(|element| is_focusable(element)).collect();
+    let count = focusable.len();
+    match chord {
+        "Tab" | "Shift+Tab" if count > 0 => {
+            *focus = Some(match (*focus, chord) {
+                (None, "Tab") => 0,
+                (None, _) => count - 1,
+                (Some(index), "Tab") => (index + 1) % count,
+                (Some(index), _) => (index.min(count - 1) + count - 1) % count,
+            });
+            return None;
+        }
+        "Escape" => {
+            *focus = None;
+            return None;
+        }
+        _ => {}
+    }
+    
+    let focused = focus.and_then(|index| focusable.get(index));
+    let action = match (focused, chord.strip_prefix("Shift+").unwrap_or(chord)) {
+        (Some(UIElement::Button { action, .. }), "Enter" | "Space") => Some(UIAction::ButtonClicked { action: action.clone() }),
+        (Some(UIElement::Checkbox { value, parameter_name, .. }), "Enter" | "Space") => Some(UIAction::ParameterChanged {
+            parameter: parameter_name.clone(),
+            value: NodeData::Boolean(!value),
+        }),
+        (Some(UIElement::Slider { value, min, max, parameter_name, .. }), key @ ("Left" | "Right" | "Up" | "Down" | "Home" | "End")) => {
+            let step = (max - min) / SLIDER_STEPS * if chord.starts_with("Shift+") { 10.0 } else { 1.0 };
+            let value = match key {
+                "Left" | "Down" => value - step,
+                "Right" | "Up" => value + step,
+                "Home" => *min,
+                _ => *max,
+            };
+            Some(UIAction::ParameterChanged {
+                parameter: parameter_name.clone(),
+                value: NodeData::Float(value.clamp(*min, *max)),
+            })
+        }
+        _ => None,
+    };
+    action.or_else(|| {
+        shortcuts.iter()
+            .filter(|shortcut| normalize_chord(shortcut.chord) == chord)
+            .find(|shortcut| elements.iter().any(|element| matches!(element, UIElement::Button { action, .. } if action == shortcut.action)))
+            .map(|shortcut| UIAction::ButtonClicked { action: shortcut.action.to_string() })
+    })
+}
+
+/// Handle an action if it is a key press, performing the action the key stands for
+///
+/// Returns None for other actions, which the node handles as usual.
+pub fn handle_key<N: PluginNode>(node: &mut N, shortcuts: &[Shortcut], action: &UIAction) -> Option<Vec<ParameterChange>> {
+    let chord = key_chord(action)?;
+    let elements = node.get_parameter_ui().elements;
+    let id = node.id();
+    let mut focus = PANEL_FOCUS.lock().unwrap().get(&id).copied();
+    let action = key_action(&mut focus, &elements, shortcuts, &chord);
+    match focus {
+        Some(index) => PANEL_FOCUS.lock().unwrap().insert(id, index),
+        None => PANEL_FOCUS.lock().unwrap().remove(&id),
+    };
+    Some(action.map(|action| node.handle_ui_action(action)).unwrap_or_default())
+}
+
+/// Mark the element with keyboard focus in a node's panel
+pub fn focus_marked(node_id: &str, mut elements: Vec<UIElement>) -> Vec<UIElement> {
+    let Some(index) = PANEL_FOCUS.lock().unwrap().get(node_id).copied() else {
+        return elements;
+    };
+    let label = elements.iter_mut()
+        .filter(|element| is_focusable(element))
+        .nth(index)
+        .and_then(|element| match element {
+            UIElement::Button { label, .. } | UIElement::Checkbox { label, .. } | UIElement::Slider { label, .. } | UIElement::TextEdit { label, .. } => Some(label),
+            _ => None,
+        });
+    if let Some(label) = label {
+        label.insert_str(0, FOCUS_MARKER);
+    }
+    elements
+}
+
+/// Append each shortcut's chord to the label of the button it presses
+pub fn advertise_shortcuts(mut elements: Vec<UIElement>, shortcuts: &[Shortcut]) -> Vec<UIElement> {
+    for element in &mut elements {
+        if let UIElement::Button { label, action } = element {
+            if let Some(shortcut) = shortcuts.iter().find(|shortcut| shortcut.action == action.as_str()) {
+                label.push_str(&format!("  [{}]", shortcut.chord));
+            }
+        }
+    }
+    elements
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn keys_move_focus_and_operate_elements() {
+        assert_eq!(normalize_chord("shift+cmd+f"), "Ctrl+Shift+F");
+        assert_eq!(normalize_chord("ArrowLeft"), "Left");
+        
+        let elements = vec![
+            UIElement::Heading("Panel".to_string()),
+            UIElement::Checkbox { label: "Lighting".to_string(), value: true, parameter_name: "lighting".to_string() },
+            UIElement::Slider { label: "Scale".to_string(), value: 1.0, min: 0.0, max: 2.0, parameter_name: "scale".to_string() },
+            UIElement::Button { label: "Frame All".to_string(), action: "frame_all".to_string() },
+        ];
+        let shortcuts = [Shortcut { chord: "A", action: "frame_all" }, Shortcut { chord: "P", action: "play" }];
+        let mut focus = None;
+        
+        // Shortcuts press shown buttons only
+        assert!(matches!(key_action(&mut focus, &elements, &shortcuts, "A"), Some(UIAction::ButtonClicked { action }) if action == "frame_all"));
+        assert!(key_action(&mut focus, &elements, &shortcuts, "P").is_none());
+        
+        assert!(key_action(&mut focus, &elements, &shortcuts, "Tab").is_none());
+        assert_eq!(focus, Some(0));
+        assert!(matches!(key_action(&mut focus, &elements, &shortcuts, "Space"),
+            Some(UIAction::ParameterChanged { parameter, value: NodeData::Boolean(false) }) if parameter == "lighting"));
+        key_action(&mut focus, &elements, &shortcuts, "Tab");
+        assert!(matches!(key_action(&mut focus, &elements, &shortcuts, "Shift+Right"),
+            Some(UIAction::ParameterChanged { value: NodeData::Float(value), .. }) if (value - 1.2).abs() < 1e-6));
+        // Shift+Tab from the first element wraps to the last
+        focus = Some(0);
+        key_action(&mut focus, &elements, &shortcuts, "Shift+Tab");
+        assert_eq!(focus, Some(2));
+        key_action(&mut focus, &elements, &shortcuts, "Escape");
+        assert_eq!(focus, None);
+        
+        let labels: Vec<String> = advertise_shortcuts(elements, &shortcuts).into_iter()
+            .filter_map(|element| match element {
+                UIElement::Button { label, .. } => Some(label),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(labels, ["Frame All  [A]"]);
+    }
+}
@@ -0,0 +1,228 @@
+//! Material preview section for shading node panels
+//!
+//! Builds a preview stage with the material bound to a turntable shape. The
+//! material network is only re-evaluated when the source stage revision, the
+//! material or the shape changes; turning the turntable just moves the time code.
+//! Thumbnails are rendered offscreen in material preview shading whenever the
+//! preview is rebuilt or turned.
+
+use nodle_plugin_sdk::*;
+use std::time::Instant;
+use crate::core::usd_engine::{with_usd_engine, PreviewShape, PREVIEW_TURNTABLE_FRAMES};
+use crate::capture::create_parent_dirs;
+use crate::viewport::renderer_3d::request_device;
+use crate::viewport::usd_rendering::USDRenderer;
+use super::choice::{choice_buttons, parse_choice};
+use super::palette::status_label;
+
+/// Turntable speed in time codes per second
+const TURNTABLE_FPS: f64 = 24.0;
+
+/// Width and height of preview thumbnails in pixels
+const THUMBNAIL_SIZE: u32 = 256;
+
+/// Preview state embedded in a material node
+#[derive(Debug, Clone)]
+pub struct MaterialPreview {
+    pub shape: PreviewShape,
+    pub turntable: bool,
+    /// Fixed view angle in degrees while the turntable is off
+    pub angle: f32,
+    /// Preview stage identifier, once built
+    pub preview_stage: Option<String>,
+    /// Source stage, material and stage revision the preview was built from
+    built_from: Option<(String, String, u64)>,
+    /// Last rendered thumbnail, a PNG path
+    pub thumbnail: Option<String>,
+    /// Preview stage and time code bits the thumbnail was rendered at
+    rendered_at: Option<(String, u64)>,
+    /// Offscreen renderer, created with a headless device by the first thumbnail
+    renderer: Option<USDRenderer>,
+    spin_started: Instant,
+    status: String,
+}
+
+impl Default for MaterialPreview {
+    fn default() -> Self {
+        Self {
+            shape: PreviewShape::Sphere,
+            turntable: false,
+            angle: 30.0,
+            preview_stage: None,
+            built_from: None,
+            thumbnail: None,
+            rendered_at: None,
+            renderer: None,
+            spin_started: Instant::now(),
+            status: "No material to preview".to_string(),
+        }
+    }
+}
+
+impl MaterialPreview {
+    /// Panel elements for the preview controls
+    pub fn elements(&self) -> Vec<UIElement> {
+        let mut elements = vec![UIElement::Label("🎨 Preview".to_string())];
+        elements.extend(choice_buttons("Shape", "preview_shape", &PreviewShape::ALL.map(|shape| shape.name()), self.shape.name()));
+        elements.push(UIElement::Checkbox {
+            label: "Turntable".to_string(),
+            value: self.turntable,
+            parameter_name: "preview_turntable".to_string(),
+        });
+        if !self.turntable {
+            elements.push(UIElement::Slider {
+                label: "Angle".to_string(),
+                value: self.angle,
+                min: 0.0,
+                max: 360.0,
+                parameter_name: "preview_angle".to_string(),
+            });
+        }
+        if let Some(thumbnail) = &self.thumbnail {
+            elements.push(UIElement::Label(format!("Thumbnail: {}", thumbnail)));
+        }
+        elements.push(status_label(&self.status));
+        elements
+    }
+    
+    /// Apply a UI action for the preview controls, returning the change if it was one
+    pub fn handle_action(&mut self, action: &UIAction) -> Option<ParameterChange> {
+        let parameter = match action {
+            UIAction::ParameterChanged { parameter, value } => {
+                self.set_parameter(parameter, value).then_some(parameter.as_str())?
+            }
+            UIAction::ButtonClicked { action } => {
+                let shape = NodeData::String(parse_choice(action, "preview_shape")?.to_string());
+                self.set_parameter("preview_shape", &shape);
+                "preview_shape"
+            }
+        };
+        Some(ParameterChange {
+            parameter: parameter.to_string(),
+            value: self.get_parameter(parameter)?,
+        })
+    }
+    
+    pub fn get_parameter(&self, name: &str) -> Option<NodeData> {
+        match name {
+            "preview_shape" => Some(NodeData::String(self.shape.name().to_string())),
+            "preview_turntable" => Some(NodeData::Boolean(self.turntable)),
+            "preview_angle" => Some(NodeData::Float(self.angle)),
+            _ => None,
+        }
+    }
+    
+    /// Set a preview parameter, returning whether the name was one
+    pub fn set_parameter(&mut self, name: &str, value: &NodeData) -> bool {
+        match name {
+            "preview_shape" => {
+                if let Some(shape) = value.as_string().and_then(PreviewShape::from_name) {
+                    if shape != self.shape {
+                        self.shape = shape;
+                        self.built_from = None;
+                    }
+                }
+            }
+            "preview_turntable" => {
+                if let Some(turntable) = value.as_boolean() {
+                    self.turntable = turntable;
+                    self.spin_started = Instant::now();
+                }
+            }
+            "preview_angle" => {
+                if let Some(angle) = value.as_float() {
+                    self.angle = angle.clamp(0.0, 360.0);
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+    
+    /// Rebuild the preview stage if the material or its stage changed
+    pub fn update(&mut self, stage_ref: &str, material_path: &str) {
+        if stage_ref.is_empty() || material_path.is_empty() {
+            self.preview_stage = None;
+            self.built_from = None;
+            self.status = "No material to preview".to_string();
+            return;
+        }
+        let revision = with_usd_engine(|engine| engine.stage_revision(stage_ref));
+        let source = (stage_ref.to_string(), material_path.to_string(), revision);
+        if self.built_from.as_ref() == Some(&source) {
+            return;
+        }
+        
+        let shape = self.shape;
+        let result = with_usd_engine(|engine| {
+            let stage = engine.resolve_stage(stage_ref)?;
+            engine.build_material_preview(&stage.identifier, material_path, shape)
+        });
+        match result {
+            Ok(preview) => {
+                self.status = format!("{} on {}", material_path, shape.name());
+                self.preview_stage = Some(preview.identifier);
+                // Previews are rebuilt in place, so the same stage needs a new thumbnail
+                self.rendered_at = None;
+            }
+            Err(e) => {
+                self.status = format!("⚠ {}", e);
+                self.preview_stage = None;
+            }
+        }
+        // Failed builds are not retried until something changes
+        self.built_from = Some(source);
+    }
+    
+    /// Render a thumbnail of the preview stage if it was rebuilt or turned since the last one
+    pub fn render(&mut self) {
+        let Some(preview_stage) = self.preview_stage.clone() else {
+            self.thumbnail = None;
+            self.rendered_at = None;
+            return;
+        };
+        let time_code = self.time_code();
+        let key = (preview_stage.clone(), time_code.to_bits());
+        if self.rendered_at.as_ref() == Some(&key) {
+            return;
+        }
+        match self.render_thumbnail(&preview_stage, time_code) {
+            Ok(path) => self.thumbnail = Some(path),
+            Err(e) => {
+                self.status = format!("⚠ No thumbnail: {}", e);
+                self.thumbnail = None;
+            }
+        }
+        // Failed renders are not retried until the preview changes
+        self.rendered_at = Some(key);
+    }
+    
+    /// Render the preview stage through its camera and write it as a PNG in the temp directory
+    fn render_thumbnail(&mut self, preview_stage: &str, time_code: f64) -> Result<String, String> {
+        let renderer = match &mut self.renderer {
+            Some(renderer) => renderer,
+            None => {
+                let (device, queue) = request_device()?;
+                let mut renderer = USDRenderer::new();
+                renderer.initialize(device, queue);
+                self.renderer.insert(renderer)
+            }
+        };
+        let frame = renderer.render_material_preview(preview_stage, time_code, THUMBNAIL_SIZE)?;
+        let name: String = preview_stage.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }).collect();
+        let path = std::env::temp_dir().join("nodle_material_preview").join(format!("{}.png", name)).to_string_lossy().into_owned();
+        create_parent_dirs(&path)?;
+        frame.save(&path)?;
+        Ok(path)
+    }
+    
+    /// Preview stage time code for the current turntable angle
+    pub fn time_code(&self) -> f64 {
+        let frame = if self.turntable {
+            self.spin_started.elapsed().as_secs_f64() * TURNTABLE_FPS
+        } else {
+            f64::from(self.angle) / 360.0 * PREVIEW_TURNTABLE_FRAMES
+        };
+        frame % PREVIEW_TURNTABLE_FRAMES
+    }
+}
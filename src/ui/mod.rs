@@ -0,0 +1,22 @@
+//! Shared parameter UI helpers for USD node panels
+
+// Search/filter box for long parameter lists
+pub mod search;
+
+// Button-row selectors standing in for combo boxes
+pub mod choice;
+
+// Material preview controls for shading nodes
+pub mod material_preview;
+
+// Node help for the "?" panel section
+pub mod help;
+
+// Message catalogs for panel labels and messages
+pub mod i18n;
+
+// Keyboard focus and shortcuts for node panels
+pub mod keyboard;
+
+// Color-blind safe highlight palettes
+pub mod palette;
@@ -0,0 +1,150 @@
+//! Highlight palettes for color-vision deficiencies
+//!
+//! Selection outlines, gizmo axes and status markers take their colors from
+//! a palette, so they can be told apart with red-green or blue-yellow color
+//! blindness. The presets besides Standard use the Okabe-Ito colors.
+//! Status labels keep their ✓ and ⚠ shapes and gain a colored marker, so
+//! no state is shown by color alone.
+//!
+//! The palette comes from `NODLE_PALETTE`, e.g. "deuteranopia", and can be
+//! changed from a viewport's panel; it applies to every panel and viewport.
+
+use nodle_plugin_sdk::*;
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use super::i18n::tr;
+
+/// Colors of a palette
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PaletteColors {
+    /// Emissive color of selection outlines
+    pub selection: [f32; 3],
+    /// Gizmo handle colors by axis
+    pub axes: [[f32; 3]; 3],
+    /// Color of the gizmo handle being dragged
+    pub active: [f32; 3],
+    /// Marker of successful statuses
+    pub ok_marker: &'static str,
+    /// Marker of error statuses
+    pub error_marker: &'static str,
+}
+
+/// Highlight palette preset
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Palette {
+    #[default]
+    Standard,
+    /// Red-green safe, for missing green cones
+    Deuteranopia,
+    /// Red-green safe with reds brightened, for missing red cones
+    Protanopia,
+    /// Blue-yellow safe, for missing blue cones
+    Tritanopia,
+}
+
+impl Palette {
+    pub const ALL: [Palette; 4] = [Palette::Standard, Palette::Deuteranopia, Palette::Protanopia, Palette::Tritanopia];
+    
+    pub fn label(&self) -> &'static str {
+        match self {
+            Palette::Standard => "Standard",
+            Palette::Deuteranopia => "Deuteranopia",
+            Palette::Protanopia => "Protanopia",
+            Palette::Tritanopia => "Tritanopia",
+        }
+    }
+    
+    /// Palette of a label, case-insensitively
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|palette| palette.label().eq_ignore_ascii_case(label.trim()))
+    }
+    
+    pub fn colors(&self) -> PaletteColors {
+        match self {
+            Palette::Standard => PaletteColors {
+                selection: [1.0, 0.55, 0.1],
+                axes: [[0.9, 0.2, 0.2], [0.3, 0.85, 0.3], [0.25, 0.45, 1.0]],
+                active: [1.0, 0.9, 0.2],
+                ok_marker: "🟢",
+                error_marker: "🔴",
+            },
+            // Vermillion, yellow and blue axes differ in lightness as well as hue
+            Palette::Deuteranopia => PaletteColors {
+                selection: [0.34, 0.71, 0.91],
+                axes: [[0.84, 0.37, 0.0], [0.94, 0.89, 0.26], [0.0, 0.45, 0.7]],
+                active: [1.0, 1.0, 1.0],
+                ok_marker: "🔵",
+                error_marker: "🟠",
+            },
+            Palette::Protanopia => PaletteColors {
+                selection: [0.34, 0.71, 0.91],
+                axes: [[0.9, 0.62, 0.0], [0.94, 0.89, 0.26], [0.0, 0.45, 0.7]],
+                active: [1.0, 1.0, 1.0],
+                ok_marker: "🔵",
+                error_marker: "🟠",
+            },
+            // Red, bluish green and reddish purple stay apart without blue cones
+            Palette::Tritanopia => PaletteColors {
+                selection: [1.0, 0.4, 0.6],
+                axes: [[0.84, 0.2, 0.1], [0.0, 0.62, 0.45], [0.8, 0.47, 0.65]],
+                active: [1.0, 1.0, 1.0],
+                ok_marker: "🟢",
+                error_marker: "🔴",
+            },
+        }
+    }
+}
+
+/// Palette of the plugin's viewports and panels
+static PALETTE: Lazy<Mutex<Palette>> = Lazy::new(|| {
+    let palette = std::env::var("NODLE_PALETTE").ok().and_then(|name| Palette::from_label(&name));
+    Mutex::new(palette.unwrap_or_default())
+});
+
+/// Current palette
+pub fn palette() -> Palette {
+    *PALETTE.lock().unwrap()
+}
+
+/// Switch palettes; viewports recolor on their next process and panels on their next redraw
+pub fn set_palette(palette: Palette) {
+    *PALETTE.lock().unwrap() = palette;
+}
+
+/// A status with the palette's marker before ✓ and ⚠ statuses, other statuses as they are
+pub fn mark_status(colors: &PaletteColors, status: &str) -> String {
+    let marker = if status.starts_with('⚠') {
+        colors.error_marker
+    } else if status.starts_with('✓') {
+        colors.ok_marker
+    } else {
+        return status.to_string();
+    };
+    format!("{} {}", marker, status)
+}
+
+/// Panel label showing a status, translated and marked in the current palette
+pub fn status_label(status: &str) -> UIElement {
+    UIElement::Label(mark_status(&palette().colors(), &tr(status)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn palettes_mark_statuses_and_set_apart_the_active_handle() {
+        assert_eq!(Palette::from_label(" deuteranopia"), Some(Palette::Deuteranopia));
+        let standard = Palette::Standard.colors();
+        let deuteranopia = Palette::Deuteranopia.colors();
+        assert_eq!(mark_status(&standard, "⚠ No stage connected"), "🔴 ⚠ No stage connected");
+        assert_eq!(mark_status(&deuteranopia, "✓ Up to date"), "🔵 ✓ Up to date");
+        assert_eq!(mark_status(&deuteranopia, "Wrote out.usda"), "Wrote out.usda");
+        
+        // The dragged handle stands out from every axis
+        for palette in Palette::ALL {
+            let colors = palette.colors();
+            assert!(!colors.axes.contains(&colors.active));
+        }
+    }
+}
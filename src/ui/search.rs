@@ -0,0 +1,82 @@
+//! Search box for filtering long parameter panels
+
+use nodle_plugin_sdk::*;
+use super::i18n::tr;
+
+/// Parameter name used by search boxes in node panels
+pub const SEARCH_PARAMETER: &str = "search_filter";
+
+/// Search/filter state for a parameter panel
+#[derive(Debug, Clone, Default)]
+pub struct ParameterSearch {
+    pub query: String,
+}
+
+impl ParameterSearch {
+    /// Build the search box element
+    pub fn element(&self) -> UIElement {
+        UIElement::TextEdit {
+            label: format!("🔍 {}", tr("Search")),
+            value: self.query.clone(),
+            parameter_name: SEARCH_PARAMETER.to_string(),
+        }
+    }
+    
+    /// Whether the filter is currently active
+    pub fn is_active(&self) -> bool {
+        !self.query.trim().is_empty()
+    }
+    
+    /// Case-insensitive match - every whitespace separated term must appear in the text
+    pub fn matches(&self, text: &str) -> bool {
+        let text = text.to_lowercase();
+        self.query
+            .split_whitespace()
+            .all(|term| text.contains(&term.to_lowercase()))
+    }
+    
+    /// Update the query from a UI action, returning the change if the action was for the search box
+    pub fn handle_action(&mut self, action: &UIAction) -> Option<ParameterChange> {
+        if let UIAction::ParameterChanged { parameter, value } = action {
+            if parameter == SEARCH_PARAMETER {
+                if let Some(query) = value.as_string() {
+                    self.query = query.to_string();
+                    return Some(ParameterChange {
+                        parameter: SEARCH_PARAMETER.to_string(),
+                        value: NodeData::String(self.query.clone()),
+                    });
+                }
+            }
+        }
+        None
+    }
+    
+    /// Filter panel entries by label, keeping headings and dropping separators left without entries
+    pub fn filter(&self, elements: Vec<UIElement>) -> Vec<UIElement> {
+        if !self.is_active() {
+            return elements;
+        }
+        
+        let mut filtered = Vec::new();
+        for element in elements {
+            let keep = match &element {
+                UIElement::Heading(_) => true,
+                UIElement::Separator => !matches!(filtered.last(), None | Some(UIElement::Separator)),
+                UIElement::Label(text) => self.matches(text),
+                UIElement::TextEdit { label, value, .. } => self.matches(label) || self.matches(value),
+                UIElement::Checkbox { label, .. } => self.matches(label),
+                UIElement::Slider { label, .. } => self.matches(label),
+                UIElement::Button { label, .. } => self.matches(label),
+                _ => true,
+            };
+            if keep {
+                filtered.push(element);
+            }
+        }
+        
+        if matches!(filtered.last(), Some(UIElement::Separator)) {
+            filtered.pop();
+        }
+        filtered
+    }
+}
@@ -0,0 +1,235 @@
+//! USD Variant Selector node - switches variant selections on a prim live
+
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use crate::core::usd_engine::{with_usd_engine, USDVariantSet};
+use crate::ui::choice::{choice_buttons, parse_choice};
+use crate::ui::help::{NodeHelp, KITCHEN_SET};
+use crate::ui::palette::status_label;
+use crate::ui::keyboard::{focus_marked, handle_key};
+use crate::registry::NodeTypeFactory;
+
+/// Help for the Variant Selector node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_VariantSelector",
+    summary: "Switch variant selections on a prim through the session layer",
+    details: "Lists the variant sets of Prim Path and switches a selection without touching the stage's layers, since selections are authored in the session layer.",
+    ports: &[
+        ("Stage", "loaded_0"),
+        ("Selection", "modelingVariant=ChairB"),
+    ],
+    samples: &[KITCHEN_SET],
+};
+
+/// Factory of the Variant Selector node, for `crate::node_factories`
+pub fn factories() -> Vec<Box<dyn NodeFactory>> {
+    vec![NodeTypeFactory::boxed(metadata, |position| PluginNodeHandle::new(Box::new(USDVariantSelectorNode::new(position))))]
+}
+
+/// Metadata of the Variant Selector node
+fn metadata() -> NodeMetadata {
+    NodeMetadata::new(
+        "USD_VariantSelector",
+        "Variant Selector",
+        NodeCategory::new(&["USD", "Composition"]),
+        HELP.summary
+    )
+    .with_color(Color32::from_rgb(80, 150, 200))
+    .with_icon("🔀")
+    .with_inputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("USD stage containing the prim"),
+    ])
+    .with_outputs(vec![
+        PortDefinition::required("Stage", DataType::String)
+            .with_description("Stage with the variant selections applied"),
+        PortDefinition::optional("Selection", DataType::String)
+            .with_description("Current selections as set=variant lines"),
+    ])
+    .with_panel_type(PanelType::Parameter)
+    .with_workspace_compatibility(vec!["3D"])
+}
+
+/// Button action prefix for variant choices, followed by the set name
+const VARIANT_ACTION: &str = "variant";
+
+/// USD Variant Selector node exposing one choice per variant set of a prim
+pub struct USDVariantSelectorNode {
+    id: String,
+    position: Pos2,
+    prim_path: String,
+    stage_ref: String,
+    variant_sets: Vec<USDVariantSet>,
+    /// Selections waiting to be authored, as (set, variant)
+    pending: Vec<(String, String)>,
+    dirty: bool,
+    status: String,
+}
+
+impl USDVariantSelectorNode {
+    pub fn new(position: Pos2) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            position,
+            prim_path: String::new(),
+            stage_ref: String::new(),
+            variant_sets: Vec::new(),
+            pending: Vec::new(),
+            dirty: true,
+            status: "No USD stage connected".to_string(),
+        }
+    }
+    
+    /// Author pending selections in the edit target and re-read the variant sets
+    fn refresh(&mut self) -> Result<(), String> {
+        if self.prim_path.is_empty() {
+            self.variant_sets.clear();
+            return Err("No prim path set".to_string());
+        }
+        let (stage_ref, prim_path) = (self.stage_ref.clone(), self.prim_path.clone());
+        let pending = std::mem::take(&mut self.pending);
+        
+        self.variant_sets = with_usd_engine(|engine| -> Result<Vec<USDVariantSet>, String> {
+            let stage = engine.resolve_stage(&stage_ref)?;
+            for (set, variant) in &pending {
+                engine.set_variant_selection(&stage.identifier, &prim_path, set, variant)?;
+            }
+            engine.get_variant_sets(&stage.identifier, &prim_path)
+        })?;
+        
+        self.status = if self.variant_sets.is_empty() {
+            format!("{} has no variant sets", self.prim_path)
+        } else {
+            format!("{} variant sets on {}", self.variant_sets.len(), self.prim_path)
+        };
+        Ok(())
+    }
+    
+    /// Current selections as "set=variant" lines
+    fn selections(&self) -> String {
+        self.variant_sets.iter()
+            .map(|set| format!("{}={}", set.name, set.selection))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl PluginNode for USDVariantSelectorNode {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+    
+    fn position(&self) -> Pos2 {
+        self.position
+    }
+    
+    fn set_position(&mut self, position: Pos2) {
+        self.position = position;
+    }
+    
+    fn get_parameter_ui(&self) -> ParameterUI {
+        let mut elements = Vec::new();
+        
+        elements.push(UIElement::Heading("USD Variant Selector".to_string()));
+        elements.push(UIElement::Separator);
+        
+        elements.push(UIElement::TextEdit {
+            label: "Prim Path".to_string(),
+            value: self.prim_path.clone(),
+            parameter_name: "prim_path".to_string(),
+        });
+        
+        elements.push(UIElement::Separator);
+        for set in &self.variant_sets {
+            let options: Vec<&str> = set.variants.iter().map(String::as_str).collect();
+            let action = format!("{}:{}", VARIANT_ACTION, set.name);
+            elements.extend(choice_buttons(&set.name, &action, &options, &set.selection));
+        }
+        
+        elements.push(UIElement::Separator);
+        elements.push(status_label(&self.status));
+        elements.extend(HELP.section());
+        
+        ParameterUI { elements: focus_marked(&self.id, elements) }
+    }
+    
+    fn handle_ui_action(&mut self, action: UIAction) -> Vec<ParameterChange> {
+        if let Some(changes) = handle_key(self, &[], &action) {
+            return changes;
+        }
+        
+        let mut changes = Vec::new();
+        
+        match action {
+            UIAction::ParameterChanged { parameter, value } => {
+                if parameter == "prim_path" {
+                    if let Some(path) = value.as_string() {
+                        self.set_parameter(&parameter, NodeData::String(path.to_string()));
+                        changes.push(ParameterChange {
+                            parameter: parameter.clone(),
+                            value: NodeData::String(path.to_string()),
+                        });
+                    }
+                }
+            }
+            UIAction::ButtonClicked { action } => {
+                for set in &self.variant_sets {
+                    let set_action = format!("{}:{}", VARIANT_ACTION, set.name);
+                    if let Some(variant) = parse_choice(&action, &set_action) {
+                        self.pending.push((set.name.clone(), variant.to_string()));
+                        self.dirty = true;
+                        break;
+                    }
+                }
+            }
+        }
+        
+        changes
+    }
+    
+    fn get_parameter(&self, name: &str) -> Option<NodeData> {
+        match name {
+            "prim_path" => Some(NodeData::String(self.prim_path.clone())),
+            _ => None,
+        }
+    }
+    
+    fn set_parameter(&mut self, name: &str, value: NodeData) {
+        match name {
+            "prim_path" => {
+                if let Some(path) = value.as_string() {
+                    self.prim_path = path.trim().to_string();
+                    self.dirty = true;
+                }
+            }
+            _ => {}
+        }
+    }
+    
+    fn process(&mut self, inputs: &HashMap<String, NodeData>) -> HashMap<String, NodeData> {
+        let mut outputs = HashMap::new();
+        
+        let Some(stage_ref) = inputs.get("Stage").and_then(|data| data.as_string()) else {
+            self.stage_ref.clear();
+            self.variant_sets.clear();
+            self.status = "No USD stage connected".to_string();
+            return outputs;
+        };
+        
+        if stage_ref != self.stage_ref {
+            self.stage_ref = stage_ref.to_string();
+            self.dirty = true;
+        }
+        
+        if self.dirty {
+            self.dirty = false;
+            if let Err(e) = self.refresh() {
+                self.status = format!("⚠ {}", e);
+            }
+        }
+        
+        outputs.insert("Stage".to_string(), NodeData::String(self.stage_ref.clone()));
+        outputs.insert("Selection".to_string(), NodeData::String(self.selections()));
+        outputs
+    }
+}
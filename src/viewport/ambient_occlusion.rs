@@ -0,0 +1,289 @@
+//! Screen space ambient occlusion
+//!
+//! After the scene pass, usd_ssao.wgsl rebuilds view space positions and
+//! normals from the depth buffer and darkens each pixel by how much of a
+//! hemisphere of `radius` scene units around it lies behind other geometry,
+//! scaled by `intensity`. Creases, contact points and cavities read even
+//! when a stage is unlit or only flat lit. The occlusion is drawn into its
+//! own target, blurred over the 4x4 pixels its kernel rotation repeats on,
+//! and multiplied into the frame by the blend state, so the frame never has
+//! to be copied.
+
+use bytemuck::{Pod, Zeroable};
+use glam::{Mat4, Vec3};
+use wgpu::util::DeviceExt;
+
+/// Samples taken per pixel (usd_ssao.wgsl `KERNEL_SIZE`)
+pub const KERNEL_SIZE: usize = 16;
+
+/// Ambient occlusion controls of the viewport
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AmbientOcclusion {
+    pub enabled: bool,
+    /// Reach of the occlusion, in scene units
+    pub radius: f32,
+    /// Darkening of fully occluded pixels, one for black
+    pub intensity: f32,
+}
+
+impl Default for AmbientOcclusion {
+    fn default() -> Self {
+        Self { enabled: false, radius: 0.5, intensity: 1.0 }
+    }
+}
+
+/// Uniform of the occlusion pass (usd_ssao.wgsl `Occlusion`)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+pub struct OcclusionUniform {
+    pub projection: [[f32; 4]; 4],
+    pub inverse_projection: [[f32; 4]; 4],
+    pub kernel: [[f32; 4]; KERNEL_SIZE],
+    pub radius: f32,
+    pub intensity: f32,
+    /// Depth difference ignored, against self-occlusion of flat surfaces
+    pub bias: f32,
+    pub padding: f32,
+}
+
+impl AmbientOcclusion {
+    /// Uniform of the pass for a camera's projection
+    pub fn uniform(&self, projection: Mat4) -> OcclusionUniform {
+        let radius = self.radius.max(1e-3);
+        OcclusionUniform {
+            projection: projection.to_cols_array_2d(),
+            inverse_projection: projection.inverse().to_cols_array_2d(),
+            kernel: hemisphere_kernel(),
+            radius,
+            intensity: self.intensity.max(0.0),
+            bias: radius * 0.025,
+            padding: 0.0,
+        }
+    }
+}
+
+/// Sample offsets in the unit hemisphere around +Z, denser near its center
+///
+/// Directions follow a golden-angle spiral over the hemisphere and lengths
+/// grow quadratically, so nearby geometry weighs more; the shader turns the
+/// kernel per pixel, which the composite blur then evens out.
+pub fn hemisphere_kernel() -> [[f32; 4]; KERNEL_SIZE] {
+    let golden_angle = std::f32::consts::PI * (3.0 - 5f32.sqrt());
+    std::array::from_fn(|index| {
+        let fraction = (index as f32 + 0.5) / KERNEL_SIZE as f32;
+        // Cosine of the angle off the normal, kept off the tangent plane
+        let z = 1.0 - fraction * 0.9;
+        let ring = (1.0 - z * z).sqrt();
+        let angle = index as f32 * golden_angle;
+        let direction = Vec3::new(ring * angle.cos(), ring * angle.sin(), z);
+        let scale = 0.1 + 0.9 * fraction * fraction;
+        (direction * scale).extend(0.0).to_array()
+    })
+}
+
+/// Occlusion and composite pipelines, drawing over a frame's color target
+///
+/// Targets the viewport's Rgba8UnormSrgb color and reads its Depth32Float
+/// depth, which must be stored by the scene pass and bindable as a texture.
+/// With MSAA the depth stays multisampled and its first sample is read. The
+/// depth is bound as an unfilterable float texture rather than a depth
+/// texture, which GL backends can't load from.
+pub struct AmbientOcclusionPass {
+    occlusion_layout: wgpu::BindGroupLayout,
+    composite_layout: wgpu::BindGroupLayout,
+    occlusion_pipeline: wgpu::RenderPipeline,
+    composite_pipeline: wgpu::RenderPipeline,
+}
+
+impl AmbientOcclusionPass {
+    /// Format the occlusion is drawn in before the composite
+    const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::R8Unorm;
+    
+    /// Pipelines reading the depth of a scene pass with `samples` samples per pixel
+    pub fn new(device: &wgpu::Device, samples: u32) -> Self {
+        let texture = |binding, sample_type, multisampled| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Texture {
+                sample_type,
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled,
+            },
+            count: None,
+        };
+        let occlusion_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("usd_ssao_layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                texture(1, wgpu::TextureSampleType::Float { filterable: false }, samples > 1),
+            ],
+        });
+        let composite_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("usd_ssao_composite_layout"),
+            entries: &[texture(0, wgpu::TextureSampleType::Float { filterable: false }, false)],
+        });
+        // textureLoad's last argument is the sample index of a multisampled depth
+        let source = match samples > 1 {
+            true => include_str!("shaders/usd_ssao.wgsl").replace("texture_2d<f32>; // depth", "texture_multisampled_2d<f32>;"),
+            false => include_str!("shaders/usd_ssao.wgsl").to_string(),
+        };
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("usd_ssao"),
+            source: wgpu::ShaderSource::Wgsl(source.into()),
+        });
+        
+        let pipeline = |label, layouts: &[&wgpu::BindGroupLayout], entry_point, target: wgpu::ColorTargetState| {
+            let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some(label),
+                bind_group_layouts: layouts,
+                push_constant_ranges: &[],
+            });
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(label),
+                layout: Some(&pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: Some("vs_main"),
+                    compilation_options: Default::default(),
+                    buffers: &[],
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point: Some(entry_point),
+                    compilation_options: Default::default(),
+                    targets: &[Some(target)],
+                }),
+                primitive: wgpu::PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                multiview: None,
+                cache: None,
+            })
+        };
+        let occlusion_pipeline = pipeline("usd_ssao", &[&occlusion_layout], "fs_occlusion", wgpu::ColorTargetState {
+            format: Self::FORMAT,
+            blend: None,
+            write_mask: wgpu::ColorWrites::ALL,
+        });
+        // The frame's color is multiplied by the occlusion; its alpha is kept
+        let composite_pipeline = pipeline("usd_ssao_composite", &[&occlusion_layout, &composite_layout], "fs_composite", wgpu::ColorTargetState {
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            blend: Some(wgpu::BlendState {
+                color: wgpu::BlendComponent {
+                    src_factor: wgpu::BlendFactor::Zero,
+                    dst_factor: wgpu::BlendFactor::Src,
+                    operation: wgpu::BlendOperation::Add,
+                },
+                alpha: wgpu::BlendComponent {
+                    src_factor: wgpu::BlendFactor::Zero,
+                    dst_factor: wgpu::BlendFactor::One,
+                    operation: wgpu::BlendOperation::Add,
+                },
+            }),
+            write_mask: wgpu::ColorWrites::ALL,
+        });
+        Self { occlusion_layout, composite_layout, occlusion_pipeline, composite_pipeline }
+    }
+    
+    /// Record the occlusion of a `width` x `height` frame and multiply it into `color`
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw(
+        &self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        color: &wgpu::TextureView,
+        depth: &wgpu::TextureView,
+        width: u32,
+        height: u32,
+        uniform: &OcclusionUniform,
+    ) {
+        let target = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("usd_ssao_occlusion"),
+            size: wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: Self::FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        let target_view = target.create_view(&wgpu::TextureViewDescriptor::default());
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("usd_ssao_uniform"),
+            contents: bytemuck::bytes_of(uniform),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let occlusion_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("usd_ssao"),
+            layout: &self.occlusion_layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: uniform_buffer.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::TextureView(depth) },
+            ],
+        });
+        let composite_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("usd_ssao_composite"),
+            layout: &self.composite_layout,
+            entries: &[wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(&target_view) }],
+        });
+        
+        {
+            let mut render_pass = fullscreen_pass(encoder, "usd_ssao_pass", &target_view, wgpu::LoadOp::Clear(wgpu::Color::WHITE));
+            render_pass.set_pipeline(&self.occlusion_pipeline);
+            render_pass.set_bind_group(0, &occlusion_group, &[]);
+            render_pass.draw(0..3, 0..1);
+        }
+        {
+            let mut render_pass = fullscreen_pass(encoder, "usd_ssao_composite_pass", color, wgpu::LoadOp::Load);
+            render_pass.set_pipeline(&self.composite_pipeline);
+            render_pass.set_bind_group(0, &occlusion_group, &[]);
+            render_pass.set_bind_group(1, &composite_group, &[]);
+            render_pass.draw(0..3, 0..1);
+        }
+    }
+}
+
+/// Render pass drawing over a whole color target, without depth
+pub fn fullscreen_pass<'a>(encoder: &'a mut wgpu::CommandEncoder, label: &str, view: &wgpu::TextureView, load: wgpu::LoadOp<wgpu::Color>) -> wgpu::RenderPass<'a> {
+    encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        label: Some(label),
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view,
+            resolve_target: None,
+            ops: wgpu::Operations { load, store: wgpu::StoreOp::Store },
+        })],
+        depth_stencil_attachment: None,
+        timestamp_writes: None,
+        occlusion_query_set: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn kernel_fills_the_hemisphere_from_its_center_out() {
+        let kernel = hemisphere_kernel();
+        let lengths: Vec<f32> = kernel.iter().map(|sample| Vec3::from_slice(&sample[..3]).length()).collect();
+        assert!(kernel.iter().all(|sample| sample[2] > 0.0 && sample[3] == 0.0));
+        assert!(lengths.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(lengths[0] >= 0.1 && lengths[KERNEL_SIZE - 1] <= 1.0);
+        
+        let settings = AmbientOcclusion { enabled: true, radius: 2.0, intensity: 1.5 };
+        let projection = Mat4::perspective_rh(1.0, 1.5, 0.1, 100.0);
+        let uniform = settings.uniform(projection);
+        assert_eq!(uniform.bias, 0.05);
+        let round_trip = Mat4::from_cols_array_2d(&uniform.projection) * Mat4::from_cols_array_2d(&uniform.inverse_projection);
+        assert!(round_trip.abs_diff_eq(Mat4::IDENTITY, 1e-4));
+    }
+}
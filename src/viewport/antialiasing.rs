@@ -0,0 +1,197 @@
+//! Anti-aliasing of the wgpu viewport
+//!
+//! The viewport is drawn with MSAA at the sample count picked in the panel,
+//! or FXAA when the device can't multisample its color and depth formats.
+//! Pipelines drawing into the scene pass are created for the resolved
+//! sample count and rebuilt when it changes. FXAA runs after the scene pass
+//! instead, reading the frame and writing the smoothed copy to a second
+//! target, as usd_fxaa.wgsl's console variant of FXAA 3.11 does.
+
+use wgpu::TextureFormatFeatureFlags;
+use super::ambient_occlusion::fullscreen_pass;
+
+/// Color and depth formats of the viewport pass, which must both multisample
+pub const PASS_FORMATS: [wgpu::TextureFormat; 2] = [wgpu::TextureFormat::Rgba8UnormSrgb, wgpu::TextureFormat::Depth32Float];
+
+/// Anti-aliasing of the viewport
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AntiAliasing {
+    Off,
+    /// Edge smoothing after the scene pass
+    Fxaa,
+    /// Multisampling with a sample count of 2, 4 or 8
+    Msaa(u32),
+}
+
+impl Default for AntiAliasing {
+    fn default() -> Self {
+        AntiAliasing::Msaa(4)
+    }
+}
+
+impl AntiAliasing {
+    pub const ALL: [AntiAliasing; 5] = [
+        AntiAliasing::Off,
+        AntiAliasing::Fxaa,
+        AntiAliasing::Msaa(2),
+        AntiAliasing::Msaa(4),
+        AntiAliasing::Msaa(8),
+    ];
+    
+    pub fn label(&self) -> String {
+        match self {
+            AntiAliasing::Off => "Off".to_string(),
+            AntiAliasing::Fxaa => "FXAA".to_string(),
+            AntiAliasing::Msaa(samples) => format!("MSAA {}x", samples),
+        }
+    }
+    
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.label() == label)
+    }
+    
+    /// Samples per pixel of the scene pass
+    pub fn sample_count(&self) -> u32 {
+        match self {
+            AntiAliasing::Msaa(samples) => *samples,
+            _ => 1,
+        }
+    }
+    
+    /// This mode as the device can draw it, given the multisample flags of the pass formats
+    ///
+    /// MSAA falls back to the most samples supported up to the count asked
+    /// for, else the fewest supported above it, and to FXAA when the formats
+    /// can't be multisampled at all.
+    pub fn supported(self, flags: TextureFormatFeatureFlags) -> Self {
+        let AntiAliasing::Msaa(requested) = self else {
+            return self;
+        };
+        let counts: Vec<u32> = [2, 4, 8].into_iter().filter(|count| flags.sample_count_supported(*count)).collect();
+        match counts.iter().rev().find(|count| **count <= requested).or(counts.first()) {
+            Some(count) => AntiAliasing::Msaa(*count),
+            None => AntiAliasing::Fxaa,
+        }
+    }
+}
+
+/// Multisample flags every pass format has on a device
+///
+/// Only the guaranteed features of the formats are known from the device;
+/// without adapter specific format features that is 4x MSAA.
+pub fn pass_sample_flags(device: &wgpu::Device) -> TextureFormatFeatureFlags {
+    PASS_FORMATS.iter().fold(TextureFormatFeatureFlags::all(), |flags, format| {
+        flags & format.guaranteed_format_features(device.features()).flags
+    })
+}
+
+/// FXAA pipeline, copying a frame's Rgba8UnormSrgb color into another target with its edges smoothed
+pub struct FxaaPass {
+    layout: wgpu::BindGroupLayout,
+    pipeline: wgpu::RenderPipeline,
+    sampler: wgpu::Sampler,
+}
+
+impl FxaaPass {
+    pub fn new(device: &wgpu::Device) -> Self {
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("usd_fxaa_layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("usd_fxaa_pipeline_layout"),
+            bind_group_layouts: &[&layout],
+            push_constant_ranges: &[],
+        });
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("usd_fxaa"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/usd_fxaa.wgsl").into()),
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("usd_fxaa"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                compilation_options: Default::default(),
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                compilation_options: Default::default(),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: wgpu::TextureFormat::Rgba8UnormSrgb,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("usd_fxaa"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+        Self { layout, pipeline, sampler }
+    }
+    
+    /// Record FXAA of `source` into `target`, a texture of the same size
+    pub fn draw(&self, device: &wgpu::Device, encoder: &mut wgpu::CommandEncoder, source: &wgpu::TextureView, target: &wgpu::TextureView) {
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("usd_fxaa"),
+            layout: &self.layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(source) },
+                wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::Sampler(&self.sampler) },
+            ],
+        });
+        let mut render_pass = fullscreen_pass(encoder, "usd_fxaa_pass", target, wgpu::LoadOp::Clear(wgpu::Color::BLACK));
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn modes_fall_back_to_what_the_formats_support() {
+        let x4 = TextureFormatFeatureFlags::MULTISAMPLE_X4;
+        let x2_x4 = x4 | TextureFormatFeatureFlags::MULTISAMPLE_X2;
+        assert_eq!(AntiAliasing::Msaa(8).supported(x4), AntiAliasing::Msaa(4));
+        assert_eq!(AntiAliasing::Msaa(2).supported(x4), AntiAliasing::Msaa(4));
+        assert_eq!(AntiAliasing::Msaa(2).supported(x2_x4), AntiAliasing::Msaa(2));
+        assert_eq!(AntiAliasing::Msaa(4).supported(TextureFormatFeatureFlags::empty()), AntiAliasing::Fxaa);
+        assert_eq!(AntiAliasing::Off.supported(TextureFormatFeatureFlags::empty()), AntiAliasing::Off);
+        
+        assert_eq!(AntiAliasing::Fxaa.sample_count(), 1);
+        for mode in AntiAliasing::ALL {
+            assert_eq!(AntiAliasing::from_label(&mode.label()), Some(mode));
+        }
+    }
+}
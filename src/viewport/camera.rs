@@ -1,16 +1,6 @@
 //! Camera system for USD viewport with Maya-style navigation
 
 use glam::{Mat4, Vec3};
-use bytemuck::{Pod, Zeroable};
-
-/// 3D Vertex structure for rendering
-#[repr(C)]
-#[derive(Debug, Copy, Clone, Pod, Zeroable)]
-pub struct Vertex3D {
-    pub position: [f32; 3],
-    pub normal: [f32; 3],
-    pub uv: [f32; 2],
-}
 
 /// 3D Camera with Maya-style navigation
 #[derive(Debug, Clone)]
@@ -213,7 +203,7 @@ impl Camera3D {
         let s = ray_origin - v0;
         let u = f * s.dot(h);
         
-        if u < 0.0 || u > 1.0 {
+        if !(0.0..=1.0).contains(&u) {
             return None;
         }
         
@@ -290,8 +280,6 @@ impl Camera3D {
         // No direct intersection - use a reasonable default distance
         // Use current target distance as a sensible fallback
         let fallback_distance = (self.target - self.position).length();
-        let fallback_point = ray_origin + ray_direction * fallback_distance;
-        
-        fallback_point
+        ray_origin + ray_direction * fallback_distance
     }
 }
\ No newline at end of file
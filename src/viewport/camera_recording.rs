@@ -0,0 +1,123 @@
+//! Camera path recording for flythroughs
+//!
+//! While recording, the free camera is sampled once per frame of the
+//! timeline rate, timed from wall-clock time since recording started. The
+//! samples become time samples of a UsdGeomCamera's xformOps and lens, so
+//! the flythrough plays back in any renderer reading the stage.
+
+use std::time::Instant;
+use glam::Vec3;
+use crate::core::usd_value::UsdValue;
+use super::projection::ProjectionCamera;
+
+/// A camera path being recorded
+#[derive(Debug, Clone)]
+pub struct CameraRecorder {
+    /// Time code of the first sample
+    pub start_time_code: f64,
+    pub fps: f64,
+    started: Instant,
+    /// Recorded cameras by time code, in order
+    pub samples: Vec<(f64, ProjectionCamera)>,
+}
+
+impl CameraRecorder {
+    pub fn start(start_time_code: f64, fps: f64, now: Instant) -> Self {
+        Self {
+            start_time_code,
+            fps: fps.max(1.0),
+            started: now,
+            samples: Vec::new(),
+        }
+    }
+    
+    /// Record the camera at the frame `now` falls on, returning whether that started a new frame
+    ///
+    /// Later calls within the same frame replace its sample, so each frame
+    /// keeps where the camera was when the frame ended.
+    pub fn record(&mut self, camera: ProjectionCamera, now: Instant) -> bool {
+        let frame = (now.duration_since(self.started).as_secs_f64() * self.fps).floor();
+        let time_code = self.start_time_code + frame;
+        match self.samples.last_mut() {
+            Some((last, sample)) if *last == time_code => {
+                *sample = camera;
+                false
+            }
+            _ => {
+                self.samples.push((time_code, camera));
+                true
+            }
+        }
+    }
+    
+    /// Time samples of the camera attributes, with rotations unwrapped so interpolation takes the short way
+    pub fn time_samples(&self) -> Vec<(&'static str, UsdValue)> {
+        let vec3 = |v: Vec3| UsdValue::Vec3([v.x as f64, v.y as f64, v.z as f64]);
+        let mut translates = Vec::with_capacity(self.samples.len());
+        let mut rotates = Vec::with_capacity(self.samples.len());
+        let mut previous: Option<Vec3> = None;
+        for (time, camera) in &self.samples {
+            let (translate, rotate) = camera.xform_ops();
+            let rotate = match previous {
+                Some(previous) => Vec3::new(
+                    unwrap_degrees(previous.x, rotate.x),
+                    unwrap_degrees(previous.y, rotate.y),
+                    unwrap_degrees(previous.z, rotate.z),
+                ),
+                None => rotate,
+            };
+            previous = Some(rotate);
+            translates.push((*time, vec3(translate)));
+            rotates.push((*time, vec3(rotate)));
+        }
+        let lens = |value: fn(&ProjectionCamera) -> f32| UsdValue::TimeSamples(
+            self.samples.iter().map(|(time, camera)| (*time, UsdValue::Float(value(camera)))).collect(),
+        );
+        vec![
+            ("xformOp:translate", UsdValue::TimeSamples(translates)),
+            ("xformOp:rotateXYZ", UsdValue::TimeSamples(rotates)),
+            ("focalLength", lens(|camera| camera.focal_length)),
+            ("horizontalAperture", lens(|camera| camera.horizontal_aperture)),
+        ]
+    }
+}
+
+/// The angle equal to `degrees` modulo 360 that is nearest to `previous`
+pub fn unwrap_degrees(previous: f32, degrees: f32) -> f32 {
+    degrees + ((previous - degrees) / 360.0).round() * 360.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    
+    #[test]
+    fn samples_follow_the_timeline_rate() {
+        let start = Instant::now();
+        let mut recorder = CameraRecorder::start(1001.0, 24.0, start);
+        let camera = |x: f32| ProjectionCamera::from_view(Vec3::new(x, 1.0, 5.0), Vec3::ZERO, Vec3::Y, 0.8, 1.5, 0.1, 100.0);
+        
+        assert!(recorder.record(camera(0.0), start));
+        assert!(!recorder.record(camera(1.0), start + Duration::from_millis(20)));
+        assert!(recorder.record(camera(2.0), start + Duration::from_millis(50)));
+        assert!(recorder.record(camera(3.0), start + Duration::from_millis(130)));
+        let times: Vec<f64> = recorder.samples.iter().map(|(time, _)| *time).collect();
+        assert_eq!(times, [1001.0, 1002.0, 1004.0]);
+        
+        let samples = recorder.time_samples();
+        let UsdValue::TimeSamples(translates) = &samples[0].1 else {
+            panic!("translate is not time sampled");
+        };
+        // The first frame keeps the camera of its last call
+        assert!(matches!(translates[0].1, UsdValue::Vec3([x, _, _]) if (x - 1.0).abs() < 1e-5));
+    }
+    
+    #[test]
+    fn rotations_unwrap_across_the_seam() {
+        assert_eq!(unwrap_degrees(179.0, -179.0), 181.0);
+        assert_eq!(unwrap_degrees(-170.0, 175.0), -185.0);
+        assert_eq!(unwrap_degrees(720.0, 10.0), 730.0);
+        assert_eq!(unwrap_degrees(0.0, 45.0), 45.0);
+    }
+}
@@ -0,0 +1,158 @@
+//! Physically based response of looked-through cameras
+//!
+//! Looking through a UsdGeomCamera previews what its lens and exposure would
+//! give. The exposure attributes scale scene lighting as
+//! `UsdGeomCamera::ComputeLinearExposureScale` does, and a depth of field
+//! post pass blurs each pixel of a frame by its circle of confusion, from
+//! the camera's fStop, focusDistance and focal length. UsdGeomCamera gives
+//! focal length and apertures in tenths of a scene unit and focus distance
+//! in scene units; an fStop of zero means a pinhole, with no depth of field.
+
+use crate::capture::{linear_to_srgb, srgb_to_linear, CapturedFrame};
+
+/// Largest blur radius of the depth of field pass, in pixels
+pub const MAX_BLUR_RADIUS: f32 = 16.0;
+
+/// Samples gathered per blurred pixel, on a golden-angle spiral
+const BLUR_SAMPLES: usize = 32;
+
+/// Lens and exposure of a camera, as far as they change its image
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CameraResponse {
+    pub focal_length: f32,
+    pub horizontal_aperture: f32,
+    /// Lens f-number, zero for no depth of field
+    pub f_stop: f32,
+    /// Distance in focus, in scene units
+    pub focus_distance: f32,
+    /// Factor scene lighting is multiplied by
+    pub exposure_scale: f32,
+}
+
+impl Default for CameraResponse {
+    fn default() -> Self {
+        // UsdGeomCamera schema defaults
+        Self {
+            focal_length: 50.0,
+            horizontal_aperture: 20.955,
+            f_stop: 0.0,
+            focus_distance: 0.0,
+            exposure_scale: 1.0,
+        }
+    }
+}
+
+/// Linear exposure scale of UsdGeomCamera's `exposure`, `exposure:fStop`, `exposure:time`, `exposure:iso` and `exposure:responsivity`
+pub fn exposure_scale(exposure: f32, f_stop: f32, time: f32, iso: f32, responsivity: f32) -> f32 {
+    exposure.exp2() * responsivity * time * (iso / 100.0) / (f_stop * f_stop).max(1e-6)
+}
+
+impl CameraResponse {
+    pub fn has_depth_of_field(&self) -> bool {
+        self.f_stop > 0.0 && self.focus_distance > 0.0
+    }
+    
+    /// Whether the response changes a frame at all
+    pub fn is_identity(&self) -> bool {
+        !self.has_depth_of_field() && self.exposure_scale == 1.0
+    }
+    
+    /// Circle of confusion diameter of a point `depth` in front of the camera, in pixels of a `width` wide frame
+    pub fn blur_diameter(&self, depth: f32, width: u32) -> f32 {
+        if !self.has_depth_of_field() || depth <= 0.0 {
+            return 0.0;
+        }
+        let focal_length = self.focal_length * 0.1;
+        let focus = self.focus_distance.max(focal_length * 1.001);
+        let aperture = focal_length / self.f_stop;
+        let diameter = aperture * focal_length * (depth - focus).abs() / (depth * (focus - focal_length));
+        diameter / (self.horizontal_aperture * 0.1).max(1e-6) * width as f32
+    }
+    
+    /// Expose a frame and blur it by depth, with the depth of each pixel top row first
+    ///
+    /// Works in linear light. Without depths, or without depth of field,
+    /// only the exposure is applied. Samples only reach a pixel if their own
+    /// blur covers it, so sharp edges don't bleed into blurred backgrounds.
+    pub fn apply(&self, frame: &mut CapturedFrame, depths: Option<&[f32]>) {
+        if self.is_identity() {
+            return;
+        }
+        let (width, height) = (frame.width as usize, frame.height as usize);
+        let linear: Vec<[f32; 4]> = frame.pixels.chunks_exact(4)
+            .map(|pixel| {
+                let channel = |index: usize| srgb_to_linear(pixel[index]) * self.exposure_scale;
+                [channel(0), channel(1), channel(2), pixel[3] as f32 / 255.0]
+            })
+            .collect();
+        
+        let radii: Option<Vec<f32>> = depths
+            .filter(|depths| self.has_depth_of_field() && depths.len() == linear.len())
+            .map(|depths| depths.iter().map(|depth| (self.blur_diameter(*depth, frame.width) * 0.5).min(MAX_BLUR_RADIUS)).collect());
+        let golden_angle = std::f32::consts::PI * (3.0 - 5f32.sqrt());
+        for (index, pixel) in frame.pixels.chunks_exact_mut(4).enumerate() {
+            let mut color = linear[index];
+            if let Some(radii) = radii.as_ref().filter(|radii| radii[index] >= 0.5) {
+                let (x, y) = ((index % width) as f32, (index / width) as f32);
+                let (mut sum, mut weight) = (color, 1.0);
+                for sample in 0..BLUR_SAMPLES {
+                    let distance = ((sample as f32 + 0.5) / BLUR_SAMPLES as f32).sqrt() * radii[index];
+                    let angle = sample as f32 * golden_angle;
+                    let (sx, sy) = ((x + distance * angle.cos()).round(), (y + distance * angle.sin()).round());
+                    if sx < 0.0 || sy < 0.0 || sx >= width as f32 || sy >= height as f32 {
+                        continue;
+                    }
+                    let other = sy as usize * width + sx as usize;
+                    if radii[other] >= distance {
+                        for (total, value) in sum.iter_mut().zip(linear[other]) {
+                            *total += value;
+                        }
+                        weight += 1.0;
+                    }
+                }
+                color = sum.map(|total| total / weight);
+            }
+            for channel in 0..3 {
+                pixel[channel] = linear_to_srgb(color[channel]);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn exposure_and_depth_of_field_follow_the_lens() {
+        assert_eq!(exposure_scale(0.0, 1.0, 1.0, 100.0, 1.0), 1.0);
+        assert_eq!(exposure_scale(1.0, 2.0, 1.0, 100.0, 1.0), 0.5);
+        
+        let lens = CameraResponse { f_stop: 2.8, focus_distance: 10.0, ..CameraResponse::default() };
+        assert_eq!(lens.blur_diameter(10.0, 1920), 0.0);
+        assert!(lens.blur_diameter(100.0, 1920) > lens.blur_diameter(20.0, 1920));
+        assert_eq!(CameraResponse::default().blur_diameter(100.0, 1920), 0.0);
+        
+        // Left half black, right half white
+        let (width, height) = (16, 4);
+        let pixels: Vec<u8> = (0..width * height).flat_map(|index| {
+            let value = if index % width < width / 2 { 0 } else { 255 };
+            [value, value, value, 255]
+        }).collect();
+        let frame = CapturedFrame::new(width as u32, height as u32, pixels).unwrap();
+        let edge = |frame: &CapturedFrame| frame.pixels[(width / 2 - 1) * 4];
+        
+        // In focus the frame is untouched, out of focus the edge blurs
+        let mut sharp = frame.clone();
+        lens.apply(&mut sharp, Some(&vec![10.0; width * height]));
+        assert_eq!(sharp.pixels, frame.pixels);
+        let mut blurred = frame.clone();
+        lens.apply(&mut blurred, Some(&vec![1000.0; width * height]));
+        assert!(edge(&blurred) > 0);
+        
+        // Exposure brightens in linear light
+        let mut exposed = CapturedFrame::new(1, 1, vec![128, 128, 128, 255]).unwrap();
+        CameraResponse { exposure_scale: 2.0, ..CameraResponse::default() }.apply(&mut exposed, None);
+        assert!(exposed.pixels[0] > 170 && exposed.pixels[3] == 255);
+    }
+}
@@ -0,0 +1,137 @@
+//! Display color management of the viewport
+//!
+//! The viewport shades in linear Rec. 709: UsdUVTexture images are decoded
+//! by their sourceColorSpace, and lights, display colors and material
+//! constants are linear as authored. Shaded values are unbounded, so they
+//! reach the screen the way an OCIO display view maps scene light: scaled
+//! by an exposure in stops, compressed into the display range by a view
+//! transform, and raised to one over the display gamma before the sRGB
+//! target encodes them. The plain sRGB view clips; Filmic rolls highlights
+//! off with Hable's filmic curve and ACES with Stephen Hill's fit of the
+//! ACES RRT and sRGB ODT. usd_mesh.wgsl's `display_transform` mirrors
+//! `ColorManagement::display` for the wgpu scene; the path traced preview
+//! is transformed on the CPU.
+
+use glam::{Mat3, Vec3};
+
+/// Curve mapping scene light into the display range
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViewTransform {
+    /// Clip at display white
+    Standard,
+    Filmic,
+    Aces,
+}
+
+impl ViewTransform {
+    pub const ALL: [ViewTransform; 3] = [ViewTransform::Standard, ViewTransform::Filmic, ViewTransform::Aces];
+    
+    pub fn label(&self) -> &'static str {
+        match self {
+            ViewTransform::Standard => "sRGB",
+            ViewTransform::Filmic => "Filmic",
+            ViewTransform::Aces => "ACES",
+        }
+    }
+    
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|transform| transform.label() == label)
+    }
+    
+    /// Index the shader switches on (usd_mesh.wgsl `USDLights.view_transform`)
+    pub fn index(&self) -> u32 {
+        match self {
+            ViewTransform::Standard => 0,
+            ViewTransform::Filmic => 1,
+            ViewTransform::Aces => 2,
+        }
+    }
+    
+    /// Display linear color in [0, 1] for a scene linear color
+    pub fn apply(&self, color: Vec3) -> Vec3 {
+        let color = color.max(Vec3::ZERO);
+        match self {
+            ViewTransform::Standard => color.min(Vec3::ONE),
+            ViewTransform::Filmic => (hable(color * 2.0) / hable(Vec3::splat(11.2))).min(Vec3::ONE),
+            ViewTransform::Aces => {
+                // Rows of Hill's matrices, applied as column vectors
+                let input = Mat3::from_cols_array(&[
+                    0.59719, 0.07600, 0.02840,
+                    0.35458, 0.90834, 0.13383,
+                    0.04823, 0.01566, 0.83777,
+                ]);
+                let output = Mat3::from_cols_array(&[
+                    1.60475, -0.10208, -0.00327,
+                    -0.53108, 1.10813, -0.07276,
+                    -0.07367, -0.00605, 1.07602,
+                ]);
+                let v = input * color;
+                let fitted = (v * (v + 0.0245786) - 0.000090537) / (v * (0.983729 * v + 0.432951) + 0.238081);
+                (output * fitted).clamp(Vec3::ZERO, Vec3::ONE)
+            }
+        }
+    }
+}
+
+/// Hable's filmic curve, before normalizing by its white point
+fn hable(x: Vec3) -> Vec3 {
+    let (a, b, c, d, e, f) = (0.15, 0.50, 0.10, 0.20, 0.02, 0.30);
+    (x * (a * x + c * b) + d * e) / (x * (a * x + b) + d * f) - e / f
+}
+
+/// How the viewport shows scene light on the display
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorManagement {
+    pub view_transform: ViewTransform,
+    /// Exposure in stops, on top of a looked-through camera's exposure
+    pub exposure: f32,
+    /// Display gamma; one leaves the sRGB encoding as is
+    pub gamma: f32,
+}
+
+impl Default for ColorManagement {
+    fn default() -> Self {
+        Self { view_transform: ViewTransform::Standard, exposure: 0.0, gamma: 1.0 }
+    }
+}
+
+impl ColorManagement {
+    /// Linear scale of the exposure
+    pub fn exposure_scale(&self) -> f32 {
+        self.exposure.exp2()
+    }
+    
+    /// Display linear color for a scene linear color, to be sRGB encoded
+    pub fn display(&self, color: Vec3) -> Vec3 {
+        let mapped = self.view_transform.apply(color * self.exposure_scale());
+        mapped.powf(1.0 / self.gamma.max(0.01))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn views_map_scene_light_into_the_display_range() {
+        let gray = Vec3::splat(0.18);
+        let standard = ColorManagement::default();
+        assert_eq!(standard.display(gray), gray);
+        assert_eq!(standard.display(Vec3::splat(4.0)), Vec3::ONE);
+        assert_eq!(ColorManagement { exposure: 1.0, ..standard }.display(gray), gray * 2.0);
+        let brighter = ColorManagement { gamma: 2.0, ..standard }.display(gray);
+        assert!((brighter.x - 0.18f32.sqrt()).abs() < 1e-6);
+        
+        // Tone curves start at black, rise monotonically and roll off below white
+        for transform in [ViewTransform::Filmic, ViewTransform::Aces] {
+            assert!(transform.apply(Vec3::ZERO).max_element() < 1e-3);
+            let levels: Vec<f32> = [0.05, 0.18, 1.0, 4.0, 16.0].iter().map(|value| transform.apply(Vec3::splat(*value)).x).collect();
+            assert!(levels.windows(2).all(|pair| pair[0] < pair[1]), "{:?}", levels);
+            assert!(levels[4] <= 1.0 && levels[3] > 0.8);
+            // Neutral stays neutral
+            let mapped = transform.apply(gray);
+            assert!((mapped.x - mapped.y).abs() < 1e-2 && (mapped.y - mapped.z).abs() < 1e-2);
+            assert_eq!(ViewTransform::from_label(transform.label()), Some(transform));
+        }
+    }
+}
@@ -0,0 +1,87 @@
+//! Frustum culling of viewport draws
+//!
+//! The wgpu renderer and the live viewport both decide what to draw through
+//! `FrustumCull`: prims whose world bounds lie outside the view frustum are
+//! skipped when drawing, while their buffers stay uploaded, and the drawn
+//! and culled counts feed the viewport panel.
+
+use glam::Mat4;
+use crate::core::bounds::BoundingBox;
+
+/// Prims drawn and skipped by frustum culling in a frame
+///
+/// An instance batch counts as one prim, drawn when any instance may be in view.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CullStats {
+    pub drawn: usize,
+    pub culled: usize,
+}
+
+/// Frustum culling of one frame's draws, counting what it draws and culls
+#[derive(Debug, Clone)]
+pub struct FrustumCull {
+    view_projection: Mat4,
+    enabled: bool,
+    stats: CullStats,
+}
+
+impl FrustumCull {
+    /// Culling against a view projection, drawing everything when not enabled
+    pub fn new(view_projection: Mat4, enabled: bool) -> Self {
+        Self { view_projection, enabled, stats: CullStats::default() }
+    }
+    
+    /// Whether a prim with these world bounds may be in view
+    ///
+    /// Prims without bounds, such as ones not uploaded yet, are never culled.
+    pub fn in_view(&self, bounds: Option<&BoundingBox>) -> bool {
+        !self.enabled || bounds.is_none_or(|bounds| bounds.intersects_frustum(&self.view_projection))
+    }
+    
+    /// Whether to draw a prim with these world bounds, counting it as drawn or culled
+    pub fn draws(&mut self, bounds: Option<&BoundingBox>) -> bool {
+        let drawn = self.in_view(bounds);
+        if drawn {
+            self.stats.drawn += 1;
+        } else {
+            self.stats.culled += 1;
+        }
+        drawn
+    }
+    
+    /// Prims drawn and culled so far
+    pub fn stats(&self) -> CullStats {
+        self.stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::Vec3;
+    
+    fn view_projection() -> Mat4 {
+        // Looking down -Z from the origin
+        Mat4::perspective_rh(60f32.to_radians(), 1.0, 0.1, 100.0) * Mat4::look_at_rh(Vec3::ZERO, Vec3::NEG_Z, Vec3::Y)
+    }
+    
+    #[test]
+    fn counts_prims_in_and_out_of_view() {
+        let ahead = BoundingBox::new(Vec3::new(-1.0, -1.0, -6.0), Vec3::new(1.0, 1.0, -4.0));
+        let behind = BoundingBox::new(Vec3::new(-1.0, -1.0, 4.0), Vec3::new(1.0, 1.0, 6.0));
+        
+        let mut cull = FrustumCull::new(view_projection(), true);
+        assert!(cull.draws(Some(&ahead)));
+        assert!(!cull.draws(Some(&behind)));
+        // Prims without bounds are drawn
+        assert!(cull.draws(None));
+        assert_eq!(cull.stats(), CullStats { drawn: 2, culled: 1 });
+        // Checking without drawing counts nothing
+        assert!(!cull.in_view(Some(&behind)));
+        assert_eq!(cull.stats(), CullStats { drawn: 2, culled: 1 });
+        
+        let mut disabled = FrustumCull::new(view_projection(), false);
+        assert!(disabled.draws(Some(&behind)));
+        assert_eq!(disabled.stats(), CullStats { drawn: 1, culled: 0 });
+    }
+}
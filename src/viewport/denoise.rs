@@ -0,0 +1,190 @@
+//! Edge-aware denoising of the path traced preview
+//!
+//! A few samples per pixel leave the path traced frame grainy, so the frame
+//! shown is filtered with the features of the first surface each pixel's
+//! rays hit: its albedo, normal and depth. Built with the "oidn" feature,
+//! Intel Open Image Denoise filters the frame with those as its auxiliary
+//! images; otherwise, or when OIDN fails, an edge-avoiding à-trous wavelet
+//! filter (Dammertz et al. 2010) blurs each pixel over growing 5x5
+//! footprints, weighting neighbors down where their color, albedo, normal
+//! or depth differ. Its color tolerance shrinks with the square root of the
+//! sample count, so the filter lets go as the frame converges.
+
+use std::ops::{AddAssign, Mul};
+use glam::Vec3;
+
+/// B3 spline taps of the à-trous kernel along each axis
+const KERNEL: [f32; 5] = [1.0 / 16.0, 1.0 / 4.0, 3.0 / 8.0, 1.0 / 4.0, 1.0 / 16.0];
+
+/// Passes of the à-trous filter; the last reaches 62 pixels across
+const ITERATIONS: u32 = 5;
+
+/// Color difference tolerated at one sample per pixel, in exposed radiance
+const COLOR_SIGMA: f32 = 1.0;
+
+const ALBEDO_SIGMA: f32 = 0.1;
+const NORMAL_SIGMA: f32 = 0.3;
+
+/// Depth difference tolerated per pixel of filter step, relative to the depth
+const DEPTH_SIGMA: f32 = 0.02;
+
+/// Features of the first surface a pixel's rays hit, zero where they left the scene
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PixelFeatures {
+    pub albedo: Vec3,
+    /// World space normal facing the camera
+    pub normal: Vec3,
+    /// Distance along the ray
+    pub depth: f32,
+}
+
+impl AddAssign for PixelFeatures {
+    fn add_assign(&mut self, other: Self) {
+        self.albedo += other.albedo;
+        self.normal += other.normal;
+        self.depth += other.depth;
+    }
+}
+
+impl Mul<f32> for PixelFeatures {
+    type Output = Self;
+    
+    fn mul(self, scale: f32) -> Self {
+        Self { albedo: self.albedo * scale, normal: self.normal * scale, depth: self.depth * scale }
+    }
+}
+
+/// Denoise a frame `width` pixels wide, top row first, averaged over `samples` samples per pixel
+pub fn denoise(color: &[Vec3], features: &[PixelFeatures], width: usize, samples: u32) -> Vec<Vec3> {
+    #[cfg(feature = "oidn")]
+    match oidn_denoise(color, features, width) {
+        Ok(denoised) => return denoised,
+        Err(error) => eprintln!("OIDN denoise failed, using the à-trous filter: {}", error),
+    }
+    a_trous(color, features, width, COLOR_SIGMA / (samples.max(1) as f32).sqrt())
+}
+
+/// Filter a frame with the edge-avoiding à-trous wavelet filter, rows split across all cores
+pub fn a_trous(color: &[Vec3], features: &[PixelFeatures], width: usize, color_sigma: f32) -> Vec<Vec3> {
+    if width == 0 || color.len() != features.len() {
+        return color.to_vec();
+    }
+    let height = color.len() / width;
+    let threads = std::thread::available_parallelism().map_or(1, |count| count.get());
+    let band = height.div_ceil(threads).max(1) * width;
+    
+    let mut source = color.to_vec();
+    let mut filtered = vec![Vec3::ZERO; color.len()];
+    for iteration in 0..ITERATIONS {
+        let step = 1 << iteration;
+        // Wider steps compare colors already smoothed, so they tolerate less
+        let sigma = color_sigma / (step as f32).sqrt();
+        let previous = &source;
+        std::thread::scope(|scope| {
+            for (chunk, pixels) in filtered.chunks_mut(band).enumerate() {
+                scope.spawn(move || {
+                    for (offset, pixel) in pixels.iter_mut().enumerate() {
+                        *pixel = filter_pixel(previous, features, width, chunk * band + offset, step, sigma);
+                    }
+                });
+            }
+        });
+        std::mem::swap(&mut source, &mut filtered);
+    }
+    source
+}
+
+/// One à-trous tap pattern around a pixel, `step` pixels between taps
+fn filter_pixel(color: &[Vec3], features: &[PixelFeatures], width: usize, index: usize, step: i32, color_sigma: f32) -> Vec3 {
+    let height = (color.len() / width) as i32;
+    let (x, y) = ((index % width) as i32, (index / width) as i32);
+    let (center, feature) = (color[index], features[index]);
+    let mut sum = Vec3::ZERO;
+    let mut total = 0.0;
+    for (row, vertical) in KERNEL.iter().enumerate() {
+        for (column, horizontal) in KERNEL.iter().enumerate() {
+            let (tap_x, tap_y) = (x + (column as i32 - 2) * step, y + (row as i32 - 2) * step);
+            if tap_x < 0 || tap_y < 0 || tap_x >= width as i32 || tap_y >= height {
+                continue;
+            }
+            let tap = tap_y as usize * width + tap_x as usize;
+            let other = features[tap];
+            let depth_tolerance = DEPTH_SIGMA * step as f32 * feature.depth.max(other.depth);
+            let weight = vertical * horizontal
+                * edge_stop(color[tap] - center, color_sigma)
+                * edge_stop(other.albedo - feature.albedo, ALBEDO_SIGMA)
+                * edge_stop(other.normal - feature.normal, NORMAL_SIGMA)
+                * (-(other.depth - feature.depth).abs() / depth_tolerance.max(1e-6)).exp();
+            sum += color[tap] * weight;
+            total += weight;
+        }
+    }
+    // The center tap always weighs in, so the total is never zero
+    sum / total
+}
+
+/// Gaussian falloff of a difference between two pixels
+fn edge_stop(difference: Vec3, sigma: f32) -> f32 {
+    (-difference.length_squared() / (sigma * sigma).max(1e-12)).exp()
+}
+
+#[cfg(feature = "oidn")]
+thread_local! {
+    /// OIDN device of the thread denoising, created on its first frame
+    static OIDN_DEVICE: oidn::Device = oidn::Device::new();
+}
+
+/// Denoise with OIDN's ray tracing filter, with albedo and normals as auxiliary images
+#[cfg(feature = "oidn")]
+fn oidn_denoise(color: &[Vec3], features: &[PixelFeatures], width: usize) -> Result<Vec<Vec3>, String> {
+    let flatten = |values: &mut dyn Iterator<Item = Vec3>| values.flat_map(|value| value.to_array()).collect::<Vec<f32>>();
+    let input = flatten(&mut color.iter().copied());
+    let albedo = flatten(&mut features.iter().map(|feature| feature.albedo));
+    let normal = flatten(&mut features.iter().map(|feature| feature.normal));
+    let mut output = vec![0.0; input.len()];
+    OIDN_DEVICE.with(|device| {
+        oidn::RayTracing::new(device)
+            .hdr(true)
+            .albedo_normal(&albedo, &normal)
+            .image_dimensions(width, color.len() / width.max(1))
+            .filter(&input, &mut output)
+            .map_err(|error| format!("{:?}", error))?;
+        device.get_error().map_err(|(_, message)| message)
+    })?;
+    Ok(output.chunks_exact(3).map(Vec3::from_slice).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn noise_is_smoothed_within_surfaces_but_not_across_edges() {
+        // Two walls meeting down the middle of the frame, lit 1 and 3, with noise
+        let (width, height) = (32, 16);
+        let mut color = Vec::new();
+        let mut features = Vec::new();
+        for index in 0..width * height {
+            let left = index % width < width / 2;
+            let noise = ((index * 7919) % 13) as f32 / 13.0 - 0.5;
+            color.push(Vec3::splat(if left { 1.0 } else { 3.0 } + noise));
+            let normal = if left { Vec3::X } else { Vec3::Z };
+            features.push(PixelFeatures { albedo: Vec3::splat(0.5), normal, depth: 5.0 });
+        }
+        
+        let denoised = a_trous(&color, &features, width, 1.0);
+        let spread = |image: &[Vec3]| {
+            let left: Vec<f32> = (0..width * height).filter(|index| index % width < width / 2).map(|index| image[index].x).collect();
+            let mean = left.iter().sum::<f32>() / left.len() as f32;
+            (mean, left.iter().map(|value| (value - mean).powi(2)).sum::<f32>() / left.len() as f32)
+        };
+        let ((_, noisy), (mean, smoothed)) = (spread(&color), spread(&denoised));
+        assert!(smoothed < noisy * 0.1);
+        assert!((mean - 1.0).abs() < 0.1);
+        // The brighter wall doesn't bleed over the edge
+        assert!(denoised[width / 2 - 1].x < 1.5 && denoised[width / 2].x > 2.5);
+        
+        // Mismatched buffers are passed through
+        assert_eq!(a_trous(&color, &features[1..], width, 1.0), color);
+    }
+}
@@ -0,0 +1,250 @@
+//! UsdGeomModelAPI draw mode stand-ins
+//!
+//! A model prim with `model:applyDrawMode` set and a computed drawMode of
+//! origin, bounds or cards is drawn as a stand-in instead of its geometry:
+//! axes at its origin, its bounding box, or texture cards on or through that
+//! box. Stand-ins are built in the model's space from its untransformed
+//! bounds and drawn with its local-to-world transform.
+
+use glam::Vec3;
+use crate::core::bounds::BoundingBox;
+use crate::core::usd_engine::USDDrawMode;
+
+/// Values of the drawMode attribute other than "inherited"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrawMode {
+    Default,
+    Origin,
+    Bounds,
+    Cards,
+}
+
+impl DrawMode {
+    pub const ALL: [DrawMode; 4] = [DrawMode::Default, DrawMode::Origin, DrawMode::Bounds, DrawMode::Cards];
+    
+    /// Token authored on model:drawMode
+    pub fn token(&self) -> &'static str {
+        match self {
+            DrawMode::Default => "default",
+            DrawMode::Origin => "origin",
+            DrawMode::Bounds => "bounds",
+            DrawMode::Cards => "cards",
+        }
+    }
+    
+    pub fn label(&self) -> &'static str {
+        match self {
+            DrawMode::Default => "Default",
+            DrawMode::Origin => "Origin",
+            DrawMode::Bounds => "Bounds",
+            DrawMode::Cards => "Cards",
+        }
+    }
+    
+    pub fn from_token(token: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.token() == token)
+    }
+    
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.label() == label)
+    }
+}
+
+/// Layout of the cards of the "cards" draw mode, from model:cardGeometry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CardGeometry {
+    /// One card per axis through the center of the bounds
+    Cross,
+    /// Cards on the faces of the bounds
+    Box,
+    /// Cards placed by their textures' worldtoscreen metadata
+    FromTexture,
+}
+
+impl CardGeometry {
+    pub fn from_token(token: &str) -> Option<Self> {
+        match token {
+            "cross" => Some(CardGeometry::Cross),
+            "box" => Some(CardGeometry::Box),
+            "fromTexture" => Some(CardGeometry::FromTexture),
+            _ => None,
+        }
+    }
+}
+
+/// Triangles of a stand-in sharing one texture, or the draw mode color without one
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StandIn {
+    pub positions: Vec<f32>,
+    pub normals: Vec<f32>,
+    pub uvs: Vec<f32>,
+    pub indices: Vec<u32>,
+    /// Card texture asset path
+    pub texture: Option<String>,
+}
+
+impl StandIn {
+    fn push_box(&mut self, bounds: BoundingBox) {
+        let base = (self.positions.len() / 3) as u32;
+        let (positions, normals, indices) = bounds.box_geometry();
+        self.uvs.resize(self.uvs.len() + positions.len() / 3 * 2, 0.0);
+        self.positions.extend(positions);
+        self.normals.extend(normals);
+        self.indices.extend(indices.into_iter().map(|index| base + index));
+    }
+    
+    /// Quad spanning `right` and `up` both ways from `center`, facing along right x up
+    ///
+    /// The image's top edge is along +up and u runs along +right, or
+    /// against it when `mirrored`.
+    fn push_quad(&mut self, center: Vec3, right: Vec3, up: Vec3, mirrored: bool) {
+        let base = (self.positions.len() / 3) as u32;
+        let normal = right.cross(up).normalize();
+        for (x, y) in [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)] {
+            self.positions.extend((center + right * x + up * y).to_array());
+            self.normals.extend(normal.to_array());
+            let u = (x + 1.0) * 0.5;
+            self.uvs.extend([if mirrored { 1.0 - u } else { u }, (1.0 - y) * 0.5]);
+        }
+        self.indices.extend([base, base + 1, base + 2, base, base + 2, base + 3]);
+    }
+}
+
+/// Whether a prim path is a model or lies under it
+pub fn is_within(model_path: &str, prim_path: &str) -> bool {
+    prim_path.strip_prefix(model_path)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/') || model_path == "/")
+}
+
+/// Geometry replacing a model's, in the model's space; empty for the default draw mode
+pub fn stand_ins(draw_mode: &USDDrawMode) -> Vec<StandIn> {
+    let bounds = draw_mode.bounds;
+    match DrawMode::from_token(&draw_mode.mode) {
+        None | Some(DrawMode::Default) => Vec::new(),
+        Some(DrawMode::Origin) => {
+            let length = if bounds.is_empty() { 1.0 } else { bounds.size().max_element().max(1e-3) };
+            let thickness = Vec3::splat(length * 0.01);
+            let mut axes = StandIn::default();
+            for axis in [Vec3::X, Vec3::Y, Vec3::Z] {
+                axes.push_box(BoundingBox::new(-thickness, axis * length + thickness));
+            }
+            vec![axes]
+        }
+        Some(DrawMode::Bounds) if bounds.is_empty() => Vec::new(),
+        Some(DrawMode::Bounds) => {
+            let mut box_stand_in = StandIn::default();
+            box_stand_in.push_box(bounds);
+            vec![box_stand_in]
+        }
+        Some(DrawMode::Cards) => cards(draw_mode),
+    }
+}
+
+/// Cards in XPos, YPos, ZPos, XNeg, YNeg, ZNeg order, as the card texture attributes
+///
+/// Without any card texture every card is drawn in the draw mode color;
+/// otherwise only cards with a texture are drawn, a missing one taking the
+/// opposite face's texture mirrored.
+fn cards(draw_mode: &USDDrawMode) -> Vec<StandIn> {
+    let bounds = draw_mode.bounds;
+    if bounds.is_empty() {
+        return Vec::new();
+    }
+    // fromTexture needs the textures' worldtoscreen metadata, so it is drawn as a box
+    let on_box = CardGeometry::from_token(&draw_mode.card_geometry) != Some(CardGeometry::Cross);
+    let textured = draw_mode.card_textures.iter().any(Option::is_some);
+    let half = bounds.size() * 0.5;
+    
+    let mut cards = Vec::new();
+    let mut untextured = StandIn::default();
+    for face in 0..6 {
+        let (texture, mirrored) = match (&draw_mode.card_textures[face], &draw_mode.card_textures[(face + 3) % 6]) {
+            (Some(own), _) => (Some(own.clone()), false),
+            (None, Some(opposite)) => (Some(opposite.clone()), true),
+            (None, None) => (None, false),
+        };
+        if textured && texture.is_none() {
+            continue;
+        }
+        // Right and up as seen looking at the card from outside
+        let (normal, right, up) = match face {
+            0 => (Vec3::X, Vec3::NEG_Z, Vec3::Y),
+            1 => (Vec3::Y, Vec3::X, Vec3::NEG_Z),
+            2 => (Vec3::Z, Vec3::X, Vec3::Y),
+            3 => (Vec3::NEG_X, Vec3::Z, Vec3::Y),
+            4 => (Vec3::NEG_Y, Vec3::X, Vec3::Z),
+            _ => (Vec3::NEG_Z, Vec3::NEG_X, Vec3::Y),
+        };
+        let (half_right, half_up) = (right.abs().dot(half), up.abs().dot(half));
+        if half_right <= 0.0 || half_up <= 0.0 {
+            continue;
+        }
+        let offset = if on_box { normal * normal.abs().dot(half) } else { Vec3::ZERO };
+        let card = match &texture {
+            Some(_) => {
+                cards.push(StandIn { texture, ..Default::default() });
+                cards.last_mut().unwrap()
+            }
+            None => &mut untextured,
+        };
+        card.push_quad(bounds.center() + offset, right * half_right, up * half_up, mirrored);
+    }
+    if !untextured.indices.is_empty() {
+        cards.push(untextured);
+    }
+    cards
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::Mat4;
+    
+    fn model(mode: &str, card_geometry: &str, card_textures: [Option<String>; 6]) -> USDDrawMode {
+        USDDrawMode {
+            prim_path: "/World/tree".to_string(),
+            mode: mode.to_string(),
+            color: [0.18, 0.18, 0.18],
+            card_geometry: card_geometry.to_string(),
+            card_textures,
+            bounds: BoundingBox::new(Vec3::new(-1.0, 0.0, -1.0), Vec3::new(1.0, 4.0, 1.0)),
+            transform: Mat4::IDENTITY,
+        }
+    }
+    
+    /// Every triangle winds towards its vertex normal
+    fn faces_out(stand_in: &StandIn) -> bool {
+        let point = |index: u32| Vec3::from_slice(&stand_in.positions[index as usize * 3..]);
+        stand_in.indices.chunks_exact(3).all(|triangle| {
+            let [a, b, c] = [point(triangle[0]), point(triangle[1]), point(triangle[2])];
+            let normal = Vec3::from_slice(&stand_in.normals[triangle[0] as usize * 3..]);
+            (b - a).cross(c - a).dot(normal) > 0.0
+        })
+    }
+    
+    #[test]
+    fn models_are_replaced_by_stand_ins() {
+        assert!(is_within("/World/tree", "/World/tree/leaves") && is_within("/World/tree", "/World/tree"));
+        assert!(!is_within("/World/tree", "/World/trees"));
+        assert!(stand_ins(&model("default", "cross", Default::default())).is_empty());
+        
+        let bounds = stand_ins(&model("bounds", "cross", Default::default()));
+        assert_eq!(bounds.len(), 1);
+        assert_eq!(BoundingBox::from_positions(&bounds[0].positions), model("bounds", "", Default::default()).bounds);
+        assert_eq!(stand_ins(&model("origin", "cross", Default::default()))[0].indices.len(), 108);
+        
+        // Untextured crosses draw all six cards through the center in one color
+        let cross = stand_ins(&model("cards", "cross", Default::default()));
+        assert_eq!((cross.len(), cross[0].indices.len(), cross[0].texture.clone()), (1, 36, None));
+        assert!(faces_out(&cross[0]));
+        assert_eq!(BoundingBox::from_positions(&cross[0].positions).center(), Vec3::new(0.0, 2.0, 0.0));
+        
+        // Box cards sit on the faces, and a missing face takes the opposite texture mirrored
+        let front = Some("front.png".to_string());
+        let cards = stand_ins(&model("cards", "box", [None, None, front.clone(), None, None, None]));
+        assert_eq!(cards.len(), 2);
+        assert!(cards.iter().all(|card| card.texture == front && card.indices.len() == 6 && faces_out(card)));
+        assert_eq!((cards[0].positions[2], cards[0].uvs[0]), (1.0, 0.0));
+        assert_eq!((cards[1].positions[2], cards[1].uvs[0]), (-1.0, 1.0));
+    }
+}
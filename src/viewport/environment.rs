@@ -0,0 +1,122 @@
+//! Dome light environment for viewport shading
+//!
+//! Dome lights light the viewport through a sky/ground hemisphere ambient
+//! term instead of a direction. A latitude-longitude texture is reduced to
+//! the solid-angle weighted average radiance of its upper and lower halves,
+//! so a sky tints upward-facing surfaces and the ground bounce the undersides.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::SystemTime;
+use glam::Vec3;
+use image::Rgb32FImage;
+use once_cell::sync::Lazy;
+
+/// Ambient sky and ground radiance
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Environment {
+    pub sky: Vec3,
+    pub ground: Vec3,
+}
+
+impl Default for Environment {
+    /// Flat ambient used when the stage has no dome light
+    fn default() -> Self {
+        Self { sky: Vec3::splat(0.2), ground: Vec3::splat(0.2) }
+    }
+}
+
+impl Environment {
+    /// Environment of one dome light; an untextured dome is uniformly its color
+    pub fn dome(radiance: Vec3, texture: Option<Environment>) -> Self {
+        let texture = texture.unwrap_or(Environment { sky: Vec3::ONE, ground: Vec3::ONE });
+        Self { sky: radiance * texture.sky, ground: radiance * texture.ground }
+    }
+}
+
+/// Domes add up on stages with several of them
+impl std::ops::Add for Environment {
+    type Output = Environment;
+    
+    fn add(self, other: Environment) -> Environment {
+        Environment { sky: self.sky + other.sky, ground: self.ground + other.ground }
+    }
+}
+
+/// Average radiance of the upper and lower halves of a latlong image
+pub fn hemisphere_averages(image: &Rgb32FImage) -> Environment {
+    let (width, height) = image.dimensions();
+    let mut sums = [(Vec3::ZERO, 0.0_f32); 2];
+    for row in 0..height {
+        // Rows are weighted by their solid angle, the cosine of their latitude
+        let latitude = std::f32::consts::PI * (0.5 - (row as f32 + 0.5) / height as f32);
+        let weight = latitude.cos();
+        let (sum, total) = &mut sums[usize::from(latitude < 0.0)];
+        for column in 0..width {
+            *sum += Vec3::from(image.get_pixel(column, row).0) * weight;
+            *total += weight;
+        }
+    }
+    let [sky, ground] = sums.map(|(sum, total)| if total > 0.0 { sum / total } else { Vec3::ZERO });
+    Environment { sky, ground }
+}
+
+/// Reduced latlong textures by path, with the modification time they were read at
+type LatlongCache = HashMap<String, (Option<SystemTime>, Environment)>;
+
+static LATLONG_CACHE: Lazy<Mutex<LatlongCache>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Hemisphere averages of a latlong texture file
+///
+/// 8-bit images are read as sRGB and float images (EXR, HDR) as linear.
+/// Results are cached until the file changes, since baked skies are rewritten
+/// in place.
+pub fn load_latlong(path: &str) -> Result<Environment, String> {
+    let modified = std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok();
+    if let Some((cached_modified, environment)) = LATLONG_CACHE.lock().unwrap().get(path) {
+        if *cached_modified == modified {
+            return Ok(*environment);
+        }
+    }
+    
+    let image = image::open(path).map_err(|e| format!("Failed to load dome texture '{}': {}", path, e))?;
+    let float = matches!(image.color(), image::ColorType::Rgb32F | image::ColorType::Rgba32F);
+    let mut pixels = image.into_rgb32f();
+    if !float {
+        for channel in pixels.iter_mut() {
+            *channel = srgb_to_linear(*channel);
+        }
+    }
+    let environment = hemisphere_averages(&pixels);
+    LATLONG_CACHE.lock().unwrap().insert(path.to_string(), (modified, environment));
+    Ok(environment)
+}
+
+fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgb;
+    
+    #[test]
+    fn latlong_halves_average_to_sky_and_ground() {
+        let image = Rgb32FImage::from_fn(8, 4, |_, row| match row {
+            0 | 1 => Rgb([0.2, 0.4, 1.0]),
+            _ => Rgb([0.1, 0.1, 0.05]),
+        });
+        let environment = hemisphere_averages(&image);
+        assert!(environment.sky.distance(Vec3::new(0.2, 0.4, 1.0)) < 1e-6);
+        assert!(environment.ground.distance(Vec3::new(0.1, 0.1, 0.05)) < 1e-6);
+        
+        let dome = Environment::dome(Vec3::splat(2.0), Some(environment));
+        assert!(dome.sky.distance(Vec3::new(0.4, 0.8, 2.0)) < 1e-6);
+        assert!((Environment::dome(Vec3::ONE, None) + dome).ground.distance(Vec3::new(1.2, 1.2, 1.1)) < 1e-6);
+    }
+}
@@ -0,0 +1,347 @@
+//! Transform manipulator gizmos
+//!
+//! A gizmo sits at the selected prim's origin with a handle per axis:
+//! arrows to translate, rings to rotate and box-tipped arrows to scale.
+//! Handles are hit by casting the cursor ray against them, and dragging
+//! maps the ray back onto the grabbed axis or ring plane to give new
+//! translate, rotateXYZ and scale op values, snapped as `snapping` sets.
+
+use glam::{EulerRot, Mat4, Quat, Vec3};
+use crate::core::usd_engine::USDXformOps;
+use super::projection::{xform_ops_to_mat4, ProjectionCamera};
+use super::snapping::{snap, SnapSettings};
+
+/// Handle length as a fraction of the distance to the camera, keeping the gizmo a steady size on screen
+const SCREEN_SIZE: f32 = 0.15;
+
+/// Distance a ray may pass from a handle and still grab it, as a fraction of the handle length
+const PICK_TOLERANCE: f32 = 0.08;
+
+/// Segments of a rotation ring
+const RING_SEGMENTS: usize = 48;
+
+/// Mesh and material id prefix of gizmo handles, followed by the axis index
+pub const GIZMO_PREFIX: &str = "gizmo:";
+
+/// Which ops a gizmo edits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GizmoMode {
+    Translate,
+    Rotate,
+    Scale,
+}
+
+impl GizmoMode {
+    pub const ALL: [GizmoMode; 3] = [GizmoMode::Translate, GizmoMode::Rotate, GizmoMode::Scale];
+    
+    pub fn label(&self) -> &'static str {
+        match self {
+            GizmoMode::Translate => "Translate",
+            GizmoMode::Rotate => "Rotate",
+            GizmoMode::Scale => "Scale",
+        }
+    }
+    
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.label() == label)
+    }
+}
+
+/// Axes gizmo handles follow
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GizmoSpace {
+    #[default]
+    World,
+    /// The prim's own axes
+    Local,
+}
+
+impl GizmoSpace {
+    pub const ALL: [GizmoSpace; 2] = [GizmoSpace::World, GizmoSpace::Local];
+    
+    pub fn label(&self) -> &'static str {
+        match self {
+            GizmoSpace::World => "World",
+            GizmoSpace::Local => "Local",
+        }
+    }
+    
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|space| space.label() == label)
+    }
+}
+
+/// World transform of a prim from its ops
+pub fn world_transform(ops: &USDXformOps) -> Mat4 {
+    ops.parent_to_world * xform_ops_to_mat4(ops.translate, ops.rotate, ops.scale)
+}
+
+/// World-space ray from a camera through pixel `(x, y)` of a `width` x `height` view
+pub fn pick_ray(camera: &ProjectionCamera, x: f32, y: f32, width: f32, height: f32) -> (Vec3, Vec3) {
+    let ndc_x = x / width.max(1.0) * 2.0 - 1.0;
+    let ndc_y = 1.0 - y / height.max(1.0) * 2.0;
+    let half_width = camera.horizontal_aperture / (2.0 * camera.focal_length);
+    let half_height = camera.vertical_aperture / (2.0 * camera.focal_length);
+    let direction = camera.world.transform_vector3(Vec3::new(ndc_x * half_width, ndc_y * half_height, -1.0)).normalize();
+    (camera.world.transform_point3(Vec3::ZERO), direction)
+}
+
+/// Triangles of gizmo handle geometry
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GizmoMesh {
+    pub positions: Vec<f32>,
+    pub normals: Vec<f32>,
+    pub indices: Vec<u32>,
+}
+
+impl GizmoMesh {
+    /// Add a box around `center` with half-size vectors along its three edges
+    fn push_box(&mut self, center: Vec3, half: [Vec3; 3]) {
+        for (axis, sign) in (0..3).flat_map(|axis| [(axis, 1.0), (axis, -1.0)]) {
+            let normal = half[axis] * sign;
+            let (u, v) = (half[(axis + 1) % 3], half[(axis + 2) % 3]);
+            // Keep faces wound counter-clockwise seen from outside
+            let (u, v) = if u.cross(v).dot(normal) < 0.0 { (v, u) } else { (u, v) };
+            let base = (self.positions.len() / 3) as u32;
+            for corner in [-u - v, u - v, u + v, v - u] {
+                self.positions.extend((center + normal + corner).to_array());
+                self.normals.extend(normal.normalize_or_zero().to_array());
+            }
+            self.indices.extend([base, base + 1, base + 2, base, base + 2, base + 3]);
+        }
+    }
+}
+
+/// Placement of a gizmo: its origin, unit handle axes and handle length
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GizmoFrame {
+    pub origin: Vec3,
+    pub axes: [Vec3; 3],
+    pub size: f32,
+}
+
+impl GizmoFrame {
+    /// Gizmo at a prim's origin seen from `eye`
+    ///
+    /// Scale handles follow the prim's axes in either space, as scale ops do.
+    pub fn new(ops: &USDXformOps, mode: GizmoMode, space: GizmoSpace, eye: Vec3) -> Self {
+        let world = world_transform(ops);
+        let origin = world.transform_point3(Vec3::ZERO);
+        let axes = match (mode, space) {
+            (GizmoMode::Translate | GizmoMode::Rotate, GizmoSpace::World) => [Vec3::X, Vec3::Y, Vec3::Z],
+            _ => [Vec3::X, Vec3::Y, Vec3::Z].map(|axis| world.transform_vector3(axis).try_normalize().unwrap_or(axis)),
+        };
+        Self { origin, axes, size: (eye - origin).length().max(1e-3) * SCREEN_SIZE }
+    }
+    
+    /// Parameter along an axis of the point nearest a ray, and the distance between them
+    fn nearest_on_axis(&self, axis: usize, origin: Vec3, direction: Vec3) -> Option<(f32, f32)> {
+        let u = self.axes[axis];
+        let offset = self.origin - origin;
+        let b = u.dot(direction);
+        let denominator = 1.0 - b * b;
+        if denominator < 1e-6 {
+            return None;
+        }
+        let (d, e) = (u.dot(offset), direction.dot(offset));
+        let t = (b * e - d) / denominator;
+        let s = (e - b * d) / denominator;
+        if s < 0.0 {
+            return None;
+        }
+        let gap = (self.origin + u * t - (origin + direction * s)).length();
+        Some((t, gap))
+    }
+    
+    /// Angle about an axis of the point where a ray crosses the plane through the origin normal to it, and its distance from the origin
+    fn angle_about_axis(&self, axis: usize, origin: Vec3, direction: Vec3) -> Option<(f32, f32)> {
+        let normal = self.axes[axis];
+        let facing = direction.dot(normal);
+        if facing.abs() < 1e-6 {
+            return None;
+        }
+        let s = (self.origin - origin).dot(normal) / facing;
+        if s < 0.0 {
+            return None;
+        }
+        let point = origin + direction * s - self.origin;
+        let (u, v) = (self.axes[(axis + 1) % 3], self.axes[(axis + 2) % 3]);
+        Some((point.dot(v).atan2(point.dot(u)), point.length()))
+    }
+    
+    /// Handle a ray grabs, the one it passes closest when it passes several
+    pub fn hit(&self, mode: GizmoMode, origin: Vec3, direction: Vec3) -> Option<usize> {
+        let tolerance = self.size * PICK_TOLERANCE;
+        (0..3)
+            .filter_map(|axis| {
+                let gap = match mode {
+                    GizmoMode::Translate | GizmoMode::Scale => {
+                        let (t, gap) = self.nearest_on_axis(axis, origin, direction)?;
+                        (0.0..=self.size * 1.1).contains(&t).then_some(gap)?
+                    }
+                    GizmoMode::Rotate => {
+                        let (_, radius) = self.angle_about_axis(axis, origin, direction)?;
+                        (radius - self.size).abs()
+                    }
+                };
+                (gap <= tolerance).then_some((axis, gap))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(axis, _)| axis)
+    }
+    
+    /// Geometry of each handle, by axis
+    pub fn handles(&self, mode: GizmoMode) -> Vec<(usize, GizmoMesh)> {
+        let thickness = self.size * 0.02;
+        (0..3)
+            .map(|axis| {
+                let (along, side, up) = (self.axes[axis], self.axes[(axis + 1) % 3], self.axes[(axis + 2) % 3]);
+                let mut mesh = GizmoMesh::default();
+                match mode {
+                    GizmoMode::Translate | GizmoMode::Scale => {
+                        let shaft = self.size * 0.45;
+                        mesh.push_box(self.origin + along * shaft, [along * shaft, side * thickness, up * thickness]);
+                        let tip = match mode {
+                            GizmoMode::Translate => [along * thickness * 4.0, side * thickness * 2.5, up * thickness * 2.5],
+                            _ => [along * thickness * 3.0, side * thickness * 3.0, up * thickness * 3.0],
+                        };
+                        mesh.push_box(self.origin + along * (self.size - tip[0].length()), tip);
+                    }
+                    GizmoMode::Rotate => {
+                        let step = std::f32::consts::TAU / RING_SEGMENTS as f32;
+                        let half_chord = self.size * (step / 2.0).sin();
+                        for segment in 0..RING_SEGMENTS {
+                            let angle = (segment as f32 + 0.5) * step;
+                            let (sin, cos) = angle.sin_cos();
+                            let radial = side * cos + up * sin;
+                            let tangent = up * cos - side * sin;
+                            mesh.push_box(self.origin + radial * self.size, [tangent * half_chord, radial * thickness, along * thickness]);
+                        }
+                    }
+                }
+                (axis, mesh)
+            })
+            .collect()
+    }
+}
+
+/// A drag of a gizmo handle
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GizmoDrag {
+    pub mode: GizmoMode,
+    pub axis: usize,
+    frame: GizmoFrame,
+    /// Ops when the drag started
+    start: USDXformOps,
+    /// Axis parameter or ring angle where the handle was grabbed
+    grab: f32,
+}
+
+impl GizmoDrag {
+    /// Start dragging the handle under a ray, if any
+    pub fn begin(frame: GizmoFrame, mode: GizmoMode, ops: USDXformOps, origin: Vec3, direction: Vec3) -> Option<Self> {
+        let axis = frame.hit(mode, origin, direction)?;
+        let grab = match mode {
+            GizmoMode::Translate => frame.nearest_on_axis(axis, origin, direction)?.0,
+            GizmoMode::Rotate => frame.angle_about_axis(axis, origin, direction)?.0,
+            GizmoMode::Scale => frame.nearest_on_axis(axis, origin, direction)?.0.max(frame.size * 0.05),
+        };
+        Some(Self { mode, axis, frame, start: ops, grab })
+    }
+    
+    /// Ops with the drag applied for the cursor ray, None when the ray misses the axis or plane
+    ///
+    /// A translate drag with a `vertex` to snap to moves the prim's origin to
+    /// the point of the dragged axis nearest it, bypassing grid snapping.
+    pub fn update(&self, origin: Vec3, direction: Vec3, snapping: &SnapSettings, vertex: Option<Vec3>) -> Option<USDXformOps> {
+        let mut ops = self.start;
+        let axis = self.frame.axes[self.axis];
+        let to_parent = ops.parent_to_world.inverse();
+        match self.mode {
+            GizmoMode::Translate => {
+                let distance = match vertex {
+                    Some(vertex) => (vertex - self.frame.origin).dot(axis),
+                    None => self.frame.nearest_on_axis(self.axis, origin, direction)?.0 - self.grab,
+                };
+                ops.translate += to_parent.transform_vector3(axis * distance);
+                // Round only the values the drag moved, keeping the others as authored
+                if vertex.is_none() {
+                    for index in 0..3 {
+                        if (ops.translate[index] - self.start.translate[index]).abs() > 1e-6 {
+                            ops.translate[index] = snap(ops.translate[index], snapping.grid);
+                        }
+                    }
+                }
+            }
+            GizmoMode::Rotate => {
+                let (angle, _) = self.frame.angle_about_axis(self.axis, origin, direction)?;
+                let degrees = snap((angle - self.grab).to_degrees(), snapping.angle);
+                let parent_axis = to_parent.transform_vector3(axis).try_normalize()?;
+                let rotation = Quat::from_euler(EulerRot::ZYX, ops.rotate.z.to_radians(), ops.rotate.y.to_radians(), ops.rotate.x.to_radians());
+                let (z, y, x) = (Quat::from_axis_angle(parent_axis, degrees.to_radians()) * rotation).to_euler(EulerRot::ZYX);
+                ops.rotate = Vec3::new(x.to_degrees(), y.to_degrees(), z.to_degrees());
+            }
+            GizmoMode::Scale => {
+                let (t, _) = self.frame.nearest_on_axis(self.axis, origin, direction)?;
+                let factor = snap(t / self.grab, snapping.scale).max(1e-3);
+                ops.scale[self.axis] *= factor;
+            }
+        }
+        Some(ops)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn dragging_handles_edits_the_grabbed_axis() {
+        let ops = USDXformOps {
+            translate: Vec3::new(1.0, 0.0, 0.0),
+            rotate: Vec3::ZERO,
+            scale: Vec3::ONE,
+            parent_to_world: Mat4::from_translation(Vec3::new(0.0, 0.0, -10.0)),
+        };
+        // Seen from 10 units up the z axis, the gizmo's handles are 1.5 long
+        let eye = Vec3::new(1.0, 0.0, 0.0);
+        let frame = GizmoFrame::new(&ops, GizmoMode::Translate, GizmoSpace::World, eye);
+        assert_eq!(frame.origin, Vec3::new(1.0, 0.0, -10.0));
+        assert!((frame.size - 1.5).abs() < 1e-5);
+        let toward = |target: Vec3| (eye, (target - eye).normalize());
+        
+        // Grab the x arrow halfway and drag it 2.3 further, snapping to a 0.5 grid
+        let (origin, direction) = toward(Vec3::new(1.75, 0.0, -10.0));
+        assert_eq!(frame.hit(GizmoMode::Translate, origin, direction), Some(0));
+        let (origin, direction) = toward(Vec3::new(1.0, -1.0, -10.0));
+        assert_eq!(frame.hit(GizmoMode::Translate, origin, direction), None);
+        let (origin, direction) = toward(Vec3::new(1.75, 0.0, -10.0));
+        let drag = GizmoDrag::begin(frame, GizmoMode::Translate, ops, origin, direction).unwrap();
+        let (origin, direction) = toward(Vec3::new(4.05, 0.0, -10.0));
+        let moved = drag.update(origin, direction, &SnapSettings { grid: 0.5, ..Default::default() }, None).unwrap();
+        assert!((moved.translate - Vec3::new(3.5, 0.0, 0.0)).length() < 1e-4);
+        // A vertex off the axis pulls the origin level with it
+        let snapped = drag.update(origin, direction, &SnapSettings::default(), Some(Vec3::new(2.25, 3.0, -12.0))).unwrap();
+        assert!((snapped.translate - Vec3::new(2.25, 0.0, 0.0)).length() < 1e-4);
+        
+        // A quarter turn about z from the ring's +x side to its +y side
+        let frame = GizmoFrame::new(&ops, GizmoMode::Rotate, GizmoSpace::World, eye);
+        let (origin, direction) = toward(Vec3::new(2.5, 0.0, -10.0));
+        let drag = GizmoDrag::begin(frame, GizmoMode::Rotate, ops, origin, direction).unwrap();
+        assert_eq!(drag.axis, 2);
+        let (origin, direction) = toward(Vec3::new(1.0, 1.4, -10.0));
+        let turned = drag.update(origin, direction, &SnapSettings { angle: 15.0, ..Default::default() }, None).unwrap();
+        assert!((turned.rotate - Vec3::new(0.0, 0.0, 90.0)).length() < 1e-3);
+        
+        // Doubling the y arrow's length doubles the y scale
+        let frame = GizmoFrame::new(&ops, GizmoMode::Scale, GizmoSpace::World, eye);
+        let (origin, direction) = toward(Vec3::new(1.0, 0.5, -10.0));
+        let drag = GizmoDrag::begin(frame, GizmoMode::Scale, ops, origin, direction).unwrap();
+        assert_eq!(drag.axis, 1);
+        let (origin, direction) = toward(Vec3::new(1.0, 1.0, -10.0));
+        let scaled = drag.update(origin, direction, &SnapSettings::default(), None).unwrap();
+        assert!((scaled.scale - Vec3::new(1.0, 2.0, 1.0)).length() < 1e-4);
+        
+        assert_eq!(frame.handles(GizmoMode::Rotate)[0].1.indices.len(), RING_SEGMENTS * 36);
+    }
+}
@@ -0,0 +1,83 @@
+//! Selection highlight outlines
+//!
+//! Selected meshes are outlined with an inverted hull: a copy of the mesh
+//! pushed out along its normals with its winding and normals flipped, so
+//! with back faces culled only the rim around the silhouette shows. The
+//! hull is drawn in the palette's selection color, flat and emissive.
+
+use glam::Vec3;
+use super::draw_mode::is_within;
+
+/// Material id shared by selection outlines
+pub const HIGHLIGHT_MATERIAL: &str = "usd_selection_highlight";
+
+/// Mesh id suffix of a mesh's outline
+pub const HIGHLIGHT_SUFFIX: &str = ":highlight";
+
+/// Outline width as a fraction of the mesh's bounding box diagonal
+pub const OUTLINE_WIDTH: f32 = 0.01;
+
+/// Inverted hull geometry outlining a mesh
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutlineShell {
+    pub positions: Vec<f32>,
+    pub normals: Vec<f32>,
+    pub indices: Vec<u32>,
+}
+
+/// Whether a scene mesh belongs to a selected prim
+///
+/// Mesh ids start with their prim path, optionally followed by `:` and a
+/// suffix, so draw mode stand-ins and subsets highlight with their prim.
+pub fn is_selected(selection: &[String], mesh_id: &str) -> bool {
+    let prim_path = mesh_id.split(':').next().unwrap_or_default();
+    prim_path.starts_with('/') && selection.iter().any(|selected| is_within(selected, prim_path))
+}
+
+/// Inverted hull of a mesh, `width` of its bounding box diagonal thick
+///
+/// Meshes without a normal per vertex have no outline.
+pub fn outline_shell(positions: &[f32], normals: &[f32], indices: &[u32], width: f32) -> Option<OutlineShell> {
+    if positions.is_empty() || normals.len() != positions.len() || indices.is_empty() {
+        return None;
+    }
+    let (min, max) = positions.chunks_exact(3).map(Vec3::from_slice)
+        .fold((Vec3::INFINITY, Vec3::NEG_INFINITY), |(min, max), point| (min.min(point), max.max(point)));
+    let offset = (max - min).length() * width;
+    let (shell_positions, shell_normals): (Vec<[f32; 3]>, Vec<[f32; 3]>) = positions.chunks_exact(3).map(Vec3::from_slice)
+        .zip(normals.chunks_exact(3).map(Vec3::from_slice))
+        .map(|(point, normal)| {
+            let normal = normal.normalize_or_zero();
+            ((point + normal * offset).to_array(), (-normal).to_array())
+        })
+        .unzip();
+    Some(OutlineShell {
+        positions: shell_positions.concat(),
+        normals: shell_normals.concat(),
+        indices: indices.chunks_exact(3).flat_map(|triangle| [triangle[0], triangle[2], triangle[1]]).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn shells_grow_along_normals_with_flipped_winding() {
+        // A unit quad facing +z
+        let positions = [0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0, 0.0];
+        let normals = [0.0, 0.0, 1.0].repeat(4);
+        let shell = outline_shell(&positions, &normals, &[0, 1, 2, 0, 2, 3], 0.5).unwrap();
+        let offset = 2.0f32.sqrt() * 0.5;
+        assert!((shell.positions[2] - offset).abs() < 1e-6);
+        assert_eq!(&shell.normals[..3], &[0.0, 0.0, -1.0]);
+        assert_eq!(shell.indices, [0, 2, 1, 0, 3, 2]);
+        assert!(outline_shell(&positions, &[], &[0, 1, 2], 0.5).is_none());
+        
+        let selection = ["/World/Kitchen".to_string()];
+        assert!(is_selected(&selection, "/World/Kitchen/Table"));
+        assert!(is_selected(&selection, "/World/Kitchen:drawMode:0"));
+        assert!(!is_selected(&selection, "/World/KitchenSink"));
+        assert!(!is_selected(&selection, "ground"));
+    }
+}
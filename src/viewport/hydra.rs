@@ -0,0 +1,777 @@
+//! Hydra rendering backend
+//!
+//! Instead of the wgpu renderer, the viewport can hand the stage to
+//! UsdImagingGL, the Hydra front end usdview draws with. The chosen render
+//! delegate (Storm by default, or Embree, Cycles, Karma... when installed)
+//! renders into an offscreen OpenGL framebuffer through PyO3, the frame is
+//! read back and uploaded to a texture, and `HydraBlit` draws that texture
+//! over the whole viewport. What the viewport shows then matches usdview
+//! pixel for pixel, at the cost of a readback per frame.
+
+use glam::{Mat4, Vec3};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use crate::capture::CapturedFrame;
+use super::camera_response::CameraResponse;
+use crate::core::usd_engine::with_usd_engine;
+#[cfg(feature = "usd")]
+use pyo3::prelude::*;
+#[cfg(feature = "usd")]
+use crate::core::profiling;
+
+/// Render delegate usdview draws with by default
+pub const STORM_RENDERER: &str = "HdStormRendererPlugin";
+
+/// Renderer drawing the viewport
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RenderBackend {
+    /// The plugin's own wgpu renderer
+    Wgpu,
+    /// UsdImagingGL with a render delegate, by plugin id such as `STORM_RENDERER`
+    Hydra(String),
+}
+
+impl RenderBackend {
+    pub fn label(&self) -> String {
+        match self {
+            RenderBackend::Wgpu => "wgpu".to_string(),
+            RenderBackend::Hydra(delegate) => format!("Hydra {}", delegate_name(delegate)),
+        }
+    }
+    
+    /// "wgpu" or the delegate's plugin id, as stored in the node's parameters
+    pub fn id(&self) -> &str {
+        match self {
+            RenderBackend::Wgpu => "wgpu",
+            RenderBackend::Hydra(delegate) => delegate,
+        }
+    }
+    
+    pub fn from_id(id: &str) -> Self {
+        match id {
+            "" | "wgpu" => RenderBackend::Wgpu,
+            delegate => RenderBackend::Hydra(delegate.to_string()),
+        }
+    }
+    
+    /// The wgpu renderer followed by every installed Hydra delegate
+    ///
+    /// Only wgpu is offered when the delegates can't be listed, e.g. without
+    /// the usd feature.
+    pub fn available() -> Vec<RenderBackend> {
+        let mut backends = vec![RenderBackend::Wgpu];
+        backends.extend(available_delegates().unwrap_or_default().into_iter().map(RenderBackend::Hydra));
+        backends
+    }
+}
+
+/// Short name of a delegate plugin id, e.g. "Storm" for "HdStormRendererPlugin" or "Karma" for "BRAY_HdKarma"
+pub fn delegate_name(delegate: &str) -> &str {
+    let name = delegate.strip_suffix("RendererPlugin").unwrap_or(delegate);
+    match name.rfind("Hd") {
+        Some(start) if start + 2 < name.len() => &name[start + 2..],
+        _ => name,
+    }
+}
+
+/// Value of a delegate render setting
+#[derive(Debug, Clone, PartialEq)]
+pub enum RenderSettingValue {
+    Flag(bool),
+    Int(i64),
+    Float(f64),
+    Text(String),
+}
+
+impl RenderSettingValue {
+    /// Parse a value of a setting type as UsdImagingGL names them: FLAG, INT, FLOAT or STRING
+    pub fn parse(kind: &str, text: &str) -> Result<Self, String> {
+        let text = text.trim();
+        match kind {
+            "FLAG" => match text.to_ascii_lowercase().as_str() {
+                "true" | "1" | "on" => Ok(RenderSettingValue::Flag(true)),
+                "false" | "0" | "off" => Ok(RenderSettingValue::Flag(false)),
+                _ => Err(format!("'{}' is not a flag", text)),
+            },
+            "INT" => text.parse().map(RenderSettingValue::Int).map_err(|_| format!("'{}' is not an integer", text)),
+            "FLOAT" => text.parse().map(RenderSettingValue::Float).map_err(|_| format!("'{}' is not a number", text)),
+            "STRING" => Ok(RenderSettingValue::Text(text.to_string())),
+            other => Err(format!("Unknown render setting type '{}'", other)),
+        }
+    }
+    
+    pub fn kind(&self) -> &'static str {
+        match self {
+            RenderSettingValue::Flag(_) => "FLAG",
+            RenderSettingValue::Int(_) => "INT",
+            RenderSettingValue::Float(_) => "FLOAT",
+            RenderSettingValue::Text(_) => "STRING",
+        }
+    }
+}
+
+impl std::fmt::Display for RenderSettingValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RenderSettingValue::Flag(value) => write!(f, "{}", value),
+            RenderSettingValue::Int(value) => write!(f, "{}", value),
+            RenderSettingValue::Float(value) => write!(f, "{}", value),
+            RenderSettingValue::Text(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+/// A render setting a delegate exposes, e.g. Storm's "enableTinyPrimCulling"
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderSetting {
+    pub key: String,
+    /// Display name
+    pub name: String,
+    pub default: RenderSettingValue,
+}
+
+/// Installed delegates and the settings of those queried so far
+///
+/// Failures are kept too, so panels redrawn every frame query Python once.
+#[derive(Default)]
+struct DelegateCache {
+    delegates: Option<Result<Vec<String>, String>>,
+    settings: HashMap<String, Result<Vec<RenderSetting>, String>>,
+}
+
+static DELEGATE_CACHE: Lazy<Mutex<DelegateCache>> = Lazy::new(|| Mutex::new(DelegateCache::default()));
+
+/// Plugin ids of the installed Hydra render delegates
+///
+/// Lists Storm and Embree from a standard USD build as well as third-party
+/// delegates such as Cycles or Karma whose plugins are on PXR_PLUGINPATH_NAME.
+pub fn available_delegates() -> Result<Vec<String>, String> {
+    if let Some(delegates) = &DELEGATE_CACHE.lock().unwrap().delegates {
+        return delegates.clone();
+    }
+    let delegates = query_delegates();
+    DELEGATE_CACHE.lock().unwrap().delegates = Some(delegates.clone());
+    delegates
+}
+
+/// Render settings a delegate exposes, with their defaults
+pub fn delegate_settings(delegate: &str) -> Result<Vec<RenderSetting>, String> {
+    if let Some(settings) = DELEGATE_CACHE.lock().unwrap().settings.get(delegate) {
+        return settings.clone();
+    }
+    let settings = query_settings(delegate);
+    DELEGATE_CACHE.lock().unwrap().settings.insert(delegate.to_string(), settings.clone());
+    settings
+}
+
+#[cfg(feature = "usd")]
+fn query_delegates() -> Result<Vec<String>, String> {
+    profiling::with_gil("hydra_delegates", |py| {
+        PyModule::from_code(py, HYDRA_HELPERS, c"nodle_hydra.py", c"nodle_hydra")
+            .and_then(|helpers| helpers.getattr("renderer_plugins")?.call0()?.extract())
+            .map_err(|e| format!("Failed to list render delegates: {}", e))
+    })
+}
+
+#[cfg(not(feature = "usd"))]
+fn query_delegates() -> Result<Vec<String>, String> {
+    Err("Hydra needs the usd feature".to_string())
+}
+
+#[cfg(feature = "usd")]
+fn query_settings(delegate: &str) -> Result<Vec<RenderSetting>, String> {
+    let settings: Vec<(String, String, String, String)> = profiling::with_gil("hydra_delegate_settings", |py| {
+        PyModule::from_code(py, HYDRA_HELPERS, c"nodle_hydra.py", c"nodle_hydra")
+            .and_then(|helpers| helpers.getattr("renderer_settings")?.call1((delegate,))?.extract())
+            .map_err(|e| format!("Failed to read the settings of '{}': {}", delegate, e))
+    })?;
+    settings.into_iter()
+        .map(|(key, name, kind, default)| Ok(RenderSetting {
+            default: RenderSettingValue::parse(&kind, &default).map_err(|e| format!("Setting '{}': {}", key, e))?,
+            key,
+            name,
+        }))
+        .collect()
+}
+
+#[cfg(not(feature = "usd"))]
+fn query_settings(delegate: &str) -> Result<Vec<RenderSetting>, String> {
+    Err(format!("Hydra needs the usd feature to query '{}'", delegate))
+}
+
+/// Camera and display settings of one Hydra frame
+#[derive(Debug, Clone, PartialEq)]
+pub struct HydraView {
+    pub width: u32,
+    pub height: u32,
+    /// World to camera transform, unused when `camera_path` is set
+    pub view: Mat4,
+    /// OpenGL projection with -1..1 clip depth, unused when `camera_path` is set
+    pub projection: Mat4,
+    /// Stage camera to look through, conformed to the frame's aspect ratio
+    pub camera_path: Option<String>,
+    pub time_code: f64,
+    /// UsdImagingGL refinement complexity, 1.0 to 2.0
+    pub complexity: f32,
+    pub enable_lighting: bool,
+    /// Purposes drawn besides "default"
+    pub purposes: Vec<String>,
+    pub clear_color: [f32; 4],
+    /// Plugin id of the render delegate
+    pub renderer: String,
+    /// Render settings passed to the delegate, as (key, value)
+    pub renderer_settings: Vec<(String, RenderSettingValue)>,
+    /// Exposure and depth of field applied to the frame, when looking through a camera
+    pub camera_response: Option<CameraResponse>,
+}
+
+impl HydraView {
+    /// A `width` x `height` view from `eye` towards `target` with a vertical field of view in radians
+    pub fn look_at(eye: Vec3, target: Vec3, fov: f32, near: f32, far: f32, width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            view: Mat4::look_at_rh(eye, target, Vec3::Y),
+            projection: Mat4::perspective_rh_gl(fov, width as f32 / height.max(1) as f32, near, far),
+            camera_path: None,
+            time_code: 0.0,
+            complexity: 1.0,
+            enable_lighting: true,
+            purposes: Vec::new(),
+            clear_color: [0.18, 0.18, 0.18, 1.0],
+            renderer: STORM_RENDERER.to_string(),
+            renderer_settings: Vec::new(),
+            camera_response: None,
+        }
+    }
+}
+
+/// Distances from the camera of OpenGL window depths, for a projection with `near` and `far` clipping planes
+pub fn linear_depths(window_depths: &[f32], near: f32, far: f32) -> Vec<f32> {
+    window_depths.iter()
+        .map(|depth| 2.0 * near * far / (far + near - (depth * 2.0 - 1.0) * (far - near)))
+        .collect()
+}
+
+/// Flip an image with rows of `row_bytes` upside down, e.g. from OpenGL's bottom-up row order
+pub fn flip_rows(pixels: &mut [u8], row_bytes: usize) {
+    if row_bytes == 0 {
+        return;
+    }
+    let rows = pixels.len() / row_bytes;
+    for row in 0..rows / 2 {
+        let (top, bottom) = pixels.split_at_mut((rows - 1 - row) * row_bytes);
+        top[row * row_bytes..(row + 1) * row_bytes].swap_with_slice(&mut bottom[..row_bytes]);
+    }
+}
+
+/// Python side of a Hydra session, plus delegate queries
+///
+/// Storm needs a current OpenGL context; the viewport draws with wgpu and
+/// has none to share, so the session owns a hidden one. The UsdImagingGL
+/// engine is bound to the first stage it draws and is rebuilt when the stage
+/// changes; switching delegates keeps the engine. Setting values travel as
+/// text with their type and are converted here.
+#[cfg(feature = "usd")]
+const HYDRA_HELPERS: &std::ffi::CStr = cr#"
+import numpy
+from OpenGL import GL
+from pxr import CameraUtil, Garch, Gf, Glf, Usd, UsdGeom, UsdImagingGL
+
+SETTING_TYPES = {
+    UsdImagingGL.RendererSettingType.FLAG: "FLAG",
+    UsdImagingGL.RendererSettingType.INT: "INT",
+    UsdImagingGL.RendererSettingType.FLOAT: "FLOAT",
+    UsdImagingGL.RendererSettingType.STRING: "STRING",
+}
+SETTING_VALUES = {
+    "FLAG": lambda text: text == "true",
+    "INT": int,
+    "FLOAT": float,
+    "STRING": str,
+}
+
+
+def renderer_plugins():
+    return [str(plugin) for plugin in UsdImagingGL.Engine.GetRendererPlugins()]
+
+
+def _setting_text(value):
+    if isinstance(value, bool):
+        return "true" if value else "false"
+    return str(value)
+
+
+def renderer_settings(renderer):
+    context = Garch.GLPlatformDebugContext(4, 5, True, False)
+    context.makeCurrent()
+    engine = UsdImagingGL.Engine()
+    if not engine.SetRendererPlugin(renderer):
+        raise RuntimeError("Render delegate %s is not available" % renderer)
+    return [
+        (setting.key, setting.name, SETTING_TYPES.get(setting.type, "STRING"), _setting_text(setting.defValue))
+        for setting in engine.GetRendererSettingsList()
+    ]
+
+
+def _near_far(projection):
+    # Clipping planes of an OpenGL perspective projection, with USD's row vectors
+    return projection[3][2] / (projection[2][2] - 1.0), projection[3][2] / (projection[2][2] + 1.0)
+
+
+class Session:
+    def __init__(self):
+        self.context = Garch.GLPlatformDebugContext(4, 5, True, False)
+        self.context.makeCurrent()
+        self.renderer = None
+        self.settings = {}
+        self.requested = (None, [])
+        self.stage = None
+        self.engine = None
+        self.size = None
+        self.framebuffer = None
+        self.renderbuffers = None
+    
+    def use_renderer(self, renderer, settings):
+        self.requested = (renderer, settings)
+    
+    def _bind(self, stage):
+        renderer, settings = self.requested
+        if self.engine is None or self.stage is not stage:
+            self.engine = UsdImagingGL.Engine()
+            self.engine.SetColorCorrectionSettings("sRGB")
+            self.stage = stage
+            self.renderer = None
+        if self.renderer != renderer:
+            if not self.engine.SetRendererPlugin(renderer):
+                raise RuntimeError("Render delegate %s is not available" % renderer)
+            self.renderer = renderer
+            self.settings = {}
+        for key, kind, text in settings:
+            if self.settings.get(key) != (kind, text):
+                self.engine.SetRendererSetting(key, SETTING_VALUES[kind](text))
+                self.settings[key] = (kind, text)
+    
+    def _resize(self, width, height):
+        if self.size == (width, height):
+            return
+        if self.framebuffer is not None:
+            GL.glDeleteFramebuffers(1, [self.framebuffer])
+            GL.glDeleteRenderbuffers(2, self.renderbuffers)
+        self.framebuffer = GL.glGenFramebuffers(1)
+        self.renderbuffers = GL.glGenRenderbuffers(2)
+        GL.glBindFramebuffer(GL.GL_FRAMEBUFFER, self.framebuffer)
+        attachments = ((GL.GL_RGBA8, GL.GL_COLOR_ATTACHMENT0), (GL.GL_DEPTH24_STENCIL8, GL.GL_DEPTH_STENCIL_ATTACHMENT))
+        for renderbuffer, (storage, attachment) in zip(self.renderbuffers, attachments):
+            GL.glBindRenderbuffer(GL.GL_RENDERBUFFER, renderbuffer)
+            GL.glRenderbufferStorage(GL.GL_RENDERBUFFER, storage, width, height)
+            GL.glFramebufferRenderbuffer(GL.GL_FRAMEBUFFER, attachment, GL.GL_RENDERBUFFER, renderbuffer)
+        if GL.glCheckFramebufferStatus(GL.GL_FRAMEBUFFER) != GL.GL_FRAMEBUFFER_COMPLETE:
+            raise RuntimeError("Offscreen framebuffer is incomplete")
+        self.size = (width, height)
+    
+    def _camera(self, view, projection, camera_path, width, height, time):
+        if not camera_path:
+            return Gf.Matrix4d(*view), Gf.Matrix4d(*projection)
+        camera = UsdGeom.Camera(self.stage.GetPrimAtPath(camera_path))
+        if not camera:
+            raise RuntimeError("%s is not a camera" % camera_path)
+        frustum = camera.GetCamera(time).frustum
+        CameraUtil.ConformWindow(frustum, CameraUtil.MatchVertically, width / max(height, 1))
+        return frustum.ComputeViewMatrix(), frustum.ComputeProjectionMatrix()
+    
+    def render(self, stage, view, projection, camera_path, width, height, time, complexity, lighting, purposes, clear_color, read_depth):
+        self.context.makeCurrent()
+        self._bind(stage)
+        self._resize(width, height)
+        GL.glBindFramebuffer(GL.GL_FRAMEBUFFER, self.framebuffer)
+        GL.glViewport(0, 0, width, height)
+        GL.glClearColor(*clear_color)
+        GL.glClear(GL.GL_COLOR_BUFFER_BIT | GL.GL_DEPTH_BUFFER_BIT)
+        
+        time = Usd.TimeCode(time)
+        view, projection = self._camera(view, projection, camera_path, width, height, time)
+        self.engine.SetRenderBufferSize(Gf.Vec2i(width, height))
+        self.engine.SetRenderViewport(Gf.Vec4d(0, 0, width, height))
+        self.engine.SetCameraState(view, projection)
+        
+        params = UsdImagingGL.RenderParams()
+        params.frame = time
+        params.complexity = complexity
+        params.enableLighting = lighting
+        params.showRender = "render" in purposes
+        params.showProxy = "proxy" in purposes
+        params.showGuides = "guide" in purposes
+        params.clearColor = Gf.Vec4f(*clear_color)
+        if lighting:
+            # usdview's default light follows the camera
+            eye = view.GetInverse().ExtractTranslation()
+            light = Glf.SimpleLight()
+            light.ambient = Gf.Vec4f(0, 0, 0, 0)
+            light.position = Gf.Vec4f(eye[0], eye[1], eye[2], 1)
+            material = Glf.SimpleMaterial()
+            material.ambient = Gf.Vec4f(0.2, 0.2, 0.2, 1)
+            material.specular = Gf.Vec4f(0.1, 0.1, 0.1, 1)
+            material.shininess = 32.0
+            self.engine.SetLightingState([light], material, Gf.Vec4f(0.2, 0.2, 0.2, 1))
+        
+        # Progressive delegates converge over several passes
+        root = stage.GetPseudoRoot()
+        self.engine.Render(root, params)
+        for _ in range(63):
+            if self.engine.IsConverged():
+                break
+            self.engine.Render(root, params)
+        
+        GL.glPixelStorei(GL.GL_PACK_ALIGNMENT, 1)
+        pixels = GL.glReadPixels(0, 0, width, height, GL.GL_RGBA, GL.GL_UNSIGNED_BYTE)
+        depth, near, far = b"", 0.0, 0.0
+        if read_depth:
+            window_depths = GL.glReadPixels(0, 0, width, height, GL.GL_DEPTH_COMPONENT, GL.GL_FLOAT)
+            depth = numpy.asarray(window_depths, dtype=numpy.float32).tobytes()
+            near, far = _near_far(projection)
+        GL.glBindFramebuffer(GL.GL_FRAMEBUFFER, 0)
+        return bytes(pixels), depth, near, far
+"#;
+
+/// Full-screen blit of the last Hydra frame into the viewport pass
+///
+/// Targets the viewport's Rgba8UnormSrgb color and Depth32Float depth
+/// attachments. The frame texture is sRGB as well, so Storm's color
+/// corrected output reaches the screen unchanged.
+pub struct HydraBlit {
+    layout: wgpu::BindGroupLayout,
+    pipeline: wgpu::RenderPipeline,
+    sampler: wgpu::Sampler,
+    /// Samples per pixel of the pass the pipeline draws into
+    samples: u32,
+    /// Frame texture with its size and bind group, replaced when the size changes
+    frame: Option<(wgpu::Texture, (u32, u32), wgpu::BindGroup)>,
+}
+
+impl HydraBlit {
+    /// Blit into a viewport pass with `samples` samples per pixel
+    pub fn new(device: &wgpu::Device, samples: u32) -> Self {
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("usd_hydra_blit_layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("usd_hydra_blit_pipeline_layout"),
+            bind_group_layouts: &[&layout],
+            push_constant_ranges: &[],
+        });
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("usd_hydra_blit"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/hydra_blit.wgsl").into()),
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("usd_hydra_blit"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                compilation_options: Default::default(),
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                compilation_options: Default::default(),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: wgpu::TextureFormat::Rgba8UnormSrgb,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            // Hydra resolved visibility itself; the blit only has to cover the pass
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: wgpu::TextureFormat::Depth32Float,
+                depth_write_enabled: false,
+                depth_compare: wgpu::CompareFunction::Always,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState { count: samples, ..Default::default() },
+            multiview: None,
+            cache: None,
+        });
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("usd_hydra_blit"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+        Self { layout, pipeline, sampler, samples, frame: None }
+    }
+    
+    /// Samples per pixel of the pass the blit draws into
+    pub fn samples(&self) -> u32 {
+        self.samples
+    }
+    
+    /// Upload a frame, reallocating the texture when its size changed
+    pub fn upload(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, frame: &CapturedFrame) {
+        let size = (frame.width, frame.height);
+        if self.frame.as_ref().is_none_or(|(_, current, _)| *current != size) {
+            let texture = device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("usd_hydra_frame"),
+                size: wgpu::Extent3d { width: frame.width, height: frame.height, depth_or_array_layers: 1 },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::Rgba8UnormSrgb,
+                usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+                view_formats: &[],
+            });
+            let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+            let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("usd_hydra_frame"),
+                layout: &self.layout,
+                entries: &[
+                    wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(&view) },
+                    wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::Sampler(&self.sampler) },
+                ],
+            });
+            self.frame = Some((texture, size, bind_group));
+        }
+        let Some((texture, _, _)) = &self.frame else {
+            return;
+        };
+        queue.write_texture(
+            wgpu::TexelCopyTextureInfo {
+                texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            &frame.pixels,
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(frame.width * 4),
+                rows_per_image: Some(frame.height),
+            },
+            wgpu::Extent3d { width: frame.width, height: frame.height, depth_or_array_layers: 1 },
+        );
+    }
+    
+    /// Draw the uploaded frame over the pass; false if nothing was uploaded yet
+    pub fn draw(&self, render_pass: &mut wgpu::RenderPass) -> bool {
+        let Some((_, _, bind_group)) = &self.frame else {
+            return false;
+        };
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+        true
+    }
+}
+
+/// Hydra backend state of a renderer
+#[derive(Default)]
+pub struct HydraRenderer {
+    /// Python `Session`, started with the first frame
+    #[cfg(feature = "usd")]
+    session: Option<Py<PyAny>>,
+    /// Stage, stage revision and view of the frame on screen
+    last_frame: Option<(String, u64, HydraView)>,
+    /// Blit pipeline and frame texture, created with the first frame
+    pub blit: Option<HydraBlit>,
+    /// Why the last frame failed; the wgpu renderer draws meanwhile
+    pub last_error: Option<String>,
+}
+
+impl HydraRenderer {
+    /// Render the stage through Hydra and upload the frame, unless stage, view and pass samples are unchanged
+    pub fn render(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, stage_id: &str, view: &HydraView, samples: u32) -> Result<(), String> {
+        let revision = with_usd_engine(|engine| engine.stage_revision(stage_id));
+        let blit_current = self.blit.as_ref().is_some_and(|blit| blit.samples == samples);
+        if self.last_error.is_none()
+            && blit_current
+            && self.last_frame.as_ref().is_some_and(|(stage, rev, last)| stage == stage_id && *rev == revision && last == view) {
+            return Ok(());
+        }
+        
+        let frame = match self.read_frame(stage_id, view) {
+            Ok(frame) => frame,
+            Err(e) => {
+                self.last_error = Some(e.clone());
+                self.last_frame = None;
+                return Err(e);
+            }
+        };
+        if !blit_current {
+            self.blit = Some(HydraBlit::new(device, samples));
+        }
+        if let Some(blit) = &mut self.blit {
+            blit.upload(device, queue, &frame);
+        }
+        self.last_frame = Some((stage_id.to_string(), revision, view.clone()));
+        self.last_error = None;
+        Ok(())
+    }
+    
+    /// Whether a Hydra frame is ready to be drawn in place of the wgpu scene
+    pub fn has_frame(&self) -> bool {
+        self.last_error.is_none() && self.last_frame.is_some()
+    }
+    
+    /// Render a frame and read it back top row first, with the view's camera response applied
+    ///
+    /// The session's OpenGL context belongs to the thread that started it,
+    /// so a renderer reading frames on a worker thread must keep to it.
+    /// Depth is only read back for depth of field.
+    #[cfg(feature = "usd")]
+    pub fn read_frame(&mut self, stage_id: &str, view: &HydraView) -> Result<CapturedFrame, String> {
+        let read_depth = view.camera_response.is_some_and(|response| response.has_depth_of_field());
+        let (mut pixels, mut depth, near, far) = with_usd_engine(|engine| {
+            let stage = engine.get_stage(stage_id)
+                .ok_or_else(|| format!("Stage '{}' not found", stage_id))?;
+            profiling::with_gil("hydra_render", |py| -> Result<(Vec<u8>, Vec<u8>, f32, f32), String> {
+                let session = match &self.session {
+                    Some(session) => session.bind(py).clone(),
+                    None => {
+                        let helpers = PyModule::from_code(py, HYDRA_HELPERS, c"nodle_hydra.py", c"nodle_hydra")
+                            .map_err(|e| format!("Failed to load Hydra helpers: {}", e))?;
+                        let session = helpers.getattr("Session")
+                            .and_then(|session| session.call0())
+                            .map_err(|e| format!("Failed to start Hydra: {}", e))?;
+                        self.session = Some(session.clone().unbind());
+                        session
+                    }
+                };
+                let py_stage = engine.open_python_stage(py, stage)?;
+                let rows = |matrix: &Mat4| matrix.to_cols_array().map(f64::from).to_vec();
+                let settings: Vec<(String, &str, String)> = view.renderer_settings.iter()
+                    .map(|(key, value)| (key.clone(), value.kind(), value.to_string()))
+                    .collect();
+                session.call_method1("use_renderer", (view.renderer.clone(), settings))
+                    .map_err(|e| format!("Failed to pass render settings: {}", e))?;
+                session.call_method1("render", (
+                    py_stage,
+                    rows(&view.view),
+                    rows(&view.projection),
+                    view.camera_path.clone().unwrap_or_default(),
+                    view.width,
+                    view.height,
+                    view.time_code,
+                    view.complexity,
+                    view.enable_lighting,
+                    view.purposes.clone(),
+                    view.clear_color.to_vec(),
+                    read_depth,
+                ))
+                .and_then(|frame| frame.extract())
+                .map_err(|e| format!("Hydra failed to render '{}': {}", stage_id, e))
+            })
+        })?;
+        flip_rows(&mut pixels, view.width as usize * 4);
+        let mut frame = CapturedFrame::new(view.width, view.height, pixels)?;
+        if let Some(response) = &view.camera_response {
+            let depths = read_depth.then(|| {
+                // Depths are 4-byte floats, so their rows are as long as the color rows
+                flip_rows(&mut depth, view.width as usize * 4);
+                let window_depths: Vec<f32> = depth.chunks_exact(4).map(|bytes| f32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])).collect();
+                linear_depths(&window_depths, near, far)
+            });
+            response.apply(&mut frame, depths.as_deref());
+        }
+        Ok(frame)
+    }
+    
+    #[cfg(not(feature = "usd"))]
+    pub fn read_frame(&mut self, stage_id: &str, _view: &HydraView) -> Result<CapturedFrame, String> {
+        Err(format!("Hydra needs the usd feature to render '{}'", stage_id))
+    }
+}
+
+/// Hydra session of renders outside any viewport
+static OFFSCREEN: Lazy<Mutex<HydraRenderer>> = Lazy::new(|| Mutex::new(HydraRenderer::default()));
+
+/// Render a frame without a viewport, e.g. to write it to disk
+///
+/// Needs no wgpu device; the frame is read back from the session's own
+/// OpenGL context. Renders share one session, so the frames of a sequence
+/// reuse the same engine.
+pub fn render_offscreen(stage_id: &str, view: &HydraView) -> Result<CapturedFrame, String> {
+    OFFSCREEN.lock().unwrap().read_frame(stage_id, view)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn rows_flip_in_place() {
+        let mut pixels: Vec<u8> = (0..12).collect();
+        flip_rows(&mut pixels, 4);
+        assert_eq!(pixels, [8, 9, 10, 11, 4, 5, 6, 7, 0, 1, 2, 3]);
+        
+        let mut even: Vec<u8> = (0..4).collect();
+        flip_rows(&mut even, 2);
+        assert_eq!(even, [2, 3, 0, 1]);
+    }
+    
+    #[test]
+    fn backends_round_trip_through_ids() {
+        assert_eq!(delegate_name(STORM_RENDERER), "Storm");
+        assert_eq!(delegate_name("HdEmbreeRendererPlugin"), "Embree");
+        assert_eq!(delegate_name("BRAY_HdKarma"), "Karma");
+        assert_eq!(delegate_name("Hd"), "Hd");
+        
+        let storm = RenderBackend::from_id(STORM_RENDERER);
+        assert_eq!(storm, RenderBackend::Hydra(STORM_RENDERER.to_string()));
+        assert_eq!(storm.label(), "Hydra Storm");
+        assert_eq!(RenderBackend::from_id(storm.id()), storm);
+        assert_eq!(RenderBackend::from_id("wgpu"), RenderBackend::Wgpu);
+        assert_eq!(RenderBackend::from_id(""), RenderBackend::Wgpu);
+    }
+    
+    #[test]
+    fn render_settings_parse_by_type() {
+        assert_eq!(RenderSettingValue::parse("FLAG", "true"), Ok(RenderSettingValue::Flag(true)));
+        assert_eq!(RenderSettingValue::parse("INT", " 16 "), Ok(RenderSettingValue::Int(16)));
+        assert_eq!(RenderSettingValue::parse("FLOAT", "0.5"), Ok(RenderSettingValue::Float(0.5)));
+        assert_eq!(RenderSettingValue::parse("STRING", "beauty"), Ok(RenderSettingValue::Text("beauty".to_string())));
+        assert!(RenderSettingValue::parse("INT", "many").is_err());
+        assert!(RenderSettingValue::parse("VEC3", "1").is_err());
+        
+        let value = RenderSettingValue::Flag(false);
+        assert_eq!(RenderSettingValue::parse(value.kind(), &value.to_string()), Ok(value));
+    }
+    
+    #[test]
+    fn views_use_opengl_clip_depth() {
+        let view = HydraView::look_at(Vec3::new(0.0, 0.0, 5.0), Vec3::ZERO, 45f32.to_radians(), 0.1, 100.0, 640, 480);
+        let clip = |z: f32| {
+            let point = view.projection * view.view * glam::Vec4::new(0.0, 0.0, z, 1.0);
+            point.z / point.w
+        };
+        assert!((clip(4.9) + 1.0).abs() < 1e-4);
+        assert!((clip(-95.0) - 1.0).abs() < 1e-3);
+        
+        // Window depths map back to distances from the camera
+        let window = (clip(-15.0) + 1.0) * 0.5;
+        let distances = linear_depths(&[0.0, window, 1.0], 0.1, 100.0);
+        assert!((distances[0] - 0.1).abs() < 1e-4 && (distances[1] - 20.0).abs() < 1e-2 && (distances[2] - 100.0).abs() < 1e-2);
+    }
+}
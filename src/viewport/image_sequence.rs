@@ -0,0 +1,166 @@
+//! Frame-accurate image sequences used as animated textures
+//!
+//! Texture file paths may name a sequence instead of a single image:
+//! `plate.####.png`, `plate.%04d.png`, `plate.$F4.png` or `plate.$F.png`.
+//! The viewport resolves such paths to the file for the current time code,
+//! holding the first/last available frame outside the rendered range.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// A parsed sequence pattern split around its frame number
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SequencePattern {
+    pub prefix: String,
+    pub suffix: String,
+    /// Zero padding width, 0 for unpadded frame numbers
+    pub padding: usize,
+}
+
+impl SequencePattern {
+    /// Parse a sequence path, returning None for plain image paths
+    pub fn parse(path: &str) -> Option<Self> {
+        if let Some(start) = path.find('#') {
+            let padding = path[start..].chars().take_while(|&c| c == '#').count();
+            return Some(Self {
+                prefix: path[..start].to_string(),
+                suffix: path[start + padding..].to_string(),
+                padding,
+            });
+        }
+        if let Some(start) = path.find('%') {
+            // printf style: %d or %0Nd
+            let spec: String = path[start + 1..].chars().take_while(|c| c.is_ascii_digit()).collect();
+            if path[start + 1 + spec.len()..].starts_with('d') {
+                return Some(Self {
+                    prefix: path[..start].to_string(),
+                    suffix: path[start + spec.len() + 2..].to_string(),
+                    padding: spec.parse().unwrap_or(0),
+                });
+            }
+        }
+        if let Some(start) = path.find("$F") {
+            // Houdini style: $F or $FN
+            let digits: String = path[start + 2..].chars().take_while(|c| c.is_ascii_digit()).collect();
+            return Some(Self {
+                prefix: path[..start].to_string(),
+                suffix: path[start + 2 + digits.len()..].to_string(),
+                padding: digits.parse().unwrap_or(0),
+            });
+        }
+        None
+    }
+    
+    /// File path for a frame number
+    pub fn frame_path(&self, frame: i64) -> String {
+        let sign = if frame < 0 { "-" } else { "" };
+        format!("{}{}{:0width$}{}", self.prefix, sign, frame.abs(), self.suffix, width = self.padding)
+    }
+    
+    /// Frame number of a file name matching this pattern
+    fn frame_of(&self, file_path: &str) -> Option<i64> {
+        let digits = file_path.strip_prefix(&self.prefix)?.strip_suffix(&self.suffix)?;
+        if digits.is_empty() || (self.padding > 0 && digits.trim_start_matches('-').len() < self.padding) {
+            return None;
+        }
+        digits.parse().ok()
+    }
+}
+
+/// An image sequence with the frames found on disk
+#[derive(Debug, Clone)]
+pub struct ImageSequence {
+    pub pattern: SequencePattern,
+    frames: BTreeMap<i64, PathBuf>,
+}
+
+impl ImageSequence {
+    /// Parse a sequence path and scan its directory for existing frames
+    pub fn open(path: &str) -> Option<Self> {
+        let pattern = SequencePattern::parse(path)?;
+        let mut sequence = Self { pattern, frames: BTreeMap::new() };
+        sequence.rescan();
+        Some(sequence)
+    }
+    
+    /// Re-read the frames available on disk
+    pub fn rescan(&mut self) {
+        self.frames.clear();
+        let directory = Path::new(&self.pattern.prefix)
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .unwrap_or(Path::new("."))
+            .to_path_buf();
+        let Ok(entries) = std::fs::read_dir(&directory) else {
+            return;
+        };
+        
+        for entry in entries.flatten() {
+            let path = entry.path();
+            // Compare using the same directory spelling as the pattern
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let candidate = match Path::new(&self.pattern.prefix).parent().filter(|p| !p.as_os_str().is_empty()) {
+                Some(parent) => parent.join(&file_name).to_string_lossy().to_string(),
+                None => file_name,
+            };
+            if let Some(frame) = self.pattern.frame_of(&candidate) {
+                self.frames.insert(frame, path);
+            }
+        }
+    }
+    
+    /// First and last frame on disk
+    pub fn frame_range(&self) -> Option<(i64, i64)> {
+        Some((*self.frames.keys().next()?, *self.frames.keys().next_back()?))
+    }
+    
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+    
+    /// File for a time code, holding the nearest earlier frame for gaps and
+    /// the first/last frame outside the sequence range
+    pub fn file_at(&self, time_code: f64) -> Option<&Path> {
+        let frame = time_code.floor() as i64;
+        self.frames.range(..=frame)
+            .next_back()
+            .or_else(|| self.frames.iter().next())
+            .map(|(_, path)| path.as_path())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn parses_pattern_styles() {
+        for path in ["plate.####.png", "plate.%04d.png", "plate.$F4.png"] {
+            let pattern = SequencePattern::parse(path).unwrap();
+            assert_eq!(pattern.prefix, "plate.");
+            assert_eq!(pattern.suffix, ".png");
+            assert_eq!(pattern.padding, 4);
+            assert_eq!(pattern.frame_path(12), "plate.0012.png");
+        }
+        assert_eq!(SequencePattern::parse("plate.$F.exr").unwrap().frame_path(7), "plate.7.exr");
+        assert!(SequencePattern::parse("plate.0001.png").is_none());
+    }
+    
+    #[test]
+    fn holds_frames_outside_range() {
+        let directory = std::env::temp_dir().join(format!("nodle_sequence_{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        for frame in [1, 2, 5] {
+            std::fs::write(directory.join(format!("plate.{:04}.png", frame)), b"").unwrap();
+        }
+        
+        let pattern = directory.join("plate.####.png").to_string_lossy().to_string();
+        let sequence = ImageSequence::open(&pattern).unwrap();
+        assert_eq!(sequence.frame_range(), Some((1, 5)));
+        assert!(sequence.file_at(0.0).unwrap().ends_with("plate.0001.png"));
+        assert!(sequence.file_at(3.5).unwrap().ends_with("plate.0002.png"));
+        assert!(sequence.file_at(99.0).unwrap().ends_with("plate.0005.png"));
+        
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
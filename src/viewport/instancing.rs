@@ -0,0 +1,101 @@
+//! Point instancer expansion into per-prototype instance batches
+//!
+//! A UsdGeomPointInstancer places copies of its prototype subtrees. Each
+//! mesh below a prototype becomes one batch: the mesh buffers are uploaded
+//! once and drawn with a per-instance transform buffer.
+
+use glam::Mat4;
+
+/// Instances of one prototype mesh
+#[derive(Debug, Clone)]
+pub struct InstanceBatch {
+    /// Mesh prim whose vertex/index buffers are shared by all instances
+    pub geometry_path: String,
+    /// World transform of every instance, including the mesh's offset inside the prototype
+    pub transforms: Vec<Mat4>,
+}
+
+impl InstanceBatch {
+    /// Column-major instance matrices for the instance vertex buffer
+    pub fn instance_data(&self) -> Vec<[[f32; 4]; 4]> {
+        self.transforms.iter().map(|transform| transform.to_cols_array_2d()).collect()
+    }
+}
+
+/// Whether a prim path is the given prototype root or below it
+pub fn is_under(path: &str, root: &str) -> bool {
+    path == root || path.strip_prefix(root).is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// Expand a point instancer into batches
+///
+/// `instance_transforms` are the instancer-local transforms from
+/// ComputeInstanceTransformsAtTime, `prototype_roots` the world transforms of
+/// the prototype prims and `geometries` the world transforms of extracted meshes.
+/// USD drops hidden and inactive instances from the transforms; `mask` is the
+/// instancer's ComputeMaskAtTime result used to drop them from `proto_indices`
+/// too, empty when every instance is shown.
+pub fn build_instance_batches(
+    instancer_world: Mat4,
+    prototypes: &[(String, Mat4)],
+    proto_indices: &[i32],
+    mask: &[bool],
+    instance_transforms: &[Mat4],
+    geometries: &[(String, Mat4)],
+) -> Result<Vec<InstanceBatch>, String> {
+    if !mask.is_empty() && mask.len() != proto_indices.len() {
+        return Err(format!("Instance mask of {} for {} proto indices", mask.len(), proto_indices.len()));
+    }
+    let proto_indices: Vec<i32> = if mask.is_empty() {
+        proto_indices.to_vec()
+    } else {
+        proto_indices.iter().zip(mask).filter(|(_, shown)| **shown).map(|(index, _)| *index).collect()
+    };
+    if proto_indices.len() != instance_transforms.len() {
+        return Err(format!("{} proto indices for {} instance transforms", proto_indices.len(), instance_transforms.len()));
+    }
+    
+    let mut batches = Vec::new();
+    for (prototype_index, (prototype_path, prototype_world)) in prototypes.iter().enumerate() {
+        let instances: Vec<Mat4> = proto_indices.iter()
+            .zip(instance_transforms)
+            .filter(|(index, _)| **index as usize == prototype_index)
+            .map(|(_, transform)| instancer_world * *transform)
+            .collect();
+        if instances.is_empty() {
+            continue;
+        }
+        
+        // Meshes keep their placement relative to the prototype root
+        let prototype_inverse = prototype_world.inverse();
+        for (geometry_path, geometry_world) in geometries.iter().filter(|(path, _)| is_under(path, prototype_path)) {
+            let relative = prototype_inverse * *geometry_world;
+            batches.push(InstanceBatch {
+                geometry_path: geometry_path.clone(),
+                transforms: instances.iter().map(|instance| *instance * relative).collect(),
+            });
+        }
+    }
+    Ok(batches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::Vec3;
+    
+    #[test]
+    fn masked_instances_are_dropped() {
+        let prototypes = vec![("/Protos/A".to_string(), Mat4::IDENTITY), ("/Protos/B".to_string(), Mat4::IDENTITY)];
+        let geometries = vec![("/Protos/A/mesh".to_string(), Mat4::IDENTITY), ("/Protos/B/mesh".to_string(), Mat4::IDENTITY)];
+        // Instance 1 is hidden, so USD returns transforms for instances 0 and 2 only
+        let transforms = [Mat4::from_translation(Vec3::X), Mat4::from_translation(Vec3::Z)];
+        
+        let batches = build_instance_batches(Mat4::IDENTITY, &prototypes, &[0, 1, 1], &[true, false, true], &transforms, &geometries).unwrap();
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].transforms, [Mat4::from_translation(Vec3::X)]);
+        assert_eq!(batches[1].transforms, [Mat4::from_translation(Vec3::Z)]);
+        
+        assert!(build_instance_batches(Mat4::IDENTITY, &prototypes, &[0, 1, 1], &[], &transforms, &geometries).is_err());
+    }
+}
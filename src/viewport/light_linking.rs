@@ -0,0 +1,89 @@
+//! UsdLux light linking
+//!
+//! A light's lightLink collection picks the geometry it illuminates and its
+//! shadowLink collection the geometry that casts its shadows. Both include the
+//! whole stage (includeRoot) unless authored otherwise. Geometry is shaded with
+//! a bitmask of the lights linked to it.
+
+use super::instancing::is_under;
+
+/// Lights distinguished by a light mask; further lights affect all geometry
+pub const MAX_LINKED_LIGHTS: usize = 32;
+
+/// Membership rule of a light's lightLink or shadowLink collection, or any other collection
+///
+/// Collections use the expandPrims rule: the nearest included or excluded
+/// ancestor of a prim decides, with includeRoot acting as an include of "/".
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkCollection {
+    pub include_root: bool,
+    pub includes: Vec<String>,
+    pub excludes: Vec<String>,
+}
+
+impl Default for LinkCollection {
+    fn default() -> Self {
+        Self {
+            include_root: true,
+            includes: Vec::new(),
+            excludes: Vec::new(),
+        }
+    }
+}
+
+impl LinkCollection {
+    /// Whether the collection has no authored restrictions
+    pub fn links_everything(&self) -> bool {
+        self.include_root && self.excludes.is_empty()
+    }
+    
+    /// Whether a prim is a member of the collection
+    pub fn contains(&self, prim_path: &str) -> bool {
+        let nearest = |roots: &[String]| roots.iter()
+            .filter(|root| root.as_str() == "/" || is_under(prim_path, root))
+            .map(|root| root.len())
+            .max();
+        let include = nearest(&self.includes).or(self.include_root.then_some(0));
+        match (include, nearest(&self.excludes)) {
+            (Some(include), Some(exclude)) => include > exclude,
+            (include, _) => include.is_some(),
+        }
+    }
+}
+
+/// Bitmask of the lights whose collection contains a prim, bit i for the i-th collection
+pub fn light_mask<'a>(collections: impl IntoIterator<Item = &'a LinkCollection>, prim_path: &str) -> u32 {
+    collections.into_iter()
+        .take(MAX_LINKED_LIGHTS)
+        .enumerate()
+        .filter(|(_, collection)| collection.contains(prim_path))
+        .fold(0, |mask, (index, _)| mask | 1 << index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn nearest_rule_decides_membership() {
+        let key = LinkCollection {
+            include_root: false,
+            includes: vec!["/World/Hero".to_string()],
+            excludes: vec!["/World/Hero/Hair".to_string()],
+        };
+        assert!(key.contains("/World/Hero/Body"));
+        assert!(!key.contains("/World/Hero/Hair/Strands"));
+        assert!(!key.contains("/World/HeroDouble"));
+        assert!(!key.contains("/World/Set"));
+        
+        let fill = LinkCollection {
+            excludes: vec!["/World/Set".to_string()],
+            ..Default::default()
+        };
+        assert!(fill.contains("/World/Hero/Hair"));
+        assert!(!fill.contains("/World/Set/Table"));
+        
+        assert_eq!(light_mask([&key, &fill, &LinkCollection::default()], "/World/Hero/Body"), 0b111);
+        assert_eq!(light_mask([&key, &fill, &LinkCollection::default()], "/World/Set/Table"), 0b100);
+    }
+}
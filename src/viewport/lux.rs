@@ -0,0 +1,207 @@
+//! UsdLux light parameters and their viewport shading units
+//!
+//! Lights are read with every UsdLux input that changes how much and what
+//! color they emit: color, intensity, exposure, color temperature, the
+//! size of area lights with their normalize flag, and the cone of lights
+//! with a ShapingAPI. The viewport shades lights as directions with an
+//! intensity, so their emission is converted the way renderers read it:
+//! the emitted color is `color` times the blackbody color when color
+//! temperature is enabled, and area lights give off their luminance times
+//! their projected area, or just their luminance when normalized, so
+//! resizing a normalized light keeps its brightness. Distant and dome
+//! lights give their intensity as is, as Storm does.
+
+use glam::Vec3;
+
+/// Light inputs read from UsdLux lights, without their "inputs:" prefix
+pub const LUX_INPUTS: [&str; 13] = [
+    "color",
+    "intensity",
+    "exposure",
+    "enableColorTemperature",
+    "colorTemperature",
+    "normalize",
+    "radius",
+    "width",
+    "height",
+    "length",
+    "angle",
+    "shaping:cone:angle",
+    "shaping:cone:softness",
+];
+
+/// Range of color temperatures the blackbody approximation covers, in Kelvin
+const TEMPERATURE_RANGE: (f32, f32) = (1667.0, 25000.0);
+
+/// Inputs of a UsdLux light
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LuxParams {
+    pub color: Vec3,
+    pub intensity: f32,
+    pub exposure: f32,
+    pub enable_color_temperature: bool,
+    /// Blackbody temperature in Kelvin
+    pub color_temperature: f32,
+    /// Divide emission by the light's area, so its size doesn't change its power
+    pub normalize: bool,
+    /// Radius of sphere, disk and cylinder lights
+    pub radius: f32,
+    /// Size of rect lights
+    pub width: f32,
+    pub height: f32,
+    /// Length of cylinder lights
+    pub length: f32,
+    /// Angular diameter of distant lights, in degrees
+    pub angle: f32,
+    /// Angle from the light's -Z axis to the edge of its cone, in degrees; None without a ShapingAPI cone
+    pub cone_angle: Option<f32>,
+    /// Fraction of the cone the light fades out over
+    pub cone_softness: Option<f32>,
+}
+
+impl Default for LuxParams {
+    /// UsdLux schema defaults
+    fn default() -> Self {
+        Self {
+            color: Vec3::ONE,
+            intensity: 1.0,
+            exposure: 0.0,
+            enable_color_temperature: false,
+            color_temperature: 6500.0,
+            normalize: false,
+            radius: 0.5,
+            width: 1.0,
+            height: 1.0,
+            length: 1.0,
+            angle: 0.53,
+            cone_angle: None,
+            cone_softness: None,
+        }
+    }
+}
+
+impl LuxParams {
+    /// Take an input's value, without its "inputs:" prefix; flags come as 0 or 1
+    pub fn set_input(&mut self, name: &str, values: &[f64]) {
+        match (name.trim_start_matches("inputs:"), values) {
+            ("color", [r, g, b]) => self.color = Vec3::new(*r as f32, *g as f32, *b as f32),
+            ("intensity", [value]) => self.intensity = *value as f32,
+            ("exposure", [value]) => self.exposure = *value as f32,
+            ("enableColorTemperature", [value]) => self.enable_color_temperature = *value != 0.0,
+            ("colorTemperature", [value]) => self.color_temperature = *value as f32,
+            ("normalize", [value]) => self.normalize = *value != 0.0,
+            ("radius", [value]) => self.radius = *value as f32,
+            ("width", [value]) => self.width = *value as f32,
+            ("height", [value]) => self.height = *value as f32,
+            ("length", [value]) => self.length = *value as f32,
+            ("angle", [value]) => self.angle = *value as f32,
+            ("shaping:cone:angle", [value]) => self.cone_angle = Some(*value as f32),
+            ("shaping:cone:softness", [value]) => self.cone_softness = Some(*value as f32),
+            _ => {}
+        }
+    }
+    
+    /// Color emitted, before intensity
+    pub fn tint(&self) -> Vec3 {
+        match self.enable_color_temperature {
+            true => self.color * blackbody_rgb(self.color_temperature),
+            false => self.color,
+        }
+    }
+    
+    /// Area seen from straight on of a light of a type ("rect", "sphere", ...), None for lights without one
+    pub fn projected_area(&self, light_type: &str) -> Option<f32> {
+        let pi = std::f32::consts::PI;
+        match light_type {
+            "rect" => Some(self.width * self.height),
+            "disk" | "sphere" => Some(pi * self.radius * self.radius),
+            "cylinder" => Some(2.0 * self.radius * self.length),
+            _ => None,
+        }
+    }
+    
+    /// Intensity the viewport shades a light of a type with, before camera exposure
+    pub fn viewport_intensity(&self, light_type: &str) -> f32 {
+        let luminance = self.intensity * self.exposure.exp2();
+        match self.projected_area(light_type).filter(|_| !self.normalize) {
+            Some(area) => luminance * area,
+            None => luminance,
+        }
+    }
+    
+    /// Cosines of the angles off the light's axis where its cone starts fading and where it ends
+    pub fn cone_cosines(&self) -> Option<(f32, f32)> {
+        let outer = self.cone_angle?.clamp(0.0, 180.0).to_radians();
+        let softness = self.cone_softness.unwrap_or(0.0).clamp(0.0, 1.0);
+        Some(((outer * (1.0 - softness)).cos(), outer.cos()))
+    }
+}
+
+/// Linear Rec. 709 color of a blackbody at a temperature in Kelvin, normalized to a luminance of one
+///
+/// Follows the Planckian locus with Kim et al.'s cubic approximation of
+/// its CIE xy chromaticities; temperatures outside 1667K to 25000K are
+/// clamped to that range.
+pub fn blackbody_rgb(kelvin: f32) -> Vec3 {
+    let t = f64::from(kelvin.clamp(TEMPERATURE_RANGE.0, TEMPERATURE_RANGE.1));
+    let x = if t <= 4000.0 {
+        -0.2661239e9 / t.powi(3) - 0.2343589e6 / t.powi(2) + 0.8776956e3 / t + 0.179910
+    } else {
+        -3.0258469e9 / t.powi(3) + 2.1070379e6 / t.powi(2) + 0.2226347e3 / t + 0.240390
+    };
+    let y = if t <= 2222.0 {
+        -1.1063814 * x.powi(3) - 1.34811020 * x.powi(2) + 2.18555832 * x - 0.20219683
+    } else if t <= 4000.0 {
+        -0.9549476 * x.powi(3) - 1.37418593 * x.powi(2) + 2.09137015 * x - 0.16748867
+    } else {
+        3.0817580 * x.powi(3) - 5.87338670 * x.powi(2) + 3.75112997 * x - 0.37001483
+    };
+    
+    // XYZ with Y = 1 to linear Rec. 709
+    let (cx, cz) = (x / y, (1.0 - x - y) / y);
+    let rgb = Vec3::new(
+        (3.2404542 * cx - 1.5371385 - 0.4985314 * cz) as f32,
+        (-0.9692660 * cx + 1.8760108 + 0.0415560 * cz) as f32,
+        (0.0556434 * cx - 0.2040259 + 1.0572252 * cz) as f32,
+    ).max(Vec3::ZERO);
+    rgb / rgb.dot(Vec3::new(0.2126, 0.7152, 0.0722))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn emission_follows_temperature_size_and_normalize() {
+        // Warm temperatures are red, near-daylight ones close to white, all at unit luminance
+        let warm = blackbody_rgb(3000.0);
+        let daylight = blackbody_rgb(6500.0);
+        assert!(warm.x > warm.z * 2.0);
+        assert!(daylight.abs_diff_eq(Vec3::ONE, 0.1));
+        assert!((warm.dot(Vec3::new(0.2126, 0.7152, 0.0722)) - 1.0).abs() < 1e-5);
+        assert_eq!(blackbody_rgb(500.0), blackbody_rgb(1667.0));
+        
+        let mut lux = LuxParams::default();
+        lux.set_input("inputs:color", &[1.0, 0.5, 0.5]);
+        assert_eq!(lux.tint(), Vec3::new(1.0, 0.5, 0.5));
+        lux.set_input("inputs:enableColorTemperature", &[1.0]);
+        lux.set_input("inputs:colorTemperature", &[3000.0]);
+        assert_eq!(lux.tint(), Vec3::new(1.0, 0.5, 0.5) * warm);
+        
+        // A 2 by 3 rect light gives off six times its luminance until normalized
+        lux.set_input("inputs:intensity", &[4.0]);
+        lux.set_input("inputs:exposure", &[1.0]);
+        lux.set_input("inputs:width", &[2.0]);
+        lux.set_input("inputs:height", &[3.0]);
+        assert_eq!(lux.viewport_intensity("rect"), 48.0);
+        assert_eq!(lux.viewport_intensity("distant"), 8.0);
+        lux.set_input("inputs:normalize", &[1.0]);
+        assert_eq!(lux.viewport_intensity("rect"), 8.0);
+        
+        assert_eq!(lux.cone_cosines(), None);
+        lux.set_input("inputs:shaping:cone:angle", &[60.0]);
+        lux.set_input("inputs:shaping:cone:softness", &[0.5]);
+        let (inner, outer) = lux.cone_cosines().unwrap();
+        assert!((inner - 30f32.to_radians().cos()).abs() < 1e-6 && (outer - 0.5).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,117 @@
+//! UsdShade material binding resolution
+//!
+//! A prim's material comes from `material:binding` relationships on the prim
+//! or its ancestors, either direct or through a collection. The nearest binding
+//! wins unless an ancestor's binding is `strongerThanDescendants`. Bindings for
+//! the requested purpose ("preview" in the viewport) are searched first, then
+//! all-purpose bindings.
+
+use std::collections::HashMap;
+use super::light_linking::LinkCollection;
+
+/// Purpose the viewport resolves bindings for
+pub const PREVIEW_PURPOSE: &str = "preview";
+
+/// bindMaterialAs metadata of a binding relationship
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BindingStrength {
+    #[default]
+    WeakerThanDescendants,
+    StrongerThanDescendants,
+}
+
+impl BindingStrength {
+    pub fn from_token(token: &str) -> Self {
+        match token {
+            "strongerThanDescendants" => BindingStrength::StrongerThanDescendants,
+            _ => BindingStrength::WeakerThanDescendants,
+        }
+    }
+}
+
+/// A material binding authored on a prim
+#[derive(Debug, Clone, PartialEq)]
+pub struct MaterialBinding {
+    pub material: String,
+    /// Material purpose, empty for all-purpose bindings
+    pub purpose: String,
+    pub strength: BindingStrength,
+    /// Collection the binding applies to, None for a direct binding of the prim's subtree
+    pub collection: Option<LinkCollection>,
+}
+
+impl MaterialBinding {
+    fn applies_to(&self, prim_path: &str) -> bool {
+        self.collection.as_ref().is_none_or(|collection| collection.contains(prim_path))
+    }
+}
+
+/// Material bound to a prim for a purpose, given the bindings authored on each prim
+///
+/// On one prim, collection bindings are considered in order before the direct
+/// binding, matching UsdShadeMaterialBindingAPI.
+pub fn resolve_binding<'a>(bindings: &'a HashMap<String, Vec<MaterialBinding>>, prim_path: &str, purpose: &str) -> Option<&'a str> {
+    let ancestors: Vec<&str> = prim_path.match_indices('/')
+        .map(|(index, _)| &prim_path[..index])
+        .filter(|ancestor| !ancestor.is_empty())
+        .chain(std::iter::once(prim_path))
+        .rev()
+        .collect();
+    
+    let purposes = if purpose.is_empty() { vec![""] } else { vec![purpose, ""] };
+    purposes.into_iter().find_map(|purpose| {
+        let mut winner: Option<&MaterialBinding> = None;
+        for ancestor in &ancestors {
+            let candidate = bindings.get(*ancestor)
+                .into_iter()
+                .flatten()
+                .filter(|binding| binding.purpose == purpose && binding.applies_to(prim_path))
+                .min_by_key(|binding| binding.collection.is_none());
+            if let Some(candidate) = candidate {
+                if winner.is_none() || candidate.strength == BindingStrength::StrongerThanDescendants {
+                    winner = Some(candidate);
+                }
+            }
+        }
+        winner.map(|binding| binding.material.as_str())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn direct(material: &str, purpose: &str, strength: BindingStrength) -> MaterialBinding {
+        MaterialBinding { material: material.to_string(), purpose: purpose.to_string(), strength, collection: None }
+    }
+    
+    #[test]
+    fn nearest_binding_wins_unless_an_ancestor_is_stronger() {
+        let mut bindings = HashMap::new();
+        bindings.insert("/World".to_string(), vec![direct("/Looks/Base", "", BindingStrength::WeakerThanDescendants)]);
+        bindings.insert("/World/Chair".to_string(), vec![
+            direct("/Looks/Wood", "", BindingStrength::WeakerThanDescendants),
+            direct("/Looks/WoodProxy", "preview", BindingStrength::WeakerThanDescendants),
+        ]);
+        bindings.insert("/World/Chair/Seat".to_string(), vec![MaterialBinding {
+            collection: Some(LinkCollection {
+                include_root: false,
+                includes: vec!["/World/Chair/Seat/Cushion".to_string()],
+                excludes: Vec::new(),
+            }),
+            ..direct("/Looks/Fabric", "", BindingStrength::WeakerThanDescendants)
+        }]);
+        
+        assert_eq!(resolve_binding(&bindings, "/World/Table", PREVIEW_PURPOSE), Some("/Looks/Base"));
+        assert_eq!(resolve_binding(&bindings, "/World/Chair/Leg", ""), Some("/Looks/Wood"));
+        assert_eq!(resolve_binding(&bindings, "/World/Chair/Leg", PREVIEW_PURPOSE), Some("/Looks/WoodProxy"));
+        assert_eq!(resolve_binding(&bindings, "/World/Chair/Seat/Cushion", ""), Some("/Looks/Fabric"));
+        assert_eq!(resolve_binding(&bindings, "/World/Chair/Seat/Frame", ""), Some("/Looks/Wood"));
+        assert_eq!(resolve_binding(&bindings, "/Other", ""), None);
+        
+        bindings.get_mut("/World").unwrap()[0].strength = BindingStrength::StrongerThanDescendants;
+        assert_eq!(resolve_binding(&bindings, "/World/Chair/Seat/Cushion", ""), Some("/Looks/Base"));
+        // A purpose-specific binding anywhere still beats all-purpose ones
+        assert_eq!(resolve_binding(&bindings, "/World/Chair/Leg", PREVIEW_PURPOSE), Some("/Looks/WoodProxy"));
+    }
+}
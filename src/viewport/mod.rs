@@ -4,22 +4,336 @@
 //! to the core. The core handles all egui and wgpu rendering.
 
 use nodle_plugin_sdk::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
+use glam::{Mat4, Vec2, Vec3, Vec4};
+use crate::core::profiling::{profile_scope, reset_profile, top_operations, total_bridge_time};
+use crate::core::usd_engine::{parse_numeric_value, with_usd_engine, USDDrawMode, USDXformOps, XFORM_OP_ORDER};
+use crate::core::usd_value::UsdValue;
+use crate::core::bounds::BoundingBox;
+use crate::core::tutorial::{record_step, TutorialStep};
+use crate::core::usdz::{self, PackagePath};
+use crate::capture::id_matte::IdMatte;
+use crate::modular::define_prim;
+use crate::ui::choice::{choice_buttons, parse_choice};
+use crate::ui::keyboard::{advertise_shortcuts, focus_marked, handle_key, Shortcut};
+use crate::ui::palette::{palette, set_palette, status_label, Palette};
+use crate::ui::search::{ParameterSearch, SEARCH_PARAMETER};
+use playback::{LoopMode, PlaybackState, FPS_PRESETS};
+use image_sequence::ImageSequence;
+use projection::{set_view_camera, xform_ops_to_mat4, ProjectionCamera, ProjectionSettings};
+use camera_recording::CameraRecorder;
+use navigation::{normalize_delta, pinch_zoom, Gesture, NavigationSmoothing, PAN_X_PARAMETER, PAN_Y_PARAMETER, PINCH_PARAMETER, ROTATE_PARAMETER};
+use draw_mode::{is_within, stand_ins, DrawMode};
+use picking::{click_select, pick_matrix, picked_faces, rect_matrix, resolve_faces, selected_prims, set_picked_faces, set_selected_prims, FaceDrag, FaceSelection, PickMode, PickRect};
+use highlight::{is_selected, outline_shell, HIGHLIGHT_MATERIAL, HIGHLIGHT_SUFFIX, OUTLINE_WIDTH};
+use gizmo::{pick_ray, GizmoDrag, GizmoFrame, GizmoMode, GizmoSpace, GIZMO_PREFIX};
+use snapping::{nearest_vertex, snap_orbit, SnapSettings};
+use panes::{PaneLayout, PaneView, ViewPane};
+use camera_response::{exposure_scale, CameraResponse};
+use ambient_occlusion::AmbientOcclusion;
+use path_tracer::PathTraceSettings;
+use antialiasing::AntiAliasing;
+use color_management::{ColorManagement, ViewTransform};
+use hydra::{delegate_settings, RenderBackend, RenderSettingValue};
+use scene_delegate::{with_scene_delegate, ExtractionSettings, SceneSink};
+use culling::{CullStats, FrustumCull};
+use usd_rendering::{CameraMode, ComplexityLevel, ShadingMode, USDCamera, USDGeometry, USDLight, USDMaterial};
+use snapshot::Snapshot;
+use primvars::PrimvarInfo;
+use crate::capture::aov::Aov;
+use instancing::InstanceBatch;
+use material_binding::MaterialBinding;
+use environment::Environment;
+use preview_surface::InputSource;
+use crate::ui::help::{NodeHelp, KITCHEN_SET, USD_WG_ASSETS};
+
+/// Help for the USD Viewport node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_Viewport",
+    summary: "3D viewport for visualizing USD stages with Maya-style navigation",
+    details: "Draws the connected stage with Alt+drag to orbit, pan and zoom, or on a trackpad or touchscreen by pinching, two-finger dragging and twisting; navigation is smoothed over time and display scale, with per-axis invert toggles. Shading, complexity, primvar display and the renderer are set in the panel: wgpu, or any installed Hydra render delegate such as Storm, Embree, Cycles or Karma, with that delegate's render settings. A Camera input, or picking one under Look Through, looks through a stage camera instead of the free camera until you navigate; Create Camera from View saves the free camera into the stage as a UsdGeomCamera, and Record Camera Path samples it at the playback rate while you navigate, authoring a flythrough as time samples on that camera. Looking through a camera exposes the lights by its exposure attributes, and Hydra frames also get its depth of field from fStop and focusDistance. Ambient Occlusion darkens creases and contact points of the wgpu scene, reaching Occlusion Radius scene units with Occlusion Intensity, so unlit or flat-lit stages still read. Final Quality Preview path traces the stage on the CPU with its materials and lights once the camera stops moving, refining up to Max Samples per pixel with Bounces of indirect light, and falls back to the wgpu scene as soon as you navigate; Denoise filters the grain of the first samples along the surfaces' edges, with Open Image Denoise in builds that include it. Anti-Aliasing draws the wgpu scene with 2x, 4x or 8x MSAA, or as many samples as the graphics device supports, falling back to FXAA on devices that can't multisample, or with FXAA alone. Shading is linear, with color textures decoded by their sourceColorSpace, and View Transform shows it on the display: sRGB clips highlights, while Filmic and ACES roll them off; Exposure brightens or darkens it in stops and Gamma adjusts the display gamma, for the wgpu scene and the path traced preview. Frame All and Frame Selected fit the stage's or the selected prim's bounding box in view; meshes outside the view are culled, with a count of drawn and culled meshes under Frustum Culling, and Bounds shading draws each mesh as its box. Shading also draws meshes Flat with face normals, as Wireframe edges alone, as Wireframe on Shaded with the authored polygons outlined over the shaded surfaces, or as Display Color in their displayColor instead of their materials; Display Primvar shows any color primvar of the stage that way instead. Material Preview shades the wgpu scene with its UsdPreviewSurface networks, textures and normal maps included. Models with a UsdGeomModelAPI draw mode of origin, bounds or cards are drawn as axes, boxes or texture cards instead of their geometry; Draw Mode of Selected authors one on the selected model. Clicking selects the prim under the cursor through an id buffer, shift-click adds or removes prims, and the selection is output with a flag set on the update after it changes. Selected prims are outlined in orange, and the selection is shared with the stage's Stage Inspectors and other viewports, so picking in one shows in all. Face Pick picks faces instead: clicking a face or dragging a rectangle replaces, adds to or removes from the stage's face selection, which Face Set nodes take with Use Viewport Selection. Gizmo shows translate arrows, rotate rings or scale handles on the selected prim; dragging a handle authors its translate, rotateXYZ and scale ops into the session layer, keyed at the current time if they are animated. Gizmo Space turns translate and rotate handles to world or the prim's local axes. Under Snapping, Grid Size rounds the translate values a drag moves, Snap to Vertices moves the prim along the dragged axis to the scene vertex under the cursor, Angle Step rounds rotations, and camera orbits with Snap Camera Orbit, and Scale Step rounds scale factors; zero steps snap nothing. Keyboard: A frames all, F frames the selection, Home resets the camera, Space plays or pauses, Shift+Space stops, and Q, W, E and R pick no gizmo, translate, rotate or scale; the keys are shown on their buttons. Layout Quad adds top, front and side panes around the perspective view, each with its own camera and display settings, for hosts that tile them; the axis cameras look down their axis through a long lens and pan and zoom without orbiting. Active Pane picks the pane clicks, gizmo drags, navigation and framing go to. Palette recolors selection outlines, gizmo axes and the status markers of every panel for deuteranopia, protanopia or tritanopia. Snapshot renders the view offscreen through the wgpu renderer at Width by Height, with the panel's shading, anti-aliasing, ambient occlusion, view transform and culling, to Snapshot Path, and outputs the files it wrote as Rendered Image; an .exr path carries the Depth, Normal and ID AOVs as depth.Z, N and Cryptomatte layers for Nuke, and ID Matte also writes a prim id matte with its manifest.",
+    ports: &[
+        ("Stage", "loaded_0"),
+        ("Camera", "/World/Cameras/shotCam"),
+        ("Time", "1001"),
+        ("Selected Prim", "/World/Kitchen/Table"),
+    ],
+    samples: &[KITCHEN_SET, USD_WG_ASSETS],
+};
+
+/// Parameter name prefix of a delegate render setting, followed by the setting's key
+const RENDER_SETTING_PREFIX: &str = "render_setting:";
+
+/// Parameter name prefix of a snapshot AOV toggle, followed by the AOV's label
+const SNAPSHOT_AOV_PREFIX: &str = "snapshot_aov:";
+
+/// Material id used for meshes shaded by the camera projection preview
+const PROJECTION_MATERIAL: &str = "usd_camera_projection";
+
+// Vertex layout, uniforms and the mesh, wireframe and line pipelines of the wgpu scene
+pub mod renderer_3d;
+
+// Free camera of the wgpu scene
+pub mod camera;
+
+// wgpu scene rendering: materials, lights, culling, shading modes and captures
+pub mod usd_rendering;
+
+// Renderer-agnostic stage extraction shared by the viewport, path tracer and scene query
+pub mod scene_delegate;
+
+// Polygon triangulation for USD mesh topology
+pub mod triangulation;
+
+// Catmull-Clark refinement for subdivision surfaces
+pub mod subdivision;
+
+// Animation playback clock
+pub mod playback;
+
+// UsdSkel linear blend skinning
+pub mod skinning;
+
+// Image sequences as animated textures
+pub mod image_sequence;
+
+// Point instancer expansion
+pub mod instancing;
+
+// Camera projection mapping preview
+pub mod projection;
+
+// UsdLux light linking masks
+pub mod light_linking;
+
+// UsdShade material binding resolution
+pub mod material_binding;
+
+// UsdPreviewSurface network evaluation
+pub mod preview_surface;
+
+// UsdUVTexture loading, GPU upload and the shared texture cache
+pub mod textures;
+
+// Dome light sky/ground ambient
+pub mod environment;
+
+// displayColor, color primvar, st and normal expansion for viewport display
+pub mod primvars;
+
+// MikkTSpace-style tangents for normal mapping
+pub mod tangents;
+
+// GPU triangle picking and the shared viewport face selection
+pub mod picking;
+
+// Hydra Storm rendering through UsdImagingGL
+pub mod hydra;
+
+// Free camera recording into animated cameras
+pub mod camera_recording;
+
+// Navigation input normalization and smoothing
+pub mod navigation;
+
+// UsdGeomModelAPI draw mode stand-ins
+pub mod draw_mode;
+
+// Selection outlines
+pub mod highlight;
+
+// Transform manipulator gizmos
+pub mod gizmo;
+
+// Grid, vertex and angle snapping of gizmos and camera orbits
+pub mod snapping;
+
+// Top, front and side panes of quad layouts
+pub mod panes;
+// Lens and exposure response of looked-through cameras
+pub mod camera_response;
+
+// UsdLux light parameters in viewport shading units
+pub mod lux;
+
+// Screen space ambient occlusion pass
+pub mod ambient_occlusion;
+
+// Progressive CPU path tracing for the final quality preview
+pub mod path_tracer;
+// Edge-aware denoising of the path traced preview
+pub mod denoise;
+
+// MSAA and FXAA of the wgpu viewport
+pub mod antialiasing;
+
+// View transforms, exposure and gamma from linear shading to the display
+pub mod color_management;
+
+// Edges drawn over shaded surfaces in wireframe on shaded
+pub mod wireframe_overlay;
+
+// Offscreen snapshots of the view through the wgpu renderer
+pub mod snapshot;
+
+// Frustum culling shared by the wgpu renderer and the live viewport
+pub mod culling;
 
 /// USD Viewport node - provides USD scene data for 3D visualization
 #[derive(Debug, Clone)]
 pub struct USDViewport {
     pub current_stage: String,
+    /// Engine identifier of the current stage
+    pub stage_id: String,
+    /// Engine revision of the current stage the scene was built from
+    pub stage_revision: u64,
     pub viewport_data: ViewportData,
     pub camera_settings: CameraSettings,
+    /// Camera deltas still being eased in
+    pub navigation: NavigationSmoothing,
+    /// Time code the scene is sampled at
+    pub time_code: f64,
+    /// Viewport playback controls
+    pub playback: PlaybackState,
+    /// Image sequence textures keyed by texture shader path
+    pub texture_sequences: Vec<(String, ImageSequence)>,
+    /// Textures embedded in USDZ packages, extracted to files, keyed by texture shader path
+    pub package_textures: Vec<(String, String)>,
+    /// Camera projection preview shading
+    pub projection: ProjectionSettings,
+    /// Most recently selected prim, clicked or set upstream, e.g. by the Stage Inspector
+    pub selected_prim: Option<String>,
+    /// Prims selected by clicking or upstream, most recent last
+    pub selected_prims: Vec<String>,
+    /// Last Selected Prim input, so the input only takes over when it changes
+    selection_input: Option<String>,
+    /// Selection changed by a click since the last process
+    selection_changed: bool,
+    /// Revision of the stage's shared selection last adopted or published
+    selection_revision: u64,
+    /// Manipulator drawn on the selected prim, None for no gizmo
+    pub gizmo_mode: Option<GizmoMode>,
+    /// Axes translate and rotate gizmos follow
+    pub gizmo_space: GizmoSpace,
+    /// Snapping of gizmo drags and camera orbits
+    pub snapping: SnapSettings,
+    /// Unsnapped camera orbit angles as (azimuth, elevation) while orbits snap
+    orbit_angles: Option<(f32, f32)>,
+    /// Transform ops of the selected prim, while a gizmo is shown
+    gizmo_ops: Option<USDXformOps>,
+    /// Placement the gizmo's handles were last built for
+    gizmo_frame: Option<GizmoFrame>,
+    /// Gizmo handle being dragged
+    gizmo_drag: Option<GizmoDrag>,
+    /// How drags and clicks pick faces into the stage's face selection, None to select prims
+    pub face_pick: Option<PickMode>,
+    /// Face pick rectangle being dragged
+    face_drag: Option<FaceDrag>,
+    /// Authored face of each triangle of the scene's meshes, keyed by mesh id
+    mesh_faces: HashMap<String, Vec<u32>>,
+    /// Palette selection outlines and gizmo handles were last colored in
+    palette: Palette,
+    /// Panes the host tiles, the perspective one showing the free camera
+    pub layout: PaneLayout,
+    /// Axis panes, built the first time a layout shows them
+    pub panes: Vec<ViewPane>,
+    /// Pane clicks, drags and navigation go to
+    pub active_pane: PaneView,
+    /// Pane `get_viewport_data` emits, picked by hosts tiling the layout; the active pane when unset
+    pub emitted_pane: Option<PaneView>,
+    /// Renderer picked in the panel, read by the host as the "renderer" parameter
+    pub renderer: RenderBackend,
+    /// Render settings of the Hydra delegate that differ from its defaults, as (key, value)
+    pub renderer_settings: Vec<(String, RenderSettingValue)>,
+    /// Ambient occlusion of the wgpu renderer, read by the host as the "ambient_occlusion", "ao_radius" and "ao_intensity" parameters
+    pub ambient_occlusion: AmbientOcclusion,
+    /// Path traced preview shown while the camera is idle, read by the host as the "path_trace", "path_trace_samples", "path_trace_bounces" and "path_trace_denoise" parameters
+    pub path_trace: PathTraceSettings,
+    /// Anti-aliasing of the wgpu renderer, read by the host as the "anti_aliasing" parameter
+    pub anti_aliasing: AntiAliasing,
+    /// Display view of the linear shading, read by the host as the "view_transform", "view_exposure" and "view_gamma" parameters
+    pub color_management: ColorManagement,
+    /// Offscreen snapshots of the view through the wgpu renderer, with its AOVs
+    pub snapshot: Snapshot,
+    /// Prim path "Create Camera from View" and camera recording author the camera at
+    pub view_camera_path: String,
+    /// Camera path being recorded, if recording
+    pub camera_recorder: Option<CameraRecorder>,
+    /// Stage camera the view looks through, None for the free camera
+    pub look_through: Option<String>,
+    /// Lens and exposure of the camera looked through
+    camera_response: Option<CameraResponse>,
+    /// Camera prims of the current stage, by path
+    pub stage_cameras: Vec<String>,
+    /// Last Camera input, so the input only takes over when it changes
+    camera_input: Option<String>,
+    /// Shading choice, read by the host as the "shading" parameter for the wgpu renderer's shading mode
+    pub shading: &'static str,
+    /// Color primvar drawn in place of materials, None to shade materials
+    pub display_primvar: Option<String>,
+    /// Subdivision refinement of catmullClark meshes, for the live scene and snapshots
+    pub complexity: ComplexityLevel,
+    /// Displayable primvars of the current stage's meshes, one per name, type and interpolation
+    pub primvars: Vec<PrimvarInfo>,
+    /// Models of the current stage drawn as stand-ins
+    pub draw_modes: Vec<USDDrawMode>,
+    /// Leave meshes outside the view frustum out of the drawn meshes
+    pub frustum_culling: bool,
+    /// World bounds of the scene's meshes keyed by mesh id
+    mesh_bounds: HashMap<String, BoundingBox>,
 }
 
+/// Look Through option of the free camera
+const FREE_CAMERA: &str = "Free Camera";
+
+/// Shading choices, as usd_rendering.rs `ShadingMode::label`s, and Bounds drawing every mesh as its bounding box
+const SHADING_MODES: [&str; 7] = ["Shaded", "Flat", "Wireframe", "Wireframe on Shaded", DISPLAY_COLOR_SHADING, "Material Preview", BOUNDS_SHADING];
+
+/// Shading choice drawing displayColor in place of materials
+const DISPLAY_COLOR_SHADING: &str = "Display Color";
+
+/// Shading choice drawing every mesh as its bounding box
+const BOUNDS_SHADING: &str = "Bounds";
+
+/// Display Primvar choice shading materials instead of a primvar
+const NO_PRIMVAR: &str = "None";
+
+/// Gizmo choice hiding the gizmo
+const NO_GIZMO: &str = "Off";
+
+/// Face Pick choice selecting prims instead of faces
+const NO_FACE_PICK: &str = "Off";
+
+/// Keyboard shortcuts of common viewport actions, by the panel buttons they press
+const VIEWPORT_SHORTCUTS: &[Shortcut] = &[
+    Shortcut { chord: "A", action: "frame_all" },
+    Shortcut { chord: "F", action: "frame_selected" },
+    Shortcut { chord: "Home", action: "reset_camera" },
+    Shortcut { chord: "Space", action: "play" },
+    Shortcut { chord: "Space", action: "pause" },
+    Shortcut { chord: "Shift+Space", action: "stop" },
+    Shortcut { chord: "Q", action: "gizmo_mode:Off" },
+    Shortcut { chord: "W", action: "gizmo_mode:Translate" },
+    Shortcut { chord: "E", action: "gizmo_mode:Rotate" },
+    Shortcut { chord: "R", action: "gizmo_mode:Scale" },
+];
+
 /// USD-specific camera settings
 #[derive(Debug, Clone)]
 pub struct CameraSettings {
     pub orbit_sensitivity: f32,
     pub pan_sensitivity: f32,
     pub zoom_sensitivity: f32,
+    /// Time constant in seconds navigation deltas are eased in over; zero applies them as they arrive
+    pub smoothing: f32,
+    /// Physical pixels per logical point of the display
+    pub display_scale: f32,
+    pub invert_orbit_x: bool,
+    pub invert_orbit_y: bool,
+    pub invert_pan_x: bool,
+    pub invert_pan_y: bool,
+    pub invert_zoom: bool,
 }
 
 impl Default for CameraSettings {
@@ -28,16 +342,94 @@ impl Default for CameraSettings {
             orbit_sensitivity: 0.5,
             pan_sensitivity: 1.0,
             zoom_sensitivity: 1.0,
+            smoothing: 0.05,
+            display_scale: 1.0,
+            invert_orbit_x: false,
+            invert_orbit_y: false,
+            invert_pan_x: false,
+            invert_pan_y: false,
+            invert_zoom: false,
         }
     }
 }
 
+impl CameraSettings {
+    /// Invert toggle stored under a parameter name
+    fn invert_mut(&mut self, name: &str) -> Option<&mut bool> {
+        match name {
+            "invert_orbit_x" => Some(&mut self.invert_orbit_x),
+            "invert_orbit_y" => Some(&mut self.invert_orbit_y),
+            "invert_pan_x" => Some(&mut self.invert_pan_x),
+            "invert_pan_y" => Some(&mut self.invert_pan_y),
+            "invert_zoom" => Some(&mut self.invert_zoom),
+            _ => None,
+        }
+    }
+}
+
+/// Invert toggles as (parameter, label)
+const INVERT_TOGGLES: [(&str, &str); 5] = [
+    ("invert_orbit_x", "Invert Orbit X"),
+    ("invert_orbit_y", "Invert Orbit Y"),
+    ("invert_pan_x", "Invert Pan X"),
+    ("invert_pan_y", "Invert Pan Y"),
+    ("invert_zoom", "Invert Zoom"),
+];
+
 impl Default for USDViewport {
     fn default() -> Self {
         Self {
             current_stage: String::new(),
+            stage_id: String::new(),
+            stage_revision: 0,
             viewport_data: ViewportData::default(),
             camera_settings: CameraSettings::default(),
+            navigation: NavigationSmoothing::default(),
+            time_code: 0.0,
+            playback: PlaybackState::default(),
+            texture_sequences: Vec::new(),
+            package_textures: Vec::new(),
+            projection: ProjectionSettings::default(),
+            selected_prim: None,
+            selected_prims: Vec::new(),
+            selection_input: None,
+            selection_changed: false,
+            selection_revision: 0,
+            gizmo_mode: None,
+            gizmo_space: GizmoSpace::default(),
+            snapping: SnapSettings::default(),
+            orbit_angles: None,
+            gizmo_ops: None,
+            gizmo_frame: None,
+            gizmo_drag: None,
+            face_pick: None,
+            face_drag: None,
+            mesh_faces: HashMap::new(),
+            palette: palette(),
+            layout: PaneLayout::default(),
+            panes: Vec::new(),
+            active_pane: PaneView::Perspective,
+            emitted_pane: None,
+            renderer: RenderBackend::Wgpu,
+            renderer_settings: Vec::new(),
+            ambient_occlusion: AmbientOcclusion::default(),
+            path_trace: PathTraceSettings::default(),
+            anti_aliasing: AntiAliasing::default(),
+            color_management: ColorManagement::default(),
+            snapshot: Snapshot::default(),
+            view_camera_path: "/World/Cameras/viewCam".to_string(),
+            camera_recorder: None,
+            look_through: None,
+            camera_response: None,
+            stage_cameras: Vec::new(),
+            camera_input: None,
+            shading: SHADING_MODES[0],
+            display_primvar: None,
+            complexity: ComplexityLevel::Medium,
+            primvars: Vec::new(),
+            draw_modes: Vec::new(),
+            frustum_culling: true,
+            mesh_bounds: HashMap::new(),
         }
     }
 }
@@ -45,115 +437,1072 @@ impl Default for USDViewport {
 impl USDViewport {
     /// Load USD stage and convert to scene data
     pub fn load_stage(&mut self, stage_path: &str) {
+        let _scope = profile_scope("viewport_load_stage");
         println!("USD Plugin: Loading stage: {}", stage_path);
+        if self.current_stage != stage_path {
+            self.current_stage = stage_path.to_string();
+            self.stage_id = with_usd_engine(|engine| engine.resolve_stage(stage_path))
+                .map(|stage| stage.identifier)
+                .unwrap_or_default();
+            self.find_texture_sequences();
+            self.find_package_textures();
+            record_step(&self.stage_id, TutorialStep::ViewStage);
+        }
         
-        // TODO: Implement actual USD stage loading
-        // For now, create a simple test scene
-        let mut scene = SceneData::default();
-        scene.name = format!("USD Stage: {}", stage_path);
-        
-        // Create a simple cube mesh as placeholder
-        let cube_mesh = MeshData {
-            id: "cube".to_string(),
-            vertices: vec![
-                // Front face
-                -1.0, -1.0,  1.0,
-                 1.0, -1.0,  1.0,
-                 1.0,  1.0,  1.0,
-                -1.0,  1.0,  1.0,
-                // Back face
-                -1.0, -1.0, -1.0,
-                -1.0,  1.0, -1.0,
-                 1.0,  1.0, -1.0,
-                 1.0, -1.0, -1.0,
-            ],
-            normals: vec![
-                // Front face
-                0.0, 0.0, 1.0,
-                0.0, 0.0, 1.0,
-                0.0, 0.0, 1.0,
-                0.0, 0.0, 1.0,
-                // Back face
-                0.0, 0.0, -1.0,
-                0.0, 0.0, -1.0,
-                0.0, 0.0, -1.0,
-                0.0, 0.0, -1.0,
-            ],
-            uvs: vec![
-                0.0, 0.0,
-                1.0, 0.0,
-                1.0, 1.0,
-                0.0, 1.0,
-                0.0, 0.0,
-                1.0, 0.0,
-                1.0, 1.0,
-                0.0, 1.0,
-            ],
-            indices: vec![
-                // Front face
-                0, 1, 2,   2, 3, 0,
-                // Back face
-                4, 5, 6,   6, 7, 4,
-                // Top face
-                3, 2, 6,   6, 5, 3,
-                // Bottom face
-                0, 4, 7,   7, 1, 0,
-                // Right face
-                1, 7, 6,   6, 2, 1,
-                // Left face
-                4, 0, 3,   3, 5, 4,
-            ],
-            material_id: Some("usd_material".to_string()),
-            transform: [
-                [1.0, 0.0, 0.0, 0.0],
-                [0.0, 1.0, 0.0, 0.0],
-                [0.0, 0.0, 1.0, 0.0],
-                [0.0, 0.0, 0.0, 1.0],
-            ],
+        // The same extraction the wgpu scene, path tracer and scene query draw from
+        let settings = ExtractionSettings { time_code: self.time_code, subdivision_level: self.complexity.subdivision_level(), display_primvar: self.display_primvar.clone() };
+        let mut sink = HostSceneSink {
+            display_colors: self.shading == DISPLAY_COLOR_SHADING || self.display_primvar.is_some(),
+            ..HostSceneSink::default()
         };
+        with_scene_delegate(|delegate| delegate.populate(&self.stage_id, &settings, &mut sink));
+        self.viewport_data.scene = sink.scene;
+        self.mesh_faces = sink.faces;
+        self.primvars = sink.primvars;
+        self.primvars.sort_by_key(PrimvarInfo::label);
+        self.primvars.dedup();
+        self.viewport_data.scene_dirty = true;
+        self.find_stage_cameras();
+        self.update_bounds();
+        for (shader_path, file) in &self.package_textures {
+            set_shader_texture(&mut self.viewport_data.scene.materials, shader_path, Some(file.clone()));
+        }
+        self.apply_texture_sequences();
+        self.apply_projection();
+        self.apply_highlight();
+        self.refresh_gizmo_target();
+        self.apply_look_through();
+    }
+    
+    /// Read a camera prim from the stage at the current time code
+    fn stage_camera(&self, camera_path: &str) -> Result<ProjectionCamera, String> {
+        let stage_path = self.current_stage.clone();
+        let time = self.time_code;
+        
+        with_usd_engine(|engine| {
+            let stage = engine.resolve_stage(&stage_path)?;
+            let read = |attr: &str| engine.evaluate_at_time(&stage.identifier, camera_path, attr, time)
+                .ok()
+                .and_then(|value| parse_numeric_value(&value));
+            let vec3 = |attr: &str, default: Vec3| read(attr)
+                .filter(|v| v.len() == 3)
+                .map(|v| Vec3::new(v[0] as f32, v[1] as f32, v[2] as f32))
+                .unwrap_or(default);
+            let scalar = |attr: &str, default: f32| read(attr)
+                .and_then(|v| v.first().copied())
+                .map(|v| v as f32)
+                .unwrap_or(default);
+            
+            let defaults = ProjectionCamera::default();
+            let clipping = read("clippingRange").filter(|v| v.len() == 2);
+            Ok(ProjectionCamera {
+                world: xform_ops_to_mat4(
+                    vec3("xformOp:translate", Vec3::ZERO),
+                    vec3("xformOp:rotateXYZ", Vec3::ZERO),
+                    vec3("xformOp:scale", Vec3::ONE),
+                ),
+                focal_length: scalar("focalLength", defaults.focal_length),
+                horizontal_aperture: scalar("horizontalAperture", defaults.horizontal_aperture),
+                vertical_aperture: scalar("verticalAperture", defaults.vertical_aperture),
+                near: clipping.as_ref().map_or(defaults.near, |c| c[0] as f32),
+                far: clipping.as_ref().map_or(defaults.far, |c| c[1] as f32),
+            })
+        })
+    }
+    
+    /// Lens and exposure response of a camera prim on the current stage, at the current time
+    fn stage_camera_response(&self, camera_path: &str) -> Result<CameraResponse, String> {
+        let stage_path = self.current_stage.clone();
+        let time = self.time_code;
         
-        scene.meshes.push(cube_mesh);
+        with_usd_engine(|engine| {
+            let stage = engine.resolve_stage(&stage_path)?;
+            let scalar = |attr: &str, default: f32| engine.evaluate_at_time(&stage.identifier, camera_path, attr, time)
+                .ok()
+                .and_then(|value| parse_numeric_value(&value))
+                .and_then(|v| v.first().copied())
+                .map(|v| v as f32)
+                .unwrap_or(default);
+            
+            let defaults = CameraResponse::default();
+            Ok(CameraResponse {
+                focal_length: scalar("focalLength", defaults.focal_length),
+                horizontal_aperture: scalar("horizontalAperture", defaults.horizontal_aperture),
+                f_stop: scalar("fStop", defaults.f_stop),
+                focus_distance: scalar("focusDistance", defaults.focus_distance),
+                exposure_scale: exposure_scale(
+                    scalar("exposure", 0.0),
+                    scalar("exposure:fStop", 1.0),
+                    scalar("exposure:time", 1.0),
+                    scalar("exposure:iso", 100.0),
+                    scalar("exposure:responsivity", 1.0),
+                ),
+            })
+        })
+    }
+    
+    /// Replace mesh UVs with coordinates projected from the projector camera
+    fn apply_projection(&mut self) {
+        if !self.projection.is_active() {
+            return;
+        }
+        let camera = match self.stage_camera(&self.projection.camera_path) {
+            Ok(camera) => camera,
+            Err(e) => {
+                eprintln!("USD Plugin: Camera projection disabled: {}", e);
+                return;
+            }
+        };
         
-        // Create a simple material
-        let material = MaterialData {
-            id: "usd_material".to_string(),
-            name: "USD Material".to_string(),
-            base_color: [0.7, 0.7, 0.9, 1.0],
+        // Sequences follow the viewport time code
+        let image = match ImageSequence::open(&self.projection.image) {
+            Some(sequence) => sequence.file_at(self.time_code).map(|path| path.to_string_lossy().to_string()),
+            None => Some(self.projection.image.clone()),
+        };
+        
+        let scene = &mut self.viewport_data.scene;
+        for mesh in &mut scene.meshes {
+            mesh.uvs = camera.project_vertices(&mesh.vertices, Mat4::from_cols_array_2d(&mesh.transform));
+            mesh.material_id = Some(PROJECTION_MATERIAL.to_string());
+        }
+        scene.materials.retain(|material| material.id != PROJECTION_MATERIAL);
+        scene.materials.push(MaterialData {
+            id: PROJECTION_MATERIAL.to_string(),
+            name: format!("Projection from {}", self.projection.camera_path),
+            base_color: [1.0, 1.0, 1.0, 1.0],
             metallic: 0.0,
-            roughness: 0.5,
+            roughness: 1.0,
             emission: [0.0, 0.0, 0.0],
-            diffuse_texture: None,
+            diffuse_texture: image,
             normal_texture: None,
             roughness_texture: None,
             metallic_texture: None,
+        });
+        self.viewport_data.scene_dirty = true;
+    }
+    
+    /// Whether meshes are drawn as their bounding boxes
+    pub fn display_bounds(&self) -> bool {
+        self.shading == BOUNDS_SHADING
+    }
+    
+    /// Rebuild the scene after projection or bounds display settings change
+    pub fn refresh_projection(&mut self) {
+        if !self.current_stage.is_empty() {
+            let camera = self.viewport_data.scene.camera.clone();
+            let stage_path = self.current_stage.clone();
+            self.load_stage(&stage_path);
+            self.viewport_data.scene.camera = camera;
+            self.apply_look_through();
+        }
+    }
+    
+    /// Collect texture shaders on the stage whose file names an image sequence
+    fn find_texture_sequences(&mut self) {
+        let stage_path = self.current_stage.clone();
+        self.texture_sequences = with_usd_engine(|engine| {
+            let Ok(stage) = engine.resolve_stage(&stage_path) else {
+                return Vec::new();
+            };
+            engine.get_stage_prims(&stage.identifier)
+                .into_iter()
+                .filter(|prim| prim.prim_type == "Shader")
+                .filter_map(|prim| {
+                    let file = engine.get_attribute(&stage.identifier, &prim.path, "inputs:file").ok()?;
+                    ImageSequence::open(&file).map(|sequence| (prim.path.clone(), sequence))
+                })
+                .collect()
+        });
+    }
+    
+    /// Collect the stage's camera prims for the Look Through selector
+    fn find_stage_cameras(&mut self) {
+        let stage_path = self.current_stage.clone();
+        self.stage_cameras = with_usd_engine(|engine| {
+            let Ok(stage) = engine.resolve_stage(&stage_path) else {
+                return Vec::new();
+            };
+            let mut cameras: Vec<String> = engine.get_stage_prims(&stage.identifier)
+                .into_iter()
+                .filter(|prim| prim.prim_type == "Camera")
+                .map(|prim| prim.path.clone())
+                .collect();
+            cameras.sort();
+            cameras
+        });
+    }
+    
+    /// Look through a stage camera, or the free camera with None
+    pub fn set_look_through(&mut self, camera: Option<String>) {
+        self.look_through = camera.filter(|path| !path.is_empty());
+        self.apply_look_through();
+    }
+    
+    /// Put the free camera where the looked-through stage camera is at the current time code
+    ///
+    /// The orbit distance is kept, so navigating afterwards continues from
+    /// the stage camera's view.
+    fn apply_look_through(&mut self) {
+        let Some(path) = self.look_through.clone() else {
+            return;
+        };
+        let camera = match self.stage_cameras.contains(&path) {
+            true => self.stage_camera(&path),
+            false => Err(format!("Stage has no camera '{}'", path)),
         };
+        match camera {
+            Ok(camera) => {
+                let view = &mut self.viewport_data.scene.camera;
+                let distance = (Vec3::from(view.target) - Vec3::from(view.position)).length().max(0.1);
+                let (eye, target, up) = camera.look_at(distance);
+                view.position = eye.into();
+                view.target = target.into();
+                view.up = up.into();
+                view.fov = camera.fov_y();
+                view.near = camera.near;
+                view.far = camera.far;
+                self.viewport_data.scene_dirty = true;
+                self.camera_response = self.stage_camera_response(&path).ok();
+            }
+            Err(e) => {
+                self.camera_response = None;
+                eprintln!("USD Plugin: Can't look through camera: {}", e);
+            }
+        }
+    }
+    
+    /// Bound the scene by the stage, swap in draw mode stand-ins and switch meshes to boxes in bounds shading
+    ///
+    /// The scene's bounding box falls back to the meshes' bounds when the
+    /// stage has no boundable prims.
+    fn update_bounds(&mut self) {
+        let (stage_bounds, draw_modes) = match self.stage_id.is_empty() {
+            true => (None, Vec::new()),
+            false => {
+                let (stage_id, time) = (self.stage_id.clone(), self.time_code);
+                let (bounds, draw_modes) = with_usd_engine(|engine| {
+                    (engine.stage_bounds(&stage_id, time), engine.get_draw_modes(&stage_id, time))
+                });
+                (
+                    bounds.map_err(|e| eprintln!("USD Plugin: Failed to compute stage bounds: {}", e)).ok(),
+                    draw_modes.unwrap_or_else(|e| {
+                        eprintln!("USD Plugin: Failed to read draw modes: {}", e);
+                        Vec::new()
+                    }),
+                )
+            }
+        };
+        self.draw_modes = draw_modes;
+        self.apply_draw_modes();
         
-        scene.materials.push(material);
-        
-        // Add a simple directional light
-        let light = LightData {
-            id: "sun".to_string(),
-            light_type: LightType::Directional,
-            position: [0.0, 10.0, 5.0],
-            direction: [-0.5, -1.0, -0.5],
-            color: [1.0, 1.0, 0.9],
-            intensity: 5.0,
-            range: 100.0,
-            spot_angle: 0.0,
+        self.mesh_bounds.clear();
+        let display_bounds = self.display_bounds();
+        let scene = &mut self.viewport_data.scene;
+        for mesh in &mut scene.meshes {
+            let local = BoundingBox::from_positions(&mesh.vertices);
+            if display_bounds {
+                (mesh.vertices, mesh.normals, mesh.indices) = local.box_geometry();
+                mesh.uvs = vec![0.0; mesh.vertices.len() / 3 * 2];
+            }
+            self.mesh_bounds.insert(mesh.id.clone(), local.transformed(&Mat4::from_cols_array_2d(&mesh.transform)));
+        }
+        let mesh_bounds = self.mesh_bounds.values().fold(BoundingBox::EMPTY, |all, bounds| all.union(*bounds));
+        scene.bounding_box = stage_bounds
+            .filter(|bounds| !bounds.is_empty())
+            .or(Some(mesh_bounds).filter(|bounds| !bounds.is_empty()))
+            .map(|bounds| bounds.to_arrays());
+    }
+    
+    /// Replace the meshes of models with a draw mode by their stand-ins, colored by drawModeColor
+    fn apply_draw_modes(&mut self) {
+        let scene = &mut self.viewport_data.scene;
+        let draw_modes = &self.draw_modes;
+        scene.meshes.retain(|mesh| !draw_modes.iter().any(|model| is_within(&model.prim_path, &mesh.id)));
+        for model in draw_modes {
+            for (index, stand_in) in stand_ins(model).into_iter().enumerate() {
+                let id = format!("{}:drawMode:{}", model.prim_path, index);
+                let [r, g, b] = match stand_in.texture {
+                    Some(_) => [1.0; 3],
+                    None => model.color,
+                };
+                scene.materials.push(MaterialData {
+                    id: id.clone(),
+                    name: format!("{} ({})", model.prim_path, model.mode),
+                    base_color: [r, g, b, 1.0],
+                    metallic: 0.0,
+                    roughness: 1.0,
+                    emission: [0.0, 0.0, 0.0],
+                    diffuse_texture: stand_in.texture,
+                    normal_texture: None,
+                    roughness_texture: None,
+                    metallic_texture: None,
+                });
+                scene.meshes.push(MeshData {
+                    id: id.clone(),
+                    vertices: stand_in.positions,
+                    normals: stand_in.normals,
+                    uvs: stand_in.uvs,
+                    indices: stand_in.indices,
+                    material_id: Some(id),
+                    transform: model.transform.to_cols_array_2d(),
+                });
+            }
+        }
+    }
+    
+    /// Author a draw mode on the selected model and redraw, keeping the view
+    pub fn set_selected_draw_mode(&mut self, mode: DrawMode) -> Result<(), String> {
+        let prim_path = self.selected_prim.clone().ok_or_else(|| "No prim selected".to_string())?;
+        let stage_id = self.stage_id.clone();
+        self.stage_revision = with_usd_engine(|engine| -> Result<u64, String> {
+            engine.set_draw_mode(&stage_id, &prim_path, mode.token())?;
+            Ok(engine.stage_revision(&stage_id))
+        })?;
+        self.refresh_projection();
+        Ok(())
+    }
+    
+    /// Prim drawn at pixel `(x, y)` of a `width` x `height` view, if any
+    ///
+    /// Draws the scene's meshes into a one-pixel id buffer zoomed onto the
+    /// clicked pixel, keeping the nearest; draw mode stand-ins pick their model.
+    pub fn pick_prim(&self, x: f32, y: f32, width: f32, height: f32) -> Option<String> {
+        let view_to_pick = pick_matrix(x, y, width.max(1.0), height.max(1.0)) * self.active_view().view_projection();
+        let meshes = &self.viewport_data.scene.meshes;
+        let mut matte = IdMatte::new(1, 1);
+        for (index, mesh) in meshes.iter().enumerate().filter(|(_, mesh)| !mesh.id.starts_with(GIZMO_PREFIX)) {
+            let points: Vec<Vec3> = mesh.vertices.chunks_exact(3).map(Vec3::from_slice).collect();
+            matte.draw_mesh(&points, &mesh.indices, view_to_pick * Mat4::from_cols_array_2d(&mesh.transform), index as u32 + 1);
+        }
+        let mesh = meshes.get((matte.id_at(0, 0) as usize).checked_sub(1)?)?;
+        let prim_path = mesh.id.split(':').next().unwrap_or_default();
+        prim_path.starts_with('/').then(|| prim_path.to_string())
+    }
+    
+    /// Authored faces drawn inside a pixel rectangle of a `width` x `height` view
+    ///
+    /// Draws each triangle of the scene's meshes with an id of its own into an
+    /// id buffer covering the rectangle, keeping the nearest; instances pick
+    /// faces of their prototype, and meshes without authored faces, like draw
+    /// mode stand-ins, only hide what is behind them.
+    pub fn faces_in(&self, rect: PickRect, width: f32, height: f32) -> FaceSelection {
+        let (width, height) = (width.max(1.0), height.max(1.0));
+        let Some(rect) = rect.clamped(width as u32, height as u32) else {
+            return FaceSelection::new();
         };
+        let view_to_rect = rect_matrix(rect, width, height) * self.active_view().view_projection();
+        let meshes = &self.viewport_data.scene.meshes;
+        let mut matte = IdMatte::new(rect.width, rect.height);
+        let mut triangles = Vec::new();
+        for (index, mesh) in meshes.iter().enumerate().filter(|(_, mesh)| !mesh.id.starts_with(GIZMO_PREFIX) && !mesh.id.ends_with(HIGHLIGHT_SUFFIX)) {
+            let points: Vec<Vec3> = mesh.vertices.chunks_exact(3).map(Vec3::from_slice).collect();
+            let object_to_rect = view_to_rect * Mat4::from_cols_array_2d(&mesh.transform);
+            for (triangle, corners) in mesh.indices.chunks_exact(3).enumerate() {
+                let Some(corners) = corners.iter().map(|corner| points.get(*corner as usize).copied()).collect::<Option<Vec<Vec3>>>() else {
+                    continue;
+                };
+                triangles.push([index as u32 + 1, triangle as u32 + 1]);
+                matte.draw_mesh(&corners, &[0, 1, 2], object_to_rect, triangles.len() as u32);
+            }
+        }
+        let pixels: Vec<[u32; 2]> = matte.ids.iter()
+            .filter_map(|id| triangles.get((*id as usize).checked_sub(1)?).copied())
+            .collect();
+        resolve_faces(&pixels, |mesh, triangle| {
+            let mesh = meshes.get(mesh as usize)?;
+            let face = *self.mesh_faces.get(&mesh.id)?.get(triangle as usize)?;
+            let prim_path = mesh.id.split(':').next()?;
+            Some((prim_path.to_string(), face))
+        })
+    }
+    
+    /// Pick the faces inside a pixel rectangle into the stage's face selection
+    ///
+    /// The selection is shared with the stage's Face Set nodes, which read it
+    /// through "Use Viewport Selection".
+    pub fn pick_faces(&mut self, rect: PickRect, width: f32, height: f32, mode: PickMode) {
+        if self.stage_id.is_empty() {
+            return;
+        }
+        let picked = self.faces_in(rect, width, height);
+        let mut selection = picked_faces(&self.stage_id);
+        mode.apply(&mut selection, picked);
+        set_picked_faces(&self.stage_id, selection);
+    }
+    
+    /// Start a face pick rectangle at pixel `(x, y)`, returning whether face picking is on
+    pub fn begin_face_drag(&mut self, x: f32, y: f32, width: f32, height: f32) -> bool {
+        self.face_drag = self.face_pick.map(|_| FaceDrag { start: (x, y), end: (x, y), width, height });
+        self.face_drag.is_some()
+    }
+    
+    /// Pick the faces inside the dragged rectangle, if one was being dragged
+    pub fn end_face_drag(&mut self) {
+        if let (Some(drag), Some(mode)) = (self.face_drag.take(), self.face_pick) {
+            self.pick_faces(drag.rect(), drag.width, drag.height, mode);
+        }
+    }
+    
+    /// Select the prim under a click, toggling it in or out of the selection with `extend`
+    pub fn click_select(&mut self, x: f32, y: f32, width: f32, height: f32, extend: bool) {
+        let picked = self.pick_prim(x, y, width, height);
+        if click_select(&mut self.selected_prims, picked, extend) {
+            self.select(self.selected_prims.clone());
+        }
+    }
+    
+    /// Replace the selection, publishing it to the stage's other viewports and Stage Inspectors
+    pub fn select(&mut self, prims: Vec<String>) {
+        self.selected_prim = prims.last().cloned();
+        self.selected_prims = prims;
+        self.selection_changed = true;
+        if !self.stage_id.is_empty() {
+            self.selection_revision = set_selected_prims(&self.stage_id, &self.selected_prims);
+        }
+        self.apply_highlight();
+        self.refresh_gizmo_target();
+    }
+    
+    /// Adopt a selection made in another viewport or a Stage Inspector of the stage
+    pub fn sync_selection(&mut self) {
+        if self.stage_id.is_empty() {
+            return;
+        }
+        let (revision, prims) = selected_prims(&self.stage_id);
+        if revision != self.selection_revision {
+            self.selection_revision = revision;
+            self.selected_prim = prims.last().cloned();
+            self.selected_prims = prims;
+            self.selection_changed = true;
+            self.apply_highlight();
+            self.refresh_gizmo_target();
+        }
+    }
+    
+    /// Outline the meshes of selected prims, replacing earlier outlines
+    ///
+    /// Outlines share their mesh's bounds, so they are culled with it.
+    fn apply_highlight(&mut self) {
+        let is_outline = |mesh: &MeshData| mesh.id.ends_with(HIGHLIGHT_SUFFIX);
+        let scene = &mut self.viewport_data.scene;
+        scene.meshes.retain(|mesh| !is_outline(mesh));
+        scene.materials.retain(|material| material.id != HIGHLIGHT_MATERIAL);
+        self.mesh_bounds.retain(|id, _| !id.ends_with(HIGHLIGHT_SUFFIX));
+        self.viewport_data.scene_dirty = true;
+        if self.selected_prims.is_empty() {
+            return;
+        }
         
-        scene.lights.push(light);
+        let outlines: Vec<MeshData> = scene.meshes.iter()
+            .filter(|mesh| is_selected(&self.selected_prims, &mesh.id))
+            .filter_map(|mesh| {
+                let shell = outline_shell(&mesh.vertices, &mesh.normals, &mesh.indices, OUTLINE_WIDTH)?;
+                Some(MeshData {
+                    id: format!("{}{}", mesh.id, HIGHLIGHT_SUFFIX),
+                    uvs: vec![0.0; shell.positions.len() / 3 * 2],
+                    vertices: shell.positions,
+                    normals: shell.normals,
+                    indices: shell.indices,
+                    material_id: Some(HIGHLIGHT_MATERIAL.to_string()),
+                    transform: mesh.transform,
+                })
+            })
+            .collect();
+        for outline in &outlines {
+            if let Some(bounds) = outline.id.strip_suffix(HIGHLIGHT_SUFFIX).and_then(|id| self.mesh_bounds.get(id)).copied() {
+                self.mesh_bounds.insert(outline.id.clone(), bounds);
+            }
+        }
+        let color = self.palette.colors().selection;
+        let [r, g, b] = color;
+        scene.materials.push(MaterialData {
+            id: HIGHLIGHT_MATERIAL.to_string(),
+            name: "Selection Highlight".to_string(),
+            base_color: [r, g, b, 1.0],
+            metallic: 0.0,
+            roughness: 1.0,
+            emission: color,
+            diffuse_texture: None,
+            normal_texture: None,
+            roughness_texture: None,
+            metallic_texture: None,
+        });
+        scene.meshes.extend(outlines);
+    }
+    
+    /// Recolor selection outlines and gizmo handles if the palette changed
+    pub fn apply_palette(&mut self) {
+        if palette() != self.palette {
+            self.palette = palette();
+            self.apply_highlight();
+            self.rebuild_gizmo();
+        }
+    }
+    
+    /// Read the transform ops of the selected prim the gizmo edits, and rebuild its handles
+    pub fn refresh_gizmo_target(&mut self) {
+        self.gizmo_ops = match (self.gizmo_mode, &self.selected_prim) {
+            (Some(_), Some(prim_path)) if !self.stage_id.is_empty() => {
+                with_usd_engine(|engine| engine.get_xform_ops(&self.stage_id, prim_path, self.time_code))
+                    .map_err(|e| eprintln!("USD Plugin: No gizmo for {}: {}", prim_path, e))
+                    .ok()
+            }
+            _ => None,
+        };
+        self.rebuild_gizmo();
+    }
+    
+    /// Placement of the gizmo for the current view, None when no gizmo is shown
+    fn gizmo_placement(&self) -> Option<GizmoFrame> {
+        let eye = self.view_camera().world.transform_point3(Vec3::ZERO);
+        Some(GizmoFrame::new(self.gizmo_ops.as_ref()?, self.gizmo_mode?, self.gizmo_space, eye))
+    }
+    
+    /// Rebuild the gizmo's handles if the prim or the view moved, keeping them a steady size on screen
+    pub fn apply_gizmo(&mut self) {
+        if self.gizmo_placement() != self.gizmo_frame {
+            self.rebuild_gizmo();
+        }
+    }
+    
+    /// Replace the gizmo's handles with ones at the selected prim
+    fn rebuild_gizmo(&mut self) {
+        let frame = self.gizmo_placement();
+        self.gizmo_frame = frame;
+        let scene = &mut self.viewport_data.scene;
+        scene.meshes.retain(|mesh| !mesh.id.starts_with(GIZMO_PREFIX));
+        scene.materials.retain(|material| !material.id.starts_with(GIZMO_PREFIX));
+        self.viewport_data.scene_dirty = true;
+        let (Some(mode), Some(frame)) = (self.gizmo_mode, frame) else {
+            return;
+        };
         
-        // Set scene bounding box
-        scene.bounding_box = Some(([-1.0, -1.0, -1.0], [1.0, 1.0, 1.0]));
+        let active = self.gizmo_drag.map(|drag| drag.axis);
+        let colors = self.palette.colors();
+        for (axis, handle) in frame.handles(mode) {
+            let id = format!("{}{}", GIZMO_PREFIX, axis);
+            let color = if active == Some(axis) { colors.active } else { colors.axes[axis] };
+            let [r, g, b] = color;
+            scene.materials.push(MaterialData {
+                id: id.clone(),
+                name: format!("Gizmo {}", ["X", "Y", "Z"][axis]),
+                base_color: [r, g, b, 1.0],
+                metallic: 0.0,
+                roughness: 1.0,
+                emission: color,
+                diffuse_texture: None,
+                normal_texture: None,
+                roughness_texture: None,
+                metallic_texture: None,
+            });
+            scene.meshes.push(MeshData {
+                id: id.clone(),
+                uvs: vec![0.0; handle.positions.len() / 3 * 2],
+                vertices: handle.positions,
+                normals: handle.normals,
+                indices: handle.indices,
+                material_id: Some(id),
+                transform: Mat4::IDENTITY.to_cols_array_2d(),
+            });
+        }
+    }
+    
+    /// Grab the gizmo handle under pixel `(x, y)` of a `width` x `height` view, returning whether one was grabbed
+    pub fn begin_gizmo_drag(&mut self, x: f32, y: f32, width: f32, height: f32) -> bool {
+        let (Some(mode), Some(ops), Some(frame)) = (self.gizmo_mode, self.gizmo_ops, self.gizmo_frame) else {
+            return false;
+        };
+        let (origin, direction) = pick_ray(&self.active_view(), x, y, width, height);
+        self.gizmo_drag = GizmoDrag::begin(frame, mode, ops, origin, direction);
+        // Redraw the grabbed handle in the active color
+        self.rebuild_gizmo();
+        self.gizmo_drag.is_some()
+    }
+    
+    /// Drag the grabbed handle to pixel `(x, y)`, authoring the selected prim's new ops at the current time code
+    ///
+    /// Edits bump the stage revision, so the scene reloads on the next process.
+    pub fn drag_gizmo(&mut self, x: f32, y: f32, width: f32, height: f32) {
+        let (Some(drag), Some(prim_path)) = (self.gizmo_drag, self.selected_prim.clone()) else {
+            return;
+        };
+        let (origin, direction) = pick_ray(&self.active_view(), x, y, width, height);
+        // Vertices of the selected prims' own meshes would drag it toward itself
+        let vertex = (self.snapping.vertex && drag.mode == GizmoMode::Translate).then(|| {
+            let meshes = self.viewport_data.scene.meshes.iter()
+                .filter(|mesh| !mesh.id.starts_with(GIZMO_PREFIX) && !is_selected(&self.selected_prims, &mesh.id))
+                .map(|mesh| (mesh.vertices.as_slice(), Mat4::from_cols_array_2d(&mesh.transform)));
+            nearest_vertex(meshes, origin, direction)
+        }).flatten();
+        let Some(ops) = drag.update(origin, direction, &self.snapping, vertex).filter(|ops| Some(*ops) != self.gizmo_ops) else {
+            return;
+        };
+        match with_usd_engine(|engine| engine.set_xform_ops(&self.stage_id, &prim_path, &ops, self.time_code)) {
+            Ok(()) => {
+                self.gizmo_ops = Some(ops);
+                self.apply_gizmo();
+            }
+            Err(e) => eprintln!("USD Plugin: Failed to transform {}: {}", prim_path, e),
+        }
+    }
+    
+    /// Release the grabbed gizmo handle
+    pub fn end_gizmo_drag(&mut self) {
+        if self.gizmo_drag.take().is_some() {
+            self.rebuild_gizmo();
+        }
+    }
+    
+    /// Frustum culling of the perspective view, from the free or looked-through camera
+    fn frustum_cull(&self) -> FrustumCull {
+        FrustumCull::new(self.view_camera().view_projection(), self.frustum_culling)
+    }
+    
+    /// Meshes of the perspective view drawn and culled by frustum culling
+    pub fn cull_stats(&self) -> CullStats {
+        let mut cull = self.frustum_cull();
+        for mesh in &self.viewport_data.scene.meshes {
+            cull.draws(self.mesh_bounds.get(&mesh.id));
+        }
+        cull.stats()
+    }
+    
+    /// Frame the selected prim, or the whole stage without a selection or when `selected` is false
+    pub fn frame(&mut self, selected: bool) -> Result<(), String> {
+        let bounds = match self.selected_prim.clone().filter(|_| selected) {
+            Some(prim_path) => {
+                let (stage_id, time) = (self.stage_id.clone(), self.time_code);
+                with_usd_engine(|engine| engine.compute_bounds(&stage_id, &prim_path, time))?.world
+            }
+            None => self.viewport_data.scene.bounding_box
+                .map_or(BoundingBox::EMPTY, |(min, max)| BoundingBox::new(min.into(), max.into())),
+        };
+        if bounds.is_empty() {
+            return Err("Nothing to frame".to_string());
+        }
+        if selected && self.selected_prim.is_some() {
+            record_step(&self.stage_id, TutorialStep::FrameSelection);
+        }
+        if let Some(pane) = self.panes.iter_mut().find(|pane| pane.view == self.active_pane) {
+            pane.fit(bounds.center(), bounds.radius());
+            self.viewport_data.scene_dirty = true;
+            return Ok(());
+        }
+        self.frame_bounds(bounds);
+        Ok(())
+    }
+    
+    /// Move the free camera back along its view direction until the bounds' sphere fills the view
+    fn frame_bounds(&mut self, bounds: BoundingBox) {
+        self.look_through = None;
+        self.navigation = NavigationSmoothing::default();
+        let camera = &mut self.viewport_data.scene.camera;
+        // Fit the narrower of the vertical and horizontal fields of view
+        let half_fov = ((camera.fov * 0.5).tan() * camera.aspect.min(1.0)).atan();
+        let radius = bounds.radius().max(1e-3);
+        let distance = radius / half_fov.sin();
+        let direction = (Vec3::from(camera.position) - Vec3::from(camera.target)).try_normalize().unwrap_or(Vec3::Z);
+        camera.target = bounds.center().into();
+        camera.position = (bounds.center() + direction * distance).into();
+        camera.far = camera.far.max(distance + radius);
+        self.viewport_data.scene_dirty = true;
+        self.track_camera();
+    }
+    
+    /// Extract texture shader files that live inside a USDZ package
+    ///
+    /// Covers explicit `@file.usdz[textures/x.png]@` paths as well as relative
+    /// paths authored in a stage opened from a package.
+    fn find_package_textures(&mut self) {
+        let stage_path = self.current_stage.clone();
+        self.package_textures = with_usd_engine(|engine| {
+            let Ok(stage) = engine.resolve_stage(&stage_path) else {
+                return Vec::new();
+            };
+            engine.get_stage_prims(&stage.identifier)
+                .into_iter()
+                .filter(|prim| prim.prim_type == "Shader")
+                .filter_map(|prim| {
+                    let file = engine.get_attribute(&stage.identifier, &prim.path, "inputs:file").ok()?;
+                    let asset = PackagePath::resolve(&file, &stage.path)?;
+                    match usdz::extract_texture(&asset) {
+                        Ok(extracted) => Some((prim.path.clone(), extracted.to_string_lossy().to_string())),
+                        Err(e) => {
+                            eprintln!("USD Plugin: Skipping embedded texture of '{}': {}", prim.path, e);
+                            None
+                        }
+                    }
+                })
+                .collect()
+        });
+    }
+    
+    /// Point sequence textures at the file for the current time code
+    fn apply_texture_sequences(&mut self) {
+        for (shader_path, sequence) in &self.texture_sequences {
+            let file = sequence.file_at(self.time_code).map(|path| path.to_string_lossy().to_string());
+            set_shader_texture(&mut self.viewport_data.scene.materials, shader_path, file);
+        }
+    }
+    
+    /// Read the playback range from the stage's startTimeCode/endTimeCode
+    pub fn refresh_time_range(&mut self) {
+        let stage_path = self.current_stage.clone();
+        let range = with_usd_engine(|engine| {
+            engine.resolve_stage(&stage_path)
+                .and_then(|stage| engine.get_stage_time_range(&stage.identifier))
+        });
+        match range {
+            Ok(range) => {
+                self.playback.set_range(range.start_time_code, range.end_time_code);
+                self.playback.fps = range.time_codes_per_second;
+            }
+            Err(e) => eprintln!("USD Plugin: Failed to read time range: {}", e),
+        }
+    }
+    
+    /// Advance playback and re-sample the scene when the frame changes
+    pub fn tick_playback(&mut self) {
+        if self.playback.tick(std::time::Instant::now()) {
+            self.set_time(self.playback.current_time_code);
+        }
+    }
+    
+    /// Move to a new time code and re-sample animated stage data
+    pub fn set_time(&mut self, time_code: f64) {
+        if time_code == self.time_code {
+            return;
+        }
+        self.time_code = time_code;
         
-        self.viewport_data.scene = scene;
+        if !self.current_stage.is_empty() {
+            // Keep the user's view while scrubbing
+            let camera = self.viewport_data.scene.camera.clone();
+            let stage_path = self.current_stage.clone();
+            self.load_stage(&stage_path);
+            self.viewport_data.scene.camera = camera;
+            self.apply_look_through();
+        }
+    }
+    
+    /// Switch the renderer, dropping the previous delegate's render settings
+    pub fn set_renderer(&mut self, renderer: RenderBackend) {
+        if renderer != self.renderer {
+            self.renderer_settings.clear();
+            self.renderer = renderer;
+        }
+    }
+    
+    /// Current value of a render setting of the active delegate
+    pub fn renderer_setting(&self, key: &str) -> Option<&RenderSettingValue> {
+        self.renderer_settings.iter().find(|(existing, _)| existing == key).map(|(_, value)| value)
+    }
+    
+    /// Set a render setting of the active delegate from a panel value, typed like the setting's default
+    pub fn set_renderer_setting(&mut self, key: &str, value: &NodeData) -> Result<(), String> {
+        let RenderBackend::Hydra(delegate) = &self.renderer else {
+            return Err("The wgpu renderer has no render settings".to_string());
+        };
+        let setting = delegate_settings(delegate)?
+            .into_iter()
+            .find(|setting| setting.key == key)
+            .ok_or_else(|| format!("Render delegate '{}' has no setting '{}'", delegate, key))?;
+        let value = match value.as_boolean() {
+            Some(flag) => RenderSettingValue::Flag(flag),
+            None => RenderSettingValue::parse(setting.default.kind(), value.as_string().unwrap_or_default())?,
+        };
+        
+        self.renderer_settings.retain(|(existing, _)| existing != key);
+        if value != setting.default {
+            self.renderer_settings.push((key.to_string(), value));
+        }
+        Ok(())
+    }
+    
+    /// The free camera in UsdGeomCamera terms
+    fn view_camera(&self) -> ProjectionCamera {
+        projection_camera(&self.viewport_data.scene.camera)
+    }
+    
+    /// The active pane's camera in UsdGeomCamera terms
+    fn active_view(&self) -> ProjectionCamera {
+        let pane = self.panes.iter().find(|pane| pane.view == self.active_pane);
+        projection_camera(pane.map_or(&self.viewport_data.scene.camera, |pane| &pane.camera))
+    }
+    
+    /// Center and radius of the scene's bounds, a unit sphere at the origin without any
+    fn scene_sphere(&self) -> (Vec3, f32) {
+        match self.viewport_data.scene.bounding_box {
+            Some((min, max)) => {
+                let bounds = BoundingBox::new(min.into(), max.into());
+                (bounds.center(), bounds.radius())
+            }
+            None => (Vec3::ZERO, 1.0),
+        }
+    }
+    
+    /// Switch pane layouts, building axis panes around the scene the first time they are shown
+    ///
+    /// New panes start with the perspective pane's display settings. The
+    /// perspective pane becomes active if the active pane isn't shown.
+    pub fn set_layout(&mut self, layout: PaneLayout) {
+        let (center, radius) = self.scene_sphere();
+        for &view in layout.views() {
+            if !self.panes.iter().any(|pane| pane.view == view) {
+                self.panes.extend(ViewPane::new(view, self.viewport_data.settings.clone(), center, radius));
+            }
+        }
+        self.layout = layout;
+        if !layout.views().contains(&self.active_pane) {
+            self.active_pane = PaneView::Perspective;
+        }
+        self.emitted_pane = self.emitted_pane.filter(|view| layout.views().contains(view));
         self.viewport_data.scene_dirty = true;
-        self.current_stage = stage_path.to_string();
+    }
+    
+    /// Fit every axis pane to the scene, e.g. for a newly connected stage
+    pub fn fit_panes(&mut self) {
+        let (center, radius) = self.scene_sphere();
+        for pane in &mut self.panes {
+            pane.fit(center, radius);
+        }
+    }
+    
+    /// Viewport data of a pane, that of the perspective pane for views without one
+    pub fn pane_data(&self, view: PaneView) -> ViewportData {
+        match self.panes.iter().find(|pane| pane.view == view) {
+            // Culling follows the perspective camera, so axis panes draw every mesh
+            Some(pane) => pane.viewport_data(&self.viewport_data),
+            None => {
+                let mut data = self.viewport_data.clone();
+                // Culled meshes are left out of the draws without marking the scene dirty
+                let mut cull = self.frustum_cull();
+                data.scene.meshes.retain(|mesh| cull.draws(self.mesh_bounds.get(&mesh.id)));
+                // Looking through a camera exposes the lights as the camera would
+                if let Some(response) = self.camera_response.filter(|_| self.look_through.is_some()) {
+                    for light in &mut data.scene.lights {
+                        light.intensity *= response.exposure_scale;
+                    }
+                }
+                data
+            }
+        }
+    }
+    
+    /// Define the camera at `view_camera_path` with translate and rotateXYZ ops and author its attributes
+    ///
+    /// Values go to the stage's edit target, overwriting an existing camera's.
+    fn author_view_camera(&self, attributes: Vec<(&str, UsdValue)>) -> Result<String, String> {
+        let path = self.view_camera_path.trim();
+        if !path.starts_with('/') {
+            return Err(format!("Camera path '{}' is not an absolute prim path", path));
+        }
+        let stage_path = self.current_stage.clone();
+        let stage_id = with_usd_engine(|engine| engine.resolve_stage(&stage_path))?.identifier;
+        
+        define_prim(&stage_id, path, "Camera", attributes)?;
+        with_usd_engine(|engine| -> Result<(), String> {
+            engine.add_xform_op(&stage_id, path, XFORM_OP_ORDER[0])?;
+            engine.add_xform_op(&stage_id, path, XFORM_OP_ORDER[1])
+        })?;
+        Ok(path.to_string())
+    }
+    
+    /// Author a UsdGeomCamera matching the free camera
+    ///
+    /// Transform, focal length (from the vertical field of view) and clipping
+    /// range are written at the default time.
+    pub fn create_camera_from_view(&self) -> Result<String, String> {
+        let camera = self.view_camera();
+        let (translate, rotate) = camera.xform_ops();
+        let vec3 = |v: Vec3| UsdValue::Vec3([v.x as f64, v.y as f64, v.z as f64]);
+        let path = self.author_view_camera(vec![
+            ("focalLength", UsdValue::Float(camera.focal_length)),
+            ("horizontalAperture", UsdValue::Float(camera.horizontal_aperture)),
+            ("verticalAperture", UsdValue::Float(camera.vertical_aperture)),
+            ("clippingRange", UsdValue::Vec2([camera.near as f64, camera.far as f64])),
+            (XFORM_OP_ORDER[0], vec3(translate)),
+            (XFORM_OP_ORDER[1], vec3(rotate)),
+        ])?;
+        println!("✓ Created camera '{}' from the viewport view", path);
+        Ok(path)
+    }
+    
+    /// Render the view through the wgpu renderer and write it with the snapshot's AOVs
+    ///
+    /// The renderer takes the panel's shading, anti-aliasing, ambient occlusion,
+    /// color management and culling, and the camera the view looks through.
+    pub fn render_snapshot(&mut self) -> Result<Vec<String>, String> {
+        if self.stage_id.is_empty() {
+            return Err("No stage to snapshot".to_string());
+        }
+        let camera = self.viewport_data.scene.camera.clone();
+        let renderer = self.snapshot.renderer()?;
+        renderer.set_render_backend(self.renderer.clone());
+        renderer.set_shading_mode(ShadingMode::from_label(self.shading).unwrap_or(ShadingMode::SmoothShaded));
+        renderer.render_settings.renderer_settings = self.renderer_settings.clone();
+        renderer.render_settings.display_primvar = self.display_primvar.clone();
+        renderer.render_settings.complexity = self.complexity.clone();
+        renderer.set_anti_aliasing(self.anti_aliasing);
+        renderer.set_color_management(self.color_management);
+        renderer.set_path_trace(self.path_trace);
+        renderer.render_settings.ambient_occlusion = self.ambient_occlusion;
+        renderer.render_settings.frustum_culling = self.frustum_culling;
+        let view = &mut renderer.base_renderer.camera;
+        view.position = Vec3::from(camera.position);
+        view.target = Vec3::from(camera.target);
+        view.up = Vec3::from(camera.up);
+        view.fov = camera.fov;
+        view.near = camera.near;
+        view.far = camera.far;
+        renderer.set_camera_mode(match &self.look_through {
+            Some(path) => CameraMode::USDCamera(path.clone()),
+            None => CameraMode::Viewport,
+        });
+        let written = self.snapshot.capture(&self.stage_id, self.time_code, self.playback.fps)?;
+        println!("✓ Wrote viewport snapshot {}", written.join(", "));
+        Ok(written)
+    }
+    
+    /// Start recording the free camera at the playback rate, from the current time code
+    pub fn start_camera_recording(&mut self) {
+        self.camera_recorder = Some(CameraRecorder::start(self.time_code, self.playback.fps, Instant::now()));
+        self.track_camera();
+    }
+    
+    /// Publish the free camera for nodes snapping to the view, and sample it while recording
+    fn track_camera(&mut self) {
+        let camera = self.view_camera();
+        if !self.stage_id.is_empty() {
+            set_view_camera(&self.stage_id, camera);
+        }
+        if let Some(recorder) = &mut self.camera_recorder {
+            recorder.record(camera, Instant::now());
+        }
+    }
+    
+    /// Stop recording and author the path as time samples on the camera at `view_camera_path`
+    ///
+    /// Returns the camera path and the number of recorded frames. Samples
+    /// are added to the camera's existing ones, replacing those at the same
+    /// time codes.
+    pub fn stop_camera_recording(&mut self) -> Result<(String, usize), String> {
+        let recorder = self.camera_recorder.take().ok_or_else(|| "No camera path is being recorded".to_string())?;
+        let (_, last) = recorder.samples.last().ok_or_else(|| "No camera samples were recorded".to_string())?;
+        let mut attributes = recorder.time_samples();
+        attributes.push(("verticalAperture", UsdValue::Float(last.vertical_aperture)));
+        attributes.push(("clippingRange", UsdValue::Vec2([last.near as f64, last.far as f64])));
+        let path = self.author_view_camera(attributes)?;
+        println!("✓ Recorded {} frames of camera path to '{}'", recorder.samples.len(), path);
+        Ok((path, recorder.samples.len()))
     }
     
     /// Handle camera manipulation with USD-specific behavior
+    ///
+    /// Orbit, pan and zoom deltas are converted to logical points, inverted
+    /// per axis as set, and eased in; `settle_navigation` applies the rest
+    /// between events. Navigating leaves a looked-through stage camera for
+    /// the free camera.
     pub fn handle_camera_manipulation(&mut self, manipulation: CameraManipulation) {
+        let settings = &self.camera_settings;
+        let manipulation = match manipulation {
+            CameraManipulation::Orbit { delta_x, delta_y } => {
+                let delta = normalize_delta(Vec2::new(delta_x, delta_y), settings.display_scale, [settings.invert_orbit_x, settings.invert_orbit_y]);
+                CameraManipulation::Orbit { delta_x: delta.x, delta_y: delta.y }
+            }
+            CameraManipulation::Pan { delta_x, delta_y } => {
+                let delta = normalize_delta(Vec2::new(delta_x, delta_y), settings.display_scale, [settings.invert_pan_x, settings.invert_pan_y]);
+                CameraManipulation::Pan { delta_x: delta.x, delta_y: delta.y }
+            }
+            CameraManipulation::Zoom { delta } => {
+                let delta = normalize_delta(Vec2::new(delta, 0.0), settings.display_scale, [settings.invert_zoom, false]);
+                CameraManipulation::Zoom { delta: delta.x }
+            }
+            other => other,
+        };
+        self.navigate(manipulation);
+    }
+    
+    /// Handle a touch or trackpad gesture as the matching mouse navigation
+    ///
+    /// Pinching zooms, two-finger drags pan and twisting orbits around the
+    /// target by the twist angle, following the same invert toggles.
+    pub fn handle_gesture(&mut self, gesture: Gesture) {
+        let settings = &self.camera_settings;
+        let sign = |inverted: bool| if inverted { -1.0 } else { 1.0 };
+        let manipulation = match gesture {
+            Gesture::Pinch { scale } => CameraManipulation::Zoom {
+                delta: pinch_zoom(scale) * sign(settings.invert_zoom),
+            },
+            Gesture::TwoFingerPan { delta_x, delta_y } => {
+                let delta = normalize_delta(Vec2::new(delta_x, delta_y), settings.display_scale, [settings.invert_pan_x, settings.invert_pan_y]);
+                CameraManipulation::Pan { delta_x: delta.x, delta_y: delta.y }
+            }
+            Gesture::Rotate { angle } => CameraManipulation::Orbit {
+                delta_x: angle / settings.orbit_sensitivity.max(0.01) * sign(settings.invert_orbit_x),
+                delta_y: 0.0,
+            },
+        };
+        self.navigate(manipulation);
+    }
+    
+    /// Ease in a manipulation with normalized deltas and move the camera
+    ///
+    /// Axis panes move at once instead, and reset by fitting the scene.
+    fn navigate(&mut self, manipulation: CameraManipulation) {
+        if let Some(index) = self.panes.iter().position(|pane| pane.view == self.active_pane) {
+            let (center, radius) = self.scene_sphere();
+            let settings = &self.camera_settings;
+            let pane = &mut self.panes[index];
+            match manipulation {
+                CameraManipulation::Reset => pane.fit(center, radius),
+                other => pane.navigate(&other, settings.pan_sensitivity, settings.zoom_sensitivity),
+            }
+            self.viewport_data.scene_dirty = true;
+            return;
+        }
+        self.look_through = None;
+        let now = Instant::now();
+        let smoothing = self.camera_settings.smoothing;
+        let navigation = &mut self.navigation;
+        let manipulation = match manipulation {
+            CameraManipulation::Orbit { delta_x, delta_y } => {
+                let delta = navigation.orbit.push(Vec2::new(delta_x, delta_y), smoothing, now);
+                CameraManipulation::Orbit { delta_x: delta.x, delta_y: delta.y }
+            }
+            CameraManipulation::Pan { delta_x, delta_y } => {
+                let delta = navigation.pan.push(Vec2::new(delta_x, delta_y), smoothing, now);
+                CameraManipulation::Pan { delta_x: delta.x, delta_y: delta.y }
+            }
+            CameraManipulation::Zoom { delta } => {
+                CameraManipulation::Zoom { delta: navigation.zoom.push(Vec2::new(delta, 0.0), smoothing, now).x }
+            }
+            CameraManipulation::Reset => {
+                *navigation = NavigationSmoothing::default();
+                CameraManipulation::Reset
+            }
+            other => other,
+        };
+        self.apply_camera_manipulation(manipulation);
+    }
+    
+    /// Apply movement still being eased in from earlier navigation events
+    pub fn settle_navigation(&mut self) {
+        let now = Instant::now();
+        let smoothing = self.camera_settings.smoothing;
+        if let Some(delta) = self.navigation.orbit.settle(smoothing, now) {
+            self.apply_camera_manipulation(CameraManipulation::Orbit { delta_x: delta.x, delta_y: delta.y });
+        }
+        if let Some(delta) = self.navigation.pan.settle(smoothing, now) {
+            self.apply_camera_manipulation(CameraManipulation::Pan { delta_x: delta.x, delta_y: delta.y });
+        }
+        if let Some(delta) = self.navigation.zoom.settle(smoothing, now) {
+            self.apply_camera_manipulation(CameraManipulation::Zoom { delta: delta.x });
+        }
+    }
+    
+    /// Move the free camera by normalized deltas
+    fn apply_camera_manipulation(&mut self, manipulation: CameraManipulation) {
         let camera = &mut self.viewport_data.scene.camera;
         
         match manipulation {
@@ -166,12 +1515,26 @@ impl USDViewport {
                 let mut theta = (camera.position[2] - camera.target[2]).atan2(camera.position[0] - camera.target[0]);
                 let mut phi = ((camera.position[1] - camera.target[1]) / radius).asin();
                 
+                // Snapped orbits continue from their unsnapped angles unless the camera moved since
+                let snapping = self.snapping.camera && self.snapping.angle > 0.0;
+                if let Some((azimuth, elevation)) = self.orbit_angles.filter(|_| snapping) {
+                    let (snapped_azimuth, snapped_elevation) = snap_orbit(azimuth, elevation, self.snapping.angle);
+                    let wrapped = (snapped_azimuth - theta).rem_euclid(std::f32::consts::TAU);
+                    if wrapped.min(std::f32::consts::TAU - wrapped) < 1e-3 && (snapped_elevation - phi).abs() < 1e-3 {
+                        (theta, phi) = (azimuth, elevation);
+                    }
+                }
+                
                 // Apply orbit deltas
                 theta += delta_x * self.camera_settings.orbit_sensitivity;
                 phi += delta_y * self.camera_settings.orbit_sensitivity;
                 
                 // Clamp phi to prevent gimbal lock
                 phi = phi.clamp(-std::f32::consts::PI * 0.49, std::f32::consts::PI * 0.49);
+                self.orbit_angles = snapping.then_some((theta, phi));
+                if snapping {
+                    (theta, phi) = snap_orbit(theta, phi, self.snapping.angle);
+                }
                 
                 // Convert back to Cartesian
                 camera.position[0] = camera.target[0] + radius * phi.cos() * theta.cos();
@@ -223,16 +1586,195 @@ impl USDViewport {
         }
         
         self.viewport_data.scene_dirty = true;
+        self.track_camera();
+    }
+}
+
+/// A viewport camera in UsdGeomCamera terms
+fn projection_camera(view: &CameraData) -> ProjectionCamera {
+    ProjectionCamera::from_view(
+        Vec3::from(view.position),
+        Vec3::from(view.target),
+        Vec3::from(view.up),
+        view.fov,
+        view.aspect,
+        view.near,
+        view.far,
+    )
+}
+
+/// Suffix of the display color material ids of meshes drawn in their vertex colors
+const DISPLAY_COLOR_SUFFIX: &str = ":displayColor";
+
+/// Falloff range of point and spot lights in the host's scene data; UsdLux lights have none
+const LIGHT_RANGE: f32 = 100.0;
+
+/// The host viewport's sink, converting an extracted scene into the SDK's scene data
+#[derive(Default)]
+struct HostSceneSink {
+    scene: SceneData,
+    prototypes: HashMap<String, USDGeometry>,
+    /// Authored face of each triangle, keyed by mesh id
+    faces: HashMap<String, Vec<u32>>,
+    /// Primvars of every extracted mesh, repeats included
+    primvars: Vec<PrimvarInfo>,
+    /// Draw meshes in their vertex colors instead of their materials
+    display_colors: bool,
+    /// Display color materials already added, by geometry prim path
+    display_materials: HashSet<String>,
+}
+
+impl HostSceneSink {
+    fn push_mesh(&mut self, id: String, geometry: &USDGeometry, transform: Mat4) {
+        if !geometry.face_ids.is_empty() {
+            self.faces.insert(id.clone(), geometry.face_ids.clone());
+        }
+        let material_id = match self.display_colors {
+            true => self.display_material(geometry),
+            false => geometry.material_path.clone(),
+        };
+        self.scene.meshes.push(MeshData {
+            id,
+            vertices: geometry.vertices.iter().flat_map(|vertex| vertex.position).collect(),
+            normals: geometry.vertices.iter().flat_map(|vertex| vertex.normal).collect(),
+            uvs: geometry.vertices.iter().flat_map(|vertex| vertex.uv).collect(),
+            indices: geometry.indices.clone(),
+            material_id,
+            transform: transform.to_cols_array_2d(),
+        });
+    }
+    
+    /// Material of a geometry's average vertex color, None for geometry without colors
+    ///
+    /// Host meshes carry no per-vertex colors, so each mesh shows the mean of
+    /// its displayColor, or of the chosen display primvar, unlit by materials.
+    fn display_material(&mut self, geometry: &USDGeometry) -> Option<String> {
+        if geometry.colors.is_empty() {
+            return None;
+        }
+        let id = format!("{}{}", geometry.prim_path, DISPLAY_COLOR_SUFFIX);
+        if self.display_materials.insert(geometry.prim_path.clone()) {
+            let color = geometry.colors.iter().sum::<Vec4>() / geometry.colors.len() as f32;
+            self.scene.materials.push(MaterialData {
+                id: id.clone(),
+                name: "displayColor".to_string(),
+                base_color: color.to_array(),
+                metallic: 0.0,
+                roughness: 1.0,
+                emission: [0.0; 3],
+                diffuse_texture: None,
+                normal_texture: None,
+                roughness_texture: None,
+                metallic_texture: None,
+            });
+        }
+        Some(id)
     }
 }
 
+impl SceneSink for HostSceneSink {
+    fn begin_scene(&mut self, stage_id: &str, time_code: f64) {
+        *self = HostSceneSink { display_colors: self.display_colors, ..HostSceneSink::default() };
+        self.scene.name = format!("USD Stage: {} @ {}", stage_id, time_code);
+    }
+    
+    fn add_geometry(&mut self, geometry: USDGeometry) {
+        self.primvars.extend(geometry.primvars.iter().cloned());
+        if geometry.visibility {
+            self.push_mesh(geometry.prim_path.clone(), &geometry, geometry.transform);
+        }
+    }
+    
+    fn add_prototype_geometry(&mut self, geometry: USDGeometry) {
+        self.primvars.extend(geometry.primvars.iter().cloned());
+        self.prototypes.insert(geometry.prim_path.clone(), geometry);
+    }
+    
+    /// Every instance as a mesh of its own, with an id picking and selection read as the prototype's
+    fn add_instance_batch(&mut self, batch: InstanceBatch) {
+        let Some(prototype) = self.prototypes.get(&batch.geometry_path).cloned() else {
+            return;
+        };
+        for (index, transform) in batch.transforms.iter().enumerate() {
+            self.push_mesh(format!("{}:instance:{}", prototype.prim_path, index), &prototype, *transform);
+        }
+    }
+    
+    fn add_material(&mut self, material: USDMaterial) {
+        let texture = |input: &str| match material.connections.get(input) {
+            Some(InputSource::Texture(texture)) => Some(texture.file.clone()),
+            _ => None,
+        };
+        self.scene.materials.push(MaterialData {
+            id: material.prim_path.clone(),
+            name: material.prim_path.rsplit('/').next().unwrap_or_default().to_string(),
+            base_color: material.diffuse_color.extend(material.opacity).to_array(),
+            metallic: material.metallic,
+            roughness: material.roughness,
+            emission: material.emission_color.to_array(),
+            diffuse_texture: texture("diffuseColor"),
+            normal_texture: texture("normal"),
+            roughness_texture: texture("roughness"),
+            metallic_texture: texture("metallic"),
+        });
+    }
+    
+    fn add_material_bindings(&mut self, _prim_path: &str, _bindings: Vec<MaterialBinding>) {}
+    
+    fn add_light(&mut self, light: USDLight) {
+        let light_type = match (light.light_type.as_str(), light.lux.cone_angle) {
+            ("distant", _) => LightType::Directional,
+            (_, Some(_)) => LightType::Spot,
+            _ => LightType::Point,
+        };
+        self.scene.lights.push(LightData {
+            id: light.prim_path.clone(),
+            light_type,
+            position: light.transform.transform_point3(Vec3::ZERO).to_array(),
+            direction: light.direction().to_array(),
+            color: light.lux.tint().to_array(),
+            intensity: light.radiance(),
+            range: LIGHT_RANGE,
+            spot_angle: light.lux.cone_angle.unwrap_or(0.0),
+        });
+    }
+    
+    fn add_environment(&mut self, _environment: Environment) {}
+    
+    fn add_camera(&mut self, _camera: USDCamera) {}
+}
+
+/// Set the diffuse texture of a texture shader's material, adding the material if needed
+fn set_shader_texture(materials: &mut Vec<MaterialData>, shader_path: &str, file: Option<String>) {
+    match materials.iter_mut().find(|material| material.id == shader_path) {
+        Some(material) => material.diffuse_texture = file,
+        None => materials.push(MaterialData {
+            id: shader_path.to_string(),
+            name: shader_path.rsplit('/').next().unwrap_or_default().to_string(),
+            base_color: [1.0, 1.0, 1.0, 1.0],
+            metallic: 0.0,
+            roughness: 0.5,
+            emission: [0.0, 0.0, 0.0],
+            diffuse_texture: file,
+            normal_texture: None,
+            roughness_texture: None,
+            metallic_texture: None,
+        }),
+    }
+}
+
+/// Factory of the USD Viewport node, for `crate::node_factories`
+pub fn factories() -> Vec<Box<dyn NodeFactory>> {
+    vec![Box::new(USDViewport::default())]
+}
+
 impl NodeFactory for USDViewport {
     fn metadata(&self) -> NodeMetadata {
         NodeMetadata::new(
             "USD_Viewport",
             "USD Viewport", 
             NodeCategory::new(&["USD", "Viewport"]),
-            "3D viewport for visualizing USD stages with Maya-style navigation"
+            HELP.summary
         )
         .with_color(Color32::from_rgb(100, 200, 100))
         .with_icon("🎥")
@@ -241,10 +1783,18 @@ impl NodeFactory for USDViewport {
                 .with_description("USD Stage to visualize"),
             PortDefinition::optional("Camera", DataType::String)
                 .with_description("Camera prim for viewport (optional)"),
+            PortDefinition::optional("Time", DataType::Float)
+                .with_description("Time code to sample animation at (optional)"),
+            PortDefinition::optional("Selected Prim", DataType::String)
+                .with_description("Prim path to select (optional)"),
         ])
         .with_outputs(vec![
             PortDefinition::required("Rendered Image", DataType::String)
-                .with_description("Viewport render output"),
+                .with_description("Viewport render output, the files of the last snapshot once one is written"),
+            PortDefinition::optional("Selected Prims", DataType::String)
+                .with_description("Prims selected in the viewport, separated by commas"),
+            PortDefinition::optional("Selection Changed", DataType::Boolean)
+                .with_description("True on the update after a click changed the selection"),
         ])
         .with_workspace_compatibility(vec!["3D"])
         .with_panel_type(PanelType::Viewport)
@@ -255,6 +1805,7 @@ impl NodeFactory for USDViewport {
             id: uuid::Uuid::new_v4().to_string(),
             position,
             viewport_data: USDViewport::default(),
+            search: ParameterSearch::default(),
         }))
     }
 }
@@ -264,6 +1815,8 @@ pub struct USDViewportNode {
     pub id: String,
     pub position: Pos2,
     pub viewport_data: USDViewport,
+    /// Filter of the settings panel
+    pub search: ParameterSearch,
 }
 
 impl std::fmt::Debug for USDViewportNode {
@@ -275,6 +1828,43 @@ impl std::fmt::Debug for USDViewportNode {
     }
 }
 
+impl USDViewportNode {
+    /// Handle a click at pixel `(x, y)` of a `width` x `height` viewport, selecting the prim under it
+    ///
+    /// Shift-click adds the prim to the selection, or removes it if selected.
+    /// With Face Pick on, the click picks the face under it instead.
+    pub fn handle_viewport_click(&mut self, x: f32, y: f32, width: f32, height: f32, shift: bool) {
+        match self.viewport_data.face_pick {
+            Some(mode) => self.viewport_data.pick_faces(PickRect::from_corners((x, y), (x, y)), width, height, mode),
+            None => self.viewport_data.click_select(x, y, width, height, shift),
+        }
+    }
+    
+    /// Handle a drag starting at pixel `(x, y)`, returning whether it grabbed a gizmo handle or started a face pick
+    ///
+    /// Drags that miss the gizmo outside Face Pick are left to the host, e.g. for navigation.
+    pub fn handle_viewport_drag_start(&mut self, x: f32, y: f32, width: f32, height: f32) -> bool {
+        self.viewport_data.begin_gizmo_drag(x, y, width, height) || self.viewport_data.begin_face_drag(x, y, width, height)
+    }
+    
+    /// Handle the cursor moving to pixel `(x, y)` during a drag
+    pub fn handle_viewport_drag(&mut self, x: f32, y: f32, width: f32, height: f32) {
+        match &mut self.viewport_data.face_drag {
+            Some(drag) => {
+                drag.end = (x, y);
+                (drag.width, drag.height) = (width, height);
+            }
+            None => self.viewport_data.drag_gizmo(x, y, width, height),
+        }
+    }
+    
+    /// Handle the end of a drag
+    pub fn handle_viewport_drag_end(&mut self) {
+        self.viewport_data.end_gizmo_drag();
+        self.viewport_data.end_face_drag();
+    }
+}
+
 impl PluginNode for USDViewportNode {
     fn id(&self) -> String {
         self.id.clone().into()
@@ -294,6 +1884,7 @@ impl PluginNode for USDViewportNode {
         // USD Viewport Parameters - no direct egui rendering of 3D content
         elements.push(UIElement::Heading("USD Viewport Settings".into()));
         elements.push(UIElement::Separator);
+        elements.push(self.search.element());
         
         // Stage Information
         elements.push(UIElement::Label("📁 Stage Information".into()));
@@ -301,9 +1892,64 @@ impl PluginNode for USDViewportNode {
             elements.push(UIElement::Label("No USD stage loaded".into()));
         } else {
             elements.push(UIElement::Label(format!("Current Stage: {}", self.viewport_data.current_stage).into()));
+            elements.push(UIElement::Label(format!("Time Code: {}", self.viewport_data.time_code).into()));
+            match self.viewport_data.selected_prims.as_slice() {
+                [] => {}
+                [selected] => elements.push(UIElement::Label(format!("Selected: {}", selected).into())),
+                selected => elements.push(UIElement::Label(format!("Selected: {} prims, last {}", selected.len(), selected[selected.len() - 1]).into())),
+            }
+            for (shader_path, sequence) in &self.viewport_data.texture_sequences {
+                elements.push(UIElement::Label(format!("🎞 {}: {} frames", shader_path, sequence.frame_count()).into()));
+            }
+            for (shader_path, _) in &self.viewport_data.package_textures {
+                elements.push(UIElement::Label(format!("📦 {}: embedded texture", shader_path).into()));
+            }
         }
         elements.push(UIElement::Separator);
         
+        // Playback
+        let playback = &self.viewport_data.playback;
+        elements.push(UIElement::Label("▶ Playback".into()));
+        elements.push(UIElement::Button {
+            label: if playback.playing { "⏸ Pause".into() } else { "▶ Play".into() },
+            action: if playback.playing { "pause".into() } else { "play".into() },
+        });
+        elements.push(UIElement::Button {
+            label: "⏹ Stop".into(),
+            action: "stop".into(),
+        });
+        elements.push(UIElement::Slider {
+            label: "Frame".into(),
+            value: playback.current_frame() as f32,
+            min: playback.start_time_code as f32,
+            max: playback.end_time_code as f32,
+            parameter_name: "frame".into(),
+        });
+        let fps = format!("{}", playback.fps);
+        elements.extend(choice_buttons("FPS", "fps", &FPS_PRESETS, &fps));
+        let loop_names: Vec<&str> = LoopMode::ALL.iter().map(|mode| mode.name()).collect();
+        elements.extend(choice_buttons("Loop", "loop_mode", &loop_names, playback.loop_mode.name()));
+        elements.push(UIElement::Separator);
+        
+        // Camera projection preview
+        elements.push(UIElement::Label("🎬 Camera Projection".into()));
+        elements.push(UIElement::Checkbox {
+            label: "Project From Camera".into(),
+            value: self.viewport_data.projection.enabled,
+            parameter_name: "projection_enabled".into(),
+        });
+        elements.push(UIElement::TextEdit {
+            label: "Projection Camera".into(),
+            value: self.viewport_data.projection.camera_path.clone(),
+            parameter_name: "projection_camera".into(),
+        });
+        elements.push(UIElement::TextEdit {
+            label: "Projection Image".into(),
+            value: self.viewport_data.projection.image.clone(),
+            parameter_name: "projection_image".into(),
+        });
+        elements.push(UIElement::Separator);
+        
         // Camera Settings
         elements.push(UIElement::Label("🎥 Camera Settings".into()));
         elements.push(UIElement::Slider {
@@ -330,47 +1976,344 @@ impl PluginNode for USDViewportNode {
             parameter_name: "zoom_sensitivity".into(),
         });
         
+        elements.push(UIElement::Slider {
+            label: "Smoothing (s)".into(),
+            value: self.viewport_data.camera_settings.smoothing,
+            min: 0.0,
+            max: 0.5,
+            parameter_name: "navigation_smoothing".into(),
+        });
+        
+        elements.push(UIElement::Slider {
+            label: "Display Scale".into(),
+            value: self.viewport_data.camera_settings.display_scale,
+            min: 0.5,
+            max: 4.0,
+            parameter_name: "display_scale".into(),
+        });
+        
+        for (parameter, label) in INVERT_TOGGLES {
+            elements.push(UIElement::Checkbox {
+                label: label.into(),
+                value: self.get_parameter(parameter).and_then(|value| value.as_boolean()).unwrap_or(false),
+                parameter_name: parameter.into(),
+            });
+        }
+        
+        let layouts: Vec<&str> = PaneLayout::ALL.iter().map(PaneLayout::label).collect();
+        elements.extend(choice_buttons("Layout", "layout", &layouts, self.viewport_data.layout.label()));
+        if self.viewport_data.layout != PaneLayout::Single {
+            let views: Vec<&str> = self.viewport_data.layout.views().iter().map(PaneView::label).collect();
+            elements.extend(choice_buttons("Active Pane", "active_pane", &views, self.viewport_data.active_pane.label()));
+        }
+        
         elements.push(UIElement::Button {
             label: "Reset Camera".into(),
             action: "reset_camera".into(),
         });
+        elements.push(UIElement::Button {
+            label: "Frame All".into(),
+            action: "frame_all".into(),
+        });
+        elements.push(UIElement::Button {
+            label: "Frame Selected".into(),
+            action: "frame_selected".into(),
+        });
+        
+        let cameras: Vec<&str> = std::iter::once(FREE_CAMERA)
+            .chain(self.viewport_data.stage_cameras.iter().map(String::as_str))
+            .collect();
+        let current = self.viewport_data.look_through.as_deref().unwrap_or(FREE_CAMERA);
+        elements.extend(choice_buttons("Look Through", "look_through", &cameras, current));
+        
+        elements.push(UIElement::TextEdit {
+            label: "View Camera Path".into(),
+            value: self.viewport_data.view_camera_path.clone(),
+            parameter_name: "view_camera_path".into(),
+        });
+        elements.push(UIElement::Button {
+            label: "📷 Create Camera from View".into(),
+            action: "create_camera_from_view".into(),
+        });
+        elements.push(match &self.viewport_data.camera_recorder {
+            Some(recorder) => UIElement::Button {
+                label: format!("⏹ Stop Recording ({} frames)", recorder.samples.len()),
+                action: "stop_camera_recording".into(),
+            },
+            None => UIElement::Button {
+                label: "⏺ Record Camera Path".into(),
+                action: "record_camera_path".into(),
+            },
+        });
+        
+        elements.push(UIElement::Separator);
+        
+        // Viewport Settings
+        elements.push(UIElement::Label("⚙️ Viewport Settings".into()));
+        
+        elements.push(UIElement::Checkbox {
+            label: "Wireframe".into(),
+            value: self.viewport_data.viewport_data.settings.wireframe,
+            parameter_name: "wireframe".into(),
+        });
+        
+        elements.push(UIElement::Checkbox {
+            label: "Lighting".into(),
+            value: self.viewport_data.viewport_data.settings.lighting,
+            parameter_name: "lighting".into(),
+        });
+        
+        elements.push(UIElement::Checkbox {
+            label: "Show Grid".into(),
+            value: self.viewport_data.viewport_data.settings.show_grid,
+            parameter_name: "show_grid".into(),
+        });
+        
+        elements.push(UIElement::Checkbox {
+            label: "Show Ground Plane".into(),
+            value: self.viewport_data.viewport_data.settings.show_ground_plane,
+            parameter_name: "show_ground_plane".into(),
+        });
+        
+        let occlusion = self.viewport_data.ambient_occlusion;
+        elements.push(UIElement::Checkbox {
+            label: "Ambient Occlusion".into(),
+            value: occlusion.enabled,
+            parameter_name: "ambient_occlusion".into(),
+        });
+        if occlusion.enabled {
+            for (label, parameter, value, max) in [
+                ("Occlusion Radius", "ao_radius", occlusion.radius, 5.0),
+                ("Occlusion Intensity", "ao_intensity", occlusion.intensity, 4.0),
+            ] {
+                elements.push(UIElement::Slider {
+                    label: label.into(),
+                    value,
+                    min: 0.0,
+                    max,
+                    parameter_name: parameter.into(),
+                });
+            }
+        }
+        
+        let path_trace = self.viewport_data.path_trace;
+        elements.push(UIElement::Checkbox {
+            label: "Final Quality Preview".into(),
+            value: path_trace.enabled,
+            parameter_name: "path_trace".into(),
+        });
+        if path_trace.enabled {
+            for (label, parameter, value, max) in [
+                ("Max Samples", "path_trace_samples", path_trace.max_samples, 4096.0),
+                ("Bounces", "path_trace_bounces", path_trace.max_bounces, 16.0),
+            ] {
+                elements.push(UIElement::Slider {
+                    label: label.into(),
+                    value: value as f32,
+                    min: 0.0,
+                    max,
+                    parameter_name: parameter.into(),
+                });
+            }
+            elements.push(UIElement::Checkbox {
+                label: "Denoise".into(),
+                value: path_trace.denoise,
+                parameter_name: "path_trace_denoise".into(),
+            });
+        }
         
-        elements.push(UIElement::Separator);
+        let anti_aliasing: Vec<String> = AntiAliasing::ALL.iter().map(AntiAliasing::label).collect();
+        let labels: Vec<&str> = anti_aliasing.iter().map(String::as_str).collect();
+        elements.extend(choice_buttons("Anti-Aliasing", "anti_aliasing", &labels, &self.viewport_data.anti_aliasing.label()));
         
-        // Viewport Settings
-        elements.push(UIElement::Label("⚙️ Viewport Settings".into()));
+        let color_management = self.viewport_data.color_management;
+        let transforms: Vec<&str> = ViewTransform::ALL.iter().map(ViewTransform::label).collect();
+        elements.extend(choice_buttons("View Transform", "view_transform", &transforms, color_management.view_transform.label()));
+        for (label, parameter, value, min, max) in [
+            ("Exposure", "view_exposure", color_management.exposure, -8.0, 8.0),
+            ("Gamma", "view_gamma", color_management.gamma, 0.2, 3.0),
+        ] {
+            elements.push(UIElement::Slider {
+                label: label.into(),
+                value,
+                min,
+                max,
+                parameter_name: parameter.into(),
+            });
+        }
+        
+        elements.extend(choice_buttons("Shading", "shading", &SHADING_MODES, self.viewport_data.shading));
+        let complexities: Vec<&str> = ComplexityLevel::ALL.iter().map(ComplexityLevel::label).collect();
+        elements.extend(choice_buttons("Complexity", "complexity", &complexities, self.viewport_data.complexity.label()));
+        if !self.viewport_data.primvars.is_empty() {
+            let mut primvars: Vec<&str> = std::iter::once(NO_PRIMVAR).chain(self.viewport_data.primvars.iter().map(|primvar| primvar.name.as_str())).collect();
+            primvars.dedup();
+            let current = self.viewport_data.display_primvar.as_deref().unwrap_or(NO_PRIMVAR);
+            elements.extend(choice_buttons("Display Primvar", "display_primvar", &primvars, current));
+        }
+        if let Some(selected) = &self.viewport_data.selected_prim {
+            let current = self.viewport_data.draw_modes.iter()
+                .find(|model| model.prim_path == *selected)
+                .and_then(|model| DrawMode::from_token(&model.mode))
+                .unwrap_or(DrawMode::Default);
+            let labels: Vec<&str> = DrawMode::ALL.iter().map(DrawMode::label).collect();
+            elements.extend(choice_buttons("Draw Mode of Selected", "draw_mode", &labels, current.label()));
+        }
+        
+        let gizmo_modes: Vec<&str> = std::iter::once(NO_GIZMO).chain(GizmoMode::ALL.iter().map(GizmoMode::label)).collect();
+        let gizmo = self.viewport_data.gizmo_mode.as_ref().map_or(NO_GIZMO, GizmoMode::label);
+        elements.extend(choice_buttons("Gizmo", "gizmo_mode", &gizmo_modes, gizmo));
+        if self.viewport_data.gizmo_mode.is_some() {
+            let spaces: Vec<&str> = GizmoSpace::ALL.iter().map(GizmoSpace::label).collect();
+            elements.extend(choice_buttons("Gizmo Space", "gizmo_space", &spaces, self.viewport_data.gizmo_space.label()));
+        }
+        let face_picks: Vec<&str> = std::iter::once(NO_FACE_PICK).chain(PickMode::ALL.iter().map(PickMode::label)).collect();
+        let face_pick = self.viewport_data.face_pick.as_ref().map_or(NO_FACE_PICK, PickMode::label);
+        elements.extend(choice_buttons("Face Pick", "face_pick", &face_picks, face_pick));
+        let palettes: Vec<&str> = Palette::ALL.iter().map(Palette::label).collect();
+        elements.extend(choice_buttons("Palette", "palette", &palettes, palette().label()));
         
+        // Snapping of gizmo drags and camera orbits
+        let snapping = &self.viewport_data.snapping;
+        elements.push(UIElement::Label("🧲 Snapping".into()));
+        for (label, parameter, value, max) in [
+            ("Grid Size", "snap_grid", snapping.grid, 10.0),
+            ("Angle Step (°)", "snap_angle", snapping.angle, 90.0),
+            ("Scale Step", "snap_scale", snapping.scale, 1.0),
+        ] {
+            elements.push(UIElement::Slider {
+                label: label.into(),
+                value,
+                min: 0.0,
+                max,
+                parameter_name: parameter.into(),
+            });
+        }
         elements.push(UIElement::Checkbox {
-            label: "Wireframe".into(),
-            value: self.viewport_data.viewport_data.settings.wireframe,
-            parameter_name: "wireframe".into(),
+            label: "Snap to Vertices".into(),
+            value: snapping.vertex,
+            parameter_name: "snap_vertex".into(),
         });
-        
         elements.push(UIElement::Checkbox {
-            label: "Lighting".into(),
-            value: self.viewport_data.viewport_data.settings.lighting,
-            parameter_name: "lighting".into(),
+            label: "Snap Camera Orbit".into(),
+            value: snapping.camera,
+            parameter_name: "snap_camera".into(),
         });
         
         elements.push(UIElement::Checkbox {
-            label: "Show Grid".into(),
-            value: self.viewport_data.viewport_data.settings.show_grid,
-            parameter_name: "show_grid".into(),
+            label: "Frustum Culling".into(),
+            value: self.viewport_data.frustum_culling,
+            parameter_name: "frustum_culling".into(),
         });
+        if self.viewport_data.frustum_culling {
+            let CullStats { drawn, culled } = self.viewport_data.cull_stats();
+            elements.push(UIElement::Label(format!("Drawn {} of {} meshes, {} culled", drawn, drawn + culled, culled)));
+        }
+        
+        elements.push(UIElement::Separator);
+        
+        // Renderer and the active delegate's render settings
+        elements.push(UIElement::Label("🖼 Renderer".into()));
+        let labels: Vec<String> = RenderBackend::available().iter().map(RenderBackend::label).collect();
+        let options: Vec<&str> = labels.iter().map(String::as_str).collect();
+        elements.extend(choice_buttons("Renderer", "renderer", &options, &self.viewport_data.renderer.label()));
+        if let RenderBackend::Hydra(delegate) = &self.viewport_data.renderer {
+            match delegate_settings(delegate) {
+                Ok(settings) => {
+                    for setting in settings {
+                        let parameter_name = format!("{}{}", RENDER_SETTING_PREFIX, setting.key);
+                        let value = self.viewport_data.renderer_setting(&setting.key).unwrap_or(&setting.default);
+                        elements.push(match value {
+                            RenderSettingValue::Flag(value) => UIElement::Checkbox {
+                                label: setting.name,
+                                value: *value,
+                                parameter_name,
+                            },
+                            other => UIElement::TextEdit {
+                                label: setting.name,
+                                value: other.to_string(),
+                                parameter_name,
+                            },
+                        });
+                    }
+                }
+                Err(e) => elements.push(status_label(&format!("⚠ {}", e))),
+            }
+        }
+        elements.push(UIElement::Separator);
         
+        // Offscreen snapshot of the view with AOVs
+        let snapshot = &self.viewport_data.snapshot;
+        elements.push(UIElement::Label("📸 Snapshot".into()));
+        elements.push(UIElement::TextEdit {
+            label: "Snapshot Path".into(),
+            value: snapshot.output_pattern.clone(),
+            parameter_name: "snapshot_path".into(),
+        });
+        for (label, parameter, value) in [
+            ("Width", "snapshot_width", snapshot.width),
+            ("Height", "snapshot_height", snapshot.height),
+        ] {
+            elements.push(UIElement::Slider {
+                label: label.into(),
+                value: value as f32,
+                min: 16.0,
+                max: 8192.0,
+                parameter_name: parameter.into(),
+            });
+        }
+        for aov in Aov::ALL {
+            elements.push(UIElement::Checkbox {
+                label: format!("{} AOV", aov.label()),
+                value: snapshot.aovs.contains(&aov),
+                parameter_name: format!("{}{}", SNAPSHOT_AOV_PREFIX, aov.label()),
+            });
+        }
         elements.push(UIElement::Checkbox {
-            label: "Show Ground Plane".into(),
-            value: self.viewport_data.viewport_data.settings.show_ground_plane,
-            parameter_name: "show_ground_plane".into(),
+            label: "ID Matte".into(),
+            value: snapshot.id_matte,
+            parameter_name: "snapshot_id_matte".into(),
+        });
+        elements.push(UIElement::Button {
+            label: "📸 Snapshot".into(),
+            action: "snapshot".into(),
+        });
+        if let Some(last) = snapshot.written.first() {
+            elements.push(UIElement::Label(format!("Last snapshot: {}", last).into()));
+        }
+        elements.push(UIElement::Separator);
+        
+        // Python bridge stats
+        elements.push(UIElement::Label(format!("📊 Python Bridge: {:.1} ms total",
+                                               total_bridge_time().as_secs_f64() * 1000.0).into()));
+        for stats in top_operations(5) {
+            elements.push(UIElement::Label(format!("{}: {}× {:.1} ms (avg {:.2} ms)",
+                                                   stats.path,
+                                                   stats.calls,
+                                                   stats.total.as_secs_f64() * 1000.0,
+                                                   stats.average().as_secs_f64() * 1000.0).into()));
+        }
+        elements.push(UIElement::Button {
+            label: "Reset Bridge Stats".into(),
+            action: "reset_bridge_stats".into(),
         });
         
         elements.push(UIElement::Separator);
         elements.push(UIElement::Label("💡 USD Plugin - Data-driven viewport rendering".into()));
+        let mut elements = self.search.filter(elements);
+        elements.extend(HELP.section());
         
-        ParameterUI { elements }
+        ParameterUI { elements: focus_marked(&self.id, advertise_shortcuts(elements, VIEWPORT_SHORTCUTS)) }
     }
     
     fn handle_ui_action(&mut self, action: UIAction) -> Vec<ParameterChange> {
+        if let Some(changes) = handle_key(self, VIEWPORT_SHORTCUTS, &action) {
+            return changes;
+        }
+        if let Some(change) = self.search.handle_action(&action) {
+            return vec![change];
+        }
+        
         let mut changes = Vec::<ParameterChange>::new();
         
         match action {
@@ -403,6 +2346,51 @@ impl PluginNode for USDViewportNode {
                             });
                         }
                     }
+                    "navigation_smoothing" | "display_scale" | "snap_grid" | "snap_angle" | "snap_scale" | "ao_radius" | "ao_intensity" | "path_trace_samples" | "path_trace_bounces" | "view_exposure" | "view_gamma" | "snapshot_width" | "snapshot_height" => {
+                        if let Some(val) = value.as_float() {
+                            self.set_parameter(&parameter, NodeData::Float(val));
+                            changes.push(ParameterChange {
+                                parameter: parameter.clone(),
+                                value: NodeData::Float(val),
+                            });
+                        }
+                    }
+                    "invert_orbit_x" | "invert_orbit_y" | "invert_pan_x" | "invert_pan_y" | "invert_zoom" | "frustum_culling" | "snap_vertex" | "snap_camera" | "ambient_occlusion" | "path_trace" | "path_trace_denoise" | "snapshot_id_matte" => {
+                        if let Some(val) = value.as_boolean() {
+                            self.set_parameter(&parameter, NodeData::Boolean(val));
+                            changes.push(ParameterChange {
+                                parameter: parameter.clone(),
+                                value: NodeData::Boolean(val),
+                            });
+                        }
+                    }
+                    "frame" => {
+                        if let Some(val) = value.as_float() {
+                            self.set_parameter("frame", NodeData::Float(val));
+                            changes.push(ParameterChange {
+                                parameter: "frame".into(),
+                                value: NodeData::Float(val),
+                            });
+                        }
+                    }
+                    "projection_enabled" => {
+                        if let Some(val) = value.as_boolean() {
+                            self.set_parameter("projection_enabled", NodeData::Boolean(val));
+                            changes.push(ParameterChange {
+                                parameter: "projection_enabled".into(),
+                                value: NodeData::Boolean(val),
+                            });
+                        }
+                    }
+                    "projection_camera" | "projection_image" | "view_camera_path" | "snapshot_path" => {
+                        if let Some(val) = value.as_string() {
+                            self.set_parameter(&parameter, NodeData::String(val.to_string()));
+                            changes.push(ParameterChange {
+                                parameter: parameter.clone(),
+                                value: NodeData::String(val.to_string()),
+                            });
+                        }
+                    }
                     "wireframe" => {
                         if let Some(val) = value.as_boolean() {
                             self.viewport_data.viewport_data.settings.wireframe = val;
@@ -443,6 +2431,21 @@ impl PluginNode for USDViewportNode {
                             });
                         }
                     }
+                    // Hosts tiling panes focus the pane under the cursor before forwarding its input
+                    "active_pane" => {
+                        self.set_parameter("active_pane", value);
+                        changes.push(ParameterChange {
+                            parameter: "active_pane".into(),
+                            value: NodeData::String(self.viewport_data.active_pane.label().to_string()),
+                        });
+                    }
+                    name if name.starts_with(RENDER_SETTING_PREFIX) || name.starts_with(SNAPSHOT_AOV_PREFIX) => {
+                        self.set_parameter(name, value.clone());
+                        changes.push(ParameterChange {
+                            parameter: parameter.clone(),
+                            value,
+                        });
+                    }
                     _ => {}
                 }
             }
@@ -455,7 +2458,141 @@ impl PluginNode for USDViewportNode {
                             value: NodeData::Boolean(true),
                         });
                     }
-                    _ => {}
+                    "snapshot" => match self.viewport_data.render_snapshot() {
+                        Ok(written) => changes.push(ParameterChange {
+                            parameter: "snapshot".into(),
+                            value: NodeData::String(written.join(", ")),
+                        }),
+                        Err(e) => eprintln!("USD Plugin: Can't snapshot the view: {}", e),
+                    },
+                    "frame_all" | "frame_selected" => {
+                        if let Err(e) = self.viewport_data.frame(action == "frame_selected") {
+                            eprintln!("USD Plugin: Can't frame: {}", e);
+                        }
+                    }
+                    "create_camera_from_view" => match self.viewport_data.create_camera_from_view() {
+                        Ok(path) => changes.push(ParameterChange {
+                            parameter: "camera_created".into(),
+                            value: NodeData::String(path),
+                        }),
+                        Err(e) => eprintln!("USD Plugin: Failed to create camera from view: {}", e),
+                    },
+                    "record_camera_path" => self.viewport_data.start_camera_recording(),
+                    "stop_camera_recording" => match self.viewport_data.stop_camera_recording() {
+                        Ok((path, _)) => changes.push(ParameterChange {
+                            parameter: "camera_recorded".into(),
+                            value: NodeData::String(path),
+                        }),
+                        Err(e) => eprintln!("USD Plugin: Failed to record camera path: {}", e),
+                    },
+                    "reset_bridge_stats" => reset_profile(),
+                    "play" => self.viewport_data.playback.play(),
+                    "pause" => self.viewport_data.playback.pause(),
+                    "stop" => {
+                        self.viewport_data.playback.stop();
+                        self.viewport_data.set_time(self.viewport_data.playback.current_time_code);
+                    }
+                    other => {
+                        if let Some(fps) = parse_choice(other, "fps") {
+                            self.set_parameter("fps", NodeData::String(fps.to_string()));
+                            changes.push(ParameterChange {
+                                parameter: "fps".into(),
+                                value: NodeData::String(fps.to_string()),
+                            });
+                        } else if let Some(mode) = parse_choice(other, "loop_mode") {
+                            self.set_parameter("loop_mode", NodeData::String(mode.to_string()));
+                            changes.push(ParameterChange {
+                                parameter: "loop_mode".into(),
+                                value: NodeData::String(mode.to_string()),
+                            });
+                        } else if let Some(mode) = parse_choice(other, "shading") {
+                            self.set_parameter("shading", NodeData::String(mode.to_string()));
+                            changes.push(ParameterChange {
+                                parameter: "shading".into(),
+                                value: NodeData::String(mode.to_string()),
+                            });
+                        } else if let Some(level) = parse_choice(other, "complexity") {
+                            self.set_parameter("complexity", NodeData::String(level.to_string()));
+                            changes.push(ParameterChange {
+                                parameter: "complexity".into(),
+                                value: NodeData::String(level.to_string()),
+                            });
+                        } else if let Some(primvar) = parse_choice(other, "display_primvar") {
+                            self.set_parameter("display_primvar", NodeData::String(primvar.to_string()));
+                            changes.push(ParameterChange {
+                                parameter: "display_primvar".into(),
+                                value: NodeData::String(primvar.to_string()),
+                            });
+                        } else if let Some(mode) = parse_choice(other, "anti_aliasing") {
+                            self.set_parameter("anti_aliasing", NodeData::String(mode.to_string()));
+                            changes.push(ParameterChange {
+                                parameter: "anti_aliasing".into(),
+                                value: NodeData::String(mode.to_string()),
+                            });
+                        } else if let Some(transform) = parse_choice(other, "view_transform") {
+                            self.set_parameter("view_transform", NodeData::String(transform.to_string()));
+                            changes.push(ParameterChange {
+                                parameter: "view_transform".into(),
+                                value: NodeData::String(transform.to_string()),
+                            });
+                        } else if let Some(layout) = parse_choice(other, "layout") {
+                            self.set_parameter("layout", NodeData::String(layout.to_string()));
+                            changes.push(ParameterChange {
+                                parameter: "layout".into(),
+                                value: NodeData::String(layout.to_string()),
+                            });
+                        } else if let Some(view) = parse_choice(other, "active_pane") {
+                            self.set_parameter("active_pane", NodeData::String(view.to_string()));
+                            changes.push(ParameterChange {
+                                parameter: "active_pane".into(),
+                                value: NodeData::String(view.to_string()),
+                            });
+                        } else if let Some(name) = parse_choice(other, "palette") {
+                            self.set_parameter("palette", NodeData::String(name.to_string()));
+                            changes.push(ParameterChange {
+                                parameter: "palette".into(),
+                                value: NodeData::String(name.to_string()),
+                            });
+                        } else if let Some(mode) = parse_choice(other, "gizmo_mode") {
+                            self.set_parameter("gizmo_mode", NodeData::String(mode.to_string()));
+                            changes.push(ParameterChange {
+                                parameter: "gizmo_mode".into(),
+                                value: NodeData::String(mode.to_string()),
+                            });
+                        } else if let Some(mode) = parse_choice(other, "face_pick") {
+                            self.set_parameter("face_pick", NodeData::String(mode.to_string()));
+                            changes.push(ParameterChange {
+                                parameter: "face_pick".into(),
+                                value: NodeData::String(mode.to_string()),
+                            });
+                        } else if let Some(space) = parse_choice(other, "gizmo_space") {
+                            self.set_parameter("gizmo_space", NodeData::String(space.to_string()));
+                            changes.push(ParameterChange {
+                                parameter: "gizmo_space".into(),
+                                value: NodeData::String(space.to_string()),
+                            });
+                        } else if let Some(mode) = parse_choice(other, "draw_mode").and_then(DrawMode::from_label) {
+                            if let Err(e) = self.viewport_data.set_selected_draw_mode(mode) {
+                                eprintln!("USD Plugin: Failed to set draw mode: {}", e);
+                            }
+                        } else if let Some(camera) = parse_choice(other, "look_through") {
+                            let camera = (camera != FREE_CAMERA).then(|| camera.to_string());
+                            self.viewport_data.set_look_through(camera.clone());
+                            changes.push(ParameterChange {
+                                parameter: "look_through".into(),
+                                value: NodeData::String(camera.unwrap_or_default()),
+                            });
+                        } else if let Some(label) = parse_choice(other, "renderer") {
+                            if let Some(renderer) = RenderBackend::available().into_iter().find(|backend| backend.label() == label) {
+                                let id = renderer.id().to_string();
+                                self.viewport_data.set_renderer(renderer);
+                                changes.push(ParameterChange {
+                                    parameter: "renderer".into(),
+                                    value: NodeData::String(id),
+                                });
+                            }
+                        }
+                    }
                 }
             }
         }
@@ -465,25 +2602,100 @@ impl PluginNode for USDViewportNode {
     
     fn get_parameter(&self, name: &str) -> Option<NodeData> {
         match name {
+            SEARCH_PARAMETER => Some(NodeData::String(self.search.query.clone())),
             "current_stage" => Some(NodeData::String(self.viewport_data.current_stage.clone().into())),
             "orbit_sensitivity" => Some(NodeData::Float(self.viewport_data.camera_settings.orbit_sensitivity)),
             "pan_sensitivity" => Some(NodeData::Float(self.viewport_data.camera_settings.pan_sensitivity)),
             "zoom_sensitivity" => Some(NodeData::Float(self.viewport_data.camera_settings.zoom_sensitivity)),
+            "navigation_smoothing" => Some(NodeData::Float(self.viewport_data.camera_settings.smoothing)),
+            "display_scale" => Some(NodeData::Float(self.viewport_data.camera_settings.display_scale)),
+            "invert_orbit_x" => Some(NodeData::Boolean(self.viewport_data.camera_settings.invert_orbit_x)),
+            "invert_orbit_y" => Some(NodeData::Boolean(self.viewport_data.camera_settings.invert_orbit_y)),
+            "invert_pan_x" => Some(NodeData::Boolean(self.viewport_data.camera_settings.invert_pan_x)),
+            "invert_pan_y" => Some(NodeData::Boolean(self.viewport_data.camera_settings.invert_pan_y)),
+            "invert_zoom" => Some(NodeData::Boolean(self.viewport_data.camera_settings.invert_zoom)),
             "wireframe" => Some(NodeData::Boolean(self.viewport_data.viewport_data.settings.wireframe)),
             "lighting" => Some(NodeData::Boolean(self.viewport_data.viewport_data.settings.lighting)),
             "show_grid" => Some(NodeData::Boolean(self.viewport_data.viewport_data.settings.show_grid)),
             "show_ground_plane" => Some(NodeData::Boolean(self.viewport_data.viewport_data.settings.show_ground_plane)),
-            _ => None,
+            "frame" => Some(NodeData::Float(self.viewport_data.playback.current_time_code as f32)),
+            "fps" => Some(NodeData::String(self.viewport_data.playback.fps.to_string())),
+            "loop_mode" => Some(NodeData::String(self.viewport_data.playback.loop_mode.name().to_string())),
+            "projection_enabled" => Some(NodeData::Boolean(self.viewport_data.projection.enabled)),
+            "projection_camera" => Some(NodeData::String(self.viewport_data.projection.camera_path.clone())),
+            "projection_image" => Some(NodeData::String(self.viewport_data.projection.image.clone())),
+            "renderer" => Some(NodeData::String(self.viewport_data.renderer.id().to_string())),
+            "view_camera_path" => Some(NodeData::String(self.viewport_data.view_camera_path.clone())),
+            "look_through" => Some(NodeData::String(self.viewport_data.look_through.clone().unwrap_or_default())),
+            "shading" => Some(NodeData::String(self.viewport_data.shading.to_string())),
+            "complexity" => Some(NodeData::String(self.viewport_data.complexity.label().to_string())),
+            "display_primvar" => Some(NodeData::String(self.viewport_data.display_primvar.clone().unwrap_or_else(|| NO_PRIMVAR.to_string()))),
+            "frustum_culling" => Some(NodeData::Boolean(self.viewport_data.frustum_culling)),
+            "ambient_occlusion" => Some(NodeData::Boolean(self.viewport_data.ambient_occlusion.enabled)),
+            "ao_radius" => Some(NodeData::Float(self.viewport_data.ambient_occlusion.radius)),
+            "ao_intensity" => Some(NodeData::Float(self.viewport_data.ambient_occlusion.intensity)),
+            "path_trace" => Some(NodeData::Boolean(self.viewport_data.path_trace.enabled)),
+            "path_trace_samples" => Some(NodeData::Float(self.viewport_data.path_trace.max_samples as f32)),
+            "path_trace_bounces" => Some(NodeData::Float(self.viewport_data.path_trace.max_bounces as f32)),
+            "path_trace_denoise" => Some(NodeData::Boolean(self.viewport_data.path_trace.denoise)),
+            "anti_aliasing" => Some(NodeData::String(self.viewport_data.anti_aliasing.label())),
+            "view_transform" => Some(NodeData::String(self.viewport_data.color_management.view_transform.label().to_string())),
+            "view_exposure" => Some(NodeData::Float(self.viewport_data.color_management.exposure)),
+            "view_gamma" => Some(NodeData::Float(self.viewport_data.color_management.gamma)),
+            "layout" => Some(NodeData::String(self.viewport_data.layout.label().to_string())),
+            "active_pane" => Some(NodeData::String(self.viewport_data.active_pane.label().to_string())),
+            "pane_views" => {
+                let views: Vec<&str> = self.viewport_data.layout.views().iter().map(PaneView::label).collect();
+                Some(NodeData::String(views.join(",")))
+            }
+            "viewport_pane" => {
+                let view = self.viewport_data.emitted_pane.unwrap_or(self.viewport_data.active_pane);
+                Some(NodeData::String(view.label().to_string()))
+            }
+            "palette" => Some(NodeData::String(palette().label().to_string())),
+            "gizmo_mode" => Some(NodeData::String(self.viewport_data.gizmo_mode.as_ref().map_or(NO_GIZMO, GizmoMode::label).to_string())),
+            "face_pick" => Some(NodeData::String(self.viewport_data.face_pick.as_ref().map_or(NO_FACE_PICK, PickMode::label).to_string())),
+            "gizmo_space" => Some(NodeData::String(self.viewport_data.gizmo_space.label().to_string())),
+            "snap_grid" => Some(NodeData::Float(self.viewport_data.snapping.grid)),
+            "snap_angle" => Some(NodeData::Float(self.viewport_data.snapping.angle)),
+            "snap_scale" => Some(NodeData::Float(self.viewport_data.snapping.scale)),
+            "snap_vertex" => Some(NodeData::Boolean(self.viewport_data.snapping.vertex)),
+            "snap_camera" => Some(NodeData::Boolean(self.viewport_data.snapping.camera)),
+            "snapshot_path" => Some(NodeData::String(self.viewport_data.snapshot.output_pattern.clone())),
+            "snapshot_width" => Some(NodeData::Float(self.viewport_data.snapshot.width as f32)),
+            "snapshot_height" => Some(NodeData::Float(self.viewport_data.snapshot.height as f32)),
+            "snapshot_id_matte" => Some(NodeData::Boolean(self.viewport_data.snapshot.id_matte)),
+            name if name.starts_with(SNAPSHOT_AOV_PREFIX) => {
+                let aov = Aov::from_label(name.strip_prefix(SNAPSHOT_AOV_PREFIX)?)?;
+                Some(NodeData::Boolean(self.viewport_data.snapshot.aovs.contains(&aov)))
+            }
+            name => {
+                let value = self.viewport_data.renderer_setting(name.strip_prefix(RENDER_SETTING_PREFIX)?)?;
+                Some(match value {
+                    RenderSettingValue::Flag(flag) => NodeData::Boolean(*flag),
+                    other => NodeData::String(other.to_string()),
+                })
+            }
         }
     }
     
     fn set_parameter(&mut self, name: &str, value: NodeData) {
         match name {
+            SEARCH_PARAMETER => {
+                if let Some(query) = value.as_string() {
+                    self.search.query = query.to_string();
+                }
+            }
             "current_stage" => {
                 if let Some(stage) = value.as_string() {
                     self.viewport_data.load_stage(stage);
                 }
             }
+            PINCH_PARAMETER | PAN_X_PARAMETER | PAN_Y_PARAMETER | ROTATE_PARAMETER => {
+                if let Some(gesture) = value.as_float().and_then(|value| Gesture::from_parameter(name, value)) {
+                    self.viewport_data.handle_gesture(gesture);
+                }
+            }
             "orbit_sensitivity" => {
                 if let Some(sensitivity) = value.as_float() {
                     self.viewport_data.camera_settings.orbit_sensitivity = sensitivity;
@@ -499,6 +2711,21 @@ impl PluginNode for USDViewportNode {
                     self.viewport_data.camera_settings.zoom_sensitivity = sensitivity;
                 }
             }
+            "navigation_smoothing" => {
+                if let Some(smoothing) = value.as_float() {
+                    self.viewport_data.camera_settings.smoothing = smoothing.max(0.0);
+                }
+            }
+            "display_scale" => {
+                if let Some(scale) = value.as_float() {
+                    self.viewport_data.camera_settings.display_scale = scale.max(0.1);
+                }
+            }
+            "invert_orbit_x" | "invert_orbit_y" | "invert_pan_x" | "invert_pan_y" | "invert_zoom" => {
+                if let (Some(invert), Some(enabled)) = (self.viewport_data.camera_settings.invert_mut(name), value.as_boolean()) {
+                    *invert = enabled;
+                }
+            }
             "wireframe" => {
                 if let Some(enabled) = value.as_boolean() {
                     self.viewport_data.viewport_data.settings.wireframe = enabled;
@@ -523,7 +2750,225 @@ impl PluginNode for USDViewportNode {
                     self.viewport_data.viewport_data.settings_dirty = true;
                 }
             }
-            _ => {}
+            "frame" => {
+                if let Some(frame) = value.as_float() {
+                    self.viewport_data.playback.seek(frame as f64);
+                    self.viewport_data.set_time(self.viewport_data.playback.current_time_code);
+                }
+            }
+            "fps" => {
+                if let Some(fps) = value.as_string().and_then(|text| text.parse::<f64>().ok()) {
+                    if fps > 0.0 {
+                        self.viewport_data.playback.fps = fps;
+                    }
+                }
+            }
+            "loop_mode" => {
+                if let Some(mode) = value.as_string().and_then(LoopMode::from_name) {
+                    self.viewport_data.playback.loop_mode = mode;
+                }
+            }
+            "projection_enabled" => {
+                if let Some(enabled) = value.as_boolean() {
+                    self.viewport_data.projection.enabled = enabled;
+                    self.viewport_data.refresh_projection();
+                }
+            }
+            "shading" => {
+                if let Some(mode) = value.as_string().and_then(|mode| SHADING_MODES.into_iter().find(|shading| *shading == mode)) {
+                    self.viewport_data.shading = mode;
+                    self.viewport_data.refresh_projection();
+                }
+            }
+            "complexity" => {
+                if let Some(level) = value.as_string().and_then(ComplexityLevel::from_label) {
+                    // Only subdivision levels change the extracted scene
+                    let refine = level.subdivision_level() != self.viewport_data.complexity.subdivision_level();
+                    self.viewport_data.complexity = level;
+                    if refine {
+                        self.viewport_data.refresh_projection();
+                    }
+                }
+            }
+            "display_primvar" => {
+                if let Some(name) = value.as_string() {
+                    self.viewport_data.display_primvar = (name != NO_PRIMVAR && !name.is_empty()).then(|| name.to_string());
+                    self.viewport_data.refresh_projection();
+                }
+            }
+            "frustum_culling" => {
+                if let Some(enabled) = value.as_boolean() {
+                    self.viewport_data.frustum_culling = enabled;
+                }
+            }
+            "ambient_occlusion" => {
+                if let Some(enabled) = value.as_boolean() {
+                    self.viewport_data.ambient_occlusion.enabled = enabled;
+                }
+            }
+            "ao_radius" | "ao_intensity" => {
+                if let Some(amount) = value.as_float() {
+                    let occlusion = &mut self.viewport_data.ambient_occlusion;
+                    match name {
+                        "ao_radius" => occlusion.radius = amount.max(0.01),
+                        _ => occlusion.intensity = amount.max(0.0),
+                    }
+                }
+            }
+            "path_trace" | "path_trace_denoise" => {
+                if let Some(enabled) = value.as_boolean() {
+                    match name {
+                        "path_trace" => self.viewport_data.path_trace.enabled = enabled,
+                        _ => self.viewport_data.path_trace.denoise = enabled,
+                    }
+                }
+            }
+            "path_trace_samples" | "path_trace_bounces" => {
+                if let Some(amount) = value.as_float() {
+                    let path_trace = &mut self.viewport_data.path_trace;
+                    match name {
+                        "path_trace_samples" => path_trace.max_samples = amount.round().max(1.0) as u32,
+                        _ => path_trace.max_bounces = amount.round().max(0.0) as u32,
+                    }
+                }
+            }
+            "anti_aliasing" => {
+                if let Some(mode) = value.as_string().and_then(AntiAliasing::from_label) {
+                    self.viewport_data.anti_aliasing = mode;
+                }
+            }
+            "view_transform" => {
+                if let Some(transform) = value.as_string().and_then(ViewTransform::from_label) {
+                    self.viewport_data.color_management.view_transform = transform;
+                }
+            }
+            "view_exposure" | "view_gamma" => {
+                if let Some(amount) = value.as_float() {
+                    let color_management = &mut self.viewport_data.color_management;
+                    match name {
+                        "view_exposure" => color_management.exposure = amount,
+                        _ => color_management.gamma = amount.max(0.01),
+                    }
+                }
+            }
+            "layout" => {
+                if let Some(layout) = value.as_string().and_then(PaneLayout::from_label) {
+                    self.viewport_data.set_layout(layout);
+                }
+            }
+            "active_pane" => {
+                if let Some(view) = value.as_string().and_then(PaneView::from_label) {
+                    if self.viewport_data.layout.views().contains(&view) {
+                        self.viewport_data.active_pane = view;
+                    }
+                }
+            }
+            "viewport_pane" => {
+                let view = value.as_string().and_then(PaneView::from_label);
+                self.viewport_data.emitted_pane = view.filter(|view| self.viewport_data.layout.views().contains(view));
+            }
+            "palette" => {
+                if let Some(palette) = value.as_string().and_then(Palette::from_label) {
+                    set_palette(palette);
+                    self.viewport_data.apply_palette();
+                }
+            }
+            "gizmo_mode" => {
+                if let Some(label) = value.as_string() {
+                    self.viewport_data.gizmo_mode = GizmoMode::from_label(label);
+                    self.viewport_data.end_gizmo_drag();
+                    self.viewport_data.refresh_gizmo_target();
+                }
+            }
+            "face_pick" => {
+                if let Some(label) = value.as_string() {
+                    self.viewport_data.face_pick = PickMode::from_label(label);
+                    self.viewport_data.face_drag = None;
+                }
+            }
+            "gizmo_space" => {
+                if let Some(space) = value.as_string().and_then(GizmoSpace::from_label) {
+                    self.viewport_data.gizmo_space = space;
+                    self.viewport_data.apply_gizmo();
+                }
+            }
+            "snap_grid" | "snap_angle" | "snap_scale" => {
+                if let Some(step) = value.as_float() {
+                    let snapping = &mut self.viewport_data.snapping;
+                    match name {
+                        "snap_grid" => snapping.grid = step.max(0.0),
+                        "snap_angle" => snapping.angle = step.max(0.0),
+                        _ => snapping.scale = step.max(0.0),
+                    }
+                }
+            }
+            "snap_vertex" => {
+                if let Some(enabled) = value.as_boolean() {
+                    self.viewport_data.snapping.vertex = enabled;
+                }
+            }
+            "snap_camera" => {
+                if let Some(enabled) = value.as_boolean() {
+                    self.viewport_data.snapping.camera = enabled;
+                }
+            }
+            "projection_camera" => {
+                if let Some(path) = value.as_string() {
+                    self.viewport_data.projection.camera_path = path.to_string();
+                    self.viewport_data.refresh_projection();
+                }
+            }
+            "projection_image" => {
+                if let Some(path) = value.as_string() {
+                    self.viewport_data.projection.image = path.to_string();
+                    self.viewport_data.refresh_projection();
+                }
+            }
+            "renderer" => {
+                if let Some(id) = value.as_string() {
+                    self.viewport_data.set_renderer(RenderBackend::from_id(id));
+                }
+            }
+            "view_camera_path" => {
+                if let Some(path) = value.as_string() {
+                    self.viewport_data.view_camera_path = path.to_string();
+                }
+            }
+            "look_through" => {
+                if let Some(path) = value.as_string() {
+                    self.viewport_data.set_look_through(Some(path.to_string()));
+                }
+            }
+            "snapshot_path" => {
+                if let Some(pattern) = value.as_string() {
+                    self.viewport_data.snapshot.output_pattern = pattern.to_string();
+                }
+            }
+            "snapshot_width" | "snapshot_height" => {
+                if let Some(size) = value.as_float() {
+                    let size = size.round().max(1.0) as u32;
+                    match name {
+                        "snapshot_width" => self.viewport_data.snapshot.width = size,
+                        _ => self.viewport_data.snapshot.height = size,
+                    }
+                }
+            }
+            "snapshot_id_matte" => {
+                if let Some(enabled) = value.as_boolean() {
+                    self.viewport_data.snapshot.id_matte = enabled;
+                }
+            }
+            name => {
+                if let Some(aov) = name.strip_prefix(SNAPSHOT_AOV_PREFIX).and_then(Aov::from_label) {
+                    if let Some(enabled) = value.as_boolean() {
+                        self.viewport_data.snapshot.set_aov(aov, enabled);
+                    }
+                } else if let Some(key) = name.strip_prefix(RENDER_SETTING_PREFIX) {
+                    if let Err(e) = self.viewport_data.set_renderer_setting(key, &value) {
+                        eprintln!("USD Plugin: {}", e);
+                    }
+                }
+            }
         }
     }
     
@@ -533,8 +2978,16 @@ impl PluginNode for USDViewportNode {
         // Process USD stage input
         if let Some(stage_data) = inputs.get("Stage") {
             if let Some(stage_path) = stage_data.as_string() {
-                if stage_path != self.viewport_data.current_stage {
+                // Session layer edits such as variant switches bump the stage revision
+                let revision = with_usd_engine(|engine| engine.stage_revision(stage_path));
+                if stage_path != self.viewport_data.current_stage || revision != self.viewport_data.stage_revision {
+                    let new_stage = stage_path != self.viewport_data.current_stage;
+                    self.viewport_data.stage_revision = revision;
                     self.viewport_data.load_stage(stage_path);
+                    self.viewport_data.refresh_time_range();
+                    if new_stage {
+                        self.viewport_data.fit_panes();
+                    }
                     outputs.insert("Rendered Image".to_string(), 
                         NodeData::String(format!("USD Stage Loaded: {}", stage_path)));
                 }
@@ -543,25 +2996,68 @@ impl PluginNode for USDViewportNode {
             // No stage connected - clear current stage
             if !self.viewport_data.current_stage.is_empty() {
                 self.viewport_data.current_stage.clear();
+                self.viewport_data.stage_id.clear();
+                self.viewport_data.gizmo_ops = None;
                 self.viewport_data.viewport_data.scene = SceneData::default();
                 self.viewport_data.viewport_data.scene_dirty = true;
             }
         }
+        if !self.viewport_data.snapshot.written.is_empty() {
+            outputs.insert("Rendered Image".to_string(),
+                NodeData::String(self.viewport_data.snapshot.written.join(", ")));
+        }
+        
+        // Playback drives time while playing, otherwise follow the time input
+        if self.viewport_data.playback.playing {
+            self.viewport_data.tick_playback();
+        } else if let Some(time) = inputs.get("Time").and_then(|data| data.as_float()) {
+            self.viewport_data.playback.seek(time as f64);
+            self.viewport_data.set_time(time as f64);
+        }
+        self.viewport_data.settle_navigation();
+        self.viewport_data.track_camera();
+        self.viewport_data.apply_gizmo();
+        self.viewport_data.apply_palette();
+        
+        // A connected selection replaces clicked prims whenever it changes to a prim not already selected
+        self.viewport_data.sync_selection();
+        let selection_input = inputs.get("Selected Prim")
+            .and_then(|data| data.as_string())
+            .filter(|path| !path.is_empty())
+            .map(str::to_string);
+        if selection_input != self.viewport_data.selection_input {
+            self.viewport_data.selection_input = selection_input.clone();
+            if selection_input.as_ref().is_none_or(|path| !self.viewport_data.selected_prims.contains(path)) {
+                self.viewport_data.select(selection_input.into_iter().collect());
+            }
+        }
+        outputs.insert("Selected Prims".to_string(), NodeData::String(self.viewport_data.selected_prims.join(", ")));
+        outputs.insert("Selection Changed".to_string(), NodeData::Boolean(std::mem::take(&mut self.viewport_data.selection_changed)));
         
-        // Handle camera input if provided
-        if let Some(camera_data) = inputs.get("Camera") {
-            if let Some(camera_path) = camera_data.as_string() {
-                println!("USD Plugin: Using camera: {}", camera_path);
-                // TODO: Extract camera from USD stage and apply to viewport
+        // A connected camera takes over the view whenever it changes
+        let camera_input = inputs.get("Camera")
+            .and_then(|data| data.as_string())
+            .filter(|path| !path.is_empty())
+            .map(str::to_string);
+        if camera_input != self.viewport_data.camera_input {
+            self.viewport_data.camera_input = camera_input.clone();
+            if let Some(camera_path) = &camera_input {
+                println!("USD Plugin: Looking through camera: {}", camera_path);
+                self.viewport_data.set_look_through(camera_input);
             }
         }
         
         outputs
     }
     
-    /// Provide viewport data to the core for rendering
+    /// Provide a pane's viewport data to the core for rendering
+    ///
+    /// Hosts tiling the layout read the "pane_views" parameter, then set
+    /// "viewport_pane" to each view before fetching its data; otherwise the
+    /// active pane is emitted.
     fn get_viewport_data(&self) -> Option<ViewportData> {
-        Some(self.viewport_data.viewport_data.clone())
+        let view = self.viewport_data.emitted_pane.unwrap_or(self.viewport_data.active_pane);
+        Some(self.viewport_data.pane_data(view))
     }
     
     /// Handle viewport camera manipulation
@@ -571,11 +3067,153 @@ impl PluginNode for USDViewportNode {
     
     /// Handle viewport settings changes
     fn handle_viewport_settings(&mut self, settings: ViewportSettings) {
-        self.viewport_data.viewport_data.settings = settings;
+        let active_pane = self.viewport_data.active_pane;
+        match self.viewport_data.panes.iter_mut().find(|pane| pane.view == active_pane) {
+            Some(pane) => pane.settings = settings,
+            None => self.viewport_data.viewport_data.settings = settings,
+        }
         self.viewport_data.viewport_data.settings_dirty = true;
     }
     
     fn supports_viewport(&self) -> bool {
         true
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::primvars::MeshPrimvars;
+    use super::scene_delegate::build_mesh_geometry;
+    use crate::face_set_node::USDFaceSetNode;
+    
+    #[test]
+    fn face_pick_drags_feed_face_set_nodes() {
+        let stage_id = with_usd_engine(|engine| engine.create_stage("face_pick_drag")).unwrap().identifier;
+        let mut viewport = USDViewportNode {
+            id: "viewport".to_string(),
+            position: Pos2::new(0.0, 0.0),
+            viewport_data: USDViewport::default(),
+            search: ParameterSearch::default(),
+        };
+        viewport.viewport_data.stage_id = stage_id.clone();
+        
+        // Two unit quads side by side facing the camera, two triangles per face
+        let scene = &mut viewport.viewport_data.viewport_data.scene;
+        scene.meshes.push(MeshData {
+            id: "/World/Plane".to_string(),
+            vertices: vec![-1.0, -1.0, 0.0, 0.0, -1.0, 0.0, 1.0, -1.0, 0.0, -1.0, 1.0, 0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0],
+            indices: vec![0, 1, 4, 0, 4, 3, 1, 2, 5, 1, 5, 4],
+            transform: Mat4::IDENTITY.to_cols_array_2d(),
+            ..Default::default()
+        });
+        scene.camera = CameraData { position: [0.0, 0.0, 5.0], target: [0.0; 3], ..Default::default() };
+        viewport.viewport_data.mesh_faces.insert("/World/Plane".to_string(), vec![0, 0, 1, 1]);
+        
+        // Without Face Pick, drags are left to the host
+        assert!(!viewport.handle_viewport_drag_start(0.0, 0.0, 100.0, 100.0));
+        viewport.set_parameter("face_pick", NodeData::String("Replace".to_string()));
+        assert!(viewport.handle_viewport_drag_start(0.0, 0.0, 100.0, 100.0));
+        // The left half of the view covers only the left quad
+        viewport.handle_viewport_drag(45.0, 100.0, 100.0, 100.0);
+        viewport.handle_viewport_drag_end();
+        assert_eq!(picked_faces(&stage_id), FaceSelection::from([("/World/Plane".to_string(), [0].into())]));
+        
+        let mut face_set = USDFaceSetNode::new(Pos2::new(0.0, 0.0));
+        face_set.handle_ui_action(UIAction::ButtonClicked { action: "use_viewport_selection".to_string() });
+        let outputs = face_set.process(&HashMap::from([("Stage".to_string(), NodeData::String(stage_id.clone()))]));
+        assert_eq!(face_set.get_parameter("mesh_path").as_ref().and_then(NodeData::as_string), Some("/World/Plane"));
+        assert_eq!(face_set.get_parameter("faces").as_ref().and_then(NodeData::as_string), Some("0"));
+        assert!(outputs.contains_key("Subset"));
+        
+        // Add picks the right quad with a click, keeping the left one
+        viewport.set_parameter("face_pick", NodeData::String("Add".to_string()));
+        viewport.handle_viewport_click(60.0, 50.0, 100.0, 100.0, false);
+        assert_eq!(picked_faces(&stage_id)["/World/Plane"], [0, 1].into());
+    }
+    
+    #[test]
+    fn gesture_parameters_move_the_camera() {
+        let mut viewport = USDViewportNode {
+            id: "viewport".to_string(),
+            position: Pos2::new(0.0, 0.0),
+            viewport_data: USDViewport::default(),
+            search: ParameterSearch::default(),
+        };
+        viewport.set_parameter("navigation_smoothing", NodeData::Float(0.0));
+        let distance = |viewport: &USDViewportNode| {
+            let camera = &viewport.viewport_data.viewport_data.scene.camera;
+            Vec3::from(camera.position).distance(Vec3::from(camera.target))
+        };
+        let before = distance(&viewport);
+        viewport.set_parameter(PINCH_PARAMETER, NodeData::Float(2.0));
+        assert!(distance(&viewport) < before);
+    }
+    
+    #[test]
+    fn hosts_tile_and_focus_panes_through_parameters() {
+        let mut viewport = USDViewportNode {
+            id: "viewport".to_string(),
+            position: Pos2::new(0.0, 0.0),
+            viewport_data: USDViewport::default(),
+            search: ParameterSearch::default(),
+        };
+        viewport.set_parameter("layout", NodeData::String("Quad".to_string()));
+        let views = viewport.get_parameter("pane_views").and_then(|views| views.as_string().map(str::to_string)).unwrap();
+        assert_eq!(views, "Top,Perspective,Front,Side");
+        
+        let cameras: Vec<_> = views.split(',').map(|view| {
+            viewport.set_parameter("viewport_pane", NodeData::String(view.to_string()));
+            viewport.get_viewport_data().unwrap().scene.camera.position
+        }).collect();
+        assert!(cameras.windows(2).all(|pair| pair[0] != pair[1]));
+        // Emitting a pane doesn't focus it
+        assert_eq!(viewport.viewport_data.active_pane, PaneView::Perspective);
+        
+        let changes = viewport.handle_ui_action(UIAction::ParameterChanged {
+            parameter: "active_pane".to_string(),
+            value: NodeData::String("Front".to_string()),
+        });
+        assert_eq!(viewport.viewport_data.active_pane, PaneView::Front);
+        assert_eq!(changes[0].value.as_string(), Some("Front"));
+        
+        // Panes the layout no longer shows are neither emitted nor active
+        viewport.set_parameter("layout", NodeData::String("Single".to_string()));
+        assert_eq!(viewport.viewport_data.emitted_pane, None);
+        assert_eq!(viewport.viewport_data.active_pane, PaneView::Perspective);
+    }
+    
+    #[test]
+    fn shading_choices_map_to_renderer_modes() {
+        for shading in SHADING_MODES.into_iter().filter(|shading| *shading != BOUNDS_SHADING) {
+            assert!(ShadingMode::from_label(shading).is_some(), "{}", shading);
+        }
+        assert_eq!(ShadingMode::from_label("Material Preview"), Some(ShadingMode::MaterialPreview));
+    }
+    
+    #[test]
+    fn display_colors_replace_materials_of_host_meshes() {
+        let points = [Vec3::ZERO, Vec3::X, Vec3::new(1.0, 1.0, 0.0), Vec3::Y];
+        let mut quad = build_mesh_geometry("/World/Quad", &points, &[4], &[0, 1, 2, 3], &[], Mat4::IDENTITY, &MeshPrimvars::default()).unwrap();
+        quad.material_path = Some("/World/Looks/Red".to_string());
+        quad.colors = (0..quad.vertices.len())
+            .map(|index| if index % 2 == 0 { Vec4::new(1.0, 0.0, 0.0, 1.0) } else { Vec4::new(0.0, 0.0, 1.0, 1.0) })
+            .collect();
+        
+        let mut shaded = HostSceneSink::default();
+        shaded.begin_scene("display_colors", 1.0);
+        shaded.add_geometry(quad.clone());
+        assert_eq!(shaded.scene.meshes[0].material_id.as_deref(), Some("/World/Looks/Red"));
+        
+        let mut sink = HostSceneSink { display_colors: true, ..HostSceneSink::default() };
+        sink.begin_scene("display_colors", 1.0);
+        sink.add_geometry(quad.clone());
+        let mut plain = quad.clone();
+        plain.prim_path = "/World/Plain".to_string();
+        plain.colors.clear();
+        sink.add_geometry(plain);
+        assert_eq!(sink.scene.meshes[0].material_id.as_deref(), Some("/World/Quad:displayColor"));
+        assert_eq!(sink.scene.meshes[1].material_id, None);
+        assert_eq!(sink.scene.materials.len(), 1);
+        assert_eq!(sink.scene.materials[0].base_color, [0.5, 0.0, 0.5, 1.0]);
+    }
+}
@@ -0,0 +1,182 @@
+//! Navigation input normalization and smoothing
+//!
+//! The host hands over camera deltas once per input event, in physical
+//! pixels. Deltas are converted to logical points so navigation feels the
+//! same on high-DPI displays, then eased in over a time constant measured in
+//! seconds: the share of a drag applied after a given time is the same
+//! whether events arrive at 30 or 240 per second.
+
+use std::time::Instant;
+use glam::Vec2;
+
+/// Assumed event interval when there is no previous event to measure from
+pub const REFERENCE_FRAME_TIME: f32 = 1.0 / 60.0;
+
+/// Longest interval a single update accounts for, so a stalled frame doesn't apply a whole drag at once
+const MAX_STEP: f32 = 0.1;
+
+/// Remaining movement small enough to drop, in logical points
+const SETTLED: f32 = 1e-3;
+
+/// Convert a raw delta to logical points, flipping inverted axes
+pub fn normalize_delta(delta: Vec2, display_scale: f32, invert: [bool; 2]) -> Vec2 {
+    let sign = |inverted: bool| if inverted { -1.0 } else { 1.0 };
+    delta / display_scale.max(0.1) * Vec2::new(sign(invert[0]), sign(invert[1]))
+}
+
+/// Touch and trackpad gestures, for navigating without a three-button mouse
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Gesture {
+    /// Fingers spread (above 1) or pinched together (below 1) by a factor since the last event
+    Pinch { scale: f32 },
+    /// Two fingers dragged together, in physical pixels
+    TwoFingerPan { delta_x: f32, delta_y: f32 },
+    /// Two fingers twisted by an angle in radians, counter-clockwise positive
+    Rotate { angle: f32 },
+}
+
+/// Parameter a host sets to the scale of a pinch
+pub const PINCH_PARAMETER: &str = "gesture_pinch";
+/// Parameter a host sets to the x delta of a two-finger pan, in physical pixels
+pub const PAN_X_PARAMETER: &str = "gesture_pan_x";
+/// Parameter a host sets to the y delta of a two-finger pan, in physical pixels
+pub const PAN_Y_PARAMETER: &str = "gesture_pan_y";
+/// Parameter a host sets to the angle of a twist, in radians
+pub const ROTATE_PARAMETER: &str = "gesture_rotate";
+
+impl Gesture {
+    /// The gesture a host reports by setting the parameter `name` to `value`, if it is a gesture parameter
+    ///
+    /// Gestures reach the viewport node through `set_parameter`, one event
+    /// per call; a pan moving both axes sets both of its parameters.
+    pub fn from_parameter(name: &str, value: f32) -> Option<Self> {
+        match name {
+            PINCH_PARAMETER => Some(Gesture::Pinch { scale: value }),
+            PAN_X_PARAMETER => Some(Gesture::TwoFingerPan { delta_x: value, delta_y: 0.0 }),
+            PAN_Y_PARAMETER => Some(Gesture::TwoFingerPan { delta_x: 0.0, delta_y: value }),
+            ROTATE_PARAMETER => Some(Gesture::Rotate { angle: value }),
+            _ => None,
+        }
+    }
+}
+
+/// Zoom delta that divides the distance to the target by a pinch's scale
+pub fn pinch_zoom(scale: f32) -> f32 {
+    if scale > 0.0 {
+        1.0 - 1.0 / scale
+    } else {
+        0.0
+    }
+}
+
+/// Eases deltas in exponentially over time
+#[derive(Debug, Clone, Default)]
+pub struct DeltaSmoother {
+    /// Movement received but not applied yet
+    pub pending: Vec2,
+    last_update: Option<Instant>,
+}
+
+impl DeltaSmoother {
+    /// Add a delta arriving at `now` and return the movement to apply now
+    ///
+    /// `smoothing` is the time constant in seconds; zero applies deltas as
+    /// they arrive.
+    pub fn push(&mut self, delta: Vec2, smoothing: f32, now: Instant) -> Vec2 {
+        self.pending += delta;
+        self.take(smoothing, now)
+    }
+    
+    /// Movement to apply between events while pending movement remains
+    pub fn settle(&mut self, smoothing: f32, now: Instant) -> Option<Vec2> {
+        if self.pending.length() < SETTLED {
+            *self = Self::default();
+            return None;
+        }
+        Some(self.take(smoothing, now))
+    }
+    
+    fn take(&mut self, smoothing: f32, now: Instant) -> Vec2 {
+        let elapsed = self.last_update
+            .map_or(REFERENCE_FRAME_TIME, |last| now.duration_since(last).as_secs_f32())
+            .min(MAX_STEP);
+        self.last_update = Some(now);
+        let share = if smoothing > 0.0 { 1.0 - (-elapsed / smoothing).exp() } else { 1.0 };
+        let applied = self.pending * share;
+        self.pending -= applied;
+        applied
+    }
+}
+
+/// Smoothing state of each kind of camera manipulation
+#[derive(Debug, Clone, Default)]
+pub struct NavigationSmoothing {
+    pub orbit: DeltaSmoother,
+    pub pan: DeltaSmoother,
+    /// Zoom delta in x
+    pub zoom: DeltaSmoother,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    
+    /// Total movement applied after one second of a steady drag, with events `rate` per second
+    fn drag(rate: u32, smoothing: f32) -> f32 {
+        let start = Instant::now();
+        let mut smoother = DeltaSmoother::default();
+        let interval = 1.0 / rate as f32;
+        let mut applied = 0.0;
+        for event in 1..=rate {
+            let now = start + Duration::from_secs_f32(event as f32 * interval);
+            applied += smoother.push(Vec2::new(100.0 * interval, 0.0), smoothing, now).x;
+        }
+        applied
+    }
+    
+    #[test]
+    fn smoothing_does_not_depend_on_the_event_rate() {
+        assert!((drag(30, 0.0) - 100.0).abs() < 1e-3);
+        let slow = drag(30, 0.1);
+        let fast = drag(240, 0.1);
+        assert!(slow < 100.0 && (slow - fast).abs() < 2.0, "{} vs {}", slow, fast);
+        
+        let mut smoother = DeltaSmoother::default();
+        let start = Instant::now();
+        smoother.push(Vec2::new(10.0, 0.0), 0.1, start);
+        let mut total = 10.0 - smoother.pending.x;
+        let mut frame = 1;
+        while let Some(delta) = smoother.settle(0.1, start + Duration::from_millis(16 * frame)) {
+            total += delta.x;
+            frame += 1;
+        }
+        assert!((total - 10.0).abs() < SETTLED);
+        assert_eq!(smoother.pending, Vec2::ZERO);
+    }
+    
+    #[test]
+    fn deltas_use_logical_points_and_inverted_axes() {
+        assert_eq!(normalize_delta(Vec2::new(4.0, 2.0), 2.0, [false, false]), Vec2::new(2.0, 1.0));
+        assert_eq!(normalize_delta(Vec2::new(4.0, 2.0), 1.0, [true, false]), Vec2::new(-4.0, 2.0));
+        assert_eq!(normalize_delta(Vec2::new(4.0, 2.0), 1.0, [false, true]), Vec2::new(4.0, -2.0));
+    }
+    
+    #[test]
+    fn gestures_arrive_as_parameters() {
+        assert_eq!(Gesture::from_parameter(PINCH_PARAMETER, 2.0), Some(Gesture::Pinch { scale: 2.0 }));
+        assert_eq!(Gesture::from_parameter(PAN_Y_PARAMETER, 3.0), Some(Gesture::TwoFingerPan { delta_x: 0.0, delta_y: 3.0 }));
+        assert_eq!(Gesture::from_parameter(ROTATE_PARAMETER, 0.5), Some(Gesture::Rotate { angle: 0.5 }));
+        assert_eq!(Gesture::from_parameter("zoom_sensitivity", 1.0), None);
+    }
+    
+    #[test]
+    fn pinches_scale_the_target_distance() {
+        // Zooming moves the camera by the delta times its distance to the target
+        let distance_after = |scale: f32| 10.0 * (1.0 - pinch_zoom(scale));
+        assert!((distance_after(2.0) - 5.0).abs() < 1e-5);
+        assert!((distance_after(0.5) - 20.0).abs() < 1e-5);
+        assert_eq!(pinch_zoom(1.0), 0.0);
+        assert_eq!(pinch_zoom(0.0), 0.0);
+    }
+}
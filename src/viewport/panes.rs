@@ -0,0 +1,189 @@
+//! Multi-pane viewport layouts
+//!
+//! A quad layout shows the stage through top, front and side cameras next
+//! to the perspective free camera, as in DCC quad views. Each axis pane has
+//! its own camera and viewport settings; its camera looks straight down an
+//! axis through a long lens, so the view is close to orthographic while
+//! still going through the host's perspective renderer. Axis views pan and
+//! zoom but never orbit, so they stay aligned.
+
+use glam::Vec3;
+use nodle_plugin_sdk::*;
+
+/// Vertical field of view of the axis cameras, in degrees
+pub const AXIS_FOV_DEGREES: f32 = 5.0;
+
+/// Camera a pane looks through
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaneView {
+    /// The free camera, or the stage camera looked through
+    Perspective,
+    /// Looking down the Y axis
+    Top,
+    /// Looking down the Z axis
+    Front,
+    /// Looking down the X axis
+    Side,
+}
+
+impl PaneView {
+    pub const ALL: [PaneView; 4] = [PaneView::Perspective, PaneView::Top, PaneView::Front, PaneView::Side];
+    
+    pub fn label(&self) -> &'static str {
+        match self {
+            PaneView::Perspective => "Perspective",
+            PaneView::Top => "Top",
+            PaneView::Front => "Front",
+            PaneView::Side => "Side",
+        }
+    }
+    
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|view| view.label() == label)
+    }
+    
+    /// Direction from the target to an axis camera and that camera's up vector, None for perspective
+    pub fn axis(&self) -> Option<(Vec3, Vec3)> {
+        match self {
+            PaneView::Perspective => None,
+            PaneView::Top => Some((Vec3::Y, Vec3::NEG_Z)),
+            PaneView::Front => Some((Vec3::Z, Vec3::Y)),
+            PaneView::Side => Some((Vec3::X, Vec3::Y)),
+        }
+    }
+}
+
+/// Arrangement of a viewport's panes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PaneLayout {
+    #[default]
+    Single,
+    Quad,
+}
+
+impl PaneLayout {
+    pub const ALL: [PaneLayout; 2] = [PaneLayout::Single, PaneLayout::Quad];
+    
+    pub fn label(&self) -> &'static str {
+        match self {
+            PaneLayout::Single => "Single",
+            PaneLayout::Quad => "Quad",
+        }
+    }
+    
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|layout| layout.label() == label)
+    }
+    
+    /// Views of the layout's panes, left to right and top to bottom
+    pub fn views(&self) -> &'static [PaneView] {
+        match self {
+            PaneLayout::Single => &[PaneView::Perspective],
+            PaneLayout::Quad => &[PaneView::Top, PaneView::Perspective, PaneView::Front, PaneView::Side],
+        }
+    }
+}
+
+/// An axis view with its own camera and display settings
+#[derive(Debug, Clone)]
+pub struct ViewPane {
+    pub view: PaneView,
+    pub camera: CameraData,
+    pub settings: ViewportSettings,
+}
+
+impl ViewPane {
+    /// Pane of an axis view showing a sphere, None for the perspective view
+    pub fn new(view: PaneView, settings: ViewportSettings, center: Vec3, radius: f32) -> Option<Self> {
+        view.axis()?;
+        let mut pane = Self {
+            view,
+            camera: CameraData {
+                fov: AXIS_FOV_DEGREES.to_radians(),
+                ..CameraData::default()
+            },
+            settings,
+        };
+        pane.fit(center, radius);
+        Some(pane)
+    }
+    
+    /// Center a sphere in the view and back the camera off along its axis until the sphere fills it
+    pub fn fit(&mut self, center: Vec3, radius: f32) {
+        let Some((direction, up)) = self.view.axis() else {
+            return;
+        };
+        let camera = &mut self.camera;
+        // Fit the narrower of the vertical and horizontal fields of view
+        let half_fov = ((camera.fov * 0.5).tan() * camera.aspect.min(1.0)).atan();
+        let radius = radius.max(1e-3);
+        let distance = radius / half_fov.sin();
+        camera.target = center.into();
+        camera.position = (center + direction * distance).into();
+        camera.up = up.into();
+        camera.near = (distance - radius * 2.0).max(distance * 0.01);
+        camera.far = distance + radius * 2.0;
+    }
+    
+    /// Move the camera by normalized navigation deltas, ignoring orbits
+    ///
+    /// Pans cover as much of the view as they would in a perspective view
+    /// at the default field of view, so both panes track the cursor alike.
+    pub fn navigate(&mut self, manipulation: &CameraManipulation, pan_sensitivity: f32, zoom_sensitivity: f32) {
+        let camera = &mut self.camera;
+        let (position, target, up) = (Vec3::from(camera.position), Vec3::from(camera.target), Vec3::from(camera.up));
+        match *manipulation {
+            CameraManipulation::Pan { delta_x, delta_y } => {
+                let forward = target - position;
+                let scale = (camera.fov * 0.5).tan() / (CameraData::default().fov * 0.5).tan();
+                let right = forward.cross(up) * scale;
+                let offset = (right * delta_x + up * forward.length() * scale * delta_y) * pan_sensitivity;
+                camera.position = (position + offset).into();
+                camera.target = (target + offset).into();
+            }
+            CameraManipulation::Zoom { delta } => {
+                camera.position = (position + (target - position) * delta * zoom_sensitivity).into();
+            }
+            CameraManipulation::SetPosition { position, target } => {
+                camera.position = position;
+                camera.target = target;
+            }
+            _ => {}
+        }
+    }
+    
+    /// Viewport data showing the scene of `data` through this pane
+    pub fn viewport_data(&self, data: &ViewportData) -> ViewportData {
+        let mut data = data.clone();
+        data.scene.camera = self.camera.clone();
+        data.settings = self.settings.clone();
+        data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn axis_panes_fit_and_pan_along_their_axis() {
+        assert_eq!(PaneLayout::Quad.views().len(), 4);
+        assert!(ViewPane::new(PaneView::Perspective, ViewportSettings::default(), Vec3::ZERO, 1.0).is_none());
+        
+        let center = Vec3::new(1.0, 2.0, 3.0);
+        let mut top = ViewPane::new(PaneView::Top, ViewportSettings::default(), center, 2.0).unwrap();
+        let offset = Vec3::from(top.camera.position) - center;
+        assert!(offset.normalize().abs_diff_eq(Vec3::Y, 1e-6));
+        // The sphere just fills the view
+        let half_fov = (top.camera.fov * 0.5).tan().atan();
+        assert!((offset.length() * half_fov.sin() - 2.0).abs() < 1e-3);
+        assert!(top.camera.near < offset.length() - 2.0 && top.camera.far > offset.length() + 2.0);
+        
+        // Pans slide along the view plane and orbits leave the view aligned
+        top.navigate(&CameraManipulation::Pan { delta_x: 0.1, delta_y: 0.0 }, 1.0, 1.0);
+        top.navigate(&CameraManipulation::Orbit { delta_x: 0.5, delta_y: 0.5 }, 1.0, 1.0);
+        let moved = Vec3::from(top.camera.target) - center;
+        assert!(moved.x.abs() > 0.01 && moved.y.abs() < 1e-5);
+        assert!((Vec3::from(top.camera.position) - Vec3::from(top.camera.target)).normalize().abs_diff_eq(Vec3::Y, 1e-6));
+    }
+}
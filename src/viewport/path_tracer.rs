@@ -0,0 +1,441 @@
+//! Progressive CPU path tracing for the final quality preview
+//!
+//! While the camera is idle the viewport can show the stage path traced on
+//! the CPU instead of rasterized. Rays are cast through a BVH of the
+//! extracted meshes' triangles, shaded with their preview surface base
+//! color, metallic, roughness and emission, lit by UsdLux lights through
+//! shadow rays and by the dome's sky and ground, and bounced diffusely or
+//! glossily for indirect light. Light linking is not followed.
+//!
+//! Each refinement traces rows of pixels across all cores within a time
+//! budget and adds their samples to an accumulation, so the frame sharpens
+//! over many viewport frames. Any change of view, frame size or scene
+//! cancels the accumulation, and tracing resumes once nothing has changed
+//! for `IDLE_DELAY`. The first surface each ray hits is accumulated along
+//! with its radiance, for the denoiser to keep that surface's edges.
+
+use std::f32::consts::PI;
+use std::time::{Duration, Instant};
+use glam::{Mat4, Vec3};
+use crate::capture::{linear_to_srgb, CapturedFrame};
+use crate::core::bvh::{Bvh, Triangle};
+use super::color_management::ColorManagement;
+use super::denoise::{denoise, PixelFeatures};
+use super::environment::Environment;
+
+/// Time the view must stay unchanged before tracing resumes
+pub const IDLE_DELAY: Duration = Duration::from_millis(300);
+
+/// Distance rays start off the surface they leave, against self-intersection
+const RAY_OFFSET: f32 = 1e-4;
+
+/// Final quality preview controls of the viewport
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PathTraceSettings {
+    pub enabled: bool,
+    /// Samples per pixel after which the frame is final
+    pub max_samples: u32,
+    /// Indirect bounces after the first hit
+    pub max_bounces: u32,
+    /// Show the frame denoised
+    pub denoise: bool,
+}
+
+impl Default for PathTraceSettings {
+    fn default() -> Self {
+        Self { enabled: false, max_samples: 256, max_bounces: 4, denoise: true }
+    }
+}
+
+/// Preview surface inputs the tracer shades with
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TraceMaterial {
+    pub base_color: Vec3,
+    pub metallic: f32,
+    pub roughness: f32,
+    pub emission: Vec3,
+}
+
+impl Default for TraceMaterial {
+    /// UsdPreviewSurface defaults
+    fn default() -> Self {
+        Self { base_color: Vec3::splat(0.18), metallic: 0.0, roughness: 0.5, emission: Vec3::ZERO }
+    }
+}
+
+impl TraceMaterial {
+    fn diffuse_color(&self) -> Vec3 {
+        self.base_color * (1.0 - self.metallic)
+    }
+    
+    /// Reflectance at normal incidence, 4% for dielectrics
+    fn specular_color(&self) -> Vec3 {
+        Vec3::splat(0.04).lerp(self.base_color, self.metallic)
+    }
+    
+    /// Color the surface reflects in all, the albedo the denoiser compares
+    fn albedo(&self) -> Vec3 {
+        (self.diffuse_color() + self.specular_color()).min(Vec3::ONE)
+    }
+    
+    /// Lambert diffuse plus a normalized Blinn-Phong highlight as wide as the roughness
+    fn brdf(&self, normal: Vec3, view: Vec3, light: Vec3) -> Vec3 {
+        let roughness = self.roughness.clamp(0.05, 1.0);
+        let shininess = (2.0 / roughness.powi(4) - 2.0).max(1.0);
+        let half = (view + light).normalize_or_zero();
+        let highlight = (shininess + 8.0) / (8.0 * PI) * normal.dot(half).max(0.0).powf(shininess);
+        self.diffuse_color() / PI + self.specular_color() * highlight
+    }
+}
+
+/// A mesh in world space
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceMesh {
+    pub points: Vec<Vec3>,
+    /// Corner indices, three per triangle
+    pub indices: Vec<u32>,
+    pub material: TraceMaterial,
+}
+
+/// A light the tracer casts shadow rays to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TraceLight {
+    /// Light travelling along `direction`, from a cone `angle` degrees wide
+    Distant { direction: Vec3, radiance: Vec3, angle: f32 },
+    /// Light from a sphere around `position`, zero wide for point lights
+    ///
+    /// `cone` holds the cosines off `axis` where a ShapingAPI cone starts
+    /// fading and where it ends, see `LuxParams::cone_cosines`.
+    Sphere { position: Vec3, radius: f32, intensity: Vec3, axis: Vec3, cone: Option<(f32, f32)> },
+}
+
+/// Triangles, materials and lights of a stage, ready to trace
+#[derive(Debug, Clone)]
+pub struct TraceScene {
+    bvh: Bvh,
+    materials: Vec<TraceMaterial>,
+    lights: Vec<TraceLight>,
+    environment: Environment,
+}
+
+impl TraceScene {
+    pub fn new(meshes: &[TraceMesh], lights: Vec<TraceLight>, environment: Environment) -> Self {
+        let triangles = meshes.iter().enumerate()
+            .flat_map(|(owner, mesh)| mesh.indices.chunks_exact(3).filter_map(move |triangle| {
+                let corner = |index: u32| mesh.points.get(index as usize).copied();
+                Some(Triangle { corners: [corner(triangle[0])?, corner(triangle[1])?, corner(triangle[2])?], owner })
+            }))
+            .collect();
+        Self {
+            bvh: Bvh::new(triangles),
+            materials: meshes.iter().map(|mesh| mesh.material).collect(),
+            lights,
+            environment,
+        }
+    }
+    
+    /// Radiance arriving along a ray, following it for up to `bounces` indirect bounces, and the first surface it hits
+    fn radiance(&self, mut origin: Vec3, mut direction: Vec3, bounces: u32, random: &mut Random) -> (Vec3, PixelFeatures) {
+        let mut color = Vec3::ZERO;
+        let mut throughput = Vec3::ONE;
+        let mut features = PixelFeatures::default();
+        for bounce in 0..=bounces {
+            let Some(hit) = self.bvh.intersect(origin, direction, f32::INFINITY) else {
+                return (color + throughput * self.sky(direction), features);
+            };
+            let material = self.materials[hit.owner];
+            let normal = if hit.normal.dot(direction) > 0.0 { -hit.normal } else { hit.normal };
+            if bounce == 0 {
+                features = PixelFeatures { albedo: material.albedo(), normal, depth: hit.distance };
+            }
+            let point = origin + direction * hit.distance + normal * RAY_OFFSET;
+            color += throughput * (material.emission + self.direct_light(point, normal, -direction, &material, random));
+            if bounce == bounces {
+                break;
+            }
+            
+            // Glossy or diffuse bounce, picked by how much each reflects
+            let (specular, diffuse) = (material.specular_color(), material.diffuse_color());
+            let glossy = (specular.max_element() / (specular.max_element() + diffuse.max_element()).max(1e-6)).clamp(0.05, 0.95);
+            if random.next() < glossy {
+                let reflected = direction - 2.0 * direction.dot(normal) * normal;
+                direction = (reflected + random.in_sphere() * material.roughness * material.roughness).normalize_or_zero();
+                if direction.dot(normal) <= 0.0 {
+                    break;
+                }
+                throughput *= specular / glossy;
+            } else {
+                direction = random.cosine_hemisphere(normal);
+                throughput *= diffuse / (1.0 - glossy);
+            }
+            origin = point;
+        }
+        (color, features)
+    }
+    
+    /// Light reaching a point from every light, through shadow rays
+    fn direct_light(&self, point: Vec3, normal: Vec3, view: Vec3, material: &TraceMaterial, random: &mut Random) -> Vec3 {
+        self.lights.iter()
+            .map(|light| {
+                let (toward, distance, incoming) = match *light {
+                    TraceLight::Distant { direction, radiance, angle } => {
+                        let spread = (angle.to_radians() * 0.5).tan();
+                        ((random.in_sphere() * spread - direction).normalize_or_zero(), f32::INFINITY, radiance)
+                    }
+                    TraceLight::Sphere { position, radius, intensity, axis, cone } => {
+                        let offset = position + random.in_sphere() * radius - point;
+                        let distance = offset.length().max(1e-6);
+                        let toward = offset / distance;
+                        let shaping = cone.map_or(1.0, |(inner, outer)| {
+                            ((-toward.dot(axis) - outer) / (inner - outer).max(1e-6)).clamp(0.0, 1.0)
+                        });
+                        (toward, distance, intensity * shaping / (distance * distance))
+                    }
+                };
+                let cosine = normal.dot(toward);
+                if cosine <= 0.0 || incoming == Vec3::ZERO || self.bvh.intersect(point, toward, distance - RAY_OFFSET).is_some() {
+                    return Vec3::ZERO;
+                }
+                material.brdf(normal, view, toward) * incoming * cosine
+            })
+            .sum()
+    }
+    
+    /// Dome radiance of rays leaving the scene, sky above the horizon and ground below
+    fn sky(&self, direction: Vec3) -> Vec3 {
+        match direction.y >= 0.0 {
+            true => self.environment.sky,
+            false => self.environment.ground,
+        }
+    }
+}
+
+/// Pinhole camera the frame is traced through
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TraceCamera {
+    pub position: Vec3,
+    pub target: Vec3,
+    pub up: Vec3,
+    /// Vertical field of view in radians
+    pub fov_y: f32,
+}
+
+impl TraceCamera {
+    /// Directions through points of a frame `aspect` wide per unit of height, given from its top left corner in [0, 1]
+    fn rays(&self, aspect: f32) -> impl Fn(f32, f32) -> Vec3 + Copy + Send {
+        let camera_to_world = Mat4::look_at_rh(self.position, self.target, self.up).inverse();
+        let half_height = (self.fov_y * 0.5).tan();
+        move |x, y| {
+            let local = Vec3::new((x * 2.0 - 1.0) * half_height * aspect, (1.0 - y * 2.0) * half_height, -1.0);
+            camera_to_world.transform_vector3(local).normalize_or_zero()
+        }
+    }
+}
+
+/// Accumulated samples of the frame being traced
+#[derive(Debug, Clone)]
+pub struct PathTracer {
+    camera: Option<TraceCamera>,
+    width: u32,
+    height: u32,
+    /// Summed radiance of every pixel, top row first
+    accumulated: Vec<Vec3>,
+    /// Summed first hits of every pixel, parallel to `accumulated`
+    features: Vec<PixelFeatures>,
+    /// Samples every pixel has
+    pub samples: u32,
+    /// Rows already holding one more sample than `samples`
+    row: u32,
+    /// Last change of view or scene
+    changed: Instant,
+}
+
+impl Default for PathTracer {
+    fn default() -> Self {
+        Self {
+            camera: None,
+            width: 0,
+            height: 0,
+            accumulated: Vec::new(),
+            features: Vec::new(),
+            samples: 0,
+            row: 0,
+            changed: Instant::now(),
+        }
+    }
+}
+
+impl PathTracer {
+    /// Drop the samples traced so far, e.g. because the scene changed
+    pub fn cancel(&mut self, now: Instant) {
+        self.accumulated.iter_mut().for_each(|sum| *sum = Vec3::ZERO);
+        self.features.iter_mut().for_each(|sum| *sum = PixelFeatures::default());
+        self.samples = 0;
+        self.row = 0;
+        self.changed = now;
+    }
+    
+    /// Follow the view, cancelling the frame if the camera or frame size changed; returns whether it did
+    pub fn set_view(&mut self, camera: TraceCamera, width: u32, height: u32, now: Instant) -> bool {
+        if self.camera == Some(camera) && (self.width, self.height) == (width, height) {
+            return false;
+        }
+        self.camera = Some(camera);
+        (self.width, self.height) = (width, height);
+        self.accumulated = vec![Vec3::ZERO; width as usize * height as usize];
+        self.features = vec![PixelFeatures::default(); width as usize * height as usize];
+        self.cancel(now);
+        true
+    }
+    
+    /// Whether the view has been still long enough to trace
+    pub fn is_idle(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.changed) >= IDLE_DELAY
+    }
+    
+    /// Trace rows until `budget` runs out or the frame is final; returns whether any were traced
+    pub fn refine(&mut self, scene: &TraceScene, settings: &PathTraceSettings, now: Instant, budget: Duration) -> bool {
+        let Some(camera) = self.camera else {
+            return false;
+        };
+        if !settings.enabled || !self.is_idle(now) || self.samples >= settings.max_samples || self.accumulated.is_empty() {
+            return false;
+        }
+        
+        let (width, height) = (self.width as usize, self.height as usize);
+        let rays = camera.rays(width as f32 / height as f32);
+        let threads = std::thread::available_parallelism().map_or(1, |count| count.get());
+        let start = Instant::now();
+        while start.elapsed() < budget && self.samples < settings.max_samples {
+            let rows = self.row as usize..(self.row as usize + threads).min(height);
+            let sample = self.samples;
+            let pixels = self.accumulated[rows.start * width..rows.end * width].chunks_mut(width);
+            let features = self.features[rows.start * width..rows.end * width].chunks_mut(width);
+            std::thread::scope(|scope| {
+                for (y, (pixels, features)) in rows.clone().zip(pixels.zip(features)) {
+                    scope.spawn(move || {
+                        for (x, (sum, feature_sum)) in pixels.iter_mut().zip(features).enumerate() {
+                            let mut random = Random::new((y * width + x) as u32, sample);
+                            let (u, v) = ((x as f32 + random.next()) / width as f32, (y as f32 + random.next()) / height as f32);
+                            let (radiance, features) = scene.radiance(camera.position, rays(u, v), settings.max_bounces, &mut random);
+                            *sum += radiance;
+                            *feature_sum += features;
+                        }
+                    });
+                }
+            });
+            self.row = rows.end as u32;
+            if rows.end == height {
+                self.row = 0;
+                self.samples += 1;
+            }
+        }
+        true
+    }
+    
+    /// The frame so far scaled by `exposure`, denoised or not and shown through `display`, None until every pixel has a sample
+    pub fn frame(&self, exposure: f32, denoised: bool, display: &ColorManagement) -> Option<CapturedFrame> {
+        if self.samples == 0 {
+            return None;
+        }
+        let width = self.width as usize;
+        let count = |index: usize| (self.samples + ((index / width) < self.row as usize) as u32) as f32;
+        let mut colors: Vec<Vec3> = self.accumulated.iter().enumerate().map(|(index, sum)| *sum * exposure / count(index)).collect();
+        if denoised {
+            let features: Vec<PixelFeatures> = self.features.iter().enumerate().map(|(index, sum)| *sum * count(index).recip()).collect();
+            colors = denoise(&colors, &features, width, self.samples);
+        }
+        let pixels = colors.iter()
+            .map(|color| display.display(*color))
+            .flat_map(|color| [linear_to_srgb(color.x), linear_to_srgb(color.y), linear_to_srgb(color.z), 255])
+            .collect();
+        CapturedFrame::new(self.width, self.height, pixels).ok()
+    }
+}
+
+/// PCG random numbers, seeded per pixel and sample so frames are repeatable
+struct Random(u32);
+
+impl Random {
+    fn new(pixel: u32, sample: u32) -> Self {
+        let mut random = Random(pixel ^ sample.wrapping_mul(0x9E37_79B9));
+        random.next();
+        random
+    }
+    
+    /// Uniform in [0, 1)
+    fn next(&mut self) -> f32 {
+        self.0 = self.0.wrapping_mul(747_796_405).wrapping_add(2_891_336_453);
+        let word = ((self.0 >> ((self.0 >> 28) + 4)) ^ self.0).wrapping_mul(277_803_737);
+        (((word >> 22) ^ word) >> 8) as f32 / 16_777_216.0
+    }
+    
+    /// Uniform in the unit ball
+    fn in_sphere(&mut self) -> Vec3 {
+        let z = 1.0 - 2.0 * self.next();
+        let angle = 2.0 * PI * self.next();
+        let ring = (1.0 - z * z).max(0.0).sqrt();
+        Vec3::new(ring * angle.cos(), ring * angle.sin(), z) * self.next().cbrt()
+    }
+    
+    /// Cosine weighted direction in the hemisphere around `normal`
+    fn cosine_hemisphere(&mut self, normal: Vec3) -> Vec3 {
+        let (tangent, bitangent) = normal.any_orthonormal_pair();
+        let (radius, angle) = (self.next().sqrt(), 2.0 * PI * self.next());
+        let height = (1.0 - radius * radius).max(0.0).sqrt();
+        (tangent * radius * angle.cos() + bitangent * radius * angle.sin() + normal * height).normalize_or_zero()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn traced_frames_follow_lights_shadows_and_the_view() {
+        // A floor quad at y = 0 under a light from straight above
+        let quad = |y: f32, size: f32| TraceMesh {
+            points: vec![
+                Vec3::new(-size, y, -size), Vec3::new(size, y, -size),
+                Vec3::new(size, y, size), Vec3::new(-size, y, size),
+            ],
+            indices: vec![0, 2, 1, 0, 3, 2],
+            material: TraceMaterial::default(),
+        };
+        let sun = TraceLight::Distant { direction: Vec3::NEG_Y, radiance: Vec3::ONE, angle: 0.53 };
+        let dark = Environment { sky: Vec3::ZERO, ground: Vec3::ZERO };
+        let lit = TraceScene::new(&[quad(0.0, 10.0)], vec![sun], dark);
+        let shaded = TraceScene::new(&[quad(0.0, 10.0), quad(5.0, 10.0)], vec![sun], dark);
+        
+        let mut random = Random::new(0, 0);
+        let down = |scene: &TraceScene, random: &mut Random| scene.radiance(Vec3::Y, Vec3::NEG_Y, 0, random);
+        let (sunlit, features) = down(&lit, &mut random);
+        assert!(sunlit.x > TraceMaterial::default().base_color.x / PI);
+        assert_eq!((features.normal, features.depth), (Vec3::Y, 1.0));
+        assert_eq!(down(&shaded, &mut random).0, Vec3::ZERO);
+        // Rays leaving the scene see the dome
+        let sky = TraceScene::new(&[], Vec::new(), Environment { sky: Vec3::ONE, ground: Vec3::ZERO });
+        assert_eq!(sky.radiance(Vec3::ZERO, Vec3::Y, 2, &mut random), (Vec3::ONE, PixelFeatures::default()));
+        
+        // Tracing waits for the view to settle, then refines until final
+        let settings = PathTraceSettings { enabled: true, max_samples: 2, max_bounces: 1, denoise: true };
+        let camera = TraceCamera { position: Vec3::new(0.0, 4.0, 4.0), target: Vec3::ZERO, up: Vec3::Y, fov_y: 1.0 };
+        let start = Instant::now();
+        let mut tracer = PathTracer::default();
+        assert!(tracer.set_view(camera, 4, 3, start));
+        assert!(!tracer.refine(&lit, &settings, start, Duration::from_secs(5)));
+        let idle = start + IDLE_DELAY;
+        assert!(tracer.refine(&lit, &settings, idle, Duration::from_secs(5)));
+        assert_eq!(tracer.samples, 2);
+        assert!(!tracer.refine(&lit, &settings, idle, Duration::from_secs(5)));
+        let frame = tracer.frame(1.0, false, &ColorManagement::default()).unwrap();
+        assert_eq!((frame.width, frame.height), (4, 3));
+        assert!(frame.pixels[(4 + 1) * 4] > 0);
+        assert!(tracer.frame(1.0, true, &ColorManagement::default()).unwrap().pixels[(4 + 1) * 4] > 0);
+        
+        // Moving the camera cancels the frame
+        assert!(!tracer.set_view(camera, 4, 3, idle));
+        assert!(tracer.set_view(TraceCamera { fov_y: 0.5, ..camera }, 4, 3, idle));
+        assert!(tracer.frame(1.0, true, &ColorManagement::default()).is_none());
+    }
+}
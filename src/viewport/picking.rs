@@ -0,0 +1,506 @@
+//! GPU face picking
+//!
+//! A pick pass redraws the scene into an Rg32Uint target holding, per pixel,
+//! the index of the nearest geometry and of its triangle, each plus one so
+//! zero means nothing was hit. face_pick.wgsl pulls vertices from the mesh's
+//! vertex and index buffers bound as storage and derives the triangle from
+//! the vertex index of a non-indexed draw, so no primitive index feature is
+//! needed. Triangles map back to authored faces through
+//! `USDGeometry::face_ids`, and geometries back to the prims clicked in the
+//! viewport.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::Mutex;
+use bytemuck::{Pod, Zeroable};
+use glam::{Mat4, Vec4};
+use once_cell::sync::Lazy;
+use super::renderer_3d::Vertex3D;
+
+/// Faces by mesh prim path
+pub type FaceSelection = BTreeMap<String, BTreeSet<u32>>;
+
+/// How a pick combines with the current face selection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PickMode {
+    Replace,
+    Add,
+    Remove,
+}
+
+impl PickMode {
+    pub const ALL: [PickMode; 3] = [PickMode::Replace, PickMode::Add, PickMode::Remove];
+    
+    pub fn label(&self) -> &'static str {
+        match self {
+            PickMode::Replace => "Replace",
+            PickMode::Add => "Add",
+            PickMode::Remove => "Remove",
+        }
+    }
+    
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.label() == label)
+    }
+    
+    /// Combine picked faces into a selection, dropping meshes left without faces
+    pub fn apply(&self, selection: &mut FaceSelection, picked: FaceSelection) {
+        if *self == PickMode::Replace {
+            selection.clear();
+        }
+        for (prim_path, faces) in picked {
+            let selected = selection.entry(prim_path).or_default();
+            match self {
+                PickMode::Replace | PickMode::Add => selected.extend(faces),
+                PickMode::Remove => selected.retain(|face| !faces.contains(face)),
+            }
+        }
+        selection.retain(|_, faces| !faces.is_empty());
+    }
+}
+
+/// Pixel rectangle of a pick, from the top left of the viewport
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PickRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl PickRect {
+    /// Rectangle spanning two corners, e.g. the ends of a drag; a click picks one pixel
+    pub fn from_corners(a: (f32, f32), b: (f32, f32)) -> Self {
+        let (min_x, min_y) = (a.0.min(b.0).max(0.0), a.1.min(b.1).max(0.0));
+        let (max_x, max_y) = (a.0.max(b.0).max(0.0), a.1.max(b.1).max(0.0));
+        Self {
+            x: min_x as u32,
+            y: min_y as u32,
+            width: (max_x as u32 - min_x as u32).max(1),
+            height: (max_y as u32 - min_y as u32).max(1),
+        }
+    }
+    
+    /// The part of the rectangle inside a `width` x `height` view, if any
+    pub fn clamped(&self, width: u32, height: u32) -> Option<PickRect> {
+        let right = self.x.saturating_add(self.width).min(width);
+        let bottom = self.y.saturating_add(self.height).min(height);
+        (self.x < right && self.y < bottom).then(|| PickRect {
+            x: self.x,
+            y: self.y,
+            width: right - self.x,
+            height: bottom - self.y,
+        })
+    }
+}
+
+/// A face pick rectangle being dragged out in a `width` x `height` view
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FaceDrag {
+    pub start: (f32, f32),
+    pub end: (f32, f32),
+    pub width: f32,
+    pub height: f32,
+}
+
+impl FaceDrag {
+    pub fn rect(&self) -> PickRect {
+        PickRect::from_corners(self.start, self.end)
+    }
+}
+
+/// Resolve picked (geometry + 1, triangle + 1) pixels into faces
+///
+/// `face_of` maps a geometry and triangle index to the mesh path and
+/// authored face, or None for geometry without pickable faces.
+pub fn resolve_faces(pixels: &[[u32; 2]], mut face_of: impl FnMut(u32, u32) -> Option<(String, u32)>) -> FaceSelection {
+    let hits: BTreeSet<[u32; 2]> = pixels.iter().copied().filter(|[geometry, triangle]| *geometry > 0 && *triangle > 0).collect();
+    let mut selection = FaceSelection::new();
+    for [geometry, triangle] in hits {
+        if let Some((prim_path, face)) = face_of(geometry - 1, triangle - 1) {
+            selection.entry(prim_path).or_default().insert(face);
+        }
+    }
+    selection
+}
+
+/// Clip-space transform zooming a view onto one pixel of a `width` x `height` viewport
+///
+/// Drawing through it into a 1x1 target draws only what covers the pixel at
+/// `(x, y)` from the top left, so a click is picked without drawing the
+/// whole view.
+pub fn pick_matrix(x: f32, y: f32, width: f32, height: f32) -> Mat4 {
+    rect_matrix(PickRect { x: x.max(0.0) as u32, y: y.max(0.0) as u32, width: 1, height: 1 }, width, height)
+}
+
+/// Clip-space transform zooming a view onto a pixel rectangle of a `width` x `height` viewport
+///
+/// Drawing through it into a target of the rectangle's size draws only what
+/// falls inside the rectangle, as `pick_matrix` does for one pixel.
+pub fn rect_matrix(rect: PickRect, width: f32, height: f32) -> Mat4 {
+    let (scale_x, scale_y) = (width / rect.width as f32, height / rect.height as f32);
+    let center_x = 2.0 * (rect.x as f32 + rect.width as f32 / 2.0) / width - 1.0;
+    let center_y = 1.0 - 2.0 * (rect.y as f32 + rect.height as f32 / 2.0) / height;
+    Mat4::from_cols(
+        Vec4::new(scale_x, 0.0, 0.0, 0.0),
+        Vec4::new(0.0, scale_y, 0.0, 0.0),
+        Vec4::Z,
+        Vec4::new(-center_x * scale_x, -center_y * scale_y, 0.0, 1.0),
+    )
+}
+
+/// Combine the prim under a click into a prim selection, returning whether it changed
+///
+/// A click selects the picked prim alone, or clears the selection over empty
+/// space; with `extend`, as on shift-click, it toggles the prim in or out of
+/// the selection and empty space leaves it alone.
+pub fn click_select(selection: &mut Vec<String>, picked: Option<String>, extend: bool) -> bool {
+    match (picked, extend) {
+        (Some(prim_path), true) => {
+            match selection.iter().position(|selected| *selected == prim_path) {
+                Some(index) => {
+                    selection.remove(index);
+                }
+                None => selection.push(prim_path),
+            }
+            true
+        }
+        (None, true) => false,
+        (picked, false) => {
+            let picked: Vec<String> = picked.into_iter().collect();
+            let changed = *selection != picked;
+            *selection = picked;
+            changed
+        }
+    }
+}
+
+/// Faces last picked in a viewport, keyed by stage identifier
+static PICKED_FACES: Lazy<Mutex<HashMap<String, FaceSelection>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Faces picked in the viewport showing a stage, for Face Set nodes to pick up
+pub fn picked_faces(stage_id: &str) -> FaceSelection {
+    PICKED_FACES.lock().unwrap().get(stage_id).cloned().unwrap_or_default()
+}
+
+/// Publish the face selection of the viewport showing a stage
+pub fn set_picked_faces(stage_id: &str, selection: FaceSelection) {
+    PICKED_FACES.lock().unwrap().insert(stage_id.to_string(), selection);
+}
+
+/// A stage's selected prim paths with a revision bumped on each change
+type Selection = (u64, Vec<String>);
+
+/// Prims selected in a stage's viewports and Stage Inspectors, keyed by stage identifier
+static SELECTED_PRIMS: Lazy<Mutex<HashMap<String, Selection>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Prims selected in a stage, most recent last, with the selection's revision
+pub fn selected_prims(stage_id: &str) -> (u64, Vec<String>) {
+    SELECTED_PRIMS.lock().unwrap().get(stage_id).cloned().unwrap_or_default()
+}
+
+/// Publish the prims selected in a stage, returning the selection's revision
+///
+/// The revision only moves when the selection does, so surfaces can tell
+/// their own selection apart from another's.
+pub fn set_selected_prims(stage_id: &str, prims: &[String]) -> u64 {
+    let mut selections = SELECTED_PRIMS.lock().unwrap();
+    let (revision, selected) = selections.entry(stage_id.to_string()).or_default();
+    if selected != prims {
+        *revision += 1;
+        *selected = prims.to_vec();
+    }
+    *revision
+}
+
+/// Per-draw constants (face_pick.wgsl `PickConstants`)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+struct PickConstants {
+    object_to_clip: [[f32; 4]; 4],
+    geometry: u32,
+    /// Vertex3D size in floats; positions are its first three
+    vertex_stride: u32,
+    _padding: [u32; 2],
+}
+
+/// One mesh draw of a pick pass
+pub struct PickDraw<'a> {
+    /// Index of the geometry, reported back by `resolve_faces`
+    pub geometry: u32,
+    /// Vertex3D buffer with STORAGE usage
+    pub vertices: &'a wgpu::Buffer,
+    /// u32 index buffer with STORAGE usage
+    pub indices: &'a wgpu::Buffer,
+    pub index_count: u32,
+    pub object_to_clip: Mat4,
+}
+
+/// Pick pass pipeline, created once the renderer has a device
+pub struct FacePicker {
+    layout: wgpu::BindGroupLayout,
+    pipeline: wgpu::RenderPipeline,
+}
+
+impl FacePicker {
+    const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rg32Uint;
+    
+    pub fn new(device: &wgpu::Device) -> Self {
+        let storage = |binding| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::VERTEX,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only: true },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("usd_face_pick_layout"),
+            entries: &[storage(0), storage(1)],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("usd_face_pick_pipeline_layout"),
+            bind_group_layouts: &[&layout],
+            push_constant_ranges: &[wgpu::PushConstantRange {
+                stages: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                range: 0..std::mem::size_of::<PickConstants>() as u32,
+            }],
+        });
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("usd_face_pick"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/face_pick.wgsl").into()),
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("usd_face_pick"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                compilation_options: Default::default(),
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                compilation_options: Default::default(),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: Self::FORMAT,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            // No culling, so open meshes can be picked from either side
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: wgpu::TextureFormat::Depth32Float,
+                depth_write_enabled: true,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+        Self { layout, pipeline }
+    }
+    
+    /// Draw a `width` x `height` pick pass and read back the pixels inside `rect`
+    ///
+    /// `rect` must lie inside the view, see `PickRect::clamped`.
+    pub fn pick(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        draws: &[PickDraw],
+        width: u32,
+        height: u32,
+        rect: PickRect,
+    ) -> Result<Vec<[u32; 2]>, String> {
+        let target = |label, format, usage| device.create_texture(&wgpu::TextureDescriptor {
+            label: Some(label),
+            size: wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage,
+            view_formats: &[],
+        });
+        let ids = target("usd_face_pick_ids", Self::FORMAT, wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC);
+        let depth = target("usd_face_pick_depth", wgpu::TextureFormat::Depth32Float, wgpu::TextureUsages::RENDER_ATTACHMENT);
+        let ids_view = ids.create_view(&wgpu::TextureViewDescriptor::default());
+        let depth_view = depth.create_view(&wgpu::TextureViewDescriptor::default());
+        
+        let bind_groups: Vec<wgpu::BindGroup> = draws.iter()
+            .map(|draw| device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("usd_face_pick_geometry"),
+                layout: &self.layout,
+                entries: &[
+                    wgpu::BindGroupEntry { binding: 0, resource: draw.vertices.as_entire_binding() },
+                    wgpu::BindGroupEntry { binding: 1, resource: draw.indices.as_entire_binding() },
+                ],
+            }))
+            .collect();
+        
+        // Rows must be padded to the copy alignment
+        let unpadded_row = rect.width * 8;
+        let padded_row = unpadded_row.div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT) * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let readback = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("usd_face_pick_readback"),
+            size: (padded_row * rect.height) as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("usd_face_pick") });
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("usd_face_pick_pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &ids_view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: &depth_view,
+                    depth_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(1.0),
+                        store: wgpu::StoreOp::Discard,
+                    }),
+                    stencil_ops: None,
+                }),
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            render_pass.set_pipeline(&self.pipeline);
+            let vertex_stride = (std::mem::size_of::<Vertex3D>() / 4) as u32;
+            for (draw, bind_group) in draws.iter().zip(&bind_groups) {
+                let constants = PickConstants {
+                    object_to_clip: draw.object_to_clip.to_cols_array_2d(),
+                    geometry: draw.geometry,
+                    vertex_stride,
+                    _padding: [0; 2],
+                };
+                render_pass.set_bind_group(0, bind_group, &[]);
+                render_pass.set_push_constants(wgpu::ShaderStages::VERTEX_FRAGMENT, 0, bytemuck::bytes_of(&constants));
+                render_pass.draw(0..draw.index_count, 0..1);
+            }
+        }
+        encoder.copy_texture_to_buffer(
+            wgpu::TexelCopyTextureInfo {
+                texture: &ids,
+                mip_level: 0,
+                origin: wgpu::Origin3d { x: rect.x, y: rect.y, z: 0 },
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::TexelCopyBufferInfo {
+                buffer: &readback,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_row),
+                    rows_per_image: Some(rect.height),
+                },
+            },
+            wgpu::Extent3d { width: rect.width, height: rect.height, depth_or_array_layers: 1 },
+        );
+        queue.submit(Some(encoder.finish()));
+        
+        let slice = readback.slice(..);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        let _ = device.poll(wgpu::PollType::Wait);
+        receiver.recv()
+            .map_err(|e| format!("Failed to read back pick: {}", e))?
+            .map_err(|e| format!("Failed to map pick buffer: {}", e))?;
+        
+        let mut pixels = Vec::with_capacity((rect.width * rect.height) as usize);
+        {
+            let data = slice.get_mapped_range();
+            for row in data.chunks(padded_row as usize) {
+                pixels.extend(bytemuck::cast_slice::<u8, [u32; 2]>(&row[..unpadded_row as usize]));
+            }
+        }
+        readback.unmap();
+        Ok(pixels)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn picks_resolve_to_authored_faces() {
+        // Geometry 0 has two triangles per face, geometry 1 has no faces
+        let face_ids = [vec![0, 0, 1, 1], vec![]];
+        let pixels = [[0, 0], [1, 1], [1, 2], [1, 4], [2, 1], [1, 4]];
+        let selection = resolve_faces(&pixels, |geometry, triangle| {
+            let face = *face_ids[geometry as usize].get(triangle as usize)?;
+            Some((format!("/Mesh{}", geometry), face))
+        });
+        assert_eq!(selection.len(), 1);
+        assert_eq!(selection["/Mesh0"], BTreeSet::from([0, 1]));
+        
+        let mut current = selection.clone();
+        PickMode::Add.apply(&mut current, FaceSelection::from([("/Other".to_string(), BTreeSet::from([3]))]));
+        assert_eq!(current.len(), 2);
+        PickMode::Remove.apply(&mut current, selection.clone());
+        assert_eq!(current.keys().collect::<Vec<_>>(), ["/Other"]);
+        PickMode::Replace.apply(&mut current, selection.clone());
+        assert_eq!(current, selection);
+    }
+    
+    #[test]
+    fn pick_rects_span_drags_and_clamp_to_the_view() {
+        let rect = PickRect::from_corners((30.5, 40.0), (10.0, 20.2));
+        assert_eq!(rect, PickRect { x: 10, y: 20, width: 20, height: 20 });
+        assert_eq!(PickRect::from_corners((5.0, 5.0), (5.0, 5.0)).width, 1);
+        assert_eq!(rect.clamped(25, 100), Some(PickRect { x: 10, y: 20, width: 15, height: 20 }));
+        assert_eq!(rect.clamped(10, 100), None);
+    }
+    
+    #[test]
+    fn pick_matrix_centers_the_clicked_pixel() {
+        let pick = pick_matrix(30.7, 10.2, 100.0, 50.0);
+        // Center of pixel (30, 10) in clip space lands on the 1x1 target's center
+        let clip = Vec4::new(2.0 * 30.5 / 100.0 - 1.0, 1.0 - 2.0 * 10.5 / 50.0, 0.5, 1.0) * 2.0;
+        let picked = pick * clip;
+        assert!((picked.x / picked.w).abs() < 1e-5 && (picked.y / picked.w).abs() < 1e-5);
+        // The neighbouring pixel falls outside it
+        let next = pick * Vec4::new(2.0 * 31.5 / 100.0 - 1.0, 1.0 - 2.0 * 10.5 / 50.0, 0.5, 1.0);
+        assert!(next.x / next.w > 1.0);
+    }
+    
+    #[test]
+    fn rect_matrix_fills_the_target_with_the_rectangle() {
+        let rect = PickRect { x: 20, y: 10, width: 30, height: 20 };
+        let zoom = rect_matrix(rect, 100.0, 50.0);
+        // The rectangle's top left and bottom right corners land on the target's
+        let clip = |x: f32, y: f32| zoom * Vec4::new(2.0 * x / 100.0 - 1.0, 1.0 - 2.0 * y / 50.0, 0.5, 1.0);
+        let top_left = clip(20.0, 10.0);
+        let bottom_right = clip(50.0, 30.0);
+        assert!((top_left.x + 1.0).abs() < 1e-5 && (top_left.y - 1.0).abs() < 1e-5);
+        assert!((bottom_right.x - 1.0).abs() < 1e-5 && (bottom_right.y + 1.0).abs() < 1e-5);
+    }
+    
+    #[test]
+    fn clicks_replace_and_shift_clicks_toggle() {
+        let mut selection = Vec::new();
+        assert!(click_select(&mut selection, Some("/World/A".to_string()), false));
+        assert!(!click_select(&mut selection, Some("/World/A".to_string()), false));
+        assert!(click_select(&mut selection, Some("/World/B".to_string()), true));
+        assert_eq!(selection, ["/World/A", "/World/B"]);
+        assert!(!click_select(&mut selection, None, true));
+        assert!(click_select(&mut selection, Some("/World/A".to_string()), true));
+        assert_eq!(selection, ["/World/B"]);
+        assert!(click_select(&mut selection, None, false));
+        assert!(selection.is_empty());
+    }
+}
@@ -0,0 +1,204 @@
+//! Animation playback clock for the USD viewport
+//!
+//! Tracks play/pause state, frame rate and loop behaviour, and advances the
+//! current time code from wall-clock time whenever the viewport cooks.
+
+use std::time::{Duration, Instant};
+
+/// What happens when playback reaches the end of the range
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoopMode {
+    /// Stop on the last frame
+    Once,
+    /// Jump back to the first frame
+    Loop,
+    /// Reverse direction at either end
+    PingPong,
+}
+
+impl LoopMode {
+    pub const ALL: [LoopMode; 3] = [LoopMode::Once, LoopMode::Loop, LoopMode::PingPong];
+    
+    pub fn name(&self) -> &'static str {
+        match self {
+            LoopMode::Once => "Once",
+            LoopMode::Loop => "Loop",
+            LoopMode::PingPong => "Ping-Pong",
+        }
+    }
+    
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.name() == name)
+    }
+}
+
+/// Frame rates offered by the viewport FPS selector
+pub const FPS_PRESETS: [&str; 6] = ["12", "24", "25", "30", "48", "60"];
+
+/// Playback state for one viewport
+#[derive(Debug, Clone)]
+pub struct PlaybackState {
+    pub playing: bool,
+    pub fps: f64,
+    pub loop_mode: LoopMode,
+    pub start_time_code: f64,
+    pub end_time_code: f64,
+    pub current_time_code: f64,
+    /// +1.0 forward, -1.0 backward (ping-pong)
+    direction: f64,
+    last_tick: Option<Instant>,
+}
+
+impl Default for PlaybackState {
+    fn default() -> Self {
+        Self {
+            playing: false,
+            fps: 24.0,
+            loop_mode: LoopMode::Loop,
+            start_time_code: 1.0,
+            end_time_code: 24.0,
+            current_time_code: 1.0,
+            direction: 1.0,
+            last_tick: None,
+        }
+    }
+}
+
+impl PlaybackState {
+    pub fn play(&mut self) {
+        if !self.playing {
+            self.playing = true;
+            self.last_tick = None;
+        }
+    }
+    
+    pub fn pause(&mut self) {
+        self.playing = false;
+        self.last_tick = None;
+    }
+    
+    /// Stop and rewind to the first frame
+    pub fn stop(&mut self) {
+        self.pause();
+        self.direction = 1.0;
+        self.current_time_code = self.start_time_code;
+    }
+    
+    /// Set the playable range, clamping the current frame into it
+    pub fn set_range(&mut self, start: f64, end: f64) {
+        self.start_time_code = start;
+        self.end_time_code = end.max(start);
+        self.current_time_code = self.current_time_code.clamp(self.start_time_code, self.end_time_code);
+    }
+    
+    /// Jump to a frame (scrubbing), clamped to the range
+    pub fn seek(&mut self, time_code: f64) {
+        self.current_time_code = time_code.clamp(self.start_time_code, self.end_time_code);
+    }
+    
+    /// Advance by wall-clock time; returns true when the current frame changed
+    pub fn tick(&mut self, now: Instant) -> bool {
+        if !self.playing {
+            return false;
+        }
+        let elapsed = match self.last_tick.replace(now) {
+            Some(last) => now.saturating_duration_since(last),
+            None => return false,
+        };
+        self.advance(elapsed)
+    }
+    
+    /// Advance by a duration at the current frame rate; returns true when the frame changed
+    pub fn advance(&mut self, elapsed: Duration) -> bool {
+        let previous = self.current_time_code;
+        let length = self.end_time_code - self.start_time_code;
+        if length <= 0.0 {
+            self.current_time_code = self.start_time_code;
+            return false;
+        }
+        
+        let mut time = self.current_time_code + elapsed.as_secs_f64() * self.fps * self.direction;
+        match self.loop_mode {
+            LoopMode::Once => {
+                if time >= self.end_time_code {
+                    time = self.end_time_code;
+                    self.pause();
+                }
+            }
+            LoopMode::Loop => {
+                // The last frame is held for a full frame before wrapping
+                if time >= self.end_time_code + 1.0 {
+                    time = self.start_time_code + (time - self.start_time_code) % (length + 1.0);
+                }
+            }
+            LoopMode::PingPong => {
+                // Reflect off the ends until the time lies inside the range
+                while time > self.end_time_code || time < self.start_time_code {
+                    if time > self.end_time_code {
+                        time = 2.0 * self.end_time_code - time;
+                    } else {
+                        time = 2.0 * self.start_time_code - time;
+                    }
+                    self.direction = -self.direction;
+                }
+            }
+        }
+        
+        self.current_time_code = time;
+        time != previous
+    }
+    
+    /// Current frame as shown in the UI (whole frames)
+    pub fn current_frame(&self) -> f64 {
+        self.current_time_code.floor()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn playing(loop_mode: LoopMode) -> PlaybackState {
+        let mut state = PlaybackState { loop_mode, ..Default::default() };
+        state.set_range(1.0, 10.0);
+        state.play();
+        state
+    }
+    
+    #[test]
+    fn once_stops_at_end() {
+        let mut state = playing(LoopMode::Once);
+        state.advance(Duration::from_secs(2));
+        assert_eq!(state.current_time_code, 10.0);
+        assert!(!state.playing);
+    }
+    
+    #[test]
+    fn loop_wraps_to_start() {
+        let mut state = playing(LoopMode::Loop);
+        state.seek(9.0);
+        // 24 fps for 1/8s = 3 frames -> 12 wraps to 2
+        state.advance(Duration::from_millis(125));
+        assert!((state.current_time_code - 2.0).abs() < 1e-6);
+        assert!(state.playing);
+    }
+    
+    #[test]
+    fn ping_pong_reverses() {
+        let mut state = playing(LoopMode::PingPong);
+        state.seek(9.0);
+        state.advance(Duration::from_millis(125));
+        assert!((state.current_time_code - 8.0).abs() < 1e-6);
+        state.advance(Duration::from_secs_f64(1.0 / 24.0));
+        assert!((state.current_time_code - 7.0).abs() < 1e-6);
+    }
+    
+    #[test]
+    fn stop_rewinds() {
+        let mut state = playing(LoopMode::Loop);
+        state.seek(5.0);
+        state.stop();
+        assert_eq!(state.current_time_code, 1.0);
+        assert!(!state.tick(Instant::now()));
+    }
+}
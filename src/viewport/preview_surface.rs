@@ -0,0 +1,117 @@
+//! UsdPreviewSurface network evaluation
+//!
+//! Each UsdPreviewSurface input is either a constant or connected to an
+//! upstream UsdUVTexture or UsdPrimvarReader. The viewport shades connected
+//! inputs with the upstream node's fallback; for a texture that is
+//! `fallback * scale + bias`, read through the connected output channels.
+
+use glam::Vec4;
+
+/// Upstream node driving a UsdPreviewSurface input
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputSource {
+    Texture(TextureInput),
+    Primvar(PrimvarInput),
+}
+
+impl InputSource {
+    /// Value the connected input evaluates to in the viewport
+    pub fn fallback_value(&self) -> Vec4 {
+        match self {
+            InputSource::Texture(texture) => texture.fallback_value(),
+            InputSource::Primvar(primvar) => output_channels(primvar.fallback, &primvar.output),
+        }
+    }
+}
+
+/// A UsdUVTexture connected to a surface input
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextureInput {
+    pub shader_path: String,
+    /// Resolved asset path of `inputs:file`, or the authored path when unresolved
+    pub file: String,
+    /// Output the surface input is connected to: "rgb", "rgba", "r", "g", "b" or "a"
+    pub output: String,
+    /// Primvar read by the UsdPrimvarReader_float2 connected to `inputs:st`
+    pub st_primvar: Option<String>,
+    pub wrap_s: String,
+    pub wrap_t: String,
+    pub scale: Vec4,
+    pub bias: Vec4,
+    pub fallback: Vec4,
+    /// "raw", "sRGB" or "auto"
+    pub source_color_space: String,
+}
+
+impl TextureInput {
+    /// Value of the connected output when the texture isn't sampled
+    pub fn fallback_value(&self) -> Vec4 {
+        output_channels(self.fallback * self.scale + self.bias, &self.output)
+    }
+}
+
+/// A UsdPrimvarReader connected to a surface input
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrimvarInput {
+    pub shader_path: String,
+    pub varname: String,
+    /// Output the surface input is connected to, "result" for every reader type
+    pub output: String,
+    pub fallback: Vec4,
+}
+
+/// Read a shader output's channels, splatting single-channel outputs
+fn output_channels(value: Vec4, output: &str) -> Vec4 {
+    match output {
+        "r" => Vec4::splat(value.x),
+        "g" => Vec4::splat(value.y),
+        "b" => Vec4::splat(value.z),
+        "a" => Vec4::splat(value.w),
+        _ => value,
+    }
+}
+
+/// Fresnel reflectance at normal incidence for a UsdPreviewSurface ior
+pub fn ior_reflectance(ior: f32) -> f32 {
+    let r = (ior - 1.0) / (ior + 1.0);
+    r * r
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn connected_inputs_evaluate_to_upstream_fallbacks() {
+        let texture = TextureInput {
+            shader_path: "/Looks/Wood/Roughness".to_string(),
+            file: "wood_rough.png".to_string(),
+            output: "g".to_string(),
+            st_primvar: Some("st".to_string()),
+            wrap_s: "repeat".to_string(),
+            wrap_t: "repeat".to_string(),
+            scale: Vec4::new(1.0, 0.5, 1.0, 1.0),
+            bias: Vec4::new(0.0, 0.1, 0.0, 0.0),
+            fallback: Vec4::new(0.0, 0.8, 0.0, 1.0),
+            source_color_space: "raw".to_string(),
+        };
+        assert_eq!(InputSource::Texture(texture.clone()).fallback_value(), Vec4::splat(0.5));
+
+        let rgb = TextureInput { output: "rgb".to_string(), ..texture };
+        assert_eq!(rgb.fallback_value(), Vec4::new(0.0, 0.5, 0.0, 1.0));
+
+        let primvar = InputSource::Primvar(PrimvarInput {
+            shader_path: "/Looks/Plastic/DisplayColor".to_string(),
+            varname: "displayColor".to_string(),
+            output: "result".to_string(),
+            fallback: Vec4::new(0.2, 0.4, 0.6, 1.0),
+        });
+        assert_eq!(primvar.fallback_value(), Vec4::new(0.2, 0.4, 0.6, 1.0));
+    }
+
+    #[test]
+    fn glass_ior_reflects_four_percent() {
+        assert!((ior_reflectance(1.5) - 0.04).abs() < 1e-6);
+        assert_eq!(ior_reflectance(1.0), 0.0);
+    }
+}
@@ -0,0 +1,565 @@
+//! Mesh primvars for viewport display
+//!
+//! displayColor and displayOpacity, or any color primvar chosen for display,
+//! the st texture coordinates and authored normals are expanded to one value
+//! per drawn vertex. Constant, vertex and varying primvars keep the mesh's
+//! shared points; uniform and faceVarying primvars split the mesh per
+//! triangle corner, and corners that agree on every value are welded back
+//! together so only UV seams and hard edges duplicate vertices. Colors are
+//! linear, like every other `color3f` in USD, and
+//! reach usd_mesh.wgsl with the normal-mapping tangents through storage
+//! buffers at group 2 indexed by vertex.
+
+use std::collections::HashMap;
+use glam::{Vec3, Vec4};
+use super::subdivision::{subdivide, subdivide_face_varying};
+use super::triangulation::TriangulatedMesh;
+
+/// How a primvar's values map onto a mesh
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interpolation {
+    Constant,
+    Uniform,
+    Varying,
+    Vertex,
+    FaceVarying,
+}
+
+impl Interpolation {
+    pub fn from_token(token: &str) -> Option<Self> {
+        match token {
+            "constant" => Some(Interpolation::Constant),
+            "uniform" => Some(Interpolation::Uniform),
+            "varying" => Some(Interpolation::Varying),
+            "vertex" => Some(Interpolation::Vertex),
+            "faceVarying" => Some(Interpolation::FaceVarying),
+            _ => None,
+        }
+    }
+    
+    pub fn token(&self) -> &'static str {
+        match self {
+            Interpolation::Constant => "constant",
+            Interpolation::Uniform => "uniform",
+            Interpolation::Varying => "varying",
+            Interpolation::Vertex => "vertex",
+            Interpolation::FaceVarying => "faceVarying",
+        }
+    }
+    
+    /// Whether values can be stored per point, without splitting the mesh
+    pub fn per_point(&self) -> bool {
+        matches!(self, Interpolation::Constant | Interpolation::Varying | Interpolation::Vertex)
+    }
+}
+
+/// A primvar authored on a mesh that the viewport can display as color
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrimvarInfo {
+    pub name: String,
+    /// Sdf value type name, e.g. "color3f[]"
+    pub type_name: String,
+    pub interpolation: Interpolation,
+}
+
+impl PrimvarInfo {
+    /// Label for the primvar dropdown, e.g. "Cd (color3f[], vertex)"
+    pub fn label(&self) -> String {
+        format!("{} ({}, {})", self.name, self.type_name, self.interpolation.token())
+    }
+}
+
+/// Channels of a numeric value type like "float", "color3f[]" or "int2", None for others
+///
+/// Only types that widen to RGBA can be displayed; strings, tokens, bools,
+/// matrices and quaternions cannot.
+pub fn type_components(type_name: &str) -> Option<usize> {
+    let scalar = type_name.trim_end_matches("[]");
+    let role_end = scalar.find(|c: char| c.is_ascii_digit()).unwrap_or(scalar.len());
+    let (role, rest) = scalar.split_at(role_end);
+    let digits_end = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+    let (digits, suffix) = rest.split_at(digits_end);
+    let components = match digits {
+        "" => 1,
+        "2" | "3" | "4" => digits.parse().ok()?,
+        _ => return None,
+    };
+    match (role, suffix) {
+        ("float" | "double" | "half" | "int" | "uint", "") => Some(components),
+        ("color" | "vector" | "point" | "normal" | "texCoord", "f" | "d" | "h") if components > 1 => Some(components),
+        _ => None,
+    }
+}
+
+/// A primvar's values widened to RGBA
+///
+/// Single-channel values are splatted to gray and missing alpha is 1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Primvar {
+    pub name: String,
+    pub interpolation: Interpolation,
+    pub values: Vec<Vec4>,
+    /// Value index per element for indexed primvars, empty otherwise
+    pub indices: Vec<i32>,
+}
+
+impl Primvar {
+    /// Widen flat float data with `components` values per element
+    pub fn from_components(name: &str, interpolation: Interpolation, flat: &[f32], components: usize) -> Self {
+        let values = match components {
+            1 => flat.iter().map(|&value| Vec3::splat(value).extend(1.0)).collect(),
+            2 => flat.chunks_exact(2).map(|value| Vec4::new(value[0], value[1], 0.0, 1.0)).collect(),
+            3 => flat.chunks_exact(3).map(|value| Vec3::from_slice(value).extend(1.0)).collect(),
+            _ => flat.chunks_exact(components.max(1)).filter(|value| value.len() >= 4).map(Vec4::from_slice).collect(),
+        };
+        Self { name: name.to_string(), interpolation, values, indices: Vec::new() }
+    }
+    
+    /// Value of the element-th entry, through the indices when indexed
+    fn element(&self, element: usize) -> Result<Vec4, String> {
+        let index = match self.indices.is_empty() {
+            true => element,
+            false => {
+                let index = *self.indices.get(element)
+                    .ok_or_else(|| format!("Primvar '{}' has {} indices, needs entry {}", self.name, self.indices.len(), element))?;
+                usize::try_from(index).map_err(|_| format!("Primvar '{}' has negative index {}", self.name, index))?
+            }
+        };
+        self.values.get(index).copied()
+            .ok_or_else(|| format!("Primvar '{}' has {} values, needs entry {}", self.name, self.values.len(), index))
+    }
+    
+    /// One value per point; only for per-point interpolations
+    pub fn point_values(&self, point_count: usize) -> Result<Vec<Vec4>, String> {
+        match self.interpolation {
+            Interpolation::Constant => Ok(vec![self.element(0)?; point_count]),
+            _ if self.interpolation.per_point() => (0..point_count).map(|point| self.element(point)).collect(),
+            interpolation => Err(format!("Primvar '{}' is {:?}, not per point", self.name, interpolation)),
+        }
+    }
+    
+    /// One value per triangle corner of a triangulated mesh
+    pub fn corner_values(&self, mesh: &TriangulatedMesh) -> Result<Vec<Vec4>, String> {
+        (0..mesh.indices.len())
+            .map(|corner| self.element(match self.interpolation {
+                Interpolation::Constant => 0,
+                Interpolation::Uniform => mesh.face_ids[corner / 3] as usize,
+                Interpolation::Varying | Interpolation::Vertex => mesh.indices[corner] as usize,
+                Interpolation::FaceVarying => mesh.face_vertex_ids[corner] as usize,
+            }))
+            .collect()
+    }
+    
+    /// One value per drawn vertex: per triangle corner when the mesh is split, else per point
+    pub fn vertex_values(&self, mesh: &TriangulatedMesh, point_count: usize, split: bool) -> Result<Vec<Vec4>, String> {
+        match split {
+            true => self.corner_values(mesh),
+            false => self.point_values(point_count),
+        }
+    }
+    
+    /// One value per entry of faceVertexIndices
+    pub fn face_vertex_values(&self, face_vertex_counts: &[i32], face_vertex_indices: &[i32]) -> Result<Vec<Vec4>, String> {
+        let faces = face_vertex_counts.iter().enumerate()
+            .flat_map(|(face, &count)| std::iter::repeat_n(face, count.max(0) as usize));
+        faces.zip(face_vertex_indices).enumerate()
+            .map(|(face_vertex, (face, &point))| self.element(match self.interpolation {
+                Interpolation::Constant => 0,
+                Interpolation::Uniform => face,
+                Interpolation::Varying | Interpolation::Vertex => point.max(0) as usize,
+                Interpolation::FaceVarying => face_vertex,
+            }))
+            .collect()
+    }
+    
+    /// Refine alongside the mesh's Catmull-Clark points
+    ///
+    /// Per-point primvars follow the point rules, since refinement is linear
+    /// in the values; the rest become faceVarying and interpolate linearly
+    /// within each face.
+    pub fn subdivide(
+        &self,
+        point_count: usize,
+        face_vertex_counts: &[i32],
+        face_vertex_indices: &[i32],
+        hole_indices: &[i32],
+        levels: u32,
+    ) -> Result<Primvar, String> {
+        if self.interpolation == Interpolation::Constant {
+            return Ok(self.clone());
+        }
+        let (interpolation, values) = match self.interpolation.per_point() {
+            true => {
+                // Three channels at a time, with alpha splatted into the second pass
+                let values = self.point_values(point_count)?;
+                let xyz: Vec<Vec3> = values.iter().map(|value| value.truncate()).collect();
+                let w: Vec<Vec3> = values.iter().map(|value| Vec3::splat(value.w)).collect();
+                let xyz = subdivide(&xyz, face_vertex_counts, face_vertex_indices, hole_indices, levels)?.points;
+                let w = subdivide(&w, face_vertex_counts, face_vertex_indices, hole_indices, levels)?.points;
+                (Interpolation::Vertex, xyz.into_iter().zip(w).map(|(xyz, w)| xyz.extend(w.x)).collect())
+            }
+            false => {
+                let values = self.face_vertex_values(face_vertex_counts, face_vertex_indices)?;
+                (Interpolation::FaceVarying, subdivide_face_varying(&values, face_vertex_counts, levels)?)
+            }
+        };
+        Ok(Primvar { name: self.name.clone(), interpolation, values, indices: Vec::new() })
+    }
+}
+
+/// A mesh's color primvar and the opacity primvar that goes with it
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorPrimvars {
+    pub color: Primvar,
+    /// displayOpacity alongside displayColor; its first channel becomes alpha
+    pub opacity: Option<Primvar>,
+}
+
+impl ColorPrimvars {
+    /// Whether the colors can be stored per point, without splitting the mesh
+    pub fn per_point(&self) -> bool {
+        self.color.interpolation.per_point()
+            && self.opacity.as_ref().is_none_or(|opacity| opacity.interpolation.per_point())
+    }
+    
+    /// One color per drawn vertex, see `Primvar::vertex_values`
+    pub fn vertex_colors(&self, mesh: &TriangulatedMesh, point_count: usize, split: bool) -> Result<Vec<Vec4>, String> {
+        let opacities = self.opacity.as_ref().map(|opacity| opacity.vertex_values(mesh, point_count, split)).transpose()?;
+        Ok(with_opacity(self.color.vertex_values(mesh, point_count, split)?, opacities))
+    }
+    
+    /// Refine both primvars alongside the mesh's points, see `Primvar::subdivide`
+    pub fn subdivide(
+        &self,
+        point_count: usize,
+        face_vertex_counts: &[i32],
+        face_vertex_indices: &[i32],
+        hole_indices: &[i32],
+        levels: u32,
+    ) -> Result<ColorPrimvars, String> {
+        let refine = |primvar: &Primvar| primvar.subdivide(point_count, face_vertex_counts, face_vertex_indices, hole_indices, levels);
+        Ok(ColorPrimvars {
+            color: refine(&self.color)?,
+            opacity: self.opacity.as_ref().map(refine).transpose()?,
+        })
+    }
+}
+
+/// The primvars a mesh is drawn with
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MeshPrimvars {
+    pub colors: Option<ColorPrimvars>,
+    /// Texture coordinates for UsdUVTexture lookups and normal-mapping tangents
+    pub st: Option<Primvar>,
+    /// Authored normals; None shades with normals computed from the points
+    pub normals: Option<Primvar>,
+}
+
+impl MeshPrimvars {
+    /// Whether every primvar can be stored per point, without splitting the mesh
+    pub fn per_point(&self) -> bool {
+        self.colors.as_ref().is_none_or(ColorPrimvars::per_point)
+            && self.st.as_ref().is_none_or(|st| st.interpolation.per_point())
+            && self.normals.as_ref().is_none_or(|normals| normals.interpolation.per_point())
+    }
+    
+    /// Refine every primvar alongside the mesh's points, see `Primvar::subdivide`
+    ///
+    /// Authored normals are dropped, since USD ignores them on subdivision
+    /// surfaces and the refined limit normals are computed instead.
+    pub fn subdivide(
+        &self,
+        point_count: usize,
+        face_vertex_counts: &[i32],
+        face_vertex_indices: &[i32],
+        hole_indices: &[i32],
+        levels: u32,
+    ) -> Result<MeshPrimvars, String> {
+        Ok(MeshPrimvars {
+            colors: self.colors.as_ref()
+                .map(|colors| colors.subdivide(point_count, face_vertex_counts, face_vertex_indices, hole_indices, levels))
+                .transpose()?,
+            st: self.st.as_ref()
+                .map(|st| st.subdivide(point_count, face_vertex_counts, face_vertex_indices, hole_indices, levels))
+                .transpose()?,
+            normals: None,
+        })
+    }
+}
+
+fn with_opacity(colors: Vec<Vec4>, opacities: Option<Vec<Vec4>>) -> Vec<Vec4> {
+    match opacities {
+        Some(opacities) => colors.into_iter().zip(opacities).map(|(color, opacity)| color.truncate().extend(opacity.x)).collect(),
+        None => colors,
+    }
+}
+
+/// Vertices of a mesh split per triangle corner, with matching corners welded
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeldedCorners {
+    /// Triangle corner each vertex takes its point and values from
+    pub vertex_corners: Vec<u32>,
+    /// Vertex of every triangle corner
+    pub indices: Vec<u32>,
+}
+
+impl WeldedCorners {
+    /// Per-vertex values from per-corner ones
+    pub fn gather<V: Copy>(&self, corner_values: &[V]) -> Vec<V> {
+        self.vertex_corners.iter().map(|&corner| corner_values[corner as usize]).collect()
+    }
+    
+    /// Polygon edges between points, routed through the first vertex of each point
+    pub fn edge_indices(&self, point_indices: &[u32], edge_indices: &[u32], point_count: usize) -> Vec<u32> {
+        let mut first_vertex = vec![0u32; point_count];
+        for (vertex, &corner) in self.vertex_corners.iter().enumerate().rev() {
+            first_vertex[point_indices[corner as usize] as usize] = vertex as u32;
+        }
+        edge_indices.iter().map(|&point| first_vertex[point as usize]).collect()
+    }
+}
+
+/// Split a mesh per triangle corner, welding corners that share a point and every value
+///
+/// `corner_values` holds one value per triangle corner for each primvar.
+/// Values are compared bit for bit, so corners across a UV seam or a hard
+/// edge keep their own vertices while the rest of the mesh stays shared.
+pub fn weld_corners(point_indices: &[u32], corner_values: &[&[Vec4]]) -> WeldedCorners {
+    let mut vertices: HashMap<(u32, Vec<[u32; 4]>), u32> = HashMap::new();
+    let mut vertex_corners = Vec::new();
+    let indices = point_indices.iter().enumerate()
+        .map(|(corner, &point)| {
+            let key = (point, corner_values.iter().map(|values| values[corner].to_array().map(f32::to_bits)).collect());
+            *vertices.entry(key).or_insert_with(|| {
+                vertex_corners.push(corner as u32);
+                vertex_corners.len() as u32 - 1
+            })
+        })
+        .collect();
+    WeldedCorners { vertex_corners, indices }
+}
+
+/// How a draw uses its vertex colors (usd_mesh.wgsl `DrawConstants.vertex_color`)
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VertexColorMode {
+    Off = 0,
+    /// displayColor and displayOpacity stand in for the material's base color and opacity
+    BaseColor = 1,
+    /// The chosen primvar is shown unlit
+    Display = 2,
+}
+
+/// Color bound for geometry without vertex colors
+const UNCOLORED: Vec4 = Vec4::ONE;
+
+/// Tangent bound for geometry without texture coordinates
+const NO_TANGENT: Vec4 = Vec4::new(1.0, 0.0, 0.0, 1.0);
+
+/// Per-geometry vertex colors and tangents for group 2 of usd_mesh.wgsl
+///
+/// Binding 0 holds the colors and binding 1 the tangents. Geometry without
+/// either binds a single default entry, which the shader clamps to.
+pub struct VertexAttributeBindings {
+    pub layout: wgpu::BindGroupLayout,
+    /// Bound for geometry with neither colors nor tangents
+    pub plain: wgpu::BindGroup,
+    /// Bind groups of colored or tangent-framed geometry, keyed by prim path
+    pub geometry: HashMap<String, wgpu::BindGroup>,
+}
+
+impl VertexAttributeBindings {
+    pub fn new(device: &wgpu::Device) -> Self {
+        let entry = |binding| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::VERTEX,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only: true },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("usd_vertex_attributes"),
+            entries: &[entry(0), entry(1)],
+        });
+        Self {
+            plain: create_bind_group(device, &layout, "usd_plain_vertices", &[], &[]),
+            layout,
+            geometry: HashMap::new(),
+        }
+    }
+    
+    /// Rebuild the bind groups of every geometry with vertex colors or tangents
+    pub fn rebuild<'a>(&mut self, device: &wgpu::Device, geometries: impl IntoIterator<Item = (&'a String, &'a [Vec4], &'a [Vec4])>) {
+        self.geometry.clear();
+        for (prim_path, colors, tangents) in geometries {
+            if !colors.is_empty() || !tangents.is_empty() {
+                self.geometry.insert(prim_path.clone(), create_bind_group(device, &self.layout, prim_path, colors, tangents));
+            }
+        }
+    }
+    
+    /// Bind group for a geometry prim, falling back to the plain one
+    pub fn bind_group(&self, prim_path: &str) -> &wgpu::BindGroup {
+        self.geometry.get(prim_path).unwrap_or(&self.plain)
+    }
+}
+
+fn create_bind_group(device: &wgpu::Device, layout: &wgpu::BindGroupLayout, label: &str, colors: &[Vec4], tangents: &[Vec4]) -> wgpu::BindGroup {
+    let storage = |values: &[Vec4], fallback: Vec4| {
+        let values = if values.is_empty() { &[fallback][..] } else { values };
+        wgpu::util::DeviceExt::create_buffer_init(device, &wgpu::util::BufferInitDescriptor {
+            label: Some(label),
+            contents: bytemuck::cast_slice(values),
+            usage: wgpu::BufferUsages::STORAGE,
+        })
+    };
+    let (colors, tangents) = (storage(colors, UNCOLORED), storage(tangents, NO_TANGENT));
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some(label),
+        layout,
+        entries: &[
+            wgpu::BindGroupEntry { binding: 0, resource: colors.as_entire_binding() },
+            wgpu::BindGroupEntry { binding: 1, resource: tangents.as_entire_binding() },
+        ],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::viewport::triangulation::triangulate;
+    
+    /// Two quads sharing an edge
+    fn two_quads() -> (Vec<Vec3>, Vec<i32>, Vec<i32>) {
+        let points = vec![
+            Vec3::new(0.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0), Vec3::new(2.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0), Vec3::new(1.0, 1.0, 0.0), Vec3::new(2.0, 1.0, 0.0),
+        ];
+        (points, vec![4, 4], vec![0, 1, 4, 3, 1, 2, 5, 4])
+    }
+    
+    #[test]
+    fn numeric_primvar_types_are_displayable() {
+        assert_eq!(type_components("color3f[]"), Some(3));
+        assert_eq!(type_components("texCoord2f[]"), Some(2));
+        assert_eq!(type_components("float"), Some(1));
+        assert_eq!(type_components("double4[]"), Some(4));
+        assert_eq!(type_components("int[]"), Some(1));
+        assert_eq!(type_components("int64[]"), None);
+        assert_eq!(type_components("token[]"), None);
+        assert_eq!(type_components("matrix4d"), None);
+        assert_eq!(type_components("quatf"), None);
+        
+        for interpolation in [Interpolation::Constant, Interpolation::Uniform, Interpolation::Varying, Interpolation::Vertex, Interpolation::FaceVarying] {
+            assert_eq!(Interpolation::from_token(interpolation.token()), Some(interpolation));
+        }
+        let info = PrimvarInfo { name: "Cd".to_string(), type_name: "color3f[]".to_string(), interpolation: Interpolation::FaceVarying };
+        assert_eq!(info.label(), "Cd (color3f[], faceVarying)");
+    }
+    
+    #[test]
+    fn face_varying_and_uniform_colors_split_per_corner() {
+        let (points, counts, indices) = two_quads();
+        let mesh = triangulate(&points, &counts, &indices, &[]).unwrap();
+        let red_green = ColorPrimvars {
+            color: Primvar::from_components("displayColor", Interpolation::Uniform, &[1.0, 0.0, 0.0, 0.0, 1.0, 0.0], 3),
+            opacity: None,
+        };
+        assert!(!red_green.per_point());
+        let corners = red_green.vertex_colors(&mesh, points.len(), true).unwrap();
+        assert_eq!(corners.len(), mesh.indices.len());
+        for (corner, color) in corners.iter().enumerate() {
+            let face = mesh.face_ids[corner / 3];
+            assert_eq!(*color, if face == 0 { Vec4::new(1.0, 0.0, 0.0, 1.0) } else { Vec4::new(0.0, 1.0, 0.0, 1.0) });
+        }
+        
+        // Refined uniform colors become faceVarying and stay flat per parent face
+        let refined = red_green.subdivide(points.len(), &counts, &indices, &[], 1).unwrap().color;
+        assert_eq!(refined.interpolation, Interpolation::FaceVarying);
+        assert_eq!(refined.values.len(), 32);
+        assert!(refined.values[..16].iter().all(|color| *color == Vec4::new(1.0, 0.0, 0.0, 1.0)));
+        assert!(refined.values[16..].iter().all(|color| *color == Vec4::new(0.0, 1.0, 0.0, 1.0)));
+        
+        // Indexed faceVarying values follow faceVertexIndices order
+        let mut face_varying = Primvar::from_components("Cd", Interpolation::FaceVarying, &[0.0, 0.5], 1);
+        face_varying.indices = vec![0, 0, 0, 0, 1, 1, 1, 1];
+        for (corner, color) in face_varying.corner_values(&mesh).unwrap().iter().enumerate() {
+            let gray = if mesh.face_ids[corner / 3] == 0 { 0.0 } else { 0.5 };
+            assert_eq!(*color, Vec4::new(gray, gray, gray, 1.0));
+        }
+        
+        face_varying.indices.pop();
+        assert!(face_varying.corner_values(&mesh).is_err());
+    }
+    
+    #[test]
+    fn face_varying_seams_duplicate_only_their_points() {
+        let (points, counts, indices) = two_quads();
+        let mesh = triangulate(&points, &counts, &indices, &[]).unwrap();
+        // Each quad gets its own UV island, splitting the shared edge 1-4
+        let mut st = Primvar::from_components("st", Interpolation::FaceVarying, &[
+            0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0, 1.0,
+            2.0, 0.0, 3.0, 0.0, 3.0, 1.0, 2.0, 1.0,
+        ], 2);
+        let corner_st = st.corner_values(&mesh).unwrap();
+        let welded = weld_corners(&mesh.indices, &[&corner_st]);
+        assert_eq!(welded.vertex_corners.len(), points.len() + 2);
+        for (corner, &vertex) in welded.indices.iter().enumerate() {
+            assert_eq!(welded.gather(&corner_st)[vertex as usize], corner_st[corner]);
+        }
+        
+        // A continuous layout welds back to one vertex per point
+        st.values = [0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0, 1.0, 1.0, 0.0, 2.0, 0.0, 2.0, 1.0, 1.0, 1.0]
+            .chunks_exact(2).map(|value| Vec4::new(value[0], value[1], 0.0, 1.0)).collect();
+        let corner_st = st.corner_values(&mesh).unwrap();
+        assert_eq!(weld_corners(&mesh.indices, &[&corner_st]).vertex_corners.len(), points.len());
+        
+        // Uniform normals make hard edges, one vertex per point of each face
+        let normals = Primvar::from_components("normals", Interpolation::Uniform, &[0.0, 0.0, 1.0, 1.0, 0.0, 0.0], 3);
+        let primvars = MeshPrimvars { normals: Some(normals.clone()), ..Default::default() };
+        assert!(!primvars.per_point());
+        let corner_normals = normals.corner_values(&mesh).unwrap();
+        assert_eq!(weld_corners(&mesh.indices, &[&corner_normals]).vertex_corners.len(), 8);
+        assert_eq!(primvars.subdivide(points.len(), &counts, &indices, &[], 1).unwrap().normals, None);
+    }
+    
+    #[test]
+    fn vertex_colors_stay_per_point_and_take_opacity() {
+        let (points, counts, indices) = two_quads();
+        let mesh = triangulate(&points, &counts, &indices, &[]).unwrap();
+        // Colors equal to the positions make refinement easy to check
+        let flat: Vec<f32> = points.iter().flat_map(|point| point.to_array()).collect();
+        let colors = ColorPrimvars {
+            color: Primvar::from_components("displayColor", Interpolation::Vertex, &flat, 3),
+            opacity: Some(Primvar::from_components("displayOpacity", Interpolation::Constant, &[0.25], 1)),
+        };
+        assert!(colors.per_point());
+        assert_eq!(
+            colors.vertex_colors(&mesh, points.len(), false).unwrap(),
+            points.iter().map(|point| point.extend(0.25)).collect::<Vec<_>>(),
+        );
+        
+        // Refined colors blend like refined points; constant opacity stays constant
+        let refined = colors.subdivide(points.len(), &counts, &indices, &[], 1).unwrap();
+        let refined_points = subdivide(&points, &counts, &indices, &[], 1).unwrap().points;
+        assert_eq!(refined.color.values.len(), refined_points.len());
+        for (color, point) in refined.color.values.iter().zip(&refined_points) {
+            assert!(color.truncate().distance(*point) < 1e-5);
+        }
+        assert_eq!(refined.opacity, colors.opacity);
+        
+        // Welding keeps each corner's point and routes edges through the points' vertices
+        let welded = weld_corners(&mesh.indices, &[]);
+        assert_eq!(welded.vertex_corners.len(), points.len());
+        let split = welded.gather(&mesh.indices.iter().map(|&point| points[point as usize]).collect::<Vec<_>>());
+        for (&vertex, &point) in welded.indices.iter().zip(&mesh.indices) {
+            assert_eq!(split[vertex as usize], points[point as usize]);
+        }
+        for (&vertex, &point) in welded.edge_indices(&mesh.indices, &mesh.edge_indices, points.len()).iter().zip(&mesh.edge_indices) {
+            assert_eq!(split[vertex as usize], points[point as usize]);
+        }
+    }
+}
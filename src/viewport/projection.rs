@@ -0,0 +1,186 @@
+//! Camera projection mapping for matte-painting and set-extension previews
+//!
+//! Projects an image from a UsdGeomCamera onto geometry by generating
+//! texture coordinates from each vertex's position in the camera's view.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use glam::{EulerRot, Mat4, Quat, Vec2, Vec3};
+use once_cell::sync::Lazy;
+
+/// Viewport camera projection preview options
+#[derive(Debug, Clone, Default)]
+pub struct ProjectionSettings {
+    pub enabled: bool,
+    /// UsdGeomCamera prim to project from
+    pub camera_path: String,
+    /// Image or image sequence pattern to project
+    pub image: String,
+}
+
+impl ProjectionSettings {
+    pub fn is_active(&self) -> bool {
+        self.enabled && !self.camera_path.is_empty() && !self.image.is_empty()
+    }
+}
+
+/// Camera parameters needed to project an image, in UsdGeomCamera units
+#[derive(Debug, Clone, Copy)]
+pub struct ProjectionCamera {
+    /// Camera-to-world transform
+    pub world: Mat4,
+    /// Focal length and apertures share units (mm by convention)
+    pub focal_length: f32,
+    pub horizontal_aperture: f32,
+    pub vertical_aperture: f32,
+    pub near: f32,
+    pub far: f32,
+}
+
+impl Default for ProjectionCamera {
+    fn default() -> Self {
+        // UsdGeomCamera schema defaults
+        Self {
+            world: Mat4::IDENTITY,
+            focal_length: 50.0,
+            horizontal_aperture: 20.955,
+            vertical_aperture: 15.2908,
+            near: 1.0,
+            far: 1_000_000.0,
+        }
+    }
+}
+
+impl ProjectionCamera {
+    /// World-to-clip transform of the projector
+    pub fn view_projection(&self) -> Mat4 {
+        let aspect = self.horizontal_aperture / self.vertical_aperture;
+        Mat4::perspective_rh(self.fov_y(), aspect, self.near, self.far) * self.world.inverse()
+    }
+    
+    /// Vertical field of view in radians
+    pub fn fov_y(&self) -> f32 {
+        2.0 * (self.vertical_aperture / (2.0 * self.focal_length)).atan()
+    }
+    
+    /// Eye, a target `distance` ahead and up vector of the camera, the inverse of `from_view`
+    pub fn look_at(&self, distance: f32) -> (Vec3, Vec3, Vec3) {
+        let eye = self.world.transform_point3(Vec3::ZERO);
+        let forward = self.world.transform_vector3(Vec3::NEG_Z).normalize_or_zero();
+        let up = self.world.transform_vector3(Vec3::Y).normalize_or_zero();
+        (eye, eye + forward * distance, up)
+    }
+    
+    /// A camera looking from `eye` at `target` with a vertical field of view in radians
+    ///
+    /// Keeps the schema's vertical aperture and fits the horizontal one to
+    /// `aspect`, so the focal length alone carries the field of view.
+    pub fn from_view(eye: Vec3, target: Vec3, up: Vec3, fov_y: f32, aspect: f32, near: f32, far: f32) -> Self {
+        let defaults = Self::default();
+        Self {
+            world: Mat4::look_at_rh(eye, target, up).inverse(),
+            focal_length: defaults.vertical_aperture / (2.0 * (fov_y / 2.0).tan()),
+            horizontal_aperture: defaults.vertical_aperture * aspect,
+            vertical_aperture: defaults.vertical_aperture,
+            near,
+            far,
+        }
+    }
+    
+    /// Translate and rotateXYZ (degrees) xformOps reproducing the camera-to-world transform
+    pub fn xform_ops(&self) -> (Vec3, Vec3) {
+        let (_, rotation, translation) = self.world.to_scale_rotation_translation();
+        let (z, y, x) = rotation.to_euler(EulerRot::ZYX);
+        (translation, Vec3::new(x, y, z) * (180.0 / std::f32::consts::PI))
+    }
+    
+    /// Texture coordinate for a world-space point, None when it lies behind the camera
+    ///
+    /// The image spans 0..1 across the camera aperture with v = 0 at the top
+    /// of the frame; points outside the frustum get coordinates outside 0..1.
+    pub fn project(&self, view_projection: &Mat4, point: Vec3) -> Option<Vec2> {
+        let clip = *view_projection * point.extend(1.0);
+        if clip.w <= 0.0 {
+            return None;
+        }
+        let ndc = clip.truncate() / clip.w;
+        Some(Vec2::new(ndc.x * 0.5 + 0.5, 0.5 - ndc.y * 0.5))
+    }
+    
+    /// Generate projected UVs for a flat xyz vertex array with an object-to-world transform
+    pub fn project_vertices(&self, positions: &[f32], transform: Mat4) -> Vec<f32> {
+        let view_projection = self.view_projection();
+        positions.chunks_exact(3)
+            .flat_map(|p| {
+                let world = transform.transform_point3(Vec3::new(p[0], p[1], p[2]));
+                // Points behind the projector get a coordinate outside the image
+                let uv = self.project(&view_projection, world).unwrap_or(Vec2::splat(-1.0));
+                [uv.x, uv.y]
+            })
+            .collect()
+    }
+}
+
+/// Compose translate/rotateXYZ (degrees)/scale xformOps into a matrix
+pub fn xform_ops_to_mat4(translate: Vec3, rotate_xyz_degrees: Vec3, scale: Vec3) -> Mat4 {
+    // rotateXYZ applies X first, then Y, then Z
+    let rotation = Quat::from_euler(
+        EulerRot::ZYX,
+        rotate_xyz_degrees.z.to_radians(),
+        rotate_xyz_degrees.y.to_radians(),
+        rotate_xyz_degrees.x.to_radians(),
+    );
+    Mat4::from_scale_rotation_translation(scale, rotation, translate)
+}
+
+/// Free camera of the viewport showing each stage, keyed by stage identifier
+static VIEW_CAMERAS: Lazy<Mutex<HashMap<String, ProjectionCamera>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Camera of the viewport showing a stage, for nodes snapping to the view
+pub fn view_camera(stage_id: &str) -> Option<ProjectionCamera> {
+    VIEW_CAMERAS.lock().unwrap().get(stage_id).copied()
+}
+
+/// Publish the camera of the viewport showing a stage
+pub fn set_view_camera(stage_id: &str, camera: ProjectionCamera) {
+    VIEW_CAMERAS.lock().unwrap().insert(stage_id.to_string(), camera);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn center_of_view_maps_to_image_center() {
+        let camera = ProjectionCamera {
+            world: Mat4::from_translation(Vec3::new(0.0, 0.0, 10.0)),
+            ..Default::default()
+        };
+        let uv = camera.project(&camera.view_projection(), Vec3::ZERO).unwrap();
+        assert!((uv - Vec2::splat(0.5)).length() < 1e-5);
+    }
+    
+    #[test]
+    fn views_round_trip_through_xform_ops() {
+        let eye = Vec3::new(5.0, 3.0, -4.0);
+        let camera = ProjectionCamera::from_view(eye, Vec3::new(0.0, 1.0, 0.0), Vec3::Y, 40f32.to_radians(), 16.0 / 9.0, 0.1, 500.0);
+        assert!((camera.fov_y() - 40f32.to_radians()).abs() < 1e-5);
+        assert!((camera.horizontal_aperture / camera.vertical_aperture - 16.0 / 9.0).abs() < 1e-5);
+        
+        let (translate, rotate) = camera.xform_ops();
+        let world = xform_ops_to_mat4(translate, rotate, Vec3::ONE);
+        assert!(world.abs_diff_eq(camera.world, 1e-4));
+        assert!((world.transform_point3(Vec3::ZERO) - eye).length() < 1e-4);
+        
+        let (look_eye, target, up) = camera.look_at(eye.distance(Vec3::Y));
+        assert!((look_eye - eye).length() < 1e-4);
+        assert!((target - Vec3::Y).length() < 1e-4);
+        assert!(up.dot(Vec3::Y) > 0.0 && up.dot(target - eye).abs() < 1e-4);
+    }
+    
+    #[test]
+    fn points_behind_camera_are_rejected() {
+        let camera = ProjectionCamera::default();
+        assert!(camera.project(&camera.view_projection(), Vec3::new(0.0, 0.0, 5.0)).is_none());
+    }
+}
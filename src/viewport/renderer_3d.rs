@@ -0,0 +1,451 @@
+//! Base wgpu renderer of the USD viewport
+//!
+//! `Renderer3D` owns the device, the free camera and the pipelines every
+//! scene pass draws with: meshes shaded by usd_mesh.wgsl, as surfaces or as
+//! a line list for wireframes, and the ground grid and axis gizmo as colored
+//! lines from usd_grid.wgsl. Object-to-world transforms are instance-rate
+//! vertex attributes, so one mesh pipeline draws single prims and instance
+//! batches alike. `USDRenderer` builds on it with the scene, its buffers and
+//! the passes around the scene pass.
+//!
+//! Mesh draws push `DrawConstants` to the fragment stage, so the device must
+//! have `Features::PUSH_CONSTANTS` and at least `DRAW_CONSTANTS_SIZE` bytes of
+//! push constants; `request_device` asks for both. Material textures are
+//! viewed as sRGB or linear, which also needs `DownlevelFlags::VIEW_FORMATS`.
+
+use bytemuck::{Pod, Zeroable};
+use glam::{Mat4, Vec3};
+use wgpu::util::DeviceExt;
+use wgpu::{Buffer, Device, Queue, RenderPass};
+use super::antialiasing::PASS_FORMATS;
+use super::camera::Camera3D;
+
+/// 3D Vertex structure for rendering
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
+pub struct Vertex3D {
+    pub position: [f32; 3],
+    pub normal: [f32; 3],
+    pub uv: [f32; 2],
+}
+
+/// Camera uniform at group 0, binding 0 (usd_mesh.wgsl and usd_grid.wgsl `USDUniforms`)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+pub struct Uniforms3D {
+    pub view_proj: [[f32; 4]; 4],
+    /// Transform of the grid and axis lines; meshes take theirs per instance
+    pub model: [[f32; 4]; 4],
+    pub camera_pos: [f32; 3],
+    pub _padding: f32,
+}
+
+impl Uniforms3D {
+    /// Uniforms viewing the scene through a camera
+    pub fn new(camera: &Camera3D) -> Self {
+        Self {
+            view_proj: camera.build_view_projection_matrix().to_cols_array_2d(),
+            model: Mat4::IDENTITY.to_cols_array_2d(),
+            camera_pos: camera.position.to_array(),
+            _padding: 0.0,
+        }
+    }
+}
+
+/// Bytes of fragment push constants a mesh draw takes (usd_mesh.wgsl `DrawConstants`)
+pub const DRAW_CONSTANTS_SIZE: u32 = 64;
+
+/// Half the extent of the ground grid, in scene units, with a line every unit
+const GRID_EXTENT: i32 = 10;
+
+/// Colored line vertex of the grid and axis gizmo (usd_grid.wgsl `VertexInput`)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+struct LineVertex {
+    position: [f32; 3],
+    color: [f32; 4],
+}
+
+/// Lines of the ground grid on the XZ plane
+fn grid_lines() -> Vec<LineVertex> {
+    let color = [0.5, 0.5, 0.5, 0.4];
+    let extent = GRID_EXTENT as f32;
+    (-GRID_EXTENT..=GRID_EXTENT)
+        .flat_map(|step| {
+            let offset = step as f32;
+            [
+                [offset, 0.0, -extent], [offset, 0.0, extent],
+                [-extent, 0.0, offset], [extent, 0.0, offset],
+            ]
+        })
+        .map(|position| LineVertex { position, color })
+        .collect()
+}
+
+/// Unit X, Y and Z axes from the origin in red, green and blue
+fn axis_lines() -> Vec<LineVertex> {
+    [Vec3::X, Vec3::Y, Vec3::Z].into_iter()
+        .flat_map(|axis| {
+            let color = axis.extend(1.0).to_array();
+            [
+                LineVertex { position: [0.0; 3], color },
+                LineVertex { position: axis.to_array(), color },
+            ]
+        })
+        .collect()
+}
+
+/// Instance-rate transform attributes, one vec4 column each (usd_mesh.wgsl `InstanceInput`)
+const INSTANCE_ATTRIBUTES: [wgpu::VertexAttribute; 4] = wgpu::vertex_attr_array![3 => Float32x4, 4 => Float32x4, 5 => Float32x4, 6 => Float32x4];
+
+/// Upload object-to-world transforms for a mesh draw, one per instance
+pub fn create_transform_buffer(device: &Device, label: &str, transforms: &[Mat4]) -> Buffer {
+    let columns: Vec<[[f32; 4]; 4]> = transforms.iter().map(Mat4::to_cols_array_2d).collect();
+    device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some(label),
+        contents: bytemuck::cast_slice(&columns),
+        usage: wgpu::BufferUsages::VERTEX,
+    })
+}
+
+/// Ask the default adapter for a device the viewport renderer can draw with
+///
+/// For rendering without a window, such as viewport snapshots.
+pub fn request_device() -> Result<(Device, Queue), String> {
+    let instance = wgpu::Instance::default();
+    let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions::default()))
+        .map_err(|e| format!("No graphics adapter: {}", e))?;
+    if !adapter.features().contains(wgpu::Features::PUSH_CONSTANTS) {
+        return Err(format!("{} has no push constants", adapter.get_info().name));
+    }
+    if !adapter.get_downlevel_capabilities().flags.contains(wgpu::DownlevelFlags::VIEW_FORMATS) {
+        return Err(format!("{} can't view textures as sRGB and linear", adapter.get_info().name));
+    }
+    pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor {
+        label: Some("usd_viewport_device"),
+        required_features: wgpu::Features::PUSH_CONSTANTS | (adapter.features() & wgpu::Features::ADDRESS_MODE_CLAMP_TO_BORDER),
+        required_limits: adapter.limits(),
+        ..Default::default()
+    }))
+    .map_err(|e| format!("Failed to create a device: {}", e))
+}
+
+/// Scene pass draws in any pass implementing it, such as the viewport's or a capture's
+pub trait USDRenderPass {
+    /// Draw into a pass with `PASS_FORMATS` attachments at the renderer's sample count
+    fn render_to_pass(&self, render_pass: &mut RenderPass);
+}
+
+/// Pipelines for one sample count
+struct ScenePipelines {
+    mesh: wgpu::RenderPipeline,
+    wireframe: wgpu::RenderPipeline,
+    lines: wgpu::RenderPipeline,
+}
+
+/// Device, free camera and scene pass pipelines of the viewport
+pub struct Renderer3D {
+    pub device: Option<Device>,
+    pub queue: Option<Queue>,
+    /// Free viewport camera
+    pub camera: Camera3D,
+    sample_count: u32,
+    scene_layout: Option<wgpu::BindGroupLayout>,
+    uniform_buffer: Option<Buffer>,
+    lighting_buffer: Option<Buffer>,
+    scene_bind_group: Option<wgpu::BindGroup>,
+    pipelines: Option<ScenePipelines>,
+    /// Grid and axis gizmo lines, with their vertex counts
+    grid: Option<(Buffer, u32)>,
+    axes: Option<(Buffer, u32)>,
+}
+
+impl std::fmt::Debug for Renderer3D {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Renderer3D")
+            .field("initialized", &self.device.is_some())
+            .field("camera", &self.camera)
+            .field("sample_count", &self.sample_count)
+            .finish()
+    }
+}
+
+impl Default for Renderer3D {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Renderer3D {
+    pub fn new() -> Self {
+        Self {
+            device: None,
+            queue: None,
+            camera: Camera3D::default(),
+            sample_count: 1,
+            scene_layout: None,
+            uniform_buffer: None,
+            lighting_buffer: None,
+            scene_bind_group: None,
+            pipelines: None,
+            grid: None,
+            axes: None,
+        }
+    }
+    
+    /// Take the device to draw with and create the camera uniform and line buffers
+    ///
+    /// Pipelines follow with `set_sample_count`.
+    pub fn initialize(&mut self, device: Device, queue: Queue) {
+        let uniform = |binding| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        self.scene_layout = Some(device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("usd_scene_layout"),
+            entries: &[uniform(0), uniform(1)],
+        }));
+        self.uniform_buffer = Some(device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("usd_scene_uniforms"),
+            contents: bytemuck::bytes_of(&Uniforms3D::new(&self.camera)),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        }));
+        let lines = |label, vertices: Vec<LineVertex>| {
+            let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some(label),
+                contents: bytemuck::cast_slice(&vertices),
+                usage: wgpu::BufferUsages::VERTEX,
+            });
+            (buffer, vertices.len() as u32)
+        };
+        self.grid = Some(lines("usd_grid", grid_lines()));
+        self.axes = Some(lines("usd_axes", axis_lines()));
+        self.lighting_buffer = None;
+        self.scene_bind_group = None;
+        self.pipelines = None;
+        self.device = Some(device);
+        self.queue = Some(queue);
+    }
+    
+    /// Samples per pixel the pipelines draw with
+    pub fn sample_count(&self) -> u32 {
+        self.sample_count
+    }
+    
+    /// Create the pipelines for a scene pass with `samples` samples per pixel
+    ///
+    /// `surface_layouts` are the material texture and vertex attribute layouts
+    /// of mesh groups 1 and 2.
+    pub fn set_sample_count(&mut self, samples: u32, surface_layouts: [&wgpu::BindGroupLayout; 2]) {
+        let (Some(device), Some(scene_layout)) = (&self.device, &self.scene_layout) else {
+            return;
+        };
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("usd_mesh"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/usd_mesh.wgsl").into()),
+        });
+        let mesh_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("usd_mesh_pipeline_layout"),
+            bind_group_layouts: &[scene_layout, surface_layouts[0], surface_layouts[1]],
+            push_constant_ranges: &[wgpu::PushConstantRange {
+                stages: wgpu::ShaderStages::FRAGMENT,
+                range: 0..DRAW_CONSTANTS_SIZE,
+            }],
+        });
+        let vertex_buffers = [
+            wgpu::VertexBufferLayout {
+                array_stride: std::mem::size_of::<Vertex3D>() as u64,
+                step_mode: wgpu::VertexStepMode::Vertex,
+                attributes: &wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x3, 2 => Float32x2],
+            },
+            wgpu::VertexBufferLayout {
+                array_stride: std::mem::size_of::<[[f32; 4]; 4]>() as u64,
+                step_mode: wgpu::VertexStepMode::Instance,
+                attributes: &INSTANCE_ATTRIBUTES,
+            },
+        ];
+        let target = [Some(wgpu::ColorTargetState {
+            format: PASS_FORMATS[0],
+            blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+            write_mask: wgpu::ColorWrites::ALL,
+        })];
+        let depth = |depth_write_enabled| Some(wgpu::DepthStencilState {
+            format: PASS_FORMATS[1],
+            depth_write_enabled,
+            depth_compare: wgpu::CompareFunction::LessEqual,
+            stencil: wgpu::StencilState::default(),
+            bias: wgpu::DepthBiasState::default(),
+        });
+        let mesh_pipeline = |label, topology| device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some(label),
+            layout: Some(&mesh_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                compilation_options: Default::default(),
+                buffers: &vertex_buffers,
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                compilation_options: Default::default(),
+                targets: &target,
+            }),
+            // No culling, since USD meshes are often open or single-sided
+            primitive: wgpu::PrimitiveState { topology, ..Default::default() },
+            depth_stencil: depth(true),
+            multisample: wgpu::MultisampleState { count: samples, ..Default::default() },
+            multiview: None,
+            cache: None,
+        });
+        let mesh = mesh_pipeline("usd_mesh", wgpu::PrimitiveTopology::TriangleList);
+        let wireframe = mesh_pipeline("usd_wireframe", wgpu::PrimitiveTopology::LineList);
+        
+        let line_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("usd_grid"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/usd_grid.wgsl").into()),
+        });
+        let line_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("usd_grid_pipeline_layout"),
+            bind_group_layouts: &[scene_layout],
+            push_constant_ranges: &[],
+        });
+        // Lines blend over the scene and leave the depth to it
+        let lines = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("usd_grid"),
+            layout: Some(&line_layout),
+            vertex: wgpu::VertexState {
+                module: &line_shader,
+                entry_point: Some("vs_main"),
+                compilation_options: Default::default(),
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: std::mem::size_of::<LineVertex>() as u64,
+                    step_mode: wgpu::VertexStepMode::Vertex,
+                    attributes: &wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x4],
+                }],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &line_shader,
+                entry_point: Some("fs_main"),
+                compilation_options: Default::default(),
+                targets: &target,
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::LineList,
+                ..Default::default()
+            },
+            depth_stencil: depth(false),
+            multisample: wgpu::MultisampleState { count: samples, ..Default::default() },
+            multiview: None,
+            cache: None,
+        });
+        
+        self.pipelines = Some(ScenePipelines { mesh, wireframe, lines });
+        self.sample_count = samples;
+    }
+    
+    /// Write the camera uniform and the lighting uniform at binding 1 for the next pass
+    pub fn update_uniforms(&mut self, uniforms: &Uniforms3D, lighting: &[u8]) {
+        let (Some(device), Some(queue), Some(layout), Some(uniform_buffer)) = (&self.device, &self.queue, &self.scene_layout, &self.uniform_buffer) else {
+            return;
+        };
+        queue.write_buffer(uniform_buffer, 0, bytemuck::bytes_of(uniforms));
+        match &self.lighting_buffer {
+            Some(buffer) if buffer.size() == lighting.len() as u64 => queue.write_buffer(buffer, 0, lighting),
+            _ => {
+                let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some("usd_scene_lighting"),
+                    contents: lighting,
+                    usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                });
+                self.scene_bind_group = Some(device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: Some("usd_scene"),
+                    layout,
+                    entries: &[
+                        wgpu::BindGroupEntry { binding: 0, resource: uniform_buffer.as_entire_binding() },
+                        wgpu::BindGroupEntry { binding: 1, resource: buffer.as_entire_binding() },
+                    ],
+                }));
+                self.lighting_buffer = Some(buffer);
+            }
+        }
+    }
+    
+    /// Set the mesh or wireframe pipeline and the scene uniforms for the mesh draws that follow
+    ///
+    /// False, setting nothing, until the pipelines and uniforms exist; the
+    /// caller then sets the push constants and groups 1 and 2 of each draw.
+    pub fn bind_mesh_pipeline(&self, render_pass: &mut RenderPass, wireframe: bool) -> bool {
+        let (Some(pipelines), Some(bind_group)) = (&self.pipelines, &self.scene_bind_group) else {
+            return false;
+        };
+        render_pass.set_pipeline(if wireframe { &pipelines.wireframe } else { &pipelines.mesh });
+        render_pass.set_bind_group(0, bind_group, &[]);
+        true
+    }
+    
+    /// Draw a mesh with the bound pipeline, once per transform in `transforms`
+    ///
+    /// `indices` are triangles for the mesh pipeline and edges for the wireframe one.
+    pub fn render_mesh(&self, render_pass: &mut RenderPass, vertices: &Buffer, indices: &Buffer, index_count: u32, transforms: &Buffer, instance_count: u32) {
+        render_pass.set_vertex_buffer(0, vertices.slice(..));
+        render_pass.set_vertex_buffer(1, transforms.slice(..));
+        render_pass.set_index_buffer(indices.slice(..), wgpu::IndexFormat::Uint32);
+        render_pass.draw_indexed(0..index_count, 0, 0..instance_count);
+    }
+    
+    /// Draw the ground grid
+    pub fn render_grid(&self, render_pass: &mut RenderPass) {
+        self.render_lines(render_pass, self.grid.as_ref());
+    }
+    
+    /// Draw the world axes at the origin
+    pub fn render_axis_gizmo(&self, render_pass: &mut RenderPass) {
+        self.render_lines(render_pass, self.axes.as_ref());
+    }
+    
+    fn render_lines(&self, render_pass: &mut RenderPass, lines: Option<&(Buffer, u32)>) {
+        let (Some(pipelines), Some(bind_group), Some((vertices, count))) = (&self.pipelines, &self.scene_bind_group, lines) else {
+            return;
+        };
+        render_pass.set_pipeline(&pipelines.lines);
+        render_pass.set_bind_group(0, bind_group, &[]);
+        render_pass.set_vertex_buffer(0, vertices.slice(..));
+        render_pass.draw(0..*count, 0..1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn uniforms_match_the_shader_layout() {
+        // view_proj and model, then camera_pos padded to 16 bytes
+        assert_eq!(std::mem::size_of::<Uniforms3D>(), 144);
+        assert_eq!(std::mem::size_of::<Vertex3D>(), 32);
+        
+        let camera = Camera3D::default();
+        let uniforms = Uniforms3D::new(&camera);
+        assert_eq!(uniforms.model, Mat4::IDENTITY.to_cols_array_2d());
+        assert_eq!(uniforms.camera_pos, camera.position.to_array());
+    }
+    
+    #[test]
+    fn grid_and_axes_are_line_lists() {
+        let grid = grid_lines();
+        assert_eq!(grid.len(), 4 * (2 * GRID_EXTENT as usize + 1));
+        assert!(grid.iter().all(|vertex| vertex.position[1] == 0.0));
+        
+        let axes = axis_lines();
+        assert_eq!(axes.len(), 6);
+        assert_eq!(axes[1].position, [1.0, 0.0, 0.0]);
+        assert_eq!(axes[3].color, [0.0, 1.0, 0.0, 1.0]);
+    }
+}
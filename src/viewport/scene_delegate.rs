@@ -0,0 +1,1683 @@
+//! Renderer-agnostic USD scene extraction
+//!
+//! The scene delegate reads a stage at a time code into a `USDScene`:
+//! meshes triangulated, subdivided and skinned, point instancer and
+//! scenegraph instance batches, UsdLux lights with their link collections,
+//! UsdPreviewSurface materials with their resolved bindings, and cameras.
+//! Renderers take the result through a `SceneSink`, so the wgpu viewport, a
+//! path tracer node or an external renderer integration share one
+//! extraction instead of each walking the stage through PyO3.
+//!
+//! Extracted scenes are cached per stage, keyed by the stage's revision and
+//! the extraction settings, and replayed into further sinks until the stage
+//! is next edited or explicitly reloaded.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use glam::{Mat4, Vec2, Vec3, Vec4};
+use once_cell::sync::Lazy;
+use crate::core::usd_engine::with_usd_engine;
+use crate::core::scene_query::{SceneLight, SceneMaterial, SceneMesh, SceneSnapshot};
+use super::renderer_3d::Vertex3D;
+use super::usd_rendering::{USDCamera, USDGeometry, USDLight, USDMaterial, USDScene, DEFAULT_MATERIAL};
+use super::triangulation::triangulate;
+use super::instancing::InstanceBatch;
+use super::light_linking::LinkCollection;
+use super::material_binding::{resolve_binding, MaterialBinding, PREVIEW_PURPOSE};
+use super::environment::Environment;
+use super::primvars::{weld_corners, MeshPrimvars};
+use super::tangents::generate_tangents;
+use super::lux::LuxParams;
+use super::path_tracer::{TraceLight, TraceMaterial, TraceMesh, TraceScene};
+#[cfg(feature = "usd")]
+use super::usd_rendering::usd_matrix_to_mat4;
+#[cfg(feature = "usd")]
+use super::subdivision::{is_catmull_clark, refined_face_origins, subdivide};
+#[cfg(feature = "usd")]
+use super::skinning::{skin_points, JointInfluences, Skeleton};
+#[cfg(feature = "usd")]
+use super::instancing::{build_instance_batches, is_under};
+#[cfg(feature = "usd")]
+use super::preview_surface::{InputSource, PrimvarInput, TextureInput};
+#[cfg(feature = "usd")]
+use super::material_binding::BindingStrength;
+#[cfg(feature = "usd")]
+use super::environment::load_latlong;
+#[cfg(feature = "usd")]
+use super::primvars::{type_components, ColorPrimvars, Interpolation, Primvar, PrimvarInfo};
+#[cfg(feature = "usd")]
+use super::projection::ProjectionCamera;
+#[cfg(feature = "usd")]
+use super::camera_response::exposure_scale;
+#[cfg(feature = "usd")]
+use super::lux::LUX_INPUTS;
+
+#[cfg(feature = "usd")]
+use pyo3::prelude::*;
+#[cfg(feature = "usd")]
+use crate::core::profiling;
+#[cfg(feature = "usd")]
+use crate::core::materialx::preview_values;
+
+/// Light added to stages without lights, not authored on the stage
+pub const DEFAULT_LIGHT: &str = "/World/DefaultLight";
+
+/// Receiver of an extracted scene
+///
+/// A delegate calls `begin_scene`, then hands over every item of the scene,
+/// then calls `end_scene`. Geometry comes before the instance batches drawing
+/// it and materials before the bindings naming them; each geometry's
+/// `material_path` is already resolved against the bindings.
+pub trait SceneSink {
+    fn begin_scene(&mut self, stage_id: &str, time_code: f64);
+    
+    fn add_geometry(&mut self, geometry: USDGeometry);
+    
+    /// Geometry below instance prototypes, only drawn through instance batches
+    fn add_prototype_geometry(&mut self, geometry: USDGeometry);
+    
+    fn add_instance_batch(&mut self, batch: InstanceBatch);
+    
+    fn add_material(&mut self, material: USDMaterial);
+    
+    /// Material bindings authored on a prim, for sinks resolving other purposes
+    fn add_material_bindings(&mut self, prim_path: &str, bindings: Vec<MaterialBinding>);
+    
+    fn add_light(&mut self, light: USDLight);
+    
+    /// Ambient of the stage's dome lights
+    fn add_environment(&mut self, environment: Environment);
+    
+    fn add_camera(&mut self, camera: USDCamera);
+    
+    fn end_scene(&mut self) {}
+}
+
+/// The wgpu viewport's sink, collecting the scene as it is
+impl SceneSink for USDScene {
+    fn begin_scene(&mut self, stage_id: &str, time_code: f64) {
+        *self = USDScene {
+            stage_id: stage_id.to_string(),
+            time_code,
+            ..USDScene::default()
+        };
+    }
+    
+    fn add_geometry(&mut self, geometry: USDGeometry) {
+        self.geometries.push(geometry);
+    }
+    
+    fn add_prototype_geometry(&mut self, geometry: USDGeometry) {
+        self.prototype_geometry.insert(geometry.prim_path.clone());
+        self.geometries.push(geometry);
+    }
+    
+    fn add_instance_batch(&mut self, batch: InstanceBatch) {
+        self.instance_batches.push(batch);
+    }
+    
+    fn add_material(&mut self, material: USDMaterial) {
+        self.materials.insert(material.prim_path.clone(), material);
+    }
+    
+    fn add_material_bindings(&mut self, prim_path: &str, bindings: Vec<MaterialBinding>) {
+        self.material_bindings.insert(prim_path.to_string(), bindings);
+    }
+    
+    fn add_light(&mut self, light: USDLight) {
+        self.lights.push(light);
+    }
+    
+    fn add_environment(&mut self, environment: Environment) {
+        self.environment = Some(self.environment.map_or(environment, |existing| existing + environment));
+    }
+    
+    fn add_camera(&mut self, camera: USDCamera) {
+        self.cameras.push(camera);
+    }
+}
+
+/// The path tracer's sink, building a `TraceScene` of world-space triangles when the scene ends
+#[derive(Debug, Default)]
+pub struct TraceSceneSink {
+    geometries: Vec<USDGeometry>,
+    prototypes: HashMap<String, USDGeometry>,
+    instance_batches: Vec<InstanceBatch>,
+    materials: HashMap<String, USDMaterial>,
+    lights: Vec<TraceLight>,
+    environment: Option<Environment>,
+    /// Scene built by the last `end_scene`
+    pub scene: Option<TraceScene>,
+}
+
+impl SceneSink for TraceSceneSink {
+    fn begin_scene(&mut self, _stage_id: &str, _time_code: f64) {
+        *self = TraceSceneSink::default();
+    }
+    
+    fn add_geometry(&mut self, geometry: USDGeometry) {
+        if geometry.visibility {
+            self.geometries.push(geometry);
+        }
+    }
+    
+    fn add_prototype_geometry(&mut self, geometry: USDGeometry) {
+        self.prototypes.insert(geometry.prim_path.clone(), geometry);
+    }
+    
+    fn add_instance_batch(&mut self, batch: InstanceBatch) {
+        self.instance_batches.push(batch);
+    }
+    
+    fn add_material(&mut self, material: USDMaterial) {
+        self.materials.insert(material.prim_path.clone(), material);
+    }
+    
+    /// Geometry arrives with its material resolved, so bindings aren't needed
+    fn add_material_bindings(&mut self, _prim_path: &str, _bindings: Vec<MaterialBinding>) {}
+    
+    /// Distant lights shine from their cone, other lights from a sphere of their projected area
+    fn add_light(&mut self, light: USDLight) {
+        let emitted = light.lux.tint() * light.radiance();
+        self.lights.push(match light.light_type.as_str() {
+            "distant" => TraceLight::Distant { direction: light.direction(), radiance: emitted, angle: light.lux.angle },
+            light_type => TraceLight::Sphere {
+                position: light.transform.transform_point3(Vec3::ZERO),
+                radius: light.lux.projected_area(light_type).map_or(0.0, |area| (area / std::f32::consts::PI).sqrt()),
+                intensity: emitted,
+                axis: light.direction(),
+                cone: light.lux.cone_cosines(),
+            },
+        });
+    }
+    
+    fn add_environment(&mut self, environment: Environment) {
+        self.environment = Some(self.environment.map_or(environment, |existing| existing + environment));
+    }
+    
+    fn add_camera(&mut self, _camera: USDCamera) {}
+    
+    fn end_scene(&mut self) {
+        let material = |path: Option<&str>| path.and_then(|path| self.materials.get(path))
+            .map_or_else(TraceMaterial::default, |material| TraceMaterial {
+                base_color: material.diffuse_color,
+                metallic: material.metallic,
+                roughness: material.roughness,
+                emission: material.emission_color,
+            });
+        let mesh = |geometry: &USDGeometry, transform: Mat4| TraceMesh {
+            points: geometry.vertices.iter().map(|vertex| transform.transform_point3(Vec3::from(vertex.position))).collect(),
+            indices: geometry.indices.clone(),
+            material: material(geometry.material_path.as_deref()),
+        };
+        let mut meshes: Vec<TraceMesh> = self.geometries.iter().map(|geometry| mesh(geometry, geometry.transform)).collect();
+        for batch in &self.instance_batches {
+            if let Some(prototype) = self.prototypes.get(&batch.geometry_path) {
+                meshes.extend(batch.transforms.iter().map(|transform| mesh(prototype, *transform)));
+            }
+        }
+        self.scene = Some(TraceScene::new(&meshes, std::mem::take(&mut self.lights), self.environment.unwrap_or_default()));
+    }
+}
+
+/// The scene query's sink, building a `SceneSnapshot` of world-space triangle meshes when the scene ends
+#[derive(Debug, Default)]
+pub struct SceneSnapshotSink {
+    geometries: Vec<USDGeometry>,
+    prototypes: HashMap<String, USDGeometry>,
+    instance_batches: Vec<InstanceBatch>,
+    materials: HashMap<String, USDMaterial>,
+    /// Snapshot built by the last `end_scene`
+    pub snapshot: SceneSnapshot,
+}
+
+impl SceneSink for SceneSnapshotSink {
+    fn begin_scene(&mut self, stage_id: &str, time_code: f64) {
+        *self = SceneSnapshotSink::default();
+        self.snapshot.stage_id = stage_id.to_string();
+        self.snapshot.time_code = time_code;
+    }
+    
+    fn add_geometry(&mut self, geometry: USDGeometry) {
+        if geometry.visibility {
+            self.geometries.push(geometry);
+        }
+    }
+    
+    fn add_prototype_geometry(&mut self, geometry: USDGeometry) {
+        self.prototypes.insert(geometry.prim_path.clone(), geometry);
+    }
+    
+    fn add_instance_batch(&mut self, batch: InstanceBatch) {
+        self.instance_batches.push(batch);
+    }
+    
+    fn add_material(&mut self, material: USDMaterial) {
+        self.materials.insert(material.prim_path.clone(), material);
+    }
+    
+    fn add_material_bindings(&mut self, _prim_path: &str, _bindings: Vec<MaterialBinding>) {}
+    
+    /// Authored lights, by their schema type, e.g. "rect" as "RectLight"
+    fn add_light(&mut self, light: USDLight) {
+        if light.prim_path == DEFAULT_LIGHT {
+            return;
+        }
+        let mut schema_type = light.light_type.clone();
+        if let Some(first) = schema_type.get_mut(..1) {
+            first.make_ascii_uppercase();
+        }
+        // glam's column-major layout is USD's row-vector layout read row by row
+        let mut scene_light = SceneLight::new(&light.prim_path, &format!("{}Light", schema_type), light.transform.to_cols_array().map(f64::from));
+        let lux = &light.lux;
+        scene_light.color = lux.tint().to_array();
+        (scene_light.intensity, scene_light.exposure, scene_light.angle) = (lux.intensity, lux.exposure, lux.angle);
+        (scene_light.radius, scene_light.width, scene_light.height) = (lux.radius, lux.width, lux.height);
+        self.snapshot.lights.push(scene_light);
+    }
+    
+    fn add_environment(&mut self, _environment: Environment) {}
+    
+    fn add_camera(&mut self, _camera: USDCamera) {}
+    
+    fn end_scene(&mut self) {
+        let material_path = |geometry: &USDGeometry| geometry.material_path.clone()
+            .filter(|path| path != DEFAULT_MATERIAL && self.materials.contains_key(path));
+        let mesh = |geometry: &USDGeometry, transform: Mat4| SceneMesh {
+            prim_path: geometry.prim_path.clone(),
+            points: geometry.vertices.iter()
+                .map(|vertex| transform.transform_point3(Vec3::from(vertex.position)).as_dvec3().to_array())
+                .collect(),
+            face_vertex_counts: vec![3; geometry.indices.len() / 3],
+            face_vertex_indices: geometry.indices.iter().map(|&index| index as i32).collect(),
+            material_path: material_path(geometry),
+        };
+        let mut meshes: Vec<SceneMesh> = self.geometries.iter().map(|geometry| mesh(geometry, geometry.transform)).collect();
+        for batch in &self.instance_batches {
+            if let Some(prototype) = self.prototypes.get(&batch.geometry_path) {
+                meshes.extend(batch.transforms.iter().map(|transform| mesh(prototype, *transform)));
+            }
+        }
+        for path in meshes.iter().filter_map(|mesh| mesh.material_path.as_deref()) {
+            if self.snapshot.material_index(path).is_none() {
+                let material = &self.materials[path];
+                let mut scene_material = SceneMaterial::new(path);
+                (scene_material.diffuse_color, scene_material.emissive_color) = (material.diffuse_color.to_array(), material.emission_color.to_array());
+                (scene_material.metallic, scene_material.roughness) = (material.metallic, material.roughness);
+                (scene_material.opacity, scene_material.ior) = (material.opacity, material.ior);
+                self.snapshot.materials.push(scene_material);
+            }
+        }
+        self.snapshot.meshes = meshes;
+    }
+}
+
+/// Settings that change what a stage extracts to
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtractionSettings {
+    pub time_code: f64,
+    /// Catmull-Clark refinement levels of subdivision meshes
+    pub subdivision_level: u32,
+    /// Color primvar read into vertex colors in place of displayColor
+    pub display_primvar: Option<String>,
+}
+
+/// A stage's last extracted scene, with what it was extracted from
+#[derive(Debug, Clone)]
+struct CachedScene {
+    revision: u64,
+    settings: ExtractionSettings,
+    scene: USDScene,
+}
+
+/// Extracts stages and caches their scenes for every sink
+#[derive(Debug, Default)]
+pub struct USDSceneDelegate {
+    scenes: HashMap<String, CachedScene>,
+}
+
+impl USDSceneDelegate {
+    /// Hand a stage's scene to a sink, extracting it unless the cached one is current
+    pub fn populate(&mut self, stage_id: &str, settings: &ExtractionSettings, sink: &mut dyn SceneSink) {
+        let revision = with_usd_engine(|engine| engine.stage_revision(stage_id));
+        let current = self.scenes.get(stage_id)
+            .is_some_and(|cached| cached.revision == revision && cached.settings == *settings);
+        if !current {
+            let scene = Extractor::extract(stage_id, settings);
+            self.scenes.insert(stage_id.to_string(), CachedScene { revision, settings: settings.clone(), scene });
+        }
+        replay(&self.scenes[stage_id].scene, sink);
+    }
+    
+    /// Drop a stage's cached scene, so the next populate reads the stage again
+    ///
+    /// Edits that don't bump the stage revision, such as attribute values,
+    /// are only picked up after this.
+    pub fn invalidate(&mut self, stage_id: &str) {
+        self.scenes.remove(stage_id);
+    }
+}
+
+/// Scene delegate shared by every renderer in the plugin
+static SCENE_DELEGATE: Lazy<Mutex<USDSceneDelegate>> = Lazy::new(|| Mutex::new(USDSceneDelegate::default()));
+
+/// Run a closure with the shared scene delegate
+pub fn with_scene_delegate<R>(f: impl FnOnce(&mut USDSceneDelegate) -> R) -> R {
+    f(&mut SCENE_DELEGATE.lock().unwrap())
+}
+
+/// Hand every item of an extracted scene to a sink
+fn replay(scene: &USDScene, sink: &mut dyn SceneSink) {
+    sink.begin_scene(&scene.stage_id, scene.time_code);
+    for geometry in &scene.geometries {
+        match scene.prototype_geometry.contains(&geometry.prim_path) {
+            true => sink.add_prototype_geometry(geometry.clone()),
+            false => sink.add_geometry(geometry.clone()),
+        }
+    }
+    for batch in &scene.instance_batches {
+        sink.add_instance_batch(batch.clone());
+    }
+    for material in scene.materials.values() {
+        sink.add_material(material.clone());
+    }
+    for (prim_path, bindings) in &scene.material_bindings {
+        sink.add_material_bindings(prim_path, bindings.clone());
+    }
+    for light in &scene.lights {
+        sink.add_light(light.clone());
+    }
+    if let Some(environment) = scene.environment {
+        sink.add_environment(environment);
+    }
+    for camera in &scene.cameras {
+        sink.add_camera(camera.clone());
+    }
+    sink.end_scene();
+}
+
+/// Reads one stage into a scene
+struct Extractor {
+    #[cfg_attr(not(feature = "usd"), allow(dead_code))]
+    settings: ExtractionSettings,
+    scene: USDScene,
+}
+
+impl Extractor {
+    /// Extract a stage, falling back to a stand-in scene when it can't be read or has no meshes
+    fn extract(stage_id: &str, settings: &ExtractionSettings) -> USDScene {
+        let mut extractor = Extractor {
+            settings: settings.clone(),
+            scene: USDScene {
+                stage_id: stage_id.to_string(),
+                time_code: settings.time_code,
+                ..USDScene::default()
+            },
+        };
+        
+        #[cfg(feature = "usd")]
+        {
+            if extractor.extract_stage_data(stage_id).is_err() || extractor.scene.geometries.is_empty() {
+                extractor.create_mock_scene();
+            }
+        }
+        
+        #[cfg(not(feature = "usd"))]
+        extractor.create_mock_scene();
+        
+        extractor.resolve_materials();
+        extractor.scene
+    }
+    
+    #[cfg(feature = "usd")]
+    fn extract_stage_data(&mut self, stage_id: &str) -> Result<(), String> {
+        with_usd_engine(|engine| {
+            // Get stage reference (this would need to be added to USDEngine)
+            if let Some(stage) = engine.get_stage(stage_id) {
+                let result = profiling::with_gil("extract_stage_data", |py| -> Result<(), String> {
+                    let stage_obj = engine.open_python_stage(py, stage)?;
+                    let usd_geom = py.import("pxr.UsdGeom").map_err(|e| format!("Failed to import UsdGeom: {}", e))?;
+                    let usd_lux = py.import("pxr.UsdLux").map_err(|e| format!("Failed to import UsdLux: {}", e))?;
+                    let usd_shade = py.import("pxr.UsdShade").map_err(|e| format!("Failed to import UsdShade: {}", e))?;
+                    
+                    // TODO: Get actual stage object from engine
+                    // For now, this is a framework for USD data extraction
+                    
+                    // Extract geometry prims
+                    self.extract_geometry_prims(py, &usd_geom, &stage_obj)?;
+                    
+                    // Extract light prims  
+                    self.extract_light_prims(py, &usd_lux, &stage_obj)?;
+                    
+                    // Extract material prims
+                    self.extract_material_prims(py, &usd_shade, &stage_obj)?;
+                    
+                    // Extract camera prims
+                    self.extract_camera_prims(py, &usd_geom, &stage_obj)?;
+                    
+                    Ok(())
+                });
+                
+                if let Err(e) = result {
+                    eprintln!("Error extracting USD stage data: {}", e);
+                }
+            }
+        });
+        
+        Ok(())
+    }
+    
+    #[cfg(feature = "usd")]
+    fn extract_geometry_prims(&mut self, py: Python, usd_geom: &Bound<'_, PyAny>, stage: &Bound<'_, PyAny>) -> Result<(), String> {
+        let err = |e: PyErr| format!("Failed to extract mesh: {}", e);
+        let usd = py.import("pxr.Usd").map_err(|e| format!("Failed to import USD: {}", e))?;
+        let time = usd.getattr("TimeCode").and_then(|t| t.call1((self.scene.time_code,))).map_err(err)?;
+        let mesh_class = usd_geom.getattr("Mesh").map_err(err)?;
+        
+        // Skinned meshes are deformed on the CPU before triangulation
+        let skinned = self.extract_skinned_points(py, usd_geom, stage, &time).unwrap_or_else(|e| {
+            eprintln!("Skipping UsdSkel deformation: {}", e);
+            HashMap::new()
+        });
+        
+        for prim in stage.call_method0("Traverse").map_err(err)?.try_iter().map_err(err)? {
+            if let Some(geometry) = self.extract_mesh(&mesh_class, &prim.map_err(err)?, &time, &skinned)? {
+                self.scene.geometries.push(geometry);
+            }
+        }
+        
+        if let Err(e) = self.extract_point_instancers(usd_geom, stage, &time) {
+            eprintln!("Skipping point instancers: {}", e);
+        }
+        
+        if let Err(e) = self.extract_scenegraph_instances(py, usd_geom, stage, &time) {
+            eprintln!("Skipping instanceable prims: {}", e);
+        }
+        
+        Ok(())
+    }
+    
+    /// Build geometry for one UsdGeomMesh prim, or None if it is not a visible mesh
+    #[cfg(feature = "usd")]
+    fn extract_mesh(
+        &self,
+        mesh_class: &Bound<'_, PyAny>,
+        prim: &Bound<'_, PyAny>,
+        time: &Bound<'_, PyAny>,
+        skinned: &HashMap<String, (Vec<Vec3>, Mat4)>,
+    ) -> Result<Option<USDGeometry>, String> {
+        let err = |e: PyErr| format!("Failed to extract mesh: {}", e);
+        if !prim.call_method1("IsA", (mesh_class,)).and_then(|r| r.extract::<bool>()).map_err(err)? {
+            return Ok(None);
+        }
+        
+        let prim_path: String = prim.call_method0("GetPath").and_then(|p| p.str()).map_err(err)?.to_string();
+        let mesh = mesh_class.call1((prim,)).map_err(err)?;
+        
+        // Animated visibility is resolved at the current time code
+        let visibility: String = mesh.call_method1("ComputeVisibility", (time,))
+            .and_then(|v| v.extract())
+            .unwrap_or_else(|_| "inherited".to_string());
+        if visibility == "invisible" {
+            return Ok(None);
+        }
+        let read = |getter: &str| mesh.call_method0(getter).and_then(|attr| attr.call_method1("Get", (time,)));
+        
+        let mut points = read("GetPointsAttr").and_then(|value| read_points(&value)).map_err(err)?;
+        let counts = read("GetFaceVertexCountsAttr").and_then(|value| read_ints(&value)).map_err(err)?;
+        let indices = read("GetFaceVertexIndicesAttr").and_then(|value| read_ints(&value)).map_err(err)?;
+        let holes = read("GetHoleIndicesAttr").and_then(|value| read_ints(&value)).unwrap_or_default();
+        let scheme: String = read("GetSubdivisionSchemeAttr").and_then(|v| v.extract())
+            .unwrap_or_else(|_| "catmullClark".to_string());
+        let matrix: Vec<Vec<f64>> = mesh.call_method1("ComputeLocalToWorldTransform", (time,))
+            .and_then(|m| m.extract())
+            .map_err(err)?;
+        
+        let mut matrix = usd_matrix_to_mat4(&matrix);
+        let display_primvar = self.settings.display_primvar.as_deref();
+        let mut primvars = read_mesh_primvars(prim, time, display_primvar).unwrap_or_else(|e| {
+            eprintln!("Skipping primvars of '{}': {}", prim_path, e);
+            MeshPrimvars::default()
+        });
+        if let Some((skinned_points, skel_transform)) = skinned.get(&prim_path) {
+            if skinned_points.len() == points.len() {
+                points = skinned_points.clone();
+                matrix = *skel_transform;
+                // Authored normals follow the bind pose, so skinned meshes recompute theirs
+                primvars.normals = None;
+            }
+        }
+        let available_primvars = list_primvars(prim).unwrap_or_else(|e| {
+            eprintln!("Failed to list primvars of '{}': {}", prim_path, e);
+            Vec::new()
+        });
+        
+        let levels = if is_catmull_clark(&scheme) { self.settings.subdivision_level } else { 0 };
+        let geometry = if levels > 0 {
+            subdivide(&points, &counts, &indices, &holes, levels).and_then(|refined| {
+                let primvars = primvars.subdivide(points.len(), &counts, &indices, &holes, levels).unwrap_or_else(|e| {
+                    eprintln!("Dropping primvars of '{}': {}", prim_path, e);
+                    MeshPrimvars::default()
+                });
+                let mut geometry = build_mesh_geometry(&prim_path, &refined.points, &refined.face_vertex_counts,
+                                                            &refined.face_vertex_indices, &refined.hole_indices, matrix, &primvars)?;
+                // Picks select the authored faces, not their refined children
+                let origins = refined_face_origins(&counts, levels);
+                for face in &mut geometry.face_ids {
+                    *face = origins[*face as usize];
+                }
+                Ok(geometry)
+            })
+        } else {
+            build_mesh_geometry(&prim_path, &points, &counts, &indices, &holes, matrix, &primvars)
+        };
+        match geometry {
+            Ok(geometry) => Ok(Some(USDGeometry { primvars: available_primvars, ..geometry })),
+            Err(e) => {
+                eprintln!("Skipping mesh '{}': {}", prim_path, e);
+                Ok(None)
+            }
+        }
+    }
+    
+    /// Extract scenegraph instancing prototypes once and batch every instanceable prim over them
+    ///
+    /// Stage traversal does not descend into instances, so prototype meshes are
+    /// read from `Stage.GetPrototypes()` with transforms relative to the
+    /// prototype root and drawn through instance batches.
+    #[cfg(feature = "usd")]
+    fn extract_scenegraph_instances(&mut self, py: Python, usd_geom: &Bound<'_, PyAny>, stage: &Bound<'_, PyAny>, time: &Bound<'_, PyAny>) -> Result<(), String> {
+        let err = |e: PyErr| format!("Failed to extract instances: {}", e);
+        let usd = py.import("pxr.Usd").map_err(|e| format!("Failed to import USD: {}", e))?;
+        let mesh_class = usd_geom.getattr("Mesh").map_err(err)?;
+        let xformable_class = usd_geom.getattr("Xformable").map_err(err)?;
+        let no_skinning = HashMap::new();
+        
+        // Prototype meshes, keyed by prototype root path
+        let mut prototype_meshes: HashMap<String, Vec<(String, Mat4)>> = HashMap::new();
+        for prototype in stage.call_method0("GetPrototypes").map_err(err)?.try_iter().map_err(err)? {
+            let prototype = prototype.map_err(err)?;
+            let prototype_path: String = prototype.call_method0("GetPath").and_then(|p| p.str()).map_err(err)?.to_string();
+            let range = usd.getattr("PrimRange").and_then(|range| range.call1((prototype,))).map_err(err)?;
+            
+            for prim in range.try_iter().map_err(err)? {
+                if let Some(geometry) = self.extract_mesh(&mesh_class, &prim.map_err(err)?, time, &no_skinning)? {
+                    prototype_meshes.entry(prototype_path.clone())
+                        .or_default()
+                        .push((geometry.prim_path.clone(), geometry.transform));
+                    self.scene.prototype_geometry.insert(geometry.prim_path.clone());
+                    self.scene.geometries.push(geometry);
+                }
+            }
+        }
+        if prototype_meshes.is_empty() {
+            return Ok(());
+        }
+        
+        // Instance world transforms grouped by prototype
+        let mut instances: HashMap<String, Vec<Mat4>> = HashMap::new();
+        for prim in stage.call_method0("Traverse").map_err(err)?.try_iter().map_err(err)? {
+            let prim = prim.map_err(err)?;
+            if !prim.call_method0("IsInstance").and_then(|r| r.extract::<bool>()).map_err(err)? {
+                continue;
+            }
+            let prototype_path: String = prim.call_method0("GetPrototype")
+                .and_then(|prototype| prototype.call_method0("GetPath"))
+                .and_then(|p| p.str())
+                .map_err(err)?
+                .to_string();
+            let world: Vec<Vec<f64>> = xformable_class.call1((prim,))
+                .and_then(|xformable| xformable.call_method1("ComputeLocalToWorldTransform", (time,)))
+                .and_then(|matrix| matrix.extract())
+                .map_err(err)?;
+            instances.entry(prototype_path).or_default().push(usd_matrix_to_mat4(&world));
+        }
+        
+        for (prototype_path, meshes) in prototype_meshes {
+            let Some(instance_transforms) = instances.get(&prototype_path) else {
+                continue;
+            };
+            for (geometry_path, relative) in meshes {
+                self.scene.instance_batches.push(InstanceBatch {
+                    geometry_path,
+                    transforms: instance_transforms.iter().map(|instance| *instance * relative).collect(),
+                });
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Expand UsdGeomPointInstancers into instance batches over already extracted prototype meshes
+    #[cfg(feature = "usd")]
+    fn extract_point_instancers(&mut self, usd_geom: &Bound<'_, PyAny>, stage: &Bound<'_, PyAny>, time: &Bound<'_, PyAny>) -> Result<(), String> {
+        let err = |e: PyErr| format!("Failed to extract point instancer: {}", e);
+        let instancer_class = usd_geom.getattr("PointInstancer").map_err(err)?;
+        let xformable_class = usd_geom.getattr("Xformable").map_err(err)?;
+        let geometries: Vec<(String, Mat4)> = self.scene.geometries.iter()
+            .map(|geometry| (geometry.prim_path.clone(), geometry.transform))
+            .collect();
+        
+        for prim in stage.call_method0("Traverse").map_err(err)?.try_iter().map_err(err)? {
+            let prim = prim.map_err(err)?;
+            if !prim.call_method1("IsA", (&instancer_class,)).and_then(|r| r.extract::<bool>()).map_err(err)? {
+                continue;
+            }
+            let prim_path: String = prim.call_method0("GetPath").and_then(|p| p.str()).map_err(err)?.to_string();
+            let instancer = instancer_class.call1((prim,)).map_err(err)?;
+            
+            let prototype_paths: Vec<String> = instancer.call_method0("GetPrototypesRel")
+                .and_then(|rel| rel.call_method0("GetTargets"))
+                .and_then(|targets| targets.try_iter()?.map(|target| target.and_then(|t| t.str()).map(|t| t.to_string())).collect())
+                .map_err(err)?;
+            let mut prototypes = Vec::with_capacity(prototype_paths.len());
+            for prototype_path in prototype_paths {
+                let world: Vec<Vec<f64>> = stage.call_method1("GetPrimAtPath", (prototype_path.as_str(),))
+                    .and_then(|prototype| xformable_class.call1((prototype,)))
+                    .and_then(|xformable| xformable.call_method1("ComputeLocalToWorldTransform", (time,)))
+                    .and_then(|matrix| matrix.extract())
+                    .map_err(err)?;
+                prototypes.push((prototype_path, usd_matrix_to_mat4(&world)));
+            }
+            
+            let proto_indices: Vec<i32> = instancer.call_method0("GetProtoIndicesAttr")
+                .and_then(|attr| attr.call_method1("Get", (time,)))
+                .and_then(|value| value.extract())
+                .map_err(err)?;
+            // Positions, orientations, scales, velocities and invisibleIds resolved by USD
+            let instance_transforms: Vec<Vec<Vec<f64>>> = instancer.call_method1("ComputeInstanceTransformsAtTime", (time, time))
+                .and_then(|value| value.extract())
+                .map_err(err)?;
+            // Hidden (invisibleIds) and inactive (inactiveIds) instances
+            let mask: Vec<bool> = instancer.call_method1("ComputeMaskAtTime", (time,))
+                .and_then(|mask| mask.extract())
+                .map_err(err)?;
+            let instancer_world: Vec<Vec<f64>> = instancer.call_method1("ComputeLocalToWorldTransform", (time,))
+                .and_then(|matrix| matrix.extract())
+                .map_err(err)?;
+            
+            let instance_transforms: Vec<Mat4> = instance_transforms.iter().map(|m| usd_matrix_to_mat4(m)).collect();
+            let batches = build_instance_batches(usd_matrix_to_mat4(&instancer_world), &prototypes, &proto_indices,
+                                                 &mask, &instance_transforms, &geometries)
+                .map_err(|e| format!("Point instancer '{}': {}", prim_path, e))?;
+            
+            for (prototype_path, _) in &prototypes {
+                for (geometry_path, _) in geometries.iter().filter(|(path, _)| is_under(path, prototype_path)) {
+                    self.scene.prototype_geometry.insert(geometry_path.clone());
+                }
+            }
+            self.scene.instance_batches.extend(batches);
+        }
+        
+        Ok(())
+    }
+    
+    /// Read UsdSkel bindings and skin every bound mesh at the given time
+    ///
+    /// Returns skinned points (in skeleton space) and the skeleton's world
+    /// transform, keyed by mesh prim path.
+    #[cfg(feature = "usd")]
+    fn extract_skinned_points(&self, py: Python, usd_geom: &Bound<'_, PyAny>, stage: &Bound<'_, PyAny>, time: &Bound<'_, PyAny>) -> Result<HashMap<String, (Vec<Vec3>, Mat4)>, String> {
+        let err = |e: PyErr| format!("Failed to read UsdSkel data: {}", e);
+        let usd = py.import("pxr.Usd").map_err(|e| format!("Failed to import USD: {}", e))?;
+        let usd_skel = py.import("pxr.UsdSkel").map_err(|e| format!("Failed to import UsdSkel: {}", e))?;
+        let skel_root_class = usd_skel.getattr("Root").map_err(err)?;
+        let predicate = usd.call_method0("TraverseInstanceProxies").map_err(err)?;
+        let cache = usd_skel.getattr("Cache").and_then(|c| c.call0()).map_err(err)?;
+        let mut skinned = HashMap::new();
+        
+        for prim in stage.call_method0("Traverse").map_err(err)?.try_iter().map_err(err)? {
+            let prim = prim.map_err(err)?;
+            if !prim.call_method1("IsA", (&skel_root_class,)).and_then(|r| r.extract::<bool>()).map_err(err)? {
+                continue;
+            }
+            let skel_root = skel_root_class.call1((prim,)).map_err(err)?;
+            cache.call_method1("Populate", (&skel_root, &predicate)).map_err(err)?;
+            
+            for binding in cache.call_method1("ComputeSkelBindings", (&skel_root, &predicate)).map_err(err)?.try_iter().map_err(err)? {
+                let binding = binding.map_err(err)?;
+                let skeleton = binding.call_method0("GetSkeleton").map_err(err)?;
+                let skel_query = cache.call_method1("GetSkelQuery", (&skeleton,)).map_err(err)?;
+                
+                // Topology, bind pose and animated local transforms
+                let joints: Vec<String> = skel_query.call_method0("GetJointOrder")
+                    .and_then(|order| order.try_iter()?.map(|joint| joint.and_then(|j| j.str()).map(|j| j.to_string())).collect())
+                    .map_err(err)?;
+                let bind: Vec<Vec<Vec<f64>>> = skeleton.call_method0("GetBindTransformsAttr")
+                    .and_then(|attr| attr.call_method0("Get"))
+                    .and_then(|value| value.extract())
+                    .map_err(err)?;
+                let local: Vec<Vec<Vec<f64>>> = skel_query.call_method1("ComputeJointLocalTransforms", (time,))
+                    .and_then(|value| value.extract())
+                    .map_err(err)?;
+                let skel_world: Vec<Vec<f64>> = skeleton.call_method1("ComputeLocalToWorldTransform", (time,))
+                    .and_then(|value| value.extract())
+                    .map_err(err)?;
+                
+                let skeleton_data = Skeleton::new(joints, bind.iter().map(|m| usd_matrix_to_mat4(m)).collect())?;
+                let local: Vec<Mat4> = local.iter().map(|m| usd_matrix_to_mat4(m)).collect();
+                let skinning_transforms = skeleton_data.skinning_transforms(&local)?;
+                let skel_transform = usd_matrix_to_mat4(&skel_world);
+                
+                for target in binding.call_method0("GetSkinningTargets").map_err(err)?.try_iter().map_err(err)? {
+                    let target = target.map_err(err)?;
+                    let mesh_prim = target.call_method0("GetPrim").map_err(err)?;
+                    let prim_path: String = mesh_prim.call_method0("GetPath").and_then(|p| p.str()).map_err(err)?.to_string();
+                    
+                    let (indices, weights): (Vec<i32>, Vec<f32>) = target.call_method0("ComputeJointInfluences")
+                        .and_then(|value| value.extract())
+                        .map_err(err)?;
+                    let influences = JointInfluences {
+                        indices,
+                        weights,
+                        influences_per_point: target.call_method0("GetNumInfluencesPerComponent").and_then(|n| n.extract()).map_err(err)?,
+                        constant: target.call_method0("IsRigidlyDeformed").and_then(|r| r.extract()).map_err(err)?,
+                    };
+                    let geom_bind: Vec<Vec<f64>> = target.call_method0("GetGeomBindTransform")
+                        .and_then(|value| value.extract())
+                        .map_err(err)?;
+                    
+                    // Prims may author their own skel:joints order
+                    let prim_joints: Option<Vec<String>> = target.call_method0("GetJointOrder")
+                        .and_then(|order| order.extract())
+                        .ok()
+                        .filter(|order: &Vec<String>| !order.is_empty());
+                    let mapping = match &prim_joints {
+                        Some(order) => Some(skeleton_data.joint_mapping(order)?),
+                        None => None,
+                    };
+                    
+                    let points = usd_geom.getattr("Mesh")
+                        .and_then(|mesh_class| mesh_class.call1((mesh_prim,)))
+                        .and_then(|mesh| mesh.call_method0("GetPointsAttr"))
+                        .and_then(|attr| attr.call_method1("Get", (time,)))
+                        .and_then(|value| read_points(&value))
+                        .map_err(err)?;
+                    
+                    let deformed = skin_points(&points, &influences, &skinning_transforms, usd_matrix_to_mat4(&geom_bind), mapping.as_deref())?;
+                    skinned.insert(prim_path, (deformed, skel_transform));
+                }
+            }
+        }
+        
+        Ok(skinned)
+    }
+    
+    /// Extract UsdLux lights with their light and shadow link collections
+    #[cfg(feature = "usd")]
+    fn extract_light_prims(&mut self, py: Python, usd_lux: &Bound<'_, PyAny>, stage: &Bound<'_, PyAny>) -> Result<(), String> {
+        let err = |e: PyErr| format!("Failed to extract lights: {}", e);
+        let usd = py.import("pxr.Usd").map_err(|e| format!("Failed to import USD: {}", e))?;
+        let time = usd.getattr("TimeCode").and_then(|t| t.call1((self.scene.time_code,))).map_err(err)?;
+        let light_api = usd_lux.getattr("LightAPI").map_err(err)?;
+        let xformable = py.import("pxr.UsdGeom").and_then(|usd_geom| usd_geom.getattr("Xformable")).map_err(err)?;
+        
+        for prim in stage.call_method0("Traverse").map_err(err)?.try_iter().map_err(err)? {
+            let prim = prim.map_err(err)?;
+            if !prim.call_method1("HasAPI", (&light_api,)).and_then(|r| r.extract::<bool>()).map_err(err)? {
+                continue;
+            }
+            let light = light_api.call1((&prim,)).map_err(err)?;
+            let type_name: String = prim.call_method0("GetTypeName").and_then(|t| t.extract()).map_err(err)?;
+            // Unauthored inputs keep their schema defaults; flags and scalars read as floats
+            let mut lux = LuxParams::default();
+            for name in LUX_INPUTS {
+                let Ok(value) = prim.call_method1("GetAttribute", (format!("inputs:{}", name),))
+                    .and_then(|attr| attr.call_method1("Get", (&time,))) else {
+                    continue;
+                };
+                if let Some(values) = value.extract::<f64>().map(|value| vec![value]).or_else(|_| value.extract::<Vec<f64>>()).ok() {
+                    lux.set_input(name, &values);
+                }
+            }
+            
+            // Domes light the scene as ambient rather than from a direction
+            if type_name == "DomeLight" {
+                let texture = read_dome_texture(usd_lux, &prim, &time).map_err(err)?;
+                let texture = texture.filter(|file| !file.is_empty()).and_then(|file| match load_latlong(&file) {
+                    Ok(environment) => Some(environment),
+                    Err(e) => {
+                        eprintln!("⚠ {}", e);
+                        None
+                    }
+                });
+                let dome = Environment::dome(lux.tint() * lux.viewport_intensity("dome"), texture);
+                let scene = &mut self.scene;
+                scene.environment = Some(scene.environment.map_or(dome, |environment| environment + dome));
+                continue;
+            }
+            let matrix: Vec<Vec<f64>> = xformable.call1((&prim,))
+                .and_then(|xform| xform.call_method1("ComputeLocalToWorldTransform", (&time,)))
+                .and_then(|m| m.extract())
+                .map_err(err)?;
+            
+            self.scene.lights.push(USDLight {
+                prim_path: prim.call_method0("GetPath").and_then(|p| p.str()).map_err(err)?.to_string(),
+                light_type: type_name.trim_end_matches("Light").to_ascii_lowercase(),
+                transform: usd_matrix_to_mat4(&matrix),
+                lux,
+                light_link: light.call_method0("GetLightLinkCollectionAPI").and_then(|collection| read_collection(&collection, true)).map_err(err)?,
+                shadow_link: light.call_method0("GetShadowLinkCollectionAPI").and_then(|collection| read_collection(&collection, true)).map_err(err)?,
+            });
+        }
+        
+        // Unlit stages get a headlight-style default
+        if self.scene.lights.is_empty() && self.scene.environment.is_none() {
+            self.scene.lights.push(USDLight {
+                prim_path: DEFAULT_LIGHT.to_string(),
+                light_type: "distant".to_string(),
+                transform: Mat4::IDENTITY,
+                lux: LuxParams::default(),
+                light_link: LinkCollection::default(),
+                shadow_link: LinkCollection::default(),
+            });
+        }
+        
+        Ok(())
+    }
+    
+    /// Extract UsdPreviewSurface materials and the material bindings authored on each prim
+    #[cfg(feature = "usd")]
+    fn extract_material_prims(&mut self, py: Python, usd_shade: &Bound<'_, PyAny>, stage: &Bound<'_, PyAny>) -> Result<(), String> {
+        let err = |e: PyErr| format!("Failed to extract materials: {}", e);
+        let default_material = USDMaterial {
+            prim_path: DEFAULT_MATERIAL.to_string(),
+            diffuse_color: Vec3::new(0.7, 0.7, 0.8),
+            metallic: 0.0,
+            roughness: 0.5,
+            opacity: 1.0,
+            emission_color: Vec3::ZERO,
+            ior: 1.5,
+            normal: Vec3::Z,
+            connections: HashMap::new(),
+        };
+        self.scene.materials.insert(DEFAULT_MATERIAL.to_string(), default_material);
+        
+        let material_class = usd_shade.getattr("Material").map_err(err)?;
+        let binding_api = usd_shade.getattr("MaterialBindingAPI").map_err(err)?;
+        let collection_api = py.import("pxr.Usd").and_then(|usd| usd.getattr("CollectionAPI")).map_err(err)?;
+        let strength = |rel: &Bound<'_, PyAny>| -> PyResult<BindingStrength> {
+            let token: String = binding_api.call_method1("GetMaterialBindingStrength", (rel,))?.extract()?;
+            Ok(BindingStrength::from_token(&token))
+        };
+        
+        for prim in stage.call_method0("Traverse").map_err(err)?.try_iter().map_err(err)? {
+            let prim = prim.map_err(err)?;
+            let prim_path = prim.call_method0("GetPath").and_then(|p| p.str()).map_err(err)?.to_string();
+            if prim.call_method1("IsA", (&material_class,)).and_then(|r| r.extract::<bool>()).map_err(err)? {
+                let material = material_class.call1((&prim,)).map_err(err)?;
+                if let Some(material) = read_preview_surface(usd_shade, &prim_path, &material).map_err(err)? {
+                    self.scene.materials.insert(prim_path, material);
+                }
+                continue;
+            }
+            
+            let api = binding_api.call1((prim,)).map_err(err)?;
+            let mut bindings = Vec::new();
+            for purpose in ["", PREVIEW_PURPOSE] {
+                for binding in api.call_method1("GetCollectionBindings", (purpose,)).and_then(|b| b.try_iter()).map_err(err)? {
+                    let binding = binding.map_err(err)?;
+                    let collection = binding.call_method0("GetCollectionPath")
+                        .and_then(|path| collection_api.call_method1("GetCollection", (stage, path)))
+                        .and_then(|collection| read_collection(&collection, false))
+                        .map_err(err)?;
+                    bindings.push(MaterialBinding {
+                        material: binding.call_method0("GetMaterialPath").and_then(|p| p.str()).map_err(err)?.to_string(),
+                        purpose: purpose.to_string(),
+                        strength: binding.call_method0("GetBindingRel").and_then(|rel| strength(&rel)).map_err(err)?,
+                        collection: Some(collection),
+                    });
+                }
+                let material = api.call_method1("GetDirectBinding", (purpose,))
+                    .and_then(|binding| binding.call_method0("GetMaterialPath"))
+                    .and_then(|p| p.str())
+                    .map_err(err)?
+                    .to_string();
+                if !material.is_empty() {
+                    bindings.push(MaterialBinding {
+                        material,
+                        purpose: purpose.to_string(),
+                        strength: api.call_method1("GetDirectBindingRel", (purpose,)).and_then(|rel| strength(&rel)).map_err(err)?,
+                        collection: None,
+                    });
+                }
+            }
+            if !bindings.is_empty() {
+                self.scene.material_bindings.insert(prim_path, bindings);
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Point every geometry at its bound preview material, falling back to the default material
+    fn resolve_materials(&mut self) {
+        let scene = &mut self.scene;
+        for geometry in &mut scene.geometries {
+            let bound = resolve_binding(&scene.material_bindings, &geometry.prim_path, PREVIEW_PURPOSE)
+                .filter(|material| scene.materials.contains_key(*material));
+            geometry.material_path = Some(bound.unwrap_or(DEFAULT_MATERIAL).to_string());
+        }
+    }
+    
+    #[cfg(feature = "usd")]
+    fn extract_camera_prims(&mut self, py: Python, usd_geom: &Bound<'_, PyAny>, stage: &Bound<'_, PyAny>) -> Result<(), String> {
+        let err = |e: PyErr| format!("Failed to extract cameras: {}", e);
+        let usd = py.import("pxr.Usd").map_err(|e| format!("Failed to import USD: {}", e))?;
+        let time = usd.getattr("TimeCode").and_then(|t| t.call1((self.scene.time_code,))).map_err(err)?;
+        let camera_schema = usd_geom.getattr("Camera").map_err(err)?;
+        let defaults = ProjectionCamera::default();
+        
+        for prim in stage.call_method0("Traverse").map_err(err)?.try_iter().map_err(err)? {
+            let prim = prim.map_err(err)?;
+            if !prim.call_method1("IsA", (&camera_schema,)).and_then(|r| r.extract::<bool>()).map_err(err)? {
+                continue;
+            }
+            let camera = camera_schema.call1((&prim,)).map_err(err)?;
+            let read = |getter: &str, default: f32| camera.call_method0(getter)
+                .and_then(|attr| attr.call_method1("Get", (&time,)))
+                .and_then(|value| value.extract::<f32>())
+                .unwrap_or(default);
+            let clipping: [f32; 2] = camera.call_method0("GetClippingRangeAttr")
+                .and_then(|attr| attr.call_method1("Get", (&time,)))
+                .and_then(|value| value.extract())
+                .unwrap_or([defaults.near, defaults.far]);
+            let matrix: Vec<Vec<f64>> = camera.call_method1("ComputeLocalToWorldTransform", (&time,))
+                .and_then(|m| m.extract())
+                .map_err(err)?;
+            
+            self.scene.cameras.push(USDCamera {
+                prim_path: prim.call_method0("GetPath").and_then(|p| p.str()).map_err(err)?.to_string(),
+                transform: usd_matrix_to_mat4(&matrix),
+                focal_length: read("GetFocalLengthAttr", defaults.focal_length),
+                horizontal_aperture: read("GetHorizontalApertureAttr", defaults.horizontal_aperture),
+                vertical_aperture: read("GetVerticalApertureAttr", defaults.vertical_aperture),
+                clipping_range: (clipping[0], clipping[1]),
+                f_stop: read("GetFStopAttr", 0.0),
+                focus_distance: read("GetFocusDistanceAttr", 0.0),
+                exposure_scale: exposure_scale(
+                    read("GetExposureAttr", 0.0),
+                    read("GetExposureFStopAttr", 1.0),
+                    read("GetExposureTimeAttr", 1.0),
+                    read("GetExposureIsoAttr", 100.0),
+                    read("GetExposureResponsivityAttr", 1.0),
+                ),
+            });
+        }
+        Ok(())
+    }
+    
+    /// Stand-in scene for stages that can't be read, and for builds without USD
+    fn create_mock_scene(&mut self) {
+        // Add some test geometry
+        let cube = self.create_cube_geometry("/World/Cube", Mat4::from_translation(Vec3::new(-2.0, 0.0, 0.0)));
+        let sphere = self.create_sphere_geometry("/World/Sphere", Mat4::from_translation(Vec3::new(2.0, 0.0, 0.0)));
+        let plane = self.create_plane_geometry("/World/Plane", Mat4::from_translation(Vec3::new(0.0, -1.0, 0.0)));
+        
+        self.scene.geometries.push(cube);
+        self.scene.geometries.push(sphere);
+        self.scene.geometries.push(plane);
+        
+        // Add a default light
+        let light = USDLight {
+            prim_path: DEFAULT_LIGHT.to_string(),
+            light_type: "distant".to_string(),
+            transform: Mat4::from_rotation_x(-45_f32.to_radians()),
+            lux: LuxParams { color: Vec3::new(1.0, 1.0, 0.9), ..LuxParams::default() },
+            light_link: LinkCollection::default(),
+            shadow_link: LinkCollection::default(),
+        };
+        self.scene.lights.push(light);
+        
+        // Add a default material
+        let material = USDMaterial {
+            prim_path: DEFAULT_MATERIAL.to_string(),
+            diffuse_color: Vec3::new(0.6, 0.7, 0.8),
+            metallic: 0.1,
+            roughness: 0.4,
+            opacity: 1.0,
+            emission_color: Vec3::ZERO,
+            ior: 1.5,
+            normal: Vec3::Z,
+            connections: HashMap::new(),
+        };
+        self.scene.materials.insert(DEFAULT_MATERIAL.to_string(), material);
+    }
+    
+    fn create_cube_geometry(&self, prim_path: &str, transform: Mat4) -> USDGeometry {
+        // Create cube vertices
+        let vertices = vec![
+            // Front face
+            Vertex3D { position: [-1.0, -1.0,  1.0], normal: [ 0.0,  0.0,  1.0], uv: [0.0, 0.0] },
+            Vertex3D { position: [ 1.0, -1.0,  1.0], normal: [ 0.0,  0.0,  1.0], uv: [1.0, 0.0] },
+            Vertex3D { position: [ 1.0,  1.0,  1.0], normal: [ 0.0,  0.0,  1.0], uv: [1.0, 1.0] },
+            Vertex3D { position: [-1.0,  1.0,  1.0], normal: [ 0.0,  0.0,  1.0], uv: [0.0, 1.0] },
+            
+            // Back face
+            Vertex3D { position: [-1.0, -1.0, -1.0], normal: [ 0.0,  0.0, -1.0], uv: [1.0, 0.0] },
+            Vertex3D { position: [-1.0,  1.0, -1.0], normal: [ 0.0,  0.0, -1.0], uv: [1.0, 1.0] },
+            Vertex3D { position: [ 1.0,  1.0, -1.0], normal: [ 0.0,  0.0, -1.0], uv: [0.0, 1.0] },
+            Vertex3D { position: [ 1.0, -1.0, -1.0], normal: [ 0.0,  0.0, -1.0], uv: [0.0, 0.0] },
+            
+            // Top face
+            Vertex3D { position: [-1.0,  1.0, -1.0], normal: [ 0.0,  1.0,  0.0], uv: [0.0, 1.0] },
+            Vertex3D { position: [-1.0,  1.0,  1.0], normal: [ 0.0,  1.0,  0.0], uv: [0.0, 0.0] },
+            Vertex3D { position: [ 1.0,  1.0,  1.0], normal: [ 0.0,  1.0,  0.0], uv: [1.0, 0.0] },
+            Vertex3D { position: [ 1.0,  1.0, -1.0], normal: [ 0.0,  1.0,  0.0], uv: [1.0, 1.0] },
+            
+            // Bottom face
+            Vertex3D { position: [-1.0, -1.0, -1.0], normal: [ 0.0, -1.0,  0.0], uv: [1.0, 1.0] },
+            Vertex3D { position: [ 1.0, -1.0, -1.0], normal: [ 0.0, -1.0,  0.0], uv: [0.0, 1.0] },
+            Vertex3D { position: [ 1.0, -1.0,  1.0], normal: [ 0.0, -1.0,  0.0], uv: [0.0, 0.0] },
+            Vertex3D { position: [-1.0, -1.0,  1.0], normal: [ 0.0, -1.0,  0.0], uv: [1.0, 0.0] },
+            
+            // Right face
+            Vertex3D { position: [ 1.0, -1.0, -1.0], normal: [ 1.0,  0.0,  0.0], uv: [1.0, 0.0] },
+            Vertex3D { position: [ 1.0,  1.0, -1.0], normal: [ 1.0,  0.0,  0.0], uv: [1.0, 1.0] },
+            Vertex3D { position: [ 1.0,  1.0,  1.0], normal: [ 1.0,  0.0,  0.0], uv: [0.0, 1.0] },
+            Vertex3D { position: [ 1.0, -1.0,  1.0], normal: [ 1.0,  0.0,  0.0], uv: [0.0, 0.0] },
+            
+            // Left face
+            Vertex3D { position: [-1.0, -1.0, -1.0], normal: [-1.0,  0.0,  0.0], uv: [0.0, 0.0] },
+            Vertex3D { position: [-1.0, -1.0,  1.0], normal: [-1.0,  0.0,  0.0], uv: [1.0, 0.0] },
+            Vertex3D { position: [-1.0,  1.0,  1.0], normal: [-1.0,  0.0,  0.0], uv: [1.0, 1.0] },
+            Vertex3D { position: [-1.0,  1.0, -1.0], normal: [-1.0,  0.0,  0.0], uv: [0.0, 1.0] },
+        ];
+        
+        let indices = vec![
+            0,  1,  2,   0,  2,  3,    // front
+            4,  5,  6,   4,  6,  7,    // back
+            8,  9,  10,  8,  10, 11,   // top
+            12, 13, 14,  12, 14, 15,   // bottom
+            16, 17, 18,  16, 18, 19,   // right
+            20, 21, 22,  20, 22, 23,   // left
+        ];
+        
+        USDGeometry {
+            prim_path: prim_path.to_string(),
+            prim_type: "Cube".to_string(),
+            vertices,
+            indices,
+            transform,
+            material_path: Some(DEFAULT_MATERIAL.to_string()),
+            visibility: true,
+            edge_indices: Vec::new(),
+            colors: Vec::new(),
+            tangents: Vec::new(),
+            face_ids: Vec::new(),
+            primvars: Vec::new(),
+        }
+    }
+    
+    fn create_sphere_geometry(&self, prim_path: &str, transform: Mat4) -> USDGeometry {
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        
+        let radius = 1.0;
+        let segments = 32;
+        let rings = 16;
+        
+        // Generate sphere vertices
+        for ring in 0..=rings {
+            let phi = std::f32::consts::PI * ring as f32 / rings as f32;
+            let y = phi.cos();
+            let ring_radius = phi.sin();
+            
+            for segment in 0..=segments {
+                let theta = 2.0 * std::f32::consts::PI * segment as f32 / segments as f32;
+                let x = ring_radius * theta.cos();
+                let z = ring_radius * theta.sin();
+                
+                vertices.push(Vertex3D {
+                    position: [x * radius, y * radius, z * radius],
+                    normal: [x, y, z],
+                    uv: [segment as f32 / segments as f32, ring as f32 / rings as f32],
+                });
+            }
+        }
+        
+        // Generate sphere indices
+        for ring in 0..rings {
+            for segment in 0..segments {
+                let current = ring * (segments + 1) + segment;
+                let next = current + segments + 1;
+                
+                indices.push(current);
+                indices.push(next);
+                indices.push(current + 1);
+                
+                indices.push(current + 1);
+                indices.push(next);
+                indices.push(next + 1);
+            }
+        }
+        
+        USDGeometry {
+            prim_path: prim_path.to_string(),
+            prim_type: "Sphere".to_string(),
+            vertices,
+            indices,
+            transform,
+            material_path: Some(DEFAULT_MATERIAL.to_string()),
+            visibility: true,
+            edge_indices: Vec::new(),
+            colors: Vec::new(),
+            tangents: Vec::new(),
+            face_ids: Vec::new(),
+            primvars: Vec::new(),
+        }
+    }
+    
+    fn create_plane_geometry(&self, prim_path: &str, transform: Mat4) -> USDGeometry {
+        let size = 5.0;
+        let vertices = vec![
+            Vertex3D { position: [-size, 0.0, -size], normal: [0.0, 1.0, 0.0], uv: [0.0, 0.0] },
+            Vertex3D { position: [ size, 0.0, -size], normal: [0.0, 1.0, 0.0], uv: [1.0, 0.0] },
+            Vertex3D { position: [ size, 0.0,  size], normal: [0.0, 1.0, 0.0], uv: [1.0, 1.0] },
+            Vertex3D { position: [-size, 0.0,  size], normal: [0.0, 1.0, 0.0], uv: [0.0, 1.0] },
+        ];
+        
+        let indices = vec![0, 1, 2, 0, 2, 3];
+        
+        USDGeometry {
+            prim_path: prim_path.to_string(),
+            prim_type: "Plane".to_string(),
+            vertices,
+            indices,
+            transform,
+            material_path: Some(DEFAULT_MATERIAL.to_string()),
+            visibility: true,
+            edge_indices: Vec::new(),
+            colors: Vec::new(),
+            tangents: Vec::new(),
+            face_ids: Vec::new(),
+            primvars: Vec::new(),
+        }
+    }
+}
+
+/// Build renderable geometry from UsdGeomMesh polygon topology
+pub fn build_mesh_geometry(
+    prim_path: &str,
+    points: &[Vec3],
+    face_vertex_counts: &[i32],
+    face_vertex_indices: &[i32],
+    hole_indices: &[i32],
+    transform: Mat4,
+    primvars: &MeshPrimvars,
+) -> Result<USDGeometry, String> {
+    let triangulated = triangulate(points, face_vertex_counts, face_vertex_indices, hole_indices)?;
+    
+    // Smooth normals accumulated from area-weighted triangle normals
+    let mut normals = vec![Vec3::ZERO; points.len()];
+    for triangle in triangulated.indices.chunks(3) {
+        let (a, b, c) = (triangle[0] as usize, triangle[1] as usize, triangle[2] as usize);
+        let face_normal = (points[b] - points[a]).cross(points[c] - points[a]);
+        normals[a] += face_normal;
+        normals[b] += face_normal;
+        normals[c] += face_normal;
+    }
+    
+    let vertices: Vec<Vertex3D> = points.iter().zip(&normals)
+        .map(|(position, normal)| Vertex3D {
+            position: position.to_array(),
+            normal: normal.normalize_or_zero().to_array(),
+            uv: [0.0, 0.0],
+        })
+        .collect();
+    
+    // Mismatched primvars only lose their values, not the mesh
+    let expand = |name: &str, values: Option<Result<Vec<Vec4>, String>>| {
+        values.transpose().unwrap_or_else(|e| {
+            eprintln!("Ignoring {} of '{}': {}", name, prim_path, e);
+            None
+        })
+    };
+    
+    // Uniform and faceVarying primvars split the mesh per triangle corner,
+    // welding corners back together wherever every value agrees
+    let split = !primvars.per_point();
+    let colors = expand("colors", primvars.colors.as_ref().map(|colors| colors.vertex_colors(&triangulated, points.len(), split)));
+    let st = expand("st", primvars.st.as_ref().map(|st| st.vertex_values(&triangulated, points.len(), split)));
+    let authored_normals = expand("normals", primvars.normals.as_ref().map(|normals| normals.vertex_values(&triangulated, points.len(), split)));
+    let (mut vertices, indices, edge_indices, colors, st, authored_normals) = match split {
+        true => {
+            let corner_values: Vec<&[Vec4]> = [&colors, &st, &authored_normals].into_iter().flatten().map(Vec::as_slice).collect();
+            let welded = weld_corners(&triangulated.indices, &corner_values);
+            let gather = |values: Option<Vec<Vec4>>| values.map(|values| welded.gather(&values));
+            (
+                welded.vertex_corners.iter().map(|&corner| vertices[triangulated.indices[corner as usize] as usize]).collect(),
+                welded.indices.clone(),
+                welded.edge_indices(&triangulated.indices, &triangulated.edge_indices, points.len()),
+                gather(colors),
+                gather(st),
+                gather(authored_normals),
+            )
+        }
+        false => (vertices, triangulated.indices.clone(), triangulated.edge_indices.clone(), colors, st, authored_normals),
+    };
+    // Authored normals replace the computed ones, hard edges included
+    if let Some(authored_normals) = authored_normals {
+        for (vertex, normal) in vertices.iter_mut().zip(&authored_normals) {
+            vertex.normal = normal.truncate().normalize_or_zero().to_array();
+        }
+    }
+    
+    let tangents = match st {
+        Some(st) => {
+            let st: Vec<Vec2> = st.iter().map(|value| value.truncate().truncate()).collect();
+            for (vertex, st) in vertices.iter_mut().zip(&st) {
+                vertex.uv = st.to_array();
+            }
+            let positions: Vec<Vec3> = vertices.iter().map(|vertex| Vec3::from(vertex.position)).collect();
+            let normals: Vec<Vec3> = vertices.iter().map(|vertex| Vec3::from(vertex.normal)).collect();
+            generate_tangents(&positions, &normals, &st, &indices)
+        }
+        None => Vec::new(),
+    };
+    
+    Ok(USDGeometry {
+        prim_path: prim_path.to_string(),
+        prim_type: "Mesh".to_string(),
+        vertices,
+        indices,
+        transform,
+        material_path: Some(DEFAULT_MATERIAL.to_string()),
+        visibility: true,
+        edge_indices,
+        colors: colors.unwrap_or_default(),
+        tangents,
+        face_ids: triangulated.face_ids,
+        primvars: Vec::new(),
+    })
+}
+
+/// View a Vt array as a contiguous NumPy array and copy it out through the buffer protocol
+///
+/// Vt arrays expose their storage to NumPy without a copy, so this costs a single
+/// memcpy instead of one Python object conversion per element.
+#[cfg(feature = "usd")]
+fn read_numpy_buffer<T: pyo3::buffer::Element + Copy>(value: &Bound<'_, PyAny>, dtype: &str) -> PyResult<Vec<T>> {
+    let py = value.py();
+    let array = py.import("numpy")?.call_method1("ascontiguousarray", (value, dtype))?;
+    pyo3::buffer::PyBuffer::<T>::get(&array)?.to_vec(py)
+}
+
+/// Read a point array (Vt.Vec3fArray), falling back to per-element conversion without NumPy
+#[cfg(feature = "usd")]
+fn read_points(value: &Bound<'_, PyAny>) -> PyResult<Vec<Vec3>> {
+    match read_numpy_buffer::<f32>(value, "float32") {
+        Ok(flat) => Ok(flat.chunks_exact(3).map(Vec3::from_slice).collect()),
+        Err(_) => {
+            let points: Vec<[f32; 3]> = value.extract()?;
+            Ok(points.into_iter().map(Vec3::from).collect())
+        }
+    }
+}
+
+/// Read an index array (Vt.IntArray), falling back to per-element conversion without NumPy
+#[cfg(feature = "usd")]
+fn read_ints(value: &Bound<'_, PyAny>) -> PyResult<Vec<i32>> {
+    read_numpy_buffer::<i32>(value, "int32").or_else(|_| value.extract())
+}
+
+/// Read a mesh's st, normals and either its chosen display primvar or displayColor and displayOpacity
+#[cfg(feature = "usd")]
+fn read_mesh_primvars(prim: &Bound<'_, PyAny>, time: &Bound<'_, PyAny>, display_primvar: Option<&str>) -> PyResult<MeshPrimvars> {
+    let usd_geom = prim.py().import("pxr.UsdGeom")?;
+    let primvars_api = usd_geom.getattr("PrimvarsAPI")?.call1((prim,))?;
+    let colors = match display_primvar {
+        Some(name) => read_primvar(&primvars_api, name, time)?.map(|color| ColorPrimvars { color, opacity: None }),
+        None => match read_primvar(&primvars_api, "displayColor", time)? {
+            Some(color) => Some(ColorPrimvars { color, opacity: read_primvar(&primvars_api, "displayOpacity", time)? }),
+            None => None,
+        },
+    };
+    Ok(MeshPrimvars {
+        colors,
+        st: read_primvar(&primvars_api, "st", time)?,
+        normals: read_normals(&usd_geom.getattr("Mesh")?.call1((prim,))?, &primvars_api, time)?,
+    })
+}
+
+/// Read a mesh's normals, preferring primvars:normals over the normals attribute
+#[cfg(feature = "usd")]
+fn read_normals(mesh: &Bound<'_, PyAny>, primvars_api: &Bound<'_, PyAny>, time: &Bound<'_, PyAny>) -> PyResult<Option<Primvar>> {
+    if let Some(normals) = read_primvar(primvars_api, "normals", time)? {
+        return Ok(Some(normals));
+    }
+    let value = mesh.call_method0("GetNormalsAttr")?.call_method1("Get", (time,))?;
+    if value.is_none() {
+        return Ok(None);
+    }
+    let token: String = mesh.call_method0("GetNormalsInterpolation")?.extract()?;
+    let Some(interpolation) = Interpolation::from_token(&token) else {
+        return Ok(None);
+    };
+    let flat = read_numpy_buffer::<f32>(&value, "float32")?;
+    Ok(Some(Primvar::from_components("normals", interpolation, &flat, 3)))
+}
+
+/// List a mesh's authored primvars whose values widen to RGBA
+#[cfg(feature = "usd")]
+fn list_primvars(prim: &Bound<'_, PyAny>) -> PyResult<Vec<PrimvarInfo>> {
+    let primvars_api = prim.py().import("pxr.UsdGeom")?.getattr("PrimvarsAPI")?.call1((prim,))?;
+    let mut primvars = Vec::new();
+    for primvar in primvars_api.call_method0("GetPrimvarsWithValues")?.try_iter()? {
+        let primvar = primvar?;
+        let type_name = primvar.call_method0("GetTypeName")?.str()?.to_string();
+        let token: String = primvar.call_method0("GetInterpolation")?.extract()?;
+        if let Some(interpolation) = Interpolation::from_token(&token).filter(|_| type_components(&type_name).is_some()) {
+            let name = primvar.call_method0("GetPrimvarName")?.str()?.to_string();
+            primvars.push(PrimvarInfo { name, type_name, interpolation });
+        }
+    }
+    Ok(primvars)
+}
+
+/// Read one primvar through UsdGeomPrimvarsAPI, widened to RGBA
+///
+/// Primvars without an authored value or with an unknown interpolation read
+/// as absent. Non-array primvars count as a single element.
+#[cfg(feature = "usd")]
+fn read_primvar(primvars_api: &Bound<'_, PyAny>, name: &str, time: &Bound<'_, PyAny>) -> PyResult<Option<Primvar>> {
+    let primvar = primvars_api.call_method1("GetPrimvar", (name.trim_start_matches("primvars:"),))?;
+    if !primvar.call_method0("HasValue")?.extract::<bool>()? {
+        return Ok(None);
+    }
+    let token: String = primvar.call_method0("GetInterpolation")?.extract()?;
+    let value = primvar.call_method1("Get", (time,))?;
+    let Some(interpolation) = Interpolation::from_token(&token).filter(|_| !value.is_none()) else {
+        return Ok(None);
+    };
+    let flat = read_numpy_buffer::<f32>(&value, "float32")?;
+    let is_array: bool = primvar.call_method0("GetTypeName")?.getattr("isArray")?.extract()?;
+    let count = if is_array { value.len()? } else { 1 };
+    let mut values = Primvar::from_components(name, interpolation, &flat, if count > 0 { flat.len() / count } else { 1 });
+    if primvar.call_method0("IsIndexed")?.extract::<bool>()? {
+        values.indices = read_ints(&primvar.call_method1("GetIndices", (time,))?)?;
+    }
+    Ok(Some(values))
+}
+
+/// Read the includeRoot, includes and excludes of a UsdCollectionAPI collection
+///
+/// The UsdLux schemas give light and shadow link collections an includeRoot
+/// fallback of true; other collections fall back to false.
+#[cfg(feature = "usd")]
+fn read_collection(collection: &Bound<'_, PyAny>, include_root_fallback: bool) -> PyResult<LinkCollection> {
+    let targets = |getter: &str| -> PyResult<Vec<String>> {
+        collection.call_method0(getter)?
+            .call_method0("GetTargets")?
+            .try_iter()?
+            .map(|target| target.and_then(|target| target.str()).map(|target| target.to_string()))
+            .collect()
+    };
+    let include_root = collection.call_method0("GetIncludeRootAttr")
+        .and_then(|attr| attr.call_method0("Get"))
+        .and_then(|value| value.extract::<Option<bool>>())?
+        .unwrap_or(include_root_fallback);
+    Ok(LinkCollection {
+        include_root,
+        includes: targets("GetIncludesRel")?,
+        excludes: targets("GetExcludesRel")?,
+    })
+}
+
+/// Resolved latlong texture of a DomeLight, or the authored path when unresolved
+#[cfg(feature = "usd")]
+fn read_dome_texture(usd_lux: &Bound<'_, PyAny>, prim: &Bound<'_, PyAny>, time: &Bound<'_, PyAny>) -> PyResult<Option<String>> {
+    let asset = usd_lux.getattr("DomeLight")?
+        .call1((prim,))?
+        .call_method0("GetTextureFileAttr")?
+        .call_method1("Get", (time,))?;
+    if asset.is_none() {
+        return Ok(None);
+    }
+    let resolved: String = asset.getattr("resolvedPath")?.extract()?;
+    Ok(Some(if resolved.is_empty() { asset.getattr("path")?.extract()? } else { resolved }))
+}
+
+/// Read a material's UsdPreviewSurface network, or None when its surface is another shader
+///
+/// Inputs connected to a UsdUVTexture or UsdPrimvarReader are recorded in
+/// `USDMaterial::connections` and take the upstream node's fallback value.
+/// Materials without a universal UsdPreviewSurface fall back to their
+/// MaterialX surface.
+#[cfg(feature = "usd")]
+fn read_preview_surface(usd_shade: &Bound<'_, PyAny>, prim_path: &str, material: &Bound<'_, PyAny>) -> PyResult<Option<USDMaterial>> {
+    let shader = material.call_method0("ComputeSurfaceSource")?.get_item(0)?;
+    if !shader.is_truthy()? {
+        return read_materialx_surface(prim_path, material);
+    }
+    let shader_id: Option<String> = shader.call_method0("GetShaderId")?.extract()?;
+    if shader_id.as_deref() != Some("UsdPreviewSurface") {
+        return read_materialx_surface(prim_path, material);
+    }
+    
+    let mut connections = HashMap::new();
+    let mut input = |name: &str, fallback: Vec4| -> PyResult<Vec4> {
+        let surface_input = shader.call_method1("GetInput", (name,))?;
+        if !surface_input.is_truthy()? {
+            return Ok(fallback);
+        }
+        if let Some(source) = read_input_source(usd_shade, &surface_input)? {
+            let value = source.fallback_value();
+            connections.insert(name.to_string(), source);
+            return Ok(value);
+        }
+        // Unauthored inputs and connections to other shaders keep the UsdPreviewSurface fallbacks
+        Ok(read_vec4(&surface_input.call_method0("Get")?).unwrap_or(fallback))
+    };
+    let diffuse_color = input("diffuseColor", Vec4::new(0.18, 0.18, 0.18, 1.0))?.truncate();
+    let emission_color = input("emissiveColor", Vec4::ZERO)?.truncate();
+    let metallic = input("metallic", Vec4::ZERO)?.x;
+    let roughness = input("roughness", Vec4::splat(0.5))?.x;
+    let opacity = input("opacity", Vec4::ONE)?.x;
+    let ior = input("ior", Vec4::splat(1.5))?.x;
+    let normal = input("normal", Vec4::new(0.0, 0.0, 1.0, 0.0))?.truncate();
+    Ok(Some(USDMaterial {
+        prim_path: prim_path.to_string(),
+        diffuse_color,
+        metallic,
+        roughness,
+        opacity,
+        emission_color,
+        ior,
+        normal,
+        connections,
+    }))
+}
+
+/// Translate a material's "mtlx" surface to UsdPreviewSurface values, or None without a known one
+///
+/// Connected inputs take the MaterialX node definition's defaults, see `preview_values`.
+#[cfg(feature = "usd")]
+fn read_materialx_surface(prim_path: &str, material: &Bound<'_, PyAny>) -> PyResult<Option<USDMaterial>> {
+    let shader = material.call_method1("ComputeSurfaceSource", ("mtlx",))?.get_item(0)?;
+    if !shader.is_truthy()? {
+        return Ok(None);
+    }
+    let Some(shader_id) = shader.call_method0("GetShaderId")?.extract::<Option<String>>()? else {
+        return Ok(None);
+    };
+    let input = |name: &str| -> Option<Vec4> {
+        let input = shader.call_method1("GetInput", (name,)).ok()?;
+        let connected: bool = input.call_method0("HasConnectedSource").and_then(|connected| connected.extract()).ok()?;
+        if !input.is_truthy().ok()? || connected {
+            return None;
+        }
+        read_vec4(&input.call_method0("Get").ok()?).ok()
+    };
+    Ok(preview_values(&shader_id, input).map(|values| USDMaterial {
+        prim_path: prim_path.to_string(),
+        diffuse_color: values.diffuse_color,
+        metallic: values.metallic,
+        roughness: values.roughness,
+        opacity: values.opacity,
+        emission_color: values.emissive_color,
+        ior: values.ior,
+        normal: Vec3::Z,
+        connections: HashMap::new(),
+    }))
+}
+
+/// Upstream UsdUVTexture or UsdPrimvarReader connected to a shader input
+#[cfg(feature = "usd")]
+fn read_input_source<'py>(usd_shade: &Bound<'py, PyAny>, input: &Bound<'py, PyAny>) -> PyResult<Option<InputSource>> {
+    let connected = input.call_method0("GetConnectedSource")?;
+    if connected.is_none() {
+        return Ok(None);
+    }
+    let upstream = usd_shade.getattr("Shader")?.call1((connected.get_item(0)?.call_method0("GetPrim")?,))?;
+    let output: String = connected.get_item(1)?.extract()?;
+    let shader_path = upstream.call_method0("GetPath")?.str()?.to_string();
+    let shader_id: Option<String> = upstream.call_method0("GetShaderId")?.extract()?;
+    let value = |name: &str| -> PyResult<Option<Bound<'py, PyAny>>> {
+        let input = upstream.call_method1("GetInput", (name,))?;
+        if !input.is_truthy()? {
+            return Ok(None);
+        }
+        let value = input.call_method0("Get")?;
+        Ok((!value.is_none()).then_some(value))
+    };
+    let token = |name: &str, fallback: &str| -> PyResult<String> {
+        Ok(value(name)?.map(|v| v.extract::<String>()).transpose()?.unwrap_or_else(|| fallback.to_string()))
+    };
+    let vec4 = |name: &str, fallback: Vec4| -> PyResult<Vec4> {
+        Ok(value(name)?.and_then(|v| read_vec4(&v).ok()).unwrap_or(fallback))
+    };
+    
+    match shader_id.as_deref() {
+        Some("UsdUVTexture") => {
+            let file = match value("file")? {
+                Some(asset) => {
+                    let resolved: String = asset.getattr("resolvedPath")?.extract()?;
+                    if resolved.is_empty() { asset.getattr("path")?.extract()? } else { resolved }
+                }
+                None => String::new(),
+            };
+            let st = upstream.call_method1("GetInput", ("st",))?;
+            let st_primvar = match st.is_truthy()? {
+                true => match read_input_source(usd_shade, &st)? {
+                    Some(InputSource::Primvar(reader)) => Some(reader.varname),
+                    _ => None,
+                },
+                false => None,
+            };
+            Ok(Some(InputSource::Texture(TextureInput {
+                shader_path,
+                file,
+                output,
+                st_primvar,
+                wrap_s: token("wrapS", "useMetadata")?,
+                wrap_t: token("wrapT", "useMetadata")?,
+                scale: vec4("scale", Vec4::ONE)?,
+                bias: vec4("bias", Vec4::ZERO)?,
+                fallback: vec4("fallback", Vec4::new(0.0, 0.0, 0.0, 1.0))?,
+                source_color_space: token("sourceColorSpace", "auto")?,
+            })))
+        }
+        Some(id) if id.starts_with("UsdPrimvarReader_") => Ok(Some(InputSource::Primvar(PrimvarInput {
+            shader_path,
+            varname: token("varname", "")?,
+            output,
+            fallback: vec4("fallback", Vec4::ZERO)?,
+        }))),
+        _ => Ok(None),
+    }
+}
+
+/// Read a scalar, 2-, 3- or 4-component shader value, splatting scalars
+#[cfg(feature = "usd")]
+fn read_vec4(value: &Bound<'_, PyAny>) -> PyResult<Vec4> {
+    if let Ok(scalar) = value.extract::<f32>() {
+        return Ok(Vec4::splat(scalar));
+    }
+    let components: Vec<f32> = value.extract()?;
+    Ok(match components.as_slice() {
+        [x, y] => Vec4::new(*x, *y, 0.0, 1.0),
+        [x, y, z] => Vec4::new(*x, *y, *z, 1.0),
+        [x, y, z, w, ..] => Vec4::new(*x, *y, *z, *w),
+        _ => return Err(pyo3::exceptions::PyValueError::new_err("expected a scalar or vector value")),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn settings() -> ExtractionSettings {
+        ExtractionSettings { time_code: 1.0, subdivision_level: 0, display_primvar: None }
+    }
+    
+    #[test]
+    fn every_sink_takes_the_same_extraction() {
+        // A stage the engine doesn't know extracts to the stand-in scene
+        let mut delegate = USDSceneDelegate::default();
+        let mut scene = USDScene::default();
+        delegate.populate("scene_delegate_test", &settings(), &mut scene);
+        assert_eq!(scene.stage_id, "scene_delegate_test");
+        assert_eq!(scene.geometries.len(), 3);
+        
+        let mut trace = TraceSceneSink::default();
+        delegate.populate("scene_delegate_test", &settings(), &mut trace);
+        assert!(trace.scene.is_some());
+        
+        // The default material and light aren't authored, so the snapshot leaves them out
+        let mut snapshot = SceneSnapshotSink::default();
+        delegate.populate("scene_delegate_test", &settings(), &mut snapshot);
+        let snapshot = snapshot.snapshot;
+        assert_eq!((snapshot.stage_id.as_str(), snapshot.time_code), ("scene_delegate_test", 1.0));
+        assert_eq!(snapshot.meshes.len(), 3);
+        assert!(snapshot.materials.is_empty() && snapshot.lights.is_empty());
+        for (mesh, geometry) in snapshot.meshes.iter().zip(&scene.geometries) {
+            assert!(mesh.face_vertex_counts.iter().all(|&count| count == 3));
+            assert_eq!(mesh.face_vertex_indices.len(), geometry.indices.len());
+            let world = geometry.transform.transform_point3(Vec3::from(geometry.vertices[0].position));
+            assert_eq!(mesh.points[0], world.as_dvec3().to_array());
+        }
+    }
+    
+    #[test]
+    fn snapshots_carry_bound_materials_and_authored_lights() {
+        let mut scene = USDScene::default();
+        USDSceneDelegate::default().populate("scene_delegate_snapshot_test", &settings(), &mut scene);
+        let mut geometry = scene.geometries[0].clone();
+        geometry.material_path = Some("/Looks/Clay".to_string());
+        
+        let mut sink = SceneSnapshotSink::default();
+        sink.begin_scene("scene_delegate_snapshot_test", 1.0);
+        sink.add_material(USDMaterial {
+            prim_path: "/Looks/Clay".to_string(),
+            diffuse_color: Vec3::new(0.8, 0.35, 0.2),
+            metallic: 0.0,
+            roughness: 0.6,
+            opacity: 1.0,
+            emission_color: Vec3::ZERO,
+            ior: 1.5,
+            normal: Vec3::Z,
+            connections: HashMap::new(),
+        });
+        sink.add_geometry(geometry.clone());
+        sink.add_geometry(geometry);
+        sink.add_light(USDLight {
+            prim_path: "/Lights/Key".to_string(),
+            light_type: "rect".to_string(),
+            transform: Mat4::from_translation(Vec3::new(1.0, 2.0, 3.0)),
+            lux: LuxParams { intensity: 4.0, width: 2.0, ..LuxParams::default() },
+            light_link: LinkCollection::default(),
+            shadow_link: LinkCollection::default(),
+        });
+        sink.end_scene();
+        
+        let snapshot = &sink.snapshot;
+        assert_eq!(snapshot.materials.len(), 1);
+        assert_eq!(snapshot.materials[0].diffuse_color, [0.8, 0.35, 0.2]);
+        assert_eq!(snapshot.meshes[1].material_path.as_deref(), Some("/Looks/Clay"));
+        let light = &snapshot.lights[0];
+        assert_eq!((light.light_type.as_str(), light.intensity, light.width), ("RectLight", 4.0, 2.0));
+        // USD's row-vector matrices keep the translation in the last row
+        assert_eq!(light.transform[12..15], [1.0, 2.0, 3.0]);
+    }
+}
@@ -0,0 +1,41 @@
+// USD Face Pick Shader
+//
+// Writes the geometry and triangle under every pixel, each plus one so that
+// zero means no hit. Drawn without an index buffer: every vertex invocation
+// is one triangle corner and pulls its index and position from storage.
+
+struct PickConstants {
+    object_to_clip: mat4x4<f32>,
+    geometry: u32,
+    // Vertex3D size in floats; the position is its first three
+    vertex_stride: u32,
+}
+
+var<push_constant> constants: PickConstants;
+
+@group(0) @binding(0)
+var<storage, read> vertices: array<f32>;
+
+@group(0) @binding(1)
+var<storage, read> indices: array<u32>;
+
+struct PickVertex {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) @interpolate(flat) triangle: u32,
+}
+
+@vertex
+fn vs_main(@builtin(vertex_index) corner: u32) -> PickVertex {
+    let base = indices[corner] * constants.vertex_stride;
+    let position = vec3<f32>(vertices[base], vertices[base + 1u], vertices[base + 2u]);
+    
+    var out: PickVertex;
+    out.clip_position = constants.object_to_clip * vec4<f32>(position, 1.0);
+    out.triangle = corner / 3u;
+    return out;
+}
+
+@fragment
+fn fs_main(in: PickVertex) -> @location(0) vec2<u32> {
+    return vec2<u32>(constants.geometry + 1u, in.triangle + 1u);
+}
@@ -0,0 +1,76 @@
+// USD FXAA
+//
+// Console variant of FXAA 3.11: pixels whose neighborhood spans enough
+// contrast are blurred along the edge direction found from the luma of
+// their four diagonal neighbors, with two or four taps, keeping the two-tap
+// result when the wider one picks up color from across the edge.
+
+// Contrast below which pixels are left as they are, absolute and relative to the brightest neighbor
+const EDGE_THRESHOLD_MIN: f32 = 0.0312;
+const EDGE_THRESHOLD: f32 = 0.125;
+const REDUCE_MIN: f32 = 0.0078125;
+const REDUCE_MUL: f32 = 0.125;
+// Longest blur along an edge, in pixels
+const SPAN_MAX: f32 = 8.0;
+
+@group(0) @binding(0)
+var frame: texture_2d<f32>;
+
+@group(0) @binding(1)
+var frame_sampler: sampler;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+}
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    var out: VertexOutput;
+    out.position = vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, 0.0, 1.0);
+    out.uv = uv;
+    return out;
+}
+
+// Perceptual brightness of a linear color
+fn luma(color: vec3<f32>) -> f32 {
+    return sqrt(dot(color, vec3<f32>(0.299, 0.587, 0.114)));
+}
+
+fn sample_at(uv: vec2<f32>) -> vec3<f32> {
+    return textureSampleLevel(frame, frame_sampler, uv, 0.0).rgb;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let texel = 1.0 / vec2<f32>(textureDimensions(frame));
+    let center = textureSampleLevel(frame, frame_sampler, in.uv, 0.0);
+    let luma_center = luma(center.rgb);
+    let luma_nw = luma(sample_at(in.uv + vec2<f32>(-1.0, -1.0) * texel));
+    let luma_ne = luma(sample_at(in.uv + vec2<f32>(1.0, -1.0) * texel));
+    let luma_sw = luma(sample_at(in.uv + vec2<f32>(-1.0, 1.0) * texel));
+    let luma_se = luma(sample_at(in.uv + vec2<f32>(1.0, 1.0) * texel));
+    let luma_min = min(luma_center, min(min(luma_nw, luma_ne), min(luma_sw, luma_se)));
+    let luma_max = max(luma_center, max(max(luma_nw, luma_ne), max(luma_sw, luma_se)));
+    if luma_max - luma_min < max(EDGE_THRESHOLD_MIN, luma_max * EDGE_THRESHOLD) {
+        return center;
+    }
+    
+    // Along the edge, across the steepest luma change
+    var direction = vec2<f32>(
+        (luma_sw + luma_se) - (luma_nw + luma_ne),
+        (luma_nw + luma_sw) - (luma_ne + luma_se),
+    );
+    let reduce = max((luma_nw + luma_ne + luma_sw + luma_se) * 0.25 * REDUCE_MUL, REDUCE_MIN);
+    let scale = 1.0 / (min(abs(direction.x), abs(direction.y)) + reduce);
+    direction = clamp(direction * scale, vec2<f32>(-SPAN_MAX), vec2<f32>(SPAN_MAX)) * texel;
+    
+    let near = 0.5 * (sample_at(in.uv - direction / 6.0) + sample_at(in.uv + direction / 6.0));
+    let far = 0.5 * near + 0.25 * (sample_at(in.uv - direction * 0.5) + sample_at(in.uv + direction * 0.5));
+    let luma_far = luma(far);
+    if luma_far < luma_min || luma_far > luma_max {
+        return vec4<f32>(near, center.a);
+    }
+    return vec4<f32>(far, center.a);
+}
@@ -11,11 +11,13 @@ var<uniform> uniforms: USDUniforms;
 
 struct VertexInput {
     @location(0) position: vec3<f32>,
+    @location(1) color: vec4<f32>,
 }
 
 struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
     @location(0) world_position: vec3<f32>,
+    @location(1) color: vec4<f32>,
 }
 
 @vertex
@@ -25,15 +27,13 @@ fn vs_main(vertex: VertexInput) -> VertexOutput {
     let world_position = uniforms.model * vec4<f32>(vertex.position, 1.0);
     out.clip_position = uniforms.view_proj * world_position;
     out.world_position = world_position.xyz;
+    out.color = vertex.color;
     
     return out;
 }
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
-    // USD grid styling - semi-transparent gray
-    let grid_color = vec3<f32>(0.5, 0.5, 0.5);
-    let alpha = 0.4;
-    
-    return vec4<f32>(grid_color, alpha);
+    // Semi-transparent gray grid lines, axis gizmo lines in their axis color
+    return in.color;
 }
\ No newline at end of file
@@ -9,54 +9,272 @@ struct USDUniforms {
 @group(0) @binding(0)
 var<uniform> uniforms: USDUniforms;
 
+struct USDLight {
+    direction: vec3<f32>,
+    intensity: f32,
+    color: vec3<f32>,
+}
+
+// Dome lights shade as a sky/ground hemisphere ambient
+struct USDLights {
+    lights: array<USDLight, 8>,
+    sky_color: vec3<f32>,
+    count: u32,
+    ground_color: vec3<f32>,
+    // Display view (color_management.rs `ViewTransform::index`), exposure scale and 1 / gamma
+    view_transform: u32,
+    exposure: f32,
+    inverse_gamma: f32,
+    // Nonzero to shade with face normals
+    flat_shading: u32,
+}
+
+@group(0) @binding(1)
+var<uniform> lighting: USDLights;
+
+// Per-draw constants: UsdLux link masks (bit i set when light i lights or
+// shadows this draw) and the bound material's UsdPreviewSurface inputs
+struct DrawConstants {
+    light_mask: u32,
+    shadow_mask: u32,
+    base_color: vec4<f32>, // rgb diffuseColor, a opacity
+    emissive: vec3<f32>,
+    metallic: f32,
+    roughness: f32,
+    reflectance: f32, // normal-incidence reflectance from ior
+    vertex_color: u32, // 0 off, 1 displayColor as base color, 2 unlit display primvar
+}
+
+var<push_constant> draw: DrawConstants;
+
+fn light_linked(index: u32) -> bool {
+    // Lights past the 32 mask bits are linked to everything
+    return index >= 32u || (draw.light_mask & (1u << index)) != 0u;
+}
+
+// UsdUVTexture slots in material preview: diffuseColor, roughness, metallic,
+// emissiveColor, normal. Unset mask bits keep the pushed constant inputs.
+struct MaterialTextures {
+    scale: array<vec4<f32>, 5>,
+    bias: array<vec4<f32>, 5>,
+    channels: array<vec4<u32>, 2>, // per slot, 0 rgba, 1-4 a single r, g, b or a channel
+    mask: u32,
+}
+
+@group(1) @binding(0)
+var<uniform> material_textures: MaterialTextures;
+@group(1) @binding(1)
+var diffuse_texture: texture_2d<f32>;
+@group(1) @binding(2)
+var diffuse_sampler: sampler;
+@group(1) @binding(3)
+var roughness_texture: texture_2d<f32>;
+@group(1) @binding(4)
+var roughness_sampler: sampler;
+@group(1) @binding(5)
+var metallic_texture: texture_2d<f32>;
+@group(1) @binding(6)
+var metallic_sampler: sampler;
+@group(1) @binding(7)
+var emissive_texture: texture_2d<f32>;
+@group(1) @binding(8)
+var emissive_sampler: sampler;
+@group(1) @binding(9)
+var normal_texture: texture_2d<f32>;
+@group(1) @binding(10)
+var normal_sampler: sampler;
+
+fn texture_input(slot: u32, texel: vec4<f32>, constant: vec4<f32>) -> vec4<f32> {
+    if ((material_textures.mask & (1u << slot)) == 0u) {
+        return constant;
+    }
+    let value = texel * material_textures.scale[slot] + material_textures.bias[slot];
+    switch material_textures.channels[slot / 4u][slot % 4u] {
+        case 1u: { return vec4<f32>(value.r); }
+        case 2u: { return vec4<f32>(value.g); }
+        case 3u: { return vec4<f32>(value.b); }
+        case 4u: { return vec4<f32>(value.a); }
+        default: { return value; }
+    }
+}
+
+// Linear RGBA per vertex from mesh color primvars, and tangents along +s with
+// the bitangent handedness in w. Draws without them bind a single default entry.
+@group(2) @binding(0)
+var<storage, read> vertex_colors: array<vec4<f32>>;
+@group(2) @binding(1)
+var<storage, read> vertex_tangents: array<vec4<f32>>;
+
+const PI: f32 = 3.14159265;
+
+// GGX specular response to one light, already weighted by n.l
+fn specular_ggx(normal: vec3<f32>, view_dir: vec3<f32>, light_dir: vec3<f32>, roughness: f32, f0: vec3<f32>) -> vec3<f32> {
+    let half_dir = normalize(light_dir + view_dir);
+    let n_dot_l = max(dot(normal, light_dir), 0.0);
+    let n_dot_v = max(dot(normal, view_dir), 1e-4);
+    let n_dot_h = max(dot(normal, half_dir), 0.0);
+    
+    let alpha = max(roughness * roughness, 1e-3);
+    let alpha2 = alpha * alpha;
+    let denom = n_dot_h * n_dot_h * (alpha2 - 1.0) + 1.0;
+    let distribution = alpha2 / (PI * denom * denom);
+    
+    let k = alpha * 0.5;
+    let geometry = (n_dot_l / (n_dot_l * (1.0 - k) + k)) * (n_dot_v / (n_dot_v * (1.0 - k) + k));
+    let fresnel = f0 + (vec3<f32>(1.0) - f0) * pow(1.0 - max(dot(half_dir, view_dir), 0.0), 5.0);
+    
+    return distribution * geometry * fresnel / (4.0 * n_dot_v);
+}
+
+// Hable's filmic curve, before normalizing by its white point
+fn hable(x: vec3<f32>) -> vec3<f32> {
+    let a = 0.15;
+    let b = 0.50;
+    let c = 0.10;
+    let d = 0.20;
+    let e = 0.02;
+    let f = 0.30;
+    return (x * (a * x + c * b) + d * e) / (x * (a * x + b) + d * f) - e / f;
+}
+
+// Linear shading to display linear color, as color_management.rs `ColorManagement::display`
+fn display_transform(color: vec3<f32>) -> vec3<f32> {
+    let exposed = max(color * lighting.exposure, vec3<f32>(0.0));
+    var mapped = min(exposed, vec3<f32>(1.0));
+    if lighting.view_transform == 1u {
+        mapped = min(hable(exposed * 2.0) / hable(vec3<f32>(11.2)), vec3<f32>(1.0));
+    } else if lighting.view_transform == 2u {
+        // Stephen Hill's fit of the ACES RRT and sRGB ODT
+        let aces_input = mat3x3<f32>(
+            vec3<f32>(0.59719, 0.07600, 0.02840),
+            vec3<f32>(0.35458, 0.90834, 0.13383),
+            vec3<f32>(0.04823, 0.01566, 0.83777),
+        );
+        let aces_output = mat3x3<f32>(
+            vec3<f32>(1.60475, -0.10208, -0.00327),
+            vec3<f32>(-0.53108, 1.10813, -0.07276),
+            vec3<f32>(-0.07367, -0.00605, 1.07602),
+        );
+        let v = aces_input * exposed;
+        let fitted = (v * (v + 0.0245786) - 0.000090537) / (v * (0.983729 * v + 0.432951) + 0.238081);
+        mapped = clamp(aces_output * fitted, vec3<f32>(0.0), vec3<f32>(1.0));
+    }
+    return pow(mapped, vec3<f32>(lighting.inverse_gamma));
+}
+
 struct VertexInput {
     @location(0) position: vec3<f32>,
     @location(1) normal: vec3<f32>,
     @location(2) uv: vec2<f32>,
 }
 
+// Object-to-world transform of the drawn prim or instance, by columns
+struct InstanceInput {
+    @location(3) model_0: vec4<f32>,
+    @location(4) model_1: vec4<f32>,
+    @location(5) model_2: vec4<f32>,
+    @location(6) model_3: vec4<f32>,
+}
+
 struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
     @location(0) world_normal: vec3<f32>,
     @location(1) world_position: vec3<f32>,
     @location(2) uv: vec2<f32>,
+    @location(3) color: vec4<f32>,
+    @location(4) world_tangent: vec4<f32>,
 }
 
 @vertex
-fn vs_main(vertex: VertexInput) -> VertexOutput {
+fn vs_main(vertex: VertexInput, instance: InstanceInput, @builtin(vertex_index) vertex_index: u32) -> VertexOutput {
     var out: VertexOutput;
+    let model = mat4x4<f32>(instance.model_0, instance.model_1, instance.model_2, instance.model_3);
     
     // Transform vertex position
-    let world_position = uniforms.model * vec4<f32>(vertex.position, 1.0);
+    let world_position = model * vec4<f32>(vertex.position, 1.0);
     out.clip_position = uniforms.view_proj * world_position;
     out.world_position = world_position.xyz;
     
     // Transform normal
-    let normal_matrix = uniforms.model; // Assuming uniform scaling
+    let normal_matrix = model; // Assuming uniform scaling
     out.world_normal = normalize((normal_matrix * vec4<f32>(vertex.normal, 0.0)).xyz);
     
     out.uv = vertex.uv;
+    out.color = vertex_colors[min(vertex_index, arrayLength(&vertex_colors) - 1u)];
+    let tangent = vertex_tangents[min(vertex_index, arrayLength(&vertex_tangents) - 1u)];
+    out.world_tangent = vec4<f32>((normal_matrix * vec4<f32>(tangent.xyz, 0.0)).xyz, tangent.w);
     
     return out;
 }
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
-    // USD material colors
-    let base_color = vec3<f32>(0.7, 0.7, 0.8); // Light gray
-    let ambient = vec3<f32>(0.2, 0.2, 0.2);
+    // Textures are sampled up front to stay in uniform control flow; st has
+    // its origin at the bottom left of the image
+    let st = vec2<f32>(in.uv.x, 1.0 - in.uv.y);
+    let diffuse_texel = textureSample(diffuse_texture, diffuse_sampler, st);
+    let roughness_texel = textureSample(roughness_texture, roughness_sampler, st);
+    let metallic_texel = textureSample(metallic_texture, metallic_sampler, st);
+    let emissive_texel = textureSample(emissive_texture, emissive_sampler, st);
+    let normal_texel = textureSample(normal_texture, normal_sampler, st);
+    // So are the derivatives of the face normal for flat shading
+    let face_normal = normalize(cross(dpdx(in.world_position), dpdy(in.world_position)));
     
-    // Simple directional lighting
-    let light_dir = normalize(vec3<f32>(-0.5, -1.0, -0.5));
-    let normal = normalize(in.world_normal);
-    let diffuse = max(dot(-light_dir, normal), 0.0);
+    // A display primvar is shown as authored, without lighting
+    if (draw.vertex_color == 2u) {
+        return in.color;
+    }
     
-    // Camera-based rim lighting
+    // displayColor and displayOpacity stand in for the material's constants
+    var constant_color = draw.base_color;
+    if (draw.vertex_color == 1u) {
+        constant_color = in.color;
+    }
+    
+    // Bound UsdPreviewSurface, metallic workflow
+    let base_color = texture_input(0u, diffuse_texel, vec4<f32>(constant_color.rgb, 1.0)).rgb;
+    let roughness = clamp(texture_input(1u, roughness_texel, vec4<f32>(draw.roughness)).r, 0.0, 1.0);
+    let metallic = clamp(texture_input(2u, metallic_texel, vec4<f32>(draw.metallic)).r, 0.0, 1.0);
+    let emissive = texture_input(3u, emissive_texel, vec4<f32>(draw.emissive, 1.0)).rgb;
+    let diffuse_color = base_color * (1.0 - metallic);
+    let f0 = mix(vec3<f32>(draw.reflectance), base_color, metallic);
+    
+    // Tangent-space normal maps bend the interpolated normal
+    var normal = normalize(in.world_normal);
+    if (lighting.flat_shading != 0u) {
+        // Facing the same side as the interpolated normal, whichever way the target's y runs
+        normal = select(face_normal, -face_normal, dot(face_normal, normal) < 0.0);
+    }
+    let tangent = in.world_tangent.xyz - normal * dot(normal, in.world_tangent.xyz);
+    if ((material_textures.mask & (1u << 4u)) != 0u && dot(tangent, tangent) > 1e-8) {
+        let tangent_normal = texture_input(4u, normal_texel, vec4<f32>(0.0, 0.0, 1.0, 0.0)).xyz;
+        let bitangent = cross(normal, normalize(tangent)) * in.world_tangent.w;
+        normal = normalize(normalize(tangent) * tangent_normal.x + bitangent * tangent_normal.y + normal * tangent_normal.z);
+    }
+    
+    // Direct lighting from the linked stage lights
+    let ambient = mix(lighting.ground_color, lighting.sky_color, normal.y * 0.5 + 0.5);
     let view_dir = normalize(uniforms.camera_pos - in.world_position);
+    var diffuse = vec3<f32>(0.0);
+    var specular = vec3<f32>(0.0);
+    for (var i = 0u; i < lighting.count; i++) {
+        if (!light_linked(i)) {
+            continue;
+        }
+        let light = lighting.lights[i];
+        let radiance = light.color * light.intensity;
+        diffuse += radiance * max(dot(-light.direction, normal), 0.0);
+        specular += radiance * specular_ggx(normal, view_dir, -light.direction, roughness, f0);
+    }
+    
+    // Camera-based rim lighting
     let rim = 1.0 - max(dot(view_dir, normal), 0.0);
     let rim_factor = pow(rim, 2.0) * 0.3;
     
-    let final_color = base_color * (ambient + vec3<f32>(diffuse) + vec3<f32>(rim_factor));
+    let final_color = diffuse_color * (ambient + diffuse + vec3<f32>(rim_factor))
+        + specular
+        + f0 * ambient
+        + emissive;
     
-    return vec4<f32>(final_color, 1.0);
+    return vec4<f32>(display_transform(final_color), constant_color.a);
 }
\ No newline at end of file
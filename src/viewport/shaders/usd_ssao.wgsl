@@ -0,0 +1,117 @@
+// USD Screen Space Ambient Occlusion
+//
+// fs_occlusion reads the scene's depth, rebuilds each pixel's view space
+// position and normal from it, and counts how many kernel samples in the
+// hemisphere around the normal land behind the depth buffer. fs_composite
+// blurs that over the 4x4 pixels the kernel rotation repeats on and
+// multiplies it into the frame through the blend state.
+
+const KERNEL_SIZE: u32 = 16u;
+
+struct Occlusion {
+    projection: mat4x4<f32>,
+    inverse_projection: mat4x4<f32>,
+    kernel: array<vec4<f32>, 16>,
+    radius: f32,
+    intensity: f32,
+    bias: f32,
+    padding: f32,
+}
+
+@group(0) @binding(0)
+var<uniform> occlusion: Occlusion;
+
+@group(0) @binding(1)
+var depth: texture_2d<f32>; // depth
+
+@group(1) @binding(0)
+var occlusion_texture: texture_2d<f32>;
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> @builtin(position) vec4<f32> {
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    return vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, 0.0, 1.0);
+}
+
+// View space position of a pixel, or a point under it at another depth
+fn view_position(pixel: vec2<i32>, pixel_depth: f32) -> vec3<f32> {
+    let uv = (vec2<f32>(pixel) + 0.5) / vec2<f32>(textureDimensions(depth));
+    let ndc = vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, pixel_depth, 1.0);
+    let view = occlusion.inverse_projection * ndc;
+    return view.xyz / view.w;
+}
+
+fn load_position(pixel: vec2<i32>) -> vec3<f32> {
+    let size = vec2<i32>(textureDimensions(depth));
+    let clamped = clamp(pixel, vec2<i32>(0), size - 1);
+    return view_position(clamped, textureLoad(depth, clamped, 0).r);
+}
+
+@fragment
+fn fs_occlusion(@builtin(position) frag_position: vec4<f32>) -> @location(0) vec4<f32> {
+    let pixel = vec2<i32>(frag_position.xy);
+    let pixel_depth = textureLoad(depth, pixel, 0).r;
+    if pixel_depth >= 1.0 {
+        return vec4<f32>(1.0);
+    }
+    let position = view_position(pixel, pixel_depth);
+    
+    // Normal from the neighbors on the same surface, so silhouettes stay sharp
+    let right = load_position(pixel + vec2<i32>(1, 0)) - position;
+    let left = position - load_position(pixel - vec2<i32>(1, 0));
+    let down = load_position(pixel + vec2<i32>(0, 1)) - position;
+    let up = position - load_position(pixel - vec2<i32>(0, 1));
+    let dx = select(left, right, abs(right.z) < abs(left.z));
+    let dy = select(up, down, abs(down.z) < abs(up.z));
+    var normal = normalize(cross(dy, dx));
+    if dot(normal, position) > 0.0 {
+        normal = -normal;
+    }
+    
+    // Kernel turned about the normal by an angle repeating every 4x4 pixels
+    let cell = vec2<f32>(vec2<i32>(pixel.x & 3, pixel.y & 3));
+    let angle = (cell.x * 4.0 + cell.y) * 0.39269908 + 0.19634954;
+    let turn = vec3<f32>(cos(angle), sin(angle), 0.0);
+    var tangent = turn - normal * dot(turn, normal);
+    if dot(tangent, tangent) < 1e-6 {
+        tangent = vec3<f32>(0.0, 0.0, 1.0) - normal * normal.z;
+    }
+    tangent = normalize(tangent);
+    let bitangent = cross(normal, tangent);
+    
+    let size = vec2<f32>(textureDimensions(depth));
+    var occluded = 0.0;
+    for (var index = 0u; index < KERNEL_SIZE; index++) {
+        let offset = occlusion.kernel[index].xyz;
+        let sample = position + (tangent * offset.x + bitangent * offset.y + normal * offset.z) * occlusion.radius;
+        let clip = occlusion.projection * vec4<f32>(sample, 1.0);
+        let uv = clip.xy / clip.w * vec2<f32>(0.5, -0.5) + 0.5;
+        if any(uv < vec2<f32>(0.0)) || any(uv >= vec2<f32>(1.0)) {
+            continue;
+        }
+        let surface = load_position(vec2<i32>(uv * size));
+        // Geometry far in front of the sample doesn't shadow it
+        let in_range = smoothstep(0.0, 1.0, occlusion.radius / max(abs(position.z - surface.z), 1e-4));
+        if surface.z >= sample.z + occlusion.bias {
+            occluded += in_range;
+        }
+    }
+    
+    let ambient = clamp(1.0 - occlusion.intensity * occluded / f32(KERNEL_SIZE), 0.0, 1.0);
+    return vec4<f32>(ambient, ambient, ambient, 1.0);
+}
+
+@fragment
+fn fs_composite(@builtin(position) frag_position: vec4<f32>) -> @location(0) vec4<f32> {
+    let pixel = vec2<i32>(frag_position.xy);
+    let size = vec2<i32>(textureDimensions(occlusion_texture));
+    var total = 0.0;
+    for (var y = -2; y < 2; y++) {
+        for (var x = -2; x < 2; x++) {
+            let neighbor = clamp(pixel + vec2<i32>(x, y), vec2<i32>(0), size - 1);
+            total += textureLoad(occlusion_texture, neighbor, 0).r;
+        }
+    }
+    let ambient = total / 16.0;
+    return vec4<f32>(ambient, ambient, ambient, 1.0);
+}
@@ -0,0 +1,38 @@
+// USD Wireframe Overlay Shader
+//
+// Draws a mesh's edges as a line list over its shaded surface. Vertices are
+// pulled from the mesh's vertex buffer bound as storage, through the edge
+// list's pairs of vertex indices. WebGPU has no depth bias for lines, so the
+// polygon offset is applied here: every line moves toward the camera by a
+// fixed amount of normalized depth, as the constant term of glPolygonOffset.
+
+struct WireConstants {
+    object_to_clip: mat4x4<f32>,
+    color: vec4<f32>,
+    // Vertex3D size in floats; the position is its first three
+    vertex_stride: u32,
+    depth_offset: f32,
+}
+
+var<push_constant> constants: WireConstants;
+
+@group(0) @binding(0)
+var<storage, read> vertices: array<f32>;
+
+@group(0) @binding(1)
+var<storage, read> edges: array<u32>;
+
+@vertex
+fn vs_main(@builtin(vertex_index) end: u32) -> @builtin(position) vec4<f32> {
+    let base = edges[end] * constants.vertex_stride;
+    let position = vec3<f32>(vertices[base], vertices[base + 1u], vertices[base + 2u]);
+    
+    var clip = constants.object_to_clip * vec4<f32>(position, 1.0);
+    clip.z = max(clip.z - constants.depth_offset * clip.w, 0.0);
+    return clip;
+}
+
+@fragment
+fn fs_main() -> @location(0) vec4<f32> {
+    return constants.color;
+}
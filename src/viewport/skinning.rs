@@ -0,0 +1,194 @@
+//! CPU linear blend skinning for UsdSkel
+//!
+//! Builds joint world transforms from a skeleton's topology and animated
+//! local transforms, then deforms mesh points with the per-point joint
+//! influences authored through UsdSkelBindingAPI. Matrices use glam's
+//! column-vector convention; USD row-major matrices are converted by the
+//! caller.
+
+use glam::{Mat4, Vec3};
+
+/// Skeleton topology and bind pose
+#[derive(Debug, Clone, Default)]
+pub struct Skeleton {
+    /// Joint paths in skeleton order, e.g. "Hips/Spine/Chest"
+    pub joints: Vec<String>,
+    /// Parent joint index per joint, None for roots
+    pub parents: Vec<Option<usize>>,
+    /// World-space bind transform per joint
+    pub bind_transforms: Vec<Mat4>,
+}
+
+impl Skeleton {
+    /// Build a skeleton, deriving parents from the joint path hierarchy
+    pub fn new(joints: Vec<String>, bind_transforms: Vec<Mat4>) -> Result<Self, String> {
+        if joints.len() != bind_transforms.len() {
+            return Err(format!("Skeleton has {} joints but {} bind transforms", joints.len(), bind_transforms.len()));
+        }
+        let parents = joints.iter()
+            .enumerate()
+            .map(|(index, joint)| {
+                let mut path = joint.as_str();
+                while let Some((parent, _)) = path.rsplit_once('/') {
+                    if let Some(parent_index) = joints[..index].iter().position(|other| other == parent) {
+                        return Some(parent_index);
+                    }
+                    path = parent;
+                }
+                None
+            })
+            .collect();
+        Ok(Self { joints, parents, bind_transforms })
+    }
+    
+    /// Concatenate local joint transforms down the hierarchy into skeleton space
+    pub fn world_transforms(&self, local_transforms: &[Mat4]) -> Result<Vec<Mat4>, String> {
+        if local_transforms.len() != self.joints.len() {
+            return Err(format!("Expected {} joint transforms, got {}", self.joints.len(), local_transforms.len()));
+        }
+        let mut world = Vec::with_capacity(local_transforms.len());
+        for (index, local) in local_transforms.iter().enumerate() {
+            // Joint order lists parents before children
+            let transform = match self.parents[index] {
+                Some(parent) => world[parent] * *local,
+                None => *local,
+            };
+            world.push(transform);
+        }
+        Ok(world)
+    }
+    
+    /// Skinning transforms (joint world * inverse bind) for animated local transforms
+    pub fn skinning_transforms(&self, local_transforms: &[Mat4]) -> Result<Vec<Mat4>, String> {
+        Ok(self.world_transforms(local_transforms)?
+            .into_iter()
+            .zip(&self.bind_transforms)
+            .map(|(world, bind)| world * bind.inverse())
+            .collect())
+    }
+    
+    /// Map a skinned prim's joint order onto skeleton joint indices
+    pub fn joint_mapping(&self, prim_joints: &[String]) -> Result<Vec<usize>, String> {
+        prim_joints.iter()
+            .map(|joint| self.joints.iter()
+                .position(|other| other == joint)
+                .ok_or_else(|| format!("Joint '{}' is not part of the skeleton", joint)))
+            .collect()
+    }
+}
+
+/// Per-point joint influences from primvars:skel:jointIndices/jointWeights
+#[derive(Debug, Clone, Default)]
+pub struct JointInfluences {
+    pub indices: Vec<i32>,
+    pub weights: Vec<f32>,
+    /// Influences per point (elementSize); a constant interpolation binding has one set for all points
+    pub influences_per_point: usize,
+    pub constant: bool,
+}
+
+/// Deform points with linear blend skinning
+///
+/// `geom_bind_transform` moves points into the skeleton's bind space first.
+/// `mapping` remaps influence joint indices to skeleton joints when the prim
+/// authors its own `skel:joints` order.
+pub fn skin_points(
+    points: &[Vec3],
+    influences: &JointInfluences,
+    skinning_transforms: &[Mat4],
+    geom_bind_transform: Mat4,
+    mapping: Option<&[usize]>,
+) -> Result<Vec<Vec3>, String> {
+    let per_point = influences.influences_per_point.max(1);
+    let required = if influences.constant { per_point } else { points.len() * per_point };
+    if influences.indices.len() < required || influences.weights.len() < required {
+        return Err(format!("Expected {} joint influences, got {}", required, influences.indices.len().min(influences.weights.len())));
+    }
+    
+    let joint_transform = |index: i32| -> Result<Mat4, String> {
+        let index = usize::try_from(index).map_err(|_| format!("Negative joint index {}", index))?;
+        let joint = match mapping {
+            Some(mapping) => *mapping.get(index).ok_or_else(|| format!("Joint index {} is out of range", index))?,
+            None => index,
+        };
+        skinning_transforms.get(joint).copied().ok_or_else(|| format!("Joint index {} is out of range", joint))
+    };
+    
+    points.iter()
+        .enumerate()
+        .map(|(point_index, point)| {
+            let bind_point = geom_bind_transform.transform_point3(*point);
+            let base = if influences.constant { 0 } else { point_index * per_point };
+            
+            let mut skinned = Vec3::ZERO;
+            let mut total_weight = 0.0;
+            for influence in base..base + per_point {
+                let weight = influences.weights[influence];
+                if weight == 0.0 {
+                    continue;
+                }
+                skinned += joint_transform(influences.indices[influence])?.transform_point3(bind_point) * weight;
+                total_weight += weight;
+            }
+            
+            // Unweighted points keep their bind position
+            Ok(if total_weight > 0.0 { skinned / total_weight } else { bind_point })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn two_joint_skeleton() -> Skeleton {
+        Skeleton::new(
+            vec!["root".to_string(), "root/tip".to_string()],
+            vec![Mat4::IDENTITY, Mat4::from_translation(Vec3::Y)],
+        ).unwrap()
+    }
+    
+    #[test]
+    fn parents_come_from_joint_paths() {
+        let skeleton = two_joint_skeleton();
+        assert_eq!(skeleton.parents, vec![None, Some(0)]);
+    }
+    
+    #[test]
+    fn rest_pose_leaves_points_in_place() {
+        let skeleton = two_joint_skeleton();
+        let rest = [Mat4::IDENTITY, Mat4::from_translation(Vec3::Y)];
+        let transforms = skeleton.skinning_transforms(&rest).unwrap();
+        let influences = JointInfluences {
+            indices: vec![0, 1],
+            weights: vec![0.5, 0.5],
+            influences_per_point: 2,
+            constant: false,
+        };
+        let skinned = skin_points(&[Vec3::new(1.0, 1.0, 0.0)], &influences, &transforms, Mat4::IDENTITY, None).unwrap();
+        assert!((skinned[0] - Vec3::new(1.0, 1.0, 0.0)).length() < 1e-5);
+    }
+    
+    #[test]
+    fn animated_root_moves_children() {
+        let skeleton = two_joint_skeleton();
+        let animated = [Mat4::from_translation(Vec3::X), Mat4::from_translation(Vec3::Y)];
+        let transforms = skeleton.skinning_transforms(&animated).unwrap();
+        let influences = JointInfluences {
+            indices: vec![1],
+            weights: vec![1.0],
+            influences_per_point: 1,
+            constant: true,
+        };
+        let skinned = skin_points(&[Vec3::new(0.0, 2.0, 0.0)], &influences, &transforms, Mat4::IDENTITY, None).unwrap();
+        assert!((skinned[0] - Vec3::new(1.0, 2.0, 0.0)).length() < 1e-5);
+    }
+    
+    #[test]
+    fn rejects_missing_influences() {
+        let skeleton = two_joint_skeleton();
+        let transforms = skeleton.skinning_transforms(&[Mat4::IDENTITY; 2]).unwrap();
+        let influences = JointInfluences { influences_per_point: 1, ..Default::default() };
+        assert!(skin_points(&[Vec3::ZERO], &influences, &transforms, Mat4::IDENTITY, None).is_err());
+    }
+}
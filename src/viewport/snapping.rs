@@ -0,0 +1,88 @@
+//! Snapping of viewport manipulation
+//!
+//! Grid snapping rounds the translate values a gizmo drag changes to
+//! multiples of the grid size, and vertex snapping moves a translated prim
+//! along the dragged axis to the scene vertex under the cursor instead.
+//! Angle snapping rounds gizmo rotations and, optionally, camera orbits to
+//! multiples of an angle step, so layout done in the viewport authors clean
+//! values.
+
+use glam::{Mat4, Vec3};
+
+/// Angle a vertex may lie off the cursor ray and still be snapped to, in radians
+pub const VERTEX_SNAP_TOLERANCE: f32 = 0.02;
+
+/// Snapping options of a viewport; zero steps snap nothing
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct SnapSettings {
+    /// Grid size translate values snap to
+    pub grid: f32,
+    /// Snap translated prims onto scene vertices under the cursor
+    pub vertex: bool,
+    /// Degrees rotations snap to
+    pub angle: f32,
+    /// Scale factor steps
+    pub scale: f32,
+    /// Snap camera orbits to the angle step too
+    pub camera: bool,
+}
+
+/// Round a value to a multiple of a step, leaving it as is without one
+pub fn snap(value: f32, step: f32) -> f32 {
+    if step > 0.0 {
+        (value / step).round() * step
+    } else {
+        value
+    }
+}
+
+/// World-space vertex of the given meshes nearest a ray, within `VERTEX_SNAP_TOLERANCE` of it
+///
+/// Meshes are given as their vertex positions and their object to world transform.
+pub fn nearest_vertex<'a>(meshes: impl IntoIterator<Item = (&'a [f32], Mat4)>, origin: Vec3, direction: Vec3) -> Option<Vec3> {
+    meshes.into_iter()
+        .flat_map(|(vertices, transform)| vertices.chunks_exact(3).map(move |point| transform.transform_point3(Vec3::from_slice(point))))
+        .filter_map(|point| {
+            let along = (point - origin).dot(direction);
+            if along <= 0.0 {
+                return None;
+            }
+            let angle = (point - (origin + direction * along)).length() / along;
+            (angle <= VERTEX_SNAP_TOLERANCE).then_some((point, angle))
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(point, _)| point)
+}
+
+/// Camera orbit angles snapped to the angle step, clamping elevation short of the poles
+///
+/// Takes and returns (azimuth, elevation) in radians. Orbits are snapped
+/// from their unsnapped angles, kept by the caller, so small drags add up.
+pub fn snap_orbit(azimuth: f32, elevation: f32, step_degrees: f32) -> (f32, f32) {
+    let step = step_degrees.to_radians();
+    let limit = std::f32::consts::PI * 0.49;
+    (snap(azimuth, step), snap(elevation, step).clamp(-limit, limit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn snapping_to_steps_and_vertices() {
+        assert_eq!(snap(3.3, 0.5), 3.5);
+        assert_eq!(snap(3.3, 0.0), 3.3);
+        let (azimuth, elevation) = snap_orbit(0.3, 1.5, 15.0);
+        assert!((azimuth - 15f32.to_radians()).abs() < 1e-6);
+        assert!((elevation - std::f32::consts::PI * 0.49).abs() < 1e-6);
+        
+        // A triangle moved 10 units down the z axis, seen from the origin
+        let triangle = [0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+        let transform = Mat4::from_translation(Vec3::new(0.0, 0.0, -10.0));
+        let toward = |target: Vec3| target.normalize();
+        let meshes = || [(&triangle[..], transform)];
+        assert_eq!(nearest_vertex(meshes(), Vec3::ZERO, toward(Vec3::new(1.1, 0.05, -10.0))), Some(Vec3::new(1.0, 0.0, -10.0)));
+        assert_eq!(nearest_vertex(meshes(), Vec3::ZERO, toward(Vec3::new(0.5, 0.5, -10.0))), None);
+        assert_eq!(nearest_vertex(meshes(), Vec3::ZERO, Vec3::Z), None);
+    }
+}
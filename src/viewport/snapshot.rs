@@ -0,0 +1,115 @@
+//! Viewport snapshots through the wgpu renderer
+//!
+//! A snapshot renders the current view offscreen with the panel's shading,
+//! anti-aliasing, ambient occlusion and color management, and writes it with
+//! the capture settings' image writers, so snapshots can carry the depth,
+//! normal and Cryptomatte id AOVs as layers of an EXR. The renderer and its
+//! device are created by the first snapshot and kept for the next ones.
+
+use crate::capture::aov::Aov;
+use crate::capture::CaptureSettings;
+use super::renderer_3d::request_device;
+use super::usd_rendering::USDRenderer;
+
+/// Output and AOVs of viewport snapshots, with the renderer that draws them
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    /// Output path with `#` frame padding; the extension picks the format
+    pub output_pattern: String,
+    pub width: u32,
+    pub height: u32,
+    /// AOVs written as layers of EXR snapshots
+    pub aovs: Vec<Aov>,
+    /// Also write a prim ID matte plus a manifest
+    pub id_matte: bool,
+    /// Files the last snapshot wrote
+    pub written: Vec<String>,
+    renderer: Option<USDRenderer>,
+}
+
+impl Default for Snapshot {
+    fn default() -> Self {
+        Self {
+            output_pattern: "snapshot.####.exr".to_string(),
+            width: 1920,
+            height: 1080,
+            aovs: Vec::new(),
+            id_matte: false,
+            written: Vec::new(),
+            renderer: None,
+        }
+    }
+}
+
+impl Snapshot {
+    /// Capture settings of a snapshot
+    pub fn capture_settings(&self) -> CaptureSettings {
+        CaptureSettings {
+            output_pattern: self.output_pattern.clone(),
+            width: self.width.max(1),
+            height: self.height.max(1),
+            id_matte: self.id_matte,
+            aovs: self.aovs.clone(),
+            ..CaptureSettings::default()
+        }
+    }
+    
+    /// Turn an AOV on or off, keeping them in `Aov::ALL` order
+    pub fn set_aov(&mut self, aov: Aov, enabled: bool) {
+        self.aovs.retain(|&other| other != aov);
+        if enabled {
+            self.aovs.push(aov);
+            self.aovs.sort_by_key(|aov| Aov::ALL.iter().position(|other| other == aov));
+        }
+    }
+    
+    /// Renderer drawing the snapshots, created with a headless device on first use
+    pub fn renderer(&mut self) -> Result<&mut USDRenderer, String> {
+        let renderer = match self.renderer.take() {
+            Some(renderer) => renderer,
+            None => {
+                let (device, queue) = request_device()?;
+                let mut renderer = USDRenderer::new();
+                renderer.initialize(device, queue);
+                renderer
+            }
+        };
+        Ok(self.renderer.insert(renderer))
+    }
+    
+    /// Load a stage into the renderer, set up by `renderer()`, and write its frame at `time_code`
+    pub fn capture(&mut self, stage_id: &str, time_code: f64, fps: f64) -> Result<Vec<String>, String> {
+        let settings = self.capture_settings();
+        let renderer = self.renderer()?;
+        renderer.current_scene.time_code = time_code;
+        renderer.load_stage(stage_id)?;
+        if let Err(e) = renderer.render_hydra_frame(settings.width, settings.height) {
+            eprintln!("Hydra unavailable, snapshot drawn with wgpu: {}", e);
+        }
+        renderer.finish_path_trace_frame(settings.width, settings.height)?;
+        let written = renderer.capture_sequence(&settings, time_code, time_code, fps)?;
+        self.written = written.clone();
+        Ok(written)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn aovs_stay_in_layer_order() {
+        let mut snapshot = Snapshot::default();
+        snapshot.set_aov(Aov::Id, true);
+        snapshot.set_aov(Aov::Depth, true);
+        snapshot.set_aov(Aov::Depth, true);
+        assert_eq!(snapshot.aovs, vec![Aov::Depth, Aov::Id]);
+        
+        snapshot.set_aov(Aov::Depth, false);
+        snapshot.width = 0;
+        let settings = snapshot.capture_settings();
+        assert_eq!(settings.aovs, vec![Aov::Id]);
+        assert_eq!((settings.width, settings.height), (1, 1080));
+        assert_eq!(settings.output_pattern, "snapshot.####.exr");
+    }
+}
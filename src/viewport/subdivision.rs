@@ -0,0 +1,316 @@
+//! Catmull-Clark subdivision for USD meshes
+//!
+//! CPU refinement of UsdGeomMesh polygon topology for meshes authored with
+//! `subdivisionScheme = catmullClark`. Every refinement level turns each
+//! n-gon into n quads. Boundary edges use the crease rules and boundary
+//! corners stay pinned, matching USD's default `edgeAndCorner` boundary
+//! interpolation, so open meshes keep their outline.
+
+use glam::{Vec3, Vec4};
+use std::collections::HashMap;
+
+/// Refined polygon topology in the same layout as UsdGeomMesh
+#[derive(Debug, Clone, Default)]
+pub struct SubdividedMesh {
+    pub points: Vec<Vec3>,
+    pub face_vertex_counts: Vec<i32>,
+    pub face_vertex_indices: Vec<i32>,
+    /// Child faces of the original holeIndices faces
+    pub hole_indices: Vec<i32>,
+}
+
+/// Whether a subdivisionScheme token asks for Catmull-Clark refinement
+pub fn is_catmull_clark(scheme: &str) -> bool {
+    scheme == "catmullClark"
+}
+
+/// Apply `levels` rounds of Catmull-Clark refinement
+pub fn subdivide(
+    points: &[Vec3],
+    face_vertex_counts: &[i32],
+    face_vertex_indices: &[i32],
+    hole_indices: &[i32],
+    levels: u32,
+) -> Result<SubdividedMesh, String> {
+    let mut mesh = SubdividedMesh {
+        points: points.to_vec(),
+        face_vertex_counts: face_vertex_counts.to_vec(),
+        face_vertex_indices: face_vertex_indices.to_vec(),
+        hole_indices: hole_indices.to_vec(),
+    };
+    for _ in 0..levels {
+        mesh = subdivide_once(&mesh)?;
+    }
+    Ok(mesh)
+}
+
+/// Refine face-varying values, one per face-vertex, alongside `subdivide`
+///
+/// Values are interpolated linearly within each face, like OpenSubdiv's
+/// `faceVaryingLinearInterpolation = all`, so UV seams and per-face colors
+/// stay sharp. The result follows the refined faceVertexIndices order.
+pub fn subdivide_face_varying(values: &[Vec4], face_vertex_counts: &[i32], levels: u32) -> Result<Vec<Vec4>, String> {
+    let mut values = values.to_vec();
+    let mut counts = face_vertex_counts.to_vec();
+    for _ in 0..levels {
+        let mut refined = Vec::with_capacity(values.len() * 4);
+        let mut offset = 0usize;
+        for (face, &count) in counts.iter().enumerate() {
+            let n = count.max(0) as usize;
+            let corners = values.get(offset..offset + n)
+                .ok_or_else(|| format!("Face {} runs past the end of the face-varying values", face))?;
+            offset += n;
+            if n < 3 {
+                continue;
+            }
+            // Same child quad layout as subdivide_once: corner, next edge, face, previous edge
+            let center = corners.iter().sum::<Vec4>() / n as f32;
+            for i in 0..n {
+                let (previous, current, next) = (corners[(i + n - 1) % n], corners[i], corners[(i + 1) % n]);
+                refined.extend([current, (current + next) * 0.5, center, (previous + current) * 0.5]);
+            }
+        }
+        counts = vec![4; refined.len() / 4];
+        values = refined;
+    }
+    Ok(values)
+}
+
+/// Original face of every face `subdivide` produces, in refined face order
+///
+/// An n-gon refines into n quads and every later level splits each quad in
+/// four, all kept contiguous, so picks on the refined mesh map back to the
+/// authored faces.
+pub fn refined_face_origins(face_vertex_counts: &[i32], levels: u32) -> Vec<u32> {
+    if levels == 0 {
+        return (0..face_vertex_counts.len() as u32).collect();
+    }
+    let children_per_corner = 4usize.pow(levels - 1);
+    face_vertex_counts.iter().enumerate()
+        .filter(|(_, &count)| count >= 3)
+        .flat_map(|(face, &count)| std::iter::repeat_n(face as u32, count as usize * children_per_corner))
+        .collect()
+}
+
+fn subdivide_once(mesh: &SubdividedMesh) -> Result<SubdividedMesh, String> {
+    let points = &mesh.points;
+    let point_count = points.len();
+    
+    // Gather faces as index slices, validating topology
+    let mut faces: Vec<&[i32]> = Vec::with_capacity(mesh.face_vertex_counts.len());
+    let mut offset = 0usize;
+    for (face, &count) in mesh.face_vertex_counts.iter().enumerate() {
+        let count = count.max(0) as usize;
+        let face_indices = mesh.face_vertex_indices.get(offset..offset + count)
+            .ok_or_else(|| format!("Face {} runs past the end of faceVertexIndices", face))?;
+        if let Some(&bad) = face_indices.iter().find(|&&i| i < 0 || i as usize >= point_count) {
+            return Err(format!("Face {} references point {} out of {}", face, bad, point_count));
+        }
+        faces.push(face_indices);
+        offset += count;
+    }
+    
+    // Face points
+    let face_points: Vec<Vec3> = faces.iter()
+        .map(|face| face.iter().map(|&i| points[i as usize]).sum::<Vec3>() / face.len().max(1) as f32)
+        .collect();
+    
+    // Edge table: (low, high) -> (edge id, adjacent faces)
+    let mut edges: HashMap<(u32, u32), (usize, Vec<usize>)> = HashMap::new();
+    let mut edge_order: Vec<(u32, u32)> = Vec::new();
+    for (face, indices) in faces.iter().enumerate() {
+        if indices.len() < 3 {
+            continue;
+        }
+        for i in 0..indices.len() {
+            let a = indices[i] as u32;
+            let b = indices[(i + 1) % indices.len()] as u32;
+            let key = (a.min(b), a.max(b));
+            let next_id = edge_order.len();
+            let entry = edges.entry(key).or_insert_with(|| {
+                edge_order.push(key);
+                (next_id, Vec::new())
+            });
+            entry.1.push(face);
+        }
+    }
+    
+    // Edge points: smooth interior edges, midpoint on boundaries and non-manifold edges
+    let edge_points: Vec<Vec3> = edge_order.iter()
+        .map(|key| {
+            let (a, b) = (points[key.0 as usize], points[key.1 as usize]);
+            match edges[key].1.as_slice() {
+                [f0, f1] => (a + b + face_points[*f0] + face_points[*f1]) * 0.25,
+                _ => (a + b) * 0.5,
+            }
+        })
+        .collect();
+    
+    // Per-vertex adjacency for the vertex point rule
+    let mut vertex_faces: Vec<Vec<usize>> = vec![Vec::new(); point_count];
+    for (face, indices) in faces.iter().enumerate() {
+        if indices.len() >= 3 {
+            for &i in indices.iter() {
+                vertex_faces[i as usize].push(face);
+            }
+        }
+    }
+    let mut vertex_edges: Vec<Vec<(u32, u32)>> = vec![Vec::new(); point_count];
+    for key in &edge_order {
+        vertex_edges[key.0 as usize].push(*key);
+        vertex_edges[key.1 as usize].push(*key);
+    }
+    
+    let vertex_points: Vec<Vec3> = (0..point_count)
+        .map(|v| {
+            let position = points[v];
+            let adjacent_edges = &vertex_edges[v];
+            let boundary: Vec<&(u32, u32)> = adjacent_edges.iter()
+                .filter(|key| edges[key].1.len() != 2)
+                .collect();
+            
+            if adjacent_edges.is_empty() {
+                position
+            } else if boundary.is_empty() {
+                let n = vertex_faces[v].len() as f32;
+                let face_average = vertex_faces[v].iter().map(|&f| face_points[f]).sum::<Vec3>() / n;
+                let edge_average = adjacent_edges.iter()
+                    .map(|key| (points[key.0 as usize] + points[key.1 as usize]) * 0.5)
+                    .sum::<Vec3>() / adjacent_edges.len() as f32;
+                (face_average + 2.0 * edge_average + (n - 3.0) * position) / n
+            } else if boundary.len() == 2 && vertex_faces[v].len() > 1 {
+                let other = |key: &(u32, u32)| if key.0 as usize == v { key.1 } else { key.0 };
+                (6.0 * position + points[other(boundary[0]) as usize] + points[other(boundary[1]) as usize]) / 8.0
+            } else {
+                // Corners (edgeAndCorner boundary interpolation) and non-manifold vertices stay put
+                position
+            }
+        })
+        .collect();
+    
+    // New point layout: vertex points, edge points, face points
+    let edge_base = point_count;
+    let face_base = edge_base + edge_points.len();
+    let mut refined = SubdividedMesh {
+        points: vertex_points,
+        ..Default::default()
+    };
+    refined.points.extend(edge_points);
+    refined.points.extend(face_points);
+    
+    let edge_point = |a: i32, b: i32| -> i32 {
+        let key = ((a as u32).min(b as u32), (a as u32).max(b as u32));
+        (edge_base + edges[&key].0) as i32
+    };
+    
+    for (face, indices) in faces.iter().enumerate() {
+        let n = indices.len();
+        if n < 3 {
+            continue;
+        }
+        let is_hole = mesh.hole_indices.contains(&(face as i32));
+        for i in 0..n {
+            let previous = indices[(i + n - 1) % n];
+            let current = indices[i];
+            let next = indices[(i + 1) % n];
+            if is_hole {
+                refined.hole_indices.push(refined.face_vertex_counts.len() as i32);
+            }
+            refined.face_vertex_counts.push(4);
+            refined.face_vertex_indices.extend([
+                current,
+                edge_point(current, next),
+                (face_base + face) as i32,
+                edge_point(previous, current),
+            ]);
+        }
+    }
+    
+    Ok(refined)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn cube() -> (Vec<Vec3>, Vec<i32>, Vec<i32>) {
+        let points = vec![
+            Vec3::new(-1.0, -1.0, -1.0), Vec3::new(1.0, -1.0, -1.0),
+            Vec3::new(1.0, 1.0, -1.0), Vec3::new(-1.0, 1.0, -1.0),
+            Vec3::new(-1.0, -1.0, 1.0), Vec3::new(1.0, -1.0, 1.0),
+            Vec3::new(1.0, 1.0, 1.0), Vec3::new(-1.0, 1.0, 1.0),
+        ];
+        let indices = vec![
+            0, 3, 2, 1, 4, 5, 6, 7, 0, 1, 5, 4,
+            1, 2, 6, 5, 2, 3, 7, 6, 3, 0, 4, 7,
+        ];
+        (points, vec![4; 6], indices)
+    }
+    
+    #[test]
+    fn cube_refines_to_expected_counts() {
+        let (points, counts, indices) = cube();
+        let refined = subdivide(&points, &counts, &indices, &[], 1).unwrap();
+        // 8 vertices + 12 edges + 6 faces
+        assert_eq!(refined.points.len(), 26);
+        assert_eq!(refined.face_vertex_counts.len(), 24);
+        assert!(refined.face_vertex_counts.iter().all(|&c| c == 4));
+        
+        let refined = subdivide(&points, &counts, &indices, &[], 2).unwrap();
+        assert_eq!(refined.face_vertex_counts.len(), 96);
+    }
+    
+    #[test]
+    fn cube_corners_move_inward() {
+        let (points, counts, indices) = cube();
+        let refined = subdivide(&points, &counts, &indices, &[], 1).unwrap();
+        // Valence-3 corner: (F + 2R + 0P) / 3 with F = 1/3, R = 2/3 per axis
+        let corner = refined.points[6];
+        assert!((corner - Vec3::splat(5.0 / 9.0)).length() < 1e-5);
+    }
+    
+    #[test]
+    fn open_quad_keeps_boundary_corners_and_holes() {
+        let points = vec![
+            Vec3::new(0.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(1.0, 1.0, 0.0), Vec3::new(0.0, 1.0, 0.0),
+        ];
+        let refined = subdivide(&points, &[4], &[0, 1, 2, 3], &[0], 1).unwrap();
+        assert_eq!(refined.points[0], points[0]);
+        assert_eq!(refined.hole_indices, vec![0, 1, 2, 3]);
+    }
+    
+    #[test]
+    fn face_varying_values_refine_linearly_per_face() {
+        let values = [Vec4::ZERO, Vec4::X, Vec4::new(1.0, 1.0, 0.0, 0.0), Vec4::Y];
+        let refined = subdivide_face_varying(&values, &[4], 1).unwrap();
+        assert_eq!(refined.len(), 16);
+        assert_eq!(&refined[..4], &[Vec4::ZERO, Vec4::new(0.5, 0.0, 0.0, 0.0), Vec4::new(0.5, 0.5, 0.0, 0.0), Vec4::new(0.0, 0.5, 0.0, 0.0)]);
+        
+        // Each refined face-vertex lines up with the refined topology
+        let points = vec![Vec3::ZERO, Vec3::X, Vec3::new(1.0, 1.0, 0.0), Vec3::Y];
+        let mesh = subdivide(&points, &[4], &[0, 1, 2, 3], &[], 2).unwrap();
+        assert_eq!(subdivide_face_varying(&values, &[4], 2).unwrap().len(), mesh.face_vertex_indices.len());
+        assert!(subdivide_face_varying(&values[..3], &[4], 1).is_err());
+    }
+    
+    #[test]
+    fn refined_faces_map_back_to_their_origin() {
+        let points = vec![Vec3::ZERO, Vec3::X, Vec3::new(1.0, 1.0, 0.0), Vec3::Y, Vec3::new(2.0, 0.0, 0.0)];
+        let (counts, indices) = ([4, 2, 3], [0, 1, 2, 3, 0, 1, 1, 4, 2]);
+        for levels in 0..3 {
+            let mesh = subdivide(&points, &counts, &indices, &[], levels).unwrap();
+            let origins = refined_face_origins(&counts, levels);
+            assert_eq!(origins.len(), mesh.face_vertex_counts.len());
+            assert_eq!(origins.last(), Some(&2));
+        }
+        assert_eq!(refined_face_origins(&counts, 1), [0, 0, 0, 0, 2, 2, 2]);
+    }
+    
+    #[test]
+    fn rejects_bad_topology() {
+        let points = vec![Vec3::ZERO; 3];
+        assert!(subdivide(&points, &[3], &[0, 1, 5], &[], 1).is_err());
+        assert!(subdivide(&points, &[4], &[0, 1, 2], &[], 1).is_err());
+    }
+}
@@ -0,0 +1,84 @@
+//! Tangent frames for normal mapping
+//!
+//! Tangents follow the MikkTSpace construction: every triangle's tangent and
+//! bitangent are solved from its st derivatives, normalized, weighted by the
+//! corner angle and summed per vertex, then orthogonalized against the vertex
+//! normal. Only the tangent is stored; its w holds the bitangent's handedness
+//! so usd_mesh.wgsl rebuilds the bitangent as `cross(normal, tangent) * w`,
+//! which keeps mirrored UV islands bumping the right way.
+
+use glam::{Vec2, Vec3, Vec4};
+
+/// Smallest st-space triangle area, times two, that still defines a tangent
+const MIN_ST_AREA: f32 = 1e-12;
+
+/// One tangent per vertex, xyz the unit tangent along +s and w the handedness
+///
+/// Vertices whose triangles all have degenerate st get an arbitrary tangent
+/// perpendicular to their normal.
+pub fn generate_tangents(positions: &[Vec3], normals: &[Vec3], st: &[Vec2], indices: &[u32]) -> Vec<Vec4> {
+    let mut tangents = vec![Vec3::ZERO; positions.len()];
+    let mut bitangents = vec![Vec3::ZERO; positions.len()];
+    for triangle in indices.chunks_exact(3) {
+        let [a, b, c] = [triangle[0] as usize, triangle[1] as usize, triangle[2] as usize];
+        let (edge1, edge2) = (positions[b] - positions[a], positions[c] - positions[a]);
+        let (st1, st2) = (st[b] - st[a], st[c] - st[a]);
+        let determinant = st1.x * st2.y - st2.x * st1.y;
+        if determinant.abs() < MIN_ST_AREA {
+            continue;
+        }
+        let tangent = ((edge1 * st2.y - edge2 * st1.y) / determinant).normalize_or_zero();
+        let bitangent = ((edge2 * st1.x - edge1 * st2.x) / determinant).normalize_or_zero();
+        for (corner, previous, next) in [(a, c, b), (b, a, c), (c, b, a)] {
+            let to_previous = (positions[previous] - positions[corner]).normalize_or_zero();
+            let to_next = (positions[next] - positions[corner]).normalize_or_zero();
+            let angle = to_previous.dot(to_next).clamp(-1.0, 1.0).acos();
+            tangents[corner] += tangent * angle;
+            bitangents[corner] += bitangent * angle;
+        }
+    }
+    
+    normals.iter().zip(tangents.iter().zip(&bitangents))
+        .map(|(normal, (tangent, bitangent))| {
+            let normal = normal.normalize_or_zero();
+            let tangent = (*tangent - normal * normal.dot(*tangent))
+                .try_normalize()
+                .unwrap_or_else(|| normal.any_orthonormal_vector());
+            let handedness = if normal.cross(tangent).dot(*bitangent) < 0.0 { -1.0 } else { 1.0 };
+            tangent.extend(handedness)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn quad() -> (Vec<Vec3>, Vec<Vec3>, Vec<u32>) {
+        let positions = vec![Vec3::ZERO, Vec3::X, Vec3::new(1.0, 1.0, 0.0), Vec3::Y];
+        (positions, vec![Vec3::Z; 4], vec![0, 1, 2, 0, 2, 3])
+    }
+    
+    #[test]
+    fn tangents_follow_s_and_flip_handedness_when_mirrored() {
+        let (positions, normals, indices) = quad();
+        let st: Vec<Vec2> = positions.iter().map(|position| position.truncate()).collect();
+        for tangent in generate_tangents(&positions, &normals, &st, &indices) {
+            assert!(tangent.distance(Vec4::new(1.0, 0.0, 0.0, 1.0)) < 1e-5);
+        }
+        
+        let mirrored: Vec<Vec2> = st.iter().map(|st| Vec2::new(-st.x, st.y)).collect();
+        for tangent in generate_tangents(&positions, &normals, &mirrored, &indices) {
+            assert!(tangent.distance(Vec4::new(-1.0, 0.0, 0.0, -1.0)) < 1e-5);
+        }
+    }
+    
+    #[test]
+    fn degenerate_st_still_gives_a_perpendicular_tangent() {
+        let (positions, normals, indices) = quad();
+        for tangent in generate_tangents(&positions, &normals, &[Vec2::ZERO; 4], &indices) {
+            assert!((tangent.truncate().length() - 1.0).abs() < 1e-5);
+            assert!(tangent.truncate().dot(Vec3::Z).abs() < 1e-5);
+        }
+    }
+}
@@ -0,0 +1,491 @@
+//! UsdUVTexture image loading and GPU upload
+//!
+//! Images are decoded once per resolved asset path into a shared cache and
+//! uploaded with a full mip chain built on the CPU. 8-bit images are stored as
+//! Rgba8Unorm with an sRGB view format, so one upload serves both readings of
+//! `sourceColorSpace`; float images (EXR) are stored as linear Rgba16Float.
+//! Shading is linear, so color textures are decoded from sRGB on sampling
+//! and data textures (normals, roughness, 16-bit and single channel maps)
+//! are sampled as stored. Image sequence textures bind the frame of the
+//! scene's time code, as the live viewport shows them.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use bytemuck::{Pod, Zeroable};
+use image::imageops::{self, FilterType};
+use once_cell::sync::Lazy;
+use crate::core::usdz::{self, PackagePath};
+use super::preview_surface::{InputSource, TextureInput};
+use super::image_sequence::ImageSequence;
+
+/// Surface inputs with a texture slot in the material bind group, in slot order
+/// (usd_mesh.wgsl group 1)
+pub const TEXTURE_SLOTS: [&str; 5] = ["diffuseColor", "roughness", "metallic", "emissiveColor", "normal"];
+
+/// Texel storage of an uploaded image
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TexelFormat {
+    Rgba8,
+    Rgba16Float,
+}
+
+impl TexelFormat {
+    fn bytes_per_texel(&self) -> u32 {
+        match self {
+            TexelFormat::Rgba8 => 4,
+            TexelFormat::Rgba16Float => 8,
+        }
+    }
+    
+    fn wgpu_format(&self) -> wgpu::TextureFormat {
+        match self {
+            TexelFormat::Rgba8 => wgpu::TextureFormat::Rgba8Unorm,
+            TexelFormat::Rgba16Float => wgpu::TextureFormat::Rgba16Float,
+        }
+    }
+}
+
+/// A decoded image with its mip levels, largest first
+#[derive(Debug, Clone)]
+pub struct MipChain {
+    pub width: u32,
+    pub height: u32,
+    pub format: TexelFormat,
+    /// Whether `auto` reads the image as sRGB, see `uses_srgb`
+    pub auto_srgb: bool,
+    pub levels: Vec<Vec<u8>>,
+}
+
+/// Sizes of every mip level down to 1x1, largest first
+pub fn mip_sizes(width: u32, height: u32) -> Vec<(u32, u32)> {
+    let mut sizes = vec![(width.max(1), height.max(1))];
+    while let Some(&(w, h)) = sizes.last().filter(|&&(w, h)| w > 1 || h > 1) {
+        sizes.push(((w / 2).max(1), (h / 2).max(1)));
+    }
+    sizes
+}
+
+/// File a texture reads at a time code: the frame of an image sequence, or the file itself
+///
+/// Sequences hold their nearest earlier frame, as `ImageSequence::file_at`
+/// does; patterns without any frame on disk are returned as authored, so
+/// loading them reports the missing file.
+pub fn texture_file_at(file: &str, time_code: f64) -> String {
+    ImageSequence::open(file)
+        .and_then(|sequence| sequence.file_at(time_code).map(|path| path.to_string_lossy().into_owned()))
+        .unwrap_or_else(|| file.to_string())
+}
+
+/// Decode an image file, or an image inside a USDZ package, with its mip chain
+pub fn decode_texture(path: &str) -> Result<MipChain, String> {
+    let file = match PackagePath::parse(path) {
+        Some(asset) => usdz::extract_texture(&asset)?,
+        None => std::path::PathBuf::from(path),
+    };
+    let image = image::open(&file).map_err(|e| format!("Failed to load texture '{}': {}", path, e))?;
+    let (width, height) = (image.width(), image.height());
+    let sizes = mip_sizes(width, height);
+    
+    let float = matches!(image.color(), image::ColorType::Rgb32F | image::ColorType::Rgba32F);
+    let auto_srgb = matches!(image.color(), image::ColorType::Rgb8 | image::ColorType::Rgba8);
+    let levels = if float {
+        let base = image.into_rgba32f();
+        sizes.iter()
+            .map(|&(w, h)| {
+                let level = if (w, h) == (width, height) { base.clone() } else { imageops::resize(&base, w, h, FilterType::Triangle) };
+                level.into_raw().into_iter().flat_map(|value| f32_to_f16(value).to_le_bytes()).collect()
+            })
+            .collect()
+    } else {
+        let base = image.into_rgba8();
+        sizes.iter()
+            .map(|&(w, h)| match (w, h) == (width, height) {
+                true => base.clone().into_raw(),
+                false => imageops::resize(&base, w, h, FilterType::Triangle).into_raw(),
+            })
+            .collect()
+    };
+    Ok(MipChain {
+        width,
+        height,
+        format: if float { TexelFormat::Rgba16Float } else { TexelFormat::Rgba8 },
+        auto_srgb,
+        levels,
+    })
+}
+
+/// Convert to IEEE half precision bits, rounding to nearest
+pub fn f32_to_f16(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7f_ffff;
+    if exponent == 0xff {
+        // Infinity stays infinity, NaN stays a quiet NaN
+        return sign | 0x7c00 | if mantissa != 0 { 0x200 } else { 0 };
+    }
+    let half_exponent = exponent - 127 + 15;
+    if half_exponent >= 0x1f {
+        return sign | 0x7c00;
+    }
+    if half_exponent <= 0 {
+        if half_exponent < -10 {
+            return sign;
+        }
+        // Subnormal: shift the implicit leading one into the mantissa
+        let mantissa = mantissa | 0x80_0000;
+        let shift = (14 - half_exponent) as u32;
+        return sign | ((mantissa + (1 << (shift - 1))) >> shift) as u16;
+    }
+    // A rounding carry out of the mantissa correctly bumps the exponent
+    sign | ((((half_exponent as u32) << 10) | (mantissa >> 13)) + ((mantissa >> 12) & 1)) as u16
+}
+
+/// Whether a texture is read through its sRGB view
+///
+/// `auto` decides as Hio does: 8-bit RGB and RGBA images are sRGB, while
+/// single channel, 16-bit and float images hold data and are linear. Float
+/// images are always linear, since there is no sRGB half-float format.
+pub fn uses_srgb(source_color_space: &str, format: TexelFormat, auto_srgb: bool) -> bool {
+    format == TexelFormat::Rgba8 && match source_color_space {
+        "sRGB" => true,
+        "raw" => false,
+        _ => auto_srgb,
+    }
+}
+
+/// Sampler address mode for a UsdUVTexture wrapS/wrapT token
+///
+/// Image files rarely carry wrap metadata, so `useMetadata` takes the spec's
+/// fallback of `black`. Black needs clamp-to-border support and otherwise
+/// clamps to the edge texels.
+pub fn address_mode(wrap: &str, border_supported: bool) -> wgpu::AddressMode {
+    match wrap {
+        "repeat" => wgpu::AddressMode::Repeat,
+        "mirror" => wgpu::AddressMode::MirrorRepeat,
+        "clamp" => wgpu::AddressMode::ClampToEdge,
+        _ if border_supported => wgpu::AddressMode::ClampToBorder,
+        _ => wgpu::AddressMode::ClampToEdge,
+    }
+}
+
+/// Channel selector of a texture output (usd_mesh.wgsl `texture_input`)
+pub fn output_channel(output: &str) -> u32 {
+    match output {
+        "r" => 1,
+        "g" => 2,
+        "b" => 3,
+        "a" => 4,
+        _ => 0,
+    }
+}
+
+/// An uploaded image, viewable as sRGB or linear
+#[derive(Debug)]
+pub struct GpuTexture {
+    pub texture: wgpu::Texture,
+    pub format: TexelFormat,
+    /// `MipChain::auto_srgb` of the image
+    pub auto_srgb: bool,
+}
+
+impl GpuTexture {
+    /// Create a texture and upload every mip level
+    pub fn upload(device: &wgpu::Device, queue: &wgpu::Queue, label: &str, chain: &MipChain) -> Self {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some(label),
+            size: wgpu::Extent3d { width: chain.width, height: chain.height, depth_or_array_layers: 1 },
+            mip_level_count: chain.levels.len() as u32,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: chain.format.wgpu_format(),
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: match chain.format {
+                TexelFormat::Rgba8 => &[wgpu::TextureFormat::Rgba8UnormSrgb],
+                TexelFormat::Rgba16Float => &[],
+            },
+        });
+        for (level, ((width, height), data)) in mip_sizes(chain.width, chain.height).into_iter().zip(&chain.levels).enumerate() {
+            queue.write_texture(
+                wgpu::TexelCopyTextureInfo {
+                    texture: &texture,
+                    mip_level: level as u32,
+                    origin: wgpu::Origin3d::ZERO,
+                    aspect: wgpu::TextureAspect::All,
+                },
+                data,
+                wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(width * chain.format.bytes_per_texel()),
+                    rows_per_image: Some(height),
+                },
+                wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+            );
+        }
+        Self { texture, format: chain.format, auto_srgb: chain.auto_srgb }
+    }
+    
+    pub fn view(&self, srgb: bool) -> wgpu::TextureView {
+        self.texture.create_view(&wgpu::TextureViewDescriptor {
+            format: (srgb && self.format == TexelFormat::Rgba8).then_some(wgpu::TextureFormat::Rgba8UnormSrgb),
+            ..Default::default()
+        })
+    }
+}
+
+/// Uploaded textures keyed by resolved asset path
+///
+/// Failed loads are cached too, so a missing file is reported once rather
+/// than on every stage reload.
+#[derive(Debug, Default)]
+pub struct TextureCache {
+    textures: HashMap<String, Result<Arc<GpuTexture>, String>>,
+}
+
+impl TextureCache {
+    pub fn get_or_load(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, path: &str) -> Result<Arc<GpuTexture>, String> {
+        self.textures.entry(path.to_string())
+            .or_insert_with(|| decode_texture(path).map(|chain| Arc::new(GpuTexture::upload(device, queue, path, &chain))))
+            .clone()
+    }
+    
+    /// Drop every texture, e.g. after files changed on disk
+    pub fn clear(&mut self) {
+        self.textures.clear();
+    }
+}
+
+/// Texture cache shared by every viewport and material preview on the device
+pub static TEXTURE_CACHE: Lazy<Mutex<TextureCache>> = Lazy::new(|| Mutex::new(TextureCache::default()));
+
+pub fn with_texture_cache<F, R>(f: F) -> R
+where
+    F: FnOnce(&mut TextureCache) -> R,
+{
+    let mut cache = TEXTURE_CACHE.lock().unwrap();
+    f(&mut cache)
+}
+
+/// Scale, bias and channel of each texture slot (usd_mesh.wgsl `MaterialTextures`)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+pub struct MaterialTextureUniform {
+    pub scale: [[f32; 4]; 5],
+    pub bias: [[f32; 4]; 5],
+    /// Channel selector per slot, packed four to a vec4 in the shader
+    pub channels: [u32; 8],
+    /// Bit i set when slot i is textured
+    pub mask: u32,
+    pub _padding: [u32; 3],
+}
+
+/// Material texture bind groups for group 1 of usd_mesh.wgsl
+pub struct MaterialTextureBindings {
+    pub layout: wgpu::BindGroupLayout,
+    /// Bound for untextured materials and outside material preview
+    pub untextured: wgpu::BindGroup,
+    /// Bind groups of materials with at least one loaded texture, keyed by material path
+    pub materials: HashMap<String, wgpu::BindGroup>,
+    white: wgpu::TextureView,
+    border_supported: bool,
+}
+
+impl MaterialTextureBindings {
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue) -> Self {
+        let mut entries = vec![wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        }];
+        for slot in 0..TEXTURE_SLOTS.len() as u32 {
+            entries.push(wgpu::BindGroupLayoutEntry {
+                binding: 1 + slot * 2,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Texture {
+                    sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            });
+            entries.push(wgpu::BindGroupLayoutEntry {
+                binding: 2 + slot * 2,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                count: None,
+            });
+        }
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("usd_material_textures"),
+            entries: &entries,
+        });
+        
+        let white = GpuTexture::upload(device, queue, "usd_white_texture", &MipChain {
+            width: 1,
+            height: 1,
+            format: TexelFormat::Rgba8,
+            auto_srgb: false,
+            levels: vec![vec![255; 4]],
+        }).view(false);
+        let border_supported = device.features().contains(wgpu::Features::ADDRESS_MODE_CLAMP_TO_BORDER);
+        Self {
+            untextured: create_bind_group(device, &layout, &white, border_supported, "usd_untextured_material", &[None, None, None, None, None]),
+            layout,
+            materials: HashMap::new(),
+            white,
+            border_supported,
+        }
+    }
+    
+    /// Rebuild the bind groups of every textured material
+    ///
+    /// Textures that fail to load leave their slot on the input's fallback
+    /// value; the failures are returned as messages.
+    pub fn rebuild<'a>(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, materials: impl IntoIterator<Item = (&'a String, &'a HashMap<String, InputSource>)>, time_code: f64) -> Vec<String> {
+        self.materials.clear();
+        let mut errors = Vec::new();
+        for (material_path, connections) in materials {
+            let slots = TEXTURE_SLOTS.map(|input| match connections.get(input) {
+                Some(InputSource::Texture(texture)) if !texture.file.is_empty() => {
+                    let file = texture_file_at(&texture.file, time_code);
+                    match with_texture_cache(|cache| cache.get_or_load(device, queue, &file)) {
+                        Ok(gpu) => Some((texture, gpu)),
+                        Err(e) => {
+                            errors.push(e);
+                            None
+                        }
+                    }
+                }
+                _ => None,
+            });
+            if slots.iter().any(Option::is_some) {
+                let slots = slots.each_ref().map(|slot| slot.as_ref().map(|(texture, gpu)| (*texture, gpu.as_ref())));
+                let bind_group = create_bind_group(device, &self.layout, &self.white, self.border_supported, material_path, &slots);
+                self.materials.insert(material_path.clone(), bind_group);
+            }
+        }
+        errors
+    }
+    
+    /// Bind group for a material, falling back to the untextured one
+    pub fn bind_group(&self, material_path: &str) -> &wgpu::BindGroup {
+        self.materials.get(material_path).unwrap_or(&self.untextured)
+    }
+}
+
+/// Bind group of one material's texture slots, with the white texture in empty slots
+fn create_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    white: &wgpu::TextureView,
+    border_supported: bool,
+    label: &str,
+    slots: &[Option<(&TextureInput, &GpuTexture)>; 5],
+) -> wgpu::BindGroup {
+    let mut uniform = MaterialTextureUniform::zeroed();
+    let mut views = Vec::new();
+    let mut samplers = Vec::new();
+    for (index, slot) in slots.iter().enumerate() {
+        let (view, wrap) = match slot {
+            Some((texture, gpu)) => {
+                uniform.scale[index] = texture.scale.to_array();
+                uniform.bias[index] = texture.bias.to_array();
+                uniform.channels[index] = output_channel(&texture.output);
+                uniform.mask |= 1 << index;
+                (gpu.view(uses_srgb(&texture.source_color_space, gpu.format, gpu.auto_srgb)), [texture.wrap_s.as_str(), texture.wrap_t.as_str()])
+            }
+            None => (white.clone(), ["repeat"; 2]),
+        };
+        views.push(view);
+        samplers.push(device.create_sampler(&wgpu::SamplerDescriptor {
+            label: None,
+            address_mode_u: address_mode(wrap[0], border_supported),
+            address_mode_v: address_mode(wrap[1], border_supported),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Linear,
+            border_color: Some(wgpu::SamplerBorderColor::TransparentBlack),
+            ..Default::default()
+        }));
+    }
+    
+    let buffer = wgpu::util::DeviceExt::create_buffer_init(device, &wgpu::util::BufferInitDescriptor {
+        label: Some(label),
+        contents: bytemuck::bytes_of(&uniform),
+        usage: wgpu::BufferUsages::UNIFORM,
+    });
+    let mut entries = vec![wgpu::BindGroupEntry { binding: 0, resource: buffer.as_entire_binding() }];
+    for (slot, (view, sampler)) in views.iter().zip(&samplers).enumerate() {
+        let slot = slot as u32;
+        entries.push(wgpu::BindGroupEntry { binding: 1 + slot * 2, resource: wgpu::BindingResource::TextureView(view) });
+        entries.push(wgpu::BindGroupEntry { binding: 2 + slot * 2, resource: wgpu::BindingResource::Sampler(sampler) });
+    }
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some(label),
+        layout,
+        entries: &entries,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn mip_chain_halves_down_to_one_texel() {
+        assert_eq!(mip_sizes(8, 2), vec![(8, 2), (4, 1), (2, 1), (1, 1)]);
+        assert_eq!(mip_sizes(1, 1), vec![(1, 1)]);
+        assert_eq!(mip_sizes(5, 3).len(), 3);
+    }
+    
+    #[test]
+    fn half_floats_round_and_saturate() {
+        assert_eq!(f32_to_f16(1.0), 0x3c00);
+        assert_eq!(f32_to_f16(-2.0), 0xc000);
+        assert_eq!(f32_to_f16(0.5), 0x3800);
+        assert_eq!(f32_to_f16(65504.0), 0x7bff);
+        assert_eq!(f32_to_f16(1.0e6), 0x7c00);
+        assert_eq!(f32_to_f16(2.0f32.powi(-24)), 0x0001);
+        assert_eq!(f32_to_f16(0.0), 0);
+        assert_eq!(f32_to_f16(f32::NAN) & 0x7e00, 0x7e00);
+    }
+    
+    #[test]
+    fn sequence_textures_read_the_frame_of_the_time_code() {
+        let directory = std::env::temp_dir().join(format!("nodle_texture_frames_{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        for frame in [1001, 1003] {
+            std::fs::write(directory.join(format!("paint.{}.png", frame)), []).unwrap();
+        }
+        let pattern = directory.join("paint.####.png").to_string_lossy().into_owned();
+        let frame = |time_code: f64| texture_file_at(&pattern, time_code);
+        assert!(frame(1001.0).ends_with("paint.1001.png"));
+        assert!(frame(1002.5).ends_with("paint.1001.png"));
+        assert!(frame(1004.0).ends_with("paint.1003.png"));
+        let plain = directory.join("albedo.png").to_string_lossy().into_owned();
+        assert_eq!(texture_file_at(&plain, 1001.0), plain);
+        std::fs::remove_dir_all(&directory).ok();
+    }
+    
+    #[test]
+    fn color_space_and_wrap_follow_usd_uv_texture() {
+        assert!(uses_srgb("auto", TexelFormat::Rgba8, true));
+        assert!(!uses_srgb("auto", TexelFormat::Rgba8, false));
+        assert!(uses_srgb("sRGB", TexelFormat::Rgba8, false));
+        assert!(!uses_srgb("raw", TexelFormat::Rgba8, true));
+        assert!(!uses_srgb("auto", TexelFormat::Rgba16Float, true));
+        assert!(!uses_srgb("sRGB", TexelFormat::Rgba16Float, true));
+        
+        assert_eq!(address_mode("repeat", false), wgpu::AddressMode::Repeat);
+        assert_eq!(address_mode("mirror", false), wgpu::AddressMode::MirrorRepeat);
+        assert_eq!(address_mode("useMetadata", true), wgpu::AddressMode::ClampToBorder);
+        assert_eq!(address_mode("black", false), wgpu::AddressMode::ClampToEdge);
+        assert_eq!(output_channel("g"), 2);
+        assert_eq!(output_channel("rgb"), 0);
+    }
+}
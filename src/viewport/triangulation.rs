@@ -0,0 +1,268 @@
+//! Polygon triangulation for USD meshes
+//!
+//! Converts UsdGeomMesh faceVertexCounts/faceVertexIndices topology into
+//! triangle index buffers. Triangles and convex polygons are fanned, concave
+//! polygons go through ear clipping. Faces listed in holeIndices are skipped.
+
+use glam::{Vec2, Vec3};
+
+/// Triangulated mesh topology ready for upload
+#[derive(Debug, Clone, Default)]
+pub struct TriangulatedMesh {
+    /// Triangle list indices into the mesh points
+    pub indices: Vec<u32>,
+    /// Source face index for every triangle
+    pub face_ids: Vec<u32>,
+    /// Face-vertex index (position in faceVertexIndices) for every triangle corner,
+    /// used to look up faceVarying data
+    pub face_vertex_ids: Vec<u32>,
+    /// Line list of the original polygon edges, for quad/n-gon wireframe display
+    pub edge_indices: Vec<u32>,
+}
+
+impl TriangulatedMesh {
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len() / 3
+    }
+}
+
+/// Triangulate USD polygon topology
+pub fn triangulate(
+    points: &[Vec3],
+    face_vertex_counts: &[i32],
+    face_vertex_indices: &[i32],
+    hole_indices: &[i32],
+) -> Result<TriangulatedMesh, String> {
+    let expected: i64 = face_vertex_counts.iter().map(|&count| count.max(0) as i64).sum();
+    if expected != face_vertex_indices.len() as i64 {
+        return Err(format!(
+            "faceVertexCounts sum to {} but faceVertexIndices has {} entries",
+            expected,
+            face_vertex_indices.len()
+        ));
+    }
+    
+    let mut mesh = TriangulatedMesh::default();
+    let mut edges = std::collections::HashSet::new();
+    let mut offset = 0usize;
+    
+    for (face, &count) in face_vertex_counts.iter().enumerate() {
+        let count = count.max(0) as usize;
+        let face_offset = offset;
+        offset += count;
+        
+        if count < 3 || hole_indices.contains(&(face as i32)) {
+            continue;
+        }
+        
+        let face_indices = &face_vertex_indices[face_offset..face_offset + count];
+        let mut corners = Vec::with_capacity(count);
+        for &index in face_indices {
+            if index < 0 || index as usize >= points.len() {
+                return Err(format!("Face {} references point {} out of {}", face, index, points.len()));
+            }
+            corners.push(points[index as usize]);
+        }
+        
+        // Original polygon edges for wireframe display
+        for i in 0..count {
+            let a = face_indices[i] as u32;
+            let b = face_indices[(i + 1) % count] as u32;
+            if edges.insert((a.min(b), a.max(b))) {
+                mesh.edge_indices.push(a);
+                mesh.edge_indices.push(b);
+            }
+        }
+        
+        for [a, b, c] in triangulate_polygon(&corners) {
+            for corner in [a, b, c] {
+                mesh.indices.push(face_indices[corner] as u32);
+                mesh.face_vertex_ids.push((face_offset + corner) as u32);
+            }
+            mesh.face_ids.push(face as u32);
+        }
+    }
+    
+    Ok(mesh)
+}
+
+/// Triangulate a single polygon, returning corner triples in the polygon's winding order
+pub fn triangulate_polygon(corners: &[Vec3]) -> Vec<[usize; 3]> {
+    let count = corners.len();
+    if count < 3 {
+        return Vec::new();
+    }
+    if count == 3 {
+        return vec![[0, 1, 2]];
+    }
+    
+    let projected = project_to_plane(corners);
+    if is_convex(&projected) {
+        return (1..count - 1).map(|i| [0, i, i + 1]).collect();
+    }
+    ear_clip(&projected)
+}
+
+/// Project polygon corners onto the plane of its Newell normal
+fn project_to_plane(corners: &[Vec3]) -> Vec<Vec2> {
+    let mut normal = Vec3::ZERO;
+    for i in 0..corners.len() {
+        let current = corners[i];
+        let next = corners[(i + 1) % corners.len()];
+        normal.x += (current.y - next.y) * (current.z + next.z);
+        normal.y += (current.z - next.z) * (current.x + next.x);
+        normal.z += (current.x - next.x) * (current.y + next.y);
+    }
+    
+    // Drop the dominant axis, keeping a right-handed 2D frame so winding is preserved
+    let abs = normal.abs();
+    corners.iter().map(|p| {
+        if abs.x >= abs.y && abs.x >= abs.z {
+            if normal.x >= 0.0 { Vec2::new(p.y, p.z) } else { Vec2::new(p.z, p.y) }
+        } else if abs.y >= abs.z {
+            if normal.y >= 0.0 { Vec2::new(p.z, p.x) } else { Vec2::new(p.x, p.z) }
+        } else if normal.z >= 0.0 {
+            Vec2::new(p.x, p.y)
+        } else {
+            Vec2::new(p.y, p.x)
+        }
+    }).collect()
+}
+
+fn cross(o: Vec2, a: Vec2, b: Vec2) -> f32 {
+    (a - o).perp_dot(b - o)
+}
+
+fn signed_area(polygon: &[Vec2]) -> f32 {
+    let mut area = 0.0;
+    for i in 0..polygon.len() {
+        area += polygon[i].perp_dot(polygon[(i + 1) % polygon.len()]);
+    }
+    area * 0.5
+}
+
+fn is_convex(polygon: &[Vec2]) -> bool {
+    let count = polygon.len();
+    let mut sign = 0.0f32;
+    for i in 0..count {
+        let turn = cross(polygon[i], polygon[(i + 1) % count], polygon[(i + 2) % count]);
+        if turn.abs() <= f32::EPSILON {
+            continue;
+        }
+        if sign == 0.0 {
+            sign = turn.signum();
+        } else if turn.signum() != sign {
+            return false;
+        }
+    }
+    true
+}
+
+fn point_in_triangle(p: Vec2, a: Vec2, b: Vec2, c: Vec2) -> bool {
+    let d1 = cross(a, b, p);
+    let d2 = cross(b, c, p);
+    let d3 = cross(c, a, p);
+    let has_negative = d1 < 0.0 || d2 < 0.0 || d3 < 0.0;
+    let has_positive = d1 > 0.0 || d2 > 0.0 || d3 > 0.0;
+    !(has_negative && has_positive)
+}
+
+/// Ear clipping for simple (possibly concave) polygons
+fn ear_clip(polygon: &[Vec2]) -> Vec<[usize; 3]> {
+    let orientation = signed_area(polygon).signum();
+    let mut remaining: Vec<usize> = (0..polygon.len()).collect();
+    let mut triangles = Vec::with_capacity(polygon.len() - 2);
+    
+    while remaining.len() > 3 {
+        let count = remaining.len();
+        let ear = (0..count).find(|&i| {
+            let prev = remaining[(i + count - 1) % count];
+            let current = remaining[i];
+            let next = remaining[(i + 1) % count];
+            let (a, b, c) = (polygon[prev], polygon[current], polygon[next]);
+            
+            if cross(a, b, c) * orientation <= 0.0 {
+                return false; // reflex or degenerate corner
+            }
+            remaining.iter()
+                .filter(|&&other| other != prev && other != current && other != next)
+                .all(|&other| !point_in_triangle(polygon[other], a, b, c))
+        });
+        
+        match ear {
+            Some(i) => {
+                let prev = remaining[(i + count - 1) % count];
+                let next = remaining[(i + 1) % count];
+                triangles.push([prev, remaining[i], next]);
+                remaining.remove(i);
+            }
+            None => {
+                // Self-intersecting or degenerate input - fan the rest rather than drop it
+                for i in 1..remaining.len() - 1 {
+                    triangles.push([remaining[0], remaining[i], remaining[i + 1]]);
+                }
+                return triangles;
+            }
+        }
+    }
+    
+    triangles.push([remaining[0], remaining[1], remaining[2]]);
+    triangles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn quad_points() -> Vec<Vec3> {
+        vec![
+            Vec3::new(0.0, 0.0, 0.0),
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(1.0, 1.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+        ]
+    }
+    
+    #[test]
+    fn test_quad_becomes_two_triangles() {
+        let mesh = triangulate(&quad_points(), &[4], &[0, 1, 2, 3], &[]).unwrap();
+        assert_eq!(mesh.indices, vec![0, 1, 2, 0, 2, 3]);
+        assert_eq!(mesh.face_ids, vec![0, 0]);
+        assert_eq!(mesh.edge_indices.len(), 8); // four quad edges, no diagonal
+    }
+    
+    #[test]
+    fn test_concave_polygon_is_ear_clipped() {
+        // L-shaped hexagon
+        let points = vec![
+            Vec3::new(0.0, 0.0, 0.0),
+            Vec3::new(2.0, 0.0, 0.0),
+            Vec3::new(2.0, 1.0, 0.0),
+            Vec3::new(1.0, 1.0, 0.0),
+            Vec3::new(1.0, 2.0, 0.0),
+            Vec3::new(0.0, 2.0, 0.0),
+        ];
+        let mesh = triangulate(&points, &[6], &[0, 1, 2, 3, 4, 5], &[]).unwrap();
+        assert_eq!(mesh.triangle_count(), 4);
+        
+        // No triangle may cover the notch at (1.5, 1.5)
+        let notch = Vec2::new(1.5, 1.5);
+        for triangle in mesh.indices.chunks(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| points[triangle[i] as usize].truncate());
+            assert!(!point_in_triangle(notch, a, b, c));
+            assert!(cross(a, b, c) > 0.0, "winding must be preserved");
+        }
+    }
+    
+    #[test]
+    fn test_hole_faces_are_skipped() {
+        let mesh = triangulate(&quad_points(), &[3, 3], &[0, 1, 2, 0, 2, 3], &[1]).unwrap();
+        assert_eq!(mesh.indices, vec![0, 1, 2]);
+    }
+    
+    #[test]
+    fn test_invalid_topology_is_rejected() {
+        assert!(triangulate(&quad_points(), &[4], &[0, 1, 2], &[]).is_err());
+        assert!(triangulate(&quad_points(), &[3], &[0, 1, 7], &[]).is_err());
+    }
+}
@@ -1,18 +1,40 @@
 //! USD-native 3D renderer 
 //! 
 //! This module implements a 3D renderer that directly reads USD stages
-//! and renders USD geometry, materials, and lights using wgpu.
+//! and renders USD geometry, materials, and lights using wgpu. Stages are
+//! read by the shared scene delegate, which `USDScene` receives as a sink.
 
-use wgpu::{Device, Queue, Buffer, BufferUsages, CommandEncoder, RenderPass};
+use wgpu::{Device, Queue, Buffer, BufferUsages, CommandEncoder};
 use bytemuck::{Pod, Zeroable};
 use glam::{Mat4, Vec3, Vec4};
+use std::cell::Cell;
 use std::collections::HashMap;
-use crate::gpu::viewport_3d_rendering::{Renderer3D, Vertex3D, Uniforms3D};
-use crate::gpu::viewport_3d_rendering::Camera3D as GpuCamera3D;
-use crate::nodes::three_d::usd::usd_engine::{USDStage, USDPrim, with_usd_engine};
-
-#[cfg(feature = "usd")]
-use pyo3::prelude::*;
+use std::time::{Duration, Instant};
+use wgpu::util::DeviceExt;
+use crate::capture::{CaptureSettings, CapturedFrame};
+use super::renderer_3d::{create_transform_buffer, Renderer3D, USDRenderPass, Uniforms3D, Vertex3D};
+use super::camera::Camera3D;
+use crate::core::bounds::BoundingBox;
+use crate::capture::id_matte::{IdManifest, IdMatte};
+use super::instancing::InstanceBatch;
+use super::light_linking::{light_mask, LinkCollection, MAX_LINKED_LIGHTS};
+use super::material_binding::MaterialBinding;
+use super::preview_surface::{ior_reflectance, InputSource};
+use super::textures::MaterialTextureBindings;
+use super::environment::Environment;
+use super::primvars::{PrimvarInfo, VertexAttributeBindings, VertexColorMode};
+use super::picking::{resolve_faces, FacePicker, FaceSelection, PickDraw, PickRect};
+use super::hydra::{HydraBlit, HydraRenderer, HydraView, RenderBackend, RenderSettingValue};
+use super::projection::ProjectionCamera;
+use super::camera_response::CameraResponse;
+use super::lux::LuxParams;
+use super::ambient_occlusion::{AmbientOcclusion, AmbientOcclusionPass};
+use super::antialiasing::{pass_sample_flags, AntiAliasing, FxaaPass};
+use super::color_management::ColorManagement;
+use super::wireframe_overlay::WireframeOverlay;
+use super::path_tracer::{PathTraceSettings, PathTracer, TraceCamera, TraceScene, IDLE_DELAY};
+use super::scene_delegate::{with_scene_delegate, ExtractionSettings, TraceSceneSink};
+use super::culling::{CullStats, FrustumCull};
 
 /// USD Geometry data extracted from USD prims
 #[derive(Debug, Clone)]
@@ -24,6 +46,16 @@ pub struct USDGeometry {
     pub transform: Mat4,
     pub material_path: Option<String>,
     pub visibility: bool,
+    /// Original polygon edges as a line list, for quad/n-gon wireframe display
+    pub edge_indices: Vec<u32>,
+    /// Linear RGBA color per vertex from displayColor/displayOpacity or the display primvar
+    pub colors: Vec<Vec4>,
+    /// Normal-mapping tangent per vertex, w the bitangent handedness; empty without st
+    pub tangents: Vec<Vec4>,
+    /// Authored face of every triangle, for face picking; empty for implicit shapes
+    pub face_ids: Vec<u32>,
+    /// Primvars of the mesh that can be chosen as the display primvar
+    pub primvars: Vec<PrimvarInfo>,
 }
 
 /// USD Light data extracted from UsdLux lights
@@ -31,14 +63,121 @@ pub struct USDGeometry {
 pub struct USDLight {
     pub prim_path: String,
     pub light_type: String, // "distant", "rect", "sphere", etc.
-    pub intensity: f32,
-    pub color: Vec3,
     pub transform: Mat4,
+    /// Color, intensity, size and cone inputs
+    pub lux: LuxParams,
+    /// Geometry this light illuminates
+    pub light_link: LinkCollection,
+    /// Geometry casting this light's shadows
+    pub shadow_link: LinkCollection,
+}
+
+impl USDLight {
+    /// World-space direction the light travels, along the light's -Z axis
+    pub fn direction(&self) -> Vec3 {
+        self.transform.transform_vector3(Vec3::NEG_Z).normalize_or_zero()
+    }
+    
+    /// Intensity in viewport shading units, see `lux`
+    pub fn radiance(&self) -> f32 {
+        self.lux.viewport_intensity(&self.light_type)
+    }
+}
+
+/// Lights shaded by usd_mesh.wgsl in one draw
+pub const MAX_SHADED_LIGHTS: usize = 8;
+
+/// One light in the lighting uniform (usd_mesh.wgsl `USDLight`)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+pub struct LightUniform {
+    pub direction: [f32; 3],
+    pub intensity: f32,
+    pub color: [f32; 3],
+    pub _padding: f32,
+}
+
+/// Scene lights for the lighting uniform at group 0, binding 1 (usd_mesh.wgsl `USDLights`)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+pub struct LightingUniform {
+    pub lights: [LightUniform; MAX_SHADED_LIGHTS],
+    /// Dome light ambient toward +Y
+    pub sky_color: [f32; 3],
+    pub count: u32,
+    /// Dome light ambient toward -Y
+    pub ground_color: [f32; 3],
+    /// `ViewTransform::index` of the display view
+    pub view_transform: u32,
+    /// Linear scale of the viewport exposure
     pub exposure: f32,
-    pub cone_angle: Option<f32>, // For spot lights
-    pub cone_softness: Option<f32>,
+    /// One over the display gamma
+    pub inverse_gamma: f32,
+    /// Nonzero to shade with face normals, in flat shading
+    pub flat_shading: u32,
+    pub _padding: f32,
+}
+
+/// Per-draw light and shadow link masks, pushed as fragment push constants
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Pod, Zeroable)]
+pub struct LightLinkMasks {
+    pub light_mask: u32,
+    pub shadow_mask: u32,
+}
+
+impl Default for LightLinkMasks {
+    fn default() -> Self {
+        Self { light_mask: u32::MAX, shadow_mask: u32::MAX }
+    }
+}
+
+/// Material drawn for geometry without a bound UsdPreviewSurface material
+pub const DEFAULT_MATERIAL: &str = "/World/DefaultMaterial";
+
+/// Per-draw material inputs, pushed as fragment push constants after the link masks
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+pub struct MaterialConstants {
+    pub base_color: [f32; 3],
+    pub opacity: f32,
+    pub emissive: [f32; 3],
+    pub metallic: f32,
+    pub roughness: f32,
+    /// Normal-incidence reflectance of dielectrics, from the ior input
+    pub reflectance: f32,
+    /// `VertexColorMode` of the draw
+    pub vertex_color: u32,
+    pub _padding: f32,
+}
+
+impl From<&USDMaterial> for MaterialConstants {
+    fn from(material: &USDMaterial) -> Self {
+        Self {
+            base_color: material.diffuse_color.to_array(),
+            opacity: material.opacity,
+            emissive: material.emission_color.to_array(),
+            metallic: material.metallic,
+            roughness: material.roughness,
+            reflectance: ior_reflectance(material.ior),
+            vertex_color: VertexColorMode::Off as u32,
+            _padding: 0.0,
+        }
+    }
 }
 
+/// Push constant offset of MaterialConstants (usd_mesh.wgsl `DrawConstants.base_color`)
+const MATERIAL_CONSTANTS_OFFSET: u32 = 16;
+
+/// Bind group index of the material textures (usd_mesh.wgsl group 1)
+const MATERIAL_TEXTURE_GROUP: u32 = 1;
+
+/// Bind group index of the vertex colors and tangents (usd_mesh.wgsl group 2)
+const VERTEX_ATTRIBUTE_GROUP: u32 = 2;
+
+/// Time a viewport frame spends refining the path traced preview
+const PATH_TRACE_BUDGET: Duration = Duration::from_millis(12);
+
 /// USD Material data extracted from UsdShade materials
 #[derive(Debug, Clone)]
 pub struct USDMaterial {
@@ -48,6 +187,11 @@ pub struct USDMaterial {
     pub roughness: f32,
     pub opacity: f32,
     pub emission_color: Vec3,
+    pub ior: f32,
+    /// Tangent-space normal
+    pub normal: Vec3,
+    /// Upstream UsdUVTexture and UsdPrimvarReader nodes keyed by surface input name
+    pub connections: HashMap<String, InputSource>,
 }
 
 /// USD Camera data extracted from UsdGeom cameras
@@ -59,6 +203,35 @@ pub struct USDCamera {
     pub horizontal_aperture: f32,
     pub vertical_aperture: f32,
     pub clipping_range: (f32, f32),
+    pub f_stop: f32,
+    pub focus_distance: f32,
+    /// Linear scale of the camera's exposure attributes
+    pub exposure_scale: f32,
+}
+
+impl USDCamera {
+    /// The camera as a projector, with its world transform and lens
+    pub fn projection(&self) -> ProjectionCamera {
+        ProjectionCamera {
+            world: self.transform,
+            focal_length: self.focal_length,
+            horizontal_aperture: self.horizontal_aperture,
+            vertical_aperture: self.vertical_aperture,
+            near: self.clipping_range.0,
+            far: self.clipping_range.1,
+        }
+    }
+    
+    /// How the camera's lens and exposure change its image
+    pub fn response(&self) -> CameraResponse {
+        CameraResponse {
+            focal_length: self.focal_length,
+            horizontal_aperture: self.horizontal_aperture,
+            f_stop: self.f_stop,
+            focus_distance: self.focus_distance,
+            exposure_scale: self.exposure_scale,
+        }
+    }
 }
 
 /// USD Scene representation
@@ -70,6 +243,16 @@ pub struct USDScene {
     pub materials: HashMap<String, USDMaterial>,
     pub cameras: Vec<USDCamera>,
     pub time_code: f64,
+    /// Point instancer batches drawn with hardware instancing
+    pub instance_batches: Vec<InstanceBatch>,
+    /// Meshes below instancer prototypes, only drawn through instance batches
+    pub prototype_geometry: std::collections::HashSet<String>,
+    /// Link masks of geometry not linked to every light; others use the default masks
+    pub light_links: HashMap<String, LightLinkMasks>,
+    /// Material bindings authored on each prim, resolved into `USDGeometry::material_path`
+    pub material_bindings: HashMap<String, Vec<MaterialBinding>>,
+    /// Ambient from the stage's dome lights, if it has any
+    pub environment: Option<Environment>,
 }
 
 impl Default for USDScene {
@@ -81,6 +264,11 @@ impl Default for USDScene {
             materials: HashMap::new(),
             cameras: Vec::new(),
             time_code: 0.0,
+            instance_batches: Vec::new(),
+            prototype_geometry: std::collections::HashSet::new(),
+            light_links: HashMap::new(),
+            material_bindings: HashMap::new(),
+            environment: None,
         }
     }
 }
@@ -93,12 +281,46 @@ pub struct USDRenderer {
     pub current_scene: USDScene,
     /// Geometry buffers for USD prims
     pub geometry_buffers: HashMap<String, (Buffer, Buffer, u32)>, // vertex, index, index_count
+    /// Object-to-world transform of each uploaded geometry, as a one-instance buffer
+    pub transform_buffers: HashMap<String, Buffer>,
+    /// Polygon edge buffers for wireframe display
+    pub edge_buffers: HashMap<String, (Buffer, u32)>, // index, index_count
+    /// Per-instance transform buffers, parallel to `current_scene.instance_batches`
+    pub instance_buffers: Vec<(Buffer, u32)>, // instance transforms, instance_count
     /// USD render settings
     pub render_settings: USDRenderSettings,
     /// Selected USD prims
     pub selected_prims: Vec<String>,
     /// Viewport camera or USD camera mode
     pub camera_mode: CameraMode,
+    /// Material texture bind groups, created once the renderer has a device
+    pub material_textures: Option<MaterialTextureBindings>,
+    /// Vertex color and tangent bind groups, created once the renderer has a device
+    pub vertex_attributes: Option<VertexAttributeBindings>,
+    /// Face pick pipeline, created on the first pick
+    pub face_picker: Option<FacePicker>,
+    /// Ambient occlusion pipelines, created once the renderer has a device
+    pub occlusion_pass: Option<AmbientOcclusionPass>,
+    /// Anti-aliasing the scene pass and its pipelines are set up for
+    pub anti_aliasing: AntiAliasing,
+    /// FXAA pipeline, created while FXAA is the anti-aliasing drawn
+    pub fxaa_pass: Option<FxaaPass>,
+    /// Edge overlay of wireframe on shaded, created once the renderer has a device
+    pub wireframe_overlay: Option<WireframeOverlay>,
+    /// Scene the path traced preview traces, built through the scene delegate on demand
+    pub trace_scene: Option<TraceScene>,
+    /// Samples of the path traced preview so far
+    pub path_tracer: PathTracer,
+    /// Blit of the path traced frame, created with its first frame
+    pub path_trace_blit: Option<HydraBlit>,
+    /// Hydra session and frame for the Hydra Storm backend
+    pub hydra: HydraRenderer,
+    /// World bounds of each uploaded geometry, for frustum culling
+    pub world_bounds: HashMap<String, BoundingBox>,
+    /// World bounds of all instances of each batch, parallel to `instance_buffers`
+    pub instance_bounds: Vec<BoundingBox>,
+    /// Prims drawn and culled by the last frame
+    cull_stats: Cell<CullStats>,
 }
 
 #[derive(Debug, Clone)]
@@ -110,7 +332,24 @@ pub struct USDRenderSettings {
     pub show_purposes: Vec<String>, // "default", "render", "proxy", "guide"
     pub complexity: ComplexityLevel,
     pub enable_lighting: bool,
-    pub ambient_occlusion: bool,
+    /// Screen space ambient occlusion over the wgpu scene
+    pub ambient_occlusion: AmbientOcclusion,
+    /// Anti-aliasing asked for; the renderer draws with what the device supports of it
+    pub anti_aliasing: AntiAliasing,
+    /// Progressive CPU path tracing shown in place of the wgpu scene while the camera is idle
+    pub path_trace: PathTraceSettings,
+    /// View transform, exposure and gamma taking linear shading to the display
+    pub color_management: ColorManagement,
+    /// Draw original polygon edges in wireframe modes instead of triangle edges
+    pub preserve_quad_wireframe: bool,
+    /// Color primvar shown unlit in place of materials, e.g. "displayColor" or "Cd"
+    pub display_primvar: Option<String>,
+    /// Renderer drawing the viewport
+    pub backend: RenderBackend,
+    /// Render settings passed to the Hydra delegate, as (key, value)
+    pub renderer_settings: Vec<(String, RenderSettingValue)>,
+    /// Skip prims whose world bounds are outside the view frustum
+    pub frustum_culling: bool,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -119,10 +358,41 @@ pub enum ShadingMode {
     WireframeOnSurface,
     FlatShaded,
     SmoothShaded,
+    /// displayColor and displayOpacity in place of every bound material
+    DisplayColor,
     MaterialPreview,
     Rendered,
 }
 
+impl ShadingMode {
+    /// Modes of the viewport panel's Shading choice
+    pub const PANEL: [ShadingMode; 6] = [
+        ShadingMode::SmoothShaded,
+        ShadingMode::FlatShaded,
+        ShadingMode::Wireframe,
+        ShadingMode::WireframeOnSurface,
+        ShadingMode::DisplayColor,
+        ShadingMode::MaterialPreview,
+    ];
+    
+    pub fn label(&self) -> &'static str {
+        match self {
+            ShadingMode::Wireframe => "Wireframe",
+            ShadingMode::WireframeOnSurface => "Wireframe on Shaded",
+            ShadingMode::FlatShaded => "Flat",
+            ShadingMode::SmoothShaded => "Shaded",
+            ShadingMode::DisplayColor => "Display Color",
+            ShadingMode::MaterialPreview => "Material Preview",
+            ShadingMode::Rendered => "Rendered",
+        }
+    }
+    
+    /// Mode of a panel Shading choice; Bounds and unknown labels have none
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::PANEL.into_iter().find(|mode| mode.label() == label)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum ComplexityLevel {
     Low,
@@ -131,6 +401,43 @@ pub enum ComplexityLevel {
     VeryHigh,
 }
 
+impl ComplexityLevel {
+    pub const ALL: [ComplexityLevel; 4] = [ComplexityLevel::Low, ComplexityLevel::Medium, ComplexityLevel::High, ComplexityLevel::VeryHigh];
+    
+    pub fn label(&self) -> &'static str {
+        match self {
+            ComplexityLevel::Low => "Low",
+            ComplexityLevel::Medium => "Medium",
+            ComplexityLevel::High => "High",
+            ComplexityLevel::VeryHigh => "Very High",
+        }
+    }
+    
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|level| level.label() == label)
+    }
+    
+    /// Catmull-Clark refinement levels applied to subdivision surfaces
+    pub fn subdivision_level(&self) -> u32 {
+        match self {
+            ComplexityLevel::Low => 0,
+            ComplexityLevel::Medium => 1,
+            ComplexityLevel::High => 2,
+            ComplexityLevel::VeryHigh => 3,
+        }
+    }
+    
+    /// UsdImagingGL complexity, as usdview maps its complexity menu
+    pub fn imaging_complexity(&self) -> f32 {
+        match self {
+            ComplexityLevel::Low => 1.0,
+            ComplexityLevel::Medium => 1.1,
+            ComplexityLevel::High => 1.2,
+            ComplexityLevel::VeryHigh => 1.3,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum CameraMode {
     Viewport,
@@ -147,7 +454,15 @@ impl Default for USDRenderSettings {
             show_purposes: vec!["default".to_string(), "render".to_string()],
             complexity: ComplexityLevel::Medium,
             enable_lighting: true,
-            ambient_occlusion: false,
+            ambient_occlusion: AmbientOcclusion::default(),
+            anti_aliasing: AntiAliasing::default(),
+            path_trace: PathTraceSettings::default(),
+            color_management: ColorManagement::default(),
+            preserve_quad_wireframe: true,
+            display_primvar: None,
+            backend: RenderBackend::Wgpu,
+            renderer_settings: Vec::new(),
+            frustum_culling: true,
         }
     }
 }
@@ -158,9 +473,26 @@ impl Clone for USDRenderer {
             base_renderer: Renderer3D::new(), // Create new renderer since it can't be cloned
             current_scene: self.current_scene.clone(),
             geometry_buffers: HashMap::new(), // Buffers can't be cloned, create new
+            transform_buffers: HashMap::new(),
+            edge_buffers: HashMap::new(),
+            instance_buffers: Vec::new(),
             render_settings: self.render_settings.clone(),
             selected_prims: self.selected_prims.clone(),
             camera_mode: self.camera_mode.clone(),
+            material_textures: None,
+            vertex_attributes: None,
+            face_picker: None,
+            occlusion_pass: None,
+            anti_aliasing: AntiAliasing::Off,
+            fxaa_pass: None,
+            wireframe_overlay: None,
+            trace_scene: self.trace_scene.clone(),
+            path_tracer: self.path_tracer.clone(),
+            path_trace_blit: None,
+            hydra: HydraRenderer::default(),
+            world_bounds: HashMap::new(),
+            instance_bounds: Vec::new(),
+            cull_stats: Cell::new(CullStats::default()),
         }
     }
 }
@@ -184,9 +516,26 @@ impl Default for USDRenderer {
             base_renderer: Renderer3D::new(),
             current_scene: USDScene::default(),
             geometry_buffers: HashMap::new(),
+            transform_buffers: HashMap::new(),
+            edge_buffers: HashMap::new(),
+            instance_buffers: Vec::new(),
             render_settings: USDRenderSettings::default(),
             selected_prims: Vec::new(),
             camera_mode: CameraMode::Viewport,
+            material_textures: None,
+            vertex_attributes: None,
+            face_picker: None,
+            occlusion_pass: None,
+            anti_aliasing: AntiAliasing::Off,
+            fxaa_pass: None,
+            wireframe_overlay: None,
+            trace_scene: None,
+            path_tracer: PathTracer::default(),
+            path_trace_blit: None,
+            hydra: HydraRenderer::default(),
+            world_bounds: HashMap::new(),
+            instance_bounds: Vec::new(),
+            cull_stats: Cell::new(CullStats::default()),
         }
     }
 }
@@ -199,40 +548,84 @@ impl USDRenderer {
     /// Initialize the USD renderer with wgpu device and queue
     pub fn initialize(&mut self, device: Device, queue: Queue) {
         self.base_renderer.initialize(device, queue);
+        self.apply_anti_aliasing();
     }
     
-    /// Load a USD stage and populate the scene
+    /// Ask for another anti-aliasing, rebuilding the pipelines that depend on it
+    pub fn set_anti_aliasing(&mut self, anti_aliasing: AntiAliasing) {
+        if anti_aliasing != self.render_settings.anti_aliasing {
+            self.render_settings.anti_aliasing = anti_aliasing;
+            self.apply_anti_aliasing();
+        }
+    }
+    
+    /// Samples per pixel the host's viewport pass attachments must have
+    pub fn sample_count(&self) -> u32 {
+        self.anti_aliasing.sample_count()
+    }
+    
+    /// Resolve the anti-aliasing asked for against the device and create pipelines for its sample count
+    ///
+    /// The Hydra blit follows on its next frame; the path traced preview is
+    /// traced again into a new blit.
+    fn apply_anti_aliasing(&mut self) {
+        let (Some(device), Some(queue)) = (&self.base_renderer.device, &self.base_renderer.queue) else {
+            return;
+        };
+        let requested = self.render_settings.anti_aliasing;
+        let anti_aliasing = requested.supported(pass_sample_flags(device));
+        if anti_aliasing != requested {
+            println!("USD Renderer: {} is not supported, drawing with {}", requested.label(), anti_aliasing.label());
+        }
+        let samples = anti_aliasing.sample_count();
+        self.occlusion_pass = Some(AmbientOcclusionPass::new(device, samples));
+        self.fxaa_pass = (anti_aliasing == AntiAliasing::Fxaa).then(|| FxaaPass::new(device));
+        let mut overlay = WireframeOverlay::new(device, samples);
+        rebuild_wire_overlay(&mut overlay, device, &self.current_scene, &self.geometry_buffers);
+        self.wireframe_overlay = Some(overlay);
+        self.path_trace_blit = None;
+        self.anti_aliasing = anti_aliasing;
+        // Mesh pipelines take the material texture and vertex attribute layouts as groups 1 and 2
+        let textures = self.material_textures.get_or_insert_with(|| MaterialTextureBindings::new(device, queue));
+        let attributes = self.vertex_attributes.get_or_insert_with(|| VertexAttributeBindings::new(device));
+        self.base_renderer.set_sample_count(samples, [&textures.layout, &attributes.layout]);
+        self.path_tracer.cancel(Instant::now());
+    }
+    
+    /// Copy a drawn frame from `source` into `target` with FXAA; false, drawing nothing, unless FXAA is on
+    ///
+    /// Runs after the scene pass and ambient occlusion, on the frame's
+    /// Rgba8UnormSrgb color; the host then shows `target` instead.
+    pub fn render_anti_aliasing(&self, encoder: &mut CommandEncoder, source: &wgpu::TextureView, target: &wgpu::TextureView) -> bool {
+        let (Some(device), Some(fxaa)) = (&self.base_renderer.device, &self.fxaa_pass) else {
+            return false;
+        };
+        fxaa.draw(device, encoder, source, target);
+        true
+    }
+    
+    /// Load a USD stage and populate the scene through the shared scene delegate
+    ///
+    /// Loading always reads the stage again; other renderers populating from
+    /// the same stage and settings then reuse this extraction.
     pub fn load_stage(&mut self, stage_id: &str) -> Result<(), String> {
         println!("Loading USD stage: {}", stage_id);
         
-        // Clear previous scene
-        self.current_scene = USDScene {
-            stage_id: stage_id.to_string(),
-            ..Default::default()
-        };
+        let settings = self.extraction_settings();
         self.geometry_buffers.clear();
+        self.transform_buffers.clear();
+        self.edge_buffers.clear();
+        self.instance_buffers.clear();
+        with_scene_delegate(|delegate| {
+            delegate.invalidate(stage_id);
+            delegate.populate(stage_id, &settings, &mut self.current_scene);
+        });
         
-        #[cfg(feature = "usd")]
-        {
-            // Try to extract real USD stage data
-            if let Err(_) = self.extract_stage_data(stage_id) {
-                // If extraction fails, create mock scene instead
-                self.create_mock_scene(stage_id);
-            }
-            
-            // If no geometries were loaded, create mock scene as fallback
-            if self.current_scene.geometries.is_empty() {
-                self.create_mock_scene(stage_id);
-            }
-        }
-        
-        #[cfg(not(feature = "usd"))]
-        {
-            // Create mock scene for testing without USD
-            self.create_mock_scene(stage_id);
-        }
-        
+        self.update_light_links();
         self.upload_geometry_buffers()?;
+        self.upload_material_textures();
+        self.trace_scene = None;
+        self.path_tracer.cancel(Instant::now());
         
         println!("✓ Loaded USD stage: {} geometries, {} lights, {} materials", 
                  self.current_scene.geometries.len(),
@@ -242,324 +635,261 @@ impl USDRenderer {
         Ok(())
     }
     
-    #[cfg(feature = "usd")]
-    fn extract_stage_data(&mut self, stage_id: &str) -> Result<(), String> {
-        with_usd_engine(|engine| {
-            // Get stage reference (this would need to be added to USDEngine)
-            if let Some(stage) = engine.get_stage(stage_id) {
-                let result = Python::with_gil(|py| -> Result<(), String> {
-                    let usd = py.import("pxr.Usd").map_err(|e| format!("Failed to import USD: {}", e))?;
-                    let usd_geom = py.import("pxr.UsdGeom").map_err(|e| format!("Failed to import UsdGeom: {}", e))?;
-                    let usd_lux = py.import("pxr.UsdLux").map_err(|e| format!("Failed to import UsdLux: {}", e))?;
-                    let usd_shade = py.import("pxr.UsdShade").map_err(|e| format!("Failed to import UsdShade: {}", e))?;
-                    
-                    // TODO: Get actual stage object from engine
-                    // For now, this is a framework for USD data extraction
-                    
-                    // Extract geometry prims
-                    self.extract_geometry_prims(py, usd_geom, stage_id)?;
-                    
-                    // Extract light prims  
-                    self.extract_light_prims(py, usd_lux, stage_id)?;
-                    
-                    // Extract material prims
-                    self.extract_material_prims(py, usd_shade, stage_id)?;
-                    
-                    // Extract camera prims
-                    self.extract_camera_prims(py, usd_geom, stage_id)?;
-                    
-                    Ok(())
-                });
-                
-                if let Err(e) = result {
-                    eprintln!("Error extracting USD stage data: {}", e);
-                }
+    /// Settings the scene delegate extracts this renderer's scene with
+    fn extraction_settings(&self) -> ExtractionSettings {
+        ExtractionSettings {
+            time_code: self.current_scene.time_code,
+            subdivision_level: self.render_settings.complexity.subdivision_level(),
+            display_primvar: self.render_settings.display_primvar.clone(),
+        }
+    }
+    
+    /// Resolve light and shadow link masks for every drawn geometry
+    ///
+    /// Only geometry excluded from some light gets an entry, so stages without
+    /// light linking keep an empty map.
+    fn update_light_links(&mut self) {
+        let scene = &mut self.current_scene;
+        scene.light_links.clear();
+        if scene.lights.iter().all(|light| light.light_link.links_everything() && light.shadow_link.links_everything()) {
+            return;
+        }
+        // Mask of every light, held by geometry no collection excludes
+        let linked = scene.lights.len().min(MAX_LINKED_LIGHTS) as u32;
+        let all = u32::MAX.checked_shr(MAX_LINKED_LIGHTS as u32 - linked).unwrap_or(0);
+        let paths = scene.geometries.iter()
+            .map(|geometry| geometry.prim_path.as_str())
+            .chain(scene.instance_batches.iter().map(|batch| batch.geometry_path.as_str()));
+        for path in paths {
+            let masks = LightLinkMasks {
+                light_mask: light_mask(scene.lights.iter().map(|light| &light.light_link), path),
+                shadow_mask: light_mask(scene.lights.iter().map(|light| &light.shadow_link), path),
+            };
+            if masks.light_mask != all || masks.shadow_mask != all {
+                scene.light_links.insert(path.to_string(), masks);
             }
-        });
-        
-        Ok(())
+        }
     }
     
-    #[cfg(feature = "usd")]
-    fn extract_geometry_prims(&mut self, py: Python, usd_geom: &PyAny, stage_id: &str) -> Result<(), String> {
-        // This would iterate through all geometry prims and extract mesh data
-        // For now, create a placeholder cube
-        let cube_geometry = self.create_cube_geometry("/World/Cube", Mat4::IDENTITY);
-        self.current_scene.geometries.push(cube_geometry);
-        
-        Ok(())
+    /// Light and shadow link masks for a geometry prim
+    pub fn light_link_masks(&self, prim_path: &str) -> LightLinkMasks {
+        self.current_scene.light_links.get(prim_path).copied().unwrap_or_default()
     }
     
-    #[cfg(feature = "usd")]
-    fn extract_light_prims(&mut self, py: Python, usd_lux: &PyAny, stage_id: &str) -> Result<(), String> {
-        // Extract USD lights
-        let default_light = USDLight {
-            prim_path: "/World/DefaultLight".to_string(),
-            light_type: "distant".to_string(),
-            intensity: 1.0,
-            color: Vec3::new(1.0, 1.0, 1.0),
-            transform: Mat4::IDENTITY,
-            exposure: 0.0,
-            cone_angle: None,
-            cone_softness: None,
+    /// Scene lights for the lighting uniform, uploaded alongside the mesh uniforms
+    ///
+    /// Looking through a camera exposes the lights by its exposure attributes;
+    /// the viewport's color management is applied to the shaded result.
+    pub fn lighting_uniform(&self) -> LightingUniform {
+        let mut uniform = LightingUniform::zeroed();
+        let exposure = self.active_camera_response().map_or(1.0, |response| response.exposure_scale);
+        let lights = if self.render_settings.enable_lighting { self.current_scene.lights.as_slice() } else { &[] };
+        for (slot, light) in uniform.lights.iter_mut().zip(lights) {
+            *slot = LightUniform {
+                direction: light.direction().to_array(),
+                intensity: light.radiance() * exposure,
+                color: light.lux.tint().to_array(),
+                _padding: 0.0,
+            };
+        }
+        uniform.count = lights.len().min(MAX_SHADED_LIGHTS) as u32;
+        let environment = match self.render_settings.enable_lighting {
+            true => self.current_scene.environment.unwrap_or_default(),
+            false => Environment::default(),
         };
-        self.current_scene.lights.push(default_light);
-        
-        Ok(())
+        uniform.sky_color = (environment.sky * exposure).to_array();
+        uniform.ground_color = (environment.ground * exposure).to_array();
+        let color_management = self.render_settings.color_management;
+        uniform.view_transform = color_management.view_transform.index();
+        uniform.exposure = color_management.exposure_scale();
+        uniform.inverse_gamma = 1.0 / color_management.gamma.max(0.01);
+        uniform.flat_shading = (self.render_settings.shading_mode == ShadingMode::FlatShaded) as u32;
+        uniform
     }
     
-    #[cfg(feature = "usd")]
-    fn extract_material_prims(&mut self, py: Python, usd_shade: &PyAny, stage_id: &str) -> Result<(), String> {
-        // Extract USD materials
-        let default_material = USDMaterial {
-            prim_path: "/World/DefaultMaterial".to_string(),
-            diffuse_color: Vec3::new(0.7, 0.7, 0.8),
-            metallic: 0.0,
-            roughness: 0.5,
-            opacity: 1.0,
-            emission_color: Vec3::ZERO,
-        };
-        self.current_scene.materials.insert("/World/DefaultMaterial".to_string(), default_material);
-        
-        Ok(())
+    /// Extracted geometry of a prim
+    fn geometry(&self, prim_path: &str) -> Option<&USDGeometry> {
+        self.current_scene.geometries.iter().find(|geometry| geometry.prim_path == prim_path)
     }
     
-    #[cfg(feature = "usd")]
-    fn extract_camera_prims(&mut self, py: Python, usd_geom: &PyAny, stage_id: &str) -> Result<(), String> {
-        // Extract USD cameras
-        Ok(())
+    /// Material path resolved for a geometry prim
+    fn geometry_material(&self, prim_path: &str) -> Option<&str> {
+        self.geometry(prim_path).and_then(|geometry| geometry.material_path.as_deref())
     }
     
-    pub fn create_mock_scene(&mut self, stage_id: &str) {
-        // Create a mock scene for testing without USD
-        
-        // Add some test geometry
-        let cube = self.create_cube_geometry("/World/Cube", Mat4::from_translation(Vec3::new(-2.0, 0.0, 0.0)));
-        let sphere = self.create_sphere_geometry("/World/Sphere", Mat4::from_translation(Vec3::new(2.0, 0.0, 0.0)));
-        let plane = self.create_plane_geometry("/World/Plane", Mat4::from_translation(Vec3::new(0.0, -1.0, 0.0)));
-        
-        self.current_scene.geometries.push(cube);
-        self.current_scene.geometries.push(sphere);
-        self.current_scene.geometries.push(plane);
-        
-        // Add a default light
-        let light = USDLight {
-            prim_path: "/World/DefaultLight".to_string(),
-            light_type: "distant".to_string(),
-            intensity: 1.0,
-            color: Vec3::new(1.0, 1.0, 0.9),
-            transform: Mat4::from_rotation_x(-45_f32.to_radians()),
-            exposure: 0.0,
-            cone_angle: None,
-            cone_softness: None,
-        };
-        self.current_scene.lights.push(light);
-        
-        // Add a default material
-        let material = USDMaterial {
-            prim_path: "/World/DefaultMaterial".to_string(),
-            diffuse_color: Vec3::new(0.6, 0.7, 0.8),
-            metallic: 0.1,
-            roughness: 0.4,
-            opacity: 1.0,
-            emission_color: Vec3::ZERO,
+    /// Load the textures of every material through the shared cache and rebuild their bind groups
+    fn upload_material_textures(&mut self) {
+        let (Some(device), Some(queue)) = (&self.base_renderer.device, &self.base_renderer.queue) else {
+            return;
         };
-        self.current_scene.materials.insert("/World/DefaultMaterial".to_string(), material);
-    }
-    
-    fn create_cube_geometry(&self, prim_path: &str, transform: Mat4) -> USDGeometry {
-        // Create cube vertices
-        let vertices = vec![
-            // Front face
-            Vertex3D { position: [-1.0, -1.0,  1.0], normal: [ 0.0,  0.0,  1.0], uv: [0.0, 0.0] },
-            Vertex3D { position: [ 1.0, -1.0,  1.0], normal: [ 0.0,  0.0,  1.0], uv: [1.0, 0.0] },
-            Vertex3D { position: [ 1.0,  1.0,  1.0], normal: [ 0.0,  0.0,  1.0], uv: [1.0, 1.0] },
-            Vertex3D { position: [-1.0,  1.0,  1.0], normal: [ 0.0,  0.0,  1.0], uv: [0.0, 1.0] },
-            
-            // Back face
-            Vertex3D { position: [-1.0, -1.0, -1.0], normal: [ 0.0,  0.0, -1.0], uv: [1.0, 0.0] },
-            Vertex3D { position: [-1.0,  1.0, -1.0], normal: [ 0.0,  0.0, -1.0], uv: [1.0, 1.0] },
-            Vertex3D { position: [ 1.0,  1.0, -1.0], normal: [ 0.0,  0.0, -1.0], uv: [0.0, 1.0] },
-            Vertex3D { position: [ 1.0, -1.0, -1.0], normal: [ 0.0,  0.0, -1.0], uv: [0.0, 0.0] },
-            
-            // Top face
-            Vertex3D { position: [-1.0,  1.0, -1.0], normal: [ 0.0,  1.0,  0.0], uv: [0.0, 1.0] },
-            Vertex3D { position: [-1.0,  1.0,  1.0], normal: [ 0.0,  1.0,  0.0], uv: [0.0, 0.0] },
-            Vertex3D { position: [ 1.0,  1.0,  1.0], normal: [ 0.0,  1.0,  0.0], uv: [1.0, 0.0] },
-            Vertex3D { position: [ 1.0,  1.0, -1.0], normal: [ 0.0,  1.0,  0.0], uv: [1.0, 1.0] },
-            
-            // Bottom face
-            Vertex3D { position: [-1.0, -1.0, -1.0], normal: [ 0.0, -1.0,  0.0], uv: [1.0, 1.0] },
-            Vertex3D { position: [ 1.0, -1.0, -1.0], normal: [ 0.0, -1.0,  0.0], uv: [0.0, 1.0] },
-            Vertex3D { position: [ 1.0, -1.0,  1.0], normal: [ 0.0, -1.0,  0.0], uv: [0.0, 0.0] },
-            Vertex3D { position: [-1.0, -1.0,  1.0], normal: [ 0.0, -1.0,  0.0], uv: [1.0, 0.0] },
-            
-            // Right face
-            Vertex3D { position: [ 1.0, -1.0, -1.0], normal: [ 1.0,  0.0,  0.0], uv: [1.0, 0.0] },
-            Vertex3D { position: [ 1.0,  1.0, -1.0], normal: [ 1.0,  0.0,  0.0], uv: [1.0, 1.0] },
-            Vertex3D { position: [ 1.0,  1.0,  1.0], normal: [ 1.0,  0.0,  0.0], uv: [0.0, 1.0] },
-            Vertex3D { position: [ 1.0, -1.0,  1.0], normal: [ 1.0,  0.0,  0.0], uv: [0.0, 0.0] },
-            
-            // Left face
-            Vertex3D { position: [-1.0, -1.0, -1.0], normal: [-1.0,  0.0,  0.0], uv: [0.0, 0.0] },
-            Vertex3D { position: [-1.0, -1.0,  1.0], normal: [-1.0,  0.0,  0.0], uv: [1.0, 0.0] },
-            Vertex3D { position: [-1.0,  1.0,  1.0], normal: [-1.0,  0.0,  0.0], uv: [1.0, 1.0] },
-            Vertex3D { position: [-1.0,  1.0, -1.0], normal: [-1.0,  0.0,  0.0], uv: [0.0, 1.0] },
-        ];
-        
-        let indices = vec![
-            0,  1,  2,   0,  2,  3,    // front
-            4,  5,  6,   4,  6,  7,    // back
-            8,  9,  10,  8,  10, 11,   // top
-            12, 13, 14,  12, 14, 15,   // bottom
-            16, 17, 18,  16, 18, 19,   // right
-            20, 21, 22,  20, 22, 23,   // left
-        ];
-        
-        USDGeometry {
-            prim_path: prim_path.to_string(),
-            prim_type: "Cube".to_string(),
-            vertices,
-            indices,
-            transform,
-            material_path: Some("/World/DefaultMaterial".to_string()),
-            visibility: true,
-        }
-    }
-    
-    fn create_sphere_geometry(&self, prim_path: &str, transform: Mat4) -> USDGeometry {
-        let mut vertices = Vec::new();
-        let mut indices = Vec::new();
-        
-        let radius = 1.0;
-        let segments = 32;
-        let rings = 16;
-        
-        // Generate sphere vertices
-        for ring in 0..=rings {
-            let phi = std::f32::consts::PI * ring as f32 / rings as f32;
-            let y = phi.cos();
-            let ring_radius = phi.sin();
-            
-            for segment in 0..=segments {
-                let theta = 2.0 * std::f32::consts::PI * segment as f32 / segments as f32;
-                let x = ring_radius * theta.cos();
-                let z = ring_radius * theta.sin();
-                
-                vertices.push(Vertex3D {
-                    position: [x * radius, y * radius, z * radius],
-                    normal: [x, y, z],
-                    uv: [segment as f32 / segments as f32, ring as f32 / rings as f32],
-                });
-            }
-        }
-        
-        // Generate sphere indices
-        for ring in 0..rings {
-            for segment in 0..segments {
-                let current = ring * (segments + 1) + segment;
-                let next = current + segments + 1;
-                
-                indices.push(current);
-                indices.push(next);
-                indices.push(current + 1);
-                
-                indices.push(current + 1);
-                indices.push(next);
-                indices.push(next + 1);
-            }
+        let bindings = self.material_textures.get_or_insert_with(|| MaterialTextureBindings::new(device, queue));
+        let materials = self.current_scene.materials.iter().map(|(path, material)| (path, &material.connections));
+        for error in bindings.rebuild(device, queue, materials, self.current_scene.time_code) {
+            eprintln!("USD Plugin: {}", error);
         }
-        
-        USDGeometry {
-            prim_path: prim_path.to_string(),
-            prim_type: "Sphere".to_string(),
-            vertices,
-            indices,
-            transform,
-            material_path: Some("/World/DefaultMaterial".to_string()),
-            visibility: true,
+    }
+    
+    /// Texture bind group for a geometry prim's draw; textures are only sampled in material preview
+    fn material_bind_group(&self, prim_path: &str) -> Option<&wgpu::BindGroup> {
+        let bindings = self.material_textures.as_ref()?;
+        if self.render_settings.shading_mode != ShadingMode::MaterialPreview {
+            return Some(&bindings.untextured);
         }
+        Some(self.geometry_material(prim_path).map_or(&bindings.untextured, |material| bindings.bind_group(material)))
     }
     
-    fn create_plane_geometry(&self, prim_path: &str, transform: Mat4) -> USDGeometry {
-        let size = 5.0;
-        let vertices = vec![
-            Vertex3D { position: [-size, 0.0, -size], normal: [0.0, 1.0, 0.0], uv: [0.0, 0.0] },
-            Vertex3D { position: [ size, 0.0, -size], normal: [0.0, 1.0, 0.0], uv: [1.0, 0.0] },
-            Vertex3D { position: [ size, 0.0,  size], normal: [0.0, 1.0, 0.0], uv: [1.0, 1.0] },
-            Vertex3D { position: [-size, 0.0,  size], normal: [0.0, 1.0, 0.0], uv: [0.0, 1.0] },
-        ];
-        
-        let indices = vec![0, 1, 2, 0, 2, 3];
-        
-        USDGeometry {
-            prim_path: prim_path.to_string(),
-            prim_type: "Plane".to_string(),
-            vertices,
-            indices,
-            transform,
-            material_path: Some("/World/DefaultMaterial".to_string()),
-            visibility: true,
+    /// Material inputs for a geometry prim's draw
+    ///
+    /// Display color shading draws every prim with the default material.
+    pub fn material_constants(&self, prim_path: &str) -> MaterialConstants {
+        let material = self.geometry_material(prim_path)
+            .filter(|_| self.render_settings.shading_mode != ShadingMode::DisplayColor)
+            .and_then(|material_path| self.current_scene.materials.get(material_path))
+            .or_else(|| self.current_scene.materials.get(DEFAULT_MATERIAL));
+        let mut constants = match material {
+            Some(material) => MaterialConstants::from(material),
+            None => MaterialConstants {
+                base_color: [0.7, 0.7, 0.8],
+                opacity: 1.0,
+                emissive: [0.0; 3],
+                metallic: 0.0,
+                roughness: 0.5,
+                reflectance: ior_reflectance(1.5),
+                vertex_color: VertexColorMode::Off as u32,
+                _padding: 0.0,
+            },
+        };
+        constants.vertex_color = self.vertex_color_mode(prim_path, material) as u32;
+        constants
+    }
+    
+    /// How a geometry prim's draw uses its vertex colors
+    ///
+    /// A chosen display primvar is shown as is. Otherwise displayColor stands
+    /// in for the base color of unbound geometry, as in usdview, of
+    /// materials whose diffuseColor reads it through a primvar reader, and of
+    /// everything in display color shading.
+    fn vertex_color_mode(&self, prim_path: &str, material: Option<&USDMaterial>) -> VertexColorMode {
+        if self.geometry(prim_path).is_none_or(|geometry| geometry.colors.is_empty()) {
+            return VertexColorMode::Off;
+        }
+        if self.render_settings.display_primvar.is_some() {
+            return VertexColorMode::Display;
+        }
+        let reads_display_color = |material: &USDMaterial| {
+            material.prim_path == DEFAULT_MATERIAL || matches!(
+                material.connections.get("diffuseColor"),
+                Some(InputSource::Primvar(reader)) if reader.varname == "displayColor"
+            )
+        };
+        match material.is_none_or(reads_display_color) {
+            true => VertexColorMode::BaseColor,
+            false => VertexColorMode::Off,
         }
     }
     
     fn upload_geometry_buffers(&mut self) -> Result<(), String> {
-        if let Some(device) = &self.base_renderer.device {
-            self.geometry_buffers.clear();
-            
-            for geometry in &self.current_scene.geometries {
-                // Create vertex buffer
-                let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                    label: Some(&format!("{}_vertices", geometry.prim_path)),
-                    contents: bytemuck::cast_slice(&geometry.vertices),
-                    usage: BufferUsages::VERTEX,
-                });
-                
-                // Create index buffer
-                let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                    label: Some(&format!("{}_indices", geometry.prim_path)),
-                    contents: bytemuck::cast_slice(&geometry.indices),
-                    usage: BufferUsages::INDEX,
-                });
-                
-                self.geometry_buffers.insert(
-                    geometry.prim_path.clone(),
-                    (vertex_buffer, index_buffer, geometry.indices.len() as u32)
-                );
-            }
+        match self.base_renderer.device.clone() {
+            Some(device) => self.upload_geometry_buffers_from_refs(&device),
+            None => Ok(()),
         }
-        
-        Ok(())
     }
     
     /// Upload geometry buffers using device reference (for callback system)
     pub fn upload_geometry_buffers_from_refs(&mut self, device: &wgpu::Device) -> Result<(), String> {
         self.geometry_buffers.clear();
+        self.transform_buffers.clear();
+        self.edge_buffers.clear();
         
         for geometry in &self.current_scene.geometries {
             // Create vertex buffer
             let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
                 label: Some(&format!("{}_vertices", geometry.prim_path)),
                 contents: bytemuck::cast_slice(&geometry.vertices),
-                usage: BufferUsages::VERTEX,
+                // Storage too, for the face pick pass and wire overlay to pull vertices
+                usage: BufferUsages::VERTEX | BufferUsages::STORAGE,
             });
             
             // Create index buffer
             let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
                 label: Some(&format!("{}_indices", geometry.prim_path)),
                 contents: bytemuck::cast_slice(&geometry.indices),
-                usage: BufferUsages::INDEX,
+                usage: BufferUsages::INDEX | BufferUsages::STORAGE,
             });
             
             self.geometry_buffers.insert(
                 geometry.prim_path.clone(),
                 (vertex_buffer, index_buffer, geometry.indices.len() as u32)
             );
+            let transform_buffer = create_transform_buffer(device, &format!("{}_transform", geometry.prim_path), &[geometry.transform]);
+            self.transform_buffers.insert(geometry.prim_path.clone(), transform_buffer);
+            
+            if !geometry.edge_indices.is_empty() {
+                let edge_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some(&format!("{}_edges", geometry.prim_path)),
+                    contents: bytemuck::cast_slice(&geometry.edge_indices),
+                    usage: BufferUsages::INDEX,
+                });
+                self.edge_buffers.insert(geometry.prim_path.clone(), (edge_buffer, geometry.edge_indices.len() as u32));
+            }
+        }
+        
+        self.instance_buffers = create_instance_buffers(device, &self.current_scene.instance_batches);
+        self.cache_world_bounds();
+        
+        let attributes = self.current_scene.geometries.iter()
+            .map(|geometry| (&geometry.prim_path, geometry.colors.as_slice(), geometry.tangents.as_slice()));
+        self.vertex_attributes.get_or_insert_with(|| VertexAttributeBindings::new(device)).rebuild(device, attributes);
+        if let Some(overlay) = &mut self.wireframe_overlay {
+            rebuild_wire_overlay(overlay, device, &self.current_scene, &self.geometry_buffers);
         }
         
         Ok(())
     }
     
+    /// Cache world bounds of the uploaded geometry and instance batches
+    fn cache_world_bounds(&mut self) {
+        let scene = &self.current_scene;
+        let local_bounds: HashMap<&str, BoundingBox> = scene.geometries.iter()
+            .map(|geometry| {
+                let bounds = geometry.vertices.iter()
+                    .fold(BoundingBox::EMPTY, |bounds, vertex| bounds.including(Vec3::from(vertex.position)));
+                (geometry.prim_path.as_str(), bounds)
+            })
+            .collect();
+        
+        self.world_bounds = scene.geometries.iter()
+            .map(|geometry| (geometry.prim_path.clone(), local_bounds[geometry.prim_path.as_str()].transformed(&geometry.transform)))
+            .collect();
+        self.instance_bounds = scene.instance_batches.iter()
+            .map(|batch| {
+                let local = local_bounds.get(batch.geometry_path.as_str()).copied().unwrap_or(BoundingBox::EMPTY);
+                batch.transforms.iter().fold(BoundingBox::EMPTY, |bounds, transform| bounds.union(local.transformed(transform)))
+            })
+            .collect();
+    }
+    
+    /// Prims drawn and culled by the last frame
+    pub fn cull_stats(&self) -> CullStats {
+        self.cull_stats.get()
+    }
+    
+    /// Draws of the scene pass left by frustum culling, which counts them
+    ///
+    /// Hidden prims and point instancer prototypes aren't drawn on their own.
+    fn scene_draws(&self, cull: &mut FrustumCull) -> Vec<SceneDraw<'_>> {
+        let scene = &self.current_scene;
+        let geometries = scene.geometries.iter()
+            .filter(|geometry| geometry.visibility && !scene.prototype_geometry.contains(&geometry.prim_path))
+            .filter(|geometry| cull.draws(self.world_bounds.get(&geometry.prim_path)))
+            .map(|geometry| SceneDraw::Geometry(&geometry.prim_path))
+            .collect::<Vec<_>>();
+        let batches = (0..scene.instance_batches.len())
+            .filter(|index| cull.draws(self.instance_bounds.get(*index)))
+            .map(SceneDraw::Batch);
+        geometries.into_iter().chain(batches).collect()
+    }
+    
     /// Select USD prim by path
     pub fn select_prim(&mut self, prim_path: &str) {
         if !self.selected_prims.contains(&prim_path.to_string()) {
@@ -582,13 +912,226 @@ impl USDRenderer {
         self.render_settings.shading_mode = mode;
     }
     
+    /// Switch between the wgpu renderer and a Hydra render delegate
+    ///
+    /// Render settings belong to one delegate and are dropped on a switch.
+    pub fn set_render_backend(&mut self, backend: RenderBackend) {
+        if backend != self.render_settings.backend {
+            self.render_settings.renderer_settings.clear();
+        }
+        self.render_settings.backend = backend;
+    }
+    
+    /// Pass a render setting to the active Hydra delegate
+    pub fn set_renderer_setting(&mut self, key: &str, value: RenderSettingValue) {
+        let settings = &mut self.render_settings.renderer_settings;
+        match settings.iter_mut().find(|(existing, _)| existing == key) {
+            Some((_, current)) => *current = value,
+            None => settings.push((key.to_string(), value)),
+        }
+    }
+    
+    /// Render the stage through Hydra for a `width` x `height` viewport
+    ///
+    /// Call before the viewport pass with a Hydra backend active; the frame
+    /// is only re-rendered when the stage, view or render settings changed.
+    /// Until a frame succeeds, the wgpu renderer keeps drawing.
+    pub fn render_hydra_frame(&mut self, width: u32, height: u32) -> Result<(), String> {
+        let RenderBackend::Hydra(renderer) = &self.render_settings.backend else {
+            return Ok(());
+        };
+        if self.current_scene.stage_id.is_empty() {
+            return Ok(());
+        }
+        let (Some(device), Some(queue)) = (&self.base_renderer.device, &self.base_renderer.queue) else {
+            return Err("Renderer is not initialized".to_string());
+        };
+        
+        let camera = self.get_active_camera();
+        let mut view = HydraView::look_at(camera.position, camera.target, camera.fov, camera.near, camera.far, width.max(1), height.max(1));
+        if let CameraMode::USDCamera(path) = &self.camera_mode {
+            view.camera_path = Some(path.clone());
+            view.camera_response = self.active_camera_response().filter(|response| !response.is_identity());
+        }
+        view.time_code = self.current_scene.time_code;
+        view.complexity = self.render_settings.complexity.imaging_complexity();
+        view.enable_lighting = self.render_settings.enable_lighting;
+        view.purposes = self.render_settings.show_purposes.clone();
+        view.renderer = renderer.clone();
+        view.renderer_settings = self.render_settings.renderer_settings.clone();
+        self.hydra.render(device, queue, &self.current_scene.stage_id, &view, self.anti_aliasing.sample_count())
+    }
+    
+    /// Turn the path traced preview on or off or change its settings, starting its frame over
+    pub fn set_path_trace(&mut self, settings: PathTraceSettings) {
+        if settings != self.render_settings.path_trace {
+            self.render_settings.path_trace = settings;
+            self.path_tracer.cancel(Instant::now());
+        }
+    }
+    
+    /// Refine the path traced preview while the camera is idle, once per viewport frame
+    ///
+    /// Moving the camera or resizing the view cancels the frame in progress,
+    /// and the wgpu scene is drawn until tracing resumes and every pixel has
+    /// a sample again. Each call traces for at most `PATH_TRACE_BUDGET`.
+    pub fn render_path_trace_frame(&mut self, width: u32, height: u32) -> Result<(), String> {
+        if !self.render_settings.path_trace.enabled || self.render_settings.backend != RenderBackend::Wgpu || self.current_scene.stage_id.is_empty() {
+            return Ok(());
+        }
+        if self.base_renderer.device.is_none() || self.base_renderer.queue.is_none() {
+            return Err("Renderer is not initialized".to_string());
+        }
+        
+        // The delegate replays its cached extraction into the tracer's sink
+        if self.trace_scene.is_none() {
+            let settings = self.extraction_settings();
+            let mut sink = TraceSceneSink::default();
+            with_scene_delegate(|delegate| delegate.populate(&self.current_scene.stage_id, &settings, &mut sink));
+            self.trace_scene = sink.scene;
+        }
+        let Some(scene) = &self.trace_scene else {
+            return Ok(());
+        };
+        
+        let camera = self.get_active_camera();
+        let view = TraceCamera { position: camera.position, target: camera.target, up: camera.up, fov_y: camera.fov };
+        let now = Instant::now();
+        self.path_tracer.set_view(view, width.max(1), height.max(1), now);
+        if self.path_tracer.refine(scene, &self.render_settings.path_trace, now, PATH_TRACE_BUDGET) {
+            self.upload_path_trace_frame();
+        }
+        Ok(())
+    }
+    
+    /// Trace the path traced preview to its final sample count for a `width` x `height` capture
+    ///
+    /// Snapshots neither wait for the camera to sit idle nor spread the work
+    /// over viewport frames; without path tracing this does nothing.
+    pub fn finish_path_trace_frame(&mut self, width: u32, height: u32) -> Result<(), String> {
+        self.render_path_trace_frame(width, height)?;
+        if !self.render_settings.path_trace.enabled || self.render_settings.backend != RenderBackend::Wgpu {
+            return Ok(());
+        }
+        let Some(scene) = &self.trace_scene else {
+            return Ok(());
+        };
+        let idle = Instant::now() + IDLE_DELAY;
+        if self.path_tracer.refine(scene, &self.render_settings.path_trace, idle, Duration::MAX) {
+            self.upload_path_trace_frame();
+        }
+        Ok(())
+    }
+    
+    /// Show the path traced frame so far through the current exposure and color management
+    fn upload_path_trace_frame(&mut self) {
+        let (Some(device), Some(queue)) = (&self.base_renderer.device, &self.base_renderer.queue) else {
+            return;
+        };
+        let exposure = self.active_camera_response().map_or(1.0, |response| response.exposure_scale);
+        let settings = &self.render_settings;
+        if let Some(frame) = self.path_tracer.frame(exposure, settings.path_trace.denoise, &settings.color_management) {
+            let samples = self.anti_aliasing.sample_count();
+            self.path_trace_blit.get_or_insert_with(|| HydraBlit::new(device, samples)).upload(device, queue, &frame);
+        }
+    }
+    
+    /// Change the view transform, exposure or gamma of the viewport
+    ///
+    /// The wgpu scene picks it up with the next lighting uniform; the path
+    /// traced frame accumulates linear radiance, so it is shown again through
+    /// the new transform without starting over.
+    pub fn set_color_management(&mut self, color_management: ColorManagement) {
+        if color_management != self.render_settings.color_management {
+            self.render_settings.color_management = color_management;
+            self.upload_path_trace_frame();
+        }
+    }
+    
+    /// Whether the path traced preview is drawn in place of the wgpu scene
+    fn shows_path_trace(&self) -> bool {
+        self.render_settings.path_trace.enabled
+            && self.render_settings.backend == RenderBackend::Wgpu
+            && self.path_tracer.samples > 0
+    }
+    
+    /// Whether frames get ambient occlusion; wireframes, Hydra and path traced frames are left as they are
+    fn draws_ambient_occlusion(&self) -> bool {
+        let hydra_frame = matches!(self.render_settings.backend, RenderBackend::Hydra(_)) && self.hydra.has_frame();
+        let wireframe = self.render_settings.shading_mode == ShadingMode::Wireframe;
+        self.render_settings.ambient_occlusion.enabled && !hydra_frame && !wireframe && !self.shows_path_trace()
+    }
+    
+    /// Darken a drawn `width` x `height` frame by screen space ambient occlusion, when enabled
+    ///
+    /// Runs after the scene pass, whose depth must be stored and bindable as a texture.
+    pub fn render_ambient_occlusion(&self, encoder: &mut CommandEncoder, color: &wgpu::TextureView, depth: &wgpu::TextureView, width: u32, height: u32) {
+        if !self.draws_ambient_occlusion() {
+            return;
+        }
+        let (Some(device), Some(pass)) = (&self.base_renderer.device, &self.occlusion_pass) else {
+            return;
+        };
+        let camera = self.get_active_camera();
+        let projection = Mat4::perspective_rh(camera.fov, width as f32 / height.max(1) as f32, camera.near, camera.far);
+        pass.draw(device, encoder, color, depth, width, height, &self.render_settings.ambient_occlusion.uniform(projection));
+    }
+    
+    /// Set the time code and re-sample transforms, visibility and points
+    pub fn set_time_code(&mut self, time_code: f64) -> Result<(), String> {
+        if time_code == self.current_scene.time_code {
+            return Ok(());
+        }
+        self.current_scene.time_code = time_code;
+        if !self.current_scene.stage_id.is_empty() {
+            let stage_id = self.current_scene.stage_id.clone();
+            self.load_stage(&stage_id)?;
+        }
+        Ok(())
+    }
+    
+    /// Set display complexity, re-extracting the stage when subdivision levels change
+    pub fn set_complexity(&mut self, level: ComplexityLevel) -> Result<(), String> {
+        let refine = level.subdivision_level() != self.render_settings.complexity.subdivision_level();
+        self.render_settings.complexity = level;
+        if refine && !self.current_scene.stage_id.is_empty() {
+            let stage_id = self.current_scene.stage_id.clone();
+            self.load_stage(&stage_id)?;
+        }
+        Ok(())
+    }
+    
+    /// Displayable primvars of the scene's meshes, one per name, type and interpolation
+    pub fn available_primvars(&self) -> Vec<PrimvarInfo> {
+        let mut primvars: Vec<PrimvarInfo> = self.current_scene.geometries.iter()
+            .flat_map(|geometry| geometry.primvars.iter().cloned())
+            .collect();
+        primvars.sort_by_key(|a| a.label());
+        primvars.dedup();
+        primvars
+    }
+    
+    /// Show a color primvar unlit in place of materials, or None for material shading
+    pub fn set_display_primvar(&mut self, primvar: Option<String>) -> Result<(), String> {
+        let primvar = primvar.filter(|name| !name.is_empty());
+        if primvar == self.render_settings.display_primvar {
+            return Ok(());
+        }
+        self.render_settings.display_primvar = primvar;
+        if !self.current_scene.stage_id.is_empty() {
+            let stage_id = self.current_scene.stage_id.clone();
+            self.load_stage(&stage_id)?;
+        }
+        Ok(())
+    }
+    
     /// Set camera mode
     pub fn set_camera_mode(&mut self, mode: CameraMode) {
         self.camera_mode = mode;
     }
     
     /// Get active camera for rendering
-    pub fn get_active_camera(&self) -> GpuCamera3D {
+    pub fn get_active_camera(&self) -> Camera3D {
         match &self.camera_mode {
             CameraMode::Viewport => self.base_renderer.camera.clone(),
             CameraMode::USDCamera(path) => {
@@ -602,22 +1145,24 @@ impl USDRenderer {
         }
     }
     
-    fn usd_camera_to_camera3d(&self, usd_camera: &USDCamera) -> GpuCamera3D {
+    /// Lens and exposure response of the USD camera being looked through, None for the viewport camera
+    pub fn active_camera_response(&self) -> Option<CameraResponse> {
+        let CameraMode::USDCamera(path) = &self.camera_mode else {
+            return None;
+        };
+        self.current_scene.cameras.iter().find(|camera| &camera.prim_path == path).map(USDCamera::response)
+    }
+    
+    fn usd_camera_to_camera3d(&self, usd_camera: &USDCamera) -> Camera3D {
         // Convert USD camera to viewport camera
         let mut camera = self.base_renderer.camera.clone();
-        
-        // Extract position and target from transform matrix
-        let position = usd_camera.transform.transform_point3(Vec3::ZERO);
-        let forward = -usd_camera.transform.transform_vector3(Vec3::Z);
-        let target = position + forward;
+        let projection = usd_camera.projection();
+        let (position, target, up) = projection.look_at(1.0);
         
         camera.position = position;
         camera.target = target;
-        
-        // Convert focal length to FOV
-        let fov_degrees = 2.0 * (usd_camera.horizontal_aperture / (2.0 * usd_camera.focal_length)).atan().to_degrees();
-        camera.fov = fov_degrees.to_radians();
-        
+        camera.up = up;
+        camera.fov = projection.fov_y();
         camera.near = usd_camera.clipping_range.0;
         camera.far = usd_camera.clipping_range.1;
         
@@ -625,23 +1170,390 @@ impl USDRenderer {
     }
 }
 
+impl USDRenderer {
+    /// Write the camera and lighting uniforms of a `width` x `height` frame, before its scene pass
+    pub fn prepare(&mut self, width: u32, height: u32) {
+        self.base_renderer.camera.aspect = width as f32 / height.max(1) as f32;
+        let uniforms = Uniforms3D::new(&self.get_active_camera());
+        let lighting = self.lighting_uniform();
+        self.base_renderer.update_uniforms(&uniforms, bytemuck::bytes_of(&lighting));
+    }
+    
+    /// Render the current scene offscreen and read it back as an RGBA8 frame
+    pub fn capture_frame(&mut self, width: u32, height: u32) -> Result<CapturedFrame, String> {
+        self.prepare(width, height);
+        let (Some(device), Some(queue)) = (&self.base_renderer.device, &self.base_renderer.queue) else {
+            return Err("Renderer is not initialized".to_string());
+        };
+        
+        let size = wgpu::Extent3d { width, height, depth_or_array_layers: 1 };
+        let samples = self.anti_aliasing.sample_count();
+        let texture = |label, sample_count, format, usage| device.create_texture(&wgpu::TextureDescriptor {
+            label: Some(label),
+            size,
+            mip_level_count: 1,
+            sample_count,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage,
+            view_formats: &[],
+        });
+        let color = texture(
+            "usd_capture_color",
+            1,
+            wgpu::TextureFormat::Rgba8UnormSrgb,
+            wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC | wgpu::TextureUsages::TEXTURE_BINDING,
+        );
+        // Ambient occlusion reads the depth after the scene pass
+        let occlusion = self.draws_ambient_occlusion();
+        let depth = texture(
+            "usd_capture_depth",
+            samples,
+            wgpu::TextureFormat::Depth32Float,
+            match occlusion {
+                true => wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+                false => wgpu::TextureUsages::RENDER_ATTACHMENT,
+            },
+        );
+        // With MSAA the pass draws into a multisampled color resolved into `color`
+        let multisampled = (samples > 1).then(|| texture(
+            "usd_capture_multisampled",
+            samples,
+            wgpu::TextureFormat::Rgba8UnormSrgb,
+            wgpu::TextureUsages::RENDER_ATTACHMENT,
+        ));
+        let color_view = color.create_view(&wgpu::TextureViewDescriptor::default());
+        let depth_view = depth.create_view(&wgpu::TextureViewDescriptor::default());
+        let multisampled_view = multisampled.as_ref().map(|texture| texture.create_view(&wgpu::TextureViewDescriptor::default()));
+        
+        // Rows must be padded to the copy alignment
+        let unpadded_row = width * 4;
+        let padded_row = unpadded_row.div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT) * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let readback = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("usd_capture_readback"),
+            size: (padded_row * height) as u64,
+            usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        
+        let depth_store = match occlusion {
+            true => wgpu::StoreOp::Store,
+            false => wgpu::StoreOp::Discard,
+        };
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("usd_capture") });
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("usd_capture_pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: multisampled_view.as_ref().unwrap_or(&color_view),
+                    resolve_target: multisampled_view.as_ref().map(|_| &color_view),
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color { r: 0.18, g: 0.18, b: 0.18, a: 1.0 }),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: &depth_view,
+                    depth_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(1.0),
+                        store: depth_store,
+                    }),
+                    stencil_ops: None,
+                }),
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            self.render_to_pass(&mut render_pass);
+        }
+        self.render_ambient_occlusion(&mut encoder, &color_view, &depth_view, width, height);
+        let smoothed = self.fxaa_pass.is_some().then(|| texture(
+            "usd_capture_fxaa",
+            1,
+            wgpu::TextureFormat::Rgba8UnormSrgb,
+            wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        ));
+        if let Some(smoothed) = &smoothed {
+            self.render_anti_aliasing(&mut encoder, &color_view, &smoothed.create_view(&wgpu::TextureViewDescriptor::default()));
+        }
+        encoder.copy_texture_to_buffer(
+            wgpu::TexelCopyTextureInfo {
+                texture: smoothed.as_ref().unwrap_or(&color),
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::TexelCopyBufferInfo {
+                buffer: &readback,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_row),
+                    rows_per_image: Some(height),
+                },
+            },
+            size,
+        );
+        queue.submit(Some(encoder.finish()));
+        
+        let slice = readback.slice(..);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        let _ = device.poll(wgpu::PollType::Wait);
+        receiver.recv()
+            .map_err(|e| format!("Failed to read back frame: {}", e))?
+            .map_err(|e| format!("Failed to map frame buffer: {}", e))?;
+        
+        let mut pixels = Vec::with_capacity((unpadded_row * height) as usize);
+        {
+            let data = slice.get_mapped_range();
+            for row in data.chunks(padded_row as usize) {
+                pixels.extend_from_slice(&row[..unpadded_row as usize]);
+            }
+        }
+        readback.unmap();
+        
+        CapturedFrame::new(width, height, pixels)
+    }
+    
+    /// Capture a frame range to disk, re-sampling the stage at every time code
+    pub fn capture_sequence(&mut self, settings: &CaptureSettings, start: f64, end: f64, fps: f64) -> Result<Vec<String>, String> {
+        let camera = match &self.camera_mode {
+            CameraMode::USDCamera(path) => path.clone(),
+            CameraMode::Viewport => "persp".to_string(),
+        };
+        let stage_id = self.current_scene.stage_id.clone();
+        
+        let mut written = Vec::new();
+        let mut manifest = IdManifest::default();
+        let mut frame = start;
+        while frame <= end {
+            self.set_time_code(frame)?;
+            let captured = self.capture_frame(settings.width, settings.height)?;
+            let matte = (settings.id_matte || !settings.aovs.is_empty())
+                .then(|| self.capture_id_matte(settings.width, settings.height, &mut manifest));
+            match &matte {
+                Some(matte) if !settings.aovs.is_empty() => {
+                    written.extend(settings.write_frame_with_aovs(captured, matte, &manifest, &stage_id, &camera, frame, fps)?);
+                }
+                _ => written.push(settings.write_frame(captured, &stage_id, &camera, frame, fps)?),
+            }
+            if let Some(matte) = matte.filter(|_| settings.id_matte) {
+                written.push(settings.write_id_matte(&matte, frame)?);
+            }
+            frame += 1.0;
+        }
+        if settings.id_matte {
+            written.push(settings.write_id_manifest(&manifest)?);
+        }
+        Ok(written)
+    }
+    
+    /// Render a material preview stage through its preview camera
+    ///
+    /// `time_code` picks the turntable angle. The stage is always reloaded,
+    /// since the engine rebuilds previews in place when the material changes.
+    pub fn render_material_preview(&mut self, preview_stage_id: &str, time_code: f64, size: u32) -> Result<CapturedFrame, String> {
+        self.current_scene.time_code = time_code;
+        self.load_stage(preview_stage_id)?;
+        self.set_shading_mode(ShadingMode::MaterialPreview);
+        self.set_camera_mode(CameraMode::USDCamera("/Preview/Camera".to_string()));
+        self.capture_frame(size, size)
+    }
+    
+    /// Rasterize a prim ID matte from the active camera, with the depth and normals of its AOVs
+    ///
+    /// Every drawn prim path is added to `manifest`. Instanced geometry is
+    /// keyed by its prototype mesh path.
+    pub fn capture_id_matte(&self, width: u32, height: u32, manifest: &mut IdManifest) -> IdMatte {
+        let mut camera = self.get_active_camera();
+        camera.aspect = width as f32 / height.max(1) as f32;
+        let view_projection = camera.build_view_projection_matrix();
+        
+        let mut matte = IdMatte::new(width, height);
+        let points_and_normals = |geometry: &USDGeometry| -> (Vec<Vec3>, Vec<Vec3>) {
+            geometry.vertices.iter().map(|vertex| (Vec3::from(vertex.position), Vec3::from(vertex.normal))).unzip()
+        };
+        
+        for geometry in &self.current_scene.geometries {
+            if !geometry.visibility || self.current_scene.prototype_geometry.contains(&geometry.prim_path) {
+                continue;
+            }
+            let id = manifest.insert(&geometry.prim_path);
+            let (points, normals) = points_and_normals(geometry);
+            matte.draw_mesh_with_normals(&points, &normals, &geometry.indices, geometry.transform, view_projection, id);
+        }
+        
+        for batch in &self.current_scene.instance_batches {
+            let Some(geometry) = self.current_scene.geometries.iter().find(|g| g.prim_path == batch.geometry_path) else {
+                continue;
+            };
+            let id = manifest.insert(&batch.geometry_path);
+            let (points, normals) = points_and_normals(geometry);
+            for transform in &batch.transforms {
+                matte.draw_mesh_with_normals(&points, &normals, &geometry.indices, *transform, view_projection, id);
+            }
+        }
+        
+        matte
+    }
+    
+    /// Authored faces drawn inside a pixel rectangle of a `width` x `height` view
+    ///
+    /// Only the nearest surface is picked, through the active camera.
+    /// Instanced geometry reports faces of its prototype mesh; implicit
+    /// shapes hide what is behind them but have no faces to pick.
+    pub fn pick_faces(&mut self, width: u32, height: u32, rect: PickRect) -> Result<FaceSelection, String> {
+        let Some(rect) = rect.clamped(width, height) else {
+            return Ok(FaceSelection::new());
+        };
+        let pixels = self.pick_pixels(width, height, rect)?;
+        Ok(self.picked_faces(&pixels))
+    }
+    
+    /// Authored faces of the pick pass's (geometry + 1, triangle + 1) pixels
+    fn picked_faces(&self, pixels: &[[u32; 2]]) -> FaceSelection {
+        let scene = &self.current_scene;
+        resolve_faces(pixels, |geometry, triangle| {
+            let geometry = scene.geometries.get(geometry as usize)?;
+            Some((geometry.prim_path.clone(), *geometry.face_ids.get(triangle as usize)?))
+        })
+    }
+    
+    /// Prim drawn at a pixel of a `width` x `height` view, if any
+    ///
+    /// Instanced geometry reports its prototype mesh.
+    pub fn pick_prim(&mut self, width: u32, height: u32, x: f32, y: f32) -> Result<Option<String>, String> {
+        let Some(rect) = PickRect::from_corners((x, y), (x, y)).clamped(width, height) else {
+            return Ok(None);
+        };
+        let pixels = self.pick_pixels(width, height, rect)?;
+        Ok(self.picked_prim(&pixels))
+    }
+    
+    /// Prim of the first of the pick pass's pixels, if anything was drawn there
+    fn picked_prim(&self, pixels: &[[u32; 2]]) -> Option<String> {
+        pixels.first()
+            .filter(|[geometry, _]| *geometry > 0)
+            .and_then(|[geometry, _]| self.current_scene.geometries.get(*geometry as usize - 1))
+            .map(|geometry| geometry.prim_path.clone())
+    }
+    
+    /// Draw the pick pass and read back its (geometry + 1, triangle + 1) pixels inside a clamped rectangle
+    fn pick_pixels(&mut self, width: u32, height: u32, rect: PickRect) -> Result<Vec<[u32; 2]>, String> {
+        let (Some(device), Some(queue)) = (&self.base_renderer.device, &self.base_renderer.queue) else {
+            return Err("Renderer is not initialized".to_string());
+        };
+        
+        let mut camera = self.get_active_camera();
+        camera.aspect = width as f32 / height.max(1) as f32;
+        let view_projection = camera.build_view_projection_matrix();
+        
+        let scene = &self.current_scene;
+        let mut draws = Vec::new();
+        for (index, geometry) in scene.geometries.iter().enumerate() {
+            let Some((vertices, indices, index_count)) = self.geometry_buffers.get(&geometry.prim_path) else {
+                continue;
+            };
+            let draw = |object_to_clip| PickDraw { geometry: index as u32, vertices, indices, index_count: *index_count, object_to_clip };
+            if geometry.visibility && !scene.prototype_geometry.contains(&geometry.prim_path) {
+                draws.push(draw(view_projection * geometry.transform));
+            }
+            for batch in scene.instance_batches.iter().filter(|batch| batch.geometry_path == geometry.prim_path) {
+                draws.extend(batch.transforms.iter().map(|transform| draw(view_projection * *transform)));
+            }
+        }
+        
+        self.face_picker.get_or_insert_with(|| FacePicker::new(device))
+            .pick(device, queue, &draws, width, height, rect)
+    }
+}
+
 impl USDRenderPass for USDRenderer {
     fn render_to_pass(&self, render_pass: &mut wgpu::RenderPass) {
-        // Camera uniforms are already updated in the callback's prepare method
+        // Camera and lighting uniforms are written by `prepare` before the pass
+        
+        // A Hydra frame replaces the scene; only the axis gizmo is drawn over it
+        if matches!(self.render_settings.backend, RenderBackend::Hydra(_)) && self.hydra.has_frame() {
+            if let Some(blit) = &self.hydra.blit {
+                if blit.draw(render_pass) {
+                    self.base_renderer.render_axis_gizmo(render_pass);
+                    return;
+                }
+            }
+        }
+        
+        // So does a path traced frame, once every pixel has a sample
+        if self.shows_path_trace() {
+            if let Some(blit) = &self.path_trace_blit {
+                if blit.draw(render_pass) {
+                    self.base_renderer.render_axis_gizmo(render_pass);
+                    return;
+                }
+            }
+        }
+        
+        // Culled prims are skipped here, their buffers stay uploaded
+        let view_projection = self.get_active_camera().build_view_projection_matrix();
+        let mut cull = FrustumCull::new(view_projection, self.render_settings.frustum_culling);
         
         // Render all geometry based on shading mode
-        for geometry in &self.current_scene.geometries {
-            if !geometry.visibility {
-                continue;
+        let wireframe = self.render_settings.shading_mode == ShadingMode::Wireframe;
+        let polygons = self.render_settings.preserve_quad_wireframe;
+        let draws_meshes = self.base_renderer.bind_mesh_pipeline(render_pass, wireframe);
+        let draw = |render_pass: &mut wgpu::RenderPass, geometry_path: &str, transforms: &Buffer, instance_count: u32| {
+            let (Some((vertex_buffer, index_buffer, index_count)), true) = (self.geometry_buffers.get(geometry_path), draws_meshes) else {
+                return;
+            };
+            let masks = self.light_link_masks(geometry_path);
+            render_pass.set_push_constants(wgpu::ShaderStages::FRAGMENT, 0, bytemuck::bytes_of(&masks));
+            let material = self.material_constants(geometry_path);
+            render_pass.set_push_constants(wgpu::ShaderStages::FRAGMENT, MATERIAL_CONSTANTS_OFFSET, bytemuck::bytes_of(&material));
+            if let Some(bind_group) = self.material_bind_group(geometry_path) {
+                render_pass.set_bind_group(MATERIAL_TEXTURE_GROUP, bind_group, &[]);
             }
-            
-            if let Some((vertex_buffer, index_buffer, index_count)) = self.geometry_buffers.get(&geometry.prim_path) {
-                match self.render_settings.shading_mode {
-                    ShadingMode::Wireframe | ShadingMode::WireframeOnSurface => {
-                        self.base_renderer.render_wireframe(render_pass, vertex_buffer, index_buffer, *index_count);
+            if let Some(bindings) = &self.vertex_attributes {
+                render_pass.set_bind_group(VERTEX_ATTRIBUTE_GROUP, bindings.bind_group(geometry_path), &[]);
+            }
+            // Wireframes draw the authored polygon edges, or the triangle indices as lines
+            let (indices, count) = match self.edge_buffers.get(geometry_path) {
+                Some((edge_buffer, edge_count)) if wireframe && polygons => (edge_buffer, *edge_count),
+                _ => (index_buffer, *index_count),
+            };
+            self.base_renderer.render_mesh(render_pass, vertex_buffer, indices, count, transforms, instance_count);
+        };
+        
+        // Point instancer prototypes share buffers and draw every instance in one call
+        for scene_draw in self.scene_draws(&mut cull) {
+            match scene_draw {
+                SceneDraw::Geometry(prim_path) => {
+                    if let Some(transform_buffer) = self.transform_buffers.get(prim_path) {
+                        draw(render_pass, prim_path, transform_buffer, 1);
                     }
-                    _ => {
-                        self.base_renderer.render_mesh(render_pass, vertex_buffer, index_buffer, *index_count);
+                }
+                SceneDraw::Batch(index) => {
+                    let batch = &self.current_scene.instance_batches[index];
+                    if let Some((instance_buffer, instance_count)) = self.instance_buffers.get(index) {
+                        draw(render_pass, &batch.geometry_path, instance_buffer, *instance_count);
+                    }
+                }
+            }
+        }
+        self.cull_stats.set(cull.stats());
+        
+        // Wireframe on shaded outlines the drawn surfaces in a second pass, pulled in front of them
+        if let (ShadingMode::WireframeOnSurface, Some(overlay)) = (&self.render_settings.shading_mode, &self.wireframe_overlay) {
+            for geometry in &self.current_scene.geometries {
+                let hidden = !geometry.visibility || self.current_scene.prototype_geometry.contains(&geometry.prim_path);
+                if hidden || !cull.in_view(self.world_bounds.get(&geometry.prim_path)) {
+                    continue;
+                }
+                overlay.draw(render_pass, &geometry.prim_path, view_projection * geometry.transform, polygons);
+            }
+            for (index, batch) in self.current_scene.instance_batches.iter().enumerate() {
+                if cull.in_view(self.instance_bounds.get(index)) {
+                    for transform in &batch.transforms {
+                        overlay.draw(render_pass, &batch.geometry_path, view_projection * *transform, polygons);
                     }
                 }
             }
@@ -657,6 +1569,777 @@ impl USDRenderPass for USDRenderer {
     }
 }
 
-// Need to add wgpu::util for buffer creation
-use wgpu::util::DeviceExt;
-use crate::gpu::viewport_3d_callback::USDRenderPass;
\ No newline at end of file
+/// A draw of the scene pass: a geometry prim on its own, or an instance batch by index
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SceneDraw<'a> {
+    Geometry(&'a str),
+    Batch(usize),
+}
+
+/// Upload per-instance transform buffers for instance batches
+fn create_instance_buffers(device: &wgpu::Device, batches: &[InstanceBatch]) -> Vec<(Buffer, u32)> {
+    batches.iter()
+        .map(|batch| {
+            let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some(&format!("{}_instances", batch.geometry_path)),
+                contents: bytemuck::cast_slice(&batch.instance_data()),
+                usage: BufferUsages::VERTEX,
+            });
+            (buffer, batch.transforms.len() as u32)
+        })
+        .collect()
+}
+
+/// Rebuild the wire overlay's edge lists of the uploaded geometry
+fn rebuild_wire_overlay(overlay: &mut WireframeOverlay, device: &wgpu::Device, scene: &USDScene, buffers: &HashMap<String, (Buffer, Buffer, u32)>) {
+    let geometries = scene.geometries.iter().filter_map(|geometry| {
+        let (vertices, _, _) = buffers.get(&geometry.prim_path)?;
+        Some((&geometry.prim_path, vertices, geometry.edge_indices.as_slice(), geometry.indices.as_slice()))
+    });
+    overlay.rebuild(device, geometries);
+}
+
+/// Convert a row-major Gf.Matrix4d (row vectors) into a column-vector glam matrix
+pub fn usd_matrix_to_mat4(rows: &[Vec<f64>]) -> Mat4 {
+    let mut cols = [[0.0f32; 4]; 4];
+    for (r, row) in rows.iter().take(4).enumerate() {
+        for (c, value) in row.iter().take(4).enumerate() {
+            cols[r][c] = *value as f32;
+        }
+    }
+    Mat4::from_cols_array_2d(&cols)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::renderer_3d::{request_device, DRAW_CONSTANTS_SIZE};
+    use super::super::primvars::{Interpolation, MeshPrimvars, Primvar, PrimvarInfo};
+    use super::super::scene_delegate::build_mesh_geometry;
+    
+    /// Renderer with the stand-in scene of a stage the engine doesn't know: a cube, a sphere and a ground plane
+    ///
+    /// No device is needed; the scene is extracted and shaded on the CPU side.
+    fn stand_in_renderer() -> USDRenderer {
+        let mut renderer = USDRenderer::new();
+        renderer.load_stage("usd_rendering_test").unwrap();
+        renderer
+    }
+    
+    /// Paths of the prims a frame draws on their own
+    fn drawn_prims(renderer: &USDRenderer, cull: &mut FrustumCull) -> Vec<String> {
+        renderer.scene_draws(cull).into_iter()
+            .filter_map(|draw| match draw {
+                SceneDraw::Geometry(prim_path) => Some(prim_path.to_string()),
+                SceneDraw::Batch(_) => None,
+            })
+            .collect()
+    }
+    
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn captures_the_stand_in_scene() {
+        let (device, queue) = request_device().expect("needs a GPU adapter");
+        let mut renderer = USDRenderer::new();
+        renderer.initialize(device, queue);
+        renderer.load_stage("usd_rendering_test").unwrap();
+        assert!(!renderer.geometry_buffers.is_empty());
+        renderer.render_settings.ambient_occlusion.enabled = true;
+        for anti_aliasing in [AntiAliasing::Off, AntiAliasing::Fxaa, AntiAliasing::Msaa(4)] {
+            renderer.set_anti_aliasing(anti_aliasing);
+            for mode in ShadingMode::PANEL {
+                renderer.set_shading_mode(mode.clone());
+                let frame = renderer.capture_frame(64, 48).unwrap();
+                assert_eq!(frame.pixels.len(), 64 * 48 * 4, "{} {}", anti_aliasing.label(), mode.label());
+                // The stand-in scene shows up against the background
+                let corner = &frame.pixels[..4];
+                assert!(frame.pixels.chunks(4).any(|pixel| pixel != corner), "{} {}", anti_aliasing.label(), mode.label());
+            }
+        }
+        let prims = renderer.current_scene.geometries.len();
+        assert_eq!(renderer.cull_stats(), CullStats { drawn: prims, culled: 0 });
+    }
+    
+    mod shading {
+        use super::*;
+        
+        #[test]
+        fn panel_shading_labels_round_trip() {
+            for mode in ShadingMode::PANEL {
+                assert_eq!(ShadingMode::from_label(mode.label()), Some(mode));
+            }
+            assert_eq!(ShadingMode::from_label("Bounds"), None);
+        }
+        
+        #[test]
+        fn anti_aliasing_waits_for_a_device() {
+            let mut renderer = USDRenderer::new();
+            renderer.set_anti_aliasing(AntiAliasing::Msaa(8));
+            assert_eq!(renderer.render_settings.anti_aliasing, AntiAliasing::Msaa(8));
+            // The device's supported sample counts resolve the request when it is initialized
+            assert_eq!(renderer.sample_count(), 1);
+        }
+        
+        #[test]
+        fn occludes_shaded_wgpu_frames() {
+            let mut renderer = stand_in_renderer();
+            assert!(!renderer.draws_ambient_occlusion());
+            renderer.render_settings.ambient_occlusion.enabled = true;
+            assert!(renderer.draws_ambient_occlusion());
+            renderer.set_shading_mode(ShadingMode::Wireframe);
+            assert!(!renderer.draws_ambient_occlusion());
+            
+            // Path traced frames are left as they are once they have a sample
+            renderer.set_shading_mode(ShadingMode::SmoothShaded);
+            renderer.set_path_trace(PathTraceSettings { enabled: true, ..PathTraceSettings::default() });
+            assert!(renderer.draws_ambient_occlusion());
+            renderer.path_tracer.samples = 1;
+            assert!(renderer.shows_path_trace());
+            assert!(!renderer.draws_ambient_occlusion());
+        }
+    }
+    
+    mod culling {
+        use super::*;
+        use super::super::super::instancing::build_instance_batches;
+        
+        fn view_projection(renderer: &USDRenderer) -> Mat4 {
+            renderer.get_active_camera().build_view_projection_matrix()
+        }
+        
+        #[test]
+        fn culls_prims_outside_the_view() {
+            let mut renderer = stand_in_renderer();
+            renderer.cache_world_bounds();
+            let prims = renderer.current_scene.geometries.len();
+            let mut cull = FrustumCull::new(view_projection(&renderer), true);
+            assert_eq!(drawn_prims(&renderer, &mut cull).len(), prims);
+            assert_eq!(cull.stats(), CullStats { drawn: prims, culled: 0 });
+            
+            // Turn the camera away from the stand-in scene around the origin
+            let camera = &mut renderer.base_renderer.camera;
+            camera.target = camera.position * 2.0;
+            let mut cull = FrustumCull::new(view_projection(&renderer), true);
+            assert!(drawn_prims(&renderer, &mut cull).is_empty());
+            assert_eq!(cull.stats(), CullStats { drawn: 0, culled: prims });
+            // Turned off, everything is drawn
+            let mut cull = FrustumCull::new(view_projection(&renderer), false);
+            assert_eq!(drawn_prims(&renderer, &mut cull).len(), prims);
+        }
+        
+        #[test]
+        fn draws_each_instance_batch_in_one_call() {
+            let mut renderer = stand_in_renderer();
+            // Turn the stand-in cube into the prototype of three instances
+            let transforms: Vec<Mat4> = (0..3).map(|i| Mat4::from_translation(Vec3::new(i as f32 * 3.0 - 3.0, 2.0, 0.0))).collect();
+            renderer.current_scene.prototype_geometry.insert("/World/Cube".to_string());
+            renderer.current_scene.instance_batches.push(InstanceBatch { geometry_path: "/World/Cube".to_string(), transforms });
+            renderer.cache_world_bounds();
+            
+            // The prototype isn't drawn on its own; its batch counts as one prim
+            let mut cull = FrustumCull::new(view_projection(&renderer), true);
+            let draws = renderer.scene_draws(&mut cull);
+            assert_eq!(draws, [SceneDraw::Geometry("/World/Sphere"), SceneDraw::Geometry("/World/Plane"), SceneDraw::Batch(0)]);
+            assert_eq!(cull.stats(), CullStats { drawn: 3, culled: 0 });
+        }
+        
+        #[test]
+        fn draws_point_instancer_prototypes_as_batches() {
+            let mut renderer = stand_in_renderer();
+            // An instancer over the stand-in cube and sphere, its third instance hidden
+            let geometries: Vec<(String, Mat4)> = renderer.current_scene.geometries.iter()
+                .map(|geometry| (geometry.prim_path.clone(), geometry.transform))
+                .collect();
+            let prototypes = [("/World/Cube".to_string(), geometries[0].1), ("/World/Sphere".to_string(), geometries[1].1)];
+            let transforms = [Mat4::IDENTITY, Mat4::from_translation(Vec3::Y), Mat4::from_translation(Vec3::NEG_Y)];
+            let batches = build_instance_batches(Mat4::IDENTITY, &prototypes, &[0, 1, 0, 1], &[true, true, false, true],
+                                                 &transforms, &geometries).unwrap();
+            assert_eq!(batches.iter().map(|batch| batch.transforms.len()).collect::<Vec<_>>(), vec![1, 2]);
+            let scene = &mut renderer.current_scene;
+            scene.prototype_geometry.extend(prototypes.iter().map(|(path, _)| path.clone()));
+            scene.instance_batches = batches;
+            renderer.cache_world_bounds();
+            
+            // The plane, then one draw per prototype
+            let mut cull = FrustumCull::new(view_projection(&renderer), true);
+            assert_eq!(renderer.scene_draws(&mut cull), [SceneDraw::Geometry("/World/Plane"), SceneDraw::Batch(0), SceneDraw::Batch(1)]);
+            assert_eq!(cull.stats(), CullStats { drawn: 3, culled: 0 });
+        }
+    }
+    
+    mod capture {
+        use super::*;
+        use crate::capture::aov::Aov;
+        use crate::capture::id_matte::prim_id;
+        use crate::capture::slate::SlateTemplate;
+        
+        /// A flat grey frame standing in for a captured one
+        fn grey_frame(width: u32, height: u32) -> CapturedFrame {
+            CapturedFrame::new(width, height, [200, 200, 200, 255].repeat((width * height) as usize)).unwrap()
+        }
+        
+        #[test]
+        fn id_mattes_key_instances_by_prototype() {
+            // The matte is rasterized on the CPU
+            let mut renderer = stand_in_renderer();
+            let mut manifest = IdManifest::default();
+            let matte = renderer.capture_id_matte(64, 48, &mut manifest);
+            assert_eq!(manifest.len(), renderer.current_scene.geometries.len());
+            for geometry in &renderer.current_scene.geometries {
+                assert!(matte.ids.contains(&prim_id(&geometry.prim_path)), "{}", geometry.prim_path);
+            }
+            
+            // Instances of the cube share its id
+            let scene = &mut renderer.current_scene;
+            scene.prototype_geometry.insert("/World/Cube".to_string());
+            scene.instance_batches.push(InstanceBatch {
+                geometry_path: "/World/Cube".to_string(),
+                transforms: vec![Mat4::from_translation(Vec3::new(0.0, 2.0, 0.0)), Mat4::from_translation(Vec3::new(-2.0, 2.0, 0.0))],
+            });
+            let mut manifest = IdManifest::default();
+            let matte = renderer.capture_id_matte(64, 48, &mut manifest);
+            assert_eq!(manifest.len(), renderer.current_scene.geometries.len());
+            assert!(matte.ids.contains(&prim_id("/World/Cube")));
+        }
+        
+        #[test]
+        fn writes_aovs_as_exr_layers() {
+            let renderer = stand_in_renderer();
+            let directory = std::env::temp_dir().join(format!("usd_rendering_test_{}", std::process::id()));
+            let settings = CaptureSettings {
+                output_pattern: directory.join("frame.#.exr").to_string_lossy().into_owned(),
+                width: 32,
+                height: 32,
+                aovs: Aov::ALL.to_vec(),
+                ..CaptureSettings::default()
+            };
+            let mut manifest = IdManifest::default();
+            let matte = renderer.capture_id_matte(32, 32, &mut manifest);
+            let written = settings.write_frame_with_aovs(grey_frame(32, 32), &matte, &manifest, "usd_rendering_test", "persp", 1.0, 24.0).unwrap();
+            let meta = exr::meta::MetaData::read_from_file(&written[0], false).unwrap();
+            let channels: Vec<String> = meta.headers[0].channels.list.iter().map(|channel| channel.name.to_string()).collect();
+            for aov in Aov::ALL {
+                assert!(aov.channel_names().iter().all(|name| channels.contains(name)), "{} in {:?}", aov.label(), channels);
+            }
+            let _ = std::fs::remove_dir_all(directory);
+        }
+        
+        #[test]
+        fn writes_slated_frames() {
+            let directory = std::env::temp_dir().join(format!("usd_rendering_slate_{}", std::process::id()));
+            let settings = CaptureSettings {
+                output_pattern: directory.join("playblast.####.png").to_string_lossy().into_owned(),
+                width: 64,
+                height: 48,
+                slate: Some(SlateTemplate::default()),
+                ..CaptureSettings::default()
+            };
+            let written = settings.write_frame(grey_frame(64, 48), "usd_rendering_test", "persp", 3.0, 24.0).unwrap();
+            assert!(written.ends_with("playblast.0003.png"), "{}", written);
+            
+            // The slate's band darkens the bottom left corner
+            let slated = image::open(&written).unwrap().to_rgba8();
+            assert_eq!(slated.dimensions(), (64, 48));
+            assert!(slated.get_pixel(0, 47)[0] < 200, "{:?}", slated.get_pixel(0, 47));
+            let _ = std::fs::remove_dir_all(directory);
+        }
+    }
+    
+    mod geometry {
+        use super::*;
+        
+        #[test]
+        fn polygon_edges_skip_triangulation_diagonals() {
+            // A quad and a pentagon sharing an edge
+            let points = [
+                Vec3::new(0.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0), Vec3::new(1.0, 1.0, 0.0), Vec3::new(0.0, 1.0, 0.0),
+                Vec3::new(2.0, 0.0, 0.0), Vec3::new(2.5, 0.5, 0.0), Vec3::new(2.0, 1.0, 0.0),
+            ];
+            let polygons = build_mesh_geometry("/World/Polygons", &points, &[4, 5], &[0, 1, 2, 3, 1, 4, 5, 6, 2], &[],
+                                               Mat4::IDENTITY, &MeshPrimvars::default()).unwrap();
+            assert_eq!(polygons.indices.len(), (2 + 3) * 3);
+            // Polygon outlines with the shared edge once
+            assert_eq!(polygons.edge_indices.len(), (4 + 5 - 1) * 2);
+        }
+        
+        #[test]
+        fn computes_tangents_of_meshes_with_st() {
+            let points = [Vec3::new(-1.0, -1.0, 0.0), Vec3::new(1.0, -1.0, 0.0), Vec3::new(1.0, 1.0, 0.0), Vec3::new(-1.0, 1.0, 0.0)];
+            let primvars = MeshPrimvars {
+                st: Some(Primvar::from_components("st", Interpolation::Vertex, &[0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0, 1.0], 2)),
+                ..MeshPrimvars::default()
+            };
+            let card = build_mesh_geometry("/World/Card", &points, &[4], &[0, 1, 2, 3], &[], Mat4::IDENTITY, &primvars).unwrap();
+            assert_eq!(card.tangents.len(), card.vertices.len());
+            // st runs along +X, so the tangents do too
+            assert!(card.tangents.iter().all(|tangent| tangent.truncate().abs_diff_eq(Vec3::X, 1e-5)), "{:?}", card.tangents);
+        }
+        
+        #[test]
+        fn welds_face_varying_corners_across_shared_edges() {
+            // Two quads sharing the edge 1-4, with st continuous across it or seamed
+            let points = [
+                Vec3::new(-2.0, 0.0, -1.0), Vec3::new(0.0, 0.0, -1.0), Vec3::new(2.0, 0.0, -1.0),
+                Vec3::new(-2.0, 0.0, 1.0), Vec3::new(0.0, 0.0, 1.0), Vec3::new(2.0, 0.0, 1.0),
+            ];
+            let strip = |prim_path: &str, seam: f32| {
+                let st = [0.0, 0.0, 0.0, 1.0, 0.5, 1.0, 0.5, 0.0, 0.5 + seam, 0.0, 0.5 + seam, 1.0, 1.0, 1.0, 1.0, 0.0];
+                let primvars = MeshPrimvars {
+                    st: Some(Primvar::from_components("st", Interpolation::FaceVarying, &st, 2)),
+                    ..MeshPrimvars::default()
+                };
+                build_mesh_geometry(prim_path, &points, &[4, 4], &[0, 3, 4, 1, 1, 4, 5, 2], &[], Mat4::IDENTITY, &primvars).unwrap()
+            };
+            let continuous = strip("/World/Continuous", 0.0);
+            let seamed = strip("/World/Seamed", 0.25);
+            assert_eq!(continuous.vertices.len(), 6);
+            assert_eq!(seamed.vertices.len(), 8);
+            // The seam doesn't add polygon edges
+            assert_eq!(continuous.edge_indices.len(), 7 * 2);
+            assert_eq!(seamed.edge_indices.len(), 7 * 2);
+            // The shared corner at point 1 keeps one st welded, or one per side of the seam
+            let corner_st = |geometry: &USDGeometry| {
+                let mut st: Vec<[f32; 2]> = geometry.vertices.iter()
+                    .filter(|vertex| vertex.position == points[1].to_array())
+                    .map(|vertex| vertex.uv)
+                    .collect();
+                st.sort_by(|a, b| a[0].total_cmp(&b[0]));
+                st
+            };
+            assert_eq!(corner_st(&continuous), vec![[0.5, 0.0]]);
+            assert_eq!(corner_st(&seamed), vec![[0.5, 0.0], [0.75, 0.0]]);
+        }
+        
+        #[test]
+        fn complexity_changes_re_extract_subdivision_levels() {
+            let mut renderer = stand_in_renderer();
+            let extracted = renderer.current_scene.geometries.len();
+            // A geometry the delegate doesn't extract tells when the scene is read again
+            let stray = renderer.current_scene.geometries[0].clone();
+            renderer.current_scene.geometries.push(stray);
+            
+            renderer.set_complexity(renderer.render_settings.complexity.clone()).unwrap();
+            assert_eq!(renderer.current_scene.geometries.len(), extracted + 1);
+            renderer.set_complexity(ComplexityLevel::VeryHigh).unwrap();
+            assert_eq!(renderer.current_scene.geometries.len(), extracted);
+            assert_eq!(renderer.extraction_settings().subdivision_level, 3);
+        }
+        
+        #[test]
+        fn time_changes_re_sample_the_stage() {
+            let mut renderer = stand_in_renderer();
+            let extracted = renderer.current_scene.geometries.len();
+            let stray = renderer.current_scene.geometries[0].clone();
+            renderer.current_scene.geometries.push(stray);
+            
+            // Skinned points, visibility and transforms are read again at the new time
+            renderer.set_time_code(0.0).unwrap();
+            assert_eq!(renderer.current_scene.geometries.len(), extracted + 1);
+            renderer.set_time_code(12.0).unwrap();
+            assert_eq!(renderer.current_scene.geometries.len(), extracted);
+            assert_eq!(renderer.current_scene.time_code, 12.0);
+            assert_eq!(renderer.extraction_settings().time_code, 12.0);
+        }
+        
+        #[test]
+        fn lists_each_display_primvar_once() {
+            let mut renderer = stand_in_renderer();
+            let primvar = |name: &str, type_name: &str| PrimvarInfo {
+                name: name.to_string(),
+                type_name: type_name.to_string(),
+                interpolation: Interpolation::Vertex,
+            };
+            renderer.current_scene.geometries[0].primvars = vec![primvar("displayColor", "color3f[]"), primvar("Cd", "color3f[]")];
+            renderer.current_scene.geometries[1].primvars = vec![primvar("Cd", "color3f[]"), primvar("Cd", "float[]")];
+            let labels: Vec<String> = renderer.available_primvars().iter().map(PrimvarInfo::label).collect();
+            assert_eq!(labels, ["Cd (color3f[], vertex)", "Cd (float[], vertex)", "displayColor (color3f[], vertex)"]);
+            
+            // Choosing a primvar extracts its colors again; an empty name is material shading
+            let extracted = renderer.current_scene.geometries.len();
+            let stray = renderer.current_scene.geometries[0].clone();
+            renderer.current_scene.geometries.push(stray);
+            renderer.set_display_primvar(Some(String::new())).unwrap();
+            assert_eq!(renderer.current_scene.geometries.len(), extracted + 1);
+            renderer.set_display_primvar(Some("Cd".to_string())).unwrap();
+            assert_eq!(renderer.current_scene.geometries.len(), extracted);
+            assert_eq!(renderer.extraction_settings().display_primvar.as_deref(), Some("Cd"));
+        }
+    }
+    
+    mod materials {
+        use super::*;
+        use crate::core::materialx::preview_values;
+        use super::super::super::textures::{decode_texture, uses_srgb};
+        
+        #[test]
+        fn uniforms_match_the_mesh_shader() {
+            // Link masks, then the material constants at vec4 alignment, fill the pipelines' push constant range
+            assert!(std::mem::size_of::<LightLinkMasks>() as u32 <= MATERIAL_CONSTANTS_OFFSET);
+            assert_eq!(MATERIAL_CONSTANTS_OFFSET % 16, 0);
+            assert_eq!(MATERIAL_CONSTANTS_OFFSET + std::mem::size_of::<MaterialConstants>() as u32, DRAW_CONSTANTS_SIZE);
+        }
+        
+        #[test]
+        fn draws_prims_with_their_bound_material() {
+            let mut renderer = stand_in_renderer();
+            let red = USDMaterial {
+                prim_path: "/World/Looks/Red".to_string(),
+                diffuse_color: Vec3::X,
+                ..renderer.current_scene.materials[DEFAULT_MATERIAL].clone()
+            };
+            let default_color = renderer.current_scene.materials[DEFAULT_MATERIAL].diffuse_color.to_array();
+            renderer.current_scene.materials.insert(red.prim_path.clone(), red);
+            renderer.current_scene.geometries[0].material_path = Some("/World/Looks/Red".to_string());
+            renderer.current_scene.geometries[1].material_path = Some("/World/Looks/Missing".to_string());
+            
+            assert_eq!(renderer.material_constants("/World/Cube").base_color, [1.0, 0.0, 0.0]);
+            assert_eq!(renderer.material_constants("/World/Sphere").base_color, default_color);
+            // Display color shading ignores bindings
+            renderer.set_shading_mode(ShadingMode::DisplayColor);
+            assert_eq!(renderer.material_constants("/World/Cube").base_color, default_color);
+        }
+        
+        #[test]
+        fn material_constants_carry_the_preview_surface() {
+            let mut renderer = stand_in_renderer();
+            let glass = USDMaterial {
+                prim_path: "/World/Looks/Glass".to_string(),
+                diffuse_color: Vec3::ONE,
+                metallic: 0.0,
+                roughness: 0.05,
+                opacity: 0.25,
+                emission_color: Vec3::new(0.0, 0.0, 2.0),
+                ior: 1.33,
+                normal: Vec3::Z,
+                connections: HashMap::new(),
+            };
+            renderer.current_scene.materials.insert(glass.prim_path.clone(), glass);
+            renderer.current_scene.geometries[1].material_path = Some("/World/Looks/Glass".to_string());
+            renderer.set_shading_mode(ShadingMode::MaterialPreview);
+            
+            let constants = renderer.material_constants("/World/Sphere");
+            assert_eq!((constants.opacity, constants.roughness), (0.25, 0.05));
+            assert_eq!(constants.emissive, [0.0, 0.0, 2.0]);
+            assert_eq!(constants.reflectance, ior_reflectance(1.33));
+            assert!(constants.reflectance < ior_reflectance(1.5));
+        }
+        
+        #[test]
+        fn shades_materialx_surfaces_as_preview_surfaces() {
+            let mut renderer = stand_in_renderer();
+            // A gold standard_surface; unauthored inputs take the node definition's defaults
+            let gold = preview_values("ND_standard_surface_surfaceshader", |name| match name {
+                "base_color" => Some(Vec4::new(1.0, 0.8, 0.3, 1.0)),
+                "metalness" => Some(Vec4::ONE),
+                _ => None,
+            }).unwrap();
+            let material = USDMaterial {
+                prim_path: "/World/Looks/Gold".to_string(),
+                diffuse_color: gold.diffuse_color,
+                metallic: gold.metallic,
+                roughness: gold.roughness,
+                opacity: gold.opacity,
+                emission_color: gold.emissive_color,
+                ior: gold.ior,
+                normal: Vec3::Z,
+                connections: HashMap::new(),
+            };
+            renderer.current_scene.materials.insert(material.prim_path.clone(), material);
+            renderer.current_scene.geometries[1].material_path = Some("/World/Looks/Gold".to_string());
+            
+            let constants = renderer.material_constants("/World/Sphere");
+            assert_eq!(constants.base_color, [1.0, 0.8, 0.3]);
+            assert_eq!((constants.metallic, constants.roughness, constants.opacity), (1.0, 0.2, 1.0));
+            assert_eq!(constants.emissive, [0.0; 3]);
+        }
+        
+        #[test]
+        fn display_colors_stand_in_for_unbound_base_colors() {
+            let mut renderer = stand_in_renderer();
+            let mode = |renderer: &USDRenderer, prim_path| renderer.material_constants(prim_path).vertex_color;
+            let cube = &mut renderer.current_scene.geometries[0];
+            cube.colors = vec![Vec4::new(1.0, 0.0, 0.0, 1.0); cube.vertices.len()];
+            assert_eq!(mode(&renderer, "/World/Cube"), VertexColorMode::BaseColor as u32);
+            assert_eq!(mode(&renderer, "/World/Sphere"), VertexColorMode::Off as u32);
+            
+            // Materials that don't read displayColor keep their base color, except in display color shading
+            let bound = USDMaterial { prim_path: "/World/Looks/Bound".to_string(), ..renderer.current_scene.materials[DEFAULT_MATERIAL].clone() };
+            renderer.current_scene.materials.insert(bound.prim_path.clone(), bound);
+            renderer.current_scene.geometries[0].material_path = Some("/World/Looks/Bound".to_string());
+            assert_eq!(mode(&renderer, "/World/Cube"), VertexColorMode::Off as u32);
+            renderer.set_shading_mode(ShadingMode::DisplayColor);
+            assert_eq!(mode(&renderer, "/World/Cube"), VertexColorMode::BaseColor as u32);
+            
+            renderer.render_settings.display_primvar = Some("Cd".to_string());
+            assert_eq!(mode(&renderer, "/World/Cube"), VertexColorMode::Display as u32);
+        }
+        
+        #[test]
+        fn decodes_the_textures_materials_connect() {
+            let directory = std::env::temp_dir().join(format!("usd_rendering_textures_{}", std::process::id()));
+            std::fs::create_dir_all(&directory).unwrap();
+            let checker = directory.join("checker.png");
+            image::RgbaImage::from_fn(4, 4, |x, y| image::Rgba(if (x + y) % 2 == 0 { [255; 4] } else { [0, 0, 0, 255] }))
+                .save(&checker)
+                .unwrap();
+            
+            let chain = decode_texture(&checker.to_string_lossy()).unwrap();
+            assert_eq!((chain.width, chain.height, chain.levels.len()), (4, 4, 3));
+            assert_eq!(&chain.levels[0][..8], [255, 255, 255, 255, 0, 0, 0, 255]);
+            // 8-bit images are base colors in sRGB unless authored raw, as normal maps are
+            assert!(uses_srgb("auto", chain.format, chain.auto_srgb));
+            assert!(!uses_srgb("raw", chain.format, chain.auto_srgb));
+            // Textures that fail to load leave their material untextured, reporting the file
+            let missing = directory.join("missing.png").to_string_lossy().into_owned();
+            assert!(decode_texture(&missing).unwrap_err().contains("missing.png"));
+            std::fs::remove_dir_all(&directory).ok();
+        }
+    }
+    
+    mod picking {
+        use super::*;
+        use crate::capture::id_matte::prim_id;
+        
+        #[test]
+        fn picks_the_prim_under_the_cursor() {
+            // The ID matte rasterizes on the CPU what the pick pass draws on the GPU
+            let mut renderer = stand_in_renderer();
+            for (prim_path, center) in [("/World/Cube", Vec3::new(-2.0, 0.0, 0.0)), ("/World/Sphere", Vec3::new(2.0, 0.0, 0.0))] {
+                renderer.base_renderer.camera.target = center;
+                let matte = renderer.capture_id_matte(64, 48, &mut IdManifest::default());
+                assert_eq!(matte.id_at(32, 24), prim_id(prim_path), "{}", prim_path);
+            }
+            // Looking away from the scene there is nothing to pick
+            let camera = &mut renderer.base_renderer.camera;
+            camera.target = camera.position * 2.0;
+            assert_eq!(renderer.capture_id_matte(64, 48, &mut IdManifest::default()).id_at(32, 24), 0);
+            
+            // Pick pass pixels hold the geometry index plus one, zero where nothing was drawn
+            assert_eq!(renderer.picked_prim(&[[2, 1]]).as_deref(), Some("/World/Sphere"));
+            assert_eq!(renderer.picked_prim(&[[0, 0]]), None);
+        }
+        
+        #[test]
+        fn picks_authored_faces_in_a_rectangle() {
+            let mut renderer = stand_in_renderer();
+            // Two quads, two triangles each
+            let points = [
+                Vec3::new(-2.0, 0.0, -1.0), Vec3::new(0.0, 0.0, -1.0), Vec3::new(2.0, 0.0, -1.0),
+                Vec3::new(-2.0, 0.0, 1.0), Vec3::new(0.0, 0.0, 1.0), Vec3::new(2.0, 0.0, 1.0),
+            ];
+            let card = build_mesh_geometry("/World/Card", &points, &[4, 4], &[0, 3, 4, 1, 1, 4, 5, 2], &[],
+                                           Mat4::from_translation(Vec3::new(0.0, 3.0, 0.0)), &MeshPrimvars::default()).unwrap();
+            assert_eq!(card.face_ids, [0, 0, 1, 1]);
+            let card_index = renderer.current_scene.geometries.len() as u32;
+            renderer.current_scene.geometries.push(card);
+            
+            // Triangles resolve to their authored faces; the stand-in shapes have none
+            let pixels = [[card_index + 1, 1], [card_index + 1, 4], [1, 1], [0, 0]];
+            let selection = renderer.picked_faces(&pixels);
+            assert_eq!(selection.keys().collect::<Vec<_>>(), ["/World/Card"]);
+            assert_eq!(selection["/World/Card"].iter().copied().collect::<Vec<_>>(), [0, 1]);
+            
+            // Rectangles outside the view pick nothing without drawing
+            let outside = PickRect::from_corners((100.0, 100.0), (120.0, 120.0));
+            assert!(renderer.pick_faces(64, 48, outside).unwrap().is_empty());
+        }
+    }
+    
+    mod cameras {
+        use super::*;
+        
+        /// A UsdGeomCamera with the schema's lens, ten units up +Z looking at the origin
+        fn usd_camera(prim_path: &str) -> USDCamera {
+            let lens = ProjectionCamera::default();
+            USDCamera {
+                prim_path: prim_path.to_string(),
+                transform: Mat4::from_translation(Vec3::new(0.0, 0.0, 10.0)),
+                focal_length: lens.focal_length,
+                horizontal_aperture: lens.horizontal_aperture,
+                vertical_aperture: lens.vertical_aperture,
+                clipping_range: (0.5, 500.0),
+                f_stop: 0.0,
+                focus_distance: 0.0,
+                exposure_scale: 1.0,
+            }
+        }
+        
+        #[test]
+        fn looks_through_usd_cameras() {
+            let mut renderer = USDRenderer::new();
+            renderer.current_scene.cameras.push(usd_camera("/World/Shot"));
+            let viewport = renderer.base_renderer.camera.clone();
+            
+            renderer.set_camera_mode(CameraMode::USDCamera("/World/Shot".to_string()));
+            let camera = renderer.get_active_camera();
+            assert_eq!(camera.position, Vec3::new(0.0, 0.0, 10.0));
+            assert_eq!(camera.target, Vec3::new(0.0, 0.0, 9.0));
+            assert_eq!((camera.near, camera.far), (0.5, 500.0));
+            assert_eq!(camera.fov, usd_camera("/World/Shot").projection().fov_y());
+            
+            // Cameras missing from the stage leave the viewport camera in place
+            renderer.set_camera_mode(CameraMode::USDCamera("/World/Missing".to_string()));
+            assert_eq!(renderer.get_active_camera().position, viewport.position);
+        }
+        
+        #[test]
+        fn looked_through_cameras_expose_the_lighting() {
+            let mut renderer = stand_in_renderer();
+            renderer.current_scene.cameras.push(USDCamera {
+                f_stop: 2.8,
+                focus_distance: 10.0,
+                exposure_scale: 4.0,
+                ..usd_camera("/World/Shot")
+            });
+            let unexposed = renderer.lighting_uniform();
+            assert!(renderer.active_camera_response().is_none());
+            
+            renderer.set_camera_mode(CameraMode::USDCamera("/World/Shot".to_string()));
+            let response = renderer.active_camera_response().unwrap();
+            assert!(response.has_depth_of_field());
+            let exposed = renderer.lighting_uniform();
+            assert_eq!(exposed.lights[0].intensity, unexposed.lights[0].intensity * 4.0);
+            assert_eq!(exposed.sky_color, unexposed.sky_color.map(|channel| channel * 4.0));
+        }
+    }
+    
+    mod lighting {
+        use super::*;
+        use super::super::super::color_management::ViewTransform;
+        
+        #[test]
+        fn lighting_uniform_matches_the_mesh_shader() {
+            // Eight 32-byte lights, then three 16-byte rows of ambient and display settings
+            assert_eq!(std::mem::size_of::<LightingUniform>(), 304);
+        }
+        
+        #[test]
+        fn dome_environments_light_the_ambient() {
+            let mut renderer = stand_in_renderer();
+            assert_eq!(renderer.lighting_uniform().sky_color, Environment::default().sky.to_array());
+            
+            // A sky dome whose latlong is darker below the horizon
+            let sky = Environment { sky: Vec3::ONE, ground: Vec3::splat(0.25) };
+            renderer.current_scene.environment = Some(Environment::dome(Vec3::new(0.5, 0.6, 1.0), Some(sky)));
+            let uniform = renderer.lighting_uniform();
+            assert_eq!(uniform.sky_color, [0.5, 0.6, 1.0]);
+            assert_eq!(uniform.ground_color, [0.125, 0.15, 0.25]);
+            
+            renderer.render_settings.enable_lighting = false;
+            assert_eq!(renderer.lighting_uniform().ground_color, Environment::default().ground.to_array());
+        }
+        
+        #[test]
+        fn lights_shade_with_their_lux_inputs() {
+            let mut renderer = USDRenderer::new();
+            let light = |light_type: &str, lux: LuxParams| USDLight {
+                prim_path: format!("/World/Lights/{}", light_type),
+                light_type: light_type.to_string(),
+                transform: Mat4::from_rotation_x(-90_f32.to_radians()),
+                lux,
+                light_link: LinkCollection::default(),
+                shadow_link: LinkCollection::default(),
+            };
+            // An unnormalized rect light emits by its area; a warm blackbody tints it
+            let rect = LuxParams { intensity: 2.0, exposure: 1.0, width: 2.0, height: 0.5, ..LuxParams::default() };
+            let warm = LuxParams { enable_color_temperature: true, color_temperature: 3000.0, ..LuxParams::default() };
+            renderer.current_scene.lights = vec![light("rect", rect), light("distant", warm)];
+            renderer.current_scene.lights.extend((0..MAX_SHADED_LIGHTS).map(|_| light("distant", LuxParams::default())));
+            
+            let uniform = renderer.lighting_uniform();
+            assert_eq!(uniform.count, MAX_SHADED_LIGHTS as u32);
+            assert_eq!(uniform.lights[0].intensity, 4.0);
+            assert!(uniform.lights[0].direction[1] < -0.99, "{:?}", uniform.lights[0].direction);
+            assert_eq!(uniform.lights[1].color, warm.tint().to_array());
+            assert!(uniform.lights[1].color[0] > uniform.lights[1].color[2]);
+            
+            renderer.render_settings.enable_lighting = false;
+            assert_eq!(renderer.lighting_uniform().count, 0);
+        }
+        
+        #[test]
+        fn grades_the_lighting_with_the_color_management() {
+            let mut renderer = stand_in_renderer();
+            let uniform = renderer.lighting_uniform();
+            assert_eq!((uniform.view_transform, uniform.exposure, uniform.inverse_gamma), (0, 1.0, 1.0));
+            
+            renderer.set_color_management(ColorManagement { view_transform: ViewTransform::Aces, exposure: 1.0, gamma: 2.0 });
+            let uniform = renderer.lighting_uniform();
+            assert_eq!(uniform.view_transform, ViewTransform::Aces.index());
+            assert_eq!((uniform.exposure, uniform.inverse_gamma), (2.0, 0.5));
+            
+            renderer.set_shading_mode(ShadingMode::FlatShaded);
+            assert_eq!(renderer.lighting_uniform().flat_shading, 1);
+        }
+    }
+    
+    mod hydra {
+        use super::*;
+        use super::super::super::hydra::STORM_RENDERER;
+        
+        #[test]
+        fn render_settings_belong_to_one_delegate() {
+            let mut renderer = USDRenderer::new();
+            let embree = RenderBackend::Hydra("HdEmbreeRendererPlugin".to_string());
+            renderer.set_render_backend(embree.clone());
+            renderer.set_renderer_setting("ambientOcclusionSamples", RenderSettingValue::Int(4));
+            renderer.set_renderer_setting("enableSceneColors", RenderSettingValue::Flag(true));
+            renderer.set_renderer_setting("ambientOcclusionSamples", RenderSettingValue::Int(16));
+            assert_eq!(renderer.render_settings.renderer_settings, vec![
+                ("ambientOcclusionSamples".to_string(), RenderSettingValue::Int(16)),
+                ("enableSceneColors".to_string(), RenderSettingValue::Flag(true)),
+            ]);
+            
+            renderer.set_render_backend(embree);
+            assert_eq!(renderer.render_settings.renderer_settings.len(), 2);
+            renderer.set_render_backend(RenderBackend::Hydra(STORM_RENDERER.to_string()));
+            assert!(renderer.render_settings.renderer_settings.is_empty());
+        }
+        
+        #[test]
+        fn draws_the_wgpu_scene_until_hydra_has_a_frame() {
+            let mut renderer = stand_in_renderer();
+            renderer.set_render_backend(RenderBackend::Hydra(STORM_RENDERER.to_string()));
+            assert!(renderer.render_hydra_frame(64, 48).is_err());
+            assert!(!renderer.hydra.has_frame());
+            // The wgpu frame gets the ambient occlusion Hydra frames go without
+            renderer.render_settings.ambient_occlusion.enabled = true;
+            assert!(renderer.draws_ambient_occlusion());
+        }
+    }
+    
+    mod path_tracing {
+        use super::*;
+        
+        #[test]
+        fn path_traced_frames_replace_the_scene_once_sampled() {
+            let mut renderer = stand_in_renderer();
+            // Without a device nothing is traced
+            renderer.set_path_trace(PathTraceSettings { enabled: true, max_samples: 2, ..PathTraceSettings::default() });
+            assert!(renderer.render_path_trace_frame(16, 12).is_err());
+            assert!(!renderer.shows_path_trace());
+            
+            renderer.path_tracer.samples = 1;
+            assert!(renderer.shows_path_trace());
+            // Hydra delegates draw their own frames
+            renderer.set_render_backend(RenderBackend::Hydra("HdEmbreeRendererPlugin".to_string()));
+            assert!(!renderer.shows_path_trace());
+            renderer.set_render_backend(RenderBackend::Wgpu);
+            
+            // Changing the settings starts the frame over
+            renderer.set_path_trace(PathTraceSettings { enabled: true, max_samples: 4, ..PathTraceSettings::default() });
+            assert_eq!(renderer.path_tracer.samples, 0);
+            assert!(!renderer.shows_path_trace());
+        }
+        
+        #[test]
+        fn only_traces_stages() {
+            let mut renderer = USDRenderer::new();
+            renderer.set_path_trace(PathTraceSettings { enabled: true, ..PathTraceSettings::default() });
+            // Nothing is loaded, so there is nothing to trace and no device is asked for
+            renderer.render_path_trace_frame(16, 12).unwrap();
+            renderer.finish_path_trace_frame(16, 12).unwrap();
+            assert!(renderer.trace_scene.is_none());
+        }
+    }
+}
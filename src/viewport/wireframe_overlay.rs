@@ -0,0 +1,193 @@
+//! Wireframe on shaded surfaces
+//!
+//! Wireframe on Shaded draws every mesh shaded and then draws its edges over
+//! it in a second pass of the scene, as a line list from usd_wire_overlay.wgsl.
+//! The lines are pulled toward the camera by a polygon offset so they win the
+//! depth test against the surface they lie on without showing through
+//! anything in front of it. Each geometry gets an edge list of its authored
+//! polygons and one of its triangles, so Preserve Quad Wireframe switches
+//! between them without a re-upload.
+
+use std::collections::{BTreeSet, HashMap};
+use bytemuck::{Pod, Zeroable};
+use glam::Mat4;
+use wgpu::util::DeviceExt;
+use super::renderer_3d::Vertex3D;
+use super::antialiasing::PASS_FORMATS;
+
+/// Linear color of the overlaid edges
+pub const WIRE_COLOR: [f32; 4] = [0.02, 0.02, 0.02, 1.0];
+
+/// Normalized depth the edges are pulled toward the camera by
+pub const WIRE_DEPTH_OFFSET: f32 = 2e-4;
+
+/// Push constants of a wire draw (usd_wire_overlay.wgsl `WireConstants`)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+struct WireConstants {
+    object_to_clip: [[f32; 4]; 4],
+    color: [f32; 4],
+    vertex_stride: u32,
+    depth_offset: f32,
+    _padding: [f32; 2],
+}
+
+/// Edges of a triangle list as a line list, each shared edge once
+pub fn triangle_edges(indices: &[u32]) -> Vec<u32> {
+    let mut edges = BTreeSet::new();
+    for triangle in indices.chunks_exact(3) {
+        for corner in 0..3 {
+            let (a, b) = (triangle[corner], triangle[(corner + 1) % 3]);
+            edges.insert((a.min(b), a.max(b)));
+        }
+    }
+    edges.into_iter().flat_map(|(a, b)| [a, b]).collect()
+}
+
+/// Edge lists of one geometry, bound with its vertices
+struct WireBindings {
+    /// Authored polygon edges, when the geometry has any
+    polygons: Option<(wgpu::BindGroup, u32)>,
+    triangles: (wgpu::BindGroup, u32),
+}
+
+/// Wire overlay pipeline for the scene pass and the edge lists of every geometry
+pub struct WireframeOverlay {
+    layout: wgpu::BindGroupLayout,
+    pipeline: wgpu::RenderPipeline,
+    geometry: HashMap<String, WireBindings>,
+}
+
+impl WireframeOverlay {
+    /// Create the pipeline for a scene pass with `samples` samples per pixel
+    pub fn new(device: &wgpu::Device, samples: u32) -> Self {
+        let storage = |binding| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::VERTEX,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only: true },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("usd_wire_overlay_layout"),
+            entries: &[storage(0), storage(1)],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("usd_wire_overlay_pipeline_layout"),
+            bind_group_layouts: &[&layout],
+            push_constant_ranges: &[wgpu::PushConstantRange {
+                stages: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                range: 0..std::mem::size_of::<WireConstants>() as u32,
+            }],
+        });
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("usd_wire_overlay"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/usd_wire_overlay.wgsl").into()),
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("usd_wire_overlay"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                compilation_options: Default::default(),
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                compilation_options: Default::default(),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: PASS_FORMATS[0],
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::LineList,
+                ..Default::default()
+            },
+            // Edges test against the surfaces but leave the depth to them
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: PASS_FORMATS[1],
+                depth_write_enabled: false,
+                depth_compare: wgpu::CompareFunction::LessEqual,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState { count: samples, ..Default::default() },
+            multiview: None,
+            cache: None,
+        });
+        Self { layout, pipeline, geometry: HashMap::new() }
+    }
+    
+    /// Rebuild the edge lists of every geometry, as (prim path, Vertex3D buffer with STORAGE usage, polygon edges, triangle indices)
+    pub fn rebuild<'a>(&mut self, device: &wgpu::Device, geometries: impl IntoIterator<Item = (&'a String, &'a wgpu::Buffer, &'a [u32], &'a [u32])>) {
+        self.geometry.clear();
+        for (prim_path, vertices, polygon_edges, indices) in geometries {
+            let bind = |edges: &[u32]| {
+                let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some(&format!("{}_wire_edges", prim_path)),
+                    contents: bytemuck::cast_slice(edges),
+                    usage: wgpu::BufferUsages::STORAGE,
+                });
+                let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: Some("usd_wire_overlay_geometry"),
+                    layout: &self.layout,
+                    entries: &[
+                        wgpu::BindGroupEntry { binding: 0, resource: vertices.as_entire_binding() },
+                        wgpu::BindGroupEntry { binding: 1, resource: buffer.as_entire_binding() },
+                    ],
+                });
+                (bind_group, edges.len() as u32)
+            };
+            let triangles = triangle_edges(indices);
+            if triangles.is_empty() {
+                continue;
+            }
+            let bindings = WireBindings {
+                polygons: (!polygon_edges.is_empty()).then(|| bind(polygon_edges)),
+                triangles: bind(&triangles),
+            };
+            self.geometry.insert(prim_path.clone(), bindings);
+        }
+    }
+    
+    /// Draw a geometry's edges into the scene pass, its authored polygon edges when `polygons` is set and it has them
+    pub fn draw(&self, render_pass: &mut wgpu::RenderPass, prim_path: &str, object_to_clip: Mat4, polygons: bool) {
+        let Some(bindings) = self.geometry.get(prim_path) else {
+            return;
+        };
+        let (bind_group, count) = bindings.polygons.as_ref().filter(|_| polygons).unwrap_or(&bindings.triangles);
+        let constants = WireConstants {
+            object_to_clip: object_to_clip.to_cols_array_2d(),
+            color: WIRE_COLOR,
+            vertex_stride: (std::mem::size_of::<Vertex3D>() / 4) as u32,
+            depth_offset: WIRE_DEPTH_OFFSET,
+            _padding: [0.0; 2],
+        };
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_push_constants(wgpu::ShaderStages::VERTEX_FRAGMENT, 0, bytemuck::bytes_of(&constants));
+        render_pass.set_bind_group(0, bind_group, &[]);
+        render_pass.draw(0..*count, 0..1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn triangle_edges_list_shared_edges_once() {
+        // A quad split along its diagonal
+        let edges = triangle_edges(&[0, 1, 2, 0, 2, 3]);
+        let pairs: Vec<&[u32]> = edges.chunks(2).collect();
+        assert_eq!(pairs, [[0, 1], [0, 2], [0, 3], [1, 2], [2, 3]]);
+        assert!(triangle_edges(&[0, 1]).is_empty());
+        assert_eq!(std::mem::size_of::<WireConstants>() % 16, 0);
+    }
+}
@@ -0,0 +1,317 @@
+//! USD Watch Folder node - follows the newest USD file delivered into a directory
+
+use nodle_plugin_sdk::*;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+use crate::core::usd_engine::with_usd_engine;
+use crate::ui::help::NodeHelp;
+use crate::ui::palette::status_label;
+use crate::ui::keyboard::{focus_marked, handle_key};
+use crate::registry::NodeTypeFactory;
+
+/// Help for the Watch Folder node
+pub const HELP: NodeHelp = NodeHelp {
+    node_type: "USD_WatchFolder",
+    summary: "Watch a directory for USD deliveries and output the newest file, optionally loading it",
+    details: "Polls Folder every few seconds for files with the listed extensions and outputs the newest one. With Auto Load on, it also opens the file as a stage.",
+    ports: &[
+        ("File Path", "/deliveries/sh010_anim_v012.usdc"),
+        ("Stage", "loaded_3"),
+    ],
+    samples: &[],
+};
+
+/// Factory of the Watch Folder node, for `crate::node_factories`
+pub fn factories() -> Vec<Box<dyn NodeFactory>> {
+    vec![NodeTypeFactory::boxed(metadata, |position| PluginNodeHandle::new(Box::new(USDWatchFolderNode::new(position))))]
+}
+
+/// Metadata of the Watch Folder node
+fn metadata() -> NodeMetadata {
+    NodeMetadata::new(
+        "USD_WatchFolder",
+        "Watch Folder",
+        NodeCategory::new(&["USD", "Stage"]),
+        HELP.summary
+    )
+    .with_color(Color32::from_rgb(80, 150, 200))
+    .with_icon("👁")
+    .with_inputs(vec![
+        // No input ports - the folder is set via parameters
+    ])
+    .with_outputs(vec![
+        PortDefinition::optional("File Path", DataType::String)
+            .with_description("Most recently modified USD file in the folder"),
+        PortDefinition::optional("Stage", DataType::String)
+            .with_description("Newest file loaded as a stage, with Auto Load on"),
+    ])
+    .with_panel_type(PanelType::Parameter)
+    .with_workspace_compatibility(vec!["3D"])
+}
+
+/// Files modified more recently than this may still be copying and are skipped
+const SETTLE_TIME: Duration = Duration::from_secs(2);
+
+/// USD Watch Folder node
+///
+/// Polls the folder each time the node is processed, at most once per poll
+/// interval, and outputs the most recently modified USD file. With "Auto Load"
+/// on, the file is opened as a stage and output on "Stage".
+pub struct USDWatchFolderNode {
+    id: String,
+    position: Pos2,
+    folder: String,
+    /// Comma-separated file extensions, without the dot
+    extensions: String,
+    include_subfolders: bool,
+    auto_load: bool,
+    poll_seconds: f32,
+    last_scan: Option<Instant>,
+    newest: Option<(PathBuf, SystemTime)>,
+    /// Stage identifier of the loaded newest file
+    stage: Option<String>,
+    status: String,
+}
+
+impl USDWatchFolderNode {
+    pub fn new(position: Pos2) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            position,
+            folder: String::new(),
+            extensions: "usd, usda, usdc, usdz".to_string(),
+            include_subfolders: false,
+            auto_load: true,
+            poll_seconds: 5.0,
+            last_scan: None,
+            newest: None,
+            stage: None,
+            status: "No folder set".to_string(),
+        }
+    }
+    
+    /// Rescan the folder on the next process
+    fn rescan(&mut self) {
+        self.last_scan = None;
+    }
+    
+    fn scan(&mut self) -> Result<(), String> {
+        if self.folder.is_empty() {
+            return Err("No folder set".to_string());
+        }
+        let extensions: Vec<String> = self.extensions.split(',')
+            .map(|extension| extension.trim().trim_start_matches('.').to_ascii_lowercase())
+            .filter(|extension| !extension.is_empty())
+            .collect();
+        let mut newest = None;
+        let count = find_newest(Path::new(&self.folder), &extensions, self.include_subfolders, &mut newest)
+            .map_err(|e| format!("Failed to scan '{}': {}", self.folder, e))?;
+        
+        let Some((path, modified)) = newest else {
+            self.newest = None;
+            self.stage = None;
+            self.status = format!("No USD files in {}", self.folder);
+            return Ok(());
+        };
+        let changed = self.newest.as_ref() != Some(&(path.clone(), modified));
+        if changed || (self.auto_load && self.stage.is_none()) {
+            self.stage = None;
+            if self.auto_load {
+                let file_path = path.to_string_lossy().to_string();
+                let stage = with_usd_engine(|engine| engine.resolve_stage(&file_path))?;
+                self.stage = Some(stage.identifier);
+            }
+        }
+        self.status = format!("Newest of {}: {}", count, path.file_name().unwrap_or_default().to_string_lossy());
+        self.newest = Some((path, modified));
+        Ok(())
+    }
+}
+
+/// Find the most recently modified settled file with one of the extensions, returning the number of matches
+fn find_newest(folder: &Path, extensions: &[String], recursive: bool, newest: &mut Option<(PathBuf, SystemTime)>) -> std::io::Result<usize> {
+    let settled_before = SystemTime::now() - SETTLE_TIME;
+    let mut count = 0;
+    for entry in std::fs::read_dir(folder)? {
+        let entry = entry?;
+        let path = entry.path();
+        let metadata = entry.metadata()?;
+        if metadata.is_dir() {
+            if recursive {
+                count += find_newest(&path, extensions, recursive, newest)?;
+            }
+            continue;
+        }
+        let matches = path.extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| extensions.contains(&extension.to_ascii_lowercase()));
+        let Ok(modified) = metadata.modified() else {
+            continue;
+        };
+        if !matches || modified > settled_before {
+            continue;
+        }
+        count += 1;
+        if newest.as_ref().is_none_or(|(_, newest_modified)| modified > *newest_modified) {
+            *newest = Some((path, modified));
+        }
+    }
+    Ok(count)
+}
+
+impl PluginNode for USDWatchFolderNode {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+    
+    fn position(&self) -> Pos2 {
+        self.position
+    }
+    
+    fn set_position(&mut self, position: Pos2) {
+        self.position = position;
+    }
+    
+    fn get_parameter_ui(&self) -> ParameterUI {
+        let mut elements = Vec::new();
+        
+        elements.push(UIElement::Heading("USD Watch Folder".to_string()));
+        elements.push(UIElement::Separator);
+        
+        elements.push(UIElement::TextEdit {
+            label: "Folder".to_string(),
+            value: self.folder.clone(),
+            parameter_name: "folder".to_string(),
+        });
+        elements.push(UIElement::TextEdit {
+            label: "Extensions".to_string(),
+            value: self.extensions.clone(),
+            parameter_name: "extensions".to_string(),
+        });
+        elements.push(UIElement::Checkbox {
+            label: "Include Subfolders".to_string(),
+            value: self.include_subfolders,
+            parameter_name: "include_subfolders".to_string(),
+        });
+        elements.push(UIElement::Checkbox {
+            label: "Auto Load".to_string(),
+            value: self.auto_load,
+            parameter_name: "auto_load".to_string(),
+        });
+        elements.push(UIElement::Slider {
+            label: "Poll Interval (s)".to_string(),
+            value: self.poll_seconds,
+            min: 1.0,
+            max: 60.0,
+            parameter_name: "poll_seconds".to_string(),
+        });
+        elements.push(UIElement::Button {
+            label: "Rescan".to_string(),
+            action: "rescan".to_string(),
+        });
+        
+        elements.push(UIElement::Separator);
+        elements.push(status_label(&self.status));
+        if let Some((path, _)) = &self.newest {
+            elements.push(UIElement::Label(path.to_string_lossy().to_string()));
+        }
+        elements.extend(HELP.section());
+        
+        ParameterUI { elements: focus_marked(&self.id, elements) }
+    }
+    
+    fn handle_ui_action(&mut self, action: UIAction) -> Vec<ParameterChange> {
+        if let Some(changes) = handle_key(self, &[], &action) {
+            return changes;
+        }
+        
+        let mut changes = Vec::new();
+        
+        match action {
+            UIAction::ParameterChanged { parameter, value } => {
+                if matches!(parameter.as_str(), "folder" | "extensions" | "include_subfolders" | "auto_load" | "poll_seconds") {
+                    self.set_parameter(&parameter, value);
+                    if let Some(value) = self.get_parameter(&parameter) {
+                        changes.push(ParameterChange { parameter, value });
+                    }
+                }
+            }
+            UIAction::ButtonClicked { action } => {
+                if action == "rescan" {
+                    self.rescan();
+                }
+            }
+        }
+        
+        changes
+    }
+    
+    fn get_parameter(&self, name: &str) -> Option<NodeData> {
+        match name {
+            "folder" => Some(NodeData::String(self.folder.clone())),
+            "extensions" => Some(NodeData::String(self.extensions.clone())),
+            "include_subfolders" => Some(NodeData::Boolean(self.include_subfolders)),
+            "auto_load" => Some(NodeData::Boolean(self.auto_load)),
+            "poll_seconds" => Some(NodeData::Float(self.poll_seconds)),
+            _ => None,
+        }
+    }
+    
+    fn set_parameter(&mut self, name: &str, value: NodeData) {
+        match name {
+            "folder" => {
+                if let Some(folder) = value.as_string() {
+                    self.folder = folder.trim().to_string();
+                    self.newest = None;
+                    self.rescan();
+                }
+            }
+            "extensions" => {
+                if let Some(extensions) = value.as_string() {
+                    self.extensions = extensions.trim().to_string();
+                    self.rescan();
+                }
+            }
+            "include_subfolders" => {
+                if let Some(include) = value.as_boolean() {
+                    self.include_subfolders = include;
+                    self.rescan();
+                }
+            }
+            "auto_load" => {
+                if let Some(auto_load) = value.as_boolean() {
+                    self.auto_load = auto_load;
+                    self.rescan();
+                }
+            }
+            "poll_seconds" => {
+                if let Some(seconds) = value.as_float() {
+                    self.poll_seconds = seconds.clamp(1.0, 60.0);
+                }
+            }
+            _ => {}
+        }
+    }
+    
+    fn process(&mut self, _inputs: &HashMap<String, NodeData>) -> HashMap<String, NodeData> {
+        let mut outputs = HashMap::new();
+        
+        let interval = Duration::from_secs_f32(self.poll_seconds);
+        if self.last_scan.is_none_or(|scanned| scanned.elapsed() >= interval) {
+            self.last_scan = Some(Instant::now());
+            if let Err(e) = self.scan() {
+                self.status = format!("⚠ {}", e);
+                self.stage = None;
+            }
+        }
+        
+        if let Some((path, _)) = &self.newest {
+            outputs.insert("File Path".to_string(), NodeData::String(path.to_string_lossy().to_string()));
+        }
+        if let Some(stage) = &self.stage {
+            outputs.insert("Stage".to_string(), NodeData::String(stage.clone()));
+        }
+        outputs
+    }
+}